@@ -1 +1,247 @@
-fn main() {}
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Latency benchmarks for the FIX hot path
+//!
+//! Covers message construction, parsing, checksum calculation and a full
+//! session round-trip (TestRequest -> Heartbeat) against a loopback mock
+//! server. Allocations per message are reported before the timed runs.
+
+use criterion::{Criterion, criterion_group};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::message::{
+    Heartbeat, MessageBuilder, NewOrderSingle, OrderSide, TestRequest, ToFixMessage,
+};
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::types::MsgType;
+use deribit_fix::session::Session;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::runtime::Runtime;
+use tokio::sync::Mutex;
+
+/// Global allocator wrapper counting allocations
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn sample_order() -> NewOrderSingle {
+    NewOrderSingle::limit(
+        "BENCH-ORDER-1".to_string(),
+        OrderSide::Buy,
+        10.0,
+        50_000.0,
+        "BTC-PERPETUAL".to_string(),
+    )
+    .with_label("bench".to_string())
+}
+
+fn sample_raw_order() -> String {
+    sample_order()
+        .to_fix_message("CLIENT", "DERIBIT", 42)
+        .expect("sample order should build")
+        .to_string()
+}
+
+/// Average number of allocations performed by `f` over `iterations` runs
+fn allocations_per_call<F: FnMut()>(iterations: usize, mut f: F) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..iterations {
+        f();
+    }
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    (after - before) as f64 / iterations as f64
+}
+
+fn report_allocations() {
+    const ITERATIONS: usize = 1_000;
+    let order = sample_order();
+    let raw = sample_raw_order();
+    let parsed = FixMessage::parse(&raw).expect("sample order should parse");
+
+    let build_heartbeat = allocations_per_call(ITERATIONS, || {
+        black_box(ToFixMessage::to_fix_message(
+            &Heartbeat::new(),
+            "CLIENT",
+            "DERIBIT",
+            1,
+        ))
+        .ok();
+    });
+    let build_order = allocations_per_call(ITERATIONS, || {
+        black_box(order.to_fix_message("CLIENT", "DERIBIT", 1)).ok();
+    });
+    let parse_order = allocations_per_call(ITERATIONS, || {
+        black_box(FixMessage::parse(black_box(&raw))).ok();
+    });
+    let checksum = allocations_per_call(ITERATIONS, || {
+        black_box(parsed.calculate_checksum());
+    });
+
+    println!("allocations per message:");
+    println!("  build heartbeat:        {build_heartbeat:.1}");
+    println!("  build new order single: {build_order:.1}");
+    println!("  parse new order single: {parse_order:.1}");
+    println!("  checksum:               {checksum:.1}");
+}
+
+fn bench_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    let order = sample_order();
+
+    group.bench_function("heartbeat", |b| {
+        b.iter(|| {
+            MessageBuilder::new()
+                .msg_type(MsgType::Heartbeat)
+                .sender_comp_id(black_box("CLIENT").to_string())
+                .target_comp_id(black_box("DERIBIT").to_string())
+                .msg_seq_num(black_box(1))
+                .build()
+        })
+    });
+    group.bench_function("new_order_single", |b| {
+        b.iter(|| black_box(&order).to_fix_message("CLIENT", "DERIBIT", black_box(1)))
+    });
+    group.finish();
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let raw = sample_raw_order();
+    c.bench_function("parse/new_order_single", |b| {
+        b.iter(|| FixMessage::parse(black_box(&raw)))
+    });
+}
+
+fn bench_checksum(c: &mut Criterion) {
+    let message = FixMessage::parse(&sample_raw_order()).expect("sample order should parse");
+    c.bench_function("checksum/new_order_single", |b| {
+        b.iter(|| black_box(&message).calculate_checksum())
+    });
+}
+
+/// Mock server answering every TestRequest with a Heartbeat echoing its TestReqID
+async fn start_mock_server() -> std::net::SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock server");
+    let addr = listener.local_addr().expect("mock server address");
+
+    tokio::spawn(async move {
+        let Ok((mut socket, _)) = listener.accept().await else {
+            return;
+        };
+        let mut buf = vec![0u8; 4096];
+        let mut seq_num = 1;
+        loop {
+            let n = match socket.read(&mut buf).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => n,
+            };
+            let received = String::from_utf8_lossy(&buf[..n]).to_string();
+            for frame in received.split("8=FIX.4.4").filter(|f| !f.is_empty()) {
+                let Ok(message) = FixMessage::parse(&format!("8=FIX.4.4{frame}")) else {
+                    continue;
+                };
+                let Some(test_req_id) = message.get_field(112) else {
+                    continue;
+                };
+                let reply = Heartbeat::new_response(test_req_id.clone())
+                    .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), seq_num)
+                    .expect("heartbeat should build");
+                seq_num += 1;
+                if socket
+                    .write_all(reply.to_string().as_bytes())
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        }
+    });
+
+    addr
+}
+
+fn bench_session_round_trip(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("tokio runtime");
+    let session = runtime.block_on(async {
+        let addr = start_mock_server().await;
+        let config = DeribitFixConfig::new()
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_secs(1));
+        let connection = Connection::new(&config)
+            .await
+            .expect("connect to mock server");
+        Arc::new(Mutex::new(
+            Session::new(&config, Arc::new(Mutex::new(connection))).expect("create session"),
+        ))
+    });
+
+    c.bench_function("session/test_request_round_trip", |b| {
+        b.to_async(&runtime).iter(|| {
+            let session = session.clone();
+            async move {
+                let mut session = session.lock().await;
+                session
+                    .send(&TestRequest::new("BENCH".to_string()))
+                    .await
+                    .expect("send test request");
+                loop {
+                    if let Some(message) = session
+                        .receive_and_process_message()
+                        .await
+                        .expect("receive heartbeat")
+                        && message.msg_type() == Some(MsgType::Heartbeat)
+                    {
+                        break message;
+                    }
+                }
+            }
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_build,
+    bench_parse,
+    bench_checksum,
+    bench_session_round_trip
+);
+
+fn main() {
+    report_allocations();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
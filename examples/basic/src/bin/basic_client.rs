@@ -28,7 +28,7 @@ async fn main() -> Result<()> {
     info!("Creating Deribit FIX client...");
 
     // Create the client
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     info!("Connecting to Deribit FIX server...");
 
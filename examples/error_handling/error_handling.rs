@@ -62,7 +62,7 @@ async fn main() -> Result<()> {
         Ok(_) => {
             info!("Creating client with invalid host configuration...");
             match DeribitFixClient::new(&invalid_host_config).await {
-                Ok(mut client) => {
+                Ok(client) => {
                     info!("Client created, attempting connection to invalid host...");
                     match client.connect().await {
                         Ok(_) => {
@@ -107,7 +107,7 @@ async fn main() -> Result<()> {
     match config.validate() {
         Ok(_) => {
             info!("Creating client for basic error testing...");
-            let mut client = DeribitFixClient::new(&config).await?;
+            let client = DeribitFixClient::new(&config).await?;
 
             info!("Connecting to server...");
             match client.connect().await {
@@ -122,10 +122,10 @@ async fn main() -> Result<()> {
                                 // Process logon messages
                             }
 
-                            if let Some(state) = client.get_session_state().await {
-                                if state == SessionState::LoggedOn {
-                                    return Ok::<(), DeribitFixError>(());
-                                }
+                            if let Some(state) = client.get_session_state().await
+                                && state == SessionState::LoggedOn
+                            {
+                                return Ok::<(), DeribitFixError>(());
                             }
 
                             sleep(Duration::from_millis(100)).await;
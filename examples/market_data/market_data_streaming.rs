@@ -35,7 +35,7 @@ async fn main() -> Result<()> {
     }
 
     info!("Creating Deribit FIX client...");
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     info!("Connecting to Deribit FIX server...");
     client.connect().await?;
@@ -51,10 +51,10 @@ async fn main() -> Result<()> {
                 // Process any initial messages
             }
 
-            if let Some(state) = client.get_session_state().await {
-                if state == SessionState::LoggedOn {
-                    return Ok::<(), DeribitFixError>(());
-                }
+            if let Some(state) = client.get_session_state().await
+                && state == SessionState::LoggedOn
+            {
+                return Ok::<(), DeribitFixError>(());
             }
 
             sleep(Duration::from_millis(100)).await;
@@ -98,11 +98,11 @@ async fn main() -> Result<()> {
         info!("Market data streaming active... update #{}", update_count);
 
         // Check if we're still connected
-        if let Some(state) = client.get_session_state().await {
-            if state != SessionState::LoggedOn {
-                error!("Session is no longer logged on, stopping market data streaming");
-                break;
-            }
+        if let Some(state) = client.get_session_state().await
+            && state != SessionState::LoggedOn
+        {
+            error!("Session is no longer logged on, stopping market data streaming");
+            break;
         }
     }
 
@@ -36,7 +36,7 @@ async fn main() -> Result<()> {
     }
 
     info!("Creating Deribit FIX client...");
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     info!("Connecting to Deribit FIX server...");
     client.connect().await?;
@@ -54,10 +54,10 @@ async fn main() -> Result<()> {
             }
 
             // Check session state
-            if let Some(state) = client.get_session_state().await {
-                if state == SessionState::LoggedOn {
-                    return Ok::<(), DeribitFixError>(());
-                }
+            if let Some(state) = client.get_session_state().await
+                && state == SessionState::LoggedOn
+            {
+                return Ok::<(), DeribitFixError>(());
             }
             sleep(Duration::from_millis(100)).await;
         }
@@ -7,8 +7,7 @@
 //! 4. Display position details
 //! 5. Close the position at the end
 
-use chrono;
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::model::request::{NewOrderRequest, OrderSide};
 use deribit_fix::prelude::*;
 use deribit_fix::session::SessionState;
 use std::time::Duration;
@@ -38,7 +37,7 @@ async fn main() -> Result<()> {
     }
 
     info!("Creating Deribit FIX client...");
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     info!("Connecting to Deribit FIX server...");
     client.connect().await?;
@@ -54,10 +53,10 @@ async fn main() -> Result<()> {
                 // Process any initial messages
             }
 
-            if let Some(state) = client.get_session_state().await {
-                if state == SessionState::LoggedOn {
-                    return Ok::<(), DeribitFixError>(());
-                }
+            if let Some(state) = client.get_session_state().await
+                && state == SessionState::LoggedOn
+            {
+                return Ok::<(), DeribitFixError>(());
             }
 
             sleep(Duration::from_millis(100)).await;
@@ -79,30 +78,17 @@ async fn main() -> Result<()> {
     let instrument = "BTC-PERPETUAL";
     let quantity = 10.0;
 
-    let market_order_request = NewOrderRequest {
-        instrument_name: instrument.to_string(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Market,
-        amount: quantity,
-        price: None,
-        time_in_force: TimeInForce::ImmediateOrCancel,
-        post_only: Some(false),
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let market_order_request = NewOrderRequest::market_buy(instrument.to_string(), quantity)
+        .with_post_only(false)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "MARKET_BUY_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "MARKET_BUY_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     info!(
         "Sending market buy order: {} {} at market price",
@@ -121,7 +107,7 @@ async fn main() -> Result<()> {
     };
 
     // Wait for order processing and execution
-    if market_order_id.is_some() {
+    if let Some(market_order_id) = &market_order_id {
         info!("Waiting for order execution...");
         let mut fill_confirmed = false;
         let start_time = std::time::Instant::now();
@@ -135,7 +121,7 @@ async fn main() -> Result<()> {
                     {
                         // ExecutionReport
                         if let Some(recv_cl_ord_id) = message.get_field(11)
-                            && recv_cl_ord_id == market_order_id.as_ref().unwrap()
+                            && recv_cl_ord_id == market_order_id
                             && let Some(ord_status) = message.get_field(39)
                             && (ord_status == "2" || ord_status == "1")
                         {
@@ -227,30 +213,22 @@ async fn main() -> Result<()> {
                 };
                 let close_quantity = position.size.abs();
 
-                let close_order_request = NewOrderRequest {
-                    instrument_name: instrument.to_string(),
-                    side: close_side,
-                    order_type: OrderType::Market,
-                    amount: close_quantity,
-                    price: None,
-                    time_in_force: TimeInForce::ImmediateOrCancel,
-                    post_only: Some(false),
-                    reduce_only: Some(true), // This ensures we only reduce the position
-                    client_order_id: Some(format!(
-                        "MARKET_CLOSE_{}",
-                        chrono::Utc::now().timestamp_millis()
-                    )),
-                    label: Some(format!(
-                        "MARKET_CLOSE_{}",
-                        chrono::Utc::now().timestamp_millis()
-                    )),
-                    stop_price: None,
-                    trigger: None,
-                    advanced: None,
-                    max_show: None,
-                    reject_post_only: None,
-                    valid_until: None,
-                };
+                let close_order_request = match close_side {
+                    OrderSide::Sell => {
+                        NewOrderRequest::market_sell(instrument.to_string(), close_quantity)
+                    }
+                    _ => NewOrderRequest::market_buy(instrument.to_string(), close_quantity),
+                }
+                .with_post_only(false)
+                .with_reduce_only(true) // This ensures we only reduce the position
+                .with_client_order_id(format!(
+                    "MARKET_CLOSE_{}",
+                    chrono::Utc::now().timestamp_millis()
+                ))
+                .with_label(format!(
+                    "MARKET_CLOSE_{}",
+                    chrono::Utc::now().timestamp_millis()
+                ));
 
                 info!(
                     "Sending market {:?} order to close position: {} {} (reduce-only)",
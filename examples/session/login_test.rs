@@ -43,7 +43,7 @@ async fn main() -> Result<()> {
     }
 
     // Create the client
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     info!("Attempting to connect to Deribit FIX server...");
 
@@ -31,7 +31,7 @@ async fn main() -> Result<()> {
     }
 
     info!("Creating Deribit FIX client...");
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     info!("Connecting to Deribit FIX server...");
     client.connect().await?;
@@ -136,7 +136,7 @@ async fn main() -> Result<()> {
 
     // Example 4: Multiple gap scenarios
     info!("--- Example 4: Multiple Gap Recovery ---");
-    let gap_scenarios = vec![
+    let gap_scenarios = [
         (10, 15, "Small gap"),
         (100, 105, "Medium gap"),
         (500, 520, "Large gap"),
@@ -202,7 +202,7 @@ async fn main() -> Result<()> {
     info!("--- Example 6: Resend Request Validation ---");
 
     // Valid scenarios
-    let valid_cases = vec![
+    let valid_cases = [
         ResendRequest::new(1, 10),
         ResendRequest::new(50, 50),            // Single message
         ResendRequest::new_from_sequence(100), // Infinite
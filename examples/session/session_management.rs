@@ -36,7 +36,7 @@ async fn main() -> Result<()> {
     }
 
     info!("Creating Deribit FIX client...");
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Monitor session state during connection
     info!(
@@ -65,11 +65,11 @@ async fn main() -> Result<()> {
             }
 
             let current_state = client.get_session_state().await;
-            if let Some(state) = current_state {
-                if state == SessionState::LoggedOn {
-                    info!("Session state changed to LoggedOn!");
-                    return Ok::<(), DeribitFixError>(());
-                }
+            if let Some(state) = current_state
+                && state == SessionState::LoggedOn
+            {
+                info!("Session state changed to LoggedOn!");
+                return Ok::<(), DeribitFixError>(());
             }
 
             sleep(Duration::from_millis(100)).await;
@@ -95,11 +95,11 @@ async fn main() -> Result<()> {
 
     while start_time.elapsed() < monitor_duration {
         // Check session state periodically
-        if let Some(state) = client.get_session_state().await {
-            if state != SessionState::LoggedOn {
-                error!("Session is no longer logged on: {:?}", state);
-                break;
-            }
+        if let Some(state) = client.get_session_state().await
+            && state != SessionState::LoggedOn
+        {
+            error!("Session is no longer logged on: {:?}", state);
+            break;
         }
 
         // Keep the connection alive
@@ -144,10 +144,10 @@ async fn main() -> Result<()> {
                         // Process reconnection messages
                     }
 
-                    if let Some(state) = client.get_session_state().await {
-                        if state == SessionState::LoggedOn {
-                            return Ok::<(), DeribitFixError>(());
-                        }
+                    if let Some(state) = client.get_session_state().await
+                        && state == SessionState::LoggedOn
+                    {
+                        return Ok::<(), DeribitFixError>(());
                     }
 
                     sleep(Duration::from_millis(100)).await;
@@ -0,0 +1,34 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Fuzz `FixMessage::parse` with arbitrary text
+//!
+//! The fields of a message that parses, written back as `tag=value` pairs,
+//! must parse again to the same fields.
+
+#![no_main]
+
+use deribit_fix::model::message::FixMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(raw) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(message) = FixMessage::parse(raw) else {
+        return;
+    };
+    let _ = message.msg_type();
+    let _ = message.msg_seq_num();
+
+    let rendered: String = message
+        .fields
+        .iter()
+        .map(|(tag, value)| format!("{tag}={value}\x01"))
+        .collect();
+    let reparsed = FixMessage::parse(&rendered).expect("written fields parse");
+    assert_eq!(reparsed.fields, message.fields);
+});
@@ -0,0 +1,46 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Fuzz the connection framing with arbitrary bytes split into reads
+//!
+//! The first byte sets the read size, so truncated messages and messages
+//! spread over several reads are covered. Every frame must start with
+//! BeginString (8), end with the CheckSum (10) trailer and stay within
+//! `MAX_BODY_LENGTH`, and a framing error must never stop the framer from
+//! making progress.
+
+#![no_main]
+
+use deribit_fix::connection::{FixFramer, MAX_BODY_LENGTH};
+use deribit_fix::model::message::FixMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&read_size, stream)) = data.split_first() else {
+        return;
+    };
+    let read_size = usize::from(read_size.max(1));
+
+    let mut framer = FixFramer::new();
+    for read in stream.chunks(read_size) {
+        framer.push(read);
+        // Each call consumes a frame or at least one byte on error
+        for _ in 0..=framer.buffered().len() {
+            match framer.next_frame() {
+                Ok(Some(frame)) => {
+                    assert!(frame.starts_with(b"8=FIX"));
+                    assert!(frame.ends_with(b"\x01"));
+                    assert!(frame.len() <= MAX_BODY_LENGTH + 64);
+                    if let Ok(raw) = std::str::from_utf8(&frame) {
+                        let _ = FixMessage::parse(raw);
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => {}
+            }
+        }
+    }
+});
@@ -0,0 +1,35 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Fuzz the UTCTimestamp, UTCDateOnly, UTCTimeOnly and TZTimeOnly parsers
+//!
+//! A parsed UTCTimestamp must format and parse back to the same instant at
+//! the millisecond precision of the formatter.
+
+#![no_main]
+
+use deribit_fix::message::time::{
+    format_utc_timestamp, parse_tz_time_only, parse_utc_date_only, parse_utc_time_only,
+    parse_utc_timestamp,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(value) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(timestamp) = parse_utc_timestamp(value) {
+        let reparsed = parse_utc_timestamp(&format_utc_timestamp(&timestamp))
+            .expect("a formatted timestamp parses");
+        assert_eq!(
+            reparsed.timestamp_millis(),
+            timestamp.timestamp_millis()
+        );
+    }
+    let _ = parse_utc_date_only(value);
+    let _ = parse_utc_time_only(value);
+    let _ = parse_tz_time_only(value);
+});
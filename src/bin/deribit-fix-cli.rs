@@ -0,0 +1,295 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Command line for diagnosing a Deribit FIX session
+//!
+//! Built with the `cli` feature:
+//!
+//! ```text
+//! cargo run --features cli --bin deribit-fix-cli -- logon
+//! ```
+//!
+//! The session is configured like any client, from the `DERIBIT_*`
+//! environment variables and `.env`, or from a TOML or YAML file given with
+//! `--config`.
+
+use deribit_fix::message::FixPrettyPrinter;
+use deribit_fix::prelude::*;
+use deribit_fix::recorder::{MarketDataPlayback, PlaybackSpeed};
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::{Instant, sleep};
+
+const USAGE: &str = "\
+Usage: deribit-fix-cli [--config <path>] <command> [arguments]
+
+Commands:
+  logon                            Log on, print the session details and log out
+  heartbeat [count]                Send Test Requests and print their round trips (default: 3)
+  book <symbol> [depth] [seconds]  Subscribe to a symbol and print its book as it changes
+                                   (default: 5 levels for 10 seconds)
+  positions                        Print the open positions
+  journal <path>                   Print the messages of a market data recording
+
+The session is configured from the DERIBIT_* environment variables and .env,
+or from the TOML or YAML file given with --config.";
+
+/// Command given on the command line
+#[derive(Debug)]
+enum Command {
+    Logon,
+    Heartbeat {
+        count: u32,
+    },
+    Book {
+        symbol: String,
+        depth: usize,
+        duration: Duration,
+    },
+    Positions,
+    Journal {
+        path: String,
+    },
+}
+
+/// Parsed command line
+#[derive(Debug)]
+struct Arguments {
+    config: Option<String>,
+    command: Command,
+}
+
+impl Arguments {
+    fn parse(mut args: impl Iterator<Item = String>) -> std::result::Result<Self, String> {
+        let mut config = None;
+        let mut words = Vec::new();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => {
+                    config = Some(args.next().ok_or("--config needs a path")?);
+                }
+                "-h" | "--help" => return Err(String::new()),
+                _ => words.push(arg),
+            }
+        }
+        let mut words = words.into_iter();
+        let command = match words.next().as_deref() {
+            Some("logon") => Command::Logon,
+            Some("heartbeat") => Command::Heartbeat {
+                count: number(words.next(), 3)?,
+            },
+            Some("book") => Command::Book {
+                symbol: words.next().ok_or("book needs a symbol")?,
+                depth: number(words.next(), 5)?,
+                duration: Duration::from_secs(number(words.next(), 10)?),
+            },
+            Some("positions") => Command::Positions,
+            Some("journal") => Command::Journal {
+                path: words
+                    .next()
+                    .ok_or("journal needs the path of a recording")?,
+            },
+            Some(other) => return Err(format!("unknown command {other}")),
+            None => return Err(String::new()),
+        };
+        if let Some(extra) = words.next() {
+            return Err(format!("unexpected argument {extra}"));
+        }
+        Ok(Self { config, command })
+    }
+
+    fn load_config(&self) -> Result<DeribitFixConfig> {
+        let config = match &self.config {
+            Some(path) => DeribitFixConfig::from_file(path)?,
+            None => DeribitFixConfig::new(),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Parse an optional numeric argument
+fn number<T: std::str::FromStr>(arg: Option<String>, default: T) -> std::result::Result<T, String> {
+    arg.map_or(Ok(default), |arg| {
+        arg.parse()
+            .map_err(|_| format!("{arg} is not a valid number"))
+    })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let arguments = match Arguments::parse(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("error: {message}\n");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    setup_logger();
+    match run(arguments).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(arguments: Arguments) -> Result<()> {
+    if let Command::Journal { path } = &arguments.command {
+        return print_journal(path).await;
+    }
+
+    let config = arguments.load_config()?;
+    println!(
+        "Connecting to {} as {}",
+        config.connection_url(),
+        config.sender_comp_id
+    );
+    let client = DeribitFixClient::new(&config).await?;
+    let started = Instant::now();
+    client.connect().await?;
+    println!("Logged on in {:?}", started.elapsed());
+
+    let outcome = match arguments.command {
+        Command::Logon => print_session(&client).await,
+        Command::Heartbeat { count } => send_heartbeats(&client, count).await,
+        Command::Book {
+            symbol,
+            depth,
+            duration,
+        } => watch_book(&client, &symbol, depth, duration).await,
+        Command::Positions => print_positions(&client).await,
+        Command::Journal { .. } => unreachable!("journals are read without a session"),
+    };
+    let logout = client.disconnect().await;
+    outcome.and(logout)
+}
+
+async fn print_session(client: &DeribitFixClient) -> Result<()> {
+    if let Some(state) = client.get_session_state().await {
+        println!("Session state:     {state:?}");
+    }
+    let capabilities = client.server_capabilities().await?;
+    if let Some(interval) = capabilities.heartbeat_interval {
+        println!("Heartbeat:         {interval} s");
+    }
+    if let Some(version) = &capabilities.default_appl_ver_id {
+        println!("DefaultApplVerID:  {version}");
+    }
+    println!("Logon tags:        {:?}", capabilities.logon_tags);
+    if let Some(handshake) = client
+        .connection_stats()
+        .await
+        .and_then(|stats| stats.handshake)
+    {
+        println!("Resolve:           {:?}", handshake.resolve);
+        println!("TCP connect:       {:?}", handshake.tcp_connect);
+        if let Some(tls) = handshake.tls_handshake {
+            println!("TLS handshake:     {tls:?}");
+        }
+    }
+    Ok(())
+}
+
+async fn send_heartbeats(client: &DeribitFixClient, count: u32) -> Result<()> {
+    for attempt in 1..=count {
+        let round_trip = client.ping().await?;
+        println!("Test Request {attempt}: answered in {round_trip:?}");
+        if attempt < count {
+            sleep(Duration::from_secs(1)).await;
+        }
+    }
+    Ok(())
+}
+
+async fn watch_book(
+    client: &DeribitFixClient,
+    symbol: &str,
+    depth: usize,
+    duration: Duration,
+) -> Result<()> {
+    let mut messages = client.subscribe_messages()?;
+    client.subscribe_market_data(symbol.to_string()).await?;
+    let deadline = Instant::now() + duration;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => break,
+            received = messages.recv() => match received {
+                Ok(message) => {
+                    let is_market_data = matches!(
+                        message.msg_type(),
+                        Some(MsgType::MarketDataSnapshotFullRefresh | MsgType::MarketDataIncrementalRefresh)
+                    );
+                    if is_market_data
+                        && let Some(book) = client.order_book(symbol).await?
+                    {
+                        print_book(&book, depth);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err(DeribitFixError::Connection("Session closed".to_string()));
+                }
+            },
+        }
+    }
+    client.unsubscribe_market_data(symbol).await?;
+    Ok(())
+}
+
+fn print_book(book: &OrderBook, depth: usize) {
+    println!("\n{}", book.symbol());
+    println!(
+        "{:>16} {:>12} | {:<12} {:<16}",
+        "bid size", "bid", "ask", "ask size"
+    );
+    let (bids, asks) = (book.bids(), book.asks());
+    for level in 0..depth.min(bids.len().max(asks.len())) {
+        let side = |levels: &[(f64, f64)]| levels.get(level).copied();
+        let (bid, bid_size) = side(&bids).map_or((String::new(), String::new()), |(p, s)| {
+            (p.to_string(), s.to_string())
+        });
+        let (ask, ask_size) = side(&asks).map_or((String::new(), String::new()), |(p, s)| {
+            (p.to_string(), s.to_string())
+        });
+        println!("{bid_size:>16} {bid:>12} | {ask:<12} {ask_size:<16}");
+    }
+}
+
+async fn print_positions(client: &DeribitFixClient) -> Result<()> {
+    let positions = client.get_positions().await?;
+    if positions.is_empty() {
+        println!("No open positions");
+    }
+    for position in positions {
+        println!(
+            "{:<24} {:?} {} @ {}",
+            position.instrument_name, position.direction, position.size, position.average_price
+        );
+    }
+    Ok(())
+}
+
+async fn print_journal(path: &str) -> Result<()> {
+    let mut playback = MarketDataPlayback::open(path)?.with_speed(PlaybackSpeed::AsFastAsPossible);
+    let printer = FixPrettyPrinter::new();
+    let mut count = 0u64;
+    while let Some(recorded) = playback.next_message().await? {
+        println!(
+            "{} {}",
+            recorded.received_at.format("%Y-%m-%d %H:%M:%S%.6f"),
+            printer.render(&recorded.message)
+        );
+        count += 1;
+    }
+    println!("{count} messages");
+    Ok(())
+}
@@ -0,0 +1,352 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Session task and command channel
+//!
+//! Every session of a [`DeribitFixClient`](crate::DeribitFixClient) is owned
+//! by its own task instead of being shared behind a mutex. The task reads and
+//! processes incoming messages, which the session publishes to its
+//! [message subscribers](Session::subscribe_messages), and runs the commands
+//! that client calls and background tasks send over an mpsc channel. A
+//! command interrupts a read that is waiting for data, so an order is written
+//! as soon as it is submitted instead of after the listener releases the
+//! session.
+//!
+//! Commands run one at a time in the order they were sent, except that
+//! cancels ([`Priority::Cancel`]) wait in a queue of their own which is
+//! always served first: under a burst of orders, a cancel runs as soon as the
+//! command in progress ends instead of behind every queued order. So that a
+//! cancel never reaches the exchange before the order it targets, the
+//! waiting orders it covers are withdrawn and never sent. A command
+//! that waits for a response, such as a cancel, keeps the session until the
+//! response arrives or its deadline passes. At most
+//! [`max_pending_commands`](crate::config::DeribitFixConfig::max_pending_commands)
+//! commands wait in each queue; further calls fail with
+//! [`DeribitFixError::QueueFull`] rather than queueing orders without bound.
+
+use crate::error::{DeribitFixError, Result};
+use crate::model::message::FixMessage;
+use crate::model::request::NewOrderRequest;
+use crate::session::fix_session::Incoming;
+use crate::session::{RequestOptions, Session};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::{Notify, broadcast, mpsc, oneshot};
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Future of a command, borrowing the session while it runs
+pub(crate) type SessionFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Command run by the session task
+type Command = Box<dyn for<'a> FnOnce(&'a mut Session) -> SessionFuture<'a, ()> + Send>;
+
+/// Box a closure as a [`Command`]
+fn command<F>(f: F) -> Command
+where
+    F: for<'a> FnOnce(&'a mut Session) -> SessionFuture<'a, ()> + Send + 'static,
+{
+    Box::new(f)
+}
+
+/// Queue a command waits in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Priority {
+    /// Run after the commands sent before it
+    Normal,
+    /// Run before any waiting [`Normal`](Priority::Normal) command, for
+    /// Order Cancel Requests (F) and Order Mass Cancel Requests (q)
+    Cancel,
+}
+
+/// Read error that stopped the session task from receiving
+#[derive(Debug, Default)]
+struct Failure {
+    error: Mutex<Option<DeribitFixError>>,
+    /// Set until the next command, which may have reconnected the session
+    stopped: AtomicBool,
+    notify: Notify,
+}
+
+/// Depth counters of the command queue
+#[derive(Debug, Default)]
+struct QueueCounters {
+    /// Most commands waiting at once
+    high_water: AtomicUsize,
+    /// Commands refused because the queue was full
+    rejected: AtomicU64,
+}
+
+/// Orders whose command waits in the queue
+#[derive(Debug, Default)]
+struct WaitingOrders {
+    next_id: u64,
+    /// Orders of each waiting command, `None` once withdrawn
+    orders: HashMap<u64, Vec<Option<NewOrderRequest>>>,
+}
+
+/// Handle to a session owned by its task
+///
+/// The task ends when every handle is dropped.
+#[derive(Debug, Clone)]
+pub(crate) struct SessionHandle {
+    commands: mpsc::Sender<Command>,
+    cancels: mpsc::Sender<Command>,
+    messages: broadcast::Sender<FixMessage>,
+    failure: Arc<Failure>,
+    queue: Arc<QueueCounters>,
+    waiting_orders: Arc<Mutex<WaitingOrders>>,
+}
+
+impl SessionHandle {
+    /// Move `session` into a new task
+    pub(crate) fn spawn(session: Session) -> Self {
+        let capacity = session.max_pending_commands().max(1);
+        let (commands, receiver) = mpsc::channel(capacity);
+        let (cancels, cancel_receiver) = mpsc::channel(capacity);
+        let messages = session.message_sender();
+        let failure = Arc::new(Failure::default());
+        tokio::spawn(run(session, receiver, cancel_receiver, failure.clone()));
+        Self {
+            commands,
+            cancels,
+            messages,
+            failure,
+            queue: Arc::new(QueueCounters::default()),
+            waiting_orders: Arc::default(),
+        }
+    }
+
+    /// Most commands that waited at once and commands refused for a full
+    /// queue
+    pub(crate) fn queue_watermarks(&self) -> (usize, u64) {
+        (
+            self.queue.high_water.load(Ordering::Relaxed),
+            self.queue.rejected.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Subscribe to the messages received by the session
+    pub(crate) fn subscribe_messages(&self) -> broadcast::Receiver<FixMessage> {
+        self.messages.subscribe()
+    }
+
+    /// Run `f` on the session, applying `options` to the responses it awaits
+    ///
+    /// The deadline and cancellation of `options` also bound the wait behind
+    /// earlier commands; a command given up before it starts is skipped.
+    pub(crate) async fn call<T, F>(&self, options: &RequestOptions, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Session) -> SessionFuture<'a, T> + Send + 'static,
+    {
+        self.call_with_priority(options, Priority::Normal, f).await
+    }
+
+    /// Run `f` on the session with `orders`, unless a cancel withdraws them
+    /// while they wait
+    ///
+    /// `f` gets the orders in the order given, each `None` if
+    /// [`withdraw_orders`](Self::withdraw_orders) took it out.
+    pub(crate) async fn call_orders<T, F>(
+        &self,
+        options: &RequestOptions,
+        orders: Vec<NewOrderRequest>,
+        f: F,
+    ) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Session, Vec<Option<NewOrderRequest>>) -> SessionFuture<'a, T>
+            + Send
+            + 'static,
+    {
+        let id = {
+            let mut waiting = self.waiting_orders();
+            let id = waiting.next_id;
+            waiting.next_id += 1;
+            waiting
+                .orders
+                .insert(id, orders.into_iter().map(Some).collect());
+            id
+        };
+        let waiting_orders = self.waiting_orders.clone();
+        let called = self
+            .call(options, move |session| {
+                let orders = waiting_orders
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .orders
+                    .remove(&id)
+                    .unwrap_or_default();
+                f(session, orders)
+            })
+            .await;
+        // Left behind when the command was refused or given up
+        self.waiting_orders().orders.remove(&id);
+        called
+    }
+
+    /// Withdraw the orders still waiting to be sent that `matches` selects
+    ///
+    /// Returns how many orders were withdrawn.
+    pub(crate) fn withdraw_orders(&self, matches: impl Fn(&NewOrderRequest) -> bool) -> usize {
+        let mut withdrawn = 0;
+        for order in self.waiting_orders().orders.values_mut().flatten() {
+            if order.as_ref().is_some_and(&matches) {
+                *order = None;
+                withdrawn += 1;
+            }
+        }
+        withdrawn
+    }
+
+    /// Lock the orders waiting to be sent
+    fn waiting_orders(&self) -> std::sync::MutexGuard<'_, WaitingOrders> {
+        self.waiting_orders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Run `f` on the session from the queue of `priority`
+    pub(crate) async fn call_with_priority<T, F>(
+        &self,
+        options: &RequestOptions,
+        priority: Priority,
+        f: F,
+    ) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Session) -> SessionFuture<'a, T> + Send + 'static,
+    {
+        let (reply, response) = oneshot::channel();
+        let request_options = options.clone();
+        let issued_at = Instant::now();
+        let command = command(move |session| {
+            Box::pin(async move {
+                if reply.is_closed() {
+                    return;
+                }
+                session.set_request_options(request_options);
+                session.set_command_issued_at(Some(issued_at));
+                let output = f(&mut *session).await;
+                session.set_command_issued_at(None);
+                session.set_request_options(RequestOptions::default());
+                let _ = reply.send(output);
+            })
+        });
+        let queue = match priority {
+            Priority::Normal => &self.commands,
+            Priority::Cancel => &self.cancels,
+        };
+        match queue.try_send(command) {
+            Ok(()) => {
+                let waiting = queue.max_capacity() - queue.capacity();
+                self.queue.high_water.fetch_max(waiting, Ordering::Relaxed);
+            }
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                self.queue.rejected.fetch_add(1, Ordering::Relaxed);
+                return Err(DeribitFixError::QueueFull(format!(
+                    "{} commands already wait for the session",
+                    queue.max_capacity()
+                )));
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => return Err(task_stopped()),
+        }
+        options
+            .run("Waiting for the session", response)
+            .await?
+            .map_err(|_| task_stopped())
+    }
+
+    /// Error that stopped the session from receiving, while it is stopped
+    ///
+    /// The error itself is returned once; later calls report that the session
+    /// is not receiving until a command runs.
+    pub(crate) fn failure(&self) -> Option<DeribitFixError> {
+        if !self.failure.stopped.load(Ordering::SeqCst) {
+            return None;
+        }
+        let error = self
+            .failure
+            .error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        Some(error.unwrap_or_else(|| {
+            DeribitFixError::Connection("Session stopped receiving".to_string())
+        }))
+    }
+
+    /// Wait until the session stops receiving
+    pub(crate) async fn failed(&self) {
+        self.failure.notify.notified().await;
+    }
+}
+
+/// Error of a call on a session whose task has ended
+fn task_stopped() -> DeribitFixError {
+    DeribitFixError::Session("Session task stopped".to_string())
+}
+
+/// What the session task does next
+enum Next {
+    /// Run a command, or stop once every handle is dropped
+    Command(Option<Command>),
+    /// Process what the session received
+    Incoming(Incoming),
+}
+
+/// Receive messages and run commands until every handle is dropped
+///
+/// Waiting cancels run before waiting commands, and both before a read. A
+/// command only interrupts the wait for data: a received message, or a logon
+/// retry after exchange maintenance, is processed to the end before the
+/// next command runs.
+async fn run(
+    mut session: Session,
+    mut commands: mpsc::Receiver<Command>,
+    mut cancels: mpsc::Receiver<Command>,
+    failure: Arc<Failure>,
+) {
+    loop {
+        if failure.stopped.load(Ordering::SeqCst) {
+            let command = tokio::select! {
+                biased;
+                command = cancels.recv() => command,
+                command = commands.recv() => command,
+            };
+            let Some(command) = command else {
+                break;
+            };
+            command(&mut session).await;
+            failure.stopped.store(false, Ordering::SeqCst);
+            continue;
+        }
+        let next = tokio::select! {
+            biased;
+            command = cancels.recv() => Next::Command(command),
+            command = commands.recv() => Next::Command(command),
+            incoming = session.wait_incoming(Duration::MAX) => Next::Incoming(incoming),
+        };
+        match next {
+            Next::Command(Some(command)) => command(&mut session).await,
+            Next::Command(None) => break,
+            Next::Incoming(incoming) => {
+                if let Err(e) = session.process_incoming(incoming).await {
+                    debug!("Session stopped receiving: {}", e);
+                    *failure.error.lock().unwrap_or_else(PoisonError::into_inner) = Some(e);
+                    failure.stopped.store(true, Ordering::SeqCst);
+                    failure.notify.notify_one();
+                }
+            }
+        }
+    }
+    debug!("Session task stopped");
+}
@@ -0,0 +1,182 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Orders sent together
+//!
+//! [`DeribitFixClient::send_orders`] writes a ladder of New Order Singles (D)
+//! to the socket in one batch and returns an [`OrderBatch`], with a
+//! [`PendingOrder`] per order waiting for its acknowledgement. With
+//! [`cancel_on_reject`](OrderBatch::cancel_on_reject) the batch is made
+//! all-or-nothing on a best-effort basis: when any order is refused, the
+//! orders of the batch the exchange accepted are cancelled again. Orders
+//! filled before their cancel arrives stay filled.
+
+use crate::client::{DeribitFixClient, PendingResponse};
+use crate::error::Result;
+use crate::message::ExecutionReport;
+use crate::model::cancel::CancelTarget;
+use crate::model::message::FixMessage;
+use tracing::warn;
+
+/// Order of an [`OrderBatch`] waiting for its acknowledgement
+pub struct PendingOrder {
+    cl_ord_id: String,
+    symbol: String,
+    response: Result<PendingResponse>,
+}
+
+impl PendingOrder {
+    pub(crate) fn new(
+        cl_ord_id: String,
+        symbol: String,
+        response: Result<PendingResponse>,
+    ) -> Self {
+        Self {
+            cl_ord_id,
+            symbol,
+            response,
+        }
+    }
+
+    /// ClOrdID (11) of the order
+    pub fn cl_ord_id(&self) -> &str {
+        &self.cl_ord_id
+    }
+
+    /// Instrument of the order
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Whether the order was sent
+    ///
+    /// An order refused locally, by the risk checks or a halted instrument,
+    /// is not.
+    pub fn is_sent(&self) -> bool {
+        self.response.is_ok()
+    }
+
+    /// Wait for the first Execution Report (8) of the order
+    ///
+    /// The order being refused locally or rejected by the exchange is
+    /// returned as the error, such as
+    /// [`DeribitFixError::OrderRejected`](crate::error::DeribitFixError::OrderRejected).
+    pub async fn acknowledgement(self) -> Result<FixMessage> {
+        self.response?.response().await
+    }
+}
+
+/// Acknowledgements of the orders of an [`OrderBatch`]
+#[derive(Debug)]
+pub struct BatchOutcome {
+    /// Acknowledgement of each order, in the order of the batch
+    pub acknowledgements: Vec<Result<FixMessage>>,
+    /// ClOrdIDs (11) of the accepted orders cancelled because another order
+    /// of the batch was refused
+    pub cancelled: Vec<String>,
+}
+
+impl BatchOutcome {
+    /// Whether every order of the batch was accepted
+    pub fn is_accepted(&self) -> bool {
+        self.acknowledgements.iter().all(Result::is_ok)
+    }
+}
+
+/// Orders written to the socket together by [`DeribitFixClient::send_orders`]
+pub struct OrderBatch {
+    client: DeribitFixClient,
+    orders: Vec<PendingOrder>,
+    cancel_on_reject: bool,
+}
+
+impl OrderBatch {
+    pub(crate) fn new(client: DeribitFixClient, orders: Vec<PendingOrder>) -> Self {
+        Self {
+            client,
+            orders,
+            cancel_on_reject: false,
+        }
+    }
+
+    /// Cancel the accepted orders when any order of the batch is refused
+    pub fn cancel_on_reject(mut self) -> Self {
+        self.cancel_on_reject = true;
+        self
+    }
+
+    /// Number of orders in the batch
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Whether the batch has no orders
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Orders of the batch, in the order they were given
+    pub fn orders(&self) -> &[PendingOrder] {
+        &self.orders
+    }
+
+    /// Wait for each order separately
+    ///
+    /// Nothing is cancelled when the orders are awaited this way.
+    pub fn into_orders(self) -> Vec<PendingOrder> {
+        self.orders
+    }
+
+    /// Wait for the acknowledgement of every order
+    ///
+    /// With [`cancel_on_reject`](Self::cancel_on_reject), a refused order
+    /// cancels the orders that were accepted and are still open; a cancel
+    /// that fails is logged and the order left as it is.
+    pub async fn acknowledgements(self) -> BatchOutcome {
+        let mut acknowledgements = Vec::with_capacity(self.orders.len());
+        for order in self.orders {
+            acknowledgements.push(order.acknowledgement().await);
+        }
+
+        let mut cancelled = Vec::new();
+        if self.cancel_on_reject && acknowledgements.iter().any(Result::is_err) {
+            for ack in acknowledgements.iter().flatten() {
+                let Ok(report) = ExecutionReport::from_fix_message(ack) else {
+                    continue;
+                };
+                if report.ord_status.is_terminal() {
+                    continue;
+                }
+                match cancel_accepted(&self.client, &report).await {
+                    Ok(()) => cancelled.push(report.cl_ord_id),
+                    Err(e) => warn!(
+                        "Failed to cancel order {} of a refused batch: {}",
+                        report.cl_ord_id, e
+                    ),
+                }
+            }
+        }
+        BatchOutcome {
+            acknowledgements,
+            cancelled,
+        }
+    }
+}
+
+/// Cancel an accepted order by its exchange OrderID (37), or by ClOrdID when
+/// the exchange did not report one
+async fn cancel_accepted(client: &DeribitFixClient, report: &ExecutionReport) -> Result<()> {
+    let target = if report.order_id.is_empty() {
+        CancelTarget::ClOrdId {
+            cl_ord_id: report.cl_ord_id.clone(),
+            symbol: report.symbol.clone(),
+        }
+    } else {
+        CancelTarget::OrderId(report.order_id.clone())
+    };
+    client.cancel(target).await?;
+    Ok(())
+}
@@ -1,24 +1,105 @@
 //! Deribit FIX client implementation
 
 use crate::{
+    client::actor::{Priority, SessionFuture, SessionHandle},
+    client::{InstrumentBatch, InstrumentStream, OrderBatch, PendingOrder, PendingResponse},
     config::DeribitFixConfig,
-    connection::Connection,
+    connection::{Connection, ConnectionStats, TcpConnector, TransportConnector, WriteStats},
     error::{DeribitFixError, Result},
+    message::{
+        CustomMessage, ExecutionReport, MassQuote, MassQuoteAcknowledgement, MdEntryType,
+        OrderCancelReplaceRequest, OrderMassCancelReport, QuoteRequest, RfqRequest, SecurityInfo,
+        SecurityListRequest, ToFixMessage,
+    },
+    model::account::AccountSummary,
+    model::cancel::{CancelReport, CancelTarget},
+    model::capabilities::ServerCapabilities,
+    model::combo::ComboOrderRequest,
+    model::index_stream::IndexUpdate,
+    model::instrument_registry::{InstrumentRegistry, InstrumentSpec},
+    model::label_routing::LabelExecutions,
+    model::ladder::{Ladder, LadderAction},
+    model::latency::LatencyStats,
+    model::market_state::InstrumentState,
+    model::market_stats::{FundingSample, MarketStats},
+    model::message::FixMessage,
+    model::message_filter::MessageStream,
+    model::option_ticker::OptionTicker,
+    model::order_book::OrderBook,
+    model::order_index::{OrderOwnership, OrderReconciliation},
+    model::order_template::{OrderTemplate, OrderTemplates},
+    model::order_tracker::{AuditFormat, OrderLifecycle},
     model::position::Position,
+    model::public_trade::PublicTrade,
+    model::quoting::{QuoteAction, QuotingEngine},
     model::request::NewOrderRequest,
-    session::Session,
+    model::subscription::MarketDataSubscription,
+    model::tags,
+    model::throttle::ThrottleStats,
+    model::top_of_book::TopOfBook,
+    model::trade_stream::TradeStream,
+    recorder::{DEFAULT_EXPORT_QUEUE, MarketDataExportHandle, MarketDataExporter},
+    session::{
+        CancelOnDisconnectStatus, CancelToken, ConnectionHealth, RequestIdGenerator,
+        RequestOptions, Session,
+    },
 };
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::info;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+use tokio::sync::{Mutex, broadcast, oneshot};
+use tracing::{debug, info, warn};
+
+/// Longest [`DeribitFixClient::receive_message`] waits before returning `None`
+const RECEIVE_WAIT: Duration = Duration::from_secs(1);
 
 /// Main Deribit FIX client
+///
+/// The client is a cheap handle: clones share the same connection and session,
+/// so orders can be submitted and market data consumed concurrently from
+/// several tasks without wrapping the client in a `Mutex`. The session runs
+/// in its own task and calls are sent to it as commands, so submitting an
+/// order never waits for a read.
+#[derive(Clone)]
 pub struct DeribitFixClient {
     /// Client configuration
     pub config: DeribitFixConfig,
+    connector: Arc<dyn TransportConnector>,
+    request_options: RequestOptions,
+    state: Arc<RwLock<ClientState>>,
+}
+
+/// Connection state shared by every clone of a [`DeribitFixClient`]
+#[derive(Default)]
+struct ClientState {
     connection: Option<Arc<Mutex<Connection>>>,
-    session: Option<Arc<Mutex<Session>>>,
+    session: Option<SessionHandle>,
+    /// Messages of the session read by [`DeribitFixClient::receive_message`]
+    inbox: Option<Arc<Mutex<broadcast::Receiver<FixMessage>>>>,
     heartbeat_task: Option<tokio::task::JoinHandle<()>>,
+    watchdog_task: Option<tokio::task::JoinHandle<()>>,
+    /// Task probing and reconnecting a silent counterparty
+    liveness_task: Option<tokio::task::JoinHandle<()>>,
+    /// Task cancelling every order when the application misses its keep-alive
+    dead_mans_switch_task: Option<tokio::task::JoinHandle<()>>,
+    /// Task polling the funding rates of the tracked perpetuals
+    funding_task: Option<tokio::task::JoinHandle<()>>,
+    /// Task keeping the hot standby alive and failing over to it
+    standby_task: Option<tokio::task::JoinHandle<()>>,
+    standby: Option<Standby>,
+    /// Number of times traffic failed over to the standby connection
+    failovers: u64,
+    /// Username and password replacing those of the configuration
+    credentials: Option<(String, String)>,
+    /// Templates of [`DeribitFixClient::order_from_template`]
+    templates: OrderTemplates,
+}
+
+/// Hot standby connection, with its session when it is logged on with its
+/// own SenderCompID
+struct Standby {
+    connection: Arc<Mutex<Connection>>,
+    session: Option<SessionHandle>,
 }
 
 impl DeribitFixClient {
@@ -28,123 +109,992 @@ impl DeribitFixClient {
         let config = config.clone();
         Ok(Self {
             config,
-            connection: None,
-            session: None,
-            heartbeat_task: None,
+            connector: Arc::new(TcpConnector::new()),
+            request_options: RequestOptions::default(),
+            state: Arc::new(RwLock::new(ClientState::default())),
         })
     }
 
+    /// Open connections through `connector` instead of TCP/TLS
+    pub fn with_connector(mut self, connector: Arc<dyn TransportConnector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
+    /// Get a handle whose calls apply `options`
+    ///
+    /// The handle shares the connection and session of this client. Its calls
+    /// give up when the deadline passes or the cancel token is cancelled,
+    /// whether they are waiting for the session or for a response; without a
+    /// deadline, responses are awaited for the configured
+    /// [`request_timeout`](DeribitFixConfig::request_timeout).
+    pub fn with_request_options(&self, options: RequestOptions) -> Self {
+        Self {
+            request_options: options,
+            ..self.clone()
+        }
+    }
+
+    /// Read the shared state; the lock is never held across an await point
+    fn state(&self) -> RwLockReadGuard<'_, ClientState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Update the shared state; the lock is never held across an await point
+    fn state_mut(&self) -> RwLockWriteGuard<'_, ClientState> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Session of the current connection
+    fn session(&self) -> Result<SessionHandle> {
+        self.state()
+            .session
+            .clone()
+            .ok_or_else(|| DeribitFixError::Session("Not connected".to_string()))
+    }
+
+    /// Run `f` on the session within the deadline and cancellation of the
+    /// request options, applying them to the responses the call awaits
+    async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Session) -> SessionFuture<'a, T> + Send + 'static,
+    {
+        self.session()?.call(&self.request_options, f).await
+    }
+
+    /// Run `f` on the session with `orders` after the calls waiting for it,
+    /// each order `None` if a cancel withdrew it meanwhile
+    async fn call_orders<T, F>(&self, orders: Vec<NewOrderRequest>, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Session, Vec<Option<NewOrderRequest>>) -> SessionFuture<'a, T>
+            + Send
+            + 'static,
+    {
+        self.session()?
+            .call_orders(&self.request_options, orders, f)
+            .await
+    }
+
+    /// Run `f` on the session ahead of the calls waiting for it, for a cancel
+    /// of `target`
+    ///
+    /// The waiting orders `target` covers are withdrawn first, since sent
+    /// after the cancel they would stay open. When `target` is a single order
+    /// that was still waiting, there is nothing left to cancel and `None` is
+    /// returned without running `f`.
+    async fn call_cancel<T, F>(&self, target: &CancelTarget, f: F) -> Result<Option<T>>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Session) -> SessionFuture<'a, T> + Send + 'static,
+    {
+        let session = self.session()?;
+        let withdrawn = session.withdraw_orders(|order| target.matches_order(order));
+        if withdrawn > 0 {
+            info!("Withdrew {} waiting orders for {:?}", withdrawn, target);
+            if !target.is_mass_cancel() {
+                return Ok(None);
+            }
+        }
+        session
+            .call_with_priority(&self.request_options, Priority::Cancel, f)
+            .await
+            .map(Some)
+    }
+
+    /// Make `session` the primary session, reading its messages in
+    /// [`receive_message`](Self::receive_message)
+    fn set_primary(&self, session: SessionHandle, inbox: broadcast::Receiver<FixMessage>) {
+        let mut state = self.state_mut();
+        state.session = Some(session);
+        state.inbox = Some(Arc::new(Mutex::new(inbox)));
+    }
+
+    /// Configuration of new sessions, with any rotated credentials
+    fn session_config(&self) -> DeribitFixConfig {
+        match self.state().credentials.clone() {
+            Some((username, password)) => self.config.clone().with_credentials(username, password),
+            None => self.config.clone(),
+        }
+    }
+
     /// Connect to the Deribit FIX server
-    pub async fn connect(&mut self) -> Result<()> {
+    ///
+    /// With [`hot_standby`](DeribitFixConfig::hot_standby) enabled, a second
+    /// connection is opened once the primary one is logged on; see
+    /// [`failover`](Self::failover).
+    pub async fn connect(&self) -> Result<()> {
         info!(
             "Connecting to Deribit FIX server at {}",
             self.config.connection_url()
         );
 
-        // Create connection
-        let connection = Connection::new(&self.config).await?;
-        self.connection = Some(Arc::new(Mutex::new(connection)));
+        // Create connection and session
+        let config = self.session_config();
+        let connection = Arc::new(Mutex::new(
+            Connection::with_connector(&config, self.connector.clone()).await?,
+        ));
+        let mut session = Session::new(&config, connection.clone())?;
+        self.state_mut().connection = Some(connection);
 
-        // Create session
-        let session = Session::new(&self.config, self.connection.as_ref().unwrap().clone())?;
-        self.session = Some(Arc::new(Mutex::new(session)));
+        // Perform logon, then hand the session to its task
+        session.logon().await?;
+        let inbox = session.subscribe_messages();
+        let session = SessionHandle::spawn(session);
+        self.set_primary(session.clone(), inbox);
+        self.start_session_tasks(&session);
 
-        // Perform logon
-        self.logon().await?;
+        if self.config.hot_standby {
+            if let Err(e) = self.open_standby().await {
+                warn!("Failed to open the standby connection: {}", e);
+            }
+            self.start_standby_task();
+        }
 
+        info!("Successfully connected to Deribit FIX server");
+        Ok(())
+    }
+
+    /// Start the heartbeat, latency watchdog and liveness tasks of the primary session
+    fn start_session_tasks(&self, session: &SessionHandle) {
         // Start background heartbeat task to keep the session alive
-        if let Some(session) = &self.session {
-            let session_arc = session.clone();
-            let hb_interval_secs = self.config.heartbeat_interval as u64;
-            self.heartbeat_task = Some(tokio::spawn(async move {
-                use tokio::time::{Duration, sleep};
+        let handle = session.clone();
+        let clock = self.config.clock.clone();
+        let hb_interval = Duration::from_secs(u64::from(self.config.heartbeat_interval));
+        let heartbeat_task = tokio::spawn(async move {
+            loop {
+                clock.sleep(hb_interval).await;
+                // Only send heartbeat when logged on, so that heartbeats resume
+                // after a re-logon; stop loop when the session task ended
+                let sent = handle
+                    .call(&RequestOptions::default(), |session| {
+                        Box::pin(async move {
+                            if session.get_state().is_logged_on() {
+                                let _ = session.send_heartbeat(None).await;
+                            }
+                        })
+                    })
+                    .await;
+                if sent.is_err() {
+                    break;
+                }
+            }
+        });
+        if let Some(previous) = self.state_mut().heartbeat_task.replace(heartbeat_task) {
+            previous.abort();
+        }
+
+        // Start the latency watchdog, keeping at most one Test Request outstanding
+        if let Some(ping_interval) = self.config.ping_interval {
+            let handle = session.clone();
+            let clock = self.config.clock.clone();
+            let watchdog_task = tokio::spawn(async move {
                 loop {
-                    sleep(Duration::from_secs(hb_interval_secs)).await;
-                    let mut guard = session_arc.lock().await;
-                    // Only send heartbeat when logged on; stop loop when not active
-                    if guard.get_state() == crate::session::SessionState::LoggedOn {
-                        let _ = guard.send_heartbeat(None).await;
-                    } else {
+                    clock.sleep(ping_interval).await;
+                    let checked = handle
+                        .call(&RequestOptions::default(), |session| {
+                            Box::pin(async move {
+                                if !session.get_state().is_logged_on() {
+                                    return false;
+                                }
+                                if !session.check_ping_latency() {
+                                    let _ = session.send_test_request().await;
+                                }
+                                true
+                            })
+                        })
+                        .await;
+                    if !matches!(checked, Ok(true)) {
                         break;
                     }
                 }
-            }));
+            });
+            if let Some(previous) = self.state_mut().watchdog_task.replace(watchdog_task) {
+                previous.abort();
+            }
         }
 
-        info!("Successfully connected to Deribit FIX server");
+        // Start the liveness checks, probing and reconnecting a silent counterparty
+        if self.config.liveness_missed_heartbeats.is_some() {
+            let handle = session.clone();
+            let clock = self.config.clock.clone();
+            let probe_timeout = self.config.liveness_probe_timeout.unwrap_or(hb_interval);
+            let check_interval = (hb_interval.min(probe_timeout) / 2).max(Duration::from_millis(1));
+            let liveness_task = tokio::spawn(async move {
+                loop {
+                    clock.sleep(check_interval).await;
+                    let checked = handle
+                        .call(&RequestOptions::default(), |session| {
+                            Box::pin(async move { session.check_liveness().await })
+                        })
+                        .await;
+                    match checked {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => warn!("Liveness check failed: {}", e),
+                        Err(_) => break,
+                    }
+                }
+            });
+            if let Some(previous) = self.state_mut().liveness_task.replace(liveness_task) {
+                previous.abort();
+            }
+        }
+
+        // Start the dead man's switch, cancelling every order on a missed keep-alive
+        if let Some(window) = self.config.dead_mans_switch {
+            let handle = session.clone();
+            let clock = self.config.clock.clone();
+            let check_interval = (window / 4).max(Duration::from_millis(1));
+            let switch_task = tokio::spawn(async move {
+                loop {
+                    clock.sleep(check_interval).await;
+                    let checked = handle
+                        .call(&RequestOptions::default(), |session| {
+                            Box::pin(async move { session.check_dead_mans_switch().await })
+                        })
+                        .await;
+                    match checked {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => warn!("Dead man's switch check failed: {}", e),
+                        Err(_) => break,
+                    }
+                }
+            });
+            if let Some(previous) = self.state_mut().dead_mans_switch_task.replace(switch_task) {
+                previous.abort();
+            }
+        }
+    }
+
+    /// Start polling the funding rates of the perpetuals tracked by `session`,
+    /// unless already polling them
+    fn start_funding_task(&self, session: SessionHandle) {
+        let mut state = self.state_mut();
+        if state
+            .funding_task
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            return;
+        }
+        let clock = self.config.clock.clone();
+        let poll_interval = (self.config.funding_poll_interval / 2).max(Duration::from_millis(1));
+        let funding_task = tokio::spawn(async move {
+            loop {
+                clock.sleep(poll_interval).await;
+                let polled = session
+                    .call(&RequestOptions::default(), |session| {
+                        Box::pin(async move { session.poll_funding().await })
+                    })
+                    .await;
+                match polled {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => warn!("Funding poll failed: {}", e),
+                    Err(_) => break,
+                }
+            }
+        });
+        state.funding_task = Some(funding_task);
+    }
+
+    /// Open the standby connection, logging it on when it has its own SenderCompID
+    async fn open_standby(&self) -> Result<()> {
+        let config = self.session_config();
+        let connection = Arc::new(Mutex::new(
+            Connection::with_connector(&config, self.connector.clone()).await?,
+        ));
+        let session = match &self.config.standby_sender_comp_id {
+            Some(sender_comp_id) => {
+                let target_comp_id = config.target_comp_id.clone();
+                let config = config.with_session_ids(sender_comp_id.clone(), target_comp_id);
+                let mut session = Session::new(&config, connection.clone())?;
+                session.logon().await?;
+                Some(SessionHandle::spawn(session))
+            }
+            None => None,
+        };
+        self.state_mut().standby = Some(Standby {
+            connection,
+            session,
+        });
+        debug!("Standby connection opened");
+        Ok(())
+    }
+
+    /// Start the task failing over when the primary connection dies
+    ///
+    /// Every half heartbeat interval the task checks the primary transport,
+    /// fails over when it is closed, and otherwise keeps the standby alive,
+    /// opening a new one when it is missing or closed.
+    fn start_standby_task(&self) {
+        let client = self.clone();
+        let interval = Duration::from_millis(u64::from(self.config.heartbeat_interval) * 500);
+        let clock = self.config.clock.clone();
+        let standby_task = tokio::spawn(async move {
+            loop {
+                clock.sleep(interval).await;
+                if client.primary_failed() && client.has_standby() {
+                    if let Err(e) = client.failover().await {
+                        warn!("Failover to the standby connection failed: {}", e);
+                    }
+                    continue;
+                }
+                if !client.keep_standby_alive().await {
+                    let closed = client.state_mut().standby.take();
+                    if let Some(standby) = closed {
+                        let _ = standby.connection.lock().await.close().await;
+                    }
+                    if let Err(e) = client.open_standby().await {
+                        debug!("Failed to open the standby connection: {}", e);
+                    }
+                }
+            }
+        });
+        if let Some(previous) = self.state_mut().standby_task.replace(standby_task) {
+            previous.abort();
+        }
+    }
+
+    /// Whether the primary transport is closed
+    ///
+    /// A connection busy reading is left alone; the reader notices it closing.
+    fn primary_failed(&self) -> bool {
+        let Some(connection) = self.state().connection.clone() else {
+            return false;
+        };
+        connection
+            .try_lock()
+            .is_ok_and(|connection| !connection.is_connected())
+    }
+
+    /// Process what the standby received and keep its session alive with a
+    /// Heartbeat; returns whether the standby is still usable
+    async fn keep_standby_alive(&self) -> bool {
+        let Some((connection, session)) = self
+            .state()
+            .standby
+            .as_ref()
+            .map(|standby| (standby.connection.clone(), standby.session.clone()))
+        else {
+            return false;
+        };
+        match session {
+            Some(session) => {
+                // The standby session task reads what the standby receives
+                if session.failure().is_some() {
+                    return false;
+                }
+                let alive = session
+                    .call(&RequestOptions::default(), |session| {
+                        Box::pin(async move {
+                            if session.get_state().is_logged_on() {
+                                let _ = session.send_heartbeat(None).await;
+                            }
+                            session.get_state() != crate::session::SessionState::Disconnected
+                        })
+                    })
+                    .await;
+                alive.unwrap_or(false) && connection.lock().await.is_connected()
+            }
+            None => {
+                let mut connection = connection.lock().await;
+                connection
+                    .receive_message_within(Duration::ZERO)
+                    .await
+                    .is_ok()
+                    && connection.is_connected()
+            }
+        }
+    }
+
+    /// Move traffic to the standby connection
+    ///
+    /// A standby logged on with its own SenderCompID takes over at once;
+    /// otherwise a new session logs on over the standby connection. Requests
+    /// in flight on the primary connection are not replayed, and market data
+    /// must be subscribed to again on the new session. The primary connection
+    /// is closed, and a new standby is opened in the background.
+    pub async fn failover(&self) -> Result<()> {
+        let standby = self.state_mut().standby.take().ok_or_else(|| {
+            DeribitFixError::Connection("No standby connection to fail over to".to_string())
+        })?;
+        let (session, inbox) = match standby.session {
+            Some(session) => {
+                let inbox = session.subscribe_messages();
+                (session, inbox)
+            }
+            None => {
+                let mut session = Session::new(&self.session_config(), standby.connection.clone())?;
+                session.logon().await?;
+                let inbox = session.subscribe_messages();
+                (SessionHandle::spawn(session), inbox)
+            }
+        };
+
+        self.set_primary(session.clone(), inbox);
+        let previous = {
+            let mut state = self.state_mut();
+            state.failovers += 1;
+            state.connection.replace(standby.connection)
+        };
+        self.start_session_tasks(&session);
+        if let Some(previous) = previous {
+            // The primary may still be locked by a reader waiting for data
+            tokio::spawn(async move {
+                let _ = previous.lock().await.close().await;
+            });
+        }
+        warn!("Failed over to the standby connection");
         Ok(())
     }
 
+    /// Whether a hot standby connection is open
+    pub fn has_standby(&self) -> bool {
+        self.state().standby.is_some()
+    }
+
+    /// Number of times traffic failed over to the standby connection
+    pub fn failover_count(&self) -> u64 {
+        self.state().failovers
+    }
+
+    /// Rotate the credentials and log on again with them
+    ///
+    /// Every clone of the client uses the new credentials, including on later
+    /// connects. When connected, the session logs out, keeping open orders,
+    /// and logs on again over a new connection with fresh auth data.
+    pub async fn update_credentials(&self, username: String, password: String) -> Result<()> {
+        if username.is_empty() || password.is_empty() {
+            return Err(DeribitFixError::Config(
+                "Username and password cannot be empty".to_string(),
+            ));
+        }
+        self.state_mut().credentials = Some((username.clone(), password.clone()));
+        if self.session().is_err() {
+            return Ok(());
+        }
+
+        self.call(|session| {
+            Box::pin(async move {
+                session.update_credentials(username, password);
+                if session
+                    .get_state()
+                    .can_transition_to(crate::session::SessionState::LogoutSent)
+                {
+                    session
+                        .logout_with_options(Some("Credential rotation".to_string()), Some(true))
+                        .await?;
+                }
+                session.relogon().await
+            })
+        })
+        .await?
+    }
+
     /// Disconnect from the server
-    pub async fn disconnect(&mut self) -> Result<()> {
+    ///
+    /// Every clone of the client is disconnected.
+    pub async fn disconnect(&self) -> Result<()> {
         info!("Disconnecting from Deribit FIX server");
 
-        // Stop heartbeat task if running
-        if let Some(handle) = self.heartbeat_task.take() {
+        let (
+            heartbeat_task,
+            watchdog_task,
+            liveness_task,
+            dead_mans_switch_task,
+            funding_task,
+            standby_task,
+            standby,
+            session,
+            connection,
+        ) = {
+            let mut state = self.state_mut();
+            (
+                state.heartbeat_task.take(),
+                state.watchdog_task.take(),
+                state.liveness_task.take(),
+                state.dead_mans_switch_task.take(),
+                state.funding_task.take(),
+                state.standby_task.take(),
+                state.standby.take(),
+                state.session.take(),
+                state.connection.take(),
+            )
+        };
+        self.state_mut().inbox = None;
+
+        // Stop heartbeat, watchdog, liveness, dead man's switch, funding and
+        // standby tasks if running
+        for handle in [
+            heartbeat_task,
+            watchdog_task,
+            liveness_task,
+            dead_mans_switch_task,
+            funding_task,
+            standby_task,
+        ]
+        .into_iter()
+        .flatten()
+        {
             handle.abort();
         }
 
-        if let Some(session) = &self.session {
-            let mut session_guard = session.lock().await;
-            session_guard.logout().await?;
+        // The standby is closed on a best effort basis
+        if let Some(standby) = standby {
+            if let Some(session) = standby.session {
+                let _ = session
+                    .call(&RequestOptions::default(), |session| {
+                        Box::pin(async move { logout_if_logged_on(session).await })
+                    })
+                    .await;
+            }
+            let _ = standby.connection.lock().await.close().await;
         }
 
-        if let Some(connection) = &self.connection {
+        if let Some(session) = session {
+            session
+                .call(&RequestOptions::default(), |session| {
+                    Box::pin(async move { logout_if_logged_on(session).await })
+                })
+                .await??;
+        }
+
+        if let Some(connection) = connection {
             let mut connection_guard = connection.lock().await;
             connection_guard.close().await?;
         }
 
-        self.connection = None;
-        self.session = None;
-
         info!("Successfully disconnected from Deribit FIX server");
         Ok(())
     }
 
+    /// Log out with `text` as the Text (58) of the Logout (5), wait for the
+    /// server's Logout and disconnect
+    ///
+    /// The connection is closed anyway when the server does not answer
+    /// within [`logout_timeout`](DeribitFixConfig::logout_timeout). Returns
+    /// whether the logout was acknowledged.
+    pub async fn logout(&self, text: Option<String>) -> Result<bool> {
+        let acknowledged = self
+            .call(move |session| Box::pin(async move { session.logout_and_wait(text).await }))
+            .await??;
+        self.disconnect().await?;
+        Ok(acknowledged)
+    }
+
+    /// Reconnect and log on again, keeping the current session
+    ///
+    /// Unlike [`disconnect`](Self::disconnect) followed by
+    /// [`connect`](Self::connect), the session keeps its subscriptions,
+    /// order tracking and sequence numbers. Retries up to
+    /// [`reconnect_attempts`](DeribitFixConfig::reconnect_attempts) times.
+    pub async fn relogon(&self) -> Result<()> {
+        self.call(|session| Box::pin(async move { session.relogon().await }))
+            .await?
+    }
+
     /// Check if the client is connected
     pub fn is_connected(&self) -> bool {
-        self.connection.is_some() && self.session.is_some()
+        let state = self.state();
+        state.connection.is_some() && state.session.is_some()
     }
 
     /// Get the current session state
     pub async fn get_session_state(&self) -> Option<crate::session::SessionState> {
-        if let Some(session) = &self.session {
-            // Use async lock to properly wait for session access
-            let session_guard = session.lock().await;
-            Some(session_guard.get_state())
-        } else {
-            None
-        }
+        self.call(|session| Box::pin(async move { session.get_state() }))
+            .await
+            .ok()
     }
 
-    /// Perform FIX logon
-    async fn logon(&self) -> Result<()> {
-        if let Some(session) = &self.session {
-            let mut session_guard = session.lock().await;
-            session_guard.logon().await?;
-        }
-        Ok(())
+    /// Check the connection with a Test Request and return the round-trip time
+    ///
+    /// Resolves when the Heartbeat echoing the generated TestReqID (112)
+    /// arrives. A round trip above `max_ping_latency` marks the connection
+    /// as degraded.
+    pub async fn ping(&self) -> Result<std::time::Duration> {
+        self.call(|session| Box::pin(async move { session.ping().await }))
+            .await?
+    }
+
+    /// Tell the dead man's switch that the application is alive
+    ///
+    /// With [`dead_mans_switch`](DeribitFixConfig::dead_mans_switch) set,
+    /// every open order is cancelled when this is not called at least once
+    /// per window. Does nothing when the switch is disabled.
+    pub async fn keep_alive(&self) -> Result<()> {
+        self.call(|session| Box::pin(async move { session.keep_alive() }))
+            .await
+    }
+
+    /// Allow logging on again after the server rejected the credentials
+    ///
+    /// A Logout rejecting the credentials opens a circuit breaker so that
+    /// the session does not lock the account with repeated attempts.
+    /// [`update_credentials`](Self::update_credentials) closes it as well.
+    pub async fn reset_logon_circuit(&self) -> Result<()> {
+        self.call(|session| Box::pin(async move { session.reset_logon_circuit() }))
+            .await
+    }
+
+    /// Get what the server was seen to accept and send since the last logon
+    ///
+    /// Lets the caller branch on the differences between the test and
+    /// production servers, such as the DefaultApplVerID (1137) agreed at
+    /// logon or the custom tags the server sends. Enable
+    /// [`capability_probe`](crate::config::DeribitFixConfig::capability_probe)
+    /// for the Test Request probe and the custom tags of every message.
+    pub async fn server_capabilities(&self) -> Result<ServerCapabilities> {
+        self.call(|session| Box::pin(async move { session.server_capabilities().clone() }))
+            .await
+    }
+
+    /// Get the request ID generator of the current session, to build
+    /// ClOrdIDs (11) that never collide with the IDs the session generates
+    pub async fn request_ids(&self) -> Result<Arc<RequestIdGenerator>> {
+        self.call(|session| Box::pin(async move { session.request_ids() }))
+            .await
+    }
+
+    /// Get the connection health measured by [`ping`](Self::ping) and the watchdog
+    pub async fn connection_health(&self) -> Option<ConnectionHealth> {
+        self.call(|session| Box::pin(async move { session.connection_health() }))
+            .await
+            .ok()
+    }
+
+    /// Get the cancel-on-disconnect setting of the session, while logged on
+    pub async fn cancel_on_disconnect(&self) -> Option<CancelOnDisconnectStatus> {
+        self.call(|session| Box::pin(async move { session.cancel_on_disconnect() }))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Enable or disable cancel-on-disconnect for the rest of the session
+    ///
+    /// A logged-on session whose setting differs logs out keeping its
+    /// orders open and logs on again with the new setting, see
+    /// [`Session::set_cancel_on_disconnect`]. Returns the setting of the
+    /// new logon, or `None` when the session is not logged on.
+    pub async fn set_cancel_on_disconnect(
+        &self,
+        enabled: bool,
+    ) -> Result<Option<CancelOnDisconnectStatus>> {
+        self.call(move |session| {
+            Box::pin(async move { session.set_cancel_on_disconnect(enabled).await })
+        })
+        .await?
+    }
+
+    /// Write any batched messages to the socket without waiting for the batch delay
+    pub async fn flush(&self) -> Result<()> {
+        self.call(|session| Box::pin(async move { session.flush().await }))
+            .await?
+    }
+
+    /// Get the counters of the messages written to the connection
+    ///
+    /// Shows how many messages write batching coalesced into each write.
+    pub async fn write_stats(&self) -> Option<WriteStats> {
+        self.call(|session| Box::pin(async move { session.write_stats().await }))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Get the traffic counters of the connection
+    ///
+    /// Bytes and messages by MsgType in each direction, the time of the last
+    /// message each way, the reconnect count, the last Test Request round
+    /// trip and the watermarks of the buffers and queues, to feed dashboards
+    /// and alerting.
+    pub async fn connection_stats(&self) -> Option<ConnectionStats> {
+        let mut stats = self
+            .call(|session| Box::pin(async move { session.connection_stats().await }))
+            .await
+            .ok()
+            .flatten()?;
+        let (high_water, rejected) = self.session().ok()?.queue_watermarks();
+        stats.buffers.pending_commands_high_water = high_water;
+        stats.buffers.rejected_commands = rejected;
+        Some(stats)
+    }
+
+    /// Get the order-entry latency percentiles
+    ///
+    /// Per-stage p50, p90, p99 and max of the recent acknowledged orders,
+    /// replaces and cancels; `None` unless
+    /// [`latency_tracing`](crate::config::DeribitFixConfig::latency_tracing)
+    /// is enabled.
+    pub async fn latency_stats(&self) -> Option<LatencyStats> {
+        self.call(|session| Box::pin(async move { session.latency_stats() }))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Get the pacing of outgoing messages
+    ///
+    /// The allowed rate is lowered by the exchange's rate-limit rejections
+    /// when [`adaptive_throttling`](DeribitFixConfig::adaptive_throttling) is
+    /// enabled, so strategies can pace their own requests to it.
+    pub async fn throttle_stats(&self) -> Option<ThrottleStats> {
+        self.call(|session| Box::pin(async move { session.throttle_stats() }))
+            .await
+            .ok()
+    }
+
+    /// Send any typed FIX message through the session
+    ///
+    /// Comp IDs, sequence number and SendingTime are handled internally.
+    /// Returns the MsgSeqNum assigned to the message.
+    pub async fn send(&self, message: impl ToFixMessage + Send + Sync + 'static) -> Result<u32> {
+        self.call(|session| Box::pin(async move { session.send(&message).await }))
+            .await?
+    }
+
+    /// Send a message type or tags the crate has no typed support for yet
+    ///
+    /// The session supplies the header and sequence number; see
+    /// [`CustomMessage::new`] for the checks applied to `msg_type` and
+    /// `fields`. The returned [`PendingResponse`] waits for the answer.
+    pub async fn send_custom(
+        &self,
+        msg_type: &str,
+        fields: Vec<(u32, String)>,
+    ) -> Result<PendingResponse> {
+        let message = CustomMessage::new(msg_type, fields)?;
+        let correlation = message.fields().to_vec();
+        self.send_correlated(message, correlation).await
+    }
+
+    /// Send a Request For Quote (AH) to the market makers
+    ///
+    /// The returned [`PendingResponse`] is answered by the messages echoing
+    /// the RFQReqID (644) of the request; a Quote Request Reject (AG) for it
+    /// fails [`response`](PendingResponse::response) with
+    /// [`DeribitFixError::QuoteRequestRejected`]
+    /// instead of waiting for the request timeout.
+    pub async fn send_rfq(&self, request: RfqRequest) -> Result<PendingResponse> {
+        // Deribit rejects an RFQ with its RFQReqID as QuoteReqID (131)
+        let correlation = vec![
+            (tags::RFQ_REQ_ID, request.rfq_req_id.clone()),
+            (tags::QUOTE_REQ_ID, request.rfq_req_id.clone()),
+        ];
+        self.send_correlated(request, correlation).await
+    }
+
+    /// Send a Quote Request (R)
+    ///
+    /// The returned [`PendingResponse`] is answered by the messages echoing
+    /// the QuoteReqID (131) of the request; a Quote Request Reject (AG) for
+    /// it fails [`response`](PendingResponse::response) with
+    /// [`DeribitFixError::QuoteRequestRejected`].
+    pub async fn send_quote_request(&self, request: QuoteRequest) -> Result<PendingResponse> {
+        let correlation = vec![(tags::QUOTE_REQ_ID, request.quote_req_id.clone())];
+        self.send_correlated(request, correlation).await
+    }
+
+    /// Send `message` and await the responses echoing `correlation`
+    async fn send_correlated(
+        &self,
+        message: impl ToFixMessage + Send + Sync + 'static,
+        correlation: Vec<(u32, String)>,
+    ) -> Result<PendingResponse> {
+        // Subscribed before sending so the response cannot be missed
+        let messages = self.session()?.subscribe_messages();
+        let msg_seq_num = self
+            .call(|session| Box::pin(async move { session.send(&message).await }))
+            .await??;
+        Ok(
+            PendingResponse::new(messages, Some(msg_seq_num), &correlation, &self.config)
+                .with_options(self.request_options.clone()),
+        )
+    }
+
+    /// Submit linked orders as an OCO (one-cancels-other) group
+    pub async fn submit_order_group(&self, orders: Vec<NewOrderRequest>) -> Result<String> {
+        self.call(move |session| Box::pin(async move { session.submit_order_group(orders).await }))
+            .await?
     }
 
     /// Send a new order
+    ///
+    /// An order still waiting for the session when a cancel covering it is
+    /// sent is withdrawn and fails with [`DeribitFixError::Cancelled`].
     pub async fn send_order(&self, order: NewOrderRequest) -> Result<String> {
-        if let Some(session) = &self.session {
-            let mut session_guard = session.lock().await;
-            session_guard.send_new_order(order).await
-        } else {
-            Err(DeribitFixError::Session("Not connected".to_string()))
-        }
+        self.call_orders(vec![order], |session, mut orders| {
+            Box::pin(async move {
+                match orders.pop().flatten() {
+                    Some(order) => session.send_new_order(order).await,
+                    None => Err(order_withdrawn()),
+                }
+            })
+        })
+        .await?
+    }
+
+    /// Send new orders written to the socket in one batch
+    ///
+    /// Suited to placing a ladder: the New Order Singles (D) leave together
+    /// and each [`PendingOrder`] of the returned [`OrderBatch`] waits for the
+    /// acknowledgement of its order. An order refused by the risk checks or
+    /// a halted instrument, or withdrawn by a cancel while the batch waited
+    /// for the session, is not sent and its acknowledgement returns the
+    /// error; [`OrderBatch::cancel_on_reject`] cancels the accepted orders
+    /// when any order is refused.
+    pub async fn send_orders(&self, orders: Vec<NewOrderRequest>) -> Result<OrderBatch> {
+        let symbols: Vec<String> = orders
+            .iter()
+            .map(|order| order.instrument_name.clone())
+            .collect();
+        let cl_ord_ids: Vec<String> = orders
+            .iter()
+            .map(|order| order.client_order_id.clone().unwrap_or_default())
+            .collect();
+        // Subscribed before sending so no acknowledgement can be missed
+        let session = self.session()?;
+        let receivers: Vec<_> = orders
+            .iter()
+            .map(|_| session.subscribe_messages())
+            .collect();
+        let sent = self
+            .call_orders(orders, move |session, orders| {
+                Box::pin(async move {
+                    let kept = orders.iter().flatten().cloned().collect();
+                    let mut sent = session.send_new_orders(kept).await.into_iter();
+                    orders
+                        .iter()
+                        .zip(cl_ord_ids)
+                        .filter_map(|(order, cl_ord_id)| match order {
+                            Some(_) => sent.next(),
+                            None => Some((cl_ord_id, Err(order_withdrawn()))),
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .await?;
+        let orders = sent
+            .into_iter()
+            .zip(symbols.into_iter().zip(receivers))
+            .map(|((cl_ord_id, result), (symbol, messages))| {
+                let response = result.map(|msg_seq_num| {
+                    let correlation = [(tags::CL_ORD_ID, cl_ord_id.clone())];
+                    PendingResponse::new(messages, msg_seq_num, &correlation, &self.config)
+                        .with_options(self.request_options.clone())
+                });
+                PendingOrder::new(cl_ord_id, symbol, response)
+            })
+            .collect();
+        Ok(OrderBatch::new(self.clone(), orders))
+    }
+
+    /// Register `template` as `name` for [`order_from_template`](Self::order_from_template)
+    ///
+    /// Replaces any template of that name; a template whose flags conflict
+    /// is refused.
+    pub fn register_template(&self, name: &str, template: OrderTemplate) -> Result<()> {
+        self.state_mut().templates.register(name, template)
+    }
+
+    /// Remove the template registered as `name`
+    pub fn remove_template(&self, name: &str) -> Option<OrderTemplate> {
+        self.state_mut().templates.remove(name)
+    }
+
+    /// Send a limit order at `price` for `amount` from the template registered
+    /// as `name`
+    ///
+    /// Returns the ClOrdID of the order.
+    pub async fn order_from_template(&self, name: &str, price: f64, amount: f64) -> Result<String> {
+        let order = self.state_mut().templates.order(name, price, amount)?;
+        self.send_order(order).await
+    }
+
+    /// Send an order on a combo instrument, given by its symbol or its legs
+    ///
+    /// Legs are resolved against the combo instruments received in Security
+    /// List or Security Definition messages.
+    pub async fn send_combo_order(&self, order: ComboOrderRequest) -> Result<String> {
+        self.call(move |session| Box::pin(async move { session.send_combo_order(order).await }))
+            .await?
+    }
+
+    /// Receive the Execution Reports of the orders labelled `label`
+    ///
+    /// Lets several strategies share one session: each labels its orders
+    /// with [`NewOrderRequest::with_label`] and reads its own fills from the
+    /// returned channel while any task drives
+    /// [`receive_message`](Self::receive_message). The channel belongs to the
+    /// current session and closes on disconnect or failover.
+    pub async fn executions_for_label(&self, label: &str) -> Result<LabelExecutions> {
+        let label = label.to_string();
+        self.call(move |session| Box::pin(async move { session.executions_for_label(&label) }))
+            .await
+    }
+
+    /// Export the lifecycle of every order of the session as CSV or JSON
+    ///
+    /// Covers the orders sent and reported since the current session was
+    /// created, with their state transitions, fills and fees.
+    pub async fn export_order_audit(&self, format: AuditFormat) -> Result<String> {
+        self.call(move |session| {
+            Box::pin(async move { session.order_tracker().export_string(format) })
+        })
+        .await?
+    }
+
+    /// Lifecycle of the order with ClOrdID (11) or OrderID (37) `id`
+    ///
+    /// For iceberg orders the lifecycle gives the quantity shown on the book
+    /// and the total remaining quantity, see
+    /// [`OrderLifecycle::displayed_qty`].
+    pub async fn order_lifecycle(&self, id: &str) -> Result<Option<OrderLifecycle>> {
+        let id = id.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.order_tracker().order(&id).cloned() })
+        })
+        .await
+    }
+
+    /// Recorded owner of the working order with ClOrdID (11) or OrderID (37)
+    /// `id`, kept across restarts by the
+    /// [`order_index_store`](DeribitFixConfig::order_index_store)
+    pub async fn order_owner(&self, id: &str) -> Result<Option<OrderOwnership>> {
+        let id = id.to_string();
+        self.call(move |session| Box::pin(async move { session.order_index().order(&id).cloned() }))
+            .await
+    }
+
+    /// Match the Execution Reports of the working orders, such as those
+    /// answering an Order Mass Status Request (AF) after a restart, against
+    /// their recorded owners
+    pub async fn reconcile_orders(
+        &self,
+        reports: Vec<ExecutionReport>,
+    ) -> Result<OrderReconciliation> {
+        self.call(move |session| Box::pin(async move { session.reconcile_orders(&reports) }))
+            .await
     }
 
     /// Cancel an order
     pub async fn cancel_order(&self, order_id: String) -> Result<()> {
-        self.cancel_order_with_symbol(order_id, None).await
+        self.cancel_order_with_symbol(order_id, None).await?;
+        Ok(())
     }
 
     /// Cancel an order with optional symbol specification
     ///
+    /// Waits for the cancel to be confirmed and returns the Execution Report
+    /// of the cancelled order, or [`CancelReport::Withdrawn`] when the order
+    /// had not been sent yet; an Order Cancel Reject (9) is returned as
+    /// [`DeribitFixError::CancelRejected`]. The cancel is sent ahead of the
+    /// orders still waiting for the session.
+    ///
     /// # Arguments
-    /// * `order_id` - The order identifier (OrigClOrdID) to cancel
+    /// * `order_id` - The exchange OrderID (OrigClOrdID) to cancel, or the
+    ///   ClOrdID of an order of the session whose OrderID was reported
     /// * `symbol` - Optional instrument symbol (e.g., "BTC-PERPETUAL").
     ///   Required when canceling by ClOrdID or DeribitLabel,
     ///   but not required when using OrigClOrdID (fastest approach)
@@ -153,44 +1103,767 @@ impl DeribitFixClient {
         &self,
         order_id: String,
         symbol: Option<String>,
-    ) -> Result<()> {
-        if let Some(session) = &self.session {
-            let mut session_guard = session.lock().await;
-            session_guard
-                .cancel_order_with_symbol(order_id, symbol)
-                .await
-        } else {
-            Err(DeribitFixError::Session("Not connected".to_string()))
-        }
+    ) -> Result<CancelReport> {
+        let target = match &symbol {
+            Some(symbol) => CancelTarget::ClOrdId {
+                cl_ord_id: order_id.clone(),
+                symbol: symbol.clone(),
+            },
+            None => CancelTarget::OrderId(order_id.clone()),
+        };
+        self.call_cancel(&target, move |session| {
+            Box::pin(async move { session.cancel_order_with_symbol(order_id, symbol).await })
+        })
+        .await?
+        .unwrap_or(Ok(CancelReport::Withdrawn))
+    }
+
+    /// Cancel the orders selected by `target` and wait for the resulting report
+    ///
+    /// Cancels a single order by exchange OrderID (37) or ClOrdID (11), or
+    /// every order carrying a DeribitLabel (100010), resting on one side of
+    /// an instrument or open on the account. Like
+    /// [`cancel_order_with_symbol`](Self::cancel_order_with_symbol), it is
+    /// sent ahead of the orders still waiting for the session.
+    pub async fn cancel(&self, target: CancelTarget) -> Result<CancelReport> {
+        let withdrawing = target.clone();
+        self.call_cancel(&withdrawing, move |session| {
+            Box::pin(async move { session.cancel(target).await })
+        })
+        .await?
+        .unwrap_or(Ok(CancelReport::Withdrawn))
+    }
+
+    /// Cancel every quote and wait for its acknowledgement
+    pub async fn cancel_all_quotes(&self) -> Result<()> {
+        self.call(|session| Box::pin(async move { session.cancel_all_quotes().await }))
+            .await?
+    }
+
+    /// Pull every quote and cancel every open order, for a clean shutdown
+    ///
+    /// Sends a Quote Cancel (Z) of all quotes, then an Order Mass Cancel
+    /// Request (q) of all orders, waiting for the confirmation of each.
+    /// Returns the Order Mass Cancel Report (r) of the orders.
+    pub async fn cancel_all_quotes_and_orders(&self) -> Result<OrderMassCancelReport> {
+        self.call_cancel(&CancelTarget::AllOrders, |session| {
+            Box::pin(async move { session.cancel_all_quotes_and_orders().await })
+        })
+        .await?
+        .unwrap_or_else(|| {
+            Err(DeribitFixError::Session(
+                "Mass cancel was not sent".to_string(),
+            ))
+        })
+    }
+
+    /// Replace an order and wait for the Execution Report confirming it
+    ///
+    /// An Order Cancel Reject (9) is returned as [`DeribitFixError::CancelRejected`].
+    pub async fn replace_order(
+        &self,
+        request: OrderCancelReplaceRequest,
+    ) -> Result<ExecutionReport> {
+        self.call(move |session| Box::pin(async move { session.replace_order(request).await }))
+            .await?
+    }
+
+    /// Send a Mass Quote (i) and wait for its acknowledgement
+    ///
+    /// A rejection is returned as [`DeribitFixError::QuoteRejected`] with the
+    /// exchange's Text (58).
+    pub async fn send_mass_quote(&self, quote: MassQuote) -> Result<()> {
+        self.call(move |session| Box::pin(async move { session.send_mass_quote(&quote).await }))
+            .await?
     }
 
     /// Subscribe to market data
     pub async fn subscribe_market_data(&self, symbol: String) -> Result<()> {
-        if let Some(session) = &self.session {
-            let mut session_guard = session.lock().await;
-            session_guard.subscribe_market_data(symbol).await
-        } else {
-            Err(DeribitFixError::Session("Not connected".to_string()))
+        self.call(move |session| {
+            Box::pin(async move { session.subscribe_market_data(symbol).await })
+        })
+        .await?
+    }
+
+    /// Subscribe to the MDEntryType (269) values `entry_types` of an
+    /// instrument, `depth` levels deep (0 for the full book)
+    ///
+    /// An instrument can have several such subscriptions, such as one for its
+    /// book and one for its trades, each with its own MDReqID (262); only one
+    /// may carry bids or offers. Returns the MDReqID of the subscription.
+    pub async fn subscribe_market_data_entries(
+        &self,
+        symbol: &str,
+        entry_types: &[MdEntryType],
+        depth: u32,
+    ) -> Result<String> {
+        let symbol = symbol.to_string();
+        let entry_types = entry_types.to_vec();
+        self.call(move |session| {
+            Box::pin(async move {
+                session
+                    .subscribe_market_data_entries(&symbol, &entry_types, depth)
+                    .await
+            })
+        })
+        .await?
+    }
+
+    /// Cancel a market data subscription by its MDReqID (262) or symbol
+    ///
+    /// A symbol stands for the book subscription of the instrument; its
+    /// other subscriptions are kept.
+    pub async fn unsubscribe_market_data(
+        &self,
+        md_req_id_or_symbol: &str,
+    ) -> Result<MarketDataSubscription> {
+        let md_req_id_or_symbol = md_req_id_or_symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.unsubscribe_market_data(&md_req_id_or_symbol).await })
+        })
+        .await?
+    }
+
+    /// Cancel every market data subscription of an instrument, returning
+    /// the subscriptions cancelled
+    pub async fn unsubscribe_symbol(&self, symbol: &str) -> Result<Vec<MarketDataSubscription>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.unsubscribe_symbol(&symbol).await }))
+            .await?
+    }
+
+    /// Cancel every market data and index subscription
+    ///
+    /// Sends an unsubscribe Market Data Request (V) per active MDReqID (262)
+    /// and returns the market data subscriptions cancelled.
+    pub async fn unsubscribe_all_market_data(&self) -> Result<Vec<MarketDataSubscription>> {
+        self.call(|session| Box::pin(async move { session.unsubscribe_all_market_data().await }))
+            .await?
+    }
+
+    /// Re-subscribe to the market data of an instrument with `depth` levels
+    ///
+    /// Use a depth of 0 for the full book. Returns the MDReqID (262) of the
+    /// new subscription.
+    pub async fn set_market_depth(&self, symbol: &str, depth: u32) -> Result<String> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.set_market_depth(&symbol, depth).await })
+        })
+        .await?
+    }
+
+    /// Subscribe to the best bid and offer of an instrument only
+    ///
+    /// Requests MarketDepth (264) = 1 without building an order book; changes
+    /// are published as [`SessionEvent::TopOfBook`](crate::session::SessionEvent::TopOfBook).
+    /// Returns the MDReqID (262) of the subscription.
+    pub async fn subscribe_top_of_book(&self, symbol: &str) -> Result<String> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.subscribe_top_of_book(&symbol).await })
+        })
+        .await?
+    }
+
+    /// Get the best bid and offer of a top-of-book subscription
+    pub async fn top_of_book(&self, symbol: &str) -> Result<Option<TopOfBook>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.top_of_book(&symbol).cloned() }))
+            .await
+    }
+
+    /// Get the order book built from a market data subscription, if any
+    pub async fn order_book(&self, symbol: &str) -> Result<Option<OrderBook>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.order_book(&symbol).cloned() }))
+            .await
+    }
+
+    /// Get the state of an instrument from the Security Status (f) messages
+    /// received, if any
+    pub async fn instrument_state(&self, symbol: &str) -> Result<Option<InstrumentState>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.market_state().instrument_state(&symbol) })
+        })
+        .await
+    }
+
+    /// Subscribe to the index value and estimated delivery price of `symbol`
+    ///
+    /// Updates arrive on the returned channel as [`IndexUpdate`]s, apart from
+    /// order book updates, while any task drives
+    /// [`receive_message`](Self::receive_message). The channel belongs to the
+    /// current session and closes on disconnect or failover.
+    pub async fn subscribe_index(&self, symbol: &str) -> Result<broadcast::Receiver<IndexUpdate>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.subscribe_index(&symbol).await }))
+            .await?
+    }
+
+    /// Cancel the index subscription of `symbol`
+    pub async fn unsubscribe_index(&self, symbol: &str) -> Result<()> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.unsubscribe_index(&symbol).await }))
+            .await?
+    }
+
+    /// Subscribe to the ticker of the option `symbol`
+    ///
+    /// Every full snapshot of the option arrives on the returned channel as
+    /// an [`OptionTicker`] with its mark, quotes, implied volatilities and
+    /// greeks, while any task drives
+    /// [`receive_message`](Self::receive_message). The channel belongs to the
+    /// current session and closes on disconnect or failover.
+    pub async fn subscribe_option_ticker(
+        &self,
+        symbol: &str,
+    ) -> Result<broadcast::Receiver<OptionTicker>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.subscribe_option_ticker(&symbol).await })
+        })
+        .await?
+    }
+
+    /// Cancel the ticker subscription of the option `symbol`
+    pub async fn unsubscribe_option_ticker(&self, symbol: &str) -> Result<()> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.unsubscribe_option_ticker(&symbol).await })
+        })
+        .await?
+    }
+
+    /// Get the last ticker of a subscribed option
+    pub async fn option_ticker(&self, symbol: &str) -> Result<Option<OptionTicker>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.option_ticker(&symbol).cloned() }))
+            .await
+    }
+
+    /// Subscribe to the trades of the account, optionally of one instrument
+    ///
+    /// Waits for the Trade Capture Report Request Ack (AQ); each Trade
+    /// Capture Report (AE) of the subscription then arrives on the returned
+    /// [`TradeStream`] while any task drives
+    /// [`receive_message`](Self::receive_message). The stream belongs to the
+    /// current session and closes on disconnect, failover or
+    /// [`unsubscribe_trades`](Self::unsubscribe_trades).
+    pub async fn subscribe_trades(&self, symbol: Option<&str>) -> Result<TradeStream> {
+        let symbol = symbol.map(str::to_string);
+        self.call(move |session| {
+            Box::pin(async move { session.subscribe_trades(symbol.as_deref()).await })
+        })
+        .await?
+    }
+
+    /// Cancel the trade subscription `trade_request_id`
+    pub async fn unsubscribe_trades(&self, trade_request_id: &str) -> Result<()> {
+        let trade_request_id = trade_request_id.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.unsubscribe_trades(&trade_request_id).await })
+        })
+        .await?
+    }
+
+    /// Get the active market data subscriptions
+    pub async fn market_data_subscriptions(&self) -> Result<Vec<MarketDataSubscription>> {
+        self.call(move |session| {
+            Box::pin(async move {
+                session
+                    .market_data_subscriptions()
+                    .iter()
+                    .cloned()
+                    .collect()
+            })
+        })
+        .await
+    }
+
+    /// Fetch a one-off snapshot of the order book of `symbol`, `depth` levels
+    /// deep (0 for the full book)
+    ///
+    /// No subscription is registered, which suits occasional pricing checks
+    /// where a market data stream would be wasted.
+    pub async fn get_order_book_snapshot(&self, symbol: &str, depth: u32) -> Result<OrderBook> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.get_order_book_snapshot(&symbol, depth).await })
+        })
+        .await?
+    }
+
+    /// Get the trades of `symbol`, optionally since a time and up to `limit` (at most 1000)
+    pub async fn get_recent_trades(
+        &self,
+        symbol: &str,
+        since: Option<DateTime<Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<PublicTrade>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.get_recent_trades(&symbol, since, limit).await })
+        })
+        .await?
+    }
+
+    /// Get the market data statistics for a symbol
+    ///
+    /// Returns `None` until market data has been received for the symbol.
+    pub async fn market_stats(&self, symbol: &str) -> Result<Option<MarketStats>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.market_stats(&symbol).cloned() }))
+            .await
+    }
+
+    /// Poll the funding rates of the perpetual `symbol`
+    ///
+    /// A lightweight snapshot request is sent straight away and then every
+    /// [`funding_poll_interval`](DeribitFixConfig::funding_poll_interval);
+    /// read the rates with [`funding_history`](Self::funding_history).
+    pub async fn track_funding(&self, symbol: &str) -> Result<()> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move {
+                session.track_funding(&symbol);
+                session.poll_funding().await.map(|_| ())
+            })
+        })
+        .await??;
+        self.start_funding_task(self.session()?);
+        Ok(())
+    }
+
+    /// Stop polling the funding rates of `symbol`
+    ///
+    /// Returns whether the symbol was tracked.
+    pub async fn untrack_funding(&self, symbol: &str) -> Result<bool> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.untrack_funding(&symbol) }))
+            .await
+    }
+
+    /// Get the funding rate changes of a perpetual received within `window`,
+    /// oldest first
+    pub async fn funding_history(
+        &self,
+        symbol: &str,
+        window: TimeDelta,
+    ) -> Result<Vec<FundingSample>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| Box::pin(async move { session.funding_history(&symbol, window) }))
+            .await
+    }
+
+    /// Mid price of `symbol` from its top-of-book subscription or order book
+    pub async fn mid_price(&self, symbol: &str) -> Result<Option<f64>> {
+        let symbol = symbol.to_string();
+        self.call(move |session| {
+            Box::pin(async move {
+                session
+                    .top_of_book(&symbol)
+                    .and_then(TopOfBook::mid_price)
+                    .or_else(|| {
+                        let book = session.order_book(&symbol)?;
+                        Some((book.best_bid()?.0 + book.best_ask()?.0) / 2.0)
+                    })
+            })
+        })
+        .await
+    }
+
+    /// Keep the quotes of `engine` in the market until `cancel` fires
+    ///
+    /// Market data of every quoted instrument must be subscribed, with
+    /// [`subscribe_top_of_book`](Self::subscribe_top_of_book) or
+    /// [`subscribe_market_data`](Self::subscribe_market_data). Quotes are
+    /// checked against the mid price after every received message and when
+    /// the next refresh is due, and the engine sees every received message,
+    /// so an MMP trigger pauses quoting. Live quotes are pulled when `cancel`
+    /// fires. A failed send ends the run; the engine keeps its state and can
+    /// be run again.
+    pub async fn run_quoting(
+        &self,
+        engine: &mut QuotingEngine,
+        cancel: &CancelToken,
+    ) -> Result<()> {
+        let mut messages = self.subscribe_messages()?;
+        let clock = self.config.clock.clone();
+        loop {
+            for symbol in engine.symbols() {
+                let mid = self.mid_price(&symbol).await?;
+                for action in engine.update(&symbol, mid, clock.utc_now()) {
+                    self.send_quote_action(action).await?;
+                }
+            }
+            let wait = engine
+                .next_refresh()
+                .map(|due| (due - clock.utc_now()).to_std().unwrap_or(Duration::ZERO));
+            let refresh = async {
+                match wait {
+                    Some(wait) => clock.sleep(wait).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    if let Some(action) = engine.cancel_all() {
+                        self.send_quote_action(action).await?;
+                    }
+                    return Ok(());
+                }
+                received = messages.recv() => match received {
+                    Ok(message) => {
+                        if let Some(error) = MassQuoteAcknowledgement::reject_error(&message) {
+                            warn!("{}", error);
+                        }
+                        engine.on_message(&message);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Quoting skipped {} messages not read in time", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(DeribitFixError::Connection("Session closed".to_string()));
+                    }
+                },
+                _ = refresh => {}
+            }
+        }
+    }
+
+    async fn send_quote_action(&self, action: QuoteAction) -> Result<()> {
+        match action {
+            QuoteAction::Submit(quote) => self.send(quote).await?,
+            QuoteAction::Cancel(cancel) => self.send(cancel).await?,
+        };
+        Ok(())
+    }
+
+    /// Keep the orders of `ladder` around the mid price until `cancel` fires
+    ///
+    /// Market data of the instrument must be subscribed, as for
+    /// [`run_quoting`](Self::run_quoting). The ladder is updated with the mid
+    /// price after every received message and sees every received message,
+    /// so fills free their level and confirmed replaces move the orders. New
+    /// orders go through [`send_order`](Self::send_order) and its risk checks
+    /// and are recorded by the order tracker. The orders are cancelled when
+    /// `cancel` fires. A failed send ends the run, the unsent order being
+    /// forgotten; the ladder keeps its state and can be run again.
+    pub async fn run_ladder(&self, ladder: &mut Ladder, cancel: &CancelToken) -> Result<()> {
+        ladder.validate()?;
+        let mut messages = self.subscribe_messages()?;
+        loop {
+            let mid = self.mid_price(ladder.symbol()).await?;
+            for action in ladder.update(mid) {
+                self.send_ladder_action(ladder, action).await?;
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    for action in ladder.cancel_all() {
+                        self.send_ladder_action(ladder, action).await?;
+                    }
+                    return Ok(());
+                }
+                received = messages.recv() => match received {
+                    Ok(message) => {
+                        ladder.on_message(&message);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Ladder skipped {} messages not read in time", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(DeribitFixError::Connection("Session closed".to_string()));
+                    }
+                },
+            }
+        }
+    }
+
+    async fn send_ladder_action(&self, ladder: &mut Ladder, action: LadderAction) -> Result<()> {
+        let (cl_ord_id, sent) = match action {
+            LadderAction::Place(order) => {
+                let cl_ord_id = order.client_order_id.clone();
+                (cl_ord_id, self.send_order(order).await.map(|_| ()))
+            }
+            LadderAction::Replace(request) => {
+                let cl_ord_id = Some(request.cl_ord_id.clone());
+                (cl_ord_id, self.send(request).await.map(|_| ()))
+            }
+            LadderAction::Cancel(request) => (None, self.send(request).await.map(|_| ())),
+        };
+        if sent.is_err()
+            && let Some(cl_ord_id) = cl_ord_id
+        {
+            ladder.forget(&cl_ord_id);
         }
+        sent
     }
 
     /// Get account positions
     pub async fn get_positions(&self) -> Result<Vec<Position>> {
-        if let Some(session) = &self.session {
-            let mut session_guard = session.lock().await;
-            session_guard.request_positions().await
-        } else {
-            Err(DeribitFixError::Session("Not connected".to_string()))
+        self.call(move |session| Box::pin(async move { session.request_positions().await }))
+            .await?
+    }
+
+    /// Close `ratio` of the position in `symbol` with a reduce-only market
+    /// order and wait for its first Execution Report (8)
+    ///
+    /// `ratio` is in `(0, 1]`, `1.0` closing the whole position. The
+    /// position is read with [`get_positions`](Self::get_positions); a
+    /// partial amount is rounded down to the instrument's amount step when
+    /// the instrument is known. The order goes through the risk checks of
+    /// [`send_orders`](Self::send_orders). A rejected order is returned as
+    /// [`DeribitFixError::OrderRejected`].
+    pub async fn close_position(&self, symbol: &str, ratio: f64) -> Result<ExecutionReport> {
+        self.close_position_with(symbol, ratio, None).await
+    }
+
+    /// Close `ratio` of the position in `symbol` with a reduce-only limit
+    /// order at `price`, like [`close_position`](Self::close_position)
+    pub async fn close_position_at(
+        &self,
+        symbol: &str,
+        ratio: f64,
+        price: f64,
+    ) -> Result<ExecutionReport> {
+        self.close_position_with(symbol, ratio, Some(price)).await
+    }
+
+    async fn close_position_with(
+        &self,
+        symbol: &str,
+        ratio: f64,
+        price: Option<f64>,
+    ) -> Result<ExecutionReport> {
+        let position = self
+            .get_positions()
+            .await?
+            .into_iter()
+            .find(|position| position.instrument_name == symbol && position.size != 0.0)
+            .ok_or_else(|| {
+                DeribitFixError::MessageConstruction(format!("No open position in {symbol}"))
+            })?;
+        let amount_step = self
+            .instruments()
+            .await?
+            .get(symbol)
+            .and_then(InstrumentSpec::amount_step);
+        let order = position.close_order(ratio, price, amount_step)?;
+        let Some(pending) = self.send_orders(vec![order]).await?.into_orders().pop() else {
+            return Err(DeribitFixError::Session(
+                "Close order was not sent".to_string(),
+            ));
+        };
+        let acknowledgement = pending.acknowledgement().await?;
+        ExecutionReport::from_fix_message(&acknowledgement)
+    }
+
+    /// Get the balance, margins and P/L of the account in `currency`
+    pub async fn get_account_summary(&self, currency: &str) -> Result<AccountSummary> {
+        let currency = currency.to_string();
+        self.call(move |session| {
+            Box::pin(async move { session.get_account_summary(&currency).await })
+        })
+        .await?
+    }
+
+    /// Request the instruments selected by `request`, e.g. only BTC options
+    ///
+    /// See [`Session::get_instruments`] for the filters applied.
+    pub async fn get_instruments(&self, request: SecurityListRequest) -> Result<Vec<SecurityInfo>> {
+        self.call(move |session| Box::pin(async move { session.get_instruments(request).await }))
+            .await?
+    }
+
+    /// Request the instruments selected by `request` and stream them
+    /// fragment by fragment
+    ///
+    /// Returns as soon as the request is queued; the session reads the
+    /// Security List (y) fragments in the background and each one arrives
+    /// on the returned [`InstrumentStream`] with the progress of the
+    /// response.
+    pub async fn stream_instruments(
+        &self,
+        request: SecurityListRequest,
+    ) -> Result<InstrumentStream> {
+        self.session()?;
+        let security_req_id = request.security_req_id.clone();
+        let (sender, batches) = broadcast::channel(self.config.max_stream_backlog.max(1));
+        let (outcome_sender, outcome) = oneshot::channel();
+        let client = self.clone();
+        tokio::spawn(async move {
+            let result = client
+                .call(move |session| {
+                    Box::pin(async move {
+                        let (mut high_water, mut dropped) = (0, 0);
+                        let progress = session
+                            .stream_instruments(request, |securities, progress| {
+                                let queued = sender.len();
+                                let batch = InstrumentBatch {
+                                    securities,
+                                    progress: progress.clone(),
+                                };
+                                if sender.send(batch).is_err() {
+                                    return;
+                                }
+                                // A full backlog evicts its oldest fragment
+                                if sender.len() == queued {
+                                    dropped += 1;
+                                }
+                                high_water = high_water.max(sender.len());
+                            })
+                            .await;
+                        session.record_instrument_backlog(high_water, dropped);
+                        progress
+                    })
+                })
+                .await;
+            let _ = outcome_sender.send(result.and_then(|progress| progress).map(|_| ()));
+        });
+        Ok(InstrumentStream::new(security_req_id, batches, outcome))
+    }
+
+    /// Get the instrument metadata received so far, to convert order amounts
+    /// to and from contracts
+    pub async fn instruments(&self) -> Result<InstrumentRegistry> {
+        self.call(|session| Box::pin(async move { session.instruments().clone() }))
+            .await
+    }
+
+    /// Receive the next message processed by the session
+    ///
+    /// The session task reads and processes messages as they arrive; this
+    /// returns the next one, or `None` when none arrives within a second.
+    /// Clones share the messages, each is returned to one caller; use
+    /// [`subscribe_messages`](Self::subscribe_messages) to see every message.
+    /// When the primary connection fails and a hot standby is open, traffic
+    /// fails over to the standby and `None` is returned.
+    ///
+    /// Messages are kept for up to
+    /// [`SESSION_MESSAGE_CHANNEL_CAPACITY`](crate::session::SESSION_MESSAGE_CHANNEL_CAPACITY)
+    /// messages; when the callers fall further behind, the oldest are dropped
+    /// and the next call fails with [`DeribitFixError::MessagesLost`] so that
+    /// missed Execution Reports can be recovered, for instance with an Order
+    /// Mass Status Request. The calls after it return the messages kept.
+    pub async fn receive_message(&self) -> Result<Option<FixMessage>> {
+        let (session, inbox) = {
+            let state = self.state();
+            match (state.session.clone(), state.inbox.clone()) {
+                (Some(session), Some(inbox)) => (session, inbox),
+                _ => return Err(DeribitFixError::Session("Not connected".to_string())),
+            }
+        };
+        let received = self
+            .request_options
+            .run("Waiting for a message", next_message(&session, &inbox))
+            .await?;
+        match received {
+            Err(e @ (DeribitFixError::Connection(_) | DeribitFixError::Io(_)))
+                if self.has_standby() =>
+            {
+                warn!("Primary connection failed: {}", e);
+                self.failover().await?;
+                Ok(None)
+            }
+            received => received,
         }
     }
 
-    /// Receive and process a message from the server
-    pub async fn receive_message(&self) -> Result<Option<crate::model::message::FixMessage>> {
-        if let Some(session) = &self.session {
-            let mut session_guard = session.lock().await;
-            session_guard.receive_and_process_message().await
-        } else {
-            Err(DeribitFixError::Session("Not connected".to_string()))
+    /// Subscribe to every message received by the current session
+    ///
+    /// Unlike [`receive_message`](Self::receive_message), every receiver gets
+    /// every message. The receiver belongs to the current session and closes
+    /// on disconnect or failover.
+    pub fn subscribe_messages(&self) -> Result<broadcast::Receiver<FixMessage>> {
+        Ok(self.session()?.subscribe_messages())
+    }
+
+    /// Stream the messages received by the current session
+    ///
+    /// Narrow it down with [`MessageStream::filter`], for instance to the
+    /// fills of an instrument with
+    /// `MsgFilter::exec_reports().symbol("BTC-PERPETUAL").exec_type(ExecType::Trade)`.
+    /// Like [`subscribe_messages`](Self::subscribe_messages), the stream ends
+    /// on disconnect or failover.
+    pub fn stream(&self) -> Result<MessageStream> {
+        Ok(MessageStream::new(self.subscribe_messages()?))
+    }
+
+    /// Export the market data received by the current session to files
+    ///
+    /// The export runs on its own thread and drops messages rather than
+    /// slowing the session down when it falls behind; see
+    /// [`MarketDataExporter::spawn`]. Like
+    /// [`subscribe_messages`](Self::subscribe_messages), it ends on
+    /// disconnect or failover.
+    pub fn export_market_data(
+        &self,
+        exporter: MarketDataExporter,
+    ) -> Result<MarketDataExportHandle> {
+        exporter.spawn(self.subscribe_messages()?, DEFAULT_EXPORT_QUEUE)
+    }
+}
+
+/// Next message of `inbox`, or the error that stopped `session` from receiving
+///
+/// Messages received before the error are returned first. Messages dropped
+/// because `inbox` lagged are reported with [`DeribitFixError::MessagesLost`].
+async fn next_message(
+    session: &SessionHandle,
+    inbox: &Mutex<broadcast::Receiver<FixMessage>>,
+) -> Result<Option<FixMessage>> {
+    let mut inbox = inbox.lock().await;
+    let wait = tokio::time::sleep(RECEIVE_WAIT);
+    tokio::pin!(wait);
+    loop {
+        match inbox.try_recv() {
+            Ok(message) => return Ok(Some(message)),
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                warn!("Lost {} messages not read in time", skipped);
+                return Err(DeribitFixError::MessagesLost(skipped));
+            }
+            Err(broadcast::error::TryRecvError::Closed) => {
+                return Err(DeribitFixError::Session("Session closed".to_string()));
+            }
+            Err(broadcast::error::TryRecvError::Empty) => {}
+        }
+        if let Some(e) = session.failure() {
+            return Err(e);
+        }
+        tokio::select! {
+            received = inbox.recv() => match received {
+                Ok(message) => return Ok(Some(message)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Lost {} messages not read in time", skipped);
+                    return Err(DeribitFixError::MessagesLost(skipped));
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err(DeribitFixError::Session("Session closed".to_string()));
+                }
+            },
+            () = session.failed() => {}
+            () = &mut wait => return Ok(None),
         }
     }
 }
+
+/// Error of an order withdrawn by a cancel sent while it waited for the session
+fn order_withdrawn() -> DeribitFixError {
+    DeribitFixError::Cancelled("Order withdrawn by a cancel sent before it".to_string())
+}
+
+/// Log out of a session that is logged on or logging on
+///
+/// A session that never logged on or was already logged out has nothing to
+/// log out of.
+async fn logout_if_logged_on(session: &mut Session) -> Result<()> {
+    if session
+        .get_state()
+        .can_transition_to(crate::session::SessionState::LogoutSent)
+    {
+        session.logout().await?;
+    }
+    Ok(())
+}
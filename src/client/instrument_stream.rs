@@ -0,0 +1,108 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Instruments streamed as they arrive
+//!
+//! The instrument list of a currency can run to thousands of entries, split
+//! by Deribit over many Security List (y) fragments.
+//! [`DeribitFixClient::stream_instruments`](crate::client::DeribitFixClient::stream_instruments)
+//! returns an [`InstrumentStream`] handing out the instruments of each
+//! fragment with the progress of the response, so they can be used before
+//! the last fragment arrives.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::{SecurityInfo, SecurityListProgress};
+use tokio::sync::{broadcast, oneshot};
+use tracing::warn;
+
+/// Instruments of one Security List (y) fragment
+#[derive(Debug, Clone)]
+pub struct InstrumentBatch {
+    /// Instruments of the fragment passing the request filters
+    pub securities: Vec<SecurityInfo>,
+    /// Progress of the response, this fragment included
+    pub progress: SecurityListProgress,
+}
+
+/// Receiver of the instruments of a Security List Request (x)
+///
+/// The stream holds up to
+/// [`max_stream_backlog`](crate::config::DeribitFixConfig::max_stream_backlog)
+/// fragments; a receiver that falls further behind loses the oldest ones.
+#[derive(Debug)]
+pub struct InstrumentStream {
+    /// SecurityReqID (320) of the request
+    pub security_req_id: String,
+    batches: broadcast::Receiver<InstrumentBatch>,
+    /// How the request ended, sent once the last fragment is streamed
+    outcome: Option<oneshot::Receiver<Result<()>>>,
+    /// Fragments dropped because the receiver fell behind
+    dropped: u64,
+}
+
+impl InstrumentStream {
+    pub(crate) fn new(
+        security_req_id: String,
+        batches: broadcast::Receiver<InstrumentBatch>,
+        outcome: oneshot::Receiver<Result<()>>,
+    ) -> Self {
+        Self {
+            security_req_id,
+            batches,
+            outcome: Some(outcome),
+            dropped: 0,
+        }
+    }
+
+    /// Wait for the instruments of the next fragment, `None` once the
+    /// response is complete
+    ///
+    /// A request that fails, for instance when a fragment does not arrive
+    /// within the
+    /// [`fragment_timeout`](crate::config::DeribitFixConfig::fragment_timeout),
+    /// ends the stream with the error. Fragments dropped because the
+    /// receiver fell behind are skipped.
+    pub async fn recv(&mut self) -> Option<Result<InstrumentBatch>> {
+        loop {
+            match self.batches.recv().await {
+                Ok(batch) => return Some(Ok(batch)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(
+                        "Dropped {} instrument fragments of {} not read in time",
+                        skipped, self.security_req_id
+                    );
+                    self.dropped += skipped;
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    return match self.outcome.take()?.await {
+                        Ok(Err(e)) => Some(Err(e)),
+                        _ => None,
+                    };
+                }
+            }
+        }
+    }
+
+    /// Fragments dropped so far because the receiver fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Wait for the whole response and return its instruments, in order
+    ///
+    /// Fails with [`DeribitFixError::MessagesLost`] when fragments were
+    /// dropped, since the list would be incomplete.
+    pub async fn collect(mut self) -> Result<Vec<SecurityInfo>> {
+        let mut securities = Vec::new();
+        while let Some(batch) = self.recv().await {
+            securities.extend(batch?.securities);
+        }
+        if self.dropped > 0 {
+            return Err(DeribitFixError::MessagesLost(self.dropped));
+        }
+        Ok(securities)
+    }
+}
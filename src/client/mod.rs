@@ -1,6 +1,25 @@
 //! FIX client module
 
+/// Session task and command channel
+pub(crate) mod actor;
+
+/// Orders sent together
+pub mod batch;
+
 /// FIX client implementation
 pub mod fix_client;
 
+/// Instruments streamed fragment by fragment
+pub mod instrument_stream;
+
+/// Responses to custom messages
+pub mod pending;
+
+/// Health supervision and remediation
+pub mod supervisor;
+
+pub use batch::*;
 pub use fix_client::*;
+pub use instrument_stream::*;
+pub use pending::*;
+pub use supervisor::*;
@@ -0,0 +1,216 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Responses to custom messages
+
+use crate::config::DeribitFixConfig;
+use crate::error::{DeribitFixError, Result};
+use crate::message::{
+    ExecutionReport, MarketDataRequestReject, MassQuoteAcknowledgement, OrderCancelReject,
+    QuoteRequestReject, reject_error_of,
+};
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use crate::session::{Clock, RequestOptions};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Request identifier tags echoed back by the responses to a request
+const CORRELATION_TAGS: [u32; 11] = [
+    tags::CL_ORD_ID,
+    tags::QUOTE_ID,
+    tags::QUOTE_REQ_ID,
+    tags::RFQ_REQ_ID,
+    tags::MD_REQ_ID,
+    tags::SECURITY_REQ_ID,
+    tags::SECURITY_STATUS_REQ_ID,
+    tags::TRADE_REQUEST_ID,
+    tags::MASS_STATUS_REQ_ID,
+    tags::POS_REQ_ID,
+    tags::USER_REQUEST_ID,
+];
+
+/// Response to a message sent with [`DeribitFixClient::send_custom`](crate::DeribitFixClient::send_custom),
+/// [`send_rfq`](crate::DeribitFixClient::send_rfq) or
+/// [`send_quote_request`](crate::DeribitFixClient::send_quote_request)
+///
+/// The response is read from the messages the session publishes, so other
+/// clones of the client keep using the session while it is awaited.
+pub struct PendingResponse {
+    messages: broadcast::Receiver<FixMessage>,
+    msg_seq_num: Option<u32>,
+    correlation: Vec<(u32, String)>,
+    options: RequestOptions,
+    clock: Arc<dyn Clock>,
+    request_timeout: Duration,
+}
+
+impl PendingResponse {
+    /// `messages` must be subscribed before the message is sent
+    ///
+    /// `msg_seq_num` is `None` for a message that did not reach the
+    /// exchange, whose rejects cannot be told apart by RefSeqNum (45).
+    pub(crate) fn new(
+        messages: broadcast::Receiver<FixMessage>,
+        msg_seq_num: Option<u32>,
+        fields: &[(u32, String)],
+        config: &DeribitFixConfig,
+    ) -> Self {
+        let correlation = fields
+            .iter()
+            .filter(|(tag, _)| CORRELATION_TAGS.contains(tag))
+            .cloned()
+            .collect();
+        Self {
+            messages,
+            msg_seq_num,
+            correlation,
+            options: RequestOptions::default(),
+            clock: config.clock.clone(),
+            request_timeout: config.request_timeout,
+        }
+    }
+
+    /// Wait for the response within the deadline and cancellation of `options`
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// MsgSeqNum (34) the message was sent with, `None` for an order queued
+    /// until its instrument is tradable or simulated by paper trading
+    pub fn msg_seq_num(&self) -> Option<u32> {
+        self.msg_seq_num
+    }
+
+    /// Whether `message` answers the request
+    ///
+    /// A response echoes one of the request identifiers of the message, such
+    /// as ClOrdID (11), MDReqID (262) or PosReqID (710).
+    pub fn is_response(&self, message: &FixMessage) -> bool {
+        self.correlation
+            .iter()
+            .any(|(tag, value)| message.get_field(*tag) == Some(value))
+    }
+
+    /// Wait for the first message that echoes a request identifier
+    ///
+    /// Messages without a request identifier never match; use
+    /// [`wait_for`](Self::wait_for) for those. A Reject (3) or Business
+    /// Message Reject (j) of the message is returned as
+    /// [`DeribitFixError::MessageRejected`]. A response rejecting the request
+    /// is returned as the matching typed error, such as
+    /// [`DeribitFixError::OrderRejected`],
+    /// [`DeribitFixError::MarketDataRejected`] or
+    /// [`DeribitFixError::QuoteRequestRejected`]; every reject error keeps the
+    /// exchange's Text (58).
+    pub async fn response(self) -> Result<FixMessage> {
+        let correlation = self.correlation.clone();
+        let message = self
+            .wait_for(move |message| {
+                correlation
+                    .iter()
+                    .any(|(tag, value)| message.get_field(*tag) == Some(value))
+            })
+            .await?;
+        match response_reject_error(&message) {
+            Some(error) => Err(error),
+            None => Ok(message),
+        }
+    }
+
+    /// Wait for the first message accepted by `matcher`
+    ///
+    /// A Reject (3) or Business Message Reject (j) of the message is returned
+    /// as [`DeribitFixError::MessageRejected`] with the exchange's Text (58).
+    pub async fn wait_for(
+        self,
+        mut matcher: impl FnMut(&FixMessage) -> bool,
+    ) -> Result<FixMessage> {
+        let msg_seq_num = self.msg_seq_num;
+        let description = match (msg_seq_num, self.correlation.first()) {
+            (Some(msg_seq_num), _) => format!("message {msg_seq_num}"),
+            (None, Some((_, request_id))) => format!("message {request_id}"),
+            (None, None) => "message".to_string(),
+        };
+        let mut messages = self.messages;
+        let waiting = async {
+            loop {
+                let message = match messages.recv().await {
+                    Ok(message) => message,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Skipped {} messages waiting for {}", skipped, description);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(DeribitFixError::Connection(format!(
+                            "Session closed while waiting for {description}"
+                        )));
+                    }
+                };
+                if let Some(error) =
+                    msg_seq_num.and_then(|msg_seq_num| reject_error_of(&message, msg_seq_num))
+                {
+                    return Err(error);
+                }
+                if matcher(&message) {
+                    return Ok(message);
+                }
+            }
+        };
+        // Without a deadline the response is awaited for the request timeout
+        let deadline = self
+            .options
+            .deadline
+            .unwrap_or_else(|| self.clock.now() + self.request_timeout);
+        let expired = self.clock.sleep_until(deadline);
+        let timeout = match self.options.deadline {
+            Some(_) => format!("No response received for {description} before its deadline"),
+            None => format!(
+                "No response received for {description} within {:?}",
+                self.request_timeout
+            ),
+        };
+        self.options
+            .run(&format!("Waiting for {description}"), async {
+                tokio::select! {
+                    response = waiting => response,
+                    () = expired => Err(DeribitFixError::Timeout(timeout)),
+                }
+            })
+            .await?
+    }
+}
+
+/// Typed error of a response rejecting the request, `None` for other responses
+///
+/// Responses that do not parse are returned as they are.
+fn response_reject_error(message: &FixMessage) -> Option<DeribitFixError> {
+    match message.msg_type()? {
+        MsgType::ExecutionReport => ExecutionReport::from_fix_message(message)
+            .ok()?
+            .reject_error(),
+        MsgType::OrderCancelReject => {
+            let order_id = [tags::ORIG_CL_ORD_ID, tags::CL_ORD_ID]
+                .into_iter()
+                .find_map(|tag| message.get_field(tag))
+                .cloned()
+                .unwrap_or_default();
+            Some(
+                OrderCancelReject::from_fix_message(message)
+                    .ok()?
+                    .into_error(order_id),
+            )
+        }
+        MsgType::MarketDataRequestReject => Some(MarketDataRequestReject::reject_error(message)),
+        MsgType::MassQuoteAcknowledgement => MassQuoteAcknowledgement::reject_error(message),
+        MsgType::QuoteRequestReject => Some(QuoteRequestReject::reject_error(message)),
+        _ => None,
+    }
+}
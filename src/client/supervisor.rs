@@ -0,0 +1,245 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Session supervisor
+//!
+//! A [`Supervisor`] samples the health signals of a client at a fixed
+//! interval, folds them into a
+//! [`HealthState`](crate::model::health::HealthState) with a
+//! [`HealthMonitor`](crate::model::health::HealthMonitor) and runs the
+//! [`Remediation`]s configured for the state entered on each transition:
+//!
+//! ```no_run
+//! use deribit_fix::prelude::*;
+//! use deribit_fix::client::{Remediation, Supervisor};
+//! use deribit_fix::model::HealthState;
+//!
+//! # async fn run(client: DeribitFixClient) {
+//! let supervisor = Supervisor::new(client)
+//!     .with_remediation(HealthState::Degraded, Remediation::Alert)
+//!     .with_remediation(HealthState::Unhealthy, Remediation::CancelAll)
+//!     .with_remediation(HealthState::Unhealthy, Remediation::Relogon)
+//!     .with_alert(|transition| eprintln!("{:?} -> {:?}", transition.from, transition.to))
+//!     .spawn();
+//! # }
+//! ```
+
+use crate::client::DeribitFixClient;
+use crate::error::Result;
+use crate::model::health::{
+    HealthMonitor, HealthSample, HealthState, HealthThresholds, HealthTransition,
+};
+use crate::session::ConnectionHealth;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Time between two health samples by default
+pub const DEFAULT_SUPERVISOR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Action taken when the health state changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Remediation {
+    /// Disconnect and connect again with a new session
+    Reconnect,
+    /// Reconnect and log on again, keeping the session
+    Relogon,
+    /// Cancel every open order and quote
+    CancelAll,
+    /// Call the alert callback
+    Alert,
+}
+
+/// Callback told about health transitions
+pub type HealthAlert = Arc<dyn Fn(&HealthTransition) + Send + Sync>;
+
+/// Watches the health of a client and remediates its transitions
+pub struct Supervisor {
+    client: DeribitFixClient,
+    monitor: HealthMonitor,
+    interval: Duration,
+    remediations: HashMap<HealthState, Vec<Remediation>>,
+    alert: Option<HealthAlert>,
+}
+
+impl fmt::Debug for Supervisor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Supervisor")
+            .field("monitor", &self.monitor)
+            .field("interval", &self.interval)
+            .field("remediations", &self.remediations)
+            .field("alert", &self.alert.is_some())
+            .finish()
+    }
+}
+
+impl Supervisor {
+    /// Supervise `client` with the default thresholds and no remediation
+    pub fn new(client: DeribitFixClient) -> Self {
+        Self {
+            client,
+            monitor: HealthMonitor::default(),
+            interval: DEFAULT_SUPERVISOR_INTERVAL,
+            remediations: HashMap::new(),
+            alert: None,
+        }
+    }
+
+    /// Set the thresholds of the health states
+    pub fn with_thresholds(mut self, thresholds: HealthThresholds) -> Self {
+        self.monitor = HealthMonitor::new(thresholds);
+        self
+    }
+
+    /// Set the time between two health samples
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Run `remediation` whenever the state changes to `state`
+    ///
+    /// Remediations of a state run in the order they were added.
+    pub fn with_remediation(mut self, state: HealthState, remediation: Remediation) -> Self {
+        self.remediations
+            .entry(state)
+            .or_default()
+            .push(remediation);
+        self
+    }
+
+    /// Set the callback of [`Remediation::Alert`]
+    pub fn with_alert(mut self, alert: impl Fn(&HealthTransition) + Send + Sync + 'static) -> Self {
+        self.alert = Some(Arc::new(alert));
+        self
+    }
+
+    /// Current health state
+    pub fn state(&self) -> HealthState {
+        self.monitor.state()
+    }
+
+    /// Sample the client and remediate if the state changes
+    ///
+    /// Returns the transition, if any. A failed remediation is logged and
+    /// the following ones still run.
+    pub async fn check(&mut self) -> Option<HealthTransition> {
+        let sample = self.sample().await;
+        let transition = self.monitor.observe(sample)?;
+        info!(
+            "Session health {:?} -> {:?}: {}",
+            transition.from,
+            transition.to,
+            transition.assessment.reasons.join(", ")
+        );
+        let remediations = self
+            .remediations
+            .get(&transition.to)
+            .cloned()
+            .unwrap_or_default();
+        for remediation in remediations {
+            if let Err(e) = self.remediate(remediation, &transition).await {
+                warn!("Remediation {:?} failed: {}", remediation, e);
+            }
+        }
+        Some(transition)
+    }
+
+    /// Check the client every interval in a background task
+    pub fn spawn(mut self) -> SupervisorHandle {
+        let (state, watched) = watch::channel(self.state());
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.interval).await;
+                if let Some(transition) = self.check().await {
+                    state.send_replace(transition.to);
+                }
+            }
+        });
+        SupervisorHandle {
+            state: watched,
+            task,
+        }
+    }
+
+    async fn sample(&self) -> HealthSample {
+        let at = Instant::now();
+        let logged_on = self
+            .client
+            .get_session_state()
+            .await
+            .is_some_and(|state| state.is_logged_on());
+        let connection_health = self.client.connection_health().await.unwrap_or_default();
+        match self.client.connection_stats().await {
+            Some(stats) => {
+                HealthSample::from_stats(at, Utc::now(), logged_on, connection_health, &stats)
+            }
+            None => HealthSample {
+                at,
+                logged_on,
+                connection_health: ConnectionHealth::Healthy,
+                silent_for: None,
+                sequence_gaps: 0,
+                rejects: 0,
+            },
+        }
+    }
+
+    async fn remediate(
+        &self,
+        remediation: Remediation,
+        transition: &HealthTransition,
+    ) -> Result<()> {
+        info!("Running remediation {:?}", remediation);
+        match remediation {
+            Remediation::Reconnect => {
+                if let Err(e) = self.client.disconnect().await {
+                    warn!("Disconnect before reconnecting failed: {}", e);
+                }
+                self.client.connect().await
+            }
+            Remediation::Relogon => self.client.relogon().await,
+            Remediation::CancelAll => self.client.cancel_all_quotes_and_orders().await.map(|_| ()),
+            Remediation::Alert => {
+                if let Some(alert) = &self.alert {
+                    alert(transition);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Supervisor running in the background, stopped when dropped
+#[derive(Debug)]
+pub struct SupervisorHandle {
+    state: watch::Receiver<HealthState>,
+    task: JoinHandle<()>,
+}
+
+impl SupervisorHandle {
+    /// Current health state
+    pub fn state(&self) -> HealthState {
+        *self.state.borrow()
+    }
+
+    /// Receiver of the health state, to wait for its changes
+    pub fn subscribe(&self) -> watch::Receiver<HealthState> {
+        self.state.clone()
+    }
+}
+
+impl Drop for SupervisorHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -4,7 +4,9 @@
    Date: 21/7/25
 ******************************************************************************/
 
+use crate::config::loader::ConfigReport;
 use crate::config::utils::{get_env_optional, get_env_or_default};
+use crate::connection::{DEFAULT_MAX_BUFFERED, MAX_BODY_LENGTH, ProxyConfig};
 use crate::constants::{
     DEFAULT_CONNECTION_TIMEOUT_SECS, DEFAULT_HEARTBEAT_INTERVAL, DEFAULT_LOG_LEVEL,
     DEFAULT_PROD_HOST, DEFAULT_PROD_PORT, DEFAULT_RECONNECT_ATTEMPTS, DEFAULT_RECONNECT_DELAY_SECS,
@@ -12,14 +14,23 @@ use crate::constants::{
     DEFAULT_TEST_PORT,
 };
 use crate::error::{DeribitFixError, Result};
+use crate::message::time::TimestampPrecision;
+use crate::model::exec_inst::SelfTradePrevention;
+use crate::model::number_format::{NumberFormat, instrument_precision};
+use crate::model::order_index::OrderIndexStore;
+use crate::model::risk::RiskLimits;
+use crate::model::symbol_map::SymbolMapper;
+use crate::session::clock::{Clock, system_clock};
 use crate::{impl_json_debug_pretty, impl_json_display};
 use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::debug;
 
 /// Configuration for the Deribit FIX client
 #[derive(Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct DeribitFixConfig {
     /// Deribit username
     pub username: String,
@@ -49,24 +60,207 @@ pub struct DeribitFixConfig {
     pub sender_comp_id: String,
     /// Target company ID for FIX messages (DERIBITSERVER)
     pub target_comp_id: String,
-    /// Cancel orders on disconnect (default: false)
+    /// OnBehalfOfCompID (115) set on every outgoing message, for setups
+    /// routing through an intermediary (default: none)
+    pub on_behalf_of_comp_id: Option<String>,
+    /// SenderSubID (50) set on every outgoing message (default: none)
+    pub sender_sub_id: Option<String>,
+    /// TargetSubID (57) set on every outgoing message (default: none)
+    pub target_sub_id: Option<String>,
+    /// Cancel orders on disconnect, sent as CancelOnDisconnect (9001) on
+    /// every Logon (default: false)
     pub cancel_on_disconnect: bool,
-    /// Application ID for registered applications
+    /// Keep the orders open when logging out of a cancel-on-disconnect
+    /// session, sent as DontCancelOnDisconnect (9003) on the Logout unless
+    /// the logout sets its own (default: not sent)
+    pub dont_cancel_on_disconnect: Option<bool>,
+    /// Application ID for registered applications, sent as DeribitAppId
+    /// (9004) and AppID (1128) on the Logon
     pub app_id: Option<String>,
-    /// Application secret for registered applications
+    /// Application secret for registered applications, signing the Logon
+    /// with DeribitAppSig (9005)
     pub app_secret: Option<String>,
-    /// Use word-safe tags (custom tags start at 5000 instead of 100000)
+    /// Use word-safe tags (custom tags start at 5000 instead of 100000),
+    /// sent as UseWordsafeTags (9002) on the Logon (default: not sent)
     pub use_wordsafe_tags: Option<bool>,
-    /// Enable sequential FIX messaging (single queue for all messages)
+    /// Enable sequential FIX messaging (single queue for all messages),
+    /// sent as DeribitSequential (9007) on the Logon (default: not sent)
     pub deribit_sequential: Option<bool>,
-    /// Unsubscribe from notificational Execution Reports
+    /// Unsubscribe from notificational Execution Reports, sent as
+    /// UnsubscribeExecutionReports (9009) on the Logon (default: not sent)
     pub unsubscribe_execution_reports: Option<bool>,
-    /// Receive Execution Reports only for orders created in this connection
+    /// Receive Execution Reports only for orders created in this connection,
+    /// sent as ConnectionOnlyExecutionReports (9010) on the Logon (default:
+    /// not sent)
     pub connection_only_execution_reports: Option<bool>,
-    /// Report fills as Execution Reports with ExecType = F(TRADE)
+    /// Report fills as Execution Reports with ExecType = F(TRADE), sent as
+    /// ReportFillsAsExecReports (9015) on the Logon (default: not sent)
     pub report_fills_as_exec_reports: Option<bool>,
-    /// Include price increment steps in symbol entries
+    /// Include price increment steps in symbol entries, sent as
+    /// DisplayIncrementSteps (9018) on the Logon (default: not sent)
     pub display_increment_steps: Option<bool>,
+    /// Send ResetSeqNumFlag (141=Y) on logon and restart both sequences at 1 (default: false)
+    pub reset_seq_num_on_logon: bool,
+    /// Redact credentials and other sensitive tags when logging FIX messages (default: true)
+    pub redact_sensitive_fields: bool,
+    /// Queue new orders while their instrument is halted, closed or being settled,
+    /// or the exchange is in maintenance, instead of rejecting them locally (default: false)
+    pub queue_orders_during_halt: bool,
+    /// Reconnect and log on again after a non-fatal Logout from the server (default: true)
+    pub relogon_after_logout: bool,
+    /// Interval between logon attempts after a Logout for exchange
+    /// maintenance, when `relogon_after_logout` is enabled (default: 60s)
+    pub maintenance_retry_interval: Duration,
+    /// Logon attempts after the server answers a Logon with a Logout for a
+    /// transient reason; 0 leaves rejected logons to `relogon_after_logout`
+    /// (default: 0)
+    pub logon_retry_attempts: u32,
+    /// Wait before the first logon retry, doubled for each further retry
+    /// (default: 1000ms)
+    pub logon_retry_delay: Duration,
+    /// Longest wait between logon retries (default: 60s)
+    pub logon_retry_max_delay: Duration,
+    /// Reject messages the session state does not allow, such as orders sent
+    /// before the logon is acknowledged (default: false)
+    pub strict_session_state: bool,
+    /// Check that incoming MsgSeqNum (34) increase by exactly one and that
+    /// outgoing ones follow the sequence acknowledged by the counterparty,
+    /// emitting a `SequenceAnomaly` event for each violation (default: false)
+    pub strict_sequence_checks: bool,
+    /// Timestamp each stage of the orders, replaces and cancels sent and
+    /// keep per-stage latency percentiles of their acknowledgements
+    /// (default: false)
+    pub latency_tracing: bool,
+    /// Fractional seconds written in SendingTime (52) and in the TransactTime
+    /// (60) of orders, cancels and replaces; inbound timestamps are read at
+    /// any precision (default: millis)
+    pub timestamp_precision: TimestampPrecision,
+    /// Probe the server after logon with a Test Request and record the
+    /// custom tags it sends, see
+    /// [`ServerCapabilities`](crate::model::capabilities::ServerCapabilities)
+    /// (default: false)
+    pub capability_probe: bool,
+    /// DefaultApplVerID (1137) proposed in the Logon, for servers that
+    /// negotiate the application version (default: none)
+    pub default_appl_ver_id: Option<String>,
+    /// Fill orders locally against the order book built from market data
+    /// instead of sending them to the exchange (default: false)
+    pub paper_trading: bool,
+    /// Keep order books order by order when market data entries carry an
+    /// OrderID (37), for queue position estimates (default: false)
+    pub order_level_books: bool,
+    /// File to record received market data to, see [`crate::recorder`] (default: none)
+    pub market_data_recording_path: Option<String>,
+    /// Disable Nagle's algorithm on the socket (TCP_NODELAY, default: true)
+    pub tcp_nodelay: bool,
+    /// Idle time before TCP keepalive probes are sent; keepalive is off when unset (default: none)
+    pub tcp_keepalive: Option<Duration>,
+    /// Interval between TCP keepalive probes, on platforms that support it (default: system)
+    pub tcp_keepalive_interval: Option<Duration>,
+    /// Socket send buffer size in bytes, SO_SNDBUF (default: system)
+    pub send_buffer_size: Option<usize>,
+    /// Socket receive buffer size in bytes, SO_RCVBUF (default: system)
+    pub recv_buffer_size: Option<usize>,
+    /// Proxy the connection is tunnelled through (default: none)
+    pub proxy: Option<ProxyConfig>,
+    /// How long the resolved server addresses are reused for reconnects
+    /// before being looked up again; zero resolves on every connect
+    /// (default: 300 seconds)
+    pub dns_cache_ttl: Duration,
+    /// Spin on the socket for up to this long before waiting for data to
+    /// arrive, trading CPU for read latency (default: none)
+    pub busy_poll: Option<Duration>,
+    /// Coalesce outgoing messages for up to this long before writing them to
+    /// the socket in one call; every message is written at once when unset
+    /// (default: none)
+    pub write_batch_delay: Option<Duration>,
+    /// Pending bytes at which a write batch is flushed early (default: 65536)
+    pub write_batch_max_bytes: usize,
+    /// Bytes the read buffer may hold without completing a message before
+    /// they are discarded; at least twice the largest message accepted
+    /// (default: 4194304)
+    pub max_read_buffer_bytes: usize,
+    /// Client calls, orders included, that may wait for the session task
+    /// before further calls fail with
+    /// [`DeribitFixError::QueueFull`](crate::error::DeribitFixError::QueueFull)
+    /// (default: 10000)
+    pub max_pending_commands: usize,
+    /// Application messages sent per second at most; messages are not
+    /// paced until the exchange rejects one for its rate limit when unset
+    /// (default: none)
+    pub max_message_rate: Option<f64>,
+    /// Lower the message rate when the exchange rejects messages for its
+    /// rate limit, and raise it again while it does not, see
+    /// [`AdaptiveRateLimiter`](crate::model::throttle::AdaptiveRateLimiter)
+    /// (default: true)
+    pub adaptive_throttling: bool,
+    /// Updates a market data stream receiver may fall behind by before the
+    /// oldest ones are dropped (default: 10000)
+    pub max_market_data_backlog: usize,
+    /// Execution Reports, trade reports or instrument fragments a stream
+    /// receiver may fall behind by; past it execution receivers fail with
+    /// [`MessagesLost`](crate::error::DeribitFixError::MessagesLost) and the
+    /// other streams drop the oldest (default: 10000)
+    pub max_stream_backlog: usize,
+    /// Interval between the snapshot requests polling the funding rates of
+    /// the perpetuals tracked with
+    /// [`track_funding`](crate::session::Session::track_funding)
+    /// (default: 60s)
+    pub funding_poll_interval: Duration,
+    /// Interval between watchdog Test Requests measuring the connection
+    /// latency; the watchdog is off when unset (default: none)
+    pub ping_interval: Option<Duration>,
+    /// Heartbeat intervals without any incoming message before a Test
+    /// Request probes the counterparty, for transports without TCP
+    /// keepalive; the liveness checks are off when unset (default: none)
+    pub liveness_missed_heartbeats: Option<u32>,
+    /// How long the liveness probe may go unanswered before the session
+    /// reconnects (default: one heartbeat interval)
+    pub liveness_probe_timeout: Option<Duration>,
+    /// Cancel every open order when the application does not call
+    /// `keep_alive` within this window (default: disabled)
+    pub dead_mans_switch: Option<Duration>,
+    /// Round-trip time above which the connection is reported as degraded (default: 1000ms)
+    pub max_ping_latency: Duration,
+    /// How long a request waits for its response when no deadline is given (default: 10s)
+    pub request_timeout: Duration,
+    /// How long a logout waits for the counterparty's Logout (5) before the
+    /// connection is closed anyway (default: 5s)
+    pub logout_timeout: Duration,
+    /// How long a Security List (y) response split in fragments may pause
+    /// between two fragments (default: 10s)
+    pub fragment_timeout: Duration,
+    /// Keep a second connection open as a hot standby and fail over to it
+    /// when the primary connection dies (default: false)
+    pub hot_standby: bool,
+    /// SenderCompID the standby connection logs on with; without one the
+    /// standby is kept at transport level and logs on when failing over
+    /// (default: none)
+    pub standby_sender_comp_id: Option<String>,
+    /// Pre-trade risk limits checked before orders are sent (default: none)
+    pub risk_limits: RiskLimits,
+    /// Self-trade prevention of orders that do not set their own (default: none)
+    pub self_trade_prevention: Option<SelfTradePrevention>,
+    /// Clock used for heartbeats, timeouts and reconnect delays; not
+    /// serialized (default: the system clock)
+    #[serde(skip, default = "system_clock")]
+    pub clock: Arc<dyn Clock>,
+    /// Translation between internal identifiers and the Deribit symbols of
+    /// sent and received messages; not serialized (default: none, symbols
+    /// are sent as given)
+    #[serde(skip)]
+    pub symbol_mapper: Option<Arc<dyn SymbolMapper>>,
+    /// Text form of the prices and quantities of sent messages, for the
+    /// instruments the session knows; not serialized (default:
+    /// [`InstrumentPrecision`](crate::model::number_format::InstrumentPrecision),
+    /// the decimals of the tick size and amount step)
+    #[serde(skip, default = "instrument_precision")]
+    pub number_format: Arc<dyn NumberFormat>,
+    /// Store keeping the owners of the working orders across restarts, such
+    /// as a [`FileOrderIndexStore`](crate::model::order_index::FileOrderIndexStore);
+    /// not serialized (default: none, owners are only kept in memory)
+    #[serde(skip)]
+    pub order_index_store: Option<Arc<dyn OrderIndexStore>>,
 }
 
 impl DeribitFixConfig {
@@ -81,17 +275,7 @@ impl DeribitFixConfig {
         let test_mode = get_env_or_default("DERIBIT_TEST_MODE", true);
         let use_ssl = get_env_or_default("DERIBIT_USE_SSL", false);
 
-        let (default_host, default_port) = if test_mode {
-            if use_ssl {
-                (DEFAULT_TEST_HOST, DEFAULT_SSL_PORT)
-            } else {
-                (DEFAULT_TEST_HOST, DEFAULT_TEST_PORT)
-            }
-        } else if use_ssl {
-            (DEFAULT_PROD_HOST, DEFAULT_SSL_PORT)
-        } else {
-            (DEFAULT_PROD_HOST, DEFAULT_PROD_PORT)
-        };
+        let (default_host, default_port) = Self::default_endpoint(test_mode, use_ssl);
 
         Self {
             username: get_env_or_default("DERIBIT_USERNAME", String::new()),
@@ -126,7 +310,14 @@ impl DeribitFixConfig {
                 "DERIBIT_TARGET_COMP_ID",
                 DEFAULT_TARGET_COMP_ID.to_string(),
             ),
+            on_behalf_of_comp_id: get_env_optional("DERIBIT_ON_BEHALF_OF_COMP_ID"),
+            sender_sub_id: get_env_optional("DERIBIT_SENDER_SUB_ID"),
+            target_sub_id: get_env_optional("DERIBIT_TARGET_SUB_ID"),
             cancel_on_disconnect: get_env_or_default("DERIBIT_CANCEL_ON_DISCONNECT", false),
+            dont_cancel_on_disconnect: get_env_optional::<String>(
+                "DERIBIT_DONT_CANCEL_ON_DISCONNECT",
+            )
+            .map(|v| v == "Y" || v == "true"),
             app_id: get_env_optional("DERIBIT_APP_ID"),
             app_secret: get_env_optional("DERIBIT_APP_SECRET"),
             use_wordsafe_tags: get_env_optional::<String>("DERIBIT_USE_WORDSAFE_TAGS")
@@ -147,6 +338,115 @@ impl DeribitFixConfig {
             .map(|v| v == "Y" || v == "true"),
             display_increment_steps: get_env_optional::<String>("DERIBIT_DISPLAY_INCREMENT_STEPS")
                 .map(|v| v == "Y" || v == "true"),
+            reset_seq_num_on_logon: get_env_or_default("DERIBIT_RESET_SEQ_NUM_ON_LOGON", false),
+            redact_sensitive_fields: get_env_or_default("DERIBIT_REDACT_SENSITIVE_FIELDS", true),
+            queue_orders_during_halt: get_env_or_default("DERIBIT_QUEUE_ORDERS_DURING_HALT", false),
+            relogon_after_logout: get_env_or_default("DERIBIT_RELOGON_AFTER_LOGOUT", true),
+            maintenance_retry_interval: Duration::from_secs(get_env_or_default(
+                "DERIBIT_MAINTENANCE_RETRY_SECS",
+                60,
+            )),
+            logon_retry_attempts: get_env_or_default("DERIBIT_LOGON_RETRY_ATTEMPTS", 0),
+            logon_retry_delay: Duration::from_millis(get_env_or_default(
+                "DERIBIT_LOGON_RETRY_DELAY_MS",
+                1000,
+            )),
+            logon_retry_max_delay: Duration::from_secs(get_env_or_default(
+                "DERIBIT_LOGON_RETRY_MAX_DELAY_SECS",
+                60,
+            )),
+            strict_session_state: get_env_or_default("DERIBIT_STRICT_SESSION_STATE", false),
+            strict_sequence_checks: get_env_or_default("DERIBIT_STRICT_SEQUENCE_CHECKS", false),
+            latency_tracing: get_env_or_default("DERIBIT_LATENCY_TRACING", false),
+            timestamp_precision: get_env_or_default(
+                "DERIBIT_TIMESTAMP_PRECISION",
+                TimestampPrecision::default(),
+            ),
+            capability_probe: get_env_or_default("DERIBIT_CAPABILITY_PROBE", false),
+            default_appl_ver_id: get_env_optional("DERIBIT_DEFAULT_APPL_VER_ID"),
+            paper_trading: get_env_or_default("DERIBIT_PAPER_TRADING", false),
+            order_level_books: get_env_or_default("DERIBIT_ORDER_LEVEL_BOOKS", false),
+            market_data_recording_path: get_env_optional("DERIBIT_MARKET_DATA_RECORDING_PATH"),
+            tcp_nodelay: get_env_or_default("DERIBIT_TCP_NODELAY", true),
+            tcp_keepalive: get_env_optional("DERIBIT_TCP_KEEPALIVE_SECS").map(Duration::from_secs),
+            tcp_keepalive_interval: get_env_optional("DERIBIT_TCP_KEEPALIVE_INTERVAL_SECS")
+                .map(Duration::from_secs),
+            send_buffer_size: get_env_optional("DERIBIT_SEND_BUFFER_SIZE"),
+            recv_buffer_size: get_env_optional("DERIBIT_RECV_BUFFER_SIZE"),
+            proxy: get_env_optional("DERIBIT_PROXY_URL"),
+            dns_cache_ttl: Duration::from_secs(get_env_or_default(
+                "DERIBIT_DNS_CACHE_TTL_SECS",
+                300,
+            )),
+            busy_poll: get_env_optional("DERIBIT_BUSY_POLL_MICROS").map(Duration::from_micros),
+            write_batch_delay: get_env_optional("DERIBIT_WRITE_BATCH_DELAY_MICROS")
+                .map(Duration::from_micros),
+            write_batch_max_bytes: get_env_or_default("DERIBIT_WRITE_BATCH_MAX_BYTES", 65536),
+            max_read_buffer_bytes: get_env_or_default(
+                "DERIBIT_MAX_READ_BUFFER_BYTES",
+                DEFAULT_MAX_BUFFERED,
+            ),
+            max_pending_commands: get_env_or_default("DERIBIT_MAX_PENDING_COMMANDS", 10_000),
+            max_message_rate: get_env_optional("DERIBIT_MAX_MESSAGE_RATE"),
+            adaptive_throttling: get_env_or_default("DERIBIT_ADAPTIVE_THROTTLING", true),
+            max_market_data_backlog: get_env_or_default("DERIBIT_MAX_MARKET_DATA_BACKLOG", 10_000),
+            max_stream_backlog: get_env_or_default("DERIBIT_MAX_STREAM_BACKLOG", 10_000),
+            funding_poll_interval: Duration::from_secs(get_env_or_default(
+                "DERIBIT_FUNDING_POLL_INTERVAL_SECS",
+                60,
+            )),
+            ping_interval: get_env_optional("DERIBIT_PING_INTERVAL_SECS").map(Duration::from_secs),
+            liveness_missed_heartbeats: get_env_optional("DERIBIT_LIVENESS_MISSED_HEARTBEATS"),
+            liveness_probe_timeout: get_env_optional("DERIBIT_LIVENESS_PROBE_TIMEOUT_SECS")
+                .map(Duration::from_secs),
+            dead_mans_switch: get_env_optional("DERIBIT_DEAD_MANS_SWITCH_SECS")
+                .map(Duration::from_secs),
+            max_ping_latency: Duration::from_millis(get_env_or_default(
+                "DERIBIT_MAX_PING_LATENCY_MS",
+                1000,
+            )),
+            request_timeout: Duration::from_secs(get_env_or_default(
+                "DERIBIT_REQUEST_TIMEOUT_SECS",
+                10,
+            )),
+            logout_timeout: Duration::from_secs(get_env_or_default(
+                "DERIBIT_LOGOUT_TIMEOUT_SECS",
+                5,
+            )),
+            fragment_timeout: Duration::from_secs(get_env_or_default(
+                "DERIBIT_FRAGMENT_TIMEOUT_SECS",
+                10,
+            )),
+            hot_standby: get_env_or_default("DERIBIT_HOT_STANDBY", false),
+            standby_sender_comp_id: get_env_optional("DERIBIT_STANDBY_SENDER_COMP_ID"),
+            risk_limits: RiskLimits {
+                max_open_orders_per_instrument: get_env_optional(
+                    "DERIBIT_MAX_OPEN_ORDERS_PER_INSTRUMENT",
+                ),
+                max_order_amount: get_env_optional("DERIBIT_MAX_ORDER_AMOUNT"),
+                max_notional_per_minute: get_env_optional("DERIBIT_MAX_NOTIONAL_PER_MINUTE"),
+                price_collar: get_env_optional("DERIBIT_PRICE_COLLAR"),
+            },
+            self_trade_prevention: get_env_optional("DERIBIT_SELF_TRADE_PREVENTION"),
+            clock: system_clock(),
+            symbol_mapper: None,
+            number_format: instrument_precision(),
+            order_index_store: None,
+        }
+    }
+
+    /// Default host and port of the test or production environment
+    pub(crate) fn default_endpoint(test_mode: bool, use_ssl: bool) -> (&'static str, u16) {
+        if test_mode {
+            if use_ssl {
+                (DEFAULT_TEST_HOST, DEFAULT_SSL_PORT)
+            } else {
+                (DEFAULT_TEST_HOST, DEFAULT_TEST_PORT)
+            }
+        } else if use_ssl {
+            (DEFAULT_PROD_HOST, DEFAULT_SSL_PORT)
+        } else {
+            (DEFAULT_PROD_HOST, DEFAULT_PROD_PORT)
         }
     }
 
@@ -240,12 +540,36 @@ impl DeribitFixConfig {
         self
     }
 
+    /// Send every message on behalf of `on_behalf_of_comp_id` (115)
+    pub fn with_on_behalf_of_comp_id(mut self, on_behalf_of_comp_id: String) -> Self {
+        self.on_behalf_of_comp_id = Some(on_behalf_of_comp_id);
+        self
+    }
+
+    /// Set the SenderSubID (50) and TargetSubID (57) of every message
+    pub fn with_sub_ids(
+        mut self,
+        sender_sub_id: Option<String>,
+        target_sub_id: Option<String>,
+    ) -> Self {
+        self.sender_sub_id = sender_sub_id;
+        self.target_sub_id = target_sub_id;
+        self
+    }
+
     /// Set cancel on disconnect behavior
     pub fn with_cancel_on_disconnect(mut self, cancel_on_disconnect: bool) -> Self {
         self.cancel_on_disconnect = cancel_on_disconnect;
         self
     }
 
+    /// Set whether a logout keeps the orders of a cancel-on-disconnect
+    /// session open
+    pub fn with_dont_cancel_on_disconnect(mut self, dont_cancel: bool) -> Self {
+        self.dont_cancel_on_disconnect = Some(dont_cancel);
+        self
+    }
+
     /// Set application credentials for registered applications
     pub fn with_app_credentials(mut self, app_id: String, app_secret: String) -> Self {
         self.app_id = Some(app_id);
@@ -289,67 +613,556 @@ impl DeribitFixConfig {
         self
     }
 
+    /// Set whether to send ResetSeqNumFlag (141=Y) on logon
+    pub fn with_reset_seq_num_on_logon(mut self, reset: bool) -> Self {
+        self.reset_seq_num_on_logon = reset;
+        self
+    }
+
+    /// Set whether sensitive tags (Password, RawData, ...) are redacted in logs
+    pub fn with_redact_sensitive_fields(mut self, redact: bool) -> Self {
+        self.redact_sensitive_fields = redact;
+        self
+    }
+
+    /// Set whether new orders are queued (rather than rejected) during halts and maintenance
+    pub fn with_queue_orders_during_halt(mut self, queue: bool) -> Self {
+        self.queue_orders_during_halt = queue;
+        self
+    }
+
+    /// Set whether the session logs on again after a non-fatal Logout from the server
+    pub fn with_relogon_after_logout(mut self, relogon: bool) -> Self {
+        self.relogon_after_logout = relogon;
+        self
+    }
+
+    /// Set the interval between logon attempts during exchange maintenance
+    pub fn with_maintenance_retry_interval(mut self, interval: Duration) -> Self {
+        self.maintenance_retry_interval = interval;
+        self
+    }
+
+    /// Set the retries of a logon the server rejects for a transient reason
+    ///
+    /// The wait starts at `delay` and doubles with each retry, up to
+    /// `max_delay`. Logons rejected for invalid credentials are never retried.
+    pub fn with_logon_retry(mut self, attempts: u32, delay: Duration, max_delay: Duration) -> Self {
+        self.logon_retry_attempts = attempts;
+        self.logon_retry_delay = delay;
+        self.logon_retry_max_delay = max_delay;
+        self
+    }
+
+    /// Set whether messages the session state does not allow are rejected
+    pub fn with_strict_session_state(mut self, strict: bool) -> Self {
+        self.strict_session_state = strict;
+        self
+    }
+
+    /// Set whether the sequence numbers of every message are checked
+    pub fn with_strict_sequence_checks(mut self, strict: bool) -> Self {
+        self.strict_sequence_checks = strict;
+        self
+    }
+
+    /// Set whether order-entry latency is traced
+    pub fn with_latency_tracing(mut self, enabled: bool) -> Self {
+        self.latency_tracing = enabled;
+        self
+    }
+
+    /// Set the fractional seconds of the outgoing timestamps
+    pub fn with_timestamp_precision(mut self, precision: TimestampPrecision) -> Self {
+        self.timestamp_precision = precision;
+        self
+    }
+
+    /// Set whether the server capabilities are probed after logon
+    pub fn with_capability_probe(mut self, enabled: bool) -> Self {
+        self.capability_probe = enabled;
+        self
+    }
+
+    /// Propose `default_appl_ver_id` as DefaultApplVerID (1137) in the Logon
+    pub fn with_default_appl_ver_id(mut self, default_appl_ver_id: String) -> Self {
+        self.default_appl_ver_id = Some(default_appl_ver_id);
+        self
+    }
+
+    /// Set whether orders are simulated against live market data (paper trading)
+    pub fn with_paper_trading(mut self, enabled: bool) -> Self {
+        self.paper_trading = enabled;
+        self
+    }
+
+    /// Set whether order books are kept order by order when entries carry order ids
+    pub fn with_order_level_books(mut self, enabled: bool) -> Self {
+        self.order_level_books = enabled;
+        self
+    }
+
+    /// Set the file received market data is recorded to
+    pub fn with_market_data_recording(mut self, path: String) -> Self {
+        self.market_data_recording_path = Some(path);
+        self
+    }
+
+    /// Set whether Nagle's algorithm is disabled (TCP_NODELAY)
+    pub fn with_tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
+    /// Enable TCP keepalive, probing every `interval` after `time` idle
+    pub fn with_tcp_keepalive(mut self, time: Duration, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(time);
+        self.tcp_keepalive_interval = Some(interval);
+        self
+    }
+
+    /// Set the socket send and receive buffer sizes in bytes
+    pub fn with_socket_buffer_sizes(mut self, send: usize, recv: usize) -> Self {
+        self.send_buffer_size = Some(send);
+        self.recv_buffer_size = Some(recv);
+        self
+    }
+
+    /// Tunnel the connection through an HTTP or SOCKS5 proxy
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set how long resolved server addresses are reused, zero to resolve
+    /// on every connect
+    pub fn with_dns_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.dns_cache_ttl = ttl;
+        self
+    }
+
+    /// Busy-poll the socket for up to `duration` before waiting for data
+    pub fn with_busy_poll(mut self, duration: Duration) -> Self {
+        self.busy_poll = Some(duration);
+        self
+    }
+
+    /// Batch outgoing messages for up to `delay`, flushing once `max_bytes` are pending
+    pub fn with_write_batching(mut self, delay: Duration, max_bytes: usize) -> Self {
+        self.write_batch_delay = Some(delay);
+        self.write_batch_max_bytes = max_bytes;
+        self
+    }
+
+    /// Discard the read buffer once it holds `max_bytes` without a complete message
+    pub fn with_max_read_buffer_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_read_buffer_bytes = max_bytes;
+        self
+    }
+
+    /// Fail client calls once `max` are waiting for the session task
+    pub fn with_max_pending_commands(mut self, max: usize) -> Self {
+        self.max_pending_commands = max;
+        self
+    }
+
+    /// Send at most `rate` application messages per second
+    pub fn with_max_message_rate(mut self, rate: f64) -> Self {
+        self.max_message_rate = Some(rate);
+        self
+    }
+
+    /// Enable or disable adjusting the message rate to rate-limit rejections
+    pub fn with_adaptive_throttling(mut self, enabled: bool) -> Self {
+        self.adaptive_throttling = enabled;
+        self
+    }
+
+    /// Drop the oldest market data updates of a receiver `max` updates behind
+    pub fn with_max_market_data_backlog(mut self, max: usize) -> Self {
+        self.max_market_data_backlog = max;
+        self
+    }
+
+    /// Bound the execution, trade and instrument streams to `max` messages
+    /// behind per receiver
+    pub fn with_max_stream_backlog(mut self, max: usize) -> Self {
+        self.max_stream_backlog = max;
+        self
+    }
+
+    /// Poll the funding rates of the tracked perpetuals every `interval`
+    pub fn with_funding_poll_interval(mut self, interval: Duration) -> Self {
+        self.funding_poll_interval = interval;
+        self
+    }
+
+    /// Run the latency watchdog, sending a Test Request every `interval`
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Probe the counterparty after `missed_heartbeats` silent heartbeat
+    /// intervals and reconnect when the probe is unanswered for `probe_timeout`
+    pub fn with_liveness_checks(
+        mut self,
+        missed_heartbeats: u32,
+        probe_timeout: Option<Duration>,
+    ) -> Self {
+        self.liveness_missed_heartbeats = Some(missed_heartbeats);
+        self.liveness_probe_timeout = probe_timeout;
+        self
+    }
+
+    /// Cancel every open order when `keep_alive` is not called within `window`
+    pub fn with_dead_mans_switch(mut self, window: Duration) -> Self {
+        self.dead_mans_switch = Some(window);
+        self
+    }
+
+    /// Set the round-trip time above which the connection is degraded
+    pub fn with_max_ping_latency(mut self, latency: Duration) -> Self {
+        self.max_ping_latency = latency;
+        self
+    }
+
+    /// Set how long requests wait for their response when no deadline is given
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Set how long a logout waits for the counterparty's Logout
+    pub fn with_logout_timeout(mut self, timeout: Duration) -> Self {
+        self.logout_timeout = timeout;
+        self
+    }
+
+    /// Set how long a fragmented Security List response may pause between
+    /// two fragments
+    pub fn with_fragment_timeout(mut self, timeout: Duration) -> Self {
+        self.fragment_timeout = timeout;
+        self
+    }
+
+    /// Keep a hot standby connection, logged on with `sender_comp_id` when given
+    pub fn with_hot_standby(mut self, sender_comp_id: Option<String>) -> Self {
+        self.hot_standby = true;
+        self.standby_sender_comp_id = sender_comp_id;
+        self
+    }
+
+    /// Set the pre-trade risk limits checked before orders are sent
+    pub fn with_risk_limits(mut self, limits: RiskLimits) -> Self {
+        self.risk_limits = limits;
+        self
+    }
+
+    /// Set the self-trade prevention of orders that do not set their own
+    pub fn with_self_trade_prevention(mut self, stp: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = Some(stp);
+        self
+    }
+
+    /// Set the clock used for session timing, e.g. a
+    /// [`ManualClock`](crate::session::ManualClock) in tests
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the mapper translating internal identifiers to Deribit symbols
+    /// and back, such as a [`SymbolTable`](crate::model::symbol_map::SymbolTable)
+    pub fn with_symbol_mapper(mut self, mapper: Arc<dyn SymbolMapper>) -> Self {
+        self.symbol_mapper = Some(mapper);
+        self
+    }
+
+    /// Set how the prices and quantities of sent messages are written
+    pub fn with_number_format(mut self, format: Arc<dyn NumberFormat>) -> Self {
+        self.number_format = format;
+        self
+    }
+
+    /// Set the store keeping the ClOrdID, OrderID and label of the working
+    /// orders across restarts
+    pub fn with_order_index_store(mut self, store: Arc<dyn OrderIndexStore>) -> Self {
+        self.order_index_store = Some(store);
+        self
+    }
+
     /// Get the connection URL
     pub fn connection_url(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
 
-    /// Validate the configuration
+    /// Validate the configuration, failing on the first problem found
+    ///
+    /// See [`validation_report`](Self::validation_report) for every problem.
     pub fn validate(&self) -> Result<()> {
+        match self.validation_report().issues.into_iter().next() {
+            Some(issue) => Err(DeribitFixError::Config(issue.message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the configuration and list every problem found
+    pub fn validation_report(&self) -> ConfigReport {
+        let mut report = ConfigReport::default();
+
         if self.username.is_empty() {
-            return Err(DeribitFixError::Config(
-                "Username cannot be empty".to_string(),
-            ));
+            report.push("username", "Username cannot be empty");
         }
 
         if self.password.is_empty() {
-            return Err(DeribitFixError::Config(
-                "Password cannot be empty".to_string(),
-            ));
+            report.push("password", "Password cannot be empty");
         }
 
         if self.host.is_empty() {
-            return Err(DeribitFixError::Config("Host cannot be empty".to_string()));
+            report.push("host", "Host cannot be empty");
         }
 
         if self.port == 0 {
-            return Err(DeribitFixError::Config(
-                "Port must be greater than 0".to_string(),
-            ));
+            report.push("port", "Port must be greater than 0");
         }
 
         if self.heartbeat_interval == 0 {
-            return Err(DeribitFixError::Config(
-                "Heartbeat interval must be greater than 0".to_string(),
-            ));
+            report.push(
+                "heartbeat_interval",
+                "Heartbeat interval must be greater than 0",
+            );
         }
 
         if self.sender_comp_id.is_empty() {
-            return Err(DeribitFixError::Config(
-                "Sender company ID cannot be empty".to_string(),
-            ));
+            report.push("sender_comp_id", "Sender company ID cannot be empty");
         }
 
         if self.target_comp_id.is_empty() {
-            return Err(DeribitFixError::Config(
-                "Target company ID cannot be empty".to_string(),
-            ));
+            report.push("target_comp_id", "Target company ID cannot be empty");
+        }
+
+        for (field, value) in [
+            ("on_behalf_of_comp_id", &self.on_behalf_of_comp_id),
+            ("sender_sub_id", &self.sender_sub_id),
+            ("target_sub_id", &self.target_sub_id),
+        ] {
+            if value
+                .as_ref()
+                .is_some_and(|value| value.is_empty() || value.contains('\x01'))
+            {
+                report.push(field, "Header IDs cannot be empty or contain SOH");
+            }
+        }
+
+        if self
+            .default_appl_ver_id
+            .as_ref()
+            .is_some_and(|value| value.is_empty() || value.contains('\x01'))
+        {
+            report.push(
+                "default_appl_ver_id",
+                "DefaultApplVerID cannot be empty or contain SOH",
+            );
         }
 
         // Validate app credentials if provided
         if self.app_id.is_some() && self.app_secret.is_none() {
-            return Err(DeribitFixError::Config(
-                "Application secret is required when app ID is provided".to_string(),
-            ));
+            report.push(
+                "app_secret",
+                "Application secret is required when app ID is provided",
+            );
         }
 
         if self.app_secret.is_some() && self.app_id.is_none() {
-            return Err(DeribitFixError::Config(
-                "Application ID is required when app secret is provided".to_string(),
-            ));
+            report.push(
+                "app_id",
+                "Application ID is required when app secret is provided",
+            );
+        }
+
+        for (field, value) in [("app_id", &self.app_id), ("app_secret", &self.app_secret)] {
+            if value
+                .as_ref()
+                .is_some_and(|value| value.is_empty() || value.contains('\x01'))
+            {
+                report.push(
+                    field,
+                    "Application credentials cannot be empty or contain SOH",
+                );
+            }
+        }
+
+        if self.unsubscribe_execution_reports == Some(true) {
+            if self.connection_only_execution_reports == Some(true) {
+                report.push(
+                    "connection_only_execution_reports",
+                    "Execution Reports cannot be limited to this connection when unsubscribed from",
+                );
+            }
+            if self.report_fills_as_exec_reports == Some(true) {
+                report.push(
+                    "report_fills_as_exec_reports",
+                    "Fills cannot be reported as Execution Reports when unsubscribed from them",
+                );
+            }
+        }
+
+        if self.tcp_keepalive_interval.is_some() && self.tcp_keepalive.is_none() {
+            report.push(
+                "tcp_keepalive",
+                "TCP keepalive time is required when a keepalive interval is provided",
+            );
+        }
+
+        if self.send_buffer_size == Some(0) || self.recv_buffer_size == Some(0) {
+            report.push(
+                "send_buffer_size",
+                "Socket buffer sizes must be greater than 0",
+            );
+        }
+
+        if let Some(proxy) = &self.proxy
+            && (proxy.host.is_empty() || proxy.port == 0)
+        {
+            report.push("proxy", "Proxy host and port must be set");
+        }
+
+        if self.write_batch_max_bytes == 0 {
+            report.push(
+                "write_batch_max_bytes",
+                "Write batch size must be greater than 0",
+            );
+        }
+
+        if self.max_read_buffer_bytes < 2 * MAX_BODY_LENGTH {
+            report.push(
+                "max_read_buffer_bytes",
+                format!(
+                    "Read buffer must hold at least {} bytes, twice the largest message",
+                    2 * MAX_BODY_LENGTH
+                ),
+            );
+        }
+
+        if self.max_pending_commands == 0 {
+            report.push(
+                "max_pending_commands",
+                "Pending command limit must be greater than 0",
+            );
+        }
+
+        if self
+            .max_message_rate
+            .is_some_and(|rate| !rate.is_finite() || rate <= 0.0)
+        {
+            report.push("max_message_rate", "Message rate must be a positive number");
+        }
+
+        if self.max_market_data_backlog == 0 {
+            report.push(
+                "max_market_data_backlog",
+                "Market data backlog must be greater than 0",
+            );
+        }
+
+        if self.max_stream_backlog == 0 {
+            report.push(
+                "max_stream_backlog",
+                "Stream backlog must be greater than 0",
+            );
+        }
+
+        if self.funding_poll_interval.is_zero() {
+            report.push(
+                "funding_poll_interval",
+                "Funding poll interval must be greater than 0",
+            );
+        }
+
+        if self
+            .ping_interval
+            .is_some_and(|interval| interval.is_zero())
+        {
+            report.push("ping_interval", "Ping interval must be greater than 0");
+        }
+
+        if self.liveness_missed_heartbeats == Some(0) {
+            report.push(
+                "liveness_missed_heartbeats",
+                "Missed heartbeats before a liveness probe must be greater than 0",
+            );
+        }
+
+        if self
+            .liveness_probe_timeout
+            .is_some_and(|timeout| timeout.is_zero())
+        {
+            report.push(
+                "liveness_probe_timeout",
+                "Liveness probe timeout must be greater than 0",
+            );
+        }
+
+        if self.dead_mans_switch.is_some_and(|window| window.is_zero()) {
+            report.push(
+                "dead_mans_switch",
+                "Dead man's switch window must be greater than 0",
+            );
+        }
+
+        if self.request_timeout.is_zero() {
+            report.push("request_timeout", "Request timeout must be greater than 0");
+        }
+
+        if self.logout_timeout.is_zero() {
+            report.push("logout_timeout", "Logout timeout must be greater than 0");
+        }
+
+        if self.fragment_timeout.is_zero() {
+            report.push(
+                "fragment_timeout",
+                "Fragment timeout must be greater than 0",
+            );
+        }
+
+        if self.maintenance_retry_interval.is_zero() {
+            report.push(
+                "maintenance_retry_interval",
+                "Maintenance retry interval must be greater than 0",
+            );
+        }
+
+        if self.logon_retry_delay > self.logon_retry_max_delay {
+            report.push(
+                "logon_retry_delay",
+                "Logon retry delay cannot exceed the maximum logon retry delay",
+            );
+        }
+
+        if let Some(standby_sender_comp_id) = &self.standby_sender_comp_id
+            && (standby_sender_comp_id.is_empty() || *standby_sender_comp_id == self.sender_comp_id)
+        {
+            report.push(
+                "standby_sender_comp_id",
+                "Standby SenderCompID must be set and differ from the primary one",
+            );
+        }
+
+        if self.risk_limits.max_open_orders_per_instrument == Some(0)
+            || [
+                self.risk_limits.max_order_amount,
+                self.risk_limits.max_notional_per_minute,
+                self.risk_limits.price_collar,
+            ]
+            .into_iter()
+            .flatten()
+            .any(|limit| limit.is_nan() || limit <= 0.0)
+        {
+            report.push("risk_limits", "Risk limits must be greater than 0");
         }
 
-        Ok(())
+        report
     }
 }
 
@@ -0,0 +1,648 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Configuration loading from files and the environment
+//!
+//! [`DeribitFixConfig::from_env`] reads the `DERIBIT_*` variables listed in
+//! `.env.example`. With the `config-files` feature,
+//! [`DeribitFixConfig::from_file`] reads a TOML or YAML file whose keys are
+//! the field names of [`DeribitFixConfig`], the risk limits nested under
+//! `risk_limits`:
+//!
+//! ```toml
+//! username = "my_key"
+//! password = "my_secret"
+//! test_mode = false
+//! connection_timeout = 5   # seconds, like DERIBIT_CONNECTION_TIMEOUT
+//! proxy = "socks5://proxy.example.com:1080"
+//!
+//! [risk_limits]
+//! max_order_amount = 100000
+//! ```
+//!
+//! Durations are numbers in the unit of their environment variable and the
+//! proxy is a URL. Environment variables override the file, and fields set
+//! by neither keep their default; the host and port follow `test_mode` and
+//! `use_ssl` unless they are set.
+//!
+//! Where [`DeribitFixConfig::new`] falls back to the default of a value it
+//! cannot parse, both loaders fail with
+//! [`DeribitFixError::InvalidConfig`], whose [`ConfigReport`] lists every
+//! unknown key, unparsable value and failed validation check.
+
+use crate::config::DeribitFixConfig;
+use crate::connection::ProxyConfig;
+use crate::error::{DeribitFixError, Result};
+use crate::message::time::TimestampPrecision;
+use crate::model::exec_inst::SelfTradePrevention;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::time::Duration;
+
+/// Problem found with one configuration field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigIssue {
+    /// Field name, or the environment variable the value came from
+    pub field: String,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+/// Every problem found while loading or validating a configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigReport {
+    /// Problems in the order they were found
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    /// Record a problem with `field`
+    pub fn push(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ConfigIssue {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Whether no problem was found
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Whether a problem was found with `field`
+    pub fn has_issue(&self, field: &str) -> bool {
+        self.issues.iter().any(|issue| issue.field == field)
+    }
+
+    fn into_result(self) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        Err(DeribitFixError::InvalidConfig(self))
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} configuration issue(s)", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "; {}: {}", issue.field, issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Format of a configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// TOML
+    Toml,
+    /// YAML
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Format of a file by its extension: `.toml`, `.yaml` or `.yml`
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// How the value of a field is written in files and environment variables
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Text,
+    Bool,
+    /// Boolean also accepting `Y` and `N`
+    Flag,
+    Integer(u64),
+    Float,
+    Seconds,
+    Millis,
+    Micros,
+    Proxy,
+    SelfTradePrevention,
+    TimestampPrecision,
+}
+
+/// Loadable field: path in [`DeribitFixConfig`], environment variable and kind
+const FIELDS: &[(&str, &str, Kind)] = &[
+    ("username", "DERIBIT_USERNAME", Kind::Text),
+    ("password", "DERIBIT_PASSWORD", Kind::Text),
+    ("host", "DERIBIT_HOST", Kind::Text),
+    ("port", "DERIBIT_PORT", Kind::Integer(u16::MAX as u64)),
+    ("use_ssl", "DERIBIT_USE_SSL", Kind::Bool),
+    ("test_mode", "DERIBIT_TEST_MODE", Kind::Bool),
+    (
+        "heartbeat_interval",
+        "DERIBIT_HEARTBEAT_INTERVAL",
+        Kind::Integer(u32::MAX as u64),
+    ),
+    (
+        "connection_timeout",
+        "DERIBIT_CONNECTION_TIMEOUT",
+        Kind::Seconds,
+    ),
+    (
+        "reconnect_attempts",
+        "DERIBIT_RECONNECT_ATTEMPTS",
+        Kind::Integer(u32::MAX as u64),
+    ),
+    ("reconnect_delay", "DERIBIT_RECONNECT_DELAY", Kind::Seconds),
+    ("enable_logging", "DERIBIT_ENABLE_LOGGING", Kind::Bool),
+    ("log_level", "DERIBIT_LOG_LEVEL", Kind::Text),
+    ("sender_comp_id", "DERIBIT_SENDER_COMP_ID", Kind::Text),
+    ("target_comp_id", "DERIBIT_TARGET_COMP_ID", Kind::Text),
+    (
+        "on_behalf_of_comp_id",
+        "DERIBIT_ON_BEHALF_OF_COMP_ID",
+        Kind::Text,
+    ),
+    ("sender_sub_id", "DERIBIT_SENDER_SUB_ID", Kind::Text),
+    ("target_sub_id", "DERIBIT_TARGET_SUB_ID", Kind::Text),
+    (
+        "cancel_on_disconnect",
+        "DERIBIT_CANCEL_ON_DISCONNECT",
+        Kind::Bool,
+    ),
+    (
+        "dont_cancel_on_disconnect",
+        "DERIBIT_DONT_CANCEL_ON_DISCONNECT",
+        Kind::Flag,
+    ),
+    ("app_id", "DERIBIT_APP_ID", Kind::Text),
+    ("app_secret", "DERIBIT_APP_SECRET", Kind::Text),
+    ("use_wordsafe_tags", "DERIBIT_USE_WORDSAFE_TAGS", Kind::Flag),
+    ("deribit_sequential", "DERIBIT_SEQUENTIAL", Kind::Flag),
+    (
+        "unsubscribe_execution_reports",
+        "DERIBIT_UNSUBSCRIBE_EXECUTION_REPORTS",
+        Kind::Flag,
+    ),
+    (
+        "connection_only_execution_reports",
+        "DERIBIT_CONNECTION_ONLY_EXECUTION_REPORTS",
+        Kind::Flag,
+    ),
+    (
+        "report_fills_as_exec_reports",
+        "DERIBIT_REPORT_FILLS_AS_EXEC_REPORTS",
+        Kind::Flag,
+    ),
+    (
+        "display_increment_steps",
+        "DERIBIT_DISPLAY_INCREMENT_STEPS",
+        Kind::Flag,
+    ),
+    (
+        "reset_seq_num_on_logon",
+        "DERIBIT_RESET_SEQ_NUM_ON_LOGON",
+        Kind::Bool,
+    ),
+    (
+        "redact_sensitive_fields",
+        "DERIBIT_REDACT_SENSITIVE_FIELDS",
+        Kind::Bool,
+    ),
+    (
+        "queue_orders_during_halt",
+        "DERIBIT_QUEUE_ORDERS_DURING_HALT",
+        Kind::Bool,
+    ),
+    (
+        "relogon_after_logout",
+        "DERIBIT_RELOGON_AFTER_LOGOUT",
+        Kind::Bool,
+    ),
+    (
+        "maintenance_retry_interval",
+        "DERIBIT_MAINTENANCE_RETRY_SECS",
+        Kind::Seconds,
+    ),
+    (
+        "logon_retry_attempts",
+        "DERIBIT_LOGON_RETRY_ATTEMPTS",
+        Kind::Integer(u32::MAX as u64),
+    ),
+    (
+        "logon_retry_delay",
+        "DERIBIT_LOGON_RETRY_DELAY_MS",
+        Kind::Millis,
+    ),
+    (
+        "logon_retry_max_delay",
+        "DERIBIT_LOGON_RETRY_MAX_DELAY_SECS",
+        Kind::Seconds,
+    ),
+    (
+        "strict_session_state",
+        "DERIBIT_STRICT_SESSION_STATE",
+        Kind::Bool,
+    ),
+    (
+        "strict_sequence_checks",
+        "DERIBIT_STRICT_SEQUENCE_CHECKS",
+        Kind::Bool,
+    ),
+    ("latency_tracing", "DERIBIT_LATENCY_TRACING", Kind::Bool),
+    (
+        "timestamp_precision",
+        "DERIBIT_TIMESTAMP_PRECISION",
+        Kind::TimestampPrecision,
+    ),
+    ("capability_probe", "DERIBIT_CAPABILITY_PROBE", Kind::Bool),
+    (
+        "default_appl_ver_id",
+        "DERIBIT_DEFAULT_APPL_VER_ID",
+        Kind::Text,
+    ),
+    ("paper_trading", "DERIBIT_PAPER_TRADING", Kind::Bool),
+    ("order_level_books", "DERIBIT_ORDER_LEVEL_BOOKS", Kind::Bool),
+    (
+        "market_data_recording_path",
+        "DERIBIT_MARKET_DATA_RECORDING_PATH",
+        Kind::Text,
+    ),
+    ("tcp_nodelay", "DERIBIT_TCP_NODELAY", Kind::Bool),
+    ("tcp_keepalive", "DERIBIT_TCP_KEEPALIVE_SECS", Kind::Seconds),
+    (
+        "tcp_keepalive_interval",
+        "DERIBIT_TCP_KEEPALIVE_INTERVAL_SECS",
+        Kind::Seconds,
+    ),
+    (
+        "send_buffer_size",
+        "DERIBIT_SEND_BUFFER_SIZE",
+        Kind::Integer(u64::MAX),
+    ),
+    (
+        "recv_buffer_size",
+        "DERIBIT_RECV_BUFFER_SIZE",
+        Kind::Integer(u64::MAX),
+    ),
+    ("proxy", "DERIBIT_PROXY_URL", Kind::Proxy),
+    ("dns_cache_ttl", "DERIBIT_DNS_CACHE_TTL_SECS", Kind::Seconds),
+    ("busy_poll", "DERIBIT_BUSY_POLL_MICROS", Kind::Micros),
+    (
+        "write_batch_delay",
+        "DERIBIT_WRITE_BATCH_DELAY_MICROS",
+        Kind::Micros,
+    ),
+    (
+        "write_batch_max_bytes",
+        "DERIBIT_WRITE_BATCH_MAX_BYTES",
+        Kind::Integer(u64::MAX),
+    ),
+    (
+        "max_read_buffer_bytes",
+        "DERIBIT_MAX_READ_BUFFER_BYTES",
+        Kind::Integer(u64::MAX),
+    ),
+    (
+        "max_pending_commands",
+        "DERIBIT_MAX_PENDING_COMMANDS",
+        Kind::Integer(u64::MAX),
+    ),
+    ("max_message_rate", "DERIBIT_MAX_MESSAGE_RATE", Kind::Float),
+    (
+        "adaptive_throttling",
+        "DERIBIT_ADAPTIVE_THROTTLING",
+        Kind::Bool,
+    ),
+    (
+        "max_market_data_backlog",
+        "DERIBIT_MAX_MARKET_DATA_BACKLOG",
+        Kind::Integer(u64::MAX),
+    ),
+    (
+        "max_stream_backlog",
+        "DERIBIT_MAX_STREAM_BACKLOG",
+        Kind::Integer(u64::MAX),
+    ),
+    (
+        "funding_poll_interval",
+        "DERIBIT_FUNDING_POLL_INTERVAL_SECS",
+        Kind::Seconds,
+    ),
+    ("ping_interval", "DERIBIT_PING_INTERVAL_SECS", Kind::Seconds),
+    (
+        "liveness_missed_heartbeats",
+        "DERIBIT_LIVENESS_MISSED_HEARTBEATS",
+        Kind::Integer(u32::MAX as u64),
+    ),
+    (
+        "liveness_probe_timeout",
+        "DERIBIT_LIVENESS_PROBE_TIMEOUT_SECS",
+        Kind::Seconds,
+    ),
+    (
+        "dead_mans_switch",
+        "DERIBIT_DEAD_MANS_SWITCH_SECS",
+        Kind::Seconds,
+    ),
+    (
+        "max_ping_latency",
+        "DERIBIT_MAX_PING_LATENCY_MS",
+        Kind::Millis,
+    ),
+    (
+        "request_timeout",
+        "DERIBIT_REQUEST_TIMEOUT_SECS",
+        Kind::Seconds,
+    ),
+    (
+        "logout_timeout",
+        "DERIBIT_LOGOUT_TIMEOUT_SECS",
+        Kind::Seconds,
+    ),
+    (
+        "fragment_timeout",
+        "DERIBIT_FRAGMENT_TIMEOUT_SECS",
+        Kind::Seconds,
+    ),
+    ("hot_standby", "DERIBIT_HOT_STANDBY", Kind::Bool),
+    (
+        "standby_sender_comp_id",
+        "DERIBIT_STANDBY_SENDER_COMP_ID",
+        Kind::Text,
+    ),
+    (
+        "risk_limits.max_open_orders_per_instrument",
+        "DERIBIT_MAX_OPEN_ORDERS_PER_INSTRUMENT",
+        Kind::Integer(u64::MAX),
+    ),
+    (
+        "risk_limits.max_order_amount",
+        "DERIBIT_MAX_ORDER_AMOUNT",
+        Kind::Float,
+    ),
+    (
+        "risk_limits.max_notional_per_minute",
+        "DERIBIT_MAX_NOTIONAL_PER_MINUTE",
+        Kind::Float,
+    ),
+    (
+        "risk_limits.price_collar",
+        "DERIBIT_PRICE_COLLAR",
+        Kind::Float,
+    ),
+    (
+        "self_trade_prevention",
+        "DERIBIT_SELF_TRADE_PREVENTION",
+        Kind::SelfTradePrevention,
+    ),
+];
+
+impl Kind {
+    /// Parse the text of an environment variable or a file string
+    fn parse(self, raw: &str) -> std::result::Result<Value, String> {
+        let invalid = |expected: &str| format!("invalid value {raw:?}, expected {expected}");
+        let number = |expected: &str| raw.trim().parse::<u64>().map_err(|_| invalid(expected));
+        match self {
+            Kind::Text => Ok(Value::from(raw)),
+            Kind::Bool => raw
+                .parse::<bool>()
+                .map(Value::from)
+                .map_err(|_| invalid("true or false")),
+            Kind::Flag => match raw {
+                "Y" | "true" => Ok(Value::from(true)),
+                "N" | "false" => Ok(Value::from(false)),
+                _ => Err(invalid("Y, N, true or false")),
+            },
+            Kind::Integer(max) => match number("a non-negative integer")? {
+                value if value > max => Err(format!("{value} is larger than {max}")),
+                value => Ok(Value::from(value)),
+            },
+            Kind::Float => match raw.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(Value::from(value)),
+                _ => Err(invalid("a number")),
+            },
+            Kind::Seconds => duration(Duration::from_secs(number("whole seconds")?)),
+            Kind::Millis => duration(Duration::from_millis(number("whole milliseconds")?)),
+            Kind::Micros => duration(Duration::from_micros(number("whole microseconds")?)),
+            Kind::Proxy => {
+                let proxy: ProxyConfig = raw.parse().map_err(|_| invalid("a proxy URL"))?;
+                serde_json::to_value(proxy).map_err(|e| e.to_string())
+            }
+            Kind::SelfTradePrevention => {
+                let stp: SelfTradePrevention = raw
+                    .parse()
+                    .map_err(|_| invalid("cancel_maker, cancel_taker or cancel_both"))?;
+                serde_json::to_value(stp).map_err(|e| e.to_string())
+            }
+            Kind::TimestampPrecision => {
+                let precision: TimestampPrecision =
+                    raw.parse().map_err(|_| invalid("millis or micros"))?;
+                serde_json::to_value(precision).map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// Convert a value read from a configuration file
+    #[cfg(feature = "config-files")]
+    fn convert(self, value: Value) -> std::result::Result<Value, String> {
+        match (self, value) {
+            (_, Value::Null) => Ok(Value::Null),
+            (_, Value::String(raw)) => self.parse(&raw),
+            (Kind::Bool | Kind::Flag, Value::Bool(value)) => Ok(Value::Bool(value)),
+            (Kind::Float, Value::Number(value)) if value.is_f64() || value.is_u64() => {
+                Ok(Value::Number(value))
+            }
+            (
+                Kind::Integer(_) | Kind::Seconds | Kind::Millis | Kind::Micros,
+                Value::Number(value),
+            ) => self.parse(&value.to_string()),
+            (Kind::Proxy, value @ Value::Object(_)) => {
+                let proxy: ProxyConfig =
+                    serde_json::from_value(value).map_err(|e| format!("invalid proxy: {e}"))?;
+                serde_json::to_value(proxy).map_err(|e| e.to_string())
+            }
+            (kind, value) => Err(format!("unexpected value {value} for a {kind:?} field")),
+        }
+    }
+}
+
+fn duration(duration: Duration) -> std::result::Result<Value, String> {
+    serde_json::to_value(duration).map_err(|e| e.to_string())
+}
+
+/// Layers files and environment variables over the default configuration
+struct ConfigLoader {
+    value: Value,
+    /// Fields set by a file or an environment variable
+    set: HashSet<&'static str>,
+    report: ConfigReport,
+}
+
+impl ConfigLoader {
+    fn new() -> Result<Self> {
+        Ok(Self {
+            value: serde_json::to_value(DeribitFixConfig::new())?,
+            set: HashSet::new(),
+            report: ConfigReport::default(),
+        })
+    }
+
+    fn set(&mut self, path: &'static str, value: Value) {
+        let mut target = &mut self.value;
+        for key in path.split('.') {
+            target = &mut target[key];
+        }
+        *target = value;
+        self.set.insert(path);
+    }
+
+    /// Apply the keys of a file, nested tables giving dotted paths
+    #[cfg(feature = "config-files")]
+    fn apply_file(&mut self, table: serde_json::Map<String, Value>, prefix: &str) {
+        for (key, value) in table {
+            let path = format!("{prefix}{key}");
+            match FIELDS.iter().find(|(field, _, _)| *field == path) {
+                Some((field, _, kind)) => match kind.convert(value) {
+                    Ok(value) => self.set(field, value),
+                    Err(message) => self.report.push(path, message),
+                },
+                None => match value {
+                    Value::Object(table) => self.apply_file(table, &format!("{path}.")),
+                    _ => self.report.push(path, "unknown field"),
+                },
+            }
+        }
+    }
+
+    fn apply_env(&mut self) {
+        for (field, env_var, kind) in FIELDS {
+            let Ok(raw) = std::env::var(env_var) else {
+                continue;
+            };
+            match kind.parse(&raw) {
+                Ok(value) => self.set(field, value),
+                Err(message) => self.report.push(*env_var, message),
+            }
+        }
+    }
+
+    fn finish(mut self) -> Result<DeribitFixConfig> {
+        let flag = |value: &Value| value.as_bool().unwrap_or_default();
+        let (host, port) = DeribitFixConfig::default_endpoint(
+            flag(&self.value["test_mode"]),
+            flag(&self.value["use_ssl"]),
+        );
+        if !self.set.contains("host") {
+            self.value["host"] = Value::from(host);
+        }
+        if !self.set.contains("port") {
+            self.value["port"] = Value::from(port);
+        }
+        std::mem::take(&mut self.report).into_result()?;
+
+        let config: DeribitFixConfig = serde_json::from_value(self.value)
+            .map_err(|e| DeribitFixError::Config(format!("Invalid configuration: {e}")))?;
+        config.validation_report().into_result()?;
+        Ok(config)
+    }
+}
+
+impl DeribitFixConfig {
+    /// Load the configuration from `DERIBIT_*` environment variables and any
+    /// `.env` file, reporting every invalid value
+    pub fn from_env() -> Result<Self> {
+        let mut loader = ConfigLoader::new()?;
+        loader.apply_env();
+        loader.finish()
+    }
+
+    /// Load the configuration from a TOML or YAML file, chosen by its
+    /// extension, with environment variables taking precedence
+    #[cfg(feature = "config-files")]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path).ok_or_else(|| {
+            DeribitFixError::Config(format!(
+                "Unsupported configuration file {}, expected .toml, .yaml or .yml",
+                path.display()
+            ))
+        })?;
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_contents(&contents, format)
+    }
+
+    /// Load the configuration from the contents of a TOML or YAML file, with
+    /// environment variables taking precedence
+    #[cfg(feature = "config-files")]
+    pub fn from_contents(contents: &str, format: ConfigFormat) -> Result<Self> {
+        let parsed: std::result::Result<Value, String> = match format {
+            ConfigFormat::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+        };
+        let table = match parsed {
+            Ok(Value::Object(table)) => table,
+            Ok(Value::Null) => serde_json::Map::new(),
+            Ok(_) => {
+                return Err(DeribitFixError::Config(
+                    "Configuration file must contain a table of fields".to_string(),
+                ));
+            }
+            Err(e) => {
+                return Err(DeribitFixError::Config(format!(
+                    "Failed to parse {format:?} configuration: {e}"
+                )));
+            }
+        };
+
+        let mut loader = ConfigLoader::new()?;
+        loader.apply_file(table, "");
+        loader.apply_env();
+        loader.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinds_parse_env_values() {
+        assert_eq!(Kind::Flag.parse("Y").unwrap(), Value::from(true));
+        assert!(Kind::Bool.parse("Y").is_err());
+        assert!(Kind::Integer(u16::MAX as u64).parse("70000").is_err());
+        assert_eq!(
+            Kind::Millis.parse("250").unwrap(),
+            serde_json::to_value(Duration::from_millis(250)).unwrap()
+        );
+        assert!(Kind::Seconds.parse("-1").is_err());
+        assert!(Kind::Float.parse("NaN").is_err());
+        assert!(Kind::Proxy.parse("ftp://proxy:1").is_err());
+    }
+
+    #[cfg(feature = "config-files")]
+    #[test]
+    fn test_kinds_convert_file_values() {
+        assert_eq!(
+            Kind::Seconds.convert(Value::from(5)).unwrap(),
+            serde_json::to_value(Duration::from_secs(5)).unwrap()
+        );
+        assert!(Kind::Text.convert(Value::from(5)).is_err());
+        assert_eq!(Kind::Text.convert(Value::Null).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_every_field_is_a_config_field() {
+        let value = serde_json::to_value(DeribitFixConfig::new()).unwrap();
+        for (field, _, _) in FIELDS {
+            let mut target = &value;
+            for key in field.split('.') {
+                target = target.get(key).unwrap_or_else(|| panic!("{field}"));
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! Configuration module for the Deribit FIX client
 
 mod base;
+mod loader;
 mod utils;
 
 pub use crate::config::base::DeribitFixConfig;
+pub use loader::{ConfigFormat, ConfigIssue, ConfigReport};
 pub use utils::gen_id;
@@ -0,0 +1,323 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! FIX message framing
+//!
+//! [`FixFramer`] splits the incoming byte stream into complete FIX messages
+//! using the standard header: every message starts with BeginString (8),
+//! immediately followed by BodyLength (9), and ends exactly BodyLength bytes
+//! later with the CheckSum (10) trailer. Field content is never searched for
+//! delimiters, so values containing `10=` or `8=FIX` cannot mis-frame the
+//! stream. Bytes that never complete a message, such as a peer sending
+//! garbage without delimiters, are discarded once they exceed the buffer
+//! limit.
+
+use crate::error::{DeribitFixError, Result};
+
+/// Largest BodyLength (9) accepted before the frame is considered corrupt
+pub const MAX_BODY_LENGTH: usize = 1024 * 1024;
+
+/// Default limit of the bytes buffered without completing a message
+pub const DEFAULT_MAX_BUFFERED: usize = 4 * MAX_BODY_LENGTH;
+
+/// Field delimiter
+const SOH: u8 = 0x01;
+/// Prefix of the BeginString (8) field
+const BEGIN_STRING: &[u8] = b"8=FIX";
+/// Prefix of the BodyLength (9) field
+const BODY_LENGTH: &[u8] = b"9=";
+/// Length of the CheckSum trailer: `10=` + 3 digits + SOH
+const TRAILER_LENGTH: usize = 7;
+/// Longest BeginString value accepted, e.g. `FIXT.1.1`
+const MAX_BEGIN_STRING_LENGTH: usize = 16;
+/// Maximum number of digits in BodyLength
+const MAX_BODY_LENGTH_DIGITS: usize = 7;
+
+/// Incremental splitter of a FIX byte stream into complete messages
+#[derive(Debug)]
+pub struct FixFramer {
+    buffer: Vec<u8>,
+    /// Bytes buffered without a complete message before they are discarded
+    max_buffered: usize,
+    /// Most bytes buffered at once
+    high_water: usize,
+    /// Times the buffer exceeded `max_buffered` and was discarded
+    overflows: u64,
+}
+
+impl Default for FixFramer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixFramer {
+    /// Create an empty framer discarding more than [`DEFAULT_MAX_BUFFERED`]
+    /// bytes without a complete message
+    pub fn new() -> Self {
+        Self::with_max_buffered(DEFAULT_MAX_BUFFERED)
+    }
+
+    /// Create an empty framer discarding more than `max_buffered` bytes
+    /// without a complete message
+    pub fn with_max_buffered(max_buffered: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(8192),
+            max_buffered,
+            high_water: 0,
+            overflows: 0,
+        }
+    }
+
+    /// Append bytes read from the stream
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+        self.high_water = self.high_water.max(self.buffer.len());
+    }
+
+    /// Bytes buffered but not yet framed
+    pub fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Discard all buffered bytes
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Most bytes buffered at once
+    pub fn high_water(&self) -> usize {
+        self.high_water
+    }
+
+    /// Times the buffered bytes exceeded the limit and were discarded
+    pub fn overflows(&self) -> u64 {
+        self.overflows
+    }
+
+    /// Take the next complete message from the buffer
+    ///
+    /// Returns `Ok(None)` when more bytes are needed. On a framing error the
+    /// offending bytes are dropped so that the next call resynchronises on the
+    /// following BeginString; more buffered bytes than the limit without a
+    /// complete message are all dropped.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        if !self.skip_to_message_start()? {
+            return self.check_overflow();
+        }
+
+        match self.frame_length() {
+            Ok(Some(length)) => Ok(Some(self.buffer.drain(..length).collect())),
+            Ok(None) => self.check_overflow(),
+            Err(e) => {
+                // Drop the BeginString so the next call looks for a new message start
+                self.buffer.drain(..1);
+                Err(e)
+            }
+        }
+    }
+
+    /// Discard the incomplete bytes buffered once they exceed the limit
+    fn check_overflow(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.buffer.len() <= self.max_buffered {
+            return Ok(None);
+        }
+        let discarded = self.buffer.len();
+        self.buffer.clear();
+        self.overflows += 1;
+        Err(framing_error(&format!(
+            "{discarded} bytes buffered without a complete message exceed the limit of {}",
+            self.max_buffered
+        )))
+    }
+
+    /// Discard bytes in front of the next BeginString
+    ///
+    /// Returns whether the buffer now starts with a BeginString. Complete
+    /// fields that do not belong to any message are reported as an error.
+    fn skip_to_message_start(&mut self) -> Result<bool> {
+        if let Some(start) = find_message_start(&self.buffer) {
+            self.buffer.drain(..start);
+            return Ok(true);
+        }
+
+        // Keep a trailing partial field, it may be the beginning of a message
+        let Some(last_soh) = self.buffer.iter().rposition(|&byte| byte == SOH) else {
+            return Ok(false);
+        };
+        let garbage: Vec<u8> = self.buffer.drain(..=last_soh).collect();
+        Err(DeribitFixError::MessageParsing(format!(
+            "Failed to parse invalid message data: {}",
+            String::from_utf8_lossy(&garbage).trim_end_matches('\x01')
+        )))
+    }
+
+    /// Total length of the message at the start of the buffer, if complete
+    fn frame_length(&self) -> Result<Option<usize>> {
+        let buffer = &self.buffer;
+
+        // BeginString (8)
+        let Some(begin_end) = find_soh(buffer, 0, BEGIN_STRING.len() + MAX_BEGIN_STRING_LENGTH)?
+        else {
+            return Ok(None);
+        };
+
+        // BodyLength (9) must immediately follow BeginString
+        let length_start = begin_end + 1;
+        let available = &buffer[length_start..];
+        let prefix_length = available.len().min(BODY_LENGTH.len());
+        if available[..prefix_length] != BODY_LENGTH[..prefix_length] {
+            return Err(framing_error("BodyLength (9) must follow BeginString (8)"));
+        }
+        let digits_start = length_start + BODY_LENGTH.len();
+        let Some(length_end) = find_soh(buffer, digits_start, MAX_BODY_LENGTH_DIGITS)? else {
+            return Ok(None);
+        };
+        let digits = &buffer[digits_start..length_end];
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            return Err(framing_error(&format!(
+                "Invalid BodyLength (9): {}",
+                String::from_utf8_lossy(digits)
+            )));
+        }
+        let body_length: usize = std::str::from_utf8(digits)
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| framing_error("Invalid BodyLength (9)"))?;
+        if body_length > MAX_BODY_LENGTH {
+            return Err(framing_error(&format!(
+                "BodyLength (9) {body_length} exceeds maximum of {MAX_BODY_LENGTH}"
+            )));
+        }
+
+        // CheckSum (10) trailer exactly BodyLength bytes after the header
+        let trailer_start = length_end + 1 + body_length;
+        let total_length = trailer_start + TRAILER_LENGTH;
+        if buffer.len() < total_length {
+            return Ok(None);
+        }
+        let trailer = &buffer[trailer_start..total_length];
+        if !trailer.starts_with(b"10=")
+            || !trailer[3..6].iter().all(u8::is_ascii_digit)
+            || trailer[6] != SOH
+        {
+            return Err(framing_error(
+                "CheckSum (10) not found where BodyLength (9) ends",
+            ));
+        }
+        Ok(Some(total_length))
+    }
+}
+
+/// Position of the first BeginString that starts a field
+fn find_message_start(buffer: &[u8]) -> Option<usize> {
+    buffer
+        .windows(BEGIN_STRING.len())
+        .enumerate()
+        .find(|(position, window)| {
+            *window == BEGIN_STRING && (*position == 0 || buffer[position - 1] == SOH)
+        })
+        .map(|(position, _)| position)
+}
+
+/// Position of the SOH ending a field value starting at `start`
+///
+/// Returns `Ok(None)` when more bytes are needed and an error when the value
+/// is longer than `max_length`.
+fn find_soh(buffer: &[u8], start: usize, max_length: usize) -> Result<Option<usize>> {
+    let end = buffer.len().min(start + max_length + 1);
+    match buffer
+        .get(start..end)
+        .and_then(|value| value.iter().position(|&byte| byte == SOH))
+    {
+        Some(offset) => Ok(Some(start + offset)),
+        None if buffer.len() > start + max_length => {
+            Err(framing_error("Header field exceeds maximum length"))
+        }
+        None => Ok(None),
+    }
+}
+
+fn framing_error(reason: &str) -> DeribitFixError {
+    DeribitFixError::MessageParsing(format!("Failed to frame FIX message: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEARTBEAT: &[u8] = b"8=FIX.4.4\x019=5\x0135=0\x0110=123\x01";
+
+    #[test]
+    fn test_frames_complete_message() {
+        let mut framer = FixFramer::new();
+        framer.push(HEARTBEAT);
+        assert_eq!(framer.next_frame().unwrap().unwrap(), HEARTBEAT);
+        assert!(framer.next_frame().unwrap().is_none());
+        assert!(framer.buffered().is_empty());
+    }
+
+    #[test]
+    fn test_waits_for_partial_message() {
+        let mut framer = FixFramer::new();
+        for split in 1..HEARTBEAT.len() {
+            framer.clear();
+            framer.push(&HEARTBEAT[..split]);
+            assert!(framer.next_frame().unwrap().is_none(), "split at {split}");
+            framer.push(&HEARTBEAT[split..]);
+            assert_eq!(framer.next_frame().unwrap().unwrap(), HEARTBEAT);
+        }
+    }
+
+    #[test]
+    fn test_checksum_inside_value_does_not_end_message() {
+        let body = b"35=0\x0158=x\x0110=999 8=FIX.4.4\x01";
+        let mut message = format!("8=FIX.4.4\x019={}\x01", body.len()).into_bytes();
+        message.extend_from_slice(body);
+        message.extend_from_slice(b"10=042\x01");
+
+        let mut framer = FixFramer::new();
+        framer.push(&message);
+        assert_eq!(framer.next_frame().unwrap().unwrap(), message);
+    }
+
+    #[test]
+    fn test_wrong_body_length_resynchronises() {
+        let mut framer = FixFramer::new();
+        framer.push(b"8=FIX.4.4\x019=3\x0135=0\x0110=123\x01");
+        framer.push(HEARTBEAT);
+
+        assert!(framer.next_frame().is_err());
+        assert_eq!(framer.next_frame().unwrap().unwrap(), HEARTBEAT);
+    }
+
+    #[test]
+    fn test_rejects_missing_or_oversized_body_length() {
+        let mut framer = FixFramer::new();
+        framer.push(b"8=FIX.4.4\x0135=0\x0110=123\x01");
+        assert!(framer.next_frame().is_err());
+
+        framer.clear();
+        framer.push(b"8=FIX.4.4\x019=9999999\x01");
+        assert!(framer.next_frame().is_err());
+    }
+
+    #[test]
+    fn test_discards_garbage_over_the_limit() {
+        let mut framer = FixFramer::with_max_buffered(64);
+        framer.push(&[b'x'; 48]);
+        assert!(framer.next_frame().unwrap().is_none());
+
+        framer.push(&[b'x'; 48]);
+        assert!(framer.next_frame().is_err());
+        assert!(framer.buffered().is_empty());
+        assert_eq!(framer.high_water(), 96);
+        assert_eq!(framer.overflows(), 1);
+
+        framer.push(HEARTBEAT);
+        assert_eq!(framer.next_frame().unwrap().unwrap(), HEARTBEAT);
+    }
+}
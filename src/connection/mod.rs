@@ -1,6 +1,18 @@
 //! Connection management module
 
-/// TCP/TLS connection implementation
+/// FIX message framing based on BodyLength
+pub mod framing;
+
+/// HTTP and SOCKS5 proxy tunnels
+pub mod proxy;
+
+/// Connection over a pluggable transport
 pub mod tcp_connection;
 
+/// Transports the connection runs over
+pub mod transport;
+
+pub use framing::*;
+pub use proxy::*;
 pub use tcp_connection::*;
+pub use transport::*;
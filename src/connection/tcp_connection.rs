@@ -1,109 +1,329 @@
 //! Connection management for Deribit FIX client
 
+use crate::connection::framing::FixFramer;
+use crate::connection::transport::{HandshakeTiming, TcpConnector, TransportConnector};
+use crate::message::FixPrettyPrinter;
+use crate::model::latency::LatencyStats;
 use crate::model::message::FixMessage;
 use crate::model::stream::Stream;
+use crate::model::tags;
+use crate::model::throttle::ThrottleStats;
+use crate::model::types::MsgType;
 use crate::{
     config::DeribitFixConfig,
     error::{DeribitFixError, Result},
 };
-use std::collections::VecDeque;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
 use std::str::FromStr;
-use tokio::{net::TcpStream, time::timeout};
-use tokio_native_tls::TlsConnector;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{time::Instant, time::timeout};
 use tracing::{debug, error, info, trace};
 
-/// TCP/TLS connection to Deribit FIX server
+/// Longest a read waits for data before returning
+const MAX_READ_WAIT: Duration = Duration::from_millis(1000);
+
+/// Counters of the messages written to the socket
+///
+/// With write batching enabled, `messages / writes` is the average number of
+/// messages coalesced into each write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct WriteStats {
+    /// Messages sent
+    pub messages: u64,
+    /// Writes issued to the socket
+    pub writes: u64,
+    /// Bytes written
+    pub bytes: u64,
+}
+
+impl WriteStats {
+    /// Average number of messages per write
+    pub fn messages_per_write(&self) -> f64 {
+        if self.writes == 0 {
+            0.0
+        } else {
+            self.messages as f64 / self.writes as f64
+        }
+    }
+}
+
+/// High-water marks and overflows of the bounded buffers and queues
+///
+/// Limits are set by
+/// [`max_read_buffer_bytes`](DeribitFixConfig::max_read_buffer_bytes),
+/// [`max_pending_commands`](DeribitFixConfig::max_pending_commands),
+/// [`max_market_data_backlog`](DeribitFixConfig::max_market_data_backlog) and
+/// [`max_stream_backlog`](DeribitFixConfig::max_stream_backlog).
+/// Market data, trade reports and instrument fragments past their limit are
+/// dropped oldest first, while order flow past its limit is refused with an
+/// error.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct BufferWatermarks {
+    /// Most bytes held in the read buffer waiting to be framed
+    pub read_buffer_high_water: usize,
+    /// Times the read buffer exceeded its limit and was discarded
+    pub read_buffer_overflows: u64,
+    /// Most client calls waiting for the session task at once
+    pub pending_commands_high_water: usize,
+    /// Client calls refused because the command queue was full
+    pub rejected_commands: u64,
+    /// Most updates a market data stream receiver fell behind by
+    pub market_data_backlog_high_water: usize,
+    /// Market data updates dropped because a receiver fell behind
+    pub dropped_market_data: u64,
+    /// Most Execution Reports a label receiver fell behind by
+    pub execution_backlog_high_water: usize,
+    /// Execution Reports lost because a label receiver fell behind
+    pub lost_execution_reports: u64,
+    /// Most trade reports or instrument fragments a stream receiver fell
+    /// behind by
+    pub stream_backlog_high_water: usize,
+    /// Trade reports and instrument fragments dropped because a receiver
+    /// fell behind
+    pub dropped_stream_messages: u64,
+}
+
+/// Traffic counters of a connection, for dashboards and alerting
+///
+/// Message counts are keyed by MsgType (35) value, so message types the
+/// crate does not model are counted too. Counters survive reconnects.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ConnectionStats {
+    /// Bytes read from the socket
+    pub bytes_in: u64,
+    /// Bytes written to the socket
+    pub bytes_out: u64,
+    /// Messages received by MsgType (35)
+    pub messages_in: BTreeMap<String, u64>,
+    /// Messages sent by MsgType (35)
+    pub messages_out: BTreeMap<String, u64>,
+    /// Time the last message was received
+    pub last_inbound: Option<DateTime<Utc>>,
+    /// Time the last message was sent
+    pub last_outbound: Option<DateTime<Utc>>,
+    /// Successful reconnects
+    pub reconnects: u64,
+    /// Last Test Request round trip measured by the session
+    pub round_trip: Option<Duration>,
+    /// Order-entry latency percentiles, when latency tracing is enabled
+    pub order_latency: Option<LatencyStats>,
+    /// High-water marks and overflows of the buffers and queues
+    pub buffers: BufferWatermarks,
+    /// Time the steps of the last connect or reconnect took, when the
+    /// transport measures them
+    pub handshake: Option<HandshakeTiming>,
+    /// Pacing of outgoing messages, filled in by the session
+    pub throttle: ThrottleStats,
+    /// Whether the server cancels the orders of the session when it
+    /// disconnects, filled in by the session while logged on
+    pub cancel_on_disconnect: Option<bool>,
+}
+
+impl ConnectionStats {
+    /// Messages of type `msg_type` received
+    pub fn received(&self, msg_type: MsgType) -> u64 {
+        self.messages_in
+            .get(msg_type.as_str())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Messages of type `msg_type` sent
+    pub fn sent(&self, msg_type: MsgType) -> u64 {
+        self.messages_out
+            .get(msg_type.as_str())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Messages received of every type
+    pub fn total_received(&self) -> u64 {
+        self.messages_in.values().sum()
+    }
+
+    /// Messages sent of every type
+    pub fn total_sent(&self) -> u64 {
+        self.messages_out.values().sum()
+    }
+
+    fn record(counts: &mut BTreeMap<String, u64>, message: &FixMessage) {
+        let msg_type = message
+            .get_field(tags::MSG_TYPE)
+            .cloned()
+            .unwrap_or_default();
+        *counts.entry(msg_type).or_default() += 1;
+    }
+}
+
+/// Connection to Deribit FIX server over a pluggable transport
 pub struct Connection {
     stream: Stream,
+    connector: Arc<dyn TransportConnector>,
     config: DeribitFixConfig,
-    buffer: Vec<u8>,
-    message_queue: VecDeque<FixMessage>,
+    framer: FixFramer,
+    /// Parsed messages with the time of the read that brought their first byte
+    message_queue: VecDeque<(FixMessage, Instant)>,
+    /// Time of the read that brought the first byte still buffered
+    buffered_since: Option<Instant>,
+    /// Read time of the last message returned
+    last_read_at: Option<Instant>,
+    /// Time of the last write to the socket
+    last_write_at: Option<Instant>,
     connected: bool,
+    printer: FixPrettyPrinter,
+    /// Messages waiting to be written when batching writes
+    write_buffer: Vec<u8>,
+    /// Time by which the pending batch must be written
+    flush_deadline: Option<Instant>,
+    /// Whether messages are held until the next flush
+    holding_writes: bool,
+    write_stats: WriteStats,
+    stats: ConnectionStats,
 }
 
 impl Connection {
-    /// Create a new connection to the Deribit FIX server
+    /// Create a new connection to the Deribit FIX server over TCP or TLS
     pub async fn new(config: &DeribitFixConfig) -> Result<Self> {
-        let stream = if config.use_ssl {
-            Self::connect_tls(config).await?
-        } else {
-            Self::connect_tcp(config).await?
-        };
+        Self::with_connector(config, Arc::new(TcpConnector::new())).await
+    }
+
+    /// Create a new connection opened through `connector`, which is also
+    /// used to reconnect
+    pub async fn with_connector(
+        config: &DeribitFixConfig,
+        connector: Arc<dyn TransportConnector>,
+    ) -> Result<Self> {
+        let stream = connector.connect(config).await?;
 
         Ok(Self {
             stream,
             config: config.clone(),
-            buffer: Vec::with_capacity(8192),
+            framer: FixFramer::with_max_buffered(config.max_read_buffer_bytes),
             message_queue: VecDeque::new(),
+            buffered_since: None,
+            last_read_at: None,
+            last_write_at: None,
             connected: true,
+            printer: FixPrettyPrinter::from_config(config),
+            write_buffer: Vec::new(),
+            flush_deadline: None,
+            holding_writes: false,
+            write_stats: WriteStats::default(),
+            stats: ConnectionStats {
+                handshake: connector.last_handshake(),
+                ..ConnectionStats::default()
+            },
+            connector,
         })
     }
 
-    /// Connect using raw TCP
-    async fn connect_tcp(config: &DeribitFixConfig) -> Result<Stream> {
-        info!("Connecting to {}:{} via TCP", config.host, config.port);
+    /// Send a FIX message
+    ///
+    /// When [`write_batch_delay`](DeribitFixConfig::write_batch_delay) is set,
+    /// application messages are buffered and written together once the delay
+    /// has passed, [`write_batch_max_bytes`](DeribitFixConfig::write_batch_max_bytes)
+    /// are pending or [`flush`](Self::flush) is called. Session-level messages
+    /// flush the batch straight away. After [`hold_writes`](Self::hold_writes)
+    /// every message waits for the flush.
+    pub async fn send_message(&mut self, message: &FixMessage) -> Result<()> {
+        if !self.connected {
+            return Err(DeribitFixError::Connection(
+                "Connection is not active".to_string(),
+            ));
+        }
 
-        let addr = format!("{}:{}", config.host, config.port);
-        let stream = timeout(config.connection_timeout, TcpStream::connect(&addr))
-            .await
-            .map_err(|_| DeribitFixError::Timeout(format!("Connection timeout to {addr}")))?
-            .map_err(|e| {
-                DeribitFixError::Connection(format!("Failed to connect to {addr}: {e}"))
-            })?;
+        let message_str = message.to_string();
+        debug!("Sending FIX message: {}", self.printer.render(message));
 
-        info!("Successfully connected via TCP");
-        Ok(Stream::Tcp(stream))
-    }
+        self.write_buffer.extend_from_slice(message_str.as_bytes());
+        self.write_stats.messages += 1;
+        ConnectionStats::record(&mut self.stats.messages_out, message);
 
-    /// Connect using TLS
-    async fn connect_tls(config: &DeribitFixConfig) -> Result<Stream> {
-        info!("Connecting to {}:{} via TLS", config.host, config.port);
-
-        let addr = format!("{}:{}", config.host, config.port);
-        let tcp_stream = timeout(config.connection_timeout, TcpStream::connect(&addr))
-            .await
-            .map_err(|_| DeribitFixError::Timeout(format!("Connection timeout to {addr}")))?
-            .map_err(|e| {
-                DeribitFixError::Connection(format!("Failed to connect to {addr}: {e}"))
-            })?;
-
-        let connector = TlsConnector::from(
-            native_tls::TlsConnector::builder()
-                .build()
-                .map_err(|e| DeribitFixError::Connection(format!("TLS setup failed: {e}")))?,
+        if self.holding_writes {
+            if self.write_buffer.len() >= self.config.write_batch_max_bytes {
+                return self.write_pending().await;
+            }
+            return Ok(());
+        }
+        let Some(delay) = self.config.write_batch_delay else {
+            return self.flush().await;
+        };
+        let deadline = *self
+            .flush_deadline
+            .get_or_insert_with(|| Instant::now() + delay);
+        let session_level = matches!(
+            message.msg_type(),
+            Some(
+                MsgType::Logon
+                    | MsgType::Logout
+                    | MsgType::Heartbeat
+                    | MsgType::TestRequest
+                    | MsgType::ResendRequest
+                    | MsgType::SequenceReset
+                    | MsgType::Reject
+            )
         );
+        if session_level
+            || self.write_buffer.len() >= self.config.write_batch_max_bytes
+            || Instant::now() >= deadline
+        {
+            self.flush().await?;
+        }
+        Ok(())
+    }
 
-        let tls_stream = connector
-            .connect(&config.host, tcp_stream)
-            .await
-            .map_err(|e| DeribitFixError::Connection(format!("TLS handshake failed: {e}")))?;
+    /// Buffer the messages sent until the next [`flush`](Self::flush), so
+    /// that they are written to the socket together
+    ///
+    /// Only [`write_batch_max_bytes`](DeribitFixConfig::write_batch_max_bytes)
+    /// of pending messages write the batch earlier.
+    pub fn hold_writes(&mut self) {
+        self.holding_writes = true;
+    }
 
-        info!("Successfully connected via TLS");
-        Ok(Stream::Tls(tls_stream))
+    /// Write all buffered messages to the socket
+    pub async fn flush(&mut self) -> Result<()> {
+        self.holding_writes = false;
+        self.write_pending().await
     }
 
-    /// Send a FIX message
-    pub async fn send_message(&mut self, message: &FixMessage) -> Result<()> {
+    /// Write the buffered messages, keeping any hold on the following ones
+    async fn write_pending(&mut self) -> Result<()> {
+        self.flush_deadline = None;
+        if self.write_buffer.is_empty() {
+            return Ok(());
+        }
         if !self.connected {
             return Err(DeribitFixError::Connection(
                 "Connection is not active".to_string(),
             ));
         }
 
-        let message_str = message.to_string();
-        debug!("Sending FIX message: {}", message_str);
-
-        match self.stream.write_all(message_str.as_bytes()).await {
-            Ok(_) => {}
-            Err(e) => {
-                error!("Failed to send message: {}", e);
-                self.connected = false;
-                return Err(DeribitFixError::Io(e));
-            }
+        let buffer = std::mem::take(&mut self.write_buffer);
+        if let Err(e) = self.stream.write_all(&buffer).await {
+            error!("Failed to send message: {}", e);
+            self.connected = false;
+            return Err(DeribitFixError::Io(e));
         }
 
         match self.stream.flush().await {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                self.write_stats.writes += 1;
+                self.write_stats.bytes += buffer.len() as u64;
+                self.stats.bytes_out += buffer.len() as u64;
+                self.stats.last_outbound = Some(Utc::now());
+                self.last_write_at = Some(Instant::now());
+                // Reuse the allocation for the next batch
+                self.write_buffer = buffer;
+                self.write_buffer.clear();
+                Ok(())
+            }
             Err(e) => {
                 error!("Failed to flush stream: {}", e);
                 self.connected = false;
@@ -112,8 +332,79 @@ impl Connection {
         }
     }
 
+    /// Write the pending batch if its delay has passed
+    pub async fn flush_if_due(&mut self) -> Result<()> {
+        if self
+            .flush_deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Counters of the messages written so far
+    pub fn write_stats(&self) -> WriteStats {
+        self.write_stats
+    }
+
+    /// Whether messages are batched and not written to the socket yet
+    pub fn has_pending_writes(&self) -> bool {
+        !self.write_buffer.is_empty()
+    }
+
+    /// Time of the last write to the socket
+    pub fn last_write_at(&self) -> Option<Instant> {
+        self.last_write_at
+    }
+
+    /// Time of the read that brought the first byte of the last message
+    /// returned by [`receive_message`](Self::receive_message)
+    pub fn last_read_at(&self) -> Option<Instant> {
+        self.last_read_at
+    }
+
+    /// Traffic counters of the connection; the round trip, order latency
+    /// and queue watermarks other than the read buffer's are left to the
+    /// session and client
+    pub fn connection_stats(&self) -> ConnectionStats {
+        let mut stats = self.stats.clone();
+        stats.buffers.read_buffer_high_water = self.framer.high_water();
+        stats.buffers.read_buffer_overflows = self.framer.overflows();
+        stats
+    }
+
     /// Receive a FIX message from the server
     pub async fn receive_message(&mut self) -> Result<Option<FixMessage>> {
+        self.receive_message_within(MAX_READ_WAIT).await
+    }
+
+    /// Receive a FIX message from the server, waiting at most `max_wait` for data
+    pub async fn receive_message_within(
+        &mut self,
+        max_wait: Duration,
+    ) -> Result<Option<FixMessage>> {
+        if !self.connected {
+            return Err(DeribitFixError::Connection(
+                "Not connected to server".to_string(),
+            ));
+        }
+
+        self.flush_if_due().await?;
+        let message = self.read_message_within(max_wait).await?;
+        if message.is_none() && self.connected {
+            self.flush_if_due().await?;
+        }
+        Ok(message)
+    }
+
+    /// Read a FIX message from the server without writing a pending batch,
+    /// waiting at most `max_wait` for data
+    ///
+    /// Cancel safe: when the returned future is dropped before it completes,
+    /// no received data is lost. The wait ends in time to write a batch,
+    /// which is left to [`flush_if_due`](Self::flush_if_due).
+    pub async fn read_message_within(&mut self, max_wait: Duration) -> Result<Option<FixMessage>> {
         if !self.connected {
             return Err(DeribitFixError::Connection(
                 "Not connected to server".to_string(),
@@ -121,26 +412,36 @@ impl Connection {
         }
 
         // Check if we have queued messages first
-        if let Some(message) = self.message_queue.pop_front() {
+        if let Some(message) = self.next_queued() {
             return Ok(Some(message));
         }
 
         // Try to parse any existing buffered data first
-        self.parse_all_messages_from_buffer()?;
-        if let Some(message) = self.message_queue.pop_front() {
+        self.parse_all_messages_from_buffer(Instant::now())?;
+        if let Some(message) = self.next_queued() {
             return Ok(Some(message));
         }
 
         // Read data from the stream with timeout
         let mut temp_buffer = vec![0u8; 4096];
 
-        // Use a timeout to avoid blocking indefinitely
-        match tokio::time::timeout(
-            std::time::Duration::from_millis(1000), // Increased to 1 second
-            self.stream.read(&mut temp_buffer),
-        )
-        .await
-        {
+        // Spin on the socket first when busy polling, then wait for data
+        let read = match self.busy_poll_read(&mut temp_buffer).await {
+            Some(read) => Ok(read),
+            None => {
+                // Use a timeout to avoid blocking indefinitely, waking up in
+                // time to write a pending batch
+                let max_wait = max_wait.min(MAX_READ_WAIT);
+                let wait = self.flush_deadline.map_or(max_wait, |deadline| {
+                    deadline
+                        .saturating_duration_since(Instant::now())
+                        .min(max_wait)
+                });
+                tokio::time::timeout(wait, self.stream.read(&mut temp_buffer)).await
+            }
+        };
+
+        match read {
             Ok(Ok(0)) => {
                 // Connection closed
                 debug!("Connection closed by server");
@@ -149,14 +450,20 @@ impl Connection {
             }
             Ok(Ok(n)) => {
                 trace!("Received {} bytes from server", n);
-                trace!("Raw bytes: {:?}", &temp_buffer[..n]);
-                self.buffer.extend_from_slice(&temp_buffer[..n]);
+                self.stats.bytes_in += n as u64;
+                trace!(
+                    "Raw bytes: {}",
+                    self.printer
+                        .render_raw(&String::from_utf8_lossy(&temp_buffer[..n]))
+                );
+                let read_at = Instant::now();
+                self.framer.push(&temp_buffer[..n]);
 
                 // Parse all complete messages from buffer and queue them
-                self.parse_all_messages_from_buffer()?;
+                self.parse_all_messages_from_buffer(read_at)?;
 
                 // Return the first message from queue
-                Ok(self.message_queue.pop_front())
+                Ok(self.next_queued())
             }
             Ok(Err(e)) => {
                 if e.kind() == std::io::ErrorKind::WouldBlock {
@@ -175,163 +482,71 @@ impl Connection {
         }
     }
 
+    /// Poll the stream without waiting until data arrives or the busy-poll budget runs out
+    ///
+    /// Returns `None` when busy polling is disabled or no data arrived in time.
+    async fn busy_poll_read(&mut self, buffer: &mut [u8]) -> Option<std::io::Result<usize>> {
+        let deadline = Instant::now() + self.config.busy_poll?;
+        loop {
+            // A zero timeout polls the read once; reads are cancel safe
+            if let Ok(read) = timeout(Duration::ZERO, self.stream.read(buffer)).await {
+                return Some(read);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
     /// Parse all complete messages from buffer and add to queue
-    fn parse_all_messages_from_buffer(&mut self) -> Result<()> {
+    ///
+    /// The first message may have started in an earlier read; every later
+    /// one started in the read at `read_at`, as complete messages are parsed
+    /// after each read.
+    fn parse_all_messages_from_buffer(&mut self, read_at: Instant) -> Result<()> {
+        let mut started = self.buffered_since.unwrap_or(read_at);
         while let Some(message) = self.try_parse_message()? {
-            self.message_queue.push_back(message);
+            ConnectionStats::record(&mut self.stats.messages_in, &message);
+            self.stats.last_inbound = Some(Utc::now());
+            self.message_queue.push_back((message, started));
+            started = read_at;
         }
+        self.buffered_since = (!self.framer.buffered().is_empty()).then_some(started);
         Ok(())
     }
 
+    /// Pop the next parsed message, remembering when it was read
+    fn next_queued(&mut self) -> Option<FixMessage> {
+        let (message, read_at) = self.message_queue.pop_front()?;
+        self.last_read_at = Some(read_at);
+        Some(message)
+    }
+
     /// Try to parse a complete FIX message from the buffer
     fn try_parse_message(&mut self) -> Result<Option<FixMessage>> {
-        if !self.buffer.is_empty() {
+        if !self.framer.buffered().is_empty() {
             trace!(
-                "Buffer contains {} bytes: {:?}",
-                self.buffer.len(),
-                String::from_utf8_lossy(&self.buffer)
+                "Buffer contains {} bytes: {}",
+                self.framer.buffered().len(),
+                self.printer
+                    .render_raw(&String::from_utf8_lossy(self.framer.buffered()))
             );
         }
 
-        // Look for SOH (Start of Header) character which delimits FIX fields
-        const SOH: u8 = 0x01;
-
-        // Find the beginning of a FIX message (looking for BeginString field)
-        let buffer_str = String::from_utf8_lossy(&self.buffer);
-
-        // Look for the start of a FIX message with BeginString (8=FIX.4.4)
-        if let Some(msg_start) = buffer_str.find("8=FIX.4.4") {
-            // For FIX messages, we need to check the BodyLength (tag 9) to know the complete message size
-            let message_from_start = &buffer_str[msg_start..];
-
-            // Parse the BodyLength to determine the complete message size
-            if let Some(body_length_start) = message_from_start.find("9=")
-                && let Some(body_length_end) =
-                    message_from_start[body_length_start + 2..].find(char::from(SOH))
-            {
-                let body_length_str = &message_from_start
-                    [body_length_start + 2..body_length_start + 2 + body_length_end];
-                if let Ok(body_length) = body_length_str.parse::<usize>() {
-                    // Calculate the total message length:
-                    // "8=FIX.4.4\x01" + body_length + checksum field
-                    let header_length = body_length_start + 2 + body_length_end + 1; // Up to and including SOH after BodyLength
-                    let expected_total_length = msg_start + header_length + body_length;
-
-                    // Check if we have the complete message
-                    if self.buffer.len() >= expected_total_length {
-                        let message_bytes = self
-                            .buffer
-                            .drain(msg_start..expected_total_length)
-                            .collect::<Vec<u8>>();
-                        let message_str = String::from_utf8_lossy(&message_bytes);
-
-                        debug!(
-                            "Received complete FIX message ({} bytes): {}",
-                            message_bytes.len(),
-                            message_str
-                        );
-
-                        // Parse the message
-                        match FixMessage::from_str(&message_str) {
-                            Ok(message) => return Ok(Some(message)),
-                            Err(e) => {
-                                return Err(DeribitFixError::MessageParsing(format!(
-                                    "Failed to parse FIX message: {e}"
-                                )));
-                            }
-                        }
-                    } else {
-                        debug!(
-                            "Incomplete message: have {} bytes, need {}",
-                            self.buffer.len(),
-                            expected_total_length
-                        );
-                        return Ok(None);
-                    }
-                }
-            }
-
-            // Fallback to old checksum-based parsing if BodyLength parsing fails
-            if let Some(checksum_pos) = message_from_start.find("10=") {
-                debug!(
-                    "Found checksum field at position {}",
-                    msg_start + checksum_pos
-                );
+        let Some(message_bytes) = self.framer.next_frame()? else {
+            return Ok(None);
+        };
+        let message_str = String::from_utf8_lossy(&message_bytes);
+        debug!(
+            "Received complete FIX message ({} bytes): {}",
+            message_bytes.len(),
+            self.printer.render_raw(&message_str)
+        );
 
-                // Find the SOH after the checksum (should be 3 digits + SOH)
-                let checksum_section = &message_from_start[checksum_pos..];
-                if let Some(end_pos) = checksum_section.find(char::from(SOH)) {
-                    // Make sure we have the full 3-digit checksum
-                    if end_pos >= 4 {
-                        // "10=" + 3 digits = 7 chars minimum, but we'll be more lenient
-                        let message_end = msg_start + checksum_pos + end_pos + 1;
-                        let message_bytes = self
-                            .buffer
-                            .drain(msg_start..message_end)
-                            .collect::<Vec<u8>>();
-                        let message_str = String::from_utf8_lossy(&message_bytes);
-
-                        debug!("Received FIX message (fallback): {}", message_str);
-
-                        // Parse the message
-                        match FixMessage::from_str(&message_str) {
-                            Ok(message) => Ok(Some(message)),
-                            Err(e) => Err(DeribitFixError::MessageParsing(format!(
-                                "Failed to parse FIX message: {e}"
-                            ))),
-                        }
-                    } else {
-                        // Incomplete checksum
-                        Ok(None)
-                    }
-                } else {
-                    // Incomplete message - checksum field found but no terminating SOH
-                    Ok(None)
-                }
-            } else {
-                // No complete message yet - found start but no checksum
-                Ok(None)
-            }
-        } else {
-            // No message start found yet - might be just leftover data or waiting for more
-            // Clear any non-message data from the beginning of buffer
-            if !buffer_str.is_empty() && !buffer_str.starts_with("8=FIX") {
-                // Find if there's a message start somewhere in the buffer
-                if let Some(start_pos) = buffer_str.find("8=FIX") {
-                    // Remove garbage data before the message start
-                    debug!(
-                        "Removing {} bytes of garbage data before message start",
-                        start_pos
-                    );
-                    self.buffer.drain(..start_pos);
-                } else {
-                    // No message start found, could be fragment - keep if small, discard if too large
-                    if self.buffer.len() > 1000 {
-                        debug!(
-                            "Clearing large buffer ({} bytes) with no message start",
-                            self.buffer.len()
-                        );
-                        self.buffer.clear();
-                    } else if self.buffer.len() > 10 && !buffer_str.trim().is_empty() {
-                        // Check if this looks like invalid data (not starting with FIX fields)
-                        let trimmed = buffer_str.trim();
-                        // Valid FIX fragments should contain field numbers like "10=", "35=", etc.
-                        // or be very short (under certain threshold)
-                        if !trimmed.contains('=')
-                            || (!trimmed.starts_with(char::is_numeric) && self.buffer.len() > 20)
-                        {
-                            // This looks like invalid data, not a FIX message fragment
-                            return Err(DeribitFixError::MessageParsing(format!(
-                                "Failed to parse invalid message data: {}",
-                                trimmed
-                            )));
-                        }
-                    }
-                    // Keep smaller fragments or those that look like valid FIX field fragments
-                }
-            }
-            Ok(None)
-        }
+        FixMessage::from_str(&message_str).map(Some).map_err(|e| {
+            DeribitFixError::MessageParsing(format!("Failed to parse FIX message: {e}"))
+        })
     }
 
     /// Check if the connection is active
@@ -341,6 +556,9 @@ impl Connection {
 
     /// Close the connection
     pub async fn close(&mut self) -> Result<()> {
+        if self.connected {
+            let _ = self.flush().await;
+        }
         self.connected = false;
         self.stream.shutdown().await.map_err(DeribitFixError::Io)?;
         info!("Connection closed");
@@ -355,16 +573,17 @@ impl Connection {
         let _ = self.close().await;
 
         // Create new connection
-        let stream = if self.config.use_ssl {
-            Self::connect_tls(&self.config).await?
-        } else {
-            Self::connect_tcp(&self.config).await?
-        };
-
-        self.stream = stream;
-        self.buffer.clear();
+        self.stream = self.connector.connect(&self.config).await?;
+        self.framer.clear();
         self.message_queue.clear();
+        self.buffered_since = None;
+        // Messages batched for the old connection are not resent on the new one
+        self.write_buffer.clear();
+        self.flush_deadline = None;
+        self.holding_writes = false;
         self.connected = true;
+        self.stats.reconnects += 1;
+        self.stats.handshake = self.connector.last_handshake();
 
         info!("Successfully reconnected");
         Ok(())
@@ -0,0 +1,388 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Pluggable transports
+//!
+//! A [`Connection`](crate::connection::Connection) opens its
+//! [`Stream`](crate::model::stream::Stream) through a [`TransportConnector`],
+//! both when created and on every reconnect. [`TcpConnector`] is the default, connecting over TCP or TLS,
+//! optionally through a [`ProxyConfig`]; [`MemoryConnector`] hands out
+//! in-memory pipes for tests. Other transports, such as FIX over WebSocket,
+//! plug in by implementing [`TransportConnector`].
+//!
+//! To keep reconnects short during a failover, a [`TcpConnector`] reuses the
+//! server addresses it resolved for up to
+//! [`dns_cache_ttl`](crate::config::DeribitFixConfig::dns_cache_ttl), falling back to a
+//! fresh lookup when none of them accepts the connection, and builds its
+//! TLS connector, with the system root certificates, only once. The TLS
+//! library decides whether a reconnect resumes the previous TLS session;
+//! native-tls offers no control over session tickets. The time each step of
+//! the last connect took is reported as a [`HandshakeTiming`] in the
+//! connection stats.
+
+use crate::config::DeribitFixConfig;
+use crate::connection::proxy::ProxyConfig;
+use crate::error::{DeribitFixError, Result};
+use crate::model::stream::Stream;
+use serde::{Deserialize, Serialize};
+use socket2::{SockRef, TcpKeepalive};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::io::DuplexStream;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_native_tls::TlsConnector;
+use tracing::{debug, info};
+
+/// Future returned by [`TransportConnector::connect`]
+pub type ConnectFuture<'a> = Pin<Box<dyn Future<Output = Result<Stream>> + Send + 'a>>;
+
+/// Opens streams to the FIX server
+pub trait TransportConnector: Send + Sync {
+    /// Open a stream to the server of `config`
+    fn connect<'a>(&'a self, config: &'a DeribitFixConfig) -> ConnectFuture<'a>;
+
+    /// Time the steps of the last successful connect took, when the
+    /// transport measures them
+    fn last_handshake(&self) -> Option<HandshakeTiming> {
+        None
+    }
+}
+
+/// Time the steps of opening a connection took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandshakeTiming {
+    /// Resolving the server address, zero when cached addresses were used
+    pub resolve: Duration,
+    /// Whether the server address came from the cache
+    pub cached_address: bool,
+    /// Opening the TCP connection, through the proxy when one is configured
+    pub tcp_connect: Duration,
+    /// TLS handshake, `None` over plain TCP
+    pub tls_handshake: Option<Duration>,
+    /// Whether the TLS connector of an earlier connect was reused
+    pub reused_tls_connector: bool,
+}
+
+impl HandshakeTiming {
+    /// Time the whole connect took
+    pub fn total(&self) -> Duration {
+        self.resolve + self.tcp_connect + self.tls_handshake.unwrap_or_default()
+    }
+}
+
+/// Addresses and TLS connector kept by a [`TcpConnector`] between connects
+#[derive(Debug, Default)]
+struct ConnectorCache {
+    addresses: HashMap<(String, u16), (Vec<SocketAddr>, Instant)>,
+    tls: Option<native_tls::TlsConnector>,
+    last_handshake: Option<HandshakeTiming>,
+}
+
+/// TCP transport, with TLS when [`use_ssl`](DeribitFixConfig::use_ssl) is set
+/// and tunnelled through [`proxy`](DeribitFixConfig::proxy) when one is configured
+///
+/// Clones share the cached addresses and TLS connector.
+#[derive(Debug, Clone, Default)]
+pub struct TcpConnector {
+    cache: Arc<Mutex<ConnectorCache>>,
+}
+
+impl TransportConnector for TcpConnector {
+    fn connect<'a>(&'a self, config: &'a DeribitFixConfig) -> ConnectFuture<'a> {
+        Box::pin(async move {
+            let mut timing = HandshakeTiming::default();
+            let tcp_stream = self.connect_tcp(config, &mut timing).await?;
+            if !config.use_ssl {
+                info!("Successfully connected via TCP");
+                self.cache().last_handshake = Some(timing);
+                return Ok(Stream::new(tcp_stream));
+            }
+
+            let (connector, reused) = self.tls_connector()?;
+            timing.reused_tls_connector = reused;
+            let started = Instant::now();
+            let tls_stream = timeout(
+                config.connection_timeout,
+                TlsConnector::from(connector).connect(&config.host, tcp_stream),
+            )
+            .await
+            .map_err(|_| {
+                DeribitFixError::Timeout(format!("TLS handshake timeout with {}", config.host))
+            })?
+            .map_err(|e| DeribitFixError::Connection(format!("TLS handshake failed: {e}")))?;
+            timing.tls_handshake = Some(started.elapsed());
+
+            info!("Successfully connected via TLS in {:?}", timing.total());
+            self.cache().last_handshake = Some(timing);
+            Ok(Stream::new(tls_stream))
+        })
+    }
+
+    fn last_handshake(&self) -> Option<HandshakeTiming> {
+        self.cache().last_handshake
+    }
+}
+
+impl TcpConnector {
+    /// Create a connector with nothing cached
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the cached server addresses, so the next connect resolves them
+    pub fn clear_address_cache(&self) {
+        self.cache().addresses.clear();
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, ConnectorCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// TLS connector of an earlier connect, or a new one, and whether it was
+    /// reused
+    fn tls_connector(&self) -> Result<(native_tls::TlsConnector, bool)> {
+        let mut cache = self.cache();
+        if let Some(connector) = &cache.tls {
+            return Ok((connector.clone(), true));
+        }
+        let connector = native_tls::TlsConnector::builder()
+            .build()
+            .map_err(|e| DeribitFixError::Connection(format!("TLS setup failed: {e}")))?;
+        cache.tls = Some(connector.clone());
+        Ok((connector, false))
+    }
+
+    /// Addresses of the server of `config`, from the cache unless `refresh`
+    /// is set or they are older than the cache TTL, and whether they were
+    /// cached
+    async fn resolve(
+        &self,
+        config: &DeribitFixConfig,
+        refresh: bool,
+    ) -> Result<(Vec<SocketAddr>, bool)> {
+        let key = (config.host.clone(), config.port);
+        if !refresh
+            && let Some((addresses, resolved_at)) = self.cache().addresses.get(&key)
+            && resolved_at.elapsed() < config.dns_cache_ttl
+        {
+            return Ok((addresses.clone(), true));
+        }
+        let addr = config.connection_url();
+        let addresses: Vec<SocketAddr> = tokio::net::lookup_host(&addr)
+            .await
+            .map_err(|e| DeribitFixError::Connection(format!("Failed to connect to {addr}: {e}")))?
+            .collect();
+        if !config.dns_cache_ttl.is_zero() {
+            self.cache()
+                .addresses
+                .insert(key, (addresses.clone(), Instant::now()));
+        }
+        Ok((addresses, false))
+    }
+
+    /// Open the TCP connection to the server, or to the proxy and through it
+    async fn connect_tcp(
+        &self,
+        config: &DeribitFixConfig,
+        timing: &mut HandshakeTiming,
+    ) -> Result<TcpStream> {
+        info!(
+            "Connecting to {}:{} via {}",
+            config.host,
+            config.port,
+            if config.use_ssl { "TLS" } else { "TCP" }
+        );
+
+        let addr = config.connection_url();
+        let connect = async {
+            let Some(proxy) = &config.proxy else {
+                return self.connect_direct(config, timing).await;
+            };
+            let started = Instant::now();
+            let stream = Self::connect_proxy(proxy, config).await;
+            timing.tcp_connect = started.elapsed();
+            stream
+        };
+        let stream = timeout(config.connection_timeout, connect)
+            .await
+            .map_err(|_| DeribitFixError::Timeout(format!("Connection timeout to {addr}")))??;
+        Self::configure_socket(&stream, config)?;
+        Ok(stream)
+    }
+
+    /// Connect to the server without a proxy, resolving its address again
+    /// when none of the cached addresses accepts the connection
+    async fn connect_direct(
+        &self,
+        config: &DeribitFixConfig,
+        timing: &mut HandshakeTiming,
+    ) -> Result<TcpStream> {
+        let addr = config.connection_url();
+        let started = Instant::now();
+        let (addresses, cached) = self.resolve(config, false).await?;
+        timing.resolve = started.elapsed();
+        timing.cached_address = cached;
+
+        let started = Instant::now();
+        let connected = TcpStream::connect(addresses.as_slice()).await;
+        let stream = match connected {
+            Ok(stream) => stream,
+            Err(e) if cached => {
+                debug!(
+                    "Cached addresses of {} failed ({}), resolving again",
+                    addr, e
+                );
+                let resolving = Instant::now();
+                let (addresses, _) = self.resolve(config, true).await?;
+                let resolved = resolving.elapsed();
+                timing.resolve += resolved;
+                timing.cached_address = false;
+                let stream = TcpStream::connect(addresses.as_slice())
+                    .await
+                    .map_err(|e| {
+                        DeribitFixError::Connection(format!("Failed to connect to {addr}: {e}"))
+                    })?;
+                timing.tcp_connect = started.elapsed().saturating_sub(resolved);
+                return Ok(stream);
+            }
+            Err(e) => {
+                return Err(DeribitFixError::Connection(format!(
+                    "Failed to connect to {addr}: {e}"
+                )));
+            }
+        };
+        timing.tcp_connect = started.elapsed();
+        Ok(stream)
+    }
+
+    /// Open a tunnel to the server through `proxy`
+    async fn connect_proxy(proxy: &ProxyConfig, config: &DeribitFixConfig) -> Result<TcpStream> {
+        info!("Tunnelling through proxy {:?}", proxy);
+
+        let proxy_addr = proxy.address();
+        let mut stream = TcpStream::connect(&proxy_addr).await.map_err(|e| {
+            DeribitFixError::Connection(format!("Failed to connect to proxy {proxy_addr}: {e}"))
+        })?;
+        proxy
+            .open_tunnel(&mut stream, &config.host, config.port)
+            .await?;
+        Ok(stream)
+    }
+
+    /// Apply the configured socket options to a connected stream
+    fn configure_socket(stream: &TcpStream, config: &DeribitFixConfig) -> Result<()> {
+        let socket = SockRef::from(stream);
+        let failed = |option: &str, e: std::io::Error| {
+            DeribitFixError::Connection(format!("Failed to set {option}: {e}"))
+        };
+
+        socket
+            .set_tcp_nodelay(config.tcp_nodelay)
+            .map_err(|e| failed("TCP_NODELAY", e))?;
+
+        if let Some(time) = config.tcp_keepalive {
+            let keepalive = TcpKeepalive::new().with_time(time);
+            #[cfg(any(
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "ios",
+                target_os = "linux",
+                target_os = "macos",
+                target_os = "netbsd",
+                target_os = "windows"
+            ))]
+            let keepalive = match config.tcp_keepalive_interval {
+                Some(interval) => keepalive.with_interval(interval),
+                None => keepalive,
+            };
+            socket
+                .set_tcp_keepalive(&keepalive)
+                .map_err(|e| failed("SO_KEEPALIVE", e))?;
+        }
+
+        if let Some(size) = config.send_buffer_size {
+            socket
+                .set_send_buffer_size(size)
+                .map_err(|e| failed("SO_SNDBUF", e))?;
+        }
+
+        if let Some(size) = config.recv_buffer_size {
+            socket
+                .set_recv_buffer_size(size)
+                .map_err(|e| failed("SO_RCVBUF", e))?;
+        }
+
+        debug!(
+            "Socket options: nodelay={}, keepalive={:?}, send_buffer={:?}, recv_buffer={:?}",
+            config.tcp_nodelay,
+            config.tcp_keepalive,
+            socket.send_buffer_size().ok(),
+            socket.recv_buffer_size().ok()
+        );
+        Ok(())
+    }
+}
+
+/// In-memory transport
+///
+/// Every connect creates a pipe and hands its server end to the paired
+/// [`MemoryListener`], so a session can be driven without a socket.
+#[derive(Debug, Clone)]
+pub struct MemoryConnector {
+    sender: mpsc::UnboundedSender<DuplexStream>,
+    buffer_size: usize,
+}
+
+/// Server side of a [`MemoryConnector`]
+#[derive(Debug)]
+pub struct MemoryListener {
+    receiver: mpsc::UnboundedReceiver<DuplexStream>,
+}
+
+impl MemoryConnector {
+    /// Create a connector and the listener receiving its connections
+    pub fn new() -> (Self, MemoryListener) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (
+            Self {
+                sender,
+                buffer_size: 64 * 1024,
+            },
+            MemoryListener { receiver },
+        )
+    }
+
+    /// Set the bytes buffered in each direction before writes wait for reads
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+}
+
+impl TransportConnector for MemoryConnector {
+    fn connect<'a>(&'a self, _config: &'a DeribitFixConfig) -> ConnectFuture<'a> {
+        Box::pin(async move {
+            let (client, server) = tokio::io::duplex(self.buffer_size);
+            self.sender.send(server).map_err(|_| {
+                DeribitFixError::Connection("In-memory listener is closed".to_string())
+            })?;
+            Ok(Stream::new(client))
+        })
+    }
+}
+
+impl MemoryListener {
+    /// Wait for the next connection; `None` once every connector is dropped
+    pub async fn accept(&mut self) -> Option<DuplexStream> {
+        self.receiver.recv().await
+    }
+}
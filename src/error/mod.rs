@@ -1,5 +1,13 @@
 //! Error types for the Deribit FIX framework
 
+use crate::config::ConfigReport;
+use crate::message::{
+    CxlRejReason, CxlRejResponseTo, MdReqRejReason, OrderRejectReason, QuoteRejectReason,
+    QuoteRequestRejectReason, TradeCaptureRequestResult,
+};
+use crate::model::market_state::InstrumentState;
+use crate::model::risk::RiskViolation;
+use crate::model::throttle::is_rate_limit_text;
 use std::fmt;
 
 /// Result type alias for the Deribit FIX framework
@@ -7,6 +15,7 @@ pub type Result<T> = std::result::Result<T, DeribitFixError>;
 
 /// Main error type for the Deribit FIX framework
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum DeribitFixError {
     /// Connection-related errors
     Connection(String),
@@ -26,10 +35,97 @@ pub enum DeribitFixError {
     Http(reqwest::Error),
     /// Configuration errors
     Config(String),
+    /// Configuration with invalid or missing fields, listing every problem
+    InvalidConfig(ConfigReport),
     /// Timeout errors
     Timeout(String),
+    /// Request stopped through its cancel token, or an order withdrawn by a
+    /// cancel sent while it waited for the session
+    Cancelled(String),
     /// Protocol violation errors
     Protocol(String),
+    /// Request refused because a bounded queue is full
+    QueueFull(String),
+    /// Received messages were dropped because they were not read in time
+    MessagesLost(u64),
+    /// Cancel or cancel/replace request rejected with an Order Cancel Reject (9)
+    CancelRejected {
+        /// Identifier of the order the request targeted
+        order_id: String,
+        /// CxlRejReason (102)
+        reason: Option<CxlRejReason>,
+        /// CxlRejResponseTo (434)
+        response_to: Option<CxlRejResponseTo>,
+        /// Text (58)
+        text: Option<String>,
+    },
+    /// Order or replace rejected with an Execution Report (8)
+    OrderRejected {
+        /// ClOrdID (11) of the rejected order or replace
+        cl_ord_id: String,
+        /// OrdRejReason (103)
+        reason: Option<OrderRejectReason>,
+        /// Text (58)
+        text: Option<String>,
+    },
+    /// Market data request rejected with a Market Data Request Reject (Y)
+    MarketDataRejected {
+        /// MDReqID (262) of the request
+        md_req_id: String,
+        /// MDReqRejReason (281)
+        reason: Option<MdReqRejReason>,
+        /// Text (58)
+        text: Option<String>,
+    },
+    /// Mass quote rejected with a Mass Quote Acknowledgement (b)
+    QuoteRejected {
+        /// QuoteID (117) of the mass quote
+        quote_id: String,
+        /// QuoteRejectReason (300)
+        reason: Option<QuoteRejectReason>,
+        /// Text (58)
+        text: Option<String>,
+    },
+    /// Quote request or RFQ rejected with a Quote Request Reject (AG)
+    QuoteRequestRejected {
+        /// QuoteReqID (131) of the request
+        quote_req_id: String,
+        /// QuoteRequestRejectReason (658)
+        reason: Option<QuoteRequestRejectReason>,
+        /// Text (58)
+        text: Option<String>,
+    },
+    /// Trade capture request rejected with a Trade Capture Report Request
+    /// Ack (AQ)
+    TradeCaptureRejected {
+        /// TradeRequestID (568) of the request
+        trade_request_id: String,
+        /// TradeRequestResult (749)
+        result: Option<TradeCaptureRequestResult>,
+        /// Text (58)
+        text: Option<String>,
+    },
+    /// Message rejected with a Reject (3) or Business Message Reject (j)
+    MessageRejected {
+        /// RefSeqNum (45) of the rejected message
+        ref_seq_num: Option<u32>,
+        /// RefMsgType (372) of the rejected message
+        ref_msg_type: Option<String>,
+        /// SessionRejectReason (373) or BusinessRejectReason (380)
+        reason: Option<u32>,
+        /// Text (58)
+        text: Option<String>,
+    },
+    /// Order rejected locally by the pre-trade risk checks
+    RiskLimit(RiskViolation),
+    /// Order rejected locally because the cached state of its instrument,
+    /// from Security Status (f), prohibits trading
+    InstrumentHalted {
+        /// Instrument symbol
+        symbol: String,
+        /// State of the instrument
+        state: InstrumentState,
+    },
     /// Generic errors
     Generic(String),
 }
@@ -48,13 +144,118 @@ impl fmt::Display for DeribitFixError {
             DeribitFixError::Json(err) => write!(f, "JSON error: {err}"),
             DeribitFixError::Http(err) => write!(f, "HTTP error: {err}"),
             DeribitFixError::Config(msg) => write!(f, "Configuration error: {msg}"),
+            DeribitFixError::InvalidConfig(report) => write!(f, "Invalid configuration: {report}"),
             DeribitFixError::Timeout(msg) => write!(f, "Timeout error: {msg}"),
+            DeribitFixError::Cancelled(msg) => write!(f, "Cancelled: {msg}"),
             DeribitFixError::Protocol(msg) => write!(f, "Protocol error: {msg}"),
+            DeribitFixError::QueueFull(msg) => write!(f, "Queue full: {msg}"),
+            DeribitFixError::MessagesLost(count) => {
+                write!(f, "{count} received messages were lost, not read in time")
+            }
+            DeribitFixError::CancelRejected {
+                order_id,
+                reason,
+                text,
+                ..
+            } => write!(
+                f,
+                "Cancel rejected for {order_id}: {}",
+                explanation(text, reason.as_ref())
+            ),
+            DeribitFixError::OrderRejected {
+                cl_ord_id,
+                reason,
+                text,
+            } => write!(
+                f,
+                "Order {cl_ord_id} rejected: {}",
+                explanation(text, reason.as_ref())
+            ),
+            DeribitFixError::MarketDataRejected {
+                md_req_id,
+                reason,
+                text,
+            } => write!(
+                f,
+                "Market data request {md_req_id} rejected: {}",
+                explanation(text, reason.as_ref())
+            ),
+            DeribitFixError::QuoteRejected {
+                quote_id,
+                reason,
+                text,
+            } => write!(
+                f,
+                "Quote {quote_id} rejected: {}",
+                explanation(text, reason.as_ref())
+            ),
+            DeribitFixError::QuoteRequestRejected {
+                quote_req_id,
+                reason,
+                text,
+            } => write!(
+                f,
+                "Quote request {quote_req_id} rejected: {}",
+                explanation(text, reason.as_ref())
+            ),
+            DeribitFixError::TradeCaptureRejected {
+                trade_request_id,
+                result,
+                text,
+            } => write!(
+                f,
+                "Trade capture request {trade_request_id} rejected: {}",
+                explanation(text, result.as_ref())
+            ),
+            DeribitFixError::MessageRejected {
+                ref_seq_num,
+                ref_msg_type,
+                reason,
+                text,
+            } => write!(
+                f,
+                "Message {} ({}) rejected: {}",
+                ref_seq_num.map_or_else(|| "?".to_string(), |seq| seq.to_string()),
+                ref_msg_type.as_deref().unwrap_or("?"),
+                explanation(text, reason.as_ref())
+            ),
+            DeribitFixError::RiskLimit(violation) => write!(f, "Risk limit: {violation}"),
+            DeribitFixError::InstrumentHalted { symbol, state } => {
+                write!(f, "Instrument {symbol} is not tradable: {state:?}")
+            }
             DeribitFixError::Generic(msg) => write!(f, "Error: {msg}"),
         }
     }
 }
 
+impl DeribitFixError {
+    /// Text (58) the exchange gave for a rejection, if any
+    pub fn reject_text(&self) -> Option<&str> {
+        match self {
+            DeribitFixError::CancelRejected { text, .. }
+            | DeribitFixError::OrderRejected { text, .. }
+            | DeribitFixError::MarketDataRejected { text, .. }
+            | DeribitFixError::QuoteRejected { text, .. }
+            | DeribitFixError::QuoteRequestRejected { text, .. }
+            | DeribitFixError::TradeCaptureRejected { text, .. }
+            | DeribitFixError::MessageRejected { text, .. } => text.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the exchange rejected the request for exceeding its rate limit
+    pub fn is_rate_limited(&self) -> bool {
+        self.reject_text().is_some_and(is_rate_limit_text)
+    }
+}
+
+/// The exchange's Text (58) for a rejection, falling back to its reason code
+fn explanation(text: &Option<String>, reason: Option<&impl fmt::Debug>) -> String {
+    text.clone()
+        .or_else(|| reason.map(|reason| format!("{reason:?}")))
+        .unwrap_or_else(|| "no reason given".to_string())
+}
+
 impl std::error::Error for DeribitFixError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -42,7 +42,7 @@
 //!         .with_credentials("your_key".to_string(), "your_secret".to_string())
 //!         .with_heartbeat_interval(30);
 //!
-//!     let mut client = DeribitFixClient::new(&config).await?;
+//!     let client = DeribitFixClient::new(&config).await?;
 //!     client.connect().await?;
 //!     
 //!     // Start trading!
@@ -250,6 +250,9 @@ pub mod macros;
 pub mod message;
 /// FIX message models and data structures
 pub mod model;
+pub mod recorder;
+/// Declarative test scenarios and a mock exchange to run them against
+pub mod scenario;
 pub mod session;
 /// Utility functions
 pub mod utils;
@@ -45,6 +45,34 @@ macro_rules! impl_json_debug_pretty {
     };
 }
 
+/// Implements `ToFixMessage` for message types that provide an inherent
+/// `to_fix_message(sender_comp_id, target_comp_id, msg_seq_num)` method.
+///
+/// Works for inherent methods taking comp IDs either as `&str` or `String`.
+#[macro_export]
+macro_rules! impl_to_fix_message {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl $crate::message::ToFixMessage for $t {
+                #[allow(clippy::useless_conversion)]
+                fn to_fix_message(
+                    &self,
+                    sender_comp_id: &str,
+                    target_comp_id: &str,
+                    msg_seq_num: u32,
+                ) -> $crate::error::Result<$crate::model::message::FixMessage> {
+                    <$t>::to_fix_message(
+                        self,
+                        sender_comp_id.into(),
+                        target_comp_id.into(),
+                        msg_seq_num,
+                    )
+                }
+            }
+        )+
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Serialize;
@@ -9,30 +9,28 @@
 //! - **Heartbeat (0)**: Periodic keep-alive messages to maintain session connectivity
 //! - **Test Request (1)**: Request for heartbeat response to test connectivity  
 //! - **Resend Request (2)**: Request to resend specific messages by sequence number range
-//! - **Reject (3)**: Rejection of received messages due to validation errors
+//! - **Reject (3)**: Rejection of received messages due to validation errors;
+//!   [`reject_error_of`] turns a Reject or Business Message Reject into an error
+//! - **Logout (5)**: Reason codes parsed into [`LogoutReason`]
 //! - **Business Message Reject (j)**: Business-level rejection of application messages
+//!
+//! The Heartbeat, Test Request, Resend Request, Sequence Reset and Reject
+//! structs and their FIX conversions are generated from the FIX dictionary;
+//! the constructors and helpers below are hand-written.
 
-use crate::error::Result;
+use crate::error::{DeribitFixError, Result};
 use crate::message::MessageBuilder;
+pub use crate::message::generated::{Heartbeat, Reject, ResendRequest, SequenceReset, TestRequest};
 use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
+use crate::session::request_ids::next_request_id;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
-/// Heartbeat message (MsgType = 0)
-///
-/// The Heartbeat message is used to monitor the status of the communication link.
-/// It is sent periodically to ensure the counterparty is still active and responsive.
-/// If no messages are received within the heartbeat interval, a Test Request should be sent.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Heartbeat {
-    /// TestReqID (112) - Optional field echoed from Test Request
-    /// Present only when responding to a Test Request message
-    pub test_req_id: Option<String>,
-}
-
 /// Business-level reject reason codes (FIX 4.4, tag 380)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum BusinessRejectReason {
     /// Other
     Other = 0,
@@ -71,78 +69,9 @@ pub struct BusinessMessageReject {
     pub text: Option<String>,
 }
 
-/// Test Request message (MsgType = 1)
-///
-/// The Test Request message is sent to force a Heartbeat response from the counterparty.
-/// It is typically used when no messages have been received within the expected heartbeat interval.
-/// The receiving party must respond with a Heartbeat message containing the same TestReqID.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct TestRequest {
-    /// TestReqID (112) - Unique identifier for this test request
-    /// Must be echoed back in the responding Heartbeat message
-    pub test_req_id: String,
-}
-
-/// Resend Request message (MsgType = 2)
-///
-/// The Resend Request message is sent to request retransmission of messages
-/// within a specified sequence number range. This is used for gap recovery
-/// when messages are detected as missing from the sequence.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ResendRequest {
-    /// BeginSeqNo (7) - Starting sequence number for resend range
-    pub begin_seq_no: u32,
-
-    /// EndSeqNo (16) - Ending sequence number for resend range
-    /// Set to 0 to request all messages from BeginSeqNo to current
-    pub end_seq_no: u32,
-}
-
-/// Sequence Reset message (MsgType = 4)
-///
-/// The Sequence Reset message is used to recover from an out-of-sequence condition,
-/// to reestablish a FIX session after a sequence loss. The MsgSeqNum(34) in the
-/// header is ignored.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct SequenceReset {
-    /// NewSeqNo (36) - New sequence number to reset to
-    /// This can only increase the sequence number, never decrease it
-    pub new_seq_no: u32,
-
-    /// GapFillFlag (123) - Indicates if this is a gap fill
-    /// Y = Gap Fill message, N = Sequence Reset message
-    pub gap_fill_flag: Option<bool>,
-}
-
-/// Reject message (MsgType = 3)
-///
-/// The Reject message is sent when a received message cannot be processed
-/// due to validation errors, formatting issues, or other problems.
-/// It provides detailed information about why the message was rejected.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Reject {
-    /// RefSeqNum (45) - Sequence number of the rejected message
-    pub ref_seq_num: u32,
-
-    /// RefTagID (371) - Tag number of the field that caused rejection
-    /// Optional field present when rejection is due to a specific field
-    pub ref_tag_id: Option<u32>,
-
-    /// RefMsgType (372) - Message type of the rejected message
-    /// Optional field to identify which message type was rejected
-    pub ref_msg_type: Option<String>,
-
-    /// SessionRejectReason (373) - Reason code for rejection
-    /// Standardized codes defined in FIX specification
-    pub session_reject_reason: Option<u32>,
-
-    /// Text (58) - Human-readable description of rejection reason
-    /// Optional free-form text providing additional details
-    pub text: Option<String>,
-}
-
 /// Session reject reason codes as defined in FIX specification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum SessionRejectReason {
     /// Invalid tag number
     InvalidTagNumber = 0,
@@ -184,6 +113,83 @@ pub enum SessionRejectReason {
     Other = 99,
 }
 
+/// Reason the counterparty ended the session with a Logout (5)
+///
+/// Derived from SessionStatus (1409) when present, otherwise from the Text (58)
+/// of the Logout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum LogoutReason {
+    /// Credentials were rejected, the account is locked or the password expired
+    CredentialsInvalid,
+    /// Heartbeats or Test Request responses were not received in time
+    HeartbeatMissed,
+    /// The exchange is going down for maintenance
+    Maintenance,
+    /// Orders were cancelled because cancel-on-disconnect was triggered
+    CancelOnDisconnect,
+    /// Response to a Logout sent by this side (SessionStatus 4)
+    Requested,
+    /// Any other reason
+    Other,
+}
+
+impl LogoutReason {
+    /// Classify a Logout (5) message
+    pub fn from_fix_message(message: &FixMessage) -> Self {
+        match message.get_field(tags::SESSION_STATUS).map(String::as_str) {
+            // Invalid username or password, account locked, password expired
+            Some("3" | "5" | "6" | "8") => return LogoutReason::CredentialsInvalid,
+            Some("4") => return LogoutReason::Requested,
+            _ => {}
+        }
+        message
+            .get_field(tags::TEXT)
+            .map_or(LogoutReason::Other, |text| Self::from_text(text))
+    }
+
+    /// Classify the Text (58) of a Logout
+    pub fn from_text(text: &str) -> Self {
+        let text = text.to_ascii_lowercase();
+        let contains_any = |needles: &[&str]| needles.iter().any(|needle| text.contains(needle));
+
+        if contains_any(&["maintenance"]) {
+            LogoutReason::Maintenance
+        } else if contains_any(&[
+            "cancel on disconnect",
+            "cancel-on-disconnect",
+            "cancel_on_disconnect",
+        ]) {
+            LogoutReason::CancelOnDisconnect
+        } else if contains_any(&["heartbeat", "test request", "timeout", "timed out"]) {
+            LogoutReason::HeartbeatMissed
+        } else if contains_any(&[
+            "credential",
+            "password",
+            "authentication",
+            "unauthorized",
+            "signature",
+        ]) {
+            LogoutReason::CredentialsInvalid
+        } else {
+            LogoutReason::Other
+        }
+    }
+
+    /// Whether logging on again cannot succeed without user intervention
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, LogoutReason::CredentialsInvalid)
+    }
+
+    /// Whether the session should log on again right away
+    ///
+    /// Fatal reasons, requested logouts and maintenance windows do not
+    /// trigger an immediate re-logon.
+    pub fn allows_relogon(&self) -> bool {
+        !self.is_fatal() && !matches!(self, LogoutReason::Requested | LogoutReason::Maintenance)
+    }
+}
+
 // Implement JSON display for all message types
 impl_json_display!(Heartbeat);
 impl_json_display!(TestRequest);
@@ -209,28 +215,6 @@ impl Heartbeat {
     pub fn is_test_response(&self) -> bool {
         self.test_req_id.is_some()
     }
-
-    /// Build a FIX message for this Heartbeat
-    pub fn to_fix_message(
-        &self,
-        sender_comp_id: String,
-        target_comp_id: String,
-        msg_seq_num: u32,
-    ) -> Result<FixMessage> {
-        let mut builder = MessageBuilder::new()
-            .msg_type(MsgType::Heartbeat)
-            .sender_comp_id(sender_comp_id)
-            .target_comp_id(target_comp_id)
-            .msg_seq_num(msg_seq_num)
-            .sending_time(Utc::now());
-
-        // Add TestReqID if present
-        if let Some(ref test_req_id) = self.test_req_id {
-            builder = builder.field(112, test_req_id.clone());
-        }
-
-        builder.build()
-    }
 }
 
 impl TestRequest {
@@ -239,28 +223,12 @@ impl TestRequest {
         Self { test_req_id }
     }
 
-    /// Generate a Test Request with a timestamp-based ID
+    /// Generate a Test Request with a unique ID from the process-wide
+    /// [`next_request_id`]
     pub fn new_with_timestamp() -> Self {
-        let test_req_id = format!("TESTREQ_{}", Utc::now().timestamp_millis());
+        let test_req_id = next_request_id("TESTREQ");
         Self::new(test_req_id)
     }
-
-    /// Build a FIX message for this Test Request
-    pub fn to_fix_message(
-        &self,
-        sender_comp_id: String,
-        target_comp_id: String,
-        msg_seq_num: u32,
-    ) -> Result<FixMessage> {
-        MessageBuilder::new()
-            .msg_type(MsgType::TestRequest)
-            .sender_comp_id(sender_comp_id)
-            .target_comp_id(target_comp_id)
-            .msg_seq_num(msg_seq_num)
-            .sending_time(Utc::now())
-            .field(112, self.test_req_id.clone()) // TestReqID
-            .build()
-    }
 }
 
 impl ResendRequest {
@@ -293,24 +261,6 @@ impl ResendRequest {
             Some(self.end_seq_no.saturating_sub(self.begin_seq_no) + 1)
         }
     }
-
-    /// Build a FIX message for this Resend Request
-    pub fn to_fix_message(
-        &self,
-        sender_comp_id: String,
-        target_comp_id: String,
-        msg_seq_num: u32,
-    ) -> Result<FixMessage> {
-        MessageBuilder::new()
-            .msg_type(MsgType::ResendRequest)
-            .sender_comp_id(sender_comp_id)
-            .target_comp_id(target_comp_id)
-            .msg_seq_num(msg_seq_num)
-            .sending_time(Utc::now())
-            .field(7, self.begin_seq_no.to_string()) // BeginSeqNo
-            .field(16, self.end_seq_no.to_string()) // EndSeqNo
-            .build()
-    }
 }
 
 impl SequenceReset {
@@ -342,29 +292,33 @@ impl SequenceReset {
     pub fn is_gap_fill(&self) -> bool {
         self.gap_fill_flag.unwrap_or(false)
     }
+}
 
-    /// Build a FIX message for this Sequence Reset
-    pub fn to_fix_message(
-        &self,
-        sender_comp_id: String,
-        target_comp_id: String,
-        msg_seq_num: u32,
-    ) -> Result<FixMessage> {
-        let mut builder = MessageBuilder::new()
-            .msg_type(MsgType::SequenceReset)
-            .sender_comp_id(sender_comp_id)
-            .target_comp_id(target_comp_id)
-            .msg_seq_num(msg_seq_num)
-            .sending_time(Utc::now())
-            .field(36, self.new_seq_no.to_string()); // NewSeqNo
-
-        // Add GapFillFlag if specified
-        if let Some(gap_fill) = self.gap_fill_flag {
-            builder = builder.field(123, if gap_fill { "Y" } else { "N" }.to_string());
-        }
-
-        builder.build()
-    }
+/// Error for a Reject (3) or Business Message Reject (j) of the message sent
+/// with `msg_seq_num`, `None` for any other message
+///
+/// The error carries the SessionRejectReason (373) or BusinessRejectReason
+/// (380) and the exchange's Text (58).
+pub fn reject_error_of(message: &FixMessage, msg_seq_num: u32) -> Option<DeribitFixError> {
+    let reason_tag = match message.msg_type()? {
+        MsgType::Reject => tags::SESSION_REJECT_REASON,
+        MsgType::BusinessMessageReject => tags::BUSINESS_REJECT_REASON,
+        _ => return None,
+    };
+    let ref_seq_num = message
+        .get_field(tags::REF_SEQ_NUM)
+        .and_then(|value| value.parse::<u32>().ok());
+    if ref_seq_num != Some(msg_seq_num) {
+        return None;
+    }
+    Some(DeribitFixError::MessageRejected {
+        ref_seq_num,
+        ref_msg_type: message.get_field(tags::REF_MSG_TYPE).cloned(),
+        reason: message
+            .get_field(reason_tag)
+            .and_then(|value| value.parse::<u32>().ok()),
+        text: message.get_field(tags::TEXT).cloned(),
+    })
 }
 
 impl Reject {
@@ -418,6 +372,17 @@ impl Reject {
         )
     }
 
+    /// Create a Reject for a MsgType (35) this session does not support
+    pub fn new_invalid_msg_type(ref_seq_num: u32, msg_type: String) -> Self {
+        Self::new_detailed(
+            ref_seq_num,
+            Some(tags::MSG_TYPE),
+            Some(msg_type.clone()),
+            Some(SessionRejectReason::InvalidMsgType),
+            Some(format!("Unsupported MsgType: {msg_type}")),
+        )
+    }
+
     /// Create a Reject for incorrect data format
     pub fn new_incorrect_format(ref_seq_num: u32, tag_id: u32, text: String) -> Self {
         Self::new_detailed(
@@ -428,41 +393,6 @@ impl Reject {
             Some(text),
         )
     }
-
-    /// Build a FIX message for this Reject
-    pub fn to_fix_message(
-        &self,
-        sender_comp_id: String,
-        target_comp_id: String,
-        msg_seq_num: u32,
-    ) -> Result<FixMessage> {
-        let mut builder = MessageBuilder::new()
-            .msg_type(MsgType::Reject)
-            .sender_comp_id(sender_comp_id)
-            .target_comp_id(target_comp_id)
-            .msg_seq_num(msg_seq_num)
-            .sending_time(Utc::now())
-            .field(45, self.ref_seq_num.to_string()); // RefSeqNum
-
-        // Add optional fields
-        if let Some(ref_tag_id) = self.ref_tag_id {
-            builder = builder.field(371, ref_tag_id.to_string()); // RefTagID
-        }
-
-        if let Some(ref ref_msg_type) = self.ref_msg_type {
-            builder = builder.field(372, ref_msg_type.clone()); // RefMsgType
-        }
-
-        if let Some(session_reject_reason) = self.session_reject_reason {
-            builder = builder.field(373, session_reject_reason.to_string()); // SessionRejectReason
-        }
-
-        if let Some(ref text) = self.text {
-            builder = builder.field(58, text.clone()); // Text
-        }
-
-        builder.build()
-    }
 }
 
 impl BusinessMessageReject {
@@ -501,15 +431,18 @@ impl BusinessMessageReject {
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
             .sending_time(Utc::now())
-            .field(372, self.ref_msg_type.clone()) // RefMsgType
-            .field(380, (self.business_reject_reason as u32).to_string()); // BusinessRejectReason
+            .field(tags::REF_MSG_TYPE, self.ref_msg_type.clone())
+            .field(
+                tags::BUSINESS_REJECT_REASON,
+                (self.business_reject_reason as u32).to_string(),
+            );
 
         if let Some(ref ref_id) = self.business_reject_ref_id {
-            builder = builder.field(379, ref_id.clone()); // BusinessRejectRefID
+            builder = builder.field(tags::BUSINESS_REJECT_REF_ID, ref_id.clone());
         }
 
         if let Some(ref text) = self.text {
-            builder = builder.field(58, text.clone()); // Text
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         builder.build()
@@ -614,6 +547,75 @@ mod tests {
         assert_eq!(msg.get_field(112), Some(&"REQ456".to_string())); // TestReqID
     }
 
+    #[test]
+    fn test_sequence_reset_from_fix_message() {
+        let gap_fill = SequenceReset::new_gap_fill(42)
+            .to_fix_message("SENDER".to_string(), "TARGET".to_string(), 10)
+            .unwrap();
+        let parsed = SequenceReset::from_fix_message(&gap_fill).unwrap();
+        assert_eq!(parsed.new_seq_no, 42);
+        assert!(parsed.is_gap_fill());
+
+        let reset = SequenceReset::new(7)
+            .to_fix_message("SENDER".to_string(), "TARGET".to_string(), 1)
+            .unwrap();
+        let parsed = SequenceReset::from_fix_message(&reset).unwrap();
+        assert_eq!(parsed.new_seq_no, 7);
+        assert_eq!(parsed.gap_fill_flag, None);
+        assert!(!parsed.is_gap_fill());
+
+        let mut missing = FixMessage::new();
+        missing.set_field(35, "4".to_string());
+        assert!(SequenceReset::from_fix_message(&missing).is_err());
+    }
+
+    #[test]
+    fn test_generated_messages_round_trip() {
+        fn encode<M>(
+            message: &M,
+            to_fix: fn(&M, String, String, u32) -> Result<FixMessage>,
+        ) -> FixMessage {
+            to_fix(message, "SENDER".to_string(), "TARGET".to_string(), 5).unwrap()
+        }
+
+        for heartbeat in [
+            Heartbeat::new(),
+            Heartbeat::new_response("PING".to_string()),
+        ] {
+            let message = encode(&heartbeat, Heartbeat::to_fix_message);
+            assert_eq!(Heartbeat::from_fix_message(&message).unwrap(), heartbeat);
+        }
+
+        let test_request = TestRequest::new("PING".to_string());
+        let message = encode(&test_request, TestRequest::to_fix_message);
+        assert_eq!(
+            TestRequest::from_fix_message(&message).unwrap(),
+            test_request
+        );
+
+        let resend = ResendRequest::new_from_sequence(12);
+        let message = encode(&resend, ResendRequest::to_fix_message);
+        assert_eq!(ResendRequest::from_fix_message(&message).unwrap(), resend);
+
+        for reset in [SequenceReset::new_gap_fill(42), SequenceReset::new_reset(7)] {
+            let message = encode(&reset, SequenceReset::to_fix_message);
+            assert_eq!(SequenceReset::from_fix_message(&message).unwrap(), reset);
+        }
+
+        for reject in [
+            Reject::new(3),
+            Reject::new_missing_tag(3, 55, "D".to_string()),
+        ] {
+            let message = encode(&reject, Reject::to_fix_message);
+            assert_eq!(Reject::from_fix_message(&message).unwrap(), reject);
+        }
+
+        let mut invalid = FixMessage::new();
+        invalid.set_field(tags::NEW_SEQ_NO, "7".to_string());
+        invalid.set_field(tags::GAP_FILL_FLAG, "maybe".to_string());
+        assert!(SequenceReset::from_fix_message(&invalid).is_err());
+    }
+
     #[test]
     fn test_business_message_reject_to_fix_message() {
         let bmr = BusinessMessageReject::new(
@@ -635,4 +637,47 @@ mod tests {
         ); // Reason
         assert_eq!(msg.get_field(58), Some(&"Unsupported type".to_string())); // Text
     }
+
+    #[test]
+    fn test_logout_reason_from_session_status_and_text() {
+        let logout = |fields: &str| {
+            FixMessage::parse(&format!("8=FIX.4.4\x019=0\x0135=5\x01{fields}10=000\x01")).unwrap()
+        };
+
+        assert_eq!(
+            LogoutReason::from_fix_message(&logout("1409=5\x0158=bye\x01")),
+            LogoutReason::CredentialsInvalid
+        );
+        assert_eq!(
+            LogoutReason::from_fix_message(&logout("1409=4\x01")),
+            LogoutReason::Requested
+        );
+        assert_eq!(
+            LogoutReason::from_fix_message(&logout("58=Heartbeat timeout\x01")),
+            LogoutReason::HeartbeatMissed
+        );
+        assert_eq!(
+            LogoutReason::from_fix_message(&logout("58=Scheduled MAINTENANCE\x01")),
+            LogoutReason::Maintenance
+        );
+        assert_eq!(
+            LogoutReason::from_text("orders cancelled: cancel on disconnect"),
+            LogoutReason::CancelOnDisconnect
+        );
+        assert_eq!(
+            LogoutReason::from_fix_message(&logout("")),
+            LogoutReason::Other
+        );
+    }
+
+    #[test]
+    fn test_logout_reason_relogon_policy() {
+        assert!(LogoutReason::HeartbeatMissed.allows_relogon());
+        assert!(LogoutReason::CancelOnDisconnect.allows_relogon());
+        assert!(LogoutReason::Other.allows_relogon());
+        assert!(LogoutReason::CredentialsInvalid.is_fatal());
+        assert!(!LogoutReason::CredentialsInvalid.allows_relogon());
+        assert!(!LogoutReason::Requested.allows_relogon());
+        assert!(!LogoutReason::Maintenance.allows_relogon());
+    }
 }
@@ -4,13 +4,45 @@
 //! FIX protocol messages used in communication with Deribit.
 
 use crate::error::{DeribitFixError, Result};
+use crate::message::time::{TimestampPrecision, format_utc_timestamp_with};
 use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 
+/// Standard header fields written after BeginString (8) and BodyLength (9),
+/// in the order of the FIX 4.4 StandardHeader component
+const HEADER_ORDER: [u32; 24] = [
+    tags::MSG_TYPE,
+    tags::SENDER_COMP_ID,
+    tags::TARGET_COMP_ID,
+    tags::ON_BEHALF_OF_COMP_ID,
+    128, // DeliverToCompID
+    90,  // SecureDataLen
+    91,  // SecureData
+    tags::MSG_SEQ_NUM,
+    tags::SENDER_SUB_ID,
+    142, // SenderLocationID
+    tags::TARGET_SUB_ID,
+    143, // TargetLocationID
+    116, // OnBehalfOfSubID
+    144, // OnBehalfOfLocationID
+    129, // DeliverToSubID
+    145, // DeliverToLocationID
+    tags::POSS_DUP_FLAG,
+    tags::POSS_RESEND,
+    tags::SENDING_TIME,
+    tags::ORIG_SENDING_TIME,
+    212, // XmlDataLen
+    213, // XmlData
+    347, // MessageEncoding
+    369, // LastMsgSeqNumProcessed
+];
+
 /// Builder for constructing FIX messages
 pub struct MessageBuilder {
     message: FixMessage,
+    timestamp_precision: TimestampPrecision,
 }
 
 impl MessageBuilder {
@@ -19,39 +51,103 @@ impl MessageBuilder {
         let mut message = FixMessage::new();
 
         // Set standard fields
-        message.set_field(8, "FIX.4.4".to_string()); // BeginString
+        message.set_field(tags::BEGIN_STRING, "FIX.4.4".to_string());
+
+        Self {
+            message,
+            timestamp_precision: TimestampPrecision::default(),
+        }
+    }
+
+    /// Create a builder pre-populated with the fields of an existing message
+    ///
+    /// BodyLength (9) and CheckSum (10) are dropped so that they are
+    /// recalculated when the message is built again.
+    pub fn from_message(message: &FixMessage) -> Self {
+        let mut rebuilt = FixMessage::new();
+        rebuilt.fields = message
+            .fields
+            .iter()
+            .filter(|(tag, _)| *tag != tags::BODY_LENGTH && *tag != tags::CHECKSUM)
+            .cloned()
+            .collect();
 
-        Self { message }
+        Self {
+            message: rebuilt,
+            timestamp_precision: TimestampPrecision::default(),
+        }
+    }
+
+    /// Mark the message as a possible duplicate of a previously sent message
+    ///
+    /// Sets PossDupFlag (43=Y) and OrigSendingTime (122), and clears SendingTime
+    /// (52) so that a fresh value is set when the message is built.
+    pub fn poss_dup(mut self, orig_sending_time: String) -> Self {
+        self.message
+            .fields
+            .retain(|(tag, _)| *tag != tags::SENDING_TIME);
+        self.message.set_field(tags::POSS_DUP_FLAG, "Y".to_string());
+        self.message
+            .set_field(tags::ORIG_SENDING_TIME, orig_sending_time);
+        self
     }
 
     /// Set message type
     pub fn msg_type(mut self, msg_type: MsgType) -> Self {
-        self.message.set_field(35, msg_type.as_str().to_string());
+        self.message
+            .set_field(tags::MSG_TYPE, msg_type.as_str().to_string());
         self
     }
 
     /// Set sender company ID
     pub fn sender_comp_id(mut self, sender_comp_id: String) -> Self {
-        self.message.set_field(49, sender_comp_id);
+        self.message.set_field(tags::SENDER_COMP_ID, sender_comp_id);
+        self
+    }
+
+    /// Set the firm the message is sent on behalf of, OnBehalfOfCompID (115)
+    pub fn on_behalf_of_comp_id(mut self, on_behalf_of_comp_id: String) -> Self {
+        self.message
+            .set_field(tags::ON_BEHALF_OF_COMP_ID, on_behalf_of_comp_id);
+        self
+    }
+
+    /// Set the sender sub ID, SenderSubID (50)
+    pub fn sender_sub_id(mut self, sender_sub_id: String) -> Self {
+        self.message.set_field(tags::SENDER_SUB_ID, sender_sub_id);
+        self
+    }
+
+    /// Set the target sub ID, TargetSubID (57)
+    pub fn target_sub_id(mut self, target_sub_id: String) -> Self {
+        self.message.set_field(tags::TARGET_SUB_ID, target_sub_id);
         self
     }
 
     /// Set target company ID
     pub fn target_comp_id(mut self, target_comp_id: String) -> Self {
-        self.message.set_field(56, target_comp_id);
+        self.message.set_field(tags::TARGET_COMP_ID, target_comp_id);
         self
     }
 
     /// Set message sequence number
     pub fn msg_seq_num(mut self, seq_num: u32) -> Self {
-        self.message.set_field(34, seq_num.to_string());
+        self.message
+            .set_field(tags::MSG_SEQ_NUM, seq_num.to_string());
+        self
+    }
+
+    /// Set the fractional seconds of the SendingTime (52) set by the builder
+    /// (default: milliseconds)
+    pub fn timestamp_precision(mut self, precision: TimestampPrecision) -> Self {
+        self.timestamp_precision = precision;
         self
     }
 
     /// Set sending time
     pub fn sending_time(mut self, time: DateTime<Utc>) -> Self {
-        let time_str = time.format("%Y%m%d-%H:%M:%S%.3f").to_string();
-        self.message.set_field(52, time_str);
+        let time_str = format_utc_timestamp_with(&time, self.timestamp_precision);
+        self.message.set_field(tags::SENDING_TIME, time_str);
         self
     }
 
@@ -61,90 +157,88 @@ impl MessageBuilder {
         self
     }
 
+    /// Append a field, keeping any earlier field with the same tag
+    ///
+    /// Used for the entries of repeating groups, whose tags recur once per
+    /// entry; [`field`](Self::field) would replace the value of the previous
+    /// entry instead.
+    pub fn push_field(mut self, tag: u32, value: String) -> Self {
+        self.message.fields.push((tag, value));
+        self
+    }
+
     /// Build the message
     pub fn build(mut self) -> Result<FixMessage> {
         // Validate required fields
-        if !self.message.has_field(8) {
+        if !self.message.has_field(tags::BEGIN_STRING) {
             return Err(DeribitFixError::MessageConstruction(
                 "BeginString (8) is required".to_string(),
             ));
         }
 
-        if !self.message.has_field(35) {
+        if !self.message.has_field(tags::MSG_TYPE) {
             return Err(DeribitFixError::MessageConstruction(
                 "MsgType (35) is required".to_string(),
             ));
         }
 
-        if !self.message.has_field(49) {
+        if !self.message.has_field(tags::SENDER_COMP_ID) {
             return Err(DeribitFixError::MessageConstruction(
                 "SenderCompID (49) is required".to_string(),
             ));
         }
 
-        if !self.message.has_field(56) {
+        if !self.message.has_field(tags::TARGET_COMP_ID) {
             return Err(DeribitFixError::MessageConstruction(
                 "TargetCompID (56) is required".to_string(),
             ));
         }
 
-        if !self.message.has_field(34) {
+        if !self.message.has_field(tags::MSG_SEQ_NUM) {
             return Err(DeribitFixError::MessageConstruction(
                 "MsgSeqNum (34) is required".to_string(),
             ));
         }
 
-        if !self.message.has_field(52) {
+        if !self.message.has_field(tags::SENDING_TIME) {
             // Set current time if not provided
-            let now = Utc::now();
-            let time_str = now.format("%Y%m%d-%H:%M:%S%.3f").to_string();
-            self.message.set_field(52, time_str);
+            let time_str = format_utc_timestamp_with(&Utc::now(), self.timestamp_precision);
+            self.message.set_field(tags::SENDING_TIME, time_str);
         }
 
         // Calculate BodyLength (all fields except BeginString and BodyLength itself)
         let body_length = self.calculate_body_length();
-        self.message.set_field(9, body_length.to_string());
+        self.message
+            .set_field(tags::BODY_LENGTH, body_length.to_string());
 
         // Calculate and set checksum
         let checksum = self.message.calculate_checksum();
-        self.message.set_field(10, format!("{checksum:03}"));
+        self.message
+            .set_field(tags::CHECKSUM, format!("{checksum:03}"));
 
-        // Generate raw message string with proper FIX field ordering:
-        // 1. BeginString (8) - first
-        // 2. BodyLength (9) - second
-        // 3. All other fields sorted by tag number
+        // Order the fields as FIX requires and generate the raw message:
+        // 1. BeginString (8) and BodyLength (9)
+        // 2. The other standard header fields, MsgType (35) first, in the
+        //    order of the StandardHeader component
+        // 3. Body fields in the order they were added, so that repeating
+        //    groups keep their count and entries together
         // 4. CheckSum (10) - last
-        let mut field_pairs: Vec<_> = self.message.fields.iter().collect();
-        field_pairs.sort_by_key(|(tag, _)| *tag);
-
-        let mut raw_parts = Vec::new();
-        let mut checksum_part = None;
-
-        // Add BeginString first if present
-        if let Some((_, value)) = field_pairs.iter().find(|(tag, _)| *tag == 8) {
-            raw_parts.push(format!("8={value}"));
-        }
-
-        // Add BodyLength second if present
-        if let Some((_, value)) = field_pairs.iter().find(|(tag, _)| *tag == 9) {
-            raw_parts.push(format!("9={value}"));
-        }
-
-        // Add all other fields except BeginString, BodyLength, and CheckSum
-        for (tag, value) in field_pairs {
-            if *tag == 10 {
-                // Save checksum for last
-                checksum_part = Some(format!("{tag}={value}"));
-            } else if *tag != 8 && *tag != 9 {
-                raw_parts.push(format!("{tag}={value}"));
-            }
-        }
-
-        // Add checksum at the end
-        if let Some(checksum) = checksum_part {
-            raw_parts.push(checksum);
-        }
-
+        // The sort is stable, which keeps the body in insertion order.
+        self.message.fields.sort_by_key(|(tag, _)| match *tag {
+            tags::BEGIN_STRING => 0,
+            tags::BODY_LENGTH => 1,
+            tags::CHECKSUM => HEADER_ORDER.len() + 3,
+            tag => match HEADER_ORDER.iter().position(|header| *header == tag) {
+                Some(position) => position + 2,
+                None => HEADER_ORDER.len() + 2,
+            },
+        });
+        let raw_parts: Vec<String> = self
+            .message
+            .fields
+            .iter()
+            .map(|(tag, value)| format!("{tag}={value}"))
+            .collect();
         self.message.raw_message = raw_parts.join("\x01") + "\x01";
 
         Ok(self.message)
@@ -157,7 +251,7 @@ impl MessageBuilder {
 
         // Add all fields except BeginString (8), BodyLength (9), and CheckSum (10)
         for (tag, value) in &self.message.fields {
-            if *tag != 8 && *tag != 9 && *tag != 10 {
+            if *tag != tags::BEGIN_STRING && *tag != tags::BODY_LENGTH && *tag != tags::CHECKSUM {
                 body_parts.push(format!("{tag}={value}"));
             }
         }
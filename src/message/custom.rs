@@ -0,0 +1,158 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Custom FIX messages
+//!
+//! [`CustomMessage`] sends a message type or tags the crate has no typed
+//! support for yet, e.g. to try a new Deribit field. The session still
+//! supplies the standard header, so sequence numbers stay consistent.
+
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
+use crate::message::{MessageBuilder, ToFixMessage};
+use crate::model::message::FixMessage;
+use crate::model::tags;
+
+/// Header and trailer tags managed by the session and the message builder
+const RESERVED_TAGS: [u32; 11] = [
+    tags::BEGIN_STRING,
+    tags::BODY_LENGTH,
+    tags::CHECKSUM,
+    tags::MSG_SEQ_NUM,
+    tags::MSG_TYPE,
+    tags::POSS_DUP_FLAG,
+    tags::SENDER_COMP_ID,
+    tags::SENDING_TIME,
+    tags::TARGET_COMP_ID,
+    tags::POSS_RESEND,
+    tags::ORIG_SENDING_TIME,
+];
+
+/// Session-level message types that would desynchronize the session:
+/// Logon (A), Logout (5), Sequence Reset (4) and Resend Request (2)
+const RESERVED_MSG_TYPES: [&str; 4] = ["A", "5", "4", "2"];
+
+/// Message with a caller-defined MsgType (35) and body fields
+///
+/// Fields are written in tag order; repeating groups are not supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomMessage {
+    msg_type: String,
+    fields: Vec<(u32, String)>,
+}
+
+impl CustomMessage {
+    /// Create a custom message, checking that it is safe to send
+    ///
+    /// Fails when the message type is empty or a session-level type, when a
+    /// field sets a header or trailer tag, has an empty value or contains the
+    /// SOH delimiter, or when a tag is repeated.
+    pub fn new(msg_type: &str, fields: Vec<(u32, String)>) -> DeribitFixResult<Self> {
+        if msg_type.is_empty() || !msg_type.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "Invalid MsgType: {msg_type:?}"
+            )));
+        }
+        if RESERVED_MSG_TYPES.contains(&msg_type) {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "MsgType {msg_type} is managed by the session"
+            )));
+        }
+
+        for (index, (tag, value)) in fields.iter().enumerate() {
+            if *tag == 0 || RESERVED_TAGS.contains(tag) {
+                return Err(DeribitFixError::MessageConstruction(format!(
+                    "Tag {tag} cannot be set on a custom message"
+                )));
+            }
+            if value.is_empty() || value.contains('\x01') {
+                return Err(DeribitFixError::MessageConstruction(format!(
+                    "Invalid value for tag {tag}: {value:?}"
+                )));
+            }
+            if fields[..index].iter().any(|(other, _)| other == tag) {
+                return Err(DeribitFixError::MessageConstruction(format!(
+                    "Tag {tag} appears more than once"
+                )));
+            }
+        }
+
+        Ok(Self {
+            msg_type: msg_type.to_string(),
+            fields,
+        })
+    }
+
+    /// MsgType (35) of the message
+    pub fn msg_type(&self) -> &str {
+        &self.msg_type
+    }
+
+    /// Body fields of the message
+    pub fn fields(&self) -> &[(u32, String)] {
+        &self.fields
+    }
+}
+
+impl ToFixMessage for CustomMessage {
+    fn to_fix_message(
+        &self,
+        sender_comp_id: &str,
+        target_comp_id: &str,
+        msg_seq_num: u32,
+    ) -> DeribitFixResult<FixMessage> {
+        let builder = MessageBuilder::new()
+            .field(tags::MSG_TYPE, self.msg_type.clone())
+            .sender_comp_id(sender_comp_id.to_string())
+            .target_comp_id(target_comp_id.to_string())
+            .msg_seq_num(msg_seq_num);
+
+        self.fields
+            .iter()
+            .fold(builder, |builder, (tag, value)| {
+                builder.field(*tag, value.clone())
+            })
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_message_gets_session_header() {
+        let message = CustomMessage::new(
+            "U99",
+            vec![(100010, "label".to_string()), (9999, "1".to_string())],
+        )
+        .unwrap()
+        .to_fix_message("CLIENT", "DERIBIT", 12)
+        .unwrap();
+
+        assert_eq!(message.get_field(35).unwrap(), "U99");
+        assert_eq!(message.get_field(49).unwrap(), "CLIENT");
+        assert_eq!(message.get_field(34).unwrap(), "12");
+        assert_eq!(message.get_field(100010).unwrap(), "label");
+        assert!(FixMessage::parse(&message.to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_unsafe_custom_messages_are_rejected() {
+        let field = |tag: u32, value: &str| vec![(tag, value.to_string())];
+
+        assert!(CustomMessage::new("", vec![]).is_err());
+        assert!(CustomMessage::new("A", vec![]).is_err());
+        assert!(CustomMessage::new("U=1", vec![]).is_err());
+        assert!(CustomMessage::new("U1", field(34, "5")).is_err());
+        assert!(CustomMessage::new("U1", field(10, "000")).is_err());
+        assert!(CustomMessage::new("U1", field(58, "")).is_err());
+        assert!(CustomMessage::new("U1", field(58, "a\x0110=000")).is_err());
+        assert!(
+            CustomMessage::new("U1", vec![(58, "a".to_string()), (58, "b".to_string())]).is_err()
+        );
+        assert!(CustomMessage::new("U1", field(58, "ok")).is_ok());
+    }
+}
@@ -0,0 +1,267 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Tag-by-tag comparison of FIX messages
+//!
+//! [`FixDiff`] compares an expected message with an actual one and reports
+//! the fields that differ, leaving out those that change from one send to
+//! the next: BodyLength (9), CheckSum (10), MsgSeqNum (34), SendingTime (52)
+//! and OrigSendingTime (122). Fields are matched by tag and by occurrence, so
+//! the second NoMDEntries (268) entry of one message is compared with the
+//! second of the other, while the relative order of different tags is not
+//! compared.
+//!
+//! ```
+//! use deribit_fix::message::FixDiff;
+//! use deribit_fix::model::message::FixMessage;
+//!
+//! let expected = FixMessage::parse("35=D\x0134=2\x0155=BTC-PERPETUAL\x0144=65000\x01").unwrap();
+//! let actual = FixMessage::parse("35=D\x0134=7\x0155=BTC-PERPETUAL\x0144=65000.5\x01").unwrap();
+//! let report = FixDiff::new().diff(&expected, &actual);
+//! assert_eq!(report.to_string(), "~ 44=Price: expected 65000, actual 65000.5");
+//! ```
+
+use crate::error::Result;
+use crate::message::printer::tag_name;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Tags left out of comparisons by default, as they change with every send
+pub const VOLATILE_TAGS: [u32; 5] = [
+    tags::BODY_LENGTH,
+    tags::CHECKSUM,
+    tags::MSG_SEQ_NUM,
+    tags::SENDING_TIME,
+    tags::ORIG_SENDING_TIME,
+];
+
+/// Compares FIX messages tag by tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixDiff {
+    ignored_tags: BTreeSet<u32>,
+}
+
+impl FixDiff {
+    /// Create a comparison ignoring [`VOLATILE_TAGS`]
+    pub fn new() -> Self {
+        Self {
+            ignored_tags: VOLATILE_TAGS.into_iter().collect(),
+        }
+    }
+
+    /// Create a comparison of every tag
+    pub fn strict() -> Self {
+        Self {
+            ignored_tags: BTreeSet::new(),
+        }
+    }
+
+    /// Leave `tag` out of the comparison
+    pub fn ignore_tag(mut self, tag: u32) -> Self {
+        self.ignored_tags.insert(tag);
+        self
+    }
+
+    /// Compare `tag`, even if it is one of [`VOLATILE_TAGS`]
+    pub fn compare_tag(mut self, tag: u32) -> Self {
+        self.ignored_tags.remove(&tag);
+        self
+    }
+
+    /// Whether `tag` is left out of the comparison
+    pub fn is_ignored(&self, tag: u32) -> bool {
+        self.ignored_tags.contains(&tag)
+    }
+
+    /// Fields of `actual` that differ from those of `expected`
+    ///
+    /// Differences are listed in the order their tags first appear in
+    /// `expected`, then in `actual`.
+    pub fn diff(&self, expected: &FixMessage, actual: &FixMessage) -> FixDiffReport {
+        let mut order = Vec::new();
+        for (tag, _) in expected.fields.iter().chain(&actual.fields) {
+            if !self.is_ignored(*tag) && !order.contains(tag) {
+                order.push(*tag);
+            }
+        }
+
+        let values = |message: &FixMessage, tag: u32| -> Vec<String> {
+            message
+                .fields
+                .iter()
+                .filter(|(field, _)| *field == tag)
+                .map(|(_, value)| value.clone())
+                .collect()
+        };
+        let mut differences = Vec::new();
+        for tag in order {
+            let (mut expected, mut actual) = (
+                values(expected, tag).into_iter(),
+                values(actual, tag).into_iter(),
+            );
+            for occurrence in 0.. {
+                let (expected, actual) = (expected.next(), actual.next());
+                if expected.is_none() && actual.is_none() {
+                    break;
+                }
+                if expected != actual {
+                    differences.push(FieldDifference {
+                        tag,
+                        occurrence,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+        FixDiffReport { differences }
+    }
+
+    /// Compare two raw SOH-delimited messages
+    pub fn diff_raw(&self, expected: &str, actual: &str) -> Result<FixDiffReport> {
+        Ok(self.diff(&FixMessage::parse(expected)?, &FixMessage::parse(actual)?))
+    }
+
+    /// Panic with the readable difference unless the messages match
+    #[track_caller]
+    pub fn assert_matches(&self, expected: &FixMessage, actual: &FixMessage) {
+        let report = self.diff(expected, actual);
+        assert!(report.is_empty(), "FIX messages differ:\n{report}");
+    }
+}
+
+impl Default for FixDiff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A field whose value differs between two messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDifference {
+    /// Tag of the field
+    pub tag: u32,
+    /// Which occurrence of the tag, from 0, for fields of repeating groups
+    pub occurrence: usize,
+    /// Value in the expected message, `None` if it lacks the field
+    pub expected: Option<String>,
+    /// Value in the actual message, `None` if it lacks the field
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for FieldDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match tag_name(self.tag) {
+            Some(name) => format!("{}={name}", self.tag),
+            None => self.tag.to_string(),
+        };
+        let occurrence = match self.occurrence {
+            0 => String::new(),
+            occurrence => format!("[{}]", occurrence + 1),
+        };
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => write!(
+                f,
+                "~ {name}{occurrence}: expected {expected}, actual {actual}"
+            ),
+            (Some(expected), None) => write!(f, "- {name}{occurrence}: {expected}"),
+            (None, Some(actual)) => write!(f, "+ {name}{occurrence}: {actual}"),
+            (None, None) => write!(f, "  {name}{occurrence}"),
+        }
+    }
+}
+
+/// Differences found by [`FixDiff`]
+///
+/// Displayed one field per line: `~` for a changed value, `-` for a field
+/// only the expected message has and `+` for one only the actual message
+/// has.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixDiffReport {
+    /// Differing fields
+    pub differences: Vec<FieldDifference>,
+}
+
+impl FixDiffReport {
+    /// Whether the messages match
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Number of differing fields
+    pub fn len(&self) -> usize {
+        self.differences.len()
+    }
+}
+
+impl fmt::Display for FixDiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, difference) in self.differences.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{difference}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volatile_fields_are_ignored() {
+        let expected =
+            "8=FIX.4.4\x019=60\x0135=0\x0134=1\x0152=20260101-00:00:00.000\x0110=123\x01";
+        let actual = "8=FIX.4.4\x019=61\x0135=0\x0134=12\x0152=20261016-09:30:00.000\x0110=045\x01";
+        assert!(
+            FixDiff::new()
+                .diff_raw(expected, actual)
+                .unwrap()
+                .is_empty()
+        );
+
+        let report = FixDiff::strict().diff_raw(expected, actual).unwrap();
+        let tags: Vec<u32> = report.differences.iter().map(|d| d.tag).collect();
+        assert_eq!(tags, [9, 34, 52, 10]);
+        assert!(
+            FixDiff::new()
+                .compare_tag(34)
+                .diff_raw(expected, actual)
+                .unwrap()
+                .differences
+                .iter()
+                .all(|d| d.tag == 34)
+        );
+    }
+
+    #[test]
+    fn test_differences_are_reported_per_occurrence() {
+        let expected = "35=W\x0155=BTC-PERPETUAL\x01268=2\x01269=0\x01270=50000\x01\
+                        269=1\x01270=50010\x0158=snapshot\x01";
+        let actual = "35=W\x0155=BTC-PERPETUAL\x01268=2\x01269=0\x01270=50000\x01\
+                      269=1\x01270=50020\x019999=extra\x01";
+        let report = FixDiff::new().diff_raw(expected, actual).unwrap();
+        assert_eq!(report.len(), 3);
+        assert_eq!(
+            report.to_string(),
+            "~ 270=MDEntryPx[2]: expected 50010, actual 50020\n\
+             - 58=Text: snapshot\n\
+             + 9999: extra"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "FIX messages differ")]
+    fn test_assert_matches_panics_on_a_difference() {
+        let expected = FixMessage::parse("35=0\x01112=A\x01").unwrap();
+        let actual = FixMessage::parse("35=0\x01112=B\x01").unwrap();
+        FixDiff::new().assert_matches(&expected, &actual);
+    }
+}
@@ -0,0 +1,313 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 21/7/25
+******************************************************************************/
+
+//! Message structs generated from the FIX dictionary
+//!
+//! `from_fix_message` reads back every body field `to_fix_message` writes.
+//! Constructors and helpers are hand-written next to the other messages of
+//! the family, which re-exports the structs.
+
+// @generated by `cargo xtask codegen` from dictionary/deribit_fix44.json.
+// Edit the dictionary and regenerate instead of changing this file.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Heartbeat message (MsgType = 0)
+///
+/// The Heartbeat message is used to monitor the status of the communication link.
+/// It is sent periodically to ensure the counterparty is still active and responsive.
+/// If no messages are received within the heartbeat interval, a Test Request should be sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    /// TestReqID (112) - Optional field echoed from Test Request
+    /// Present only when responding to a Test Request message
+    pub test_req_id: Option<String>,
+}
+
+impl Heartbeat {
+    /// Parse a Heartbeat from a FIX message
+    pub fn from_fix_message(message: &FixMessage) -> Result<Self> {
+        Ok(Self {
+            test_req_id: optional(message, tags::TEST_REQ_ID, "TestReqID", parse_string)?,
+        })
+    }
+
+    /// Build a FIX message for this Heartbeat
+    pub fn to_fix_message(
+        &self,
+        sender_comp_id: String,
+        target_comp_id: String,
+        msg_seq_num: u32,
+    ) -> Result<FixMessage> {
+        let mut builder = MessageBuilder::new()
+            .msg_type(MsgType::Heartbeat)
+            .sender_comp_id(sender_comp_id)
+            .target_comp_id(target_comp_id)
+            .msg_seq_num(msg_seq_num)
+            .sending_time(Utc::now());
+        if let Some(test_req_id) = &self.test_req_id {
+            builder = builder.field(tags::TEST_REQ_ID, test_req_id.clone());
+        }
+        builder.build()
+    }
+}
+
+/// Test Request message (MsgType = 1)
+///
+/// The Test Request message is sent to force a Heartbeat response from the counterparty.
+/// It is typically used when no messages have been received within the expected heartbeat interval.
+/// The receiving party must respond with a Heartbeat message containing the same TestReqID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestRequest {
+    /// TestReqID (112) - Unique identifier for this test request
+    /// Must be echoed back in the responding Heartbeat message
+    pub test_req_id: String,
+}
+
+impl TestRequest {
+    /// Parse a Test Request from a FIX message
+    pub fn from_fix_message(message: &FixMessage) -> Result<Self> {
+        Ok(Self {
+            test_req_id: required(message, tags::TEST_REQ_ID, "TestReqID", parse_string)?,
+        })
+    }
+
+    /// Build a FIX message for this Test Request
+    pub fn to_fix_message(
+        &self,
+        sender_comp_id: String,
+        target_comp_id: String,
+        msg_seq_num: u32,
+    ) -> Result<FixMessage> {
+        let mut builder = MessageBuilder::new()
+            .msg_type(MsgType::TestRequest)
+            .sender_comp_id(sender_comp_id)
+            .target_comp_id(target_comp_id)
+            .msg_seq_num(msg_seq_num)
+            .sending_time(Utc::now());
+        builder = builder.field(tags::TEST_REQ_ID, self.test_req_id.clone());
+        builder.build()
+    }
+}
+
+/// Resend Request message (MsgType = 2)
+///
+/// The Resend Request message is sent to request retransmission of messages
+/// within a specified sequence number range. This is used for gap recovery
+/// when messages are detected as missing from the sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResendRequest {
+    /// BeginSeqNo (7) - Starting sequence number for resend range
+    pub begin_seq_no: u32,
+
+    /// EndSeqNo (16) - Ending sequence number for resend range
+    /// Set to 0 to request all messages from BeginSeqNo to current
+    pub end_seq_no: u32,
+}
+
+impl ResendRequest {
+    /// Parse a Resend Request from a FIX message
+    pub fn from_fix_message(message: &FixMessage) -> Result<Self> {
+        Ok(Self {
+            begin_seq_no: required(message, tags::BEGIN_SEQ_NO, "BeginSeqNo", parse_u32)?,
+            end_seq_no: required(message, tags::END_SEQ_NO, "EndSeqNo", parse_u32)?,
+        })
+    }
+
+    /// Build a FIX message for this Resend Request
+    pub fn to_fix_message(
+        &self,
+        sender_comp_id: String,
+        target_comp_id: String,
+        msg_seq_num: u32,
+    ) -> Result<FixMessage> {
+        let mut builder = MessageBuilder::new()
+            .msg_type(MsgType::ResendRequest)
+            .sender_comp_id(sender_comp_id)
+            .target_comp_id(target_comp_id)
+            .msg_seq_num(msg_seq_num)
+            .sending_time(Utc::now());
+        builder = builder.field(tags::BEGIN_SEQ_NO, self.begin_seq_no.to_string());
+        builder = builder.field(tags::END_SEQ_NO, self.end_seq_no.to_string());
+        builder.build()
+    }
+}
+
+/// Sequence Reset message (MsgType = 4)
+///
+/// The Sequence Reset message is used to recover from an out-of-sequence condition,
+/// to reestablish a FIX session after a sequence loss. The MsgSeqNum(34) in the
+/// header is ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceReset {
+    /// NewSeqNo (36) - New sequence number to reset to
+    /// This can only increase the sequence number, never decrease it
+    pub new_seq_no: u32,
+
+    /// GapFillFlag (123) - Indicates if this is a gap fill
+    /// Y = Gap Fill message, N = Sequence Reset message
+    pub gap_fill_flag: Option<bool>,
+}
+
+impl SequenceReset {
+    /// Parse a Sequence Reset from a FIX message
+    pub fn from_fix_message(message: &FixMessage) -> Result<Self> {
+        Ok(Self {
+            new_seq_no: required(message, tags::NEW_SEQ_NO, "NewSeqNo", parse_u32)?,
+            gap_fill_flag: optional(message, tags::GAP_FILL_FLAG, "GapFillFlag", parse_bool)?,
+        })
+    }
+
+    /// Build a FIX message for this Sequence Reset
+    pub fn to_fix_message(
+        &self,
+        sender_comp_id: String,
+        target_comp_id: String,
+        msg_seq_num: u32,
+    ) -> Result<FixMessage> {
+        let mut builder = MessageBuilder::new()
+            .msg_type(MsgType::SequenceReset)
+            .sender_comp_id(sender_comp_id)
+            .target_comp_id(target_comp_id)
+            .msg_seq_num(msg_seq_num)
+            .sending_time(Utc::now());
+        builder = builder.field(tags::NEW_SEQ_NO, self.new_seq_no.to_string());
+        if let Some(gap_fill_flag) = self.gap_fill_flag {
+            builder = builder.field(
+                tags::GAP_FILL_FLAG,
+                if gap_fill_flag { "Y" } else { "N" }.to_string(),
+            );
+        }
+        builder.build()
+    }
+}
+
+/// Reject message (MsgType = 3)
+///
+/// The Reject message is sent when a received message cannot be processed
+/// due to validation errors, formatting issues, or other problems.
+/// It provides detailed information about why the message was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reject {
+    /// RefSeqNum (45) - Sequence number of the rejected message
+    pub ref_seq_num: u32,
+
+    /// RefTagID (371) - Tag number of the field that caused rejection
+    /// Optional field present when rejection is due to a specific field
+    pub ref_tag_id: Option<u32>,
+
+    /// RefMsgType (372) - Message type of the rejected message
+    /// Optional field to identify which message type was rejected
+    pub ref_msg_type: Option<String>,
+
+    /// SessionRejectReason (373) - Reason code for rejection
+    /// Standardized codes defined in FIX specification
+    pub session_reject_reason: Option<u32>,
+
+    /// Text (58) - Human-readable description of rejection reason
+    /// Optional free-form text providing additional details
+    pub text: Option<String>,
+}
+
+impl Reject {
+    /// Parse a Reject from a FIX message
+    pub fn from_fix_message(message: &FixMessage) -> Result<Self> {
+        Ok(Self {
+            ref_seq_num: required(message, tags::REF_SEQ_NUM, "RefSeqNum", parse_u32)?,
+            ref_tag_id: optional(message, tags::REF_TAG_ID, "RefTagID", parse_u32)?,
+            ref_msg_type: optional(message, tags::REF_MSG_TYPE, "RefMsgType", parse_string)?,
+            session_reject_reason: optional(
+                message,
+                tags::SESSION_REJECT_REASON,
+                "SessionRejectReason",
+                parse_u32,
+            )?,
+            text: optional(message, tags::TEXT, "Text", parse_string)?,
+        })
+    }
+
+    /// Build a FIX message for this Reject
+    pub fn to_fix_message(
+        &self,
+        sender_comp_id: String,
+        target_comp_id: String,
+        msg_seq_num: u32,
+    ) -> Result<FixMessage> {
+        let mut builder = MessageBuilder::new()
+            .msg_type(MsgType::Reject)
+            .sender_comp_id(sender_comp_id)
+            .target_comp_id(target_comp_id)
+            .msg_seq_num(msg_seq_num)
+            .sending_time(Utc::now());
+        builder = builder.field(tags::REF_SEQ_NUM, self.ref_seq_num.to_string());
+        if let Some(ref_tag_id) = self.ref_tag_id {
+            builder = builder.field(tags::REF_TAG_ID, ref_tag_id.to_string());
+        }
+        if let Some(ref_msg_type) = &self.ref_msg_type {
+            builder = builder.field(tags::REF_MSG_TYPE, ref_msg_type.clone());
+        }
+        if let Some(session_reject_reason) = self.session_reject_reason {
+            builder = builder.field(
+                tags::SESSION_REJECT_REASON,
+                session_reject_reason.to_string(),
+            );
+        }
+        if let Some(text) = &self.text {
+            builder = builder.field(tags::TEXT, text.clone());
+        }
+        builder.build()
+    }
+}
+
+/// Value of the required `tag`, converted with `parse`
+fn required<T>(
+    message: &FixMessage,
+    tag: u32,
+    name: &str,
+    parse: fn(&str) -> Option<T>,
+) -> Result<T> {
+    optional(message, tag, name, parse)?
+        .ok_or_else(|| DeribitFixError::MessageParsing(format!("{name} ({tag}) is required")))
+}
+
+/// Value of `tag` if present, converted with `parse`
+fn optional<T>(
+    message: &FixMessage,
+    tag: u32,
+    name: &str,
+    parse: fn(&str) -> Option<T>,
+) -> Result<Option<T>> {
+    message
+        .get_field(tag)
+        .map(|value| {
+            parse(value).ok_or_else(|| {
+                DeribitFixError::MessageParsing(format!("Invalid {name} ({tag}): {value}"))
+            })
+        })
+        .transpose()
+}
+
+fn parse_string(value: &str) -> Option<String> {
+    Some(value.to_string())
+}
+
+fn parse_u32(value: &str) -> Option<u32> {
+    value.parse().ok()
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "Y" => Some(true),
+        "N" => Some(false),
+        _ => None,
+    }
+}
@@ -14,8 +14,14 @@
 //! - Market Data Snapshot/Full Refresh (MsgType = 'W')
 //! - Market Data Incremental Refresh (MsgType = 'X')
 
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::MessageBuilder;
+use crate::message::time::{
+    format_utc_date_only, format_utc_time_only, parse_tz_time_only, parse_utc_date_only,
+    parse_utc_timestamp,
+};
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -79,25 +85,36 @@ impl TryFrom<i32> for MdUpdateType {
 
 /// MD Entry Type enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum MdEntryType {
     /// Bid (Bid side of the order book)
-    Bid = 0,
+    Bid,
     /// Offer (Ask side of the order book)
-    Offer = 1,
+    Offer,
     /// Trade (Info about recent trades)
-    Trade = 2,
+    Trade,
     /// Index Value (value of Index for INDEX instruments)
-    IndexValue = 3,
+    IndexValue,
     /// Settlement Price (Estimated Delivery Price for INDEX instruments)
-    SettlementPrice = 6,
+    SettlementPrice,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<MdEntryType> for i32 {
     fn from(value: MdEntryType) -> Self {
-        value as i32
+        match value {
+            MdEntryType::Bid => 0,
+            MdEntryType::Offer => 1,
+            MdEntryType::Trade => 2,
+            MdEntryType::IndexValue => 3,
+            MdEntryType::SettlementPrice => 6,
+            MdEntryType::Unknown(value) => value,
+        }
     }
 }
 
+/// Never fails: values this version does not know become [`MdEntryType::Unknown`]
 impl TryFrom<i32> for MdEntryType {
     type Error = String;
 
@@ -108,20 +125,23 @@ impl TryFrom<i32> for MdEntryType {
             2 => Ok(MdEntryType::Trade),
             3 => Ok(MdEntryType::IndexValue),
             6 => Ok(MdEntryType::SettlementPrice),
-            _ => Err(format!("Invalid MdEntryType: {value}")),
+            _ => Ok(MdEntryType::Unknown(value)),
         }
     }
 }
 
 /// MD Update Action enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum MdUpdateAction {
     /// New entry
-    New = 0,
+    New,
     /// Change existing entry
-    Change = 1,
+    Change,
     /// Delete entry
-    Delete = 2,
+    Delete,
+    /// Value this version does not know, kept as received
+    Unknown(char),
 }
 
 impl From<MdUpdateAction> for char {
@@ -130,10 +150,12 @@ impl From<MdUpdateAction> for char {
             MdUpdateAction::New => '0',
             MdUpdateAction::Change => '1',
             MdUpdateAction::Delete => '2',
+            MdUpdateAction::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`MdUpdateAction::Unknown`]
 impl TryFrom<char> for MdUpdateAction {
     type Error = String;
 
@@ -142,42 +164,45 @@ impl TryFrom<char> for MdUpdateAction {
             '0' => Ok(MdUpdateAction::New),
             '1' => Ok(MdUpdateAction::Change),
             '2' => Ok(MdUpdateAction::Delete),
-            _ => Err(format!("Invalid MdUpdateAction: {value}")),
+            _ => Ok(MdUpdateAction::Unknown(value)),
         }
     }
 }
 
 /// MD Request Reject Reason enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum MdReqRejReason {
     /// Unknown symbol
-    UnknownSymbol = 0,
+    UnknownSymbol,
     /// Duplicate MDReqID
-    DuplicateMdReqId = 1,
+    DuplicateMdReqId,
     /// Insufficient Bandwidth
-    InsufficientBandwidth = 2,
+    InsufficientBandwidth,
     /// Insufficient Permissions
-    InsufficientPermissions = 3,
+    InsufficientPermissions,
     /// Unsupported SubscriptionRequestType
-    UnsupportedSubscriptionRequestType = 4,
+    UnsupportedSubscriptionRequestType,
     /// Unsupported MarketDepth
-    UnsupportedMarketDepth = 5,
+    UnsupportedMarketDepth,
     /// Unsupported MDUpdateType
-    UnsupportedMdUpdateType = 6,
+    UnsupportedMdUpdateType,
     /// Unsupported AggregatedBook
-    UnsupportedAggregatedBook = 7,
+    UnsupportedAggregatedBook,
     /// Unsupported MDEntryType
-    UnsupportedMdEntryType = 8,
+    UnsupportedMdEntryType,
     /// Unsupported TradingSessionID
-    UnsupportedTradingSessionId = 9,
+    UnsupportedTradingSessionId,
     /// Unsupported Scope
-    UnsupportedScope = 10,
+    UnsupportedScope,
     /// Unsupported OpenCloseSettlFlag
-    UnsupportedOpenCloseSettlFlag = 11,
+    UnsupportedOpenCloseSettlFlag,
     /// Unsupported MDImplicitDelete
-    UnsupportedMdImplicitDelete = 12,
+    UnsupportedMdImplicitDelete,
     /// Insufficient credit
-    InsufficientCredit = 13,
+    InsufficientCredit,
+    /// Value this version does not know, kept as received
+    Unknown(char),
 }
 
 impl From<MdReqRejReason> for char {
@@ -197,10 +222,12 @@ impl From<MdReqRejReason> for char {
             MdReqRejReason::UnsupportedOpenCloseSettlFlag => 'B',
             MdReqRejReason::UnsupportedMdImplicitDelete => 'C',
             MdReqRejReason::InsufficientCredit => 'D',
+            MdReqRejReason::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`MdReqRejReason::Unknown`]
 impl TryFrom<char> for MdReqRejReason {
     type Error = String;
 
@@ -220,7 +247,7 @@ impl TryFrom<char> for MdReqRejReason {
             'B' => Ok(MdReqRejReason::UnsupportedOpenCloseSettlFlag),
             'C' => Ok(MdReqRejReason::UnsupportedMdImplicitDelete),
             'D' => Ok(MdReqRejReason::InsufficientCredit),
-            _ => Err(format!("Invalid MdReqRejReason: {value}")),
+            _ => Ok(MdReqRejReason::Unknown(value)),
         }
     }
 }
@@ -314,60 +341,66 @@ impl MarketDataRequest {
         sender_comp_id: String,
         target_comp_id: String,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MarketDataRequest)
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
             .sending_time(Utc::now())
-            .field(262, self.md_req_id.clone()) // MDReqID
-            .field(263, i32::from(self.subscription_request_type).to_string()); // SubscriptionRequestType
+            .field(tags::MD_REQ_ID, self.md_req_id.clone())
+            .field(
+                tags::SUBSCRIPTION_REQUEST_TYPE,
+                i32::from(self.subscription_request_type).to_string(),
+            );
 
         // Add optional fields
         if let Some(depth) = self.market_depth {
-            builder = builder.field(264, depth.to_string()); // MarketDepth
+            builder = builder.field(tags::MARKET_DEPTH, depth.to_string());
         }
 
         if let Some(update_type) = self.md_update_type {
-            builder = builder.field(265, i32::from(update_type).to_string()); // MDUpdateType
+            builder = builder.field(tags::MD_UPDATE_TYPE, i32::from(update_type).to_string());
         }
 
         // Add entry types group
-        builder = builder.field(267, self.entry_types.len().to_string()); // NoMDEntryTypes
+        builder = builder.field(tags::NO_MD_ENTRY_TYPES, self.entry_types.len().to_string());
         for entry_type in &self.entry_types {
-            builder = builder.field(269, i32::from(*entry_type).to_string()); // MDEntryType
+            builder = builder.push_field(tags::MD_ENTRY_TYPE, i32::from(*entry_type).to_string());
         }
 
         // Add Deribit-specific optional fields
         if let Some(skip_block_trades) = self.skip_block_trades {
-            builder = builder.field(9011, if skip_block_trades { "Y" } else { "N" }.to_string()); // DeribitSkipBlockTrades
+            builder = builder.field(
+                tags::DERIBIT_SKIP_BLOCK_TRADES,
+                if skip_block_trades { "Y" } else { "N" }.to_string(),
+            );
         }
 
         if let Some(show_block_trade_id) = self.show_block_trade_id {
             builder = builder.field(
-                9012,
+                tags::DERIBIT_SHOW_BLOCK_TRADE_ID,
                 if show_block_trade_id { "Y" } else { "N" }.to_string(),
             ); // DeribitShowBlockTradeId
         }
 
         if let Some(trade_amount) = self.trade_amount {
-            builder = builder.field(100007, trade_amount.to_string()); // DeribitTradeAmount
+            builder = builder.field(tags::DERIBIT_TRADE_AMOUNT, trade_amount.to_string());
         }
 
         if let Some(since_timestamp) = self.since_timestamp {
-            builder = builder.field(100008, since_timestamp.to_string()); // DeribitSinceTimestamp
+            builder = builder.field(tags::DERIBIT_SINCE_TIMESTAMP, since_timestamp.to_string());
         }
 
         // Add symbols group
         if !self.symbols.is_empty() {
-            builder = builder.field(146, self.symbols.len().to_string()); // NoRelatedSym
+            builder = builder.field(tags::NO_RELATED_SYM, self.symbols.len().to_string());
             for symbol in &self.symbols {
-                builder = builder.field(55, symbol.clone()); // Symbol
+                builder = builder.push_field(tags::SYMBOL, symbol.clone());
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -401,27 +434,48 @@ impl MarketDataRequestReject {
         }
     }
 
+    /// Error for a received Market Data Request Reject (Y)
+    ///
+    /// Keeps the exchange's Text (58); an unknown MDReqRejReason (281) is
+    /// left out rather than failing.
+    pub fn reject_error(message: &FixMessage) -> DeribitFixError {
+        DeribitFixError::MarketDataRejected {
+            md_req_id: message
+                .get_field(tags::MD_REQ_ID)
+                .cloned()
+                .unwrap_or_default(),
+            reason: message
+                .get_field(tags::MD_REQ_REJ_REASON)
+                .and_then(|value| value.chars().next())
+                .and_then(|value| MdReqRejReason::try_from(value).ok()),
+            text: message.get_field(tags::TEXT).cloned(),
+        }
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: String,
         target_comp_id: String,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MarketDataRequestReject)
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
             .sending_time(Utc::now())
-            .field(262, self.md_req_id.clone()) // MDReqID
-            .field(281, char::from(self.md_req_rej_reason).to_string()); // MDReqRejReason
+            .field(tags::MD_REQ_ID, self.md_req_id.clone())
+            .field(
+                tags::MD_REQ_REJ_REASON,
+                char::from(self.md_req_rej_reason).to_string(),
+            );
 
         if let Some(ref text) = self.text {
-            builder = builder.field(58, text.clone()); // Text
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -434,7 +488,7 @@ pub struct MdEntry {
     pub md_entry_px: Option<f64>,
     /// Size of entry (optional)
     pub md_entry_size: Option<f64>,
-    /// Timestamp for entry (optional)
+    /// Timestamp for entry, from MDEntryDate (272) and MDEntryTime (273) (optional)
     pub md_entry_date: Option<DateTime<Utc>>,
     /// Update action (for incremental refresh)
     pub md_update_action: Option<MdUpdateAction>,
@@ -458,6 +512,8 @@ pub struct MdEntry {
     pub deribit_liquidation: Option<String>,
     /// Block trade ID (snapshot-only)
     pub trd_match_id: Option<String>,
+    /// Per-instrument sequence number of the update (RptSeq, tag 83)
+    pub rpt_seq: Option<u64>,
 }
 
 impl MdEntry {
@@ -479,6 +535,7 @@ impl MdEntry {
             deribit_label: None,
             deribit_liquidation: None,
             trd_match_id: None,
+            rpt_seq: None,
         }
     }
 
@@ -500,6 +557,7 @@ impl MdEntry {
             deribit_label: None,
             deribit_liquidation: None,
             trd_match_id: None,
+            rpt_seq: None,
         }
     }
 
@@ -527,6 +585,7 @@ impl MdEntry {
             deribit_label: None,
             deribit_liquidation: None,
             trd_match_id: None,
+            rpt_seq: None,
         }
     }
 
@@ -535,6 +594,167 @@ impl MdEntry {
         self.md_update_action = Some(action);
         self
     }
+
+    /// Set the OrderID (37) of the entry
+    pub fn with_order_id(mut self, order_id: String) -> Self {
+        self.order_id = Some(order_id);
+        self
+    }
+
+    /// Set the per-instrument update sequence number (RptSeq)
+    pub fn with_rpt_seq(mut self, rpt_seq: u64) -> Self {
+        self.rpt_seq = Some(rpt_seq);
+        self
+    }
+
+    /// Create an empty entry of the given type, used while parsing repeating groups
+    fn empty(md_entry_type: MdEntryType) -> Self {
+        Self {
+            md_entry_type,
+            md_entry_px: None,
+            md_entry_size: None,
+            md_entry_date: None,
+            md_update_action: None,
+            trade_id: None,
+            side: None,
+            order_id: None,
+            secondary_order_id: None,
+            price: None,
+            text: None,
+            ord_status: None,
+            deribit_label: None,
+            deribit_liquidation: None,
+            trd_match_id: None,
+            rpt_seq: None,
+        }
+    }
+
+    /// Apply a single group field to the entry
+    fn apply_field(&mut self, tag: u32, value: &str) -> DeribitFixResult<()> {
+        match tag {
+            tags::MD_ENTRY_TYPE => {
+                let entry_type = value
+                    .parse::<i32>()
+                    .map_err(|_| invalid_field(tags::MD_ENTRY_TYPE, value))
+                    .and_then(|v| {
+                        MdEntryType::try_from(v).map_err(DeribitFixError::MessageParsing)
+                    })?;
+                self.md_entry_type = entry_type;
+            }
+            tags::MD_ENTRY_PX => {
+                self.md_entry_px = Some(
+                    value
+                        .parse()
+                        .map_err(|_| invalid_field(tags::MD_ENTRY_PX, value))?,
+                )
+            }
+            tags::MD_ENTRY_SIZE => {
+                self.md_entry_size = Some(
+                    value
+                        .parse()
+                        .map_err(|_| invalid_field(tags::MD_ENTRY_SIZE, value))?,
+                )
+            }
+            tags::MD_ENTRY_DATE => self.md_entry_date = Some(parse_md_entry_date(value)?),
+            tags::MD_ENTRY_TIME => {
+                let time = parse_tz_time_only(value)
+                    .map_err(|_| invalid_field(tags::MD_ENTRY_TIME, value))?;
+                // Without MDEntryDate the time refers to the current day
+                let date = self
+                    .md_entry_date
+                    .map_or_else(|| Utc::now().date_naive(), |date| date.date_naive());
+                self.md_entry_date = Some(date.and_time(time).and_utc());
+            }
+            tags::MD_UPDATE_ACTION => {
+                let action = value
+                    .chars()
+                    .next()
+                    .ok_or_else(|| invalid_field(tags::MD_UPDATE_ACTION, value))
+                    .and_then(|c| {
+                        MdUpdateAction::try_from(c).map_err(DeribitFixError::MessageParsing)
+                    })?;
+                self.md_update_action = Some(action);
+            }
+            tags::RPT_SEQ => {
+                self.rpt_seq = Some(
+                    value
+                        .parse()
+                        .map_err(|_| invalid_field(tags::RPT_SEQ, value))?,
+                )
+            }
+            tags::DERIBIT_TRADE_ID => self.trade_id = Some(value.to_string()),
+            tags::SIDE => self.side = value.chars().next(),
+            tags::ORDER_ID => self.order_id = Some(value.to_string()),
+            tags::SECONDARY_ORDER_ID => self.secondary_order_id = Some(value.to_string()),
+            tags::PRICE => self.price = value.parse().ok(),
+            tags::TEXT => self.text = Some(value.to_string()),
+            tags::ORD_STATUS => self.ord_status = value.chars().next(),
+            tags::DERIBIT_LABEL => self.deribit_label = Some(value.to_string()),
+            tags::DERIBIT_LIQUIDATION => self.deribit_liquidation = Some(value.to_string()),
+            tags::TRD_MATCH_ID => self.trd_match_id = Some(value.to_string()),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Parse MDEntryDate (272)
+///
+/// Deribit sends the entry time as milliseconds since the Unix epoch; a FIX
+/// UTCDateOnly or UTCTimestamp is accepted as well.
+fn parse_md_entry_date(value: &str) -> DeribitFixResult<DateTime<Utc>> {
+    let date = if value.len() == 8 {
+        parse_utc_date_only(value)
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc())
+    } else if value.bytes().all(|b| b.is_ascii_digit()) {
+        value
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_millis)
+    } else {
+        parse_utc_timestamp(value).ok()
+    };
+    date.ok_or_else(|| invalid_field(tags::MD_ENTRY_DATE, value))
+}
+
+fn invalid_field(tag: u32, value: &str) -> DeribitFixError {
+    DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+}
+
+/// Parse the NoMDEntries (268) repeating group of a market data message
+///
+/// `delimiter` is the first tag of each group instance: MDEntryType (269) for
+/// snapshots and MDUpdateAction (279) for incremental refreshes.
+fn parse_md_entries(message: &FixMessage, delimiter: u32) -> DeribitFixResult<Vec<MdEntry>> {
+    let mut entries = Vec::new();
+    let mut current: Option<MdEntry> = None;
+    let mut in_group = false;
+
+    for (tag, value) in &message.fields {
+        if *tag == tags::NO_MD_ENTRIES {
+            in_group = true;
+            continue;
+        }
+        if !in_group || *tag == tags::CHECKSUM {
+            continue;
+        }
+        if *tag == delimiter {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            }
+            current = Some(MdEntry::empty(MdEntryType::Bid));
+        }
+        if let Some(entry) = current.as_mut() {
+            entry.apply_field(*tag, value)?;
+        }
+    }
+
+    if let Some(entry) = current {
+        entries.push(entry);
+    }
+    Ok(entries)
 }
 
 /// Market Data Snapshot/Full Refresh message structure
@@ -651,123 +871,171 @@ impl MarketDataSnapshotFullRefresh {
         self
     }
 
+    /// Parse from FIX message
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let symbol = message
+            .get_field(tags::SYMBOL)
+            .ok_or_else(|| DeribitFixError::MessageParsing("Symbol (55) is required".to_string()))?
+            .clone();
+
+        let mut snapshot = Self::new(symbol);
+        snapshot.md_req_id = message.get_field(tags::MD_REQ_ID).cloned();
+        snapshot.underlying_symbol = message.get_field(tags::UNDERLYING_SYMBOL).cloned();
+        snapshot.underlying_px = message
+            .get_field(tags::UNDERLYING_PX)
+            .and_then(|v| v.parse().ok());
+        snapshot.contract_multiplier = message
+            .get_field(tags::CONTRACT_MULTIPLIER)
+            .and_then(|v| v.parse().ok());
+        snapshot.put_or_call = message
+            .get_field(tags::PUT_OR_CALL)
+            .and_then(|v| v.parse().ok());
+        snapshot.trade_volume_24h = message
+            .get_field(tags::TRADE_VOLUME_24H)
+            .and_then(|v| v.parse().ok());
+        snapshot.mark_price = message
+            .get_field(tags::MARK_PRICE)
+            .and_then(|v| v.parse().ok());
+        snapshot.open_interest = message
+            .get_field(tags::OPEN_INTEREST)
+            .and_then(|v| v.parse().ok());
+        snapshot.current_funding = message
+            .get_field(tags::CURRENT_FUNDING)
+            .and_then(|v| v.parse().ok());
+        snapshot.funding_8h = message
+            .get_field(tags::FUNDING_8H)
+            .and_then(|v| v.parse().ok());
+        snapshot.entries = parse_md_entries(message, tags::MD_ENTRY_TYPE)?;
+
+        Ok(snapshot)
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: String,
         target_comp_id: String,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MarketDataSnapshotFullRefresh)
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
             .sending_time(Utc::now())
-            .field(55, self.symbol.clone()); // Symbol
+            .field(tags::SYMBOL, self.symbol.clone());
 
         if let Some(ref md_req_id) = self.md_req_id {
-            builder = builder.field(262, md_req_id.clone()); // MDReqID
+            builder = builder.field(tags::MD_REQ_ID, md_req_id.clone());
         }
 
         // Add optional snapshot-specific fields
         if let Some(ref underlying_symbol) = self.underlying_symbol {
-            builder = builder.field(311, underlying_symbol.clone()); // UnderlyingSymbol
+            builder = builder.field(tags::UNDERLYING_SYMBOL, underlying_symbol.clone());
         }
 
         if let Some(underlying_px) = self.underlying_px {
-            builder = builder.field(810, underlying_px.to_string()); // UnderlyingPx
+            builder = builder.field(tags::UNDERLYING_PX, underlying_px.to_string());
         }
 
         if let Some(contract_multiplier) = self.contract_multiplier {
-            builder = builder.field(231, contract_multiplier.to_string()); // ContractMultiplier
+            builder = builder.field(tags::CONTRACT_MULTIPLIER, contract_multiplier.to_string());
         }
 
         if let Some(put_or_call) = self.put_or_call {
-            builder = builder.field(201, put_or_call.to_string()); // PutOrCall
+            builder = builder.field(tags::PUT_OR_CALL, put_or_call.to_string());
         }
 
         if let Some(trade_volume_24h) = self.trade_volume_24h {
-            builder = builder.field(100087, trade_volume_24h.to_string()); // TradeVolume24h
+            builder = builder.field(tags::TRADE_VOLUME_24H, trade_volume_24h.to_string());
         }
 
         if let Some(mark_price) = self.mark_price {
-            builder = builder.field(100090, mark_price.to_string()); // MarkPrice
+            builder = builder.field(tags::MARK_PRICE, mark_price.to_string());
         }
 
         if let Some(open_interest) = self.open_interest {
-            builder = builder.field(746, open_interest.to_string()); // OpenInterest
+            builder = builder.field(tags::OPEN_INTEREST, open_interest.to_string());
         }
 
         if let Some(current_funding) = self.current_funding {
-            builder = builder.field(100092, current_funding.to_string()); // CurrentFunding
+            builder = builder.field(tags::CURRENT_FUNDING, current_funding.to_string());
         }
 
         if let Some(funding_8h) = self.funding_8h {
-            builder = builder.field(100093, funding_8h.to_string()); // Funding8h
+            builder = builder.field(tags::FUNDING_8H, funding_8h.to_string());
         }
 
         // Add entries group
-        builder = builder.field(268, self.entries.len().to_string()); // NoMDEntries
+        builder = builder.field(tags::NO_MD_ENTRIES, self.entries.len().to_string());
 
         for entry in &self.entries {
-            builder = builder.field(269, i32::from(entry.md_entry_type).to_string()); // MDEntryType
+            builder = builder.push_field(
+                tags::MD_ENTRY_TYPE,
+                i32::from(entry.md_entry_type).to_string(),
+            );
 
             if let Some(px) = entry.md_entry_px {
-                builder = builder.field(270, px.to_string()); // MDEntryPx
+                builder = builder.push_field(tags::MD_ENTRY_PX, px.to_string());
             }
 
             if let Some(size) = entry.md_entry_size {
-                builder = builder.field(271, size.to_string()); // MDEntrySize
+                builder = builder.push_field(tags::MD_ENTRY_SIZE, size.to_string());
             }
 
             if let Some(date) = entry.md_entry_date {
-                builder = builder.field(272, date.timestamp_millis().to_string()); // MDEntryDate
+                builder = builder
+                    .push_field(
+                        tags::MD_ENTRY_DATE,
+                        format_utc_date_only(&date.date_naive()),
+                    )
+                    .push_field(tags::MD_ENTRY_TIME, format_utc_time_only(&date.time()));
             }
 
             if let Some(ref trade_id) = entry.trade_id {
-                builder = builder.field(100009, trade_id.clone()); // DeribitTradeId
+                builder = builder.push_field(tags::DERIBIT_TRADE_ID, trade_id.clone());
             }
 
             if let Some(side) = entry.side {
-                builder = builder.field(54, side.to_string()); // Side
+                builder = builder.push_field(tags::SIDE, side.to_string());
             }
 
             // Snapshot-only optional fields
             if let Some(price) = entry.price {
-                builder = builder.field(44, price.to_string()); // Price (index price at trade moment)
+                builder = builder.push_field(tags::PRICE, price.to_string()); // Price (index price at trade moment)
             }
 
             if let Some(ref text) = entry.text {
-                builder = builder.field(58, text.clone()); // Text (trade sequence number)
+                builder = builder.push_field(tags::TEXT, text.clone()); // Text (trade sequence number)
             }
 
             if let Some(ref order_id) = entry.order_id {
-                builder = builder.field(37, order_id.clone()); // OrderId (taker's matching order id)
+                builder = builder.push_field(tags::ORDER_ID, order_id.clone()); // OrderId (taker's matching order id)
             }
 
             if let Some(ref secondary_order_id) = entry.secondary_order_id {
-                builder = builder.field(198, secondary_order_id.clone()); // SecondaryOrderId (maker's matching order id)
+                builder = builder.push_field(tags::SECONDARY_ORDER_ID, secondary_order_id.clone()); // SecondaryOrderId (maker's matching order id)
             }
 
             if let Some(ord_status) = entry.ord_status {
-                builder = builder.field(39, ord_status.to_string()); // OrdStatus (order status)
+                builder = builder.push_field(tags::ORD_STATUS, ord_status.to_string()); // OrdStatus (order status)
             }
 
             if let Some(ref deribit_label) = entry.deribit_label {
-                builder = builder.field(100010, deribit_label.clone()); // DeribitLabel (user defined label)
+                builder = builder.push_field(tags::DERIBIT_LABEL, deribit_label.clone()); // DeribitLabel (user defined label)
             }
 
             if let Some(ref deribit_liquidation) = entry.deribit_liquidation {
-                builder = builder.field(100091, deribit_liquidation.clone()); // DeribitLiquidation (liquidation indicator)
+                builder =
+                    builder.push_field(tags::DERIBIT_LIQUIDATION, deribit_liquidation.clone()); // DeribitLiquidation (liquidation indicator)
             }
 
             if let Some(ref trd_match_id) = entry.trd_match_id {
-                builder = builder.field(880, trd_match_id.clone()); // TrdMatchID (block trade id)
+                builder = builder.push_field(tags::TRD_MATCH_ID, trd_match_id.clone()); // TrdMatchID (block trade id)
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -792,6 +1060,20 @@ impl MarketDataIncrementalRefresh {
         }
     }
 
+    /// Parse from FIX message
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let symbol = message
+            .get_field(tags::SYMBOL)
+            .ok_or_else(|| DeribitFixError::MessageParsing("Symbol (55) is required".to_string()))?
+            .clone();
+
+        Ok(Self {
+            symbol,
+            md_req_id: message.get_field(tags::MD_REQ_ID).cloned(),
+            entries: parse_md_entries(message, tags::MD_UPDATE_ACTION)?,
+        })
+    }
+
     /// Set request ID
     pub fn with_request_id(mut self, md_req_id: String) -> Self {
         self.md_req_id = Some(md_req_id);
@@ -810,51 +1092,60 @@ impl MarketDataIncrementalRefresh {
         sender_comp_id: String,
         target_comp_id: String,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MarketDataIncrementalRefresh)
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
             .sending_time(Utc::now())
-            .field(55, self.symbol.clone()); // Symbol
+            .field(tags::SYMBOL, self.symbol.clone());
 
         if let Some(ref md_req_id) = self.md_req_id {
-            builder = builder.field(262, md_req_id.clone()); // MDReqID
+            builder = builder.field(tags::MD_REQ_ID, md_req_id.clone());
         }
 
         // Add entries group
-        builder = builder.field(268, self.entries.len().to_string()); // NoMDEntries
+        builder = builder.field(tags::NO_MD_ENTRIES, self.entries.len().to_string());
 
         for entry in &self.entries {
             if let Some(action) = entry.md_update_action {
-                builder = builder.field(279, char::from(action).to_string()); // MDUpdateAction
+                builder =
+                    builder.push_field(tags::MD_UPDATE_ACTION, char::from(action).to_string());
             }
 
-            builder = builder.field(269, i32::from(entry.md_entry_type).to_string()); // MDEntryType
+            builder = builder.push_field(
+                tags::MD_ENTRY_TYPE,
+                i32::from(entry.md_entry_type).to_string(),
+            );
 
             if let Some(px) = entry.md_entry_px {
-                builder = builder.field(270, px.to_string()); // MDEntryPx
+                builder = builder.push_field(tags::MD_ENTRY_PX, px.to_string());
             }
 
             if let Some(size) = entry.md_entry_size {
-                builder = builder.field(271, size.to_string()); // MDEntrySize
+                builder = builder.push_field(tags::MD_ENTRY_SIZE, size.to_string());
             }
 
             if let Some(date) = entry.md_entry_date {
-                builder = builder.field(272, date.timestamp_millis().to_string()); // MDEntryDate
+                builder = builder
+                    .push_field(
+                        tags::MD_ENTRY_DATE,
+                        format_utc_date_only(&date.date_naive()),
+                    )
+                    .push_field(tags::MD_ENTRY_TIME, format_utc_time_only(&date.time()));
             }
 
             if let Some(ref trade_id) = entry.trade_id {
-                builder = builder.field(100009, trade_id.clone()); // DeribitTradeId
+                builder = builder.push_field(tags::DERIBIT_TRADE_ID, trade_id.clone());
             }
 
             if let Some(side) = entry.side {
-                builder = builder.field(54, side.to_string()); // Side
+                builder = builder.push_field(tags::SIDE, side.to_string());
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -892,7 +1183,8 @@ mod tests {
         assert_eq!(MdEntryType::try_from(0).unwrap(), MdEntryType::Bid);
         assert_eq!(MdEntryType::try_from(1).unwrap(), MdEntryType::Offer);
         assert_eq!(MdEntryType::try_from(2).unwrap(), MdEntryType::Trade);
-        assert!(MdEntryType::try_from(99).is_err());
+        assert_eq!(MdEntryType::try_from(99).unwrap(), MdEntryType::Unknown(99));
+        assert_eq!(i32::from(MdEntryType::Unknown(99)), 99);
     }
 
     #[test]
@@ -911,6 +1203,30 @@ mod tests {
         assert_eq!(request.entry_types.len(), 2);
     }
 
+    #[test]
+    fn test_market_data_request_writes_every_entry_type() {
+        let message = MarketDataRequest::snapshot(
+            "REQ123".to_string(),
+            vec!["BTC-PERPETUAL".to_string(), "ETH-PERPETUAL".to_string()],
+            vec![MdEntryType::Bid, MdEntryType::Offer],
+        )
+        .to_fix_message("CLIENT".to_string(), "DERIBIT".to_string(), 1)
+        .unwrap();
+
+        let values = |tag| {
+            message
+                .fields
+                .iter()
+                .filter(|(field_tag, _)| *field_tag == tag)
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(tags::NO_MD_ENTRY_TYPES), ["2"]);
+        assert_eq!(values(tags::MD_ENTRY_TYPE), ["0", "1"]);
+        assert_eq!(values(tags::NO_RELATED_SYM), ["2"]);
+        assert_eq!(values(tags::SYMBOL), ["BTC-PERPETUAL", "ETH-PERPETUAL"]);
+    }
+
     #[test]
     fn test_market_data_request_reject_creation() {
         let reject =
@@ -933,7 +1249,10 @@ mod tests {
             MdReqRejReason::try_from('D').unwrap(),
             MdReqRejReason::InsufficientCredit
         );
-        assert!(MdReqRejReason::try_from('Z').is_err());
+        assert_eq!(
+            MdReqRejReason::try_from('Z').unwrap(),
+            MdReqRejReason::Unknown('Z')
+        );
     }
 
     #[test]
@@ -999,6 +1318,103 @@ mod tests {
             MdUpdateAction::try_from('2').unwrap(),
             MdUpdateAction::Delete
         );
-        assert!(MdUpdateAction::try_from('9').is_err());
+        assert_eq!(
+            MdUpdateAction::try_from('9').unwrap(),
+            MdUpdateAction::Unknown('9')
+        );
+        assert_eq!(char::from(MdUpdateAction::Unknown('9')), '9');
+    }
+
+    #[test]
+    fn test_market_data_snapshot_from_fix_message() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=W\x0155=BTC-PERPETUAL\x01262=MDR1\x01100090=100.5\x01268=2\x01269=0\x01270=100\x01271=1.5\x01269=1\x01270=101\x01271=2\x0183=7\x0110=000\x01",
+        )
+        .unwrap();
+
+        let snapshot = MarketDataSnapshotFullRefresh::from_fix_message(&message).unwrap();
+        assert_eq!(snapshot.symbol, "BTC-PERPETUAL");
+        assert_eq!(snapshot.md_req_id, Some("MDR1".to_string()));
+        assert_eq!(snapshot.mark_price, Some(100.5));
+        assert_eq!(snapshot.entries.len(), 2);
+        assert_eq!(snapshot.entries[0].md_entry_type, MdEntryType::Bid);
+        assert_eq!(snapshot.entries[0].md_entry_px, Some(100.0));
+        assert_eq!(snapshot.entries[0].md_entry_size, Some(1.5));
+        assert_eq!(snapshot.entries[1].md_entry_type, MdEntryType::Offer);
+        assert_eq!(snapshot.entries[1].rpt_seq, Some(7));
+    }
+
+    #[test]
+    fn test_market_data_incremental_from_fix_message() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=X\x0155=BTC-PERPETUAL\x01268=2\x01279=0\x01269=0\x01270=99.5\x01271=3\x01279=2\x01269=1\x01270=101\x01271=0\x0110=000\x01",
+        )
+        .unwrap();
+
+        let update = MarketDataIncrementalRefresh::from_fix_message(&message).unwrap();
+        assert_eq!(update.entries.len(), 2);
+        assert_eq!(
+            update.entries[0].md_update_action,
+            Some(MdUpdateAction::New)
+        );
+        assert_eq!(update.entries[0].md_entry_type, MdEntryType::Bid);
+        assert_eq!(update.entries[0].md_entry_px, Some(99.5));
+        assert_eq!(
+            update.entries[1].md_update_action,
+            Some(MdUpdateAction::Delete)
+        );
+        assert_eq!(update.entries[1].md_entry_type, MdEntryType::Offer);
+    }
+
+    #[test]
+    fn test_md_entry_date_and_time_formats() {
+        let expected = DateTime::parse_from_rfc3339("2026-01-01T12:30:45.123Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=W\x0155=BTC-PERPETUAL\x01268=3\x01\
+             269=2\x01270=100\x01272=1767270645123\x01\
+             269=2\x01270=100\x01272=20260101-12:30:45.123\x01\
+             269=2\x01270=100\x01272=20260101\x01273=12:30:45.123\x0110=000\x01",
+        )
+        .unwrap();
+
+        let snapshot = MarketDataSnapshotFullRefresh::from_fix_message(&message).unwrap();
+        assert_eq!(snapshot.entries.len(), 3);
+        for entry in &snapshot.entries {
+            assert_eq!(entry.md_entry_date, Some(expected));
+        }
+
+        let invalid = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=W\x0155=BTC-PERPETUAL\x01268=1\x01269=2\x01272=yesterday\x0110=000\x01",
+        )
+        .unwrap();
+        assert!(MarketDataSnapshotFullRefresh::from_fix_message(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_md_entry_date_is_written_as_fix_date_and_time() {
+        let timestamp = DateTime::parse_from_rfc3339("2026-01-01T12:30:45.123Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let message = MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string())
+            .with_entries(vec![MdEntry::trade(
+                100.0,
+                1.0,
+                '1',
+                "T1".to_string(),
+                timestamp,
+            )])
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), 1)
+            .unwrap();
+
+        assert_eq!(message.get_field(272), Some(&"20260101".to_string()));
+        assert_eq!(message.get_field(273), Some(&"12:30:45.123".to_string()));
+    }
+
+    #[test]
+    fn test_market_data_from_fix_message_requires_symbol() {
+        let message = FixMessage::parse("8=FIX.4.4\x019=0\x0135=X\x01268=0\x0110=000\x01").unwrap();
+        assert!(MarketDataIncrementalRefresh::from_fix_message(&message).is_err());
     }
 }
@@ -10,6 +10,13 @@ pub mod admin;
 /// Message builder implementation
 pub mod builder;
 
+/// Message structs generated from the FIX dictionary, re-exported by their
+/// message family
+mod generated;
+
+/// Custom messages for types and tags without typed support
+pub mod custom;
+
 /// Security List Request and Security List messages
 pub mod security_list;
 
@@ -40,15 +47,38 @@ pub mod security_definition;
 /// Security status messages
 pub mod security_status;
 
+/// Message conversion traits
+pub mod traits;
+
+/// FIX date and time field formats
+pub mod time;
+
+/// Pretty printing and redaction of FIX messages for logs
+pub mod printer;
+
+/// Tag-by-tag comparison of FIX messages
+pub mod diff;
+
+/// JSON and tag=value export of FIX messages
+#[cfg(feature = "wire-formats")]
+pub mod wire;
+
 pub use admin::*;
 pub use builder::*;
+pub use custom::*;
+pub use diff::*;
 pub use market_data::*;
 pub use orders::*;
 pub use positions::*;
+pub use printer::*;
 pub use quotes::*;
 pub use risk::*;
 pub use security_definition::*;
 pub use security_list::*;
 pub use security_status::*;
+pub use time::*;
 pub use trade::*;
+pub use traits::*;
 pub use user::*;
+#[cfg(feature = "wire-formats")]
+pub use wire::*;
@@ -9,6 +9,8 @@
 use super::*;
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -77,13 +79,63 @@ impl OrderCancelReject {
         self
     }
 
+    /// Parse from FIX message
+    ///
+    /// Every field is optional; unknown CxlRejReason (102) values are kept as
+    /// sent and reported as [`CxlRejReason::Other`] by [`Self::reason`].
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let mut reject = Self::new(
+            message
+                .get_field(tags::ORD_STATUS)
+                .map(|value| parse_char(tags::ORD_STATUS, value))
+                .transpose()?,
+            message
+                .get_field(tags::CXL_REJ_REASON)
+                .map(|value| parse_field(tags::CXL_REJ_REASON, value))
+                .transpose()?,
+            message.get_field(tags::TEXT).cloned(),
+        );
+        if let Some(sending_time) = message.get_field(tags::SENDING_TIME) {
+            reject.sending_time = parse_timestamp(tags::SENDING_TIME, sending_time)?;
+        }
+        reject.cxl_rej_response_to = message
+            .get_field(tags::CXL_REJ_RESPONSE_TO)
+            .and_then(|value| value.chars().next());
+        reject.cl_ord_id = message.get_field(tags::CL_ORD_ID).cloned();
+        reject.orig_cl_ord_id = message.get_field(tags::ORIG_CL_ORD_ID).cloned();
+        reject.deribit_label = message.get_field(tags::DERIBIT_LABEL).cloned();
+        Ok(reject)
+    }
+
+    /// Typed CxlRejReason (102)
+    pub fn reason(&self) -> Option<CxlRejReason> {
+        self.cxl_rej_reason
+            .map(|reason| CxlRejReason::try_from(reason).unwrap_or(CxlRejReason::Unknown(reason)))
+    }
+
+    /// Typed CxlRejResponseTo (434)
+    pub fn response_to(&self) -> Option<CxlRejResponseTo> {
+        self.cxl_rej_response_to
+            .and_then(|response_to| CxlRejResponseTo::try_from(response_to).ok())
+    }
+
+    /// Convert into a [`DeribitFixError::CancelRejected`] for the order `order_id`
+    pub fn into_error(self, order_id: String) -> DeribitFixError {
+        DeribitFixError::CancelRejected {
+            order_id,
+            reason: self.reason(),
+            response_to: self.response_to(),
+            text: self.text,
+        }
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::OrderCancelReject)
             .sender_comp_id(sender_comp_id.to_string())
@@ -93,40 +145,40 @@ impl OrderCancelReject {
 
         // Required field
         builder = builder.field(
-            52,
+            tags::SENDING_TIME,
             self.sending_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
         );
 
         // Optional fields
         if let Some(ord_status) = &self.ord_status {
-            builder = builder.field(39, char::from(*ord_status).to_string());
+            builder = builder.field(tags::ORD_STATUS, char::from(*ord_status).to_string());
         }
 
         if let Some(cxl_rej_reason) = &self.cxl_rej_reason {
-            builder = builder.field(102, cxl_rej_reason.to_string());
+            builder = builder.field(tags::CXL_REJ_REASON, cxl_rej_reason.to_string());
         }
 
         if let Some(cxl_rej_response_to) = &self.cxl_rej_response_to {
-            builder = builder.field(434, cxl_rej_response_to.to_string());
+            builder = builder.field(tags::CXL_REJ_RESPONSE_TO, cxl_rej_response_to.to_string());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(cl_ord_id) = &self.cl_ord_id {
-            builder = builder.field(11, cl_ord_id.clone());
+            builder = builder.field(tags::CL_ORD_ID, cl_ord_id.clone());
         }
 
         if let Some(orig_cl_ord_id) = &self.orig_cl_ord_id {
-            builder = builder.field(41, orig_cl_ord_id.clone());
+            builder = builder.field(tags::ORIG_CL_ORD_ID, orig_cl_ord_id.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -136,6 +188,7 @@ impl_json_debug_pretty!(OrderCancelReject);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_order_cancel_reject_creation() {
@@ -196,7 +249,7 @@ mod tests {
         )
         .with_cl_ord_id("ORDER123".to_string());
 
-        let fix_message = reject.to_fix_message("DERIBITSERVER", "CLIENT", 1);
+        let fix_message = reject.to_fix_string("DERIBITSERVER", "CLIENT", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
@@ -211,11 +264,40 @@ mod tests {
     fn test_order_cancel_reject_minimal() {
         let reject = OrderCancelReject::new(None, None, None);
 
-        let fix_message = reject.to_fix_message("DERIBITSERVER", "CLIENT", 1);
+        let fix_message = reject.to_fix_string("DERIBITSERVER", "CLIENT", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
         assert!(message.contains("35=9")); // MsgType = OrderCancelReject
         assert!(message.contains("52=")); // SendingTime should be present
     }
+
+    #[test]
+    fn test_order_cancel_reject_from_fix_message() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=9\x0152=20260101-00:00:00.000\x0111=CANCEL_1\x0141=ETH-123\x0139=2\x01102=0\x01434=1\x0158=order already filled\x0110=000\x01",
+        )
+        .unwrap();
+        let reject = OrderCancelReject::from_fix_message(&message).unwrap();
+
+        assert_eq!(reject.ord_status, Some(OrderStatus::Filled));
+        assert_eq!(reject.reason(), Some(CxlRejReason::TooLateToCancel));
+        assert_eq!(
+            reject.response_to(),
+            Some(CxlRejResponseTo::OrderCancelRequest)
+        );
+        assert_eq!(reject.orig_cl_ord_id.as_deref(), Some("ETH-123"));
+
+        let error = reject.into_error("ETH-123".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Cancel rejected for ETH-123: order already filled"
+        );
+    }
+
+    #[test]
+    fn test_unknown_cxl_rej_reason_is_kept() {
+        let reject = OrderCancelReject::new(None, Some(42), None);
+        assert_eq!(reject.reason(), Some(CxlRejReason::Unknown(42)));
+    }
 }
@@ -9,6 +9,10 @@
 use super::*;
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
+use crate::message::time::{TimestampPrecision, format_utc_timestamp_with};
+use crate::model::exec_inst::{ExecInst, check_order_instructions};
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -36,6 +40,9 @@ pub struct OrderCancelReplaceRequest {
     pub time_in_force: Option<TimeInForce>,
     /// Stop price
     pub stop_px: Option<f64>,
+    /// Execution instructions (post-only, reduce-only)
+    #[serde(default)]
+    pub exec_inst: ExecInst,
     /// Display quantity
     pub display_qty: Option<f64>,
     /// Quantity type
@@ -44,6 +51,9 @@ pub struct OrderCancelReplaceRequest {
     pub deribit_label: Option<String>,
     /// Market Maker Protection flag
     pub deribit_mm_protection: Option<bool>,
+    /// Fractional seconds of SendingTime (52) and TransactTime (60)
+    #[serde(default)]
+    pub timestamp_precision: TimestampPrecision,
 }
 
 impl OrderCancelReplaceRequest {
@@ -60,10 +70,12 @@ impl OrderCancelReplaceRequest {
             ord_type: None,
             time_in_force: None,
             stop_px: None,
+            exec_inst: ExecInst::default(),
             display_qty: None,
             qty_type: None,
             deribit_label: None,
             deribit_mm_protection: None,
+            timestamp_precision: TimestampPrecision::default(),
         }
     }
 
@@ -97,12 +109,35 @@ impl OrderCancelReplaceRequest {
         self
     }
 
+    /// Set the execution instructions
+    pub fn with_exec_inst(mut self, exec_inst: ExecInst) -> Self {
+        self.exec_inst = exec_inst;
+        self
+    }
+
+    /// Keep the replaced order post only
+    pub fn post_only(mut self) -> Self {
+        self.exec_inst.post_only = true;
+        self
+    }
+
+    /// Keep the replaced order reduce only
+    pub fn reduce_only(mut self) -> Self {
+        self.exec_inst.reduce_only = true;
+        self
+    }
+
     /// Set display quantity
     pub fn with_display_qty(mut self, display_qty: f64) -> Self {
         self.display_qty = Some(display_qty);
         self
     }
 
+    /// Hide the whole order quantity from the book
+    pub fn hidden(self) -> Self {
+        self.with_display_qty(0.0)
+    }
+
     /// Set quantity type
     pub fn with_qty_type(mut self, qty_type: QuantityType) -> Self {
         self.qty_type = Some(qty_type);
@@ -121,72 +156,97 @@ impl OrderCancelReplaceRequest {
         self
     }
 
+    /// Set the fractional seconds of the timestamps
+    pub fn with_timestamp_precision(mut self, precision: TimestampPrecision) -> Self {
+        self.timestamp_precision = precision;
+        self
+    }
+
+    /// Check that the execution instructions, order type, time in force and
+    /// display quantity can be combined
+    pub fn validate(&self) -> DeribitFixResult<()> {
+        check_order_instructions(
+            self.exec_inst,
+            self.ord_type.is_some_and(OrderType::is_market),
+            self.time_in_force.is_some_and(TimeInForce::is_immediate),
+            self.display_qty,
+            self.order_qty,
+        )
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
+        self.validate()?;
+
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::OrderCancelReplaceRequest)
             .sender_comp_id(sender_comp_id.to_string())
             .target_comp_id(target_comp_id.to_string())
             .msg_seq_num(msg_seq_num)
+            .timestamp_precision(self.timestamp_precision)
             .sending_time(Utc::now());
 
         // Required fields
         builder = builder
-            .field(41, self.orig_cl_ord_id.clone()) // OrigClOrdID
-            .field(11, self.cl_ord_id.clone()) // ClOrdID
-            .field(55, self.symbol.clone()) // Symbol
-            .field(54, char::from(self.side).to_string()) // Side
+            .field(tags::ORIG_CL_ORD_ID, self.orig_cl_ord_id.clone())
+            .field(tags::CL_ORD_ID, self.cl_ord_id.clone())
+            .field(tags::SYMBOL, self.symbol.clone())
+            .field(tags::SIDE, char::from(self.side).to_string())
             .field(
-                60,
-                self.transact_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+                tags::TRANSACT_TIME,
+                format_utc_timestamp_with(&self.transact_time, self.timestamp_precision),
             ); // TransactTime
 
         // Optional fields
         if let Some(order_qty) = &self.order_qty {
-            builder = builder.field(38, order_qty.to_string());
+            builder = builder.field(tags::ORDER_QTY, order_qty.to_string());
         }
 
         if let Some(price) = &self.price {
-            builder = builder.field(44, price.to_string());
+            builder = builder.field(tags::PRICE, price.to_string());
         }
 
         if let Some(ord_type) = &self.ord_type {
-            builder = builder.field(40, char::from(*ord_type).to_string());
+            builder = builder.field(tags::ORD_TYPE, char::from(*ord_type).to_string());
         }
 
         if let Some(time_in_force) = &self.time_in_force {
-            builder = builder.field(59, char::from(*time_in_force).to_string());
+            builder = builder.field(tags::TIME_IN_FORCE, char::from(*time_in_force).to_string());
         }
 
         if let Some(stop_px) = &self.stop_px {
-            builder = builder.field(99, stop_px.to_string());
+            builder = builder.field(tags::STOP_PX, stop_px.to_string());
+        }
+
+        if let Some(exec_inst) = self.exec_inst.to_fix_value() {
+            builder = builder.field(tags::EXEC_INST, exec_inst);
         }
 
         if let Some(display_qty) = &self.display_qty {
-            builder = builder.field(1138, display_qty.to_string());
+            builder = builder.field(tags::DISPLAY_QTY, display_qty.to_string());
         }
 
         if let Some(qty_type) = &self.qty_type {
-            builder = builder.field(854, i32::from(*qty_type).to_string());
+            builder = builder.field(tags::QTY_TYPE, i32::from(*qty_type).to_string());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         if let Some(deribit_mm_protection) = &self.deribit_mm_protection {
             builder = builder.field(
-                9008,
+                tags::DERIBIT_MM_PROTECTION,
                 if *deribit_mm_protection { "Y" } else { "N" }.to_string(),
             );
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -196,6 +256,7 @@ impl_json_debug_pretty!(OrderCancelReplaceRequest);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_cancel_replace_request_creation() {
@@ -271,7 +332,7 @@ mod tests {
         .with_price(51000.0)
         .with_order_type(OrderType::Limit);
 
-        let fix_message = request.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=G")); // MsgType
@@ -284,6 +345,28 @@ mod tests {
         assert!(fix_message.contains("40=2")); // OrdType=Limit
     }
 
+    #[test]
+    fn test_cancel_replace_request_flags_are_encoded_and_checked() {
+        let request = OrderCancelReplaceRequest::new(
+            "ORIG123".to_string(),
+            "NEW123".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            OrderSide::Buy,
+        )
+        .with_qty(10.0)
+        .post_only()
+        .with_display_qty(2.0);
+
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 1).unwrap();
+        assert!(fix_message.contains("\x0118=6\x01"));
+        assert!(fix_message.contains("\x011138=2\x01"));
+
+        let market = request.clone().with_order_type(OrderType::Market);
+        assert!(market.to_fix_message("SENDER", "TARGET", 1).is_err());
+        let oversized = request.with_qty(1.0);
+        assert!(oversized.validate().is_err());
+    }
+
     #[test]
     fn test_cancel_replace_request_minimal_fix_message() {
         let request = OrderCancelReplaceRequest::new(
@@ -293,7 +376,7 @@ mod tests {
             OrderSide::Sell,
         );
 
-        let fix_message = request.to_fix_message("SENDER", "TARGET", 2).unwrap();
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 2).unwrap();
 
         // Check required fields only
         assert!(fix_message.contains("35=G")); // MsgType
@@ -319,7 +402,7 @@ mod tests {
         .with_label("strategy-v2".to_string())
         .with_mmp(false);
 
-        let fix_message = request.to_fix_message("SENDER", "TARGET", 3).unwrap();
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 3).unwrap();
 
         assert!(fix_message.contains("100010=strategy-v2")); // Custom label
         assert!(fix_message.contains("9008=N")); // MMP disabled
@@ -7,7 +7,11 @@
 //! Order Cancel Request FIX Message Implementation
 
 use crate::error::{DeribitFixError, Result as DeribitFixResult};
-use crate::{message::builder::MessageBuilder, model::types::MsgType};
+use crate::model::tags;
+use crate::{
+    message::builder::MessageBuilder,
+    model::{message::FixMessage, types::MsgType},
+};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
@@ -38,6 +42,14 @@ impl OrderCancelRequest {
         }
     }
 
+    /// Create cancel request by the exchange OrderID (37)
+    ///
+    /// Deribit expects its own order identifier, reported as OrderID (37) in
+    /// Execution Reports, in the OrigClOrdID (41) field of the cancel.
+    pub fn by_order_id(order_id: String) -> Self {
+        Self::by_orig_cl_ord_id(order_id)
+    }
+
     /// Create cancel request by client order ID
     pub fn by_cl_ord_id(cl_ord_id: String, symbol: String) -> Self {
         Self {
@@ -72,7 +84,7 @@ impl OrderCancelRequest {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::OrderCancelRequest)
             .sender_comp_id(sender_comp_id.to_string())
@@ -89,26 +101,26 @@ impl OrderCancelRequest {
         }
 
         if let Some(cl_ord_id) = &self.cl_ord_id {
-            builder = builder.field(11, cl_ord_id.clone());
+            builder = builder.field(tags::CL_ORD_ID, cl_ord_id.clone());
         }
 
         if let Some(orig_cl_ord_id) = &self.orig_cl_ord_id {
-            builder = builder.field(41, orig_cl_ord_id.clone());
+            builder = builder.field(tags::ORIG_CL_ORD_ID, orig_cl_ord_id.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(currency) = &self.currency {
-            builder = builder.field(15, currency.clone());
+            builder = builder.field(tags::CURRENCY, currency.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -118,6 +130,7 @@ impl_json_debug_pretty!(OrderCancelRequest);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_order_cancel_request_by_orig_cl_ord_id() {
@@ -166,7 +179,7 @@ mod tests {
     fn test_order_cancel_request_to_fix_message() {
         let cancel_request = OrderCancelRequest::by_orig_cl_ord_id("ORIG123".to_string());
 
-        let fix_message = cancel_request.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = cancel_request.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
@@ -184,7 +197,7 @@ mod tests {
             currency: None,
         };
 
-        let fix_message = cancel_request.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = cancel_request.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_err());
     }
 }
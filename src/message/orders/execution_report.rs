@@ -7,9 +7,13 @@
 //! Execution Report FIX Message Implementation
 
 use super::*;
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
-use crate::model::types::{ExecType, MsgType};
+use crate::model::exec_inst::SelfTradePrevention;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::{ExecType, Liquidation, MsgType};
+use crate::session::request_ids::next_request_id;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -88,15 +92,35 @@ pub struct ExecutionReport {
     pub quote_entry_id: Option<String>,
     /// Execution instruction
     pub exec_inst: Option<String>,
+    /// Self-trade prevention of the order
+    pub self_trade_prevention: Option<SelfTradePrevention>,
     /// Stop price
     pub stop_px: Option<f64>,
     /// Condition trigger method
     pub condition_trigger_method: Option<i32>,
     /// Last liquidity indicator (1=Added Liquidity, 2=Removed Liquidity)
     pub last_liquidity_ind: Option<i32>,
+    /// Side liquidated by the fill, DeribitLiquidation (100091)
+    #[serde(default)]
+    pub liquidation: Liquidation,
 }
 
 impl ExecutionReport {
+    /// Error for a rejected order or replace, `None` unless the report is a
+    /// rejection
+    ///
+    /// The error carries the OrdRejReason (103) and the exchange's Text (58).
+    pub fn reject_error(&self) -> Option<DeribitFixError> {
+        if self.exec_type != ExecType::Rejected && self.ord_status != OrderStatus::Rejected {
+            return None;
+        }
+        Some(DeribitFixError::OrderRejected {
+            cl_ord_id: self.cl_ord_id.clone(),
+            reason: self.ord_rej_reason,
+            text: self.text.clone(),
+        })
+    }
+
     /// Create a new execution report for a new order
     #[allow(clippy::too_many_arguments)]
     pub fn new_order(
@@ -146,9 +170,11 @@ impl ExecutionReport {
             quote_id: None,
             quote_entry_id: None,
             exec_inst: None,
+            self_trade_prevention: None,
             stop_px: None,
             condition_trigger_method: None,
             last_liquidity_ind: None,
+            liquidation: Liquidation::None,
         }
     }
 
@@ -208,9 +234,11 @@ impl ExecutionReport {
             quote_id: None,
             quote_entry_id: None,
             exec_inst: None,
+            self_trade_prevention: None,
             stop_px: None,
             condition_trigger_method: None,
             last_liquidity_ind: None,
+            liquidation: Liquidation::None,
         }
     }
 
@@ -227,7 +255,7 @@ impl ExecutionReport {
             order_id: String::new(),
             cl_ord_id,
             orig_cl_ord_id: None,
-            exec_id: format!("REJ{}", Utc::now().timestamp_millis()),
+            exec_id: next_request_id("REJ"),
             exec_type: ExecType::Rejected,
             ord_status: OrderStatus::Rejected,
             symbol,
@@ -260,9 +288,11 @@ impl ExecutionReport {
             quote_id: None,
             quote_entry_id: None,
             exec_inst: None,
+            self_trade_prevention: None,
             stop_px: None,
             condition_trigger_method: None,
             last_liquidity_ind: None,
+            liquidation: Liquidation::None,
         }
     }
 
@@ -272,13 +302,91 @@ impl ExecutionReport {
         self
     }
 
+    /// Parse from FIX message
+    ///
+    /// OrderID (37), ExecType (150), OrdStatus (39), Symbol (55) and Side (54)
+    /// are required; missing quantities default to zero and a missing
+    /// TransactTime (60) to the current time.
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let required = |tag: u32, name: &str| {
+            message.get_field(tag).ok_or_else(|| {
+                DeribitFixError::MessageParsing(format!("{name} ({tag}) is required"))
+            })
+        };
+        let optional = |tag: u32| -> DeribitFixResult<Option<f64>> {
+            message
+                .get_field(tag)
+                .map(|value| parse_field(tag, value))
+                .transpose()
+        };
+
+        let mut report = Self::new_order(
+            required(tags::ORDER_ID, "OrderID")?.clone(),
+            message
+                .get_field(tags::CL_ORD_ID)
+                .cloned()
+                .unwrap_or_default(),
+            message
+                .get_field(tags::EXEC_ID)
+                .cloned()
+                .unwrap_or_default(),
+            required(tags::SYMBOL, "Symbol")?.clone(),
+            parse_char(tags::SIDE, required(tags::SIDE, "Side")?)?,
+            optional(tags::ORDER_QTY)?.unwrap_or_default(),
+            optional(tags::LEAVES_QTY)?.unwrap_or_default(),
+            optional(tags::PRICE)?,
+        );
+        report.exec_type = parse_char(tags::EXEC_TYPE, required(tags::EXEC_TYPE, "ExecType")?)?;
+        report.ord_status = parse_char(tags::ORD_STATUS, required(tags::ORD_STATUS, "OrdStatus")?)?;
+        report.cum_qty = optional(tags::CUM_QTY)?.unwrap_or_default();
+        report.orig_cl_ord_id = message.get_field(tags::ORIG_CL_ORD_ID).cloned();
+        report.avg_px = optional(tags::AVG_PX)?;
+        report.last_px = optional(tags::LAST_PX)?;
+        report.last_qty = optional(tags::LAST_QTY)?;
+        report.commission = optional(tags::COMMISSION)?;
+        report.text = message.get_field(tags::TEXT).cloned();
+        report.deribit_label = message.get_field(tags::DERIBIT_LABEL).cloned();
+        report.secondary_exec_id = message.get_field(tags::SECONDARY_EXEC_ID).cloned();
+        report.trd_match_id = message.get_field(tags::TRD_MATCH_ID).cloned();
+        report.mmp_group = message.get_field(tags::MMP_GROUP).cloned();
+        report.exec_inst = message.get_field(tags::EXEC_INST).cloned();
+        report.self_trade_prevention = message
+            .get_field(tags::SELF_MATCH_PREVENTION_INSTRUCTION)
+            .map(|value| SelfTradePrevention::from_fix_value(value))
+            .transpose()?;
+        report.stop_px = optional(tags::STOP_PX)?;
+        report.display_qty = optional(tags::DISPLAY_QTY)?;
+        report.contract_multiplier = optional(tags::CONTRACT_MULTIPLIER)?;
+        if let Some(value) = message.get_field(tags::TRANSACT_TIME) {
+            report.transact_time = parse_timestamp(tags::TRANSACT_TIME, value)?;
+        }
+        if let Some(value) = message.get_field(tags::ORD_TYPE) {
+            report.ord_type = Some(parse_char(tags::ORD_TYPE, value)?);
+        }
+        if let Some(value) = message.get_field(tags::ORD_REJ_REASON) {
+            report.ord_rej_reason = Some(
+                OrderRejectReason::try_from(parse_field::<i32>(103, value)?)
+                    .map_err(DeribitFixError::MessageParsing)?,
+            );
+        }
+        if let Some(value) = message.get_field(tags::LAST_LIQUIDITY_IND) {
+            report.last_liquidity_ind = Some(parse_field(tags::LAST_LIQUIDITY_IND, value)?);
+        }
+        report.liquidation = Liquidation::from_fix_value(
+            message
+                .get_field(tags::DERIBIT_LIQUIDATION)
+                .map(String::as_str),
+        );
+        Ok(report)
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::ExecutionReport)
             .sender_comp_id(sender_comp_id.to_string())
@@ -288,139 +396,156 @@ impl ExecutionReport {
 
         // Required fields
         builder = builder
-            .field(37, self.order_id.clone()) // OrderID
-            .field(11, self.cl_ord_id.clone()) // ClOrdID
-            .field(17, self.exec_id.clone()) // ExecID
-            .field(150, char::from(self.exec_type).to_string()) // ExecType
-            .field(39, char::from(self.ord_status).to_string()) // OrdStatus
-            .field(55, self.symbol.clone()) // Symbol
-            .field(54, char::from(self.side).to_string()) // Side
-            .field(151, self.leaves_qty.to_string()) // LeavesQty
-            .field(14, self.cum_qty.to_string()) // CumQty
-            .field(38, self.order_qty.to_string()) // OrderQty
+            .field(tags::ORDER_ID, self.order_id.clone())
+            .field(tags::CL_ORD_ID, self.cl_ord_id.clone())
+            .field(tags::EXEC_ID, self.exec_id.clone())
+            .field(tags::EXEC_TYPE, char::from(self.exec_type).to_string())
+            .field(tags::ORD_STATUS, char::from(self.ord_status).to_string())
+            .field(tags::SYMBOL, self.symbol.clone())
+            .field(tags::SIDE, char::from(self.side).to_string())
+            .field(tags::LEAVES_QTY, self.leaves_qty.to_string())
+            .field(tags::CUM_QTY, self.cum_qty.to_string())
+            .field(tags::ORDER_QTY, self.order_qty.to_string())
             .field(
-                60,
+                tags::TRANSACT_TIME,
                 self.transact_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             ); // TransactTime
 
         // Optional fields
         if let Some(orig_cl_ord_id) = &self.orig_cl_ord_id {
-            builder = builder.field(41, orig_cl_ord_id.clone());
+            builder = builder.field(tags::ORIG_CL_ORD_ID, orig_cl_ord_id.clone());
         }
 
         if let Some(avg_px) = &self.avg_px {
-            builder = builder.field(6, avg_px.to_string());
+            builder = builder.field(tags::AVG_PX, avg_px.to_string());
         }
 
         if let Some(last_px) = &self.last_px {
-            builder = builder.field(31, last_px.to_string());
+            builder = builder.field(tags::LAST_PX, last_px.to_string());
         }
 
         if let Some(last_qty) = &self.last_qty {
-            builder = builder.field(32, last_qty.to_string());
+            builder = builder.field(tags::LAST_QTY, last_qty.to_string());
         }
 
         if let Some(price) = &self.price {
-            builder = builder.field(44, price.to_string());
+            builder = builder.field(tags::PRICE, price.to_string());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(reason) = &self.ord_rej_reason {
-            builder = builder.field(103, i32::from(*reason).to_string());
+            builder = builder.field(tags::ORD_REJ_REASON, i32::from(*reason).to_string());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         // Additional optional fields from specification
         if let Some(secondary_exec_id) = &self.secondary_exec_id {
-            builder = builder.field(527, secondary_exec_id.clone());
+            builder = builder.field(tags::SECONDARY_EXEC_ID, secondary_exec_id.clone());
         }
 
         if let Some(ord_type) = &self.ord_type {
-            builder = builder.field(40, char::from(*ord_type).to_string());
+            builder = builder.field(tags::ORD_TYPE, char::from(*ord_type).to_string());
         }
 
         if let Some(commission) = &self.commission {
-            builder = builder.field(12, commission.to_string());
+            builder = builder.field(tags::COMMISSION, commission.to_string());
         }
 
         if let Some(security_exchange) = &self.security_exchange {
-            builder = builder.field(207, security_exchange.clone());
+            builder = builder.field(tags::SECURITY_EXCHANGE, security_exchange.clone());
         }
 
         if let Some(qty_type) = &self.qty_type {
-            builder = builder.field(854, i32::from(*qty_type).to_string());
+            builder = builder.field(tags::QTY_TYPE, i32::from(*qty_type).to_string());
         }
 
         if let Some(contract_multiplier) = &self.contract_multiplier {
-            builder = builder.field(231, contract_multiplier.to_string());
+            builder = builder.field(tags::CONTRACT_MULTIPLIER, contract_multiplier.to_string());
         }
 
         if let Some(display_qty) = &self.display_qty {
-            builder = builder.field(1138, display_qty.to_string());
+            builder = builder.field(tags::DISPLAY_QTY, display_qty.to_string());
         }
 
         if let Some(deribit_adv_order_type) = &self.deribit_adv_order_type {
-            builder = builder.field(100012, deribit_adv_order_type.to_string());
+            builder = builder.field(
+                tags::DERIBIT_ADV_ORDER_TYPE,
+                deribit_adv_order_type.to_string(),
+            );
         }
 
         if let Some(volatility) = &self.volatility {
-            builder = builder.field(1188, volatility.to_string());
+            builder = builder.field(tags::VOLATILITY, volatility.to_string());
         }
 
         if let Some(pegged_price) = &self.pegged_price {
-            builder = builder.field(839, pegged_price.to_string());
+            builder = builder.field(tags::PEGGED_PRICE, pegged_price.to_string());
         }
 
         if let Some(trd_match_id) = &self.trd_match_id {
-            builder = builder.field(880, trd_match_id.clone());
+            builder = builder.field(tags::TRD_MATCH_ID, trd_match_id.clone());
         }
 
         if let Some(deribit_mm_protection) = &self.deribit_mm_protection {
             builder = builder.field(
-                9008,
+                tags::DERIBIT_MM_PROTECTION,
                 if *deribit_mm_protection { "Y" } else { "N" }.to_string(),
             );
         }
 
         if let Some(mmp_group) = &self.mmp_group {
-            builder = builder.field(9019, mmp_group.clone());
+            builder = builder.field(tags::MMP_GROUP, mmp_group.clone());
         }
 
         if let Some(quote_set_id) = &self.quote_set_id {
-            builder = builder.field(302, quote_set_id.clone());
+            builder = builder.field(tags::QUOTE_SET_ID, quote_set_id.clone());
         }
 
         if let Some(quote_id) = &self.quote_id {
-            builder = builder.field(117, quote_id.clone());
+            builder = builder.field(tags::QUOTE_ID, quote_id.clone());
         }
 
         if let Some(quote_entry_id) = &self.quote_entry_id {
-            builder = builder.field(299, quote_entry_id.clone());
+            builder = builder.field(tags::QUOTE_ENTRY_ID, quote_entry_id.clone());
         }
 
         if let Some(exec_inst) = &self.exec_inst {
-            builder = builder.field(18, exec_inst.clone());
+            builder = builder.field(tags::EXEC_INST, exec_inst.clone());
+        }
+
+        if let Some(stp) = self.self_trade_prevention {
+            builder = builder.field(
+                tags::SELF_MATCH_PREVENTION_INSTRUCTION,
+                stp.to_fix_value().to_string(),
+            );
         }
 
         if let Some(stop_px) = &self.stop_px {
-            builder = builder.field(99, stop_px.to_string());
+            builder = builder.field(tags::STOP_PX, stop_px.to_string());
         }
 
         if let Some(condition_trigger_method) = &self.condition_trigger_method {
-            builder = builder.field(5127, condition_trigger_method.to_string());
+            builder = builder.field(
+                tags::CONDITION_TRIGGER_METHOD,
+                condition_trigger_method.to_string(),
+            );
         }
 
         if let Some(last_liquidity_ind) = &self.last_liquidity_ind {
-            builder = builder.field(851, last_liquidity_ind.to_string());
+            builder = builder.field(tags::LAST_LIQUIDITY_IND, last_liquidity_ind.to_string());
         }
 
-        Ok(builder.build()?.to_string())
+        if let Some(liquidation) = self.liquidation.to_fix_value() {
+            builder = builder.field(tags::DERIBIT_LIQUIDATION, liquidation.to_string());
+        }
+
+        builder.build()
     }
 }
 
@@ -430,6 +555,7 @@ impl_json_debug_pretty!(ExecutionReport);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_execution_report_new_order() {
@@ -555,7 +681,7 @@ mod tests {
             Some(50000.0),
         );
 
-        let fix_message = report.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = report.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=8")); // MsgType
@@ -571,4 +697,35 @@ mod tests {
         assert!(fix_message.contains("38=10")); // OrderQty
         assert!(fix_message.contains("44=50000")); // Price
     }
+
+    #[test]
+    fn test_execution_report_from_fix_message() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=8\x0137=ORD123\x0111=CLORD123\x0117=EXEC123\x01150=4\x0139=4\x0155=BTC-PERPETUAL\x0154=2\x0138=10\x01151=0\x0114=3\x0144=50000\x0160=20260101-12:00:00.123\x012964=2\x01100010=mm\x01100091=M\x0110=000\x01",
+        )
+        .unwrap();
+        let report = ExecutionReport::from_fix_message(&message).unwrap();
+
+        assert_eq!(report.order_id, "ORD123");
+        assert_eq!(report.cl_ord_id, "CLORD123");
+        assert_eq!(report.exec_type, ExecType::Canceled);
+        assert_eq!(report.ord_status, OrderStatus::Cancelled);
+        assert_eq!(report.side, OrderSide::Sell);
+        assert_eq!(report.order_qty, 10.0);
+        assert_eq!(report.cum_qty, 3.0);
+        assert_eq!(report.price, Some(50000.0));
+        assert_eq!(report.deribit_label, Some("mm".to_string()));
+        assert_eq!(report.liquidation, Liquidation::Maker);
+        assert_eq!(
+            report.self_trade_prevention,
+            Some(SelfTradePrevention::CancelMaker)
+        );
+        assert_eq!(
+            report.transact_time.format("%H:%M:%S%.3f").to_string(),
+            "12:00:00.123"
+        );
+
+        let missing = FixMessage::parse("8=FIX.4.4\x019=0\x0135=8\x0111=X\x0110=000\x01").unwrap();
+        assert!(ExecutionReport::from_fix_message(&missing).is_err());
+    }
 }
@@ -9,6 +9,8 @@
 use super::*;
 use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -30,6 +32,8 @@ pub struct OrderMassCancelRequest {
     pub currency: Option<String>,
     /// Whether to reject incoming quotes for 1 second after cancelling
     pub freeze_quotes: Option<bool>,
+    /// Cancel only orders on this side
+    pub side: Option<OrderSide>,
 }
 
 impl OrderMassCancelRequest {
@@ -43,6 +47,7 @@ impl OrderMassCancelRequest {
             symbol: None,
             currency: None,
             freeze_quotes: None,
+            side: None,
         }
     }
 
@@ -56,6 +61,7 @@ impl OrderMassCancelRequest {
             symbol: Some(symbol),
             currency: None,
             freeze_quotes: None,
+            side: None,
         }
     }
 
@@ -69,6 +75,7 @@ impl OrderMassCancelRequest {
             symbol: None,
             currency: None,
             freeze_quotes: None,
+            side: None,
         }
     }
 
@@ -82,6 +89,7 @@ impl OrderMassCancelRequest {
             symbol: None,
             currency: None,
             freeze_quotes: None,
+            side: None,
         }
     }
 
@@ -97,13 +105,19 @@ impl OrderMassCancelRequest {
         self
     }
 
+    /// Restrict the cancel to one side of the book
+    pub fn with_side(mut self, side: OrderSide) -> Self {
+        self.side = Some(side);
+        self
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::OrderMassCancelRequest)
             .sender_comp_id(sender_comp_id.to_string())
@@ -113,14 +127,17 @@ impl OrderMassCancelRequest {
 
         // Required fields
         builder = builder
-            .field(11, self.cl_ord_id.clone()) // ClOrdID
-            .field(530, i32::from(self.mass_cancel_request_type).to_string()); // MassCancelRequestType
+            .field(tags::CL_ORD_ID, self.cl_ord_id.clone())
+            .field(
+                tags::MASS_CANCEL_REQUEST_TYPE,
+                i32::from(self.mass_cancel_request_type).to_string(),
+            );
 
         // Conditional required fields
         match self.mass_cancel_request_type {
             MassCancelRequestType::ByDeribitLabel => {
                 if let Some(deribit_label) = &self.deribit_label {
-                    builder = builder.field(100010, deribit_label.clone());
+                    builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
                 } else {
                     return Err(DeribitFixError::Generic(
                         "DeribitLabel is required for ByDeribitLabel mass cancel type".to_string(),
@@ -129,7 +146,7 @@ impl OrderMassCancelRequest {
             }
             MassCancelRequestType::BySecurityType => {
                 if let Some(security_type) = &self.security_type {
-                    builder = builder.field(167, security_type.clone());
+                    builder = builder.field(tags::SECURITY_TYPE, security_type.clone());
                 } else {
                     return Err(DeribitFixError::Generic(
                         "SecurityType is required for BySecurityType mass cancel type".to_string(),
@@ -138,7 +155,7 @@ impl OrderMassCancelRequest {
             }
             MassCancelRequestType::BySymbol => {
                 if let Some(symbol) = &self.symbol {
-                    builder = builder.field(55, symbol.clone());
+                    builder = builder.field(tags::SYMBOL, symbol.clone());
                 } else {
                     return Err(DeribitFixError::Generic(
                         "Symbol is required for BySymbol mass cancel type".to_string(),
@@ -152,14 +169,21 @@ impl OrderMassCancelRequest {
 
         // Optional fields
         if let Some(currency) = &self.currency {
-            builder = builder.field(15, currency.clone());
+            builder = builder.field(tags::CURRENCY, currency.clone());
         }
 
         if let Some(freeze_quotes) = &self.freeze_quotes {
-            builder = builder.field(9031, if *freeze_quotes { "Y" } else { "N" }.to_string());
+            builder = builder.field(
+                tags::FREEZE_QUOTES,
+                if *freeze_quotes { "Y" } else { "N" }.to_string(),
+            );
         }
 
-        Ok(builder.build()?.to_string())
+        if let Some(side) = &self.side {
+            builder = builder.field(tags::SIDE, char::from(*side).to_string());
+        }
+
+        builder.build()
     }
 }
 
@@ -236,13 +260,59 @@ impl OrderMassCancelReport {
         self
     }
 
+    /// Parse from FIX message
+    ///
+    /// Affected orders are read from every OrigClOrdID (41) of the
+    /// NoAffectedOrders (534) group.
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let request_type = message
+            .get_field(tags::MASS_CANCEL_REQUEST_TYPE)
+            .ok_or_else(|| {
+                DeribitFixError::MessageParsing(
+                    "MassCancelRequestType (530) is required".to_string(),
+                )
+            })?;
+        let mass_cancel_request_type =
+            MassCancelRequestType::try_from(parse_field::<i32>(530, request_type)?)
+                .map_err(DeribitFixError::MessageParsing)?;
+        let optional = |tag: u32| -> DeribitFixResult<Option<i32>> {
+            message
+                .get_field(tag)
+                .map(|value| parse_field(tag, value))
+                .transpose()
+        };
+
+        let mut report = Self::new(
+            message.get_field(tags::CL_ORD_ID).cloned(),
+            mass_cancel_request_type,
+        );
+        report.order_id = message.get_field(tags::ORDER_ID).cloned();
+        report.mass_cancel_response = optional(tags::MASS_CANCEL_RESPONSE)?;
+        report.mass_cancel_reject_reason = optional(tags::MASS_CANCEL_REJECT_REASON)?;
+        report.total_affected_orders = optional(tags::TOTAL_AFFECTED_ORDERS)?;
+        report.no_affected_orders = optional(tags::NO_AFFECTED_ORDERS)?;
+        report.affected_orig_cl_ord_ids = message
+            .fields
+            .iter()
+            .filter(|(tag, _)| *tag == tags::ORIG_CL_ORD_ID)
+            .map(|(_, value)| value.clone())
+            .collect();
+        report.text = message.get_field(tags::TEXT).cloned();
+        Ok(report)
+    }
+
+    /// Whether the request was rejected (MassCancelResponse (531) = 0)
+    pub fn is_rejected(&self) -> bool {
+        self.mass_cancel_response == Some(0)
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::OrderMassCancelReport)
             .sender_comp_id(sender_comp_id.to_string())
@@ -251,43 +321,52 @@ impl OrderMassCancelReport {
             .sending_time(Utc::now());
 
         // Required field
-        builder = builder.field(530, i32::from(self.mass_cancel_request_type).to_string());
+        builder = builder.field(
+            tags::MASS_CANCEL_REQUEST_TYPE,
+            i32::from(self.mass_cancel_request_type).to_string(),
+        );
 
         // Optional fields
         if let Some(cl_ord_id) = &self.cl_ord_id {
-            builder = builder.field(11, cl_ord_id.clone());
+            builder = builder.field(tags::CL_ORD_ID, cl_ord_id.clone());
         }
 
         if let Some(order_id) = &self.order_id {
-            builder = builder.field(37, order_id.clone());
+            builder = builder.field(tags::ORDER_ID, order_id.clone());
         }
 
         if let Some(mass_cancel_response) = &self.mass_cancel_response {
-            builder = builder.field(531, mass_cancel_response.to_string());
+            builder = builder.field(tags::MASS_CANCEL_RESPONSE, mass_cancel_response.to_string());
         }
 
         if let Some(mass_cancel_reject_reason) = &self.mass_cancel_reject_reason {
-            builder = builder.field(532, mass_cancel_reject_reason.to_string());
+            builder = builder.field(
+                tags::MASS_CANCEL_REJECT_REASON,
+                mass_cancel_reject_reason.to_string(),
+            );
         }
 
         if let Some(total_affected_orders) = &self.total_affected_orders {
-            builder = builder.field(533, total_affected_orders.to_string());
+            builder = builder.field(
+                tags::TOTAL_AFFECTED_ORDERS,
+                total_affected_orders.to_string(),
+            );
         }
 
         if let Some(no_affected_orders) = &self.no_affected_orders {
-            builder = builder.field(534, no_affected_orders.to_string());
+            builder = builder.field(tags::NO_AFFECTED_ORDERS, no_affected_orders.to_string());
         }
 
         // Add affected order IDs as repeating group
         for (i, order_id) in self.affected_orig_cl_ord_ids.iter().enumerate() {
-            builder = builder.field(535 + i as u32, order_id.clone());
+            builder = builder.field(tags::AFFECTED_ORDER_ID + i as u32, order_id.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -297,6 +376,7 @@ impl_json_debug_pretty!(OrderMassCancelReport);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_order_mass_cancel_request_all_orders() {
@@ -359,7 +439,7 @@ mod tests {
             .with_currency("BTC".to_string())
             .with_freeze_quotes(true);
 
-        let fix_message = request.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = request.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
@@ -400,7 +480,7 @@ mod tests {
         .with_response(7)
         .with_total_affected_orders(3);
 
-        let fix_message = report.to_fix_message("DERIBITSERVER", "CLIENT", 1);
+        let fix_message = report.to_fix_string("DERIBITSERVER", "CLIENT", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
@@ -410,4 +490,37 @@ mod tests {
         assert!(message.contains("531=7")); // MassCancelResponse
         assert!(message.contains("533=3")); // TotalAffectedOrders
     }
+
+    #[test]
+    fn test_order_mass_cancel_report_from_fix_message() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=r\x0111=MC1\x01530=10\x01531=10\x01533=2\x01534=2\x0141=A\x0141=B\x0110=000\x01",
+        )
+        .unwrap();
+        let report = OrderMassCancelReport::from_fix_message(&message).unwrap();
+
+        assert_eq!(report.cl_ord_id, Some("MC1".to_string()));
+        assert_eq!(
+            report.mass_cancel_request_type,
+            MassCancelRequestType::ByDeribitLabel
+        );
+        assert_eq!(report.total_affected_orders, Some(2));
+        assert_eq!(report.affected_orig_cl_ord_ids, vec!["A", "B"]);
+        assert!(!report.is_rejected());
+    }
+
+    #[test]
+    fn test_order_mass_cancel_request_by_symbol_and_side() {
+        let request =
+            OrderMassCancelRequest::by_symbol("MASS789".to_string(), "BTC-PERPETUAL".to_string())
+                .with_side(OrderSide::Sell);
+        let fix_message = request.to_fix_message("CLIENT", "DERIBIT", 1).unwrap();
+
+        assert_eq!(fix_message.get_field(530), Some(&"1".to_string()));
+        assert_eq!(
+            fix_message.get_field(55),
+            Some(&"BTC-PERPETUAL".to_string())
+        );
+        assert_eq!(fix_message.get_field(54), Some(&"2".to_string()));
+    }
 }
@@ -9,6 +9,8 @@
 use super::*;
 use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -99,7 +101,7 @@ impl OrderMassStatusRequest {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::OrderMassStatusRequest)
             .sender_comp_id(sender_comp_id.to_string())
@@ -109,12 +111,18 @@ impl OrderMassStatusRequest {
 
         // Required fields
         builder = builder
-            .field(584, self.mass_status_req_id.clone()) // MassStatusReqID
-            .field(585, i32::from(self.mass_status_req_type).to_string()); // MassStatusReqType
+            .field(tags::MASS_STATUS_REQ_ID, self.mass_status_req_id.clone())
+            .field(
+                tags::MASS_STATUS_REQ_TYPE,
+                i32::from(self.mass_status_req_type).to_string(),
+            );
 
         // Optional fields
         if let Some(mass_status_req_id_type) = &self.mass_status_req_id_type {
-            builder = builder.field(9014, i32::from(*mass_status_req_id_type).to_string());
+            builder = builder.field(
+                tags::MASS_STATUS_REQ_ID_TYPE,
+                i32::from(*mass_status_req_id_type).to_string(),
+            );
         }
 
         // Validation: Currency or Symbol required if MassStatusReqIDType is ClOrdId or DeribitLabel
@@ -132,14 +140,14 @@ impl OrderMassStatusRequest {
         }
 
         if let Some(currency) = &self.currency {
-            builder = builder.field(15, currency.clone()); // Note: Using tag 15 for Currency, but doc shows tag 11
+            builder = builder.field(tags::CURRENCY, currency.clone()); // Note: Using tag 15 for Currency, but doc shows tag 11
         }
 
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -149,6 +157,7 @@ impl_json_debug_pretty!(OrderMassStatusRequest);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_order_mass_status_request_all_orders() {
@@ -234,7 +243,7 @@ mod tests {
     fn test_order_mass_status_request_to_fix_message() {
         let request = OrderMassStatusRequest::all_orders("STATUS123".to_string());
 
-        let fix_message = request.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = request.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
@@ -248,7 +257,7 @@ mod tests {
         let request =
             OrderMassStatusRequest::specific_order_by_orig_cl_ord_id("ORIG123".to_string());
 
-        let fix_message = request.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = request.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
@@ -266,7 +275,7 @@ mod tests {
             None, // No symbol
         );
 
-        let fix_message = request.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = request.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_err());
     }
 
@@ -278,7 +287,7 @@ mod tests {
             Some("BTC-PERPETUAL".to_string()),
         );
 
-        let fix_message = request.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = request.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
@@ -6,8 +6,12 @@
 
 //! Order Management FIX Messages Module
 
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
+use crate::message::time::parse_utc_timestamp;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
+use std::str::FromStr;
 
 pub mod cancel_reject;
 pub mod cancel_replace_request;
@@ -25,6 +29,31 @@ pub use mass_cancel::*;
 pub use mass_status::*;
 pub use new_order::*;
 
+/// Parse a field value, reporting the tag on failure
+fn parse_field<T: FromStr>(tag: u32, value: &str) -> DeribitFixResult<T> {
+    value.parse().map_err(|_| {
+        DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+    })
+}
+
+/// Parse a single character enumeration field such as Side (54)
+fn parse_char<T: TryFrom<char, Error = String>>(tag: u32, value: &str) -> DeribitFixResult<T> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => T::try_from(c).map_err(DeribitFixError::MessageParsing),
+        _ => Err(DeribitFixError::MessageParsing(format!(
+            "Invalid value for tag {tag}: {value}"
+        ))),
+    }
+}
+
+/// Parse a UTCTimestamp field
+fn parse_timestamp(tag: u32, value: &str) -> DeribitFixResult<DateTime<Utc>> {
+    parse_utc_timestamp(value).map_err(|_| {
+        DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+    })
+}
+
 /// Order side enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderSide {
@@ -72,6 +101,16 @@ pub enum OrderType {
     StopLimitOnBidOffer,
 }
 
+impl OrderType {
+    /// Whether the order executes at market, on entry or when triggered
+    pub fn is_market(self) -> bool {
+        matches!(
+            self,
+            OrderType::Market | OrderType::MarketLimit | OrderType::StopLimitOnBidOffer
+        )
+    }
+}
+
 impl From<OrderType> for char {
     fn from(order_type: OrderType) -> Self {
         match order_type {
@@ -114,6 +153,16 @@ pub enum TimeInForce {
     FillOrKill,
 }
 
+impl TimeInForce {
+    /// Whether the order is cancelled unless it executes on entry
+    pub fn is_immediate(self) -> bool {
+        matches!(
+            self,
+            TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill
+        )
+    }
+}
+
 impl From<TimeInForce> for char {
     fn from(tif: TimeInForce) -> Self {
         match tif {
@@ -139,8 +188,9 @@ impl TryFrom<char> for TimeInForce {
     }
 }
 
-/// Order status enumeration
+/// Order status enumeration, OrdStatus (39)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum OrderStatus {
     /// New order
     New,
@@ -148,12 +198,47 @@ pub enum OrderStatus {
     PartiallyFilled,
     /// Filled
     Filled,
+    /// Done for day
+    DoneForDay,
     /// Cancelled
     Cancelled,
+    /// Replaced, sent by FIX 4.2 counterparties for an amended order
+    Replaced,
     /// Pending cancel
     PendingCancel,
+    /// Stopped
+    Stopped,
     /// Rejected
     Rejected,
+    /// Suspended
+    Suspended,
+    /// Pending new, accepted but not yet on the book; Deribit reports
+    /// untriggered stop and take-profit orders this way
+    PendingNew,
+    /// Calculated
+    Calculated,
+    /// Expired at the end of its time in force
+    Expired,
+    /// Accepted for bidding
+    AcceptedForBidding,
+    /// Pending replace
+    PendingReplace,
+    /// Value this version does not know, kept as received
+    Unknown(char),
+}
+
+impl OrderStatus {
+    /// Whether the order reached a final state: filled, cancelled, rejected
+    /// or expired
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled
+                | OrderStatus::Cancelled
+                | OrderStatus::Rejected
+                | OrderStatus::Expired
+        )
+    }
 }
 
 impl From<OrderStatus> for char {
@@ -162,13 +247,24 @@ impl From<OrderStatus> for char {
             OrderStatus::New => '0',
             OrderStatus::PartiallyFilled => '1',
             OrderStatus::Filled => '2',
+            OrderStatus::DoneForDay => '3',
             OrderStatus::Cancelled => '4',
+            OrderStatus::Replaced => '5',
             OrderStatus::PendingCancel => '6',
+            OrderStatus::Stopped => '7',
             OrderStatus::Rejected => '8',
+            OrderStatus::Suspended => '9',
+            OrderStatus::PendingNew => 'A',
+            OrderStatus::Calculated => 'B',
+            OrderStatus::Expired => 'C',
+            OrderStatus::AcceptedForBidding => 'D',
+            OrderStatus::PendingReplace => 'E',
+            OrderStatus::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`OrderStatus::Unknown`]
 impl TryFrom<char> for OrderStatus {
     type Error = String;
 
@@ -177,16 +273,26 @@ impl TryFrom<char> for OrderStatus {
             '0' => Ok(OrderStatus::New),
             '1' => Ok(OrderStatus::PartiallyFilled),
             '2' => Ok(OrderStatus::Filled),
+            '3' => Ok(OrderStatus::DoneForDay),
             '4' => Ok(OrderStatus::Cancelled),
+            '5' => Ok(OrderStatus::Replaced),
             '6' => Ok(OrderStatus::PendingCancel),
+            '7' => Ok(OrderStatus::Stopped),
             '8' => Ok(OrderStatus::Rejected),
-            _ => Err(format!("Invalid OrderStatus: {value}")),
+            '9' => Ok(OrderStatus::Suspended),
+            'A' => Ok(OrderStatus::PendingNew),
+            'B' => Ok(OrderStatus::Calculated),
+            'C' => Ok(OrderStatus::Expired),
+            'D' => Ok(OrderStatus::AcceptedForBidding),
+            'E' => Ok(OrderStatus::PendingReplace),
+            _ => Ok(OrderStatus::Unknown(value)),
         }
     }
 }
 
 /// Order rejection reason enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum OrderRejectReason {
     /// No reject (accepted)
     NoReject,
@@ -226,6 +332,8 @@ pub enum OrderRejectReason {
     InvalidPriceIncrement,
     /// Other
     Other,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<OrderRejectReason> for i32 {
@@ -250,10 +358,12 @@ impl From<OrderRejectReason> for i32 {
             OrderRejectReason::PriceExceedsPriceBand => 16,
             OrderRejectReason::InvalidPriceIncrement => 18,
             OrderRejectReason::Other => 99,
+            OrderRejectReason::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`OrderRejectReason::Unknown`]
 impl TryFrom<i32> for OrderRejectReason {
     type Error = String;
 
@@ -278,7 +388,88 @@ impl TryFrom<i32> for OrderRejectReason {
             16 => Ok(OrderRejectReason::PriceExceedsPriceBand),
             18 => Ok(OrderRejectReason::InvalidPriceIncrement),
             99 => Ok(OrderRejectReason::Other),
-            _ => Err(format!("Invalid OrderRejectReason: {value}")),
+            _ => Ok(OrderRejectReason::Unknown(value)),
+        }
+    }
+}
+
+/// Cancel reject reason (CxlRejReason, tag 102)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CxlRejReason {
+    /// Too late to cancel
+    TooLateToCancel,
+    /// Unknown order
+    UnknownOrder,
+    /// Broker option
+    BrokerOption,
+    /// Order already in pending cancel or pending replace status
+    AlreadyPending,
+    /// Duplicate ClOrdID received
+    DuplicateClOrdId,
+    /// Other
+    Other,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
+}
+
+impl From<CxlRejReason> for i32 {
+    fn from(reason: CxlRejReason) -> Self {
+        match reason {
+            CxlRejReason::TooLateToCancel => 0,
+            CxlRejReason::UnknownOrder => 1,
+            CxlRejReason::BrokerOption => 2,
+            CxlRejReason::AlreadyPending => 3,
+            CxlRejReason::DuplicateClOrdId => 6,
+            CxlRejReason::Other => 99,
+            CxlRejReason::Unknown(value) => value,
+        }
+    }
+}
+
+/// Never fails: values this version does not know become [`CxlRejReason::Unknown`]
+impl TryFrom<i32> for CxlRejReason {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CxlRejReason::TooLateToCancel),
+            1 => Ok(CxlRejReason::UnknownOrder),
+            2 => Ok(CxlRejReason::BrokerOption),
+            3 => Ok(CxlRejReason::AlreadyPending),
+            6 => Ok(CxlRejReason::DuplicateClOrdId),
+            99 => Ok(CxlRejReason::Other),
+            _ => Ok(CxlRejReason::Unknown(value)),
+        }
+    }
+}
+
+/// Request type an Order Cancel Reject answers (CxlRejResponseTo, tag 434)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CxlRejResponseTo {
+    /// Order Cancel Request (F)
+    OrderCancelRequest,
+    /// Order Cancel/Replace Request (G)
+    OrderCancelReplaceRequest,
+}
+
+impl From<CxlRejResponseTo> for char {
+    fn from(response_to: CxlRejResponseTo) -> Self {
+        match response_to {
+            CxlRejResponseTo::OrderCancelRequest => '1',
+            CxlRejResponseTo::OrderCancelReplaceRequest => '2',
+        }
+    }
+}
+
+impl TryFrom<char> for CxlRejResponseTo {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '1' => Ok(CxlRejResponseTo::OrderCancelRequest),
+            '2' => Ok(CxlRejResponseTo::OrderCancelReplaceRequest),
+            _ => Err(format!("Invalid CxlRejResponseTo: {value}")),
         }
     }
 }
@@ -482,7 +673,20 @@ mod tests {
         );
         assert_eq!(OrderStatus::try_from('2').unwrap(), OrderStatus::Filled);
         assert_eq!(OrderStatus::try_from('4').unwrap(), OrderStatus::Cancelled);
-        assert!(OrderStatus::try_from('9').is_err());
+        assert_eq!(OrderStatus::try_from('9').unwrap(), OrderStatus::Suspended);
+        assert_eq!(OrderStatus::try_from('A').unwrap(), OrderStatus::PendingNew);
+        assert_eq!(OrderStatus::try_from('C').unwrap(), OrderStatus::Expired);
+        assert_eq!(
+            OrderStatus::try_from('Z').unwrap(),
+            OrderStatus::Unknown('Z')
+        );
+        for value in "0123456789ABCDE".chars() {
+            assert_eq!(char::from(OrderStatus::try_from(value).unwrap()), value);
+        }
+
+        assert!(OrderStatus::Expired.is_terminal());
+        assert!(!OrderStatus::PendingNew.is_terminal());
+        assert!(!OrderStatus::Unknown('Z').is_terminal());
     }
 
     #[test]
@@ -503,7 +707,10 @@ mod tests {
             OrderRejectReason::try_from(99).unwrap(),
             OrderRejectReason::Other
         );
-        assert!(OrderRejectReason::try_from(100).is_err());
+        assert_eq!(
+            OrderRejectReason::try_from(100).unwrap(),
+            OrderRejectReason::Unknown(100)
+        );
     }
 
     #[test]
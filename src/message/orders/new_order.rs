@@ -9,6 +9,9 @@
 use super::*;
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
+use crate::model::exec_inst::{ExecInst, SelfTradePrevention, check_order_instructions};
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -28,8 +31,10 @@ pub struct NewOrderSingle {
     pub symbol: String,
     /// Indicates expiration time of indication message
     pub valid_until_time: Option<DateTime<Utc>>,
-    /// Execution instructions (POST ONLY, REDUCE ONLY)
-    pub exec_inst: Option<String>,
+    /// Execution instructions (post-only, reduce-only)
+    pub exec_inst: ExecInst,
+    /// Self-trade prevention
+    pub self_trade_prevention: Option<SelfTradePrevention>,
     /// Order type
     pub ord_type: Option<OrderType>,
     /// Time in force
@@ -66,7 +71,8 @@ impl NewOrderSingle {
             price: 0.0, // Market orders don't need price
             symbol,
             valid_until_time: None,
-            exec_inst: None,
+            exec_inst: ExecInst::default(),
+            self_trade_prevention: None,
             ord_type: Some(OrderType::Market),
             time_in_force: None,
             stop_px: None,
@@ -97,7 +103,8 @@ impl NewOrderSingle {
             price,
             symbol,
             valid_until_time: None,
-            exec_inst: None,
+            exec_inst: ExecInst::default(),
+            self_trade_prevention: None,
             ord_type: Some(OrderType::Limit),
             time_in_force: None,
             stop_px: None,
@@ -127,19 +134,30 @@ impl NewOrderSingle {
 
     /// Set as post only order
     pub fn post_only(mut self) -> Self {
-        self.exec_inst = Some("6".to_string());
+        self.exec_inst.post_only = true;
         self
     }
 
     /// Set as reduce only order
     pub fn reduce_only(mut self) -> Self {
-        self.exec_inst = Some("E".to_string());
+        self.exec_inst.reduce_only = true;
         self
     }
 
     /// Set as post only and reduce only order
-    pub fn post_only_reduce_only(mut self) -> Self {
-        self.exec_inst = Some("6E".to_string());
+    pub fn post_only_reduce_only(self) -> Self {
+        self.post_only().reduce_only()
+    }
+
+    /// Set the execution instructions
+    pub fn with_exec_inst(mut self, exec_inst: ExecInst) -> Self {
+        self.exec_inst = exec_inst;
+        self
+    }
+
+    /// Set the self-trade prevention
+    pub fn with_self_trade_prevention(mut self, stp: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = Some(stp);
         self
     }
 
@@ -155,12 +173,29 @@ impl NewOrderSingle {
         self
     }
 
+    /// Hide the whole order quantity from the book
+    pub fn hidden(self) -> Self {
+        self.with_display_qty(0.0)
+    }
+
     /// Set quantity type
     pub fn with_qty_type(mut self, qty_type: QuantityType) -> Self {
         self.qty_type = Some(qty_type);
         self
     }
 
+    /// Check that the execution instructions, order type, time in force and
+    /// display quantity can be combined
+    pub fn validate(&self) -> DeribitFixResult<()> {
+        check_order_instructions(
+            self.exec_inst,
+            self.ord_type.is_some_and(OrderType::is_market),
+            self.time_in_force.is_some_and(TimeInForce::is_immediate),
+            self.display_qty,
+            Some(self.order_qty),
+        )
+    }
+
     /// Set Market Maker Protection flag
     pub fn with_mmp(mut self, enabled: bool) -> Self {
         self.deribit_mm_protection = Some(enabled);
@@ -173,7 +208,9 @@ impl NewOrderSingle {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
+        self.validate()?;
+
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::NewOrderSingle)
             .sender_comp_id(sender_comp_id.to_string())
@@ -183,76 +220,89 @@ impl NewOrderSingle {
 
         // Required fields
         builder = builder
-            .field(11, self.cl_ord_id.clone()) // ClOrdID
-            .field(54, char::from(self.side).to_string()) // Side
-            .field(38, self.order_qty.to_string()) // OrderQty
-            .field(44, self.price.to_string()) // Price
-            .field(55, self.symbol.clone()); // Symbol
+            .field(tags::CL_ORD_ID, self.cl_ord_id.clone())
+            .field(tags::SIDE, char::from(self.side).to_string())
+            .field(tags::ORDER_QTY, self.order_qty.to_string())
+            .field(tags::PRICE, self.price.to_string())
+            .field(tags::SYMBOL, self.symbol.clone());
 
         // Optional fields
         if let Some(valid_until_time) = &self.valid_until_time {
             builder = builder.field(
-                62,
+                tags::VALID_UNTIL_TIME,
                 valid_until_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             );
         }
 
-        if let Some(exec_inst) = &self.exec_inst {
-            builder = builder.field(18, exec_inst.clone());
+        if let Some(exec_inst) = self.exec_inst.to_fix_value() {
+            builder = builder.field(tags::EXEC_INST, exec_inst);
+        }
+
+        if let Some(stp) = self.self_trade_prevention {
+            builder = builder.field(
+                tags::SELF_MATCH_PREVENTION_INSTRUCTION,
+                stp.to_fix_value().to_string(),
+            );
         }
 
         if let Some(ord_type) = &self.ord_type {
-            builder = builder.field(40, char::from(*ord_type).to_string());
+            builder = builder.field(tags::ORD_TYPE, char::from(*ord_type).to_string());
         }
 
         if let Some(time_in_force) = &self.time_in_force {
-            builder = builder.field(59, char::from(*time_in_force).to_string());
+            builder = builder.field(tags::TIME_IN_FORCE, char::from(*time_in_force).to_string());
         }
 
         if let Some(stop_px) = &self.stop_px {
-            builder = builder.field(99, stop_px.to_string());
+            builder = builder.field(tags::STOP_PX, stop_px.to_string());
         }
 
         if let Some(display_qty) = &self.display_qty {
-            builder = builder.field(1138, display_qty.to_string());
+            builder = builder.field(tags::DISPLAY_QTY, display_qty.to_string());
         }
 
         if let Some(refresh_qty) = &self.refresh_qty {
-            builder = builder.field(1088, refresh_qty.to_string());
+            builder = builder.field(tags::REFRESH_QTY, refresh_qty.to_string());
         }
 
         if let Some(qty_type) = &self.qty_type {
-            builder = builder.field(854, i32::from(*qty_type).to_string());
+            builder = builder.field(tags::QTY_TYPE, i32::from(*qty_type).to_string());
         }
 
         if let Some(peg_offset_value) = &self.peg_offset_value {
-            builder = builder.field(211, peg_offset_value.to_string());
+            builder = builder.field(tags::PEG_OFFSET_VALUE, peg_offset_value.to_string());
         }
 
         if let Some(peg_price_type) = &self.peg_price_type {
-            builder = builder.field(1094, peg_price_type.to_string());
+            builder = builder.field(tags::PEG_PRICE_TYPE, peg_price_type.to_string());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         if let Some(deribit_adv_order_type) = &self.deribit_adv_order_type {
-            builder = builder.field(100012, deribit_adv_order_type.to_string());
+            builder = builder.field(
+                tags::DERIBIT_ADV_ORDER_TYPE,
+                deribit_adv_order_type.to_string(),
+            );
         }
 
         if let Some(deribit_mm_protection) = &self.deribit_mm_protection {
             builder = builder.field(
-                9008,
+                tags::DERIBIT_MM_PROTECTION,
                 if *deribit_mm_protection { "Y" } else { "N" }.to_string(),
             );
         }
 
         if let Some(deribit_condition_trigger_method) = &self.deribit_condition_trigger_method {
-            builder = builder.field(5127, deribit_condition_trigger_method.to_string());
+            builder = builder.field(
+                tags::CONDITION_TRIGGER_METHOD,
+                deribit_condition_trigger_method.to_string(),
+            );
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -262,6 +312,7 @@ impl_json_debug_pretty!(NewOrderSingle);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_new_order_single_market_creation() {
@@ -323,7 +374,7 @@ mod tests {
         )
         .post_only();
 
-        assert_eq!(order.exec_inst, Some("6".to_string()));
+        assert_eq!(order.exec_inst, ExecInst::post_only());
     }
 
     #[test]
@@ -337,7 +388,42 @@ mod tests {
         )
         .reduce_only();
 
-        assert_eq!(order.exec_inst, Some("E".to_string()));
+        assert_eq!(order.exec_inst, ExecInst::reduce_only());
+    }
+
+    #[test]
+    fn test_new_order_single_flags_are_encoded_and_checked() {
+        let order = NewOrderSingle::limit(
+            "ORDER103".to_string(),
+            OrderSide::Buy,
+            10.0,
+            45000.0,
+            "BTC-PERPETUAL".to_string(),
+        )
+        .post_only_reduce_only()
+        .hidden()
+        .with_self_trade_prevention(SelfTradePrevention::CancelBoth);
+
+        let message = order.to_fix_string("CLIENT", "DERIBITSERVER", 1).unwrap();
+        assert!(message.contains("\x0118=6 E\x01"));
+        assert!(message.contains("\x011138=0\x01"));
+        assert!(message.contains("\x012964=3\x01"));
+
+        let market = NewOrderSingle::market(
+            "ORDER104".to_string(),
+            OrderSide::Buy,
+            10.0,
+            "BTC-PERPETUAL".to_string(),
+        );
+        assert!(market.clone().post_only().validate().is_err());
+        assert!(market.clone().hidden().validate().is_err());
+        assert!(market.reduce_only().validate().is_ok());
+        assert!(
+            order
+                .with_time_in_force(TimeInForce::ImmediateOrCancel)
+                .to_fix_message("CLIENT", "DERIBITSERVER", 1)
+                .is_err()
+        );
     }
 
     #[test]
@@ -351,7 +437,7 @@ mod tests {
         )
         .with_label("test-order".to_string());
 
-        let fix_message = order.to_fix_message("CLIENT", "DERIBITSERVER", 1);
+        let fix_message = order.to_fix_string("CLIENT", "DERIBITSERVER", 1);
         assert!(fix_message.is_ok());
 
         let message = fix_message.unwrap();
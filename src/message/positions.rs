@@ -15,6 +15,7 @@ use crate::error::Result;
 use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::MessageBuilder;
 use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 
 use crate::model::position::{Direction, Position};
@@ -160,24 +161,27 @@ impl RequestForPositions {
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
-            .field(710, self.pos_req_id.clone()) // PosReqID
-            .field(724, i32::from(self.pos_req_type).to_string()); // PosReqType
+            .field(tags::POS_REQ_ID, self.pos_req_id.clone())
+            .field(tags::POS_REQ_TYPE, i32::from(self.pos_req_type).to_string());
 
         // Add optional subscription request type
         if let Some(subscription_type) = self.subscription_request_type {
-            builder = builder.field(263, i32::from(subscription_type).to_string());
+            builder = builder.field(
+                tags::SUBSCRIPTION_REQUEST_TYPE,
+                i32::from(subscription_type).to_string(),
+            );
         }
 
         // Add optional clearing business date
         if let Some(ref date) = self.clearing_business_date {
-            builder = builder.field(715, date.clone());
+            builder = builder.field(tags::CLEARING_BUSINESS_DATE, date.clone());
         }
 
         // Add symbols if present
         if !self.symbols.is_empty() {
-            builder = builder.field(146, self.symbols.len().to_string()); // NoRelatedSym
+            builder = builder.field(tags::NO_RELATED_SYM, self.symbols.len().to_string());
             for symbol in &self.symbols {
-                builder = builder.field(55, symbol.clone()); // Symbol
+                builder = builder.push_field(tags::SYMBOL, symbol.clone());
             }
         }
 
@@ -235,18 +239,18 @@ impl PositionReport {
         let get_f64 = |tag| message.get_field(tag).and_then(|s| s.parse::<f64>().ok());
         let get_string = |tag| message.get_field(tag).map(|s| s.to_string());
 
-        let instrument_name = get_string(55).ok_or_else(|| {
+        let instrument_name = get_string(tags::SYMBOL).ok_or_else(|| {
             DeribitFixError::Generic("Missing instrument name (tag 55)".to_string())
         })?;
-        let long_qty = get_f64(704).unwrap_or(0.0);
-        let short_qty = get_f64(705).unwrap_or(0.0);
+        let long_qty = get_f64(tags::LONG_QTY).unwrap_or(0.0);
+        let short_qty = get_f64(tags::SHORT_QTY).unwrap_or(0.0);
         let size = long_qty - short_qty;
         let direction = if size > 0.0 {
             Direction::Buy
         } else {
             Direction::Sell
         };
-        let average_price = get_f64(730).unwrap_or(0.0);
+        let average_price = get_f64(tags::SETTL_PRICE).unwrap_or(0.0);
 
         Ok(Position {
             instrument_name,
@@ -254,27 +258,27 @@ impl PositionReport {
             direction,
             average_price,
             average_price_usd: None,
-            delta: get_f64(811),                          // Greeks delta
-            estimated_liquidation_price: get_f64(100088), // DeribitLiquidationPrice
-            floating_profit_loss: get_f64(707),           // Unrealized PnL
+            delta: get_f64(tags::position_report::DELTA), // Greeks delta
+            estimated_liquidation_price: get_f64(tags::DERIBIT_LIQUIDATION_PRICE),
+            floating_profit_loss: get_f64(tags::position_report::FLOATING_PNL), // Unrealized PnL
             floating_profit_loss_usd: None,
-            gamma: get_f64(812),          // Greeks gamma
-            index_price: get_f64(731),    // Index price
-            initial_margin: get_f64(899), // Initial margin
+            gamma: get_f64(tags::position_report::GAMMA), // Greeks gamma
+            index_price: get_f64(tags::position_report::INDEX_PRICE),
+            initial_margin: get_f64(tags::position_report::INITIAL_MARGIN),
             interest_value: None,
-            kind: get_string(461), // CFICode for instrument type
+            kind: get_string(tags::CFI_CODE), // CFICode for instrument type
             leverage: None,
-            maintenance_margin: get_f64(898), // Maintenance margin
-            mark_price: get_f64(732),         // Mark price
+            maintenance_margin: get_f64(tags::position_report::MAINTENANCE_MARGIN),
+            mark_price: get_f64(tags::position_report::MARK_PRICE),
             open_orders_margin: None,
             realized_funding: None,
-            realized_profit_loss: get_f64(706),   // Realized PnL
-            settlement_price: get_f64(730),       // Settlement price (same as avg price for now)
-            size_currency: get_f64(100089),       // DeribitSizeInCurrency
-            theta: get_f64(813),                  // Greeks theta
-            total_profit_loss: get_f64(708),      // Total PnL
-            vega: get_f64(814),                   // Greeks vega
-            unrealized_profit_loss: get_f64(707), // Same as floating PnL
+            realized_profit_loss: get_f64(tags::position_report::REALIZED_PNL),
+            settlement_price: get_f64(tags::SETTL_PRICE), // Settlement price (same as avg price for now)
+            size_currency: get_f64(tags::DERIBIT_SIZE_IN_CURRENCY),
+            theta: get_f64(tags::position_report::THETA), // Greeks theta
+            total_profit_loss: get_f64(tags::position_report::TOTAL_PNL),
+            vega: get_f64(tags::position_report::VEGA), // Greeks vega
+            unrealized_profit_loss: get_f64(tags::position_report::FLOATING_PNL), // Same as floating PnL
         })
     }
 
@@ -335,95 +339,110 @@ impl PositionReport {
             .msg_seq_num(msg_seq_num);
 
         // Add position-specific fields
-        let msg = msg.field(55, position.instrument_name.clone()); // Symbol
-        let msg = msg.field(730, position.average_price.to_string()); // SettlPx
+        let msg = msg.field(tags::SYMBOL, position.instrument_name.clone());
+        let msg = msg.field(tags::SETTL_PRICE, position.average_price.to_string()); // SettlPx
 
         // Add position quantity based on direction
         let msg = match position.direction {
-            Direction::Buy => msg.field(704, position.size.to_string()), // LongQty
-            Direction::Sell => msg.field(705, position.size.abs().to_string()), // ShortQty (absolute value)
+            Direction::Buy => msg.field(tags::LONG_QTY, position.size.to_string()),
+            Direction::Sell => msg.field(tags::SHORT_QTY, position.size.abs().to_string()), // ShortQty (absolute value)
         };
 
         // Add other position fields (only if they exist)
         let msg = if let Some(realized_pnl) = position.realized_profit_loss {
-            msg.field(706, realized_pnl.to_string())
+            msg.field(
+                tags::position_report::REALIZED_PNL,
+                realized_pnl.to_string(),
+            )
         } else {
             msg
         };
 
         let msg = if let Some(floating_pnl) = position.floating_profit_loss {
-            msg.field(707, floating_pnl.to_string())
+            msg.field(
+                tags::position_report::FLOATING_PNL,
+                floating_pnl.to_string(),
+            )
         } else {
             msg
         };
 
         let msg = if let Some(total_pnl) = position.total_profit_loss {
-            msg.field(708, total_pnl.to_string())
+            msg.field(tags::position_report::TOTAL_PNL, total_pnl.to_string())
         } else {
             msg
         };
 
         // Add Greeks if available
         let msg = if let Some(delta) = position.delta {
-            msg.field(811, delta.to_string())
+            msg.field(tags::position_report::DELTA, delta.to_string())
         } else {
             msg
         };
 
         let msg = if let Some(gamma) = position.gamma {
-            msg.field(812, gamma.to_string())
+            msg.field(tags::position_report::GAMMA, gamma.to_string())
         } else {
             msg
         };
 
         let msg = if let Some(theta) = position.theta {
-            msg.field(813, theta.to_string())
+            msg.field(tags::position_report::THETA, theta.to_string())
         } else {
             msg
         };
 
         let msg = if let Some(vega) = position.vega {
-            msg.field(814, vega.to_string())
+            msg.field(tags::position_report::VEGA, vega.to_string())
         } else {
             msg
         };
 
         // Add other optional fields
         let msg = if let Some(index_price) = position.index_price {
-            msg.field(731, index_price.to_string())
+            msg.field(tags::position_report::INDEX_PRICE, index_price.to_string())
         } else {
             msg
         };
 
         let msg = if let Some(mark_price) = position.mark_price {
-            msg.field(732, mark_price.to_string())
+            msg.field(tags::position_report::MARK_PRICE, mark_price.to_string())
         } else {
             msg
         };
 
         let msg = if let Some(initial_margin) = position.initial_margin {
-            msg.field(899, initial_margin.to_string())
+            msg.field(
+                tags::position_report::INITIAL_MARGIN,
+                initial_margin.to_string(),
+            )
         } else {
             msg
         };
 
         let msg = if let Some(maintenance_margin) = position.maintenance_margin {
-            msg.field(898, maintenance_margin.to_string())
+            msg.field(
+                tags::position_report::MAINTENANCE_MARGIN,
+                maintenance_margin.to_string(),
+            )
         } else {
             msg
         };
 
-        let msg = msg.field(979, "FMTM".to_string()); // PosAmtType
+        let msg = msg.field(tags::position_report::POS_AMT_TYPE, "FMTM".to_string());
 
         // Deribit custom tags
         let msg = if let Some(liquidation_price) = position.estimated_liquidation_price {
-            msg.field(100088, liquidation_price.to_string()) // DeribitLiquidationPrice
+            msg.field(
+                tags::DERIBIT_LIQUIDATION_PRICE,
+                liquidation_price.to_string(),
+            )
         } else {
             msg
         };
 
         let msg = if let Some(size_currency) = position.size_currency {
-            msg.field(100089, size_currency.to_string()) // DeribitSizeInCurrency
+            msg.field(tags::DERIBIT_SIZE_IN_CURRENCY, size_currency.to_string())
         } else {
             msg
         };
@@ -0,0 +1,275 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Human readable rendering of FIX messages
+//!
+//! [`FixPrettyPrinter`] renders messages as `35=MsgType(D) | 55=Symbol(BTC-PERPETUAL)`
+//! for logs and debugging. Credentials and other sensitive fields are redacted
+//! by default so messages can be logged safely.
+
+use crate::config::DeribitFixConfig;
+use crate::model::message::FixMessage;
+use std::collections::BTreeSet;
+
+/// Placeholder written in place of redacted values
+pub const REDACTED: &str = "***";
+
+/// Tags redacted by default: RawData (96), Password (554), NewPassword (925),
+/// EncryptedPassword (1402), EncryptedNewPassword (1404) and DeribitAppSig (9005)
+pub const DEFAULT_REDACTED_TAGS: [u32; 6] = [96, 554, 925, 1402, 1404, 9005];
+
+/// Renders FIX messages with tag names and redacts sensitive values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixPrettyPrinter {
+    redacted_tags: BTreeSet<u32>,
+    redaction_enabled: bool,
+    separator: String,
+}
+
+impl FixPrettyPrinter {
+    /// Create a printer redacting [`DEFAULT_REDACTED_TAGS`]
+    pub fn new() -> Self {
+        Self {
+            redacted_tags: DEFAULT_REDACTED_TAGS.into_iter().collect(),
+            redaction_enabled: true,
+            separator: " | ".to_string(),
+        }
+    }
+
+    /// Create a printer honouring `redact_sensitive_fields` from the configuration
+    pub fn from_config(config: &DeribitFixConfig) -> Self {
+        Self::new().with_redaction(config.redact_sensitive_fields)
+    }
+
+    /// Enable or disable redaction
+    ///
+    /// Disabling redaction writes credentials to the logs and should only be
+    /// used when debugging against a test environment.
+    pub fn with_redaction(mut self, enabled: bool) -> Self {
+        self.redaction_enabled = enabled;
+        self
+    }
+
+    /// Redact an additional tag
+    pub fn redact_tag(mut self, tag: u32) -> Self {
+        self.redacted_tags.insert(tag);
+        self
+    }
+
+    /// Set the separator written between fields (default `" | "`)
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Whether the value of `tag` is replaced by [`REDACTED`]
+    pub fn is_redacted(&self, tag: u32) -> bool {
+        self.redaction_enabled && self.redacted_tags.contains(&tag)
+    }
+
+    /// Render a single field as `tag=Name(value)`, or `tag=value` for unknown tags
+    pub fn render_field(&self, tag: u32, value: &str) -> String {
+        let value = if self.is_redacted(tag) {
+            REDACTED
+        } else {
+            value
+        };
+        match tag_name(tag) {
+            Some(name) => format!("{tag}={name}({value})"),
+            None => format!("{tag}={value}"),
+        }
+    }
+
+    /// Render a parsed message
+    pub fn render(&self, message: &FixMessage) -> String {
+        message
+            .fields
+            .iter()
+            .map(|(tag, value)| self.render_field(*tag, value))
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    /// Render a raw SOH-delimited message without parsing it first
+    ///
+    /// Malformed fragments are kept as-is so the output is still useful when
+    /// debugging framing problems.
+    pub fn render_raw(&self, raw_message: &str) -> String {
+        raw_message
+            .split('\x01')
+            .filter(|part| !part.is_empty())
+            .map(|part| match part.split_once('=') {
+                Some((tag, value)) => match tag.parse::<u32>() {
+                    Ok(tag) => self.render_field(tag, value),
+                    Err(_) => part.to_string(),
+                },
+                None => part.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+}
+
+impl Default for FixPrettyPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Name of a FIX tag, including Deribit custom tags, if known
+pub fn tag_name(tag: u32) -> Option<&'static str> {
+    let name = match tag {
+        7 => "BeginSeqNo",
+        8 => "BeginString",
+        9 => "BodyLength",
+        10 => "CheckSum",
+        11 => "ClOrdID",
+        14 => "CumQty",
+        16 => "EndSeqNo",
+        17 => "ExecID",
+        18 => "ExecInst",
+        31 => "LastPx",
+        32 => "LastQty",
+        34 => "MsgSeqNum",
+        35 => "MsgType",
+        36 => "NewSeqNo",
+        37 => "OrderID",
+        38 => "OrderQty",
+        39 => "OrdStatus",
+        40 => "OrdType",
+        41 => "OrigClOrdID",
+        43 => "PossDupFlag",
+        44 => "Price",
+        45 => "RefSeqNum",
+        49 => "SenderCompID",
+        50 => "SenderSubID",
+        52 => "SendingTime",
+        54 => "Side",
+        55 => "Symbol",
+        56 => "TargetCompID",
+        57 => "TargetSubID",
+        58 => "Text",
+        59 => "TimeInForce",
+        60 => "TransactTime",
+        83 => "RptSeq",
+        95 => "RawDataLength",
+        96 => "RawData",
+        97 => "PossResend",
+        98 => "EncryptMethod",
+        99 => "StopPx",
+        102 => "CxlRejReason",
+        103 => "OrdRejReason",
+        108 => "HeartBtInt",
+        112 => "TestReqID",
+        115 => "OnBehalfOfCompID",
+        117 => "QuoteID",
+        122 => "OrigSendingTime",
+        123 => "GapFillFlag",
+        131 => "QuoteReqID",
+        141 => "ResetSeqNumFlag",
+        146 => "NoRelatedSym",
+        150 => "ExecType",
+        151 => "LeavesQty",
+        198 => "SecondaryOrderID",
+        262 => "MDReqID",
+        263 => "SubscriptionRequestType",
+        264 => "MarketDepth",
+        265 => "MDUpdateType",
+        267 => "NoMDEntryTypes",
+        268 => "NoMDEntries",
+        269 => "MDEntryType",
+        270 => "MDEntryPx",
+        271 => "MDEntrySize",
+        272 => "MDEntryDate",
+        279 => "MDUpdateAction",
+        281 => "MDReqRejReason",
+        320 => "SecurityReqID",
+        324 => "SecurityStatusReqID",
+        326 => "SecurityTradingStatus",
+        371 => "RefTagID",
+        372 => "RefMsgType",
+        373 => "SessionRejectReason",
+        379 => "BusinessRejectRefID",
+        380 => "BusinessRejectReason",
+        553 => "Username",
+        554 => "Password",
+        559 => "SecurityListRequestType",
+        568 => "TradeRequestID",
+        584 => "MassStatusReqID",
+        585 => "MassStatusReqType",
+        710 => "PosReqID",
+        721 => "PosMaintRptID",
+        724 => "PosReqType",
+        923 => "UserRequestID",
+        924 => "UserRequestType",
+        925 => "NewPassword",
+        1128 => "AppID",
+        1137 => "DefaultApplVerID",
+        1402 => "EncryptedPassword",
+        1404 => "EncryptedNewPassword",
+        9001 => "CancelOnDisconnect",
+        9002 => "UseWordsafeTags",
+        9003 => "DontCancelOnDisconnect",
+        9004 => "DeribitAppId",
+        9005 => "DeribitAppSig",
+        9007 => "DeribitSequential",
+        9009 => "UnsubscribeExecutionReports",
+        9010 => "ConnectionOnlyExecutionReports",
+        100009 => "DeribitTradeId",
+        100010 => "DeribitLabel",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGON: &str = "8=FIX.4.4\x019=100\x0135=A\x0149=CLIENT\x0156=DERIBIT\x0134=1\x0196=1700000000000.bm9uY2U=\x01553=user\x01554=c2VjcmV0\x0110=123\x01";
+
+    #[test]
+    fn test_render_redacts_sensitive_fields() {
+        let message = FixMessage::parse(LOGON).unwrap();
+        let rendered = FixPrettyPrinter::new().render(&message);
+
+        assert!(rendered.contains("35=MsgType(A)"));
+        assert!(rendered.contains("553=Username(user)"));
+        assert!(rendered.contains("554=Password(***)"));
+        assert!(rendered.contains("96=RawData(***)"));
+        assert!(!rendered.contains("c2VjcmV0"));
+        assert!(!rendered.contains("bm9uY2U"));
+    }
+
+    #[test]
+    fn test_render_raw_matches_render() {
+        let printer = FixPrettyPrinter::new();
+        let message = FixMessage::parse(LOGON).unwrap();
+        assert_eq!(printer.render_raw(LOGON), printer.render(&message));
+    }
+
+    #[test]
+    fn test_redaction_can_be_configured() {
+        let message = FixMessage::parse(LOGON).unwrap();
+
+        let plain = FixPrettyPrinter::new()
+            .with_redaction(false)
+            .render(&message);
+        assert!(plain.contains("554=Password(c2VjcmV0)"));
+
+        let extra = FixPrettyPrinter::new().redact_tag(553).render(&message);
+        assert!(extra.contains("553=Username(***)"));
+    }
+
+    #[test]
+    fn test_unknown_tags_and_separator() {
+        let printer = FixPrettyPrinter::new().with_separator(" ");
+        assert_eq!(
+            printer.render_raw("35=0\x0177777=x\x01"),
+            "35=MsgType(0) 77777=x"
+        );
+    }
+}
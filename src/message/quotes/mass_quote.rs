@@ -5,10 +5,21 @@
 ******************************************************************************/
 
 //! Mass Quote FIX Message Implementation
-
-use crate::error::Result as DeribitFixResult;
+//!
+//! Quotes may be sent on Deribit's future combos (`BTC-FS-27DEC24_PERP`) and
+//! option combos (`BTC-CS-28MAR25-60000_70000`) as on single instruments. A
+//! spread trades at the price difference of its legs, so its bid and offer
+//! may be zero or negative and are not required to be below one another;
+//! [`QuoteEntry::validate`] only checks that the prices are finite for
+//! combos, and that they are positive with the bid below the offer for other
+//! instruments.
+
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::{OrderSide, TimeInForce};
+use crate::model::instrument::InstrumentName;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -142,6 +153,54 @@ impl QuoteEntry {
         self.trading_session_id = Some(session_id);
         self
     }
+
+    /// Whether the entry quotes a future or option combo
+    pub fn is_combo(&self) -> bool {
+        self.symbol
+            .parse::<InstrumentName>()
+            .is_ok_and(|name| name.is_combo())
+    }
+
+    /// Check the prices and sizes of the entry
+    ///
+    /// Prices must be finite and sizes positive. Outside combos prices must
+    /// also be positive and a two-sided quote must have its bid below its
+    /// offer; combo quotes may be negative or inverted.
+    pub fn validate(&self) -> DeribitFixResult<()> {
+        let invalid = |reason: String| {
+            Err(DeribitFixError::MessageConstruction(format!(
+                "Quote entry {} on {}: {reason}",
+                self.quote_entry_id, self.symbol
+            )))
+        };
+        let combo = self.is_combo();
+        for (side, price) in [("bid", self.bid_px), ("offer", self.offer_px)] {
+            let Some(price) = price else {
+                continue;
+            };
+            if !price.is_finite() {
+                return invalid(format!("{side} price {price} is not a number"));
+            }
+            if !combo && price <= 0.0 {
+                return invalid(format!("{side} price {price} must be positive"));
+            }
+        }
+        for (side, size) in [("bid", self.bid_size), ("offer", self.offer_size)] {
+            if let Some(size) = size
+                && !(size.is_finite() && size > 0.0)
+            {
+                return invalid(format!("{side} size {size} must be positive"));
+            }
+        }
+        if let (false, Some(bid_px), Some(offer_px)) = (combo, self.bid_px, self.offer_px)
+            && bid_px >= offer_px
+        {
+            return invalid(format!(
+                "bid price {bid_px} must be below the offer price {offer_px}"
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// Mass quote response type enumeration
@@ -209,7 +268,7 @@ pub struct MassQuote {
     pub time_in_force: Option<TimeInForce>,
     /// Custom label
     pub deribit_label: Option<String>,
-    /// Mass quote response type
+    /// Mass quote response type, sent as QuoteResponseLevel (301) unless `quote_resp_level` is set
     pub mass_quote_response_type: Option<MassQuoteResponseType>,
     /// Use standard FIX repeating groups instead of simplified custom tags
     pub use_standard_repeating_groups: bool,
@@ -317,13 +376,18 @@ impl MassQuote {
         self
     }
 
+    /// Check every quote entry, see [`QuoteEntry::validate`]
+    pub fn validate(&self) -> DeribitFixResult<()> {
+        self.quote_entries.iter().try_for_each(QuoteEntry::validate)
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MassQuote)
             .sender_comp_id(sender_comp_id.to_string())
@@ -333,30 +397,33 @@ impl MassQuote {
 
         // Required fields
         builder = builder
-            .field(117, self.quote_id.clone()) // QuoteID
-            .field(302, self.quote_set_id.clone()) // QuoteSetID
-            .field(295, self.tot_quote_entries.to_string()); // TotQuoteEntries
+            .field(tags::QUOTE_ID, self.quote_id.clone())
+            .field(tags::QUOTE_SET_ID, self.quote_set_id.clone());
 
         // Optional fields
         if let Some(quote_req_id) = &self.quote_req_id {
-            builder = builder.field(131, quote_req_id.clone());
+            builder = builder.field(tags::QUOTE_REQ_ID, quote_req_id.clone());
         }
 
-        if let Some(quote_resp_level) = &self.quote_resp_level {
-            builder = builder.field(301, quote_resp_level.to_string());
+        // The response type is the acknowledgement level; an explicit level takes precedence
+        if let Some(quote_resp_level) = self
+            .quote_resp_level
+            .or(self.mass_quote_response_type.map(i32::from))
+        {
+            builder = builder.field(tags::QUOTE_RESPONSE_LEVEL, quote_resp_level.to_string());
         }
 
         if let Some(default_bid_size) = &self.defaul_bid_size {
-            builder = builder.field(293, default_bid_size.to_string());
+            builder = builder.field(tags::DEF_BID_SIZE, default_bid_size.to_string());
         }
 
         if let Some(default_offer_size) = &self.default_offer_size {
-            builder = builder.field(294, default_offer_size.to_string());
+            builder = builder.field(tags::DEF_OFFER_SIZE, default_offer_size.to_string());
         }
 
         if let Some(quote_set_valid_until_time) = &self.quote_set_valid_until_time {
             builder = builder.field(
-                367,
+                tags::QUOTE_SET_VALID_UNTIL_TIME,
                 quote_set_valid_until_time
                     .format("%Y%m%d-%H:%M:%S%.3f")
                     .to_string(),
@@ -364,53 +431,51 @@ impl MassQuote {
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(time_in_force) = &self.time_in_force {
-            builder = builder.field(59, char::from(*time_in_force).to_string());
+            builder = builder.field(tags::TIME_IN_FORCE, char::from(*time_in_force).to_string());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
-        }
-
-        if let Some(mass_quote_response_type) = &self.mass_quote_response_type {
-            builder = builder.field(296, i32::from(*mass_quote_response_type).to_string());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         // Add quote entries - support both standard FIX repeating groups and simplified custom tags
         if self.use_standard_repeating_groups {
             // Standard FIX repeating groups implementation
-            builder = builder.field(295, self.quote_entries.len().to_string()); // NoQuoteEntries (using 295 to avoid conflict with 296)
+            builder = builder.field(tags::NO_QUOTE_ENTRIES, self.quote_entries.len().to_string()); // NoQuoteEntries
 
             for entry in &self.quote_entries {
                 builder = builder
-                    .field(299, entry.quote_entry_id.clone()) // QuoteEntryID
-                    .field(55, entry.symbol.clone()); // Symbol
+                    .push_field(tags::QUOTE_ENTRY_ID, entry.quote_entry_id.clone())
+                    .push_field(tags::SYMBOL, entry.symbol.clone());
 
                 if let Some(side) = &entry.side {
-                    builder = builder.field(54, char::from(*side).to_string()); // Side
+                    builder = builder.push_field(tags::SIDE, char::from(*side).to_string());
                 }
 
                 if let Some(bid_px) = &entry.bid_px {
-                    builder = builder.field(132, bid_px.to_string()); // BidPx
+                    builder = builder.push_field(tags::BID_PX, bid_px.to_string());
                 }
 
                 if let Some(offer_px) = &entry.offer_px {
-                    builder = builder.field(133, offer_px.to_string()); // OfferPx
+                    builder = builder.push_field(tags::OFFER_PX, offer_px.to_string());
                 }
 
                 if let Some(bid_size) = &entry.bid_size {
-                    builder = builder.field(134, bid_size.to_string()); // BidSize
+                    builder = builder.push_field(tags::BID_SIZE, bid_size.to_string());
                 }
 
                 if let Some(offer_size) = &entry.offer_size {
-                    builder = builder.field(135, offer_size.to_string()); // OfferSize
+                    builder = builder.push_field(tags::OFFER_SIZE, offer_size.to_string());
                 }
             }
         } else {
             // Simplified custom tags implementation (backward compatibility)
+            builder = builder.field(tags::NO_QUOTE_ENTRIES, self.tot_quote_entries.to_string()); // TotQuoteEntries
+
             for (i, entry) in self.quote_entries.iter().enumerate() {
                 let base_tag = 2000 + (i * 100); // Custom tag range for quote entries
 
@@ -440,7 +505,7 @@ impl MassQuote {
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -450,6 +515,7 @@ impl_json_debug_pretty!(MassQuote, QuoteEntry);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_quote_entry_creation() {
@@ -479,6 +545,60 @@ mod tests {
         assert_eq!(entry.mid_px, Some(3202.5));
     }
 
+    #[test]
+    fn test_quote_entry_validation() {
+        let quote = |symbol: &str, bid_px: f64, offer_px: f64| {
+            QuoteEntry::two_sided(
+                "QE1".to_string(),
+                symbol.to_string(),
+                bid_px,
+                offer_px,
+                1.0,
+                1.0,
+            )
+        };
+        assert!(quote("BTC-PERPETUAL", 50000.0, 50010.0).validate().is_ok());
+        assert!(quote("BTC-PERPETUAL", 50010.0, 50000.0).validate().is_err());
+        assert!(quote("BTC-PERPETUAL", -5.0, 10.0).validate().is_err());
+        assert!(quote("BTC-PERPETUAL", f64::NAN, 10.0).validate().is_err());
+
+        // Spreads may trade at negative or inverted prices
+        let spread = quote("BTC-FS-27DEC24_PERP", -12.5, -15.0);
+        assert!(spread.is_combo());
+        assert!(spread.validate().is_ok());
+        assert!(
+            quote("BTC-CS-28MAR25-60000_70000", 0.0, 0.01)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            quote("BTC-FS-27DEC24_PERP", f64::INFINITY, 1.0)
+                .validate()
+                .is_err()
+        );
+
+        let mut empty = quote("BTC-FS-27DEC24_PERP", -12.5, -10.0);
+        empty.bid_size = Some(0.0);
+        assert!(empty.validate().is_err());
+
+        let mass_quote = MassQuote::new(
+            "Q1".to_string(),
+            "S1".to_string(),
+            vec![spread, quote("ETH-PERPETUAL", 3205.0, 3200.0)],
+        );
+        assert!(mass_quote.validate().is_err());
+        let message = MassQuote::new(
+            "Q2".to_string(),
+            "S1".to_string(),
+            vec![quote("BTC-FS-27DEC24_PERP", -12.5, -10.0)],
+        )
+        .with_standard_repeating_groups()
+        .to_fix_message("CLIENT", "DERIBIT", 1)
+        .unwrap();
+        assert_eq!(message.get_field(tags::BID_PX).unwrap(), "-12.5");
+        assert_eq!(message.get_field(tags::OFFER_PX).unwrap(), "-10");
+    }
+
     #[test]
     fn test_quote_entry_one_sided_buy() {
         let entry = QuoteEntry::one_sided(
@@ -618,7 +738,7 @@ mod tests {
         let mass_quote = MassQuote::new("MQ123".to_string(), "QS456".to_string(), vec![entry])
             .with_label("test-label".to_string());
 
-        let fix_message = mass_quote.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = mass_quote.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=i")); // MsgType
@@ -634,6 +754,81 @@ mod tests {
         assert!(fix_message.contains("2011=50010")); // OfferPx
     }
 
+    #[test]
+    fn test_mass_quote_standard_groups_keep_every_combo_entry() {
+        let entries = vec![
+            QuoteEntry::two_sided(
+                "QE1".to_string(),
+                "BTC-FS-27DEC24_PERP".to_string(),
+                -12.5,
+                -10.0,
+                1.0,
+                2.0,
+            ),
+            QuoteEntry::two_sided(
+                "QE2".to_string(),
+                "BTC-CS-28MAR25-60000_70000".to_string(),
+                0.01,
+                0.02,
+                3.0,
+                4.0,
+            ),
+        ];
+        let message = MassQuote::new("MQ1".to_string(), "QS1".to_string(), entries)
+            .with_standard_repeating_groups()
+            .with_response_type(MassQuoteResponseType::AckRequired)
+            .to_fix_message("CLIENT", "DERIBIT", 1)
+            .unwrap();
+
+        let values = |tag: u32| {
+            message
+                .fields
+                .iter()
+                .filter(|(field_tag, _)| *field_tag == tag)
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(tags::NO_QUOTE_ENTRIES), ["2"]);
+        assert_eq!(values(tags::QUOTE_ENTRY_ID), ["QE1", "QE2"]);
+        assert_eq!(
+            values(tags::SYMBOL),
+            ["BTC-FS-27DEC24_PERP", "BTC-CS-28MAR25-60000_70000"]
+        );
+        assert_eq!(values(tags::BID_PX), ["-12.5", "0.01"]);
+        assert_eq!(values(tags::OFFER_PX), ["-10", "0.02"]);
+        assert_eq!(values(tags::BID_SIZE), ["1", "3"]);
+        assert_eq!(values(tags::OFFER_SIZE), ["2", "4"]);
+        assert!(values(tags::NO_QUOTE_SETS).is_empty());
+        assert_eq!(values(tags::QUOTE_RESPONSE_LEVEL), ["1"]);
+
+        // Each entry opens with its QuoteEntryID right after the group count
+        let group = message
+            .fields
+            .iter()
+            .skip_while(|(tag, _)| *tag != tags::NO_QUOTE_ENTRIES)
+            .map(|(tag, _)| *tag)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            group,
+            [
+                tags::NO_QUOTE_ENTRIES,
+                tags::QUOTE_ENTRY_ID,
+                tags::SYMBOL,
+                tags::BID_PX,
+                tags::OFFER_PX,
+                tags::BID_SIZE,
+                tags::OFFER_SIZE,
+                tags::QUOTE_ENTRY_ID,
+                tags::SYMBOL,
+                tags::BID_PX,
+                tags::OFFER_PX,
+                tags::BID_SIZE,
+                tags::OFFER_SIZE,
+                tags::CHECKSUM,
+            ]
+        );
+    }
+
     #[test]
     fn test_mass_quote_response_type_conversions() {
         assert_eq!(i32::from(MassQuoteResponseType::NoAckRequired), 0);
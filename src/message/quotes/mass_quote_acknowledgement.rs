@@ -6,15 +6,18 @@
 
 //! Mass Quote Acknowledgement FIX Message Implementation
 
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::OrderSide;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 /// Quote acknowledgement status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum QuoteAckStatus {
     /// Received - not yet processed
     Received,
@@ -22,6 +25,8 @@ pub enum QuoteAckStatus {
     Accepted,
     /// Rejected
     Rejected,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<QuoteAckStatus> for i32 {
@@ -30,10 +35,12 @@ impl From<QuoteAckStatus> for i32 {
             QuoteAckStatus::Received => 0,
             QuoteAckStatus::Accepted => 1,
             QuoteAckStatus::Rejected => 5,
+            QuoteAckStatus::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`QuoteAckStatus::Unknown`]
 impl TryFrom<i32> for QuoteAckStatus {
     type Error = String;
 
@@ -42,13 +49,14 @@ impl TryFrom<i32> for QuoteAckStatus {
             0 => Ok(QuoteAckStatus::Received),
             1 => Ok(QuoteAckStatus::Accepted),
             5 => Ok(QuoteAckStatus::Rejected),
-            _ => Err(format!("Invalid QuoteAckStatus: {}", value)),
+            _ => Ok(QuoteAckStatus::Unknown(value)),
         }
     }
 }
 
 /// Quote reject reason enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum QuoteRejectReason {
     /// Unknown symbol
     UnknownSymbol,
@@ -78,6 +86,8 @@ pub enum QuoteRejectReason {
     InvalidOrUnknownIssuerOfUnderlyingSecurity,
     /// Other
     Other,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<QuoteRejectReason> for i32 {
@@ -97,10 +107,12 @@ impl From<QuoteRejectReason> for i32 {
             QuoteRejectReason::InvalidOrUnknownSecurityIssuer => 12,
             QuoteRejectReason::InvalidOrUnknownIssuerOfUnderlyingSecurity => 13,
             QuoteRejectReason::Other => 99,
+            QuoteRejectReason::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`QuoteRejectReason::Unknown`]
 impl TryFrom<i32> for QuoteRejectReason {
     type Error = String;
 
@@ -120,7 +132,7 @@ impl TryFrom<i32> for QuoteRejectReason {
             12 => Ok(QuoteRejectReason::InvalidOrUnknownSecurityIssuer),
             13 => Ok(QuoteRejectReason::InvalidOrUnknownIssuerOfUnderlyingSecurity),
             99 => Ok(QuoteRejectReason::Other),
-            _ => Err(format!("Invalid QuoteRejectReason: {}", value)),
+            _ => Ok(QuoteRejectReason::Unknown(value)),
         }
     }
 }
@@ -311,6 +323,32 @@ impl MassQuoteAcknowledgement {
         }
     }
 
+    /// Error for a received Mass Quote Acknowledgement (b), `None` unless
+    /// the quote was rejected
+    ///
+    /// The error carries the QuoteRejectReason (300) and the exchange's Text
+    /// (58).
+    pub fn reject_error(message: &FixMessage) -> Option<DeribitFixError> {
+        let status = message
+            .get_field(tags::QUOTE_STATUS)
+            .and_then(|value| value.parse::<i32>().ok())
+            .and_then(|value| QuoteAckStatus::try_from(value).ok());
+        if status != Some(QuoteAckStatus::Rejected) {
+            return None;
+        }
+        Some(DeribitFixError::QuoteRejected {
+            quote_id: message
+                .get_field(tags::QUOTE_ID)
+                .cloned()
+                .unwrap_or_default(),
+            reason: message
+                .get_field(tags::QUOTE_REJECT_REASON)
+                .and_then(|value| value.parse::<i32>().ok())
+                .and_then(|value| QuoteRejectReason::try_from(value).ok()),
+            text: message.get_field(tags::TEXT).cloned(),
+        })
+    }
+
     /// Add a quote entry acknowledgement
     pub fn add_quote_entry_ack(mut self, entry_ack: QuoteEntryAck) -> Self {
         self.quote_entry_acks.push(entry_ack);
@@ -360,7 +398,7 @@ impl MassQuoteAcknowledgement {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MassQuoteAcknowledgement)
             .sender_comp_id(sender_comp_id.to_string())
@@ -369,87 +407,106 @@ impl MassQuoteAcknowledgement {
             .sending_time(Utc::now());
 
         // Required fields
-        builder = builder
-            .field(117, self.quote_id.clone()) // QuoteID
-            .field(297, i32::from(self.quote_ack_status).to_string()); // QuoteAckStatus
+        builder = builder.field(tags::QUOTE_ID, self.quote_id.clone()).field(
+            tags::QUOTE_STATUS,
+            i32::from(self.quote_ack_status).to_string(),
+        ); // QuoteAckStatus
 
         // Optional fields
         if let Some(quote_req_id) = &self.quote_req_id {
-            builder = builder.field(131, quote_req_id.clone());
+            builder = builder.field(tags::QUOTE_REQ_ID, quote_req_id.clone());
         }
 
         if let Some(quote_reject_reason) = &self.quote_reject_reason {
-            builder = builder.field(300, i32::from(*quote_reject_reason).to_string());
+            builder = builder.field(
+                tags::QUOTE_REJECT_REASON,
+                i32::from(*quote_reject_reason).to_string(),
+            );
         }
 
         if let Some(quote_resp_level) = &self.quote_resp_level {
-            builder = builder.field(301, quote_resp_level.to_string());
+            builder = builder.field(tags::QUOTE_RESPONSE_LEVEL, quote_resp_level.to_string());
         }
 
         if let Some(quote_set_id) = &self.quote_set_id {
-            builder = builder.field(302, quote_set_id.clone());
+            builder = builder.field(tags::QUOTE_SET_ID, quote_set_id.clone());
         }
 
-        if let Some(tot_quote_entries) = &self.tot_quote_entries {
-            builder = builder.field(295, tot_quote_entries.to_string());
+        // The standard repeating group writes its own NoQuoteEntries (295)
+        if let Some(tot_quote_entries) = &self.tot_quote_entries
+            && !self.use_standard_repeating_groups
+        {
+            builder = builder.field(tags::NO_QUOTE_ENTRIES, tot_quote_entries.to_string());
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         // Add quote entry acknowledgements - support both standard FIX repeating groups and simplified custom tags
         if self.use_standard_repeating_groups {
             // Standard FIX repeating groups implementation
-            builder = builder.field(295, self.quote_entry_acks.len().to_string()); // NoQuoteEntries
+            builder = builder.field(
+                tags::NO_QUOTE_ENTRIES,
+                self.quote_entry_acks.len().to_string(),
+            );
 
             for entry_ack in &self.quote_entry_acks {
                 builder = builder
-                    .field(299, entry_ack.quote_entry_id.clone()) // QuoteEntryID
-                    .field(9020, i32::from(entry_ack.quote_ack_status).to_string()); // QuoteEntryType (0 = order, 1 = trade, 2 = error)
+                    .push_field(tags::QUOTE_ENTRY_ID, entry_ack.quote_entry_id.clone())
+                    .push_field(
+                        tags::QUOTE_ENTRY_TYPE,
+                        i32::from(entry_ack.quote_ack_status).to_string(),
+                    ); // QuoteEntryType (0 = order, 1 = trade, 2 = error)
 
                 if let Some(quote_set_id) = &self.quote_set_id {
-                    builder = builder.field(302, quote_set_id.clone()); // QuoteSetID
+                    builder = builder.push_field(tags::QUOTE_SET_ID, quote_set_id.clone());
                 }
 
-                builder = builder.field(1167, i32::from(entry_ack.quote_ack_status).to_string()); // QuoteEntryStatus
+                builder = builder.push_field(
+                    tags::QUOTE_ENTRY_STATUS,
+                    i32::from(entry_ack.quote_ack_status).to_string(),
+                );
 
-                builder = builder.field(55, entry_ack.symbol.clone()); // Symbol
+                builder = builder.push_field(tags::SYMBOL, entry_ack.symbol.clone());
 
                 if let Some(side) = &entry_ack.side {
-                    builder = builder.field(54, char::from(*side).to_string()); // Side
+                    builder = builder.push_field(tags::SIDE, char::from(*side).to_string());
                 }
 
                 if let Some(bid_px) = &entry_ack.bid_px {
-                    builder = builder.field(132, bid_px.to_string()); // BidPx
+                    builder = builder.push_field(tags::BID_PX, bid_px.to_string());
                 }
 
                 if let Some(offer_px) = &entry_ack.offer_px {
-                    builder = builder.field(133, offer_px.to_string()); // OfferPx
+                    builder = builder.push_field(tags::OFFER_PX, offer_px.to_string());
                 }
 
                 if let Some(bid_size) = &entry_ack.bid_size {
-                    builder = builder.field(134, bid_size.to_string()); // BidSize
+                    builder = builder.push_field(tags::BID_SIZE, bid_size.to_string());
                 }
 
                 if let Some(offer_size) = &entry_ack.offer_size {
-                    builder = builder.field(135, offer_size.to_string()); // OfferSize
+                    builder = builder.push_field(tags::OFFER_SIZE, offer_size.to_string());
                 }
 
                 if let Some(quote_reject_reason) = &entry_ack.quote_reject_reason {
-                    builder = builder.field(368, i32::from(*quote_reject_reason).to_string()); // QuoteEntryRejectReason
+                    builder = builder.push_field(
+                        tags::QUOTE_ENTRY_REJECT_REASON,
+                        i32::from(*quote_reject_reason).to_string(),
+                    );
                 }
 
                 if let Some(text) = &entry_ack.text {
-                    builder = builder.field(58, text.clone()); // Text
+                    builder = builder.push_field(tags::TEXT, text.clone());
                 }
             }
         } else {
@@ -498,7 +555,7 @@ impl MassQuoteAcknowledgement {
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -508,6 +565,7 @@ impl_json_debug_pretty!(MassQuoteAcknowledgement, QuoteEntryAck);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_quote_entry_ack_creation() {
@@ -650,9 +708,7 @@ mod tests {
         )
         .with_label("test-label".to_string());
 
-        let fix_message = mass_quote_ack
-            .to_fix_message("SENDER", "TARGET", 1)
-            .unwrap();
+        let fix_message = mass_quote_ack.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=b")); // MsgType
@@ -687,7 +743,10 @@ mod tests {
             QuoteAckStatus::Rejected
         );
 
-        assert!(QuoteAckStatus::try_from(99).is_err());
+        assert_eq!(
+            QuoteAckStatus::try_from(99).unwrap(),
+            QuoteAckStatus::Unknown(99)
+        );
     }
 
     #[test]
@@ -709,6 +768,37 @@ mod tests {
             QuoteRejectReason::Other
         );
 
-        assert!(QuoteRejectReason::try_from(50).is_err());
+        assert_eq!(
+            QuoteRejectReason::try_from(50).unwrap(),
+            QuoteRejectReason::Unknown(50)
+        );
+    }
+
+    #[test]
+    fn test_reject_error_keeps_reason_and_text() {
+        let rejected = MassQuoteAcknowledgement::rejected(
+            "Q1".to_string(),
+            QuoteRejectReason::InvalidPrice,
+            Some("price_too_high".to_string()),
+        )
+        .to_fix_message("DERIBIT", "CLIENT", 1)
+        .unwrap();
+        match MassQuoteAcknowledgement::reject_error(&rejected) {
+            Some(DeribitFixError::QuoteRejected {
+                quote_id,
+                reason,
+                text,
+            }) => {
+                assert_eq!(quote_id, "Q1");
+                assert_eq!(reason, Some(QuoteRejectReason::InvalidPrice));
+                assert_eq!(text.as_deref(), Some("price_too_high"));
+            }
+            other => panic!("Expected a quote reject, got {other:?}"),
+        }
+
+        let accepted = MassQuoteAcknowledgement::new("Q2".to_string(), QuoteAckStatus::Accepted)
+            .to_fix_message("DERIBIT", "CLIENT", 2)
+            .unwrap();
+        assert!(MassQuoteAcknowledgement::reject_error(&accepted).is_none());
     }
 }
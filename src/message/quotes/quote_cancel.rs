@@ -9,6 +9,8 @@
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::OrderSide;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -282,7 +284,7 @@ impl QuoteCancel {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::QuoteCancel)
             .sender_comp_id(sender_comp_id.to_string())
@@ -291,66 +293,76 @@ impl QuoteCancel {
             .sending_time(Utc::now());
 
         // Required fields
-        builder = builder
-            .field(117, self.quote_id.clone()) // QuoteID
-            .field(298, i32::from(self.quote_cancel_type).to_string()); // QuoteCancelType
+        builder = builder.field(tags::QUOTE_ID, self.quote_id.clone()).field(
+            tags::QUOTE_CANCEL_TYPE,
+            i32::from(self.quote_cancel_type).to_string(),
+        );
 
         // Optional fields
         if let Some(quote_req_id) = &self.quote_req_id {
-            builder = builder.field(131, quote_req_id.clone());
+            builder = builder.field(tags::QUOTE_REQ_ID, quote_req_id.clone());
         }
 
         if let Some(quote_resp_level) = &self.quote_resp_level {
-            builder = builder.field(301, quote_resp_level.to_string());
+            builder = builder.field(tags::QUOTE_RESPONSE_LEVEL, quote_resp_level.to_string());
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(quote_set_id) = &self.quote_set_id {
-            builder = builder.field(302, quote_set_id.clone());
+            builder = builder.field(tags::QUOTE_SET_ID, quote_set_id.clone());
         }
 
         if let Some(underlying_symbol) = &self.underlying_symbol {
-            builder = builder.field(311, underlying_symbol.clone());
+            builder = builder.field(tags::UNDERLYING_SYMBOL, underlying_symbol.clone());
         }
 
-        if let Some(tot_quote_entries) = &self.tot_quote_entries {
-            builder = builder.field(295, tot_quote_entries.to_string());
+        // The standard repeating group writes its own NoQuoteEntries (295)
+        if let Some(tot_quote_entries) = &self.tot_quote_entries
+            && !self.use_standard_repeating_groups
+        {
+            builder = builder.field(tags::NO_QUOTE_ENTRIES, tot_quote_entries.to_string());
         }
 
         if let Some(trading_session_id) = &self.trading_session_id {
-            builder = builder.field(336, trading_session_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_ID, trading_session_id.clone());
         }
 
         if let Some(trading_session_sub_id) = &self.trading_session_sub_id {
-            builder = builder.field(625, trading_session_sub_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_SUB_ID, trading_session_sub_id.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         // Add quote cancel entries - support both standard FIX repeating groups and simplified custom tags
         if self.use_standard_repeating_groups {
             // Standard FIX repeating groups implementation
-            builder = builder.field(295, self.quote_cancel_entries.len().to_string()); // NoQuoteEntries
+            builder = builder.field(
+                tags::NO_QUOTE_ENTRIES,
+                self.quote_cancel_entries.len().to_string(),
+            );
 
             for entry in &self.quote_cancel_entries {
-                builder = builder.field(299, entry.quote_entry_id.clone()); // QuoteEntryID
-                builder = builder.field(55, entry.symbol.clone()); // Symbol
+                builder = builder.push_field(tags::QUOTE_ENTRY_ID, entry.quote_entry_id.clone());
+                builder = builder.push_field(tags::SYMBOL, entry.symbol.clone());
 
                 if let Some(side) = &entry.side {
-                    builder = builder.field(54, char::from(*side).to_string()); // Side
+                    builder = builder.push_field(tags::SIDE, char::from(*side).to_string());
                 }
 
                 if let Some(quote_entry_reject_reason) = &entry.quote_entry_reject_reason {
-                    builder = builder.field(368, quote_entry_reject_reason.to_string()); // QuoteEntryRejectReason
+                    builder = builder.push_field(
+                        tags::QUOTE_ENTRY_REJECT_REASON,
+                        quote_entry_reject_reason.to_string(),
+                    );
                 }
             }
         } else {
@@ -375,7 +387,7 @@ impl QuoteCancel {
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -385,6 +397,7 @@ impl_json_debug_pretty!(QuoteCancel, QuoteCancelEntry);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_quote_cancel_entry_creation() {
@@ -497,7 +510,7 @@ mod tests {
         )
         .with_label("test-label".to_string());
 
-        let fix_message = cancel.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = cancel.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=Z")); // MsgType
@@ -517,7 +530,7 @@ mod tests {
         let cancel = QuoteCancel::cancel_all("QC456".to_string())
             .with_text("Cancel all active quotes".to_string());
 
-        let fix_message = cancel.to_fix_message("SENDER", "TARGET", 2).unwrap();
+        let fix_message = cancel.to_fix_string("SENDER", "TARGET", 2).unwrap();
 
         // Check required fields
         assert!(fix_message.contains("35=Z")); // MsgType
@@ -574,9 +587,7 @@ mod tests {
             QuoteCancel::with_entries("QC789".to_string(), QuoteCancelType::CancelAll, entries)
                 .enable_standard_repeating_groups();
 
-        let fix_message = quote_cancel
-            .to_fix_message("SENDER", "TARGET", 123)
-            .unwrap();
+        let fix_message = quote_cancel.to_fix_string("SENDER", "TARGET", 123).unwrap();
 
         // Should contain standard FIX tags
         assert!(fix_message.contains("295=2")); // NoQuoteEntries
@@ -608,9 +619,7 @@ mod tests {
             QuoteCancel::with_entries("QC789".to_string(), QuoteCancelType::CancelAll, entries)
                 .disable_standard_repeating_groups(); // Explicitly disable (though it's default)
 
-        let fix_message = quote_cancel
-            .to_fix_message("SENDER", "TARGET", 123)
-            .unwrap();
+        let fix_message = quote_cancel.to_fix_string("SENDER", "TARGET", 123).unwrap();
 
         // Should contain custom tags
         assert!(fix_message.contains("4000=QCE123")); // First entry ID
@@ -9,6 +9,8 @@
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::{OrderSide, TimeInForce};
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -193,7 +195,7 @@ impl QuoteRequest {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::QuoteRequest)
             .sender_comp_id(sender_comp_id.to_string())
@@ -203,53 +205,56 @@ impl QuoteRequest {
 
         // Required fields
         builder = builder
-            .field(131, self.quote_req_id.clone()) // QuoteReqID
-            .field(55, self.symbol.clone()) // Symbol
-            .field(537, i32::from(self.quote_type).to_string()) // QuoteType
-            .field(54, char::from(self.side).to_string()) // Side
-            .field(38, self.order_qty.to_string()); // OrderQty
+            .field(tags::QUOTE_REQ_ID, self.quote_req_id.clone())
+            .field(tags::SYMBOL, self.symbol.clone())
+            .field(tags::QUOTE_TYPE, i32::from(self.quote_type).to_string())
+            .field(tags::SIDE, char::from(self.side).to_string())
+            .field(tags::ORDER_QTY, self.order_qty.to_string());
 
         // Optional fields
         if let Some(valid_until_time) = &self.valid_until_time {
             builder = builder.field(
-                62,
+                tags::VALID_UNTIL_TIME,
                 valid_until_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             );
         }
 
         if let Some(quote_request_type) = &self.quote_request_type {
-            builder = builder.field(303, quote_request_type.to_string());
+            builder = builder.field(tags::QUOTE_REQUEST_TYPE, quote_request_type.to_string());
         }
 
         if let Some(time_in_force) = &self.time_in_force {
-            builder = builder.field(59, char::from(*time_in_force).to_string());
+            builder = builder.field(tags::TIME_IN_FORCE, char::from(*time_in_force).to_string());
         }
 
         if let Some(min_qty) = &self.min_qty {
-            builder = builder.field(110, min_qty.to_string());
+            builder = builder.field(tags::MIN_QTY, min_qty.to_string());
         }
 
         if let Some(settlement_type) = &self.settlement_type {
-            builder = builder.field(63, settlement_type.to_string());
+            builder = builder.field(tags::SETTL_TYPE, settlement_type.to_string());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         if let Some(market_segment_id) = &self.market_segment_id {
-            builder = builder.field(1300, market_segment_id.clone());
+            builder = builder.field(tags::MARKET_SEGMENT_ID, market_segment_id.clone());
         }
 
         if let Some(total_volume_traded) = self.total_volume_traded {
-            builder = builder.field(387, total_volume_traded.to_string());
+            builder = builder.field(tags::TOTAL_VOLUME_TRADED, total_volume_traded.to_string());
         }
 
         if let Some(transact_time) = &self.transact_time {
-            builder = builder.field(60, transact_time.format("%Y%m%d-%H:%M:%S%.3f").to_string());
+            builder = builder.field(
+                tags::TRANSACT_TIME,
+                transact_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+            );
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -259,6 +264,7 @@ impl_json_debug_pretty!(QuoteRequest);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_quote_request_creation() {
@@ -341,7 +347,7 @@ mod tests {
         )
         .with_label("test-label".to_string());
 
-        let fix_message = request.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=R")); // MsgType
@@ -399,7 +405,7 @@ mod tests {
         .with_total_volume_traded(500.25)
         .with_transact_time(transact_time);
 
-        let fix_message = request.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that new fields are present
         assert!(fix_message.contains("387=500.25")); // TotalVolumeTraded
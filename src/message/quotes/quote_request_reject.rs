@@ -6,14 +6,17 @@
 
 //! Quote Request Reject FIX Message Implementation
 
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 /// Quote request reject reason enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum QuoteRequestRejectReason {
     /// Unknown symbol
     UnknownSymbol,
@@ -27,10 +30,20 @@ pub enum QuoteRequestRejectReason {
     InvalidPrice,
     /// Not authorized to request quote
     NotAuthorizedToRequestQuote,
-    /// No matching quote
+    /// No match for inquiry
     NoMatchingQuote,
+    /// No market for instrument
+    NoMarketForInstrument,
+    /// No inventory
+    NoInventory,
+    /// Pass
+    Pass,
+    /// Insufficient credit
+    InsufficientCredit,
     /// Other
     Other,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<QuoteRequestRejectReason> for i32 {
@@ -40,14 +53,20 @@ impl From<QuoteRequestRejectReason> for i32 {
             QuoteRequestRejectReason::ExchangeClosed => 2,
             QuoteRequestRejectReason::QuoteRequestExceedsLimit => 3,
             QuoteRequestRejectReason::TooLateToEnter => 4,
-            QuoteRequestRejectReason::InvalidPrice => 6,
-            QuoteRequestRejectReason::NotAuthorizedToRequestQuote => 7,
-            QuoteRequestRejectReason::NoMatchingQuote => 8,
+            QuoteRequestRejectReason::InvalidPrice => 5,
+            QuoteRequestRejectReason::NotAuthorizedToRequestQuote => 6,
+            QuoteRequestRejectReason::NoMatchingQuote => 7,
+            QuoteRequestRejectReason::NoMarketForInstrument => 8,
+            QuoteRequestRejectReason::NoInventory => 9,
+            QuoteRequestRejectReason::Pass => 10,
+            QuoteRequestRejectReason::InsufficientCredit => 11,
             QuoteRequestRejectReason::Other => 99,
+            QuoteRequestRejectReason::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`QuoteRequestRejectReason::Unknown`]
 impl TryFrom<i32> for QuoteRequestRejectReason {
     type Error = String;
 
@@ -57,11 +76,15 @@ impl TryFrom<i32> for QuoteRequestRejectReason {
             2 => Ok(QuoteRequestRejectReason::ExchangeClosed),
             3 => Ok(QuoteRequestRejectReason::QuoteRequestExceedsLimit),
             4 => Ok(QuoteRequestRejectReason::TooLateToEnter),
-            6 => Ok(QuoteRequestRejectReason::InvalidPrice),
-            7 => Ok(QuoteRequestRejectReason::NotAuthorizedToRequestQuote),
-            8 => Ok(QuoteRequestRejectReason::NoMatchingQuote),
+            5 => Ok(QuoteRequestRejectReason::InvalidPrice),
+            6 => Ok(QuoteRequestRejectReason::NotAuthorizedToRequestQuote),
+            7 => Ok(QuoteRequestRejectReason::NoMatchingQuote),
+            8 => Ok(QuoteRequestRejectReason::NoMarketForInstrument),
+            9 => Ok(QuoteRequestRejectReason::NoInventory),
+            10 => Ok(QuoteRequestRejectReason::Pass),
+            11 => Ok(QuoteRequestRejectReason::InsufficientCredit),
             99 => Ok(QuoteRequestRejectReason::Other),
-            _ => Err(format!("Invalid QuoteRequestRejectReason: {}", value)),
+            _ => Ok(QuoteRequestRejectReason::Unknown(value)),
         }
     }
 }
@@ -156,13 +179,67 @@ impl QuoteRequestReject {
         self
     }
 
+    /// Error for a received Quote Request Reject (AG)
+    ///
+    /// Keeps the exchange's Text (58); an unparsable QuoteRequestRejectReason
+    /// (658) is left out rather than failing.
+    pub fn reject_error(message: &FixMessage) -> DeribitFixError {
+        DeribitFixError::QuoteRequestRejected {
+            quote_req_id: message
+                .get_field(tags::QUOTE_REQ_ID)
+                .cloned()
+                .unwrap_or_default(),
+            reason: message
+                .get_field(tags::QUOTE_REQUEST_REJECT_REASON)
+                .and_then(|value| value.parse::<i32>().ok())
+                .and_then(|value| QuoteRequestRejectReason::try_from(value).ok()),
+            text: message.get_field(tags::TEXT).cloned(),
+        }
+    }
+
+    /// Parse from FIX message
+    ///
+    /// QuoteReqID (131) and QuoteRequestRejectReason (658) are required; a
+    /// reason outside the FIX 4.4 values is kept as
+    /// [`QuoteRequestRejectReason::Unknown`].
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let required = |tag: u32, name: &str| {
+            message.get_field(tag).ok_or_else(|| {
+                DeribitFixError::MessageParsing(format!("{name} ({tag}) is required"))
+            })
+        };
+        let reason = required(
+            tags::QUOTE_REQUEST_REJECT_REASON,
+            "QuoteRequestRejectReason",
+        )?;
+        let reason = reason.parse::<i32>().map_err(|_| {
+            DeribitFixError::MessageParsing(format!(
+                "Invalid value for tag {}: {reason}",
+                tags::QUOTE_REQUEST_REJECT_REASON
+            ))
+        })?;
+
+        let mut reject = Self::new(
+            required(tags::QUOTE_REQ_ID, "QuoteReqID")?.clone(),
+            QuoteRequestRejectReason::try_from(reason)
+                .unwrap_or(QuoteRequestRejectReason::Unknown(reason)),
+        );
+        reject.text = message.get_field(tags::TEXT).cloned();
+        reject.symbol = message.get_field(tags::SYMBOL).cloned();
+        reject.no_related_sym = message
+            .get_field(tags::NO_RELATED_SYM)
+            .and_then(|value| value.parse().ok());
+        reject.deribit_label = message.get_field(tags::DERIBIT_LABEL).cloned();
+        Ok(reject)
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::QuoteRequestReject)
             .sender_comp_id(sender_comp_id.to_string())
@@ -172,27 +249,30 @@ impl QuoteRequestReject {
 
         // Required fields
         builder = builder
-            .field(131, self.quote_req_id.clone()) // QuoteReqID
-            .field(658, i32::from(self.quote_request_reject_reason).to_string()); // QuoteRequestRejectReason
+            .field(tags::QUOTE_REQ_ID, self.quote_req_id.clone())
+            .field(
+                tags::QUOTE_REQUEST_REJECT_REASON,
+                i32::from(self.quote_request_reject_reason).to_string(),
+            );
 
         // Optional fields
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(no_related_sym) = &self.no_related_sym {
-            builder = builder.field(146, no_related_sym.to_string());
+            builder = builder.field(tags::NO_RELATED_SYM, no_related_sym.to_string());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -202,6 +282,7 @@ impl_json_debug_pretty!(QuoteRequestReject);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_quote_request_reject_creation() {
@@ -276,7 +357,7 @@ mod tests {
             QuoteRequestReject::unknown_symbol("QR123".to_string(), "BTC-PERPETUAL".to_string())
                 .with_label("test-label".to_string());
 
-        let fix_message = reject.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = reject.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=AG")); // MsgType
@@ -296,12 +377,19 @@ mod tests {
             3
         );
         assert_eq!(i32::from(QuoteRequestRejectReason::TooLateToEnter), 4);
-        assert_eq!(i32::from(QuoteRequestRejectReason::InvalidPrice), 6);
+        assert_eq!(i32::from(QuoteRequestRejectReason::InvalidPrice), 5);
         assert_eq!(
             i32::from(QuoteRequestRejectReason::NotAuthorizedToRequestQuote),
-            7
+            6
+        );
+        assert_eq!(i32::from(QuoteRequestRejectReason::NoMatchingQuote), 7);
+        assert_eq!(
+            i32::from(QuoteRequestRejectReason::NoMarketForInstrument),
+            8
         );
-        assert_eq!(i32::from(QuoteRequestRejectReason::NoMatchingQuote), 8);
+        assert_eq!(i32::from(QuoteRequestRejectReason::NoInventory), 9);
+        assert_eq!(i32::from(QuoteRequestRejectReason::Pass), 10);
+        assert_eq!(i32::from(QuoteRequestRejectReason::InsufficientCredit), 11);
         assert_eq!(i32::from(QuoteRequestRejectReason::Other), 99);
 
         assert_eq!(
@@ -321,30 +409,41 @@ mod tests {
             QuoteRequestRejectReason::TooLateToEnter
         );
         assert_eq!(
-            QuoteRequestRejectReason::try_from(6).unwrap(),
+            QuoteRequestRejectReason::try_from(5).unwrap(),
             QuoteRequestRejectReason::InvalidPrice
         );
         assert_eq!(
-            QuoteRequestRejectReason::try_from(7).unwrap(),
+            QuoteRequestRejectReason::try_from(6).unwrap(),
             QuoteRequestRejectReason::NotAuthorizedToRequestQuote
         );
         assert_eq!(
-            QuoteRequestRejectReason::try_from(8).unwrap(),
+            QuoteRequestRejectReason::try_from(7).unwrap(),
             QuoteRequestRejectReason::NoMatchingQuote
         );
+        assert_eq!(
+            QuoteRequestRejectReason::try_from(8).unwrap(),
+            QuoteRequestRejectReason::NoMarketForInstrument
+        );
+        assert_eq!(
+            QuoteRequestRejectReason::try_from(11).unwrap(),
+            QuoteRequestRejectReason::InsufficientCredit
+        );
         assert_eq!(
             QuoteRequestRejectReason::try_from(99).unwrap(),
             QuoteRequestRejectReason::Other
         );
 
-        assert!(QuoteRequestRejectReason::try_from(50).is_err());
+        assert_eq!(
+            QuoteRequestRejectReason::try_from(50).unwrap(),
+            QuoteRequestRejectReason::Unknown(50)
+        );
     }
 
     #[test]
     fn test_quote_request_reject_minimal_fix_message() {
         let reject = QuoteRequestReject::new("QR456".to_string(), QuoteRequestRejectReason::Other);
 
-        let fix_message = reject.to_fix_message("SENDER", "TARGET", 2).unwrap();
+        let fix_message = reject.to_fix_string("SENDER", "TARGET", 2).unwrap();
 
         // Check required fields only
         assert!(fix_message.contains("35=AG")); // MsgType
@@ -357,4 +456,60 @@ mod tests {
         assert!(!fix_message.contains("\x0155=")); // Symbol field not set  
         assert!(!fix_message.contains("\x01146=")); // NoRelatedSym field not set
     }
+
+    #[test]
+    fn test_quote_request_reject_from_fix_message() {
+        let message = QuoteRequestReject::new(
+            "RFQ_1".to_string(),
+            QuoteRequestRejectReason::NotAuthorizedToRequestQuote,
+        )
+        .with_text("not_enough_funds".to_string())
+        .with_symbol("BTC-PERPETUAL".to_string())
+        .to_fix_message("DERIBIT", "CLIENT", 3)
+        .unwrap();
+
+        let parsed = QuoteRequestReject::from_fix_message(&message).unwrap();
+        assert_eq!(parsed.quote_req_id, "RFQ_1");
+        assert_eq!(
+            parsed.quote_request_reject_reason,
+            QuoteRequestRejectReason::NotAuthorizedToRequestQuote
+        );
+        assert_eq!(parsed.text.as_deref(), Some("not_enough_funds"));
+        assert_eq!(parsed.symbol.as_deref(), Some("BTC-PERPETUAL"));
+
+        match QuoteRequestReject::reject_error(&message) {
+            DeribitFixError::QuoteRequestRejected {
+                quote_req_id,
+                reason,
+                text,
+            } => {
+                assert_eq!(quote_req_id, "RFQ_1");
+                assert_eq!(
+                    reason,
+                    Some(QuoteRequestRejectReason::NotAuthorizedToRequestQuote)
+                );
+                assert_eq!(text.as_deref(), Some("not_enough_funds"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        let mut unknown = message.clone();
+        unknown.set_field(tags::QUOTE_REQUEST_REJECT_REASON, "50".to_string());
+        assert_eq!(
+            QuoteRequestReject::from_fix_message(&unknown)
+                .unwrap()
+                .quote_request_reject_reason,
+            QuoteRequestRejectReason::Unknown(50)
+        );
+
+        let missing = MessageBuilder::new()
+            .msg_type(MsgType::QuoteRequestReject)
+            .sender_comp_id("DERIBIT".to_string())
+            .target_comp_id("CLIENT".to_string())
+            .msg_seq_num(4)
+            .field(tags::QUOTE_REQUEST_REJECT_REASON, "1".to_string())
+            .build()
+            .unwrap();
+        assert!(QuoteRequestReject::from_fix_message(&missing).is_err());
+    }
 }
@@ -9,12 +9,15 @@
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::OrderSide;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Quote status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum QuoteStatus {
     /// Quote accepted
     Accepted,
@@ -48,6 +51,8 @@ pub enum QuoteStatus {
     CanceledDueToLockMarket,
     /// Canceled due to cross market
     CanceledDueToCrossMarket,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<QuoteStatus> for i32 {
@@ -69,10 +74,12 @@ impl From<QuoteStatus> for i32 {
             QuoteStatus::CrossMarketWarning => 13,
             QuoteStatus::CanceledDueToLockMarket => 14,
             QuoteStatus::CanceledDueToCrossMarket => 15,
+            QuoteStatus::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`QuoteStatus::Unknown`]
 impl TryFrom<i32> for QuoteStatus {
     type Error = String;
 
@@ -94,7 +101,7 @@ impl TryFrom<i32> for QuoteStatus {
             13 => Ok(QuoteStatus::CrossMarketWarning),
             14 => Ok(QuoteStatus::CanceledDueToLockMarket),
             15 => Ok(QuoteStatus::CanceledDueToCrossMarket),
-            _ => Err(format!("Invalid QuoteStatus: {}", value)),
+            _ => Ok(QuoteStatus::Unknown(value)),
         }
     }
 }
@@ -296,7 +303,7 @@ impl QuoteStatusReport {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::QuoteStatusReport)
             .sender_comp_id(sender_comp_id.to_string())
@@ -306,71 +313,74 @@ impl QuoteStatusReport {
 
         // Required fields
         builder = builder
-            .field(649, self.quote_status_report_id.clone()) // QuoteStatusReportID
-            .field(297, i32::from(self.quote_status).to_string()) // QuoteStatus
-            .field(55, self.symbol.clone()) // Symbol
             .field(
-                60,
+                tags::QUOTE_STATUS_REQ_ID,
+                self.quote_status_report_id.clone(),
+            ) // QuoteStatusReportID
+            .field(tags::QUOTE_STATUS, i32::from(self.quote_status).to_string())
+            .field(tags::SYMBOL, self.symbol.clone())
+            .field(
+                tags::TRANSACT_TIME,
                 self.transact_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             ); // TransactTime
 
         // Optional fields
         if let Some(quote_req_id) = &self.quote_req_id {
-            builder = builder.field(131, quote_req_id.clone());
+            builder = builder.field(tags::QUOTE_REQ_ID, quote_req_id.clone());
         }
 
         if let Some(quote_id) = &self.quote_id {
-            builder = builder.field(117, quote_id.clone());
+            builder = builder.field(tags::QUOTE_ID, quote_id.clone());
         }
 
         if let Some(quote_resp_level) = &self.quote_resp_level {
-            builder = builder.field(301, quote_resp_level.to_string());
+            builder = builder.field(tags::QUOTE_RESPONSE_LEVEL, quote_resp_level.to_string());
         }
 
         if let Some(quote_reject_reason) = &self.quote_reject_reason {
-            builder = builder.field(300, quote_reject_reason.to_string());
+            builder = builder.field(tags::QUOTE_REJECT_REASON, quote_reject_reason.to_string());
         }
 
         if let Some(side) = &self.side {
-            builder = builder.field(54, char::from(*side).to_string());
+            builder = builder.field(tags::SIDE, char::from(*side).to_string());
         }
 
         if let Some(bid_px) = &self.bid_px {
-            builder = builder.field(132, bid_px.to_string());
+            builder = builder.field(tags::BID_PX, bid_px.to_string());
         }
 
         if let Some(offer_px) = &self.offer_px {
-            builder = builder.field(133, offer_px.to_string());
+            builder = builder.field(tags::OFFER_PX, offer_px.to_string());
         }
 
         if let Some(bid_size) = &self.bid_size {
-            builder = builder.field(134, bid_size.to_string());
+            builder = builder.field(tags::BID_SIZE, bid_size.to_string());
         }
 
         if let Some(offer_size) = &self.offer_size {
-            builder = builder.field(135, offer_size.to_string());
+            builder = builder.field(tags::OFFER_SIZE, offer_size.to_string());
         }
 
         if let Some(valid_until_time) = &self.valid_until_time {
             builder = builder.field(
-                62,
+                tags::VALID_UNTIL_TIME,
                 valid_until_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             );
         }
 
         if let Some(mid_px) = &self.mid_px {
-            builder = builder.field(631, mid_px.to_string());
+            builder = builder.field(tags::MID_PX, mid_px.to_string());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -381,6 +391,7 @@ impl_json_debug_pretty!(QuoteStatusReport);
 mod tests {
     use super::*;
     use crate::message::QuoteCancelType;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_quote_status_report_creation() {
@@ -462,7 +473,7 @@ mod tests {
         .with_quote_req_id("QR123".to_string())
         .with_label("test-label".to_string());
 
-        let fix_message = report.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = report.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=AI")); // MsgType
@@ -490,7 +501,7 @@ mod tests {
         assert_eq!(QuoteStatus::try_from(5).unwrap(), QuoteStatus::Rejected);
         assert_eq!(QuoteStatus::try_from(7).unwrap(), QuoteStatus::Expired);
 
-        assert!(QuoteStatus::try_from(99).is_err());
+        assert_eq!(QuoteStatus::try_from(99).unwrap(), QuoteStatus::Unknown(99));
     }
 
     #[test]
@@ -9,6 +9,8 @@
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::{OrderSide, TimeInForce};
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -349,7 +351,7 @@ impl RfqRequest {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::RfqRequest)
             .sender_comp_id(sender_comp_id.to_string())
@@ -359,77 +361,83 @@ impl RfqRequest {
 
         // Required fields
         builder = builder
-            .field(644, self.rfq_req_id.clone()) // RFQReqID
-            .field(146, self.no_related_sym.to_string()) // NoRelatedSym
-            .field(55, self.symbol.clone()) // Symbol
-            .field(38, self.order_qty.to_string()); // OrderQty
+            .field(tags::RFQ_REQ_ID, self.rfq_req_id.clone())
+            .field(tags::NO_RELATED_SYM, self.no_related_sym.to_string())
+            .field(tags::SYMBOL, self.symbol.clone())
+            .field(tags::ORDER_QTY, self.order_qty.to_string());
 
         // Optional fields
         if let Some(rfq_request_type) = &self.rfq_request_type {
-            builder = builder.field(303, i32::from(*rfq_request_type).to_string());
+            builder = builder.field(
+                tags::QUOTE_REQUEST_TYPE,
+                i32::from(*rfq_request_type).to_string(),
+            );
         }
 
         if let Some(subscription_request_type) = &self.subscription_request_type {
-            builder = builder.field(263, subscription_request_type.to_string());
+            builder = builder.field(
+                tags::SUBSCRIPTION_REQUEST_TYPE,
+                subscription_request_type.to_string(),
+            );
         }
 
         if let Some(side) = &self.side {
-            builder = builder.field(54, i32::from(*side).to_string());
+            builder = builder.field(tags::SIDE, i32::from(*side).to_string());
         }
 
         if let Some(valid_until_time) = &self.valid_until_time {
             builder = builder.field(
-                62,
+                tags::VALID_UNTIL_TIME,
                 valid_until_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             );
         }
 
         if let Some(time_in_force) = &self.time_in_force {
-            builder = builder.field(59, char::from(*time_in_force).to_string());
+            builder = builder.field(tags::TIME_IN_FORCE, char::from(*time_in_force).to_string());
         }
 
         if let Some(settlement_type) = &self.settlement_type {
-            builder = builder.field(63, settlement_type.to_string());
+            builder = builder.field(tags::SETTL_TYPE, settlement_type.to_string());
         }
 
         if let Some(settlement_date) = &self.settlement_date {
-            builder = builder.field(64, settlement_date.clone());
+            builder = builder.field(tags::SETTL_DATE, settlement_date.clone());
         }
 
         if let Some(currency) = &self.currency {
-            builder = builder.field(15, currency.clone());
+            builder = builder.field(tags::CURRENCY, currency.clone());
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(clearing_account) = &self.clearing_account {
-            builder = builder.field(440, clearing_account.clone());
+            builder = builder.field(tags::CLEARING_ACCOUNT, clearing_account.clone());
         }
 
         if let Some(position_effect) = &self.position_effect {
-            builder = builder.field(77, position_effect.to_string());
+            builder = builder.field(tags::POSITION_EFFECT, position_effect.to_string());
         }
 
         if let Some(no_legs) = &self.no_legs {
-            builder = builder.field(555, no_legs.to_string());
+            builder = builder.field(tags::NO_LEGS, no_legs.to_string());
         }
 
         if let Some(trading_session_id) = &self.trading_session_id {
-            builder = builder.field(336, trading_session_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_ID, trading_session_id.clone());
         }
 
         if let Some(trading_session_sub_id) = &self.trading_session_sub_id {
-            builder = builder.field(625, trading_session_sub_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_SUB_ID, trading_session_sub_id.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         // Add RFQ request legs (simplified - in real implementation would need repeating groups)
@@ -454,7 +462,7 @@ impl RfqRequest {
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -464,6 +472,7 @@ impl_json_debug_pretty!(RfqRequest, RfqRequestLeg);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_rfq_request_leg_creation() {
@@ -572,7 +581,7 @@ mod tests {
             .with_rfq_request_type(RfqRequestType::Manual)
             .with_label("test-label".to_string());
 
-        let fix_message = rfq.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = rfq.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=AH")); // MsgType
@@ -592,7 +601,7 @@ mod tests {
 
         let rfq = RfqRequest::multi_leg("RFQ456".to_string(), vec![leg]);
 
-        let fix_message = rfq.to_fix_message("SENDER", "TARGET", 2).unwrap();
+        let fix_message = rfq.to_fix_string("SENDER", "TARGET", 2).unwrap();
 
         // Check required fields
         assert!(fix_message.contains("35=AH")); // MsgType
@@ -8,6 +8,8 @@
 
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -299,7 +301,7 @@ impl MMProtectionLimits {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MmProtectionLimits)
             .sender_comp_id(sender_comp_id.to_string())
@@ -309,92 +311,125 @@ impl MMProtectionLimits {
 
         // Required fields
         builder = builder
-            .field(9001, self.mm_protection_req_id.clone()) // MMProtectionReqID (custom tag)
-            .field(9002, i32::from(self.mm_protection_action).to_string()) // MMProtectionAction (custom tag)
-            .field(9003, i32::from(self.mm_protection_scope).to_string()); // MMProtectionScope (custom tag)
+            .field(
+                tags::mm_protection::REQ_ID,
+                self.mm_protection_req_id.clone(),
+            )
+            .field(
+                tags::mm_protection::ACTION,
+                i32::from(self.mm_protection_action).to_string(),
+            )
+            .field(
+                tags::mm_protection::SCOPE,
+                i32::from(self.mm_protection_scope).to_string(),
+            );
 
         // Optional fields based on scope
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(underlying_symbol) = &self.underlying_symbol {
-            builder = builder.field(311, underlying_symbol.clone());
+            builder = builder.field(tags::UNDERLYING_SYMBOL, underlying_symbol.clone());
         }
 
         if let Some(instrument_group) = &self.instrument_group {
-            builder = builder.field(9004, instrument_group.clone()); // Custom tag for instrument group
+            builder = builder.field(
+                tags::mm_protection::INSTRUMENT_GROUP,
+                instrument_group.clone(),
+            ); // Custom tag for instrument group
         }
 
         // Risk limits
         if let Some(max_position_limit) = &self.max_position_limit {
-            builder = builder.field(9005, max_position_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::MAX_POSITION_LIMIT,
+                max_position_limit.to_string(),
+            );
         }
 
         if let Some(max_order_qty_limit) = &self.max_order_qty_limit {
-            builder = builder.field(9006, max_order_qty_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::MAX_ORDER_QTY_LIMIT,
+                max_order_qty_limit.to_string(),
+            );
         }
 
         if let Some(max_orders_limit) = &self.max_orders_limit {
-            builder = builder.field(9007, max_orders_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::MAX_ORDERS_LIMIT,
+                max_orders_limit.to_string(),
+            );
         }
 
         if let Some(time_window_seconds) = &self.time_window_seconds {
-            builder = builder.field(9009, time_window_seconds.to_string());
+            builder = builder.field(
+                tags::mm_protection::TIME_WINDOW_SECONDS,
+                time_window_seconds.to_string(),
+            );
         }
 
         // Greeks limits
         if let Some(delta_limit) = &self.delta_limit {
-            builder = builder.field(9010, delta_limit.to_string());
+            builder = builder.field(tags::mm_protection::DELTA_LIMIT, delta_limit.to_string());
         }
 
         if let Some(vega_limit) = &self.vega_limit {
-            builder = builder.field(9011, vega_limit.to_string());
+            builder = builder.field(tags::mm_protection::VEGA_LIMIT, vega_limit.to_string());
         }
 
         if let Some(gamma_limit) = &self.gamma_limit {
-            builder = builder.field(9012, gamma_limit.to_string());
+            builder = builder.field(tags::mm_protection::GAMMA_LIMIT, gamma_limit.to_string());
         }
 
         if let Some(theta_limit) = &self.theta_limit {
-            builder = builder.field(9013, theta_limit.to_string());
+            builder = builder.field(tags::mm_protection::THETA_LIMIT, theta_limit.to_string());
         }
 
         if let Some(total_risk_limit) = &self.total_risk_limit {
-            builder = builder.field(9014, total_risk_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::TOTAL_RISK_LIMIT,
+                total_risk_limit.to_string(),
+            );
         }
 
         // Validity period
         if let Some(valid_from) = &self.valid_from {
-            builder = builder.field(9015, valid_from.format("%Y%m%d-%H:%M:%S%.3f").to_string());
+            builder = builder.field(
+                tags::mm_protection::VALID_FROM,
+                valid_from.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+            );
         }
 
         if let Some(valid_until) = &self.valid_until {
-            builder = builder.field(9016, valid_until.format("%Y%m%d-%H:%M:%S%.3f").to_string());
+            builder = builder.field(
+                tags::mm_protection::VALID_UNTIL,
+                valid_until.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+            );
         }
 
         // Standard optional fields
         if let Some(trading_session_id) = &self.trading_session_id {
-            builder = builder.field(336, trading_session_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_ID, trading_session_id.clone());
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(parties) = &self.parties {
-            builder = builder.field(453, parties.clone());
+            builder = builder.field(tags::NO_PARTY_IDS, parties.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -404,6 +439,7 @@ impl_json_debug_pretty!(MMProtectionLimits);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_mm_protection_limits_creation() {
@@ -525,7 +561,7 @@ mod tests {
                 .with_order_qty_limit(50.0)
                 .with_label("test-label".to_string());
 
-        let fix_message = limits.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = limits.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=MM")); // MsgType
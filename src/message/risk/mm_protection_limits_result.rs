@@ -8,6 +8,8 @@
 
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,7 @@ pub use super::mm_protection_limits::{MMProtectionAction, MMProtectionScope};
 
 /// MM Protection result status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum MMProtectionResultStatus {
     /// Request accepted
     Accepted,
@@ -28,6 +31,8 @@ pub enum MMProtectionResultStatus {
     PartiallyCompleted,
     /// Request pending
     Pending,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<MMProtectionResultStatus> for i32 {
@@ -38,10 +43,12 @@ impl From<MMProtectionResultStatus> for i32 {
             MMProtectionResultStatus::Completed => 2,
             MMProtectionResultStatus::PartiallyCompleted => 3,
             MMProtectionResultStatus::Pending => 4,
+            MMProtectionResultStatus::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`MMProtectionResultStatus::Unknown`]
 impl TryFrom<i32> for MMProtectionResultStatus {
     type Error = String;
 
@@ -52,13 +59,14 @@ impl TryFrom<i32> for MMProtectionResultStatus {
             2 => Ok(MMProtectionResultStatus::Completed),
             3 => Ok(MMProtectionResultStatus::PartiallyCompleted),
             4 => Ok(MMProtectionResultStatus::Pending),
-            _ => Err(format!("Invalid MMProtectionResultStatus: {}", value)),
+            _ => Ok(MMProtectionResultStatus::Unknown(value)),
         }
     }
 }
 
 /// MM Protection reject reason enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum MMProtectionRejectReason {
     /// Unknown request
     UnknownRequest,
@@ -82,6 +90,8 @@ pub enum MMProtectionRejectReason {
     LimitsNotFound,
     /// Other
     Other,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<MMProtectionRejectReason> for i32 {
@@ -98,10 +108,12 @@ impl From<MMProtectionRejectReason> for i32 {
             MMProtectionRejectReason::LimitsAlreadyExist => 9,
             MMProtectionRejectReason::LimitsNotFound => 10,
             MMProtectionRejectReason::Other => 99,
+            MMProtectionRejectReason::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`MMProtectionRejectReason::Unknown`]
 impl TryFrom<i32> for MMProtectionRejectReason {
     type Error = String;
 
@@ -118,7 +130,7 @@ impl TryFrom<i32> for MMProtectionRejectReason {
             9 => Ok(MMProtectionRejectReason::LimitsAlreadyExist),
             10 => Ok(MMProtectionRejectReason::LimitsNotFound),
             99 => Ok(MMProtectionRejectReason::Other),
-            _ => Err(format!("Invalid MMProtectionRejectReason: {}", value)),
+            _ => Ok(MMProtectionRejectReason::Unknown(value)),
         }
     }
 }
@@ -354,7 +366,7 @@ impl MMProtectionLimitsResult {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MmProtectionLimitsResult)
             .sender_comp_id(sender_comp_id.to_string())
@@ -364,15 +376,24 @@ impl MMProtectionLimitsResult {
 
         // Required fields
         builder = builder
-            .field(9001, self.mm_protection_req_id.clone()) // MMProtectionReqID
-            .field(9002, i32::from(self.mm_protection_action).to_string()) // MMProtectionAction
-            .field(9003, i32::from(self.mm_protection_scope).to_string()) // MMProtectionScope
             .field(
-                9017,
+                tags::mm_protection::REQ_ID,
+                self.mm_protection_req_id.clone(),
+            )
+            .field(
+                tags::mm_protection::ACTION,
+                i32::from(self.mm_protection_action).to_string(),
+            )
+            .field(
+                tags::mm_protection::SCOPE,
+                i32::from(self.mm_protection_scope).to_string(),
+            )
+            .field(
+                tags::mm_protection::RESULT_STATUS,
                 i32::from(self.mm_protection_result_status).to_string(),
             ) // MMProtectionResultStatus
             .field(
-                9018,
+                tags::mm_protection::PROCESSING_TIME,
                 self.processing_time
                     .format("%Y%m%d-%H:%M:%S%.3f")
                     .to_string(),
@@ -380,70 +401,103 @@ impl MMProtectionLimitsResult {
 
         // Optional fields
         if let Some(reject_reason) = &self.mm_protection_reject_reason {
-            builder = builder.field(9019, i32::from(*reject_reason).to_string());
+            builder = builder.field(
+                tags::mm_protection::REJECT_REASON,
+                i32::from(*reject_reason).to_string(),
+            );
         }
 
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(underlying_symbol) = &self.underlying_symbol {
-            builder = builder.field(311, underlying_symbol.clone());
+            builder = builder.field(tags::UNDERLYING_SYMBOL, underlying_symbol.clone());
         }
 
         if let Some(instrument_group) = &self.instrument_group {
-            builder = builder.field(9004, instrument_group.clone());
+            builder = builder.field(
+                tags::mm_protection::INSTRUMENT_GROUP,
+                instrument_group.clone(),
+            );
         }
 
         // Current limits
         if let Some(current_max_position_limit) = &self.current_max_position_limit {
-            builder = builder.field(9020, current_max_position_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_MAX_POSITION_LIMIT,
+                current_max_position_limit.to_string(),
+            );
         }
 
         if let Some(current_max_order_qty_limit) = &self.current_max_order_qty_limit {
-            builder = builder.field(9021, current_max_order_qty_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_MAX_ORDER_QTY_LIMIT,
+                current_max_order_qty_limit.to_string(),
+            );
         }
 
         if let Some(current_max_orders_limit) = &self.current_max_orders_limit {
-            builder = builder.field(9022, current_max_orders_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_MAX_ORDERS_LIMIT,
+                current_max_orders_limit.to_string(),
+            );
         }
 
         if let Some(current_time_window_seconds) = &self.current_time_window_seconds {
-            builder = builder.field(9023, current_time_window_seconds.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_TIME_WINDOW_SECONDS,
+                current_time_window_seconds.to_string(),
+            );
         }
 
         // Current Greeks limits
         if let Some(current_delta_limit) = &self.current_delta_limit {
-            builder = builder.field(9024, current_delta_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_DELTA_LIMIT,
+                current_delta_limit.to_string(),
+            );
         }
 
         if let Some(current_vega_limit) = &self.current_vega_limit {
-            builder = builder.field(9025, current_vega_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_VEGA_LIMIT,
+                current_vega_limit.to_string(),
+            );
         }
 
         if let Some(current_gamma_limit) = &self.current_gamma_limit {
-            builder = builder.field(9026, current_gamma_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_GAMMA_LIMIT,
+                current_gamma_limit.to_string(),
+            );
         }
 
         if let Some(current_theta_limit) = &self.current_theta_limit {
-            builder = builder.field(9027, current_theta_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_THETA_LIMIT,
+                current_theta_limit.to_string(),
+            );
         }
 
         if let Some(current_total_risk_limit) = &self.current_total_risk_limit {
-            builder = builder.field(9028, current_total_risk_limit.to_string());
+            builder = builder.field(
+                tags::mm_protection::CURRENT_TOTAL_RISK_LIMIT,
+                current_total_risk_limit.to_string(),
+            );
         }
 
         // Current validity period
         if let Some(current_valid_from) = &self.current_valid_from {
             builder = builder.field(
-                9029,
+                tags::mm_protection::CURRENT_VALID_FROM,
                 current_valid_from.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             );
         }
 
         if let Some(current_valid_until) = &self.current_valid_until {
             builder = builder.field(
-                9030,
+                tags::mm_protection::CURRENT_VALID_UNTIL,
                 current_valid_until
                     .format("%Y%m%d-%H:%M:%S%.3f")
                     .to_string(),
@@ -451,27 +505,30 @@ impl MMProtectionLimitsResult {
         }
 
         if let Some(affected_instruments_count) = &self.affected_instruments_count {
-            builder = builder.field(9031, affected_instruments_count.to_string());
+            builder = builder.field(
+                tags::mm_protection::AFFECTED_INSTRUMENTS_COUNT,
+                affected_instruments_count.to_string(),
+            );
         }
 
         // Standard optional fields
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(parties) = &self.parties {
-            builder = builder.field(453, parties.clone());
+            builder = builder.field(tags::NO_PARTY_IDS, parties.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -481,6 +538,7 @@ impl_json_debug_pretty!(MMProtectionLimitsResult);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_mm_protection_limits_result_creation() {
@@ -616,7 +674,7 @@ mod tests {
         .with_current_limits(Some(500.0), Some(50.0), None, None, None)
         .with_label("test-label".to_string());
 
-        let fix_message = result.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = result.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=MR")); // MsgType
@@ -659,7 +717,10 @@ mod tests {
             MMProtectionResultStatus::Pending
         );
 
-        assert!(MMProtectionResultStatus::try_from(99).is_err());
+        assert_eq!(
+            MMProtectionResultStatus::try_from(99).unwrap(),
+            MMProtectionResultStatus::Unknown(99)
+        );
     }
 
     #[test]
@@ -686,6 +747,9 @@ mod tests {
             MMProtectionRejectReason::Other
         );
 
-        assert!(MMProtectionRejectReason::try_from(50).is_err());
+        assert_eq!(
+            MMProtectionRejectReason::try_from(50).unwrap(),
+            MMProtectionRejectReason::Unknown(50)
+        );
     }
 }
@@ -8,6 +8,8 @@
 
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -385,7 +387,7 @@ impl MMProtectionReset {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MmProtectionReset)
             .sender_comp_id(sender_comp_id.to_string())
@@ -395,27 +397,42 @@ impl MMProtectionReset {
 
         // Required fields
         builder = builder
-            .field(9032, self.mm_protection_reset_req_id.clone()) // MMProtectionResetReqID
-            .field(9033, i32::from(self.mm_protection_reset_type).to_string()) // MMProtectionResetType
-            .field(9034, i32::from(self.mm_protection_reset_reason).to_string()) // MMProtectionResetReason
-            .field(9003, i32::from(self.mm_protection_scope).to_string()); // MMProtectionScope
+            .field(
+                tags::mm_protection::RESET_REQ_ID,
+                self.mm_protection_reset_req_id.clone(),
+            )
+            .field(
+                tags::mm_protection::RESET_TYPE,
+                i32::from(self.mm_protection_reset_type).to_string(),
+            )
+            .field(
+                tags::mm_protection::RESET_REASON,
+                i32::from(self.mm_protection_reset_reason).to_string(),
+            )
+            .field(
+                tags::mm_protection::SCOPE,
+                i32::from(self.mm_protection_scope).to_string(),
+            );
 
         // Optional fields
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(underlying_symbol) = &self.underlying_symbol {
-            builder = builder.field(311, underlying_symbol.clone());
+            builder = builder.field(tags::UNDERLYING_SYMBOL, underlying_symbol.clone());
         }
 
         if let Some(instrument_group) = &self.instrument_group {
-            builder = builder.field(9004, instrument_group.clone());
+            builder = builder.field(
+                tags::mm_protection::INSTRUMENT_GROUP,
+                instrument_group.clone(),
+            );
         }
 
         if let Some(reset_effective_time) = &self.reset_effective_time {
             builder = builder.field(
-                9035,
+                tags::mm_protection::RESET_EFFECTIVE_TIME,
                 reset_effective_time
                     .format("%Y%m%d-%H:%M:%S%.3f")
                     .to_string(),
@@ -424,19 +441,22 @@ impl MMProtectionReset {
 
         if let Some(reset_expiry_time) = &self.reset_expiry_time {
             builder = builder.field(
-                9036,
+                tags::mm_protection::RESET_EXPIRY_TIME,
                 reset_expiry_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
             );
         }
 
         // Boolean flags
         if let Some(force_reset) = &self.force_reset {
-            builder = builder.field(9037, if *force_reset { "Y" } else { "N" }.to_string());
+            builder = builder.field(
+                tags::mm_protection::FORCE_RESET,
+                if *force_reset { "Y" } else { "N" }.to_string(),
+            );
         }
 
         if let Some(notify_all_participants) = &self.notify_all_participants {
             builder = builder.field(
-                9038,
+                tags::mm_protection::NOTIFY_ALL_PARTICIPANTS,
                 if *notify_all_participants { "Y" } else { "N" }.to_string(),
             );
         }
@@ -444,28 +464,28 @@ impl MMProtectionReset {
         // Counter reset flags
         if let Some(reset_position_counters) = &self.reset_position_counters {
             builder = builder.field(
-                9039,
+                tags::mm_protection::RESET_POSITION_COUNTERS,
                 if *reset_position_counters { "Y" } else { "N" }.to_string(),
             );
         }
 
         if let Some(reset_order_counters) = &self.reset_order_counters {
             builder = builder.field(
-                9040,
+                tags::mm_protection::RESET_ORDER_COUNTERS,
                 if *reset_order_counters { "Y" } else { "N" }.to_string(),
             );
         }
 
         if let Some(reset_volume_counters) = &self.reset_volume_counters {
             builder = builder.field(
-                9041,
+                tags::mm_protection::RESET_VOLUME_COUNTERS,
                 if *reset_volume_counters { "Y" } else { "N" }.to_string(),
             );
         }
 
         if let Some(reset_time_window_counters) = &self.reset_time_window_counters {
             builder = builder.field(
-                9042,
+                tags::mm_protection::RESET_TIME_WINDOW_COUNTERS,
                 if *reset_time_window_counters {
                     "Y"
                 } else {
@@ -477,44 +497,44 @@ impl MMProtectionReset {
 
         if let Some(reset_greeks_counters) = &self.reset_greeks_counters {
             builder = builder.field(
-                9043,
+                tags::mm_protection::RESET_GREEKS_COUNTERS,
                 if *reset_greeks_counters { "Y" } else { "N" }.to_string(),
             );
         }
 
         if let Some(reset_risk_counters) = &self.reset_risk_counters {
             builder = builder.field(
-                9044,
+                tags::mm_protection::RESET_RISK_COUNTERS,
                 if *reset_risk_counters { "Y" } else { "N" }.to_string(),
             );
         }
 
         // Standard optional fields
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(parties) = &self.parties {
-            builder = builder.field(453, parties.clone());
+            builder = builder.field(tags::NO_PARTY_IDS, parties.clone());
         }
 
         if let Some(trading_session_id) = &self.trading_session_id {
-            builder = builder.field(336, trading_session_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_ID, trading_session_id.clone());
         }
 
         if let Some(trading_session_sub_id) = &self.trading_session_sub_id {
-            builder = builder.field(625, trading_session_sub_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_SUB_ID, trading_session_sub_id.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -524,6 +544,7 @@ impl_json_debug_pretty!(MMProtectionReset);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_mm_protection_reset_creation() {
@@ -688,7 +709,7 @@ mod tests {
             .with_counter_resets(true, true, true, true, true, true)
             .with_label("test-label".to_string());
 
-        let fix_message = reset.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = reset.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=MZ")); // MsgType
@@ -14,6 +14,7 @@
 use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::MessageBuilder;
 use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -150,28 +151,34 @@ impl SecurityDefinitionRequest {
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
-            .field(320, self.security_req_id.clone()) // SecurityReqID
-            .field(856, i32::from(self.request_type).to_string()); // SecurityDefinitionRequestType
+            .field(tags::SECURITY_REQ_ID, self.security_req_id.clone())
+            .field(
+                tags::SECURITY_DEFINITION_REQUEST_TYPE,
+                i32::from(self.request_type).to_string(),
+            );
 
         // Add optional fields
         if let Some(ref symbol) = self.symbol {
-            builder = builder.field(55, symbol.clone()); // Symbol
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(ref security_type) = self.security_type {
-            builder = builder.field(167, security_type.clone()); // SecurityType
+            builder = builder.field(tags::SECURITY_TYPE, security_type.clone());
         }
 
         if let Some(ref currency) = self.currency {
-            builder = builder.field(15, currency.clone()); // Currency
+            builder = builder.field(tags::CURRENCY, currency.clone());
         }
 
         if let Some(ref text) = self.text {
-            builder = builder.field(58, text.clone()); // Text
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(subscription_type) = self.subscription_request_type {
-            builder = builder.field(263, subscription_type.to_string()); // SubscriptionRequestType
+            builder = builder.field(
+                tags::SUBSCRIPTION_REQUEST_TYPE,
+                subscription_type.to_string(),
+            );
         }
 
         builder.build()
@@ -238,43 +245,52 @@ impl SecurityDefinition {
     /// Parse from FIX message
     pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
         let security_req_id = message
-            .get_field(320)
+            .get_field(tags::SECURITY_REQ_ID)
             .ok_or_else(|| {
                 DeribitFixError::MessageParsing("Missing SecurityReqID (320)".to_string())
             })?
             .clone();
 
         let security_response_id = message
-            .get_field(322)
+            .get_field(tags::SECURITY_RESPONSE_ID)
             .ok_or_else(|| {
                 DeribitFixError::MessageParsing("Missing SecurityResponseID (322)".to_string())
             })?
             .clone();
 
         let symbol = message
-            .get_field(55)
+            .get_field(tags::SYMBOL)
             .ok_or_else(|| DeribitFixError::MessageParsing("Missing Symbol (55)".to_string()))?
             .clone();
 
-        let security_type = message.get_field(167).cloned();
-        let currency = message.get_field(15).cloned();
-        let security_desc = message.get_field(107).cloned();
+        let security_type = message.get_field(tags::SECURITY_TYPE).cloned();
+        let currency = message.get_field(tags::CURRENCY).cloned();
+        let security_desc = message.get_field(tags::SECURITY_DESC).cloned();
 
-        let strike_price = message.get_field(202).and_then(|s| s.parse::<f64>().ok());
+        let strike_price = message
+            .get_field(tags::STRIKE_PRICE)
+            .and_then(|s| s.parse::<f64>().ok());
 
-        let strike_currency = message.get_field(947).cloned();
+        let strike_currency = message.get_field(tags::STRIKE_CURRENCY).cloned();
 
-        let put_or_call = message.get_field(201).and_then(|s| s.parse::<i32>().ok());
+        let put_or_call = message
+            .get_field(tags::PUT_OR_CALL)
+            .and_then(|s| s.parse::<i32>().ok());
 
-        let contract_multiplier = message.get_field(231).and_then(|s| s.parse::<f64>().ok());
+        let contract_multiplier = message
+            .get_field(tags::CONTRACT_MULTIPLIER)
+            .and_then(|s| s.parse::<f64>().ok());
 
-        let maturity_date = message.get_field(541).cloned();
-        let issue_date = message.get_field(225).cloned();
+        let maturity_date = message.get_field(tags::MATURITY_DATE).cloned();
+        let issue_date = message.get_field(tags::ISSUE_DATE).cloned();
 
-        let min_trade_vol = message.get_field(562).and_then(|s| s.parse::<f64>().ok());
+        let min_trade_vol = message
+            .get_field(tags::MIN_TRADE_VOL)
+            .and_then(|s| s.parse::<f64>().ok());
 
-        let security_def_response_type =
-            message.get_field(1570).and_then(|s| s.parse::<i32>().ok());
+        let security_def_response_type = message
+            .get_field(tags::SECURITY_DEFINITION_RESPONSE_TYPE)
+            .and_then(|s| s.parse::<i32>().ok());
 
         Ok(Self {
             security_req_id,
@@ -307,53 +323,59 @@ impl SecurityDefinition {
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
-            .field(320, self.security_req_id.clone()) // SecurityReqID
-            .field(322, self.security_response_id.clone()) // SecurityResponseID
-            .field(55, self.symbol.clone()); // Symbol
+            .field(tags::SECURITY_REQ_ID, self.security_req_id.clone())
+            .field(
+                tags::SECURITY_RESPONSE_ID,
+                self.security_response_id.clone(),
+            )
+            .field(tags::SYMBOL, self.symbol.clone());
 
         // Add optional fields
         if let Some(ref security_type) = self.security_type {
-            builder = builder.field(167, security_type.clone()); // SecurityType
+            builder = builder.field(tags::SECURITY_TYPE, security_type.clone());
         }
 
         if let Some(ref currency) = self.currency {
-            builder = builder.field(15, currency.clone()); // Currency
+            builder = builder.field(tags::CURRENCY, currency.clone());
         }
 
         if let Some(ref security_desc) = self.security_desc {
-            builder = builder.field(107, security_desc.clone()); // SecurityDesc
+            builder = builder.field(tags::SECURITY_DESC, security_desc.clone());
         }
 
         if let Some(strike_price) = self.strike_price {
-            builder = builder.field(202, strike_price.to_string()); // StrikePrice
+            builder = builder.field(tags::STRIKE_PRICE, strike_price.to_string());
         }
 
         if let Some(ref strike_currency) = self.strike_currency {
-            builder = builder.field(947, strike_currency.clone()); // StrikeCurrency
+            builder = builder.field(tags::STRIKE_CURRENCY, strike_currency.clone());
         }
 
         if let Some(put_or_call) = self.put_or_call {
-            builder = builder.field(201, put_or_call.to_string()); // PutOrCall
+            builder = builder.field(tags::PUT_OR_CALL, put_or_call.to_string());
         }
 
         if let Some(contract_multiplier) = self.contract_multiplier {
-            builder = builder.field(231, contract_multiplier.to_string()); // ContractMultiplier
+            builder = builder.field(tags::CONTRACT_MULTIPLIER, contract_multiplier.to_string());
         }
 
         if let Some(ref maturity_date) = self.maturity_date {
-            builder = builder.field(541, maturity_date.clone()); // MaturityDate
+            builder = builder.field(tags::MATURITY_DATE, maturity_date.clone());
         }
 
         if let Some(ref issue_date) = self.issue_date {
-            builder = builder.field(225, issue_date.clone()); // IssueDate
+            builder = builder.field(tags::ISSUE_DATE, issue_date.clone());
         }
 
         if let Some(min_trade_vol) = self.min_trade_vol {
-            builder = builder.field(562, min_trade_vol.to_string()); // MinTradeVol
+            builder = builder.field(tags::MIN_TRADE_VOL, min_trade_vol.to_string());
         }
 
         if let Some(response_type) = self.security_def_response_type {
-            builder = builder.field(1570, response_type.to_string()); // SecurityDefinitionResponseType
+            builder = builder.field(
+                tags::SECURITY_DEFINITION_RESPONSE_TYPE,
+                response_type.to_string(),
+            );
         }
 
         builder.build()
@@ -10,8 +10,11 @@
 //! security/instrument information from Deribit according to the official
 //! FIX API specification.
 
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::MessageBuilder;
+use crate::message::time::{parse_utc_date_only, parse_utc_timestamp};
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -74,7 +77,8 @@ impl TryFrom<i32> for SubscriptionRequestType {
 }
 
 /// Security Type enumeration for FIX protocol
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum SecurityType {
     /// Currency exchange spot
     FxSpot,
@@ -88,11 +92,13 @@ pub enum SecurityType {
     OptionCombo,
     /// Indexes
     Index,
+    /// Value this version does not know, kept as received
+    Unknown(String),
 }
 
 impl SecurityType {
     /// Convert to FIX string representation
-    pub fn as_fix_str(&self) -> &'static str {
+    pub fn as_fix_str(&self) -> &str {
         match self {
             SecurityType::FxSpot => "FXSPOT",
             SecurityType::Future => "FUT",
@@ -100,10 +106,14 @@ impl SecurityType {
             SecurityType::FutureCombo => "FUTCO",
             SecurityType::OptionCombo => "OPTCO",
             SecurityType::Index => "INDEX",
+            SecurityType::Unknown(value) => value,
         }
     }
 
     /// Parse from FIX string representation
+    ///
+    /// Never fails: values this version does not know become
+    /// [`SecurityType::Unknown`]
     pub fn from_fix_str(s: &str) -> Result<Self, String> {
         match s {
             "FXSPOT" => Ok(SecurityType::FxSpot),
@@ -112,7 +122,7 @@ impl SecurityType {
             "FUTCO" => Ok(SecurityType::FutureCombo),
             "OPTCO" => Ok(SecurityType::OptionCombo),
             "INDEX" => Ok(SecurityType::Index),
-            _ => Err(format!("Invalid SecurityType: {s}")),
+            _ => Ok(SecurityType::Unknown(s.to_string())),
         }
     }
 }
@@ -136,6 +146,10 @@ pub struct SecurityListRequest {
     pub secondary_currency: Option<String>,
     /// Security Type filter (Tag 167) - Optional
     pub security_type: Option<SecurityType>,
+    /// Instrument state filter, applied to the response - Optional
+    pub security_status: Option<SecurityStatus>,
+    /// Ticker filter keeping symbols with this prefix, applied to the response - Optional
+    pub symbol_prefix: Option<String>,
 }
 
 impl SecurityListRequest {
@@ -150,6 +164,8 @@ impl SecurityListRequest {
             currency: None,
             secondary_currency: None,
             security_type: None,
+            security_status: None,
+            symbol_prefix: None,
         }
     }
 
@@ -183,6 +199,46 @@ impl SecurityListRequest {
         self
     }
 
+    /// Set instrument state filter
+    ///
+    /// Deribit has no request field for the state, so instruments are
+    /// filtered on the SecurityStatus (965) of the response.
+    pub fn with_security_status(mut self, status: SecurityStatus) -> Self {
+        self.security_status = Some(status);
+        self
+    }
+
+    /// Set ticker filter, e.g. `BTC-27JUN25` for the options of one expiry
+    ///
+    /// Instruments are filtered on the Symbol (55) of the response.
+    pub fn with_symbol_prefix(mut self, prefix: String) -> Self {
+        self.symbol_prefix = Some(prefix);
+        self
+    }
+
+    /// Whether `security` passes the currency, type, state and ticker filters
+    ///
+    /// Instruments without the filtered field pass, so a response lacking
+    /// SecurityStatus (965) is not emptied by the state filter.
+    pub fn matches(&self, security: &SecurityInfo) -> bool {
+        fn passes<T: PartialEq>(filter: Option<&T>, value: Option<&T>) -> bool {
+            match (filter, value) {
+                (Some(filter), Some(value)) => filter == value,
+                _ => true,
+            }
+        }
+        passes(self.currency.as_ref(), security.currency.as_ref())
+            && passes(self.security_type.as_ref(), security.security_type.as_ref())
+            && passes(
+                self.security_status.as_ref(),
+                security.security_status.as_ref(),
+            )
+            && self
+                .symbol_prefix
+                .as_ref()
+                .is_none_or(|prefix| security.symbol.starts_with(prefix.as_str()))
+    }
+
     /// Enable multicast instrument ID display
     pub fn with_multicast_instrument_id(mut self, enable: bool) -> Self {
         self.display_multicast_instrument_id = Some(enable);
@@ -208,17 +264,23 @@ impl SecurityListRequest {
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
             .sending_time(Utc::now())
-            .field(320, self.security_req_id.clone()) // SecurityReqId
-            .field(559, i32::from(self.security_list_request_type).to_string()); // SecurityListRequestType
+            .field(tags::SECURITY_REQ_ID, self.security_req_id.clone())
+            .field(
+                tags::SECURITY_LIST_REQUEST_TYPE,
+                i32::from(self.security_list_request_type).to_string(),
+            );
 
         // Add optional fields
         if let Some(subscription_type) = self.subscription_request_type {
-            builder = builder.field(263, i32::from(subscription_type).to_string()); // SubscriptionRequestType
+            builder = builder.field(
+                tags::SUBSCRIPTION_REQUEST_TYPE,
+                i32::from(subscription_type).to_string(),
+            );
         }
 
         if let Some(display_multicast) = self.display_multicast_instrument_id {
             builder = builder.field(
-                9013,
+                tags::DISPLAY_MULTICAST_INSTRUMENT_ID,
                 if display_multicast {
                     "Y".to_string()
                 } else {
@@ -229,7 +291,7 @@ impl SecurityListRequest {
 
         if let Some(display_steps) = self.display_increment_steps {
             builder = builder.field(
-                9018,
+                tags::DISPLAY_INCREMENT_STEPS,
                 if display_steps {
                     "Y".to_string()
                 } else {
@@ -239,15 +301,15 @@ impl SecurityListRequest {
         }
 
         if let Some(ref currency) = self.currency {
-            builder = builder.field(15, currency.clone()); // Currency
+            builder = builder.field(tags::CURRENCY, currency.clone());
         }
 
         if let Some(ref secondary_currency) = self.secondary_currency {
-            builder = builder.field(5544, secondary_currency.clone()); // SecondaryCurrency
+            builder = builder.field(tags::SECONDARY_CURRENCY, secondary_currency.clone());
         }
 
         if let Some(ref security_type) = self.security_type {
-            builder = builder.field(167, security_type.as_fix_str().to_string()); // SecurityType
+            builder = builder.field(tags::SECURITY_TYPE, security_type.as_fix_str().to_string());
         }
 
         builder.build()
@@ -256,19 +318,27 @@ impl SecurityListRequest {
 
 /// Put or Call indicator for options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum PutOrCall {
     /// Put option
-    Put = 0,
+    Put,
     /// Call option
-    Call = 1,
+    Call,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<PutOrCall> for i32 {
     fn from(put_or_call: PutOrCall) -> Self {
-        put_or_call as i32
+        match put_or_call {
+            PutOrCall::Put => 0,
+            PutOrCall::Call => 1,
+            PutOrCall::Unknown(value) => value,
+        }
     }
 }
 
+/// Never fails: values this version does not know become [`PutOrCall::Unknown`]
 impl TryFrom<i32> for PutOrCall {
     type Error = String;
 
@@ -276,32 +346,43 @@ impl TryFrom<i32> for PutOrCall {
         match value {
             0 => Ok(PutOrCall::Put),
             1 => Ok(PutOrCall::Call),
-            _ => Err(format!("Invalid PutOrCall: {value}")),
+            _ => Ok(PutOrCall::Unknown(value)),
         }
     }
 }
 
 /// Security Status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum SecurityStatus {
     /// Active/Started
-    Active = 1,
+    Active,
     /// Terminated/Inactive
-    Terminated = 2,
+    Terminated,
     /// Closed
-    Closed = 4,
+    Closed,
     /// Published/Created
-    Published = 10,
+    Published,
     /// Settled
-    Settled = 12,
+    Settled,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<SecurityStatus> for i32 {
     fn from(status: SecurityStatus) -> Self {
-        status as i32
+        match status {
+            SecurityStatus::Active => 1,
+            SecurityStatus::Terminated => 2,
+            SecurityStatus::Closed => 4,
+            SecurityStatus::Published => 10,
+            SecurityStatus::Settled => 12,
+            SecurityStatus::Unknown(value) => value,
+        }
     }
 }
 
+/// Never fails: values this version does not know become [`SecurityStatus::Unknown`]
 impl TryFrom<i32> for SecurityStatus {
     type Error = String;
 
@@ -312,7 +393,7 @@ impl TryFrom<i32> for SecurityStatus {
             4 => Ok(SecurityStatus::Closed),
             10 => Ok(SecurityStatus::Published),
             12 => Ok(SecurityStatus::Settled),
-            _ => Err(format!("Invalid SecurityStatus: {value}")),
+            _ => Ok(SecurityStatus::Unknown(value)),
         }
     }
 }
@@ -590,6 +671,10 @@ pub struct SecurityList {
     pub security_request_result: i32,
     /// List of securities (Tag 146 - NoRelatedSym)
     pub securities: Vec<SecurityInfo>,
+    /// Number of securities across all fragments (Tag 393)
+    pub tot_no_related_sym: Option<usize>,
+    /// Whether this is the last fragment of the response (Tag 893)
+    pub last_fragment: Option<bool>,
 }
 
 impl SecurityList {
@@ -604,6 +689,8 @@ impl SecurityList {
             security_response_id,
             security_request_result: 0, // Always 0 for successful response
             securities,
+            tot_no_related_sym: None,
+            last_fragment: None,
         }
     }
 
@@ -626,11 +713,141 @@ impl SecurityList {
         self.security_request_result == 0
     }
 
+    /// Mark this list as one fragment of a response of `total` securities
+    pub fn with_fragment(mut self, total: usize, last_fragment: bool) -> Self {
+        self.tot_no_related_sym = Some(total);
+        self.last_fragment = Some(last_fragment);
+        self
+    }
+
+    /// Split a Security List (y) or Security Definition (d) into one message
+    /// per instrument
+    ///
+    /// Each part keeps the MsgType (35) and the fields from one Symbol (55)
+    /// up to the next, so a malformed instrument can be skipped without
+    /// losing the others.
+    pub fn split_instruments(message: &FixMessage) -> Vec<FixMessage> {
+        let mut parts: Vec<FixMessage> = Vec::new();
+        for (tag, value) in &message.fields {
+            if *tag == tags::SYMBOL {
+                let mut part = FixMessage::new();
+                if let Some(msg_type) = message.get_field(tags::MSG_TYPE) {
+                    part.set_field(tags::MSG_TYPE, msg_type.clone());
+                }
+                parts.push(part);
+            }
+            if let Some(part) = parts.last_mut() {
+                part.fields.push((*tag, value.clone()));
+            }
+        }
+        parts
+    }
+
+    /// Parse a Security List (y) message
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let header = |tag: u32| message.get_field(tag).map(String::as_str);
+        let mut list = Self::new(
+            header(tags::SECURITY_REQ_ID)
+                .unwrap_or_default()
+                .to_string(),
+            header(tags::SECURITY_RESPONSE_ID)
+                .unwrap_or_default()
+                .to_string(),
+            Vec::new(),
+        );
+        if let Some(value) = header(tags::SECURITY_REQUEST_RESULT) {
+            list.security_request_result = parse_number(tags::SECURITY_REQUEST_RESULT, value)?;
+        }
+        if let Some(value) = header(tags::TOT_NO_RELATED_SYM) {
+            list.tot_no_related_sym = Some(parse_number(tags::TOT_NO_RELATED_SYM, value)?);
+        }
+        list.last_fragment = header(tags::LAST_FRAGMENT).map(|value| value == "Y");
+
+        for (tag, value) in &message.fields {
+            if *tag == tags::SYMBOL {
+                list.securities.push(SecurityInfo::new(value.clone()));
+                continue;
+            }
+            let Some(security) = list.securities.last_mut() else {
+                continue;
+            };
+            let text = || Some(value.clone());
+            match *tag {
+                tags::SECURITY_DESC => security.security_desc = text(),
+                tags::SECURITY_TYPE => {
+                    security.security_type = Some(
+                        SecurityType::from_fix_str(value)
+                            .map_err(DeribitFixError::MessageParsing)?,
+                    )
+                }
+                tags::PUT_OR_CALL => {
+                    security.put_or_call = Some(
+                        PutOrCall::try_from(parse_number::<i32>(*tag, value)?)
+                            .map_err(DeribitFixError::MessageParsing)?,
+                    )
+                }
+                tags::STRIKE_PRICE => security.strike_price = Some(parse_number(*tag, value)?),
+                tags::STRIKE_CURRENCY => security.strike_currency = text(),
+                tags::CURRENCY => security.currency = text(),
+                tags::PRICE_QUOTE_CURRENCY => security.price_quote_currency = text(),
+                tags::INSTRUMENT_PRICE_PRECISION => {
+                    security.instrument_price_precision = Some(parse_number(*tag, value)?)
+                }
+                tags::MIN_PRICE_INCREMENT => {
+                    security.min_price_increment = Some(parse_number(*tag, value)?)
+                }
+                tags::UNDERLYING_SYMBOL => security.underlying_symbol = text(),
+                tags::ISSUE_DATE => security.issue_date = Some(parse_utc_timestamp(value)?),
+                tags::MATURITY_DATE => {
+                    security.maturity_date = Some(
+                        parse_utc_date_only(value)?
+                            .and_time(Default::default())
+                            .and_utc(),
+                    )
+                }
+                tags::MATURITY_TIME => security.maturity_time = Some(parse_utc_timestamp(value)?),
+                tags::MIN_TRADE_VOL => security.min_trade_vol = Some(parse_number(*tag, value)?),
+                tags::SETTL_TYPE => security.settl_type = text(),
+                tags::SETTL_CURRENCY => security.settl_currency = text(),
+                tags::COMM_CURRENCY => security.comm_currency = text(),
+                tags::CONTRACT_MULTIPLIER => {
+                    security.contract_multiplier = Some(parse_number(*tag, value)?)
+                }
+                tags::SECURITY_ALT_ID => security.security_alt_ids.push(SecurityAltId {
+                    security_alt_id: value.clone(),
+                    security_alt_id_source: String::new(),
+                }),
+                tags::SECURITY_ALT_ID_SOURCE => {
+                    if let Some(alt_id) = security.security_alt_ids.last_mut() {
+                        alt_id.security_alt_id_source = value.clone();
+                    }
+                }
+                tags::START_TICK_PRICE_RANGE => security.tick_rules.push(TickRule {
+                    start_tick_price_range: parse_number(*tag, value)?,
+                    tick_increment: 0.0,
+                }),
+                tags::TICK_INCREMENT => {
+                    if let Some(rule) = security.tick_rules.last_mut() {
+                        rule.tick_increment = parse_number(*tag, value)?;
+                    }
+                }
+                tags::SECURITY_STATUS => {
+                    security.security_status = Some(
+                        SecurityStatus::try_from(parse_number::<i32>(*tag, value)?)
+                            .map_err(DeribitFixError::MessageParsing)?,
+                    )
+                }
+                _ => {}
+            }
+        }
+        Ok(list)
+    }
+
     /// Filter securities by type
     pub fn filter_by_type(&self, security_type: SecurityType) -> Vec<&SecurityInfo> {
         self.securities
             .iter()
-            .filter(|s| s.security_type == Some(security_type))
+            .filter(|s| s.security_type.as_ref() == Some(&security_type))
             .collect()
     }
 
@@ -655,114 +872,159 @@ impl SecurityList {
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
             .sending_time(Utc::now())
-            .field(320, self.security_req_id.clone()) // SecurityReqId
-            .field(322, self.security_response_id.clone()) // SecurityResponseID
-            .field(560, self.security_request_result.to_string()) // SecurityRequestResult
-            .field(146, self.securities.len().to_string()); // NoRelatedSym
+            .field(tags::SECURITY_REQ_ID, self.security_req_id.clone())
+            .field(
+                tags::SECURITY_RESPONSE_ID,
+                self.security_response_id.clone(),
+            )
+            .field(
+                tags::SECURITY_REQUEST_RESULT,
+                self.security_request_result.to_string(),
+            );
+
+        if let Some(total) = self.tot_no_related_sym {
+            builder = builder.field(tags::TOT_NO_RELATED_SYM, total.to_string());
+        }
+
+        if let Some(last_fragment) = self.last_fragment {
+            builder = builder.field(
+                tags::LAST_FRAGMENT,
+                if last_fragment { "Y" } else { "N" }.to_string(),
+            );
+        }
 
         // Add security information with proper FIX repeating group structure
+        builder = builder.field(tags::NO_RELATED_SYM, self.securities.len().to_string());
         for security in &self.securities {
             // Required fields
-            builder = builder.field(55, security.symbol.clone()); // Symbol
+            builder = builder.push_field(tags::SYMBOL, security.symbol.clone());
 
             // Optional security fields
             if let Some(ref desc) = security.security_desc {
-                builder = builder.field(107, desc.clone()); // SecurityDesc
+                builder = builder.push_field(tags::SECURITY_DESC, desc.clone());
             }
 
             if let Some(ref sec_type) = security.security_type {
-                builder = builder.field(167, sec_type.as_fix_str().to_string()); // SecurityType
+                builder =
+                    builder.push_field(tags::SECURITY_TYPE, sec_type.as_fix_str().to_string());
             }
 
             if let Some(put_or_call) = security.put_or_call {
-                builder = builder.field(201, i32::from(put_or_call).to_string()); // PutOrCall
+                builder = builder.push_field(tags::PUT_OR_CALL, i32::from(put_or_call).to_string());
             }
 
             if let Some(strike_price) = security.strike_price {
-                builder = builder.field(202, strike_price.to_string()); // StrikePrice
+                builder = builder.push_field(tags::STRIKE_PRICE, strike_price.to_string());
             }
 
             if let Some(ref strike_currency) = security.strike_currency {
-                builder = builder.field(947, strike_currency.clone()); // StrikeCurrency
+                builder = builder.push_field(tags::STRIKE_CURRENCY, strike_currency.clone());
             }
 
             if let Some(ref currency) = security.currency {
-                builder = builder.field(15, currency.clone()); // Currency
+                builder = builder.push_field(tags::CURRENCY, currency.clone());
             }
 
             if let Some(ref price_quote_currency) = security.price_quote_currency {
-                builder = builder.field(1524, price_quote_currency.clone()); // PriceQuoteCurrency
+                builder =
+                    builder.push_field(tags::PRICE_QUOTE_CURRENCY, price_quote_currency.clone());
             }
 
             if let Some(instrument_price_precision) = security.instrument_price_precision {
-                builder = builder.field(2576, instrument_price_precision.to_string()); // InstrumentPricePrecision
+                builder = builder.push_field(
+                    tags::INSTRUMENT_PRICE_PRECISION,
+                    instrument_price_precision.to_string(),
+                );
             }
 
             if let Some(min_price_increment) = security.min_price_increment {
-                builder = builder.field(969, min_price_increment.to_string()); // MinPriceIncrement
+                builder =
+                    builder.push_field(tags::MIN_PRICE_INCREMENT, min_price_increment.to_string());
             }
 
             if let Some(ref underlying_symbol) = security.underlying_symbol {
-                builder = builder.field(311, underlying_symbol.clone()); // UnderlyingSymbol
+                builder = builder.push_field(tags::UNDERLYING_SYMBOL, underlying_symbol.clone());
             }
 
             if let Some(issue_date) = security.issue_date {
-                builder = builder.field(225, issue_date.format("%Y%m%d-%H:%M:%S%.3f").to_string()); // IssueDate
+                builder = builder.push_field(
+                    tags::ISSUE_DATE,
+                    issue_date.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+                );
             }
 
             if let Some(maturity_date) = security.maturity_date {
-                builder = builder.field(541, maturity_date.format("%Y%m%d").to_string()); // MaturityDate
+                builder = builder.push_field(
+                    tags::MATURITY_DATE,
+                    maturity_date.format("%Y%m%d").to_string(),
+                );
             }
 
             if let Some(maturity_time) = security.maturity_time {
-                builder = builder.field(
-                    1079,
+                builder = builder.push_field(
+                    tags::MATURITY_TIME,
                     maturity_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
                 ); // MaturityTime
             }
 
             if let Some(min_trade_vol) = security.min_trade_vol {
-                builder = builder.field(562, min_trade_vol.to_string()); // MinTradeVol
+                builder = builder.push_field(tags::MIN_TRADE_VOL, min_trade_vol.to_string());
             }
 
             if let Some(ref settl_type) = security.settl_type {
-                builder = builder.field(63, settl_type.clone()); // SettlType
+                builder = builder.push_field(tags::SETTL_TYPE, settl_type.clone());
             }
 
             if let Some(ref settl_currency) = security.settl_currency {
-                builder = builder.field(120, settl_currency.clone()); // SettlCurrency
+                builder = builder.push_field(tags::SETTL_CURRENCY, settl_currency.clone());
             }
 
             if let Some(ref comm_currency) = security.comm_currency {
-                builder = builder.field(479, comm_currency.clone()); // CommCurrency
+                builder = builder.push_field(tags::COMM_CURRENCY, comm_currency.clone());
             }
 
             if let Some(contract_multiplier) = security.contract_multiplier {
-                builder = builder.field(231, contract_multiplier.to_string()); // ContractMultiplier
+                builder =
+                    builder.push_field(tags::CONTRACT_MULTIPLIER, contract_multiplier.to_string());
             }
 
             // Security Alternative IDs repeating group
             if !security.security_alt_ids.is_empty() {
-                builder = builder.field(454, security.security_alt_ids.len().to_string()); // NoSecurityAltID
+                builder = builder.push_field(
+                    tags::NO_SECURITY_ALT_ID,
+                    security.security_alt_ids.len().to_string(),
+                );
 
                 for alt_id in &security.security_alt_ids {
-                    builder = builder.field(455, alt_id.security_alt_id.clone()); // SecurityAltID
-                    builder = builder.field(456, alt_id.security_alt_id_source.clone()); // SecurityAltIDSource
+                    builder =
+                        builder.push_field(tags::SECURITY_ALT_ID, alt_id.security_alt_id.clone());
+                    builder = builder.push_field(
+                        tags::SECURITY_ALT_ID_SOURCE,
+                        alt_id.security_alt_id_source.clone(),
+                    );
                 }
             }
 
             // Tick Rules repeating group
             if !security.tick_rules.is_empty() {
-                builder = builder.field(1205, security.tick_rules.len().to_string()); // NoTickRules
+                builder =
+                    builder.push_field(tags::NO_TICK_RULES, security.tick_rules.len().to_string());
 
                 for tick_rule in &security.tick_rules {
-                    builder = builder.field(1206, tick_rule.start_tick_price_range.to_string()); // StartTickPriceRange
-                    builder = builder.field(1208, tick_rule.tick_increment.to_string()); // TickIncrement
+                    builder = builder.push_field(
+                        tags::START_TICK_PRICE_RANGE,
+                        tick_rule.start_tick_price_range.to_string(),
+                    );
+                    builder = builder
+                        .push_field(tags::TICK_INCREMENT, tick_rule.tick_increment.to_string());
                 }
             }
 
             if let Some(security_status) = security.security_status {
-                builder = builder.field(965, i32::from(security_status).to_string()); // SecurityStatus
+                builder = builder.push_field(
+                    tags::SECURITY_STATUS,
+                    i32::from(security_status).to_string(),
+                );
             }
         }
 
@@ -770,9 +1032,114 @@ impl SecurityList {
     }
 }
 
+/// Progress of a Security List response arriving in fragments
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityListProgress {
+    /// SecurityReqID (320) of the request
+    pub security_req_id: String,
+    /// Number of fragments received
+    pub fragments: usize,
+    /// Number of securities received across the fragments
+    pub received: usize,
+    /// TotNoRelatedSym (393), the number of securities of the whole response
+    /// when the server reports it
+    pub total: Option<usize>,
+    /// Whether the last fragment was received
+    pub complete: bool,
+}
+
+/// Reassembles the fragments of a Security List response
+///
+/// Deribit splits long instrument lists over several Security List (y)
+/// messages with the same SecurityReqID (320), the last one flagged with
+/// LastFragment (893) = Y. A response without fragment fields is complete
+/// on its own. The securities can be taken after each fragment, to handle
+/// them as they arrive, or once the response is complete.
+#[derive(Debug, Clone)]
+pub struct SecurityListAssembler {
+    security_req_id: String,
+    securities: Vec<SecurityInfo>,
+    fragments: usize,
+    received: usize,
+    total: Option<usize>,
+    complete: bool,
+}
+
+impl SecurityListAssembler {
+    /// Collect the response to the request `security_req_id`
+    pub fn new(security_req_id: String) -> Self {
+        Self {
+            security_req_id,
+            securities: Vec::new(),
+            fragments: 0,
+            received: 0,
+            total: None,
+            complete: false,
+        }
+    }
+
+    /// Number of fragments received
+    pub fn fragments(&self) -> usize {
+        self.fragments
+    }
+
+    /// Whether the last fragment was received
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Progress of the response so far
+    pub fn progress(&self) -> SecurityListProgress {
+        SecurityListProgress {
+            security_req_id: self.security_req_id.clone(),
+            fragments: self.fragments,
+            received: self.received,
+            total: self.total,
+            complete: self.complete,
+        }
+    }
+
+    /// Add a fragment, returning whether the response is complete
+    ///
+    /// Fragments of other requests are ignored; an unsuccessful response is
+    /// returned as an error.
+    pub fn push(&mut self, fragment: SecurityList) -> DeribitFixResult<bool> {
+        if fragment.security_req_id != self.security_req_id {
+            return Ok(false);
+        }
+        if !fragment.is_successful() {
+            return Err(DeribitFixError::Session(format!(
+                "Security List Request {} failed with SecurityRequestResult {}",
+                self.security_req_id, fragment.security_request_result
+            )));
+        }
+        self.fragments += 1;
+        self.received += fragment.securities.len();
+        self.total = fragment.tot_no_related_sym.or(self.total);
+        self.securities.extend(fragment.securities);
+        self.complete = match fragment.last_fragment {
+            Some(last) => last,
+            None => self.total.is_none_or(|total| self.received >= total),
+        };
+        Ok(self.complete)
+    }
+
+    /// Take the securities received since they were last taken, in order
+    pub fn take_securities(&mut self) -> Vec<SecurityInfo> {
+        std::mem::take(&mut self.securities)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(tag: u32, value: &str) -> DeribitFixResult<T> {
+    value.parse().map_err(|_| {
+        DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_security_list_request_creation() {
@@ -825,7 +1192,9 @@ mod tests {
             SecurityType::from_fix_str("OPT").unwrap(),
             SecurityType::Option
         );
-        assert!(SecurityType::from_fix_str("INVALID").is_err());
+        let unknown = SecurityType::from_fix_str("SWAP").unwrap();
+        assert_eq!(unknown, SecurityType::Unknown("SWAP".to_string()));
+        assert_eq!(unknown.as_fix_str(), "SWAP");
     }
 
     #[test]
@@ -862,7 +1231,8 @@ mod tests {
         assert_eq!(i32::from(PutOrCall::Call), 1);
         assert_eq!(PutOrCall::try_from(0).unwrap(), PutOrCall::Put);
         assert_eq!(PutOrCall::try_from(1).unwrap(), PutOrCall::Call);
-        assert!(PutOrCall::try_from(2).is_err());
+        assert_eq!(PutOrCall::try_from(2).unwrap(), PutOrCall::Unknown(2));
+        assert_eq!(i32::from(PutOrCall::Unknown(2)), 2);
     }
 
     #[test]
@@ -874,7 +1244,10 @@ mod tests {
             SecurityStatus::try_from(2).unwrap(),
             SecurityStatus::Terminated
         );
-        assert!(SecurityStatus::try_from(99).is_err());
+        assert_eq!(
+            SecurityStatus::try_from(99).unwrap(),
+            SecurityStatus::Unknown(99)
+        );
     }
 
     #[test]
@@ -1011,4 +1384,149 @@ mod tests {
         assert!(fix_str.contains("1208=0.5")); // TickIncrement
         assert!(fix_str.contains("965=1")); // SecurityStatus
     }
+
+    #[test]
+    fn test_security_list_from_fix_message() {
+        let mut message = FixMessage::new();
+        message.fields = [
+            (35, "y"),
+            (320, "REQ1"),
+            (322, "RESP1"),
+            (560, "0"),
+            (393, "5"),
+            (893, "N"),
+            (146, "2"),
+            (55, "BTC-27JUN25-100000-C"),
+            (167, "OPT"),
+            (201, "1"),
+            (202, "100000"),
+            (15, "BTC"),
+            (541, "20250627"),
+            (454, "1"),
+            (455, "42"),
+            (456, "101"),
+            (1205, "1"),
+            (1206, "0.005"),
+            (1208, "0.0005"),
+            (965, "1"),
+            (55, "ETH-PERPETUAL"),
+            (167, "FUT"),
+            (15, "ETH"),
+        ]
+        .into_iter()
+        .map(|(tag, value)| (tag, value.to_string()))
+        .collect();
+
+        let parsed = SecurityList::from_fix_message(&message).unwrap();
+        assert_eq!(parsed.security_req_id, "REQ1");
+        assert_eq!(parsed.security_response_id, "RESP1");
+        assert_eq!(parsed.tot_no_related_sym, Some(5));
+        assert_eq!(parsed.last_fragment, Some(false));
+        assert_eq!(parsed.count(), 2);
+
+        let option = &parsed.securities[0];
+        assert_eq!(option.security_type, Some(SecurityType::Option));
+        assert_eq!(option.put_or_call, Some(PutOrCall::Call));
+        assert_eq!(option.strike_price, Some(100_000.0));
+        assert_eq!(
+            option.maturity_date,
+            Some(Utc.with_ymd_and_hms(2025, 6, 27, 0, 0, 0).unwrap())
+        );
+        assert_eq!(option.security_alt_ids[0].security_alt_id, "42");
+        assert_eq!(option.security_alt_ids[0].security_alt_id_source, "101");
+        assert_eq!(option.tick_rules[0].tick_increment, 0.0005);
+        assert_eq!(option.security_status, Some(SecurityStatus::Active));
+        let future = &parsed.securities[1];
+        assert!(future.is_future());
+        assert_eq!(future.currency.as_deref(), Some("ETH"));
+
+        let mut invalid = message.clone();
+        invalid.fields.push((202, "high".to_string()));
+        assert!(SecurityList::from_fix_message(&invalid).is_err());
+
+        // Fragment fields are written back
+        let written = parsed
+            .to_fix_message("CLIENT".to_string(), "DERIBIT".to_string(), 1)
+            .unwrap();
+        assert_eq!(written.get_field(tags::TOT_NO_RELATED_SYM).unwrap(), "5");
+        assert_eq!(written.get_field(tags::LAST_FRAGMENT).unwrap(), "N");
+    }
+
+    #[test]
+    fn test_security_list_assembler_joins_fragments() {
+        let fragment = |symbols: &[&str], last: Option<bool>| {
+            let securities = symbols
+                .iter()
+                .map(|symbol| SecurityInfo::new(symbol.to_string()))
+                .collect();
+            let mut list = SecurityList::success("REQ1".to_string(), "R".to_string(), securities);
+            list.tot_no_related_sym = Some(3);
+            list.last_fragment = last;
+            list
+        };
+
+        let mut assembler = SecurityListAssembler::new("REQ1".to_string());
+        assert!(!assembler.push(fragment(&["A", "B"], Some(false))).unwrap());
+        let mut other = fragment(&["X"], Some(true));
+        other.security_req_id = "REQ2".to_string();
+        assert!(!assembler.push(other).unwrap());
+        assert!(assembler.push(fragment(&["C"], Some(true))).unwrap());
+        assert_eq!(assembler.fragments(), 2);
+        assert_eq!(
+            assembler.progress(),
+            SecurityListProgress {
+                security_req_id: "REQ1".to_string(),
+                fragments: 2,
+                received: 3,
+                total: Some(3),
+                complete: true,
+            }
+        );
+        let symbols: Vec<_> = assembler
+            .take_securities()
+            .into_iter()
+            .map(|security| security.symbol)
+            .collect();
+        assert_eq!(symbols, ["A", "B", "C"]);
+
+        // Without LastFragment the total tells when the response is complete,
+        // also when the securities are taken fragment by fragment
+        let mut assembler = SecurityListAssembler::new("REQ1".to_string());
+        assert!(!assembler.push(fragment(&["A", "B"], None)).unwrap());
+        assert_eq!(assembler.take_securities().len(), 2);
+        assert!(assembler.push(fragment(&["C"], None)).unwrap());
+        assert_eq!(assembler.take_securities().len(), 1);
+
+        let mut failed = fragment(&[], None);
+        failed.security_request_result = 1;
+        assert!(assembler.push(failed).is_err());
+    }
+
+    #[test]
+    fn test_security_list_request_filters_match() {
+        let request = SecurityListRequest::snapshot("REQ1".to_string())
+            .with_currency("BTC".to_string())
+            .with_security_type(SecurityType::Option)
+            .with_security_status(SecurityStatus::Active)
+            .with_symbol_prefix("BTC-27JUN25".to_string());
+        let option = SecurityInfo::new("BTC-27JUN25-100000-C".to_string())
+            .with_currency("BTC".to_string())
+            .with_security_type(SecurityType::Option);
+
+        assert!(request.matches(&option));
+        assert!(request.matches(&option.clone().with_security_status(SecurityStatus::Active)));
+        assert!(!request.matches(&option.clone().with_security_status(SecurityStatus::Closed)));
+        assert!(!request.matches(&option.clone().with_security_type(SecurityType::Future)));
+        let mut other_expiry = option.clone();
+        other_expiry.symbol = "BTC-26SEP25-100000-C".to_string();
+        assert!(!request.matches(&other_expiry));
+
+        // Client-side filters are not sent
+        let message = request
+            .to_fix_message("CLIENT".to_string(), "DERIBIT".to_string(), 1)
+            .unwrap();
+        assert_eq!(message.get_field(tags::CURRENCY).unwrap(), "BTC");
+        assert_eq!(message.get_field(tags::SECURITY_TYPE).unwrap(), "OPT");
+        assert!(message.get_field(tags::SECURITY_STATUS).is_none());
+    }
 }
@@ -11,6 +11,7 @@
 use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::MessageBuilder;
 use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use serde::{Deserialize, Serialize};
 
@@ -70,9 +71,15 @@ impl SecurityStatusRequest {
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
-            .field(324, self.security_status_req_id.clone()) // SecurityStatusReqID
-            .field(55, self.symbol.clone()) // Symbol
-            .field(263, self.subscription_request_type.to_string()); // SubscriptionRequestType
+            .field(
+                tags::SECURITY_STATUS_REQ_ID,
+                self.security_status_req_id.clone(),
+            )
+            .field(tags::SYMBOL, self.symbol.clone())
+            .field(
+                tags::SUBSCRIPTION_REQUEST_TYPE,
+                self.subscription_request_type.to_string(),
+            );
 
         builder.build()
     }
@@ -172,54 +179,54 @@ impl SecurityStatus {
     /// Parse from FIX message
     pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
         let symbol = message
-            .get_field(55)
+            .get_field(tags::SYMBOL)
             .ok_or_else(|| DeribitFixError::MessageParsing("Symbol (55) is required".to_string()))?
             .clone();
 
         let mut security_status = Self::new(symbol);
 
         // Optional fields
-        if let Some(req_id) = message.get_field(324) {
+        if let Some(req_id) = message.get_field(tags::SECURITY_STATUS_REQ_ID) {
             security_status.security_status_req_id = Some(req_id.clone());
         }
 
-        if let Some(status_str) = message.get_field(326)
+        if let Some(status_str) = message.get_field(tags::SECURITY_TRADING_STATUS)
             && let Ok(status) = status_str.parse::<i32>()
         {
             security_status.security_trading_status = Some(status);
         }
 
-        if let Some(buy_vol_str) = message.get_field(330)
+        if let Some(buy_vol_str) = message.get_field(tags::BUY_VOLUME)
             && let Ok(buy_vol) = buy_vol_str.parse::<f64>()
         {
             security_status.buy_volume = Some(buy_vol);
         }
 
-        if let Some(sell_vol_str) = message.get_field(331)
+        if let Some(sell_vol_str) = message.get_field(tags::SELL_VOLUME)
             && let Ok(sell_vol) = sell_vol_str.parse::<f64>()
         {
             security_status.sell_volume = Some(sell_vol);
         }
 
-        if let Some(high_str) = message.get_field(332)
+        if let Some(high_str) = message.get_field(tags::HIGH_PX)
             && let Ok(high) = high_str.parse::<f64>()
         {
             security_status.high_px = Some(high);
         }
 
-        if let Some(low_str) = message.get_field(333)
+        if let Some(low_str) = message.get_field(tags::LOW_PX)
             && let Ok(low) = low_str.parse::<f64>()
         {
             security_status.low_px = Some(low);
         }
 
-        if let Some(last_str) = message.get_field(31)
+        if let Some(last_str) = message.get_field(tags::LAST_PX)
             && let Ok(last) = last_str.parse::<f64>()
         {
             security_status.last_px = Some(last);
         }
 
-        if let Some(text) = message.get_field(58) {
+        if let Some(text) = message.get_field(tags::TEXT) {
             security_status.text = Some(text.clone());
         }
 
@@ -238,39 +245,39 @@ impl SecurityStatus {
             .sender_comp_id(sender_comp_id)
             .target_comp_id(target_comp_id)
             .msg_seq_num(msg_seq_num)
-            .field(55, self.symbol.clone()); // Symbol
+            .field(tags::SYMBOL, self.symbol.clone());
 
         // Optional fields
         if let Some(ref req_id) = self.security_status_req_id {
-            builder = builder.field(324, req_id.clone()); // SecurityStatusReqID
+            builder = builder.field(tags::SECURITY_STATUS_REQ_ID, req_id.clone());
         }
 
         if let Some(status) = self.security_trading_status {
-            builder = builder.field(326, status.to_string()); // SecurityTradingStatus
+            builder = builder.field(tags::SECURITY_TRADING_STATUS, status.to_string());
         }
 
         if let Some(buy_vol) = self.buy_volume {
-            builder = builder.field(330, buy_vol.to_string()); // BuyVolume
+            builder = builder.field(tags::BUY_VOLUME, buy_vol.to_string());
         }
 
         if let Some(sell_vol) = self.sell_volume {
-            builder = builder.field(331, sell_vol.to_string()); // SellVolume
+            builder = builder.field(tags::SELL_VOLUME, sell_vol.to_string());
         }
 
         if let Some(high) = self.high_px {
-            builder = builder.field(332, high.to_string()); // HighPx
+            builder = builder.field(tags::HIGH_PX, high.to_string());
         }
 
         if let Some(low) = self.low_px {
-            builder = builder.field(333, low.to_string()); // LowPx
+            builder = builder.field(tags::LOW_PX, low.to_string());
         }
 
         if let Some(last) = self.last_px {
-            builder = builder.field(31, last.to_string()); // LastPx
+            builder = builder.field(tags::LAST_PX, last.to_string());
         }
 
         if let Some(ref text) = self.text {
-            builder = builder.field(58, text.clone()); // Text
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         builder.build()
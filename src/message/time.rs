@@ -0,0 +1,280 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! FIX date and time field formats
+//!
+//! Formatting and parsing of the FIX 4.4 UTCTimestamp, UTCDateOnly,
+//! UTCTimeOnly and TZTimeOnly field types. Timestamps are written with
+//! millisecond precision, or microsecond precision where a
+//! [`TimestampPrecision`] is given, and read with or without fractional
+//! seconds, up to nanoseconds.
+
+use crate::error::{DeribitFixError, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// UTCTimestamp layout with millisecond precision (`YYYYMMDD-HH:MM:SS.sss`)
+pub const UTC_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H:%M:%S%.3f";
+
+/// UTCTimestamp layout with microsecond precision (`YYYYMMDD-HH:MM:SS.ssssss`)
+pub const UTC_TIMESTAMP_MICROS_FORMAT: &str = "%Y%m%d-%H:%M:%S%.6f";
+
+/// UTCDateOnly layout (`YYYYMMDD`)
+pub const UTC_DATE_ONLY_FORMAT: &str = "%Y%m%d";
+
+/// UTCTimeOnly layout with millisecond precision (`HH:MM:SS.sss`)
+pub const UTC_TIME_ONLY_FORMAT: &str = "%H:%M:%S%.3f";
+
+/// Fractional seconds written in UTCTimestamp fields
+///
+/// FIX 4.4 allows any number of fractional digits, but counterparties
+/// expecting milliseconds may not accept more.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampPrecision {
+    /// Milliseconds (`.sss`)
+    #[default]
+    Millis,
+    /// Microseconds (`.ssssss`)
+    Micros,
+}
+
+impl TimestampPrecision {
+    /// UTCTimestamp layout of the precision
+    pub fn utc_timestamp_format(self) -> &'static str {
+        match self {
+            TimestampPrecision::Millis => UTC_TIMESTAMP_FORMAT,
+            TimestampPrecision::Micros => UTC_TIMESTAMP_MICROS_FORMAT,
+        }
+    }
+
+    /// Name used in the configuration
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimestampPrecision::Millis => "millis",
+            TimestampPrecision::Micros => "micros",
+        }
+    }
+}
+
+impl fmt::Display for TimestampPrecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse `millis` or `micros`, also accepting `ms` and `us`
+impl FromStr for TimestampPrecision {
+    type Err = DeribitFixError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "millis" | "ms" => Ok(TimestampPrecision::Millis),
+            "micros" | "us" => Ok(TimestampPrecision::Micros),
+            other => Err(DeribitFixError::Config(format!(
+                "Unsupported timestamp precision: {other}"
+            ))),
+        }
+    }
+}
+
+/// Format a UTCTimestamp field with millisecond precision
+pub fn format_utc_timestamp(timestamp: &DateTime<Utc>) -> String {
+    format_utc_timestamp_with(timestamp, TimestampPrecision::Millis)
+}
+
+/// Format a UTCTimestamp field with `precision`
+pub fn format_utc_timestamp_with(
+    timestamp: &DateTime<Utc>,
+    precision: TimestampPrecision,
+) -> String {
+    timestamp
+        .format(precision.utc_timestamp_format())
+        .to_string()
+}
+
+/// Parse a UTCTimestamp field (`YYYYMMDD-HH:MM:SS[.sss|.ssssss|.sssssssss]`)
+pub fn parse_utc_timestamp(value: &str) -> Result<DateTime<Utc>> {
+    if !has_layout(value, "99999999-99:99:99", true) {
+        return Err(invalid("UTCTimestamp", value));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%d-%H:%M:%S%.f")
+        .map(|timestamp| timestamp.and_utc())
+        .map_err(|_| invalid("UTCTimestamp", value))
+}
+
+/// Format a UTCDateOnly field
+pub fn format_utc_date_only(date: &NaiveDate) -> String {
+    date.format(UTC_DATE_ONLY_FORMAT).to_string()
+}
+
+/// Parse a UTCDateOnly field (`YYYYMMDD`)
+pub fn parse_utc_date_only(value: &str) -> Result<NaiveDate> {
+    if !has_layout(value, "99999999", false) {
+        return Err(invalid("UTCDateOnly", value));
+    }
+    NaiveDate::parse_from_str(value, UTC_DATE_ONLY_FORMAT)
+        .map_err(|_| invalid("UTCDateOnly", value))
+}
+
+/// Format a UTCTimeOnly field
+pub fn format_utc_time_only(time: &NaiveTime) -> String {
+    time.format(UTC_TIME_ONLY_FORMAT).to_string()
+}
+
+/// Parse a UTCTimeOnly field (`HH:MM:SS[.sss|.ssssss|.sssssssss]`)
+pub fn parse_utc_time_only(value: &str) -> Result<NaiveTime> {
+    if !has_layout(value, "99:99:99", true) {
+        return Err(invalid("UTCTimeOnly", value));
+    }
+    NaiveTime::parse_from_str(value, "%H:%M:%S%.f").map_err(|_| invalid("UTCTimeOnly", value))
+}
+
+/// Parse a TZTimeOnly field and convert it to UTC
+///
+/// Accepts `HH:MM[:SS[.sss]]` followed by `Z`, an offset `±hh[:mm]` or
+/// nothing, in which case the time is taken as UTC.
+pub fn parse_tz_time_only(value: &str) -> Result<NaiveTime> {
+    let (time, offset) = match value.find(['Z', '+', '-']) {
+        Some(position) => value.split_at(position),
+        None => (value, ""),
+    };
+    if !has_layout(time, "99:99", false) && !has_layout(time, "99:99:99", true) {
+        return Err(invalid("TZTimeOnly", value));
+    }
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .map_err(|_| invalid("TZTimeOnly", value))?;
+    let offset = parse_tz_offset(offset).ok_or_else(|| invalid("TZTimeOnly", value))?;
+    let (utc, _) = time.overflowing_sub_signed(TimeDelta::seconds(offset.local_minus_utc().into()));
+    Ok(utc)
+}
+
+/// Parse the zone suffix of a TZTimeOnly field
+fn parse_tz_offset(value: &str) -> Option<FixedOffset> {
+    let sign = match value.chars().next() {
+        None => return FixedOffset::east_opt(0),
+        Some('Z') if value.len() == 1 => return FixedOffset::east_opt(0),
+        Some('+') => 1,
+        Some('-') => -1,
+        Some(_) => return None,
+    };
+    let (hours, minutes) = value[1..].split_once(':').unwrap_or((&value[1..], "0"));
+    if hours.len() != 2 || minutes.len() > 2 || !is_digits(hours) || !is_digits(minutes) {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Whether `value` follows `layout`, in which `9` stands for any digit,
+/// optionally followed by a fraction of 1 to 9 digits
+///
+/// chrono accepts signs, longer years and whitespace the FIX layouts do not
+/// allow, so the shape is checked before parsing.
+fn has_layout(value: &str, layout: &str, fraction: bool) -> bool {
+    let Some(rest) = value.get(layout.len()..) else {
+        return false;
+    };
+    let fixed = value
+        .bytes()
+        .zip(layout.bytes())
+        .all(|(byte, expected)| match expected {
+            b'9' => byte.is_ascii_digit(),
+            _ => byte == expected,
+        });
+    let fraction_ok = match rest.strip_prefix('.') {
+        None => rest.is_empty(),
+        Some(digits) => fraction && (1..=9).contains(&digits.len()) && is_digits(digits),
+    };
+    fixed && fraction_ok
+}
+
+fn is_digits(value: &str) -> bool {
+    value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn invalid(kind: &str, value: &str) -> DeribitFixError {
+    DeribitFixError::MessageParsing(format!("Invalid {kind}: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    #[test]
+    fn test_utc_timestamp_precisions() {
+        let seconds = parse_utc_timestamp("20260101-12:30:45").unwrap();
+        assert_eq!(seconds.nanosecond(), 0);
+        let millis = parse_utc_timestamp("20260101-12:30:45.123").unwrap();
+        assert_eq!(millis.nanosecond(), 123_000_000);
+        let micros = parse_utc_timestamp("20260101-12:30:45.123456").unwrap();
+        assert_eq!(micros.nanosecond(), 123_456_000);
+        let nanos = parse_utc_timestamp("20260101-12:30:45.123456789").unwrap();
+        assert_eq!(nanos.nanosecond(), 123_456_789);
+
+        assert_eq!(format_utc_timestamp(&micros), "20260101-12:30:45.123");
+        assert_eq!(
+            format_utc_timestamp_with(&nanos, TimestampPrecision::Micros),
+            "20260101-12:30:45.123456"
+        );
+        assert_eq!(
+            format_utc_timestamp_with(&seconds, TimestampPrecision::Micros),
+            "20260101-12:30:45.000000"
+        );
+        assert_eq!(
+            "us".parse::<TimestampPrecision>().unwrap(),
+            TimestampPrecision::Micros
+        );
+        assert!("nanos".parse::<TimestampPrecision>().is_err());
+        assert!(parse_utc_timestamp("2026-01-01T12:30:45Z").is_err());
+        assert!(parse_utc_timestamp("1767270645123").is_err());
+        assert!(parse_utc_timestamp("+90106\t2\t6-9:00:2").is_err());
+        assert!(parse_utc_timestamp("20260101-12:30:45.").is_err());
+        assert!(parse_utc_timestamp("20260101-12:30:45.1234567890").is_err());
+    }
+
+    #[test]
+    fn test_utc_date_only() {
+        let date = parse_utc_date_only("20260101").unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+        assert_eq!(format_utc_date_only(&date), "20260101");
+        assert!(parse_utc_date_only("2026011").is_err());
+        assert!(parse_utc_date_only("20261301").is_err());
+        assert!(parse_utc_date_only("+0260101").is_err());
+    }
+
+    #[test]
+    fn test_utc_time_only() {
+        let time = parse_utc_time_only("07:05:09.5").unwrap();
+        assert_eq!(format_utc_time_only(&time), "07:05:09.500");
+        assert!(parse_utc_time_only("25:00:00").is_err());
+    }
+
+    #[test]
+    fn test_tz_time_only_is_converted_to_utc() {
+        let expected = NaiveTime::from_hms_opt(7, 39, 0).unwrap();
+        assert_eq!(parse_tz_time_only("07:39Z").unwrap(), expected);
+        assert_eq!(parse_tz_time_only("07:39").unwrap(), expected);
+        assert_eq!(parse_tz_time_only("02:39-05").unwrap(), expected);
+        assert_eq!(parse_tz_time_only("13:09+05:30").unwrap(), expected);
+        assert_eq!(
+            parse_tz_time_only("00:30:00+01").unwrap(),
+            NaiveTime::from_hms_opt(23, 30, 0).unwrap()
+        );
+        assert!(parse_tz_time_only("07:39+5").is_err());
+        assert!(parse_tz_time_only("07:39X").is_err());
+        assert!(parse_tz_time_only("07:39++1").is_err());
+        assert!(parse_tz_time_only(" 7:39Z").is_err());
+    }
+}
@@ -6,12 +6,19 @@
 
 //! Trade Capture Report FIX Message Implementation
 
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::OrderSide;
+use crate::message::time::{format_utc_timestamp, parse_utc_timestamp};
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// TrdType (828) value identifying a block trade
+pub const TRD_TYPE_BLOCK_TRADE: i32 = 1;
 
 /// Trade Capture Report Leg for multi-leg trades (NoLegs group)
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
@@ -38,6 +45,25 @@ impl TradeCaptureReportLeg {
     }
 }
 
+impl TradeCaptureReportLeg {
+    /// Empty leg filled in while parsing the NoLegs group
+    fn empty() -> Self {
+        Self::new(String::new(), 0.0, 0.0, OrderSide::Buy)
+    }
+
+    /// Apply a field of the NoLegs group, ignoring tags outside the group
+    fn apply_field(&mut self, tag: u32, value: &str) -> DeribitFixResult<()> {
+        match tag {
+            tags::LEG_SYMBOL => self.leg_symbol = value.to_string(),
+            tags::LEG_QTY => self.leg_qty = parse_field(tag, value)?,
+            tags::LEG_PRICE => self.leg_price = parse_field(tag, value)?,
+            tags::LEG_SIDE => self.leg_side = parse_side(tag, value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
 impl_json_display!(TradeCaptureReportLeg);
 impl_json_debug_pretty!(TradeCaptureReportLeg);
 
@@ -74,11 +100,74 @@ impl TradeCaptureReportSide {
     }
 }
 
+impl TradeCaptureReportSide {
+    /// Apply a field of the NoSides group, ignoring tags outside the group
+    fn apply_field(&mut self, tag: u32, value: &str) -> DeribitFixResult<()> {
+        match tag {
+            tags::SIDE => self.side = parse_side(tag, value)?,
+            tags::ORDER_ID => self.order_id = value.to_string(),
+            tags::COMMISSION => self.commission = Some(parse_field(tag, value)?),
+            tags::COMM_CURRENCY => self.comm_currency = Some(value.to_string()),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
 impl_json_display!(TradeCaptureReportSide);
 impl_json_debug_pretty!(TradeCaptureReportSide);
 
+/// Block trade or combo execution reported as a single Trade Capture Report
+///
+/// Built from a report with TrdType (828) = 1 or a NoLegs (555) group, so that
+/// option strategies and block trades keep their legs together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockTrade {
+    /// Block Trade ID or Combo Trade ID (TrdMatchID, tag 880)
+    pub trd_match_id: Option<String>,
+    /// Trade report ID (tag 571)
+    pub trade_report_id: String,
+    /// Trade ID (tag 1003)
+    pub trade_id: Option<String>,
+    /// Combo or instrument symbol (tag 55)
+    pub symbol: String,
+    /// Side (tag 54)
+    pub side: OrderSide,
+    /// Quantity (tag 53)
+    pub quantity: f64,
+    /// Price of the strategy (LastPx, tag 31)
+    pub price: f64,
+    /// Whether the report was flagged as a block trade (TrdType 828 = 1)
+    pub is_block_trade: bool,
+    /// Executed legs (NoLegs group, tag 555)
+    pub legs: Vec<TradeCaptureReportLeg>,
+    /// Transaction time (tag 60)
+    pub transact_time: DateTime<Utc>,
+}
+
+fn parse_field<T: FromStr>(tag: u32, value: &str) -> DeribitFixResult<T> {
+    value.parse().map_err(|_| {
+        DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+    })
+}
+
+fn parse_side(tag: u32, value: &str) -> DeribitFixResult<OrderSide> {
+    value
+        .chars()
+        .next()
+        .ok_or_else(|| DeribitFixError::MessageParsing(format!("Empty value for tag {tag}")))
+        .and_then(|side| OrderSide::try_from(side).map_err(DeribitFixError::MessageParsing))
+}
+
+fn parse_timestamp(tag: u32, value: &str) -> DeribitFixResult<DateTime<Utc>> {
+    parse_utc_timestamp(value).map_err(|_| {
+        DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+    })
+}
+
 /// Trade capture report type enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum TradeCaptureReportType {
     /// Submit
     Submit,
@@ -98,6 +187,8 @@ pub enum TradeCaptureReportType {
     LockedInTradeBreak,
     /// Restated
     Restated,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<TradeCaptureReportType> for i32 {
@@ -112,10 +203,12 @@ impl From<TradeCaptureReportType> for i32 {
             TradeCaptureReportType::TradeReportCancel => 6,
             TradeCaptureReportType::LockedInTradeBreak => 7,
             TradeCaptureReportType::Restated => 8,
+            TradeCaptureReportType::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`TradeCaptureReportType::Unknown`]
 impl TryFrom<i32> for TradeCaptureReportType {
     type Error = String;
 
@@ -130,13 +223,14 @@ impl TryFrom<i32> for TradeCaptureReportType {
             6 => Ok(TradeCaptureReportType::TradeReportCancel),
             7 => Ok(TradeCaptureReportType::LockedInTradeBreak),
             8 => Ok(TradeCaptureReportType::Restated),
-            _ => Err(format!("Invalid TradeCaptureReportType: {}", value)),
+            _ => Ok(TradeCaptureReportType::Unknown(value)),
         }
     }
 }
 
 /// Trade report transaction type enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum TradeReportTransType {
     /// New
     New,
@@ -150,6 +244,8 @@ pub enum TradeReportTransType {
     Reverse,
     /// Cancel Due To Back Out Of Trade
     CancelDueToBackOutOfTrade,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<TradeReportTransType> for i32 {
@@ -161,10 +257,12 @@ impl From<TradeReportTransType> for i32 {
             TradeReportTransType::Release => 3,
             TradeReportTransType::Reverse => 4,
             TradeReportTransType::CancelDueToBackOutOfTrade => 5,
+            TradeReportTransType::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`TradeReportTransType::Unknown`]
 impl TryFrom<i32> for TradeReportTransType {
     type Error = String;
 
@@ -176,7 +274,7 @@ impl TryFrom<i32> for TradeReportTransType {
             3 => Ok(TradeReportTransType::Release),
             4 => Ok(TradeReportTransType::Reverse),
             5 => Ok(TradeReportTransType::CancelDueToBackOutOfTrade),
-            _ => Err(format!("Invalid TradeReportTransType: {}", value)),
+            _ => Ok(TradeReportTransType::Unknown(value)),
         }
     }
 }
@@ -498,13 +596,178 @@ impl TradeCaptureReport {
         self
     }
 
+    /// Whether the report is flagged as a block trade (TrdType 828 = 1)
+    pub fn is_block_trade(&self) -> bool {
+        self.trd_type == Some(TRD_TYPE_BLOCK_TRADE)
+    }
+
+    /// Typed view of a block trade or multi-leg execution
+    ///
+    /// Returns `None` for plain single-instrument trades.
+    pub fn block_trade(&self) -> Option<BlockTrade> {
+        if !self.is_block_trade() && self.legs.is_empty() {
+            return None;
+        }
+        Some(BlockTrade {
+            trd_match_id: self.trd_match_id.clone(),
+            trade_report_id: self.trade_report_id.clone(),
+            trade_id: self.trade_id.clone(),
+            symbol: self.symbol.clone(),
+            side: self.side,
+            quantity: self.quantity,
+            price: self.last_px,
+            is_block_trade: self.is_block_trade(),
+            legs: self.legs.clone(),
+            transact_time: self.transact_time,
+        })
+    }
+
+    /// Parse from FIX message
+    ///
+    /// Body fields take their first occurrence; the NoLegs (555) and NoSides
+    /// (552) repeating groups are parsed into `legs` and `sides`, each instance
+    /// starting at LegSymbol (600) and Side (54) respectively.
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let required = |tag: u32, name: &str| {
+            message.get_field(tag).ok_or_else(|| {
+                DeribitFixError::MessageParsing(format!("{name} ({tag}) is required"))
+            })
+        };
+
+        let trade_report_id = required(tags::TRADE_REPORT_ID, "TradeReportID")?.clone();
+        let symbol = required(tags::SYMBOL, "Symbol")?.clone();
+        let side = parse_side(tags::SIDE, required(tags::SIDE, "Side")?)?;
+        let last_px = parse_field(tags::LAST_PX, required(tags::LAST_PX, "LastPx")?)?;
+        let last_qty: f64 = match message.get_field(tags::LAST_QTY) {
+            Some(value) => parse_field(tags::LAST_QTY, value)?,
+            None => 0.0,
+        };
+        let quantity = match message.get_field(tags::QUANTITY) {
+            Some(value) => parse_field(tags::QUANTITY, value)?,
+            None => last_qty,
+        };
+        let trade_date = message
+            .get_field(tags::TRADE_DATE)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut report = Self::new(
+            trade_report_id,
+            symbol,
+            side,
+            quantity,
+            last_qty,
+            last_px,
+            trade_date,
+        );
+
+        if let Some(value) = message.get_field(tags::TRANSACT_TIME) {
+            report.transact_time = parse_timestamp(tags::TRANSACT_TIME, value)?;
+        }
+        if let Some(value) = message.get_field(tags::EXPIRE_TIME) {
+            report.exec_time = Some(parse_timestamp(tags::EXPIRE_TIME, value)?);
+        }
+        if let Some(value) = message.get_field(tags::TRADE_REPORT_TRANS_TYPE) {
+            report.trade_report_trans_type = Some(
+                TradeReportTransType::try_from(parse_field::<i32>(487, value)?)
+                    .map_err(DeribitFixError::MessageParsing)?,
+            );
+        }
+        if let Some(value) = message.get_field(tags::TRADE_REPORT_TYPE) {
+            report.trade_report_type = Some(
+                TradeCaptureReportType::try_from(parse_field::<i32>(856, value)?)
+                    .map_err(DeribitFixError::MessageParsing)?,
+            );
+        }
+        if let Some(value) = message.get_field(tags::TRD_TYPE) {
+            report.trd_type = Some(parse_field(tags::TRD_TYPE, value)?);
+        }
+        if let Some(value) = message.get_field(tags::TRD_SUB_TYPE) {
+            report.trade_sub_type = Some(parse_field(tags::TRD_SUB_TYPE, value)?);
+        }
+        if let Some(value) = message.get_field(tags::ORDER_QTY) {
+            report.order_qty = Some(parse_field(tags::ORDER_QTY, value)?);
+        }
+        if let Some(value) = message.get_field(tags::GROSS_TRADE_AMT) {
+            report.gross_trade_amt = Some(parse_field(tags::GROSS_TRADE_AMT, value)?);
+        }
+        if let Some(value) = message.get_field(tags::MULTI_LEG_REPORTING_TYPE) {
+            report.multi_leg_reporting_type = value.chars().next();
+        }
+        if let Some(value) = message.get_field(tags::PREVIOUSLY_REPORTED) {
+            report.previously_reported = Some(value == "Y");
+        }
+        if let Some(value) = message.get_field(tags::UNDERLYING_PX) {
+            report.underlying_price = Some(parse_field(tags::UNDERLYING_PX, value)?);
+        }
+        report.trade_id = message.get_field(tags::TRADE_ID).cloned();
+        report.secondary_trade_id = message.get_field(tags::SECONDARY_TRADE_ID).cloned();
+        report.firm_trade_id = message.get_field(tags::FIRM_TRADE_ID).cloned();
+        report.trade_request_id = message.get_field(tags::TRADE_REQUEST_ID).cloned();
+        report.settlement_date = message.get_field(tags::SETTL_DATE).cloned();
+        report.account = message.get_field(tags::ACCOUNT).cloned();
+        report.clearing_account = message.get_field(tags::CLEARING_ACCOUNT).cloned();
+        report.position_effect = message
+            .get_field(tags::POSITION_EFFECT)
+            .and_then(|value| value.chars().next());
+        report.clearing_business_date = message.get_field(tags::CLEARING_BUSINESS_DATE).cloned();
+        report.trading_session_id = message.get_field(tags::TRADING_SESSION_ID).cloned();
+        report.trading_session_sub_id = message.get_field(tags::TRADING_SESSION_SUB_ID).cloned();
+        report.market_segment_id = message.get_field(tags::MARKET_SEGMENT_ID).cloned();
+        report.text = message.get_field(tags::TEXT).cloned();
+        report.deribit_label = message.get_field(tags::DERIBIT_LABEL).cloned();
+        report.trd_match_id = message.get_field(tags::TRD_MATCH_ID).cloned();
+
+        report.parse_groups(message)?;
+        Ok(report)
+    }
+
+    /// Parse the NoLegs (555) and NoSides (552) repeating groups
+    fn parse_groups(&mut self, message: &FixMessage) -> DeribitFixResult<()> {
+        enum Group {
+            None,
+            Legs,
+            Sides,
+        }
+        let mut group = Group::None;
+
+        for (tag, value) in &message.fields {
+            match *tag {
+                tags::NO_LEGS => group = Group::Legs,
+                tags::NO_SIDES => group = Group::Sides,
+                tags::CHECKSUM => break,
+                _ => match group {
+                    Group::None => {}
+                    Group::Legs => {
+                        if *tag == tags::LEG_SYMBOL {
+                            self.legs.push(TradeCaptureReportLeg::empty());
+                        }
+                        if let Some(leg) = self.legs.last_mut() {
+                            leg.apply_field(*tag, value)?;
+                        }
+                    }
+                    Group::Sides => {
+                        if *tag == tags::SIDE {
+                            self.sides
+                                .push(TradeCaptureReportSide::new(OrderSide::Buy, String::new()));
+                        }
+                        if let Some(side) = self.sides.last_mut() {
+                            side.apply_field(*tag, value)?;
+                        }
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::TradeCaptureReport)
             .sender_comp_id(sender_comp_id.to_string())
@@ -514,158 +777,167 @@ impl TradeCaptureReport {
 
         // Required fields
         builder = builder
-            .field(571, self.trade_report_id.clone()) // TradeReportID
-            .field(55, self.symbol.clone()) // Symbol
-            .field(54, char::from(self.side).to_string()) // Side
-            .field(53, self.quantity.to_string()) // Quantity
-            .field(32, self.last_qty.to_string()) // LastQty
-            .field(31, self.last_px.to_string()) // LastPx
-            .field(75, self.trade_date.clone()) // TradeDate
+            .field(tags::TRADE_REPORT_ID, self.trade_report_id.clone())
+            .field(tags::SYMBOL, self.symbol.clone())
+            .field(tags::SIDE, char::from(self.side).to_string())
+            .field(tags::QUANTITY, self.quantity.to_string())
+            .field(tags::LAST_QTY, self.last_qty.to_string())
+            .field(tags::LAST_PX, self.last_px.to_string())
+            .field(tags::TRADE_DATE, self.trade_date.clone())
             .field(
-                60,
-                self.transact_time.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
-            ); // TransactTime
+                tags::TRANSACT_TIME,
+                format_utc_timestamp(&self.transact_time),
+            );
 
         // Optional fields
         if let Some(trade_id) = &self.trade_id {
-            builder = builder.field(1003, trade_id.clone());
+            builder = builder.field(tags::TRADE_ID, trade_id.clone());
         }
 
         if let Some(secondary_trade_id) = &self.secondary_trade_id {
-            builder = builder.field(1040, secondary_trade_id.clone());
+            builder = builder.field(tags::SECONDARY_TRADE_ID, secondary_trade_id.clone());
         }
 
         if let Some(firm_trade_id) = &self.firm_trade_id {
-            builder = builder.field(1041, firm_trade_id.clone());
+            builder = builder.field(tags::FIRM_TRADE_ID, firm_trade_id.clone());
         }
 
         if let Some(trade_report_trans_type) = &self.trade_report_trans_type {
-            builder = builder.field(487, i32::from(*trade_report_trans_type).to_string());
+            builder = builder.field(
+                tags::TRADE_REPORT_TRANS_TYPE,
+                i32::from(*trade_report_trans_type).to_string(),
+            );
         }
 
         if let Some(trade_report_type) = &self.trade_report_type {
-            builder = builder.field(856, i32::from(*trade_report_type).to_string());
+            builder = builder.field(
+                tags::TRADE_REPORT_TYPE,
+                i32::from(*trade_report_type).to_string(),
+            );
         }
 
         if let Some(trade_request_id) = &self.trade_request_id {
-            builder = builder.field(568, trade_request_id.clone());
+            builder = builder.field(tags::TRADE_REQUEST_ID, trade_request_id.clone());
         }
 
         if let Some(trd_type) = &self.trd_type {
-            builder = builder.field(828, trd_type.to_string());
+            builder = builder.field(tags::TRD_TYPE, trd_type.to_string());
         }
 
         if let Some(trade_sub_type) = &self.trade_sub_type {
-            builder = builder.field(829, trade_sub_type.to_string());
+            builder = builder.field(tags::TRD_SUB_TYPE, trade_sub_type.to_string());
         }
 
         if let Some(order_qty) = &self.order_qty {
-            builder = builder.field(38, order_qty.to_string());
+            builder = builder.field(tags::ORDER_QTY, order_qty.to_string());
         }
 
         if let Some(gross_trade_amt) = &self.gross_trade_amt {
-            builder = builder.field(381, gross_trade_amt.to_string());
+            builder = builder.field(tags::GROSS_TRADE_AMT, gross_trade_amt.to_string());
         }
 
         if let Some(exec_time) = &self.exec_time {
-            builder = builder.field(126, exec_time.format("%Y%m%d-%H:%M:%S%.3f").to_string());
+            builder = builder.field(tags::EXPIRE_TIME, format_utc_timestamp(exec_time));
         }
 
         if let Some(settlement_date) = &self.settlement_date {
-            builder = builder.field(64, settlement_date.clone());
+            builder = builder.field(tags::SETTL_DATE, settlement_date.clone());
         }
 
         if let Some(multi_leg_reporting_type) = &self.multi_leg_reporting_type {
-            builder = builder.field(442, multi_leg_reporting_type.to_string());
+            builder = builder.field(
+                tags::MULTI_LEG_REPORTING_TYPE,
+                multi_leg_reporting_type.to_string(),
+            );
         }
 
         if let Some(previously_reported) = &self.previously_reported {
             builder = builder.field(
-                570,
+                tags::PREVIOUSLY_REPORTED,
                 if *previously_reported { "Y" } else { "N" }.to_string(),
             );
         }
 
         if let Some(price_type) = &self.price_type {
-            builder = builder.field(423, price_type.to_string());
+            builder = builder.field(tags::PRICE_TYPE, price_type.to_string());
         }
 
         if let Some(underlying_price) = &self.underlying_price {
-            builder = builder.field(810, underlying_price.to_string());
+            builder = builder.field(tags::UNDERLYING_PX, underlying_price.to_string());
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(clearing_account) = &self.clearing_account {
-            builder = builder.field(440, clearing_account.clone());
+            builder = builder.field(tags::CLEARING_ACCOUNT, clearing_account.clone());
         }
 
         if let Some(position_effect) = &self.position_effect {
-            builder = builder.field(77, position_effect.to_string());
+            builder = builder.field(tags::POSITION_EFFECT, position_effect.to_string());
         }
 
         if let Some(clearing_business_date) = &self.clearing_business_date {
-            builder = builder.field(715, clearing_business_date.clone());
+            builder = builder.field(tags::CLEARING_BUSINESS_DATE, clearing_business_date.clone());
         }
 
         if let Some(trading_session_id) = &self.trading_session_id {
-            builder = builder.field(336, trading_session_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_ID, trading_session_id.clone());
         }
 
         if let Some(trading_session_sub_id) = &self.trading_session_sub_id {
-            builder = builder.field(625, trading_session_sub_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_SUB_ID, trading_session_sub_id.clone());
         }
 
         if let Some(market_segment_id) = &self.market_segment_id {
-            builder = builder.field(1300, market_segment_id.clone());
+            builder = builder.field(tags::MARKET_SEGMENT_ID, market_segment_id.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         // Block trade match ID (tag 880)
         if let Some(trd_match_id) = &self.trd_match_id {
-            builder = builder.field(880, trd_match_id.clone());
+            builder = builder.field(tags::TRD_MATCH_ID, trd_match_id.clone());
         }
 
         // Legs group (NoLegs, tag 555)
         if !self.legs.is_empty() {
-            builder = builder.field(555, self.legs.len().to_string());
+            builder = builder.field(tags::NO_LEGS, self.legs.len().to_string());
             for leg in &self.legs {
                 builder = builder
-                    .field(600, leg.leg_symbol.clone()) // LegSymbol
-                    .field(687, leg.leg_qty.to_string()) // LegQty
-                    .field(566, leg.leg_price.to_string()) // LegPrice
-                    .field(624, char::from(leg.leg_side).to_string()); // LegSide
+                    .push_field(tags::LEG_SYMBOL, leg.leg_symbol.clone())
+                    .push_field(tags::LEG_QTY, leg.leg_qty.to_string())
+                    .push_field(tags::LEG_PRICE, leg.leg_price.to_string())
+                    .push_field(tags::LEG_SIDE, char::from(leg.leg_side).to_string());
             }
         }
 
         // Sides group (NoSides, tag 552)
         if !self.sides.is_empty() {
-            builder = builder.field(552, self.sides.len().to_string());
+            builder = builder.field(tags::NO_SIDES, self.sides.len().to_string());
             for side in &self.sides {
                 builder = builder
-                    .field(54, char::from(side.side).to_string()) // Side
-                    .field(37, side.order_id.clone()); // OrderId
+                    .push_field(tags::SIDE, char::from(side.side).to_string())
+                    .push_field(tags::ORDER_ID, side.order_id.clone());
 
                 if let Some(commission) = side.commission {
-                    builder = builder.field(12, commission.to_string());
+                    builder = builder.push_field(tags::COMMISSION, commission.to_string());
                 }
 
                 if let Some(comm_currency) = &side.comm_currency {
-                    builder = builder.field(479, comm_currency.clone());
+                    builder = builder.push_field(tags::COMM_CURRENCY, comm_currency.clone());
                 }
             }
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -675,6 +947,7 @@ impl_json_debug_pretty!(TradeCaptureReport);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_trade_capture_report_creation() {
@@ -807,7 +1080,7 @@ mod tests {
         )
         .with_label("test-label".to_string());
 
-        let fix_message = report.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = report.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=AE")); // MsgType
@@ -848,7 +1121,11 @@ mod tests {
             TradeCaptureReportType::TradeReportCancel
         );
 
-        assert!(TradeCaptureReportType::try_from(99).is_err());
+        assert_eq!(
+            TradeCaptureReportType::try_from(99).unwrap(),
+            TradeCaptureReportType::Unknown(99)
+        );
+        assert_eq!(i32::from(TradeCaptureReportType::Unknown(99)), 99);
     }
 
     #[test]
@@ -878,7 +1155,11 @@ mod tests {
             TradeReportTransType::CancelDueToBackOutOfTrade
         );
 
-        assert!(TradeReportTransType::try_from(99).is_err());
+        assert_eq!(
+            TradeReportTransType::try_from(99).unwrap(),
+            TradeReportTransType::Unknown(99)
+        );
+        assert_eq!(i32::from(TradeReportTransType::Unknown(99)), 99);
     }
 
     #[test]
@@ -961,7 +1242,7 @@ mod tests {
         .with_leg(leg)
         .with_side(side);
 
-        let fix_message = report.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = report.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check block trade fields
         assert!(fix_message.contains("880=BLOCK_456")); // TrdMatchID
@@ -975,4 +1256,65 @@ mod tests {
         assert!(fix_message.contains("12=0.01")); // Commission
         assert!(fix_message.contains("479=ETH")); // CommCurrency
     }
+
+    #[test]
+    fn test_trade_capture_report_from_fix_message_with_legs() {
+        let raw = "8=FIX.4.4\x019=300\x0135=AE\x01571=TR_COMBO\x011003=T1\x0155=BTC-CS-25DEC25-100000_110000\x0154=1\x0153=2\x0132=2\x0131=0.02\x0175=20250812\x0160=20250812-10:00:00.123\x01828=1\x01880=BLOCK_1\x01555=2\x01600=BTC-25DEC25-100000-C\x01687=2\x01566=0.05\x01624=1\x01600=BTC-25DEC25-110000-C\x01687=2\x01566=0.03\x01624=2\x01552=1\x0154=1\x0137=ORD1\x0112=0.0003\x01479=BTC\x0110=000\x01";
+        let message = FixMessage::parse(raw).unwrap();
+        let report = TradeCaptureReport::from_fix_message(&message).unwrap();
+
+        assert_eq!(report.trade_report_id, "TR_COMBO");
+        assert_eq!(report.trd_match_id, Some("BLOCK_1".to_string()));
+        assert!(report.is_block_trade());
+        assert_eq!(
+            report.transact_time.format("%H:%M:%S%.3f").to_string(),
+            "10:00:00.123"
+        );
+        assert_eq!(report.legs.len(), 2);
+        assert_eq!(report.legs[1].leg_symbol, "BTC-25DEC25-110000-C");
+        assert_eq!(report.legs[1].leg_price, 0.03);
+        assert_eq!(report.legs[1].leg_side, OrderSide::Sell);
+        assert_eq!(report.sides.len(), 1);
+        assert_eq!(report.sides[0].order_id, "ORD1");
+        assert_eq!(report.sides[0].commission, Some(0.0003));
+
+        let block = report.block_trade().unwrap();
+        assert_eq!(block.trd_match_id, Some("BLOCK_1".to_string()));
+        assert_eq!(block.price, 0.02);
+        assert_eq!(block.quantity, 2.0);
+        assert_eq!(block.legs, report.legs);
+    }
+
+    #[test]
+    fn test_trade_capture_report_round_trip_without_legs() {
+        let report = TradeCaptureReport::new_trade(
+            "TR1".to_string(),
+            "TRADE1".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            OrderSide::Sell,
+            10.0,
+            10.0,
+            50000.0,
+            "20250812".to_string(),
+        );
+        let message = report.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let parsed = TradeCaptureReport::from_fix_message(&message).unwrap();
+
+        assert_eq!(parsed.trade_id, Some("TRADE1".to_string()));
+        assert_eq!(parsed.side, OrderSide::Sell);
+        assert_eq!(
+            parsed.trade_report_trans_type,
+            Some(TradeReportTransType::New)
+        );
+        assert!(parsed.block_trade().is_none());
+    }
+
+    #[test]
+    fn test_trade_capture_report_from_fix_message_requires_report_id() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=20\x0135=AE\x0155=BTC-PERPETUAL\x0154=1\x0131=1\x0110=000\x01",
+        )
+        .unwrap();
+        assert!(TradeCaptureReport::from_fix_message(&message).is_err());
+    }
 }
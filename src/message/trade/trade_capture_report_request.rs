@@ -9,6 +9,9 @@
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
 use crate::message::orders::OrderSide;
+use crate::message::time::format_utc_timestamp;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -241,7 +244,7 @@ impl TradeCaptureReportRequest {
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::TradeCaptureReportRequest)
             .sender_comp_id(sender_comp_id.to_string())
@@ -251,81 +254,87 @@ impl TradeCaptureReportRequest {
 
         // Required fields
         builder = builder
-            .field(568, self.trade_request_id.clone()) // TradeRequestID
-            .field(569, i32::from(self.trade_request_type).to_string()); // TradeRequestType
+            .field(tags::TRADE_REQUEST_ID, self.trade_request_id.clone())
+            .field(
+                tags::TRADE_REQUEST_TYPE,
+                i32::from(self.trade_request_type).to_string(),
+            );
 
         // Optional fields
         if let Some(subscription_request_type) = &self.subscription_request_type {
-            builder = builder.field(263, char::from(*subscription_request_type).to_string());
+            builder = builder.field(
+                tags::SUBSCRIPTION_REQUEST_TYPE,
+                char::from(*subscription_request_type).to_string(),
+            );
         }
 
         if let Some(trade_report_id) = &self.trade_report_id {
-            builder = builder.field(571, trade_report_id.clone());
+            builder = builder.field(tags::TRADE_REPORT_ID, trade_report_id.clone());
         }
 
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(side) = &self.side {
-            builder = builder.field(54, char::from(*side).to_string());
+            builder = builder.field(tags::SIDE, char::from(*side).to_string());
         }
 
         if let Some(order_qty) = &self.order_qty {
-            builder = builder.field(38, order_qty.to_string());
+            builder = builder.field(tags::ORDER_QTY, order_qty.to_string());
         }
 
         if let Some(transact_time_from) = &self.transact_time_from {
             builder = builder.field(
-                60, // Using TransactTime field for from time
-                transact_time_from.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+                tags::TRANSACT_TIME, // Using TransactTime field for from time
+                format_utc_timestamp(transact_time_from),
             );
         }
 
         if let Some(transact_time_to) = &self.transact_time_to {
             builder = builder.field(
-                126, // Using ExpireTime field for to time
-                transact_time_to.format("%Y%m%d-%H:%M:%S%.3f").to_string(),
+                tags::EXPIRE_TIME, // Using ExpireTime field for to time
+                format_utc_timestamp(transact_time_to),
             );
         }
 
         if let Some(clearing_business_date) = &self.clearing_business_date {
-            builder = builder.field(715, clearing_business_date.clone());
+            builder = builder.field(tags::CLEARING_BUSINESS_DATE, clearing_business_date.clone());
         }
 
         if let Some(trade_date) = &self.trade_date {
-            builder = builder.field(75, trade_date.clone());
+            builder = builder.field(tags::TRADE_DATE, trade_date.clone());
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(clearing_account) = &self.clearing_account {
-            builder = builder.field(440, clearing_account.clone());
+            builder = builder.field(tags::CLEARING_ACCOUNT, clearing_account.clone());
         }
 
         if let Some(market_segment_id) = &self.market_segment_id {
-            builder = builder.field(1300, market_segment_id.clone());
+            builder = builder.field(tags::MARKET_SEGMENT_ID, market_segment_id.clone());
         }
 
         if let Some(trading_session_id) = &self.trading_session_id {
-            builder = builder.field(336, trading_session_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_ID, trading_session_id.clone());
         }
 
         if let Some(trading_session_sub_id) = &self.trading_session_sub_id {
-            builder = builder.field(625, trading_session_sub_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_SUB_ID, trading_session_sub_id.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -335,6 +344,7 @@ impl_json_debug_pretty!(TradeCaptureReportRequest);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_trade_capture_report_request_creation() {
@@ -430,7 +440,7 @@ mod tests {
                 .with_subscription_type(SubscriptionRequestType::Snapshot)
                 .with_label("test-label".to_string());
 
-        let fix_message = request.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=AD")); // MsgType
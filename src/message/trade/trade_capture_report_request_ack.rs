@@ -6,14 +6,17 @@
 
 //! Trade Capture Report Request Ack FIX Message Implementation
 
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 /// Trade capture report request result enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum TradeCaptureRequestResult {
     /// Successful (default)
     Successful,
@@ -33,6 +36,8 @@ pub enum TradeCaptureRequestResult {
     UnauthorizedForTradeCaptureReportRequest,
     /// Other
     Other,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<TradeCaptureRequestResult> for i32 {
@@ -47,10 +52,12 @@ impl From<TradeCaptureRequestResult> for i32 {
             TradeCaptureRequestResult::TradeRequestTypeNotSupported => 8,
             TradeCaptureRequestResult::UnauthorizedForTradeCaptureReportRequest => 9,
             TradeCaptureRequestResult::Other => 99,
+            TradeCaptureRequestResult::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`TradeCaptureRequestResult::Unknown`]
 impl TryFrom<i32> for TradeCaptureRequestResult {
     type Error = String;
 
@@ -65,13 +72,14 @@ impl TryFrom<i32> for TradeCaptureRequestResult {
             8 => Ok(TradeCaptureRequestResult::TradeRequestTypeNotSupported),
             9 => Ok(TradeCaptureRequestResult::UnauthorizedForTradeCaptureReportRequest),
             99 => Ok(TradeCaptureRequestResult::Other),
-            _ => Err(format!("Invalid TradeCaptureRequestResult: {}", value)),
+            _ => Ok(TradeCaptureRequestResult::Unknown(value)),
         }
     }
 }
 
 /// Trade capture request status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum TradeCaptureRequestStatus {
     /// Accepted
     Accepted,
@@ -79,6 +87,8 @@ pub enum TradeCaptureRequestStatus {
     Completed,
     /// Rejected
     Rejected,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<TradeCaptureRequestStatus> for i32 {
@@ -87,10 +97,12 @@ impl From<TradeCaptureRequestStatus> for i32 {
             TradeCaptureRequestStatus::Accepted => 0,
             TradeCaptureRequestStatus::Completed => 1,
             TradeCaptureRequestStatus::Rejected => 2,
+            TradeCaptureRequestStatus::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`TradeCaptureRequestStatus::Unknown`]
 impl TryFrom<i32> for TradeCaptureRequestStatus {
     type Error = String;
 
@@ -99,7 +111,7 @@ impl TryFrom<i32> for TradeCaptureRequestStatus {
             0 => Ok(TradeCaptureRequestStatus::Accepted),
             1 => Ok(TradeCaptureRequestStatus::Completed),
             2 => Ok(TradeCaptureRequestStatus::Rejected),
-            _ => Err(format!("Invalid TradeCaptureRequestStatus: {}", value)),
+            _ => Ok(TradeCaptureRequestStatus::Unknown(value)),
         }
     }
 }
@@ -239,13 +251,68 @@ impl TradeCaptureReportRequestAck {
         self
     }
 
+    /// Whether the request was rejected
+    pub fn is_rejected(&self) -> bool {
+        self.trade_request_status == TradeCaptureRequestStatus::Rejected
+    }
+
+    /// Error for a rejected request, keeping the exchange's Text (58)
+    pub fn reject_error(&self) -> Option<DeribitFixError> {
+        self.is_rejected()
+            .then(|| DeribitFixError::TradeCaptureRejected {
+                trade_request_id: self.trade_request_id.clone(),
+                result: self.trade_request_result,
+                text: self.text.clone(),
+            })
+    }
+
+    /// Parse from FIX message
+    ///
+    /// TradeRequestID (568) and TradeRequestStatus (750) are required; an
+    /// unknown TradeRequestResult (749) is left out rather than failing.
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let required = |tag: u32, name: &str| {
+            message.get_field(tag).ok_or_else(|| {
+                DeribitFixError::MessageParsing(format!("{name} ({tag}) is required"))
+            })
+        };
+        let number = |tag: u32, value: &str| {
+            value.parse::<i32>().map_err(|_| {
+                DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+            })
+        };
+        let status = required(tags::TRADE_REQUEST_STATUS, "TradeRequestStatus")?;
+        let trade_request_status =
+            TradeCaptureRequestStatus::try_from(number(tags::TRADE_REQUEST_STATUS, status)?)
+                .map_err(DeribitFixError::MessageParsing)?;
+
+        let mut ack = Self::new(
+            required(tags::TRADE_REQUEST_ID, "TradeRequestID")?.clone(),
+            trade_request_status,
+        );
+        ack.trade_request_result = message
+            .get_field(tags::TRADE_REQUEST_RESULT)
+            .and_then(|value| value.parse::<i32>().ok())
+            .and_then(|value| TradeCaptureRequestResult::try_from(value).ok());
+        ack.trade_report_id = message.get_field(tags::TRADE_REPORT_ID).cloned();
+        ack.symbol = message.get_field(tags::SYMBOL).cloned();
+        ack.tot_num_trade_reports = message
+            .get_field(tags::TOT_NUM_TRADE_REPORTS)
+            .map(|value| number(tags::TOT_NUM_TRADE_REPORTS, value))
+            .transpose()?;
+        ack.account = message.get_field(tags::ACCOUNT).cloned();
+        ack.text = message.get_field(tags::TEXT).cloned();
+        ack.deribit_label = message.get_field(tags::DERIBIT_LABEL).cloned();
+        Ok(ack)
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::TradeCaptureReportRequestAck)
             .sender_comp_id(sender_comp_id.to_string())
@@ -255,67 +322,82 @@ impl TradeCaptureReportRequestAck {
 
         // Required fields
         builder = builder
-            .field(568, self.trade_request_id.clone()) // TradeRequestID
-            .field(749, i32::from(self.trade_request_status).to_string()); // TradeRequestStatus
+            .field(tags::TRADE_REQUEST_ID, self.trade_request_id.clone())
+            .field(
+                tags::TRADE_REQUEST_STATUS,
+                i32::from(self.trade_request_status).to_string(),
+            );
 
         // Optional fields
         if let Some(trade_request_result) = &self.trade_request_result {
-            builder = builder.field(750, i32::from(*trade_request_result).to_string());
+            builder = builder.field(
+                tags::TRADE_REQUEST_RESULT,
+                i32::from(*trade_request_result).to_string(),
+            );
         }
 
         if let Some(trade_report_id) = &self.trade_report_id {
-            builder = builder.field(571, trade_report_id.clone());
+            builder = builder.field(tags::TRADE_REPORT_ID, trade_report_id.clone());
         }
 
         if let Some(symbol) = &self.symbol {
-            builder = builder.field(55, symbol.clone());
+            builder = builder.field(tags::SYMBOL, symbol.clone());
         }
 
         if let Some(tot_num_trade_reports) = &self.tot_num_trade_reports {
-            builder = builder.field(748, tot_num_trade_reports.to_string());
+            builder = builder.field(
+                tags::TOT_NUM_TRADE_REPORTS,
+                tot_num_trade_reports.to_string(),
+            );
         }
 
         if let Some(multi_leg_reporting_type) = &self.multi_leg_reporting_type {
-            builder = builder.field(442, multi_leg_reporting_type.to_string());
+            builder = builder.field(
+                tags::MULTI_LEG_REPORTING_TYPE,
+                multi_leg_reporting_type.to_string(),
+            );
         }
 
         if let Some(response_transport_type) = &self.response_transport_type {
-            builder = builder.field(725, response_transport_type.to_string());
+            builder = builder.field(
+                tags::RESPONSE_TRANSPORT_TYPE,
+                response_transport_type.to_string(),
+            );
         }
 
         if let Some(response_destination) = &self.response_destination {
-            builder = builder.field(726, response_destination.clone());
+            builder = builder.field(tags::RESPONSE_DESTINATION, response_destination.clone());
         }
 
         if let Some(account) = &self.account {
-            builder = builder.field(1, account.clone());
+            builder = builder.field(tags::ACCOUNT, account.clone());
         }
 
         if let Some(clearing_account) = &self.clearing_account {
-            builder = builder.field(440, clearing_account.clone());
+            builder = builder.field(tags::CLEARING_ACCOUNT, clearing_account.clone());
         }
 
         if let Some(market_segment_id) = &self.market_segment_id {
-            builder = builder.field(1300, market_segment_id.clone());
+            builder = builder.field(tags::MARKET_SEGMENT_ID, market_segment_id.clone());
         }
 
         if let Some(trading_session_id) = &self.trading_session_id {
-            builder = builder.field(336, trading_session_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_ID, trading_session_id.clone());
         }
 
         if let Some(trading_session_sub_id) = &self.trading_session_sub_id {
-            builder = builder.field(625, trading_session_sub_id.clone());
+            builder = builder.field(tags::TRADING_SESSION_SUB_ID, trading_session_sub_id.clone());
         }
 
         if let Some(text) = &self.text {
-            builder = builder.field(58, text.clone());
+            builder = builder.field(tags::TEXT, text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -325,6 +407,7 @@ impl_json_debug_pretty!(TradeCaptureReportRequestAck);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_trade_capture_report_request_ack_creation() {
@@ -417,17 +500,49 @@ mod tests {
             .with_symbol("BTC-PERPETUAL".to_string())
             .with_label("test-label".to_string());
 
-        let fix_message = ack.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = ack.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=AQ")); // MsgType
         assert!(fix_message.contains("568=TR123")); // TradeRequestID
-        assert!(fix_message.contains("749=1")); // TradeRequestStatus=Completed
+        assert!(fix_message.contains("750=1")); // TradeRequestStatus=Completed
         assert!(fix_message.contains("748=5")); // TotNumTradeReports
         assert!(fix_message.contains("55=BTC-PERPETUAL")); // Symbol
         assert!(fix_message.contains("100010=test-label")); // Custom label
     }
 
+    #[test]
+    fn test_trade_capture_report_request_ack_round_trip() {
+        let ack = TradeCaptureReportRequestAck::rejected(
+            "TR321".to_string(),
+            TradeCaptureRequestResult::UnauthorizedForTradeCaptureReportRequest,
+            Some("not allowed".to_string()),
+        );
+        let message = ack.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        assert_eq!(message.get_field(750).unwrap(), "2");
+        assert_eq!(message.get_field(749).unwrap(), "9");
+
+        let parsed = TradeCaptureReportRequestAck::from_fix_message(&message).unwrap();
+        assert_eq!(parsed.trade_request_id, "TR321");
+        assert!(parsed.is_rejected());
+        match parsed.reject_error() {
+            Some(DeribitFixError::TradeCaptureRejected { result, text, .. }) => {
+                assert_eq!(
+                    result,
+                    Some(TradeCaptureRequestResult::UnauthorizedForTradeCaptureReportRequest)
+                );
+                assert_eq!(text.as_deref(), Some("not allowed"));
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        let accepted = TradeCaptureReportRequestAck::accepted("TR322".to_string())
+            .to_fix_message("SENDER", "TARGET", 2)
+            .unwrap();
+        let parsed = TradeCaptureReportRequestAck::from_fix_message(&accepted).unwrap();
+        assert!(parsed.reject_error().is_none());
+    }
+
     #[test]
     fn test_trade_capture_request_result_conversions() {
         assert_eq!(i32::from(TradeCaptureRequestResult::Successful), 0);
@@ -458,7 +573,10 @@ mod tests {
             TradeCaptureRequestResult::Other
         );
 
-        assert!(TradeCaptureRequestResult::try_from(50).is_err());
+        assert_eq!(
+            TradeCaptureRequestResult::try_from(50).unwrap(),
+            TradeCaptureRequestResult::Unknown(50)
+        );
     }
 
     #[test]
@@ -480,6 +598,9 @@ mod tests {
             TradeCaptureRequestStatus::Rejected
         );
 
-        assert!(TradeCaptureRequestStatus::try_from(99).is_err());
+        assert_eq!(
+            TradeCaptureRequestStatus::try_from(99).unwrap(),
+            TradeCaptureRequestStatus::Unknown(99)
+        );
     }
 }
@@ -0,0 +1,149 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Message conversion traits
+//!
+//! [`ToFixMessage`] lets the client and session send any typed message struct
+//! without the caller having to track comp IDs or sequence numbers.
+
+use crate::error::Result as DeribitFixResult;
+use crate::message::security_status::SecurityStatus;
+use crate::message::{
+    BusinessMessageReject, ExecutionReport, Heartbeat, MMProtectionLimits,
+    MMProtectionLimitsResult, MMProtectionReset, MarketDataIncrementalRefresh, MarketDataRequest,
+    MarketDataRequestReject, MarketDataSnapshotFullRefresh, MassQuote, MassQuoteAcknowledgement,
+    NewOrderSingle, OrderCancelReject, OrderCancelReplaceRequest, OrderCancelRequest,
+    OrderMassCancelReport, OrderMassCancelRequest, OrderMassStatusRequest, QuoteCancel,
+    QuoteRequest, QuoteRequestReject, QuoteStatusReport, Reject, RequestForPositions,
+    ResendRequest, RfqRequest, SecurityDefinition, SecurityDefinitionRequest, SecurityList,
+    SecurityListRequest, SecurityStatusRequest, SequenceReset, TestRequest, TradeCaptureReport,
+    TradeCaptureReportRequest, TradeCaptureReportRequestAck, UserRequest, UserResponse,
+};
+use crate::model::message::FixMessage;
+
+/// Conversion of a typed message into a FIX wire message
+///
+/// The header fields that depend on session state (SenderCompID, TargetCompID
+/// and MsgSeqNum) are supplied by the caller, normally the session.
+/// SendingTime, BodyLength and CheckSum are filled in by the message builder.
+pub trait ToFixMessage {
+    /// Convert to a structured FIX message
+    fn to_fix_message(
+        &self,
+        sender_comp_id: &str,
+        target_comp_id: &str,
+        msg_seq_num: u32,
+    ) -> DeribitFixResult<FixMessage>;
+
+    /// Convert to the raw FIX wire string, mainly for display and logging
+    fn to_fix_string(
+        &self,
+        sender_comp_id: &str,
+        target_comp_id: &str,
+        msg_seq_num: u32,
+    ) -> DeribitFixResult<String> {
+        self.to_fix_message(sender_comp_id, target_comp_id, msg_seq_num)
+            .map(|message| message.to_string())
+    }
+}
+
+impl<T: ToFixMessage + ?Sized> ToFixMessage for &T {
+    fn to_fix_message(
+        &self,
+        sender_comp_id: &str,
+        target_comp_id: &str,
+        msg_seq_num: u32,
+    ) -> DeribitFixResult<FixMessage> {
+        (**self).to_fix_message(sender_comp_id, target_comp_id, msg_seq_num)
+    }
+}
+
+impl_to_fix_message!(
+    Heartbeat,
+    TestRequest,
+    ResendRequest,
+    SequenceReset,
+    Reject,
+    BusinessMessageReject,
+    NewOrderSingle,
+    OrderCancelRequest,
+    OrderCancelReplaceRequest,
+    OrderCancelReject,
+    OrderMassCancelRequest,
+    OrderMassCancelReport,
+    OrderMassStatusRequest,
+    ExecutionReport,
+    MarketDataRequest,
+    MarketDataRequestReject,
+    MarketDataSnapshotFullRefresh,
+    MarketDataIncrementalRefresh,
+    QuoteRequest,
+    QuoteRequestReject,
+    QuoteCancel,
+    QuoteStatusReport,
+    MassQuote,
+    MassQuoteAcknowledgement,
+    RfqRequest,
+    MMProtectionLimits,
+    MMProtectionLimitsResult,
+    MMProtectionReset,
+    TradeCaptureReportRequest,
+    TradeCaptureReportRequestAck,
+    TradeCaptureReport,
+    RequestForPositions,
+    SecurityListRequest,
+    SecurityList,
+    SecurityDefinitionRequest,
+    SecurityDefinition,
+    SecurityStatusRequest,
+    SecurityStatus,
+    UserRequest,
+    UserResponse,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<M: ToFixMessage>(message: M) -> FixMessage {
+        message.to_fix_message("CLIENT", "DERIBIT", 7).unwrap()
+    }
+
+    #[test]
+    fn test_to_fix_message_from_fix_message_struct() {
+        let parsed = encode(TestRequest::new("PING".to_string()));
+        assert_eq!(parsed.get_field(35).unwrap(), "1");
+        assert_eq!(parsed.get_field(49).unwrap(), "CLIENT");
+        assert_eq!(parsed.get_field(56).unwrap(), "DERIBIT");
+        assert_eq!(parsed.get_field(34).unwrap(), "7");
+        assert_eq!(parsed.get_field(112).unwrap(), "PING");
+        assert!(parsed.has_field(52));
+    }
+
+    #[test]
+    fn test_to_fix_message_from_string_struct() {
+        let request = MarketDataRequest::snapshot(
+            "MDR1".to_string(),
+            vec!["BTC-PERPETUAL".to_string()],
+            vec![crate::message::MdEntryType::Bid],
+        );
+        let parsed = encode(&request);
+        assert_eq!(parsed.get_field(35).unwrap(), "V");
+        assert_eq!(parsed.get_field(34).unwrap(), "7");
+        assert_eq!(parsed.get_field(262).unwrap(), "MDR1");
+    }
+
+    #[test]
+    fn test_to_fix_string_round_trips() {
+        let request = TestRequest::new("PING".to_string());
+        let raw = ToFixMessage::to_fix_string(&request, "CLIENT", "DERIBIT", 3).unwrap();
+        assert!(raw.starts_with("8=FIX.4.4\x01"));
+        assert!(raw.contains("112=PING\x01"));
+
+        let parsed = FixMessage::parse(&raw).unwrap();
+        assert_eq!(parsed.get_field(34).unwrap(), "3");
+    }
+}
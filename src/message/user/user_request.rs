@@ -8,6 +8,8 @@
 
 use crate::error::Result as DeribitFixResult;
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use base64::{Engine as _, engine::general_purpose};
 use chrono::Utc;
@@ -53,6 +55,7 @@ impl TryFrom<i32> for UserRequestType {
 
 /// User status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum UserStatus {
     /// Logged in
     LoggedIn,
@@ -66,6 +69,8 @@ pub enum UserStatus {
     PasswordChanged,
     /// Other
     Other,
+    /// Value this version does not know, kept as received
+    Unknown(i32),
 }
 
 impl From<UserStatus> for i32 {
@@ -77,10 +82,12 @@ impl From<UserStatus> for i32 {
             UserStatus::PasswordIncorrect => 4,
             UserStatus::PasswordChanged => 5,
             UserStatus::Other => 99,
+            UserStatus::Unknown(value) => value,
         }
     }
 }
 
+/// Never fails: values this version does not know become [`UserStatus::Unknown`]
 impl TryFrom<i32> for UserStatus {
     type Error = String;
 
@@ -92,7 +99,7 @@ impl TryFrom<i32> for UserStatus {
             4 => Ok(UserStatus::PasswordIncorrect),
             5 => Ok(UserStatus::PasswordChanged),
             99 => Ok(UserStatus::Other),
-            _ => Err(format!("Invalid UserStatus: {}", value)),
+            _ => Ok(UserStatus::Unknown(value)),
         }
     }
 }
@@ -120,6 +127,8 @@ pub struct UserRequest {
     pub user_status_text: Option<String>,
     /// Custom label
     pub deribit_label: Option<String>,
+    /// Currency (15) of the account summary returned for a status request
+    pub currency: Option<String>,
 }
 
 impl UserRequest {
@@ -140,6 +149,7 @@ impl UserRequest {
             user_status: None,
             user_status_text: None,
             deribit_label: None,
+            currency: None,
         }
     }
 
@@ -218,13 +228,19 @@ impl UserRequest {
         self
     }
 
+    /// Set the currency of the account summary to return
+    pub fn with_currency(mut self, currency: String) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::UserRequest)
             .sender_comp_id(sender_comp_id.to_string())
@@ -234,42 +250,49 @@ impl UserRequest {
 
         // Required fields
         builder = builder
-            .field(923, self.user_request_id.clone()) // UserRequestID
-            .field(924, i32::from(self.user_request_type).to_string()) // UserRequestType
-            .field(553, self.username.clone()); // Username
+            .field(tags::USER_REQUEST_ID, self.user_request_id.clone())
+            .field(
+                tags::USER_REQUEST_TYPE,
+                i32::from(self.user_request_type).to_string(),
+            )
+            .field(tags::USERNAME, self.username.clone());
 
         // Optional fields
         if let Some(password) = &self.password {
-            builder = builder.field(554, password.clone());
+            builder = builder.field(tags::PASSWORD, password.clone());
         }
 
         if let Some(new_password) = &self.new_password {
-            builder = builder.field(925, new_password.clone());
+            builder = builder.field(tags::NEW_PASSWORD, new_password.clone());
         }
 
         if let Some(raw_data_length) = &self.raw_data_length {
-            builder = builder.field(95, raw_data_length.to_string());
+            builder = builder.field(tags::RAW_DATA_LENGTH, raw_data_length.to_string());
         }
 
         if let Some(raw_data) = &self.raw_data {
             // Convert raw data to base64 for FIX transmission
             let encoded_data = general_purpose::STANDARD.encode(raw_data);
-            builder = builder.field(96, encoded_data);
+            builder = builder.field(tags::RAW_DATA, encoded_data);
         }
 
         if let Some(user_status) = &self.user_status {
-            builder = builder.field(926, i32::from(*user_status).to_string());
+            builder = builder.field(tags::USER_STATUS, i32::from(*user_status).to_string());
         }
 
         if let Some(user_status_text) = &self.user_status_text {
-            builder = builder.field(927, user_status_text.clone());
+            builder = builder.field(tags::USER_STATUS_TEXT, user_status_text.clone());
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
+        }
+
+        if let Some(currency) = &self.currency {
+            builder = builder.field(tags::CURRENCY, currency.clone());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -279,6 +302,7 @@ impl_json_debug_pretty!(UserRequest);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_user_request_creation() {
@@ -383,7 +407,7 @@ mod tests {
         )
         .with_label("test-label".to_string());
 
-        let fix_message = request.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=BE")); // MsgType
@@ -421,6 +445,16 @@ mod tests {
         assert!(UserRequestType::try_from(99).is_err());
     }
 
+    #[test]
+    fn test_user_request_with_currency() {
+        let request = UserRequest::status_request("UR1".to_string(), "testuser".to_string())
+            .with_currency("BTC".to_string());
+        let fix_message = request.to_fix_string("SENDER", "TARGET", 1).unwrap();
+
+        assert!(fix_message.contains("\x01924=4\x01"));
+        assert!(fix_message.contains("\x0115=BTC\x01"));
+    }
+
     #[test]
     fn test_user_status_conversions() {
         assert_eq!(i32::from(UserStatus::LoggedIn), 1);
@@ -446,6 +480,6 @@ mod tests {
         );
         assert_eq!(UserStatus::try_from(99).unwrap(), UserStatus::Other);
 
-        assert!(UserStatus::try_from(50).is_err());
+        assert_eq!(UserStatus::try_from(50).unwrap(), UserStatus::Unknown(50));
     }
 }
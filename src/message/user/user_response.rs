@@ -6,8 +6,10 @@
 
 //! User Response FIX Message Implementation
 
-use crate::error::Result as DeribitFixResult;
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
 use crate::message::builder::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
 use crate::model::types::MsgType;
 use base64::{Engine as _, engine::general_purpose};
 use chrono::Utc;
@@ -190,13 +192,64 @@ impl UserResponse {
         self
     }
 
+    /// Parse a User Response (BF) message
+    ///
+    /// RawData (96) is decoded from base64 when possible and kept as sent
+    /// otherwise.
+    pub fn from_fix_message(message: &FixMessage) -> DeribitFixResult<Self> {
+        let get_f64 = |tag| message.get_field(tag).and_then(|s| s.parse::<f64>().ok());
+        let required = |tag: u32, name: &str| {
+            message
+                .get_field(tag)
+                .ok_or_else(|| DeribitFixError::MessageParsing(format!("Missing {name} ({tag})")))
+        };
+
+        let user_request_id = required(tags::USER_REQUEST_ID, "UserRequestID")?.clone();
+        let user_status = required(tags::USER_STATUS, "UserStatus")?
+            .parse::<i32>()
+            .ok()
+            .and_then(|status| UserStatus::try_from(status).ok())
+            .ok_or_else(|| {
+                DeribitFixError::MessageParsing(format!(
+                    "Invalid UserStatus: {:?}",
+                    message.get_field(tags::USER_STATUS)
+                ))
+            })?;
+        let raw_data = message.get_field(tags::RAW_DATA).map(|data| {
+            general_purpose::STANDARD
+                .decode(data)
+                .unwrap_or_else(|_| data.as_bytes().to_vec())
+        });
+
+        Ok(Self {
+            user_request_id,
+            username: message
+                .get_field(tags::USERNAME)
+                .cloned()
+                .unwrap_or_default(),
+            user_status,
+            user_status_text: message.get_field(tags::USER_STATUS_TEXT).cloned(),
+            raw_data_length: raw_data.as_ref().map(|data| data.len() as i32),
+            raw_data,
+            deribit_label: message.get_field(tags::DERIBIT_LABEL).cloned(),
+            user_equity: get_f64(tags::USER_EQUITY),
+            user_balance: get_f64(tags::USER_BALANCE),
+            user_initial_margin: get_f64(tags::USER_INITIAL_MARGIN),
+            user_maintenance_margin: get_f64(tags::USER_MAINTENANCE_MARGIN),
+            unrealized_pl: get_f64(tags::UNREALIZED_PL),
+            realized_pl: get_f64(tags::REALIZED_PL),
+            total_pl: get_f64(tags::TOTAL_PL),
+            margin_balance: get_f64(tags::MARGIN_BALANCE),
+        })
+    }
+
     /// Convert to FIX message
     pub fn to_fix_message(
         &self,
         sender_comp_id: &str,
         target_comp_id: &str,
         msg_seq_num: u32,
-    ) -> DeribitFixResult<String> {
+    ) -> DeribitFixResult<FixMessage> {
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::UserResponse)
             .sender_comp_id(sender_comp_id.to_string())
@@ -206,63 +259,66 @@ impl UserResponse {
 
         // Required fields
         builder = builder
-            .field(923, self.user_request_id.clone()) // UserRequestID
-            .field(553, self.username.clone()) // Username
-            .field(926, i32::from(self.user_status).to_string()); // UserStatus
+            .field(tags::USER_REQUEST_ID, self.user_request_id.clone())
+            .field(tags::USERNAME, self.username.clone())
+            .field(tags::USER_STATUS, i32::from(self.user_status).to_string());
 
         // Optional fields
         if let Some(user_status_text) = &self.user_status_text {
-            builder = builder.field(927, user_status_text.clone());
+            builder = builder.field(tags::USER_STATUS_TEXT, user_status_text.clone());
         }
 
         if let Some(raw_data_length) = &self.raw_data_length {
-            builder = builder.field(95, raw_data_length.to_string());
+            builder = builder.field(tags::RAW_DATA_LENGTH, raw_data_length.to_string());
         }
 
         if let Some(raw_data) = &self.raw_data {
             // Convert raw data to base64 for FIX transmission
             let encoded_data = general_purpose::STANDARD.encode(raw_data);
-            builder = builder.field(96, encoded_data);
+            builder = builder.field(tags::RAW_DATA, encoded_data);
         }
 
         if let Some(deribit_label) = &self.deribit_label {
-            builder = builder.field(100010, deribit_label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, deribit_label.clone());
         }
 
         // Account info fields (Deribit custom tags)
         if let Some(equity) = self.user_equity {
-            builder = builder.field(100001, equity.to_string());
+            builder = builder.field(tags::USER_EQUITY, equity.to_string());
         }
 
         if let Some(balance) = self.user_balance {
-            builder = builder.field(100002, balance.to_string());
+            builder = builder.field(tags::USER_BALANCE, balance.to_string());
         }
 
         if let Some(initial_margin) = self.user_initial_margin {
-            builder = builder.field(100003, initial_margin.to_string());
+            builder = builder.field(tags::USER_INITIAL_MARGIN, initial_margin.to_string());
         }
 
         if let Some(maintenance_margin) = self.user_maintenance_margin {
-            builder = builder.field(100004, maintenance_margin.to_string());
+            builder = builder.field(
+                tags::USER_MAINTENANCE_MARGIN,
+                maintenance_margin.to_string(),
+            );
         }
 
         if let Some(unrealized) = self.unrealized_pl {
-            builder = builder.field(100005, unrealized.to_string());
+            builder = builder.field(tags::UNREALIZED_PL, unrealized.to_string());
         }
 
         if let Some(realized) = self.realized_pl {
-            builder = builder.field(100006, realized.to_string());
+            builder = builder.field(tags::REALIZED_PL, realized.to_string());
         }
 
         if let Some(total) = self.total_pl {
-            builder = builder.field(100011, total.to_string());
+            builder = builder.field(tags::TOTAL_PL, total.to_string());
         }
 
         if let Some(margin) = self.margin_balance {
-            builder = builder.field(100013, margin.to_string());
+            builder = builder.field(tags::MARGIN_BALANCE, margin.to_string());
         }
 
-        Ok(builder.build()?.to_string())
+        builder.build()
     }
 }
 
@@ -272,6 +328,7 @@ impl_json_debug_pretty!(UserResponse);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::ToFixMessage;
 
     #[test]
     fn test_user_response_creation() {
@@ -394,7 +451,7 @@ mod tests {
         let response = UserResponse::logged_in("UR123".to_string(), "testuser".to_string())
             .with_label("test-label".to_string());
 
-        let fix_message = response.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = response.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check that the message contains required fields
         assert!(fix_message.contains("35=BF")); // MsgType
@@ -413,7 +470,7 @@ mod tests {
             UserStatus::NotLoggedIn,
         );
 
-        let fix_message = response.to_fix_message("SENDER", "TARGET", 2).unwrap();
+        let fix_message = response.to_fix_string("SENDER", "TARGET", 2).unwrap();
 
         // Check required fields only
         assert!(fix_message.contains("35=BF")); // MsgType
@@ -438,7 +495,7 @@ mod tests {
         )
         .with_raw_data(raw_data.clone());
 
-        let fix_message = response.to_fix_message("SENDER", "TARGET", 3).unwrap();
+        let fix_message = response.to_fix_string("SENDER", "TARGET", 3).unwrap();
 
         // Check that raw data fields are present
         assert!(fix_message.contains("95=3")); // RawDataLength
@@ -483,7 +540,7 @@ mod tests {
         .with_user_initial_margin(300.0)
         .with_user_maintenance_margin(150.0);
 
-        let fix_message = response.to_fix_message("SENDER", "TARGET", 1).unwrap();
+        let fix_message = response.to_fix_string("SENDER", "TARGET", 1).unwrap();
 
         // Check account info tags are present
         assert!(fix_message.contains("100001=5000")); // DeribitUserEquity
@@ -491,4 +548,31 @@ mod tests {
         assert!(fix_message.contains("100003=300")); // DeribitUserInitialMargin
         assert!(fix_message.contains("100004=150")); // DeribitUserMaintenanceMargin
     }
+
+    #[test]
+    fn test_user_response_from_fix_message() {
+        let response = UserResponse::logged_in("UR555".to_string(), "user7".to_string())
+            .with_raw_data(vec![1, 2, 3])
+            .with_user_equity(10.5)
+            .with_user_balance(10.0)
+            .with_user_initial_margin(1.25)
+            .with_user_maintenance_margin(0.75)
+            .with_margin_balance(9.0);
+        let message = response.to_fix_message("DERIBIT", "CLIENT", 3).unwrap();
+
+        assert_eq!(UserResponse::from_fix_message(&message).unwrap(), response);
+
+        let mut unknown_status = message.clone();
+        unknown_status.set_field(tags::USER_STATUS, "7".to_string());
+        assert_eq!(
+            UserResponse::from_fix_message(&unknown_status)
+                .unwrap()
+                .user_status,
+            UserStatus::Unknown(7)
+        );
+
+        let mut invalid_status = message.clone();
+        invalid_status.set_field(tags::USER_STATUS, "logged_in".to_string());
+        assert!(UserResponse::from_fix_message(&invalid_status).is_err());
+    }
 }
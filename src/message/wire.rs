@@ -0,0 +1,127 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Alternative wire representations of FIX messages
+//!
+//! Available with the `wire-formats` feature. [`WireFormat`] exports a message
+//! as JSON keyed by tag name (`{"MsgType": "D", "Symbol": "BTC-PERPETUAL"}`) or
+//! as a tag=value map, so downstream systems can consume captured traffic
+//! without a FIX parser.
+//!
+//! A [`FixMessage`](crate::model::message::FixMessage) is exported as a
+//! whole. Typed message structs export their body only: session header and
+//! trailer fields (BeginString, BodyLength, MsgSeqNum, SenderCompID,
+//! SendingTime, TargetCompID, CheckSum) are dropped since they are assigned
+//! by the session when the message is sent.
+//! Repeating tags keep every value, in message order.
+
+use crate::error::Result as DeribitFixResult;
+use crate::message::ToFixMessage;
+use crate::message::printer::tag_name;
+use crate::model::message::FixMessage;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Tag number to values, in message order for repeating tags
+pub type TagValueMap = BTreeMap<u32, Vec<String>>;
+
+/// Session header and trailer tags omitted when exporting typed messages
+const SESSION_TAGS: [u32; 7] = [8, 9, 10, 34, 49, 52, 56];
+
+/// Export of a message into JSON or a tag=value map
+pub trait WireFormat {
+    /// Tag/value pairs to export, in message order
+    fn wire_fields(&self) -> DeribitFixResult<Vec<(u32, String)>>;
+
+    /// JSON object keyed by tag name, or by tag number for unknown tags
+    ///
+    /// Tags present more than once are exported as an array of values.
+    fn to_wire_json(&self) -> DeribitFixResult<Value> {
+        let mut object = Map::new();
+        for (tag, value) in self.wire_fields()? {
+            let key = tag_name(tag).map_or_else(|| tag.to_string(), str::to_string);
+            match object.get_mut(&key) {
+                Some(Value::Array(values)) => values.push(Value::String(value)),
+                Some(existing) => {
+                    let first = existing.take();
+                    *existing = Value::Array(vec![first, Value::String(value)]);
+                }
+                None => {
+                    object.insert(key, Value::String(value));
+                }
+            }
+        }
+        Ok(Value::Object(object))
+    }
+
+    /// Map of tag number to its values
+    fn to_tag_value_map(&self) -> DeribitFixResult<TagValueMap> {
+        let mut map = TagValueMap::new();
+        for (tag, value) in self.wire_fields()? {
+            map.entry(tag).or_default().push(value);
+        }
+        Ok(map)
+    }
+}
+
+impl WireFormat for FixMessage {
+    fn wire_fields(&self) -> DeribitFixResult<Vec<(u32, String)>> {
+        Ok(self.fields.clone())
+    }
+}
+
+impl<T: ToFixMessage + ?Sized> WireFormat for T {
+    fn wire_fields(&self) -> DeribitFixResult<Vec<(u32, String)>> {
+        let message = self.to_fix_message("", "", 0)?;
+        Ok(message
+            .fields
+            .into_iter()
+            .filter(|(tag, _)| !SESSION_TAGS.contains(tag))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{NewOrderSingle, OrderSide};
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_message_to_wire_json() {
+        let order = NewOrderSingle::limit(
+            "ORDER-1".to_string(),
+            OrderSide::Buy,
+            10.0,
+            50_000.0,
+            "BTC-PERPETUAL".to_string(),
+        );
+        let value = order.to_wire_json().unwrap();
+
+        assert_eq!(value["MsgType"], json!("D"));
+        assert_eq!(value["ClOrdID"], json!("ORDER-1"));
+        assert_eq!(value["Symbol"], json!("BTC-PERPETUAL"));
+        assert!(value.get("SenderCompID").is_none());
+        assert!(value.get("CheckSum").is_none());
+    }
+
+    #[test]
+    fn test_fix_message_keeps_header_and_repeating_tags() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=40\x0135=W\x0155=BTC-PERPETUAL\x01268=2\x01269=0\x01269=1\x0177777=x\x0110=000\x01",
+        )
+        .unwrap();
+
+        let value = message.to_wire_json().unwrap();
+        assert_eq!(value["BeginString"], json!("FIX.4.4"));
+        assert_eq!(value["MDEntryType"], json!(["0", "1"]));
+        assert_eq!(value["77777"], json!("x"));
+
+        let map = message.to_tag_value_map().unwrap();
+        assert_eq!(map[&35], vec!["W".to_string()]);
+        assert_eq!(map[&269], vec!["0".to_string(), "1".to_string()]);
+    }
+}
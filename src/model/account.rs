@@ -0,0 +1,96 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Account summary
+//!
+//! Deribit reports the balance, margins and P/L of an account in custom tags
+//! of the User Response (BF) to a status User Request (BE) carrying a
+//! Currency (15). [`AccountSummary`] is the typed view of those tags.
+
+use crate::message::UserResponse;
+use serde::{Deserialize, Serialize};
+
+/// Balance, margins and P/L of an account in one currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountSummary {
+    /// Currency of the summary
+    pub currency: String,
+    /// UserEquity (100001)
+    pub equity: Option<f64>,
+    /// UserBalance (100002)
+    pub balance: Option<f64>,
+    /// UserInitialMargin (100003)
+    pub initial_margin: Option<f64>,
+    /// UserMaintenanceMargin (100004)
+    pub maintenance_margin: Option<f64>,
+    /// UnrealizedPL (100005)
+    pub unrealized_pl: Option<f64>,
+    /// RealizedPL (100006)
+    pub realized_pl: Option<f64>,
+    /// TotalPL (100011)
+    pub total_pl: Option<f64>,
+    /// MarginBalance (100013), reported for cross-collateral accounts
+    pub margin_balance: Option<f64>,
+}
+
+impl AccountSummary {
+    /// Build the summary of `currency` from a User Response (BF)
+    pub fn from_user_response(currency: String, response: &UserResponse) -> Self {
+        Self {
+            currency,
+            equity: response.user_equity,
+            balance: response.user_balance,
+            initial_margin: response.user_initial_margin,
+            maintenance_margin: response.user_maintenance_margin,
+            unrealized_pl: response.unrealized_pl,
+            realized_pl: response.realized_pl,
+            total_pl: response.total_pl,
+            margin_balance: response.margin_balance,
+        }
+    }
+
+    /// Funds backing the margin: the margin balance, or the equity when the
+    /// account is not cross-collateral
+    pub fn collateral(&self) -> Option<f64> {
+        self.margin_balance.or(self.equity)
+    }
+
+    /// Funds left for new orders: the collateral less the initial margin
+    pub fn available_funds(&self) -> Option<f64> {
+        Some(self.collateral()? - self.initial_margin?)
+    }
+
+    /// Maintenance margin as a fraction of the collateral; the account is
+    /// liquidated as it approaches 1
+    pub fn maintenance_margin_ratio(&self) -> Option<f64> {
+        let collateral = self.collateral().filter(|collateral| *collateral > 0.0)?;
+        Some(self.maintenance_margin? / collateral)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_margin_figures() {
+        let response = UserResponse::logged_in("UR1".to_string(), "user".to_string())
+            .with_user_equity(10.0)
+            .with_user_initial_margin(4.0)
+            .with_user_maintenance_margin(2.0);
+        let mut summary = AccountSummary::from_user_response("BTC".to_string(), &response);
+
+        assert_eq!(summary.available_funds(), Some(6.0));
+        assert_eq!(summary.maintenance_margin_ratio(), Some(0.2));
+
+        summary.margin_balance = Some(8.0);
+        assert_eq!(summary.available_funds(), Some(4.0));
+        assert_eq!(summary.maintenance_margin_ratio(), Some(0.25));
+
+        summary.initial_margin = None;
+        assert_eq!(summary.available_funds(), None);
+    }
+}
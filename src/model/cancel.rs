@@ -0,0 +1,193 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Typed order cancel targets
+//!
+//! A [`CancelTarget`] selects the orders to cancel and maps to the FIX message
+//! Deribit expects: single orders are cancelled with Order Cancel Request (F),
+//! every order carrying a label, resting on one side of an instrument or
+//! open on the account with Order Mass Cancel Request (q). The session waits for the matching report and
+//! returns it as a [`CancelReport`].
+
+use crate::message::{ExecutionReport, OrderMassCancelReport, OrderStatus};
+use crate::model::request::{NewOrderRequest, OrderSide};
+use serde::{Deserialize, Serialize};
+
+/// Orders selected for cancellation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CancelTarget {
+    /// Single order by exchange OrderID (37), sent as OrigClOrdID (41)
+    ///
+    /// The session also accepts the ClOrdID (11) of an order it sent and
+    /// resolves it to the OrderID reported for the order.
+    OrderId(String),
+    /// Single order by ClOrdID (11)
+    ClOrdId {
+        /// ClOrdID (11) of the order
+        cl_ord_id: String,
+        /// Instrument symbol, required by Deribit when cancelling by ClOrdID
+        symbol: String,
+    },
+    /// Every order carrying this DeribitLabel (100010)
+    Label(String),
+    /// Every order of an instrument on one side
+    SymbolSide {
+        /// Instrument symbol
+        symbol: String,
+        /// Side (54) of the orders to cancel
+        side: OrderSide,
+    },
+    /// Every open order of the account
+    AllOrders,
+}
+
+impl CancelTarget {
+    /// Whether the target is cancelled with an Order Mass Cancel Request (q)
+    pub fn is_mass_cancel(&self) -> bool {
+        matches!(
+            self,
+            CancelTarget::Label(_) | CancelTarget::SymbolSide { .. } | CancelTarget::AllOrders
+        )
+    }
+
+    /// Whether an order identifier refers to the single order targeted
+    ///
+    /// Deribit reports its order identifier in OrderID (37) and may echo it in
+    /// OrigClOrdID (41); the user identifier is in ClOrdID (11).
+    pub fn matches_id(&self, id: &str) -> bool {
+        match self {
+            CancelTarget::OrderId(order_id) => order_id == id,
+            CancelTarget::ClOrdId { cl_ord_id, .. } => cl_ord_id == id,
+            CancelTarget::Label(_) | CancelTarget::SymbolSide { .. } | CancelTarget::AllOrders => {
+                false
+            }
+        }
+    }
+
+    /// Whether the target covers an order not sent yet
+    ///
+    /// An order waiting to be sent only has the ClOrdID (11) it was given,
+    /// so single order targets match it by that identifier.
+    pub fn matches_order(&self, order: &NewOrderRequest) -> bool {
+        match self {
+            CancelTarget::OrderId(id) => order.client_order_id.as_ref() == Some(id),
+            CancelTarget::ClOrdId { cl_ord_id, symbol } => {
+                order.client_order_id.as_ref() == Some(cl_ord_id)
+                    && &order.instrument_name == symbol
+            }
+            CancelTarget::Label(label) => order.label.as_ref() == Some(label),
+            CancelTarget::SymbolSide { symbol, side } => {
+                &order.instrument_name == symbol && order.side == *side
+            }
+            CancelTarget::AllOrders => true,
+        }
+    }
+
+    /// Whether an Execution Report (8) confirms the cancellation of the targeted order
+    pub fn is_confirmed_by(&self, report: &ExecutionReport) -> bool {
+        report.ord_status == OrderStatus::Cancelled
+            && [
+                Some(&report.order_id),
+                Some(&report.cl_ord_id),
+                report.orig_cl_ord_id.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|id| self.matches_id(id))
+    }
+}
+
+/// Report returned for a completed cancel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CancelReport {
+    /// Execution Report (8) of the cancelled order
+    Order(Box<ExecutionReport>),
+    /// Order Mass Cancel Report (r) listing the affected orders
+    Mass(OrderMassCancelReport),
+    /// The order was still waiting to be sent and was withdrawn instead of
+    /// being cancelled at the exchange
+    Withdrawn,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::OrderSide as FixOrderSide;
+
+    fn cancelled(order_id: &str, cl_ord_id: &str) -> ExecutionReport {
+        let mut report = ExecutionReport::new_order(
+            order_id.to_string(),
+            cl_ord_id.to_string(),
+            "EXEC-1".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            FixOrderSide::Buy,
+            10.0,
+            0.0,
+            Some(50_000.0),
+        );
+        report.ord_status = OrderStatus::Cancelled;
+        report
+    }
+
+    #[test]
+    fn test_order_id_target_matches_order_id() {
+        let target = CancelTarget::OrderId("ETH-123".to_string());
+        assert!(!target.is_mass_cancel());
+        assert!(target.is_confirmed_by(&cancelled("ETH-123", "MY-ORDER")));
+        assert!(!target.is_confirmed_by(&cancelled("ETH-456", "MY-ORDER")));
+
+        let mut open = cancelled("ETH-123", "MY-ORDER");
+        open.ord_status = OrderStatus::New;
+        assert!(!target.is_confirmed_by(&open));
+    }
+
+    #[test]
+    fn test_cl_ord_id_target_matches_client_id() {
+        let target = CancelTarget::ClOrdId {
+            cl_ord_id: "MY-ORDER".to_string(),
+            symbol: "BTC-PERPETUAL".to_string(),
+        };
+        assert!(target.is_confirmed_by(&cancelled("ETH-123", "MY-ORDER")));
+    }
+
+    #[test]
+    fn test_targets_match_unsent_orders() {
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_client_order_id("MY-ORDER".to_string())
+            .with_label("mm-btc".to_string());
+        assert!(CancelTarget::OrderId("MY-ORDER".to_string()).matches_order(&order));
+        assert!(
+            CancelTarget::ClOrdId {
+                cl_ord_id: "MY-ORDER".to_string(),
+                symbol: "BTC-PERPETUAL".to_string(),
+            }
+            .matches_order(&order)
+        );
+        assert!(
+            !CancelTarget::ClOrdId {
+                cl_ord_id: "MY-ORDER".to_string(),
+                symbol: "ETH-PERPETUAL".to_string(),
+            }
+            .matches_order(&order)
+        );
+        assert!(CancelTarget::Label("mm-btc".to_string()).matches_order(&order));
+        assert!(
+            !CancelTarget::SymbolSide {
+                symbol: "BTC-PERPETUAL".to_string(),
+                side: OrderSide::Sell,
+            }
+            .matches_order(&order)
+        );
+        assert!(CancelTarget::AllOrders.matches_order(&order));
+    }
+
+    #[test]
+    fn test_mass_targets_never_match_single_reports() {
+        let target = CancelTarget::Label("mm-btc".to_string());
+        assert!(target.is_mass_cancel());
+        assert!(!target.is_confirmed_by(&cancelled("mm-btc", "mm-btc")));
+    }
+}
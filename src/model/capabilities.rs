@@ -0,0 +1,208 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Server capabilities
+//!
+//! The test and production servers do not accept the same fields, and
+//! Deribit adds custom tags over time. [`ServerCapabilities`] records what the
+//! session learns about the server it is logged on to: the Logon (A)
+//! response and the DefaultApplVerID (1137) agreed in it, the tags its
+//! Rejects (3) refuse and, with
+//! [`capability_probe`](crate::config::DeribitFixConfig::capability_probe)
+//! enabled, whether it answers a Test Request (1) and the custom tags it
+//! sends. FIX has no exchange before the Logon, so the capabilities are
+//! learnt from the logon on.
+
+use crate::message::SessionRejectReason;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// First user-defined tag number; Deribit's custom tags are above it
+pub const CUSTOM_TAG_START: u32 = 5000;
+
+/// Tags of the standard header and trailer, left out of the Logon tags
+const HEADER_TAGS: [u32; 7] = [8, 9, 10, 34, 35, 49, 56];
+
+/// What the server was seen to accept and send
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ServerCapabilities {
+    /// Time the Logon (A) response was received
+    pub logged_on_at: Option<DateTime<Utc>>,
+    /// HeartBtInt (108) of the Logon response
+    pub heartbeat_interval: Option<u32>,
+    /// DefaultApplVerID (1137) of the Logon response, the application
+    /// version the server agreed to
+    pub default_appl_ver_id: Option<String>,
+    /// Body tags of the Logon response
+    pub logon_tags: BTreeSet<u32>,
+    /// Round trip of the probe Test Request (1), once it was answered
+    pub test_request_round_trip: Option<Duration>,
+    /// Custom tags, [`CUSTOM_TAG_START`] and above, seen in received messages
+    pub custom_tags: BTreeSet<u32>,
+    /// Tags a Reject (3) refused as invalid, undefined or not defined for
+    /// the message type
+    pub unsupported_tags: BTreeSet<u32>,
+}
+
+impl ServerCapabilities {
+    /// Create capabilities with nothing learnt yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the Logon (A) response, received at `now`
+    ///
+    /// The capabilities of a previous logon are forgotten.
+    pub fn record_logon(&mut self, message: &FixMessage, now: DateTime<Utc>) {
+        *self = Self {
+            logged_on_at: Some(now),
+            heartbeat_interval: message
+                .get_field(tags::HEART_BT_INT)
+                .and_then(|value| value.parse().ok()),
+            default_appl_ver_id: message.get_field(tags::DEFAULT_APPL_VER_ID).cloned(),
+            logon_tags: message
+                .fields
+                .iter()
+                .map(|(tag, _)| *tag)
+                .filter(|tag| !HEADER_TAGS.contains(tag) && *tag != tags::SENDING_TIME)
+                .collect(),
+            ..Self::default()
+        };
+        self.record_message(message);
+    }
+
+    /// Record the custom tags of a received message
+    pub fn record_message(&mut self, message: &FixMessage) {
+        self.custom_tags.extend(
+            message
+                .fields
+                .iter()
+                .map(|(tag, _)| *tag)
+                .filter(|tag| *tag >= CUSTOM_TAG_START),
+        );
+    }
+
+    /// Record the tag a Reject (3) refused, if it names one
+    pub fn record_reject(&mut self, message: &FixMessage) {
+        let refused = [
+            SessionRejectReason::InvalidTagNumber,
+            SessionRejectReason::TagNotDefinedForMessageType,
+            SessionRejectReason::UndefinedTag,
+        ]
+        .map(|reason| (reason as u32).to_string());
+        if message
+            .get_field(tags::SESSION_REJECT_REASON)
+            .is_some_and(|reason| refused.contains(reason))
+            && let Some(tag) = message
+                .get_field(tags::REF_TAG_ID)
+                .and_then(|tag| tag.parse().ok())
+        {
+            self.unsupported_tags.insert(tag);
+        }
+    }
+
+    /// Whether the server was seen to send `tag`, in the Logon response or,
+    /// for custom tags, in any message
+    pub fn sends_tag(&self, tag: u32) -> bool {
+        self.logon_tags.contains(&tag) || self.custom_tags.contains(&tag)
+    }
+
+    /// Whether the server refused `tag` in a Reject (3)
+    pub fn rejects_tag(&self, tag: u32) -> bool {
+        self.unsupported_tags.contains(&tag)
+    }
+
+    /// Whether the server answered the probe Test Request (1)
+    pub fn answers_test_requests(&self) -> bool {
+        self.test_request_round_trip.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageBuilder;
+    use crate::model::types::MsgType;
+
+    fn message(msg_type: MsgType, fields: &[(u32, &str)]) -> FixMessage {
+        fields
+            .iter()
+            .fold(
+                MessageBuilder::new()
+                    .msg_type(msg_type)
+                    .sender_comp_id("DERIBIT".to_string())
+                    .target_comp_id("CLIENT".to_string())
+                    .msg_seq_num(1),
+                |builder, (tag, value)| builder.field(*tag, value.to_string()),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_logon_response_is_recorded() {
+        let mut capabilities = ServerCapabilities::new();
+        capabilities.unsupported_tags.insert(9999);
+        let logon = message(
+            MsgType::Logon,
+            &[
+                (tags::HEART_BT_INT, "30"),
+                (tags::DEFAULT_APPL_VER_ID, "9"),
+                (tags::CANCEL_ON_DISCONNECT, "Y"),
+            ],
+        );
+        capabilities.record_logon(&logon, Utc::now());
+
+        assert_eq!(capabilities.heartbeat_interval, Some(30));
+        assert_eq!(capabilities.default_appl_ver_id.as_deref(), Some("9"));
+        assert_eq!(
+            capabilities.logon_tags,
+            BTreeSet::from([tags::HEART_BT_INT, tags::DEFAULT_APPL_VER_ID, 9001])
+        );
+        assert!(capabilities.sends_tag(tags::CANCEL_ON_DISCONNECT));
+        assert!(!capabilities.sends_tag(tags::SENDING_TIME));
+        // A new logon starts over
+        assert!(!capabilities.rejects_tag(9999));
+    }
+
+    #[test]
+    fn test_rejected_tags_are_unsupported() {
+        let mut capabilities = ServerCapabilities::new();
+        capabilities.record_reject(&message(
+            MsgType::Reject,
+            &[
+                (tags::REF_SEQ_NUM, "2"),
+                (tags::REF_TAG_ID, "1137"),
+                (tags::SESSION_REJECT_REASON, "0"),
+            ],
+        ));
+        // Other reasons do not say the tag is unknown
+        capabilities.record_reject(&message(
+            MsgType::Reject,
+            &[
+                (tags::REF_SEQ_NUM, "3"),
+                (tags::REF_TAG_ID, "44"),
+                (tags::SESSION_REJECT_REASON, "5"),
+            ],
+        ));
+        assert!(capabilities.rejects_tag(1137));
+        assert!(!capabilities.rejects_tag(44));
+
+        capabilities.record_message(&message(
+            MsgType::ExecutionReport,
+            &[(tags::SYMBOL, "BTC-PERPETUAL"), (tags::DERIBIT_LABEL, "mm")],
+        ));
+        assert_eq!(
+            capabilities.custom_tags,
+            BTreeSet::from([tags::DERIBIT_LABEL])
+        );
+    }
+}
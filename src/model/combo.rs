@@ -0,0 +1,425 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Combo (multi-leg) instruments and orders
+//!
+//! Deribit lists futures combos (FUTCO) and option strategies (OPTCO) as
+//! instruments of their own, whose legs are described by the NoLegs (555)
+//! group of Security List (y) and Security Definition (d) messages.
+//! [`ComboRegistry`] learns those definitions, so a [`ComboOrderRequest`] can
+//! name either the combo instrument or just its legs and still be sent as a
+//! single New Order Single (D) on the combo.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::{SecurityList, SecurityType};
+use crate::model::message::FixMessage;
+use crate::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use crate::model::tags;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Leg of a combo instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboLeg {
+    /// LegSymbol (600)
+    pub symbol: String,
+    /// LegSide (624) when buying the combo
+    pub side: OrderSide,
+    /// LegRatioQty (623), amount of the leg per unit of the combo
+    pub ratio: f64,
+}
+
+impl ComboLeg {
+    /// Create a leg
+    pub fn new(symbol: String, side: OrderSide, ratio: f64) -> Self {
+        Self {
+            symbol,
+            side,
+            ratio,
+        }
+    }
+
+    /// Buy `ratio` of `symbol` per unit of the combo
+    pub fn buy(symbol: String, ratio: f64) -> Self {
+        Self::new(symbol, OrderSide::Buy, ratio)
+    }
+
+    /// Sell `ratio` of `symbol` per unit of the combo
+    pub fn sell(symbol: String, ratio: f64) -> Self {
+        Self::new(symbol, OrderSide::Sell, ratio)
+    }
+
+    fn inverted(&self) -> Self {
+        let side = match self.side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        };
+        Self::new(self.symbol.clone(), side, self.ratio)
+    }
+}
+
+/// Combo instrument and its legs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboDefinition {
+    /// Symbol (55) of the combo instrument
+    pub symbol: String,
+    /// SecurityType (167), FUTCO or OPTCO
+    pub security_type: Option<SecurityType>,
+    /// Legs of the combo
+    pub legs: Vec<ComboLeg>,
+}
+
+impl ComboDefinition {
+    /// Read the combo instruments of a Security List (y) or Security Definition (d)
+    ///
+    /// Instruments without legs are skipped.
+    pub fn from_fix_message(message: &FixMessage) -> Result<Vec<ComboDefinition>> {
+        let mut combos: Vec<ComboDefinition> = Vec::new();
+        let mut current: Option<ComboDefinition> = None;
+
+        for (tag, value) in &message.fields {
+            match *tag {
+                tags::SYMBOL => {
+                    combos.extend(current.take().filter(|combo| !combo.legs.is_empty()));
+                    current = Some(ComboDefinition {
+                        symbol: value.clone(),
+                        security_type: None,
+                        legs: Vec::new(),
+                    });
+                }
+                tags::SECURITY_TYPE => {
+                    if let Some(combo) = current.as_mut() {
+                        combo.security_type = SecurityType::from_fix_str(value).ok();
+                    }
+                }
+                tags::LEG_SYMBOL => {
+                    if let Some(combo) = current.as_mut() {
+                        combo.legs.push(ComboLeg::buy(value.clone(), 1.0));
+                    }
+                }
+                tags::LEG_SIDE => {
+                    if let Some(leg) = current.as_mut().and_then(|c| c.legs.last_mut()) {
+                        leg.side = match value.as_str() {
+                            "1" => OrderSide::Buy,
+                            "2" => OrderSide::Sell,
+                            _ => {
+                                return Err(DeribitFixError::MessageParsing(format!(
+                                    "Invalid LegSide: {value}"
+                                )));
+                            }
+                        };
+                    }
+                }
+                tags::LEG_RATIO_QTY => {
+                    if let Some(leg) = current.as_mut().and_then(|c| c.legs.last_mut()) {
+                        leg.ratio = value.parse().map_err(|_| {
+                            DeribitFixError::MessageParsing(format!("Invalid LegRatioQty: {value}"))
+                        })?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        combos.extend(current.filter(|combo| !combo.legs.is_empty()));
+        Ok(combos)
+    }
+
+    /// Whether the combo consists of exactly `legs`, in any order
+    fn has_legs(&self, legs: &[ComboLeg]) -> bool {
+        self.legs.len() == legs.len()
+            && legs.iter().all(|leg| {
+                self.legs.iter().any(|own| {
+                    own.symbol == leg.symbol
+                        && own.side == leg.side
+                        && (own.ratio - leg.ratio).abs() < f64::EPSILON
+                })
+            })
+    }
+}
+
+/// Combo instruments learned from Security List and Security Definition messages
+#[derive(Debug, Clone, Default)]
+pub struct ComboRegistry {
+    combos: HashMap<String, ComboDefinition>,
+}
+
+impl ComboRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a combo instrument, replacing any previous definition
+    pub fn register(&mut self, combo: ComboDefinition) {
+        self.combos.insert(combo.symbol.clone(), combo);
+    }
+
+    /// Register the combo instruments of a Security List (y) or Security Definition (d)
+    ///
+    /// Combo metadata is advisory: an instrument whose legs cannot be read is
+    /// skipped with a warning and the others are still registered. Returns the
+    /// number of combos registered.
+    pub fn apply(&mut self, message: &FixMessage) -> usize {
+        let mut count = 0;
+        for instrument in SecurityList::split_instruments(message) {
+            match ComboDefinition::from_fix_message(&instrument) {
+                Ok(combos) => {
+                    count += combos.len();
+                    for combo in combos {
+                        self.register(combo);
+                    }
+                }
+                Err(e) => warn!(
+                    "Skipping combo {:?}: {}",
+                    instrument.get_field(tags::SYMBOL),
+                    e
+                ),
+            }
+        }
+        count
+    }
+
+    /// Definition of a combo instrument
+    pub fn definition(&self, symbol: &str) -> Option<&ComboDefinition> {
+        self.combos.get(symbol)
+    }
+
+    /// Number of known combo instruments
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    /// Whether no combo instrument is known
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Find the combo with the given legs
+    ///
+    /// Returns the combo and the side to trade it on so that each leg is
+    /// traded on its requested side: buying a combo whose legs are the
+    /// inverse of `legs` is done by selling it.
+    pub fn find_by_legs(&self, legs: &[ComboLeg]) -> Option<(&ComboDefinition, OrderSide)> {
+        let inverted: Vec<ComboLeg> = legs.iter().map(ComboLeg::inverted).collect();
+        self.combos.values().find_map(|combo| {
+            if combo.has_legs(legs) {
+                Some((combo, OrderSide::Buy))
+            } else if combo.has_legs(&inverted) {
+                Some((combo, OrderSide::Sell))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Build the New Order Single for a combo order
+    ///
+    /// Orders given by legs are placed on the matching combo instrument.
+    pub fn resolve(&self, order: ComboOrderRequest) -> Result<NewOrderRequest> {
+        let (symbol, side) = match (&order.symbol, order.legs.is_empty()) {
+            (Some(symbol), _) => (symbol.clone(), order.side),
+            (None, false) => {
+                let (combo, combo_side) = self.find_by_legs(&order.legs).ok_or_else(|| {
+                    DeribitFixError::Session(
+                        "No combo instrument matches the requested legs".to_string(),
+                    )
+                })?;
+                let side = match (combo_side, order.side) {
+                    (OrderSide::Buy, side) => side,
+                    (OrderSide::Sell, OrderSide::Buy) => OrderSide::Sell,
+                    (OrderSide::Sell, OrderSide::Sell) => OrderSide::Buy,
+                };
+                (combo.symbol.clone(), side)
+            }
+            (None, true) => {
+                return Err(DeribitFixError::MessageConstruction(
+                    "A combo order needs a combo symbol or legs".to_string(),
+                ));
+            }
+        };
+
+        let mut request = match (order.order_type, order.price) {
+            (OrderType::Limit, Some(price)) => match side {
+                OrderSide::Buy => NewOrderRequest::limit_buy(symbol, order.amount, price),
+                OrderSide::Sell => NewOrderRequest::limit_sell(symbol, order.amount, price),
+            },
+            (OrderType::Market, _) => match side {
+                OrderSide::Buy => NewOrderRequest::market_buy(symbol, order.amount),
+                OrderSide::Sell => NewOrderRequest::market_sell(symbol, order.amount),
+            },
+            (order_type, _) => {
+                return Err(DeribitFixError::MessageConstruction(format!(
+                    "Unsupported combo order type {order_type:?}"
+                )));
+            }
+        };
+        if let Some(time_in_force) = order.time_in_force {
+            request.time_in_force = time_in_force;
+        }
+        request.label = order.label;
+        request.client_order_id = order.client_order_id;
+        Ok(request)
+    }
+}
+
+/// Order on a combo instrument, given by its symbol or by its legs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboOrderRequest {
+    /// Combo instrument symbol, resolved from `legs` when unset
+    pub symbol: Option<String>,
+    /// Legs as they should be traded when buying
+    pub legs: Vec<ComboLeg>,
+    /// Side of the combo; selling trades every leg on the opposite side
+    pub side: OrderSide,
+    /// Order amount in combo units
+    pub amount: f64,
+    /// Limit or market order
+    pub order_type: OrderType,
+    /// Limit price of the combo
+    pub price: Option<f64>,
+    /// Time in force, the [`NewOrderRequest`] default when unset
+    pub time_in_force: Option<TimeInForce>,
+    /// Order label
+    pub label: Option<String>,
+    /// Client order ID
+    pub client_order_id: Option<String>,
+}
+
+impl ComboOrderRequest {
+    /// Market order on the combo instrument `symbol`
+    pub fn for_symbol(symbol: String, side: OrderSide, amount: f64) -> Self {
+        Self {
+            symbol: Some(symbol),
+            legs: Vec::new(),
+            side,
+            amount,
+            order_type: OrderType::Market,
+            price: None,
+            time_in_force: None,
+            label: None,
+            client_order_id: None,
+        }
+    }
+
+    /// Market order on the combo instrument made of `legs`
+    pub fn from_legs(legs: Vec<ComboLeg>, side: OrderSide, amount: f64) -> Self {
+        Self {
+            symbol: None,
+            legs,
+            side,
+            amount,
+            order_type: OrderType::Market,
+            price: None,
+            time_in_force: None,
+            label: None,
+            client_order_id: None,
+        }
+    }
+
+    /// Make the order a limit order at `price`
+    pub fn with_limit_price(mut self, price: f64) -> Self {
+        self.order_type = OrderType::Limit;
+        self.price = Some(price);
+        self
+    }
+
+    /// Set the time in force
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Set the order label
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Set the client order ID
+    pub fn with_client_order_id(mut self, client_order_id: String) -> Self {
+        self.client_order_id = Some(client_order_id);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = "BTC-FS-27DEC24_PERP";
+
+    fn registry() -> ComboRegistry {
+        let message = FixMessage::parse(&format!(
+            "8=FIX.4.4\x019=0\x0135=y\x01146=2\x0155=BTC-PERPETUAL\x01167=FUT\x0155={CALENDAR}\x01167=FUTCO\x01555=2\x01600=BTC-PERPETUAL\x01624=2\x01623=1\x01600=BTC-27DEC24\x01624=1\x01623=1\x0110=000\x01"
+        ))
+        .unwrap();
+        let mut registry = ComboRegistry::new();
+        assert_eq!(registry.apply(&message), 1);
+        registry
+    }
+
+    #[test]
+    fn test_combo_definitions_from_security_list() {
+        let registry = registry();
+        let combo = registry.definition(CALENDAR).unwrap();
+        assert_eq!(combo.security_type, Some(SecurityType::FutureCombo));
+        assert_eq!(
+            combo.legs,
+            vec![
+                ComboLeg::sell("BTC-PERPETUAL".to_string(), 1.0),
+                ComboLeg::buy("BTC-27DEC24".to_string(), 1.0),
+            ]
+        );
+        assert!(registry.definition("BTC-PERPETUAL").is_none());
+    }
+
+    #[test]
+    fn test_unreadable_combos_are_skipped() {
+        let message = FixMessage::parse(&format!(
+            "8=FIX.4.4\x019=0\x0135=y\x01146=2\x0155=BTC-FS-BAD\x01167=FUTCO\x01555=1\x01600=BTC-PERPETUAL\x01624=9\x01623=1\x0155={CALENDAR}\x01167=FUTCO\x01555=1\x01600=BTC-27DEC24\x01624=1\x01623=1\x0110=000\x01"
+        ))
+        .unwrap();
+        let mut registry = ComboRegistry::new();
+        assert_eq!(registry.apply(&message), 1);
+        assert!(registry.definition("BTC-FS-BAD").is_none());
+        assert!(registry.definition(CALENDAR).is_some());
+    }
+
+    #[test]
+    fn test_combo_order_resolved_from_legs() {
+        let registry = registry();
+        let legs = vec![
+            ComboLeg::buy("BTC-27DEC24".to_string(), 1.0),
+            ComboLeg::sell("BTC-PERPETUAL".to_string(), 1.0),
+        ];
+
+        let order = ComboOrderRequest::from_legs(legs.clone(), OrderSide::Buy, 10.0)
+            .with_limit_price(25.0)
+            .with_label("calendar".to_string());
+        let request = registry.resolve(order).unwrap();
+        assert_eq!(request.instrument_name, CALENDAR);
+        assert_eq!(request.side, OrderSide::Buy);
+        assert_eq!(request.price, Some(25.0));
+        assert_eq!(request.label.as_deref(), Some("calendar"));
+
+        // The inverse legs sell the combo
+        let inverse = legs.iter().map(ComboLeg::inverted).collect();
+        let request = registry
+            .resolve(ComboOrderRequest::from_legs(inverse, OrderSide::Buy, 10.0))
+            .unwrap();
+        assert_eq!(request.instrument_name, CALENDAR);
+        assert_eq!(request.side, OrderSide::Sell);
+        assert_eq!(request.order_type, OrderType::Market);
+
+        let unknown = vec![ComboLeg::buy("ETH-PERPETUAL".to_string(), 1.0)];
+        assert!(
+            registry
+                .resolve(ComboOrderRequest::from_legs(unknown, OrderSide::Buy, 1.0))
+                .is_err()
+        );
+    }
+}
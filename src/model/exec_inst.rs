@@ -0,0 +1,270 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Execution instructions
+//!
+//! Deribit reads two values of ExecInst (18) on New Order Single (D) and
+//! Order Cancel/Replace Request (G): `6` (participate don't initiate) for
+//! post-only orders and `E` (do not increase) for reduce-only orders. Hidden
+//! and iceberg orders are set with DisplayQty (1138), a displayed quantity
+//! of 0 hiding the order completely.
+//!
+//! Instructions that cannot be combined are rejected locally before the
+//! order is sent: a post-only order must rest on the book, so it cannot be a
+//! market order or be immediate-or-cancel or fill-or-kill, and only limit
+//! orders can hide part of their quantity.
+//!
+//! Self-trade prevention, what happens when an order would match another
+//! order of the same account, travels next to ExecInst (18) in
+//! SelfMatchPreventionInstruction (2964) and is echoed on the Execution
+//! Reports of the order.
+
+use crate::error::{DeribitFixError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// ExecInst (18) value for post-only orders, participate don't initiate
+const PARTICIPATE_DONT_INITIATE: char = '6';
+/// ExecInst (18) value for reduce-only orders, do not increase
+const DO_NOT_INCREASE: char = 'E';
+
+/// Execution instructions of an order, ExecInst (18)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExecInst {
+    /// Post-only: the order only adds liquidity and is never matched on entry
+    pub post_only: bool,
+    /// Reduce-only: the order can only reduce the position
+    pub reduce_only: bool,
+}
+
+impl ExecInst {
+    /// Post-only instruction
+    pub fn post_only() -> Self {
+        Self {
+            post_only: true,
+            reduce_only: false,
+        }
+    }
+
+    /// Reduce-only instruction
+    pub fn reduce_only() -> Self {
+        Self {
+            post_only: false,
+            reduce_only: true,
+        }
+    }
+
+    /// Set whether the order is post-only
+    pub fn with_post_only(mut self, post_only: bool) -> Self {
+        self.post_only = post_only;
+        self
+    }
+
+    /// Set whether the order is reduce-only
+    pub fn with_reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = reduce_only;
+        self
+    }
+
+    /// Whether no instruction is set
+    pub fn is_empty(&self) -> bool {
+        !self.post_only && !self.reduce_only
+    }
+
+    /// Value of ExecInst (18), `None` when no instruction is set
+    pub fn to_fix_value(&self) -> Option<String> {
+        (!self.is_empty()).then(|| self.to_string())
+    }
+}
+
+/// Space separated ExecInst (18) values
+impl fmt::Display for ExecInst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values: Vec<String> = [
+            (self.post_only, PARTICIPATE_DONT_INITIATE),
+            (self.reduce_only, DO_NOT_INCREASE),
+        ]
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, value)| value.to_string())
+        .collect();
+        write!(f, "{}", values.join(" "))
+    }
+}
+
+/// Parse ExecInst (18) values, separated by spaces or not
+impl FromStr for ExecInst {
+    type Err = DeribitFixError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        value
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .try_fold(Self::default(), |exec_inst, c| match c {
+                PARTICIPATE_DONT_INITIATE => Ok(exec_inst.with_post_only(true)),
+                DO_NOT_INCREASE => Ok(exec_inst.with_reduce_only(true)),
+                other => Err(DeribitFixError::MessageParsing(format!(
+                    "Unsupported ExecInst value: {other}"
+                ))),
+            })
+    }
+}
+
+/// Self-trade prevention of an order, SelfMatchPreventionInstruction (2964)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTradePrevention {
+    /// Cancel the resting order of the account
+    CancelMaker,
+    /// Cancel the incoming order
+    CancelTaker,
+    /// Cancel both orders
+    CancelBoth,
+}
+
+impl SelfTradePrevention {
+    /// Value of SelfMatchPreventionInstruction (2964)
+    pub fn to_fix_value(self) -> char {
+        match self {
+            SelfTradePrevention::CancelTaker => '1',
+            SelfTradePrevention::CancelMaker => '2',
+            SelfTradePrevention::CancelBoth => '3',
+        }
+    }
+
+    /// Parse a SelfMatchPreventionInstruction (2964) value
+    pub fn from_fix_value(value: &str) -> Result<Self> {
+        match value {
+            "1" => Ok(SelfTradePrevention::CancelTaker),
+            "2" => Ok(SelfTradePrevention::CancelMaker),
+            "3" => Ok(SelfTradePrevention::CancelBoth),
+            other => Err(DeribitFixError::MessageParsing(format!(
+                "Unsupported SelfMatchPreventionInstruction value: {other}"
+            ))),
+        }
+    }
+
+    /// Name used in the configuration
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SelfTradePrevention::CancelMaker => "cancel_maker",
+            SelfTradePrevention::CancelTaker => "cancel_taker",
+            SelfTradePrevention::CancelBoth => "cancel_both",
+        }
+    }
+}
+
+impl fmt::Display for SelfTradePrevention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse `cancel_maker`, `cancel_taker` or `cancel_both`, also accepting
+/// dashes and the FIX values
+impl FromStr for SelfTradePrevention {
+    type Err = DeribitFixError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "cancel_maker" => Ok(SelfTradePrevention::CancelMaker),
+            "cancel_taker" => Ok(SelfTradePrevention::CancelTaker),
+            "cancel_both" => Ok(SelfTradePrevention::CancelBoth),
+            other => Self::from_fix_value(other),
+        }
+    }
+}
+
+/// Check that the execution instructions and displayed quantity of an order
+/// can be combined
+///
+/// `market` is whether the order executes at market, on entry or when
+/// triggered, and `immediate` whether its time in force is immediate-or-cancel
+/// or fill-or-kill.
+pub(crate) fn check_order_instructions(
+    exec_inst: ExecInst,
+    market: bool,
+    immediate: bool,
+    display_qty: Option<f64>,
+    order_qty: Option<f64>,
+) -> Result<()> {
+    let conflict = |reason: &str| Err(DeribitFixError::MessageConstruction(reason.to_string()));
+
+    if exec_inst.post_only && market {
+        return conflict("A post-only order cannot be a market order");
+    }
+    if exec_inst.post_only && immediate {
+        return conflict("A post-only order cannot be immediate-or-cancel or fill-or-kill");
+    }
+    let Some(display_qty) = display_qty else {
+        return Ok(());
+    };
+    if display_qty.is_nan() || display_qty < 0.0 {
+        return conflict("Display quantity must not be negative");
+    }
+    if market {
+        return conflict("A market order cannot hide its quantity");
+    }
+    if order_qty.is_some_and(|order_qty| display_qty > order_qty) {
+        return conflict("Display quantity cannot exceed the order quantity");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_inst_values() {
+        let both = ExecInst::post_only().with_reduce_only(true);
+        assert_eq!(both.to_fix_value().as_deref(), Some("6 E"));
+        assert_eq!(ExecInst::reduce_only().to_fix_value().as_deref(), Some("E"));
+        assert_eq!(ExecInst::default().to_fix_value(), None);
+
+        assert_eq!("6E".parse::<ExecInst>().unwrap(), both);
+        assert_eq!("6 E".parse::<ExecInst>().unwrap(), both);
+        assert_eq!("6".parse::<ExecInst>().unwrap(), ExecInst::post_only());
+        assert!("6 X".parse::<ExecInst>().is_err());
+    }
+
+    #[test]
+    fn test_self_trade_prevention_values() {
+        for stp in [
+            SelfTradePrevention::CancelMaker,
+            SelfTradePrevention::CancelTaker,
+            SelfTradePrevention::CancelBoth,
+        ] {
+            let fix_value = stp.to_fix_value().to_string();
+            assert_eq!(
+                SelfTradePrevention::from_fix_value(&fix_value).unwrap(),
+                stp
+            );
+            assert_eq!(stp.to_string().parse::<SelfTradePrevention>().unwrap(), stp);
+        }
+        assert_eq!(SelfTradePrevention::CancelMaker.to_fix_value(), '2');
+        assert_eq!(
+            "Cancel-Both".parse::<SelfTradePrevention>().unwrap(),
+            SelfTradePrevention::CancelBoth
+        );
+        assert!("cancel_none".parse::<SelfTradePrevention>().is_err());
+    }
+
+    #[test]
+    fn test_conflicting_instructions() {
+        let post_only = ExecInst::post_only();
+        assert!(check_order_instructions(post_only, false, false, Some(0.0), Some(10.0)).is_ok());
+        assert!(check_order_instructions(ExecInst::reduce_only(), true, true, None, None).is_ok());
+
+        assert!(check_order_instructions(post_only, true, false, None, None).is_err());
+        assert!(check_order_instructions(post_only, false, true, None, None).is_err());
+        let none = ExecInst::default();
+        assert!(check_order_instructions(none, true, false, Some(1.0), Some(10.0)).is_err());
+        assert!(check_order_instructions(none, false, false, Some(11.0), Some(10.0)).is_err());
+        assert!(check_order_instructions(none, false, false, Some(-1.0), None).is_err());
+    }
+}
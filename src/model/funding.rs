@@ -0,0 +1,155 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Funding rate polling
+//!
+//! Deribit reports the CurrentFunding (100092) and Funding8h (100093) of a
+//! perpetual only in Market Data Snapshot/Full Refresh (W) messages, so a
+//! market data subscription yields them once. [`FundingTracker`] schedules a
+//! lightweight snapshot request, the best bid and offer only, for each
+//! tracked perpetual once per
+//! [`funding_poll_interval`](crate::config::DeribitFixConfig::funding_poll_interval).
+//! The rates the snapshots bring are recorded by the
+//! [`MarketStatsTracker`](crate::model::market_stats::MarketStatsTracker) as
+//! the [funding history](crate::model::market_stats::MarketStats::funding_history)
+//! of the instrument.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Last poll of a tracked perpetual
+#[derive(Debug, Clone)]
+struct Poll {
+    /// MDReqID (262) of the snapshot request
+    md_req_id: String,
+    /// Time the request was sent
+    sent: Instant,
+}
+
+/// Schedule of the funding rate polls of the tracked perpetuals
+#[derive(Debug, Clone)]
+pub struct FundingTracker {
+    interval: Duration,
+    /// Last poll of each tracked symbol, `None` until it is first polled
+    polls: HashMap<String, Option<Poll>>,
+}
+
+impl FundingTracker {
+    /// Create a tracker polling every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            polls: HashMap::new(),
+        }
+    }
+
+    /// Interval between the polls of a symbol
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Start polling `symbol`
+    ///
+    /// Returns whether the symbol was not tracked yet.
+    pub fn track(&mut self, symbol: &str) -> bool {
+        if self.polls.contains_key(symbol) {
+            return false;
+        }
+        self.polls.insert(symbol.to_string(), None);
+        true
+    }
+
+    /// Stop polling `symbol`
+    ///
+    /// Returns whether the symbol was tracked.
+    pub fn untrack(&mut self, symbol: &str) -> bool {
+        self.polls.remove(symbol).is_some()
+    }
+
+    /// Tracked symbols
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.polls.keys().map(String::as_str)
+    }
+
+    /// Symbols never polled or last polled at least an interval before `now`,
+    /// in alphabetical order
+    pub fn due(&self, now: Instant) -> Vec<String> {
+        let mut due: Vec<String> = self
+            .polls
+            .iter()
+            .filter(|(_, poll)| {
+                poll.as_ref()
+                    .is_none_or(|poll| now.saturating_duration_since(poll.sent) >= self.interval)
+            })
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        due.sort_unstable();
+        due
+    }
+
+    /// Record the snapshot request `md_req_id` polling `symbol` at `now`
+    pub fn polled(&mut self, symbol: &str, md_req_id: String, now: Instant) {
+        if let Some(poll) = self.polls.get_mut(symbol) {
+            *poll = Some(Poll {
+                md_req_id,
+                sent: now,
+            });
+        }
+    }
+
+    /// Whether `md_req_id` is the latest poll of a tracked symbol
+    ///
+    /// The snapshot answering a poll must not replace the order book of a
+    /// subscription to the same instrument.
+    pub fn is_poll(&self, md_req_id: &str) -> bool {
+        self.polls
+            .values()
+            .flatten()
+            .any(|poll| poll.md_req_id == md_req_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_symbols_are_due_once_per_interval() {
+        let start = Instant::now();
+        let mut tracker = FundingTracker::new(INTERVAL);
+        assert!(tracker.track("ETH-PERPETUAL"));
+        assert!(tracker.track("BTC-PERPETUAL"));
+        assert!(!tracker.track("BTC-PERPETUAL"));
+        assert_eq!(tracker.due(start), ["BTC-PERPETUAL", "ETH-PERPETUAL"]);
+
+        tracker.polled("BTC-PERPETUAL", "FND_1".to_string(), start);
+        assert_eq!(tracker.due(start), ["ETH-PERPETUAL"]);
+        assert!(tracker.is_poll("FND_1"));
+
+        let later = start + INTERVAL;
+        tracker.polled("ETH-PERPETUAL", "FND_2".to_string(), later);
+        assert_eq!(tracker.due(later), ["BTC-PERPETUAL"]);
+    }
+
+    #[test]
+    fn test_untracked_symbols_are_not_polled() {
+        let start = Instant::now();
+        let mut tracker = FundingTracker::new(INTERVAL);
+        tracker.polled("BTC-PERPETUAL", "FND_1".to_string(), start);
+        assert!(!tracker.is_poll("FND_1"));
+
+        tracker.track("BTC-PERPETUAL");
+        tracker.polled("BTC-PERPETUAL", "FND_2".to_string(), start);
+        assert!(tracker.untrack("BTC-PERPETUAL"));
+        assert!(!tracker.untrack("BTC-PERPETUAL"));
+        assert!(!tracker.is_poll("FND_2"));
+        assert!(tracker.due(start + INTERVAL).is_empty());
+        assert_eq!(tracker.symbols().count(), 0);
+    }
+}
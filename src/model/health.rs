@@ -0,0 +1,344 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Overall session health
+//!
+//! [`HealthMonitor`] folds the signals the session already measures into a
+//! single [`HealthState`]: whether the session is logged on, the
+//! [`ConnectionHealth`](crate::session::ConnectionHealth) of the Test Request
+//! round trips, how long the counterparty has been silent, the sequence gaps
+//! that needed a Resend Request (2) or Sequence Reset (4), and the rate of
+//! rejections. Each
+//! [`HealthSample`] is compared with the previous one, so gaps and rejects
+//! count over the interval between two samples rather than since logon.
+//!
+//! The state gets worse as soon as a sample crosses a threshold but only
+//! improves after [`HealthThresholds::recovery_samples`] consecutive better
+//! samples, so that a single quiet interval does not flap the state.
+
+use crate::connection::ConnectionStats;
+use crate::model::types::MsgType;
+use crate::session::ConnectionHealth;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Overall health of a session
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub enum HealthState {
+    /// Every signal is within its thresholds
+    #[default]
+    Healthy,
+    /// The session works but a signal crossed its degraded threshold
+    Degraded,
+    /// The session is not logged on or a signal crossed its unhealthy threshold
+    Unhealthy,
+}
+
+/// Thresholds turning health signals into a [`HealthState`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthThresholds {
+    /// Silence of the counterparty making the session degraded
+    pub degraded_silence: Duration,
+    /// Silence of the counterparty making the session unhealthy
+    pub unhealthy_silence: Duration,
+    /// Sequence gaps per sample making the session degraded
+    pub degraded_sequence_gaps: u64,
+    /// Sequence gaps per sample making the session unhealthy
+    pub unhealthy_sequence_gaps: u64,
+    /// Rejections per minute making the session degraded
+    pub degraded_reject_rate: f64,
+    /// Rejections per minute making the session unhealthy
+    pub unhealthy_reject_rate: f64,
+    /// Consecutive better samples needed before the state improves
+    pub recovery_samples: u32,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            degraded_silence: Duration::from_secs(45),
+            unhealthy_silence: Duration::from_secs(90),
+            degraded_sequence_gaps: 1,
+            unhealthy_sequence_gaps: 5,
+            degraded_reject_rate: 5.0,
+            unhealthy_reject_rate: 30.0,
+            recovery_samples: 2,
+        }
+    }
+}
+
+/// Health signals of a session at one point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthSample {
+    /// Time the sample was taken
+    pub at: Instant,
+    /// Whether the session is logged on
+    pub logged_on: bool,
+    /// Health of the Test Request round trips
+    pub connection_health: ConnectionHealth,
+    /// Time since the last message was received, if any was
+    pub silent_for: Option<Duration>,
+    /// Resend Requests (2) sent and Sequence Resets (4) received since the
+    /// connection was created
+    pub sequence_gaps: u64,
+    /// Rejects (3), Business Message Rejects (j), Order Cancel Rejects (9)
+    /// and rate-limit rejections received since the connection was created
+    pub rejects: u64,
+}
+
+impl HealthSample {
+    /// Sample the counters of `stats`
+    pub fn from_stats(
+        at: Instant,
+        now: DateTime<Utc>,
+        logged_on: bool,
+        connection_health: ConnectionHealth,
+        stats: &ConnectionStats,
+    ) -> Self {
+        let rejects = [
+            MsgType::Reject,
+            MsgType::BusinessMessageReject,
+            MsgType::OrderCancelReject,
+        ]
+        .into_iter()
+        .map(|msg_type| stats.received(msg_type))
+        .sum::<u64>();
+        Self {
+            at,
+            logged_on,
+            connection_health,
+            silent_for: stats
+                .last_inbound
+                .map(|last| (now - last).to_std().unwrap_or_default()),
+            sequence_gaps: stats.sent(MsgType::ResendRequest)
+                + stats.received(MsgType::SequenceReset),
+            rejects: rejects + stats.throttle.rate_limit_rejects,
+        }
+    }
+}
+
+/// Health state derived from a sample, with the signals that set it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthAssessment {
+    /// State the signals call for
+    pub state: HealthState,
+    /// Signals outside their healthy range, worst first
+    pub reasons: Vec<String>,
+    /// Rejections per minute since the previous sample
+    pub reject_rate: f64,
+    /// Sequence gaps since the previous sample
+    pub sequence_gaps: u64,
+}
+
+/// Change of the health state
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthTransition {
+    /// State before the change
+    pub from: HealthState,
+    /// State after the change
+    pub to: HealthState,
+    /// Assessment that caused the change
+    pub assessment: HealthAssessment,
+}
+
+/// Tracks the health state from successive samples
+#[derive(Debug, Clone)]
+pub struct HealthMonitor {
+    thresholds: HealthThresholds,
+    state: HealthState,
+    previous: Option<HealthSample>,
+    /// Consecutive samples calling for a better state
+    improving: u32,
+}
+
+impl HealthMonitor {
+    /// Create a healthy monitor
+    pub fn new(thresholds: HealthThresholds) -> Self {
+        Self {
+            thresholds,
+            state: HealthState::Healthy,
+            previous: None,
+            improving: 0,
+        }
+    }
+
+    /// Current health state
+    pub fn state(&self) -> HealthState {
+        self.state
+    }
+
+    /// Thresholds of the monitor
+    pub fn thresholds(&self) -> &HealthThresholds {
+        &self.thresholds
+    }
+
+    /// State called for by `sample`, compared with the previous sample
+    pub fn assess(&self, sample: &HealthSample) -> HealthAssessment {
+        let thresholds = &self.thresholds;
+        let (sequence_gaps, rejects, elapsed) = match &self.previous {
+            Some(previous) => (
+                sample.sequence_gaps.saturating_sub(previous.sequence_gaps),
+                sample.rejects.saturating_sub(previous.rejects),
+                sample.at.saturating_duration_since(previous.at),
+            ),
+            None => (0, 0, Duration::ZERO),
+        };
+        let reject_rate = if elapsed.is_zero() {
+            0.0
+        } else {
+            rejects as f64 * 60.0 / elapsed.as_secs_f64()
+        };
+
+        let mut signals = Vec::new();
+        if !sample.logged_on {
+            signals.push((HealthState::Unhealthy, "not logged on".to_string()));
+        }
+        if let Some(silent_for) = sample.silent_for {
+            let state = if silent_for >= thresholds.unhealthy_silence {
+                HealthState::Unhealthy
+            } else if silent_for >= thresholds.degraded_silence {
+                HealthState::Degraded
+            } else {
+                HealthState::Healthy
+            };
+            signals.push((state, format!("no message received for {silent_for:?}")));
+        }
+        if sample.connection_health == ConnectionHealth::Degraded {
+            signals.push((
+                HealthState::Degraded,
+                "Test Request round trip too slow".to_string(),
+            ));
+        }
+        let state = if sequence_gaps >= thresholds.unhealthy_sequence_gaps {
+            HealthState::Unhealthy
+        } else if sequence_gaps >= thresholds.degraded_sequence_gaps {
+            HealthState::Degraded
+        } else {
+            HealthState::Healthy
+        };
+        signals.push((state, format!("{sequence_gaps} sequence gaps")));
+        let state = if reject_rate >= thresholds.unhealthy_reject_rate {
+            HealthState::Unhealthy
+        } else if reject_rate >= thresholds.degraded_reject_rate {
+            HealthState::Degraded
+        } else {
+            HealthState::Healthy
+        };
+        signals.push((state, format!("{reject_rate:.1} rejections per minute")));
+
+        signals.retain(|(state, _)| *state != HealthState::Healthy);
+        signals.sort_by_key(|(state, _)| std::cmp::Reverse(*state));
+        HealthAssessment {
+            state: signals
+                .first()
+                .map_or(HealthState::Healthy, |(state, _)| *state),
+            reasons: signals.into_iter().map(|(_, reason)| reason).collect(),
+            reject_rate,
+            sequence_gaps,
+        }
+    }
+
+    /// Take a sample into account
+    ///
+    /// Returns the transition when the state changes.
+    pub fn observe(&mut self, sample: HealthSample) -> Option<HealthTransition> {
+        let assessment = self.assess(&sample);
+        self.previous = Some(sample);
+
+        let to = if assessment.state >= self.state {
+            self.improving = 0;
+            assessment.state
+        } else {
+            self.improving += 1;
+            if self.improving < self.thresholds.recovery_samples {
+                return None;
+            }
+            self.improving = 0;
+            assessment.state
+        };
+        if to == self.state {
+            return None;
+        }
+        let from = std::mem::replace(&mut self.state, to);
+        Some(HealthTransition {
+            from,
+            to,
+            assessment,
+        })
+    }
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new(HealthThresholds::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(at: Instant, sequence_gaps: u64, rejects: u64) -> HealthSample {
+        HealthSample {
+            at,
+            logged_on: true,
+            connection_health: ConnectionHealth::Healthy,
+            silent_for: Some(Duration::from_secs(1)),
+            sequence_gaps,
+            rejects,
+        }
+    }
+
+    #[test]
+    fn test_signals_set_the_worst_state() {
+        let monitor = HealthMonitor::default();
+        let start = Instant::now();
+        assert_eq!(
+            monitor.assess(&sample(start, 0, 0)).state,
+            HealthState::Healthy
+        );
+
+        let mut silent = sample(start, 0, 0);
+        silent.silent_for = Some(Duration::from_secs(60));
+        silent.connection_health = ConnectionHealth::Degraded;
+        let assessment = monitor.assess(&silent);
+        assert_eq!(assessment.state, HealthState::Degraded);
+        assert_eq!(assessment.reasons.len(), 2);
+
+        silent.logged_on = false;
+        let assessment = monitor.assess(&silent);
+        assert_eq!(assessment.state, HealthState::Unhealthy);
+        assert_eq!(assessment.reasons[0], "not logged on");
+    }
+
+    #[test]
+    fn test_counters_are_compared_with_the_previous_sample() {
+        let mut monitor = HealthMonitor::default();
+        let start = Instant::now();
+        assert_eq!(monitor.observe(sample(start, 3, 100)), None);
+
+        // 10 rejections in 10 seconds is 60 per minute
+        let transition = monitor
+            .observe(sample(start + Duration::from_secs(10), 3, 110))
+            .unwrap();
+        assert_eq!(transition.from, HealthState::Healthy);
+        assert_eq!(transition.to, HealthState::Unhealthy);
+        assert_eq!(transition.assessment.reject_rate, 60.0);
+
+        // Recovery needs two better samples, the first gap only degrades
+        let later = start + Duration::from_secs(20);
+        assert_eq!(monitor.observe(sample(later, 4, 110)), None);
+        let transition = monitor
+            .observe(sample(later + Duration::from_secs(10), 5, 110))
+            .unwrap();
+        assert_eq!(transition.to, HealthState::Degraded);
+        assert_eq!(monitor.state(), HealthState::Degraded);
+    }
+}
@@ -0,0 +1,329 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Index value and settlement price stream
+//!
+//! Index values (MDEntryType 3) and estimated delivery prices (MDEntryType
+//! 6) arrive in the same Market Data Snapshot (W) and Incremental Refresh
+//! (X) messages as book entries. [`IndexStreams`] picks them out of every
+//! market data message and delivers them as typed [`IndexUpdate`]s to the
+//! receivers of their instrument, separate from order book updates.
+//!
+//! Each receiver holds a bounded backlog: a receiver that falls behind loses
+//! the oldest updates and learns how many from
+//! [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged).
+
+use crate::message::{MdEntry, MdEntryType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+/// Index value of an instrument, MDEntryType (269) = 3
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexTick {
+    /// Index instrument
+    pub symbol: String,
+    /// Index value
+    pub price: f64,
+    /// MDEntryDate (272), or the time the message was received
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Estimated delivery price of an instrument, MDEntryType (269) = 6
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettlementEstimate {
+    /// Index instrument
+    pub symbol: String,
+    /// Estimated delivery price
+    pub price: f64,
+    /// MDEntryDate (272), or the time the message was received
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Index or settlement price update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IndexUpdate {
+    /// New index value
+    Index(IndexTick),
+    /// New estimated delivery price
+    Settlement(SettlementEstimate),
+}
+
+impl IndexUpdate {
+    /// Instrument of the update
+    pub fn symbol(&self) -> &str {
+        match self {
+            IndexUpdate::Index(tick) => &tick.symbol,
+            IndexUpdate::Settlement(estimate) => &estimate.symbol,
+        }
+    }
+
+    /// Price of the update
+    pub fn price(&self) -> f64 {
+        match self {
+            IndexUpdate::Index(tick) => tick.price,
+            IndexUpdate::Settlement(estimate) => estimate.price,
+        }
+    }
+
+    /// Index and settlement price entries of a market data message, in order
+    pub fn from_entries(
+        symbol: &str,
+        entries: &[MdEntry],
+        received_at: DateTime<Utc>,
+    ) -> Vec<IndexUpdate> {
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let price = entry.md_entry_px?;
+                let symbol = symbol.to_string();
+                let timestamp = entry.md_entry_date.unwrap_or(received_at);
+                match entry.md_entry_type {
+                    MdEntryType::IndexValue => Some(IndexUpdate::Index(IndexTick {
+                        symbol,
+                        price,
+                        timestamp,
+                    })),
+                    MdEntryType::SettlementPrice => {
+                        Some(IndexUpdate::Settlement(SettlementEstimate {
+                            symbol,
+                            price,
+                            timestamp,
+                        }))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Default number of updates a receiver may fall behind by
+pub const DEFAULT_INDEX_BACKLOG: usize = 10_000;
+
+/// Delivers index and settlement price updates to per-instrument channels
+#[derive(Debug)]
+pub struct IndexStreams {
+    channels: HashMap<String, Vec<broadcast::Sender<IndexUpdate>>>,
+    /// Instrument of each index subscription, by MDReqID (262)
+    requests: HashMap<String, String>,
+    /// Updates each receiver may fall behind by
+    backlog: usize,
+    /// Most updates a receiver fell behind by
+    high_water: usize,
+    /// Updates dropped because a receiver fell behind
+    dropped: u64,
+}
+
+impl Default for IndexStreams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IndexStreams {
+    /// Create streams without receivers, each receiver holding up to
+    /// [`DEFAULT_INDEX_BACKLOG`] updates
+    pub fn new() -> Self {
+        Self::with_backlog(DEFAULT_INDEX_BACKLOG)
+    }
+
+    /// Create streams without receivers, each receiver holding up to
+    /// `backlog` updates, rounded up to a power of two
+    pub fn with_backlog(backlog: usize) -> Self {
+        Self {
+            channels: HashMap::new(),
+            requests: HashMap::new(),
+            backlog: backlog.max(1),
+            high_water: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Receive the index and settlement price updates of `symbol`
+    ///
+    /// A dropped receiver is removed when the next update is published.
+    pub fn subscribe(&mut self, symbol: &str) -> broadcast::Receiver<IndexUpdate> {
+        let (sender, receiver) = broadcast::channel(self.backlog);
+        self.channels
+            .entry(symbol.to_string())
+            .or_default()
+            .push(sender);
+        receiver
+    }
+
+    /// Remember that `md_req_id` subscribed to the index of `symbol`
+    pub fn track(&mut self, md_req_id: String, symbol: String) {
+        self.requests.insert(md_req_id, symbol);
+    }
+
+    /// Instrument of the index subscription with MDReqID `md_req_id`
+    pub fn symbol_of(&self, md_req_id: &str) -> Option<&str> {
+        self.requests.get(md_req_id).map(String::as_str)
+    }
+
+    /// MDReqID (262) of the index subscription of `symbol`
+    pub fn request_for(&self, symbol: &str) -> Option<&str> {
+        self.requests
+            .iter()
+            .find(|(_, subscribed)| *subscribed == symbol)
+            .map(|(md_req_id, _)| md_req_id.as_str())
+    }
+
+    /// Forget the index subscription of `symbol` and close its receivers
+    ///
+    /// Returns the MDReqID (262) of the subscription.
+    pub fn remove(&mut self, symbol: &str) -> Option<String> {
+        self.channels.remove(symbol);
+        let md_req_id = self.request_for(symbol)?.to_string();
+        self.requests.remove(&md_req_id);
+        Some(md_req_id)
+    }
+
+    /// Instruments with at least one receiver
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.channels.keys().map(String::as_str)
+    }
+
+    /// Most updates a receiver fell behind by
+    pub fn high_water(&self) -> usize {
+        self.high_water
+    }
+
+    /// Updates dropped, oldest first, because a receiver fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Publish the index and settlement price entries of a market data message
+    ///
+    /// A receiver whose backlog is full loses its oldest update to make room.
+    /// Returns the number of updates delivered.
+    pub fn publish(
+        &mut self,
+        symbol: &str,
+        entries: &[MdEntry],
+        received_at: DateTime<Utc>,
+    ) -> usize {
+        let Some(senders) = self.channels.get_mut(symbol) else {
+            return 0;
+        };
+        let updates = IndexUpdate::from_entries(symbol, entries, received_at);
+        if updates.is_empty() {
+            return 0;
+        }
+        let (mut high_water, mut dropped) = (self.high_water, 0);
+        senders.retain(|sender| {
+            updates.iter().all(|update| {
+                let queued = sender.len();
+                if sender.send(update.clone()).is_err() {
+                    return false;
+                }
+                // A full backlog evicts its oldest update instead of growing
+                if sender.len() == queued {
+                    dropped += 1;
+                }
+                high_water = high_water.max(sender.len());
+                true
+            })
+        });
+        self.high_water = high_water;
+        self.dropped += dropped;
+        if senders.is_empty() {
+            self.channels.remove(symbol);
+            return 0;
+        }
+        updates.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(md_entry_type: MdEntryType, price: f64) -> MdEntry {
+        let mut entry = MdEntry::bid(price, 0.0);
+        entry.md_entry_type = md_entry_type;
+        entry.md_entry_size = None;
+        entry
+    }
+
+    #[test]
+    fn test_index_entries_are_split_from_book_entries() {
+        let received_at = Utc::now();
+        let entries = vec![
+            MdEntry::bid(49_990.0, 10.0),
+            entry(MdEntryType::IndexValue, 50_000.5),
+            MdEntry::offer(50_010.0, 5.0),
+            entry(MdEntryType::SettlementPrice, 50_001.0),
+        ];
+
+        let updates = IndexUpdate::from_entries("BTC-USD", &entries, received_at);
+        assert_eq!(
+            updates,
+            vec![
+                IndexUpdate::Index(IndexTick {
+                    symbol: "BTC-USD".to_string(),
+                    price: 50_000.5,
+                    timestamp: received_at,
+                }),
+                IndexUpdate::Settlement(SettlementEstimate {
+                    symbol: "BTC-USD".to_string(),
+                    price: 50_001.0,
+                    timestamp: received_at,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_updates_reach_the_receivers_of_their_instrument() {
+        let mut streams = IndexStreams::new();
+        let mut btc = streams.subscribe("BTC-USD");
+        let mut eth = streams.subscribe("ETH-USD");
+        streams.track("IDX_1".to_string(), "BTC-USD".to_string());
+
+        let entries = [entry(MdEntryType::IndexValue, 50_000.0)];
+        assert_eq!(streams.publish("BTC-USD", &entries, Utc::now()), 1);
+        assert_eq!(streams.publish("SOL-USD", &entries, Utc::now()), 0);
+        assert_eq!(btc.try_recv().unwrap().price(), 50_000.0);
+        assert!(eth.try_recv().is_err());
+
+        // Book entries alone publish nothing
+        let book = [MdEntry::bid(1.0, 1.0)];
+        assert_eq!(streams.publish("BTC-USD", &book, Utc::now()), 0);
+
+        assert_eq!(streams.symbol_of("IDX_1"), Some("BTC-USD"));
+        assert_eq!(streams.remove("BTC-USD").as_deref(), Some("IDX_1"));
+        assert_eq!(streams.symbol_of("IDX_1"), None);
+        assert!(btc.try_recv().is_err());
+
+        drop(eth);
+        assert_eq!(streams.publish("ETH-USD", &entries, Utc::now()), 0);
+        assert_eq!(streams.symbols().count(), 0);
+    }
+
+    #[test]
+    fn test_slow_receiver_loses_the_oldest_updates() {
+        let mut streams = IndexStreams::with_backlog(2);
+        let mut btc = streams.subscribe("BTC-USD");
+
+        for price in [1.0, 2.0, 3.0] {
+            let entries = [entry(MdEntryType::IndexValue, price)];
+            assert_eq!(streams.publish("BTC-USD", &entries, Utc::now()), 1);
+        }
+        assert_eq!(streams.high_water(), 2);
+        assert_eq!(streams.dropped(), 1);
+
+        assert!(matches!(
+            btc.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+        assert_eq!(btc.try_recv().unwrap().price(), 2.0);
+        assert_eq!(btc.try_recv().unwrap().price(), 3.0);
+    }
+}
@@ -0,0 +1,460 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Deribit instrument names
+//!
+//! Deribit names its instruments with dash separated segments, starting with
+//! the currency and, for linear contracts, the settlement currency joined by
+//! an underscore:
+//!
+//! ```text
+//! BTC-PERPETUAL            inverse perpetual
+//! BTC_USDC-PERPETUAL       USDC-settled linear perpetual
+//! ETH-28MAR25              future expiring on 28 March 2025
+//! BTC-28MAR25-60000-C      call option with a 60000 strike
+//! XRP_USDC-7MAR25-0d625-P  linear put option, `d` marking the decimal point
+//! BTC-FS-27DEC24_PERP      future spread combo
+//! BTC-CS-28MAR25-60000_70000  call spread option combo
+//! ```
+//!
+//! [`InstrumentName`] parses those names into their parts and formats them
+//! back to the canonical string, so `name.parse::<InstrumentName>()?.to_string()`
+//! returns `name` unchanged.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::PutOrCall;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Last segment of perpetual instrument names
+const PERPETUAL: &str = "PERPETUAL";
+
+/// Month abbreviations used in expiry dates
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Kind of instrument and the parts its name carries
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstrumentKind {
+    /// Perpetual future
+    Perpetual,
+    /// Dated future
+    Future {
+        /// Expiry date
+        expiry: NaiveDate,
+    },
+    /// Option
+    Option {
+        /// Expiry date
+        expiry: NaiveDate,
+        /// Strike price
+        strike: f64,
+        /// Call or put
+        option_type: PutOrCall,
+    },
+    /// Combo (multi-leg) instrument
+    Combo {
+        /// Strategy code, such as FS (future spread) or CS (call spread)
+        strategy: String,
+        /// Remaining dash separated segments describing the legs
+        legs: Vec<String>,
+    },
+}
+
+/// Parsed Deribit instrument name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct InstrumentName {
+    /// Base currency, such as BTC
+    pub currency: String,
+    /// Settlement currency, the base currency for inverse instruments and
+    /// USDC or USDT for linear ones
+    pub settlement: String,
+    /// Kind of instrument
+    pub kind: InstrumentKind,
+}
+
+impl InstrumentName {
+    /// Inverse perpetual of `currency`
+    pub fn perpetual(currency: &str) -> Self {
+        Self::inverse(currency, InstrumentKind::Perpetual)
+    }
+
+    /// Inverse future of `currency` expiring on `expiry`
+    pub fn future(currency: &str, expiry: NaiveDate) -> Self {
+        Self::inverse(currency, InstrumentKind::Future { expiry })
+    }
+
+    /// Inverse option of `currency`
+    pub fn option(currency: &str, expiry: NaiveDate, strike: f64, option_type: PutOrCall) -> Self {
+        Self::inverse(
+            currency,
+            InstrumentKind::Option {
+                expiry,
+                strike,
+                option_type,
+            },
+        )
+    }
+
+    /// Settle the instrument in `settlement`, making it linear
+    pub fn with_settlement(mut self, settlement: &str) -> Self {
+        self.settlement = settlement.to_string();
+        self
+    }
+
+    fn inverse(currency: &str, kind: InstrumentKind) -> Self {
+        Self {
+            currency: currency.to_string(),
+            settlement: currency.to_string(),
+            kind,
+        }
+    }
+
+    /// Whether the instrument is settled in another currency than its base
+    pub fn is_linear(&self) -> bool {
+        self.settlement != self.currency
+    }
+
+    /// Whether the instrument is a perpetual future
+    pub fn is_perpetual(&self) -> bool {
+        self.kind == InstrumentKind::Perpetual
+    }
+
+    /// Whether the instrument is an option
+    pub fn is_option(&self) -> bool {
+        matches!(self.kind, InstrumentKind::Option { .. })
+    }
+
+    /// Whether the instrument is a combo
+    pub fn is_combo(&self) -> bool {
+        matches!(self.kind, InstrumentKind::Combo { .. })
+    }
+
+    /// Expiry date of a future or option, or the first expiry named by a combo
+    pub fn expiry(&self) -> Option<NaiveDate> {
+        match &self.kind {
+            InstrumentKind::Perpetual => None,
+            InstrumentKind::Future { expiry } | InstrumentKind::Option { expiry, .. } => {
+                Some(*expiry)
+            }
+            InstrumentKind::Combo { legs, .. } => legs
+                .iter()
+                .flat_map(|leg| leg.split('_'))
+                .find_map(|part| parse_expiry(part).ok()),
+        }
+    }
+
+    /// Strike price of an option
+    pub fn strike(&self) -> Option<f64> {
+        match self.kind {
+            InstrumentKind::Option { strike, .. } => Some(strike),
+            _ => None,
+        }
+    }
+
+    /// Call or put of an option
+    pub fn option_type(&self) -> Option<PutOrCall> {
+        match self.kind {
+            InstrumentKind::Option { option_type, .. } => Some(option_type),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InstrumentName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.currency)?;
+        if self.is_linear() {
+            write!(f, "_{}", self.settlement)?;
+        }
+        match &self.kind {
+            InstrumentKind::Perpetual => write!(f, "-{PERPETUAL}"),
+            InstrumentKind::Future { expiry } => write!(f, "-{}", format_expiry(*expiry)),
+            InstrumentKind::Option {
+                expiry,
+                strike,
+                option_type,
+            } => {
+                let option_type = match option_type {
+                    PutOrCall::Call => "C".to_string(),
+                    PutOrCall::Put => "P".to_string(),
+                    PutOrCall::Unknown(value) => value.to_string(),
+                };
+                write!(
+                    f,
+                    "-{}-{}-{option_type}",
+                    format_expiry(*expiry),
+                    strike.to_string().replace('.', "d")
+                )
+            }
+            InstrumentKind::Combo { strategy, legs } => {
+                write!(f, "-{strategy}")?;
+                legs.iter().try_for_each(|leg| write!(f, "-{leg}"))
+            }
+        }
+    }
+}
+
+impl FromStr for InstrumentName {
+    type Err = DeribitFixError;
+
+    fn from_str(name: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            DeribitFixError::MessageParsing(format!("Invalid instrument name {name}: {reason}"))
+        };
+        let segments: Vec<&str> = name.split('-').collect();
+        let (currency, settlement) = match segments[0].split_once('_') {
+            Some((currency, settlement)) => (currency, settlement),
+            None => (segments[0], segments[0]),
+        };
+        if !is_currency(currency) || !is_currency(settlement) {
+            return Err(invalid("expected a currency"));
+        }
+
+        let kind = match segments[1..] {
+            [] => return Err(invalid("missing instrument kind")),
+            [PERPETUAL] => InstrumentKind::Perpetual,
+            [expiry] => InstrumentKind::Future {
+                expiry: parse_expiry(expiry).map_err(|reason| invalid(&reason))?,
+            },
+            [expiry, strike, option_type] if parse_expiry(expiry).is_ok() => {
+                InstrumentKind::Option {
+                    expiry: parse_expiry(expiry).map_err(|reason| invalid(&reason))?,
+                    strike: parse_strike(strike).map_err(|reason| invalid(&reason))?,
+                    option_type: match option_type {
+                        "C" => PutOrCall::Call,
+                        "P" => PutOrCall::Put,
+                        _ => return Err(invalid("option type must be C or P")),
+                    },
+                }
+            }
+            [strategy, ref legs @ ..] if is_strategy(strategy) && !legs.is_empty() => {
+                if legs.iter().any(|leg| leg.is_empty()) {
+                    return Err(invalid("empty combo leg"));
+                }
+                InstrumentKind::Combo {
+                    strategy: strategy.to_string(),
+                    legs: legs.iter().map(|leg| leg.to_string()).collect(),
+                }
+            }
+            _ => return Err(invalid("unrecognized format")),
+        };
+
+        Ok(Self {
+            currency: currency.to_string(),
+            settlement: settlement.to_string(),
+            kind,
+        })
+    }
+}
+
+impl TryFrom<String> for InstrumentName {
+    type Error = DeribitFixError;
+
+    fn try_from(name: String) -> Result<Self> {
+        name.parse()
+    }
+}
+
+impl From<InstrumentName> for String {
+    fn from(name: InstrumentName) -> Self {
+        name.to_string()
+    }
+}
+
+fn is_currency(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+fn is_strategy(value: &str) -> bool {
+    !value.is_empty() && value != PERPETUAL && value.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// Parse an expiry such as `28MAR25` or `7MAR25`
+fn parse_expiry(value: &str) -> std::result::Result<NaiveDate, String> {
+    let day_len = value.chars().take_while(char::is_ascii_digit).count();
+    let (day, rest) = value.split_at(day_len);
+    let invalid = || format!("invalid expiry date {value}");
+    if !(1..=2).contains(&day_len) || day.starts_with('0') || rest.len() != 5 {
+        return Err(invalid());
+    }
+    let (month, year) = rest.split_at(3);
+    let month = MONTHS
+        .iter()
+        .position(|m| *m == month)
+        .ok_or_else(invalid)?;
+    if !year.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let day: u32 = day.parse().map_err(|_| invalid())?;
+    NaiveDate::from_ymd_opt(2000 + year, month as u32 + 1, day).ok_or_else(invalid)
+}
+
+fn format_expiry(expiry: NaiveDate) -> String {
+    format!(
+        "{}{}{:02}",
+        expiry.day(),
+        MONTHS[expiry.month0() as usize],
+        expiry.year() % 100
+    )
+}
+
+/// Parse a strike such as `60000` or `0d625`
+fn parse_strike(value: &str) -> std::result::Result<f64, String> {
+    let invalid = || format!("invalid strike {value}");
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit() || c == 'd') {
+        return Err(invalid());
+    }
+    let strike: f64 = value.replace('d', ".").parse().map_err(|_| invalid())?;
+    if strike <= 0.0 {
+        return Err(invalid());
+    }
+    Ok(strike)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn parse(name: &str) -> InstrumentName {
+        let parsed: InstrumentName = name.parse().unwrap();
+        assert_eq!(parsed.to_string(), name);
+        parsed
+    }
+
+    #[test]
+    fn test_perpetuals() {
+        let btc = parse("BTC-PERPETUAL");
+        assert_eq!(btc, InstrumentName::perpetual("BTC"));
+        assert!(btc.is_perpetual() && !btc.is_linear());
+        assert_eq!(btc.expiry(), None);
+
+        let linear = parse("ETH_USDC-PERPETUAL");
+        assert_eq!(linear.currency, "ETH");
+        assert_eq!(linear.settlement, "USDC");
+        assert!(linear.is_perpetual() && linear.is_linear());
+        assert_eq!(
+            linear,
+            InstrumentName::perpetual("ETH").with_settlement("USDC")
+        );
+    }
+
+    #[test]
+    fn test_futures() {
+        let future = parse("ETH-28MAR25");
+        assert_eq!(future, InstrumentName::future("ETH", date(2025, 3, 28)));
+        assert_eq!(future.expiry(), Some(date(2025, 3, 28)));
+        assert_eq!(future.strike(), None);
+
+        // Days before the 10th have a single digit
+        assert_eq!(parse("BTC-7MAR25").expiry(), Some(date(2025, 3, 7)));
+        assert_eq!(
+            InstrumentName::future("BTC", date(2030, 1, 3)).to_string(),
+            "BTC-3JAN30"
+        );
+        assert!(parse("BTC_USDC-27JUN25").is_linear());
+    }
+
+    #[test]
+    fn test_options() {
+        let call = parse("BTC-28MAR25-60000-C");
+        assert_eq!(
+            call,
+            InstrumentName::option("BTC", date(2025, 3, 28), 60000.0, PutOrCall::Call)
+        );
+        assert!(call.is_option());
+        assert_eq!(call.strike(), Some(60000.0));
+        assert_eq!(call.option_type(), Some(PutOrCall::Call));
+
+        let put = parse("XRP_USDC-7MAR25-0d625-P");
+        assert_eq!(put.settlement, "USDC");
+        assert_eq!(put.strike(), Some(0.625));
+        assert_eq!(put.option_type(), Some(PutOrCall::Put));
+        assert_eq!(put.expiry(), Some(date(2025, 3, 7)));
+    }
+
+    #[test]
+    fn test_combos() {
+        let spread = parse("BTC-FS-27DEC24_PERP");
+        assert!(spread.is_combo());
+        assert_eq!(
+            spread.kind,
+            InstrumentKind::Combo {
+                strategy: "FS".to_string(),
+                legs: vec!["27DEC24_PERP".to_string()],
+            }
+        );
+        assert_eq!(spread.expiry(), Some(date(2024, 12, 27)));
+
+        let call_spread = parse("BTC-CS-28MAR25-60000_70000");
+        match &call_spread.kind {
+            InstrumentKind::Combo { strategy, legs } => {
+                assert_eq!(strategy, "CS");
+                assert_eq!(legs, &["28MAR25", "60000_70000"]);
+            }
+            other => panic!("Expected a combo, got {other:?}"),
+        }
+        assert_eq!(call_spread.expiry(), Some(date(2025, 3, 28)));
+        assert_eq!(call_spread.strike(), None);
+        assert!(parse("ETH_USDC-STRD-27JUN25-3000").is_linear());
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        for name in [
+            "",
+            "BTC",
+            "btc-PERPETUAL",
+            "-PERPETUAL",
+            "BTC_-PERPETUAL",
+            "BTC-PERPETUAL-C",
+            "BTC-28mar25",
+            "BTC-32MAR25",
+            "BTC-07MAR25",
+            "BTC-28MAR2025",
+            "BTC-30FEB25",
+            "BTC-28MAR25-60000-X",
+            "BTC-28MAR25-0-C",
+            "BTC-28MAR25-6.5-C",
+            "BTC-28MAR25--C",
+            "BTC-FS",
+            "BTC-FS--27DEC24",
+            "BTC-28MAR25-60000",
+        ] {
+            assert!(
+                matches!(
+                    name.parse::<InstrumentName>(),
+                    Err(DeribitFixError::MessageParsing(_))
+                ),
+                "{name} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_serde_uses_the_canonical_name() {
+        let option = parse("BTC-28MAR25-60000-C");
+        let json = serde_json::to_string(&option).unwrap();
+        assert_eq!(json, "\"BTC-28MAR25-60000-C\"");
+        assert_eq!(
+            serde_json::from_str::<InstrumentName>(&json).unwrap(),
+            option
+        );
+        assert!(serde_json::from_str::<InstrumentName>("\"BTC\"").is_err());
+    }
+}
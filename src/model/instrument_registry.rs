@@ -0,0 +1,412 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Instrument metadata and quantity conversions
+//!
+//! Order amounts are given in units of the instrument's amount currency: USD
+//! for inverse futures and perpetuals, the base currency for linear futures,
+//! options and spot. The exchange trades them in whole contracts of
+//! ContractMultiplier (231) units, the [`QuantityType::Contracts`] quantity.
+//! [`InstrumentRegistry`] learns each instrument's multiplier and currencies
+//! from Security List (y) and Security Definition (d) messages and converts
+//! between the two quantities. The tick size and amount step it learns set
+//! the decimals outgoing prices and quantities are written with, see
+//! [`number_format`](crate::model::number_format).
+//!
+//! [`QuantityType::Contracts`]: crate::message::QuantityType::Contracts
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::{SecurityInfo, SecurityList, SecurityType};
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Relative tolerance when checking that an amount is a whole number of contracts
+const WHOLE_CONTRACT_TOLERANCE: f64 = 1e-9;
+
+/// Sizing metadata of an instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentSpec {
+    /// Symbol (55)
+    pub symbol: String,
+    /// SecurityType (167)
+    pub security_type: Option<SecurityType>,
+    /// Currency (15), the base currency
+    pub currency: Option<String>,
+    /// SettlCurrency (120)
+    pub settl_currency: Option<String>,
+    /// PriceQuoteCurrency (1524)
+    pub price_quote_currency: Option<String>,
+    /// ContractMultiplier (231), units of the amount currency per contract
+    pub contract_multiplier: Option<f64>,
+    /// MinTradeVol (562), in units of the amount currency
+    pub min_trade_vol: Option<f64>,
+    /// MinPriceIncrement (969), the tick size
+    pub min_price_increment: Option<f64>,
+    /// InstrumentPricePrecision (2576), decimals of the price
+    pub price_precision: Option<u32>,
+}
+
+impl InstrumentSpec {
+    /// Create a spec with only the symbol known
+    pub fn new(symbol: String) -> Self {
+        Self {
+            symbol,
+            security_type: None,
+            currency: None,
+            settl_currency: None,
+            price_quote_currency: None,
+            contract_multiplier: None,
+            min_trade_vol: None,
+            min_price_increment: None,
+            price_precision: None,
+        }
+    }
+
+    /// Set the security type
+    pub fn with_security_type(mut self, security_type: SecurityType) -> Self {
+        self.security_type = Some(security_type);
+        self
+    }
+
+    /// Set the base, settlement and price quote currencies
+    pub fn with_currencies(mut self, currency: &str, settl_currency: &str, quote: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self.settl_currency = Some(settl_currency.to_string());
+        self.price_quote_currency = Some(quote.to_string());
+        self
+    }
+
+    /// Set the contract multiplier
+    pub fn with_contract_multiplier(mut self, contract_multiplier: f64) -> Self {
+        self.contract_multiplier = Some(contract_multiplier);
+        self
+    }
+
+    /// Set the minimum trade volume
+    pub fn with_min_trade_vol(mut self, min_trade_vol: f64) -> Self {
+        self.min_trade_vol = Some(min_trade_vol);
+        self
+    }
+
+    /// Set the tick size
+    pub fn with_min_price_increment(mut self, min_price_increment: f64) -> Self {
+        self.min_price_increment = Some(min_price_increment);
+        self
+    }
+
+    /// Set the number of decimals of the price
+    pub fn with_price_precision(mut self, price_precision: u32) -> Self {
+        self.price_precision = Some(price_precision);
+        self
+    }
+
+    /// Read the spec of the instrument a Security Definition (d) describes
+    pub fn from_security_definition(message: &FixMessage) -> Result<Self> {
+        let field = |tag: u32| message.get_field(tag).cloned();
+        let number = |tag: u32| message.get_field(tag).and_then(|v| v.parse::<f64>().ok());
+        let symbol = field(tags::SYMBOL)
+            .ok_or_else(|| DeribitFixError::MessageParsing("Missing Symbol (55)".to_string()))?;
+        Ok(Self {
+            symbol,
+            security_type: message
+                .get_field(tags::SECURITY_TYPE)
+                .and_then(|v| SecurityType::from_fix_str(v).ok()),
+            currency: field(tags::CURRENCY),
+            settl_currency: field(tags::SETTL_CURRENCY),
+            price_quote_currency: field(tags::PRICE_QUOTE_CURRENCY),
+            contract_multiplier: number(tags::CONTRACT_MULTIPLIER),
+            min_trade_vol: number(tags::MIN_TRADE_VOL),
+            min_price_increment: number(tags::MIN_PRICE_INCREMENT),
+            price_precision: message
+                .get_field(tags::INSTRUMENT_PRICE_PRECISION)
+                .and_then(|v| v.parse().ok()),
+        })
+    }
+
+    /// Whether the instrument is a future settled in its base currency
+    /// rather than its quote currency
+    pub fn is_inverse(&self) -> bool {
+        matches!(
+            self.security_type,
+            Some(SecurityType::Future | SecurityType::FutureCombo)
+        ) && self.settl_currency.is_some()
+            && self.settl_currency != self.price_quote_currency
+    }
+
+    /// Currency order amounts are given in
+    pub fn amount_currency(&self) -> Option<&str> {
+        if self.is_inverse() {
+            self.price_quote_currency.as_deref()
+        } else {
+            self.currency.as_deref()
+        }
+    }
+
+    /// Smallest change of an order amount, the minimum trade volume or
+    /// else the contract multiplier
+    pub fn amount_step(&self) -> Option<f64> {
+        self.min_trade_vol
+            .or(self.contract_multiplier)
+            .filter(|step| *step > 0.0)
+    }
+
+    /// Decimals prices are written with: those of the tick size, or else
+    /// the instrument's price precision
+    pub fn price_decimals(&self) -> Option<usize> {
+        self.min_price_increment
+            .and_then(step_decimals)
+            .or(self.price_precision.map(|precision| precision as usize))
+    }
+
+    /// Decimals amounts are written with, those of the amount step
+    pub fn amount_decimals(&self) -> Option<usize> {
+        self.amount_step().and_then(step_decimals)
+    }
+}
+
+impl From<&SecurityInfo> for InstrumentSpec {
+    fn from(info: &SecurityInfo) -> Self {
+        Self {
+            symbol: info.symbol.clone(),
+            security_type: info.security_type.clone(),
+            currency: info.currency.clone(),
+            settl_currency: info.settl_currency.clone(),
+            price_quote_currency: info.price_quote_currency.clone(),
+            contract_multiplier: info.contract_multiplier,
+            min_trade_vol: info.min_trade_vol,
+            min_price_increment: info.min_price_increment,
+            price_precision: info
+                .instrument_price_precision
+                .and_then(|precision| u32::try_from(precision).ok()),
+        }
+    }
+}
+
+/// Number of decimals of a positive tick or amount step, such as 4 for
+/// `0.0001` and 0 for `10`
+pub fn step_decimals(step: f64) -> Option<usize> {
+    if !step.is_finite() || step <= 0.0 {
+        return None;
+    }
+    // Display never uses an exponent and prints the shortest exact form
+    let text = step.to_string();
+    Some(
+        text.split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len()),
+    )
+}
+
+/// Registry of the known instruments' sizing metadata, by symbol
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentRegistry {
+    instruments: HashMap<String, InstrumentSpec>,
+}
+
+impl InstrumentRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an instrument, replacing any previous spec
+    pub fn register(&mut self, spec: InstrumentSpec) {
+        self.instruments.insert(spec.symbol.clone(), spec);
+    }
+
+    /// Register the instruments of a Security List (y) or Security Definition (d)
+    ///
+    /// An instrument that cannot be read is skipped with a warning and the
+    /// others are still registered. Returns the number of instruments
+    /// registered.
+    pub fn apply(&mut self, message: &FixMessage) -> usize {
+        let mut count = 0;
+        for instrument in SecurityList::split_instruments(message) {
+            let spec = match message.msg_type() {
+                Some(MsgType::SecurityList) => SecurityList::from_fix_message(&instrument)
+                    .map(|list| list.securities.iter().map(InstrumentSpec::from).collect()),
+                Some(MsgType::SecurityDefinition) => {
+                    InstrumentSpec::from_security_definition(&instrument).map(|spec| vec![spec])
+                }
+                _ => Ok(Vec::new()),
+            };
+            match spec {
+                Ok(specs) => {
+                    count += specs.len();
+                    for spec in specs {
+                        self.register(spec);
+                    }
+                }
+                Err(e) => warn!(
+                    "Skipping instrument {:?}: {}",
+                    instrument.get_field(tags::SYMBOL),
+                    e
+                ),
+            }
+        }
+        count
+    }
+
+    /// Spec of an instrument
+    pub fn get(&self, symbol: &str) -> Option<&InstrumentSpec> {
+        self.instruments.get(symbol)
+    }
+
+    /// Number of known instruments
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    /// Whether no instrument is known
+    pub fn is_empty(&self) -> bool {
+        self.instruments.is_empty()
+    }
+
+    /// Currency the amounts of `symbol` are given in
+    pub fn amount_currency(&self, symbol: &str) -> Option<&str> {
+        self.get(symbol)?.amount_currency()
+    }
+
+    /// Number of contracts `amount` units of `symbol` make
+    ///
+    /// Fails when the instrument or its multiplier is unknown, or when
+    /// `amount` is not a whole number of contracts.
+    pub fn to_contracts(&self, symbol: &str, amount: f64) -> Result<f64> {
+        let multiplier = self.contract_multiplier(symbol)?;
+        let contracts = amount / multiplier;
+        let whole = contracts.round();
+        if (contracts - whole).abs() > WHOLE_CONTRACT_TOLERANCE * whole.abs().max(1.0) {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "Amount {amount} of {symbol} is not a multiple of its contract size {multiplier}"
+            )));
+        }
+        Ok(whole)
+    }
+
+    /// Amount in units of `symbol` that `contracts` contracts make
+    pub fn to_units(&self, symbol: &str, contracts: f64) -> Result<f64> {
+        Ok(contracts * self.contract_multiplier(symbol)?)
+    }
+
+    fn contract_multiplier(&self, symbol: &str) -> Result<f64> {
+        let spec = self.get(symbol).ok_or_else(|| {
+            DeribitFixError::MessageConstruction(format!("Unknown instrument {symbol}"))
+        })?;
+        spec.contract_multiplier
+            .filter(|multiplier| *multiplier > 0.0)
+            .ok_or_else(|| {
+                DeribitFixError::MessageConstruction(format!(
+                    "No contract multiplier known for {symbol}"
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageBuilder;
+
+    fn registry() -> InstrumentRegistry {
+        let mut registry = InstrumentRegistry::new();
+        registry.register(
+            InstrumentSpec::new("BTC-PERPETUAL".to_string())
+                .with_security_type(SecurityType::Future)
+                .with_currencies("BTC", "BTC", "USD")
+                .with_contract_multiplier(10.0),
+        );
+        registry.register(
+            InstrumentSpec::new("BTC_USDC-PERPETUAL".to_string())
+                .with_security_type(SecurityType::Future)
+                .with_currencies("BTC", "USDC", "USDC")
+                .with_contract_multiplier(0.001),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_inverse_amounts_are_in_the_quote_currency() {
+        let registry = registry();
+        assert_eq!(registry.amount_currency("BTC-PERPETUAL"), Some("USD"));
+        assert_eq!(registry.amount_currency("BTC_USDC-PERPETUAL"), Some("BTC"));
+        assert_eq!(registry.amount_currency("ETH-PERPETUAL"), None);
+
+        assert_eq!(registry.to_contracts("BTC-PERPETUAL", 250.0).unwrap(), 25.0);
+        assert_eq!(registry.to_units("BTC-PERPETUAL", 25.0).unwrap(), 250.0);
+        assert_eq!(
+            registry.to_contracts("BTC_USDC-PERPETUAL", 0.123).unwrap(),
+            123.0
+        );
+        assert!((registry.to_units("BTC_USDC-PERPETUAL", 123.0).unwrap() - 0.123).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_partial_contracts_and_unknown_instruments_fail() {
+        let mut registry = registry();
+        assert!(registry.to_contracts("BTC-PERPETUAL", 255.0).is_err());
+        assert!(registry.to_contracts("ETH-PERPETUAL", 10.0).is_err());
+        registry.register(InstrumentSpec::new("ETH-PERPETUAL".to_string()));
+        assert!(registry.to_units("ETH-PERPETUAL", 1.0).is_err());
+    }
+
+    #[test]
+    fn test_price_and_amount_decimals() {
+        let spec = InstrumentSpec::new("BTC-PERPETUAL".to_string())
+            .with_contract_multiplier(10.0)
+            .with_min_price_increment(0.5)
+            .with_price_precision(4);
+        assert_eq!(spec.price_decimals(), Some(1));
+        assert_eq!(spec.amount_decimals(), Some(0));
+
+        let spec = InstrumentSpec::new("ETH-10OCT26-2500-C".to_string())
+            .with_contract_multiplier(1.0)
+            .with_min_trade_vol(0.001)
+            .with_price_precision(4);
+        assert_eq!(spec.price_decimals(), Some(4));
+        assert_eq!(spec.amount_decimals(), Some(3));
+        assert_eq!(step_decimals(0.00001), Some(5));
+        assert_eq!(step_decimals(0.0), None);
+    }
+
+    #[test]
+    fn test_security_definition_is_registered() {
+        let message = MessageBuilder::new()
+            .msg_type(MsgType::SecurityDefinition)
+            .sender_comp_id("DERIBIT".to_string())
+            .target_comp_id("CLIENT".to_string())
+            .msg_seq_num(1)
+            .field(tags::SYMBOL, "ETH-PERPETUAL".to_string())
+            .field(tags::SECURITY_TYPE, "FUT".to_string())
+            .field(tags::CURRENCY, "ETH".to_string())
+            .field(tags::SETTL_CURRENCY, "ETH".to_string())
+            .field(tags::PRICE_QUOTE_CURRENCY, "USD".to_string())
+            .field(tags::CONTRACT_MULTIPLIER, "1".to_string())
+            .build()
+            .unwrap();
+        let mut registry = InstrumentRegistry::new();
+        assert_eq!(registry.apply(&message), 1);
+        assert!(registry.get("ETH-PERPETUAL").unwrap().is_inverse());
+        assert_eq!(registry.to_contracts("ETH-PERPETUAL", 7.0).unwrap(), 7.0);
+    }
+
+    #[test]
+    fn test_unreadable_instruments_are_skipped() {
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=y\x01146=2\x0155=BTC-10OCT26-60000-C\x01167=OPT\x01202=abc\x0155=ETH-PERPETUAL\x01167=FUT\x01231=1\x0110=000\x01",
+        )
+        .unwrap();
+        let mut registry = InstrumentRegistry::new();
+        assert_eq!(registry.apply(&message), 1);
+        assert!(registry.get("BTC-10OCT26-60000-C").is_none());
+        assert_eq!(
+            registry.get("ETH-PERPETUAL").unwrap().contract_multiplier,
+            Some(1.0)
+        );
+    }
+}
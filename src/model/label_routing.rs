@@ -0,0 +1,275 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Execution Report routing by order label
+//!
+//! Orders can carry a strategy label in DeribitLabel (100010). A
+//! [`LabelRouter`] splits the Execution Report (8) stream into one channel
+//! per label, so several strategies can share a session and each only sees
+//! the executions of its own orders. Reports that omit the label are matched
+//! through the ClOrdID (11) or OrigClOrdID (41) of the labelled order.
+//!
+//! Each receiver holds a bounded backlog. Executions are order flow, so a
+//! receiver that falls behind is not left to miss them silently: the next
+//! [`LabelExecutions::recv`] fails with
+//! [`DeribitFixError::MessagesLost`](crate::error::DeribitFixError::MessagesLost),
+//! and the lost reports can be recovered with an Order Mass Status Request.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::ExecutionReport;
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+/// Default number of Execution Reports a receiver may fall behind by
+pub const DEFAULT_EXECUTION_BACKLOG: usize = 10_000;
+
+/// Receiver of the Execution Reports of one order label
+#[derive(Debug)]
+pub struct LabelExecutions {
+    reports: broadcast::Receiver<ExecutionReport>,
+}
+
+impl LabelExecutions {
+    /// Wait for the next report, `None` once the channel is closed
+    ///
+    /// Fails with [`DeribitFixError::MessagesLost`] when the receiver fell
+    /// further behind than its backlog and the oldest reports were dropped;
+    /// the calls after it return the reports kept.
+    pub async fn recv(&mut self) -> Result<Option<ExecutionReport>> {
+        match self.reports.recv().await {
+            Ok(report) => Ok(Some(report)),
+            Err(broadcast::error::RecvError::Lagged(lost)) => {
+                Err(DeribitFixError::MessagesLost(lost))
+            }
+            Err(broadcast::error::RecvError::Closed) => Ok(None),
+        }
+    }
+
+    /// Next report if one is waiting, without blocking
+    ///
+    /// Fails like [`recv`](Self::recv) when reports were lost.
+    pub fn try_recv(&mut self) -> Result<Option<ExecutionReport>> {
+        match self.reports.try_recv() {
+            Ok(report) => Ok(Some(report)),
+            Err(broadcast::error::TryRecvError::Lagged(lost)) => {
+                Err(DeribitFixError::MessagesLost(lost))
+            }
+            Err(broadcast::error::TryRecvError::Empty | broadcast::error::TryRecvError::Closed) => {
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Routes Execution Reports to the channels of their order label
+#[derive(Debug)]
+pub struct LabelRouter {
+    channels: HashMap<String, Vec<broadcast::Sender<ExecutionReport>>>,
+    /// Label of each open order, by ClOrdID (11)
+    order_labels: HashMap<String, String>,
+    /// Reports each receiver may fall behind by
+    backlog: usize,
+    /// Most reports a receiver fell behind by
+    high_water: usize,
+    /// Reports lost because a receiver fell behind
+    lost: u64,
+}
+
+impl Default for LabelRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LabelRouter {
+    /// Create a router without subscribers, each receiver holding up to
+    /// [`DEFAULT_EXECUTION_BACKLOG`] reports
+    pub fn new() -> Self {
+        Self::with_backlog(DEFAULT_EXECUTION_BACKLOG)
+    }
+
+    /// Create a router without subscribers, each receiver holding up to
+    /// `backlog` reports, rounded up to a power of two
+    pub fn with_backlog(backlog: usize) -> Self {
+        Self {
+            channels: HashMap::new(),
+            order_labels: HashMap::new(),
+            backlog: backlog.max(1),
+            high_water: 0,
+            lost: 0,
+        }
+    }
+
+    /// Receive the Execution Reports of the orders labelled `label`
+    ///
+    /// Every receiver of a label gets every report; a dropped receiver is
+    /// removed when the next report is routed.
+    pub fn subscribe(&mut self, label: &str) -> LabelExecutions {
+        let (sender, reports) = broadcast::channel(self.backlog);
+        self.channels
+            .entry(label.to_string())
+            .or_default()
+            .push(sender);
+        LabelExecutions { reports }
+    }
+
+    /// Remember the label of an order sent with ClOrdID `cl_ord_id`
+    pub fn track(&mut self, cl_ord_id: String, label: String) {
+        self.order_labels.insert(cl_ord_id, label);
+    }
+
+    /// Label of the open order with ClOrdID `cl_ord_id`
+    pub fn label_of(&self, cl_ord_id: &str) -> Option<&str> {
+        self.order_labels.get(cl_ord_id).map(String::as_str)
+    }
+
+    /// Whether there is no receiver and no labelled order to route
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty() && self.order_labels.is_empty()
+    }
+
+    /// Labels with at least one receiver
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.channels.keys().map(String::as_str)
+    }
+
+    /// Most reports a receiver fell behind by
+    pub fn high_water(&self) -> usize {
+        self.high_water
+    }
+
+    /// Reports lost, oldest first, because a receiver fell behind
+    pub fn lost(&self) -> u64 {
+        self.lost
+    }
+
+    /// Send `report` to the receivers of its label
+    ///
+    /// Returns whether a receiver got the report. Orders are forgotten once
+    /// they are filled, cancelled or rejected.
+    pub fn route(&mut self, report: &ExecutionReport) -> bool {
+        let label = report
+            .deribit_label
+            .clone()
+            .or_else(|| self.order_labels.get(&report.cl_ord_id).cloned())
+            .or_else(|| {
+                let orig_cl_ord_id = report.orig_cl_ord_id.as_ref()?;
+                self.order_labels.get(orig_cl_ord_id).cloned()
+            });
+        let Some(label) = label else {
+            return false;
+        };
+
+        let terminal = report.ord_status.is_terminal();
+        for cl_ord_id in std::iter::once(&report.cl_ord_id).chain(&report.orig_cl_ord_id) {
+            if terminal {
+                self.order_labels.remove(cl_ord_id);
+            } else if !cl_ord_id.is_empty() {
+                self.order_labels.insert(cl_ord_id.clone(), label.clone());
+            }
+        }
+
+        let Some(senders) = self.channels.get_mut(&label) else {
+            return false;
+        };
+        let (mut high_water, mut lost) = (self.high_water, 0);
+        senders.retain(|sender| {
+            let queued = sender.len();
+            if sender.send(report.clone()).is_err() {
+                return false;
+            }
+            // A full backlog evicts its oldest report, which the receiver
+            // learns about on its next read
+            if sender.len() == queued {
+                lost += 1;
+            }
+            high_water = high_water.max(sender.len());
+            true
+        });
+        self.high_water = high_water;
+        self.lost += lost;
+        if senders.is_empty() {
+            self.channels.remove(&label);
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{OrderSide, OrderStatus};
+
+    fn report(cl_ord_id: &str, status: OrderStatus, label: Option<&str>) -> ExecutionReport {
+        let mut report = ExecutionReport::new_order(
+            "order-1".to_string(),
+            cl_ord_id.to_string(),
+            "exec-1".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            OrderSide::Buy,
+            10.0,
+            10.0,
+            Some(50_000.0),
+        );
+        report.ord_status = status;
+        report.deribit_label = label.map(str::to_string);
+        report
+    }
+
+    #[test]
+    fn test_routes_by_label_and_cl_ord_id() {
+        let mut router = LabelRouter::new();
+        let mut mm = router.subscribe("mm-btc");
+        let mut arb = router.subscribe("arb");
+        router.track("A".to_string(), "mm-btc".to_string());
+
+        assert!(router.route(&report("A", OrderStatus::New, None)));
+        assert!(router.route(&report("B", OrderStatus::New, Some("arb"))));
+        assert!(!router.route(&report("C", OrderStatus::New, None)));
+        assert!(!router.route(&report("D", OrderStatus::New, Some("other"))));
+        assert_eq!(mm.try_recv().unwrap().unwrap().cl_ord_id, "A");
+        assert!(mm.try_recv().unwrap().is_none());
+        assert_eq!(arb.try_recv().unwrap().unwrap().cl_ord_id, "B");
+
+        // The label learned from a report routes later reports without it
+        assert_eq!(router.label_of("B"), Some("arb"));
+        assert!(router.route(&report("B", OrderStatus::Filled, None)));
+        assert_eq!(
+            arb.try_recv().unwrap().unwrap().ord_status,
+            OrderStatus::Filled
+        );
+        assert_eq!(router.label_of("B"), None);
+
+        // A replacement inherits the label of the order it replaces
+        let mut replaced = report("A2", OrderStatus::New, None);
+        replaced.orig_cl_ord_id = Some("A".to_string());
+        assert!(router.route(&replaced));
+        assert_eq!(router.label_of("A2"), Some("mm-btc"));
+
+        drop(mm);
+        assert!(!router.route(&report("A2", OrderStatus::Cancelled, None)));
+        assert_eq!(router.labels().collect::<Vec<_>>(), vec!["arb"]);
+    }
+
+    #[test]
+    fn test_lagging_receiver_learns_reports_were_lost() {
+        let mut router = LabelRouter::with_backlog(2);
+        let mut mm = router.subscribe("mm-btc");
+        for cl_ord_id in ["A", "B", "C"] {
+            assert!(router.route(&report(cl_ord_id, OrderStatus::New, Some("mm-btc"))));
+        }
+        assert_eq!(router.high_water(), 2);
+        assert_eq!(router.lost(), 1);
+
+        assert!(matches!(
+            mm.try_recv(),
+            Err(DeribitFixError::MessagesLost(1))
+        ));
+        assert_eq!(mm.try_recv().unwrap().unwrap().cl_ord_id, "B");
+        assert_eq!(mm.try_recv().unwrap().unwrap().cl_ord_id, "C");
+    }
+}
@@ -0,0 +1,543 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Resting order ladders
+//!
+//! A [`Ladder`] keeps a number of limit orders on one side of an instrument,
+//! one per price level, spaced by a fixed step away from a reference price.
+//! As the reference moves, each order whose level changed is moved with an
+//! Order Cancel/Replace Request (G); a level left without an order, because
+//! the order filled, was cancelled or the reference was lost, gets a new
+//! order on the next update.
+//!
+//! ```
+//! use deribit_fix::model::ladder::{Ladder, LadderAction};
+//! use deribit_fix::model::request::OrderSide;
+//!
+//! let mut bids = Ladder::new("BTC-PERPETUAL".to_string(), OrderSide::Buy, 3, 5.0, 100.0)
+//!     .with_offset(10.0);
+//! assert_eq!(bids.prices(50_000.0), [49_990.0, 49_985.0, 49_980.0]);
+//! let actions = bids.update(Some(50_000.0));
+//! assert!(matches!(actions[0], LadderAction::Place(_)));
+//! ```
+//!
+//! Like the [`QuotingEngine`](crate::model::quoting::QuotingEngine), the
+//! ladder only decides what to send;
+//! [`DeribitFixClient::run_ladder`](crate::DeribitFixClient::run_ladder)
+//! feeds it the mid price and the Execution Reports of its orders.
+
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
+use crate::message::{
+    ExecutionReport, OrderCancelReject, OrderCancelReplaceRequest, OrderCancelRequest,
+};
+use crate::model::instrument::InstrumentName;
+use crate::model::message::FixMessage;
+use crate::model::request::{NewOrderRequest, OrderSide};
+use crate::model::types::{ExecType, MsgType};
+use crate::session::request_ids::next_request_id;
+
+/// Order the ladder rests at one of its levels
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderOrder {
+    /// ClOrdID (11) of the order, the one of its last confirmed replace
+    pub cl_ord_id: String,
+    /// Exchange OrderID (37), once the order was acknowledged
+    pub order_id: Option<String>,
+    /// Price the order rests at
+    pub price: f64,
+    /// ClOrdID and price of a replace waiting for its confirmation
+    pub replacing: Option<(String, f64)>,
+}
+
+impl LadderOrder {
+    fn is_known_as(&self, id: &str) -> bool {
+        self.cl_ord_id == id
+            || self.order_id.as_deref() == Some(id)
+            || self
+                .replacing
+                .as_ref()
+                .is_some_and(|(cl_ord_id, _)| cl_ord_id == id)
+    }
+}
+
+/// Message the ladder wants sent
+#[derive(Debug, Clone)]
+pub enum LadderAction {
+    /// Rest a new order at a level
+    Place(NewOrderRequest),
+    /// Move an order to the new price of its level
+    Replace(OrderCancelReplaceRequest),
+    /// Pull an order
+    Cancel(OrderCancelRequest),
+}
+
+/// Limit orders spaced by a fixed step away from a reference price
+#[derive(Debug, Clone)]
+pub struct Ladder {
+    symbol: String,
+    side: OrderSide,
+    step: f64,
+    offset: f64,
+    amount: f64,
+    tick_size: Option<f64>,
+    post_only: bool,
+    label: Option<String>,
+    combo: bool,
+    levels: Vec<Option<LadderOrder>>,
+}
+
+impl Ladder {
+    /// Ladder of `levels` orders of `amount`, `step` apart
+    ///
+    /// The first level is at the reference price, bids below it and offers
+    /// above; see [`with_offset`](Self::with_offset).
+    pub fn new(symbol: String, side: OrderSide, levels: usize, step: f64, amount: f64) -> Self {
+        let combo = symbol
+            .parse::<InstrumentName>()
+            .is_ok_and(|name| name.is_combo());
+        Self {
+            symbol,
+            side,
+            step,
+            offset: 0.0,
+            amount,
+            tick_size: None,
+            post_only: false,
+            label: None,
+            combo,
+            levels: vec![None; levels],
+        }
+    }
+
+    /// Set the distance of the first level from the reference price
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Round prices away from the reference to the tick size of the instrument
+    pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    /// Send the orders post only
+    pub fn with_post_only(mut self, post_only: bool) -> Self {
+        self.post_only = post_only;
+        self
+    }
+
+    /// Label the orders with DeribitLabel (100010) `label`
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Check the step, offset and amount of the ladder
+    pub fn validate(&self) -> DeribitFixResult<()> {
+        if self.symbol.is_empty() {
+            return Err(DeribitFixError::Config(
+                "Ladder symbol must not be empty".to_string(),
+            ));
+        }
+        if self.levels.is_empty() {
+            return Err(DeribitFixError::Config(format!(
+                "Ladder of {} must have at least one level",
+                self.symbol
+            )));
+        }
+        if !(self.step.is_finite() && self.step > 0.0) {
+            return Err(DeribitFixError::Config(format!(
+                "Ladder step of {} must be positive",
+                self.symbol
+            )));
+        }
+        if !(self.offset.is_finite() && self.offset >= 0.0) {
+            return Err(DeribitFixError::Config(format!(
+                "Ladder offset of {} must not be negative",
+                self.symbol
+            )));
+        }
+        if !(self.amount.is_finite() && self.amount > 0.0) {
+            return Err(DeribitFixError::Config(format!(
+                "Ladder amount of {} must be positive",
+                self.symbol
+            )));
+        }
+        Ok(())
+    }
+
+    /// Instrument symbol
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Side of the orders
+    pub fn side(&self) -> OrderSide {
+        self.side
+    }
+
+    /// Order resting at each level, nearest to the reference first
+    pub fn levels(&self) -> &[Option<LadderOrder>] {
+        &self.levels
+    }
+
+    /// Price of each level around `reference`, nearest first
+    pub fn prices(&self, reference: f64) -> Vec<f64> {
+        (0..self.levels.len())
+            .map(|level| {
+                let distance = self.offset + self.step * level as f64;
+                match (self.side, self.tick_size) {
+                    (OrderSide::Buy, Some(tick)) if tick > 0.0 => {
+                        ((reference - distance) / tick).floor() * tick
+                    }
+                    (OrderSide::Sell, Some(tick)) if tick > 0.0 => {
+                        ((reference + distance) / tick).ceil() * tick
+                    }
+                    (OrderSide::Buy, _) => reference - distance,
+                    (OrderSide::Sell, _) => reference + distance,
+                }
+            })
+            .collect()
+    }
+
+    /// Bring the orders in line with the `reference` price
+    ///
+    /// Returns the messages to send: a new order for each empty level, a
+    /// replace for each acknowledged order whose level moved and a cancel
+    /// for each order whose level has no valid price, which only combos may
+    /// quote at zero or below. Orders waiting for an acknowledgement or a
+    /// replace confirmation are left alone. Without a reference price every
+    /// order is cancelled.
+    pub fn update(&mut self, reference: Option<f64>) -> Vec<LadderAction> {
+        let Some(reference) = reference.filter(|reference| reference.is_finite()) else {
+            return self.cancel_all();
+        };
+        let prices = self.prices(reference);
+        let mut actions = Vec::new();
+        for (level, price) in prices.into_iter().enumerate() {
+            let price = Some(price).filter(|price| self.combo || *price > 0.0);
+            match (self.levels[level].clone(), price) {
+                (None, Some(price)) => {
+                    let cl_ord_id = next_request_id("LADDER");
+                    actions.push(LadderAction::Place(self.order(cl_ord_id.clone(), price)));
+                    self.levels[level] = Some(LadderOrder {
+                        cl_ord_id,
+                        order_id: None,
+                        price,
+                        replacing: None,
+                    });
+                }
+                (Some(order), Some(price)) if order.price != price && order.replacing.is_none() => {
+                    let Some(orig) = order.order_id else {
+                        continue;
+                    };
+                    let cl_ord_id = next_request_id("LADDER");
+                    actions.push(LadderAction::Replace(self.replace(
+                        orig,
+                        cl_ord_id.clone(),
+                        price,
+                    )));
+                    if let Some(order) = &mut self.levels[level] {
+                        order.replacing = Some((cl_ord_id, price));
+                    }
+                }
+                (Some(order), None) => {
+                    if let Some(cancel) = self.cancel(&order) {
+                        actions.push(cancel);
+                        self.levels[level] = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+        actions
+    }
+
+    /// Pull every order of the ladder
+    pub fn cancel_all(&mut self) -> Vec<LadderAction> {
+        let orders: Vec<LadderOrder> = self.levels.iter_mut().filter_map(Option::take).collect();
+        orders
+            .iter()
+            .map(|order| {
+                LadderAction::Cancel(match &order.order_id {
+                    Some(order_id) => OrderCancelRequest::by_order_id(order_id.clone()),
+                    None => OrderCancelRequest::by_cl_ord_id(
+                        order.cl_ord_id.clone(),
+                        self.symbol.clone(),
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Forget the order or replace with ClOrdID `cl_ord_id` after it could
+    /// not be sent
+    ///
+    /// A forgotten order frees its level for the next update. Returns
+    /// whether the ClOrdID belonged to the ladder.
+    pub fn forget(&mut self, cl_ord_id: &str) -> bool {
+        for slot in &mut self.levels {
+            let Some(order) = slot else {
+                continue;
+            };
+            if order.cl_ord_id == cl_ord_id {
+                *slot = None;
+                return true;
+            }
+            if order
+                .replacing
+                .as_ref()
+                .is_some_and(|(replacing, _)| replacing == cl_ord_id)
+            {
+                order.replacing = None;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Apply a received message
+    ///
+    /// Execution Reports (8) of the ladder's orders record their OrderID and
+    /// confirmed replaces; an order that is filled, cancelled, rejected or
+    /// expired frees its level. An Order Cancel Reject (9) of a replace
+    /// leaves the order at its previous price. Returns whether the state of
+    /// the ladder changed.
+    pub fn on_message(&mut self, message: &FixMessage) -> bool {
+        match message.msg_type() {
+            Some(MsgType::ExecutionReport) => ExecutionReport::from_fix_message(message)
+                .is_ok_and(|report| self.on_execution_report(&report)),
+            Some(MsgType::OrderCancelReject) => {
+                let Ok(reject) = OrderCancelReject::from_fix_message(message) else {
+                    return false;
+                };
+                let Some(order) = reject
+                    .cl_ord_id
+                    .as_deref()
+                    .and_then(|cl_ord_id| self.order_mut(cl_ord_id))
+                else {
+                    return false;
+                };
+                order.replacing.take().is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn on_execution_report(&mut self, report: &ExecutionReport) -> bool {
+        let Some(level) = [
+            Some(&report.cl_ord_id),
+            report.orig_cl_ord_id.as_ref(),
+            Some(&report.order_id),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|id| !id.is_empty())
+        .find_map(|id| {
+            self.levels
+                .iter()
+                .position(|slot| slot.as_ref().is_some_and(|order| order.is_known_as(id)))
+        }) else {
+            return false;
+        };
+        if report.ord_status.is_terminal() {
+            self.levels[level] = None;
+            return true;
+        }
+        let Some(order) = self.levels[level].as_mut() else {
+            return false;
+        };
+        let before = order.clone();
+        if !report.order_id.is_empty() {
+            order.order_id = Some(report.order_id.clone());
+        }
+        if report.exec_type == ExecType::Replaced
+            && let Some((cl_ord_id, price)) = order.replacing.take()
+        {
+            order.cl_ord_id = cl_ord_id;
+            order.price = report.price.unwrap_or(price);
+        }
+        *order != before
+    }
+
+    fn order_mut(&mut self, id: &str) -> Option<&mut LadderOrder> {
+        self.levels
+            .iter_mut()
+            .flatten()
+            .find(|order| order.is_known_as(id))
+    }
+
+    fn order(&self, cl_ord_id: String, price: f64) -> NewOrderRequest {
+        let order = match self.side {
+            OrderSide::Buy => NewOrderRequest::limit_buy(self.symbol.clone(), self.amount, price),
+            OrderSide::Sell => NewOrderRequest::limit_sell(self.symbol.clone(), self.amount, price),
+        }
+        .with_client_order_id(cl_ord_id)
+        .with_post_only(self.post_only);
+        match &self.label {
+            Some(label) => order.with_label(label.clone()),
+            None => order,
+        }
+    }
+
+    fn replace(&self, orig: String, cl_ord_id: String, price: f64) -> OrderCancelReplaceRequest {
+        let side = match self.side {
+            OrderSide::Buy => crate::message::OrderSide::Buy,
+            OrderSide::Sell => crate::message::OrderSide::Sell,
+        };
+        let mut request =
+            OrderCancelReplaceRequest::new(orig, cl_ord_id, self.symbol.clone(), side)
+                .with_qty(self.amount)
+                .with_price(price);
+        if self.post_only {
+            request = request.post_only();
+        }
+        request.deribit_label = self.label.clone();
+        request
+    }
+
+    fn cancel(&self, order: &LadderOrder) -> Option<LadderAction> {
+        let order_id = order
+            .order_id
+            .clone()
+            .filter(|_| order.replacing.is_none())?;
+        Some(LadderAction::Cancel(OrderCancelRequest::by_order_id(
+            order_id,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::OrderStatus;
+
+    fn placed(actions: &[LadderAction]) -> Vec<(String, f64)> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                LadderAction::Place(order) => Some((order.client_order_id.clone()?, order.price?)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn report(
+        order: &(String, f64),
+        order_id: &str,
+        exec_type: ExecType,
+        ord_status: OrderStatus,
+    ) -> FixMessage {
+        let mut report = ExecutionReport::new_order(
+            order_id.to_string(),
+            order.0.clone(),
+            "EXEC-1".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            crate::message::OrderSide::Buy,
+            100.0,
+            100.0,
+            Some(order.1),
+        );
+        report.exec_type = exec_type;
+        report.ord_status = ord_status;
+        report.to_fix_message("DERIBIT", "CLIENT", 1).unwrap()
+    }
+
+    fn ladder() -> Ladder {
+        Ladder::new("BTC-PERPETUAL".to_string(), OrderSide::Buy, 3, 5.0, 100.0)
+            .with_offset(10.0)
+            .with_tick_size(0.5)
+    }
+
+    #[test]
+    fn test_levels_are_placed_and_moved_with_the_reference() {
+        let mut ladder = ladder();
+        let orders = placed(&ladder.update(Some(50_000.2)));
+        let prices: Vec<f64> = orders.iter().map(|(_, price)| *price).collect();
+        assert_eq!(prices, [49_990.0, 49_985.0, 49_980.0]);
+
+        // Orders waiting for their acknowledgement are not moved
+        assert!(ladder.update(Some(50_100.0)).is_empty());
+        for (n, order) in orders.iter().enumerate() {
+            let ack = report(order, &format!("ORD-{n}"), ExecType::New, OrderStatus::New);
+            assert!(ladder.on_message(&ack));
+        }
+
+        let actions = ladder.update(Some(50_100.0));
+        assert_eq!(actions.len(), 3);
+        let LadderAction::Replace(replace) = &actions[0] else {
+            panic!("expected a replace, got {actions:?}");
+        };
+        assert_eq!(replace.orig_cl_ord_id, "ORD-0");
+        assert_eq!(replace.price, Some(50_090.0));
+        // Nothing more is sent until the replaces are confirmed
+        assert!(ladder.update(Some(50_200.0)).is_empty());
+
+        let confirmed = (replace.cl_ord_id.clone(), 50_090.0);
+        ladder.on_message(&report(
+            &confirmed,
+            "ORD-0",
+            ExecType::Replaced,
+            OrderStatus::New,
+        ));
+        let level = ladder.levels()[0].as_ref().unwrap();
+        assert_eq!(level.cl_ord_id, confirmed.0);
+        assert_eq!(level.price, 50_090.0);
+        assert!(level.replacing.is_none());
+    }
+
+    #[test]
+    fn test_filled_level_is_placed_again() {
+        let mut ladder = ladder();
+        let orders = placed(&ladder.update(Some(50_000.0)));
+        ladder.on_message(&report(
+            &orders[1],
+            "ORD-1",
+            ExecType::Trade,
+            OrderStatus::Filled,
+        ));
+        assert!(ladder.levels()[1].is_none());
+
+        let refill = placed(&ladder.update(Some(50_000.0)));
+        assert_eq!(refill.len(), 1);
+        assert_eq!(refill[0].1, 49_985.0);
+        assert_ne!(refill[0].0, orders[1].0);
+
+        // An order that could not be sent frees its level too
+        assert!(ladder.forget(&refill[0].0));
+        assert!(ladder.levels()[1].is_none());
+    }
+
+    #[test]
+    fn test_lost_reference_cancels_every_order() {
+        let mut ladder = ladder();
+        let orders = placed(&ladder.update(Some(50_000.0)));
+        ladder.on_message(&report(
+            &orders[0],
+            "ORD-0",
+            ExecType::New,
+            OrderStatus::New,
+        ));
+
+        let actions = ladder.update(None);
+        assert_eq!(actions.len(), 3);
+        assert!(matches!(&actions[0], LadderAction::Cancel(cancel)
+            if cancel.orig_cl_ord_id.as_deref() == Some("ORD-0")));
+        assert!(matches!(&actions[1], LadderAction::Cancel(cancel)
+            if cancel.cl_ord_id.as_ref() == Some(&orders[1].0)));
+        assert!(ladder.levels().iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_ladder_is_validated() {
+        assert!(ladder().validate().is_ok());
+        let flat = Ladder::new("BTC-PERPETUAL".to_string(), OrderSide::Sell, 3, 0.0, 1.0);
+        assert!(flat.validate().is_err());
+        let empty = Ladder::new("BTC-PERPETUAL".to_string(), OrderSide::Sell, 0, 1.0, 1.0);
+        assert!(empty.validate().is_err());
+    }
+}
@@ -0,0 +1,323 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Order-entry latency tracing
+//!
+//! With [`latency_tracing`](crate::config::DeribitFixConfig::latency_tracing)
+//! enabled the session timestamps every stage of the orders, replaces and
+//! cancels it sends, by ClOrdID (11):
+//!
+//! 1. the API call, when the client handed the request to the session task;
+//! 2. the message serialized and handed to the connection;
+//! 3. the message written to the socket;
+//! 4. the read that brought the first byte of the acknowledgement, an
+//!    Execution Report (8) or Order Cancel Reject (9);
+//! 5. the acknowledgement parsed and handed to the session.
+//!
+//! [`LatencyTracer`] turns each acknowledged order into an [`OrderLatency`]
+//! and summarises the recent ones as [`LatencyStats`] percentiles, telling
+//! apart time spent in the crate (submit, write and parse) from time spent
+//! on the network and at the exchange.
+
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Acknowledged orders kept for the percentiles
+pub const LATENCY_SAMPLE_CAPACITY: usize = 10_000;
+
+/// Most orders traced while waiting for their acknowledgement
+const MAX_PENDING_TRACES: usize = 10_000;
+
+/// Stage timestamps of an order waiting for its acknowledgement
+#[derive(Debug, Clone, Copy)]
+struct PendingTrace {
+    called: Instant,
+    serialized: Instant,
+    written: Option<Instant>,
+}
+
+/// Time spent in each stage of an acknowledged order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct OrderLatency {
+    /// ClOrdID (11) of the order, replace or cancel
+    pub cl_ord_id: String,
+    /// From the API call to the serialized message
+    pub submit: Duration,
+    /// From the serialized message to the socket write, when known
+    pub write: Option<Duration>,
+    /// From the socket write to the first byte of the acknowledgement
+    pub network: Option<Duration>,
+    /// From the first byte of the acknowledgement to the parsed message
+    pub parse: Option<Duration>,
+    /// From the API call to the parsed acknowledgement
+    pub total: Duration,
+}
+
+/// Percentiles of one stage over the recent acknowledged orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    /// Median
+    pub p50: Duration,
+    /// 90th percentile
+    pub p90: Duration,
+    /// 99th percentile
+    pub p99: Duration,
+    /// Slowest
+    pub max: Duration,
+}
+
+impl LatencyPercentiles {
+    /// Nearest-rank percentiles of `samples`, `None` when there are none
+    pub fn of(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        let rank = |percentile: usize| {
+            let index = (samples.len() * percentile).div_ceil(100).max(1) - 1;
+            samples[index]
+        };
+        Some(Self {
+            p50: rank(50),
+            p90: rank(90),
+            p99: rank(99),
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+/// Latency percentiles of each stage of the recent acknowledged orders
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct LatencyStats {
+    /// Acknowledged orders the percentiles are computed from
+    pub samples: usize,
+    /// From the API call to the serialized message
+    pub submit: Option<LatencyPercentiles>,
+    /// From the serialized message to the socket write
+    pub write: Option<LatencyPercentiles>,
+    /// From the socket write to the first byte of the acknowledgement
+    pub network: Option<LatencyPercentiles>,
+    /// From the first byte of the acknowledgement to the parsed message
+    pub parse: Option<LatencyPercentiles>,
+    /// From the API call to the parsed acknowledgement
+    pub total: Option<LatencyPercentiles>,
+}
+
+/// Stage timestamps of the orders in flight and latencies of the recent ones
+#[derive(Debug, Clone, Default)]
+pub struct LatencyTracer {
+    pending: HashMap<String, PendingTrace>,
+    samples: VecDeque<OrderLatency>,
+}
+
+impl LatencyTracer {
+    /// Create a tracer without samples
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ClOrdID (11) of an order, replace or cancel request, the messages
+    /// whose latency is traced
+    pub fn traced_order_id(message: &FixMessage) -> Option<&String> {
+        match message.msg_type()? {
+            MsgType::NewOrderSingle
+            | MsgType::OrderCancelReplaceRequest
+            | MsgType::OrderCancelRequest => message.get_field(tags::CL_ORD_ID),
+            _ => None,
+        }
+    }
+
+    /// Start tracing `cl_ord_id`, called at `called` and serialized at
+    /// `serialized`, with the time it was written if it was not batched
+    pub fn start(
+        &mut self,
+        cl_ord_id: String,
+        called: Instant,
+        serialized: Instant,
+        written: Option<Instant>,
+    ) {
+        if self.pending.len() >= MAX_PENDING_TRACES {
+            return;
+        }
+        self.pending.insert(
+            cl_ord_id,
+            PendingTrace {
+                called: called.min(serialized),
+                serialized,
+                written,
+            },
+        );
+    }
+
+    /// Complete the trace acknowledged by `message`, first read at `read`
+    /// and parsed at `parsed`
+    ///
+    /// `last_write` stands in for the write time of an order that was
+    /// batched. Returns the latency of the order, if it was traced.
+    pub fn acknowledge(
+        &mut self,
+        message: &FixMessage,
+        read: Option<Instant>,
+        parsed: Instant,
+        last_write: Option<Instant>,
+    ) -> Option<OrderLatency> {
+        if !matches!(
+            message.msg_type(),
+            Some(MsgType::ExecutionReport | MsgType::OrderCancelReject)
+        ) {
+            return None;
+        }
+        let cl_ord_id = message.get_field(tags::CL_ORD_ID)?;
+        let trace = self.pending.remove(cl_ord_id)?;
+        let written = trace
+            .written
+            .or(last_write.filter(|write| *write >= trace.serialized));
+        let read = read.filter(|read| written.is_none_or(|written| *read >= written));
+        let latency = OrderLatency {
+            cl_ord_id: cl_ord_id.clone(),
+            submit: trace.serialized.saturating_duration_since(trace.called),
+            write: written.map(|written| written.saturating_duration_since(trace.serialized)),
+            network: written
+                .zip(read)
+                .map(|(written, read)| read.saturating_duration_since(written)),
+            parse: read.map(|read| parsed.saturating_duration_since(read)),
+            total: parsed.saturating_duration_since(trace.called),
+        };
+        if self.samples.len() == LATENCY_SAMPLE_CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency.clone());
+        Some(latency)
+    }
+
+    /// Orders sent and not acknowledged yet
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Latencies of the recent acknowledged orders, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = &OrderLatency> {
+        self.samples.iter()
+    }
+
+    /// Percentiles of each stage over the recent acknowledged orders
+    pub fn stats(&self) -> LatencyStats {
+        let stage = |select: fn(&OrderLatency) -> Option<Duration>| {
+            LatencyPercentiles::of(self.samples.iter().filter_map(select).collect())
+        };
+        LatencyStats {
+            samples: self.samples.len(),
+            submit: stage(|latency| Some(latency.submit)),
+            write: stage(|latency| latency.write),
+            network: stage(|latency| latency.network),
+            parse: stage(|latency| latency.parse),
+            total: stage(|latency| Some(latency.total)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageBuilder;
+
+    fn ack(cl_ord_id: &str) -> FixMessage {
+        MessageBuilder::new()
+            .msg_type(MsgType::ExecutionReport)
+            .sender_comp_id("DERIBIT".to_string())
+            .target_comp_id("CLIENT".to_string())
+            .msg_seq_num(1)
+            .field(tags::CL_ORD_ID, cl_ord_id.to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_acknowledged_order_is_split_into_stages() {
+        let start = Instant::now();
+        let mut tracer = LatencyTracer::new();
+        tracer.start(
+            "ORDER_1".to_string(),
+            start,
+            start + ms(1),
+            Some(start + ms(2)),
+        );
+        assert_eq!(tracer.pending(), 1);
+
+        assert!(
+            tracer
+                .acknowledge(&ack("ORDER_2"), None, start, None)
+                .is_none()
+        );
+        let latency = tracer
+            .acknowledge(&ack("ORDER_1"), Some(start + ms(12)), start + ms(13), None)
+            .unwrap();
+        assert_eq!(
+            latency,
+            OrderLatency {
+                cl_ord_id: "ORDER_1".to_string(),
+                submit: ms(1),
+                write: Some(ms(1)),
+                network: Some(ms(10)),
+                parse: Some(ms(1)),
+                total: ms(13),
+            }
+        );
+        assert_eq!(tracer.pending(), 0);
+    }
+
+    #[test]
+    fn test_batched_order_takes_the_last_write() {
+        let start = Instant::now();
+        let mut tracer = LatencyTracer::new();
+        tracer.start("ORDER_1".to_string(), start, start, None);
+
+        let latency = tracer
+            .acknowledge(
+                &ack("ORDER_1"),
+                Some(start + ms(10)),
+                start + ms(10),
+                Some(start + ms(4)),
+            )
+            .unwrap();
+        assert_eq!(latency.write, Some(ms(4)));
+        assert_eq!(latency.network, Some(ms(6)));
+    }
+
+    #[test]
+    fn test_percentiles_use_nearest_rank() {
+        let samples = (1..=100).map(ms).collect();
+        let percentiles = LatencyPercentiles::of(samples).unwrap();
+        assert_eq!(percentiles.p50, ms(50));
+        assert_eq!(percentiles.p90, ms(90));
+        assert_eq!(percentiles.p99, ms(99));
+        assert_eq!(percentiles.max, ms(100));
+        assert_eq!(LatencyPercentiles::of(Vec::new()), None);
+
+        let start = Instant::now();
+        let mut tracer = LatencyTracer::new();
+        for (i, millis) in [3, 1, 2].into_iter().enumerate() {
+            let cl_ord_id = format!("ORDER_{i}");
+            tracer.start(cl_ord_id.clone(), start, start, None);
+            tracer.acknowledge(&ack(&cl_ord_id), None, start + ms(millis), None);
+        }
+        let stats = tracer.stats();
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.total.unwrap().p50, ms(2));
+        assert_eq!(stats.network, None);
+    }
+}
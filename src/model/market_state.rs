@@ -0,0 +1,355 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Instrument trading state and exchange maintenance tracking
+//!
+//! [`MarketStateTracker`] is fed by Security Status (f) messages and by
+//! Logout (5) messages announcing maintenance. While an instrument is halted,
+//! closed or being settled, or while the exchange is in maintenance, order
+//! submission is paused: new orders are either rejected locally with
+//! [`DeribitFixError::InstrumentHalted`](crate::error::DeribitFixError::InstrumentHalted)
+//! or queued and released once trading resumes.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::security_status::SecurityStatus;
+use crate::model::request::NewOrderRequest;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// SecurityTradingStatus (326) value for "Trading halt"
+const TRADING_HALT: i32 = 2;
+/// SecurityTradingStatus (326) value for "No open / no resume"
+const NO_OPEN: i32 = 4;
+/// SecurityTradingStatus (326) value for "Ready to trade"
+const READY_TO_TRADE: i32 = 7;
+/// SecurityTradingStatus (326) value for "Not available for trading"
+const NOT_AVAILABLE_FOR_TRADING: i32 = 8;
+/// SecurityTradingStatus (326) value for "Ready to trade (start of session)"
+const START_OF_SESSION: i32 = 17;
+/// SecurityTradingStatus (326) value for "Not available for trading (end of session)"
+const END_OF_SESSION: i32 = 18;
+
+/// Trading state of a single instrument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstrumentState {
+    /// Instrument is open for trading (326=7 or 17)
+    Open,
+    /// Instrument is closed, at the end of its session or expired (326=4 or
+    /// 18, or not available with a Text (58) saying it is closed or expired)
+    Closed,
+    /// Instrument is being settled (not available with a Text (58)
+    /// mentioning settlement)
+    Settlement,
+    /// Instrument is halted (326=2 or 8)
+    Halted,
+    /// Status unknown or invalid (326=20, or any other value)
+    Unknown,
+}
+
+impl From<i32> for InstrumentState {
+    fn from(status: i32) -> Self {
+        match status {
+            READY_TO_TRADE | START_OF_SESSION => InstrumentState::Open,
+            NO_OPEN | END_OF_SESSION => InstrumentState::Closed,
+            TRADING_HALT | NOT_AVAILABLE_FOR_TRADING => InstrumentState::Halted,
+            _ => InstrumentState::Unknown,
+        }
+    }
+}
+
+impl InstrumentState {
+    /// State of the instrument of a Security Status (f), if it has a
+    /// SecurityTradingStatus (326)
+    ///
+    /// Deribit reports settlement and expiry as "not available for trading";
+    /// the Text (58) tells them apart from a halt.
+    pub fn of(status: &SecurityStatus) -> Option<Self> {
+        let state = InstrumentState::from(status.security_trading_status?);
+        if state != InstrumentState::Halted {
+            return Some(state);
+        }
+        let text = status
+            .text
+            .as_deref()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Some(if text.contains("settl") {
+            InstrumentState::Settlement
+        } else if text.contains("closed") || text.contains("expired") {
+            InstrumentState::Closed
+        } else {
+            InstrumentState::Halted
+        })
+    }
+
+    /// Whether orders can be sent for an instrument in this state
+    ///
+    /// An unknown state does not block trading.
+    pub fn allows_trading(self) -> bool {
+        matches!(self, InstrumentState::Open | InstrumentState::Unknown)
+    }
+}
+
+/// Notification about instrument trading state and exchange maintenance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MarketStateEvent {
+    /// An instrument changed trading state
+    TradingStateChanged {
+        /// Instrument symbol
+        symbol: String,
+        /// State before the change, `None` if the instrument was not tracked yet
+        previous: Option<InstrumentState>,
+        /// New state
+        current: InstrumentState,
+    },
+    /// The exchange announced maintenance; order submission is paused
+    MaintenanceStarted {
+        /// Text (58) of the Logout announcing the maintenance
+        text: String,
+    },
+    /// The session logged on again after maintenance; order submission resumed
+    MaintenanceEnded,
+    /// An order was queued because its instrument is not tradable
+    OrderQueued {
+        /// Instrument symbol
+        symbol: String,
+        /// ClOrdID (11) of the queued order
+        cl_ord_id: String,
+    },
+    /// A queued order was released and sent after trading resumed
+    OrderReleased {
+        /// Instrument symbol
+        symbol: String,
+        /// ClOrdID (11) of the released order
+        cl_ord_id: String,
+    },
+}
+
+/// Tracks instrument trading state and exchange maintenance windows
+#[derive(Clone, Default)]
+pub struct MarketStateTracker {
+    states: HashMap<String, InstrumentState>,
+    maintenance: Option<String>,
+    queued: Vec<NewOrderRequest>,
+}
+
+impl MarketStateTracker {
+    /// Create an empty tracker; untracked instruments are considered tradable
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// State of an instrument, if a Security Status has been received for it
+    pub fn instrument_state(&self, symbol: &str) -> Option<InstrumentState> {
+        self.states.get(symbol).copied()
+    }
+
+    /// Whether the exchange is in a maintenance window
+    pub fn in_maintenance(&self) -> bool {
+        self.maintenance.is_some()
+    }
+
+    /// Text of the maintenance announcement, if in maintenance
+    pub fn maintenance_text(&self) -> Option<&str> {
+        self.maintenance.as_deref()
+    }
+
+    /// Whether orders for `symbol` can be sent now
+    ///
+    /// Instruments without a known state are tradable so that the tracker never
+    /// blocks trading when Security Status is not subscribed.
+    pub fn is_tradable(&self, symbol: &str) -> bool {
+        self.check_tradable(symbol).is_ok()
+    }
+
+    /// Check that orders for `symbol` can be sent now
+    ///
+    /// Fails with [`DeribitFixError::InstrumentHalted`] when the cached state
+    /// of the instrument prohibits trading, or with a session error during
+    /// maintenance.
+    pub fn check_tradable(&self, symbol: &str) -> Result<()> {
+        if self.in_maintenance() {
+            return Err(DeribitFixError::Session(format!(
+                "Order submission paused: exchange maintenance, {symbol} is not tradable"
+            )));
+        }
+        match self.instrument_state(symbol) {
+            Some(state) if !state.allows_trading() => Err(DeribitFixError::InstrumentHalted {
+                symbol: symbol.to_string(),
+                state,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply a Security Status (f) message
+    ///
+    /// Returns an event when the trading state of the instrument changed.
+    pub fn apply_security_status(&mut self, status: &SecurityStatus) -> Option<MarketStateEvent> {
+        let current = InstrumentState::of(status)?;
+        let previous = self.states.insert(status.symbol.clone(), current);
+        if previous == Some(current) {
+            return None;
+        }
+        Some(MarketStateEvent::TradingStateChanged {
+            symbol: status.symbol.clone(),
+            previous,
+            current,
+        })
+    }
+
+    /// Enter a maintenance window announced by `text`
+    pub fn start_maintenance(&mut self, text: String) -> MarketStateEvent {
+        self.maintenance = Some(text.clone());
+        MarketStateEvent::MaintenanceStarted { text }
+    }
+
+    /// Leave the maintenance window, returning an event if one was active
+    pub fn end_maintenance(&mut self) -> Option<MarketStateEvent> {
+        self.maintenance
+            .take()
+            .map(|_| MarketStateEvent::MaintenanceEnded)
+    }
+
+    /// Queue an order until its instrument becomes tradable
+    pub fn queue_order(&mut self, order: NewOrderRequest) {
+        self.queued.push(order);
+    }
+
+    /// Number of queued orders
+    pub fn queued_orders(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// Remove the queued order with ClOrdID `cl_ord_id`, returning whether
+    /// one was queued
+    pub fn withdraw_queued(&mut self, cl_ord_id: &str) -> bool {
+        let queued = self.queued.len();
+        self.queued
+            .retain(|order| order.client_order_id.as_deref() != Some(cl_ord_id));
+        self.queued.len() != queued
+    }
+
+    /// Remove and return the queued orders whose instruments are now tradable,
+    /// preserving submission order
+    pub fn take_releasable(&mut self) -> Vec<NewOrderRequest> {
+        let (ready, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queued)
+            .into_iter()
+            .partition(|order| self.is_tradable(&order.instrument_name));
+        self.queued = pending;
+        ready
+    }
+}
+
+/// Whether a Logout Text (58) announces exchange maintenance
+pub fn is_maintenance_text(text: &str) -> bool {
+    text.to_ascii_lowercase().contains("maintenance")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(symbol: &str) -> NewOrderRequest {
+        NewOrderRequest::limit_buy(symbol.to_string(), 1.0, 100.0)
+    }
+
+    #[test]
+    fn test_trading_state_from_status() {
+        assert_eq!(InstrumentState::from(7), InstrumentState::Open);
+        assert_eq!(InstrumentState::from(8), InstrumentState::Halted);
+        assert_eq!(InstrumentState::from(18), InstrumentState::Closed);
+        assert_eq!(InstrumentState::from(20), InstrumentState::Unknown);
+    }
+
+    #[test]
+    fn test_settlement_and_expiry_are_told_apart_from_halts() {
+        let status = |text: &str| {
+            SecurityStatus::new("BTC-27DEC26".to_string())
+                .with_trading_status(8)
+                .with_text(text.to_string())
+        };
+        assert_eq!(
+            InstrumentState::of(&status("Instrument in settlement")),
+            Some(InstrumentState::Settlement)
+        );
+        assert_eq!(
+            InstrumentState::of(&status("Instrument expired")),
+            Some(InstrumentState::Closed)
+        );
+        assert_eq!(
+            InstrumentState::of(&status("Trading halted")),
+            Some(InstrumentState::Halted)
+        );
+        assert_eq!(
+            InstrumentState::of(&SecurityStatus::new("BTC-27DEC26".to_string())),
+            None
+        );
+        assert!(!InstrumentState::Settlement.allows_trading());
+        assert!(InstrumentState::Unknown.allows_trading());
+    }
+
+    #[test]
+    fn test_apply_security_status_reports_changes_only() {
+        let mut tracker = MarketStateTracker::new();
+        let halted = SecurityStatus::new("BTC-PERPETUAL".to_string()).with_trading_status(8);
+
+        assert_eq!(
+            tracker.apply_security_status(&halted),
+            Some(MarketStateEvent::TradingStateChanged {
+                symbol: "BTC-PERPETUAL".to_string(),
+                previous: None,
+                current: InstrumentState::Halted,
+            })
+        );
+        assert_eq!(tracker.apply_security_status(&halted), None);
+        assert!(!tracker.is_tradable("BTC-PERPETUAL"));
+        assert!(matches!(
+            tracker.check_tradable("BTC-PERPETUAL"),
+            Err(DeribitFixError::InstrumentHalted {
+                state: InstrumentState::Halted,
+                ..
+            })
+        ));
+        assert!(tracker.is_tradable("ETH-PERPETUAL"));
+    }
+
+    #[test]
+    fn test_maintenance_pauses_all_instruments() {
+        let mut tracker = MarketStateTracker::new();
+        tracker.start_maintenance("Scheduled maintenance".to_string());
+        assert!(!tracker.is_tradable("ETH-PERPETUAL"));
+        assert_eq!(
+            tracker.end_maintenance(),
+            Some(MarketStateEvent::MaintenanceEnded)
+        );
+        assert_eq!(tracker.end_maintenance(), None);
+        assert!(tracker.is_tradable("ETH-PERPETUAL"));
+    }
+
+    #[test]
+    fn test_take_releasable_keeps_halted_orders() {
+        let mut tracker = MarketStateTracker::new();
+        tracker.apply_security_status(
+            &SecurityStatus::new("BTC-PERPETUAL".to_string()).with_trading_status(8),
+        );
+        tracker.queue_order(order("BTC-PERPETUAL"));
+        tracker.queue_order(order("ETH-PERPETUAL"));
+
+        let released = tracker.take_releasable();
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].instrument_name, "ETH-PERPETUAL");
+        assert_eq!(tracker.queued_orders(), 1);
+    }
+
+    #[test]
+    fn test_is_maintenance_text() {
+        assert!(is_maintenance_text(
+            "Exchange is going down for MAINTENANCE"
+        ));
+        assert!(!is_maintenance_text("Logout requested by user"));
+    }
+}
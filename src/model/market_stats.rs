@@ -0,0 +1,351 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Per-instrument statistics derived from market data
+//!
+//! [`MarketStatsTracker`] is fed by the same Market Data Snapshot/Full Refresh
+//! (W) and Incremental Refresh (X) messages as the order books. For every
+//! instrument it keeps the exchange-reported 24h volume (100087) and mark
+//! price, a VWAP and volume over the trades seen in a rolling window, and the
+//! funding (100092, 100093) and open interest (746) history of perpetuals.
+
+use crate::message::{
+    MarketDataIncrementalRefresh, MarketDataSnapshotFullRefresh, MdEntry, MdEntryType,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Default length of the rolling window
+pub const DEFAULT_STATS_WINDOW: TimeDelta = TimeDelta::hours(24);
+
+/// Trade seen in the market data stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeSample {
+    /// TradeID (1003), if reported
+    pub trade_id: Option<String>,
+    /// Time of the trade, or the time it was received when not reported
+    pub time: DateTime<Utc>,
+    /// Trade price
+    pub price: f64,
+    /// Trade amount
+    pub size: f64,
+}
+
+/// Funding rates reported for a perpetual
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FundingSample {
+    /// Time the rates were received
+    pub time: DateTime<Utc>,
+    /// CurrentFunding (100092)
+    pub current_funding: Option<f64>,
+    /// Funding8h (100093)
+    pub funding_8h: Option<f64>,
+}
+
+/// Statistics of a single instrument
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketStats {
+    /// Instrument symbol
+    pub symbol: String,
+    /// TradeVolume24h (100087) as last reported by the exchange
+    pub volume_24h: Option<f64>,
+    /// MarkPrice (100090) as last reported by the exchange
+    pub mark_price: Option<f64>,
+    window: TimeDelta,
+    trades: VecDeque<TradeSample>,
+    trade_ids: HashSet<String>,
+    funding: VecDeque<FundingSample>,
+    open_interest: VecDeque<(DateTime<Utc>, f64)>,
+}
+
+impl MarketStats {
+    /// Create empty statistics keeping `window` of history
+    pub fn new(symbol: String, window: TimeDelta) -> Self {
+        Self {
+            symbol,
+            volume_24h: None,
+            mark_price: None,
+            window,
+            trades: VecDeque::new(),
+            trade_ids: HashSet::new(),
+            funding: VecDeque::new(),
+            open_interest: VecDeque::new(),
+        }
+    }
+
+    /// Volume-weighted average price of the trades in the window
+    pub fn vwap(&self) -> Option<f64> {
+        let volume = self.rolling_volume();
+        if volume <= 0.0 {
+            return None;
+        }
+        let notional: f64 = self.trades.iter().map(|t| t.price * t.size).sum();
+        Some(notional / volume)
+    }
+
+    /// Total amount traded in the window
+    pub fn rolling_volume(&self) -> f64 {
+        self.trades.iter().map(|t| t.size).sum()
+    }
+
+    /// Trades in the window, oldest first
+    pub fn trades(&self) -> impl Iterator<Item = &TradeSample> {
+        self.trades.iter()
+    }
+
+    /// Most recent funding rates, if the instrument is a perpetual
+    pub fn last_funding(&self) -> Option<&FundingSample> {
+        self.funding.back()
+    }
+
+    /// Funding rate changes in the window, oldest first
+    pub fn funding_history(&self) -> impl Iterator<Item = &FundingSample> {
+        self.funding.iter()
+    }
+
+    /// Most recent OpenInterest (746)
+    pub fn open_interest(&self) -> Option<f64> {
+        self.open_interest.back().map(|(_, value)| *value)
+    }
+
+    /// Open interest changes in the window, oldest first
+    pub fn open_interest_series(&self) -> impl Iterator<Item = &(DateTime<Utc>, f64)> {
+        self.open_interest.iter()
+    }
+
+    /// Apply a snapshot received at `received_at`
+    pub fn apply_snapshot(
+        &mut self,
+        snapshot: &MarketDataSnapshotFullRefresh,
+        received_at: DateTime<Utc>,
+    ) {
+        if snapshot.trade_volume_24h.is_some() {
+            self.volume_24h = snapshot.trade_volume_24h;
+        }
+        if snapshot.mark_price.is_some() {
+            self.mark_price = snapshot.mark_price;
+        }
+
+        if snapshot.current_funding.is_some() || snapshot.funding_8h.is_some() {
+            let changed = self.funding.back().is_none_or(|last| {
+                last.current_funding != snapshot.current_funding
+                    || last.funding_8h != snapshot.funding_8h
+            });
+            if changed {
+                self.funding.push_back(FundingSample {
+                    time: received_at,
+                    current_funding: snapshot.current_funding,
+                    funding_8h: snapshot.funding_8h,
+                });
+            }
+        }
+
+        if let Some(open_interest) = snapshot.open_interest
+            && self.open_interest() != Some(open_interest)
+        {
+            self.open_interest.push_back((received_at, open_interest));
+        }
+
+        self.add_trades(&snapshot.entries, received_at);
+    }
+
+    /// Apply an incremental refresh received at `received_at`
+    pub fn apply_incremental(
+        &mut self,
+        update: &MarketDataIncrementalRefresh,
+        received_at: DateTime<Utc>,
+    ) {
+        self.add_trades(&update.entries, received_at);
+    }
+
+    /// Record new trades and drop history older than the window
+    ///
+    /// Trades already seen, e.g. repeated by a later snapshot, are skipped.
+    fn add_trades(&mut self, entries: &[MdEntry], received_at: DateTime<Utc>) {
+        for entry in entries {
+            let (MdEntryType::Trade, Some(price), Some(size)) =
+                (entry.md_entry_type, entry.md_entry_px, entry.md_entry_size)
+            else {
+                continue;
+            };
+            if let Some(trade_id) = &entry.trade_id
+                && !self.trade_ids.insert(trade_id.clone())
+            {
+                continue;
+            }
+            let time = entry.md_entry_date.unwrap_or(received_at);
+            let position = self.trades.partition_point(|trade| trade.time <= time);
+            self.trades.insert(
+                position,
+                TradeSample {
+                    trade_id: entry.trade_id.clone(),
+                    time,
+                    price,
+                    size,
+                },
+            );
+        }
+        self.prune(received_at);
+    }
+
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - self.window;
+        if self.trades.iter().any(|trade| trade.time < cutoff) {
+            self.trades.retain(|trade| trade.time >= cutoff);
+            // Ids are only needed while a repeated trade could still be in the window
+            self.trade_ids = self
+                .trades
+                .iter()
+                .filter_map(|trade| trade.trade_id.clone())
+                .collect();
+        }
+        // Keep the latest sample so the current value stays known
+        while self.funding.len() > 1 && self.funding[0].time < cutoff {
+            self.funding.pop_front();
+        }
+        while self.open_interest.len() > 1 && self.open_interest[0].0 < cutoff {
+            self.open_interest.pop_front();
+        }
+    }
+}
+
+/// Statistics of every instrument with market data
+#[derive(Debug, Clone)]
+pub struct MarketStatsTracker {
+    window: TimeDelta,
+    stats: HashMap<String, MarketStats>,
+}
+
+impl Default for MarketStatsTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATS_WINDOW)
+    }
+}
+
+impl MarketStatsTracker {
+    /// Create an empty tracker keeping `window` of history per instrument
+    pub fn new(window: TimeDelta) -> Self {
+        Self {
+            window,
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Statistics of an instrument, if market data has been received for it
+    pub fn stats(&self, symbol: &str) -> Option<&MarketStats> {
+        self.stats.get(symbol)
+    }
+
+    /// Apply a snapshot received at `received_at`
+    pub fn apply_snapshot(
+        &mut self,
+        snapshot: &MarketDataSnapshotFullRefresh,
+        received_at: DateTime<Utc>,
+    ) {
+        self.entry(&snapshot.symbol)
+            .apply_snapshot(snapshot, received_at);
+    }
+
+    /// Apply an incremental refresh received at `received_at`
+    pub fn apply_incremental(
+        &mut self,
+        update: &MarketDataIncrementalRefresh,
+        received_at: DateTime<Utc>,
+    ) {
+        self.entry(&update.symbol)
+            .apply_incremental(update, received_at);
+    }
+
+    fn entry(&mut self, symbol: &str) -> &mut MarketStats {
+        let window = self.window;
+        self.stats
+            .entry(symbol.to_string())
+            .or_insert_with(|| MarketStats::new(symbol.to_string(), window))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(price: f64, size: f64, id: &str, time: DateTime<Utc>) -> MdEntry {
+        MdEntry::trade(price, size, 'B', id.to_string(), time)
+    }
+
+    #[test]
+    fn test_vwap_over_rolling_window() {
+        let now = Utc::now();
+        let mut tracker = MarketStatsTracker::new(TimeDelta::hours(1));
+        let update =
+            MarketDataIncrementalRefresh::new("BTC-PERPETUAL".to_string()).with_entries(vec![
+                trade(90.0, 5.0, "1", now - TimeDelta::hours(2)),
+                trade(100.0, 1.0, "2", now - TimeDelta::minutes(30)),
+                trade(110.0, 3.0, "3", now),
+                MdEntry::bid(99.0, 10.0),
+            ]);
+        tracker.apply_incremental(&update, now);
+
+        let stats = tracker.stats("BTC-PERPETUAL").unwrap();
+        assert_eq!(stats.rolling_volume(), 4.0);
+        assert_eq!(stats.vwap(), Some(107.5));
+        assert_eq!(stats.trades().count(), 2);
+
+        // A snapshot repeating a trade does not count it twice
+        let snapshot = MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string())
+            .with_entries(vec![trade(110.0, 3.0, "3", now)]);
+        tracker.apply_snapshot(&snapshot, now);
+        assert_eq!(
+            tracker.stats("BTC-PERPETUAL").unwrap().rolling_volume(),
+            4.0
+        );
+
+        tracker.apply_incremental(
+            &MarketDataIncrementalRefresh::new("BTC-PERPETUAL".to_string()),
+            now + TimeDelta::hours(2),
+        );
+        let stats = tracker.stats("BTC-PERPETUAL").unwrap();
+        assert_eq!(stats.vwap(), None);
+        assert_eq!(stats.rolling_volume(), 0.0);
+    }
+
+    #[test]
+    fn test_funding_and_open_interest_history() {
+        let start = Utc::now();
+        let mut tracker = MarketStatsTracker::default();
+        let snapshot = |funding: f64, open_interest: f64| {
+            MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string())
+                .with_trade_volume_24h(1500.0)
+                .with_current_funding(funding)
+                .with_funding_8h(funding * 8.0)
+                .with_open_interest(open_interest)
+        };
+
+        tracker.apply_snapshot(&snapshot(0.0001, 100.0), start);
+        tracker.apply_snapshot(&snapshot(0.0001, 100.0), start + TimeDelta::minutes(1));
+        tracker.apply_snapshot(&snapshot(0.0002, 120.0), start + TimeDelta::minutes(2));
+
+        let stats = tracker.stats("BTC-PERPETUAL").unwrap();
+        assert_eq!(stats.volume_24h, Some(1500.0));
+        assert_eq!(stats.funding_history().count(), 2);
+        let last = stats.last_funding().unwrap();
+        assert_eq!(last.current_funding, Some(0.0002));
+        assert_eq!(last.funding_8h, Some(0.0016));
+        assert_eq!(last.time, start + TimeDelta::minutes(2));
+        assert_eq!(stats.open_interest(), Some(120.0));
+        assert_eq!(stats.open_interest_series().count(), 2);
+
+        // The latest sample survives the window
+        tracker.apply_snapshot(
+            &MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string()),
+            start + TimeDelta::days(2),
+        );
+        let stats = tracker.stats("BTC-PERPETUAL").unwrap();
+        assert_eq!(stats.funding_history().count(), 1);
+        assert_eq!(stats.open_interest(), Some(120.0));
+        assert!(tracker.stats("ETH-PERPETUAL").is_none());
+    }
+}
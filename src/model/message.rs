@@ -4,6 +4,8 @@
    Date: 21/7/25
 ******************************************************************************/
 use crate::DeribitFixError;
+use crate::message::printer::{FixPrettyPrinter, REDACTED};
+use crate::model::tags;
 use crate::model::types::MsgType;
 use std::str::FromStr;
 
@@ -67,22 +69,22 @@ impl FixMessage {
 
     /// Get message type
     pub fn msg_type(&self) -> Option<MsgType> {
-        self.get_field(35).and_then(|s| s.parse().ok())
+        self.get_field(tags::MSG_TYPE).and_then(|s| s.parse().ok())
     }
 
     /// Get sender company ID
     pub fn sender_comp_id(&self) -> Option<&String> {
-        self.get_field(49)
+        self.get_field(tags::SENDER_COMP_ID)
     }
 
     /// Get target company ID
     pub fn target_comp_id(&self) -> Option<&String> {
-        self.get_field(56)
+        self.get_field(tags::TARGET_COMP_ID)
     }
 
     /// Get message sequence number
     pub fn msg_seq_num(&self) -> Option<u32> {
-        self.get_field(34)?.parse().ok()
+        self.get_field(tags::MSG_SEQ_NUM)?.parse().ok()
     }
 
     /// Check if a field exists
@@ -140,13 +142,24 @@ impl std::fmt::Display for FixMessage {
 }
 
 impl std::fmt::Debug for FixMessage {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Create a readable version by replacing SOH (\x01) with " | "
-        let mut readable_message = self.raw_message.replace('\x01', " | ");
-        // Remove trailing separator if present
-        if readable_message.ends_with(" | ") {
-            readable_message.truncate(readable_message.len() - 3);
-        }
+    fn fmt<'a>(&'a self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Sensitive values (Password, RawData, ...) are never written to debug output
+        let printer = FixPrettyPrinter::new();
+        let redact = |tag: u32, value: &'a str| -> &'a str {
+            if printer.is_redacted(tag) {
+                REDACTED
+            } else {
+                value
+            }
+        };
+
+        // Create a readable version of the message with " | " separators
+        let readable_message = self
+            .fields
+            .iter()
+            .map(|(tag, value)| format!("{tag}={}", redact(*tag, value)))
+            .collect::<Vec<_>>()
+            .join(" | ");
 
         // Get common FIX field names for better readability
         let mut field_descriptions = Vec::new();
@@ -181,7 +194,7 @@ impl std::fmt::Debug for FixMessage {
                 721 => "PosMaintRptID",
                 _ => "Unknown",
             };
-            field_descriptions.push(format!("{field_name}({tag})={value}"));
+            field_descriptions.push(format!("{field_name}({tag})={}", redact(*tag, value)));
         }
 
         f.debug_struct("FixMessage")
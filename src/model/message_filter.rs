@@ -0,0 +1,284 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Filtered message streams
+//!
+//! Every message the session receives is published to its subscribers.
+//! [`MsgFilter`] describes the messages a consumer is interested in, by
+//! message type and field values, so that it need not match on raw message
+//! types itself:
+//!
+//! ```
+//! use deribit_fix::model::message_filter::MsgFilter;
+//! use deribit_fix::model::types::ExecType;
+//!
+//! let fills = MsgFilter::exec_reports()
+//!     .symbol("BTC-PERPETUAL")
+//!     .exec_type(ExecType::Trade);
+//! ```
+//!
+//! A [`MessageStream`] reads the published messages and only returns those
+//! its filter accepts.
+
+use crate::message::OrderStatus;
+use crate::model::message::FixMessage;
+use crate::model::request::OrderSide;
+use crate::model::tags;
+use crate::model::types::{ExecType, MsgType};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Condition a message must meet to pass a [`MsgFilter`]
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    /// MsgType (35) is one of these
+    MsgType(Vec<MsgType>),
+    /// The field has this value
+    Field(u32, String),
+    /// The field is present
+    HasField(u32),
+    /// At least one of the filters accepts the message
+    Any(Vec<MsgFilter>),
+    /// The filter rejects the message
+    Not(Box<MsgFilter>),
+}
+
+impl Condition {
+    fn matches(&self, message: &FixMessage) -> bool {
+        match self {
+            Condition::MsgType(msg_types) => message
+                .msg_type()
+                .is_some_and(|msg_type| msg_types.contains(&msg_type)),
+            Condition::Field(tag, value) => message.get_field(*tag) == Some(value),
+            Condition::HasField(tag) => message.get_field(*tag).is_some(),
+            Condition::Any(filters) => filters.iter().any(|filter| filter.matches(message)),
+            Condition::Not(filter) => !filter.matches(message),
+        }
+    }
+}
+
+/// Predicate over received messages, built from conditions that must all hold
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MsgFilter {
+    conditions: Vec<Condition>,
+}
+
+impl MsgFilter {
+    /// Filter accepting every message
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Messages of any of `msg_types`
+    pub fn msg_types(msg_types: impl IntoIterator<Item = MsgType>) -> Self {
+        Self::all().with(Condition::MsgType(msg_types.into_iter().collect()))
+    }
+
+    /// Execution Reports (8)
+    pub fn exec_reports() -> Self {
+        Self::msg_types([MsgType::ExecutionReport])
+    }
+
+    /// Order Cancel Rejects (9)
+    pub fn cancel_rejects() -> Self {
+        Self::msg_types([MsgType::OrderCancelReject])
+    }
+
+    /// Market Data Snapshots (W) and Incremental Refreshes (X)
+    pub fn market_data() -> Self {
+        Self::msg_types([
+            MsgType::MarketDataSnapshotFullRefresh,
+            MsgType::MarketDataIncrementalRefresh,
+        ])
+    }
+
+    /// Only messages where field `tag` is `value`
+    pub fn field(self, tag: u32, value: impl Into<String>) -> Self {
+        self.with(Condition::Field(tag, value.into()))
+    }
+
+    /// Only messages carrying field `tag`
+    pub fn has_field(self, tag: u32) -> Self {
+        self.with(Condition::HasField(tag))
+    }
+
+    /// Only messages for the instrument `symbol` (55)
+    pub fn symbol(self, symbol: impl Into<String>) -> Self {
+        self.field(tags::SYMBOL, symbol)
+    }
+
+    /// Only messages with ExecType (150) `exec_type`
+    pub fn exec_type(self, exec_type: ExecType) -> Self {
+        self.field(tags::EXEC_TYPE, char::from(exec_type).to_string())
+    }
+
+    /// Only messages with OrdStatus (39) `status`
+    pub fn ord_status(self, status: OrderStatus) -> Self {
+        self.field(tags::ORD_STATUS, char::from(status).to_string())
+    }
+
+    /// Only messages for the `side` (54)
+    pub fn side(self, side: OrderSide) -> Self {
+        let side = match side {
+            OrderSide::Buy => "1",
+            OrderSide::Sell => "2",
+        };
+        self.field(tags::SIDE, side)
+    }
+
+    /// Only messages of the order with ClOrdID (11) `cl_ord_id`
+    pub fn cl_ord_id(self, cl_ord_id: impl Into<String>) -> Self {
+        self.field(tags::CL_ORD_ID, cl_ord_id)
+    }
+
+    /// Only messages carrying the DeribitLabel (100010) `label`
+    pub fn label(self, label: impl Into<String>) -> Self {
+        self.field(tags::DERIBIT_LABEL, label)
+    }
+
+    /// Messages accepted by this filter or by `other`
+    pub fn or(self, other: MsgFilter) -> Self {
+        Self::all().with(Condition::Any(vec![self, other]))
+    }
+
+    /// Only messages also accepted by `other`
+    pub fn and(mut self, other: MsgFilter) -> Self {
+        self.conditions.extend(other.conditions);
+        self
+    }
+
+    /// Only messages `other` does not accept
+    pub fn except(self, other: MsgFilter) -> Self {
+        self.with(Condition::Not(Box::new(other)))
+    }
+
+    /// Whether `message` passes the filter
+    pub fn matches(&self, message: &FixMessage) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(message))
+    }
+
+    fn with(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+}
+
+/// Received messages accepted by a [`MsgFilter`]
+///
+/// The stream belongs to the session it was opened on and ends on
+/// disconnect or failover.
+#[derive(Debug)]
+pub struct MessageStream {
+    messages: broadcast::Receiver<FixMessage>,
+    filter: MsgFilter,
+}
+
+impl MessageStream {
+    /// Stream of every message published on `messages`
+    pub fn new(messages: broadcast::Receiver<FixMessage>) -> Self {
+        Self {
+            messages,
+            filter: MsgFilter::all(),
+        }
+    }
+
+    /// Only return the messages `filter` accepts, on top of the current filter
+    pub fn filter(mut self, filter: MsgFilter) -> Self {
+        self.filter = self.filter.and(filter);
+        self
+    }
+
+    /// Filter of the stream
+    pub fn current_filter(&self) -> &MsgFilter {
+        &self.filter
+    }
+
+    /// Wait for the next accepted message, `None` once the session closed
+    ///
+    /// Messages missed because the stream was not read fast enough are
+    /// logged and skipped.
+    pub async fn recv(&mut self) -> Option<FixMessage> {
+        loop {
+            match self.messages.recv().await {
+                Ok(message) if self.filter.matches(&message) => return Some(message),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Message stream skipped {} messages", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageBuilder;
+
+    fn message(msg_type: MsgType, fields: &[(u32, &str)]) -> FixMessage {
+        fields
+            .iter()
+            .fold(
+                MessageBuilder::new()
+                    .msg_type(msg_type)
+                    .sender_comp_id("DERIBIT".to_string())
+                    .target_comp_id("CLIENT".to_string())
+                    .msg_seq_num(1),
+                |builder, (tag, value)| builder.field(*tag, value.to_string()),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_conditions_must_all_hold() {
+        let fills = MsgFilter::exec_reports()
+            .symbol("BTC-PERPETUAL")
+            .exec_type(ExecType::Trade);
+        let fill = message(
+            MsgType::ExecutionReport,
+            &[(tags::SYMBOL, "BTC-PERPETUAL"), (tags::EXEC_TYPE, "F")],
+        );
+        assert!(fills.matches(&fill));
+        assert!(!fills.matches(&message(
+            MsgType::ExecutionReport,
+            &[(tags::SYMBOL, "ETH-PERPETUAL"), (tags::EXEC_TYPE, "F")],
+        )));
+        assert!(!fills.matches(&message(
+            MsgType::ExecutionReport,
+            &[(tags::SYMBOL, "BTC-PERPETUAL"), (tags::EXEC_TYPE, "0")],
+        )));
+        assert!(!fills.matches(&message(
+            MsgType::MarketDataIncrementalRefresh,
+            &[(tags::SYMBOL, "BTC-PERPETUAL")],
+        )));
+        assert!(MsgFilter::all().matches(&fill));
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        let order_events = MsgFilter::exec_reports()
+            .or(MsgFilter::cancel_rejects())
+            .label("mm")
+            .except(MsgFilter::all().ord_status(OrderStatus::Rejected));
+        assert!(order_events.matches(&message(
+            MsgType::OrderCancelReject,
+            &[(tags::DERIBIT_LABEL, "mm")],
+        )));
+        assert!(order_events.matches(&message(
+            MsgType::ExecutionReport,
+            &[(tags::DERIBIT_LABEL, "mm"), (tags::ORD_STATUS, "0")],
+        )));
+        assert!(!order_events.matches(&message(
+            MsgType::ExecutionReport,
+            &[(tags::DERIBIT_LABEL, "mm"), (tags::ORD_STATUS, "8")],
+        )));
+        assert!(!order_events.matches(&message(MsgType::OrderCancelReject, &[])));
+    }
+}
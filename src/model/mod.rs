@@ -4,20 +4,118 @@
    Date: 21/7/25
 ******************************************************************************/
 
+/// Account balance and margin summary
+pub mod account;
+/// Typed order cancel targets and reports
+pub mod cancel;
+/// Server capabilities detected at logon
+pub mod capabilities;
+/// Combo (multi-leg) instruments and orders
+pub mod combo;
+/// Order execution instructions
+pub mod exec_inst;
+/// Funding rate polling of perpetuals
+pub mod funding;
+/// Overall session health and its thresholds
+pub mod health;
+/// Index value and settlement price stream
+pub mod index_stream;
+/// Deribit instrument name parsing and formatting
+pub mod instrument;
+/// Instrument metadata and quantity conversions
+pub mod instrument_registry;
+/// Execution Report routing by order label
+pub mod label_routing;
+/// Limit order ladders around a reference price
+pub mod ladder;
+/// Order-entry latency tracing
+pub mod latency;
+/// Instrument trading state and maintenance tracking
+pub mod market_state;
+/// Per-instrument statistics derived from market data
+pub mod market_stats;
 /// FIX message structures
 pub mod message;
+/// Filtered message streams
+pub mod message_filter;
+/// FIX message types, generated from the FIX dictionary
+mod msg_type;
+/// Instrument precision of outgoing prices and quantities
+pub mod number_format;
+/// Option tickers with implied volatility and greeks
+pub mod option_ticker;
+/// Local order book built from market data
+pub mod order_book;
+/// Client-side OCO order groups
+pub mod order_group;
+/// Persistent order ownership surviving restarts
+pub mod order_index;
+/// Named order templates
+pub mod order_template;
+/// Order lifecycle tracking and audit export
+pub mod order_tracker;
+/// Simulated order execution against live market data
+pub mod paper_trading;
 /// Position model types
 pub mod position;
+/// Public trades from market data
+pub mod public_trade;
+/// Two-sided quoting with a validity window
+pub mod quoting;
 /// Order request model types
 pub mod request;
+/// Pre-trade risk checks
+pub mod risk;
 /// Network stream handling
 pub mod stream;
+/// Market data subscriptions
+pub mod subscription;
+/// Translation between internal identifiers and Deribit symbols
+pub mod symbol_map;
 /// FIX protocol tags
 pub mod tags;
+/// Adaptive pacing of outgoing messages
+pub mod throttle;
+/// Best bid and offer from top-of-book market data
+pub mod top_of_book;
+/// Own trade stream from Trade Capture Reports
+pub mod trade_stream;
 /// FIX message types and enums
 pub mod types;
 
+pub use account::*;
+pub use cancel::*;
+pub use capabilities::*;
+pub use combo::*;
+pub use exec_inst::*;
+pub use funding::*;
+pub use health::*;
+pub use index_stream::*;
+pub use instrument::*;
+pub use instrument_registry::*;
+pub use label_routing::*;
+pub use ladder::*;
+pub use latency::*;
+pub use market_state::*;
+pub use market_stats::*;
 pub use message::FixMessage;
+pub use message_filter::*;
+pub use number_format::*;
+pub use option_ticker::*;
+pub use order_book::*;
+pub use order_group::*;
+pub use order_index::*;
+pub use order_template::*;
+pub use order_tracker::*;
+pub use paper_trading::*;
 pub use position::*;
+pub use public_trade::*;
+pub use quoting::*;
 pub use request::NewOrderRequest;
+pub use risk::*;
+pub use subscription::*;
+pub use symbol_map::*;
+pub use throttle::*;
+pub use top_of_book::*;
+pub use trade_stream::*;
 pub use types::*;
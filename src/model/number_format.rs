@@ -0,0 +1,197 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Instrument precision of outgoing prices and quantities
+//!
+//! Prices and amounts are `f64`s, whose default text form can carry more
+//! decimals than the instrument accepts, such as `0.30000000000000004`, and
+//! the exchange rejects those. Before a message is sent, the session rewrites
+//! its prices and quantities with the [`NumberFormat`] set with
+//! [`with_number_format`](crate::config::DeribitFixConfig::with_number_format),
+//! looking up the instrument of each in the session's
+//! [`InstrumentRegistry`]. The default, [`InstrumentPrecision`], writes
+//! prices with the decimals of the tick size and quantities with those of
+//! the amount step. Values of instruments the registry does not know are
+//! sent as they are.
+
+use crate::error::Result;
+use crate::message::MessageBuilder;
+use crate::model::instrument_registry::{InstrumentRegistry, InstrumentSpec};
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use std::fmt;
+use std::sync::Arc;
+
+/// Tags carrying a price of the instrument
+pub const PRICE_TAGS: [u32; 5] = [
+    tags::PRICE,
+    tags::STOP_PX,
+    tags::BID_PX,
+    tags::OFFER_PX,
+    tags::PEG_OFFSET_VALUE,
+];
+
+/// Tags carrying an amount of the instrument
+pub const QUANTITY_TAGS: [u32; 5] = [
+    tags::ORDER_QTY,
+    tags::MIN_QTY,
+    tags::BID_SIZE,
+    tags::OFFER_SIZE,
+    tags::DISPLAY_QTY,
+];
+
+/// Text form of the prices and quantities of an instrument
+pub trait NumberFormat: fmt::Debug + Send + Sync {
+    /// Text of `price` of `instrument`, `None` to send it unchanged
+    fn format_price(&self, instrument: &InstrumentSpec, price: f64) -> Option<String>;
+
+    /// Text of the amount `quantity` of `instrument`, `None` to send it
+    /// unchanged
+    fn format_quantity(&self, instrument: &InstrumentSpec, quantity: f64) -> Option<String>;
+}
+
+/// [`NumberFormat`] writing prices with the decimals of the tick size and
+/// quantities with those of the amount step
+///
+/// Trailing zeros are dropped, so `65000.0` is still written `65000`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InstrumentPrecision;
+
+impl NumberFormat for InstrumentPrecision {
+    fn format_price(&self, instrument: &InstrumentSpec, price: f64) -> Option<String> {
+        Some(format_decimals(price, instrument.price_decimals()?))
+    }
+
+    fn format_quantity(&self, instrument: &InstrumentSpec, quantity: f64) -> Option<String> {
+        Some(format_decimals(quantity, instrument.amount_decimals()?))
+    }
+}
+
+/// The default number format of a configuration
+pub fn instrument_precision() -> Arc<dyn NumberFormat> {
+    Arc::new(InstrumentPrecision)
+}
+
+/// `value` rounded to `decimals` decimals, without trailing zeros
+pub fn format_decimals(value: f64, decimals: usize) -> String {
+    let text = format!("{value:.decimals$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+/// Rebuild `message` with its prices and quantities written by `format`
+///
+/// Each value belongs to the instrument of the last Symbol (55) before it,
+/// so the entries of a Mass Quote (i) are formatted with their own
+/// instrument. Values ahead of any Symbol, such as the OrderQty (38) and
+/// Price (44) of a New Order Single (D), belong to the first Symbol of the
+/// message. Returns `None` when no value changed, so that the message
+/// need not be rebuilt.
+pub fn format_numbers(
+    message: &FixMessage,
+    instruments: &InstrumentRegistry,
+    format: &dyn NumberFormat,
+) -> Result<Option<FixMessage>> {
+    if instruments.is_empty() {
+        return Ok(None);
+    }
+    let mut formatted = message.clone();
+    let mut changed = false;
+    let mut instrument = message
+        .fields
+        .iter()
+        .find(|(tag, _)| *tag == tags::SYMBOL)
+        .and_then(|(_, symbol)| instruments.get(symbol));
+    for (tag, value) in &mut formatted.fields {
+        if *tag == tags::SYMBOL {
+            instrument = instruments.get(value);
+            continue;
+        }
+        let (Some(spec), Ok(number)) = (instrument, value.parse::<f64>()) else {
+            continue;
+        };
+        let text = if PRICE_TAGS.contains(tag) {
+            format.format_price(spec, number)
+        } else if QUANTITY_TAGS.contains(tag) {
+            format.format_quantity(spec, number)
+        } else {
+            None
+        };
+        if let Some(text) = text
+            && text != *value
+        {
+            *value = text;
+            changed = true;
+        }
+    }
+    if !changed {
+        return Ok(None);
+    }
+    MessageBuilder::from_message(&formatted).build().map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimals_are_rounded_and_trimmed() {
+        assert_eq!(format_decimals(0.1 + 0.2, 4), "0.3");
+        assert_eq!(format_decimals(65000.0, 1), "65000");
+        assert_eq!(format_decimals(65000.26, 1), "65000.3");
+        assert_eq!(format_decimals(-12.5, 2), "-12.5");
+        assert_eq!(format_decimals(-0.0001, 2), "0");
+        assert_eq!(format_decimals(1250.0, 0), "1250");
+    }
+
+    #[test]
+    fn test_values_are_formatted_per_instrument() {
+        let mut instruments = InstrumentRegistry::new();
+        instruments.register(
+            InstrumentSpec::new("BTC-PERPETUAL".to_string())
+                .with_contract_multiplier(10.0)
+                .with_min_price_increment(0.5),
+        );
+        instruments.register(
+            InstrumentSpec::new("ETH-10OCT26-2500-C".to_string())
+                .with_min_trade_vol(0.01)
+                .with_price_precision(4),
+        );
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=i\x0149=CLIENT\x0156=DERIBIT\x0134=2\x01\
+             52=20260101-00:00:00.000\x01295=3\x01\
+             55=BTC-PERPETUAL\x01132=65000.00000000001\x01134=100\x01\
+             55=ETH-10OCT26-2500-C\x01132=0.030000000000000002\x01134=1.2300000000000002\x01\
+             55=SOL-PERPETUAL\x01132=150.123456\x0110=000\x01",
+        )
+        .unwrap();
+
+        let formatted = format_numbers(&message, &instruments, &InstrumentPrecision)
+            .unwrap()
+            .unwrap();
+        let values: Vec<&str> = formatted
+            .fields
+            .iter()
+            .filter(|(tag, _)| *tag == tags::BID_PX || *tag == tags::BID_SIZE)
+            .map(|(_, value)| value.as_str())
+            .collect();
+        assert_eq!(values, ["65000", "100", "0.03", "1.23", "150.123456"]);
+        assert!(formatted.raw_message.contains("132=0.03\x01"));
+
+        assert!(
+            format_numbers(&formatted, &instruments, &InstrumentPrecision)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,456 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Option tickers from market data
+//!
+//! Deribit's Market Data Snapshot/Full Refresh (W) of an option carries its
+//! UnderlyingSymbol (311), UnderlyingPx (810), MarkPrice (100090),
+//! OpenInterest (746) and TradeVolume24h (100087) next to the book entries.
+//! The FIX API documents no tag for the implied volatility or the greeks, so
+//! [`OptionTicker`] derives them from the mark, bid and ask prices with the
+//! Black-76 model Deribit uses: the underlying price is taken as the forward,
+//! the rate is zero and options expire at 08:00 UTC. Prices of inverse
+//! options, quoted in the base currency, are converted at the underlying
+//! price first.
+//!
+//! [`OptionTickerStreams`] delivers the tickers of each subscribed option to
+//! its receivers, the same way as the
+//! [`IndexStreams`](crate::model::index_stream::IndexStreams).
+
+use crate::message::{MarketDataSnapshotFullRefresh, MdEntryType, PutOrCall};
+use crate::model::instrument::{InstrumentKind, InstrumentName};
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use tokio::sync::broadcast;
+
+/// Hour of the day, UTC, at which Deribit options expire
+pub const OPTION_EXPIRY_HOUR: u32 = 8;
+
+/// Default number of tickers a receiver may fall behind by
+pub const DEFAULT_OPTION_TICKER_BACKLOG: usize = 1024;
+
+/// Seconds in the year used for the time to expiry
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+/// Bounds and tolerance of the implied volatility search
+const MIN_VOLATILITY: f64 = 1e-4;
+const MAX_VOLATILITY: f64 = 10.0;
+const VOLATILITY_TOLERANCE: f64 = 1e-8;
+
+/// Black-76 sensitivities of an option
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OptionGreeks {
+    /// Change of the price per unit change of the underlying
+    pub delta: f64,
+    /// Change of the delta per unit change of the underlying
+    pub gamma: f64,
+    /// Change of the price, in the quote currency, per volatility point
+    pub vega: f64,
+    /// Change of the price, in the quote currency, per calendar day
+    pub theta: f64,
+}
+
+impl OptionGreeks {
+    /// Greeks of an option on `forward` with `volatility` as a fraction and
+    /// `years` to expiry
+    ///
+    /// The delta is NaN for an option type this version does not know.
+    pub fn black76(
+        forward: f64,
+        strike: f64,
+        years: f64,
+        volatility: f64,
+        option_type: PutOrCall,
+    ) -> Self {
+        let root = years.sqrt();
+        let d1 =
+            ((forward / strike).ln() + volatility * volatility * years / 2.0) / (volatility * root);
+        let density = normal_pdf(d1);
+        Self {
+            delta: match option_type {
+                PutOrCall::Call => normal_cdf(d1),
+                PutOrCall::Put => normal_cdf(d1) - 1.0,
+                PutOrCall::Unknown(_) => f64::NAN,
+            },
+            gamma: density / (forward * volatility * root),
+            vega: forward * density * root / 100.0,
+            theta: -forward * density * volatility / (2.0 * root) / 365.0,
+        }
+    }
+}
+
+/// Black-76 price of an option in the quote currency
+///
+/// NaN for an option type this version does not know.
+pub fn black76_price(
+    forward: f64,
+    strike: f64,
+    years: f64,
+    volatility: f64,
+    option_type: PutOrCall,
+) -> f64 {
+    let spread = volatility * years.sqrt();
+    let d1 = ((forward / strike).ln() + spread * spread / 2.0) / spread;
+    let d2 = d1 - spread;
+    match option_type {
+        PutOrCall::Call => forward * normal_cdf(d1) - strike * normal_cdf(d2),
+        PutOrCall::Put => strike * normal_cdf(-d2) - forward * normal_cdf(-d1),
+        PutOrCall::Unknown(_) => f64::NAN,
+    }
+}
+
+/// Volatility, as a fraction, at which the Black-76 price is `price`
+///
+/// `None` when no volatility between 0.01% and 1000% gives the price, such as
+/// a price below the intrinsic value, or for an option type this version does
+/// not know.
+pub fn implied_volatility(
+    price: f64,
+    forward: f64,
+    strike: f64,
+    years: f64,
+    option_type: PutOrCall,
+) -> Option<f64> {
+    if !(price > 0.0 && forward > 0.0 && strike > 0.0 && years > 0.0)
+        || matches!(option_type, PutOrCall::Unknown(_))
+    {
+        return None;
+    }
+    let at = |volatility| black76_price(forward, strike, years, volatility, option_type);
+    let (mut low, mut high) = (MIN_VOLATILITY, MAX_VOLATILITY);
+    if price < at(low) || price > at(high) {
+        return None;
+    }
+    // The price grows with the volatility, so bisection always converges
+    while high - low > VOLATILITY_TOLERANCE {
+        let middle = (low + high) / 2.0;
+        if at(middle) < price {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+/// Standard normal distribution, from the complementary error function of
+/// Numerical Recipes (relative error below 1.2e-7)
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + z / 2.0);
+    let erfc = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 {
+        1.0 - erfc / 2.0
+    } else {
+        erfc / 2.0
+    }
+}
+
+/// Mark, quotes, implied volatilities and greeks of an option
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionTicker {
+    /// Option instrument
+    pub symbol: String,
+    /// Call or put
+    pub option_type: PutOrCall,
+    /// Strike price
+    pub strike: f64,
+    /// Expiry time
+    pub expiry: DateTime<Utc>,
+    /// UnderlyingSymbol (311), the future or index the option is priced on
+    pub underlying_symbol: Option<String>,
+    /// UnderlyingPx (810)
+    pub underlying_price: Option<f64>,
+    /// MarkPrice (100090), in the currency the option is quoted in
+    pub mark_price: Option<f64>,
+    /// Best bid price and size
+    pub best_bid: Option<(f64, f64)>,
+    /// Best ask price and size
+    pub best_ask: Option<(f64, f64)>,
+    /// Price of the last trade in the snapshot
+    pub last_price: Option<f64>,
+    /// OpenInterest (746)
+    pub open_interest: Option<f64>,
+    /// TradeVolume24h (100087)
+    pub volume_24h: Option<f64>,
+    /// Implied volatility of the mark price, in percent
+    pub mark_iv: Option<f64>,
+    /// Implied volatility of the best bid, in percent
+    pub bid_iv: Option<f64>,
+    /// Implied volatility of the best ask, in percent
+    pub ask_iv: Option<f64>,
+    /// Greeks at the mark implied volatility
+    pub greeks: Option<OptionGreeks>,
+    /// Time the snapshot was received
+    pub timestamp: DateTime<Utc>,
+}
+
+impl OptionTicker {
+    /// Ticker of an option snapshot, `None` if the instrument is not an option
+    pub fn from_snapshot(
+        snapshot: &MarketDataSnapshotFullRefresh,
+        received_at: DateTime<Utc>,
+    ) -> Option<Self> {
+        let name: InstrumentName = snapshot.symbol.parse().ok()?;
+        let InstrumentKind::Option {
+            expiry,
+            strike,
+            option_type,
+        } = name.kind
+        else {
+            return None;
+        };
+        let expiry = expiry
+            .and_time(NaiveTime::from_hms_opt(OPTION_EXPIRY_HOUR, 0, 0)?)
+            .and_utc();
+
+        let level = |entry_type| {
+            snapshot
+                .entries
+                .iter()
+                .filter(move |entry| entry.md_entry_type == entry_type)
+                .filter_map(|entry| Some((entry.md_entry_px?, entry.md_entry_size.unwrap_or(0.0))))
+        };
+        let best_bid = level(MdEntryType::Bid).max_by(|a, b| a.0.total_cmp(&b.0));
+        let best_ask = level(MdEntryType::Offer).min_by(|a, b| a.0.total_cmp(&b.0));
+        let last_price = level(MdEntryType::Trade)
+            .next_back()
+            .map(|(price, _)| price);
+
+        let forward = snapshot.underlying_px.filter(|price| *price > 0.0);
+        let years = (expiry - received_at).num_milliseconds() as f64 / 1000.0 / SECONDS_PER_YEAR;
+        let linear = name.is_linear();
+        let volatility = |price: Option<f64>| {
+            let forward = forward?;
+            let price = if linear { price? } else { price? * forward };
+            implied_volatility(price, forward, strike, years, option_type)
+        };
+        let mark_volatility = volatility(snapshot.mark_price);
+        let percent = |volatility: Option<f64>| volatility.map(|volatility| volatility * 100.0);
+
+        Some(Self {
+            symbol: snapshot.symbol.clone(),
+            option_type,
+            strike,
+            expiry,
+            underlying_symbol: snapshot.underlying_symbol.clone(),
+            underlying_price: snapshot.underlying_px,
+            mark_price: snapshot.mark_price,
+            best_bid,
+            best_ask,
+            last_price,
+            open_interest: snapshot.open_interest,
+            volume_24h: snapshot.trade_volume_24h,
+            mark_iv: percent(mark_volatility),
+            bid_iv: percent(volatility(best_bid.map(|(price, _)| price))),
+            ask_iv: percent(volatility(best_ask.map(|(price, _)| price))),
+            greeks: forward.zip(mark_volatility).map(|(forward, volatility)| {
+                OptionGreeks::black76(forward, strike, years, volatility, option_type)
+            }),
+            timestamp: received_at,
+        })
+    }
+}
+
+/// Delivers option tickers to per-instrument channels
+#[derive(Debug)]
+pub struct OptionTickerStreams {
+    channels: HashMap<String, Vec<broadcast::Sender<OptionTicker>>>,
+    /// Instrument of each ticker subscription, by MDReqID (262)
+    requests: HashMap<String, String>,
+    /// Last ticker of each subscribed instrument
+    latest: HashMap<String, OptionTicker>,
+    /// Tickers each receiver may fall behind by
+    backlog: usize,
+    /// Tickers dropped because a receiver fell behind
+    dropped: u64,
+}
+
+impl Default for OptionTickerStreams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptionTickerStreams {
+    /// Create streams without receivers, each receiver holding up to
+    /// [`DEFAULT_OPTION_TICKER_BACKLOG`] tickers
+    pub fn new() -> Self {
+        Self::with_backlog(DEFAULT_OPTION_TICKER_BACKLOG)
+    }
+
+    /// Create streams without receivers, each receiver holding up to
+    /// `backlog` tickers, rounded up to a power of two
+    pub fn with_backlog(backlog: usize) -> Self {
+        Self {
+            channels: HashMap::new(),
+            requests: HashMap::new(),
+            latest: HashMap::new(),
+            backlog: backlog.max(1),
+            dropped: 0,
+        }
+    }
+
+    /// Receive the tickers of `symbol`
+    pub fn subscribe(&mut self, symbol: &str) -> broadcast::Receiver<OptionTicker> {
+        let (sender, receiver) = broadcast::channel(self.backlog);
+        self.channels
+            .entry(symbol.to_string())
+            .or_default()
+            .push(sender);
+        receiver
+    }
+
+    /// Remember that `md_req_id` subscribed to the tickers of `symbol`
+    pub fn track(&mut self, md_req_id: String, symbol: String) {
+        self.requests.insert(md_req_id, symbol);
+    }
+
+    /// Instrument of the ticker subscription with MDReqID `md_req_id`
+    pub fn symbol_of(&self, md_req_id: &str) -> Option<&str> {
+        self.requests.get(md_req_id).map(String::as_str)
+    }
+
+    /// MDReqID (262) of the ticker subscription of `symbol`
+    pub fn request_for(&self, symbol: &str) -> Option<&str> {
+        self.requests
+            .iter()
+            .find(|(_, subscribed)| *subscribed == symbol)
+            .map(|(md_req_id, _)| md_req_id.as_str())
+    }
+
+    /// Forget the ticker subscription of `symbol` and close its receivers
+    ///
+    /// Returns the MDReqID (262) of the subscription.
+    pub fn remove(&mut self, symbol: &str) -> Option<String> {
+        self.channels.remove(symbol);
+        self.latest.remove(symbol);
+        let md_req_id = self.request_for(symbol)?.to_string();
+        self.requests.remove(&md_req_id);
+        Some(md_req_id)
+    }
+
+    /// Instruments with a ticker subscription
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.requests.values().map(String::as_str)
+    }
+
+    /// Last ticker of `symbol`
+    pub fn latest(&self, symbol: &str) -> Option<&OptionTicker> {
+        self.latest.get(symbol)
+    }
+
+    /// Tickers dropped, oldest first, because a receiver fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Publish the ticker of a snapshot of a subscribed option
+    ///
+    /// Returns the number of receivers the ticker was delivered to.
+    pub fn publish(
+        &mut self,
+        snapshot: &MarketDataSnapshotFullRefresh,
+        received_at: DateTime<Utc>,
+    ) -> usize {
+        if self.request_for(&snapshot.symbol).is_none() {
+            return 0;
+        }
+        let Some(ticker) = OptionTicker::from_snapshot(snapshot, received_at) else {
+            return 0;
+        };
+        self.latest.insert(ticker.symbol.clone(), ticker.clone());
+        let Some(senders) = self.channels.get_mut(&ticker.symbol) else {
+            return 0;
+        };
+        let mut dropped = 0;
+        senders.retain(|sender| {
+            let queued = sender.len();
+            if sender.send(ticker.clone()).is_err() {
+                return false;
+            }
+            // A full backlog evicts its oldest ticker instead of growing
+            if sender.len() == queued {
+                dropped += 1;
+            }
+            true
+        });
+        self.dropped += dropped;
+        senders.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MdEntry;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_implied_volatility_inverts_the_price() {
+        let price = black76_price(60_000.0, 65_000.0, 0.25, 0.6, PutOrCall::Call);
+        let volatility = implied_volatility(price, 60_000.0, 65_000.0, 0.25, PutOrCall::Call);
+        assert!((volatility.unwrap() - 0.6).abs() < 1e-6);
+
+        // Put-call parity with a zero rate
+        let put = black76_price(60_000.0, 65_000.0, 0.25, 0.6, PutOrCall::Put);
+        assert!((price - put - (60_000.0 - 65_000.0)).abs() < 1e-6);
+
+        // Below the intrinsic value
+        assert_eq!(
+            implied_volatility(4_000.0, 60_000.0, 65_000.0, 0.25, PutOrCall::Put),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ticker_from_an_inverse_option_snapshot() {
+        let received_at = Utc.with_ymd_and_hms(2026, 3, 27, 8, 0, 0).unwrap();
+        let forward: f64 = 60_000.0;
+        let years = 91.0 / 365.0;
+        let mark = black76_price(forward, 60_000.0, years, 0.5, PutOrCall::Call) / forward;
+        let snapshot = MarketDataSnapshotFullRefresh::new("BTC-26JUN26-60000-C".to_string())
+            .with_underlying_symbol("BTC-26JUN26".to_string())
+            .with_underlying_px(forward)
+            .with_mark_price(mark)
+            .with_entries(vec![
+                MdEntry::bid(mark - 0.001, 5.0),
+                MdEntry::bid(mark - 0.002, 9.0),
+                MdEntry::offer(mark + 0.001, 3.0),
+            ]);
+
+        let ticker = OptionTicker::from_snapshot(&snapshot, received_at).unwrap();
+        assert_eq!(ticker.option_type, PutOrCall::Call);
+        assert_eq!(ticker.strike, 60_000.0);
+        assert_eq!(ticker.best_bid, Some((mark - 0.001, 5.0)));
+        assert!((ticker.mark_iv.unwrap() - 50.0).abs() < 1e-4);
+        assert!(ticker.bid_iv.unwrap() < ticker.mark_iv.unwrap());
+        assert!(ticker.ask_iv.unwrap() > ticker.mark_iv.unwrap());
+
+        let greeks = ticker.greeks.unwrap();
+        assert!(greeks.delta > 0.5 && greeks.delta < 0.6);
+        assert!(greeks.gamma > 0.0 && greeks.vega > 0.0 && greeks.theta < 0.0);
+
+        let future = MarketDataSnapshotFullRefresh::new("BTC-26JUN26".to_string());
+        assert_eq!(OptionTicker::from_snapshot(&future, received_at), None);
+    }
+}
@@ -0,0 +1,754 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Local order book built from market data messages
+//!
+//! The book is seeded from a Market Data Snapshot/Full Refresh (W) and kept up
+//! to date with Market Data Incremental Refresh (X) messages. Every incremental
+//! update is validated: a crossed book, a Change/Delete for a level that does
+//! not exist, or a gap in RptSeq (83) marks the book as inconsistent until a
+//! new snapshot is applied.
+//!
+//! Books created [`with_order_level`](OrderBook::with_order_level) also keep
+//! every resting order when the entries carry an OrderID (37), or a
+//! SecondaryOrderID (198), in the order they joined their price level, so
+//! the position of an order in the queue can be estimated. Price levels are
+//! still aggregated from the orders, and entries without an id update the
+//! levels directly, as in a price-level book.
+
+use crate::message::{
+    MarketDataIncrementalRefresh, MarketDataSnapshotFullRefresh, MdEntry, MdEntryType,
+    MdUpdateAction,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+/// Sizes at or below this are treated as an empty level
+const SIZE_EPSILON: f64 = 1e-9;
+
+/// Price key with a total ordering so prices can be used as map keys
+#[derive(Debug, Clone, Copy)]
+struct PriceKey(f64);
+
+impl PartialEq for PriceKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PriceKey {}
+
+impl PartialOrd for PriceKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriceKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Reason an order book failed validation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BookIntegrityIssue {
+    /// Best bid is at or above best offer
+    Crossed {
+        /// Best bid price
+        best_bid: f64,
+        /// Best offer price
+        best_ask: f64,
+    },
+    /// A Change or Delete referenced a price level that is not in the book
+    MissingLevel {
+        /// Side of the missing level
+        side: MdEntryType,
+        /// Price of the missing level
+        price: f64,
+    },
+    /// A Change or Delete referenced an order that is not in the book
+    MissingOrder {
+        /// Side of the missing order
+        side: MdEntryType,
+        /// OrderID (37) of the missing order
+        order_id: String,
+    },
+    /// RptSeq (83) did not follow the last applied update
+    SequenceGap {
+        /// Next expected RptSeq
+        expected: u64,
+        /// RptSeq received
+        received: u64,
+    },
+    /// A market data message for the book could not be parsed and was dropped
+    Unparsable {
+        /// Parse error
+        reason: String,
+    },
+}
+
+/// Notification about order book integrity and recovery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BookIntegrityEvent {
+    /// The book for `symbol` failed validation and is no longer trusted
+    Violation {
+        /// Instrument symbol
+        symbol: String,
+        /// What failed
+        issue: BookIntegrityIssue,
+    },
+    /// A snapshot was requested to rebuild the book
+    SnapshotRequested {
+        /// Instrument symbol
+        symbol: String,
+        /// MDReqID of the snapshot request
+        md_req_id: String,
+    },
+    /// The book was rebuilt from a fresh snapshot
+    Rebuilt {
+        /// Instrument symbol
+        symbol: String,
+    },
+}
+
+/// Resting order of an order-by-order book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookOrder {
+    /// OrderID (37), or SecondaryOrderID (198), of the order
+    pub order_id: String,
+    /// Book side, [`MdEntryType::Bid`] or [`MdEntryType::Offer`]
+    pub side: MdEntryType,
+    /// Limit price
+    pub price: f64,
+    /// Resting size
+    pub size: f64,
+}
+
+/// Place of an order in the queue of its price level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuePosition {
+    /// Price of the level
+    pub price: f64,
+    /// Orders that joined the level earlier
+    pub orders_ahead: usize,
+    /// Size of the orders that joined the level earlier
+    pub size_ahead: f64,
+    /// Size of the whole level, including entries without an order id
+    pub level_size: f64,
+}
+
+/// Local price-level order book for a single instrument
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    symbol: String,
+    bids: BTreeMap<PriceKey, f64>,
+    asks: BTreeMap<PriceKey, f64>,
+    last_rpt_seq: Option<u64>,
+    recovering: bool,
+    order_level: bool,
+    /// Resting orders by id, when the book is kept order by order
+    orders: HashMap<String, BookOrder>,
+    /// Order ids of each bid level, in the order they joined it
+    bid_queues: BTreeMap<PriceKey, Vec<String>>,
+    /// Order ids of each offer level, in the order they joined it
+    ask_queues: BTreeMap<PriceKey, Vec<String>>,
+}
+
+impl OrderBook {
+    /// Create an empty book for the given instrument
+    pub fn new(symbol: String) -> Self {
+        Self {
+            symbol,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            last_rpt_seq: None,
+            recovering: false,
+            order_level: false,
+            orders: HashMap::new(),
+            bid_queues: BTreeMap::new(),
+            ask_queues: BTreeMap::new(),
+        }
+    }
+
+    /// Keep the book order by order for entries that carry an order id
+    #[must_use]
+    pub fn with_order_level(mut self, order_level: bool) -> Self {
+        self.order_level = order_level;
+        self
+    }
+
+    /// Whether the book keeps individual orders
+    pub fn is_order_level(&self) -> bool {
+        self.order_level
+    }
+
+    /// Instrument symbol
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Bid levels as (price, size), best first
+    pub fn bids(&self) -> Vec<(f64, f64)> {
+        self.bids.iter().rev().map(|(p, s)| (p.0, *s)).collect()
+    }
+
+    /// Offer levels as (price, size), best first
+    pub fn asks(&self) -> Vec<(f64, f64)> {
+        self.asks.iter().map(|(p, s)| (p.0, *s)).collect()
+    }
+
+    /// Best bid as (price, size)
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids.iter().next_back().map(|(p, s)| (p.0, *s))
+    }
+
+    /// Best offer as (price, size)
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.iter().next().map(|(p, s)| (p.0, *s))
+    }
+
+    /// Last applied RptSeq, if the feed provides one
+    pub fn last_rpt_seq(&self) -> Option<u64> {
+        self.last_rpt_seq
+    }
+
+    /// Number of resting orders known by id
+    pub fn order_count(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Resting order with OrderID `order_id`
+    pub fn order(&self, order_id: &str) -> Option<&BookOrder> {
+        self.orders.get(order_id)
+    }
+
+    /// Orders known by id at `price` on `side`, first in the queue first
+    pub fn orders_at(&self, side: MdEntryType, price: f64) -> Vec<&BookOrder> {
+        self.queues(side)
+            .and_then(|queues| queues.get(&PriceKey(price)))
+            .map(|queue| queue.iter().filter_map(|id| self.orders.get(id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Position of the order with OrderID `order_id` in its price level
+    ///
+    /// Entries of the level without an order id are not counted ahead, as
+    /// their place in the queue is unknown.
+    pub fn queue_position(&self, order_id: &str) -> Option<QueuePosition> {
+        let order = self.orders.get(order_id)?;
+        let key = PriceKey(order.price);
+        let queue = self.queues(order.side)?.get(&key)?;
+        let ahead = queue.iter().position(|id| id == order_id)?;
+        let size_ahead = queue[..ahead]
+            .iter()
+            .filter_map(|id| self.orders.get(id))
+            .map(|order| order.size)
+            .sum();
+        let level_size = match order.side {
+            MdEntryType::Bid => self.bids.get(&key),
+            _ => self.asks.get(&key),
+        };
+        Some(QueuePosition {
+            price: order.price,
+            orders_ahead: ahead,
+            size_ahead,
+            level_size: level_size.copied().unwrap_or(order.size),
+        })
+    }
+
+    /// Whether the book failed validation and is waiting for a new snapshot
+    pub fn is_recovering(&self) -> bool {
+        self.recovering
+    }
+
+    /// Mark the book as inconsistent; incremental updates are ignored until
+    /// the next snapshot
+    pub fn mark_recovering(&mut self) {
+        self.recovering = true;
+    }
+
+    /// Replace the book contents with a full snapshot
+    ///
+    /// Returns `true` when the snapshot rebuilt a book that was recovering.
+    pub fn apply_snapshot(&mut self, snapshot: &MarketDataSnapshotFullRefresh) -> bool {
+        self.bids.clear();
+        self.asks.clear();
+        self.orders.clear();
+        self.bid_queues.clear();
+        self.ask_queues.clear();
+        self.last_rpt_seq = None;
+
+        for entry in &snapshot.entries {
+            if let (Some(order_id), Some(price), Some(size)) = (
+                self.entry_order_id(entry),
+                entry.md_entry_px,
+                entry.md_entry_size,
+            ) && matches!(entry.md_entry_type, MdEntryType::Bid | MdEntryType::Offer)
+            {
+                if size > 0.0 {
+                    self.add_order(BookOrder {
+                        order_id,
+                        side: entry.md_entry_type,
+                        price,
+                        size,
+                    });
+                }
+            } else if let (Some(levels), Some(price), Some(size)) = (
+                self.levels_mut(entry.md_entry_type),
+                entry.md_entry_px,
+                entry.md_entry_size,
+            ) && size > 0.0
+            {
+                levels.insert(PriceKey(price), size);
+            }
+            if let Some(rpt_seq) = entry.rpt_seq {
+                self.last_rpt_seq = Some(self.last_rpt_seq.map_or(rpt_seq, |s| s.max(rpt_seq)));
+            }
+        }
+
+        std::mem::replace(&mut self.recovering, false)
+    }
+
+    /// Apply an incremental refresh, validating the result
+    ///
+    /// Updates are ignored while the book is recovering. On failure the book
+    /// is marked as recovering and the issue is returned.
+    pub fn apply_incremental(
+        &mut self,
+        update: &MarketDataIncrementalRefresh,
+    ) -> Result<(), BookIntegrityIssue> {
+        if self.recovering {
+            return Ok(());
+        }
+
+        let result = update
+            .entries
+            .iter()
+            .try_for_each(|entry| self.apply_entry(entry))
+            .and_then(|_| self.check_crossed());
+
+        if result.is_err() {
+            self.recovering = true;
+        }
+        result
+    }
+
+    fn apply_entry(&mut self, entry: &MdEntry) -> Result<(), BookIntegrityIssue> {
+        if let Some(received) = entry.rpt_seq {
+            if let Some(last) = self.last_rpt_seq {
+                if received <= last {
+                    // Already applied
+                    return Ok(());
+                }
+                if received != last + 1 {
+                    return Err(BookIntegrityIssue::SequenceGap {
+                        expected: last + 1,
+                        received,
+                    });
+                }
+            }
+            self.last_rpt_seq = Some(received);
+        }
+
+        let side = entry.md_entry_type;
+        if let Some(order_id) = self.entry_order_id(entry)
+            && matches!(side, MdEntryType::Bid | MdEntryType::Offer)
+        {
+            return self.apply_order_entry(entry, order_id);
+        }
+        let (Some(levels), Some(price)) = (self.levels_mut(side), entry.md_entry_px) else {
+            return Ok(());
+        };
+        let key = PriceKey(price);
+        let size = entry.md_entry_size.unwrap_or(0.0);
+
+        match entry.md_update_action.unwrap_or(MdUpdateAction::New) {
+            MdUpdateAction::New if size > 0.0 => {
+                levels.insert(key, size);
+            }
+            MdUpdateAction::New => {
+                levels.remove(&key);
+            }
+            MdUpdateAction::Change => {
+                if !levels.contains_key(&key) {
+                    return Err(BookIntegrityIssue::MissingLevel { side, price });
+                }
+                if size > 0.0 {
+                    levels.insert(key, size);
+                } else {
+                    levels.remove(&key);
+                }
+            }
+            MdUpdateAction::Delete => {
+                if levels.remove(&key).is_none() {
+                    return Err(BookIntegrityIssue::MissingLevel { side, price });
+                }
+            }
+            MdUpdateAction::Unknown(action) => return Err(unknown_action(action)),
+        }
+        Ok(())
+    }
+
+    /// Order id of an entry, when the book is kept order by order
+    fn entry_order_id(&self, entry: &MdEntry) -> Option<String> {
+        if !self.order_level {
+            return None;
+        }
+        entry
+            .order_id
+            .as_ref()
+            .or(entry.secondary_order_id.as_ref())
+            .filter(|id| !id.is_empty())
+            .cloned()
+    }
+
+    fn apply_order_entry(
+        &mut self,
+        entry: &MdEntry,
+        order_id: String,
+    ) -> Result<(), BookIntegrityIssue> {
+        let side = entry.md_entry_type;
+        let size = entry.md_entry_size.unwrap_or(0.0);
+        let action = entry.md_update_action.unwrap_or(MdUpdateAction::New);
+        let Some(existing) = self.orders.get(&order_id).cloned() else {
+            return match action {
+                MdUpdateAction::New => {
+                    if let Some(price) = entry.md_entry_px
+                        && size > 0.0
+                    {
+                        self.add_order(BookOrder {
+                            order_id,
+                            side,
+                            price,
+                            size,
+                        });
+                    }
+                    Ok(())
+                }
+                MdUpdateAction::Change | MdUpdateAction::Delete => {
+                    Err(BookIntegrityIssue::MissingOrder { side, order_id })
+                }
+                MdUpdateAction::Unknown(action) => Err(unknown_action(action)),
+            };
+        };
+
+        if let MdUpdateAction::Unknown(action) = action {
+            return Err(unknown_action(action));
+        }
+        let price = entry.md_entry_px.unwrap_or(existing.price);
+        if action == MdUpdateAction::Delete || size <= 0.0 {
+            self.remove_order(&order_id);
+        } else if price != existing.price || size > existing.size {
+            // A new price or a larger size loses the place in the queue
+            self.remove_order(&order_id);
+            self.add_order(BookOrder {
+                order_id,
+                side,
+                price,
+                size,
+            });
+        } else if let Some(levels) = self.levels_mut(side) {
+            let level = levels.entry(PriceKey(price)).or_insert(0.0);
+            *level += size - existing.size;
+            if let Some(order) = self.orders.get_mut(&order_id) {
+                order.size = size;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add an order at the back of its price level
+    fn add_order(&mut self, order: BookOrder) {
+        let key = PriceKey(order.price);
+        if let Some(levels) = self.levels_mut(order.side) {
+            *levels.entry(key).or_insert(0.0) += order.size;
+        }
+        if let Some(queues) = self.queues_mut(order.side) {
+            queues.entry(key).or_default().push(order.order_id.clone());
+        }
+        self.orders.insert(order.order_id.clone(), order);
+    }
+
+    /// Remove an order from its price level
+    fn remove_order(&mut self, order_id: &str) {
+        let Some(order) = self.orders.remove(order_id) else {
+            return;
+        };
+        let key = PriceKey(order.price);
+        if let Some(levels) = self.levels_mut(order.side)
+            && let Some(level) = levels.get_mut(&key)
+        {
+            *level -= order.size;
+            if *level <= SIZE_EPSILON {
+                levels.remove(&key);
+            }
+        }
+        if let Some(queues) = self.queues_mut(order.side)
+            && let Some(queue) = queues.get_mut(&key)
+        {
+            queue.retain(|id| id != order_id);
+            if queue.is_empty() {
+                queues.remove(&key);
+            }
+        }
+    }
+
+    fn queues(&self, side: MdEntryType) -> Option<&BTreeMap<PriceKey, Vec<String>>> {
+        match side {
+            MdEntryType::Bid => Some(&self.bid_queues),
+            MdEntryType::Offer => Some(&self.ask_queues),
+            _ => None,
+        }
+    }
+
+    fn queues_mut(&mut self, side: MdEntryType) -> Option<&mut BTreeMap<PriceKey, Vec<String>>> {
+        match side {
+            MdEntryType::Bid => Some(&mut self.bid_queues),
+            MdEntryType::Offer => Some(&mut self.ask_queues),
+            _ => None,
+        }
+    }
+
+    fn check_crossed(&self) -> Result<(), BookIntegrityIssue> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some((best_bid, _)), Some((best_ask, _))) if best_bid >= best_ask => {
+                Err(BookIntegrityIssue::Crossed { best_bid, best_ask })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn levels_mut(&mut self, side: MdEntryType) -> Option<&mut BTreeMap<PriceKey, f64>> {
+        match side {
+            MdEntryType::Bid => Some(&mut self.bids),
+            MdEntryType::Offer => Some(&mut self.asks),
+            _ => None,
+        }
+    }
+}
+
+/// Issue for an update action this version does not know how to apply
+fn unknown_action(action: char) -> BookIntegrityIssue {
+    BookIntegrityIssue::Unparsable {
+        reason: format!("unknown MDUpdateAction {action}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_book() -> OrderBook {
+        let snapshot = MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string())
+            .with_entries(vec![
+                MdEntry::bid(100.0, 1.0),
+                MdEntry::bid(99.0, 2.0),
+                MdEntry::offer(101.0, 3.0),
+                MdEntry::offer(102.0, 4.0),
+            ]);
+        let mut book = OrderBook::new("BTC-PERPETUAL".to_string());
+        assert!(!book.apply_snapshot(&snapshot));
+        book
+    }
+
+    fn update(entries: Vec<MdEntry>) -> MarketDataIncrementalRefresh {
+        MarketDataIncrementalRefresh::new("BTC-PERPETUAL".to_string()).with_entries(entries)
+    }
+
+    #[test]
+    fn test_snapshot_orders_levels() {
+        let book = seeded_book();
+        assert_eq!(book.best_bid(), Some((100.0, 1.0)));
+        assert_eq!(book.best_ask(), Some((101.0, 3.0)));
+        assert_eq!(book.bids(), vec![(100.0, 1.0), (99.0, 2.0)]);
+        assert_eq!(book.asks(), vec![(101.0, 3.0), (102.0, 4.0)]);
+    }
+
+    #[test]
+    fn test_incremental_new_change_delete() {
+        let mut book = seeded_book();
+        let result = book.apply_incremental(&update(vec![
+            MdEntry::bid(100.5, 5.0).with_update_action(MdUpdateAction::New),
+            MdEntry::offer(101.0, 1.5).with_update_action(MdUpdateAction::Change),
+            MdEntry::bid(99.0, 0.0).with_update_action(MdUpdateAction::Delete),
+        ]));
+
+        assert!(result.is_ok());
+        assert_eq!(book.bids(), vec![(100.5, 5.0), (100.0, 1.0)]);
+        assert_eq!(book.best_ask(), Some((101.0, 1.5)));
+    }
+
+    #[test]
+    fn test_missing_level_marks_recovering() {
+        let mut book = seeded_book();
+        let result = book.apply_incremental(&update(vec![
+            MdEntry::offer(150.0, 0.0).with_update_action(MdUpdateAction::Delete),
+        ]));
+
+        assert_eq!(
+            result,
+            Err(BookIntegrityIssue::MissingLevel {
+                side: MdEntryType::Offer,
+                price: 150.0
+            })
+        );
+        assert!(book.is_recovering());
+
+        // Further updates are ignored until a snapshot arrives
+        assert!(
+            book.apply_incremental(&update(vec![
+                MdEntry::bid(100.7, 1.0).with_update_action(MdUpdateAction::New)
+            ]))
+            .is_ok()
+        );
+        assert_eq!(book.best_bid(), Some((100.0, 1.0)));
+    }
+
+    #[test]
+    fn test_unknown_update_action_marks_recovering() {
+        let mut book = seeded_book();
+        let result = book.apply_incremental(&update(vec![
+            MdEntry::bid(100.0, 1.0).with_update_action(MdUpdateAction::Unknown('9')),
+        ]));
+
+        assert_eq!(
+            result,
+            Err(BookIntegrityIssue::Unparsable {
+                reason: "unknown MDUpdateAction 9".to_string()
+            })
+        );
+        assert!(book.is_recovering());
+    }
+
+    #[test]
+    fn test_crossed_book_detected() {
+        let mut book = seeded_book();
+        let result = book.apply_incremental(&update(vec![
+            MdEntry::bid(101.5, 1.0).with_update_action(MdUpdateAction::New),
+        ]));
+
+        assert_eq!(
+            result,
+            Err(BookIntegrityIssue::Crossed {
+                best_bid: 101.5,
+                best_ask: 101.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_rpt_seq_gap_detected() {
+        let mut book = seeded_book();
+        assert!(
+            book.apply_incremental(&update(vec![
+                MdEntry::bid(98.0, 1.0)
+                    .with_update_action(MdUpdateAction::New)
+                    .with_rpt_seq(10)
+            ]))
+            .is_ok()
+        );
+
+        let result = book.apply_incremental(&update(vec![
+            MdEntry::bid(97.0, 1.0)
+                .with_update_action(MdUpdateAction::New)
+                .with_rpt_seq(12),
+        ]));
+        assert_eq!(
+            result,
+            Err(BookIntegrityIssue::SequenceGap {
+                expected: 11,
+                received: 12
+            })
+        );
+    }
+
+    fn order(entry: MdEntry, order_id: &str) -> MdEntry {
+        entry.with_order_id(order_id.to_string())
+    }
+
+    #[test]
+    fn test_order_level_book_tracks_queue_position() {
+        let snapshot = MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string())
+            .with_entries(vec![
+                order(MdEntry::bid(100.0, 1.0), "B1"),
+                order(MdEntry::bid(100.0, 2.0), "B2"),
+                order(MdEntry::bid(100.0, 3.0), "B3"),
+                order(MdEntry::offer(101.0, 4.0), "A1"),
+                // No id: aggregated as a price level
+                MdEntry::offer(102.0, 5.0),
+            ]);
+        let mut book = OrderBook::new("BTC-PERPETUAL".to_string()).with_order_level(true);
+        book.apply_snapshot(&snapshot);
+        assert_eq!(book.order_count(), 4);
+        assert_eq!(book.best_bid(), Some((100.0, 6.0)));
+        assert_eq!(book.asks(), vec![(101.0, 4.0), (102.0, 5.0)]);
+        assert_eq!(
+            book.queue_position("B3"),
+            Some(QueuePosition {
+                price: 100.0,
+                orders_ahead: 2,
+                size_ahead: 3.0,
+                level_size: 6.0,
+            })
+        );
+
+        let result = book.apply_incremental(&update(vec![
+            // A partial fill keeps the place in the queue
+            order(MdEntry::bid(100.0, 0.5), "B1").with_update_action(MdUpdateAction::Change),
+            // A larger size goes to the back
+            order(MdEntry::bid(100.0, 2.5), "B2").with_update_action(MdUpdateAction::Change),
+            order(MdEntry::offer(101.0, 0.0), "A1").with_update_action(MdUpdateAction::Delete),
+            MdEntry::offer(102.0, 1.0).with_update_action(MdUpdateAction::Change),
+        ]));
+        assert!(result.is_ok());
+        let queue: Vec<&str> = book
+            .orders_at(MdEntryType::Bid, 100.0)
+            .iter()
+            .map(|order| order.order_id.as_str())
+            .collect();
+        assert_eq!(queue, vec!["B1", "B3", "B2"]);
+        assert_eq!(book.best_bid(), Some((100.0, 6.0)));
+        assert_eq!(book.queue_position("B3").unwrap().size_ahead, 0.5);
+        assert_eq!(book.asks(), vec![(102.0, 1.0)]);
+        assert!(book.order("A1").is_none());
+
+        let result = book.apply_incremental(&update(vec![
+            order(MdEntry::bid(99.0, 1.0), "B9").with_update_action(MdUpdateAction::Delete),
+        ]));
+        assert_eq!(
+            result,
+            Err(BookIntegrityIssue::MissingOrder {
+                side: MdEntryType::Bid,
+                order_id: "B9".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_order_ids_are_ignored_in_price_level_books() {
+        let snapshot = MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string())
+            .with_entries(vec![order(MdEntry::bid(100.0, 1.0), "B1")]);
+        let mut book = OrderBook::new("BTC-PERPETUAL".to_string());
+        book.apply_snapshot(&snapshot);
+        assert_eq!(book.order_count(), 0);
+        assert!(book.queue_position("B1").is_none());
+        assert_eq!(book.best_bid(), Some((100.0, 1.0)));
+    }
+
+    #[test]
+    fn test_snapshot_rebuilds_recovering_book() {
+        let mut book = seeded_book();
+        book.mark_recovering();
+
+        let snapshot = MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string())
+            .with_entries(vec![MdEntry::bid(90.0, 1.0), MdEntry::offer(91.0, 1.0)]);
+        assert!(book.apply_snapshot(&snapshot));
+        assert!(!book.is_recovering());
+        assert_eq!(book.best_bid(), Some((90.0, 1.0)));
+    }
+}
@@ -0,0 +1,275 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Client-side OCO (one-cancels-other) order groups
+//!
+//! An [`OrderGroup`] links orders such as a take-profit and a stop-loss. The
+//! group is driven by the Execution Report (8) stream: as soon as one member
+//! is filled, even partially, the remaining open members are cancelled. A
+//! group completes once every member has reached a terminal status.
+
+use crate::message::OrderStatus;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Member order of an [`OrderGroup`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupMember {
+    /// ClOrdID (11) of the order
+    pub cl_ord_id: String,
+    /// Instrument symbol
+    pub symbol: String,
+    /// Last OrdStatus (39) reported for the order, `None` until acknowledged
+    pub status: Option<OrderStatus>,
+}
+
+impl GroupMember {
+    /// Create a member that has not been acknowledged yet
+    pub fn new(cl_ord_id: String, symbol: String) -> Self {
+        Self {
+            cl_ord_id,
+            symbol,
+            status: None,
+        }
+    }
+
+    /// Whether the order can no longer trade
+    pub fn is_terminal(&self) -> bool {
+        self.status.is_some_and(OrderStatus::is_terminal)
+    }
+}
+
+/// Lifecycle of an [`OrderGroup`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrderGroupState {
+    /// No member has been filled yet
+    Active,
+    /// A member was filled and its siblings are being cancelled
+    Triggered {
+        /// ClOrdID (11) of the member that was filled
+        filled_cl_ord_id: String,
+    },
+}
+
+/// Linked orders where a fill of one cancels the others
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderGroup {
+    /// Group identifier
+    pub id: String,
+    /// Member orders
+    pub members: Vec<GroupMember>,
+    /// Current state
+    pub state: OrderGroupState,
+}
+
+impl OrderGroup {
+    /// Create an active group
+    pub fn new(id: String, members: Vec<GroupMember>) -> Self {
+        Self {
+            id,
+            members,
+            state: OrderGroupState::Active,
+        }
+    }
+
+    /// Whether a member was filled
+    pub fn is_triggered(&self) -> bool {
+        matches!(self.state, OrderGroupState::Triggered { .. })
+    }
+
+    /// Whether every member reached a terminal status
+    pub fn is_complete(&self) -> bool {
+        self.members.iter().all(GroupMember::is_terminal)
+    }
+
+    /// Open members that must be cancelled, empty unless the group was triggered
+    pub fn cancel_targets(&self) -> Vec<&GroupMember> {
+        match &self.state {
+            OrderGroupState::Active => Vec::new(),
+            OrderGroupState::Triggered { filled_cl_ord_id } => self
+                .members
+                .iter()
+                .filter(|member| &member.cl_ord_id != filled_cl_ord_id && !member.is_terminal())
+                .collect(),
+        }
+    }
+}
+
+/// Notification about order group progress
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrderGroupEvent {
+    /// A member was filled; its open siblings will be cancelled
+    Triggered {
+        /// Group identifier
+        group_id: String,
+        /// ClOrdID (11) of the filled member
+        filled_cl_ord_id: String,
+    },
+    /// A cancel was sent for an open sibling
+    SiblingCancelRequested {
+        /// Group identifier
+        group_id: String,
+        /// ClOrdID (11) of the sibling
+        cl_ord_id: String,
+    },
+    /// Order status was requested for the members of a group after logon
+    RecoveryRequested {
+        /// Group identifier
+        group_id: String,
+    },
+    /// Every member reached a terminal status and the group was removed
+    Completed {
+        /// Group identifier
+        group_id: String,
+    },
+}
+
+/// Tracks order groups and routes Execution Reports to them
+#[derive(Debug, Clone, Default)]
+pub struct OrderGroupManager {
+    groups: HashMap<String, OrderGroup>,
+    member_index: HashMap<String, String>,
+}
+
+impl OrderGroupManager {
+    /// Create an empty manager
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a group; its members are tracked from now on
+    pub fn register(&mut self, group: OrderGroup) {
+        for member in &group.members {
+            self.member_index
+                .insert(member.cl_ord_id.clone(), group.id.clone());
+        }
+        self.groups.insert(group.id.clone(), group);
+    }
+
+    /// Get a group by identifier
+    pub fn group(&self, group_id: &str) -> Option<&OrderGroup> {
+        self.groups.get(group_id)
+    }
+
+    /// Iterate over all tracked groups
+    pub fn groups(&self) -> impl Iterator<Item = &OrderGroup> {
+        self.groups.values()
+    }
+
+    /// Identifier of the group `cl_ord_id` belongs to
+    pub fn group_of(&self, cl_ord_id: &str) -> Option<&str> {
+        self.member_index.get(cl_ord_id).map(String::as_str)
+    }
+
+    /// Record the status of a member order
+    ///
+    /// Returns the identifier of the affected group, or `None` when the order
+    /// is not part of a group. The first fill of any member triggers the group.
+    pub fn apply_status(&mut self, cl_ord_id: &str, status: OrderStatus) -> Option<String> {
+        let group_id = self.member_index.get(cl_ord_id)?.clone();
+        let group = self.groups.get_mut(&group_id)?;
+        if let Some(member) = group
+            .members
+            .iter_mut()
+            .find(|member| member.cl_ord_id == cl_ord_id)
+        {
+            member.status = Some(status);
+        }
+
+        let filled = matches!(status, OrderStatus::PartiallyFilled | OrderStatus::Filled);
+        if filled && !group.is_triggered() {
+            group.state = OrderGroupState::Triggered {
+                filled_cl_ord_id: cl_ord_id.to_string(),
+            };
+        }
+        Some(group_id)
+    }
+
+    /// Remove a group once all its members are terminal, returning it
+    pub fn take_if_complete(&mut self, group_id: &str) -> Option<OrderGroup> {
+        if !self.groups.get(group_id)?.is_complete() {
+            return None;
+        }
+        self.remove(group_id)
+    }
+
+    /// Stop tracking a group whatever the status of its members, returning it
+    pub fn remove(&mut self, group_id: &str) -> Option<OrderGroup> {
+        let group = self.groups.remove(group_id)?;
+        for member in &group.members {
+            self.member_index.remove(&member.cl_ord_id);
+        }
+        Some(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oco() -> OrderGroupManager {
+        let mut manager = OrderGroupManager::new();
+        manager.register(OrderGroup::new(
+            "OCO-1".to_string(),
+            vec![
+                GroupMember::new("TP".to_string(), "BTC-PERPETUAL".to_string()),
+                GroupMember::new("SL".to_string(), "BTC-PERPETUAL".to_string()),
+            ],
+        ));
+        manager
+    }
+
+    #[test]
+    fn test_fill_triggers_group_and_targets_sibling() {
+        let mut manager = oco();
+        assert_eq!(
+            manager.apply_status("TP", OrderStatus::New),
+            Some("OCO-1".to_string())
+        );
+        assert!(!manager.group("OCO-1").unwrap().is_triggered());
+
+        manager.apply_status("TP", OrderStatus::PartiallyFilled);
+        let group = manager.group("OCO-1").unwrap();
+        assert_eq!(
+            group.state,
+            OrderGroupState::Triggered {
+                filled_cl_ord_id: "TP".to_string()
+            }
+        );
+        let targets: Vec<_> = group
+            .cancel_targets()
+            .iter()
+            .map(|m| &m.cl_ord_id)
+            .collect();
+        assert_eq!(targets, vec!["SL"]);
+    }
+
+    #[test]
+    fn test_group_completes_when_all_members_terminal() {
+        let mut manager = oco();
+        manager.apply_status("SL", OrderStatus::Filled);
+        assert!(manager.take_if_complete("OCO-1").is_none());
+
+        manager.apply_status("TP", OrderStatus::Cancelled);
+        assert!(manager.take_if_complete("OCO-1").is_some());
+        assert!(manager.group_of("TP").is_none());
+        assert_eq!(manager.groups().count(), 0);
+    }
+
+    #[test]
+    fn test_removed_group_stops_tracking_members() {
+        let mut manager = oco();
+        assert!(manager.remove("OCO-1").is_some());
+        assert!(manager.group_of("TP").is_none());
+        assert_eq!(manager.apply_status("SL", OrderStatus::Filled), None);
+    }
+
+    #[test]
+    fn test_unknown_order_is_ignored() {
+        let mut manager = oco();
+        assert_eq!(manager.apply_status("OTHER", OrderStatus::Filled), None);
+    }
+}
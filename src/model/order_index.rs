@@ -0,0 +1,501 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Persistent order ownership
+//!
+//! The [`OrderTracker`](crate::model::order_tracker::OrderTracker) lives and
+//! dies with the session. To know after a process restart which strategy
+//! owns the orders still working on the exchange, the session also records
+//! the ClOrdID (11), OrderID (37) and DeribitLabel (100010) of every working
+//! order in an [`OrderIndex`], written through to the
+//! [`OrderIndexStore`] set with
+//! [`with_order_index_store`](crate::config::DeribitFixConfig::with_order_index_store).
+//! Orders are dropped from the index once they are filled, cancelled,
+//! rejected or expired.
+//!
+//! [`FileOrderIndexStore`] keeps the index in a JSON lines file; other
+//! stores, such as sled or Redis, implement [`OrderIndexStore`]. After a
+//! restart, the Execution Reports (8) answering an Order Mass Status Request
+//! (AF) are matched against the index with [`OrderIndex::reconcile`].
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::ExecutionReport;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Identifiers and owner of a working order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderOwnership {
+    /// ClOrdID (11) of the order, the one of its last replace
+    pub cl_ord_id: String,
+    /// Exchange OrderID (37), once a report carried it
+    pub order_id: Option<String>,
+    /// Instrument symbol
+    pub symbol: String,
+    /// DeribitLabel (100010) of the strategy owning the order
+    pub label: Option<String>,
+}
+
+/// Storage of the [`OrderIndex`]
+///
+/// Writes happen on the session task as orders are sent and reported, so
+/// they should be quick.
+pub trait OrderIndexStore: fmt::Debug + Send + Sync {
+    /// Every order saved and not removed
+    fn load(&self) -> Result<Vec<OrderOwnership>>;
+
+    /// Save `order`, replacing the order with the same ClOrdID
+    fn save(&self, order: &OrderOwnership) -> Result<()>;
+
+    /// Remove the order with ClOrdID `cl_ord_id`
+    fn remove(&self, cl_ord_id: &str) -> Result<()>;
+}
+
+/// [`OrderIndexStore`] kept in memory, for tests and processes that share
+/// the store between sessions
+#[derive(Debug, Default)]
+pub struct MemoryOrderIndexStore {
+    orders: Mutex<HashMap<String, OrderOwnership>>,
+}
+
+impl MemoryOrderIndexStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OrderIndexStore for MemoryOrderIndexStore {
+    fn load(&self) -> Result<Vec<OrderOwnership>> {
+        let orders = self.orders.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(orders.values().cloned().collect())
+    }
+
+    fn save(&self, order: &OrderOwnership) -> Result<()> {
+        self.orders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(order.cl_ord_id.clone(), order.clone());
+        Ok(())
+    }
+
+    fn remove(&self, cl_ord_id: &str) -> Result<()> {
+        self.orders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(cl_ord_id);
+        Ok(())
+    }
+}
+
+/// Change appended to a [`FileOrderIndexStore`]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FileRecord {
+    Save(OrderOwnership),
+    Remove(String),
+}
+
+/// [`OrderIndexStore`] appending each change to a JSON lines file
+///
+/// Loading replays the file and rewrites it with the orders still indexed,
+/// so it does not grow across restarts.
+#[derive(Debug)]
+pub struct FileOrderIndexStore {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl FileOrderIndexStore {
+    /// Store kept in the file at `path`, created on the first write
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: Mutex::new(None),
+        }
+    }
+
+    fn append(&self, record: &FileRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        if file.is_none() {
+            *file = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            );
+        }
+        if let Some(file) = file.as_mut() {
+            file.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl OrderIndexStore for FileOrderIndexStore {
+    fn load(&self) -> Result<Vec<OrderOwnership>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut orders: HashMap<String, OrderOwnership> = HashMap::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str(&line).map_err(|e| {
+                DeribitFixError::Generic(format!(
+                    "Invalid order index record on line {} of {}: {e}",
+                    number + 1,
+                    self.path.display()
+                ))
+            })?;
+            match record {
+                FileRecord::Save(order) => {
+                    orders.insert(order.cl_ord_id.clone(), order);
+                }
+                FileRecord::Remove(cl_ord_id) => {
+                    orders.remove(&cl_ord_id);
+                }
+            }
+        }
+
+        // Compact the file to the orders still indexed
+        let compacted = self.path.with_extension("compact");
+        let mut contents = String::new();
+        for order in orders.values() {
+            contents.push_str(&serde_json::to_string(&FileRecord::Save(order.clone()))?);
+            contents.push('\n');
+        }
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        *file = None;
+        fs::write(&compacted, contents)?;
+        fs::rename(&compacted, &self.path)?;
+        Ok(orders.into_values().collect())
+    }
+
+    fn save(&self, order: &OrderOwnership) -> Result<()> {
+        self.append(&FileRecord::Save(order.clone()))
+    }
+
+    fn remove(&self, cl_ord_id: &str) -> Result<()> {
+        self.append(&FileRecord::Remove(cl_ord_id.to_string()))
+    }
+}
+
+/// Working orders matched against the index after a restart
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderReconciliation {
+    /// Working orders the index knows, with their recorded owner
+    pub owned: Vec<(OrderOwnership, ExecutionReport)>,
+    /// Working orders the index has no record of
+    pub unknown: Vec<ExecutionReport>,
+    /// Indexed orders the exchange did not report as working
+    pub missing: Vec<OrderOwnership>,
+}
+
+/// ClOrdID, OrderID and label of the working orders, written through to an
+/// [`OrderIndexStore`]
+#[derive(Debug, Default)]
+pub struct OrderIndex {
+    orders: HashMap<String, OrderOwnership>,
+    order_ids: HashMap<String, String>,
+    store: Option<Arc<dyn OrderIndexStore>>,
+}
+
+impl OrderIndex {
+    /// Index kept in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index loaded from and written through to `store`
+    pub fn with_store(store: Arc<dyn OrderIndexStore>) -> Result<Self> {
+        let mut index = Self::new();
+        for order in store.load()? {
+            index.insert(order);
+        }
+        index.store = Some(store);
+        Ok(index)
+    }
+
+    /// Number of indexed orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Whether no order is indexed
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Indexed orders, in no particular order
+    pub fn orders(&self) -> impl Iterator<Item = &OrderOwnership> {
+        self.orders.values()
+    }
+
+    /// Order with ClOrdID (11) or OrderID (37) `id`
+    pub fn order(&self, id: &str) -> Option<&OrderOwnership> {
+        self.orders
+            .get(id)
+            .or_else(|| self.orders.get(self.order_ids.get(id)?))
+    }
+
+    /// Record an order sent with ClOrdID `cl_ord_id`
+    pub fn record_sent(
+        &mut self,
+        cl_ord_id: &str,
+        symbol: &str,
+        label: Option<&str>,
+    ) -> Result<()> {
+        self.save(OrderOwnership {
+            cl_ord_id: cl_ord_id.to_string(),
+            order_id: None,
+            symbol: symbol.to_string(),
+            label: label.map(str::to_string),
+        })
+    }
+
+    /// Record an Execution Report (8)
+    ///
+    /// The report is matched by ClOrdID (11), OrigClOrdID (41) or OrderID
+    /// (37); a labelled order the index does not know is added. A replace
+    /// moves the order to its new ClOrdID and an order reaching a final
+    /// state is removed.
+    pub fn record_report(&mut self, report: &ExecutionReport) -> Result<()> {
+        let known = [
+            Some(&report.cl_ord_id),
+            report.orig_cl_ord_id.as_ref(),
+            Some(&report.order_id),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|id| !id.is_empty())
+        .find_map(|id| self.order(id).cloned());
+
+        let Some(mut order) = known.or_else(|| {
+            report.deribit_label.as_ref().map(|label| OrderOwnership {
+                cl_ord_id: report.cl_ord_id.clone(),
+                order_id: None,
+                symbol: report.symbol.clone(),
+                label: Some(label.clone()),
+            })
+        }) else {
+            return Ok(());
+        };
+
+        if report.ord_status.is_terminal() {
+            return self.remove(&order.cl_ord_id);
+        }
+        let mut changed = false;
+        if !report.order_id.is_empty() && order.order_id.as_ref() != Some(&report.order_id) {
+            order.order_id = Some(report.order_id.clone());
+            changed = true;
+        }
+        if let Some(label) = &report.deribit_label
+            && order.label.as_ref() != Some(label)
+        {
+            order.label = Some(label.clone());
+            changed = true;
+        }
+        if !report.cl_ord_id.is_empty() && report.cl_ord_id != order.cl_ord_id {
+            self.remove(&order.cl_ord_id)?;
+            order.cl_ord_id = report.cl_ord_id.clone();
+            changed = true;
+        }
+        if changed || !self.orders.contains_key(&order.cl_ord_id) {
+            self.save(order)?;
+        }
+        Ok(())
+    }
+
+    /// Remove the order with ClOrdID `cl_ord_id`
+    pub fn remove(&mut self, cl_ord_id: &str) -> Result<()> {
+        let Some(order) = self.orders.remove(cl_ord_id) else {
+            return Ok(());
+        };
+        if let Some(order_id) = &order.order_id {
+            self.order_ids.remove(order_id);
+        }
+        match &self.store {
+            Some(store) => store.remove(cl_ord_id),
+            None => Ok(()),
+        }
+    }
+
+    /// Match the Execution Reports of the working orders, such as those
+    /// answering an Order Mass Status Request (AF), against the index
+    ///
+    /// Reports of orders in a final state are not working and left out.
+    pub fn reconcile(&self, reports: &[ExecutionReport]) -> OrderReconciliation {
+        let mut reconciliation = OrderReconciliation::default();
+        let mut seen = Vec::new();
+        for report in reports
+            .iter()
+            .filter(|report| !report.ord_status.is_terminal())
+        {
+            let owner = [Some(&report.order_id), Some(&report.cl_ord_id)]
+                .into_iter()
+                .flatten()
+                .filter(|id| !id.is_empty())
+                .find_map(|id| self.order(id));
+            match owner {
+                Some(owner) => {
+                    seen.push(owner.cl_ord_id.clone());
+                    reconciliation.owned.push((owner.clone(), report.clone()));
+                }
+                None => reconciliation.unknown.push(report.clone()),
+            }
+        }
+        reconciliation.missing = self
+            .orders
+            .values()
+            .filter(|order| !seen.contains(&order.cl_ord_id))
+            .cloned()
+            .collect();
+        reconciliation
+    }
+
+    fn insert(&mut self, order: OrderOwnership) {
+        if let Some(order_id) = &order.order_id {
+            self.order_ids
+                .insert(order_id.clone(), order.cl_ord_id.clone());
+        }
+        self.orders.insert(order.cl_ord_id.clone(), order);
+    }
+
+    fn save(&mut self, order: OrderOwnership) -> Result<()> {
+        let saved = match &self.store {
+            Some(store) => store.save(&order),
+            None => Ok(()),
+        };
+        self.insert(order);
+        saved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{OrderSide, OrderStatus};
+    use crate::model::types::ExecType;
+
+    fn report(order_id: &str, cl_ord_id: &str, ord_status: OrderStatus) -> ExecutionReport {
+        let mut report = ExecutionReport::new_order(
+            order_id.to_string(),
+            cl_ord_id.to_string(),
+            "EXEC-1".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            OrderSide::Buy,
+            10.0,
+            10.0,
+            Some(50_000.0),
+        );
+        report.ord_status = ord_status;
+        report
+    }
+
+    #[test]
+    fn test_working_orders_are_indexed_until_final() {
+        let store = Arc::new(MemoryOrderIndexStore::new());
+        let mut index = OrderIndex::with_store(store.clone()).unwrap();
+        index
+            .record_sent("ORDER_1", "BTC-PERPETUAL", Some("mm"))
+            .unwrap();
+        index
+            .record_report(&report("ORD-1", "ORDER_1", OrderStatus::New))
+            .unwrap();
+        assert_eq!(index.order("ORD-1").unwrap().label.as_deref(), Some("mm"));
+
+        // A replace moves the order to its new ClOrdID
+        let mut replaced = report("ORD-1", "ORDER_2", OrderStatus::New);
+        replaced.exec_type = ExecType::Replaced;
+        replaced.orig_cl_ord_id = Some("ORDER_1".to_string());
+        index.record_report(&replaced).unwrap();
+        assert!(index.order("ORDER_1").is_none());
+        assert_eq!(index.order("ORD-1").unwrap().cl_ord_id, "ORDER_2");
+
+        // The store sees the same orders as the index
+        let reloaded = OrderIndex::with_store(store.clone()).unwrap();
+        assert_eq!(reloaded.order("ORD-1"), index.order("ORD-1"));
+
+        index
+            .record_report(&report("ORD-1", "ORDER_2", OrderStatus::Filled))
+            .unwrap();
+        assert!(index.is_empty());
+        assert!(store.load().unwrap().is_empty());
+
+        // Unlabelled orders sent elsewhere are not indexed
+        index
+            .record_report(&report("ORD-9", "OTHER", OrderStatus::New))
+            .unwrap();
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_matches_working_orders() {
+        let mut index = OrderIndex::new();
+        for (cl_ord_id, label) in [("ORDER_1", "mm"), ("ORDER_2", "hedge")] {
+            index
+                .record_sent(cl_ord_id, "BTC-PERPETUAL", Some(label))
+                .unwrap();
+        }
+        index
+            .record_report(&report("ORD-1", "ORDER_1", OrderStatus::New))
+            .unwrap();
+
+        // Reports of a mass status may only carry the OrderID
+        let reconciliation = index.reconcile(&[
+            report("ORD-1", "", OrderStatus::PartiallyFilled),
+            report("ORD-3", "", OrderStatus::New),
+            report("ORD-4", "", OrderStatus::Cancelled),
+        ]);
+        assert_eq!(reconciliation.owned.len(), 1);
+        assert_eq!(reconciliation.owned[0].0.label.as_deref(), Some("mm"));
+        assert_eq!(reconciliation.unknown.len(), 1);
+        assert_eq!(reconciliation.unknown[0].order_id, "ORD-3");
+        assert_eq!(reconciliation.missing.len(), 1);
+        assert_eq!(reconciliation.missing[0].cl_ord_id, "ORDER_2");
+    }
+
+    #[test]
+    fn test_file_store_survives_a_restart() {
+        let path = std::env::temp_dir().join(format!(
+            "deribit-fix-order-index-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let mut index = OrderIndex::with_store(Arc::new(FileOrderIndexStore::new(&path))).unwrap();
+        index
+            .record_sent("ORDER_1", "BTC-PERPETUAL", Some("mm"))
+            .unwrap();
+        index.record_sent("ORDER_2", "ETH-PERPETUAL", None).unwrap();
+        index
+            .record_report(&report("ORD-1", "ORDER_1", OrderStatus::New))
+            .unwrap();
+        index.remove("ORDER_2").unwrap();
+        drop(index);
+
+        let restarted = OrderIndex::with_store(Arc::new(FileOrderIndexStore::new(&path))).unwrap();
+        assert_eq!(restarted.len(), 1);
+        let order = restarted.order("ORD-1").unwrap();
+        assert_eq!(order.cl_ord_id, "ORDER_1");
+        assert_eq!(order.label.as_deref(), Some("mm"));
+        // Loading compacted the file to the indexed order
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,249 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Named order templates
+//!
+//! An [`OrderTemplate`] holds everything about a limit order except its price
+//! and amount: instrument, side, time in force, post-only and reduce-only
+//! flags, display amount and a label prefix. Templates are registered by name
+//! in [`OrderTemplates`] and turned into validated [`NewOrderRequest`]s, so
+//! strategy code only supplies the price and amount of each order.
+
+use crate::error::{DeribitFixError, Result};
+use crate::model::exec_inst::SelfTradePrevention;
+use crate::model::request::{NewOrderRequest, OrderSide, TimeInForce};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Defaults of the limit orders created from a template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderTemplate {
+    /// Instrument name (e.g., "BTC-PERPETUAL")
+    pub instrument_name: String,
+    /// Order side
+    pub side: OrderSide,
+    /// Time in force (default: good til cancelled)
+    pub time_in_force: TimeInForce,
+    /// Post-only flag
+    pub post_only: Option<bool>,
+    /// Reduce-only flag
+    pub reduce_only: Option<bool>,
+    /// Prefix of the order labels, followed by a sequence number per template
+    pub label_prefix: Option<String>,
+    /// Maximum show amount (iceberg orders)
+    pub max_show: Option<f64>,
+    /// Self-trade prevention, the session default when unset
+    pub self_trade_prevention: Option<SelfTradePrevention>,
+}
+
+impl OrderTemplate {
+    /// Create a template of good til cancelled limit orders
+    #[must_use]
+    pub fn new(instrument_name: String, side: OrderSide) -> Self {
+        Self {
+            instrument_name,
+            side,
+            time_in_force: TimeInForce::GoodTilCancelled,
+            post_only: None,
+            reduce_only: None,
+            label_prefix: None,
+            max_show: None,
+            self_trade_prevention: None,
+        }
+    }
+
+    /// Set the time in force
+    #[must_use]
+    pub fn with_time_in_force(mut self, tif: TimeInForce) -> Self {
+        self.time_in_force = tif;
+        self
+    }
+
+    /// Set the orders as post-only
+    #[must_use]
+    pub fn with_post_only(mut self, post_only: bool) -> Self {
+        self.post_only = Some(post_only);
+        self
+    }
+
+    /// Set the orders as reduce-only
+    #[must_use]
+    pub fn with_reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = Some(reduce_only);
+        self
+    }
+
+    /// Label the orders `prefix` followed by their sequence number
+    #[must_use]
+    pub fn with_label_prefix(mut self, prefix: String) -> Self {
+        self.label_prefix = Some(prefix);
+        self
+    }
+
+    /// Show at most `max_show` of the amount on the book
+    #[must_use]
+    pub fn with_max_show(mut self, max_show: f64) -> Self {
+        self.max_show = Some(max_show);
+        self
+    }
+
+    /// Set what happens when an order would match an order of the same account
+    #[must_use]
+    pub fn with_self_trade_prevention(mut self, stp: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = Some(stp);
+        self
+    }
+
+    /// Build an order at `price` for `amount`, labelled `label`
+    ///
+    /// Fails when the price or amount is not positive, or when the flags do
+    /// not fit the time in force.
+    pub fn order(&self, price: f64, amount: f64, label: Option<String>) -> Result<NewOrderRequest> {
+        if !(price.is_finite() && price > 0.0) {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "Template order price must be positive, got {price}"
+            )));
+        }
+        if !(amount.is_finite() && amount > 0.0) {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "Template order amount must be positive, got {amount}"
+            )));
+        }
+        let mut order = match self.side {
+            OrderSide::Buy => {
+                NewOrderRequest::limit_buy(self.instrument_name.clone(), amount, price)
+            }
+            OrderSide::Sell => {
+                NewOrderRequest::limit_sell(self.instrument_name.clone(), amount, price)
+            }
+        }
+        .with_time_in_force(self.time_in_force);
+        order.post_only = self.post_only;
+        order.reduce_only = self.reduce_only;
+        order.max_show = self.max_show;
+        order.self_trade_prevention = self.self_trade_prevention;
+        order.label = label;
+        order.validate_instructions()?;
+        Ok(order)
+    }
+}
+
+/// Order templates registered by name
+#[derive(Debug, Clone, Default)]
+pub struct OrderTemplates {
+    templates: HashMap<String, OrderTemplate>,
+    /// Orders created so far from each template, numbering their labels
+    created: HashMap<String, u64>,
+}
+
+impl OrderTemplates {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `template` as `name`, replacing any template of that name
+    ///
+    /// The template is checked by building a sample order, so a template
+    /// whose flags conflict is refused here rather than on first use.
+    pub fn register(&mut self, name: &str, template: OrderTemplate) -> Result<()> {
+        if name.is_empty() {
+            return Err(DeribitFixError::Config(
+                "Order template name must not be empty".to_string(),
+            ));
+        }
+        if template.instrument_name.is_empty() {
+            return Err(DeribitFixError::Config(format!(
+                "Order template {name} has no instrument"
+            )));
+        }
+        template.order(1.0, 1.0, template.label_prefix.clone())?;
+        self.templates.insert(name.to_string(), template);
+        Ok(())
+    }
+
+    /// Remove the template registered as `name`
+    pub fn remove(&mut self, name: &str) -> Option<OrderTemplate> {
+        self.created.remove(name);
+        self.templates.remove(name)
+    }
+
+    /// Template registered as `name`
+    pub fn get(&self, name: &str) -> Option<&OrderTemplate> {
+        self.templates.get(name)
+    }
+
+    /// Names of the registered templates
+    pub fn names(&self) -> Vec<&str> {
+        self.templates.keys().map(String::as_str).collect()
+    }
+
+    /// Build an order from the template registered as `name`
+    pub fn order(&mut self, name: &str, price: f64, amount: f64) -> Result<NewOrderRequest> {
+        let template = self
+            .templates
+            .get(name)
+            .ok_or_else(|| DeribitFixError::Config(format!("Unknown order template: {name}")))?;
+        let created = self.created.get(name).copied().unwrap_or_default();
+        let label = template
+            .label_prefix
+            .as_ref()
+            .map(|prefix| format!("{prefix}{}", created + 1));
+        let order = template.order(price, amount, label)?;
+        self.created.insert(name.to_string(), created + 1);
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::request::OrderType;
+
+    fn post_only_bid() -> OrderTemplate {
+        OrderTemplate::new("BTC-PERPETUAL".to_string(), OrderSide::Buy)
+            .with_post_only(true)
+            .with_label_prefix("mm-bid-".to_string())
+    }
+
+    #[test]
+    fn test_orders_take_the_template_defaults() {
+        let mut templates = OrderTemplates::new();
+        templates
+            .register("post-only-limit-BTC", post_only_bid())
+            .unwrap();
+
+        let first = templates
+            .order("post-only-limit-BTC", 50_000.0, 10.0)
+            .unwrap();
+        assert_eq!(first.instrument_name, "BTC-PERPETUAL");
+        assert_eq!(first.order_type, OrderType::Limit);
+        assert_eq!(first.side, OrderSide::Buy);
+        assert_eq!(first.price, Some(50_000.0));
+        assert_eq!(first.amount, 10.0);
+        assert_eq!(first.post_only, Some(true));
+        assert_eq!(first.label.as_deref(), Some("mm-bid-1"));
+
+        let second = templates
+            .order("post-only-limit-BTC", 50_010.0, 10.0)
+            .unwrap();
+        assert_eq!(second.label.as_deref(), Some("mm-bid-2"));
+    }
+
+    #[test]
+    fn test_invalid_orders_and_templates_are_refused() {
+        let mut templates = OrderTemplates::new();
+        templates.register("bid", post_only_bid()).unwrap();
+
+        assert!(templates.order("bid", 0.0, 10.0).is_err());
+        assert!(templates.order("bid", 50_000.0, -1.0).is_err());
+        assert!(templates.order("ask", 50_000.0, 10.0).is_err());
+
+        let post_only_ioc = post_only_bid().with_time_in_force(TimeInForce::ImmediateOrCancel);
+        assert!(templates.register("ioc", post_only_ioc).is_err());
+        assert!(templates.get("ioc").is_none());
+    }
+}
@@ -0,0 +1,679 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Order lifecycle tracking and audit export
+//!
+//! An [`OrderTracker`] keeps the full lifecycle of every order of a session:
+//! when it was sent and every Execution Report (8) received for it, with the
+//! resulting state, fills and fees. Replacements stay in the lifecycle of the
+//! order they replace. The lifecycles can be exported to JSON, one object per
+//! order, or to CSV, one row per event, for compliance and reconciliation.
+//!
+//! Iceberg orders, sent with a DisplayQty (1138) below their quantity, only
+//! show a slice of the order on the book. Deribit shows the next slice once
+//! the displayed one is filled; the tracker counts these refills from the
+//! fills so the displayed and total remaining quantity of the order are known.
+//!
+//! The tracker also remembers the ExecID (17) of every report it recorded, so
+//! that reports replayed by a resend can be recognised with
+//! [`OrderTracker::has_execution`] and delivered only once.
+
+use crate::error::Result;
+use crate::message::{ExecutionReport, OrderStatus};
+use crate::model::request::{NewOrderRequest, OrderSide};
+use crate::model::types::ExecType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+/// Columns of the CSV export
+const CSV_HEADER: &str = "cl_ord_id,order_id,symbol,side,order_qty,price,label,timestamp,\
+                          event,exec_id,exec_type,ord_status,last_qty,last_px,cum_qty,\
+                          leaves_qty,avg_px,commission,text";
+
+/// Tolerance when comparing filled quantities with the displayed quantity
+const QTY_EPSILON: f64 = 1e-9;
+
+/// Format of an order audit export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditFormat {
+    /// One row per lifecycle event
+    Csv,
+    /// Array of order lifecycles
+    Json,
+}
+
+/// What happened to an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditEventKind {
+    /// The order was sent
+    Submitted,
+    /// An Execution Report (8) was received for the order
+    ExecutionReport,
+}
+
+/// One step in the lifecycle of an order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderAuditEvent {
+    /// When the order was sent, or TransactTime (60) of the report
+    pub timestamp: DateTime<Utc>,
+    /// What happened
+    pub kind: AuditEventKind,
+    /// ExecID (17)
+    pub exec_id: Option<String>,
+    /// ExecType (150)
+    pub exec_type: Option<ExecType>,
+    /// OrdStatus (39) after the event; `None` until the order is acknowledged
+    pub ord_status: Option<OrderStatus>,
+    /// ClOrdID (11) of the request, which changes on replace
+    pub cl_ord_id: String,
+    /// LastQty (32) of a fill
+    pub last_qty: Option<f64>,
+    /// LastPx (31) of a fill
+    pub last_px: Option<f64>,
+    /// CumQty (14)
+    pub cum_qty: Option<f64>,
+    /// LeavesQty (151)
+    pub leaves_qty: Option<f64>,
+    /// AvgPx (6)
+    pub avg_px: Option<f64>,
+    /// Commission (12) of the event
+    pub commission: Option<f64>,
+    /// Text (58)
+    pub text: Option<String>,
+}
+
+/// Lifecycle of one order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderLifecycle {
+    /// ClOrdID (11) the order was sent with
+    pub cl_ord_id: String,
+    /// OrderID (37) assigned by Deribit
+    pub order_id: Option<String>,
+    /// Instrument symbol
+    pub symbol: String,
+    /// Order side
+    pub side: OrderSide,
+    /// Order quantity, updated on replace
+    pub order_qty: f64,
+    /// Limit price, updated on replace
+    pub price: Option<f64>,
+    /// DeribitLabel (100010)
+    pub label: Option<String>,
+    /// Latest OrdStatus (39)
+    pub status: Option<OrderStatus>,
+    /// Filled quantity
+    pub filled_qty: f64,
+    /// Average fill price
+    pub avg_px: Option<f64>,
+    /// Sum of the commissions reported for the order
+    pub fees: f64,
+    /// DisplayQty (1138), the quantity shown on the book at a time
+    pub display_qty: Option<f64>,
+    /// Quantity filled from the slice currently shown on the book
+    pub slice_filled_qty: f64,
+    /// Number of times the displayed quantity was refilled
+    pub refills: u32,
+    /// Events in the order they happened
+    pub events: Vec<OrderAuditEvent>,
+}
+
+/// Refill of the displayed quantity of an iceberg order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IcebergRefill {
+    /// ClOrdID (11) of the order
+    pub cl_ord_id: String,
+    /// OrderID (37) assigned by Deribit
+    pub order_id: Option<String>,
+    /// Refills of the order so far, starting at 1
+    pub refills: u32,
+    /// Quantity shown on the book after the refill
+    pub displayed_qty: f64,
+    /// Total quantity still to fill
+    pub remaining_qty: f64,
+}
+
+impl OrderLifecycle {
+    /// When the order was first seen
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.events.first().map(|event| event.timestamp)
+    }
+
+    /// When the order last changed
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.events.last().map(|event| event.timestamp)
+    }
+
+    /// Whether the order is filled, cancelled, rejected or expired
+    pub fn is_complete(&self) -> bool {
+        self.status.is_some_and(OrderStatus::is_terminal)
+    }
+
+    /// Quantity still to fill
+    pub fn remaining_qty(&self) -> f64 {
+        if self.is_complete() {
+            return 0.0;
+        }
+        (self.order_qty - self.filled_qty).max(0.0)
+    }
+
+    /// Whether only part of the order is shown on the book
+    pub fn is_iceberg(&self) -> bool {
+        self.display_qty
+            .is_some_and(|display_qty| display_qty + QTY_EPSILON < self.order_qty)
+    }
+
+    /// Quantity currently shown on the book
+    ///
+    /// The remaining quantity for orders without a DisplayQty (1138).
+    pub fn displayed_qty(&self) -> f64 {
+        let remaining = self.remaining_qty();
+        match self.display_qty {
+            Some(display_qty) => (display_qty - self.slice_filled_qty)
+                .max(0.0)
+                .min(remaining),
+            None => remaining,
+        }
+    }
+
+    /// Count the refills caused by a fill of `last_qty`
+    ///
+    /// Returns whether the displayed quantity was refilled.
+    fn apply_fill(&mut self, last_qty: f64) -> bool {
+        let Some(display_qty) = self.display_qty.filter(|display_qty| *display_qty > 0.0) else {
+            return false;
+        };
+        let refills = self.refills;
+        let remaining = self.remaining_qty();
+        self.slice_filled_qty += last_qty;
+        // A slice is refilled when quantity is left once it is filled, even
+        // if the same fill also takes part of the next slice
+        while self.slice_filled_qty + QTY_EPSILON >= display_qty
+            && self.slice_filled_qty - display_qty + remaining > QTY_EPSILON
+        {
+            self.slice_filled_qty -= display_qty;
+            self.refills += 1;
+        }
+        if self.slice_filled_qty < QTY_EPSILON {
+            self.slice_filled_qty = 0.0;
+        }
+        self.refills > refills
+    }
+
+    /// Fills of the order, as (quantity, price)
+    pub fn fills(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.events
+            .iter()
+            .filter(|event| event.exec_type == Some(ExecType::Trade))
+            .filter_map(|event| Some((event.last_qty?, event.last_px?)))
+    }
+}
+
+/// Lifecycle of every order sent or reported in a session
+#[derive(Debug, Clone, Default)]
+pub struct OrderTracker {
+    orders: Vec<OrderLifecycle>,
+    /// Index in `orders` by ClOrdID (11), including replacements, and OrderID (37)
+    index: HashMap<String, usize>,
+    /// ExecID (17) of every recorded report
+    exec_ids: HashSet<String>,
+}
+
+impl OrderTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of tracked orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Whether no order is tracked
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Tracked orders in the order they were first seen
+    pub fn orders(&self) -> &[OrderLifecycle] {
+        &self.orders
+    }
+
+    /// Lifecycle of the order with ClOrdID (11) or OrderID (37) `id`
+    pub fn order(&self, id: &str) -> Option<&OrderLifecycle> {
+        self.index.get(id).map(|&index| &self.orders[index])
+    }
+
+    /// Whether a report with ExecID (17) `exec_id` was already recorded
+    pub fn has_execution(&self, exec_id: &str) -> bool {
+        self.exec_ids.contains(exec_id)
+    }
+
+    /// Exchange OrderID (37) of the order sent or replaced with ClOrdID (11)
+    /// `cl_ord_id`, once a report carried it
+    pub fn order_id_of(&self, cl_ord_id: &str) -> Option<&str> {
+        self.order(cl_ord_id)
+            .filter(|order| {
+                order.cl_ord_id == cl_ord_id
+                    || order
+                        .events
+                        .iter()
+                        .any(|event| event.cl_ord_id == cl_ord_id)
+            })?
+            .order_id
+            .as_deref()
+    }
+
+    /// ClOrdID (11) the order with exchange OrderID (37) `order_id` was first
+    /// sent or reported with
+    pub fn cl_ord_id_of(&self, order_id: &str) -> Option<&str> {
+        self.order(order_id)
+            .filter(|order| order.order_id.as_deref() == Some(order_id))
+            .map(|order| order.cl_ord_id.as_str())
+    }
+
+    /// Record an order sent with ClOrdID `cl_ord_id`
+    pub fn record_sent(&mut self, order: &NewOrderRequest, cl_ord_id: &str) {
+        self.index.insert(cl_ord_id.to_string(), self.orders.len());
+        self.orders.push(OrderLifecycle {
+            cl_ord_id: cl_ord_id.to_string(),
+            order_id: None,
+            symbol: order.instrument_name.clone(),
+            side: order.side,
+            order_qty: order.amount,
+            price: order.price,
+            label: order.label.clone(),
+            status: None,
+            filled_qty: 0.0,
+            avg_px: None,
+            fees: 0.0,
+            display_qty: order.max_show,
+            slice_filled_qty: 0.0,
+            refills: 0,
+            events: vec![OrderAuditEvent {
+                timestamp: Utc::now(),
+                kind: AuditEventKind::Submitted,
+                exec_id: None,
+                exec_type: None,
+                ord_status: None,
+                cl_ord_id: cl_ord_id.to_string(),
+                last_qty: None,
+                last_px: None,
+                cum_qty: None,
+                leaves_qty: None,
+                avg_px: None,
+                commission: None,
+                text: None,
+            }],
+        });
+    }
+
+    /// Record an Execution Report (8)
+    ///
+    /// The report is matched by ClOrdID (11), OrigClOrdID (41) or OrderID
+    /// (37); reports for orders sent outside the session start a new
+    /// lifecycle. Returns the refill of an iceberg order the report's fill
+    /// caused, if any.
+    pub fn record_report(&mut self, report: &ExecutionReport) -> Option<IcebergRefill> {
+        let found = [
+            Some(&report.cl_ord_id),
+            report.orig_cl_ord_id.as_ref(),
+            Some(&report.order_id),
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|id| self.index.get(id).copied());
+        let index = found.unwrap_or_else(|| {
+            self.orders.push(OrderLifecycle {
+                cl_ord_id: report.cl_ord_id.clone(),
+                order_id: None,
+                symbol: report.symbol.clone(),
+                side: match report.side {
+                    crate::message::OrderSide::Buy => OrderSide::Buy,
+                    crate::message::OrderSide::Sell => OrderSide::Sell,
+                },
+                order_qty: report.order_qty,
+                price: report.price,
+                label: None,
+                status: None,
+                filled_qty: 0.0,
+                avg_px: None,
+                fees: 0.0,
+                display_qty: None,
+                slice_filled_qty: 0.0,
+                refills: 0,
+                events: Vec::new(),
+            });
+            self.orders.len() - 1
+        });
+        for id in [&report.cl_ord_id, &report.order_id] {
+            if !id.is_empty() {
+                self.index.entry(id.clone()).or_insert(index);
+            }
+        }
+        if !report.exec_id.is_empty() {
+            self.exec_ids.insert(report.exec_id.clone());
+        }
+
+        let order = &mut self.orders[index];
+        if !report.order_id.is_empty() {
+            order.order_id = Some(report.order_id.clone());
+        }
+        if report.exec_type == ExecType::Replaced {
+            order.order_qty = report.order_qty;
+            order.price = report.price.or(order.price);
+        }
+        if let Some(label) = &report.deribit_label {
+            order.label = Some(label.clone());
+        }
+        if let Some(display_qty) = report.display_qty {
+            order.display_qty = Some(display_qty);
+        }
+        // Reports arriving after the order reached a final state, such as a
+        // late Pending Cancel, do not reopen it
+        if !order.is_complete() || report.ord_status.is_terminal() {
+            order.status = Some(report.ord_status);
+        }
+        order.filled_qty = report.cum_qty;
+        let refilled =
+            report.exec_type == ExecType::Trade && order.apply_fill(report.last_qty.unwrap_or(0.0));
+        order.avg_px = report.avg_px.or(order.avg_px);
+        order.fees += report.commission.unwrap_or(0.0);
+        order.events.push(OrderAuditEvent {
+            timestamp: report.transact_time,
+            kind: AuditEventKind::ExecutionReport,
+            exec_id: Some(report.exec_id.clone()),
+            exec_type: Some(report.exec_type),
+            ord_status: Some(report.ord_status),
+            cl_ord_id: report.cl_ord_id.clone(),
+            last_qty: report.last_qty,
+            last_px: report.last_px,
+            cum_qty: Some(report.cum_qty),
+            leaves_qty: Some(report.leaves_qty),
+            avg_px: report.avg_px,
+            commission: report.commission,
+            text: report.text.clone(),
+        });
+        refilled.then(|| IcebergRefill {
+            cl_ord_id: order.cl_ord_id.clone(),
+            order_id: order.order_id.clone(),
+            refills: order.refills,
+            displayed_qty: order.displayed_qty(),
+            remaining_qty: order.remaining_qty(),
+        })
+    }
+
+    /// Forget the orders that are filled, cancelled, rejected or expired
+    ///
+    /// Their executions are forgotten too, so a later replay of one of their
+    /// reports is no longer recognised by [`has_execution`](Self::has_execution).
+    pub fn clear_completed(&mut self) {
+        self.orders.retain(|order| !order.is_complete());
+        self.index.clear();
+        self.exec_ids = self
+            .orders
+            .iter()
+            .flat_map(|order| &order.events)
+            .filter_map(|event| event.exec_id.clone())
+            .collect();
+        for (index, order) in self.orders.iter().enumerate() {
+            let ids = std::iter::once(&order.cl_ord_id)
+                .chain(&order.order_id)
+                .chain(order.events.iter().map(|event| &event.cl_ord_id));
+            for id in ids {
+                self.index.insert(id.clone(), index);
+            }
+        }
+    }
+
+    /// Write every lifecycle to `writer` in `format`
+    pub fn export<W: Write>(&self, format: AuditFormat, mut writer: W) -> Result<()> {
+        match format {
+            AuditFormat::Json => serde_json::to_writer_pretty(&mut writer, &self.orders)?,
+            AuditFormat::Csv => {
+                writeln!(writer, "{CSV_HEADER}")?;
+                for order in &self.orders {
+                    for event in &order.events {
+                        writeln!(writer, "{}", csv_row(order, event))?;
+                    }
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Every lifecycle as a CSV or JSON document
+    pub fn export_string(&self, format: AuditFormat) -> Result<String> {
+        let mut buffer = Vec::new();
+        self.export(format, &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+fn csv_row(order: &OrderLifecycle, event: &OrderAuditEvent) -> String {
+    let number = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+    [
+        csv_field(&order.cl_ord_id),
+        csv_field(order.order_id.as_deref().unwrap_or_default()),
+        csv_field(&order.symbol),
+        variant_name(&order.side),
+        order.order_qty.to_string(),
+        number(order.price),
+        csv_field(order.label.as_deref().unwrap_or_default()),
+        event.timestamp.to_rfc3339(),
+        variant_name(&event.kind),
+        csv_field(event.exec_id.as_deref().unwrap_or_default()),
+        event
+            .exec_type
+            .as_ref()
+            .map(variant_name)
+            .unwrap_or_default(),
+        event
+            .ord_status
+            .as_ref()
+            .map(variant_name)
+            .unwrap_or_default(),
+        number(event.last_qty),
+        number(event.last_px),
+        number(event.cum_qty),
+        number(event.leaves_qty),
+        number(event.avg_px),
+        number(event.commission),
+        csv_field(event.text.as_deref().unwrap_or_default()),
+    ]
+    .join(",")
+}
+
+/// Quote a CSV field holding a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Serialized name of a unit enum variant
+fn variant_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::OrderSide as FixOrderSide;
+
+    fn report(cl_ord_id: &str, exec_type: ExecType, status: OrderStatus) -> ExecutionReport {
+        let mut report = ExecutionReport::new_order(
+            "ORD-1".to_string(),
+            cl_ord_id.to_string(),
+            format!("EXEC-{cl_ord_id}-{status:?}"),
+            "BTC-PERPETUAL".to_string(),
+            FixOrderSide::Buy,
+            10.0,
+            10.0,
+            Some(50_000.0),
+        );
+        report.exec_type = exec_type;
+        report.ord_status = status;
+        report
+    }
+
+    #[test]
+    fn test_lifecycle_with_replace_and_fills() {
+        let mut tracker = OrderTracker::new();
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_label("mm".to_string());
+        tracker.record_sent(&order, "A");
+        tracker.record_report(&report("A", ExecType::New, OrderStatus::New));
+
+        let mut replaced = report("A2", ExecType::Replaced, OrderStatus::New);
+        replaced.orig_cl_ord_id = Some("A".to_string());
+        replaced.order_qty = 20.0;
+        tracker.record_report(&replaced);
+
+        let mut fill = report("A2", ExecType::Trade, OrderStatus::Filled);
+        fill.last_qty = Some(20.0);
+        fill.last_px = Some(49_990.0);
+        fill.cum_qty = 20.0;
+        fill.commission = Some(0.5);
+        fill.text = Some("filled, \"fully\"".to_string());
+        tracker.record_report(&fill);
+
+        assert_eq!(tracker.len(), 1);
+        let lifecycle = tracker.order("ORD-1").unwrap();
+        assert_eq!(lifecycle, tracker.order("A2").unwrap());
+        assert_eq!(lifecycle.events.len(), 4);
+        assert_eq!(lifecycle.order_qty, 20.0);
+        assert_eq!(lifecycle.status, Some(OrderStatus::Filled));
+        assert_eq!(lifecycle.fees, 0.5);
+        assert_eq!(
+            lifecycle.fills().collect::<Vec<_>>(),
+            vec![(20.0, 49_990.0)]
+        );
+
+        let csv = tracker.export_string(AuditFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[1].starts_with("A,ORD-1,BTC-PERPETUAL,Buy,20,50000,mm,"));
+        assert!(lines[4].contains(",Trade,Filled,20,49990,20,"));
+        assert!(lines[4].ends_with(",0.5,\"filled, \"\"fully\"\"\""));
+
+        let json: Vec<OrderLifecycle> =
+            serde_json::from_str(&tracker.export_string(AuditFormat::Json).unwrap()).unwrap();
+        assert_eq!(json, tracker.orders());
+        assert!(tracker.has_execution(&fill.exec_id));
+        assert!(!tracker.has_execution("EXEC-UNKNOWN"));
+
+        tracker.clear_completed();
+        assert!(tracker.is_empty());
+        assert!(tracker.order("A").is_none());
+        assert!(!tracker.has_execution(&fill.exec_id));
+    }
+
+    #[test]
+    fn test_final_states_are_not_reopened() {
+        let mut tracker = OrderTracker::new();
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0);
+        tracker.record_sent(&order, "A");
+        // An untriggered stop order is pending until it triggers
+        tracker.record_report(&report("A", ExecType::PendingNew, OrderStatus::PendingNew));
+        assert!(!tracker.order("A").unwrap().is_complete());
+
+        tracker.record_report(&report("A", ExecType::Expired, OrderStatus::Expired));
+        tracker.record_report(&report(
+            "A",
+            ExecType::PendingCancel,
+            OrderStatus::PendingCancel,
+        ));
+        let lifecycle = tracker.order("A").unwrap();
+        assert_eq!(lifecycle.status, Some(OrderStatus::Expired));
+        assert_eq!(lifecycle.events.len(), 4);
+        assert_eq!(lifecycle.remaining_qty(), 0.0);
+    }
+
+    #[test]
+    fn test_client_and_exchange_ids_resolve_each_other() {
+        let mut tracker = OrderTracker::new();
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0);
+        tracker.record_sent(&order, "A");
+        assert_eq!(tracker.order_id_of("A"), None);
+
+        tracker.record_report(&report("A", ExecType::New, OrderStatus::New));
+        let mut replaced = report("A2", ExecType::Replaced, OrderStatus::New);
+        replaced.orig_cl_ord_id = Some("A".to_string());
+        tracker.record_report(&replaced);
+
+        assert_eq!(tracker.order_id_of("A"), Some("ORD-1"));
+        assert_eq!(tracker.order_id_of("A2"), Some("ORD-1"));
+        assert_eq!(tracker.cl_ord_id_of("ORD-1"), Some("A"));
+        // Each lookup only takes identifiers of its own kind
+        assert_eq!(tracker.order_id_of("ORD-1"), None);
+        assert_eq!(tracker.cl_ord_id_of("A"), None);
+    }
+
+    #[test]
+    fn test_iceberg_refills_are_counted_from_fills() {
+        let mut tracker = OrderTracker::new();
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_max_show(4.0);
+        tracker.record_sent(&order, "A");
+        tracker.record_report(&report("A", ExecType::New, OrderStatus::New));
+        let lifecycle = tracker.order("A").unwrap();
+        assert!(lifecycle.is_iceberg());
+        assert_eq!(lifecycle.displayed_qty(), 4.0);
+        assert_eq!(lifecycle.remaining_qty(), 10.0);
+
+        let fill = |last_qty: f64, cum_qty: f64, status: OrderStatus| {
+            let mut fill = report("A", ExecType::Trade, status);
+            fill.exec_id = format!("EXEC-{cum_qty}");
+            fill.last_qty = Some(last_qty);
+            fill.last_px = Some(50_000.0);
+            fill.cum_qty = cum_qty;
+            fill.leaves_qty = 10.0 - cum_qty;
+            fill
+        };
+
+        // Part of the first slice
+        assert_eq!(
+            tracker.record_report(&fill(3.0, 3.0, OrderStatus::PartiallyFilled)),
+            None
+        );
+        assert_eq!(tracker.order("A").unwrap().displayed_qty(), 1.0);
+
+        // The rest of the first slice and part of the second
+        let refill = tracker
+            .record_report(&fill(2.0, 5.0, OrderStatus::PartiallyFilled))
+            .unwrap();
+        assert_eq!(refill.refills, 1);
+        assert_eq!(refill.displayed_qty, 3.0);
+        assert_eq!(refill.remaining_qty, 5.0);
+
+        // The second slice leaves 2 for the last one
+        let refill = tracker
+            .record_report(&fill(3.0, 8.0, OrderStatus::PartiallyFilled))
+            .unwrap();
+        assert_eq!(refill.refills, 2);
+        assert_eq!(refill.displayed_qty, 2.0);
+
+        // Filling the last slice is not a refill
+        assert_eq!(
+            tracker.record_report(&fill(2.0, 10.0, OrderStatus::Filled)),
+            None
+        );
+        let lifecycle = tracker.order("A").unwrap();
+        assert_eq!(lifecycle.refills, 2);
+        assert_eq!(lifecycle.displayed_qty(), 0.0);
+        assert_eq!(lifecycle.remaining_qty(), 0.0);
+    }
+}
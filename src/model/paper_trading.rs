@@ -0,0 +1,703 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Simulated order execution (paper trading)
+//!
+//! When paper trading is enabled the session hands every outgoing New Order
+//! Single (D), Order Cancel Request (F) and Order Mass Cancel Request (q) to a
+//! [`PaperTradingEngine`] instead of the connection. Orders are matched against
+//! the local [`OrderBook`] built from market data and the engine answers with
+//! the messages Deribit would send: Execution Reports (8), Order Cancel
+//! Rejects (9) and Order Mass Cancel Reports (r).
+//!
+//! Only market and limit orders are simulated. Fills take the displayed size
+//! of each price level without consuming it, so resting orders and repeated
+//! orders may fill against the same quotes.
+
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
+use crate::message::{
+    ExecutionReport, MassCancelRequestType, OrderCancelReject, OrderMassCancelReport,
+    OrderRejectReason, OrderSide, OrderStatus, OrderType, TimeInForce,
+};
+use crate::model::message::FixMessage;
+use crate::model::order_book::OrderBook;
+use crate::model::tags;
+use crate::model::types::{ExecType, MsgType};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Order resting in the simulated book
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperOrder {
+    /// Simulated exchange OrderID (37)
+    pub order_id: String,
+    /// ClOrdID (11) of the order
+    pub cl_ord_id: String,
+    /// Instrument symbol
+    pub symbol: String,
+    /// Side (54)
+    pub side: OrderSide,
+    /// OrderQty (38)
+    pub order_qty: f64,
+    /// Limit price (44), `None` for market orders
+    pub price: Option<f64>,
+    /// TimeInForce (59)
+    pub time_in_force: TimeInForce,
+    /// DeribitLabel (100010)
+    pub label: Option<String>,
+    /// Quantity filled so far
+    pub cum_qty: f64,
+    /// Notional filled so far, used for the average price
+    notional: f64,
+}
+
+impl PaperOrder {
+    /// Quantity still open
+    pub fn leaves_qty(&self) -> f64 {
+        (self.order_qty - self.cum_qty).max(0.0)
+    }
+
+    /// Average fill price, `None` before the first fill
+    pub fn avg_px(&self) -> Option<f64> {
+        (self.cum_qty > 0.0).then(|| self.notional / self.cum_qty)
+    }
+
+    /// Executions available against `book` as (price, quantity), best first
+    fn executions(&self, book: &OrderBook) -> Vec<(f64, f64)> {
+        let levels = match self.side {
+            OrderSide::Buy => book.asks(),
+            OrderSide::Sell => book.bids(),
+        };
+        let mut remaining = self.leaves_qty();
+        let mut executions = Vec::new();
+        for (price, size) in levels {
+            let crosses = match (self.side, self.price) {
+                (_, None) => true,
+                (OrderSide::Buy, Some(limit)) => price <= limit,
+                (OrderSide::Sell, Some(limit)) => price >= limit,
+            };
+            if remaining <= 0.0 || !crosses {
+                break;
+            }
+            let quantity = size.min(remaining);
+            executions.push((price, quantity));
+            remaining -= quantity;
+        }
+        executions
+    }
+
+    /// Execution Report (8) with the current state of the order
+    fn report(&self, exec_id: String, exec_type: ExecType, status: OrderStatus) -> ExecutionReport {
+        let mut report = ExecutionReport::new_order(
+            self.order_id.clone(),
+            self.cl_ord_id.clone(),
+            exec_id,
+            self.symbol.clone(),
+            self.side,
+            self.order_qty,
+            self.leaves_qty(),
+            self.price,
+        );
+        report.exec_type = exec_type;
+        report.ord_status = status;
+        report.cum_qty = self.cum_qty;
+        report.avg_px = self.avg_px();
+        report.deribit_label = self.label.clone();
+        report
+    }
+}
+
+/// Matches orders against the local order books and produces synthetic reports
+#[derive(Debug, Clone)]
+pub struct PaperTradingEngine {
+    sender_comp_id: String,
+    target_comp_id: String,
+    orders: Vec<PaperOrder>,
+    next_order_id: u64,
+    next_exec_id: u64,
+}
+
+impl PaperTradingEngine {
+    /// Create an engine without open orders
+    ///
+    /// The comp IDs are those of the generated reports: the simulated exchange
+    /// is the sender and the client the target.
+    pub fn new(sender_comp_id: String, target_comp_id: String) -> Self {
+        Self {
+            sender_comp_id,
+            target_comp_id,
+            orders: Vec::new(),
+            next_order_id: 1,
+            next_exec_id: 1,
+        }
+    }
+
+    /// Open orders in time priority
+    pub fn open_orders(&self) -> &[PaperOrder] {
+        &self.orders
+    }
+
+    /// Simulate an outgoing message
+    ///
+    /// Returns the reports answering an order, cancel or mass cancel request,
+    /// or `None` for any other message, which must be sent to the exchange.
+    pub fn handle_outgoing(
+        &mut self,
+        message: &FixMessage,
+        books: &HashMap<String, OrderBook>,
+    ) -> DeribitFixResult<Option<Vec<FixMessage>>> {
+        match message.msg_type() {
+            Some(MsgType::NewOrderSingle) => self.new_order(message, books).map(Some),
+            Some(MsgType::OrderCancelRequest) => self.cancel(message).map(Some),
+            Some(MsgType::OrderMassCancelRequest) => self.mass_cancel(message).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Match the open orders of `book`'s instrument after a market data update
+    pub fn on_book_update(&mut self, book: &OrderBook) -> DeribitFixResult<Vec<FixMessage>> {
+        let mut reports = Vec::new();
+        let mut index = 0;
+        while index < self.orders.len() {
+            if self.orders[index].symbol != book.symbol() {
+                index += 1;
+                continue;
+            }
+            let mut order = self.orders.remove(index);
+            self.fill(&mut order, book, &mut reports)?;
+            if order.leaves_qty() > 0.0 {
+                self.orders.insert(index, order);
+                index += 1;
+            }
+        }
+        Ok(reports)
+    }
+
+    /// Simulate a New Order Single (D)
+    fn new_order(
+        &mut self,
+        message: &FixMessage,
+        books: &HashMap<String, OrderBook>,
+    ) -> DeribitFixResult<Vec<FixMessage>> {
+        let cl_ord_id = required(message, tags::CL_ORD_ID)?.clone();
+        let symbol = required(message, tags::SYMBOL)?.clone();
+        let side =
+            parse_char::<OrderSide>(message, tags::SIDE)?.ok_or_else(|| missing(tags::SIDE))?;
+        let order_qty = parse_field::<f64>(message, tags::ORDER_QTY)?
+            .ok_or_else(|| missing(tags::ORDER_QTY))?;
+        let price = parse_field::<f64>(message, tags::PRICE)?;
+        let label = message.get_field(tags::DERIBIT_LABEL).cloned();
+        // Time in force values without a simulation, such as good till date, rest
+        let time_in_force = parse_char::<TimeInForce>(message, tags::TIME_IN_FORCE)
+            .ok()
+            .flatten()
+            .unwrap_or(TimeInForce::GoodTillCancelled);
+        let post_only = message
+            .get_field(tags::EXEC_INST)
+            .is_some_and(|exec_inst| exec_inst.contains('6'));
+
+        let reject = |reason: OrderRejectReason, text: &str| {
+            let mut report = ExecutionReport::reject(
+                cl_ord_id.clone(),
+                symbol.clone(),
+                side,
+                order_qty,
+                reason,
+                Some(text.to_string()),
+            );
+            report.deribit_label = label.clone();
+            self.to_message(&report).map(|message| vec![message])
+        };
+
+        let ord_type = message
+            .get_field(tags::ORD_TYPE)
+            .and_then(|value| value.chars().next())
+            .and_then(|value| OrderType::try_from(value).ok());
+        let price = match (ord_type, price) {
+            (Some(OrderType::Market), _) => None,
+            (Some(OrderType::Limit), Some(price)) => Some(price),
+            (Some(OrderType::Limit), None) => {
+                return reject(OrderRejectReason::Other, "Limit order without price");
+            }
+            _ => {
+                return reject(
+                    OrderRejectReason::UnsupportedOrderCharacteristic,
+                    "Paper trading supports only market and limit orders",
+                );
+            }
+        };
+        if order_qty <= 0.0 {
+            return reject(
+                OrderRejectReason::IncorrectQuantity,
+                "Quantity must be positive",
+            );
+        }
+        let Some(book) = books.get(&symbol) else {
+            return reject(
+                OrderRejectReason::UnknownSymbol,
+                "No market data for symbol, subscribe before paper trading it",
+            );
+        };
+
+        let mut order = PaperOrder {
+            order_id: format!("PAPER-{}", self.next_order_id),
+            cl_ord_id,
+            symbol,
+            side,
+            order_qty,
+            price,
+            time_in_force,
+            label,
+            cum_qty: 0.0,
+            notional: 0.0,
+        };
+        self.next_order_id += 1;
+
+        let exec_id = self.next_exec_id();
+        let mut reports =
+            vec![self.to_message(&order.report(exec_id, ExecType::New, OrderStatus::New))?];
+
+        let available: f64 = order
+            .executions(book)
+            .iter()
+            .map(|(_, quantity)| quantity)
+            .sum();
+        let cancel_reason = if post_only && available > 0.0 {
+            Some("Post-only order would take liquidity")
+        } else if order.time_in_force == TimeInForce::FillOrKill && available < order.order_qty {
+            Some("Fill or kill order cannot be filled completely")
+        } else {
+            self.fill(&mut order, book, &mut reports)?;
+            let immediate =
+                order.price.is_none() || order.time_in_force == TimeInForce::ImmediateOrCancel;
+            (immediate && order.leaves_qty() > 0.0).then_some("Remaining quantity cancelled")
+        };
+
+        match cancel_reason {
+            Some(text) => reports.push(self.cancelled(&order, text)?),
+            None if order.leaves_qty() > 0.0 => self.orders.push(order),
+            None => {}
+        }
+        Ok(reports)
+    }
+
+    /// Simulate an Order Cancel Request (F)
+    ///
+    /// The order is looked up by OrigClOrdID (41), which may hold the OrderID
+    /// or the ClOrdID, then by ClOrdID (11) and DeribitLabel (100010).
+    fn cancel(&mut self, message: &FixMessage) -> DeribitFixResult<Vec<FixMessage>> {
+        let position = if let Some(orig_cl_ord_id) = message.get_field(tags::ORIG_CL_ORD_ID) {
+            self.orders.iter().position(|order| {
+                &order.order_id == orig_cl_ord_id || &order.cl_ord_id == orig_cl_ord_id
+            })
+        } else if let Some(cl_ord_id) = message.get_field(tags::CL_ORD_ID) {
+            self.orders
+                .iter()
+                .position(|order| &order.cl_ord_id == cl_ord_id)
+        } else {
+            let label = message.get_field(tags::DERIBIT_LABEL);
+            self.orders
+                .iter()
+                .position(|order| label.is_some() && order.label.as_ref() == label)
+        };
+
+        let Some(position) = position else {
+            let mut reject = OrderCancelReject::new(
+                Some(OrderStatus::Rejected),
+                Some(1), // Unknown order
+                Some("Order not found".to_string()),
+            )
+            .with_cxl_rej_response_to('1');
+            if let Some(cl_ord_id) = message.get_field(tags::CL_ORD_ID) {
+                reject = reject.with_cl_ord_id(cl_ord_id.clone());
+            }
+            if let Some(orig_cl_ord_id) = message.get_field(tags::ORIG_CL_ORD_ID) {
+                reject = reject.with_orig_cl_ord_id(orig_cl_ord_id.clone());
+            }
+            return reject
+                .to_fix_message(&self.sender_comp_id, &self.target_comp_id, 0)
+                .map(|message| vec![message]);
+        };
+
+        let order = self.orders.remove(position);
+        Ok(vec![self.cancelled(&order, "Cancelled by request")?])
+    }
+
+    /// Simulate an Order Mass Cancel Request (q)
+    fn mass_cancel(&mut self, message: &FixMessage) -> DeribitFixResult<Vec<FixMessage>> {
+        let cl_ord_id = message.get_field(tags::CL_ORD_ID).cloned();
+        let request_type = parse_field::<i32>(message, tags::MASS_CANCEL_REQUEST_TYPE)?
+            .ok_or_else(|| missing(tags::MASS_CANCEL_REQUEST_TYPE))
+            .and_then(|value| {
+                MassCancelRequestType::try_from(value).map_err(DeribitFixError::MessageParsing)
+            })?;
+        let symbol = message.get_field(tags::SYMBOL);
+        let label = message.get_field(tags::DERIBIT_LABEL);
+        let side = parse_char::<OrderSide>(message, tags::SIDE)?;
+
+        let selected = |order: &PaperOrder| -> bool {
+            let selected = match request_type {
+                MassCancelRequestType::AllOrders => true,
+                MassCancelRequestType::BySymbol => Some(&order.symbol) == symbol,
+                MassCancelRequestType::ByDeribitLabel => {
+                    label.is_some() && order.label.as_ref() == label
+                }
+                MassCancelRequestType::BySecurityType => false,
+            };
+            selected && side.is_none_or(|side| side == order.side)
+        };
+
+        let mut report = OrderMassCancelReport::new(cl_ord_id, request_type);
+        if request_type == MassCancelRequestType::BySecurityType {
+            report = report
+                .with_response(0)
+                .with_text("Cancel by security type is not simulated".to_string());
+            return report
+                .to_fix_message(&self.sender_comp_id, &self.target_comp_id, 0)
+                .map(|message| vec![message]);
+        }
+
+        let (cancelled, open): (Vec<_>, Vec<_>) = self.orders.drain(..).partition(selected);
+        self.orders = open;
+
+        let mut reports = Vec::new();
+        for order in &cancelled {
+            reports.push(self.cancelled(order, "Cancelled by mass cancel")?);
+        }
+        report = report
+            .with_response(i32::from(request_type))
+            .with_total_affected_orders(cancelled.len() as i32)
+            .with_affected_orders(cancelled.into_iter().map(|order| order.order_id).collect());
+        reports.push(report.to_fix_message(&self.sender_comp_id, &self.target_comp_id, 0)?);
+        Ok(reports)
+    }
+
+    /// Fill `order` against `book`, appending a Trade report per price level
+    fn fill(
+        &mut self,
+        order: &mut PaperOrder,
+        book: &OrderBook,
+        reports: &mut Vec<FixMessage>,
+    ) -> DeribitFixResult<()> {
+        for (price, quantity) in order.executions(book) {
+            order.cum_qty += quantity;
+            order.notional += price * quantity;
+            let status = if order.leaves_qty() > 0.0 {
+                OrderStatus::PartiallyFilled
+            } else {
+                OrderStatus::Filled
+            };
+            let exec_id = self.next_exec_id();
+            let mut report = order.report(exec_id, ExecType::Trade, status);
+            report.last_px = Some(price);
+            report.last_qty = Some(quantity);
+            reports.push(self.to_message(&report)?);
+        }
+        Ok(())
+    }
+
+    /// Cancelled report for `order`
+    fn cancelled(&mut self, order: &PaperOrder, text: &str) -> DeribitFixResult<FixMessage> {
+        let exec_id = self.next_exec_id();
+        let mut report = order.report(exec_id, ExecType::Canceled, OrderStatus::Cancelled);
+        report.leaves_qty = 0.0;
+        report.text = Some(text.to_string());
+        self.to_message(&report)
+    }
+
+    fn next_exec_id(&mut self) -> String {
+        let exec_id = format!("PAPER-EXEC-{}", self.next_exec_id);
+        self.next_exec_id += 1;
+        exec_id
+    }
+
+    fn to_message(&self, report: &ExecutionReport) -> DeribitFixResult<FixMessage> {
+        report.to_fix_message(&self.sender_comp_id, &self.target_comp_id, 0)
+    }
+}
+
+fn missing(tag: u32) -> DeribitFixError {
+    DeribitFixError::MessageParsing(format!("Tag {tag} is required for paper trading"))
+}
+
+fn required(message: &FixMessage, tag: u32) -> DeribitFixResult<&String> {
+    message.get_field(tag).ok_or_else(|| missing(tag))
+}
+
+fn parse_field<T: FromStr>(message: &FixMessage, tag: u32) -> DeribitFixResult<Option<T>> {
+    message
+        .get_field(tag)
+        .map(|value| {
+            value.parse().map_err(|_| {
+                DeribitFixError::MessageParsing(format!("Invalid value for tag {tag}: {value}"))
+            })
+        })
+        .transpose()
+}
+
+fn parse_char<T: TryFrom<char, Error = String>>(
+    message: &FixMessage,
+    tag: u32,
+) -> DeribitFixResult<Option<T>> {
+    message
+        .get_field(tag)
+        .map(|value| {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => T::try_from(c).map_err(DeribitFixError::MessageParsing),
+                _ => Err(DeribitFixError::MessageParsing(format!(
+                    "Invalid value for tag {tag}: {value}"
+                ))),
+            }
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{
+        MarketDataSnapshotFullRefresh, MdEntry, NewOrderSingle, OrderCancelRequest,
+        OrderMassCancelRequest,
+    };
+
+    fn books(entries: Vec<MdEntry>) -> HashMap<String, OrderBook> {
+        let snapshot =
+            MarketDataSnapshotFullRefresh::new("BTC-PERPETUAL".to_string()).with_entries(entries);
+        let mut book = OrderBook::new("BTC-PERPETUAL".to_string());
+        book.apply_snapshot(&snapshot);
+        HashMap::from([("BTC-PERPETUAL".to_string(), book)])
+    }
+
+    fn seeded_books() -> HashMap<String, OrderBook> {
+        books(vec![
+            MdEntry::bid(100.0, 1.0),
+            MdEntry::offer(101.0, 3.0),
+            MdEntry::offer(102.0, 4.0),
+        ])
+    }
+
+    fn engine() -> PaperTradingEngine {
+        PaperTradingEngine::new("DERIBITSERVER".to_string(), "CLIENT".to_string())
+    }
+
+    fn submit(
+        engine: &mut PaperTradingEngine,
+        order: NewOrderSingle,
+        books: &HashMap<String, OrderBook>,
+    ) -> Vec<ExecutionReport> {
+        let message = order.to_fix_message("CLIENT", "DERIBITSERVER", 1).unwrap();
+        parse_reports(engine.handle_outgoing(&message, books).unwrap().unwrap())
+    }
+
+    fn parse_reports(messages: Vec<FixMessage>) -> Vec<ExecutionReport> {
+        messages
+            .iter()
+            .map(|message| ExecutionReport::from_fix_message(message).unwrap())
+            .collect()
+    }
+
+    fn statuses(reports: &[ExecutionReport]) -> Vec<OrderStatus> {
+        reports.iter().map(|report| report.ord_status).collect()
+    }
+
+    #[test]
+    fn test_market_order_walks_the_book() {
+        let mut engine = engine();
+        let order = NewOrderSingle::market(
+            "BUY-1".to_string(),
+            OrderSide::Buy,
+            5.0,
+            "BTC-PERPETUAL".to_string(),
+        );
+        let reports = submit(&mut engine, order, &seeded_books());
+
+        assert_eq!(
+            statuses(&reports),
+            vec![
+                OrderStatus::New,
+                OrderStatus::PartiallyFilled,
+                OrderStatus::Filled
+            ]
+        );
+        assert_eq!(reports[1].last_px, Some(101.0));
+        assert_eq!(reports[1].last_qty, Some(3.0));
+        assert_eq!(reports[2].last_px, Some(102.0));
+        assert_eq!(reports[2].cum_qty, 5.0);
+        assert_eq!(reports[2].avg_px, Some((3.0 * 101.0 + 2.0 * 102.0) / 5.0));
+        assert!(engine.open_orders().is_empty());
+    }
+
+    #[test]
+    fn test_market_order_remainder_is_cancelled() {
+        let mut engine = engine();
+        let order = NewOrderSingle::market(
+            "SELL-1".to_string(),
+            OrderSide::Sell,
+            3.0,
+            "BTC-PERPETUAL".to_string(),
+        );
+        let reports = submit(&mut engine, order, &seeded_books());
+
+        assert_eq!(
+            statuses(&reports),
+            vec![
+                OrderStatus::New,
+                OrderStatus::PartiallyFilled,
+                OrderStatus::Cancelled
+            ]
+        );
+        assert_eq!(reports[2].cum_qty, 1.0);
+        assert!(engine.open_orders().is_empty());
+    }
+
+    #[test]
+    fn test_limit_order_rests_and_fills_on_book_update() {
+        let mut engine = engine();
+        let order = NewOrderSingle::limit(
+            "BID-1".to_string(),
+            OrderSide::Buy,
+            2.0,
+            100.5,
+            "BTC-PERPETUAL".to_string(),
+        );
+        let reports = submit(&mut engine, order, &seeded_books());
+        assert_eq!(statuses(&reports), vec![OrderStatus::New]);
+        assert_eq!(engine.open_orders().len(), 1);
+
+        let books = books(vec![MdEntry::bid(100.0, 1.0), MdEntry::offer(100.5, 5.0)]);
+        let reports = parse_reports(engine.on_book_update(&books["BTC-PERPETUAL"]).unwrap());
+        assert_eq!(statuses(&reports), vec![OrderStatus::Filled]);
+        assert_eq!(reports[0].order_id, "PAPER-1");
+        assert_eq!(reports[0].last_px, Some(100.5));
+        assert!(engine.open_orders().is_empty());
+    }
+
+    #[test]
+    fn test_fill_or_kill_without_liquidity_is_cancelled() {
+        let mut engine = engine();
+        let order = NewOrderSingle::limit(
+            "FOK-1".to_string(),
+            OrderSide::Buy,
+            5.0,
+            101.0,
+            "BTC-PERPETUAL".to_string(),
+        )
+        .with_time_in_force(TimeInForce::FillOrKill);
+        let reports = submit(&mut engine, order, &seeded_books());
+
+        assert_eq!(
+            statuses(&reports),
+            vec![OrderStatus::New, OrderStatus::Cancelled]
+        );
+        assert_eq!(reports[1].cum_qty, 0.0);
+    }
+
+    #[test]
+    fn test_unsupported_order_and_unknown_symbol_are_rejected() {
+        let mut engine = engine();
+        let order = NewOrderSingle::limit(
+            "STOP-1".to_string(),
+            OrderSide::Buy,
+            1.0,
+            100.0,
+            "BTC-PERPETUAL".to_string(),
+        )
+        .with_stop_price(99.0);
+        let mut message = order.to_fix_message("CLIENT", "DERIBITSERVER", 1).unwrap();
+        message.set_field(40, "4".to_string());
+        let reports = parse_reports(
+            engine
+                .handle_outgoing(&message, &seeded_books())
+                .unwrap()
+                .unwrap(),
+        );
+        assert_eq!(statuses(&reports), vec![OrderStatus::Rejected]);
+
+        let order = NewOrderSingle::market(
+            "ETH-1".to_string(),
+            OrderSide::Buy,
+            1.0,
+            "ETH-PERPETUAL".to_string(),
+        );
+        let reports = submit(&mut engine, order, &seeded_books());
+        assert_eq!(
+            reports[0].ord_rej_reason,
+            Some(OrderRejectReason::UnknownSymbol)
+        );
+    }
+
+    #[test]
+    fn test_cancel_by_order_id_and_unknown_order() {
+        let mut engine = engine();
+        let books = seeded_books();
+        let order = NewOrderSingle::limit(
+            "BID-1".to_string(),
+            OrderSide::Buy,
+            1.0,
+            90.0,
+            "BTC-PERPETUAL".to_string(),
+        );
+        submit(&mut engine, order, &books);
+
+        let cancel = OrderCancelRequest::by_order_id("PAPER-1".to_string())
+            .to_fix_message("CLIENT", "DERIBITSERVER", 2)
+            .unwrap();
+        let reports = parse_reports(engine.handle_outgoing(&cancel, &books).unwrap().unwrap());
+        assert_eq!(statuses(&reports), vec![OrderStatus::Cancelled]);
+        assert_eq!(reports[0].cl_ord_id, "BID-1");
+
+        let responses = engine.handle_outgoing(&cancel, &books).unwrap().unwrap();
+        assert_eq!(responses[0].msg_type(), Some(MsgType::OrderCancelReject));
+    }
+
+    #[test]
+    fn test_mass_cancel_by_label() {
+        let mut engine = engine();
+        let books = seeded_books();
+        for (cl_ord_id, label) in [("A", "grid"), ("B", "other"), ("C", "grid")] {
+            let order = NewOrderSingle::limit(
+                cl_ord_id.to_string(),
+                OrderSide::Buy,
+                1.0,
+                90.0,
+                "BTC-PERPETUAL".to_string(),
+            )
+            .with_label(label.to_string());
+            submit(&mut engine, order, &books);
+        }
+
+        let request =
+            OrderMassCancelRequest::by_deribit_label("MC-1".to_string(), "grid".to_string())
+                .to_fix_message("CLIENT", "DERIBITSERVER", 4)
+                .unwrap();
+        let responses = engine.handle_outgoing(&request, &books).unwrap().unwrap();
+
+        assert_eq!(responses.len(), 3);
+        let report = OrderMassCancelReport::from_fix_message(&responses[2]).unwrap();
+        assert_eq!(report.cl_ord_id.as_deref(), Some("MC-1"));
+        assert_eq!(report.total_affected_orders, Some(2));
+        let cancelled = parse_reports(responses[..2].to_vec());
+        assert_eq!(statuses(&cancelled), vec![OrderStatus::Cancelled; 2]);
+        assert_eq!(cancelled[0].order_id, "PAPER-1");
+        assert_eq!(cancelled[1].order_id, "PAPER-3");
+        assert_eq!(engine.open_orders().len(), 1);
+        assert_eq!(engine.open_orders()[0].cl_ord_id, "B");
+    }
+
+    #[test]
+    fn test_other_messages_are_not_simulated() {
+        let mut engine = engine();
+        let mut heartbeat = FixMessage::new();
+        heartbeat.set_field(35, "0".to_string());
+        assert!(
+            engine
+                .handle_outgoing(&heartbeat, &seeded_books())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
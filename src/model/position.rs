@@ -9,8 +9,13 @@
 //! This module provides position-related types that were previously imported from
 //! deribit-base. These types represent trading positions and their associated data.
 
+use crate::error::{DeribitFixError, Result};
+use crate::model::request::NewOrderRequest;
 use serde::{Deserialize, Serialize};
 
+/// Relative tolerance when rounding a closing amount down to the amount step
+const AMOUNT_STEP_TOLERANCE: f64 = 1e-9;
+
 /// Position direction enumeration
 ///
 /// Indicates whether a position is long (buy) or short (sell).
@@ -83,6 +88,50 @@ pub struct Position {
     pub unrealized_profit_loss: Option<f64>,
 }
 
+impl Position {
+    /// Reduce-only order closing `ratio` of the position
+    ///
+    /// `ratio` is in `(0, 1]`, `1.0` closing the whole position. The order
+    /// is a limit order at `price` when one is given and a market order
+    /// otherwise. A partial amount is rounded down to a multiple of
+    /// `amount_step`, such as the instrument's
+    /// [`amount_step`](crate::model::instrument_registry::InstrumentSpec::amount_step).
+    /// Fails when the position is flat or the amount to close rounds to zero.
+    pub fn close_order(
+        &self,
+        ratio: f64,
+        price: Option<f64>,
+        amount_step: Option<f64>,
+    ) -> Result<NewOrderRequest> {
+        if !(ratio > 0.0 && ratio <= 1.0) {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "Close ratio {ratio} must be in (0, 1]"
+            )));
+        }
+        let size = self.size.abs();
+        let mut amount = size * ratio;
+        if ratio < 1.0
+            && let Some(step) = amount_step.filter(|step| *step > 0.0)
+        {
+            amount = (amount / step + AMOUNT_STEP_TOLERANCE).floor() * step;
+        }
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "Nothing to close of the {size} {} position",
+                self.instrument_name
+            )));
+        }
+        let symbol = self.instrument_name.clone();
+        let order = match (self.direction, price) {
+            (Direction::Buy, None) => NewOrderRequest::market_sell(symbol, amount),
+            (Direction::Sell, None) => NewOrderRequest::market_buy(symbol, amount),
+            (Direction::Buy, Some(price)) => NewOrderRequest::limit_sell(symbol, amount, price),
+            (Direction::Sell, Some(price)) => NewOrderRequest::limit_buy(symbol, amount, price),
+        };
+        Ok(order.with_reduce_only(true))
+    }
+}
+
 impl_json_display!(Position);
 impl_json_debug_pretty!(Position);
 
@@ -145,6 +194,33 @@ mod tests {
         assert_eq!(position.average_price, 50000.0);
     }
 
+    #[test]
+    fn test_close_order_is_reduce_only_and_opposite() {
+        let position: Position = serde_json::from_value(serde_json::json!({
+            "instrument_name": "BTC-PERPETUAL",
+            "size": -250.0,
+            "direction": "sell",
+            "average_price": 60000.0,
+        }))
+        .unwrap();
+
+        let order = position.close_order(1.0, None, Some(10.0)).unwrap();
+        assert_eq!(order.side, crate::model::request::OrderSide::Buy);
+        assert_eq!(order.order_type, crate::model::request::OrderType::Market);
+        assert_eq!(order.amount, 250.0);
+        assert_eq!(order.reduce_only, Some(true));
+
+        // Partial closes are rounded down to the amount step
+        let order = position
+            .close_order(0.33, Some(59000.0), Some(10.0))
+            .unwrap();
+        assert_eq!(order.amount, 80.0);
+        assert_eq!(order.price, Some(59000.0));
+
+        assert!(position.close_order(0.01, None, Some(10.0)).is_err());
+        assert!(position.close_order(1.5, None, None).is_err());
+    }
+
     #[test]
     fn test_position_serialization_roundtrip() {
         let position = Position {
@@ -0,0 +1,98 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Public trades
+//!
+//! [`PublicTrade`] is a trade entry (MDEntryType 269 = 2) of a Market Data
+//! Snapshot/Full Refresh (W) or Incremental Refresh (X), as returned by a
+//! trade history request with DeribitTradeAmount (100007) and
+//! DeribitSinceTimestamp (100008).
+
+use crate::message::{MdEntry, MdEntryType};
+use crate::model::request::OrderSide;
+use crate::model::types::Liquidation;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Trade executed on an instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicTrade {
+    /// Instrument symbol
+    pub symbol: String,
+    /// DeribitTradeId (100009)
+    pub trade_id: Option<String>,
+    /// Trade price
+    pub price: f64,
+    /// Trade amount
+    pub amount: f64,
+    /// Side (54) of the taker
+    pub side: Option<OrderSide>,
+    /// Time of the trade
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Index price (44) at the time of the trade
+    pub index_price: Option<f64>,
+    /// Side liquidated by the trade, DeribitLiquidation (100091)
+    #[serde(default)]
+    pub liquidation: Liquidation,
+    /// Block trade ID, TrdMatchID (880)
+    pub block_trade_id: Option<String>,
+}
+
+impl PublicTrade {
+    /// Build a trade from a market data entry of `symbol`
+    ///
+    /// Returns `None` for entries that are not trades or lack a price or amount.
+    pub fn from_md_entry(symbol: &str, entry: &MdEntry) -> Option<Self> {
+        let (MdEntryType::Trade, Some(price), Some(amount)) =
+            (entry.md_entry_type, entry.md_entry_px, entry.md_entry_size)
+        else {
+            return None;
+        };
+        Some(Self {
+            symbol: symbol.to_string(),
+            trade_id: entry.trade_id.clone(),
+            price,
+            amount,
+            side: match entry.side {
+                Some('1') => Some(OrderSide::Buy),
+                Some('2') => Some(OrderSide::Sell),
+                _ => None,
+            },
+            timestamp: entry.md_entry_date,
+            index_price: entry.price,
+            liquidation: Liquidation::from_fix_value(entry.deribit_liquidation.as_deref()),
+            block_trade_id: entry.trd_match_id.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_md_entry() {
+        let time = Utc::now();
+        let mut entry = MdEntry::trade(100.5, 2.0, '2', "T1".to_string(), time);
+        entry.price = Some(100.0);
+
+        let trade = PublicTrade::from_md_entry("BTC-PERPETUAL", &entry).unwrap();
+        assert_eq!(trade.symbol, "BTC-PERPETUAL");
+        assert_eq!(trade.trade_id.as_deref(), Some("T1"));
+        assert_eq!(trade.price, 100.5);
+        assert_eq!(trade.amount, 2.0);
+        assert_eq!(trade.side, Some(OrderSide::Sell));
+        assert_eq!(trade.timestamp, Some(time));
+        assert_eq!(trade.index_price, Some(100.0));
+        assert_eq!(trade.liquidation, Liquidation::None);
+
+        entry.deribit_liquidation = Some("MT".to_string());
+        let trade = PublicTrade::from_md_entry("BTC-PERPETUAL", &entry).unwrap();
+        assert!(trade.liquidation.maker_liquidated() && trade.liquidation.taker_liquidated());
+
+        assert!(PublicTrade::from_md_entry("BTC-PERPETUAL", &MdEntry::bid(99.0, 1.0)).is_none());
+    }
+}
@@ -0,0 +1,595 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Two-sided quoting with a validity window
+//!
+//! A [`QuotingEngine`] holds the quotes a market maker wants to keep in the
+//! market, one [`QuoteSpec`] per instrument, and turns them into Mass Quote
+//! (i) and Quote Cancel (Z) messages. Prices are set around the mid price of
+//! the instrument; every quote is sent with a QuoteSetValidUntilTime (367)
+//! and refreshed shortly before it expires. When the mid moves further than
+//! the jump threshold from the price a quote was set at, the quote is
+//! cancelled and replaced at the new mid. Future and option combos are
+//! quoted around their spread price, which may be zero or negative.
+//!
+//! The engine also follows market maker protection (MMP): once Deribit
+//! reports that MMP was triggered, the exchange has already pulled the
+//! quotes and the engine stops quoting until a successful MM Protection
+//! Limits Result (MR), Deribit's answer to an MM Protection Reset (MZ), or
+//! [`set_mmp_frozen`](QuotingEngine::set_mmp_frozen) lifts the freeze.
+//!
+//! The engine only decides what to send; [`DeribitFixClient::run_quoting`](crate::DeribitFixClient::run_quoting)
+//! feeds it market data and sends its actions.
+
+use crate::error::{DeribitFixError, Result as DeribitFixResult};
+use crate::message::{MassQuote, QuoteCancel, QuoteEntry};
+use crate::model::instrument::InstrumentName;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// QuoteStatus (297) of a rejected Mass Quote Acknowledgement (b)
+const QUOTE_STATUS_REJECTED: &str = "5";
+
+/// MMProtectionResultStatus (9017) of a rejected MM Protection request
+const MMP_RESULT_REJECTED: &str = "1";
+
+/// ExecType (150) of a cancelled order or quote
+const EXEC_TYPE_CANCELED: &str = "4";
+
+/// Desired two-sided quote of an instrument
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteSpec {
+    /// Instrument symbol
+    pub symbol: String,
+    /// Distance between the bid and the offer price
+    pub spread: f64,
+    /// Bid size
+    pub bid_amount: f64,
+    /// Offer size
+    pub ask_amount: f64,
+    /// Price increment the bid is rounded down and the offer up to
+    pub tick_size: Option<f64>,
+}
+
+impl QuoteSpec {
+    /// Quote `amount` on both sides, `spread` apart around the mid price
+    pub fn new(symbol: String, spread: f64, amount: f64) -> Self {
+        Self {
+            symbol,
+            spread,
+            bid_amount: amount,
+            ask_amount: amount,
+            tick_size: None,
+        }
+    }
+
+    /// Set different bid and offer sizes
+    pub fn with_amounts(mut self, bid_amount: f64, ask_amount: f64) -> Self {
+        self.bid_amount = bid_amount;
+        self.ask_amount = ask_amount;
+        self
+    }
+
+    /// Round prices to the tick size of the instrument
+    pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    /// Bid and offer prices around `mid`
+    pub fn prices(&self, mid: f64) -> (f64, f64) {
+        let half = self.spread / 2.0;
+        match self.tick_size {
+            Some(tick) if tick > 0.0 => (
+                ((mid - half) / tick).floor() * tick,
+                ((mid + half) / tick).ceil() * tick,
+            ),
+            _ => (mid - half, mid + half),
+        }
+    }
+
+    fn validate(&self) -> DeribitFixResult<()> {
+        if self.symbol.is_empty() {
+            return Err(DeribitFixError::Config(
+                "Quote symbol must not be empty".to_string(),
+            ));
+        }
+        if !(self.spread.is_finite() && self.spread > 0.0) {
+            return Err(DeribitFixError::Config(format!(
+                "Quote spread of {} must be positive",
+                self.symbol
+            )));
+        }
+        if !(self.bid_amount > 0.0 && self.ask_amount > 0.0) {
+            return Err(DeribitFixError::Config(format!(
+                "Quote amounts of {} must be positive",
+                self.symbol
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Quote the engine has in the market
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveQuote {
+    /// QuoteID (117) of the Mass Quote that set it
+    pub quote_id: String,
+    /// QuoteEntryID (299)
+    pub quote_entry_id: String,
+    /// Instrument symbol
+    pub symbol: String,
+    /// Bid price
+    pub bid_px: f64,
+    /// Offer price
+    pub offer_px: f64,
+    /// Mid price the quote was set around
+    pub mid: f64,
+    /// Time the exchange drops the quote
+    pub valid_until: DateTime<Utc>,
+    /// Specification the quote was built from
+    pub spec: QuoteSpec,
+}
+
+/// Message the engine wants sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteAction {
+    /// Set or refresh quotes
+    Submit(MassQuote),
+    /// Pull quotes
+    Cancel(QuoteCancel),
+}
+
+/// Keeps declared two-sided quotes in the market
+#[derive(Debug, Clone)]
+pub struct QuotingEngine {
+    specs: BTreeMap<String, QuoteSpec>,
+    live: HashMap<String, LiveQuote>,
+    validity: Duration,
+    refresh_margin: Duration,
+    jump_threshold: f64,
+    mmp_frozen: bool,
+    next_id: u64,
+}
+
+impl Default for QuotingEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuotingEngine {
+    /// Create an engine without quotes
+    ///
+    /// Quotes are valid for 10 seconds, refreshed 2 seconds before they
+    /// expire and replaced when the mid moves by more than 0.1%.
+    pub fn new() -> Self {
+        Self {
+            specs: BTreeMap::new(),
+            live: HashMap::new(),
+            validity: Duration::from_secs(10),
+            refresh_margin: Duration::from_secs(2),
+            jump_threshold: 0.001,
+            mmp_frozen: false,
+            next_id: 0,
+        }
+    }
+
+    /// Set how long each quote stays valid
+    pub fn with_validity(mut self, validity: Duration) -> Self {
+        self.validity = validity;
+        self
+    }
+
+    /// Set how long before expiry a quote is refreshed
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Set the relative mid move that cancels and replaces a quote
+    pub fn with_jump_threshold(mut self, threshold: f64) -> Self {
+        self.jump_threshold = threshold;
+        self
+    }
+
+    /// Declare or change the quote of an instrument
+    ///
+    /// A changed specification is sent on the next [`update`](Self::update).
+    pub fn set_quote(&mut self, spec: QuoteSpec) -> DeribitFixResult<()> {
+        spec.validate()?;
+        self.specs.insert(spec.symbol.clone(), spec);
+        Ok(())
+    }
+
+    /// Stop quoting an instrument, returning the cancel of its live quote
+    pub fn remove_quote(&mut self, symbol: &str) -> Option<QuoteAction> {
+        self.specs.remove(symbol);
+        self.live.remove(symbol).map(|quote| self.cancel(&quote))
+    }
+
+    /// Symbols with a declared quote
+    pub fn symbols(&self) -> Vec<String> {
+        self.specs.keys().cloned().collect()
+    }
+
+    /// Declared quote of an instrument
+    pub fn spec(&self, symbol: &str) -> Option<&QuoteSpec> {
+        self.specs.get(symbol)
+    }
+
+    /// Quote currently in the market for an instrument
+    pub fn live_quote(&self, symbol: &str) -> Option<&LiveQuote> {
+        self.live.get(symbol)
+    }
+
+    /// Whether MMP has frozen quoting
+    pub fn is_mmp_frozen(&self) -> bool {
+        self.mmp_frozen
+    }
+
+    /// Freeze or resume quoting after an MMP trigger
+    ///
+    /// Freezing forgets the live quotes, which the exchange has pulled.
+    pub fn set_mmp_frozen(&mut self, frozen: bool) {
+        self.mmp_frozen = frozen;
+        if frozen {
+            self.live.clear();
+        }
+    }
+
+    /// Bring the quote of `symbol` in line with the mid price at `now`
+    ///
+    /// Returns the messages to send: nothing while the quote is current, a
+    /// Mass Quote when it is missing, changed or close to expiry, and a
+    /// Quote Cancel followed by a new Mass Quote after a book jump. Without a
+    /// mid price the live quote is cancelled.
+    pub fn update(
+        &mut self,
+        symbol: &str,
+        mid: Option<f64>,
+        now: DateTime<Utc>,
+    ) -> Vec<QuoteAction> {
+        let Some(spec) = self.specs.get(symbol).cloned() else {
+            return Vec::new();
+        };
+        if self.mmp_frozen {
+            return Vec::new();
+        }
+        let mut actions = Vec::new();
+        let combo = symbol
+            .parse::<InstrumentName>()
+            .is_ok_and(|name| name.is_combo());
+        let Some(mid) = mid.filter(|mid| mid.is_finite() && (combo || *mid > 0.0)) else {
+            if let Some(quote) = self.live.remove(symbol) {
+                actions.push(self.cancel(&quote));
+            }
+            return actions;
+        };
+        if let Some(quote) = self.live.get(symbol) {
+            let jumped = (mid - quote.mid).abs() > self.jump_threshold * quote.mid.abs();
+            let expiring = now + self.refresh_margin >= quote.valid_until;
+            if !jumped && !expiring && quote.spec == spec {
+                return actions;
+            }
+            if jumped {
+                let quote = quote.clone();
+                actions.push(self.cancel(&quote));
+            }
+        }
+        actions.push(self.submit(spec, mid, now));
+        actions
+    }
+
+    /// Time the next live quote is due for a refresh
+    pub fn next_refresh(&self) -> Option<DateTime<Utc>> {
+        self.live
+            .values()
+            .map(|quote| quote.valid_until - self.refresh_margin)
+            .min()
+    }
+
+    /// Pull every live quote
+    pub fn cancel_all(&mut self) -> Option<QuoteAction> {
+        if self.live.is_empty() {
+            return None;
+        }
+        self.live.clear();
+        Some(QuoteAction::Cancel(QuoteCancel::cancel_all(
+            self.next_id("cancel"),
+        )))
+    }
+
+    /// Apply a received message
+    ///
+    /// Rejected Mass Quote Acknowledgements (b) drop the quotes they refer
+    /// to so they are sent again, and a rejection or cancellation whose Text
+    /// (58) mentions MMP freezes quoting. A successful MM Protection Limits
+    /// Result (MR) resumes it. Returns whether the state of the engine
+    /// changed.
+    pub fn on_message(&mut self, message: &FixMessage) -> bool {
+        let mentions_mmp = message
+            .get_field(tags::TEXT)
+            .is_some_and(|text| text.to_ascii_lowercase().contains("mmp"));
+        match message.get_field(tags::MSG_TYPE).map(String::as_str) {
+            Some("b")
+                if message.get_field(tags::QUOTE_STATUS).map(String::as_str)
+                    == Some(QUOTE_STATUS_REJECTED) =>
+            {
+                if mentions_mmp {
+                    self.set_mmp_frozen(true);
+                    return true;
+                }
+                let Some(quote_id) = message.get_field(tags::QUOTE_ID) else {
+                    return false;
+                };
+                let before = self.live.len();
+                self.live.retain(|_, quote| &quote.quote_id != quote_id);
+                self.live.len() != before
+            }
+            Some("8")
+                if mentions_mmp
+                    && message.get_field(tags::EXEC_TYPE).map(String::as_str)
+                        == Some(EXEC_TYPE_CANCELED) =>
+            {
+                self.set_mmp_frozen(true);
+                true
+            }
+            Some("MR")
+                if self.mmp_frozen
+                    && message
+                        .get_field(tags::mm_protection::RESULT_STATUS)
+                        .map(String::as_str)
+                        != Some(MMP_RESULT_REJECTED) =>
+            {
+                self.mmp_frozen = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}-{}", prefix, self.next_id)
+    }
+
+    fn submit(&mut self, spec: QuoteSpec, mid: f64, now: DateTime<Utc>) -> QuoteAction {
+        let quote_id = self.next_id("quote");
+        let quote_entry_id = format!("{}-{}", spec.symbol, self.next_id);
+        let valid_until =
+            now + chrono::Duration::from_std(self.validity).unwrap_or(chrono::Duration::MAX);
+        let (bid_px, offer_px) = spec.prices(mid);
+        let entry = QuoteEntry::two_sided(
+            quote_entry_id.clone(),
+            spec.symbol.clone(),
+            bid_px,
+            offer_px,
+            spec.bid_amount,
+            spec.ask_amount,
+        )
+        .with_valid_until(valid_until);
+        let message = MassQuote::new(quote_id.clone(), spec.symbol.clone(), vec![entry])
+            .with_quote_set_valid_until(valid_until);
+        self.live.insert(
+            spec.symbol.clone(),
+            LiveQuote {
+                quote_id,
+                quote_entry_id,
+                symbol: spec.symbol.clone(),
+                bid_px,
+                offer_px,
+                mid,
+                valid_until,
+                spec,
+            },
+        );
+        QuoteAction::Submit(message)
+    }
+
+    fn cancel(&mut self, quote: &LiveQuote) -> QuoteAction {
+        QuoteAction::Cancel(QuoteCancel::cancel_for_symbol(
+            self.next_id("cancel"),
+            quote.symbol.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn submitted(actions: &[QuoteAction]) -> Vec<&MassQuote> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                QuoteAction::Submit(quote) => Some(quote),
+                QuoteAction::Cancel(_) => None,
+            })
+            .collect()
+    }
+
+    fn engine() -> QuotingEngine {
+        let mut engine = QuotingEngine::new()
+            .with_validity(Duration::from_secs(10))
+            .with_refresh_margin(Duration::from_secs(2))
+            .with_jump_threshold(0.01);
+        engine
+            .set_quote(QuoteSpec::new("BTC-PERPETUAL".to_string(), 10.0, 100.0).with_tick_size(0.5))
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_quotes_are_set_around_the_mid_and_refreshed_before_expiry() {
+        let mut engine = engine();
+        let now = start();
+
+        let actions = engine.update("BTC-PERPETUAL", Some(50_000.2), now);
+        let quotes = submitted(&actions);
+        assert_eq!(quotes.len(), 1);
+        let entry = &quotes[0].quote_entries[0];
+        assert_eq!(entry.bid_px, Some(49_995.0));
+        assert_eq!(entry.offer_px, Some(50_005.5));
+        assert_eq!(
+            quotes[0].quote_set_valid_until_time,
+            Some(now + chrono::Duration::seconds(10))
+        );
+        assert_eq!(
+            engine.next_refresh(),
+            Some(now + chrono::Duration::seconds(8))
+        );
+
+        // Current quotes are left alone until the refresh margin
+        let later = now + chrono::Duration::seconds(7);
+        assert!(
+            engine
+                .update("BTC-PERPETUAL", Some(50_010.0), later)
+                .is_empty()
+        );
+        let due = now + chrono::Duration::seconds(8);
+        let actions = engine.update("BTC-PERPETUAL", Some(50_010.0), due);
+        assert_eq!(submitted(&actions).len(), 1);
+        assert!(!actions.iter().any(|a| matches!(a, QuoteAction::Cancel(_))));
+        assert_eq!(engine.live_quote("BTC-PERPETUAL").unwrap().mid, 50_010.0);
+    }
+
+    #[test]
+    fn test_book_jump_cancels_and_requotes() {
+        let mut engine = engine();
+        let now = start();
+        engine.update("BTC-PERPETUAL", Some(50_000.0), now);
+
+        let actions = engine.update("BTC-PERPETUAL", Some(51_000.0), now);
+        assert_eq!(actions.len(), 2);
+        assert!(matches!(&actions[0], QuoteAction::Cancel(cancel)
+            if cancel.quote_cancel_entries[0].symbol == "BTC-PERPETUAL"));
+        assert_eq!(
+            submitted(&actions)[0].quote_entries[0].bid_px,
+            Some(50_995.0)
+        );
+
+        // Losing the reference price pulls the quote
+        let actions = engine.update("BTC-PERPETUAL", None, now);
+        assert!(matches!(actions.as_slice(), [QuoteAction::Cancel(_)]));
+        assert!(engine.live_quote("BTC-PERPETUAL").is_none());
+    }
+
+    #[test]
+    fn test_combo_is_quoted_around_a_negative_mid() {
+        let mut engine = engine();
+        let now = start();
+        engine
+            .set_quote(QuoteSpec::new("BTC-FS-27DEC24_PERP".to_string(), 5.0, 10.0))
+            .unwrap();
+
+        let actions = engine.update("BTC-FS-27DEC24_PERP", Some(-20.0), now);
+        let quotes = submitted(&actions);
+        let entry = &quotes[0].quote_entries[0];
+        assert_eq!((entry.bid_px, entry.offer_px), (Some(-22.5), Some(-17.5)));
+        assert!(quotes[0].validate().is_ok());
+
+        // The jump is measured against the size of the mid
+        assert!(
+            engine
+                .update("BTC-FS-27DEC24_PERP", Some(-20.1), now)
+                .is_empty()
+        );
+        let actions = engine.update("BTC-FS-27DEC24_PERP", Some(-19.0), now);
+        assert_eq!(actions.len(), 2);
+
+        // Outright instruments still need a positive mid
+        assert!(engine.update("BTC-PERPETUAL", Some(-1.0), now).is_empty());
+    }
+
+    #[test]
+    fn test_changed_spec_is_sent_and_removed_spec_cancelled() {
+        let mut engine = engine();
+        let now = start();
+        engine.update("BTC-PERPETUAL", Some(50_000.0), now);
+
+        engine
+            .set_quote(QuoteSpec::new("BTC-PERPETUAL".to_string(), 20.0, 50.0).with_tick_size(0.5))
+            .unwrap();
+        let actions = engine.update("BTC-PERPETUAL", Some(50_000.0), now);
+        assert_eq!(submitted(&actions)[0].quote_entries[0].bid_size, Some(50.0));
+
+        assert!(matches!(
+            engine.remove_quote("BTC-PERPETUAL"),
+            Some(QuoteAction::Cancel(_))
+        ));
+        assert!(
+            engine
+                .update("BTC-PERPETUAL", Some(50_000.0), now)
+                .is_empty()
+        );
+        assert!(
+            engine
+                .set_quote(QuoteSpec::new("ETH-PERPETUAL".to_string(), 0.0, 1.0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_mmp_trigger_freezes_quoting_until_reset() {
+        let mut engine = engine();
+        let now = start();
+        engine.update("BTC-PERPETUAL", Some(50_000.0), now);
+
+        let mut cancelled = FixMessage::new();
+        cancelled.set_field(tags::MSG_TYPE, "8".to_string());
+        cancelled.set_field(tags::EXEC_TYPE, "4".to_string());
+        cancelled.set_field(tags::TEXT, "mmp_trigger".to_string());
+        assert!(engine.on_message(&cancelled));
+        assert!(engine.is_mmp_frozen());
+        assert!(engine.live_quote("BTC-PERPETUAL").is_none());
+        assert!(
+            engine
+                .update("BTC-PERPETUAL", Some(50_000.0), now)
+                .is_empty()
+        );
+        assert!(engine.cancel_all().is_none());
+
+        let mut reset = FixMessage::new();
+        reset.set_field(tags::MSG_TYPE, "MR".to_string());
+        reset.set_field(tags::mm_protection::RESULT_STATUS, "2".to_string());
+        assert!(engine.on_message(&reset));
+        assert!(!engine.is_mmp_frozen());
+        assert_eq!(
+            submitted(&engine.update("BTC-PERPETUAL", Some(50_000.0), now)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_rejected_quote_is_sent_again() {
+        let mut engine = engine();
+        let now = start();
+        engine.update("BTC-PERPETUAL", Some(50_000.0), now);
+        let quote_id = engine.live_quote("BTC-PERPETUAL").unwrap().quote_id.clone();
+
+        let mut ack = FixMessage::new();
+        ack.set_field(tags::MSG_TYPE, "b".to_string());
+        ack.set_field(tags::QUOTE_ID, quote_id);
+        ack.set_field(tags::QUOTE_STATUS, "5".to_string());
+        assert!(engine.on_message(&ack));
+        assert!(!engine.is_mmp_frozen());
+        assert_eq!(
+            submitted(&engine.update("BTC-PERPETUAL", Some(50_000.0), now)).len(),
+            1
+        );
+        assert!(matches!(engine.cancel_all(), Some(QuoteAction::Cancel(_))));
+    }
+}
@@ -10,6 +10,9 @@
 //! deribit-base. These types represent order requests and their parameters
 //! in API-style format (not FIX protocol format).
 
+use crate::error::Result;
+use crate::model::exec_inst::{ExecInst, SelfTradePrevention, check_order_instructions};
+use crate::model::instrument_registry::InstrumentRegistry;
 use serde::{Deserialize, Serialize};
 
 /// Time in force enumeration (API style)
@@ -42,6 +45,15 @@ impl TimeInForce {
             TimeInForce::ImmediateOrCancel => "immediate_or_cancel",
         }
     }
+
+    /// Whether the order is cancelled unless it executes on entry
+    #[must_use]
+    pub fn is_immediate(&self) -> bool {
+        matches!(
+            self,
+            TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill
+        )
+    }
 }
 
 /// Order side enumeration (API style)
@@ -101,6 +113,19 @@ impl OrderType {
             OrderType::TrailingStop => "trailing_stop",
         }
     }
+
+    /// Whether the order executes at market, on entry or when triggered
+    #[must_use]
+    pub fn is_market(&self) -> bool {
+        matches!(
+            self,
+            OrderType::Market
+                | OrderType::StopMarket
+                | OrderType::TakeMarket
+                | OrderType::MarketLimit
+                | OrderType::TrailingStop
+        )
+    }
 }
 
 /// Trigger type for stop orders
@@ -132,7 +157,12 @@ pub enum AdvancedOrderType {
 ///
 /// This structure represents an order request in the API format used by
 /// deribit-base. It contains all parameters needed to place a new order.
+/// Orders are created with [`market_buy`](Self::market_buy),
+/// [`limit_sell`](Self::limit_sell) and the other constructors and refined
+/// with the `with_*` methods, so new fields can be added without breaking
+/// callers.
 #[derive(Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct NewOrderRequest {
     /// Instrument name (e.g., "BTC-PERPETUAL")
     pub instrument_name: String,
@@ -167,6 +197,8 @@ pub struct NewOrderRequest {
     pub valid_until: Option<i64>,
     /// Client order ID for tracking
     pub client_order_id: Option<String>,
+    /// Self-trade prevention, the session default when unset
+    pub self_trade_prevention: Option<SelfTradePrevention>,
 }
 
 impl_json_display!(NewOrderRequest);
@@ -193,6 +225,7 @@ impl NewOrderRequest {
             reject_post_only: None,
             valid_until: None,
             client_order_id: None,
+            self_trade_prevention: None,
         }
     }
 
@@ -216,6 +249,7 @@ impl NewOrderRequest {
             reject_post_only: None,
             valid_until: None,
             client_order_id: None,
+            self_trade_prevention: None,
         }
     }
 
@@ -239,6 +273,7 @@ impl NewOrderRequest {
             reject_post_only: None,
             valid_until: None,
             client_order_id: None,
+            self_trade_prevention: None,
         }
     }
 
@@ -262,6 +297,7 @@ impl NewOrderRequest {
             reject_post_only: None,
             valid_until: None,
             client_order_id: None,
+            self_trade_prevention: None,
         }
     }
 
@@ -279,6 +315,46 @@ impl NewOrderRequest {
         self
     }
 
+    /// Show at most `max_show` of the amount on the book, 0 hiding the order
+    #[must_use]
+    pub fn with_max_show(mut self, max_show: f64) -> Self {
+        self.max_show = Some(max_show);
+        self
+    }
+
+    /// Hide the whole amount from the book
+    #[must_use]
+    pub fn hidden(self) -> Self {
+        self.with_max_show(0.0)
+    }
+
+    /// Execution instructions set by the post-only and reduce-only flags
+    #[must_use]
+    pub fn exec_inst(&self) -> ExecInst {
+        ExecInst::default()
+            .with_post_only(self.post_only == Some(true))
+            .with_reduce_only(self.reduce_only == Some(true))
+    }
+
+    /// Set what happens when the order would match an order of the same account
+    #[must_use]
+    pub fn with_self_trade_prevention(mut self, stp: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = Some(stp);
+        self
+    }
+
+    /// Check that the post-only, reduce-only and display flags fit the
+    /// order type and time in force
+    pub fn validate_instructions(&self) -> Result<()> {
+        check_order_instructions(
+            self.exec_inst(),
+            self.order_type.is_market(),
+            self.time_in_force.is_immediate(),
+            self.max_show,
+            Some(self.amount),
+        )
+    }
+
     /// Set order label
     #[must_use]
     pub fn with_label(mut self, label: String) -> Self {
@@ -299,6 +375,55 @@ impl NewOrderRequest {
         self.client_order_id = Some(client_order_id);
         self
     }
+
+    /// Set the stop price of a stop order
+    #[must_use]
+    pub fn with_stop_price(mut self, stop_price: f64) -> Self {
+        self.stop_price = Some(stop_price);
+        self
+    }
+
+    /// Set the price triggering a stop order
+    #[must_use]
+    pub fn with_trigger(mut self, trigger: TriggerType) -> Self {
+        self.trigger = Some(trigger);
+        self
+    }
+
+    /// Set the advanced order type of an option order
+    #[must_use]
+    pub fn with_advanced(mut self, advanced: AdvancedOrderType) -> Self {
+        self.advanced = Some(advanced);
+        self
+    }
+
+    /// Set whether a post-only order crossing the book is rejected rather
+    /// than repriced
+    #[must_use]
+    pub fn with_reject_post_only(mut self, reject_post_only: bool) -> Self {
+        self.reject_post_only = Some(reject_post_only);
+        self
+    }
+
+    /// Set the timestamp after which the request is not processed
+    #[must_use]
+    pub fn with_valid_until(mut self, valid_until: i64) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    /// Set the amount to `contracts` contracts of the instrument
+    ///
+    /// The amount is converted to units with the contract multiplier known
+    /// to `instruments`, failing for an instrument it does not know.
+    pub fn with_contracts(
+        mut self,
+        contracts: f64,
+        instruments: &InstrumentRegistry,
+    ) -> Result<Self> {
+        self.amount = instruments.to_units(&self.instrument_name, contracts)?;
+        Ok(self)
+    }
 }
 
 #[cfg(test)]
@@ -377,4 +502,21 @@ mod tests {
         assert_eq!(order.post_only, deserialized.post_only);
         assert_eq!(order.label, deserialized.label);
     }
+
+    #[test]
+    fn test_amount_given_in_contracts() {
+        use crate::model::instrument_registry::InstrumentSpec;
+
+        let mut instruments = InstrumentRegistry::new();
+        instruments.register(
+            InstrumentSpec::new("BTC-PERPETUAL".to_string()).with_contract_multiplier(10.0),
+        );
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 0.0, 50000.0)
+            .with_contracts(3.0, &instruments)
+            .unwrap();
+        assert_eq!(order.amount, 30.0);
+
+        let unknown = NewOrderRequest::market_sell("ETH-PERPETUAL".to_string(), 0.0);
+        assert!(unknown.with_contracts(1.0, &instruments).is_err());
+    }
 }
@@ -0,0 +1,366 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Pre-trade risk checks
+//!
+//! [`RiskGuard`] checks every new order against the configured
+//! [`RiskLimits`] before it is sent, so an order breaching a limit is rejected
+//! locally with [`DeribitFixError::RiskLimit`](crate::error::DeribitFixError::RiskLimit)
+//! and never reaches the exchange. Open orders are tracked from the orders
+//! sent and the Execution Reports (8) received for them. The notional rate is
+//! measured at the times passed in by the session, read from its
+//! [`Clock`](crate::session::Clock).
+
+use crate::message::OrderStatus;
+use crate::model::request::{NewOrderRequest, OrderType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Window over which the notional of sent orders is limited
+const NOTIONAL_WINDOW: Duration = Duration::from_secs(60);
+
+/// Limits enforced before an order is sent; unset limits are not checked
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiskLimits {
+    /// Maximum number of open orders per instrument
+    pub max_open_orders_per_instrument: Option<usize>,
+    /// Maximum amount of a single order
+    pub max_order_amount: Option<f64>,
+    /// Maximum notional (amount × price) sent in any 60 second window
+    pub max_notional_per_minute: Option<f64>,
+    /// Maximum distance of a limit price from the mark price, as a fraction
+    /// of the mark price (e.g. `0.05` for 5%)
+    pub price_collar: Option<f64>,
+}
+
+impl RiskLimits {
+    /// Create limits that check nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of open orders per instrument
+    pub fn with_max_open_orders_per_instrument(mut self, max: usize) -> Self {
+        self.max_open_orders_per_instrument = Some(max);
+        self
+    }
+
+    /// Set the maximum amount of a single order
+    pub fn with_max_order_amount(mut self, max: f64) -> Self {
+        self.max_order_amount = Some(max);
+        self
+    }
+
+    /// Set the maximum notional sent per minute
+    pub fn with_max_notional_per_minute(mut self, max: f64) -> Self {
+        self.max_notional_per_minute = Some(max);
+        self
+    }
+
+    /// Set the maximum distance of a limit price from the mark price
+    pub fn with_price_collar(mut self, collar: f64) -> Self {
+        self.price_collar = Some(collar);
+        self
+    }
+}
+
+/// Limit an order would breach
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RiskViolation {
+    /// The instrument already has the maximum number of open orders
+    TooManyOpenOrders {
+        /// Instrument symbol
+        symbol: String,
+        /// Configured maximum
+        limit: usize,
+    },
+    /// The order amount is above the maximum
+    OrderTooLarge {
+        /// Order amount
+        amount: f64,
+        /// Configured maximum
+        limit: f64,
+    },
+    /// The order would take the notional sent in the last minute above the maximum
+    NotionalRateExceeded {
+        /// Notional of the last minute including the order
+        notional: f64,
+        /// Configured maximum
+        limit: f64,
+    },
+    /// The limit price is too far from the mark price
+    PriceOutsideCollar {
+        /// Order price
+        price: f64,
+        /// Mark price of the instrument
+        mark_price: f64,
+        /// Configured collar
+        collar: f64,
+    },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskViolation::TooManyOpenOrders { symbol, limit } => {
+                write!(f, "{symbol} already has {limit} open orders")
+            }
+            RiskViolation::OrderTooLarge { amount, limit } => {
+                write!(f, "order amount {amount} exceeds {limit}")
+            }
+            RiskViolation::NotionalRateExceeded { notional, limit } => {
+                write!(
+                    f,
+                    "notional of {notional} in the last minute exceeds {limit}"
+                )
+            }
+            RiskViolation::PriceOutsideCollar {
+                price,
+                mark_price,
+                collar,
+            } => write!(
+                f,
+                "price {price} is more than {}% away from mark price {mark_price}",
+                collar * 100.0
+            ),
+        }
+    }
+}
+
+/// Enforces [`RiskLimits`] and tracks the open orders they depend on
+#[derive(Debug, Clone, Default)]
+pub struct RiskGuard {
+    limits: RiskLimits,
+    /// ClOrdIDs (11) of open orders by instrument
+    open_orders: HashMap<String, Vec<String>>,
+    /// Send time and notional of recent orders
+    notional: VecDeque<(Instant, f64)>,
+}
+
+impl RiskGuard {
+    /// Create a guard enforcing `limits`
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Limits enforced by the guard
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Number of open orders tracked for an instrument
+    pub fn open_orders(&self, symbol: &str) -> usize {
+        self.open_orders.get(symbol).map_or(0, Vec::len)
+    }
+
+    /// Check an order to be sent at `now` against the limits without
+    /// recording it
+    ///
+    /// `mark_price` prices market orders for the notional limit and anchors
+    /// the price collar; checks that need it are skipped while it is unknown.
+    pub fn check(
+        &mut self,
+        order: &NewOrderRequest,
+        mark_price: Option<f64>,
+        now: Instant,
+    ) -> Result<(), RiskViolation> {
+        if let Some(limit) = self.limits.max_order_amount
+            && order.amount > limit
+        {
+            return Err(RiskViolation::OrderTooLarge {
+                amount: order.amount,
+                limit,
+            });
+        }
+
+        if let Some(limit) = self.limits.max_open_orders_per_instrument
+            && self.open_orders(&order.instrument_name) >= limit
+        {
+            return Err(RiskViolation::TooManyOpenOrders {
+                symbol: order.instrument_name.clone(),
+                limit,
+            });
+        }
+
+        if let (Some(collar), Some(price), Some(mark_price)) =
+            (self.limits.price_collar, order.price, mark_price)
+            && order.order_type != OrderType::Market
+            && mark_price > 0.0
+            && ((price - mark_price) / mark_price).abs() > collar
+        {
+            return Err(RiskViolation::PriceOutsideCollar {
+                price,
+                mark_price,
+                collar,
+            });
+        }
+
+        if let (Some(limit), Some(order_notional)) = (
+            self.limits.max_notional_per_minute,
+            Self::notional_of(order, mark_price),
+        ) {
+            self.expire_notional(now);
+            let notional = self.notional.iter().map(|(_, n)| n).sum::<f64>() + order_notional;
+            if notional > limit {
+                return Err(RiskViolation::NotionalRateExceeded { notional, limit });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record an order that was sent at `now` with ClOrdID `cl_ord_id`
+    pub fn record_sent(
+        &mut self,
+        order: &NewOrderRequest,
+        cl_ord_id: &str,
+        mark_price: Option<f64>,
+        now: Instant,
+    ) {
+        self.open_orders
+            .entry(order.instrument_name.clone())
+            .or_default()
+            .push(cl_ord_id.to_string());
+        if self.limits.max_notional_per_minute.is_some()
+            && let Some(notional) = Self::notional_of(order, mark_price)
+        {
+            self.expire_notional(now);
+            self.notional.push_back((now, notional));
+        }
+    }
+
+    /// Apply the OrdStatus (39) reported for an order
+    ///
+    /// Orders that are filled, cancelled, rejected or expired no longer
+    /// count as open.
+    pub fn apply_status(&mut self, cl_ord_id: &str, status: OrderStatus) {
+        if !status.is_terminal() {
+            return;
+        }
+        for orders in self.open_orders.values_mut() {
+            orders.retain(|id| id != cl_ord_id);
+        }
+        self.open_orders.retain(|_, orders| !orders.is_empty());
+    }
+
+    /// Notional of an order at its limit price, or at `mark_price` when it has none
+    fn notional_of(order: &NewOrderRequest, mark_price: Option<f64>) -> Option<f64> {
+        order
+            .price
+            .filter(|_| order.order_type != OrderType::Market)
+            .or(mark_price)
+            .map(|price| (order.amount * price).abs())
+    }
+
+    fn expire_notional(&mut self, now: Instant) {
+        while self
+            .notional
+            .front()
+            .is_some_and(|(sent_at, _)| now.saturating_duration_since(*sent_at) >= NOTIONAL_WINDOW)
+        {
+            self.notional.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit_buy(amount: f64, price: f64) -> NewOrderRequest {
+        NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), amount, price)
+    }
+
+    #[test]
+    fn test_open_orders_per_instrument() {
+        let now = Instant::now();
+        let mut guard = RiskGuard::new(RiskLimits::new().with_max_open_orders_per_instrument(1));
+        let order = limit_buy(10.0, 100.0);
+        assert!(guard.check(&order, None, now).is_ok());
+        guard.record_sent(&order, "A", None, now);
+
+        assert_eq!(
+            guard.check(&order, None, now),
+            Err(RiskViolation::TooManyOpenOrders {
+                symbol: "BTC-PERPETUAL".to_string(),
+                limit: 1,
+            })
+        );
+        let other = NewOrderRequest::limit_buy("ETH-PERPETUAL".to_string(), 1.0, 100.0);
+        assert!(guard.check(&other, None, now).is_ok());
+
+        guard.apply_status("A", OrderStatus::PartiallyFilled);
+        assert!(guard.check(&order, None, now).is_err());
+        guard.apply_status("A", OrderStatus::Cancelled);
+        assert_eq!(guard.open_orders("BTC-PERPETUAL"), 0);
+        assert!(guard.check(&order, None, now).is_ok());
+    }
+
+    #[test]
+    fn test_order_amount_and_notional_rate() {
+        let now = Instant::now();
+        let mut guard = RiskGuard::new(
+            RiskLimits::new()
+                .with_max_order_amount(100.0)
+                .with_max_notional_per_minute(10_000.0),
+        );
+        assert!(matches!(
+            guard.check(&limit_buy(150.0, 10.0), None, now),
+            Err(RiskViolation::OrderTooLarge { .. })
+        ));
+
+        let order = limit_buy(60.0, 100.0);
+        assert!(guard.check(&order, None, now).is_ok());
+        guard.record_sent(&order, "A", None, now);
+        assert_eq!(
+            guard.check(&order, None, now),
+            Err(RiskViolation::NotionalRateExceeded {
+                notional: 12_000.0,
+                limit: 10_000.0,
+            })
+        );
+
+        // Market orders are priced at the mark price, or not limited without one
+        let market = NewOrderRequest::market_buy("BTC-PERPETUAL".to_string(), 50.0);
+        assert!(guard.check(&market, None, now).is_ok());
+        assert!(guard.check(&market, Some(100.0), now).is_err());
+
+        // The notional of the first order leaves the window after a minute
+        assert!(guard.check(&order, None, now + NOTIONAL_WINDOW).is_ok());
+    }
+
+    #[test]
+    fn test_price_collar() {
+        let now = Instant::now();
+        let mut guard = RiskGuard::new(RiskLimits::new().with_price_collar(0.05));
+        assert!(
+            guard
+                .check(&limit_buy(1.0, 104.0), Some(100.0), now)
+                .is_ok()
+        );
+        assert!(
+            guard
+                .check(&limit_buy(1.0, 94.0), Some(100.0), now)
+                .is_err()
+        );
+        assert!(guard.check(&limit_buy(1.0, 94.0), None, now).is_ok());
+        assert!(
+            guard
+                .check(
+                    &NewOrderRequest::market_buy("BTC-PERPETUAL".to_string(), 1.0),
+                    Some(100.0),
+                    now
+                )
+                .is_ok()
+        );
+    }
+}
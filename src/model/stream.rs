@@ -3,48 +3,47 @@
    Email: jb@taunais.com
    Date: 21/7/25
 ******************************************************************************/
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
-use tokio_native_tls::TlsStream;
-
-/// Connection wrapper for both TCP and TLS streams
-pub enum Stream {
-    /// Plain TCP stream
-    Tcp(TcpStream),
-    /// TLS encrypted stream
-    Tls(TlsStream<TcpStream>),
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Byte stream carrying FIX messages
+///
+/// Implemented for every async reader and writer, such as a `TcpStream`, a
+/// `TlsStream` or an in-memory `DuplexStream`, so the connection can run
+/// over any of them.
+pub trait Transport: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Transport for T {}
+
+/// Connection wrapper over any [`Transport`]
+pub struct Stream {
+    inner: Box<dyn Transport>,
 }
 
 impl Stream {
+    /// Wrap a transport
+    pub fn new(transport: impl Transport + 'static) -> Self {
+        Self {
+            inner: Box::new(transport),
+        }
+    }
+
     /// Read data from the stream
     pub async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        match self {
-            Stream::Tcp(stream) => stream.read(buf).await,
-            Stream::Tls(stream) => stream.read(buf).await,
-        }
+        self.inner.read(buf).await
     }
 
     /// Write all data to the stream
     pub async fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
-        match self {
-            Stream::Tcp(stream) => stream.write_all(buf).await,
-            Stream::Tls(stream) => stream.write_all(buf).await,
-        }
+        self.inner.write_all(buf).await
     }
 
     /// Flush the stream
     pub async fn flush(&mut self) -> std::io::Result<()> {
-        match self {
-            Stream::Tcp(stream) => stream.flush().await,
-            Stream::Tls(stream) => stream.flush().await,
-        }
+        self.inner.flush().await
     }
 
     /// Shutdown the stream
     pub async fn shutdown(&mut self) -> std::io::Result<()> {
-        match self {
-            Stream::Tcp(stream) => stream.shutdown().await,
-            Stream::Tls(stream) => stream.shutdown().await,
-        }
+        self.inner.shutdown().await
     }
 }
@@ -0,0 +1,191 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Market data subscriptions
+//!
+//! [`MarketDataSubscriptions`] remembers the MDReqID (262) and MarketDepth
+//! (264) of every Market Data Request (V) subscribed by the session, so a
+//! subscription can later be cancelled or re-issued with another depth by
+//! request ID or by symbol.
+//!
+//! An instrument may have several subscriptions at once, each with its own
+//! MDReqID and set of MDEntryType (269) values, such as the book for pricing
+//! and the trades for analytics. At most one of them carries bids and
+//! offers and feeds the local order book.
+
+use crate::message::market_data::MdEntryType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Active market data subscription
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketDataSubscription {
+    /// MDReqID (262) of the subscription request
+    pub md_req_id: String,
+    /// Subscribed instrument
+    pub symbol: String,
+    /// MarketDepth (264) requested; 0 is the full book
+    pub market_depth: u32,
+    /// Whether only the best bid and offer are kept, see
+    /// [`TopOfBook`](crate::model::top_of_book::TopOfBook)
+    #[serde(default)]
+    pub top_of_book: bool,
+    /// MDEntryType (269) values requested
+    #[serde(default = "book_entry_types")]
+    pub entry_types: Vec<MdEntryType>,
+}
+
+impl MarketDataSubscription {
+    /// Whether the subscription carries bids or offers, and so feeds the
+    /// order book of its instrument
+    pub fn has_book(&self) -> bool {
+        self.entry_types
+            .iter()
+            .any(|entry_type| matches!(entry_type, MdEntryType::Bid | MdEntryType::Offer))
+    }
+}
+
+/// Entry types of an order book subscription, bids and offers
+pub fn book_entry_types() -> Vec<MdEntryType> {
+    vec![MdEntryType::Bid, MdEntryType::Offer]
+}
+
+/// Active market data subscriptions by MDReqID (262)
+#[derive(Debug, Clone, Default)]
+pub struct MarketDataSubscriptions {
+    subscriptions: HashMap<String, MarketDataSubscription>,
+}
+
+impl MarketDataSubscriptions {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a subscription
+    pub fn insert(&mut self, subscription: MarketDataSubscription) {
+        self.subscriptions
+            .insert(subscription.md_req_id.clone(), subscription);
+    }
+
+    /// Forget a subscription
+    pub fn remove(&mut self, md_req_id: &str) -> Option<MarketDataSubscription> {
+        self.subscriptions.remove(md_req_id)
+    }
+
+    /// Subscription with MDReqID `md_req_id`
+    pub fn get(&self, md_req_id: &str) -> Option<&MarketDataSubscription> {
+        self.subscriptions.get(md_req_id)
+    }
+
+    /// Subscription of an instrument, its book subscription if it has one
+    pub fn for_symbol(&self, symbol: &str) -> Option<&MarketDataSubscription> {
+        self.book_for_symbol(symbol)
+            .or_else(|| self.all_for_symbol(symbol).next())
+    }
+
+    /// Subscription feeding the order book of an instrument
+    pub fn book_for_symbol(&self, symbol: &str) -> Option<&MarketDataSubscription> {
+        self.all_for_symbol(symbol)
+            .find(|subscription| subscription.has_book())
+    }
+
+    /// All subscriptions of an instrument
+    pub fn all_for_symbol(&self, symbol: &str) -> impl Iterator<Item = &MarketDataSubscription> {
+        self.subscriptions
+            .values()
+            .filter(move |subscription| subscription.symbol == symbol)
+    }
+
+    /// Subscription matching a MDReqID (262), or else an instrument symbol
+    /// as with [`for_symbol`](Self::for_symbol)
+    pub fn find(&self, md_req_id_or_symbol: &str) -> Option<&MarketDataSubscription> {
+        self.get(md_req_id_or_symbol)
+            .or_else(|| self.for_symbol(md_req_id_or_symbol))
+    }
+
+    /// All active subscriptions
+    pub fn iter(&self) -> impl Iterator<Item = &MarketDataSubscription> {
+        self.subscriptions.values()
+    }
+
+    /// Number of active subscriptions
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Whether there are no active subscriptions
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_by_request_id_or_symbol() {
+        let mut subscriptions = MarketDataSubscriptions::new();
+        subscriptions.insert(MarketDataSubscription {
+            md_req_id: "MDR_1".to_string(),
+            symbol: "BTC-PERPETUAL".to_string(),
+            market_depth: 0,
+            top_of_book: false,
+            entry_types: book_entry_types(),
+        });
+
+        assert_eq!(subscriptions.find("MDR_1").unwrap().symbol, "BTC-PERPETUAL");
+        assert_eq!(
+            subscriptions.find("BTC-PERPETUAL").unwrap().md_req_id,
+            "MDR_1"
+        );
+        assert!(subscriptions.find("ETH-PERPETUAL").is_none());
+
+        assert!(subscriptions.remove("MDR_1").is_some());
+        assert!(subscriptions.is_empty());
+    }
+
+    #[test]
+    fn test_several_subscriptions_of_one_symbol() {
+        let mut subscriptions = MarketDataSubscriptions::new();
+        subscriptions.insert(MarketDataSubscription {
+            md_req_id: "MDR_1".to_string(),
+            symbol: "BTC-PERPETUAL".to_string(),
+            market_depth: 0,
+            top_of_book: false,
+            entry_types: vec![MdEntryType::Trade],
+        });
+        assert!(subscriptions.book_for_symbol("BTC-PERPETUAL").is_none());
+        assert_eq!(
+            subscriptions.find("BTC-PERPETUAL").unwrap().md_req_id,
+            "MDR_1"
+        );
+
+        subscriptions.insert(MarketDataSubscription {
+            md_req_id: "MDR_2".to_string(),
+            symbol: "BTC-PERPETUAL".to_string(),
+            market_depth: 10,
+            top_of_book: false,
+            entry_types: book_entry_types(),
+        });
+        assert_eq!(subscriptions.all_for_symbol("BTC-PERPETUAL").count(), 2);
+        assert_eq!(
+            subscriptions.find("BTC-PERPETUAL").unwrap().md_req_id,
+            "MDR_2"
+        );
+        assert!(!subscriptions.get("MDR_1").unwrap().has_book());
+    }
+
+    #[test]
+    fn test_entry_types_default_to_the_book() {
+        let subscription: MarketDataSubscription = serde_json::from_str(
+            r#"{"md_req_id":"MDR_1","symbol":"BTC-PERPETUAL","market_depth":0}"#,
+        )
+        .unwrap();
+        assert_eq!(subscription.entry_types, book_entry_types());
+    }
+}
@@ -0,0 +1,180 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Symbol normalization
+//!
+//! Strategies often name instruments with their own identifiers, such as
+//! `BTC-PERP` or ISIN-like codes, rather than Deribit's. A [`SymbolMapper`]
+//! set with
+//! [`with_symbol_mapper`](crate::config::DeribitFixConfig::with_symbol_mapper)
+//! translates the Symbol (55), UnderlyingSymbol (311) and LegSymbol (600) of
+//! every message the session sends into Deribit symbols, and those of every
+//! message it receives back, so the rest of the client only sees the
+//! internal identifiers. Symbols the mapper does not know pass unchanged.
+//!
+//! [`SymbolTable`] maps a fixed list of symbols:
+//!
+//! ```
+//! use deribit_fix::model::symbol_map::{SymbolMapper, SymbolTable};
+//!
+//! let symbols = SymbolTable::new().with("BTC-PERP", "BTC-PERPETUAL");
+//! assert_eq!(symbols.to_exchange("BTC-PERP").as_deref(), Some("BTC-PERPETUAL"));
+//! assert_eq!(symbols.to_internal("BTC-PERPETUAL").as_deref(), Some("BTC-PERP"));
+//! ```
+
+use crate::error::Result;
+use crate::message::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Tags carrying an instrument symbol
+pub const SYMBOL_TAGS: [u32; 3] = [tags::SYMBOL, tags::UNDERLYING_SYMBOL, tags::LEG_SYMBOL];
+
+/// Translation between internal instrument identifiers and Deribit symbols
+pub trait SymbolMapper: fmt::Debug + Send + Sync {
+    /// Deribit symbol of the internal `symbol`, `None` to send it unchanged
+    fn to_exchange(&self, symbol: &str) -> Option<String>;
+
+    /// Internal identifier of the Deribit `symbol`, `None` to keep it
+    fn to_internal(&self, symbol: &str) -> Option<String>;
+}
+
+/// [`SymbolMapper`] over a fixed table of symbol pairs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+    exchange: HashMap<String, String>,
+    internal: HashMap<String, String>,
+}
+
+impl SymbolTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map the internal `symbol` to the Deribit symbol `exchange_symbol`
+    pub fn with(mut self, symbol: impl Into<String>, exchange_symbol: impl Into<String>) -> Self {
+        self.insert(symbol, exchange_symbol);
+        self
+    }
+
+    /// Map the internal `symbol` to the Deribit symbol `exchange_symbol`,
+    /// replacing any previous mapping of either
+    pub fn insert(&mut self, symbol: impl Into<String>, exchange_symbol: impl Into<String>) {
+        let (symbol, exchange_symbol) = (symbol.into(), exchange_symbol.into());
+        if let Some(previous) = self.exchange.remove(&symbol) {
+            self.internal.remove(&previous);
+        }
+        if let Some(previous) = self.internal.remove(&exchange_symbol) {
+            self.exchange.remove(&previous);
+        }
+        self.exchange
+            .insert(symbol.clone(), exchange_symbol.clone());
+        self.internal.insert(exchange_symbol, symbol);
+    }
+
+    /// Number of mapped symbols
+    pub fn len(&self) -> usize {
+        self.exchange.len()
+    }
+
+    /// Whether no symbol is mapped
+    pub fn is_empty(&self) -> bool {
+        self.exchange.is_empty()
+    }
+}
+
+impl SymbolMapper for SymbolTable {
+    fn to_exchange(&self, symbol: &str) -> Option<String> {
+        self.exchange.get(symbol).cloned()
+    }
+
+    fn to_internal(&self, symbol: &str) -> Option<String> {
+        self.internal.get(symbol).cloned()
+    }
+}
+
+/// Rebuild `message` with the symbols of [`SYMBOL_TAGS`] replaced by
+/// `translate`
+///
+/// Returns `None` when no symbol was translated, so that the message need
+/// not be rebuilt.
+pub fn translate_symbols(
+    message: &FixMessage,
+    translate: impl Fn(&str) -> Option<String>,
+) -> Result<Option<FixMessage>> {
+    let mut translated = message.clone();
+    let mut changed = false;
+    for (tag, value) in &mut translated.fields {
+        if SYMBOL_TAGS.contains(tag)
+            && let Some(symbol) = translate(value)
+            && symbol != *value
+        {
+            *value = symbol;
+            changed = true;
+        }
+    }
+    if !changed {
+        return Ok(None);
+    }
+    MessageBuilder::from_message(&translated).build().map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_maps_both_ways() {
+        let mut symbols = SymbolTable::new()
+            .with("BTC-PERP", "BTC-PERPETUAL")
+            .with("ETH-PERP", "ETH-PERPETUAL");
+        assert_eq!(
+            symbols.to_exchange("ETH-PERP").as_deref(),
+            Some("ETH-PERPETUAL")
+        );
+        assert_eq!(symbols.to_internal("BTC-PERP"), None);
+
+        // Remapping a symbol forgets its previous pair
+        symbols.insert("BTC-PERP", "BTC-27DEC26");
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols.to_internal("BTC-PERPETUAL"), None);
+        assert_eq!(
+            symbols.to_internal("BTC-27DEC26").as_deref(),
+            Some("BTC-PERP")
+        );
+    }
+
+    #[test]
+    fn test_symbols_of_a_message_are_translated() {
+        let symbols = SymbolTable::new().with("BTC-PERP", "BTC-PERPETUAL");
+        let message = FixMessage::parse(
+            "8=FIX.4.4\x019=0\x0135=V\x0149=CLIENT\x0156=DERIBIT\x0134=2\x01\
+             52=20260101-00:00:00.000\x01146=2\x0155=BTC-PERP\x0155=ETH-PERPETUAL\x0110=000\x01",
+        )
+        .unwrap();
+
+        let translated = translate_symbols(&message, |symbol| symbols.to_exchange(symbol))
+            .unwrap()
+            .unwrap();
+        let translated_symbols: Vec<&str> = translated
+            .fields
+            .iter()
+            .filter(|(tag, _)| *tag == tags::SYMBOL)
+            .map(|(_, value)| value.as_str())
+            .collect();
+        assert_eq!(translated_symbols, ["BTC-PERPETUAL", "ETH-PERPETUAL"]);
+        assert!(translated.raw_message.contains("55=BTC-PERPETUAL\x01"));
+
+        assert!(
+            translate_symbols(&translated, |symbol| symbols.to_exchange(symbol))
+                .unwrap()
+                .is_none()
+        );
+    }
+}
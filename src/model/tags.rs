@@ -4,7 +4,22 @@
    Date: 21/7/25
 ******************************************************************************/
 
-/// FIX field tags commonly used in Deribit
+//! FIX field tags used with Deribit
+//!
+//! Standard FIX 4.4 tags and the Deribit custom tags, named after the
+//! specification. Builders and parsers use these constants rather than
+//! numeric literals. Tags that carry a different meaning in a single message
+//! family are grouped in a submodule named after it.
+
+// @generated by `cargo xtask codegen` from dictionary/deribit_fix44.json.
+// Edit the dictionary and regenerate instead of changing this file.
+
+/// Account (1)
+pub const ACCOUNT: u32 = 1;
+/// AvgPx (6)
+pub const AVG_PX: u32 = 6;
+/// BeginSeqNo (7)
+pub const BEGIN_SEQ_NO: u32 = 7;
 /// BeginString (8)
 pub const BEGIN_STRING: u32 = 8;
 /// BodyLength (9)
@@ -13,71 +28,664 @@ pub const BODY_LENGTH: u32 = 9;
 pub const CHECKSUM: u32 = 10;
 /// ClOrdID (11)
 pub const CL_ORD_ID: u32 = 11;
+/// Commission (12)
+pub const COMMISSION: u32 = 12;
+/// CumQty (14)
+pub const CUM_QTY: u32 = 14;
+/// Currency (15)
+pub const CURRENCY: u32 = 15;
+/// EndSeqNo (16)
+pub const END_SEQ_NO: u32 = 16;
+/// ExecID (17)
+pub const EXEC_ID: u32 = 17;
+/// ExecInst (18)
+pub const EXEC_INST: u32 = 18;
+/// LastPx (31)
+pub const LAST_PX: u32 = 31;
+/// LastQty (32)
+pub const LAST_QTY: u32 = 32;
 /// MsgSeqNum (34)
 pub const MSG_SEQ_NUM: u32 = 34;
 /// MsgType (35)
 pub const MSG_TYPE: u32 = 35;
+/// NewSeqNo (36)
+pub const NEW_SEQ_NO: u32 = 36;
+/// OrderID (37)
+pub const ORDER_ID: u32 = 37;
 /// OrderQty (38)
 pub const ORDER_QTY: u32 = 38;
+/// OrdStatus (39)
+pub const ORD_STATUS: u32 = 39;
 /// OrdType (40)
 pub const ORD_TYPE: u32 = 40;
 /// OrigClOrdID (41)
 pub const ORIG_CL_ORD_ID: u32 = 41;
+/// PossDupFlag (43)
+pub const POSS_DUP_FLAG: u32 = 43;
 /// Price (44)
 pub const PRICE: u32 = 44;
+/// RefSeqNum (45)
+pub const REF_SEQ_NUM: u32 = 45;
 /// SenderCompID (49)
 pub const SENDER_COMP_ID: u32 = 49;
+/// SenderSubID (50)
+pub const SENDER_SUB_ID: u32 = 50;
 /// SendingTime (52)
 pub const SENDING_TIME: u32 = 52;
+/// Quantity (53)
+pub const QUANTITY: u32 = 53;
 /// Side (54)
 pub const SIDE: u32 = 54;
 /// Symbol (55)
 pub const SYMBOL: u32 = 55;
 /// TargetCompID (56)
 pub const TARGET_COMP_ID: u32 = 56;
+/// TargetSubID (57)
+pub const TARGET_SUB_ID: u32 = 57;
+/// Text (58)
+pub const TEXT: u32 = 58;
 /// TimeInForce (59)
 pub const TIME_IN_FORCE: u32 = 59;
+/// TransactTime (60)
+pub const TRANSACT_TIME: u32 = 60;
+/// ValidUntilTime (62)
+pub const VALID_UNTIL_TIME: u32 = 62;
+/// SettlType (63)
+pub const SETTL_TYPE: u32 = 63;
+/// SettlDate (64)
+pub const SETTL_DATE: u32 = 64;
+/// TradeDate (75)
+pub const TRADE_DATE: u32 = 75;
+/// PositionEffect (77)
+pub const POSITION_EFFECT: u32 = 77;
+/// RptSeq (83)
+pub const RPT_SEQ: u32 = 83;
 /// RawDataLength (95)
 pub const RAW_DATA_LENGTH: u32 = 95;
 /// RawData (96)
 pub const RAW_DATA: u32 = 96;
+/// PossResend (97)
+pub const POSS_RESEND: u32 = 97;
+/// EncryptMethod (98)
+pub const ENCRYPT_METHOD: u32 = 98;
+/// StopPx (99)
+pub const STOP_PX: u32 = 99;
+/// CxlRejReason (102)
+pub const CXL_REJ_REASON: u32 = 102;
+/// OrdRejReason (103)
+pub const ORD_REJ_REASON: u32 = 103;
+/// SecurityDesc (107)
+pub const SECURITY_DESC: u32 = 107;
 /// HeartBtInt (108)
 pub const HEART_BT_INT: u32 = 108;
+/// MinQty (110)
+pub const MIN_QTY: u32 = 110;
 /// TestReqID (112)
 pub const TEST_REQ_ID: u32 = 112;
+/// OnBehalfOfCompID (115)
+pub const ON_BEHALF_OF_COMP_ID: u32 = 115;
+/// QuoteID (117)
+pub const QUOTE_ID: u32 = 117;
+/// SettlCurrency (120)
+pub const SETTL_CURRENCY: u32 = 120;
+/// OrigSendingTime (122)
+pub const ORIG_SENDING_TIME: u32 = 122;
+/// GapFillFlag (123)
+pub const GAP_FILL_FLAG: u32 = 123;
+/// ExpireTime (126)
+pub const EXPIRE_TIME: u32 = 126;
+/// QuoteReqID (131)
+pub const QUOTE_REQ_ID: u32 = 131;
+/// BidPx (132)
+pub const BID_PX: u32 = 132;
+/// OfferPx (133)
+pub const OFFER_PX: u32 = 133;
+/// BidSize (134)
+pub const BID_SIZE: u32 = 134;
+/// OfferSize (135)
+pub const OFFER_SIZE: u32 = 135;
+/// ResetSeqNumFlag (141)
+pub const RESET_SEQ_NUM_FLAG: u32 = 141;
 /// NoRelatedSym (146)
 pub const NO_RELATED_SYM: u32 = 146;
+/// ExecType (150)
+pub const EXEC_TYPE: u32 = 150;
+/// LeavesQty (151)
+pub const LEAVES_QTY: u32 = 151;
+/// SecurityType (167)
+pub const SECURITY_TYPE: u32 = 167;
+/// SecondaryOrderID (198)
+pub const SECONDARY_ORDER_ID: u32 = 198;
+/// PutOrCall (201)
+pub const PUT_OR_CALL: u32 = 201;
+/// StrikePrice (202)
+pub const STRIKE_PRICE: u32 = 202;
+/// SecurityExchange (207)
+pub const SECURITY_EXCHANGE: u32 = 207;
+/// PegOffsetValue (211)
+pub const PEG_OFFSET_VALUE: u32 = 211;
+/// IssueDate (225)
+pub const ISSUE_DATE: u32 = 225;
+/// ContractMultiplier (231)
+pub const CONTRACT_MULTIPLIER: u32 = 231;
 /// MDReqID (262)
 pub const MD_REQ_ID: u32 = 262;
 /// SubscriptionRequestType (263)
 pub const SUBSCRIPTION_REQUEST_TYPE: u32 = 263;
 /// MarketDepth (264)
 pub const MARKET_DEPTH: u32 = 264;
+/// MDUpdateType (265)
+pub const MD_UPDATE_TYPE: u32 = 265;
+/// NoMDEntryTypes (267)
+pub const NO_MD_ENTRY_TYPES: u32 = 267;
+/// NoMDEntries (268)
+pub const NO_MD_ENTRIES: u32 = 268;
+/// MDEntryType (269)
+pub const MD_ENTRY_TYPE: u32 = 269;
+/// MDEntryPx (270)
+pub const MD_ENTRY_PX: u32 = 270;
+/// MDEntrySize (271)
+pub const MD_ENTRY_SIZE: u32 = 271;
+/// MDEntryDate (272)
+pub const MD_ENTRY_DATE: u32 = 272;
+/// MDEntryTime (273)
+pub const MD_ENTRY_TIME: u32 = 273;
+/// MDUpdateAction (279)
+pub const MD_UPDATE_ACTION: u32 = 279;
+/// MDReqRejReason (281)
+pub const MD_REQ_REJ_REASON: u32 = 281;
+/// DefBidSize (293)
+pub const DEF_BID_SIZE: u32 = 293;
+/// DefOfferSize (294)
+pub const DEF_OFFER_SIZE: u32 = 294;
+/// NoQuoteEntries (295)
+pub const NO_QUOTE_ENTRIES: u32 = 295;
+/// NoQuoteSets (296)
+pub const NO_QUOTE_SETS: u32 = 296;
+/// QuoteStatus (297)
+pub const QUOTE_STATUS: u32 = 297;
+/// QuoteCancelType (298)
+pub const QUOTE_CANCEL_TYPE: u32 = 298;
+/// QuoteEntryID (299)
+pub const QUOTE_ENTRY_ID: u32 = 299;
+/// QuoteRejectReason (300)
+pub const QUOTE_REJECT_REASON: u32 = 300;
+/// QuoteResponseLevel (301)
+pub const QUOTE_RESPONSE_LEVEL: u32 = 301;
+/// QuoteSetID (302)
+pub const QUOTE_SET_ID: u32 = 302;
+/// QuoteRequestType (303)
+pub const QUOTE_REQUEST_TYPE: u32 = 303;
+/// UnderlyingSymbol (311)
+pub const UNDERLYING_SYMBOL: u32 = 311;
+/// SecurityReqID (320)
+pub const SECURITY_REQ_ID: u32 = 320;
+/// SecurityResponseID (322)
+pub const SECURITY_RESPONSE_ID: u32 = 322;
+/// SecurityStatusReqID (324)
+pub const SECURITY_STATUS_REQ_ID: u32 = 324;
+/// SecurityTradingStatus (326)
+pub const SECURITY_TRADING_STATUS: u32 = 326;
+/// BuyVolume (330)
+pub const BUY_VOLUME: u32 = 330;
+/// SellVolume (331)
+pub const SELL_VOLUME: u32 = 331;
+/// HighPx (332)
+pub const HIGH_PX: u32 = 332;
+/// LowPx (333)
+pub const LOW_PX: u32 = 333;
+/// TradingSessionID (336)
+pub const TRADING_SESSION_ID: u32 = 336;
+/// QuoteSetValidUntilTime (367)
+pub const QUOTE_SET_VALID_UNTIL_TIME: u32 = 367;
+/// QuoteEntryRejectReason (368)
+pub const QUOTE_ENTRY_REJECT_REASON: u32 = 368;
+/// RefTagID (371)
+pub const REF_TAG_ID: u32 = 371;
+/// RefMsgType (372)
+pub const REF_MSG_TYPE: u32 = 372;
+/// SessionRejectReason (373)
+pub const SESSION_REJECT_REASON: u32 = 373;
+/// BusinessRejectRefID (379)
+pub const BUSINESS_REJECT_REF_ID: u32 = 379;
+/// BusinessRejectReason (380)
+pub const BUSINESS_REJECT_REASON: u32 = 380;
+/// GrossTradeAmt (381)
+pub const GROSS_TRADE_AMT: u32 = 381;
+/// TotalVolumeTraded (387)
+pub const TOTAL_VOLUME_TRADED: u32 = 387;
+/// TotNoRelatedSym (393)
+pub const TOT_NO_RELATED_SYM: u32 = 393;
+/// PriceType (423)
+pub const PRICE_TYPE: u32 = 423;
+/// CxlRejResponseTo (434)
+pub const CXL_REJ_RESPONSE_TO: u32 = 434;
+/// ClearingAccount (440)
+pub const CLEARING_ACCOUNT: u32 = 440;
+/// MultiLegReportingType (442)
+pub const MULTI_LEG_REPORTING_TYPE: u32 = 442;
+/// NoPartyIDs (453)
+pub const NO_PARTY_IDS: u32 = 453;
+/// NoSecurityAltID (454)
+pub const NO_SECURITY_ALT_ID: u32 = 454;
+/// SecurityAltID (455)
+pub const SECURITY_ALT_ID: u32 = 455;
+/// SecurityAltIDSource (456)
+pub const SECURITY_ALT_ID_SOURCE: u32 = 456;
+/// CFICode (461)
+pub const CFI_CODE: u32 = 461;
+/// CommCurrency (479)
+pub const COMM_CURRENCY: u32 = 479;
+/// TradeReportTransType (487)
+pub const TRADE_REPORT_TRANS_TYPE: u32 = 487;
+/// SecondaryExecID (527)
+pub const SECONDARY_EXEC_ID: u32 = 527;
+/// MassCancelRequestType (530)
+pub const MASS_CANCEL_REQUEST_TYPE: u32 = 530;
+/// MassCancelResponse (531)
+pub const MASS_CANCEL_RESPONSE: u32 = 531;
+/// MassCancelRejectReason (532)
+pub const MASS_CANCEL_REJECT_REASON: u32 = 532;
+/// TotalAffectedOrders (533)
+pub const TOTAL_AFFECTED_ORDERS: u32 = 533;
+/// NoAffectedOrders (534)
+pub const NO_AFFECTED_ORDERS: u32 = 534;
+/// AffectedOrderID (535)
+pub const AFFECTED_ORDER_ID: u32 = 535;
+/// QuoteType (537)
+pub const QUOTE_TYPE: u32 = 537;
+/// MaturityDate (541)
+pub const MATURITY_DATE: u32 = 541;
+/// NoSides (552)
+pub const NO_SIDES: u32 = 552;
 /// Username (553)
 pub const USERNAME: u32 = 553;
 /// Password (554)
 pub const PASSWORD: u32 = 554;
-/// Average Price (6)
-pub const AVERAGE_PRICE: u32 = 6;
-/// Position Date (704)
-pub const POSITION_DATE: u32 = 704;
-/// Position Quantity (703)
-pub const POSITION_QTY: u32 = 703;
+/// NoLegs (555)
+pub const NO_LEGS: u32 = 555;
+/// SecurityListRequestType (559)
+pub const SECURITY_LIST_REQUEST_TYPE: u32 = 559;
+/// SecurityRequestResult (560)
+pub const SECURITY_REQUEST_RESULT: u32 = 560;
+/// MinTradeVol (562)
+pub const MIN_TRADE_VOL: u32 = 562;
+/// LegPrice (566)
+pub const LEG_PRICE: u32 = 566;
+/// TradeRequestID (568)
+pub const TRADE_REQUEST_ID: u32 = 568;
+/// TradeRequestType (569)
+pub const TRADE_REQUEST_TYPE: u32 = 569;
+/// PreviouslyReported (570)
+pub const PREVIOUSLY_REPORTED: u32 = 570;
+/// TradeReportID (571)
+pub const TRADE_REPORT_ID: u32 = 571;
+/// MassStatusReqID (584)
+pub const MASS_STATUS_REQ_ID: u32 = 584;
+/// MassStatusReqType (585)
+pub const MASS_STATUS_REQ_TYPE: u32 = 585;
+/// LegSymbol (600)
+pub const LEG_SYMBOL: u32 = 600;
+/// LegRatioQty (623)
+pub const LEG_RATIO_QTY: u32 = 623;
+/// LegSide (624)
+pub const LEG_SIDE: u32 = 624;
+/// TradingSessionSubID (625)
+pub const TRADING_SESSION_SUB_ID: u32 = 625;
+/// MidPx (631)
+pub const MID_PX: u32 = 631;
+/// RFQReqID (644)
+pub const RFQ_REQ_ID: u32 = 644;
+/// QuoteStatusReqID (649)
+pub const QUOTE_STATUS_REQ_ID: u32 = 649;
+/// QuoteRequestRejectReason (658)
+pub const QUOTE_REQUEST_REJECT_REASON: u32 = 658;
+/// LegQty (687)
+pub const LEG_QTY: u32 = 687;
+/// PosType (703)
+pub const POS_TYPE: u32 = 703;
+/// LongQty (704)
+pub const LONG_QTY: u32 = 704;
+/// ShortQty (705)
+pub const SHORT_QTY: u32 = 705;
+/// PosQtyStatus (706)
+pub const POS_QTY_STATUS: u32 = 706;
+/// PosAmtType (707)
+pub const POS_AMT_TYPE: u32 = 707;
+/// PosAmt (708)
+pub const POS_AMT: u32 = 708;
 /// PosReqID (710)
 pub const POS_REQ_ID: u32 = 710;
-/// Clearing Business Date (715)
+/// ClearingBusinessDate (715)
 pub const CLEARING_BUSINESS_DATE: u32 = 715;
+/// PosMaintRptID (721)
+pub const POS_MAINT_RPT_ID: u32 = 721;
 /// PosReqType (724)
 pub const POS_REQ_TYPE: u32 = 724;
+/// ResponseTransportType (725)
+pub const RESPONSE_TRANSPORT_TYPE: u32 = 725;
+/// ResponseDestination (726)
+pub const RESPONSE_DESTINATION: u32 = 726;
+/// SettlPrice (730)
+pub const SETTL_PRICE: u32 = 730;
+/// SettlPriceType (731)
+pub const SETTL_PRICE_TYPE: u32 = 731;
+/// PriorSettlPrice (732)
+pub const PRIOR_SETTL_PRICE: u32 = 732;
+/// OpenInterest (746)
+pub const OPEN_INTEREST: u32 = 746;
+/// TotNumTradeReports (748)
+pub const TOT_NUM_TRADE_REPORTS: u32 = 748;
+/// TradeRequestResult (749)
+pub const TRADE_REQUEST_RESULT: u32 = 749;
+/// TradeRequestStatus (750)
+pub const TRADE_REQUEST_STATUS: u32 = 750;
+/// UnderlyingPx (810)
+pub const UNDERLYING_PX: u32 = 810;
+/// PriceDelta (811)
+pub const PRICE_DELTA: u32 = 811;
+/// ApplQueueMax (812)
+pub const APPL_QUEUE_MAX: u32 = 812;
+/// ApplQueueDepth (813)
+pub const APPL_QUEUE_DEPTH: u32 = 813;
+/// ApplQueueResolution (814)
+pub const APPL_QUEUE_RESOLUTION: u32 = 814;
+/// TrdType (828)
+pub const TRD_TYPE: u32 = 828;
+/// TrdSubType (829)
+pub const TRD_SUB_TYPE: u32 = 829;
+/// PeggedPrice (839)
+pub const PEGGED_PRICE: u32 = 839;
+/// LastLiquidityInd (851)
+pub const LAST_LIQUIDITY_IND: u32 = 851;
+/// QtyType (854)
+pub const QTY_TYPE: u32 = 854;
+/// TradeReportType (856)
+pub const TRADE_REPORT_TYPE: u32 = 856;
+/// TrdMatchID (880)
+pub const TRD_MATCH_ID: u32 = 880;
+/// LastFragment (893)
+pub const LAST_FRAGMENT: u32 = 893;
+/// MarginRatio (898)
+pub const MARGIN_RATIO: u32 = 898;
+/// MarginExcess (899)
+pub const MARGIN_EXCESS: u32 = 899;
+/// UserRequestID (923)
+pub const USER_REQUEST_ID: u32 = 923;
+/// UserRequestType (924)
+pub const USER_REQUEST_TYPE: u32 = 924;
+/// NewPassword (925)
+pub const NEW_PASSWORD: u32 = 925;
+/// UserStatus (926)
+pub const USER_STATUS: u32 = 926;
+/// UserStatusText (927)
+pub const USER_STATUS_TEXT: u32 = 927;
+/// StrikeCurrency (947)
+pub const STRIKE_CURRENCY: u32 = 947;
+/// SecurityStatus (965)
+pub const SECURITY_STATUS: u32 = 965;
+/// MinPriceIncrement (969)
+pub const MIN_PRICE_INCREMENT: u32 = 969;
+/// TradeID (1003)
+pub const TRADE_ID: u32 = 1003;
+/// SecondaryTradeID (1040)
+pub const SECONDARY_TRADE_ID: u32 = 1040;
+/// FirmTradeID (1041)
+pub const FIRM_TRADE_ID: u32 = 1041;
+/// MaturityTime (1079)
+pub const MATURITY_TIME: u32 = 1079;
+/// RefreshQty (1088)
+pub const REFRESH_QTY: u32 = 1088;
+/// PegPriceType (1094)
+pub const PEG_PRICE_TYPE: u32 = 1094;
+/// AppID (1128)
+pub const APP_ID: u32 = 1128;
+/// DefaultApplVerID (1137)
+pub const DEFAULT_APPL_VER_ID: u32 = 1137;
+/// DisplayQty (1138)
+pub const DISPLAY_QTY: u32 = 1138;
+/// QuoteEntryStatus (1167)
+pub const QUOTE_ENTRY_STATUS: u32 = 1167;
+/// Volatility (1188)
+pub const VOLATILITY: u32 = 1188;
+/// NoTickRules (1205)
+pub const NO_TICK_RULES: u32 = 1205;
+/// StartTickPriceRange (1206)
+pub const START_TICK_PRICE_RANGE: u32 = 1206;
+/// TickIncrement (1208)
+pub const TICK_INCREMENT: u32 = 1208;
+/// MarketSegmentID (1300)
+pub const MARKET_SEGMENT_ID: u32 = 1300;
+/// EncryptedPassword (1402)
+pub const ENCRYPTED_PASSWORD: u32 = 1402;
+/// EncryptedNewPassword (1404)
+pub const ENCRYPTED_NEW_PASSWORD: u32 = 1404;
+/// SessionStatus (1409)
+pub const SESSION_STATUS: u32 = 1409;
+/// PriceQuoteCurrency (1524)
+pub const PRICE_QUOTE_CURRENCY: u32 = 1524;
+/// SecurityDefinitionResponseType (1570)
+pub const SECURITY_DEFINITION_RESPONSE_TYPE: u32 = 1570;
+/// InstrumentPricePrecision (2576)
+pub const INSTRUMENT_PRICE_PRECISION: u32 = 2576;
+/// SelfMatchPreventionInstruction (2964)
+pub const SELF_MATCH_PREVENTION_INSTRUCTION: u32 = 2964;
+
+/// SecurityDefinitionRequestType (856), as sent in Security Definition Requests
+pub const SECURITY_DEFINITION_REQUEST_TYPE: u32 = 856;
+/// Average Price (6)
+pub const AVERAGE_PRICE: u32 = 6;
+/// Position Quantity (703)
+#[deprecated(note = "tag 703 is PosType, use POS_TYPE, LONG_QTY or SHORT_QTY")]
+pub const POSITION_QTY: u32 = 703;
+/// Position Date (704)
+#[deprecated(note = "tag 704 is LongQty, use LONG_QTY or CLEARING_BUSINESS_DATE")]
+pub const POSITION_DATE: u32 = 704;
 /// Unrealized PnL (1247)
 pub const UNREALIZED_PNL: u32 = 1247;
 /// Realized PnL (1248)
 pub const REALIZED_PNL: u32 = 1248;
 
 // Deribit custom tags
+/// ConditionTriggerMethod (5127)
+pub const CONDITION_TRIGGER_METHOD: u32 = 5127;
+/// SecondaryCurrency (5544)
+pub const SECONDARY_CURRENCY: u32 = 5544;
 /// CancelOnDisconnect (9001)
 pub const CANCEL_ON_DISCONNECT: u32 = 9001;
+/// UseWordsafeTags (9002)
+pub const USE_WORDSAFE_TAGS: u32 = 9002;
+/// DontCancelOnDisconnect (9003)
+pub const DONT_CANCEL_ON_DISCONNECT: u32 = 9003;
 /// DeribitAppId (9004)
 pub const DERIBIT_APP_ID: u32 = 9004;
 /// DeribitAppSig (9005)
 pub const DERIBIT_APP_SIG: u32 = 9005;
+/// DeribitSequential (9007)
+pub const DERIBIT_SEQUENTIAL: u32 = 9007;
+/// DeribitMMProtection (9008)
+pub const DERIBIT_MM_PROTECTION: u32 = 9008;
+/// UnsubscribeExecutionReports (9009)
+pub const UNSUBSCRIBE_EXECUTION_REPORTS: u32 = 9009;
+/// ConnectionOnlyExecutionReports (9010)
+pub const CONNECTION_ONLY_EXECUTION_REPORTS: u32 = 9010;
+/// DeribitSkipBlockTrades (9011)
+pub const DERIBIT_SKIP_BLOCK_TRADES: u32 = 9011;
+/// DeribitShowBlockTradeId (9012)
+pub const DERIBIT_SHOW_BLOCK_TRADE_ID: u32 = 9012;
+/// DisplayMulticastInstrumentID (9013)
+pub const DISPLAY_MULTICAST_INSTRUMENT_ID: u32 = 9013;
+/// MassStatusReqIDType (9014)
+pub const MASS_STATUS_REQ_ID_TYPE: u32 = 9014;
+/// ReportFillsAsExecReports (9015)
+pub const REPORT_FILLS_AS_EXEC_REPORTS: u32 = 9015;
+/// DisplayIncrementSteps (9018)
+pub const DISPLAY_INCREMENT_STEPS: u32 = 9018;
+/// MMPGroup (9019)
+pub const MMP_GROUP: u32 = 9019;
+/// QuoteEntryType (9020)
+pub const QUOTE_ENTRY_TYPE: u32 = 9020;
+/// FreezeQuotes (9031)
+pub const FREEZE_QUOTES: u32 = 9031;
+/// UserEquity (100001)
+pub const USER_EQUITY: u32 = 100001;
+/// UserBalance (100002)
+pub const USER_BALANCE: u32 = 100002;
+/// UserInitialMargin (100003)
+pub const USER_INITIAL_MARGIN: u32 = 100003;
+/// UserMaintenanceMargin (100004)
+pub const USER_MAINTENANCE_MARGIN: u32 = 100004;
+/// UnrealizedPL (100005)
+pub const UNREALIZED_PL: u32 = 100005;
+/// RealizedPL (100006)
+pub const REALIZED_PL: u32 = 100006;
+/// DeribitTradeAmount (100007)
+pub const DERIBIT_TRADE_AMOUNT: u32 = 100007;
+/// DeribitSinceTimestamp (100008)
+pub const DERIBIT_SINCE_TIMESTAMP: u32 = 100008;
+/// DeribitTradeId (100009)
+pub const DERIBIT_TRADE_ID: u32 = 100009;
+/// DeribitLabel (100010)
+pub const DERIBIT_LABEL: u32 = 100010;
+/// TotalPL (100011)
+pub const TOTAL_PL: u32 = 100011;
+/// DeribitAdvOrderType (100012)
+pub const DERIBIT_ADV_ORDER_TYPE: u32 = 100012;
+/// MarginBalance (100013)
+pub const MARGIN_BALANCE: u32 = 100013;
+/// TradeVolume24h (100087)
+pub const TRADE_VOLUME_24H: u32 = 100087;
+/// DeribitLiquidationPrice (100088)
+pub const DERIBIT_LIQUIDATION_PRICE: u32 = 100088;
+/// DeribitSizeInCurrency (100089)
+pub const DERIBIT_SIZE_IN_CURRENCY: u32 = 100089;
+/// MarkPrice (100090)
+pub const MARK_PRICE: u32 = 100090;
+/// DeribitLiquidation (100091)
+pub const DERIBIT_LIQUIDATION: u32 = 100091;
+/// CurrentFunding (100092)
+pub const CURRENT_FUNDING: u32 = 100092;
+/// Funding8h (100093)
+pub const FUNDING_8H: u32 = 100093;
+
+/// Tags of the MM Protection Limits (MM), MM Protection Limits Result (MR) and
+/// MM Protection Reset (MZ) messages
+///
+/// Several share their number with a Logon tag; the two never appear in the
+/// same message.
+pub mod mm_protection {
+    /// MMProtectionReqID (9001)
+    pub const REQ_ID: u32 = 9001;
+    /// MMProtectionAction (9002)
+    pub const ACTION: u32 = 9002;
+    /// MMProtectionScope (9003)
+    pub const SCOPE: u32 = 9003;
+    /// InstrumentGroup (9004)
+    pub const INSTRUMENT_GROUP: u32 = 9004;
+    /// MaxPositionLimit (9005)
+    pub const MAX_POSITION_LIMIT: u32 = 9005;
+    /// MaxOrderQtyLimit (9006)
+    pub const MAX_ORDER_QTY_LIMIT: u32 = 9006;
+    /// MaxOrdersLimit (9007)
+    pub const MAX_ORDERS_LIMIT: u32 = 9007;
+    /// TimeWindowSeconds (9009)
+    pub const TIME_WINDOW_SECONDS: u32 = 9009;
+    /// DeltaLimit (9010)
+    pub const DELTA_LIMIT: u32 = 9010;
+    /// VegaLimit (9011)
+    pub const VEGA_LIMIT: u32 = 9011;
+    /// GammaLimit (9012)
+    pub const GAMMA_LIMIT: u32 = 9012;
+    /// ThetaLimit (9013)
+    pub const THETA_LIMIT: u32 = 9013;
+    /// TotalRiskLimit (9014)
+    pub const TOTAL_RISK_LIMIT: u32 = 9014;
+    /// ValidFrom (9015)
+    pub const VALID_FROM: u32 = 9015;
+    /// ValidUntil (9016)
+    pub const VALID_UNTIL: u32 = 9016;
+    /// MMProtectionResultStatus (9017)
+    pub const RESULT_STATUS: u32 = 9017;
+    /// ProcessingTime (9018)
+    pub const PROCESSING_TIME: u32 = 9018;
+    /// MMProtectionRejectReason (9019)
+    pub const REJECT_REASON: u32 = 9019;
+    /// CurrentMaxPositionLimit (9020)
+    pub const CURRENT_MAX_POSITION_LIMIT: u32 = 9020;
+    /// CurrentMaxOrderQtyLimit (9021)
+    pub const CURRENT_MAX_ORDER_QTY_LIMIT: u32 = 9021;
+    /// CurrentMaxOrdersLimit (9022)
+    pub const CURRENT_MAX_ORDERS_LIMIT: u32 = 9022;
+    /// CurrentTimeWindowSeconds (9023)
+    pub const CURRENT_TIME_WINDOW_SECONDS: u32 = 9023;
+    /// CurrentDeltaLimit (9024)
+    pub const CURRENT_DELTA_LIMIT: u32 = 9024;
+    /// CurrentVegaLimit (9025)
+    pub const CURRENT_VEGA_LIMIT: u32 = 9025;
+    /// CurrentGammaLimit (9026)
+    pub const CURRENT_GAMMA_LIMIT: u32 = 9026;
+    /// CurrentThetaLimit (9027)
+    pub const CURRENT_THETA_LIMIT: u32 = 9027;
+    /// CurrentTotalRiskLimit (9028)
+    pub const CURRENT_TOTAL_RISK_LIMIT: u32 = 9028;
+    /// CurrentValidFrom (9029)
+    pub const CURRENT_VALID_FROM: u32 = 9029;
+    /// CurrentValidUntil (9030)
+    pub const CURRENT_VALID_UNTIL: u32 = 9030;
+    /// AffectedInstrumentsCount (9031)
+    pub const AFFECTED_INSTRUMENTS_COUNT: u32 = 9031;
+    /// MMProtectionResetReqID (9032)
+    pub const RESET_REQ_ID: u32 = 9032;
+    /// MMProtectionResetType (9033)
+    pub const RESET_TYPE: u32 = 9033;
+    /// MMProtectionResetReason (9034)
+    pub const RESET_REASON: u32 = 9034;
+    /// ResetEffectiveTime (9035)
+    pub const RESET_EFFECTIVE_TIME: u32 = 9035;
+    /// ResetExpiryTime (9036)
+    pub const RESET_EXPIRY_TIME: u32 = 9036;
+    /// ForceReset (9037)
+    pub const FORCE_RESET: u32 = 9037;
+    /// NotifyAllParticipants (9038)
+    pub const NOTIFY_ALL_PARTICIPANTS: u32 = 9038;
+    /// ResetPositionCounters (9039)
+    pub const RESET_POSITION_COUNTERS: u32 = 9039;
+    /// ResetOrderCounters (9040)
+    pub const RESET_ORDER_COUNTERS: u32 = 9040;
+    /// ResetVolumeCounters (9041)
+    pub const RESET_VOLUME_COUNTERS: u32 = 9041;
+    /// ResetTimeWindowCounters (9042)
+    pub const RESET_TIME_WINDOW_COUNTERS: u32 = 9042;
+    /// ResetGreeksCounters (9043)
+    pub const RESET_GREEKS_COUNTERS: u32 = 9043;
+    /// ResetRiskCounters (9044)
+    pub const RESET_RISK_COUNTERS: u32 = 9044;
+}
+
+/// Position Report (AP) values Deribit sends in tags with another standard meaning
+pub mod position_report {
+    /// RealizedPnL (706)
+    pub const REALIZED_PNL: u32 = 706;
+    /// FloatingPnL (707)
+    pub const FLOATING_PNL: u32 = 707;
+    /// TotalPnL (708)
+    pub const TOTAL_PNL: u32 = 708;
+    /// IndexPrice (731)
+    pub const INDEX_PRICE: u32 = 731;
+    /// MarkPrice (732)
+    pub const MARK_PRICE: u32 = 732;
+    /// Delta (811)
+    pub const DELTA: u32 = 811;
+    /// Gamma (812)
+    pub const GAMMA: u32 = 812;
+    /// Theta (813)
+    pub const THETA: u32 = 813;
+    /// Vega (814)
+    pub const VEGA: u32 = 814;
+    /// MaintenanceMargin (898)
+    pub const MAINTENANCE_MARGIN: u32 = 898;
+    /// InitialMargin (899)
+    pub const INITIAL_MARGIN: u32 = 899;
+    /// PosAmtType (979)
+    pub const POS_AMT_TYPE: u32 = 979;
+}
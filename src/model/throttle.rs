@@ -0,0 +1,343 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Adaptive pacing of outgoing application messages
+//!
+//! Deribit rejects requests sent faster than the account's rate limit with
+//! the error `too_many_requests` (code 10028) in the Text (58) of the
+//! rejection. [`AdaptiveRateLimiter`] paces the application messages of the
+//! session with a token bucket holding one second of messages, and adjusts
+//! the rate by additive increase, multiplicative decrease (AIMD): each
+//! rate-limit rejection halves the allowed rate, at most once per
+//! [`RATE_INCREASE_INTERVAL`], and every interval without one adds
+//! [`RATE_INCREASE_STEP`] messages per second, up to
+//! [`max_message_rate`](crate::config::DeribitFixConfig::max_message_rate).
+//! Without a configured rate messages are not paced until the first
+//! rejection, which halves the rate measured over the last second.
+//!
+//! The current estimate is reported in [`ThrottleStats`], so strategies can
+//! pace themselves instead of queueing behind the limiter.
+
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Fraction of the allowed rate kept after a rate-limit rejection
+pub const RATE_DECREASE_FACTOR: f64 = 0.5;
+
+/// Messages per second added to the allowed rate after every
+/// [`RATE_INCREASE_INTERVAL`] without a rate-limit rejection
+pub const RATE_INCREASE_STEP: f64 = 1.0;
+
+/// Interval between two increases, and the least time between two decreases
+pub const RATE_INCREASE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lowest rate the limiter backs off to, in messages per second
+pub const MIN_MESSAGE_RATE: f64 = 1.0;
+
+/// Window over which the outgoing rate is measured
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Whether the Text (58) of a rejection reports an exceeded rate limit
+pub fn is_rate_limit_text(text: &str) -> bool {
+    let text = text.to_ascii_lowercase();
+    [
+        "too_many_requests",
+        "too many requests",
+        "rate limit",
+        "10028",
+    ]
+    .iter()
+    .any(|marker| text.contains(marker))
+}
+
+/// Rejection of a message sent over the exchange's rate limit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitReject {
+    /// MsgType (35) of the rejection
+    pub msg_type: String,
+    /// Text (58) of the rejection
+    pub text: String,
+    /// ClOrdID (11) of the rejected order or cancel, if any
+    pub cl_ord_id: Option<String>,
+    /// RefSeqNum (45) of the rejected message, if any
+    pub ref_seq_num: Option<u32>,
+}
+
+/// Classify `message` as a rate-limit rejection
+///
+/// Reject (3), Business Message Reject (j), rejected Execution Reports (8),
+/// Order Cancel Rejects (9), Market Data Request Rejects (Y) and Quote
+/// Request Rejects (AG) are rate-limit rejections when their Text (58) says
+/// so.
+pub fn rate_limit_reject(message: &FixMessage) -> Option<RateLimitReject> {
+    let rejection = match message.msg_type()? {
+        MsgType::Reject
+        | MsgType::BusinessMessageReject
+        | MsgType::OrderCancelReject
+        | MsgType::MarketDataRequestReject
+        | MsgType::QuoteRequestReject => true,
+        MsgType::ExecutionReport => {
+            message.get_field(tags::ORD_STATUS).map(String::as_str) == Some("8")
+        }
+        _ => false,
+    };
+    let text = message.get_field(tags::TEXT)?;
+    if !rejection || !is_rate_limit_text(text) {
+        return None;
+    }
+    Some(RateLimitReject {
+        msg_type: message.get_field(tags::MSG_TYPE)?.clone(),
+        text: text.clone(),
+        cl_ord_id: message.get_field(tags::CL_ORD_ID).cloned(),
+        ref_seq_num: message
+            .get_field(tags::REF_SEQ_NUM)
+            .and_then(|value| value.parse().ok()),
+    })
+}
+
+/// Pacing of outgoing messages and its adjustments
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ThrottleStats {
+    /// Messages per second currently allowed, `None` while unpaced
+    pub allowed_rate: Option<f64>,
+    /// Configured ceiling of the allowed rate, if any
+    pub max_rate: Option<f64>,
+    /// Rate-limit rejections received
+    pub rate_limit_rejects: u64,
+    /// Messages held back to respect the allowed rate
+    pub delayed_messages: u64,
+    /// Time messages were held back in total
+    pub total_delay: Duration,
+    /// Last rate-limit rejection received
+    pub last_reject: Option<RateLimitReject>,
+}
+
+/// Token bucket pacing outgoing messages, with an AIMD-adjusted rate
+#[derive(Debug, Clone)]
+pub struct AdaptiveRateLimiter {
+    max_rate: Option<f64>,
+    adaptive: bool,
+    allowed_rate: Option<f64>,
+    tokens: f64,
+    refilled_at: Option<Instant>,
+    /// Start of the current increase interval, set after a decrease
+    adjusted_at: Option<Instant>,
+    last_decrease: Option<Instant>,
+    recent: VecDeque<Instant>,
+    stats: ThrottleStats,
+}
+
+impl AdaptiveRateLimiter {
+    /// Create a limiter allowing `max_rate` messages per second, unpaced
+    /// when `None`, adjusting the rate to rate-limit rejections when
+    /// `adaptive`
+    pub fn new(max_rate: Option<f64>, adaptive: bool) -> Self {
+        Self {
+            max_rate,
+            adaptive,
+            allowed_rate: max_rate,
+            tokens: max_rate.map_or(0.0, burst),
+            refilled_at: None,
+            adjusted_at: None,
+            last_decrease: None,
+            recent: VecDeque::new(),
+            stats: ThrottleStats {
+                allowed_rate: max_rate,
+                max_rate,
+                ..ThrottleStats::default()
+            },
+        }
+    }
+
+    /// Messages per second currently allowed, `None` while unpaced
+    pub fn allowed_rate(&self) -> Option<f64> {
+        self.allowed_rate
+    }
+
+    /// Reserve a slot for a message sent at `now`, returning how long to
+    /// wait before sending it
+    pub fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        let wait = match self.allowed_rate {
+            Some(rate) if self.tokens < 1.0 => Duration::from_secs_f64((1.0 - self.tokens) / rate),
+            _ => Duration::ZERO,
+        };
+        if self.allowed_rate.is_some() {
+            self.tokens -= 1.0;
+        }
+        if !wait.is_zero() {
+            self.stats.delayed_messages += 1;
+            self.stats.total_delay += wait;
+        }
+        self.recent.push_back(now + wait);
+        self.trim(now);
+        wait
+    }
+
+    /// Record a rate-limit rejection received at `now`
+    ///
+    /// Returns the new allowed rate when the rejection lowered it; further
+    /// rejections within [`RATE_INCREASE_INTERVAL`] of a decrease, caused by
+    /// the same burst, only count.
+    pub fn on_rate_limited(&mut self, now: Instant, reject: RateLimitReject) -> Option<f64> {
+        self.stats.rate_limit_rejects += 1;
+        self.stats.last_reject = Some(reject);
+        if !self.adaptive
+            || self
+                .last_decrease
+                .is_some_and(|at| now.saturating_duration_since(at) < RATE_INCREASE_INTERVAL)
+        {
+            return None;
+        }
+        self.refill(now);
+        self.trim(now);
+        let measured = self.recent.len() as f64;
+        let base = match self.allowed_rate {
+            Some(rate) if measured >= MIN_MESSAGE_RATE => rate.min(measured),
+            Some(rate) => rate,
+            None => measured,
+        };
+        let rate = (base * RATE_DECREASE_FACTOR).max(MIN_MESSAGE_RATE);
+        self.allowed_rate = Some(rate);
+        self.stats.allowed_rate = Some(rate);
+        self.tokens = self.tokens.min(0.0);
+        self.last_decrease = Some(now);
+        self.adjusted_at = Some(now);
+        Some(rate)
+    }
+
+    /// Pacing statistics
+    pub fn stats(&self) -> ThrottleStats {
+        self.stats.clone()
+    }
+
+    /// Add the tokens and rate increases earned since the last refill
+    fn refill(&mut self, now: Instant) {
+        if let (Some(rate), Some(adjusted_at)) = (self.allowed_rate, self.adjusted_at) {
+            let steps = (now.saturating_duration_since(adjusted_at).as_secs_f64()
+                / RATE_INCREASE_INTERVAL.as_secs_f64())
+            .floor();
+            if steps >= 1.0 {
+                let increased = rate + steps * RATE_INCREASE_STEP;
+                let rate = self.max_rate.map_or(increased, |max| increased.min(max));
+                self.allowed_rate = Some(rate);
+                self.stats.allowed_rate = Some(rate);
+                self.adjusted_at = Some(adjusted_at + RATE_INCREASE_INTERVAL.mul_f64(steps));
+            }
+        }
+        if let (Some(rate), Some(refilled_at)) = (self.allowed_rate, self.refilled_at) {
+            let earned = rate * now.saturating_duration_since(refilled_at).as_secs_f64();
+            self.tokens = (self.tokens + earned).min(burst(rate));
+        }
+        self.refilled_at = Some(now);
+    }
+
+    /// Forget sends older than the measuring window
+    fn trim(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|sent| now.saturating_duration_since(*sent) >= RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+}
+
+/// Messages the bucket holds at `rate`, one second's worth
+fn burst(rate: f64) -> f64 {
+    rate.max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reject() -> RateLimitReject {
+        RateLimitReject {
+            msg_type: "8".to_string(),
+            text: "too_many_requests".to_string(),
+            cl_ord_id: Some("ORDER_1".to_string()),
+            ref_seq_num: None,
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_rejects_are_classified() {
+        let rejected =
+            FixMessage::parse("35=8\x0111=ORDER_1\x0139=8\x0158=too_many_requests (10028)\x01")
+                .unwrap();
+        let classified = rate_limit_reject(&rejected).unwrap();
+        assert_eq!(classified.msg_type, "8");
+        assert_eq!(classified.cl_ord_id.as_deref(), Some("ORDER_1"));
+
+        let filled =
+            FixMessage::parse("35=8\x0111=ORDER_1\x0139=2\x0158=too_many_requests\x01").unwrap();
+        assert!(rate_limit_reject(&filled).is_none());
+        let other = FixMessage::parse("35=3\x0145=7\x0158=Invalid tag\x01").unwrap();
+        assert!(rate_limit_reject(&other).is_none());
+        let session_reject =
+            FixMessage::parse("35=3\x0145=7\x0158=Rate limit exceeded\x01").unwrap();
+        assert_eq!(
+            rate_limit_reject(&session_reject).unwrap().ref_seq_num,
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn test_messages_are_paced_to_the_configured_rate() {
+        let start = Instant::now();
+        let mut limiter = AdaptiveRateLimiter::new(Some(2.0), true);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(start), Duration::from_secs(1));
+        assert_eq!(
+            limiter.reserve(start + Duration::from_secs(3)),
+            Duration::ZERO
+        );
+        let stats = limiter.stats();
+        assert_eq!(stats.delayed_messages, 2);
+        assert_eq!(stats.total_delay, Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_rejections_halve_the_rate_and_quiet_intervals_raise_it() {
+        let start = Instant::now();
+        let mut limiter = AdaptiveRateLimiter::new(Some(10.0), true);
+        assert_eq!(limiter.on_rate_limited(start, reject()), Some(5.0));
+        // The same burst is only counted
+        assert_eq!(limiter.on_rate_limited(start, reject()), None);
+        assert_eq!(limiter.stats().rate_limit_rejects, 2);
+
+        limiter.reserve(start + Duration::from_secs(3));
+        assert_eq!(limiter.allowed_rate(), Some(8.0));
+        limiter.reserve(start + Duration::from_secs(10));
+        assert_eq!(limiter.allowed_rate(), Some(10.0));
+        assert_eq!(limiter.stats().last_reject, Some(reject()));
+    }
+
+    #[test]
+    fn test_unpaced_limiter_learns_from_the_measured_rate() {
+        let start = Instant::now();
+        let mut limiter = AdaptiveRateLimiter::new(None, true);
+        for _ in 0..20 {
+            assert_eq!(limiter.reserve(start), Duration::ZERO);
+        }
+        assert_eq!(limiter.on_rate_limited(start, reject()), Some(10.0));
+        assert!(limiter.reserve(start) > Duration::ZERO);
+
+        let mut fixed = AdaptiveRateLimiter::new(None, false);
+        assert_eq!(fixed.on_rate_limited(start, reject()), None);
+        assert_eq!(fixed.allowed_rate(), None);
+    }
+}
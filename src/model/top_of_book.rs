@@ -0,0 +1,203 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Top of book
+//!
+//! A top-of-book subscription requests MarketDepth (264) = 1 and only keeps
+//! the best bid and offer with their sizes. Its Market Data Snapshot (W) and
+//! Incremental Refresh (X) messages are scanned field by field instead of
+//! being parsed into entries and applied to an
+//! [`OrderBook`](crate::model::order_book::OrderBook), for latency-sensitive
+//! consumers that do not need depth.
+
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use serde::{Deserialize, Serialize};
+
+/// Best bid and offer of an instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopOfBook {
+    /// Instrument symbol
+    pub symbol: String,
+    /// Best bid as (price, size)
+    pub bid: Option<(f64, f64)>,
+    /// Best offer as (price, size)
+    pub ask: Option<(f64, f64)>,
+}
+
+/// Fields of a market data entry that matter for the top of book
+#[derive(Default)]
+struct ScannedEntry {
+    action: Option<char>,
+    entry_type: Option<char>,
+    price: Option<f64>,
+    size: Option<f64>,
+}
+
+impl TopOfBook {
+    /// Create an empty top of book for `symbol`
+    pub fn new(symbol: String) -> Self {
+        Self {
+            symbol,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    /// Difference between the best offer and the best bid
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.ask?.0 - self.bid?.0)
+    }
+
+    /// Midpoint between the best bid and the best offer
+    pub fn mid_price(&self) -> Option<f64> {
+        Some((self.ask?.0 + self.bid?.0) / 2.0)
+    }
+
+    /// Apply a Market Data Snapshot (W) or Incremental Refresh (X)
+    ///
+    /// Only the bid and offer entries are read; trades and other entry types
+    /// are skipped. Returns whether the best bid or offer changed.
+    pub fn apply(&mut self, message: &FixMessage) -> bool {
+        let snapshot = match message.msg_type() {
+            Some(MsgType::MarketDataSnapshotFullRefresh) => true,
+            Some(MsgType::MarketDataIncrementalRefresh) => false,
+            _ => return false,
+        };
+        let delimiter = if snapshot {
+            tags::MD_ENTRY_TYPE
+        } else {
+            tags::MD_UPDATE_ACTION
+        };
+        let previous = (self.bid, self.ask);
+        if snapshot {
+            self.bid = None;
+            self.ask = None;
+        }
+
+        let mut current: Option<ScannedEntry> = None;
+        let mut in_group = false;
+        for (tag, value) in &message.fields {
+            if *tag == tags::NO_MD_ENTRIES {
+                in_group = true;
+                continue;
+            }
+            if !in_group {
+                continue;
+            }
+            if *tag == delimiter
+                && let Some(entry) = current.replace(ScannedEntry::default())
+            {
+                self.apply_entry(&entry, snapshot);
+            }
+            let Some(entry) = current.as_mut() else {
+                continue;
+            };
+            match *tag {
+                tags::MD_UPDATE_ACTION => entry.action = value.chars().next(),
+                tags::MD_ENTRY_TYPE => entry.entry_type = value.chars().next(),
+                tags::MD_ENTRY_PX => entry.price = value.parse().ok(),
+                tags::MD_ENTRY_SIZE => entry.size = value.parse().ok(),
+                _ => {}
+            }
+        }
+        if let Some(entry) = current {
+            self.apply_entry(&entry, snapshot);
+        }
+
+        (self.bid, self.ask) != previous
+    }
+
+    fn apply_entry(&mut self, entry: &ScannedEntry, snapshot: bool) {
+        let (level, bid) = match entry.entry_type {
+            Some('0') => (&mut self.bid, true),
+            Some('1') => (&mut self.ask, false),
+            _ => return,
+        };
+        let Some(price) = entry.price else {
+            return;
+        };
+        let size = entry.size.unwrap_or(0.0);
+        // A snapshot deeper than one level keeps its best price
+        if snapshot && level.is_some_and(|(top, _)| if bid { top >= price } else { top <= price }) {
+            return;
+        }
+        // A delete (279=2) or an empty level only removes the current top
+        if entry.action != Some('2') && size > 0.0 {
+            *level = Some((price, size));
+        } else if level.is_some_and(|(top, _)| top == price) {
+            *level = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(fields: &[(u32, &str)]) -> FixMessage {
+        let mut message = FixMessage::new();
+        message
+            .fields
+            .extend(fields.iter().map(|(tag, value)| (*tag, value.to_string())));
+        message
+    }
+
+    #[test]
+    fn test_snapshot_and_incremental_updates() {
+        let mut top = TopOfBook::new("BTC-PERPETUAL".to_string());
+        let snapshot = message(&[
+            (35, "W"),
+            (55, "BTC-PERPETUAL"),
+            (268, "3"),
+            (269, "0"),
+            (270, "50000"),
+            (271, "10"),
+            (269, "0"),
+            (270, "49990"),
+            (271, "20"),
+            (269, "1"),
+            (270, "50010"),
+            (271, "5"),
+            (269, "2"),
+            (270, "50005"),
+            (271, "1"),
+        ]);
+        assert!(top.apply(&snapshot));
+        assert_eq!(top.bid, Some((50000.0, 10.0)));
+        assert_eq!(top.ask, Some((50010.0, 5.0)));
+        assert_eq!(top.spread(), Some(10.0));
+        assert_eq!(top.mid_price(), Some(50005.0));
+        assert!(!top.apply(&snapshot));
+
+        let update = message(&[
+            (35, "X"),
+            (55, "BTC-PERPETUAL"),
+            (268, "2"),
+            (279, "2"),
+            (269, "0"),
+            (270, "50000"),
+            (279, "0"),
+            (269, "0"),
+            (270, "50002"),
+            (271, "3"),
+        ]);
+        assert!(top.apply(&update));
+        assert_eq!(top.bid, Some((50002.0, 3.0)));
+
+        // Deleting a level that is no longer at the top changes nothing
+        let stale = message(&[
+            (35, "X"),
+            (268, "1"),
+            (279, "2"),
+            (269, "1"),
+            (270, "50020"),
+        ]);
+        assert!(!top.apply(&stale));
+        assert_eq!(top.ask, Some((50010.0, 5.0)));
+    }
+}
@@ -0,0 +1,234 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Own trade stream
+//!
+//! A Trade Capture Report Request (AD) with SubscriptionRequestType (263) = 1
+//! subscribes to the executions of the account: Deribit acknowledges it with
+//! a Trade Capture Report Request Ack (AQ), then sends a Trade Capture Report
+//! (AE) for every trade until the subscription is disabled. [`TradeStreams`]
+//! delivers each report to the receivers of the subscription its
+//! TradeRequestID (568) names.
+//!
+//! Each subscription holds a bounded backlog: a receiver that falls behind
+//! loses the oldest reports, which can be fetched again with a trade history
+//! request.
+
+use crate::message::TradeCaptureReport;
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Default number of trade reports a receiver may fall behind by
+pub const DEFAULT_TRADE_BACKLOG: usize = 10_000;
+
+/// Receiver of the trade reports of one subscription
+#[derive(Debug)]
+pub struct TradeStream {
+    /// TradeRequestID (568) of the subscription, to unsubscribe with
+    pub trade_request_id: String,
+    /// Trade Capture Reports (AE) of the subscription, in arrival order
+    pub reports: broadcast::Receiver<TradeCaptureReport>,
+}
+
+impl TradeStream {
+    /// Wait for the next trade report, `None` once the subscription ended
+    ///
+    /// Reports dropped because the receiver fell behind are skipped.
+    pub async fn recv(&mut self) -> Option<TradeCaptureReport> {
+        loop {
+            match self.reports.recv().await {
+                Ok(report) => return Some(report),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(
+                        "Dropped {} trade reports of {} not read in time",
+                        skipped, self.trade_request_id
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Delivers trade reports to the channels of their subscription
+#[derive(Debug)]
+pub struct TradeStreams {
+    /// Sender of each subscription, by TradeRequestID (568)
+    channels: HashMap<String, broadcast::Sender<TradeCaptureReport>>,
+    /// Reports each receiver may fall behind by
+    backlog: usize,
+    /// Most reports a receiver fell behind by
+    high_water: usize,
+    /// Reports dropped because a receiver fell behind
+    dropped: u64,
+}
+
+impl Default for TradeStreams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TradeStreams {
+    /// Create streams without subscriptions, each receiver holding up to
+    /// [`DEFAULT_TRADE_BACKLOG`] reports
+    pub fn new() -> Self {
+        Self::with_backlog(DEFAULT_TRADE_BACKLOG)
+    }
+
+    /// Create streams without subscriptions, each receiver holding up to
+    /// `backlog` reports, rounded up to a power of two
+    pub fn with_backlog(backlog: usize) -> Self {
+        Self {
+            channels: HashMap::new(),
+            backlog: backlog.max(1),
+            high_water: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Open the channel of the subscription `trade_request_id`
+    pub fn subscribe(&mut self, trade_request_id: &str) -> TradeStream {
+        let (sender, reports) = broadcast::channel(self.backlog);
+        self.channels.insert(trade_request_id.to_string(), sender);
+        TradeStream {
+            trade_request_id: trade_request_id.to_string(),
+            reports,
+        }
+    }
+
+    /// Whether `trade_request_id` is an open subscription
+    pub fn contains(&self, trade_request_id: &str) -> bool {
+        self.channels.contains_key(trade_request_id)
+    }
+
+    /// Close the channel of the subscription `trade_request_id`
+    ///
+    /// Returns whether the subscription was open.
+    pub fn remove(&mut self, trade_request_id: &str) -> bool {
+        self.channels.remove(trade_request_id).is_some()
+    }
+
+    /// TradeRequestIDs (568) of the open subscriptions
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.channels.keys().map(String::as_str)
+    }
+
+    /// Most reports a receiver fell behind by
+    pub fn high_water(&self) -> usize {
+        self.high_water
+    }
+
+    /// Reports dropped, oldest first, because a receiver fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Deliver a trade report
+    ///
+    /// A report naming a subscription goes to that subscription only; one
+    /// without TradeRequestID (568) goes to every subscription. A channel
+    /// whose receiver was dropped is closed, and a receiver whose backlog is
+    /// full loses its oldest report. Returns the number of subscriptions the
+    /// report was delivered to.
+    pub fn publish(&mut self, report: &TradeCaptureReport) -> usize {
+        let (mut delivered, mut high_water, mut dropped) = (0, self.high_water, 0);
+        self.channels.retain(|trade_request_id, sender| {
+            if report
+                .trade_request_id
+                .as_ref()
+                .is_some_and(|id| id != trade_request_id)
+            {
+                return true;
+            }
+            let queued = sender.len();
+            if sender.send(report.clone()).is_err() {
+                return false;
+            }
+            // A full backlog evicts its oldest report instead of growing
+            if sender.len() == queued {
+                dropped += 1;
+            }
+            high_water = high_water.max(sender.len());
+            delivered += 1;
+            true
+        });
+        self.high_water = high_water;
+        self.dropped += dropped;
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::OrderSide;
+
+    fn report(trade_request_id: Option<&str>) -> TradeCaptureReport {
+        let report = TradeCaptureReport::new_trade(
+            "REPORT-1".to_string(),
+            "TRADE-1".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            OrderSide::Buy,
+            10.0,
+            10.0,
+            50_000.0,
+            "20261016".to_string(),
+        );
+        match trade_request_id {
+            Some(id) => report.with_trade_request_id(id.to_string()),
+            None => report,
+        }
+    }
+
+    #[test]
+    fn test_reports_go_to_their_subscription() {
+        let mut streams = TradeStreams::new();
+        let mut first = streams.subscribe("TCR_1");
+        let mut second = streams.subscribe("TCR_2");
+
+        assert_eq!(streams.publish(&report(Some("TCR_1"))), 1);
+        assert!(first.reports.try_recv().is_ok());
+        assert!(second.reports.try_recv().is_err());
+
+        assert_eq!(streams.publish(&report(None)), 2);
+        assert!(first.reports.try_recv().is_ok());
+        assert!(second.reports.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_removed_and_dropped_subscriptions_are_closed() {
+        let mut streams = TradeStreams::new();
+        let mut kept = streams.subscribe("TCR_1");
+        drop(streams.subscribe("TCR_2"));
+
+        assert_eq!(streams.publish(&report(None)), 1);
+        assert!(!streams.contains("TCR_2"));
+
+        assert!(streams.remove("TCR_1"));
+        assert!(!streams.remove("TCR_1"));
+        assert!(kept.reports.try_recv().is_ok());
+        assert!(kept.reports.try_recv().is_err());
+        assert_eq!(streams.ids().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_lagging_receiver_loses_oldest_reports() {
+        let mut streams = TradeStreams::with_backlog(2);
+        let mut stream = streams.subscribe("TCR_1");
+        for report_id in ["REPORT-1", "REPORT-2", "REPORT-3"] {
+            let mut trade = report(Some("TCR_1"));
+            trade.trade_report_id = report_id.to_string();
+            assert_eq!(streams.publish(&trade), 1);
+        }
+        assert_eq!(streams.high_water(), 2);
+        assert_eq!(streams.dropped(), 1);
+
+        assert_eq!(stream.recv().await.unwrap().trade_report_id, "REPORT-2");
+        assert_eq!(stream.recv().await.unwrap().trade_report_id, "REPORT-3");
+    }
+}
@@ -47,7 +47,7 @@ pub use crate::message::{
 pub use crate::model::*;
 
 // Session exports - session management
-pub use crate::session::{Session, SessionState};
+pub use crate::session::{Session, SessionEvent, SessionState};
 
 // Utility exports
 pub use crate::utils::setup_logger;
@@ -0,0 +1,812 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+use super::MarketDataUpdate;
+use crate::error::{DeribitFixError, Result};
+use crate::message::{MdEntry, MdEntryType, MdUpdateAction};
+use crate::model::message::FixMessage;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, TrySendError};
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Columns of an export, in file order
+pub const EXPORT_COLUMNS: [&str; 11] = [
+    "received_at",
+    "symbol",
+    "kind",
+    "entry_type",
+    "action",
+    "price",
+    "size",
+    "side",
+    "trade_id",
+    "entry_time",
+    "rpt_seq",
+];
+
+/// Messages queued between the session and the export file by default
+pub const DEFAULT_EXPORT_QUEUE: usize = 1024;
+
+/// How long the export thread waits for data before flushing the file
+const IDLE_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Rows buffered before a Parquet export writes them as a record batch
+#[cfg(feature = "parquet")]
+const PARQUET_BATCH_ROWS: usize = 8192;
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma separated values with a header line
+    Csv,
+    /// Apache Parquet, requires the `parquet` feature
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl ExportFormat {
+    /// File extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// What an exported row describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportRowKind {
+    /// Bid or offer level of the order book
+    Book,
+    /// Trade
+    Trade,
+    /// Index value or settlement price
+    Index,
+}
+
+impl ExportRowKind {
+    /// Name of the kind in exported files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportRowKind::Book => "book",
+            ExportRowKind::Trade => "trade",
+            ExportRowKind::Index => "index",
+        }
+    }
+}
+
+/// Update an exported row comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportAction {
+    /// Entry of a Market Data Snapshot/Full Refresh (W)
+    Snapshot,
+    /// New entry of an Incremental Refresh (X)
+    New,
+    /// Changed entry of an Incremental Refresh (X)
+    Change,
+    /// Deleted entry of an Incremental Refresh (X)
+    Delete,
+}
+
+impl ExportAction {
+    /// Name of the action in exported files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportAction::Snapshot => "snapshot",
+            ExportAction::New => "new",
+            ExportAction::Change => "change",
+            ExportAction::Delete => "delete",
+        }
+    }
+}
+
+impl TryFrom<MdUpdateAction> for ExportAction {
+    type Error = MdUpdateAction;
+
+    fn try_from(action: MdUpdateAction) -> std::result::Result<Self, Self::Error> {
+        match action {
+            MdUpdateAction::New => Ok(ExportAction::New),
+            MdUpdateAction::Change => Ok(ExportAction::Change),
+            MdUpdateAction::Delete => Ok(ExportAction::Delete),
+            MdUpdateAction::Unknown(_) => Err(action),
+        }
+    }
+}
+
+/// Normalized market data entry, one row of an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRow {
+    /// Time the message was received
+    pub received_at: DateTime<Utc>,
+    /// Instrument symbol
+    pub symbol: String,
+    /// What the row describes
+    pub kind: ExportRowKind,
+    /// Type of the market data entry
+    pub entry_type: MdEntryType,
+    /// Update the row comes from
+    pub action: ExportAction,
+    /// Price, or index value
+    pub price: Option<f64>,
+    /// Size
+    pub size: Option<f64>,
+    /// Aggressor side of a trade
+    pub side: Option<char>,
+    /// Trade ID
+    pub trade_id: Option<String>,
+    /// Exchange time of the entry
+    pub entry_time: Option<DateTime<Utc>>,
+    /// Per-instrument sequence number (RptSeq, tag 83)
+    pub rpt_seq: Option<u64>,
+}
+
+impl ExportRow {
+    /// Rows of a market data update, one per entry
+    ///
+    /// Entries whose type or update action this version does not know are
+    /// left out.
+    pub fn from_update(received_at: DateTime<Utc>, update: &MarketDataUpdate) -> Vec<Self> {
+        let (symbol, entries, snapshot) = match update {
+            MarketDataUpdate::Snapshot(snapshot) => (&snapshot.symbol, &snapshot.entries, true),
+            MarketDataUpdate::Incremental(update) => (&update.symbol, &update.entries, false),
+        };
+        entries
+            .iter()
+            .filter_map(|entry| Self::from_entry(received_at, symbol, entry, snapshot))
+            .collect()
+    }
+
+    fn from_entry(
+        received_at: DateTime<Utc>,
+        symbol: &str,
+        entry: &MdEntry,
+        snapshot: bool,
+    ) -> Option<Self> {
+        let kind = match entry.md_entry_type {
+            MdEntryType::Bid | MdEntryType::Offer => ExportRowKind::Book,
+            MdEntryType::Trade => ExportRowKind::Trade,
+            MdEntryType::IndexValue | MdEntryType::SettlementPrice => ExportRowKind::Index,
+            MdEntryType::Unknown(_) => return None,
+        };
+        let action = match (snapshot, entry.md_update_action) {
+            (true, _) => ExportAction::Snapshot,
+            (false, Some(action)) => action.try_into().ok()?,
+            (false, None) => ExportAction::New,
+        };
+        Some(Self {
+            received_at,
+            symbol: symbol.to_string(),
+            kind,
+            entry_type: entry.md_entry_type,
+            action,
+            price: entry.md_entry_px,
+            size: entry.md_entry_size,
+            side: entry.side,
+            trade_id: entry.trade_id.clone(),
+            entry_time: entry.md_entry_date,
+            rpt_seq: entry.rpt_seq,
+        })
+    }
+
+    /// The row as CSV fields, in [`EXPORT_COLUMNS`] order
+    fn csv_line(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        [
+            timestamp(self.received_at),
+            csv_field(&self.symbol),
+            self.kind.as_str().to_string(),
+            entry_type_name(self.entry_type).to_string(),
+            self.action.as_str().to_string(),
+            optional(self.price.map(|price| price.to_string())),
+            optional(self.size.map(|size| size.to_string())),
+            optional(self.side.map(String::from)),
+            optional(self.trade_id.as_deref().map(csv_field)),
+            optional(self.entry_time.map(timestamp)),
+            optional(self.rpt_seq.map(|seq| seq.to_string())),
+        ]
+        .join(",")
+    }
+}
+
+/// Name of an entry type in exported files
+fn entry_type_name(entry_type: MdEntryType) -> &'static str {
+    match entry_type {
+        MdEntryType::Bid => "bid",
+        MdEntryType::Offer => "offer",
+        MdEntryType::Trade => "trade",
+        MdEntryType::IndexValue => "index",
+        MdEntryType::SettlementPrice => "settlement",
+        MdEntryType::Unknown(_) => "unknown",
+    }
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes normalized market data rows to rotating files
+///
+/// Files are named `<prefix>-<UTC time>-<index>.<extension>` in the export
+/// directory and opened on the first row written. A new file is started
+/// once the current one holds [`with_max_rows`](Self::with_max_rows) rows
+/// or has been open for the
+/// [`with_rotation_interval`](Self::with_rotation_interval). Parquet files
+/// are only readable once rotated or [`finish`](Self::finish)ed.
+#[derive(Debug)]
+pub struct MarketDataExporter {
+    directory: PathBuf,
+    prefix: String,
+    format: ExportFormat,
+    max_rows: Option<u64>,
+    rotation_interval: Option<Duration>,
+    current: Option<ExportFile>,
+    files: Vec<PathBuf>,
+    rows: u64,
+}
+
+impl MarketDataExporter {
+    /// Export to files named after `prefix` in `directory`
+    pub fn new(directory: impl AsRef<Path>, prefix: &str, format: ExportFormat) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            format,
+            max_rows: None,
+            rotation_interval: None,
+            current: None,
+            files: Vec::new(),
+            rows: 0,
+        }
+    }
+
+    /// Start a new file once the current one holds `max_rows` rows
+    pub fn with_max_rows(mut self, max_rows: u64) -> Self {
+        self.max_rows = Some(max_rows.max(1));
+        self
+    }
+
+    /// Start a new file once the current one has been open for `interval`
+    pub fn with_rotation_interval(mut self, interval: Duration) -> Self {
+        self.rotation_interval = Some(interval);
+        self
+    }
+
+    /// Format of the exported files
+    pub fn format(&self) -> ExportFormat {
+        self.format
+    }
+
+    /// Files written so far, including the current one
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Rows written so far
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Write the rows of a market data update
+    ///
+    /// Returns the number of rows written.
+    pub fn write_update(
+        &mut self,
+        received_at: DateTime<Utc>,
+        update: &MarketDataUpdate,
+    ) -> Result<usize> {
+        let rows = ExportRow::from_update(received_at, update);
+        self.write_rows(&rows)?;
+        Ok(rows.len())
+    }
+
+    /// Write the rows of `message` if it is market data
+    ///
+    /// Returns the number of rows written, 0 for other message types.
+    pub fn write_message(
+        &mut self,
+        received_at: DateTime<Utc>,
+        message: &FixMessage,
+    ) -> Result<usize> {
+        match MarketDataUpdate::from_fix_message(message)? {
+            Some(update) => self.write_update(received_at, &update),
+            None => Ok(0),
+        }
+    }
+
+    /// Write rows, rotating files as needed
+    pub fn write_rows(&mut self, rows: &[ExportRow]) -> Result<()> {
+        for row in rows {
+            let due = self.current.as_ref().is_some_and(|file| {
+                self.max_rows.is_some_and(|max| file.rows >= max)
+                    || self
+                        .rotation_interval
+                        .is_some_and(|interval| file.opened_at.elapsed() >= interval)
+            });
+            if due {
+                self.rotate()?;
+            }
+            if self.current.is_none() {
+                self.current = Some(self.open()?);
+            }
+            if let Some(file) = &mut self.current {
+                file.write(row)?;
+            }
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// Close the current file; the next row starts a new one
+    pub fn rotate(&mut self) -> Result<()> {
+        if let Some(file) = self.current.take() {
+            file.close()?;
+        }
+        Ok(())
+    }
+
+    /// Flush buffered rows of the current file
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some(file) = &mut self.current {
+            file.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Close the current file and return every file written
+    pub fn finish(mut self) -> Result<Vec<PathBuf>> {
+        self.rotate()?;
+        Ok(self.files)
+    }
+
+    /// Export market data from the session messages on a background thread
+    ///
+    /// Up to `capacity` messages wait for the file. When the export falls
+    /// behind, further messages are dropped and counted in
+    /// [`ExportStats::dropped_messages`] rather than slowing the session down.
+    pub fn spawn(
+        self,
+        messages: broadcast::Receiver<FixMessage>,
+        capacity: usize,
+    ) -> Result<MarketDataExportHandle> {
+        let counters = Arc::new(ExportCounters::default());
+        let (queue, queued) = mpsc::sync_channel(capacity.max(1));
+        let writer = std::thread::Builder::new()
+            .name("deribit-fix-export".to_string())
+            .spawn({
+                let counters = counters.clone();
+                move || self.run(queued, &counters)
+            })?;
+        let (stop, stopped) = oneshot::channel();
+        let forwarder = tokio::spawn(forward(messages, queue, stopped, counters.clone()));
+        Ok(MarketDataExportHandle {
+            stop,
+            forwarder,
+            writer,
+            counters,
+        })
+    }
+
+    /// Write queued updates until the queue closes
+    fn run(
+        mut self,
+        queued: mpsc::Receiver<(DateTime<Utc>, MarketDataUpdate)>,
+        counters: &ExportCounters,
+    ) -> Result<Vec<PathBuf>> {
+        loop {
+            match queued.recv_timeout(IDLE_FLUSH_INTERVAL) {
+                Ok((received_at, update)) => {
+                    let rows = self.write_update(received_at, &update)?;
+                    counters.messages.fetch_add(1, Ordering::Relaxed);
+                    counters.rows.fetch_add(rows as u64, Ordering::Relaxed);
+                }
+                Err(RecvTimeoutError::Timeout) => self.flush()?,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        self.finish()
+    }
+
+    fn open(&mut self) -> Result<ExportFile> {
+        let path = self.directory.join(format!(
+            "{}-{}-{:04}.{}",
+            self.prefix,
+            Utc::now().format("%Y%m%dT%H%M%S"),
+            self.files.len(),
+            self.format.extension()
+        ));
+        let file = File::create(&path).map_err(|e| {
+            DeribitFixError::Config(format!(
+                "Cannot create market data export {}: {e}",
+                path.display()
+            ))
+        })?;
+        debug!("Exporting market data to {}", path.display());
+        let writer = match self.format {
+            ExportFormat::Csv => {
+                let mut writer = BufWriter::new(file);
+                writeln!(writer, "{}", EXPORT_COLUMNS.join(","))?;
+                FileWriter::Csv(writer)
+            }
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => {
+                FileWriter::Parquet(Box::new(parquet_export::ParquetFile::new(file)?))
+            }
+        };
+        self.files.push(path);
+        Ok(ExportFile {
+            writer,
+            rows: 0,
+            opened_at: std::time::Instant::now(),
+        })
+    }
+}
+
+/// Forward the market data of the session messages to the export thread
+///
+/// Once stopped, the messages already received are forwarded before the
+/// queue closes.
+async fn forward(
+    mut messages: broadcast::Receiver<FixMessage>,
+    queue: mpsc::SyncSender<(DateTime<Utc>, MarketDataUpdate)>,
+    mut stopped: oneshot::Receiver<()>,
+    counters: Arc<ExportCounters>,
+) {
+    loop {
+        let received = tokio::select! {
+            _ = &mut stopped => break,
+            received = messages.recv() => received,
+        };
+        match received {
+            Ok(message) => {
+                if !enqueue(&message, &queue, &counters) {
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                counters.dropped.fetch_add(missed, Ordering::Relaxed);
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+    loop {
+        match messages.try_recv() {
+            Ok(message) => {
+                if !enqueue(&message, &queue, &counters) {
+                    return;
+                }
+            }
+            Err(broadcast::error::TryRecvError::Lagged(missed)) => {
+                counters.dropped.fetch_add(missed, Ordering::Relaxed);
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+/// Queue the market data of `message` without waiting for the export thread
+///
+/// Returns `false` once the export thread has stopped.
+fn enqueue(
+    message: &FixMessage,
+    queue: &mpsc::SyncSender<(DateTime<Utc>, MarketDataUpdate)>,
+    counters: &ExportCounters,
+) -> bool {
+    let update = match MarketDataUpdate::from_fix_message(message) {
+        Ok(Some(update)) => update,
+        Ok(None) => return true,
+        Err(e) => {
+            warn!("Market data not exported: {}", e);
+            return true;
+        }
+    };
+    match queue.try_send((Utc::now(), update)) {
+        Ok(()) => true,
+        Err(TrySendError::Full(_)) => {
+            counters.dropped.fetch_add(1, Ordering::Relaxed);
+            true
+        }
+        // The export thread stopped on an error, reported by finish()
+        Err(TrySendError::Disconnected(_)) => false,
+    }
+}
+
+/// Counters shared with the export thread
+#[derive(Debug, Default)]
+struct ExportCounters {
+    messages: AtomicU64,
+    rows: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Progress of a background export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportStats {
+    /// Market data messages written
+    pub messages_exported: u64,
+    /// Rows written
+    pub rows_written: u64,
+    /// Market data messages dropped because the export fell behind
+    pub dropped_messages: u64,
+}
+
+/// Background export started by [`MarketDataExporter::spawn`]
+#[derive(Debug)]
+pub struct MarketDataExportHandle {
+    stop: oneshot::Sender<()>,
+    forwarder: JoinHandle<()>,
+    writer: std::thread::JoinHandle<Result<Vec<PathBuf>>>,
+    counters: Arc<ExportCounters>,
+}
+
+impl MarketDataExportHandle {
+    /// Progress of the export
+    pub fn stats(&self) -> ExportStats {
+        ExportStats {
+            messages_exported: self.counters.messages.load(Ordering::Relaxed),
+            rows_written: self.counters.rows.load(Ordering::Relaxed),
+            dropped_messages: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stop exporting, write the queued messages and return the files written
+    pub async fn finish(self) -> Result<Vec<PathBuf>> {
+        let _ = self.stop.send(());
+        let _ = self.forwarder.await;
+        let writer = self.writer;
+        tokio::task::spawn_blocking(move || writer.join())
+            .await
+            .map_err(|e| DeribitFixError::Generic(format!("Market data export failed: {e}")))?
+            .map_err(|_| DeribitFixError::Generic("Market data export panicked".to_string()))?
+    }
+}
+
+/// File being written by an export
+#[derive(Debug)]
+struct ExportFile {
+    writer: FileWriter,
+    rows: u64,
+    opened_at: std::time::Instant,
+}
+
+impl ExportFile {
+    fn write(&mut self, row: &ExportRow) -> Result<()> {
+        match &mut self.writer {
+            FileWriter::Csv(writer) => writeln!(writer, "{}", row.csv_line())?,
+            #[cfg(feature = "parquet")]
+            FileWriter::Parquet(writer) => writer.write(row.clone())?,
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match &mut self.writer {
+            FileWriter::Csv(writer) => writer.flush()?,
+            #[cfg(feature = "parquet")]
+            FileWriter::Parquet(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+
+    fn close(self) -> Result<()> {
+        match self.writer {
+            FileWriter::Csv(mut writer) => writer.flush()?,
+            #[cfg(feature = "parquet")]
+            FileWriter::Parquet(writer) => writer.close()?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum FileWriter {
+    Csv(BufWriter<File>),
+    #[cfg(feature = "parquet")]
+    Parquet(Box<parquet_export::ParquetFile>),
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_export {
+    use super::{ExportRow, PARQUET_BATCH_ROWS, entry_type_name};
+    use crate::error::{DeribitFixError, Result};
+    use arrow_array::{
+        ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray, UInt64Array,
+    };
+    use arrow_schema::{DataType, Field, Schema, TimeUnit};
+    use parquet::arrow::ArrowWriter;
+    use std::fs::File;
+    use std::sync::Arc;
+
+    fn parquet_error(error: impl std::fmt::Display) -> DeribitFixError {
+        DeribitFixError::Generic(format!("Parquet export failed: {error}"))
+    }
+
+    fn schema() -> Arc<Schema> {
+        let time = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+        Arc::new(Schema::new(vec![
+            Field::new("received_at", time.clone(), false),
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("kind", DataType::Utf8, false),
+            Field::new("entry_type", DataType::Utf8, false),
+            Field::new("action", DataType::Utf8, false),
+            Field::new("price", DataType::Float64, true),
+            Field::new("size", DataType::Float64, true),
+            Field::new("side", DataType::Utf8, true),
+            Field::new("trade_id", DataType::Utf8, true),
+            Field::new("entry_time", time, true),
+            Field::new("rpt_seq", DataType::UInt64, true),
+        ]))
+    }
+
+    /// Parquet file written in record batches
+    pub(super) struct ParquetFile {
+        writer: ArrowWriter<File>,
+        schema: Arc<Schema>,
+        pending: Vec<ExportRow>,
+    }
+
+    impl std::fmt::Debug for ParquetFile {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ParquetFile")
+                .field("pending", &self.pending.len())
+                .finish()
+        }
+    }
+
+    impl ParquetFile {
+        pub(super) fn new(file: File) -> Result<Self> {
+            let schema = schema();
+            Ok(Self {
+                writer: ArrowWriter::try_new(file, schema.clone(), None).map_err(parquet_error)?,
+                schema,
+                pending: Vec::new(),
+            })
+        }
+
+        pub(super) fn write(&mut self, row: ExportRow) -> Result<()> {
+            self.pending.push(row);
+            if self.pending.len() >= PARQUET_BATCH_ROWS {
+                self.write_pending()?;
+            }
+            Ok(())
+        }
+
+        /// Write the pending rows and end the row group
+        pub(super) fn flush(&mut self) -> Result<()> {
+            self.write_pending()?;
+            self.writer.flush().map_err(parquet_error)
+        }
+
+        pub(super) fn close(mut self) -> Result<()> {
+            self.write_pending()?;
+            self.writer.close().map_err(parquet_error)?;
+            Ok(())
+        }
+
+        fn write_pending(&mut self) -> Result<()> {
+            if self.pending.is_empty() {
+                return Ok(());
+            }
+            let rows = std::mem::take(&mut self.pending);
+            let strings = |value: fn(&ExportRow) -> Option<String>| -> ArrayRef {
+                Arc::new(rows.iter().map(value).collect::<StringArray>())
+            };
+            let times = |value: fn(&ExportRow) -> Option<i64>| -> ArrayRef {
+                Arc::new(
+                    rows.iter()
+                        .map(value)
+                        .collect::<TimestampMicrosecondArray>()
+                        .with_timezone("UTC"),
+                )
+            };
+            let floats = |value: fn(&ExportRow) -> Option<f64>| -> ArrayRef {
+                Arc::new(rows.iter().map(value).collect::<Float64Array>())
+            };
+            let columns = vec![
+                times(|row| Some(row.received_at.timestamp_micros())),
+                strings(|row| Some(row.symbol.clone())),
+                strings(|row| Some(row.kind.as_str().to_string())),
+                strings(|row| Some(entry_type_name(row.entry_type).to_string())),
+                strings(|row| Some(row.action.as_str().to_string())),
+                floats(|row| row.price),
+                floats(|row| row.size),
+                strings(|row| row.side.map(String::from)),
+                strings(|row| row.trade_id.clone()),
+                times(|row| row.entry_time.map(|time| time.timestamp_micros())),
+                Arc::new(rows.iter().map(|row| row.rpt_seq).collect::<UInt64Array>()),
+            ];
+            let batch =
+                RecordBatch::try_new(self.schema.clone(), columns).map_err(parquet_error)?;
+            self.writer.write(&batch).map_err(parquet_error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{MarketDataIncrementalRefresh, MarketDataSnapshotFullRefresh};
+
+    #[test]
+    fn test_rows_are_normalized() {
+        let received_at = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let trade = MdEntry::trade(50005.0, 0.5, '1', "T,1".to_string(), received_at);
+        let update = MarketDataUpdate::Incremental(
+            MarketDataIncrementalRefresh::new("BTC-PERPETUAL".to_string()).with_entries(vec![
+                MdEntry::bid(50000.0, 10.0).with_update_action(MdUpdateAction::Delete),
+                trade,
+            ]),
+        );
+
+        let rows = ExportRow::from_update(received_at, &update);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].kind, ExportRowKind::Book);
+        assert_eq!(rows[0].action, ExportAction::Delete);
+        assert_eq!(rows[1].kind, ExportRowKind::Trade);
+        assert_eq!(rows[1].action, ExportAction::New);
+        assert_eq!(
+            rows[1].csv_line(),
+            "2025-10-09T08:53:20.000000Z,BTC-PERPETUAL,trade,trade,new,50005,0.5,1,\"T,1\",\
+             2025-10-09T08:53:20.000000Z,"
+        );
+
+        let mut index = MdEntry::bid(50001.5, 0.0);
+        index.md_entry_type = MdEntryType::IndexValue;
+        let snapshot = MarketDataUpdate::Snapshot(
+            MarketDataSnapshotFullRefresh::new("BTC_USD".to_string()).with_entries(vec![index]),
+        );
+        let rows = ExportRow::from_update(received_at, &snapshot);
+        assert_eq!(rows[0].kind, ExportRowKind::Index);
+        assert_eq!(rows[0].action, ExportAction::Snapshot);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_export_round_trip() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let directory =
+            std::env::temp_dir().join(format!("deribit_fix_parquet_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let update = MarketDataUpdate::Incremental(
+            MarketDataIncrementalRefresh::new("ETH-PERPETUAL".to_string()).with_entries(vec![
+                MdEntry::bid(2500.0, 3.0).with_update_action(MdUpdateAction::New),
+                MdEntry::offer(2501.0, 4.0).with_update_action(MdUpdateAction::Change),
+            ]),
+        );
+        let mut exporter = MarketDataExporter::new(&directory, "md", ExportFormat::Parquet);
+        assert_eq!(exporter.write_update(Utc::now(), &update).unwrap(), 2);
+        let files = exporter.finish().unwrap();
+        assert_eq!(files.len(), 1);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&files[0]).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(rows, 2);
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
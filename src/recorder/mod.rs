@@ -0,0 +1,172 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Market data recording and playback
+//!
+//! [`MarketDataRecorder`] appends every Market Data Snapshot/Full Refresh (W)
+//! and Incremental Refresh (X) to a compact binary file together with the time
+//! it was received. [`MarketDataPlayback`] reads such a file back at real-time
+//! or accelerated speed as typed [`MarketDataUpdate`]s and maintains the same
+//! [`OrderBook`](crate::model::order_book::OrderBook)s the session builds from
+//! live data, so strategies can be backtested and issues replayed offline.
+//!
+//! The file starts with the [`RECORDING_MAGIC`] header, followed by one record
+//! per message: the receive time in microseconds since the Unix epoch (`i64`,
+//! little endian), the message length (`u32`, little endian) and the raw FIX
+//! message.
+//!
+//! [`MarketDataExporter`] writes the same data as normalized rows, one per
+//! book level, trade or index value, to rotating CSV files, or Parquet files
+//! with the `parquet` feature, for research pipelines.
+
+/// Export of market data to CSV and Parquet files
+pub mod export;
+/// Playback of recorded market data
+pub mod playback;
+
+pub use export::*;
+pub use playback::*;
+
+use crate::error::{DeribitFixError, Result};
+use crate::model::message::FixMessage;
+use crate::model::types::MsgType;
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Header identifying a market data recording, including the format version
+pub const RECORDING_MAGIC: &[u8; 8] = b"DFIXMD1\n";
+
+/// Length of the fixed part of a record: timestamp and message length
+const RECORD_HEADER_LENGTH: usize = 12;
+
+/// Message read from a recording
+#[derive(Debug, Clone)]
+pub struct RecordedMessage {
+    /// Time the message was received
+    pub received_at: DateTime<Utc>,
+    /// The recorded message
+    pub message: FixMessage,
+}
+
+/// Appends market data messages to a recording
+#[derive(Debug)]
+pub struct MarketDataRecorder<W: Write = File> {
+    writer: W,
+    records: u64,
+}
+
+impl MarketDataRecorder<File> {
+    /// Create or truncate the recording at `path`
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| {
+            DeribitFixError::Config(format!(
+                "Cannot create market data recording {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::new(file)
+    }
+}
+
+impl<W: Write> MarketDataRecorder<W> {
+    /// Start a recording on `writer`, writing the file header
+    pub fn new(mut writer: W) -> Result<Self> {
+        writer.write_all(RECORDING_MAGIC)?;
+        Ok(Self { writer, records: 0 })
+    }
+
+    /// Whether a message is market data that gets recorded
+    pub fn is_recorded(message: &FixMessage) -> bool {
+        matches!(
+            message.msg_type(),
+            Some(MsgType::MarketDataSnapshotFullRefresh | MsgType::MarketDataIncrementalRefresh)
+        )
+    }
+
+    /// Record `message` with the current time if it is market data
+    ///
+    /// Returns whether the message was recorded.
+    pub fn record(&mut self, message: &FixMessage) -> Result<bool> {
+        if !Self::is_recorded(message) {
+            return Ok(false);
+        }
+        self.record_at(Utc::now(), message)?;
+        Ok(true)
+    }
+
+    /// Record `message` as received at `received_at`, whatever its type
+    pub fn record_at(&mut self, received_at: DateTime<Utc>, message: &FixMessage) -> Result<()> {
+        let raw = if message.raw_message.is_empty() {
+            message
+                .fields
+                .iter()
+                .map(|(tag, value)| format!("{tag}={value}\x01"))
+                .collect()
+        } else {
+            message.raw_message.clone()
+        };
+        let length = u32::try_from(raw.len()).map_err(|_| {
+            DeribitFixError::MessageConstruction(format!(
+                "Message of {} bytes is too large to record",
+                raw.len()
+            ))
+        })?;
+
+        // One write per record so a crash never leaves more than one partial record
+        let mut record = Vec::with_capacity(RECORD_HEADER_LENGTH + raw.len());
+        record.extend_from_slice(&received_at.timestamp_micros().to_le_bytes());
+        record.extend_from_slice(&length.to_le_bytes());
+        record.extend_from_slice(raw.as_bytes());
+        self.writer.write_all(&record)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Number of messages recorded
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Flush buffered records to the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Flush and return the underlying writer
+    pub fn into_inner(mut self) -> Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_market_data_is_recorded() {
+        let mut recorder = MarketDataRecorder::new(Vec::new()).unwrap();
+        let heartbeat = FixMessage::parse("8=FIX.4.4\x019=5\x0135=0\x0110=000\x01").unwrap();
+        let snapshot =
+            FixMessage::parse("8=FIX.4.4\x019=20\x0135=W\x0155=BTC-PERPETUAL\x0110=000\x01")
+                .unwrap();
+
+        assert!(!recorder.record(&heartbeat).unwrap());
+        assert!(recorder.record(&snapshot).unwrap());
+        assert_eq!(recorder.records(), 1);
+
+        let bytes = recorder.into_inner().unwrap();
+        assert!(bytes.starts_with(RECORDING_MAGIC));
+        assert_eq!(
+            bytes.len(),
+            RECORDING_MAGIC.len() + RECORD_HEADER_LENGTH + snapshot.raw_message.len()
+        );
+    }
+}
@@ -0,0 +1,345 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+use super::{RECORD_HEADER_LENGTH, RECORDING_MAGIC, RecordedMessage};
+use crate::connection::MAX_BODY_LENGTH;
+use crate::error::{DeribitFixError, Result};
+use crate::message::{MarketDataIncrementalRefresh, MarketDataSnapshotFullRefresh};
+use crate::model::message::FixMessage;
+use crate::model::order_book::{BookIntegrityIssue, OrderBook};
+use crate::model::types::MsgType;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// Longest message a record may hold: a frame with the longest body the
+/// framer accepts, plus its BeginString, BodyLength and CheckSum fields
+const MAX_RECORD_LENGTH: usize = MAX_BODY_LENGTH + 64;
+
+/// Pace at which recorded messages are replayed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaybackSpeed {
+    /// Keep the original spacing between messages
+    RealTime,
+    /// Divide the original spacing by the factor, e.g. `10.0` is ten times faster
+    Accelerated(f64),
+    /// Replay without waiting
+    AsFastAsPossible,
+}
+
+/// Typed market data update read from a recording
+#[derive(Debug, Clone)]
+pub enum MarketDataUpdate {
+    /// Market Data Snapshot/Full Refresh (W)
+    Snapshot(MarketDataSnapshotFullRefresh),
+    /// Market Data Incremental Refresh (X)
+    Incremental(MarketDataIncrementalRefresh),
+}
+
+impl MarketDataUpdate {
+    /// Parse a W or X message, `None` for other message types
+    pub fn from_fix_message(message: &FixMessage) -> Result<Option<Self>> {
+        Ok(match message.msg_type() {
+            Some(MsgType::MarketDataSnapshotFullRefresh) => Some(MarketDataUpdate::Snapshot(
+                MarketDataSnapshotFullRefresh::from_fix_message(message)?,
+            )),
+            Some(MsgType::MarketDataIncrementalRefresh) => Some(MarketDataUpdate::Incremental(
+                MarketDataIncrementalRefresh::from_fix_message(message)?,
+            )),
+            _ => None,
+        })
+    }
+
+    /// Instrument the update refers to
+    pub fn symbol(&self) -> &str {
+        match self {
+            MarketDataUpdate::Snapshot(snapshot) => &snapshot.symbol,
+            MarketDataUpdate::Incremental(update) => &update.symbol,
+        }
+    }
+}
+
+/// Replays a market data recording
+///
+/// Order books are maintained from the replayed updates exactly like the
+/// session does for live market data.
+#[derive(Debug)]
+pub struct MarketDataPlayback<R: Read = BufReader<File>> {
+    reader: R,
+    speed: PlaybackSpeed,
+    /// Playback start and the receive time of the first message
+    origin: Option<(Instant, DateTime<Utc>)>,
+    order_books: HashMap<String, OrderBook>,
+}
+
+impl MarketDataPlayback<BufReader<File>> {
+    /// Open the recording at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| {
+            DeribitFixError::Config(format!(
+                "Cannot open market data recording {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> MarketDataPlayback<R> {
+    /// Start a real-time playback of `reader`, checking the file header
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; RECORDING_MAGIC.len()];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| not_a_recording())?;
+        if &magic != RECORDING_MAGIC {
+            return Err(not_a_recording());
+        }
+        Ok(Self {
+            reader,
+            speed: PlaybackSpeed::RealTime,
+            origin: None,
+            order_books: HashMap::new(),
+        })
+    }
+
+    /// Set the playback speed
+    pub fn with_speed(mut self, speed: PlaybackSpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Order book of `symbol` as of the last replayed update
+    pub fn order_book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.order_books.get(symbol)
+    }
+
+    /// Next recorded message, waiting until it is due at the playback speed
+    ///
+    /// Returns `None` at the end of the recording. A record cut short, as left
+    /// by a recorder that was interrupted, also ends the recording.
+    pub async fn next_message(&mut self) -> Result<Option<RecordedMessage>> {
+        let Some(recorded) = self.read_record()? else {
+            return Ok(None);
+        };
+
+        let (started, first) = *self
+            .origin
+            .get_or_insert((Instant::now(), recorded.received_at));
+        let elapsed = (recorded.received_at - first).to_std().unwrap_or_default();
+        let delay = match self.speed {
+            PlaybackSpeed::RealTime => Some(elapsed),
+            PlaybackSpeed::Accelerated(factor) if factor > 0.0 => Some(elapsed.div_f64(factor)),
+            PlaybackSpeed::Accelerated(_) | PlaybackSpeed::AsFastAsPossible => None,
+        };
+        if let Some(delay) = delay {
+            tokio::time::sleep_until(started + delay).await;
+        }
+        Ok(Some(recorded))
+    }
+
+    /// Next market data update, applied to the order books before it is returned
+    ///
+    /// Messages other than W and X are skipped. Updates that leave a book
+    /// inconsistent are returned anyway; the book then waits for the next
+    /// snapshot, as in a live session.
+    pub async fn next_update(&mut self) -> Result<Option<MarketDataUpdate>> {
+        while let Some(recorded) = self.next_message().await? {
+            let Some(update) = MarketDataUpdate::from_fix_message(&recorded.message)? else {
+                continue;
+            };
+            if let Err(issue) = self.apply(&update) {
+                warn!(
+                    "Recorded order book for {} failed validation: {:?}",
+                    update.symbol(),
+                    issue
+                );
+            }
+            return Ok(Some(update));
+        }
+        Ok(None)
+    }
+
+    /// Apply an update to the order book of its instrument
+    fn apply(&mut self, update: &MarketDataUpdate) -> std::result::Result<(), BookIntegrityIssue> {
+        match update {
+            MarketDataUpdate::Snapshot(snapshot) => {
+                self.order_books
+                    .entry(snapshot.symbol.clone())
+                    .or_insert_with(|| OrderBook::new(snapshot.symbol.clone()))
+                    .apply_snapshot(snapshot);
+                Ok(())
+            }
+            MarketDataUpdate::Incremental(update) => match self.order_books.get_mut(&update.symbol)
+            {
+                Some(book) => book.apply_incremental(update),
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Read the next record without waiting
+    fn read_record(&mut self) -> Result<Option<RecordedMessage>> {
+        let mut header = [0u8; RECORD_HEADER_LENGTH];
+        if !self.read_complete(&mut header)? {
+            return Ok(None);
+        }
+        let (timestamp, length) = header.split_at(8);
+        let timestamp = i64::from_le_bytes(timestamp.try_into().unwrap_or_default());
+        let length = u32::from_le_bytes(length.try_into().unwrap_or_default()) as usize;
+        // A corrupt length must not decide how much memory is allocated
+        if length > MAX_RECORD_LENGTH {
+            return Err(DeribitFixError::MessageParsing(format!(
+                "Recorded message of {length} bytes exceeds the {MAX_RECORD_LENGTH} byte limit"
+            )));
+        }
+
+        let mut raw = vec![0u8; length];
+        if !self.read_complete(&mut raw)? {
+            return Ok(None);
+        }
+        let received_at = DateTime::from_timestamp_micros(timestamp).ok_or_else(|| {
+            DeribitFixError::MessageParsing(format!("Invalid recorded timestamp: {timestamp}"))
+        })?;
+        let raw = String::from_utf8(raw).map_err(|e| {
+            DeribitFixError::MessageParsing(format!("Recorded message is not UTF-8: {e}"))
+        })?;
+        Ok(Some(RecordedMessage {
+            received_at,
+            message: FixMessage::parse(&raw)?,
+        }))
+    }
+
+    /// Fill `buffer`, returning `false` at the end of the recording
+    fn read_complete(&mut self, buffer: &mut [u8]) -> Result<bool> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(0) => {
+                    if filled > 0 {
+                        warn!("Market data recording ends with a partial record");
+                    }
+                    return Ok(false);
+                }
+                Ok(read) => filled += read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn not_a_recording() -> DeribitFixError {
+    DeribitFixError::MessageParsing("Not a market data recording".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::MarketDataRecorder;
+    use chrono::TimeDelta;
+
+    fn snapshot(bid: f64, ask: f64) -> FixMessage {
+        FixMessage::parse(&format!(
+            "8=FIX.4.4\x019=0\x0135=W\x0155=BTC-PERPETUAL\x01268=2\x01269=0\x01270={bid}\x01271=1\x01269=1\x01270={ask}\x01271=2\x0110=000\x01"
+        ))
+        .unwrap()
+    }
+
+    fn recording(spacing: TimeDelta) -> Vec<u8> {
+        let start = Utc::now();
+        let mut recorder = MarketDataRecorder::new(Vec::new()).unwrap();
+        recorder.record_at(start, &snapshot(100.0, 101.0)).unwrap();
+        recorder
+            .record_at(start + spacing, &snapshot(100.5, 101.5))
+            .unwrap();
+        assert_eq!(recorder.records(), 2);
+        recorder.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_playback_rebuilds_order_books() {
+        let bytes = recording(TimeDelta::seconds(60));
+        let mut playback = MarketDataPlayback::new(bytes.as_slice())
+            .unwrap()
+            .with_speed(PlaybackSpeed::AsFastAsPossible);
+
+        let update = playback.next_update().await.unwrap().unwrap();
+        assert!(matches!(update, MarketDataUpdate::Snapshot(_)));
+        assert_eq!(update.symbol(), "BTC-PERPETUAL");
+        assert_eq!(
+            playback.order_book("BTC-PERPETUAL").unwrap().best_bid(),
+            Some((100.0, 1.0))
+        );
+
+        playback.next_update().await.unwrap().unwrap();
+        assert_eq!(
+            playback.order_book("BTC-PERPETUAL").unwrap().best_ask(),
+            Some((101.5, 2.0))
+        );
+        assert!(playback.next_update().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_accelerated_playback_keeps_relative_spacing() {
+        let bytes = recording(TimeDelta::milliseconds(400));
+        let mut playback = MarketDataPlayback::new(bytes.as_slice())
+            .unwrap()
+            .with_speed(PlaybackSpeed::Accelerated(10.0));
+
+        let start = std::time::Instant::now();
+        playback.next_message().await.unwrap().unwrap();
+        playback.next_message().await.unwrap().unwrap();
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= std::time::Duration::from_millis(40),
+            "{elapsed:?}"
+        );
+        assert!(
+            elapsed < std::time::Duration::from_millis(400),
+            "{elapsed:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partial_record_ends_playback() {
+        let mut bytes = recording(TimeDelta::zero());
+        bytes.truncate(bytes.len() - 5);
+        let mut playback = MarketDataPlayback::new(bytes.as_slice())
+            .unwrap()
+            .with_speed(PlaybackSpeed::AsFastAsPossible);
+
+        assert!(playback.next_message().await.unwrap().is_some());
+        assert!(playback.next_message().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_record_is_rejected() {
+        let mut bytes = RECORDING_MAGIC.to_vec();
+        bytes.extend_from_slice(&Utc::now().timestamp_micros().to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        let mut playback = MarketDataPlayback::new(bytes.as_slice())
+            .unwrap()
+            .with_speed(PlaybackSpeed::AsFastAsPossible);
+
+        assert!(matches!(
+            playback.next_message().await,
+            Err(DeribitFixError::MessageParsing(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_files_that_are_not_recordings() {
+        assert!(MarketDataPlayback::new(&b"8=FIX.4.4\x019=5\x01"[..]).is_err());
+        assert!(MarketDataPlayback::new(&b""[..]).is_err());
+    }
+}
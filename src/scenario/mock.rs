@@ -0,0 +1,359 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Mock exchange
+//!
+//! [`MockExchange`](crate::scenario::MockExchange) answers a client connected
+//! through a [`MemoryConnector`](crate::connection::MemoryConnector) the way
+//! Deribit does for the flows scenarios cover:
+//!
+//! - Logon (A), Logout (5) and Test Request (1) are answered in kind, the
+//!   Logon echoing CancelOnDisconnect (9001);
+//! - a Market Data Request (V) gets a Market Data Snapshot/Full Refresh (W)
+//!   of each instrument, with the best bid and offer set by
+//!   [`MockExchange::with_book`], or no entries for other instruments;
+//! - a limit New Order Single (D) rests and is acknowledged by an Execution
+//!   Report (8) with OrdStatus New, a market order is filled at once at the
+//!   best price of its book or rejected when the book is empty;
+//! - an Order Cancel Request (F) for a resting order is confirmed by an
+//!   Execution Report (8) with OrdStatus Cancelled and refused with an Order
+//!   Cancel Reject (9) otherwise.
+//!
+//! Replies swap the SenderCompID (49) and TargetCompID (56) of the request,
+//! so any session identifiers work. Every message read from the client is
+//! kept for inspection.
+
+use crate::connection::{FixFramer, MemoryConnector};
+use crate::message::time::format_utc_timestamp;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Size of the resting orders of the books of a mock exchange
+const BOOK_SIZE: f64 = 100_000.0;
+
+/// Best bid and offer of an instrument
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MockBook {
+    bid: f64,
+    ask: f64,
+}
+
+/// Order resting on a mock exchange
+#[derive(Debug, Clone)]
+struct MockOrder {
+    order_id: String,
+    cl_ord_id: String,
+    symbol: String,
+    side: String,
+    qty: String,
+    price: Option<String>,
+}
+
+/// State shared by the connections of a mock exchange
+#[derive(Debug, Default)]
+struct MockState {
+    books: HashMap<String, MockBook>,
+    /// Resting orders by OrderID (37)
+    orders: HashMap<String, MockOrder>,
+    received: Vec<FixMessage>,
+    next_id: u64,
+}
+
+impl MockState {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+/// Exchange simulator for scenarios and tests
+#[derive(Debug, Clone, Default)]
+pub struct MockExchange {
+    books: HashMap<String, MockBook>,
+}
+
+impl MockExchange {
+    /// Create an exchange without books
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Quote `symbol` at `bid` and `ask`
+    pub fn with_book(mut self, symbol: impl Into<String>, bid: f64, ask: f64) -> Self {
+        self.books.insert(symbol.into(), MockBook { bid, ask });
+        self
+    }
+
+    /// Serve the connections of the returned connector in the background
+    ///
+    /// Pass the connector to
+    /// [`DeribitFixClient::with_connector`](crate::client::DeribitFixClient::with_connector).
+    pub fn spawn(self) -> (MemoryConnector, MockExchangeHandle) {
+        let (connector, mut listener) = MemoryConnector::new();
+        let state = Arc::new(Mutex::new(MockState {
+            books: self.books,
+            ..MockState::default()
+        }));
+        let task = tokio::spawn({
+            let state = state.clone();
+            async move {
+                while let Some(stream) = listener.accept().await {
+                    tokio::spawn(serve(stream, state.clone()));
+                }
+            }
+        });
+        (connector, MockExchangeHandle { state, task })
+    }
+}
+
+/// Mock exchange running in the background, stopped when dropped
+#[derive(Debug)]
+pub struct MockExchangeHandle {
+    state: Arc<Mutex<MockState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockExchangeHandle {
+    /// Messages read from the client so far
+    pub fn received(&self) -> Vec<FixMessage> {
+        lock(&self.state).received.clone()
+    }
+
+    /// Number of orders resting on the exchange
+    pub fn open_orders(&self) -> usize {
+        lock(&self.state).orders.len()
+    }
+}
+
+impl Drop for MockExchangeHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn lock(state: &Mutex<MockState>) -> MutexGuard<'_, MockState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Answer the messages of one connection until it closes or logs out
+async fn serve(mut stream: DuplexStream, state: Arc<Mutex<MockState>>) {
+    let mut framer = FixFramer::new();
+    let mut buf = [0u8; 8192];
+    let mut seq_num = 1;
+    loop {
+        let n = match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => n,
+        };
+        framer.push(&buf[..n]);
+        loop {
+            let frame = match framer.next_frame() {
+                Ok(Some(frame)) => frame,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("Mock exchange dropped a malformed frame: {}", e);
+                    continue;
+                }
+            };
+            let Ok(message) = FixMessage::parse(&String::from_utf8_lossy(&frame)) else {
+                continue;
+            };
+            let logout = message.msg_type() == Some(MsgType::Logout);
+            let replies = {
+                let mut state = lock(&state);
+                state.received.push(message.clone());
+                reply(&mut state, &message)
+            };
+            for (msg_type, body) in replies {
+                let framed = frame_reply(&message, msg_type, seq_num, &body);
+                seq_num += 1;
+                if stream.write_all(framed.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+            if logout {
+                return;
+            }
+        }
+    }
+}
+
+/// Replies to `message`, as MsgType and body fields
+fn reply(state: &mut MockState, message: &FixMessage) -> Vec<(MsgType, String)> {
+    let field = |tag: u32| message.get_field(tag).cloned().unwrap_or_default();
+    match message.msg_type() {
+        Some(MsgType::Logon) => {
+            let heartbeat = message
+                .get_field(tags::HEART_BT_INT)
+                .cloned()
+                .unwrap_or_else(|| "30".to_string());
+            // Deribit echoes the CancelOnDisconnect (9001) of the Logon
+            let cancel_on_disconnect = message
+                .get_field(tags::CANCEL_ON_DISCONNECT)
+                .map(|value| format!("9001={value}\x01"))
+                .unwrap_or_default();
+            vec![(
+                MsgType::Logon,
+                format!("98=0\x01108={heartbeat}\x01{cancel_on_disconnect}"),
+            )]
+        }
+        Some(MsgType::Logout) => vec![(MsgType::Logout, String::new())],
+        Some(MsgType::TestRequest) => vec![(
+            MsgType::Heartbeat,
+            format!("112={}\x01", field(tags::TEST_REQ_ID)),
+        )],
+        Some(MsgType::MarketDataRequest) if field(tags::SUBSCRIPTION_REQUEST_TYPE) != "2" => {
+            let md_req_id = field(tags::MD_REQ_ID);
+            message
+                .fields
+                .iter()
+                .filter(|(tag, _)| *tag == tags::SYMBOL)
+                .map(|(_, symbol)| {
+                    let entries = match state.books.get(symbol) {
+                        Some(book) => format!(
+                            "268=2\x01269=0\x01270={}\x01271={BOOK_SIZE}\x01\
+                             269=1\x01270={}\x01271={BOOK_SIZE}\x01",
+                            book.bid, book.ask
+                        ),
+                        None => "268=0\x01".to_string(),
+                    };
+                    (
+                        MsgType::MarketDataSnapshotFullRefresh,
+                        format!("262={md_req_id}\x0155={symbol}\x01{entries}"),
+                    )
+                })
+                .collect()
+        }
+        Some(MsgType::NewOrderSingle) => {
+            vec![(MsgType::ExecutionReport, new_order(state, message))]
+        }
+        Some(MsgType::OrderCancelRequest) => vec![cancel(state, message)],
+        msg_type => {
+            debug!("Mock exchange ignores {:?}", msg_type);
+            Vec::new()
+        }
+    }
+}
+
+/// Execution Report (8) body answering a New Order Single (D)
+fn new_order(state: &mut MockState, message: &FixMessage) -> String {
+    let field = |tag: u32| message.get_field(tag).cloned().unwrap_or_default();
+    let order = MockOrder {
+        order_id: format!("MOCK-{}", state.next_id()),
+        cl_ord_id: field(tags::CL_ORD_ID),
+        symbol: field(tags::SYMBOL),
+        side: field(tags::SIDE),
+        qty: field(tags::ORDER_QTY),
+        price: message.get_field(tags::PRICE).cloned(),
+    };
+    let exec_id = format!("MOCK-EXEC-{}", state.next_id());
+    let head = format!(
+        "37={}\x0111={}\x0117={exec_id}\x0155={}\x0154={}\x0138={}\x01",
+        order.order_id, order.cl_ord_id, order.symbol, order.side, order.qty
+    );
+
+    // OrdType (40) 1 is a market order
+    if message.get_field(tags::ORD_TYPE).map(String::as_str) != Some("1") {
+        let price = order
+            .price
+            .as_ref()
+            .map(|price| format!("44={price}\x01"))
+            .unwrap_or_default();
+        let body = format!(
+            "{head}150=0\x0139=0\x0114=0\x01151={}\x01{price}",
+            order.qty
+        );
+        state.orders.insert(order.order_id.clone(), order);
+        return body;
+    }
+    let fill_px = state.books.get(&order.symbol).map(|book| {
+        if order.side == "1" {
+            book.ask
+        } else {
+            book.bid
+        }
+    });
+    match fill_px {
+        Some(px) => format!(
+            "{head}150=F\x0139=2\x0114={qty}\x01151=0\x0132={qty}\x0131={px}\x016={px}\x01",
+            qty = order.qty
+        ),
+        None => format!("{head}150=8\x0139=8\x0114=0\x01151=0\x0158=No liquidity\x01"),
+    }
+}
+
+/// Reply to an Order Cancel Request (F)
+fn cancel(state: &mut MockState, message: &FixMessage) -> (MsgType, String) {
+    let orig_cl_ord_id = message.get_field(tags::ORIG_CL_ORD_ID);
+    let cl_ord_id = message.get_field(tags::CL_ORD_ID);
+    // Deribit takes the OrderID in OrigClOrdID (41), or the ClOrdID (11)
+    // of the order with its symbol
+    let order_id = match orig_cl_ord_id {
+        Some(order_id) => state
+            .orders
+            .contains_key(order_id)
+            .then(|| order_id.clone()),
+        None => state
+            .orders
+            .values()
+            .find(|order| Some(&order.cl_ord_id) == cl_ord_id)
+            .map(|order| order.order_id.clone()),
+    };
+    let Some(order) = order_id.and_then(|order_id| state.orders.remove(&order_id)) else {
+        let id = orig_cl_ord_id.or(cl_ord_id).cloned().unwrap_or_default();
+        return (
+            MsgType::OrderCancelReject,
+            format!(
+                "37=NONE\x0111={id}\x0141={id}\x0139=8\x01434=1\x01102=1\x0158=Unknown order\x01"
+            ),
+        );
+    };
+    let orig = orig_cl_ord_id
+        .map(|id| format!("41={id}\x01"))
+        .unwrap_or_default();
+    (
+        MsgType::ExecutionReport,
+        format!(
+            "37={}\x0111={}\x01{orig}17=MOCK-EXEC-{}\x01150=4\x0139=4\x0155={}\x0154={}\x01\
+             38={}\x0114=0\x01151=0\x01",
+            order.order_id,
+            order.cl_ord_id,
+            state.next_id(),
+            order.symbol,
+            order.side,
+            order.qty
+        ),
+    )
+}
+
+/// Frame a reply to `request` with the standard header and trailer
+fn frame_reply(request: &FixMessage, msg_type: MsgType, seq_num: u32, body: &str) -> String {
+    let sender = request
+        .get_field(tags::TARGET_COMP_ID)
+        .cloned()
+        .unwrap_or_default();
+    let target = request
+        .get_field(tags::SENDER_COMP_ID)
+        .cloned()
+        .unwrap_or_default();
+    let body = format!(
+        "35={}\x0134={seq_num}\x0149={sender}\x0156={target}\x0152={}\x01{body}",
+        msg_type.as_str(),
+        format_utc_timestamp(&Utc::now())
+    );
+    let head = format!("8=FIX.4.4\x019={}\x01{body}", body.len());
+    let checksum = head.bytes().map(u32::from).sum::<u32>() % 256;
+    format!("{head}10={checksum:03}\x01")
+}
@@ -0,0 +1,453 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Declarative test scenarios
+//!
+//! A [`Scenario`](crate::scenario::Scenario) describes a test flow as data: a
+//! list of [`ScenarioStep`](crate::scenario::ScenarioStep)s such as logging
+//! on, subscribing to market data, sending an order or waiting for a message
+//! matching an [`ExpectedMessage`](crate::scenario::ExpectedMessage). The
+//! [`ScenarioRunner`](crate::scenario::ScenarioRunner) executes it through a
+//! [`DeribitFixClient`](crate::client::DeribitFixClient), so the same
+//! scenario runs against the [`MockExchange`](crate::scenario::MockExchange)
+//! in unit tests and against the Deribit test server for certification.
+//!
+//! Scenarios are written with the builder methods of
+//! [`Scenario`](crate::scenario::Scenario):
+//!
+//! ```
+//! use deribit_fix::model::request::NewOrderRequest;
+//! use deribit_fix::model::types::MsgType;
+//! use deribit_fix::scenario::{ExpectedMessage, Scenario};
+//!
+//! let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 40_000.0);
+//! let scenario = Scenario::new("order entry")
+//!     .logon()
+//!     .subscribe("BTC-PERPETUAL")
+//!     .expect(ExpectedMessage::new(MsgType::MarketDataSnapshotFullRefresh).with_field(55, "BTC-PERPETUAL"))
+//!     .send_order("bid", order)
+//!     .expect(ExpectedMessage::new(MsgType::ExecutionReport).with_order("bid").with_field(39, "0"))
+//!     .cancel("bid")
+//!     .expect(ExpectedMessage::new(MsgType::ExecutionReport).with_order("bid").with_field(39, "4"))
+//!     .logout();
+//! assert_eq!(scenario.steps.len(), 8);
+//! ```
+//!
+//! or, with the `config-files` feature, loaded from YAML or TOML files
+//! where each step names its kind in `step`:
+//!
+//! ```yaml
+//! name: order entry
+//! steps:
+//!   - step: logon
+//!   - step: subscribe
+//!     symbol: BTC-PERPETUAL
+//!   - step: expect
+//!     msg_type: W
+//!     fields:
+//!       55: BTC-PERPETUAL
+//!   - step: send_order
+//!     name: bid
+//!     order:
+//!       instrument_name: BTC-PERPETUAL
+//!       side: Buy
+//!       type: limit
+//!       amount: 10
+//!       price: 40000
+//!       time_in_force: good_til_cancelled
+//!   - step: expect
+//!     msg_type: 8
+//!     order: bid
+//!     fields:
+//!       39: 0
+//!   - step: logout
+//! ```
+
+/// Exchange simulator serving in-memory connections
+pub mod mock;
+/// Execution of scenarios through a client
+pub mod runner;
+
+pub use mock::*;
+pub use runner::*;
+
+use crate::error::{DeribitFixError, Result};
+use crate::model::message::FixMessage;
+use crate::model::request::NewOrderRequest;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// Field value of an [`ExpectedMessage`] matching any value
+pub const ANY_VALUE: &str = "*";
+
+/// Test flow described as data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    /// Name shown in reports
+    pub name: String,
+    /// What the scenario checks
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Steps in the order they run
+    #[serde(default)]
+    pub steps: Vec<ScenarioStep>,
+}
+
+/// One step of a [`Scenario`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum ScenarioStep {
+    /// Connect and wait until the session is logged on
+    Logon,
+    /// Log out, wait for the server's Logout (5) and disconnect
+    Logout,
+    /// Subscribe to the order book of an instrument
+    Subscribe {
+        /// Instrument symbol
+        symbol: String,
+    },
+    /// Cancel every market data subscription of an instrument
+    Unsubscribe {
+        /// Instrument symbol
+        symbol: String,
+    },
+    /// Send a New Order Single (D), remembered under `name`
+    SendOrder {
+        /// Name later steps refer to the order by
+        name: String,
+        /// Order to send
+        order: NewOrderRequest,
+    },
+    /// Cancel an order sent by a previous step and wait for the confirmation
+    Cancel {
+        /// Name the order was sent under
+        order: String,
+    },
+    /// Wait for a message matching the expectation
+    Expect(ExpectedMessage),
+    /// Pause the scenario
+    Wait {
+        /// Time to wait in milliseconds
+        millis: u64,
+    },
+}
+
+impl ScenarioStep {
+    /// Short description of the step for reports
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Logon => "logon".to_string(),
+            Self::Logout => "logout".to_string(),
+            Self::Subscribe { symbol } => format!("subscribe {symbol}"),
+            Self::Unsubscribe { symbol } => format!("unsubscribe {symbol}"),
+            Self::SendOrder { name, order } => format!(
+                "send order {name} ({:?} {} {})",
+                order.side, order.amount, order.instrument_name
+            ),
+            Self::Cancel { order } => format!("cancel order {order}"),
+            Self::Expect(expected) => format!("expect {expected}"),
+            Self::Wait { millis } => format!("wait {millis} ms"),
+        }
+    }
+}
+
+/// Message a scenario waits for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedMessage {
+    /// MsgType (35) of the message
+    #[serde(deserialize_with = "deserialize_scalar")]
+    pub msg_type: String,
+    /// Values of tags the message must carry; [`ANY_VALUE`] only requires
+    /// the tag to be present
+    #[serde(default, deserialize_with = "deserialize_fields")]
+    pub fields: BTreeMap<u32, String>,
+    /// Name of an order sent by the scenario the message must refer to by
+    /// ClOrdID (11), OrigClOrdID (41) or OrderID (37)
+    #[serde(default)]
+    pub order: Option<String>,
+    /// Time to wait for the message in milliseconds, the runner's step
+    /// timeout when unset
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+impl ExpectedMessage {
+    /// Expect a message of type `msg_type`
+    pub fn new(msg_type: MsgType) -> Self {
+        Self {
+            msg_type: msg_type.as_str().to_string(),
+            fields: BTreeMap::new(),
+            order: None,
+            timeout_ms: None,
+        }
+    }
+
+    /// Require `tag` to have `value`, or any value for [`ANY_VALUE`]
+    pub fn with_field(mut self, tag: u32, value: impl Into<String>) -> Self {
+        self.fields.insert(tag, value.into());
+        self
+    }
+
+    /// Require the message to refer to the order sent as `name`
+    pub fn with_order(mut self, name: impl Into<String>) -> Self {
+        self.order = Some(name.into());
+        self
+    }
+
+    /// Set the time to wait for the message
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Whether the type and fields of `message` match
+    ///
+    /// The order is not checked, it needs the identifiers the runner knows.
+    pub fn matches(&self, message: &FixMessage) -> bool {
+        message.get_field(tags::MSG_TYPE) == Some(&self.msg_type)
+            && self
+                .fields
+                .iter()
+                .all(|(tag, expected)| match message.get_field(*tag) {
+                    Some(value) => expected == ANY_VALUE || value == expected,
+                    None => false,
+                })
+    }
+}
+
+impl std::fmt::Display for ExpectedMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "35={}", self.msg_type)?;
+        if let Some(order) = &self.order {
+            write!(f, " for order {order}")?;
+        }
+        for (tag, value) in &self.fields {
+            write!(f, " {tag}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Scenario {
+    /// Create a scenario without steps
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Set what the scenario checks
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Append a step
+    pub fn with_step(mut self, step: ScenarioStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Append a [`ScenarioStep::Logon`]
+    pub fn logon(self) -> Self {
+        self.with_step(ScenarioStep::Logon)
+    }
+
+    /// Append a [`ScenarioStep::Logout`]
+    pub fn logout(self) -> Self {
+        self.with_step(ScenarioStep::Logout)
+    }
+
+    /// Append a [`ScenarioStep::Subscribe`]
+    pub fn subscribe(self, symbol: impl Into<String>) -> Self {
+        self.with_step(ScenarioStep::Subscribe {
+            symbol: symbol.into(),
+        })
+    }
+
+    /// Append a [`ScenarioStep::Unsubscribe`]
+    pub fn unsubscribe(self, symbol: impl Into<String>) -> Self {
+        self.with_step(ScenarioStep::Unsubscribe {
+            symbol: symbol.into(),
+        })
+    }
+
+    /// Append a [`ScenarioStep::SendOrder`]
+    pub fn send_order(self, name: impl Into<String>, order: NewOrderRequest) -> Self {
+        self.with_step(ScenarioStep::SendOrder {
+            name: name.into(),
+            order,
+        })
+    }
+
+    /// Append a [`ScenarioStep::Cancel`]
+    pub fn cancel(self, order: impl Into<String>) -> Self {
+        self.with_step(ScenarioStep::Cancel {
+            order: order.into(),
+        })
+    }
+
+    /// Append a [`ScenarioStep::Expect`]
+    pub fn expect(self, expected: ExpectedMessage) -> Self {
+        self.with_step(ScenarioStep::Expect(expected))
+    }
+
+    /// Append a [`ScenarioStep::Wait`]
+    pub fn wait(self, millis: u64) -> Self {
+        self.with_step(ScenarioStep::Wait { millis })
+    }
+
+    /// Load a scenario from JSON
+    pub fn from_json(contents: &str) -> Result<Self> {
+        Ok(serde_json::from_str(contents)?)
+    }
+
+    /// Load a scenario from the contents of a TOML or YAML file
+    #[cfg(feature = "config-files")]
+    pub fn from_contents(contents: &str, format: crate::config::ConfigFormat) -> Result<Self> {
+        match format {
+            crate::config::ConfigFormat::Toml => toml::from_str(contents)
+                .map_err(|e| DeribitFixError::Config(format!("Invalid scenario: {e}"))),
+            crate::config::ConfigFormat::Yaml => serde_yaml::from_str(contents)
+                .map_err(|e| DeribitFixError::Config(format!("Invalid scenario: {e}"))),
+        }
+    }
+
+    /// Load a scenario from a TOML or YAML file, chosen by its extension
+    #[cfg(feature = "config-files")]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let format = crate::config::ConfigFormat::from_path(path).ok_or_else(|| {
+            DeribitFixError::Config(format!(
+                "Unsupported scenario file {}, expected .toml, .yaml or .yml",
+                path.display()
+            ))
+        })?;
+        Self::from_contents(&std::fs::read_to_string(path)?, format)
+    }
+
+    /// Check that every order a step refers to is sent by an earlier step
+    pub fn validate(&self) -> Result<()> {
+        let mut orders = Vec::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            let referenced = match step {
+                ScenarioStep::SendOrder { name, .. } => {
+                    if orders.contains(&name) {
+                        return Err(DeribitFixError::Config(format!(
+                            "Step {} sends order {name} a second time",
+                            index + 1
+                        )));
+                    }
+                    orders.push(name);
+                    None
+                }
+                ScenarioStep::Cancel { order } => Some(order),
+                ScenarioStep::Expect(expected) => expected.order.as_ref(),
+                _ => None,
+            };
+            if let Some(order) = referenced.filter(|order| !orders.contains(order)) {
+                return Err(DeribitFixError::Config(format!(
+                    "Step {} refers to order {order} before it is sent",
+                    index + 1
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Scalar written in a scenario file, where tag values may be numbers
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    Text(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl From<Scalar> for String {
+    fn from(scalar: Scalar) -> Self {
+        match scalar {
+            Scalar::Text(text) => text,
+            Scalar::Integer(value) => value.to_string(),
+            Scalar::Float(value) => value.to_string(),
+            Scalar::Bool(value) => if value { "Y" } else { "N" }.to_string(),
+        }
+    }
+}
+
+/// Tag of a field, written as a number or a string
+#[derive(Deserialize, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+enum TagKey {
+    Number(u32),
+    Text(String),
+}
+
+fn deserialize_scalar<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<String, D::Error> {
+    Scalar::deserialize(deserializer).map(String::from)
+}
+
+fn deserialize_fields<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<BTreeMap<u32, String>, D::Error> {
+    std::collections::HashMap::<TagKey, Scalar>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(tag, value)| {
+            let tag = match tag {
+                TagKey::Number(tag) => tag,
+                TagKey::Text(tag) => tag
+                    .parse()
+                    .map_err(|_| serde::de::Error::custom(format!("invalid tag {tag}")))?,
+            };
+            Ok((tag, value.into()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_message_matches_type_and_fields() {
+        let message = FixMessage::parse("35=8\x0139=0\x0155=BTC-PERPETUAL\x01").unwrap();
+        let expected = ExpectedMessage::new(MsgType::ExecutionReport).with_field(39, "0");
+        assert!(expected.matches(&message));
+        assert!(expected.clone().with_field(55, ANY_VALUE).matches(&message));
+        assert!(!expected.clone().with_field(58, ANY_VALUE).matches(&message));
+        assert!(!expected.with_field(39, "4").matches(&message));
+        assert!(!ExpectedMessage::new(MsgType::Heartbeat).matches(&message));
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_orders() {
+        let order = NewOrderRequest::market_buy("BTC-PERPETUAL".to_string(), 10.0);
+        let scenario = Scenario::new("cancel before send")
+            .cancel("bid")
+            .send_order("bid", order.clone());
+        assert!(scenario.validate().is_err());
+
+        let scenario = Scenario::new("sent twice")
+            .send_order("bid", order.clone())
+            .send_order("bid", order.clone());
+        assert!(scenario.validate().is_err());
+
+        let scenario = Scenario::new("valid").send_order("bid", order).expect(
+            ExpectedMessage::new(MsgType::ExecutionReport)
+                .with_order("bid")
+                .with_field(150, "F"),
+        );
+        assert!(scenario.validate().is_ok());
+    }
+}
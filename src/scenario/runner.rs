@@ -0,0 +1,333 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Scenario runner
+//!
+//! [`ScenarioRunner`](crate::scenario::ScenarioRunner) runs the steps of a
+//! [`Scenario`](crate::scenario::Scenario) one after the other through a
+//! [`DeribitFixClient`](crate::client::DeribitFixClient) and stops at the
+//! first step that fails.
+//! Expectations read the messages the client receives in order; messages
+//! not matching the current expectation are skipped, so heartbeats and
+//! unrelated reports do not make a scenario fail. The client is left as
+//! the last step left it, connected or not.
+
+use crate::client::DeribitFixClient;
+use crate::error::{DeribitFixError, Result};
+use crate::model::cancel::CancelTarget;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::scenario::{ExpectedMessage, Scenario, ScenarioStep};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Time a step may take by default
+pub const DEFAULT_STEP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of one step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// The step completed
+    Passed,
+    /// The step failed for the given reason
+    Failed(String),
+}
+
+/// Report of one step of a scenario
+#[derive(Debug, Clone)]
+pub struct StepReport {
+    /// Position of the step, starting at 1
+    pub index: usize,
+    /// Description of the step
+    pub description: String,
+    /// Time the step took
+    pub elapsed: Duration,
+    /// Result of the step
+    pub outcome: StepOutcome,
+    /// Message that satisfied an expectation
+    pub matched: Option<FixMessage>,
+}
+
+/// Report of a scenario run
+#[derive(Debug, Clone)]
+pub struct ScenarioReport {
+    /// Name of the scenario
+    pub name: String,
+    /// Steps that ran, in order
+    pub steps: Vec<StepReport>,
+    /// Steps not run after a failure
+    pub skipped: usize,
+}
+
+impl ScenarioReport {
+    /// Whether every step ran and passed
+    pub fn passed(&self) -> bool {
+        self.skipped == 0
+            && self
+                .steps
+                .iter()
+                .all(|step| step.outcome == StepOutcome::Passed)
+    }
+
+    /// Step that failed, if any
+    pub fn failure(&self) -> Option<&StepReport> {
+        self.steps
+            .iter()
+            .find(|step| step.outcome != StepOutcome::Passed)
+    }
+
+    /// The report if the scenario passed, otherwise an error naming the
+    /// failed step
+    pub fn into_result(self) -> Result<Self> {
+        match self.failure() {
+            Some(StepReport {
+                index,
+                description,
+                outcome: StepOutcome::Failed(reason),
+                ..
+            }) => Err(DeribitFixError::Generic(format!(
+                "Scenario {} failed at step {index} ({description}): {reason}",
+                self.name
+            ))),
+            _ => Ok(self),
+        }
+    }
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Scenario {}", self.name)?;
+        for step in &self.steps {
+            match &step.outcome {
+                StepOutcome::Passed => writeln!(
+                    f,
+                    "  [ok]     {}. {} ({:?})",
+                    step.index, step.description, step.elapsed
+                )?,
+                StepOutcome::Failed(reason) => writeln!(
+                    f,
+                    "  [failed] {}. {} ({:?}): {reason}",
+                    step.index, step.description, step.elapsed
+                )?,
+            }
+        }
+        if self.skipped > 0 {
+            writeln!(f, "  {} steps skipped", self.skipped)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs scenarios through a client
+pub struct ScenarioRunner {
+    client: DeribitFixClient,
+    step_timeout: Duration,
+    /// ClOrdID (11) of the orders sent by the running scenario, by name
+    orders: HashMap<String, String>,
+}
+
+impl fmt::Debug for ScenarioRunner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScenarioRunner")
+            .field("step_timeout", &self.step_timeout)
+            .field("orders", &self.orders)
+            .finish()
+    }
+}
+
+impl ScenarioRunner {
+    /// Run scenarios through `client`
+    pub fn new(client: DeribitFixClient) -> Self {
+        Self {
+            client,
+            step_timeout: DEFAULT_STEP_TIMEOUT,
+            orders: HashMap::new(),
+        }
+    }
+
+    /// Set the time a step may take, unless an expectation sets its own
+    pub fn with_step_timeout(mut self, step_timeout: Duration) -> Self {
+        self.step_timeout = step_timeout;
+        self
+    }
+
+    /// Client the scenarios run through
+    pub fn client(&self) -> &DeribitFixClient {
+        &self.client
+    }
+
+    /// ClOrdID (11) of the order sent as `name` by the last scenario
+    pub fn cl_ord_id(&self, name: &str) -> Option<&str> {
+        self.orders.get(name).map(String::as_str)
+    }
+
+    /// Run every step of `scenario` until one fails
+    ///
+    /// A scenario referring to orders it does not send is refused before
+    /// any step runs; a failing step is reported, not returned as an error.
+    pub async fn run(&mut self, scenario: &Scenario) -> Result<ScenarioReport> {
+        scenario.validate()?;
+        info!("Running scenario {}", scenario.name);
+        self.orders.clear();
+
+        let mut report = ScenarioReport {
+            name: scenario.name.clone(),
+            steps: Vec::with_capacity(scenario.steps.len()),
+            skipped: 0,
+        };
+        for (index, step) in scenario.steps.iter().enumerate() {
+            let description = step.describe();
+            debug!("Step {}: {}", index + 1, description);
+            let started = Instant::now();
+            let result = self.run_step(step).await;
+            let (outcome, matched) = match result {
+                Ok(matched) => (StepOutcome::Passed, matched),
+                Err(e) => {
+                    warn!("Step {} ({}) failed: {}", index + 1, description, e);
+                    (StepOutcome::Failed(e.to_string()), None)
+                }
+            };
+            let failed = outcome != StepOutcome::Passed;
+            report.steps.push(StepReport {
+                index: index + 1,
+                description,
+                elapsed: started.elapsed(),
+                outcome,
+                matched,
+            });
+            if failed {
+                report.skipped = scenario.steps.len() - index - 1;
+                break;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Run one step, returning the message an expectation matched
+    async fn run_step(&mut self, step: &ScenarioStep) -> Result<Option<FixMessage>> {
+        match step {
+            ScenarioStep::Logon => {
+                if !self.client.is_connected() {
+                    self.client.connect().await?;
+                }
+                self.wait_for_logon().await?;
+            }
+            ScenarioStep::Logout => {
+                if !self.client.logout(None).await? {
+                    return Err(DeribitFixError::Session(
+                        "Logout was not acknowledged".to_string(),
+                    ));
+                }
+            }
+            ScenarioStep::Subscribe { symbol } => {
+                self.client.subscribe_market_data(symbol.clone()).await?;
+            }
+            ScenarioStep::Unsubscribe { symbol } => {
+                self.client.unsubscribe_symbol(symbol).await?;
+            }
+            ScenarioStep::SendOrder { name, order } => {
+                let cl_ord_id = self.client.send_order(order.clone()).await?;
+                self.orders.insert(name.clone(), cl_ord_id);
+            }
+            ScenarioStep::Cancel { order } => {
+                let cl_ord_id = self.order(order)?.to_string();
+                self.client.cancel(CancelTarget::OrderId(cl_ord_id)).await?;
+            }
+            ScenarioStep::Expect(expected) => return self.expect(expected).await.map(Some),
+            ScenarioStep::Wait { millis } => {
+                tokio::time::sleep(Duration::from_millis(*millis)).await;
+            }
+        }
+        Ok(None)
+    }
+
+    /// ClOrdID (11) of the order sent as `name`
+    fn order(&self, name: &str) -> Result<&str> {
+        self.cl_ord_id(name)
+            .ok_or_else(|| DeribitFixError::Config(format!("Order {name} was not sent")))
+    }
+
+    /// Process messages until the session is logged on
+    async fn wait_for_logon(&self) -> Result<()> {
+        let deadline = Instant::now() + self.step_timeout;
+        loop {
+            if self
+                .client
+                .get_session_state()
+                .await
+                .is_some_and(|state| state.is_logged_on())
+            {
+                return Ok(());
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(DeribitFixError::Timeout(format!(
+                    "Not logged on after {:?}",
+                    self.step_timeout
+                )));
+            }
+            // Reading the messages lets the session process the logon
+            let _ = tokio::time::timeout(remaining, self.client.receive_message()).await;
+        }
+    }
+
+    /// Read messages until one matches `expected`
+    async fn expect(&self, expected: &ExpectedMessage) -> Result<FixMessage> {
+        let timeout = expected
+            .timeout_ms
+            .map_or(self.step_timeout, Duration::from_millis);
+        let cl_ord_id = expected
+            .order
+            .as_deref()
+            .map(|name| self.order(name))
+            .transpose()?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(DeribitFixError::Timeout(format!(
+                    "No message matching {expected} within {timeout:?}"
+                )));
+            }
+            let Ok(received) = tokio::time::timeout(remaining, self.client.receive_message()).await
+            else {
+                continue;
+            };
+            let Some(message) = received? else {
+                continue;
+            };
+            if !expected.matches(&message) {
+                debug!("Skipping message not matching {}", expected);
+                continue;
+            }
+            match cl_ord_id {
+                Some(cl_ord_id) if !self.refers_to_order(&message, cl_ord_id).await? => {
+                    debug!("Skipping message of another order than {}", cl_ord_id);
+                }
+                _ => return Ok(message),
+            }
+        }
+    }
+
+    /// Whether `message` refers to the order sent with `cl_ord_id`
+    ///
+    /// Replacements and cancels change the ClOrdID (11) of an order, so
+    /// every identifier the order tracker knows for the order is accepted.
+    async fn refers_to_order(&self, message: &FixMessage, cl_ord_id: &str) -> Result<bool> {
+        let mut ids = vec![cl_ord_id.to_string()];
+        if let Some(lifecycle) = self.client.order_lifecycle(cl_ord_id).await? {
+            ids.extend(lifecycle.order_id);
+            ids.extend(lifecycle.events.into_iter().map(|event| event.cl_ord_id));
+        }
+        Ok([tags::ORDER_ID, tags::CL_ORD_ID, tags::ORIG_CL_ORD_ID]
+            .into_iter()
+            .filter_map(|tag| message.get_field(tag))
+            .any(|id| ids.contains(id)))
+    }
+}
@@ -0,0 +1,80 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Order book integrity recovery of a session
+//!
+//! A book that fails validation, or may have been changed by a message that
+//! could not be parsed, ignores updates until a new snapshot rebuilds it.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::{MarketDataRequest, MdEntryType};
+use crate::model::message::FixMessage;
+use crate::model::order_book::{BookIntegrityEvent, BookIntegrityIssue};
+use crate::model::tags;
+use crate::session::Session;
+use crate::session::events::SessionEvent;
+use tracing::{info, warn};
+
+impl Session {
+    /// Drop a market data message that cannot be parsed
+    ///
+    /// The message may have changed the order book of its instrument, so the
+    /// book is no longer trusted and is rebuilt from a new snapshot.
+    pub(super) async fn drop_unparsable_market_data(
+        &mut self,
+        message: &FixMessage,
+        error: DeribitFixError,
+    ) -> Result<()> {
+        warn!("Dropping unparsable market data: {}", error);
+        if message
+            .get_field(tags::MD_REQ_ID)
+            .is_some_and(|md_req_id| !self.feeds_book(md_req_id))
+        {
+            return Ok(());
+        }
+        let Some(symbol) = message
+            .get_field(tags::SYMBOL)
+            .filter(|symbol| self.order_books.contains_key(*symbol))
+            .cloned()
+        else {
+            return Ok(());
+        };
+        let issue = BookIntegrityIssue::Unparsable {
+            reason: error.to_string(),
+        };
+        self.recover_book(symbol, issue).await
+    }
+
+    /// Mark the order book of `symbol` as inconsistent because of `issue` and
+    /// request a snapshot to rebuild it
+    pub(super) async fn recover_book(
+        &mut self,
+        symbol: String,
+        issue: BookIntegrityIssue,
+    ) -> Result<()> {
+        if let Some(book) = self.order_books.get_mut(&symbol) {
+            book.mark_recovering();
+        }
+        warn!("Order book for {} failed validation: {:?}", symbol, issue);
+        self.emit_event(SessionEvent::BookIntegrity(BookIntegrityEvent::Violation {
+            symbol: symbol.clone(),
+            issue,
+        }));
+
+        let md_req_id = self.request_ids.next("MDR");
+        let request = MarketDataRequest::snapshot(
+            md_req_id.clone(),
+            vec![symbol.clone()],
+            vec![MdEntryType::Bid, MdEntryType::Offer],
+        );
+        self.send(&request).await?;
+        info!("Requested snapshot {} to rebuild {}", md_req_id, symbol);
+        self.emit_event(SessionEvent::BookIntegrity(
+            BookIntegrityEvent::SnapshotRequested { symbol, md_req_id },
+        ));
+        Ok(())
+    }
+}
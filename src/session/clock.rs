@@ -0,0 +1,194 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Injectable clock and timers
+//!
+//! Session timing (heartbeats, the latency watchdog, response timeouts,
+//! reconnect delays and the logon timestamp) reads the time and sleeps
+//! through the [`Clock`] of the configuration, see
+//! [`DeribitFixConfig::with_clock`](crate::config::DeribitFixConfig::with_clock).
+//! [`SystemClock`] follows the wall clock and tokio timers; [`ManualClock`]
+//! only moves when [`advance`](ManualClock::advance) is called, so tests can
+//! step through heartbeat and timeout logic without waiting.
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+/// Future completing when a [`Clock`] timer fires
+pub type Timer = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Source of the current time and of timers
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Current monotonic time
+    fn now(&self) -> Instant;
+
+    /// Current wall-clock time
+    fn utc_now(&self) -> DateTime<Utc>;
+
+    /// Timer firing at `deadline`
+    fn sleep_until(&self, deadline: Instant) -> Timer;
+
+    /// Timer firing after `duration`
+    fn sleep(&self, duration: Duration) -> Timer {
+        self.sleep_until(self.now() + duration)
+    }
+
+    /// Time elapsed since `earlier`, zero if `earlier` is in the future
+    fn elapsed(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// The default clock of a configuration
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+/// Clock following the system time and tokio timers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Timer {
+        Box::pin(tokio::time::sleep_until(deadline))
+    }
+}
+
+/// Virtual clock that only moves when advanced
+///
+/// Timers fire when the clock is advanced past their deadline. The
+/// monotonic time starts at the tokio time of creation, so deadlines
+/// computed from [`Instant::now`] outside the clock are roughly comparable.
+#[derive(Debug)]
+pub struct ManualClock {
+    state: Mutex<ManualState>,
+}
+
+#[derive(Debug)]
+struct ManualState {
+    now: Instant,
+    utc_now: DateTime<Utc>,
+    timers: Vec<(Instant, oneshot::Sender<()>)>,
+}
+
+impl ManualClock {
+    /// Create a clock stopped at wall-clock time `utc_now`
+    pub fn new(utc_now: DateTime<Utc>) -> Self {
+        Self {
+            state: Mutex::new(ManualState {
+                now: Instant::now(),
+                utc_now,
+                timers: Vec::new(),
+            }),
+        }
+    }
+
+    /// Move the clock forward by `duration`, firing the timers that are due
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.now += duration;
+        state.utc_now += duration;
+        let now = state.now;
+        let (due, pending) = std::mem::take(&mut state.timers)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+        state.timers = pending;
+        for (_, sender) in due {
+            let _ = sender.send(());
+        }
+    }
+
+    /// Number of timers that have not fired
+    pub fn pending_timers(&self) -> usize {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.timers.retain(|(_, sender)| !sender.is_closed());
+        state.timers.len()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .now
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .utc_now
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Timer {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if deadline <= state.now {
+            return Box::pin(std::future::ready(()));
+        }
+        let (sender, receiver) = oneshot::channel();
+        state.timers.push((deadline, sender));
+        Box::pin(async move {
+            let _ = receiver.await;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::{Context, Waker};
+
+    fn fired(timer: &mut Timer) -> bool {
+        timer
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+            .is_ready()
+    }
+
+    #[test]
+    fn test_manual_clock_fires_due_timers() {
+        let start = Utc::now();
+        let clock = ManualClock::new(start);
+        let began = clock.now();
+        let mut short = clock.sleep(Duration::from_secs(1));
+        let mut long = clock.sleep(Duration::from_secs(30));
+        assert!(fired(&mut clock.sleep(Duration::ZERO)));
+        assert_eq!(clock.pending_timers(), 2);
+
+        clock.advance(Duration::from_millis(999));
+        assert!(!fired(&mut short));
+        clock.advance(Duration::from_millis(1));
+        assert!(fired(&mut short));
+        assert!(!fired(&mut long));
+        assert_eq!(clock.pending_timers(), 1);
+
+        clock.advance(Duration::from_secs(60));
+        assert!(fired(&mut long));
+        assert_eq!(clock.elapsed(began), Duration::from_secs(61));
+        assert_eq!(clock.utc_now() - start, chrono::TimeDelta::seconds(61));
+    }
+}
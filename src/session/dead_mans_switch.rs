@@ -0,0 +1,88 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Dead man's switch
+//!
+//! Cancel-on-disconnect only protects against a lost connection. A strategy
+//! that hangs while the client keeps the session alive leaves its orders in
+//! the market. With
+//! [`dead_mans_switch`](crate::config::DeribitFixConfig::dead_mans_switch)
+//! set, the application must call
+//! [`keep_alive`](crate::client::DeribitFixClient::keep_alive) at least once
+//! per window; when it does not, the session cancels every open order with an
+//! Order Mass Cancel Request (q). The switch fires once and is armed again by
+//! the next keep-alive.
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Keep-alive deadline of the application
+#[derive(Debug, Clone)]
+pub(crate) struct DeadMansSwitch {
+    /// Time allowed between keep-alives
+    window: Duration,
+    /// Time of the last keep-alive, or of the logon
+    last_keep_alive: Instant,
+    /// Whether the switch fired since the last keep-alive
+    fired: bool,
+}
+
+impl DeadMansSwitch {
+    /// Switch firing when no keep-alive follows `now` within `window`
+    pub(crate) fn new(window: Duration, now: Instant) -> Self {
+        Self {
+            window,
+            last_keep_alive: now,
+            fired: false,
+        }
+    }
+
+    /// Time allowed between keep-alives
+    pub(crate) fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Record a keep-alive at `now`, arming the switch again
+    pub(crate) fn keep_alive(&mut self, now: Instant) {
+        self.last_keep_alive = now;
+        self.fired = false;
+    }
+
+    /// Fire if the window passed at `now` without a keep-alive
+    ///
+    /// Returns the time since the last keep-alive when the switch fires.
+    pub(crate) fn check(&mut self, now: Instant) -> Option<Duration> {
+        let silent_for = now.saturating_duration_since(self.last_keep_alive);
+        if self.fired || silent_for < self.window {
+            return None;
+        }
+        self.fired = true;
+        Some(silent_for)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn test_switch_fires_once_per_missed_window() {
+        let start = Instant::now();
+        let mut switch = DeadMansSwitch::new(WINDOW, start);
+        assert_eq!(switch.check(start + Duration::from_secs(9)), None);
+
+        switch.keep_alive(start + Duration::from_secs(9));
+        assert_eq!(switch.check(start + Duration::from_secs(18)), None);
+        let late = start + Duration::from_secs(20);
+        assert_eq!(switch.check(late), Some(Duration::from_secs(11)));
+        assert_eq!(switch.check(late + WINDOW), None);
+
+        switch.keep_alive(late);
+        assert_eq!(switch.check(late + WINDOW), Some(WINDOW));
+    }
+}
@@ -0,0 +1,91 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Duplicate detection of a session
+//!
+//! Possible duplicates already processed and Execution Reports (8) replayed
+//! after a resend are dropped and published as session events instead of
+//! being delivered a second time.
+
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use crate::session::Session;
+use crate::session::events::SessionEvent;
+use tracing::warn;
+
+impl Session {
+    /// Check whether a message is a possible duplicate of one already processed
+    ///
+    /// Only messages flagged with PossDupFlag (43=Y) and carrying a sequence
+    /// number below the next expected one are treated as duplicates. A flagged
+    /// message that has not been seen before is processed normally.
+    pub(super) fn is_duplicate(&self, message: &FixMessage) -> bool {
+        let poss_dup = message
+            .get_field(tags::POSS_DUP_FLAG)
+            .is_some_and(|flag| flag == "Y");
+        poss_dup
+            && message
+                .msg_seq_num()
+                .is_some_and(|seq_num| seq_num < self.incoming_seq_num)
+    }
+
+    /// Log and publish a dropped duplicate message
+    pub(super) fn report_duplicate(&self, message: &FixMessage) {
+        let msg_seq_num = message.msg_seq_num().unwrap_or_default();
+        let msg_type = message
+            .get_field(tags::MSG_TYPE)
+            .cloned()
+            .unwrap_or_default();
+        let poss_resend = message
+            .get_field(tags::POSS_RESEND)
+            .is_some_and(|flag| flag == "Y");
+
+        warn!(
+            "Dropping duplicate message {} (MsgType {}), next expected {}",
+            msg_seq_num, msg_type, self.incoming_seq_num
+        );
+        self.emit_event(SessionEvent::DuplicateMessage {
+            msg_seq_num,
+            msg_type,
+            poss_resend,
+        });
+    }
+
+    /// Check whether a message replays an Execution Report (8) already processed
+    ///
+    /// After a resend or gap fill, Deribit may send again reports that were
+    /// already received under other sequence numbers. Reports flagged with
+    /// PossDupFlag (43=Y) or PossResend (97=Y) whose ExecID (17) is known to
+    /// the order tracker are not delivered a second time.
+    pub(super) fn is_replayed_execution(&self, message: &FixMessage) -> bool {
+        message.msg_type() == Some(MsgType::ExecutionReport)
+            && [tags::POSS_DUP_FLAG, tags::POSS_RESEND]
+                .into_iter()
+                .any(|tag| message.get_field(tag).is_some_and(|flag| flag == "Y"))
+            && message
+                .get_field(tags::EXEC_ID)
+                .is_some_and(|exec_id| self.order_tracker.has_execution(exec_id))
+    }
+
+    /// Log and publish a dropped replayed Execution Report
+    pub(super) fn report_replayed_execution(&self, message: &FixMessage) {
+        let msg_seq_num = message.msg_seq_num().unwrap_or_default();
+        let exec_id = message
+            .get_field(tags::EXEC_ID)
+            .cloned()
+            .unwrap_or_default();
+        warn!(
+            "Dropping replayed Execution Report {} (ExecID {})",
+            msg_seq_num, exec_id
+        );
+        self.emit_event(SessionEvent::DuplicateExecution {
+            msg_seq_num,
+            exec_id,
+            cl_ord_id: message.get_field(tags::CL_ORD_ID).cloned(),
+        });
+    }
+}
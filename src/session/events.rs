@@ -0,0 +1,171 @@
+//! Session event notifications
+//!
+//! Events are published by the [`Session`](crate::session::Session) on a broadcast
+//! channel so that applications can observe session-level changes (such as
+//! sequence number resets) without polling session state.
+
+use crate::message::admin::LogoutReason;
+use crate::message::security_list::SecurityListProgress;
+use crate::model::market_state::MarketStateEvent;
+use crate::model::order_book::BookIntegrityEvent;
+use crate::model::order_group::OrderGroupEvent;
+use crate::model::order_tracker::IcebergRefill;
+use crate::model::throttle::RateLimitReject;
+use crate::model::top_of_book::TopOfBook;
+use crate::session::sequence::SequenceAnomaly;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Capacity of the session event broadcast channel
+pub(crate) const SESSION_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Capacity of the received message broadcast channel
+pub const SESSION_MESSAGE_CHANNEL_CAPACITY: usize = 4096;
+
+/// Connection health as measured by Test Request round trips
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConnectionHealth {
+    /// The last round trip was within `max_ping_latency`, or none was measured yet
+    #[default]
+    Healthy,
+    /// The last round trip, or an unanswered Test Request, exceeded `max_ping_latency`
+    Degraded,
+}
+
+/// Cancel-on-disconnect setting of a logged-on session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancelOnDisconnectStatus {
+    /// CancelOnDisconnect (9001) sent on the Logon
+    pub requested: bool,
+    /// CancelOnDisconnect (9001) echoed by the server's Logon, if any
+    pub confirmed: Option<bool>,
+}
+
+impl CancelOnDisconnectStatus {
+    /// Whether the server cancels the orders of the session when it
+    /// disconnects, as confirmed by the server or else as requested
+    pub fn is_active(&self) -> bool {
+        self.confirmed.unwrap_or(self.requested)
+    }
+}
+
+/// Session-level event emitted by the FIX session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum SessionEvent {
+    /// Both sequence numbers were reset to 1 because Logon was sent with ResetSeqNumFlag (141=Y)
+    SequenceNumbersReset,
+    /// A Sequence Reset (4) message was applied to the expected incoming sequence number
+    SequenceReset {
+        /// Expected incoming sequence number before the reset
+        previous_seq_num: u32,
+        /// NewSeqNo (36) - next expected incoming sequence number
+        new_seq_no: u32,
+        /// Whether the message was a GapFill (123=Y) rather than a hard Reset
+        gap_fill: bool,
+    },
+    /// A possible duplicate (43=Y) of an already processed message was received and dropped
+    DuplicateMessage {
+        /// MsgSeqNum (34) of the duplicate
+        msg_seq_num: u32,
+        /// MsgType (35) of the duplicate
+        msg_type: String,
+        /// Whether PossResend (97=Y) was also set
+        poss_resend: bool,
+    },
+    /// A resent Execution Report (8) whose ExecID (17) was already processed was dropped
+    DuplicateExecution {
+        /// MsgSeqNum (34) of the resent report
+        msg_seq_num: u32,
+        /// ExecID (17) of the report
+        exec_id: String,
+        /// ClOrdID (11) of the report
+        cl_ord_id: Option<String>,
+    },
+    /// Order book integrity violation or recovery progress
+    BookIntegrity(BookIntegrityEvent),
+    /// Instrument trading state or exchange maintenance change
+    MarketState(MarketStateEvent),
+    /// OCO order group progress
+    OrderGroup(OrderGroupEvent),
+    /// The displayed quantity of an iceberg order was filled and shown again
+    IcebergRefilled(IcebergRefill),
+    /// The counterparty ended the session with a Logout (5)
+    LoggedOut {
+        /// Classified reason of the logout
+        reason: LogoutReason,
+        /// Text (58) of the Logout, if any
+        text: Option<String>,
+        /// Whether the session will reconnect and log on again
+        relogon: bool,
+    },
+    /// Reconnecting and sending Logon again after a non-fatal logout
+    RelogonAttempt {
+        /// Attempt number, starting at 1
+        attempt: u32,
+    },
+    /// A logon the server rejected for a transient reason will be retried
+    LogonRetryScheduled {
+        /// Retry number, starting at 1
+        attempt: u32,
+        /// Wait before the retry
+        delay: Duration,
+    },
+    /// The logon was rejected after every retry and the session stays
+    /// disconnected
+    LogonRetriesExhausted {
+        /// Retries made
+        attempts: u32,
+    },
+    /// The server rejected the credentials; no Logon is sent until the
+    /// circuit breaker is reset
+    LogonCircuitOpen {
+        /// Text (58) of the Logout, if any
+        text: Option<String>,
+    },
+    /// The session logged on again after a Logout for exchange maintenance
+    ServiceResumed {
+        /// Logon attempts made during the maintenance
+        attempts: u32,
+        /// Time since the maintenance Logout
+        downtime: Duration,
+    },
+    /// The best bid or offer of a top-of-book subscription changed
+    TopOfBook(TopOfBook),
+    /// The connection health changed
+    ConnectionHealth {
+        /// New health of the connection
+        health: ConnectionHealth,
+        /// Round-trip time, or time waited so far for an unanswered Test Request
+        round_trip: Duration,
+    },
+    /// A message broke the strict sequence checks
+    SequenceAnomaly(SequenceAnomaly),
+    /// The counterparty was silent too long and was sent a Test Request
+    LivenessProbe {
+        /// Time since the last message was received
+        silent_for: Duration,
+    },
+    /// The application missed its keep-alive and every open order is being
+    /// cancelled
+    DeadMansSwitchTriggered {
+        /// Time since the last keep-alive
+        silent_for: Duration,
+    },
+    /// The liveness probe went unanswered and the session is reconnecting
+    LivenessLost {
+        /// Time since the last message was received
+        silent_for: Duration,
+    },
+    /// A fragment of a Security List (y) response was received
+    SecurityListProgress(SecurityListProgress),
+    /// A logon enabled or disabled cancel-on-disconnect
+    CancelOnDisconnect(CancelOnDisconnectStatus),
+    /// The exchange rejected a message for exceeding its rate limit
+    RateLimited {
+        /// The rejection
+        reject: RateLimitReject,
+        /// Messages per second now allowed, if the rejection lowered the rate
+        allowed_rate: Option<f64>,
+    },
+}
@@ -1,57 +1,289 @@
 //! FIX session management
 
-use crate::config::gen_id;
 use crate::model::message::FixMessage;
+use crate::model::number_format::format_numbers;
 use crate::model::position::Position;
 use crate::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
-use crate::model::types::MsgType;
+use crate::model::symbol_map::translate_symbols;
+use crate::model::tags;
+use crate::model::types::{ExecType, MsgType};
+use crate::session::dead_mans_switch::DeadMansSwitch;
+use crate::session::events::{
+    CancelOnDisconnectStatus, ConnectionHealth, SESSION_EVENT_CHANNEL_CAPACITY,
+    SESSION_MESSAGE_CHANNEL_CAPACITY, SessionEvent,
+};
+use crate::session::liveness::{LivenessAction, LivenessMonitor};
+use crate::session::logon_retry::{LogonRetry, LogonRetryAction};
+use crate::session::options::RequestOptions;
+use crate::session::request_ids::RequestIdGenerator;
+use crate::session::sequence::{self, SequenceAnomaly, Violation};
+use crate::session::state::{SessionState, is_admin};
 use crate::{
     config::DeribitFixConfig,
-    connection::Connection,
+    connection::{Connection, ConnectionStats, WriteStats},
     error::{DeribitFixError, Result},
-    message::{MessageBuilder, PositionReport, RequestForPositions},
+    message::{
+        ExecutionReport, FixPrettyPrinter, MarketDataIncrementalRefresh, MarketDataRequest,
+        MarketDataRequestReject, MarketDataSnapshotFullRefresh, MassQuote,
+        MassQuoteAcknowledgement, MdEntryType, MessageBuilder, OrderCancelReject,
+        OrderCancelReplaceRequest, OrderCancelRequest, OrderMassCancelReport,
+        OrderMassCancelRequest, OrderMassStatusRequest, OrderSide as FixOrderSide, OrderStatus,
+        PositionReport, QuoteCancel, QuoteRequestReject, Reject, RequestForPositions,
+        ResendRequest, SecurityInfo, SecurityList, SecurityListAssembler, SecurityListProgress,
+        SecurityListRequest, SequenceReset, TestRequest, ToFixMessage, TradeCaptureReport,
+        TradeCaptureReportRequest, TradeCaptureReportRequestAck, UserRequest, UserResponse,
+        UserStatus, admin::LogoutReason, admin::reject_error_of, time::TimestampPrecision,
+        time::format_utc_timestamp_with, time::parse_utc_timestamp,
+        trade::SubscriptionRequestType as TradeSubscriptionRequestType,
+    },
+    model::account::AccountSummary,
+    model::cancel::{CancelReport, CancelTarget},
+    model::capabilities::ServerCapabilities,
+    model::combo::{ComboOrderRequest, ComboRegistry},
+    model::funding::FundingTracker,
+    model::index_stream::IndexStreams,
+    model::instrument_registry::InstrumentRegistry,
+    model::label_routing::{LabelExecutions, LabelRouter},
+    model::latency::{LatencyStats, LatencyTracer},
+    model::market_state::{MarketStateTracker, is_maintenance_text},
+    model::market_stats::{MarketStats, MarketStatsTracker},
+    model::option_ticker::OptionTickerStreams,
+    model::order_book::{BookIntegrityEvent, OrderBook},
+    model::order_group::{GroupMember, OrderGroup, OrderGroupEvent, OrderGroupManager},
+    model::order_index::{OrderIndex, OrderReconciliation},
+    model::order_tracker::{AuditFormat, OrderTracker},
+    model::paper_trading::PaperTradingEngine,
+    model::public_trade::PublicTrade,
+    model::risk::RiskGuard,
+    model::subscription::{MarketDataSubscription, MarketDataSubscriptions, book_entry_types},
+    model::throttle::{AdaptiveRateLimiter, RateLimitReject, ThrottleStats, rate_limit_reject},
+    model::top_of_book::TopOfBook,
+    model::trade_stream::{TradeStream, TradeStreams},
+    recorder::MarketDataRecorder,
 };
 use base64::prelude::*;
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
 use rand;
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{debug, error, info, trace};
-
-/// FIX session state
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SessionState {
-    /// Session is disconnected
-    Disconnected,
-    /// Logon message sent, waiting for response
-    LogonSent,
-    /// Session is logged on and active
-    LoggedOn,
-    /// Logout message sent, waiting for confirmation
-    LogoutSent,
+use std::sync::atomic::{AtomicI64, Ordering};
+use tokio::sync::{Mutex, broadcast};
+use tracing::{debug, error, info, trace, warn};
+
+/// Longest a wait for a response goes without checking its cancel token
+const CANCEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Pause before reading again after a read returned no message
+const READ_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// Pause before reading again after a read failed
+const READ_ERROR_BACKOFF: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Longest a position request waits for its reports
+const POSITION_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Time after the request within which the position reports are collected,
+/// once the first one has arrived
+const POSITION_COLLECTION_TIME: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Most trades Deribit returns for a single trade history request
+const MAX_TRADE_HISTORY: u32 = 1000;
+
+/// Clock offset from the server beyond which a warning is logged
+const CLOCK_DRIFT_WARNING: TimeDelta = TimeDelta::seconds(1);
+
+/// Logon retries of a session logged out for exchange maintenance
+#[derive(Debug, Clone, Copy)]
+struct MaintenanceRetry {
+    /// When the maintenance Logout was received
+    started: tokio::time::Instant,
+    /// When the next logon attempt is due
+    next_attempt: tokio::time::Instant,
+    /// Logon attempts made so far
+    attempts: u32,
+}
+
+/// What a session waiting for messages has to process, see
+/// [`Session::wait_incoming`]
+#[derive(Debug)]
+pub(crate) enum Incoming {
+    /// A simulated report is waiting
+    Simulated,
+    /// The wait for the next logon attempt after exchange maintenance ended
+    Maintenance,
+    /// A read from the connection ended
+    Read {
+        received: Result<Option<FixMessage>>,
+        /// Time of the read that brought the first byte of the message
+        read_at: Option<tokio::time::Instant>,
+        /// Time of the last write before it
+        last_write_at: Option<tokio::time::Instant>,
+    },
 }
 
 /// FIX session manager
 pub struct Session {
-    config: DeribitFixConfig,
+    pub(super) config: DeribitFixConfig,
     connection: Option<Arc<Mutex<Connection>>>,
-    state: SessionState,
+    pub(super) state: SessionState,
     outgoing_seq_num: u32,
-    incoming_seq_num: u32,
+    pub(super) incoming_seq_num: u32,
+    events: broadcast::Sender<SessionEvent>,
+    /// Received application and session messages, for subscribers
+    messages: broadcast::Sender<FixMessage>,
+    /// Generator of the IDs of the requests sent on the session
+    pub(super) request_ids: Arc<RequestIdGenerator>,
+    pub(super) order_books: HashMap<String, OrderBook>,
+    pub(super) market_state: MarketStateTracker,
+    market_stats: MarketStatsTracker,
+    pub(super) funding: FundingTracker,
+    order_groups: OrderGroupManager,
+    pub(super) paper_trading: Option<PaperTradingEngine>,
+    pub(super) simulated: VecDeque<FixMessage>,
+    recorder: Option<MarketDataRecorder>,
+    pub(super) printer: FixPrettyPrinter,
+    /// Outstanding Test Requests by TestReqID (112) and the time they were sent
+    pending_pings: HashMap<String, tokio::time::Instant>,
+    last_round_trip: Option<std::time::Duration>,
+    health: ConnectionHealth,
+    risk_guard: RiskGuard,
+    combos: ComboRegistry,
+    instruments: InstrumentRegistry,
+    md_subscriptions: MarketDataSubscriptions,
+    /// MDReqIDs (262) of pending one-shot snapshot requests, trade history
+    /// and order book snapshots, whose snapshots must not replace the order
+    /// book
+    one_shot_requests: HashSet<String>,
+    /// Deadline and cancellation of the responses awaited by requests
+    request_options: RequestOptions,
+    /// EndSeqNo (16) of the Resend Request in progress, 0 for every message
+    resend_end_seq_no: Option<u32>,
+    /// Best bid and offer of the top-of-book subscriptions by symbol
+    top_of_books: HashMap<String, TopOfBook>,
+    /// Server clock minus local clock, estimated from SendingTime (52)
+    clock_offset: TimeDelta,
+    /// Timestamp of the last logon RawData (96), kept strictly increasing
+    last_auth_timestamp: AtomicI64,
+    /// Per-label channels of the Execution Report stream
+    label_router: LabelRouter,
+    /// Lifecycle of every order of the session
+    pub(super) order_tracker: OrderTracker,
+    /// Owners of the working orders, kept across restarts
+    order_index: OrderIndex,
+    /// Index value and settlement price channels by symbol
+    pub(super) index_streams: IndexStreams,
+    /// Option ticker channels by symbol
+    pub(super) option_tickers: OptionTickerStreams,
+    /// Own trade report channels by TradeRequestID (568)
+    trade_streams: TradeStreams,
+    /// Most instrument fragments a stream receiver fell behind by
+    instrument_backlog_high_water: usize,
+    /// Instrument fragments dropped because a receiver fell behind
+    dropped_instrument_batches: u64,
+    /// Logon retries while the exchange is in maintenance
+    maintenance_retry: Option<MaintenanceRetry>,
+    /// Order-entry stage timestamps, when latency tracing is enabled
+    latency: Option<LatencyTracer>,
+    /// Pacing of outgoing application messages
+    throttle: AdaptiveRateLimiter,
+    /// When the client issued the command running on the session
+    command_issued_at: Option<tokio::time::Instant>,
+    /// Silence tracker of the incoming traffic, when liveness checks are enabled
+    liveness: Option<LivenessMonitor>,
+    /// Keep-alive deadline of the application, when the switch is enabled
+    dead_mans_switch: Option<DeadMansSwitch>,
+    /// Retries and circuit breaker of logons rejected by the server
+    logon_retry: LogonRetry,
+    /// What the server was seen to accept and send since the last logon
+    capabilities: ServerCapabilities,
+    /// TestReqID (112) of the capability probe awaiting its Heartbeat
+    capability_probe: Option<String>,
+    /// Cancel-on-disconnect setting of the last logon
+    cancel_on_disconnect: Option<CancelOnDisconnectStatus>,
 }
 
 impl Session {
     /// Create a new FIX session
     pub fn new(config: &DeribitFixConfig, connection: Arc<Mutex<Connection>>) -> Result<Self> {
         info!("Creating new FIX session");
+        let (events, _) = broadcast::channel(SESSION_EVENT_CHANNEL_CAPACITY);
+        let (messages, _) = broadcast::channel(SESSION_MESSAGE_CHANNEL_CAPACITY);
+        let order_index = match &config.order_index_store {
+            Some(store) => OrderIndex::with_store(store.clone())?,
+            None => OrderIndex::new(),
+        };
         Ok(Self {
             config: config.clone(),
             state: SessionState::Disconnected,
             outgoing_seq_num: 1,
             incoming_seq_num: 1,
             connection: Some(connection),
+            events,
+            messages,
+            request_ids: Arc::new(RequestIdGenerator::new()),
+            order_books: HashMap::new(),
+            market_state: MarketStateTracker::new(),
+            market_stats: MarketStatsTracker::default(),
+            funding: FundingTracker::new(config.funding_poll_interval),
+            order_groups: OrderGroupManager::new(),
+            paper_trading: config.paper_trading.then(|| {
+                PaperTradingEngine::new(
+                    config.target_comp_id.clone(),
+                    config.sender_comp_id.clone(),
+                )
+            }),
+            simulated: VecDeque::new(),
+            recorder: config
+                .market_data_recording_path
+                .as_ref()
+                .map(MarketDataRecorder::create)
+                .transpose()?,
+            printer: FixPrettyPrinter::from_config(config),
+            pending_pings: HashMap::new(),
+            last_round_trip: None,
+            health: ConnectionHealth::Healthy,
+            risk_guard: RiskGuard::new(config.risk_limits.clone()),
+            combos: ComboRegistry::new(),
+            instruments: InstrumentRegistry::new(),
+            md_subscriptions: MarketDataSubscriptions::new(),
+            one_shot_requests: HashSet::new(),
+            request_options: RequestOptions::default(),
+            resend_end_seq_no: None,
+            top_of_books: HashMap::new(),
+            clock_offset: TimeDelta::zero(),
+            last_auth_timestamp: AtomicI64::new(0),
+            label_router: LabelRouter::with_backlog(config.max_stream_backlog),
+            order_tracker: OrderTracker::new(),
+            order_index,
+            index_streams: IndexStreams::with_backlog(config.max_market_data_backlog),
+            option_tickers: OptionTickerStreams::with_backlog(config.max_market_data_backlog),
+            trade_streams: TradeStreams::with_backlog(config.max_stream_backlog),
+            instrument_backlog_high_water: 0,
+            dropped_instrument_batches: 0,
+            maintenance_retry: None,
+            latency: config.latency_tracing.then(LatencyTracer::new),
+            throttle: AdaptiveRateLimiter::new(config.max_message_rate, config.adaptive_throttling),
+            command_issued_at: None,
+            dead_mans_switch: config
+                .dead_mans_switch
+                .map(|window| DeadMansSwitch::new(window, config.clock.now())),
+            logon_retry: LogonRetry::new(
+                config.logon_retry_attempts,
+                config.logon_retry_delay,
+                config.logon_retry_max_delay,
+            ),
+            capabilities: ServerCapabilities::new(),
+            capability_probe: None,
+            cancel_on_disconnect: None,
+            liveness: config.liveness_missed_heartbeats.map(|missed_heartbeats| {
+                LivenessMonitor::new(
+                    missed_heartbeats,
+                    config.liveness_probe_timeout,
+                    config.clock.now(),
+                )
+            }),
         })
     }
 
@@ -65,12 +297,235 @@ impl Session {
         self.state
     }
 
+    /// Generator of the request IDs of the session, to build ClOrdIDs (11)
+    /// and other IDs that must not collide with the session's own
+    pub fn request_ids(&self) -> Arc<RequestIdGenerator> {
+        self.request_ids.clone()
+    }
+
+    /// Subscribe to session events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Subscribe to the messages received by the session
+    ///
+    /// Every message returned by
+    /// [`receive_and_process_message`](Self::receive_and_process_message) is
+    /// also published here, including those read while a request waits for
+    /// its response. A receiver falling more than
+    /// [`SESSION_MESSAGE_CHANNEL_CAPACITY`] messages behind skips the oldest.
+    pub fn subscribe_messages(&self) -> broadcast::Receiver<FixMessage> {
+        self.messages.subscribe()
+    }
+
+    /// Sender of the received messages, to subscribe once the session is moved
+    pub(crate) fn message_sender(&self) -> broadcast::Sender<FixMessage> {
+        self.messages.clone()
+    }
+
+    /// Client calls that may wait for the task owning the session
+    pub(crate) fn max_pending_commands(&self) -> usize {
+        self.config.max_pending_commands
+    }
+
+    /// Get the next outgoing message sequence number
+    pub fn outgoing_seq_num(&self) -> u32 {
+        self.outgoing_seq_num
+    }
+
+    /// Get the next expected incoming message sequence number
+    pub fn incoming_seq_num(&self) -> u32 {
+        self.incoming_seq_num
+    }
+
+    /// Get the local order book for a symbol, if market data has been received for it
+    pub fn order_book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.order_books.get(symbol)
+    }
+
+    /// Get the market data statistics for a symbol, if market data has been received for it
+    pub fn market_stats(&self, symbol: &str) -> Option<&MarketStats> {
+        self.market_stats.stats(symbol)
+    }
+
+    /// Get the connection health measured by Test Request round trips
+    pub fn connection_health(&self) -> ConnectionHealth {
+        self.health
+    }
+
+    /// Get the cancel-on-disconnect setting of the session, while logged on
+    pub fn cancel_on_disconnect(&self) -> Option<CancelOnDisconnectStatus> {
+        self.cancel_on_disconnect
+            .filter(|_| self.state.is_logged_on())
+    }
+
+    /// Get the round-trip time of the last answered Test Request
+    pub fn last_round_trip(&self) -> Option<std::time::Duration> {
+        self.last_round_trip
+    }
+
+    /// Get the estimated offset of the server clock from the local clock
+    ///
+    /// Positive when the server clock is ahead. Logon timestamps are shifted
+    /// by it so that clock drift does not get them rejected.
+    pub fn clock_offset(&self) -> TimeDelta {
+        self.clock_offset
+    }
+
+    /// Replace the credentials used by the next logon
+    ///
+    /// The current logon is not affected; call [`relogon`](Self::relogon) to
+    /// authenticate with the new credentials straight away. The logon
+    /// circuit breaker, if open, is closed.
+    pub fn update_credentials(&mut self, username: String, password: String) {
+        info!("Updating credentials for user {}", username);
+        self.config.username = username;
+        self.config.password = password;
+        self.logon_retry.reset();
+    }
+
+    /// Get the pre-trade risk guard and the open orders it tracks
+    pub fn risk_guard(&self) -> &RiskGuard {
+        &self.risk_guard
+    }
+
+    /// Get the combo instruments learned from Security List and Security Definition messages
+    pub fn combos(&self) -> &ComboRegistry {
+        &self.combos
+    }
+
+    /// Get the instrument metadata learned from Security List and Security Definition messages
+    pub fn instruments(&self) -> &InstrumentRegistry {
+        &self.instruments
+    }
+
+    /// Get the active market data subscriptions
+    pub fn market_data_subscriptions(&self) -> &MarketDataSubscriptions {
+        &self.md_subscriptions
+    }
+
+    /// Get instrument trading state and maintenance status
+    pub fn market_state(&self) -> &MarketStateTracker {
+        &self.market_state
+    }
+
+    /// Get the tracked OCO order groups
+    pub fn order_groups(&self) -> &OrderGroupManager {
+        &self.order_groups
+    }
+
+    /// Get the lifecycle of every order sent or reported in the session
+    pub fn order_tracker(&self) -> &OrderTracker {
+        &self.order_tracker
+    }
+
+    /// Get the owners of the working orders, loaded from the
+    /// [`order_index_store`](DeribitFixConfig::order_index_store) when one is
+    /// set
+    pub fn order_index(&self) -> &OrderIndex {
+        &self.order_index
+    }
+
+    /// Match the Execution Reports of the working orders, such as those
+    /// answering an Order Mass Status Request (AF), against the owners in the
+    /// order index
+    pub fn reconcile_orders(&self, reports: &[ExecutionReport]) -> OrderReconciliation {
+        self.order_index.reconcile(reports)
+    }
+
+    /// Forget the tracked orders that are filled, cancelled or rejected
+    pub fn clear_completed_orders(&mut self) {
+        self.order_tracker.clear_completed();
+    }
+
+    /// Write the lifecycle of every order of the session as CSV or JSON
+    pub fn export_order_audit<W: std::io::Write>(
+        &self,
+        format: AuditFormat,
+        writer: W,
+    ) -> Result<()> {
+        self.order_tracker.export(format, writer)
+    }
+
+    /// Receive the Execution Reports of the orders labelled `label`
+    ///
+    /// Orders are labelled with DeribitLabel (100010), see
+    /// [`NewOrderRequest::with_label`]. Reports are delivered as they are
+    /// processed, in addition to being returned by
+    /// [`receive_and_process_message`](Self::receive_and_process_message).
+    /// A receiver falling more than
+    /// [`max_stream_backlog`](crate::config::DeribitFixConfig::max_stream_backlog)
+    /// reports behind learns it lost some with
+    /// [`DeribitFixError::MessagesLost`].
+    pub fn executions_for_label(&mut self, label: &str) -> LabelExecutions {
+        self.label_router.subscribe(label)
+    }
+
+    /// Publish a session event, ignoring the case where nobody is subscribed
+    pub(super) fn emit_event(&self, event: SessionEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Publish the sequence anomalies found in `message`
+    fn report_sequence_anomalies(&self, message: &FixMessage, violations: Vec<Violation>) {
+        for (kind, expected, actual) in violations {
+            let anomaly = SequenceAnomaly {
+                kind,
+                msg_type: message
+                    .get_field(tags::MSG_TYPE)
+                    .cloned()
+                    .unwrap_or_default(),
+                msg_seq_num: message.msg_seq_num(),
+                expected,
+                actual,
+                poss_dup: sequence::is_poss_dup(message),
+                next_incoming_seq_num: self.incoming_seq_num,
+                next_outgoing_seq_num: self.outgoing_seq_num,
+                state: self.state,
+            };
+            warn!(
+                "Sequence anomaly {:?} in MsgType {}: expected {}, found {}",
+                anomaly.kind, anomaly.msg_type, expected, actual
+            );
+            self.emit_event(SessionEvent::SequenceAnomaly(anomaly));
+        }
+    }
+
     /// Send a FIX message through the connection
     async fn send_message(&mut self, message: FixMessage) -> Result<()> {
+        if self.config.strict_session_state
+            && let Some(msg_type) = message.msg_type()
+            && !self.state.allows_outgoing(&msg_type)
+        {
+            return Err(DeribitFixError::Session(format!(
+                "{msg_type:?} cannot be sent while the session is {:?}",
+                self.state
+            )));
+        }
+        if self.config.strict_sequence_checks
+            && let Some(violation) = sequence::check_outgoing(&message, self.outgoing_seq_num)
+        {
+            self.report_sequence_anomalies(&message, vec![violation]);
+        }
+        let message = self.with_optional_header(message)?;
+        let message = self.with_timestamp_precision(message)?;
+        let message = self.with_instrument_precision(message)?;
+        let message = self.with_exchange_symbols(message)?;
+        if message
+            .msg_type()
+            .is_some_and(|msg_type| !is_admin(&msg_type))
+        {
+            let delay = self.throttle.reserve(self.config.clock.now());
+            if !delay.is_zero() {
+                debug!("Holding back message {:?} for the rate limit", delay);
+                self.config.clock.sleep(delay).await;
+            }
+        }
         if let Some(connection) = &self.connection {
             let mut conn_guard = connection.lock().await;
             conn_guard.send_message(&message).await?;
-            debug!("Sent FIX message: {}", message.to_string());
+            debug!("Sent FIX message: {}", self.printer.render(&message));
         } else {
             return Err(DeribitFixError::Connection(
                 "No connection available".to_string(),
@@ -79,9 +534,212 @@ impl Session {
         Ok(())
     }
 
+    /// Add the configured OnBehalfOfCompID (115), SenderSubID (50) and
+    /// TargetSubID (57) to an outgoing message
+    fn with_optional_header(&self, message: FixMessage) -> Result<FixMessage> {
+        let header = [
+            (
+                tags::ON_BEHALF_OF_COMP_ID,
+                &self.config.on_behalf_of_comp_id,
+            ),
+            (tags::SENDER_SUB_ID, &self.config.sender_sub_id),
+            (tags::TARGET_SUB_ID, &self.config.target_sub_id),
+        ];
+        if header
+            .iter()
+            .all(|(tag, value)| value.is_none() || message.get_field(*tag) == value.as_ref())
+        {
+            return Ok(message);
+        }
+        header
+            .into_iter()
+            .filter_map(|(tag, value)| Some((tag, value.clone()?)))
+            .fold(
+                MessageBuilder::from_message(&message),
+                |builder, (tag, value)| builder.field(tag, value),
+            )
+            .build()
+    }
+
+    /// Stamp the SendingTime (52) of an outgoing message with the configured
+    /// [`timestamp_precision`](DeribitFixConfig::timestamp_precision)
+    ///
+    /// Messages are built with millisecond timestamps, which are left as
+    /// they are.
+    fn with_timestamp_precision(&self, message: FixMessage) -> Result<FixMessage> {
+        let precision = self.config.timestamp_precision;
+        if precision == TimestampPrecision::Millis {
+            return Ok(message);
+        }
+        MessageBuilder::from_message(&message)
+            .timestamp_precision(precision)
+            .sending_time(self.config.clock.utc_now())
+            .build()
+    }
+
+    /// Write the prices and quantities of an outgoing message with the
+    /// configured [`number_format`](DeribitFixConfig::number_format) of
+    /// their instruments
+    fn with_instrument_precision(&self, message: FixMessage) -> Result<FixMessage> {
+        let format = self.config.number_format.as_ref();
+        Ok(format_numbers(&message, &self.instruments, format)?.unwrap_or(message))
+    }
+
+    /// Translate the symbols of an outgoing message into Deribit symbols with
+    /// the configured [`symbol_mapper`](DeribitFixConfig::symbol_mapper)
+    fn with_exchange_symbols(&self, message: FixMessage) -> Result<FixMessage> {
+        let Some(mapper) = &self.config.symbol_mapper else {
+            return Ok(message);
+        };
+        Ok(translate_symbols(&message, |symbol| mapper.to_exchange(symbol))?.unwrap_or(message))
+    }
+
+    /// Translate the symbols of a received message back into internal
+    /// identifiers
+    fn with_internal_symbols(&self, message: FixMessage) -> Result<FixMessage> {
+        let Some(mapper) = &self.config.symbol_mapper else {
+            return Ok(message);
+        };
+        Ok(translate_symbols(&message, |symbol| mapper.to_internal(symbol))?.unwrap_or(message))
+    }
+
+    /// Write any messages batched on the connection to the socket
+    pub async fn flush(&mut self) -> Result<()> {
+        match &self.connection {
+            Some(connection) => connection.lock().await.flush().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Get the counters of the messages written to the connection
+    pub async fn write_stats(&self) -> Option<WriteStats> {
+        let connection = self.connection.as_ref()?;
+        Some(connection.lock().await.write_stats())
+    }
+
+    /// Get the traffic counters of the connection with the last measured round trip
+    pub async fn connection_stats(&self) -> Option<ConnectionStats> {
+        let connection = self.connection.as_ref()?;
+        let mut stats = connection.lock().await.connection_stats();
+        stats.round_trip = self.last_round_trip;
+        stats.order_latency = self.latency_stats();
+        stats.buffers.market_data_backlog_high_water = self.index_streams.high_water();
+        stats.buffers.dropped_market_data =
+            self.index_streams.dropped() + self.option_tickers.dropped();
+        stats.buffers.execution_backlog_high_water = self.label_router.high_water();
+        stats.buffers.lost_execution_reports = self.label_router.lost();
+        stats.buffers.stream_backlog_high_water = self
+            .trade_streams
+            .high_water()
+            .max(self.instrument_backlog_high_water);
+        stats.buffers.dropped_stream_messages =
+            self.trade_streams.dropped() + self.dropped_instrument_batches;
+        stats.throttle = self.throttle_stats();
+        stats.cancel_on_disconnect = self.cancel_on_disconnect().map(|status| status.is_active());
+        Some(stats)
+    }
+
+    /// Pacing of outgoing messages: the rate currently allowed and the
+    /// rate-limit rejections that lowered it
+    pub fn throttle_stats(&self) -> ThrottleStats {
+        self.throttle.stats()
+    }
+
+    /// Order-entry latency percentiles, when latency tracing is enabled
+    pub fn latency_stats(&self) -> Option<LatencyStats> {
+        self.latency.as_ref().map(LatencyTracer::stats)
+    }
+
+    /// Order-entry stage timestamps, when latency tracing is enabled
+    pub fn latency_tracer(&self) -> Option<&LatencyTracer> {
+        self.latency.as_ref()
+    }
+
+    /// Record how far the receiver of an instrument stream fell behind and
+    /// the fragments it lost
+    pub(crate) fn record_instrument_backlog(&mut self, high_water: usize, dropped: u64) {
+        self.instrument_backlog_high_water = self.instrument_backlog_high_water.max(high_water);
+        self.dropped_instrument_batches += dropped;
+    }
+
+    /// Record when the client issued the command about to run, the start of
+    /// the latency traces of the orders it sends
+    pub(crate) fn set_command_issued_at(&mut self, issued_at: Option<tokio::time::Instant>) {
+        self.command_issued_at = issued_at;
+    }
+
+    /// Send an application message, or simulate it when paper trading
+    ///
+    /// Simulated messages never reach the exchange and do not consume an
+    /// outgoing sequence number; their reports are returned by
+    /// [`Session::receive_and_process_message`]. Returns the MsgSeqNum used,
+    /// `None` for a simulated message.
+    async fn send_or_simulate(&mut self, message: FixMessage) -> Result<Option<u32>> {
+        if self.simulate(&message)? {
+            return Ok(None);
+        }
+
+        let serialized = tokio::time::Instant::now();
+        let traced = self
+            .latency
+            .is_some()
+            .then(|| LatencyTracer::traced_order_id(&message).cloned())
+            .flatten();
+        self.send_message(message).await?;
+        let msg_seq_num = self.outgoing_seq_num;
+        self.outgoing_seq_num += 1;
+        if let Some(cl_ord_id) = traced {
+            self.start_latency_trace(cl_ord_id, serialized).await;
+        }
+        Ok(Some(msg_seq_num))
+    }
+
+    /// Start the latency trace of an order handed to the connection at
+    /// `serialized`
+    async fn start_latency_trace(&mut self, cl_ord_id: String, serialized: tokio::time::Instant) {
+        let written = match &self.connection {
+            Some(connection) => {
+                let connection = connection.lock().await;
+                if connection.has_pending_writes() {
+                    None
+                } else {
+                    connection.last_write_at()
+                }
+            }
+            None => None,
+        };
+        let called = self.command_issued_at.unwrap_or(serialized);
+        if let Some(tracer) = &mut self.latency {
+            tracer.start(cl_ord_id, called, serialized, written);
+        }
+    }
+
     /// Perform FIX logon
     pub async fn logon(&mut self) -> Result<()> {
-        info!("Performing FIX logon");
+        self.logon_with_reset(self.config.reset_seq_num_on_logon)
+            .await
+    }
+
+    /// Perform FIX logon, optionally requesting a sequence number reset
+    ///
+    /// When `reset_seq_num` is true, both the outgoing and expected incoming
+    /// sequence numbers are restarted at 1 and the Logon is sent with
+    /// ResetSeqNumFlag (141=Y).
+    pub async fn logon_with_reset(&mut self, reset_seq_num: bool) -> Result<()> {
+        info!("Performing FIX logon (reset_seq_num: {})", reset_seq_num);
+        if self.logon_retry.is_circuit_open() {
+            return Err(DeribitFixError::Authentication(
+                "Credentials were rejected; reset the logon circuit before logging on again"
+                    .to_string(),
+            ));
+        }
+        let next_state = self.state.transition(SessionState::LogonSent)?;
+
+        if reset_seq_num {
+            self.outgoing_seq_num = 1;
+            self.incoming_seq_num = 1;
+            self.emit_event(SessionEvent::SequenceNumbersReset);
+        }
 
         // Generate RawData and password hash according to Deribit FIX spec
         let (raw_data, password_hash) = self.generate_auth_data(&self.config.password)?;
@@ -91,23 +749,32 @@ impl Session {
             .sender_comp_id(self.config.sender_comp_id.clone())
             .target_comp_id(self.config.target_comp_id.clone())
             .msg_seq_num(self.outgoing_seq_num)
-            .field(108, self.config.heartbeat_interval.to_string()) // HeartBtInt - Required
-            .field(96, raw_data.clone()) // RawData - Required (timestamp.nonce)
-            .field(553, self.config.username.clone()) // Username - Required
-            .field(554, password_hash); // Password - Required
+            .field(
+                tags::HEART_BT_INT,
+                self.config.heartbeat_interval.to_string(),
+            ) // HeartBtInt - Required
+            .field(tags::RAW_DATA, raw_data.clone()) // RawData - Required (timestamp.nonce)
+            .field(tags::USERNAME, self.config.username.clone()) // Username - Required
+            .field(tags::PASSWORD, password_hash); // Password - Required
 
         // Add RawDataLength if needed (optional but recommended)
-        message_builder = message_builder.field(95, raw_data.len().to_string()); // RawDataLength
+        message_builder = message_builder.field(tags::RAW_DATA_LENGTH, raw_data.len().to_string());
+
+        if reset_seq_num {
+            message_builder = message_builder.field(tags::RESET_SEQ_NUM_FLAG, "Y".to_string());
+        }
 
         // Add optional Deribit-specific tags based on configuration
         if let Some(use_wordsafe_tags) = &self.config.use_wordsafe_tags {
-            message_builder =
-                message_builder.field(9002, if *use_wordsafe_tags { "Y" } else { "N" }.to_string()); // UseWordsafeTags
+            message_builder = message_builder.field(
+                tags::USE_WORDSAFE_TAGS,
+                if *use_wordsafe_tags { "Y" } else { "N" }.to_string(),
+            );
         }
 
         // CancelOnDisconnect - always include based on config
         message_builder = message_builder.field(
-            9001,
+            tags::CANCEL_ON_DISCONNECT,
             if self.config.cancel_on_disconnect {
                 "Y"
             } else {
@@ -117,7 +784,7 @@ impl Session {
         ); // CancelOnDisconnect
 
         if let Some(app_id) = &self.config.app_id {
-            message_builder = message_builder.field(9004, app_id.clone()); // DeribitAppId
+            message_builder = message_builder.field(tags::DERIBIT_APP_ID, app_id.clone());
         }
 
         if let Some(app_secret) = &self.config.app_secret
@@ -126,26 +793,26 @@ impl Session {
                 .map(|(timestamp, nonce)| format!("{}.{}", timestamp, nonce))
             && let Ok(app_sig) = self.calculate_app_signature(&raw_data_str, app_secret)
         {
-            message_builder = message_builder.field(9005, app_sig); // DeribitAppSig
+            message_builder = message_builder.field(tags::DERIBIT_APP_SIG, app_sig);
         }
 
         if let Some(deribit_sequential) = &self.config.deribit_sequential {
             message_builder = message_builder.field(
-                9007,
+                tags::DERIBIT_SEQUENTIAL,
                 if *deribit_sequential { "Y" } else { "N" }.to_string(),
             ); // DeribitSequential
         }
 
         if let Some(unsubscribe_exec_reports) = &self.config.unsubscribe_execution_reports {
             message_builder = message_builder.field(
-                9009,
+                tags::UNSUBSCRIBE_EXECUTION_REPORTS,
                 if *unsubscribe_exec_reports { "Y" } else { "N" }.to_string(),
             ); // UnsubscribeExecutionReports
         }
 
         if let Some(connection_only_exec_reports) = &self.config.connection_only_execution_reports {
             message_builder = message_builder.field(
-                9010,
+                tags::CONNECTION_ONLY_EXECUTION_REPORTS,
                 if *connection_only_exec_reports {
                     "Y"
                 } else {
@@ -157,7 +824,7 @@ impl Session {
 
         if let Some(report_fills_as_exec_reports) = &self.config.report_fills_as_exec_reports {
             message_builder = message_builder.field(
-                9015,
+                tags::REPORT_FILLS_AS_EXEC_REPORTS,
                 if *report_fills_as_exec_reports {
                     "Y"
                 } else {
@@ -169,21 +836,26 @@ impl Session {
 
         if let Some(display_increment_steps) = &self.config.display_increment_steps {
             message_builder = message_builder.field(
-                9018,
+                tags::DISPLAY_INCREMENT_STEPS,
                 if *display_increment_steps { "Y" } else { "N" }.to_string(),
             ); // DisplayIncrementSteps
         }
 
         // Add AppID if available
         if let Some(app_id) = &self.config.app_id {
-            message_builder = message_builder.field(1128, app_id.clone()); // AppID
+            message_builder = message_builder.field(tags::APP_ID, app_id.clone());
+        }
+
+        if let Some(default_appl_ver_id) = &self.config.default_appl_ver_id {
+            message_builder =
+                message_builder.field(tags::DEFAULT_APPL_VER_ID, default_appl_ver_id.clone());
         }
 
         let logon_message = message_builder.build()?;
 
         // Send the logon message
         self.send_message(logon_message).await?;
-        self.state = SessionState::LogonSent;
+        self.state = next_state;
         self.outgoing_seq_num += 1;
 
         info!("Logon message sent");
@@ -191,6 +863,10 @@ impl Session {
     }
 
     /// Perform FIX logout
+    ///
+    /// The session is left in [`SessionState::LogoutSent`] until the
+    /// counterparty's Logout (5) is processed; use
+    /// [`logout_and_wait`](Self::logout_and_wait) to wait for it.
     pub async fn logout(&mut self) -> Result<()> {
         self.logout_with_options(None, None).await
     }
@@ -202,6 +878,7 @@ impl Session {
         dont_cancel_on_disconnect: Option<bool>,
     ) -> Result<()> {
         info!("Performing FIX logout");
+        let next_state = self.state.transition(SessionState::LogoutSent)?;
 
         let mut message_builder = MessageBuilder::new()
             .msg_type(MsgType::Logout)
@@ -211,25 +888,115 @@ impl Session {
 
         // Add Text field (tag 58) - optional
         let logout_text = text.unwrap_or_else(|| "Normal logout".to_string());
-        message_builder = message_builder.field(58, logout_text); // Text
+        message_builder = message_builder.field(tags::TEXT, logout_text);
 
         // Add DontCancelOnDisconnect field (tag 9003) - optional
-        if let Some(dont_cancel) = dont_cancel_on_disconnect {
-            message_builder =
-                message_builder.field(9003, if dont_cancel { "Y" } else { "N" }.to_string()); // DontCancelOnDisconnect
+        if let Some(dont_cancel) =
+            dont_cancel_on_disconnect.or(self.config.dont_cancel_on_disconnect)
+        {
+            message_builder = message_builder.field(
+                tags::DONT_CANCEL_ON_DISCONNECT,
+                if dont_cancel { "Y" } else { "N" }.to_string(),
+            );
         }
 
         let logout_message = message_builder.build()?;
 
         // Send the logout message
         self.send_message(logout_message).await?;
-        self.state = SessionState::LogoutSent;
+        self.state = next_state;
         self.outgoing_seq_num += 1;
 
         info!("Logout message sent");
         Ok(())
     }
 
+    /// Log out and wait for the counterparty's Logout (5)
+    ///
+    /// Sends a Logout with `text` as its Text (58), or "Normal logout", and
+    /// processes incoming messages until the counterparty answers with its
+    /// own Logout, for up to
+    /// [`logout_timeout`](DeribitFixConfig::logout_timeout). When no answer
+    /// arrives in time or the connection fails first, the connection is
+    /// closed and the session marked disconnected anyway. Returns whether the
+    /// logout was acknowledged.
+    pub async fn logout_and_wait(&mut self, text: Option<String>) -> Result<bool> {
+        self.logout_with_options(text, None).await?;
+        let timeout = self.config.logout_timeout;
+        let answered = self
+            .await_response_within("logout", timeout, |message| {
+                Ok((message.msg_type() == Some(MsgType::Logout)).then_some(()))
+            })
+            .await;
+        match answered {
+            Ok(()) => {
+                info!("Logout acknowledged");
+                Ok(true)
+            }
+            Err(error) => {
+                warn!("Logout not acknowledged, closing the connection: {}", error);
+                if let Some(connection) = &self.connection
+                    && let Err(e) = connection.lock().await.close().await
+                {
+                    warn!("Failed to close the connection after logout: {}", e);
+                }
+                if self.state == SessionState::LogoutSent {
+                    self.transition(SessionState::Disconnected)?;
+                }
+                match error {
+                    DeribitFixError::Timeout(_) | DeribitFixError::Connection(_) => Ok(false),
+                    error => Err(error),
+                }
+            }
+        }
+    }
+
+    /// Enable or disable cancel-on-disconnect for the rest of the session
+    ///
+    /// Deribit only reads CancelOnDisconnect (9001) on the Logon, so a
+    /// logged-on session whose setting differs logs out with
+    /// DontCancelOnDisconnect (9003) set, keeping its orders open, and logs
+    /// on again with the new setting. The setting also applies to later
+    /// logons. Returns the setting of the new logon, or `None` when the
+    /// session is not logged on and nothing was sent.
+    pub async fn set_cancel_on_disconnect(
+        &mut self,
+        enabled: bool,
+    ) -> Result<Option<CancelOnDisconnectStatus>> {
+        self.config.cancel_on_disconnect = enabled;
+        let Some(current) = self.cancel_on_disconnect() else {
+            return Ok(None);
+        };
+        if current.requested == enabled && current.is_active() == enabled {
+            return Ok(Some(current));
+        }
+
+        info!(
+            "Logging on again to set cancel on disconnect to {}",
+            enabled
+        );
+        self.logout_with_options(
+            Some("Changing cancel on disconnect".to_string()),
+            Some(true),
+        )
+        .await?;
+        let timeout = self.config.logout_timeout;
+        if let Err(e) = self
+            .await_response_within("logout", timeout, |message| {
+                Ok((message.msg_type() == Some(MsgType::Logout)).then_some(()))
+            })
+            .await
+        {
+            warn!("Logout not acknowledged, logging on again anyway: {}", e);
+        }
+        self.relogon().await?;
+        self.await_response("logon", |message| {
+            Ok((message.msg_type() == Some(MsgType::Logon)).then_some(()))
+        })
+        .await?;
+        Ok(self.cancel_on_disconnect())
+    }
+
     /// Send a heartbeat message
     pub async fn send_heartbeat(&mut self, test_req_id: Option<String>) -> Result<()> {
         debug!("Sending heartbeat message");
@@ -241,7 +1008,7 @@ impl Session {
             .msg_seq_num(self.outgoing_seq_num);
 
         if let Some(test_req_id) = test_req_id {
-            builder = builder.field(112, test_req_id); // TestReqID
+            builder = builder.field(tags::TEST_REQ_ID, test_req_id);
         }
 
         let heartbeat_message = builder.build()?;
@@ -254,184 +1021,1376 @@ impl Session {
         Ok(())
     }
 
-    /// Send a new order
-    pub async fn send_new_order(&mut self, order: NewOrderRequest) -> Result<String> {
-        info!("Sending new order: {:?}", order);
+    /// Send a Test Request (1) with a generated TestReqID (112)
+    ///
+    /// The round trip is measured when the matching Heartbeat arrives.
+    /// Returns the TestReqID.
+    pub async fn send_test_request(&mut self) -> Result<String> {
+        let test_req_id = self.request_ids.next("PING");
+        self.send(&TestRequest::new(test_req_id.clone())).await?;
+        self.pending_pings
+            .insert(test_req_id.clone(), self.config.clock.now());
+        Ok(test_req_id)
+    }
 
-        // Use the client order ID if provided, otherwise generate one
-        let order_id = order
-            .client_order_id
-            .clone()
-            .unwrap_or_else(|| format!("ORDER_{}", gen_id()));
+    /// Ask the counterparty to resend messages `begin_seq_no` to `end_seq_no`
+    ///
+    /// An `end_seq_no` of 0 requests every message from `begin_seq_no`. The
+    /// session stays in [`SessionState::ResendInProgress`] until the gap is
+    /// filled: once the expected incoming sequence number passes
+    /// `end_seq_no`, or for an open range when a message that is not a
+    /// possible duplicate arrives.
+    pub async fn send_resend_request(&mut self, begin_seq_no: u32, end_seq_no: u32) -> Result<()> {
+        let next_state = self.state.transition(SessionState::ResendInProgress)?;
+        self.send(&ResendRequest::new(begin_seq_no, end_seq_no))
+            .await?;
+        self.state = next_state;
+        self.resend_end_seq_no = Some(end_seq_no);
+        info!(
+            "Resend of messages {} to {} requested",
+            begin_seq_no, end_seq_no
+        );
+        Ok(())
+    }
 
-        // Determine order type
-        let ord_type = match order.order_type {
-            OrderType::Market => "1",     // Market
-            OrderType::Limit => "2",      // Limit
-            OrderType::StopLimit => "4",  // Stop Limit
-            OrderType::StopMarket => "3", // Stop Market
-            _ => "2",                     // Default to Limit for other types
+    /// Return to [`SessionState::LoggedOn`] once the requested gap is filled
+    fn check_resend_complete(&mut self, message: &FixMessage) -> Result<()> {
+        if self.state != SessionState::ResendInProgress {
+            return Ok(());
+        }
+        let Some(end_seq_no) = self.resend_end_seq_no else {
+            return Ok(());
         };
+        let complete = if end_seq_no == 0 {
+            message
+                .get_field(tags::POSS_DUP_FLAG)
+                .is_none_or(|flag| flag != "Y")
+        } else {
+            self.incoming_seq_num > end_seq_no
+        };
+        if complete {
+            info!("Resend complete, next expected {}", self.incoming_seq_num);
+            self.resend_end_seq_no = None;
+            self.transition(SessionState::LoggedOn)?;
+        }
+        Ok(())
+    }
 
-        let mut builder = MessageBuilder::new()
-            .msg_type(MsgType::NewOrderSingle)
-            .sender_comp_id(self.config.sender_comp_id.clone())
-            .target_comp_id(self.config.target_comp_id.clone())
-            .msg_seq_num(self.outgoing_seq_num)
-            .field(11, order_id.clone()) // ClOrdID
-            .field(55, order.instrument_name.clone()) // Symbol
-            .field(
-                54,
-                match order.side {
-                    OrderSide::Buy => "1".to_string(),
-                    OrderSide::Sell => "2".to_string(),
-                },
-            ) // Side
-            .field(60, Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string()) // TransactTime
-            .field(38, order.amount.to_string()) // OrderQty
-            .field(40, ord_type.to_string()); // OrdType
+    /// What the server was seen to accept and send since the last logon
+    ///
+    /// The Logon (A) response and the tags refused by Rejects (3) are always
+    /// recorded; the Test Request round trip and the custom tags of other
+    /// messages only with
+    /// [`capability_probe`](crate::config::DeribitFixConfig::capability_probe).
+    pub fn server_capabilities(&self) -> &ServerCapabilities {
+        &self.capabilities
+    }
 
-        // Add price for limit orders
-        if order.order_type == OrderType::Limit || order.price.is_some() {
-            builder = builder.field(44, order.price.unwrap_or(0.0).to_string()); // Price
-        }
+    /// Send a Test Request and wait for the matching Heartbeat
+    ///
+    /// Returns the round-trip time; the connection health is updated from it.
+    pub async fn ping(&mut self) -> Result<std::time::Duration> {
+        let test_req_id = self.send_test_request().await?;
+        let clock = self.config.clock.clone();
+        let sent_at = clock.now();
+        self.await_response(&format!("test request {test_req_id}"), |message| {
+            let answered = message.msg_type() == Some(MsgType::Heartbeat)
+                && message.get_field(tags::TEST_REQ_ID) == Some(&test_req_id);
+            Ok(answered.then(|| clock.elapsed(sent_at)))
+        })
+        .await
+    }
 
-        // Add time in force
-        let tif = match order.time_in_force {
-            TimeInForce::GoodTilCancelled => "1",
-            TimeInForce::ImmediateOrCancel => "3",
-            TimeInForce::FillOrKill => "4",
-            TimeInForce::GoodTilDay => "6",
+    /// Mark the connection degraded if a Test Request is unanswered for
+    /// longer than `max_ping_latency`
+    ///
+    /// Returns whether a Test Request is still outstanding.
+    pub fn check_ping_latency(&mut self) -> bool {
+        let Some(waited) = self
+            .pending_pings
+            .values()
+            .map(|sent_at| self.config.clock.elapsed(*sent_at))
+            .max()
+        else {
+            return false;
         };
-        builder = builder.field(59, tif.to_string()); // TimeInForce
-
-        // Add execution instructions
-        let mut exec_inst = String::new();
-        if order.post_only == Some(true) {
-            exec_inst.push('6'); // Participate don't initiate
+        if waited > self.config.max_ping_latency {
+            self.update_health(waited);
         }
-        if order.reduce_only == Some(true) {
-            exec_inst.push('E'); // Reduce only
-        }
-        if !exec_inst.is_empty() {
-            builder = builder.field(18, exec_inst); // ExecInst
+        true
+    }
+
+    /// Complete the Test Request answered by a Heartbeat (0)
+    fn handle_heartbeat(&mut self, message: &FixMessage) {
+        let Some(sent_at) = message
+            .get_field(tags::TEST_REQ_ID)
+            .and_then(|test_req_id| self.pending_pings.remove(test_req_id))
+        else {
+            return;
+        };
+        let round_trip = self.config.clock.elapsed(sent_at);
+        debug!("Test request answered in {:?}", round_trip);
+        if self.capability_probe.as_ref() == message.get_field(tags::TEST_REQ_ID) {
+            self.capability_probe = None;
+            self.capabilities.test_request_round_trip = Some(round_trip);
         }
+        self.last_round_trip = Some(round_trip);
+        self.update_health(round_trip);
+    }
 
-        // Add label if provided
-        if let Some(label) = &order.label {
-            builder = builder.field(100010, label.clone()); // Deribit label
+    /// Set the connection health from a round-trip time, emitting changes
+    fn update_health(&mut self, round_trip: std::time::Duration) {
+        let health = if round_trip > self.config.max_ping_latency {
+            ConnectionHealth::Degraded
+        } else {
+            ConnectionHealth::Healthy
+        };
+        if health == self.health {
+            return;
+        }
+        self.health = health;
+        match health {
+            ConnectionHealth::Degraded => warn!("Connection degraded: round trip {:?}", round_trip),
+            ConnectionHealth::Healthy => info!("Connection healthy: round trip {:?}", round_trip),
         }
+        self.emit_event(SessionEvent::ConnectionHealth { health, round_trip });
+    }
 
-        let order_message = builder.build()?;
+    /// Send a typed FIX message
+    ///
+    /// SenderCompID, TargetCompID and MsgSeqNum are taken from the session and
+    /// SendingTime is set when the message is built. Returns the MsgSeqNum used;
+    /// orders simulated by paper trading leave it unused.
+    pub async fn send<M: ToFixMessage + ?Sized>(&mut self, message: &M) -> Result<u32> {
+        let msg_seq_num = self.outgoing_seq_num;
+        let fix_message = message.to_fix_message(
+            &self.config.sender_comp_id,
+            &self.config.target_comp_id,
+            msg_seq_num,
+        )?;
 
-        // Actually send the message
-        self.send_message(order_message).await?;
-        self.outgoing_seq_num += 1;
+        self.send_or_simulate(fix_message).await?;
 
-        info!("New order message sent with ID: {}", order_id);
-        Ok(order_id)
+        Ok(msg_seq_num)
     }
 
-    /// Cancel an order
+    /// Send a new order
     ///
-    /// # Arguments
-    /// * `order_id` - The order identifier (OrigClOrdID) to cancel
-    /// * `symbol` - Optional instrument symbol. Required when canceling by ClOrdID or DeribitLabel,
-    ///   but not required when using OrigClOrdID (fastest approach)
-    /// * `currency` - Optional currency to speed up search when using ClOrdID or DeribitLabel
-    pub async fn cancel_order(&mut self, order_id: String) -> Result<()> {
-        self.cancel_order_with_symbol(order_id, None).await
+    /// While the instrument is halted or the exchange is in maintenance the
+    /// order is rejected locally, or queued and sent once trading resumes when
+    /// `queue_orders_during_halt` is enabled.
+    pub async fn send_new_order(&mut self, order: NewOrderRequest) -> Result<String> {
+        self.place_new_order(order)
+            .await
+            .map(|(order_id, _)| order_id)
     }
 
-    /// Cancel an order with optional symbol specification
-    ///
-    /// According to Deribit FIX documentation:
-    /// - Canceling by OrigClOrdId is fastest and recommended when possible
-    /// - Symbol is required only when OrigClOrdId is absent (canceling by ClOrdID or DeribitLabel)
-    /// - Currency can optionally speed up searches by DeribitLabel or ClOrdID
+    /// Send or queue a new order, returning its ClOrdID with the MsgSeqNum
+    /// it was sent with, `None` when it was queued or simulated
+    async fn place_new_order(
+        &mut self,
+        mut order: NewOrderRequest,
+    ) -> Result<(String, Option<u32>)> {
+        // Use the client order ID if provided, otherwise generate one
+        let order_id = order
+            .client_order_id
+            .get_or_insert_with(|| self.request_ids.next("ORDER"))
+            .clone();
+
+        order.validate_instructions()?;
+
+        let mark_price = self.mark_price(&order.instrument_name);
+        if let Err(violation) = self
+            .risk_guard
+            .check(&order, mark_price, self.config.clock.now())
+        {
+            warn!("Order {} rejected by risk checks: {}", order_id, violation);
+            return Err(DeribitFixError::RiskLimit(violation));
+        }
+
+        let Some(order) = self.hold_while_halted(order, &order_id)? else {
+            return Ok((order_id, None));
+        };
+        self.submit_new_order(order, order_id).await
+    }
+
+    /// Send an order on a combo instrument
+    ///
+    /// An order given by its legs is placed on the matching combo instrument,
+    /// which must have been received in a Security List or Security
+    /// Definition. Returns the ClOrdID.
+    pub async fn send_combo_order(&mut self, order: ComboOrderRequest) -> Result<String> {
+        let request = self.combos.resolve(order)?;
+        self.send_new_order(request).await
+    }
+
+    /// Submit linked orders as an OCO (one-cancels-other) group
+    ///
+    /// When any member is filled the remaining open members are cancelled.
+    /// Returns the group identifier.
+    ///
+    /// If a member cannot be sent, the members already sent are cancelled,
+    /// the group is dropped and the error is returned.
+    pub async fn submit_order_group(&mut self, mut orders: Vec<NewOrderRequest>) -> Result<String> {
+        if orders.len() < 2 {
+            return Err(DeribitFixError::Session(
+                "An OCO group needs at least two orders".to_string(),
+            ));
+        }
+
+        let group_id = self.request_ids.next("OCO");
+        let members = orders
+            .iter_mut()
+            .map(|order| {
+                let cl_ord_id = order
+                    .client_order_id
+                    .get_or_insert_with(|| self.request_ids.next("ORDER"))
+                    .clone();
+                GroupMember::new(cl_ord_id, order.instrument_name.clone())
+            })
+            .collect();
+        // Register before sending so that an immediate fill is already tracked
+        self.order_groups
+            .register(OrderGroup::new(group_id.clone(), members));
+
+        let mut sent = Vec::with_capacity(orders.len());
+        for order in orders {
+            let symbol = order.instrument_name.clone();
+            match self.send_new_order(order).await {
+                Ok(cl_ord_id) => sent.push((cl_ord_id, symbol)),
+                Err(e) => {
+                    warn!("OCO group {} not submitted: {}", group_id, e);
+                    self.order_groups.remove(&group_id);
+                    self.withdraw_group_members(sent).await;
+                    return Err(e);
+                }
+            }
+        }
+        info!("Submitted OCO group {}", group_id);
+        Ok(group_id)
+    }
+
+    /// Cancel the members of a group that could not be submitted in full
+    ///
+    /// Members still queued for a halted instrument are withdrawn from the
+    /// queue instead.
+    async fn withdraw_group_members(&mut self, members: Vec<(String, String)>) {
+        for (cl_ord_id, symbol) in members {
+            if self.market_state.withdraw_queued(&cl_ord_id) {
+                continue;
+            }
+            let cancel = OrderCancelRequest::by_cl_ord_id(cl_ord_id.clone(), symbol);
+            if let Err(e) = self.send(&cancel).await {
+                warn!("Failed to cancel OCO group member {}: {}", cl_ord_id, e);
+            }
+        }
+    }
+
+    /// Send new orders in one write batch
+    ///
+    /// The connection holds the messages until the last order is sent, so
+    /// they reach the socket together. Each order is checked and sent like
+    /// [`send_new_order`](Self::send_new_order); returns, in order, the
+    /// ClOrdID of each order with the MsgSeqNum it was sent with or the
+    /// error that refused it. The MsgSeqNum is `None` for an order queued
+    /// until its instrument is tradable or simulated by paper trading.
+    pub async fn send_new_orders(
+        &mut self,
+        orders: Vec<NewOrderRequest>,
+    ) -> Vec<(String, Result<Option<u32>>)> {
+        if let Some(connection) = &self.connection {
+            connection.lock().await.hold_writes();
+        }
+        let mut sent = Vec::with_capacity(orders.len());
+        for mut order in orders {
+            let cl_ord_id = order
+                .client_order_id
+                .get_or_insert_with(|| self.request_ids.next("ORDER"))
+                .clone();
+            let result = self
+                .place_new_order(order)
+                .await
+                .map(|(_, msg_seq_num)| msg_seq_num);
+            sent.push((cl_ord_id, result));
+        }
+        if let Err(e) = self.flush().await {
+            warn!("Failed to write the order batch: {}", e);
+            for (_, result) in sent.iter_mut().filter(|(_, result)| result.is_ok()) {
+                *result = Err(DeribitFixError::Connection(e.to_string()));
+            }
+        }
+        sent
+    }
+
+    /// Mark price of an instrument, or the middle of its order book when the
+    /// exchange has not reported one
+    fn mark_price(&self, symbol: &str) -> Option<f64> {
+        self.market_stats
+            .stats(symbol)
+            .and_then(|stats| stats.mark_price)
+            .or_else(|| {
+                let book = self.order_books.get(symbol)?;
+                let ((bid, _), (ask, _)) = (book.best_bid()?, book.best_ask()?);
+                Some((bid + ask) / 2.0)
+            })
+    }
+
+    /// Build and send a New Order Single (D) without market state checks
+    ///
+    /// Returns the ClOrdID with the MsgSeqNum used, `None` when simulated.
+    pub(super) async fn submit_new_order(
+        &mut self,
+        order: NewOrderRequest,
+        order_id: String,
+    ) -> Result<(String, Option<u32>)> {
+        info!("Sending new order: {:?}", order);
+
+        // Determine order type
+        let ord_type = match order.order_type {
+            OrderType::Market => "1",     // Market
+            OrderType::Limit => "2",      // Limit
+            OrderType::StopLimit => "4",  // Stop Limit
+            OrderType::StopMarket => "3", // Stop Market
+            _ => "2",                     // Default to Limit for other types
+        };
+
+        let mut builder = MessageBuilder::new()
+            .msg_type(MsgType::NewOrderSingle)
+            .sender_comp_id(self.config.sender_comp_id.clone())
+            .target_comp_id(self.config.target_comp_id.clone())
+            .msg_seq_num(self.outgoing_seq_num)
+            .field(tags::CL_ORD_ID, order_id.clone())
+            .field(tags::SYMBOL, order.instrument_name.clone())
+            .field(
+                tags::SIDE,
+                match order.side {
+                    OrderSide::Buy => "1".to_string(),
+                    OrderSide::Sell => "2".to_string(),
+                },
+            ) // Side
+            .field(
+                tags::TRANSACT_TIME,
+                format_utc_timestamp_with(
+                    &self.config.clock.utc_now(),
+                    self.config.timestamp_precision,
+                ),
+            )
+            .field(tags::ORDER_QTY, order.amount.to_string())
+            .field(tags::ORD_TYPE, ord_type.to_string());
+
+        // Add price for limit orders
+        if order.order_type == OrderType::Limit || order.price.is_some() {
+            builder = builder.field(tags::PRICE, order.price.unwrap_or(0.0).to_string());
+        }
+
+        // Add time in force
+        let tif = match order.time_in_force {
+            TimeInForce::GoodTilCancelled => "1",
+            TimeInForce::ImmediateOrCancel => "3",
+            TimeInForce::FillOrKill => "4",
+            TimeInForce::GoodTilDay => "6",
+        };
+        builder = builder.field(tags::TIME_IN_FORCE, tif.to_string());
+
+        // Add execution instructions, the displayed amount and self-trade prevention
+        if let Some(exec_inst) = order.exec_inst().to_fix_value() {
+            builder = builder.field(tags::EXEC_INST, exec_inst);
+        }
+        if let Some(max_show) = order.max_show {
+            builder = builder.field(tags::DISPLAY_QTY, max_show.to_string());
+        }
+        let self_trade_prevention = order
+            .self_trade_prevention
+            .or(self.config.self_trade_prevention);
+        if let Some(stp) = self_trade_prevention {
+            builder = builder.field(
+                tags::SELF_MATCH_PREVENTION_INSTRUCTION,
+                stp.to_fix_value().to_string(),
+            );
+        }
+
+        // Add label if provided
+        if let Some(label) = &order.label {
+            self.label_router.track(order_id.clone(), label.clone());
+            builder = builder.field(tags::DERIBIT_LABEL, label.clone());
+        }
+
+        let order_message = builder.build()?;
+
+        // Actually send the message
+        let msg_seq_num = self.send_or_simulate(order_message).await?;
+        let mark_price = self.mark_price(&order.instrument_name);
+        self.risk_guard
+            .record_sent(&order, &order_id, mark_price, self.config.clock.now());
+        self.order_tracker.record_sent(&order, &order_id);
+        if let Err(e) =
+            self.order_index
+                .record_sent(&order_id, &order.instrument_name, order.label.as_deref())
+        {
+            warn!("Failed to store the owner of order {}: {}", order_id, e);
+        }
+
+        info!("New order message sent with ID: {}", order_id);
+        Ok((order_id, msg_seq_num))
+    }
+
+    /// Cancel an order
     ///
     /// # Arguments
     /// * `order_id` - The order identifier (OrigClOrdID) to cancel
+    /// * `symbol` - Optional instrument symbol. Required when canceling by ClOrdID or DeribitLabel,
+    ///   but not required when using OrigClOrdID (fastest approach)
+    /// * `currency` - Optional currency to speed up search when using ClOrdID or DeribitLabel
+    pub async fn cancel_order(&mut self, order_id: String) -> Result<()> {
+        self.cancel_order_with_symbol(order_id, None).await?;
+        Ok(())
+    }
+
+    /// Cancel an order with optional symbol specification
+    ///
+    /// According to Deribit FIX documentation:
+    /// - Canceling by OrigClOrdId is fastest and recommended when possible
+    /// - Symbol is required only when OrigClOrdId is absent (canceling by ClOrdID or DeribitLabel)
+    /// - Currency can optionally speed up searches by DeribitLabel or ClOrdID
+    ///
+    /// Waits for and returns the Execution Report confirming the cancel; an
+    /// Order Cancel Reject (9) is returned as
+    /// [`DeribitFixError::CancelRejected`].
+    ///
+    /// # Arguments
+    /// * `order_id` - The exchange OrderID (OrigClOrdID) to cancel, or the
+    ///   ClOrdID of an order of the session whose OrderID was reported
     /// * `symbol` - Optional instrument symbol (e.g., "BTC-PERPETUAL")
     /// * `currency` - Optional currency to speed up search
     pub async fn cancel_order_with_symbol(
         &mut self,
         order_id: String,
         symbol: Option<String>,
-    ) -> Result<()> {
+    ) -> Result<CancelReport> {
         info!("Cancelling order: {} with symbol: {:?}", order_id, symbol);
+        // A ClOrdID of the session is sent as the OrderID reported for it
+        let order_id = match self.order_tracker.order_id_of(&order_id) {
+            Some(resolved) => resolved.to_string(),
+            None => order_id,
+        };
 
         // Generate a proper unique cancel ID using random number instead of timestamp
-        let cancel_id = format!("CANCEL_{}", gen_id());
+        let cancel_id = self.request_ids.next("CANCEL");
 
         let mut builder = MessageBuilder::new()
             .msg_type(MsgType::OrderCancelRequest)
             .sender_comp_id(self.config.sender_comp_id.clone())
             .target_comp_id(self.config.target_comp_id.clone())
             .msg_seq_num(self.outgoing_seq_num)
-            .field(11, cancel_id) // ClOrdID - Original order identifier assigned by the user
-            .field(41, order_id) // OrigClOrdID - Order identifier assigned by Deribit
-            .field(60, Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string()); // TransactTime
+            .field(tags::CL_ORD_ID, cancel_id) // ClOrdID - Original order identifier assigned by the user
+            .field(tags::ORIG_CL_ORD_ID, order_id.clone()) // OrigClOrdID - Order identifier assigned by Deribit
+            .field(
+                tags::TRANSACT_TIME,
+                format_utc_timestamp_with(
+                    &self.config.clock.utc_now(),
+                    self.config.timestamp_precision,
+                ),
+            );
 
         // Add symbol if provided - required when OrigClOrdId is absent
         if let Some(symbol_value) = symbol {
-            builder = builder.field(55, symbol_value); // Symbol
+            builder = builder.field(tags::SYMBOL, symbol_value);
         }
 
         let cancel_message = builder.build()?;
 
         // Actually send the cancel message
-        self.send_message(cancel_message).await?;
-        self.outgoing_seq_num += 1;
-
+        self.send_or_simulate(cancel_message).await?;
         info!("Order cancel message sent");
-        Ok(())
+
+        let target = CancelTarget::OrderId(order_id);
+        self.await_response(&format!("cancel of {target:?}"), |message| {
+            Self::match_cancel_report(&target, None, message)
+        })
+        .await
+    }
+
+    /// Cancel the orders selected by `target` and wait for the resulting report
+    ///
+    /// Single orders are cancelled with Order Cancel Request (F) and confirmed
+    /// by the Execution Report of the cancelled order; labels and symbol/side
+    /// selections use Order Mass Cancel Request (q) and return its Order Mass
+    /// Cancel Report (r). A matching Order Cancel Reject (9) is returned as
+    /// [`DeribitFixError::CancelRejected`] and a Reject (3) or Business
+    /// Message Reject (j) of the request as
+    /// [`DeribitFixError::MessageRejected`], both with the exchange's Text
+    /// (58). [`CancelTarget::OrderId`] also takes the ClOrdID of an order of
+    /// the session.
+    pub async fn cancel(&mut self, target: CancelTarget) -> Result<CancelReport> {
+        info!("Cancelling {:?}", target);
+        let target = match target {
+            CancelTarget::OrderId(id) => self.cancel_target_of(&id),
+            target => target,
+        };
+
+        let (msg_seq_num, mass_cancel_id) = match &target {
+            CancelTarget::OrderId(order_id) => (
+                self.send(&OrderCancelRequest::by_order_id(order_id.clone()))
+                    .await?,
+                None,
+            ),
+            CancelTarget::ClOrdId { cl_ord_id, symbol } => (
+                self.send(&OrderCancelRequest::by_cl_ord_id(
+                    cl_ord_id.clone(),
+                    symbol.clone(),
+                ))
+                .await?,
+                None,
+            ),
+            CancelTarget::Label(label) => {
+                let cancel_id = self.request_ids.next("MASS_CANCEL");
+                let msg_seq_num = self
+                    .send(&OrderMassCancelRequest::by_deribit_label(
+                        cancel_id.clone(),
+                        label.clone(),
+                    ))
+                    .await?;
+                (msg_seq_num, Some(cancel_id))
+            }
+            CancelTarget::SymbolSide { symbol, side } => {
+                let cancel_id = self.request_ids.next("MASS_CANCEL");
+                let side = match side {
+                    OrderSide::Buy => FixOrderSide::Buy,
+                    OrderSide::Sell => FixOrderSide::Sell,
+                };
+                let msg_seq_num = self
+                    .send(
+                        &OrderMassCancelRequest::by_symbol(cancel_id.clone(), symbol.clone())
+                            .with_side(side),
+                    )
+                    .await?;
+                (msg_seq_num, Some(cancel_id))
+            }
+            CancelTarget::AllOrders => {
+                let cancel_id = self.request_ids.next("MASS_CANCEL");
+                let msg_seq_num = self
+                    .send(&OrderMassCancelRequest::all_orders(cancel_id.clone()))
+                    .await?;
+                (msg_seq_num, Some(cancel_id))
+            }
+        };
+
+        let report = self
+            .await_response(&format!("cancel of {target:?}"), |message| {
+                if let Some(error) = reject_error_of(message, msg_seq_num) {
+                    return Err(error);
+                }
+                Self::match_cancel_report(&target, mass_cancel_id.as_deref(), message)
+            })
+            .await?;
+        info!("Cancel of {:?} completed", target);
+        Ok(report)
+    }
+
+    /// Cancel target of `id`, a ClOrdID (11) or exchange OrderID (37)
+    ///
+    /// Deribit identifies the order by its OrderID in OrigClOrdID (41), so a
+    /// ClOrdID of the session is resolved to the OrderID reported for it; an
+    /// order not acknowledged yet is targeted by ClOrdID and symbol instead.
+    /// Unknown identifiers are taken as OrderIDs.
+    fn cancel_target_of(&self, id: &str) -> CancelTarget {
+        if let Some(order_id) = self.order_tracker.order_id_of(id) {
+            return CancelTarget::OrderId(order_id.to_string());
+        }
+        match self.order_tracker.order(id) {
+            Some(order) if order.order_id.is_none() => CancelTarget::ClOrdId {
+                cl_ord_id: order.cl_ord_id.clone(),
+                symbol: order.symbol.clone(),
+            },
+            _ => CancelTarget::OrderId(id.to_string()),
+        }
+    }
+
+    /// Replace an order and wait for its Execution Report (8)
+    ///
+    /// Returns the report with ExecType Replaced,
+    /// [`DeribitFixError::CancelRejected`] when the exchange answers with an
+    /// Order Cancel Reject (9), [`DeribitFixError::OrderRejected`] for a
+    /// rejecting Execution Report and [`DeribitFixError::MessageRejected`]
+    /// for a Reject (3) or Business Message Reject (j) of the request. Each
+    /// error keeps the exchange's Text (58). The OrigClOrdID (41) of the
+    /// request may be the ClOrdID of an order of the session; it is sent as
+    /// the OrderID reported for the order.
+    pub async fn replace_order(
+        &mut self,
+        mut request: OrderCancelReplaceRequest,
+    ) -> Result<ExecutionReport> {
+        info!("Replacing order {}", request.orig_cl_ord_id);
+        let given_id = request.orig_cl_ord_id.clone();
+        if let Some(order_id) = self.order_tracker.order_id_of(&given_id) {
+            request.orig_cl_ord_id = order_id.to_string();
+        }
+        request.timestamp_precision = self.config.timestamp_precision;
+        let msg_seq_num = self.send(&request).await?;
+
+        let ids = [
+            given_id.as_str(),
+            request.orig_cl_ord_id.as_str(),
+            request.cl_ord_id.as_str(),
+        ];
+        self.await_response(&format!("replace of {given_id}"), |message| {
+            match message.msg_type() {
+                Some(MsgType::ExecutionReport) => {
+                    // Reports of unrelated orders need not be parseable
+                    let Some(report) =
+                        ExecutionReport::from_fix_message(message)
+                            .ok()
+                            .filter(|report| {
+                                [
+                                    Some(&report.order_id),
+                                    Some(&report.cl_ord_id),
+                                    report.orig_cl_ord_id.as_ref(),
+                                ]
+                                .into_iter()
+                                .flatten()
+                                .any(|id| ids.contains(&id.as_str()))
+                            })
+                    else {
+                        return Ok(None);
+                    };
+                    if report.exec_type == ExecType::Rejected
+                        && let Some(error) = report.reject_error()
+                    {
+                        return Err(error);
+                    }
+                    Ok(Some(report).filter(|report| report.exec_type == ExecType::Replaced))
+                }
+                Some(MsgType::OrderCancelReject)
+                    if [tags::CL_ORD_ID, tags::ORIG_CL_ORD_ID]
+                        .into_iter()
+                        .filter_map(|tag| message.get_field(tag))
+                        .any(|id| ids.contains(&id.as_str())) =>
+                {
+                    Err(OrderCancelReject::from_fix_message(message)?.into_error(given_id.clone()))
+                }
+                _ => reject_error_of(message, msg_seq_num).map_or(Ok(None), Err),
+            }
+        })
+        .await
+    }
+
+    /// Send a Mass Quote (i) and wait for its Mass Quote Acknowledgement (b)
+    ///
+    /// A rejected quote is returned as [`DeribitFixError::QuoteRejected`] and
+    /// a Reject (3) or Business Message Reject (j) of the quote as
+    /// [`DeribitFixError::MessageRejected`], both with the exchange's Text
+    /// (58). A quote failing [`MassQuote::validate`] is refused before it is
+    /// sent.
+    pub async fn send_mass_quote(&mut self, quote: &MassQuote) -> Result<()> {
+        quote.validate()?;
+        let msg_seq_num = self.send(quote).await?;
+        let quote_id = &quote.quote_id;
+        self.await_response(&format!("mass quote {quote_id}"), |message| {
+            if let Some(error) = reject_error_of(message, msg_seq_num) {
+                return Err(error);
+            }
+            if message.msg_type() != Some(MsgType::MassQuoteAcknowledgement)
+                || message.get_field(tags::QUOTE_ID) != Some(quote_id)
+            {
+                return Ok(None);
+            }
+            MassQuoteAcknowledgement::reject_error(message).map_or(Ok(Some(())), Err)
+        })
+        .await
+    }
+
+    /// Cancel every quote of the session and wait for the acknowledgement
+    ///
+    /// Sends a Quote Cancel (Z) with QuoteCancelType (298) = 5 (cancel all)
+    /// and waits for the Mass Quote Acknowledgement (b) echoing its QuoteID
+    /// (117). A rejection is returned as [`DeribitFixError::QuoteRejected`]
+    /// and a Reject (3) or Business Message Reject (j) of the cancel as
+    /// [`DeribitFixError::MessageRejected`].
+    pub async fn cancel_all_quotes(&mut self) -> Result<()> {
+        let cancel = QuoteCancel::cancel_all(self.request_ids.next("QUOTE_CANCEL"));
+        info!("Cancelling all quotes with {}", cancel.quote_id);
+        let msg_seq_num = self.send(&cancel).await?;
+        let quote_id = &cancel.quote_id;
+        self.await_response(&format!("quote cancel {quote_id}"), |message| {
+            if let Some(error) = reject_error_of(message, msg_seq_num) {
+                return Err(error);
+            }
+            if message.msg_type() != Some(MsgType::MassQuoteAcknowledgement)
+                || message.get_field(tags::QUOTE_ID) != Some(quote_id)
+            {
+                return Ok(None);
+            }
+            MassQuoteAcknowledgement::reject_error(message).map_or(Ok(Some(())), Err)
+        })
+        .await
+    }
+
+    /// Pull every quote and cancel every open order, for a clean shutdown
+    ///
+    /// Quotes are cancelled first with [`cancel_all_quotes`](Self::cancel_all_quotes),
+    /// then the orders with an Order Mass Cancel Request (q) of
+    /// MassCancelRequestType (530) = 7 (all orders), whose Order Mass Cancel
+    /// Report (r) is returned. Each step waits for its confirmation and the
+    /// first failure stops the cleanup.
+    pub async fn cancel_all_quotes_and_orders(&mut self) -> Result<OrderMassCancelReport> {
+        self.cancel_all_quotes().await?;
+        match self.cancel(CancelTarget::AllOrders).await? {
+            CancelReport::Mass(report) => Ok(report),
+            CancelReport::Order(_) | CancelReport::Withdrawn => Err(DeribitFixError::Session(
+                "Mass cancel answered with a single order report".to_string(),
+            )),
+        }
+    }
+
+    /// Process incoming messages until `matcher` accepts one
+    ///
+    /// `matcher` returns `Ok(Some(..))` to finish with a response, `Ok(None)`
+    /// to keep waiting or an error to give up. Every message is processed by
+    /// the session as usual. Gives up with a timeout at the deadline of the
+    /// [request options](Self::set_request_options), or after the configured
+    /// request timeout, and with [`DeribitFixError::Cancelled`] when their
+    /// cancel token is cancelled.
+    pub async fn await_response<T>(
+        &mut self,
+        description: &str,
+        matcher: impl FnMut(&FixMessage) -> Result<Option<T>>,
+    ) -> Result<T> {
+        self.await_response_within(description, self.config.request_timeout, matcher)
+            .await
+    }
+
+    /// Process incoming messages until `matcher` accepts one, giving up
+    /// after `timeout` unless the request options set a deadline
+    async fn await_response_within<T>(
+        &mut self,
+        description: &str,
+        timeout: std::time::Duration,
+        mut matcher: impl FnMut(&FixMessage) -> Result<Option<T>>,
+    ) -> Result<T> {
+        let options = self.request_options.clone();
+        let clock = self.config.clock.clone();
+        let deadline = options.deadline.unwrap_or_else(|| clock.now() + timeout);
+        loop {
+            options.check_cancelled(description)?;
+            let remaining = deadline.saturating_duration_since(clock.now());
+            if remaining.is_zero() {
+                break;
+            }
+            let max_wait = match options.cancel_token {
+                Some(_) => remaining.min(CANCEL_POLL_INTERVAL),
+                None => remaining,
+            };
+            // The clock decides when the deadline passes, the transport only
+            // bounds how long a single read may block
+            let incoming = tokio::select! {
+                incoming = self.wait_incoming(max_wait) => incoming,
+                () = clock.sleep_until(deadline) => continue,
+            };
+            let Some(message) = self.process_incoming(incoming).await? else {
+                clock.sleep(READ_RETRY_INTERVAL.min(max_wait)).await;
+                continue;
+            };
+            if let Some(response) = matcher(&message)? {
+                return Ok(response);
+            }
+        }
+
+        Err(DeribitFixError::Timeout(match options.deadline {
+            Some(_) => format!("No response received for {description} before its deadline"),
+            None => format!("No response received for {description} within {timeout:?}"),
+        }))
+    }
+
+    /// Set the deadline and cancellation of the responses awaited by
+    /// requests, such as [`cancel`](Self::cancel) or [`ping`](Self::ping)
+    ///
+    /// The options apply until they are replaced.
+    pub fn set_request_options(&mut self, options: RequestOptions) {
+        self.request_options = options;
+    }
+
+    /// Get the deadline and cancellation of awaited responses
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.request_options
+    }
+
+    /// Check whether `message` completes the cancel of `target`
+    fn match_cancel_report(
+        target: &CancelTarget,
+        mass_cancel_id: Option<&str>,
+        message: &FixMessage,
+    ) -> Result<Option<CancelReport>> {
+        match message.msg_type() {
+            Some(MsgType::ExecutionReport) if !target.is_mass_cancel() => {
+                // Reports of unrelated orders need not be parseable
+                Ok(ExecutionReport::from_fix_message(message)
+                    .ok()
+                    .filter(|report| target.is_confirmed_by(report))
+                    .map(|report| CancelReport::Order(Box::new(report))))
+            }
+            Some(MsgType::OrderCancelReject) => {
+                let Some(order_id) = [tags::CL_ORD_ID, tags::ORIG_CL_ORD_ID]
+                    .into_iter()
+                    .filter_map(|tag| message.get_field(tag))
+                    .find(|id| target.matches_id(id))
+                else {
+                    return Ok(None);
+                };
+                Err(OrderCancelReject::from_fix_message(message)?.into_error(order_id.clone()))
+            }
+            Some(MsgType::OrderMassCancelReport)
+                if mass_cancel_id.is_some()
+                    && message.get_field(tags::CL_ORD_ID).map(String::as_str) == mass_cancel_id =>
+            {
+                Ok(Some(CancelReport::Mass(
+                    OrderMassCancelReport::from_fix_message(message)?,
+                )))
+            }
+            _ => Ok(None),
+        }
     }
 
     /// Subscribe to market data
     pub async fn subscribe_market_data(&mut self, symbol: String) -> Result<()> {
+        self.request_market_data(symbol, book_entry_types(), 0, false)
+            .await?;
+        Ok(())
+    }
+
+    /// Subscribe to the MDEntryType (269) values `entry_types` of an
+    /// instrument
+    ///
+    /// Each call is a separate Market Data Request (V) with its own MDReqID
+    /// (262), so an instrument can be subscribed at once for its book and
+    /// for its trades; cancel each with
+    /// [`unsubscribe_market_data`](Self::unsubscribe_market_data) by its
+    /// MDReqID. Only one subscription per instrument may carry bids or
+    /// offers, as they all feed the same order book; use
+    /// [`set_market_depth`](Self::set_market_depth) to change it. `depth` is
+    /// the MarketDepth (264), 0 for the full book. Returns the MDReqID of the
+    /// subscription.
+    pub async fn subscribe_market_data_entries(
+        &mut self,
+        symbol: &str,
+        entry_types: &[MdEntryType],
+        depth: u32,
+    ) -> Result<String> {
+        if entry_types.is_empty() {
+            return Err(DeribitFixError::MessageConstruction(
+                "A market data subscription needs at least one entry type".to_string(),
+            ));
+        }
+        let has_book = entry_types
+            .iter()
+            .any(|entry_type| matches!(entry_type, MdEntryType::Bid | MdEntryType::Offer));
+        if has_book && let Some(existing) = self.md_subscriptions.book_for_symbol(symbol) {
+            return Err(DeribitFixError::Session(format!(
+                "The book of {symbol} is already subscribed with ID {}",
+                existing.md_req_id
+            )));
+        }
+        self.request_market_data(symbol.to_string(), entry_types.to_vec(), depth, false)
+            .await
+    }
+
+    /// Subscribe to the best bid and offer of an instrument only
+    ///
+    /// Requests MarketDepth (264) = 1 and keeps a [`TopOfBook`] instead of an
+    /// [`OrderBook`]; every change is published as
+    /// [`SessionEvent::TopOfBook`]. Any existing book subscription of the
+    /// instrument is replaced; subscriptions of other entry types are kept.
+    /// Returns the MDReqID (262) of the subscription.
+    pub async fn subscribe_top_of_book(&mut self, symbol: &str) -> Result<String> {
+        self.cancel_book_subscription(symbol).await?;
+        self.request_market_data(symbol.to_string(), book_entry_types(), 1, true)
+            .await
+    }
+
+    /// Get the best bid and offer of a top-of-book subscription
+    pub fn top_of_book(&self, symbol: &str) -> Option<&TopOfBook> {
+        self.top_of_books.get(symbol)
+    }
+
+    /// Subscribe to the trades of the account, optionally of one instrument
+    ///
+    /// Sends a Trade Capture Report Request (AD) with
+    /// SubscriptionRequestType (263) = 1 and waits for its Trade Capture
+    /// Report Request Ack (AQ). Every Trade Capture Report (AE) of the
+    /// subscription is then delivered on the returned stream until
+    /// [`unsubscribe_trades`](Self::unsubscribe_trades). A rejection is
+    /// returned as [`DeribitFixError::TradeCaptureRejected`] with the
+    /// exchange's Text (58).
+    pub async fn subscribe_trades(&mut self, symbol: Option<&str>) -> Result<TradeStream> {
+        let trade_request_id = self.request_ids.next("TCR");
+        let request = match symbol {
+            Some(symbol) => {
+                TradeCaptureReportRequest::for_symbol(trade_request_id.clone(), symbol.to_string())
+            }
+            None => TradeCaptureReportRequest::all_trades(trade_request_id.clone()),
+        }
+        .with_subscription_type(TradeSubscriptionRequestType::SnapshotPlusUpdates);
+        // Reports may follow the acknowledgement in the same read
+        let stream = self.trade_streams.subscribe(&trade_request_id);
+        if let Err(e) = self.await_trade_capture_ack(&request).await {
+            self.trade_streams.remove(&trade_request_id);
+            return Err(e);
+        }
+        info!("Subscribed to own trades with ID: {}", trade_request_id);
+        Ok(stream)
+    }
+
+    /// Cancel the trade subscription `trade_request_id`, closing its stream
+    ///
+    /// Sends a Trade Capture Report Request (AD) with
+    /// SubscriptionRequestType (263) = 2 for the subscription.
+    pub async fn unsubscribe_trades(&mut self, trade_request_id: &str) -> Result<()> {
+        if !self.trade_streams.contains(trade_request_id) {
+            return Err(DeribitFixError::Session(format!(
+                "No trade subscription {trade_request_id}"
+            )));
+        }
+        let request = TradeCaptureReportRequest::all_trades(trade_request_id.to_string())
+            .with_subscription_type(TradeSubscriptionRequestType::DisablePrevious);
+        self.send(&request).await?;
+        info!("Unsubscribed own trades {}", trade_request_id);
+        self.trade_streams.remove(trade_request_id);
+        Ok(())
+    }
+
+    /// Send a Trade Capture Report Request (AD) and wait for its Trade
+    /// Capture Report Request Ack (AQ)
+    async fn await_trade_capture_ack(&mut self, request: &TradeCaptureReportRequest) -> Result<()> {
+        let msg_seq_num = self.send(request).await?;
+        let trade_request_id = &request.trade_request_id;
+        self.await_response(
+            &format!("trade capture request {trade_request_id}"),
+            |message| {
+                if let Some(error) = reject_error_of(message, msg_seq_num) {
+                    return Err(error);
+                }
+                if message.msg_type() != Some(MsgType::TradeCaptureReportRequestAck)
+                    || message.get_field(tags::TRADE_REQUEST_ID) != Some(trade_request_id)
+                {
+                    return Ok(None);
+                }
+                TradeCaptureReportRequestAck::from_fix_message(message)?
+                    .reject_error()
+                    .map_or(Ok(Some(())), Err)
+            },
+        )
+        .await
+    }
+
+    /// Subscribe to `entry_types` of the full book (depth 0) or the top
+    /// `depth` levels of an instrument, returning the MDReqID (262) of the
+    /// subscription
+    async fn request_market_data(
+        &mut self,
+        symbol: String,
+        entry_types: Vec<MdEntryType>,
+        depth: u32,
+        top_of_book: bool,
+    ) -> Result<String> {
         info!("Subscribing to market data for: {}", symbol);
 
-        let request_id = format!("MDR_{}", gen_id());
+        let request_id = self.request_ids.next("MDR");
 
-        let market_data_request = MessageBuilder::new()
+        let mut builder = MessageBuilder::new()
             .msg_type(MsgType::MarketDataRequest)
             .sender_comp_id(self.config.sender_comp_id.clone())
             .target_comp_id(self.config.target_comp_id.clone())
             .msg_seq_num(self.outgoing_seq_num)
-            .field(262, request_id.clone()) // MDReqID
-            .field(263, "1".to_string()) // SubscriptionRequestType (1 = Snapshot + Updates)
-            .field(264, "0".to_string()) // MarketDepth (0 = Full Book)
-            .field(267, "2".to_string()) // NoMDEntryTypes
-            .field(269, "0".to_string()) // MDEntryType (0 = Bid)
-            .field(269, "1".to_string()) // MDEntryType (1 = Offer)
-            .field(146, "1".to_string()) // NoRelatedSym
-            .field(55, symbol.clone()) // Symbol
+            .field(tags::MD_REQ_ID, request_id.clone())
+            .field(tags::SUBSCRIPTION_REQUEST_TYPE, "1".to_string()) // SubscriptionRequestType (1 = Snapshot + Updates)
+            .field(tags::MARKET_DEPTH, depth.to_string()) // MarketDepth (0 = Full Book)
+            .field(tags::NO_MD_ENTRY_TYPES, entry_types.len().to_string());
+        for entry_type in &entry_types {
+            builder = builder.push_field(tags::MD_ENTRY_TYPE, i32::from(*entry_type).to_string());
+        }
+        let market_data_request = builder
+            .field(tags::NO_RELATED_SYM, "1".to_string())
+            .field(tags::SYMBOL, symbol.clone())
             .build()?;
 
         // Send the market data request
         self.send_message(market_data_request).await?;
         self.outgoing_seq_num += 1;
 
-        info!(
-            "Market data subscription request sent for symbol: {} with ID: {}",
-            symbol, request_id
+        info!(
+            "Market data subscription request sent for symbol: {} with ID: {}",
+            symbol, request_id
+        );
+        if top_of_book {
+            self.top_of_books
+                .insert(symbol.clone(), TopOfBook::new(symbol.clone()));
+        }
+        self.md_subscriptions.insert(MarketDataSubscription {
+            md_req_id: request_id.clone(),
+            symbol,
+            market_depth: depth,
+            top_of_book,
+            entry_types,
+        });
+        Ok(request_id)
+    }
+
+    /// Cancel the subscription feeding the order book of `symbol`, if any
+    async fn cancel_book_subscription(&mut self, symbol: &str) -> Result<()> {
+        if let Some(subscription) = self.md_subscriptions.book_for_symbol(symbol) {
+            let md_req_id = subscription.md_req_id.clone();
+            self.unsubscribe_market_data(&md_req_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Cancel a market data subscription by its MDReqID (262) or symbol
+    ///
+    /// Sends a Market Data Request (V) with SubscriptionRequestType (263) = 2.
+    /// A symbol stands for the book subscription of the instrument, or its
+    /// only one; other subscriptions of the instrument are kept. Cancelling
+    /// the book subscription drops the local order book, which would no
+    /// longer be kept up to date.
+    pub async fn unsubscribe_market_data(
+        &mut self,
+        md_req_id_or_symbol: &str,
+    ) -> Result<MarketDataSubscription> {
+        let subscription = self
+            .md_subscriptions
+            .find(md_req_id_or_symbol)
+            .cloned()
+            .ok_or_else(|| {
+                DeribitFixError::Session(format!(
+                    "No market data subscription for {md_req_id_or_symbol}"
+                ))
+            })?;
+
+        let mut request = MarketDataRequest::unsubscribe(subscription.md_req_id.clone());
+        request.symbols = vec![subscription.symbol.clone()];
+        self.send(&request).await?;
+
+        info!(
+            "Unsubscribed market data {} for {}",
+            subscription.md_req_id, subscription.symbol
+        );
+        self.md_subscriptions.remove(&subscription.md_req_id);
+        if subscription.has_book()
+            && self
+                .md_subscriptions
+                .book_for_symbol(&subscription.symbol)
+                .is_none()
+        {
+            self.order_books.remove(&subscription.symbol);
+        }
+        if subscription.top_of_book {
+            self.top_of_books.remove(&subscription.symbol);
+        }
+        Ok(subscription)
+    }
+
+    /// Cancel every market data subscription of an instrument
+    ///
+    /// Returns the subscriptions cancelled, none if the instrument was not
+    /// subscribed.
+    pub async fn unsubscribe_symbol(
+        &mut self,
+        symbol: &str,
+    ) -> Result<Vec<MarketDataSubscription>> {
+        let md_req_ids: Vec<String> = self
+            .md_subscriptions
+            .all_for_symbol(symbol)
+            .map(|subscription| subscription.md_req_id.clone())
+            .collect();
+        let mut cancelled = Vec::with_capacity(md_req_ids.len());
+        for md_req_id in md_req_ids {
+            cancelled.push(self.unsubscribe_market_data(&md_req_id).await?);
+        }
+        Ok(cancelled)
+    }
+
+    /// Cancel every market data and index subscription
+    ///
+    /// Sends a Market Data Request (V) with SubscriptionRequestType (263) = 2
+    /// for each active MDReqID (262). Deribit does not acknowledge
+    /// unsubscribes, so each one is complete once written; the local books
+    /// and index channels are dropped. Returns the market data subscriptions
+    /// cancelled.
+    pub async fn unsubscribe_all_market_data(&mut self) -> Result<Vec<MarketDataSubscription>> {
+        let md_req_ids: Vec<String> = self
+            .md_subscriptions
+            .iter()
+            .map(|subscription| subscription.md_req_id.clone())
+            .collect();
+        let mut cancelled = Vec::with_capacity(md_req_ids.len());
+        for md_req_id in md_req_ids {
+            cancelled.push(self.unsubscribe_market_data(&md_req_id).await?);
+        }
+        let index_symbols: Vec<String> = self.index_streams.symbols().map(str::to_string).collect();
+        for symbol in index_symbols {
+            self.unsubscribe_index(&symbol).await?;
+        }
+        let option_symbols: Vec<String> =
+            self.option_tickers.symbols().map(str::to_string).collect();
+        for symbol in option_symbols {
+            self.unsubscribe_option_ticker(&symbol).await?;
+        }
+        Ok(cancelled)
+    }
+
+    /// Change the MarketDepth (264) of the market data of an instrument
+    ///
+    /// A subscription cannot be modified in place, so any existing book
+    /// subscription is cancelled and a new one requested with `depth` levels
+    /// (0 for the full book). Subscriptions of other entry types are kept.
+    /// Returns the MDReqID (262) of the new subscription.
+    pub async fn set_market_depth(&mut self, symbol: &str, depth: u32) -> Result<String> {
+        self.cancel_book_subscription(symbol).await?;
+        self.request_market_data(symbol.to_string(), book_entry_types(), depth, false)
+            .await
+    }
+
+    /// Request the trades of `symbol`, optionally since a time and up to `limit`
+    ///
+    /// Sends a snapshot Market Data Request (V) for trade entries with
+    /// DeribitSinceTimestamp (100008) and DeribitTradeAmount (100007), at most
+    /// 1000, and returns the trades of the snapshot (W), oldest first. The
+    /// snapshot does not touch the local order book. A Market Data Request
+    /// Reject (Y) is returned as [`DeribitFixError::MarketDataRejected`].
+    pub async fn get_recent_trades(
+        &mut self,
+        symbol: &str,
+        since: Option<DateTime<Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<PublicTrade>> {
+        if let Some(limit) = limit
+            && !(1..=MAX_TRADE_HISTORY).contains(&limit)
+        {
+            return Err(DeribitFixError::MessageConstruction(format!(
+                "Trade limit must be between 1 and {MAX_TRADE_HISTORY}, got {limit}"
+            )));
+        }
+
+        let md_req_id = self.request_ids.next("TRD");
+        let mut request = MarketDataRequest::snapshot(
+            md_req_id.clone(),
+            vec![symbol.to_string()],
+            vec![MdEntryType::Trade],
+        );
+        request.trade_amount = limit.map(|limit| limit as i32);
+        request.since_timestamp = since.map(|since| since.timestamp_millis());
+
+        let snapshot = self.request_snapshot(&request, "trades").await?;
+        let mut trades: Vec<PublicTrade> = snapshot
+            .entries
+            .iter()
+            .filter_map(|entry| PublicTrade::from_md_entry(&snapshot.symbol, entry))
+            .collect();
+        trades.sort_by_key(|trade| trade.timestamp);
+        Ok(trades)
+    }
+
+    /// Request a one-off snapshot of the order book of `symbol`
+    ///
+    /// Sends a snapshot Market Data Request (V) for the bids and offers with
+    /// MarketDepth (264) `depth` (0 for the full book) and returns the book
+    /// of its snapshot (W). No subscription is registered and the local
+    /// order book of the instrument, if any, is left alone. A Market Data
+    /// Request Reject (Y) is returned as
+    /// [`DeribitFixError::MarketDataRejected`].
+    pub async fn get_order_book_snapshot(&mut self, symbol: &str, depth: u32) -> Result<OrderBook> {
+        let md_req_id = self.request_ids.next("SNAP");
+        let mut request = MarketDataRequest::snapshot(
+            md_req_id,
+            vec![symbol.to_string()],
+            vec![MdEntryType::Bid, MdEntryType::Offer],
+        );
+        request.market_depth = Some(i32::try_from(depth).map_err(|_| {
+            DeribitFixError::MessageConstruction(format!("Market depth {depth} is too large"))
+        })?);
+
+        let snapshot = self.request_snapshot(&request, "order book").await?;
+        let mut book = OrderBook::new(symbol.to_string());
+        book.apply_snapshot(&snapshot);
+        Ok(book)
+    }
+
+    /// Send a one-shot snapshot Market Data Request (V) and wait for its
+    /// snapshot, which does not reach the local books
+    async fn request_snapshot(
+        &mut self,
+        request: &MarketDataRequest,
+        description: &str,
+    ) -> Result<MarketDataSnapshotFullRefresh> {
+        let md_req_id = request.md_req_id.clone();
+        self.one_shot_requests.insert(md_req_id.clone());
+        let snapshot = self.await_snapshot(request, description).await;
+        self.one_shot_requests.remove(&md_req_id);
+        snapshot
+    }
+
+    async fn await_snapshot(
+        &mut self,
+        request: &MarketDataRequest,
+        description: &str,
+    ) -> Result<MarketDataSnapshotFullRefresh> {
+        let msg_seq_num = self.send(request).await?;
+        let md_req_id = &request.md_req_id;
+        self.await_response(&format!("{description} {md_req_id}"), |message| {
+            if let Some(error) = reject_error_of(message, msg_seq_num) {
+                return Err(error);
+            }
+            if message.get_field(tags::MD_REQ_ID) != Some(md_req_id) {
+                return Ok(None);
+            }
+            match message.msg_type() {
+                Some(MsgType::MarketDataSnapshotFullRefresh) => {
+                    MarketDataSnapshotFullRefresh::from_fix_message(message).map(Some)
+                }
+                Some(MsgType::MarketDataRequestReject) => {
+                    Err(MarketDataRequestReject::reject_error(message))
+                }
+                _ => Ok(None),
+            }
+        })
+        .await
+    }
+
+    /// Request the account summary of `currency`
+    ///
+    /// Sends a status User Request (BE) with Currency (15) and parses the
+    /// balance, margin and P/L tags of the User Response (BF). A response
+    /// reporting an unknown user or another failure is returned as
+    /// [`DeribitFixError::Session`].
+    pub async fn get_account_summary(&mut self, currency: &str) -> Result<AccountSummary> {
+        let user_request_id = self.request_ids.next("USR");
+        let request =
+            UserRequest::status_request(user_request_id.clone(), self.config.username.clone())
+                .with_currency(currency.to_string());
+        let msg_seq_num = self.send(&request).await?;
+
+        let response = self
+            .await_response(&format!("account summary {user_request_id}"), |message| {
+                if let Some(error) = reject_error_of(message, msg_seq_num) {
+                    return Err(error);
+                }
+                if message.msg_type() != Some(MsgType::UserResponse)
+                    || message.get_field(tags::USER_REQUEST_ID) != Some(&user_request_id)
+                {
+                    return Ok(None);
+                }
+                UserResponse::from_fix_message(message).map(Some)
+            })
+            .await?;
+
+        if matches!(
+            response.user_status,
+            UserStatus::UserNotRecognised | UserStatus::PasswordIncorrect | UserStatus::Other
+        ) {
+            return Err(DeribitFixError::Session(format!(
+                "Account summary request failed ({:?}): {}",
+                response.user_status,
+                response
+                    .user_status_text
+                    .as_deref()
+                    .unwrap_or("no reason given")
+            )));
+        }
+        Ok(AccountSummary::from_user_response(
+            currency.to_string(),
+            &response,
+        ))
+    }
+
+    /// Request the instruments selected by `request`
+    ///
+    /// Sends the Security List Request (x), reassembles the Security List (y)
+    /// fragments of the response and returns the instruments passing the
+    /// request filters. Deribit applies the currency and security type
+    /// filters itself; the state and ticker filters are applied here.
+    pub async fn get_instruments(
+        &mut self,
+        request: SecurityListRequest,
+    ) -> Result<Vec<SecurityInfo>> {
+        let mut securities = Vec::new();
+        self.stream_instruments(request, |batch, _| securities.extend(batch))
+            .await?;
+        Ok(securities)
+    }
+
+    /// Request the instruments selected by `request`, handing the
+    /// instruments of each Security List (y) fragment to `on_batch` as it
+    /// arrives, with the progress of the response
+    ///
+    /// The first fragment is awaited for the request timeout, each next one
+    /// for the [`fragment_timeout`](DeribitFixConfig::fragment_timeout), and
+    /// a [`SessionEvent::SecurityListProgress`] is published per fragment.
+    /// The instruments are filtered as in
+    /// [`get_instruments`](Self::get_instruments). Returns the progress of
+    /// the completed response.
+    pub async fn stream_instruments(
+        &mut self,
+        request: SecurityListRequest,
+        mut on_batch: impl FnMut(Vec<SecurityInfo>, &SecurityListProgress),
+    ) -> Result<SecurityListProgress> {
+        let msg_seq_num = self.send(&request).await?;
+
+        let security_req_id = request.security_req_id.clone();
+        let mut assembler = SecurityListAssembler::new(security_req_id.clone());
+        while !assembler.is_complete() {
+            let (description, timeout) = match assembler.fragments() {
+                0 => (
+                    format!("security list {security_req_id}"),
+                    self.config.request_timeout,
+                ),
+                fragments => (
+                    format!(
+                        "fragment {} of security list {security_req_id}",
+                        fragments + 1
+                    ),
+                    self.config.fragment_timeout,
+                ),
+            };
+            let fragment = self
+                .await_response_within(&description, timeout, |message| {
+                    if let Some(error) = reject_error_of(message, msg_seq_num) {
+                        return Err(error);
+                    }
+                    if message.msg_type() != Some(MsgType::SecurityList)
+                        || message.get_field(tags::SECURITY_REQ_ID) != Some(&security_req_id)
+                    {
+                        return Ok(None);
+                    }
+                    SecurityList::from_fix_message(message).map(Some)
+                })
+                .await?;
+            assembler.push(fragment)?;
+            let progress = assembler.progress();
+            let batch = assembler
+                .take_securities()
+                .into_iter()
+                .filter(|security| request.matches(security))
+                .collect();
+            on_batch(batch, &progress);
+            self.emit_event(SessionEvent::SecurityListProgress(progress));
+        }
+        debug!(
+            "Security list {} received in {} fragment(s)",
+            security_req_id,
+            assembler.fragments()
         );
-        Ok(())
+        Ok(assembler.progress())
     }
 
     /// Request positions asynchronously
     pub async fn request_positions(&mut self) -> Result<Vec<Position>> {
-        use std::time::{Duration, Instant};
         use tracing::{debug, info, warn};
 
         info!("Requesting positions");
 
-        let request_id = format!("POS_{}", gen_id());
+        let request_id = self.request_ids.next("POS");
 
         // Create typed position request
         let position_request = RequestForPositions::all_positions(request_id.clone())
-            .with_clearing_date(Utc::now().format("%Y%m%d").to_string());
+            .with_clearing_date(self.config.clock.utc_now().format("%Y%m%d").to_string());
 
         // Build the FIX message
         let fix_message = position_request.to_fix_message(
@@ -451,38 +2410,69 @@ impl Session {
 
         // Collect position reports with correlation by PosReqID
         let mut positions = Vec::new();
-        let timeout = Duration::from_secs(30); // 30 second timeout
-        let start_time = Instant::now();
+        let clock = self.config.clock.clone();
+        let start_time = clock.now();
+        let options = self.request_options.clone();
+        let deadline = options
+            .deadline
+            .unwrap_or(start_time + POSITION_REQUEST_TIMEOUT);
 
         loop {
-            // Check for timeout
-            if start_time.elapsed() > timeout {
-                warn!("Position request timed out after {:?}", timeout);
+            options.check_cancelled("position request")?;
+
+            // Stop at the deadline, or a while after the first positions
+            // arrived; there is no end-of-transmission signal to wait for
+            let until = if positions.is_empty() {
+                deadline
+            } else {
+                deadline.min(start_time + POSITION_COLLECTION_TIME)
+            };
+            let remaining = until.saturating_duration_since(clock.now());
+            if remaining.is_zero() {
+                if positions.is_empty() {
+                    warn!(
+                        "Position request timed out after {:?}",
+                        clock.elapsed(start_time)
+                    );
+                } else {
+                    debug!(
+                        "Received {} positions, stopping collection",
+                        positions.len()
+                    );
+                }
                 break;
             }
+            let max_wait = match options.cancel_token {
+                Some(_) => remaining.min(CANCEL_POLL_INTERVAL),
+                None => remaining,
+            };
 
             // Receive and process messages
-            match self.receive_and_process_message().await {
+            let incoming = tokio::select! {
+                incoming = self.wait_incoming(max_wait) => incoming,
+                () = clock.sleep_until(until) => continue,
+            };
+            match self.process_incoming(incoming).await {
                 Ok(Some(message)) => {
                     // Check if this is a PositionReport message
-                    if let Some(msg_type_str) = message.get_field(35)
+                    if let Some(msg_type_str) = message.get_field(tags::MSG_TYPE)
                         && msg_type_str == "AP"
                     {
                         // PositionReport
                         // Check if this position report matches our request ID
-                        if let Some(pos_req_id) = message.get_field(710) {
+                        if let Some(pos_req_id) = message.get_field(tags::POS_REQ_ID) {
                             if pos_req_id == &request_id {
                                 debug!("Received PositionReport for request: {}", request_id);
 
                                 // Check if this is an empty position report (no instrument name)
-                                if message.get_field(55).is_none() {
+                                if message.get_field(tags::SYMBOL).is_none() {
                                     info!(
                                         "Received empty position report - indicates no active positions for this request"
                                     );
                                     debug!(
                                         "Empty PositionReport details: PosReqID={}, PosMaintRptID={:?}",
                                         request_id,
-                                        message.get_field(721)
+                                        message.get_field(tags::POS_MAINT_RPT_ID)
                                     );
                                     // This is an end-of-positions marker indicating no positions, continue waiting
                                     continue;
@@ -509,24 +2499,14 @@ impl Session {
                 }
                 Ok(None) => {
                     // No message received, continue loop
-                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    clock.sleep(READ_RETRY_INTERVAL.min(remaining)).await;
                 }
                 Err(e) => {
                     warn!("Error receiving message: {}", e);
                     // Continue trying to receive more messages
-                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    clock.sleep(READ_ERROR_BACKOFF.min(remaining)).await;
                 }
             }
-
-            // For now, we'll break after receiving some positions or after a reasonable time
-            // In a real implementation, we might wait for an end-of-transmission signal
-            if !positions.is_empty() && start_time.elapsed() > Duration::from_secs(5) {
-                debug!(
-                    "Received {} positions, stopping collection",
-                    positions.len()
-                );
-                break;
-            }
         }
 
         info!(
@@ -539,8 +2519,16 @@ impl Session {
     /// Generate authentication data according to Deribit FIX specification
     /// Returns (raw_data, base64_password_hash)
     pub fn generate_auth_data(&self, access_secret: &str) -> Result<(String, String)> {
-        // Generate timestamp (strictly increasing integer in milliseconds)
-        let timestamp = Utc::now().timestamp_millis();
+        // Generate timestamp (strictly increasing integer in milliseconds),
+        // following the server clock
+        let now = (self.config.clock.utc_now() + self.clock_offset).timestamp_millis();
+        let previous = self
+            .last_auth_timestamp
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(now.max(last + 1))
+            })
+            .unwrap_or_default();
+        let timestamp = now.max(previous + 1);
 
         // Generate random nonce (at least 32 bytes as recommended by Deribit)
         let mut nonce_bytes = vec![0u8; 32];
@@ -558,9 +2546,6 @@ impl Session {
 
         debug!("Auth Data at Timestamp: {}", timestamp);
         trace!("Nonce length: {} bytes", nonce_bytes.len());
-        trace!("Nonce (base64): {}", nonce_b64);
-        trace!("RawData: {}", raw_data);
-        trace!("Access secret: {}", access_secret);
         trace!("Auth data length: {} bytes", auth_data.len());
 
         let mut hasher = Sha256::new();
@@ -568,8 +2553,6 @@ impl Session {
         let hash_result = hasher.finalize();
         let password_hash = BASE64_STANDARD.encode(hash_result);
 
-        debug!("Password hash: {}", password_hash);
-
         Ok((raw_data, password_hash))
     }
 
@@ -587,17 +2570,27 @@ impl Session {
         self.state
     }
 
-    /// Set session state (for testing)
+    /// Set session state without checking the transition (for testing)
     pub fn set_state(&mut self, state: SessionState) {
         self.state = state;
     }
 
+    /// Move the session to `next`, failing on an illegal transition
+    fn transition(&mut self, next: SessionState) -> Result<()> {
+        self.state = self.state.transition(next)?;
+        debug!("Session state is now {:?}", next);
+        Ok(())
+    }
+
     /// Process incoming FIX message
     async fn process_message(&mut self, message: &FixMessage) -> Result<()> {
-        debug!("Processing FIX message: {:?}", message);
+        debug!("Processing FIX message: {}", self.printer.render(message));
 
         // Get message type
-        let msg_type_str = message.get_field(35).unwrap_or(&String::new()).clone();
+        let msg_type_str = message
+            .get_field(tags::MSG_TYPE)
+            .unwrap_or(&String::new())
+            .clone();
 
         // Skip messages with empty or missing message type
         if msg_type_str.is_empty() {
@@ -605,30 +2598,184 @@ impl Session {
             return Ok(());
         }
 
-        let msg_type = MsgType::from_str(&msg_type_str).map_err(|_| {
-            DeribitFixError::MessageParsing(format!("Unknown message type: {msg_type_str}"))
-        })?;
+        // Message types added by the exchange later are rejected, not fatal
+        let msg_type = match MsgType::from_str(&msg_type_str) {
+            Ok(MsgType::Unknown(_)) | Err(_) => {
+                warn!("Rejecting message of unknown type {}", msg_type_str);
+                let ref_seq_num = message.msg_seq_num().unwrap_or(self.incoming_seq_num);
+                self.incoming_seq_num += 1;
+                self.send(&Reject::new_invalid_msg_type(ref_seq_num, msg_type_str))
+                    .await?;
+                return Ok(());
+            }
+            Ok(msg_type) => msg_type,
+        };
+        if self.config.strict_session_state && !self.state.allows_incoming(&msg_type) {
+            return Err(DeribitFixError::Session(format!(
+                "{msg_type:?} received while the session is {:?}",
+                self.state
+            )));
+        }
+
+        if self.config.capability_probe && msg_type != MsgType::Logon {
+            self.capabilities.record_message(message);
+        }
+        if let Some(reject) = rate_limit_reject(message) {
+            self.on_rate_limited(reject);
+        }
 
         match msg_type {
             MsgType::Logon => {
                 info!("Received logon response");
-                self.state = SessionState::LoggedOn;
+                self.transition(SessionState::LoggedOn)?;
+                // Test Requests of a previous connection will not be answered
+                self.pending_pings.clear();
+                self.logon_retry.logged_on();
+                // The application gets a full window from the logon
+                let now = self.config.clock.now();
+                if let Some(switch) = &mut self.dead_mans_switch {
+                    switch.keep_alive(now);
+                }
+                self.capabilities
+                    .record_logon(message, self.config.clock.utc_now());
+                let status = CancelOnDisconnectStatus {
+                    requested: self.config.cancel_on_disconnect,
+                    confirmed: message
+                        .get_field(tags::CANCEL_ON_DISCONNECT)
+                        .map(|value| value == "Y"),
+                };
+                // A session is without cancel-on-disconnect until a logon enables it
+                let was_active = self
+                    .cancel_on_disconnect
+                    .replace(status)
+                    .is_some_and(|previous| previous.is_active());
+                if status.is_active() != was_active {
+                    info!("Cancel on disconnect active: {}", status.is_active());
+                    self.emit_event(SessionEvent::CancelOnDisconnect(status));
+                }
+                self.capability_probe = None;
+                if self.config.capability_probe {
+                    self.capability_probe = Some(self.send_test_request().await?);
+                }
+                if let Some(retry) = self.maintenance_retry.take() {
+                    let downtime = self.config.clock.elapsed(retry.started);
+                    info!(
+                        "Service resumed after {:?} and {} logon attempts",
+                        downtime, retry.attempts
+                    );
+                    self.emit_event(SessionEvent::ServiceResumed {
+                        attempts: retry.attempts,
+                        downtime,
+                    });
+                }
+                if let Some(event) = self.market_state.end_maintenance() {
+                    info!("Maintenance ended, resuming order submission");
+                    self.emit_event(SessionEvent::MarketState(event));
+                    self.release_queued_orders().await?;
+                }
+                self.recover_order_groups().await?;
             }
             MsgType::Logout => {
-                info!("Received logout message");
-                self.state = SessionState::Disconnected;
+                if self.handle_logout(message).await? {
+                    // Logon already set up the sequence numbers of the new connection
+                    return Ok(());
+                }
+            }
+            MsgType::SecurityStatus => {
+                self.handle_security_status(message).await?;
             }
             MsgType::Heartbeat => {
                 debug!("Received heartbeat");
+                self.handle_heartbeat(message);
+            }
+            MsgType::SequenceReset => {
+                self.handle_sequence_reset(message)?;
+                // NewSeqNo already points at the next expected message
+                return Ok(());
             }
             MsgType::TestRequest => {
                 debug!("Received test request, sending heartbeat response");
-                let test_req_id = message.get_field(112);
+                let test_req_id = message.get_field(tags::TEST_REQ_ID);
                 self.send_heartbeat(test_req_id.cloned()).await?;
             }
+            MsgType::MarketDataSnapshotFullRefresh | MsgType::MarketDataIncrementalRefresh
+                if self.apply_top_of_book(message) => {}
+            MsgType::MarketDataSnapshotFullRefresh => {
+                self.handle_market_data_snapshot(message).await?;
+            }
+            MsgType::MarketDataIncrementalRefresh => {
+                self.handle_market_data_incremental(message).await?;
+            }
+            MsgType::MarketDataRequestReject => {
+                if let Some(md_req_id) = message.get_field(tags::MD_REQ_ID)
+                    && let Some(subscription) = self.md_subscriptions.remove(md_req_id)
+                {
+                    warn!(
+                        "Market data request {} for {} rejected: {:?}",
+                        md_req_id,
+                        subscription.symbol,
+                        message.get_field(tags::TEXT)
+                    );
+                } else if let Some(md_req_id) = message.get_field(tags::MD_REQ_ID)
+                    && let Some(symbol) = self.index_streams.symbol_of(md_req_id)
+                {
+                    warn!(
+                        "Index subscription {} for {} rejected: {:?}",
+                        md_req_id,
+                        symbol,
+                        message.get_field(tags::TEXT)
+                    );
+                    let symbol = symbol.to_string();
+                    self.index_streams.remove(&symbol);
+                } else if let Some(md_req_id) = message.get_field(tags::MD_REQ_ID)
+                    && let Some(symbol) = self.option_tickers.symbol_of(md_req_id)
+                {
+                    warn!(
+                        "Option ticker subscription {} for {} rejected: {:?}",
+                        md_req_id,
+                        symbol,
+                        message.get_field(tags::TEXT)
+                    );
+                    let symbol = symbol.to_string();
+                    self.option_tickers.remove(&symbol);
+                }
+            }
+            MsgType::QuoteRequestReject => {
+                // The request's PendingResponse returns the typed error
+                warn!("{}", QuoteRequestReject::reject_error(message));
+            }
+            MsgType::TradeCaptureReport => match TradeCaptureReport::from_fix_message(message) {
+                Ok(report) => {
+                    let delivered = self.trade_streams.publish(&report);
+                    debug!(
+                        "Trade report {} delivered to {} streams",
+                        report.trade_report_id, delivered
+                    );
+                }
+                Err(e) => warn!("Dropping unparseable trade report: {}", e),
+            },
+            MsgType::TradeCaptureReportRequestAck => {
+                if let Ok(ack) = TradeCaptureReportRequestAck::from_fix_message(message)
+                    && ack.is_rejected()
+                    && self.trade_streams.remove(&ack.trade_request_id)
+                {
+                    warn!(
+                        "Trade subscription {} rejected: {:?}",
+                        ack.trade_request_id, ack.text
+                    );
+                }
+            }
             MsgType::ExecutionReport => {
                 debug!("Received ExecutionReport: {:?}", message);
-                // ExecutionReport processing - let the client handle the details
+                self.handle_execution_report(message).await?;
+            }
+            MsgType::SecurityList | MsgType::SecurityDefinition => {
+                let combos = self.combos.apply(message);
+                if combos > 0 {
+                    debug!("Registered {} combo instruments", combos);
+                }
+                let instruments = self.instruments.apply(message);
+                debug!("Registered {} instruments", instruments);
             }
             MsgType::PositionReport => {
                 debug!("Received PositionReport: {:?}", message);
@@ -636,7 +2783,7 @@ impl Session {
             }
             MsgType::Reject => {
                 error!("Received Reject message: {:?}", message);
-                // Reject message processing
+                self.capabilities.record_reject(message);
             }
             _ => {
                 debug!("Received message type: {:?}", msg_type);
@@ -647,20 +2794,824 @@ impl Session {
         Ok(())
     }
 
+    /// Handle a Logout (5) from the counterparty
+    ///
+    /// Unsolicited logouts with a non-fatal reason are followed by a re-logon
+    /// when `relogon_after_logout` is enabled. A logout for exchange
+    /// maintenance instead moves the session to [`SessionState::Maintenance`],
+    /// from which the logon is retried every `maintenance_retry_interval`.
+    /// Returns whether a re-logon was performed.
+    async fn handle_logout(&mut self, message: &FixMessage) -> Result<bool> {
+        let requested = self.state == SessionState::LogoutSent;
+        let logon_rejected = self.state == SessionState::LogonSent;
+        let reason = if requested {
+            LogoutReason::Requested
+        } else {
+            LogoutReason::from_fix_message(message)
+        };
+        let text = message.get_field(tags::TEXT).cloned();
+        info!("Received logout ({:?}): {:?}", reason, text);
+        self.transition(SessionState::Disconnected)?;
+
+        let maintenance = !reason.is_fatal()
+            && (reason == LogoutReason::Maintenance
+                || text.as_deref().is_some_and(is_maintenance_text));
+        if let Some(text) = &text
+            && maintenance
+            && !self.market_state.in_maintenance()
+        {
+            warn!("Exchange maintenance announced: {}", text);
+            let event = self.market_state.start_maintenance(text.clone());
+            self.emit_event(SessionEvent::MarketState(event));
+        }
+        if reason.is_fatal() {
+            error!("Logout is fatal, not logging on again: {:?}", text);
+        }
+
+        let retry_after_maintenance = maintenance && self.config.relogon_after_logout;
+        if logon_rejected
+            && !retry_after_maintenance
+            && (reason.is_fatal() || self.logon_retry.is_enabled())
+        {
+            return self.retry_rejected_logon(reason, text).await;
+        }
+        let relogon = self.config.relogon_after_logout && reason.allows_relogon();
+        self.emit_event(SessionEvent::LoggedOut {
+            reason,
+            text,
+            relogon: relogon || retry_after_maintenance,
+        });
+        if retry_after_maintenance {
+            self.schedule_maintenance_retry()?;
+            return Ok(false);
+        }
+        if !relogon {
+            return Ok(false);
+        }
+
+        self.incoming_seq_num += 1;
+        self.relogon().await?;
+        Ok(true)
+    }
+
+    /// Apply the logon retry policy to a Logon answered with a Logout
+    ///
+    /// Returns whether the logon was sent again.
+    async fn retry_rejected_logon(
+        &mut self,
+        reason: LogoutReason,
+        text: Option<String>,
+    ) -> Result<bool> {
+        let action = self.logon_retry.rejected(reason);
+        self.emit_event(SessionEvent::LoggedOut {
+            reason,
+            text: text.clone(),
+            relogon: matches!(action, LogonRetryAction::Retry { .. }),
+        });
+        match action {
+            LogonRetryAction::Retry { attempt, delay } => {
+                info!("Logon rejected, retry {} in {:?}", attempt, delay);
+                self.emit_event(SessionEvent::LogonRetryScheduled { attempt, delay });
+                self.incoming_seq_num += 1;
+                self.config.clock.sleep(delay).await;
+                self.relogon().await?;
+                Ok(true)
+            }
+            LogonRetryAction::Exhausted { attempts } => {
+                error!("Logon still rejected after {} retries", attempts);
+                self.emit_event(SessionEvent::LogonRetriesExhausted { attempts });
+                Ok(false)
+            }
+            LogonRetryAction::CircuitOpen => {
+                error!("Credentials rejected, logon circuit open: {:?}", text);
+                self.emit_event(SessionEvent::LogonCircuitOpen { text });
+                Ok(false)
+            }
+        }
+    }
+
+    /// Whether logons are refused because the server rejected the credentials
+    pub fn is_logon_circuit_open(&self) -> bool {
+        self.logon_retry.is_circuit_open()
+    }
+
+    /// Allow logging on again after the credentials were rejected
+    ///
+    /// Also restarts the count of logon retries.
+    pub fn reset_logon_circuit(&mut self) {
+        self.logon_retry.reset();
+    }
+
+    /// Wait in [`SessionState::Maintenance`] for the next logon attempt
+    fn schedule_maintenance_retry(&mut self) -> Result<()> {
+        let interval = self.config.maintenance_retry_interval;
+        let now = self.config.clock.now();
+        let retry = self.maintenance_retry.get_or_insert(MaintenanceRetry {
+            started: now,
+            next_attempt: now,
+            attempts: 0,
+        });
+        retry.next_attempt = now + interval;
+        self.transition(SessionState::Disconnected)?;
+        self.transition(SessionState::Maintenance)?;
+        info!(
+            "Exchange in maintenance, next logon attempt in {:?}",
+            interval
+        );
+        Ok(())
+    }
+
+    /// Whether losing the connection means the exchange is in maintenance
+    fn expecting_maintenance(&self) -> bool {
+        self.config.relogon_after_logout
+            && (self.maintenance_retry.is_some() || self.market_state.in_maintenance())
+    }
+
+    /// Retry the logon of a session waiting for exchange maintenance to end,
+    /// once the attempt is due
+    ///
+    /// Returns whether a Logon was sent; when the exchange cannot be reached
+    /// yet, the next attempt is scheduled.
+    async fn relogon_after_maintenance(&mut self) -> Result<bool> {
+        let Some(retry) = self.maintenance_retry else {
+            return Ok(false);
+        };
+        if self.config.clock.now() < retry.next_attempt {
+            return Ok(false);
+        }
+
+        let connection = self
+            .connection
+            .clone()
+            .ok_or_else(|| DeribitFixError::Session("No connection to reconnect".to_string()))?;
+        let attempt = retry.attempts + 1;
+        self.maintenance_retry = Some(MaintenanceRetry {
+            attempts: attempt,
+            ..retry
+        });
+        info!("Logon attempt {} after exchange maintenance", attempt);
+        self.emit_event(SessionEvent::RelogonAttempt { attempt });
+        self.transition(SessionState::Connecting)?;
+        let reconnected = connection.lock().await.reconnect().await;
+        let logged_on = match reconnected {
+            Ok(()) => self.logon().await,
+            Err(e) => Err(e),
+        };
+        match logged_on {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                warn!("Exchange still unreachable after maintenance: {}", e);
+                self.schedule_maintenance_retry()?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Record that the application is alive, arming the dead man's switch
+    pub fn keep_alive(&mut self) {
+        let now = self.config.clock.now();
+        if let Some(switch) = &mut self.dead_mans_switch {
+            switch.keep_alive(now);
+        }
+    }
+
+    /// Window of the dead man's switch, `None` when it is disabled
+    pub fn dead_mans_switch_window(&self) -> Option<std::time::Duration> {
+        self.dead_mans_switch.as_ref().map(DeadMansSwitch::window)
+    }
+
+    /// Cancel every open order if the application missed its keep-alive
+    ///
+    /// The Order Mass Cancel Request (q) is sent without waiting for its
+    /// report. Returns whether the switch fired.
+    pub async fn check_dead_mans_switch(&mut self) -> Result<bool> {
+        if !self.state.is_logged_on() {
+            return Ok(false);
+        }
+        let now = self.config.clock.now();
+        let Some(silent_for) = self
+            .dead_mans_switch
+            .as_mut()
+            .and_then(|switch| switch.check(now))
+        else {
+            return Ok(false);
+        };
+        error!(
+            "No keep-alive from the application for {:?}, cancelling all orders",
+            silent_for
+        );
+        self.emit_event(SessionEvent::DeadMansSwitchTriggered { silent_for });
+        self.send(&OrderMassCancelRequest::all_orders(
+            self.request_ids.next("DEAD_MANS_SWITCH"),
+        ))
+        .await?;
+        Ok(true)
+    }
+
+    /// Probe a silent counterparty and reconnect when it stays silent
+    ///
+    /// Does nothing unless liveness checks are configured and the session is
+    /// logged on. See [`crate::session::liveness`] for the escalation.
+    pub async fn check_liveness(&mut self) -> Result<LivenessAction> {
+        if !self.state.is_logged_on() {
+            return Ok(LivenessAction::Alive);
+        }
+        let Some(monitor) = &self.liveness else {
+            return Ok(LivenessAction::Alive);
+        };
+        let now = self.config.clock.now();
+        let heartbeat = std::time::Duration::from_secs(self.config.heartbeat_interval.into());
+        let silent_for = monitor.silent_for(now);
+        let action = monitor.check(now, heartbeat);
+        match action {
+            LivenessAction::Alive => {}
+            LivenessAction::Probe => {
+                warn!("No message received for {:?}, probing", silent_for);
+                self.send_test_request().await?;
+                if let Some(monitor) = &mut self.liveness {
+                    monitor.probed(now);
+                }
+                self.emit_event(SessionEvent::LivenessProbe { silent_for });
+            }
+            LivenessAction::Reconnect => {
+                error!(
+                    "No message received for {:?} despite a Test Request, reconnecting",
+                    silent_for
+                );
+                self.emit_event(SessionEvent::LivenessLost { silent_for });
+                self.relogon().await?;
+                let now = self.config.clock.now();
+                if let Some(monitor) = &mut self.liveness {
+                    monitor.received(now);
+                }
+            }
+        }
+        Ok(action)
+    }
+
+    /// Reconnect and log on again
+    ///
+    /// Retries up to `reconnect_attempts` times, waiting `reconnect_delay`
+    /// between attempts.
+    pub async fn relogon(&mut self) -> Result<()> {
+        let connection = self
+            .connection
+            .clone()
+            .ok_or_else(|| DeribitFixError::Session("No connection to reconnect".to_string()))?;
+        let attempts = self.config.reconnect_attempts.max(1);
+        self.transition(SessionState::Disconnected)?;
+        self.transition(SessionState::Connecting)?;
+
+        let mut attempt = 1;
+        loop {
+            info!("Re-logon attempt {}/{}", attempt, attempts);
+            self.emit_event(SessionEvent::RelogonAttempt { attempt });
+            let reconnected = connection.lock().await.reconnect().await;
+            match reconnected {
+                Ok(()) => return self.logon().await,
+                Err(e) if attempt >= attempts => {
+                    self.transition(SessionState::Disconnected)?;
+                    return Err(e);
+                }
+                Err(e) => {
+                    warn!("Reconnect attempt {} failed: {}", attempt, e);
+                    self.config.clock.sleep(self.config.reconnect_delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Apply a Sequence Reset (4) message to the expected incoming sequence number
+    ///
+    /// In GapFill mode (123=Y) the reset is only honoured when it moves the
+    /// sequence forward. In Reset mode the MsgSeqNum is ignored and NewSeqNo is
+    /// applied unconditionally, except that a decrease is rejected as a
+    /// protocol violation.
+    fn handle_sequence_reset(&mut self, message: &FixMessage) -> Result<()> {
+        let reset = SequenceReset::from_fix_message(message)?;
+        let previous_seq_num = self.incoming_seq_num;
+        let gap_fill = reset.is_gap_fill();
+
+        if reset.new_seq_no < previous_seq_num {
+            if gap_fill {
+                warn!(
+                    "Ignoring GapFill with NewSeqNo {} lower than expected {}",
+                    reset.new_seq_no, previous_seq_num
+                );
+                return Ok(());
+            }
+            return Err(DeribitFixError::Protocol(format!(
+                "SequenceReset attempted to decrease incoming sequence from {} to {}",
+                previous_seq_num, reset.new_seq_no
+            )));
+        }
+
+        info!(
+            "Applying SequenceReset ({}): incoming sequence {} -> {}",
+            if gap_fill { "GapFill" } else { "Reset" },
+            previous_seq_num,
+            reset.new_seq_no
+        );
+        self.incoming_seq_num = reset.new_seq_no;
+        self.emit_event(SessionEvent::SequenceReset {
+            previous_seq_num,
+            new_seq_no: reset.new_seq_no,
+            gap_fill,
+        });
+        Ok(())
+    }
+
+    /// Route an Execution Report (8) to the order tracker, the risk guard and
+    /// the OCO group of its order
+    ///
+    /// Cancel reports may carry the cancel request in ClOrdID (11), so the
+    /// member is also looked up by OrigClOrdID (41).
+    async fn handle_execution_report(&mut self, message: &FixMessage) -> Result<()> {
+        self.track_execution_report(message);
+        let Some(status) = message
+            .get_field(tags::ORD_STATUS)
+            .and_then(|value| value.chars().next())
+            .and_then(|value| OrderStatus::try_from(value).ok())
+        else {
+            return Ok(());
+        };
+        for tag in [tags::CL_ORD_ID, tags::ORIG_CL_ORD_ID] {
+            if let Some(id) = message.get_field(tag) {
+                self.risk_guard.apply_status(id, status);
+            }
+        }
+        let Some(cl_ord_id) = [tags::CL_ORD_ID, tags::ORIG_CL_ORD_ID]
+            .into_iter()
+            .filter_map(|tag| message.get_field(tag))
+            .find(|id| self.order_groups.group_of(id).is_some())
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let was_triggered = self
+            .order_groups
+            .group_of(&cl_ord_id)
+            .and_then(|group_id| self.order_groups.group(group_id))
+            .is_some_and(OrderGroup::is_triggered);
+        let Some(group_id) = self.order_groups.apply_status(&cl_ord_id, status) else {
+            return Ok(());
+        };
+
+        if !was_triggered
+            && self
+                .order_groups
+                .group(&group_id)
+                .is_some_and(OrderGroup::is_triggered)
+        {
+            info!("OCO group {} triggered by fill of {}", group_id, cl_ord_id);
+            self.emit_event(SessionEvent::OrderGroup(OrderGroupEvent::Triggered {
+                group_id: group_id.clone(),
+                filled_cl_ord_id: cl_ord_id,
+            }));
+            self.cancel_group_siblings(&group_id).await?;
+        }
+
+        if self.order_groups.take_if_complete(&group_id).is_some() {
+            info!("OCO group {} completed", group_id);
+            self.emit_event(SessionEvent::OrderGroup(OrderGroupEvent::Completed {
+                group_id,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Record an Execution Report in the order tracker and order index and
+    /// deliver it to the channels of its order label
+    fn track_execution_report(&mut self, message: &FixMessage) {
+        match ExecutionReport::from_fix_message(message) {
+            Ok(report) => {
+                if let Some(refill) = self.order_tracker.record_report(&report) {
+                    debug!(
+                        "Iceberg order {} refilled, {} shown of {} remaining",
+                        refill.cl_ord_id, refill.displayed_qty, refill.remaining_qty
+                    );
+                    self.emit_event(SessionEvent::IcebergRefilled(refill));
+                }
+                if let Err(e) = self.order_index.record_report(&report) {
+                    warn!(
+                        "Failed to store the owner of order {}: {}",
+                        report.order_id, e
+                    );
+                }
+                if !self.label_router.is_empty() {
+                    self.label_router.route(&report);
+                }
+            }
+            Err(e) => debug!("Execution Report not tracked: {}", e),
+        }
+    }
+
+    /// Cancel the open members of a triggered OCO group
+    async fn cancel_group_siblings(&mut self, group_id: &str) -> Result<()> {
+        let targets: Vec<(String, String)> = self
+            .order_groups
+            .group(group_id)
+            .map(|group| {
+                group
+                    .cancel_targets()
+                    .into_iter()
+                    .map(|member| (member.cl_ord_id.clone(), member.symbol.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for (cl_ord_id, symbol) in targets {
+            self.send(&OrderCancelRequest::by_cl_ord_id(cl_ord_id.clone(), symbol))
+                .await?;
+            self.emit_event(SessionEvent::OrderGroup(
+                OrderGroupEvent::SiblingCancelRequested {
+                    group_id: group_id.to_string(),
+                    cl_ord_id,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resynchronise OCO groups after logon
+    ///
+    /// Requests the status of every open member, so fills missed while
+    /// disconnected are replayed as Execution Reports, and re-sends the
+    /// sibling cancels of groups that were already triggered.
+    async fn recover_order_groups(&mut self) -> Result<()> {
+        let groups: Vec<(String, Vec<(String, String)>)> = self
+            .order_groups
+            .groups()
+            .map(|group| {
+                let open = group
+                    .members
+                    .iter()
+                    .filter(|member| !member.is_terminal())
+                    .map(|member| (member.cl_ord_id.clone(), member.symbol.clone()))
+                    .collect();
+                (group.id.clone(), open)
+            })
+            .collect();
+
+        for (group_id, open_members) in groups {
+            for (cl_ord_id, symbol) in open_members {
+                self.send(&OrderMassStatusRequest::specific_order_by_cl_ord_id(
+                    cl_ord_id,
+                    None,
+                    Some(symbol),
+                ))
+                .await?;
+            }
+            self.emit_event(SessionEvent::OrderGroup(
+                OrderGroupEvent::RecoveryRequested {
+                    group_id: group_id.clone(),
+                },
+            ));
+            self.cancel_group_siblings(&group_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Back off after a rejection for the exchange's rate limit
+    fn on_rate_limited(&mut self, reject: RateLimitReject) {
+        let allowed_rate = self
+            .throttle
+            .on_rate_limited(self.config.clock.now(), reject.clone());
+        match allowed_rate {
+            Some(rate) => warn!(
+                "Rate limit exceeded ({}), sending at most {:.1} messages per second",
+                reject.text, rate
+            ),
+            None => warn!("Rate limit exceeded ({})", reject.text),
+        }
+        self.emit_event(SessionEvent::RateLimited {
+            reject,
+            allowed_rate,
+        });
+    }
+
+    /// Apply a Market Data Snapshot/Full Refresh (W) to the local order book
+    async fn handle_market_data_snapshot(&mut self, message: &FixMessage) -> Result<()> {
+        let snapshot = match MarketDataSnapshotFullRefresh::from_fix_message(message) {
+            Ok(snapshot) => snapshot,
+            Err(e) => return self.drop_unparsable_market_data(message, e).await,
+        };
+        let received_at = self.config.clock.utc_now();
+        self.market_stats.apply_snapshot(&snapshot, received_at);
+        self.index_streams
+            .publish(&snapshot.symbol, &snapshot.entries, received_at);
+        self.option_tickers.publish(&snapshot, received_at);
+        if snapshot.md_req_id.as_ref().is_some_and(|md_req_id| {
+            self.one_shot_requests.contains(md_req_id)
+                || self.funding.is_poll(md_req_id)
+                || self.index_streams.symbol_of(md_req_id).is_some()
+                || self.option_tickers.symbol_of(md_req_id).is_some()
+                || !self.feeds_book(md_req_id)
+        }) {
+            return Ok(());
+        }
+        let symbol = snapshot.symbol.clone();
+        let order_level = self.config.order_level_books;
+        let book = self
+            .order_books
+            .entry(symbol.clone())
+            .or_insert_with(|| OrderBook::new(symbol.clone()).with_order_level(order_level));
+
+        if book.apply_snapshot(&snapshot) {
+            info!("Order book for {} rebuilt from snapshot", symbol);
+            self.emit_event(SessionEvent::BookIntegrity(BookIntegrityEvent::Rebuilt {
+                symbol: symbol.clone(),
+            }));
+        }
+        self.match_paper_orders(&symbol)
+    }
+
+    /// Whether market data of `md_req_id` belongs in the order book, which
+    /// it does unless it is of a subscription without bids or offers
+    pub(super) fn feeds_book(&self, md_req_id: &str) -> bool {
+        self.md_subscriptions
+            .get(md_req_id)
+            .is_none_or(MarketDataSubscription::has_book)
+    }
+
+    /// Apply market data of a top-of-book subscription, publishing changes
+    ///
+    /// Returns whether the message belonged to one, in which case it must not
+    /// reach the order book. One-shot snapshots and messages of the other
+    /// subscriptions of the instrument are left alone.
+    fn apply_top_of_book(&mut self, message: &FixMessage) -> bool {
+        if message.get_field(tags::MD_REQ_ID).is_some_and(|md_req_id| {
+            self.one_shot_requests.contains(md_req_id)
+                || self
+                    .md_subscriptions
+                    .get(md_req_id)
+                    .is_some_and(|subscription| !subscription.top_of_book)
+        }) {
+            return false;
+        }
+        let Some(top) = message
+            .get_field(tags::SYMBOL)
+            .and_then(|symbol| self.top_of_books.get_mut(symbol))
+        else {
+            return false;
+        };
+        if top.apply(message) {
+            let event = SessionEvent::TopOfBook(top.clone());
+            self.emit_event(event);
+        }
+        true
+    }
+
+    /// Apply a Market Data Incremental Refresh (X) to the local order book
+    ///
+    /// If the update leaves the book inconsistent, a snapshot is requested for
+    /// the instrument and the book ignores updates until it arrives.
+    async fn handle_market_data_incremental(&mut self, message: &FixMessage) -> Result<()> {
+        let update = match MarketDataIncrementalRefresh::from_fix_message(message) {
+            Ok(update) => update,
+            Err(e) => return self.drop_unparsable_market_data(message, e).await,
+        };
+        let received_at = self.config.clock.utc_now();
+        self.market_stats.apply_incremental(&update, received_at);
+        self.index_streams
+            .publish(&update.symbol, &update.entries, received_at);
+        if update.md_req_id.as_ref().is_some_and(|md_req_id| {
+            !self.feeds_book(md_req_id) || self.option_tickers.symbol_of(md_req_id).is_some()
+        }) {
+            return Ok(());
+        }
+        let Some(book) = self.order_books.get_mut(&update.symbol) else {
+            debug!(
+                "Ignoring incremental refresh for {} without a snapshot",
+                update.symbol
+            );
+            return Ok(());
+        };
+
+        let Err(issue) = book.apply_incremental(&update) else {
+            return self.match_paper_orders(&update.symbol);
+        };
+        self.recover_book(update.symbol, issue).await
+    }
+
     /// Receive and process a FIX message from the connection
+    ///
+    /// When paper trading, simulated reports are returned before any message
+    /// from the connection.
     pub async fn receive_and_process_message(&mut self) -> Result<Option<FixMessage>> {
-        let message = if let Some(connection) = &self.connection {
-            let mut conn_guard = connection.lock().await;
-            conn_guard.receive_message().await?
-        } else {
-            None
+        self.receive_and_process_message_within(std::time::Duration::MAX)
+            .await
+    }
+
+    /// Receive and process a message, waiting at most `max_wait` for data
+    pub async fn receive_and_process_message_within(
+        &mut self,
+        max_wait: std::time::Duration,
+    ) -> Result<Option<FixMessage>> {
+        let incoming = self.wait_incoming(max_wait).await;
+        self.process_incoming(incoming).await
+    }
+
+    /// Wait at most `max_wait` for something to process
+    ///
+    /// Cancel safe: dropping the returned future loses no received data and
+    /// leaves the session as it was, so the wait can be raced against other
+    /// work. Whatever it returns is handled by
+    /// [`process_incoming`](Self::process_incoming), which must run to
+    /// completion.
+    pub(crate) async fn wait_incoming(&mut self, max_wait: std::time::Duration) -> Incoming {
+        if !self.simulated.is_empty() {
+            return Incoming::Simulated;
+        }
+
+        if self.state == SessionState::Maintenance {
+            if let Some(retry) = self.maintenance_retry {
+                let clock = self.config.clock.clone();
+                let deadline = clock
+                    .now()
+                    .checked_add(max_wait)
+                    .map_or(retry.next_attempt, |deadline| {
+                        deadline.min(retry.next_attempt)
+                    });
+                clock.sleep_until(deadline).await;
+            }
+            return Incoming::Maintenance;
+        }
+
+        let Some(connection) = &self.connection else {
+            return Incoming::Read {
+                received: Ok(None),
+                read_at: None,
+                last_write_at: None,
+            };
+        };
+        let mut connection = connection.lock().await;
+        let received = connection.read_message_within(max_wait).await;
+        Incoming::Read {
+            received,
+            read_at: connection.last_read_at(),
+            last_write_at: connection.last_write_at(),
+        }
+    }
+
+    /// Process what [`wait_incoming`](Self::wait_incoming) returned
+    ///
+    /// Retries the logon when exchange maintenance is over and writes a
+    /// pending batch whose delay has passed.
+    pub(crate) async fn process_incoming(
+        &mut self,
+        incoming: Incoming,
+    ) -> Result<Option<FixMessage>> {
+        let (received, read_times) = match incoming {
+            Incoming::Simulated => {
+                let Some(report) = self.simulated.pop_front() else {
+                    return Ok(None);
+                };
+                debug!(
+                    "Received simulated FIX message: {}",
+                    self.printer.render(&report)
+                );
+                if report.msg_type() == Some(MsgType::ExecutionReport) {
+                    self.handle_execution_report(&report).await?;
+                }
+                if self.messages.receiver_count() > 0 {
+                    let _ = self.messages.send(report.clone());
+                }
+                return Ok(Some(report));
+            }
+            Incoming::Maintenance => {
+                self.relogon_after_maintenance().await?;
+                return Ok(None);
+            }
+            Incoming::Read {
+                received,
+                read_at,
+                last_write_at,
+            } => (received, (read_at, last_write_at)),
+        };
+
+        let message = match received {
+            Err(e @ (DeribitFixError::Connection(_) | DeribitFixError::Io(_)))
+                if self.expecting_maintenance() =>
+            {
+                warn!("Connection lost during exchange maintenance: {}", e);
+                self.schedule_maintenance_retry()?;
+                return Ok(None);
+            }
+            received => received?,
         };
+        if let Some(connection) = self.connection.clone() {
+            let mut connection = connection.lock().await;
+            if connection.is_connected() {
+                connection.flush_if_due().await?;
+            }
+        }
 
         if let Some(message) = message {
+            let message = self.with_internal_symbols(message)?;
+            if let Some(monitor) = &mut self.liveness {
+                monitor.received(self.config.clock.now());
+            }
+            if let Some(tracer) = &mut self.latency {
+                let (read, last_write) = read_times;
+                let parsed = tokio::time::Instant::now();
+                if let Some(latency) = tracer.acknowledge(&message, read, parsed, last_write) {
+                    debug!("Order latency: {:?}", latency);
+                }
+            }
+            if self.is_duplicate(&message) {
+                self.report_duplicate(&message);
+                return Ok(None);
+            }
+            if self.is_replayed_execution(&message) {
+                // The sequence number is new, only the execution was seen
+                self.report_replayed_execution(&message);
+                self.incoming_seq_num += 1;
+                self.check_resend_complete(&message)?;
+                return Ok(None);
+            }
+            if self.config.strict_sequence_checks {
+                let violations = sequence::check_incoming(
+                    &message,
+                    self.incoming_seq_num,
+                    self.outgoing_seq_num,
+                );
+                self.report_sequence_anomalies(&message, violations);
+            }
+            self.sync_clock(&message);
+            if let Some(recorder) = &mut self.recorder
+                && let Err(e) = recorder.record(&message)
+            {
+                warn!("Failed to record market data: {}", e);
+            }
             self.process_message(&message).await?;
+            self.check_resend_complete(&message)?;
+            if self.messages.receiver_count() > 0 {
+                let _ = self.messages.send(message.clone());
+            }
             Ok(Some(message))
         } else {
             Ok(None)
         }
     }
+
+    /// Estimate the server clock offset from the SendingTime (52) of a message
+    ///
+    /// Resent messages carry their original SendingTime and are skipped. Half
+    /// of the last measured round trip is taken as the transit time.
+    fn sync_clock(&mut self, message: &FixMessage) {
+        if message
+            .get_field(tags::POSS_DUP_FLAG)
+            .is_some_and(|flag| flag == "Y")
+        {
+            return;
+        }
+        let Some(sending_time) = message
+            .get_field(tags::SENDING_TIME)
+            .and_then(|value| parse_utc_timestamp(value).ok())
+        else {
+            return;
+        };
+        let transit = self
+            .last_round_trip
+            .and_then(|round_trip| TimeDelta::from_std(round_trip / 2).ok())
+            .unwrap_or_default();
+        let offset = sending_time + transit - self.config.clock.utc_now();
+        if offset.abs() > CLOCK_DRIFT_WARNING && self.clock_offset.abs() <= CLOCK_DRIFT_WARNING {
+            warn!(
+                "Server clock is {} ms off the local clock",
+                offset.num_milliseconds()
+            );
+        }
+        self.clock_offset = offset;
+    }
+
+    /// Resend a previously sent message with PossDupFlag (43=Y)
+    ///
+    /// The original MsgSeqNum is kept and OrigSendingTime (122) carries the
+    /// SendingTime of the first transmission. The outgoing sequence number is
+    /// not advanced.
+    pub async fn resend_message(&mut self, original: &FixMessage) -> Result<()> {
+        let orig_sending_time = original
+            .get_field(tags::ORIG_SENDING_TIME)
+            .or_else(|| original.get_field(tags::SENDING_TIME))
+            .cloned()
+            .ok_or_else(|| {
+                DeribitFixError::MessageConstruction(
+                    "SendingTime (52) is required to resend a message".to_string(),
+                )
+            })?;
+
+        let resent = MessageBuilder::from_message(original)
+            .poss_dup(orig_sending_time)
+            .build()?;
+
+        debug!(
+            "Resending message {:?} as possible duplicate",
+            resent.msg_seq_num()
+        );
+        self.send_message(resent).await
+    }
 }
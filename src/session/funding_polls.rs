@@ -0,0 +1,92 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Funding rate polls of a session
+//!
+//! The perpetuals tracked by the
+//! [`FundingTracker`](crate::model::funding::FundingTracker) are polled with
+//! one-shot snapshot Market Data Requests (V) whose funding rates feed the
+//! market statistics, not the order book.
+
+use crate::error::Result;
+use crate::message::{MarketDataRequest, MdEntryType};
+use crate::model::funding::FundingTracker;
+use crate::model::market_stats::FundingSample;
+use crate::session::Session;
+use chrono::TimeDelta;
+use tracing::debug;
+
+impl Session {
+    /// Get the funding rate changes of a perpetual received within `window`,
+    /// oldest first
+    ///
+    /// The history spans at most the market statistics window; the latest
+    /// rates are kept even when older than it.
+    pub fn funding_history(&self, symbol: &str, window: TimeDelta) -> Vec<FundingSample> {
+        let cutoff = self.config.clock.utc_now() - window;
+        self.market_stats(symbol)
+            .map(|stats| {
+                stats
+                    .funding_history()
+                    .filter(|sample| sample.time >= cutoff)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the schedule of the funding rate polls
+    pub fn funding_tracker(&self) -> &FundingTracker {
+        &self.funding
+    }
+
+    /// Poll the funding rates of the perpetual `symbol` every
+    /// [`funding_poll_interval`](crate::config::DeribitFixConfig::funding_poll_interval),
+    /// see [`poll_funding`](Self::poll_funding)
+    ///
+    /// Returns whether the symbol was not tracked yet.
+    pub fn track_funding(&mut self, symbol: &str) -> bool {
+        self.funding.track(symbol)
+    }
+
+    /// Stop polling the funding rates of `symbol`
+    ///
+    /// Returns whether the symbol was tracked.
+    pub fn untrack_funding(&mut self, symbol: &str) -> bool {
+        self.funding.untrack(symbol)
+    }
+
+    /// Send a snapshot Market Data Request (V) for every tracked perpetual
+    /// due for a funding poll
+    ///
+    /// Each request asks for the best bid and offer only; the rates of the
+    /// snapshot are recorded in the [`funding_history`](Self::funding_history)
+    /// and the snapshot does not touch the local order book. Responses are
+    /// not awaited. Does nothing unless the session is logged on. Returns the
+    /// number of requests sent.
+    pub async fn poll_funding(&mut self) -> Result<usize> {
+        if !self.state.is_logged_on() {
+            return Ok(0);
+        }
+        let now = self.config.clock.now();
+        let due = self.funding.due(now);
+        for symbol in &due {
+            let md_req_id = self.request_ids.next("FND");
+            let mut request = MarketDataRequest::snapshot(
+                md_req_id.clone(),
+                vec![symbol.clone()],
+                vec![MdEntryType::Bid, MdEntryType::Offer],
+            );
+            request.market_depth = Some(1);
+            self.send(&request).await?;
+            self.funding.polled(symbol, md_req_id, now);
+        }
+        if !due.is_empty() {
+            debug!("Polled the funding rates of {} perpetuals", due.len());
+        }
+        Ok(due.len())
+    }
+}
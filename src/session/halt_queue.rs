@@ -0,0 +1,83 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Orders held while trading is halted
+//!
+//! While an instrument is halted or the exchange is in maintenance, new
+//! orders are rejected locally or, with
+//! [`queue_orders_during_halt`](crate::config::DeribitFixConfig::queue_orders_during_halt),
+//! queued by the [`MarketStateTracker`](crate::model::market_state::MarketStateTracker)
+//! and sent once trading resumes.
+
+use crate::error::Result;
+use crate::message::security_status::SecurityStatus;
+use crate::model::market_state::MarketStateEvent;
+use crate::model::message::FixMessage;
+use crate::model::request::NewOrderRequest;
+use crate::session::Session;
+use crate::session::events::SessionEvent;
+use tracing::info;
+
+impl Session {
+    /// Hold `order` back while its instrument is not tradable
+    ///
+    /// Returns the order when it can be sent, `None` when it was queued, and
+    /// the market state error when queueing during halts is disabled.
+    pub(super) fn hold_while_halted(
+        &mut self,
+        order: NewOrderRequest,
+        order_id: &str,
+    ) -> Result<Option<NewOrderRequest>> {
+        let Err(e) = self.market_state.check_tradable(&order.instrument_name) else {
+            return Ok(Some(order));
+        };
+        if !self.config.queue_orders_during_halt {
+            return Err(e);
+        }
+        info!(
+            "Queueing order {} until {} is tradable",
+            order_id, order.instrument_name
+        );
+        let symbol = order.instrument_name.clone();
+        self.market_state.queue_order(order);
+        self.emit_event(SessionEvent::MarketState(MarketStateEvent::OrderQueued {
+            symbol,
+            cl_ord_id: order_id.to_string(),
+        }));
+        Ok(None)
+    }
+
+    /// Send queued orders whose instruments are tradable again
+    pub(super) async fn release_queued_orders(&mut self) -> Result<()> {
+        for order in self.market_state.take_releasable() {
+            let symbol = order.instrument_name.clone();
+            let order_id = order.client_order_id.clone().unwrap_or_default();
+            self.submit_new_order(order, order_id.clone()).await?;
+            self.emit_event(SessionEvent::MarketState(MarketStateEvent::OrderReleased {
+                symbol,
+                cl_ord_id: order_id,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Apply a Security Status (f) message to the market state tracker
+    ///
+    /// Queued orders are released when an instrument reopens.
+    pub(super) async fn handle_security_status(&mut self, message: &FixMessage) -> Result<()> {
+        let status = SecurityStatus::from_fix_message(message)?;
+        let Some(event) = self.market_state.apply_security_status(&status) else {
+            return Ok(());
+        };
+
+        info!("Trading state changed: {:?}", event);
+        self.emit_event(SessionEvent::MarketState(event));
+        if self.market_state.is_tradable(&status.symbol) {
+            self.release_queued_orders().await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Index value and settlement price subscriptions of a session
+//!
+//! An index subscription is a Market Data Request (V) of its own, kept apart
+//! from the book subscriptions; its entries are published on the
+//! [`IndexStreams`](crate::model::index_stream::IndexStreams) channels of the
+//! session.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::{MarketDataRequest, MdEntryType, MdUpdateType};
+use crate::model::index_stream::IndexUpdate;
+use crate::session::Session;
+use tokio::sync::broadcast;
+use tracing::info;
+
+impl Session {
+    /// Subscribe to the index value and estimated delivery price of `symbol`
+    ///
+    /// Sends a Market Data Request (V) for MDEntryType (269) 3 and 6 unless
+    /// the index is already subscribed. Index and settlement price entries of
+    /// every market data message of `symbol` are delivered on the returned
+    /// channel as [`IndexUpdate`]s; they never reach the order book. A
+    /// receiver more than
+    /// [`max_market_data_backlog`](crate::config::DeribitFixConfig::max_market_data_backlog)
+    /// updates behind loses the oldest ones.
+    pub async fn subscribe_index(
+        &mut self,
+        symbol: &str,
+    ) -> Result<broadcast::Receiver<IndexUpdate>> {
+        if self.index_streams.request_for(symbol).is_none() {
+            let md_req_id = self.request_ids.next("IDX");
+            let request = MarketDataRequest::subscription(
+                md_req_id.clone(),
+                vec![symbol.to_string()],
+                vec![MdEntryType::IndexValue, MdEntryType::SettlementPrice],
+                MdUpdateType::IncrementalRefresh,
+            );
+            self.send(&request).await?;
+            info!("Subscribed to index {} with ID: {}", symbol, md_req_id);
+            self.index_streams.track(md_req_id, symbol.to_string());
+        }
+        Ok(self.index_streams.subscribe(symbol))
+    }
+
+    /// Cancel the index subscription of `symbol`, closing its channels
+    pub async fn unsubscribe_index(&mut self, symbol: &str) -> Result<()> {
+        let md_req_id = self
+            .index_streams
+            .request_for(symbol)
+            .ok_or_else(|| DeribitFixError::Session(format!("No index subscription for {symbol}")))?
+            .to_string();
+        let mut request = MarketDataRequest::unsubscribe(md_req_id.clone());
+        request.symbols = vec![symbol.to_string()];
+        self.send(&request).await?;
+        info!("Unsubscribed index {} for {}", md_req_id, symbol);
+        self.index_streams.remove(symbol);
+        Ok(())
+    }
+}
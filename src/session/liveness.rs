@@ -0,0 +1,150 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Application-level liveness checks
+//!
+//! TCP keepalive cannot be configured on every platform and transport, such
+//! as in-memory pipes or some proxies. With
+//! [`liveness_missed_heartbeats`](crate::config::DeribitFixConfig::liveness_missed_heartbeats)
+//! set, the [`Session`](crate::session::Session) watches the FIX traffic
+//! itself and escalates as the counterparty falls silent:
+//!
+//! 1. after that many heartbeat intervals without any incoming message, a
+//!    Test Request (1) probes the counterparty;
+//! 2. when nothing arrives within
+//!    [`liveness_probe_timeout`](crate::config::DeribitFixConfig::liveness_probe_timeout)
+//!    of the probe, the session reconnects and logs on again.
+//!
+//! Any incoming message, not only the Heartbeat answering the probe, proves
+//! the counterparty alive.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Step taken by a liveness check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LivenessAction {
+    /// The counterparty was heard from recently enough
+    Alive,
+    /// The counterparty was silent too long and was sent a Test Request
+    Probe,
+    /// The Test Request went unanswered and the session reconnected
+    Reconnect,
+}
+
+/// Silence tracker of the incoming traffic
+#[derive(Debug, Clone)]
+pub(crate) struct LivenessMonitor {
+    /// Heartbeat intervals of silence before probing
+    missed_heartbeats: u32,
+    /// Time the probe may go unanswered, one heartbeat interval when unset
+    probe_timeout: Option<Duration>,
+    /// Time the last message was received, or the monitor was reset
+    last_inbound: Instant,
+    /// Time the outstanding probe was sent
+    probe_sent: Option<Instant>,
+}
+
+impl LivenessMonitor {
+    /// Monitor probing after `missed_heartbeats` silent heartbeat intervals
+    pub(crate) fn new(
+        missed_heartbeats: u32,
+        probe_timeout: Option<Duration>,
+        now: Instant,
+    ) -> Self {
+        Self {
+            missed_heartbeats,
+            probe_timeout,
+            last_inbound: now,
+            probe_sent: None,
+        }
+    }
+
+    /// Record a message received at `now`, answering any outstanding probe
+    pub(crate) fn received(&mut self, now: Instant) {
+        self.last_inbound = now;
+        self.probe_sent = None;
+    }
+
+    /// Record the probe sent at `now`
+    pub(crate) fn probed(&mut self, now: Instant) {
+        self.probe_sent = Some(now);
+    }
+
+    /// Time since the last message was received
+    pub(crate) fn silent_for(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_inbound)
+    }
+
+    /// Step due at `now` with heartbeat interval `heartbeat`
+    pub(crate) fn check(&self, now: Instant, heartbeat: Duration) -> LivenessAction {
+        match self.probe_sent {
+            Some(sent) => {
+                let timeout = self.probe_timeout.unwrap_or(heartbeat);
+                if now.saturating_duration_since(sent) >= timeout {
+                    LivenessAction::Reconnect
+                } else {
+                    LivenessAction::Alive
+                }
+            }
+            None if self.silent_for(now) >= heartbeat * self.missed_heartbeats => {
+                LivenessAction::Probe
+            }
+            None => LivenessAction::Alive,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEARTBEAT: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_silence_escalates_from_probe_to_reconnect() {
+        let start = Instant::now();
+        let mut monitor = LivenessMonitor::new(2, Some(Duration::from_secs(5)), start);
+
+        assert_eq!(
+            monitor.check(start + HEARTBEAT, HEARTBEAT),
+            LivenessAction::Alive
+        );
+        let silent = start + HEARTBEAT * 2;
+        assert_eq!(monitor.check(silent, HEARTBEAT), LivenessAction::Probe);
+
+        monitor.probed(silent);
+        assert_eq!(
+            monitor.check(silent + Duration::from_secs(4), HEARTBEAT),
+            LivenessAction::Alive
+        );
+        assert_eq!(
+            monitor.check(silent + Duration::from_secs(5), HEARTBEAT),
+            LivenessAction::Reconnect
+        );
+        assert_eq!(monitor.silent_for(silent), HEARTBEAT * 2);
+    }
+
+    #[test]
+    fn test_any_message_answers_the_probe() {
+        let start = Instant::now();
+        let mut monitor = LivenessMonitor::new(1, None, start);
+        monitor.probed(start + HEARTBEAT);
+
+        monitor.received(start + HEARTBEAT + Duration::from_secs(1));
+        assert_eq!(
+            monitor.check(start + HEARTBEAT * 2, HEARTBEAT),
+            LivenessAction::Alive
+        );
+        // Without a probe timeout the probe waits one heartbeat interval
+        monitor.probed(start + HEARTBEAT * 3);
+        assert_eq!(
+            monitor.check(start + HEARTBEAT * 4, HEARTBEAT),
+            LivenessAction::Reconnect
+        );
+    }
+}
@@ -0,0 +1,162 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Logon retries
+//!
+//! `reconnect_attempts` and `reconnect_delay` cover a connection that cannot
+//! be opened. A connection that opens but whose Logon (A) is answered with a
+//! Logout (5) follows its own policy, set with
+//! [`with_logon_retry`](crate::config::DeribitFixConfig::with_logon_retry):
+//!
+//! - a transient reject is retried after a backoff starting at
+//!   `logon_retry_delay` and doubling up to `logon_retry_max_delay`, at most
+//!   `logon_retry_attempts` times;
+//! - a reject for invalid credentials opens a circuit breaker instead. No
+//!   logon is sent while it is open, since each attempt with bad
+//!   credentials brings the account closer to being locked, until
+//!   [`reset_logon_circuit`](crate::session::Session::reset_logon_circuit)
+//!   or new credentials close it.
+
+use crate::message::admin::LogoutReason;
+use std::time::Duration;
+
+/// What to do after a rejected logon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogonRetryAction {
+    /// Log on again after `delay`
+    Retry {
+        /// Retry number, starting at 1
+        attempt: u32,
+        /// Wait before the retry
+        delay: Duration,
+    },
+    /// Every retry was rejected too
+    Exhausted {
+        /// Retries made
+        attempts: u32,
+    },
+    /// The credentials were rejected and the circuit breaker opened
+    CircuitOpen,
+}
+
+/// Retries and circuit breaker of rejected logons
+#[derive(Debug, Clone)]
+pub(crate) struct LogonRetry {
+    max_attempts: u32,
+    delay: Duration,
+    max_delay: Duration,
+    /// Consecutive rejected logons since the last accepted one
+    attempts: u32,
+    circuit_open: bool,
+}
+
+impl LogonRetry {
+    /// Retry at most `max_attempts` times, waiting from `delay` up to `max_delay`
+    pub(crate) fn new(max_attempts: u32, delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            delay,
+            max_delay,
+            attempts: 0,
+            circuit_open: false,
+        }
+    }
+
+    /// Whether transient rejects are retried under this policy
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.max_attempts > 0
+    }
+
+    /// Whether logons are refused after the credentials were rejected
+    pub(crate) fn is_circuit_open(&self) -> bool {
+        self.circuit_open
+    }
+
+    /// Record a logon rejected for `reason`
+    pub(crate) fn rejected(&mut self, reason: LogoutReason) -> LogonRetryAction {
+        if reason.is_fatal() {
+            self.circuit_open = true;
+            return LogonRetryAction::CircuitOpen;
+        }
+        if self.attempts >= self.max_attempts {
+            return LogonRetryAction::Exhausted {
+                attempts: self.attempts,
+            };
+        }
+        self.attempts += 1;
+        LogonRetryAction::Retry {
+            attempt: self.attempts,
+            delay: self.backoff(self.attempts),
+        }
+    }
+
+    /// Record an accepted logon
+    pub(crate) fn logged_on(&mut self) {
+        self.attempts = 0;
+    }
+
+    /// Close the circuit breaker and start counting retries again
+    pub(crate) fn reset(&mut self) {
+        self.attempts = 0;
+        self.circuit_open = false;
+    }
+
+    /// Wait before retry `attempt`
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transient_rejects_back_off_until_exhausted() {
+        let mut retry = LogonRetry::new(3, Duration::from_secs(1), Duration::from_secs(3));
+        let delays: Vec<_> = (0..3)
+            .map(|_| match retry.rejected(LogoutReason::Other) {
+                LogonRetryAction::Retry { delay, .. } => delay,
+                other => panic!("unexpected action: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            delays,
+            [
+                Duration::from_secs(1),
+                Duration::from_secs(2),
+                Duration::from_secs(3)
+            ]
+        );
+        assert_eq!(
+            retry.rejected(LogoutReason::HeartbeatMissed),
+            LogonRetryAction::Exhausted { attempts: 3 }
+        );
+
+        retry.logged_on();
+        assert_eq!(
+            retry.rejected(LogoutReason::Other),
+            LogonRetryAction::Retry {
+                attempt: 1,
+                delay: Duration::from_secs(1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_credential_rejects_open_the_circuit() {
+        let mut retry = LogonRetry::new(3, Duration::from_secs(1), Duration::from_secs(60));
+        assert_eq!(
+            retry.rejected(LogoutReason::CredentialsInvalid),
+            LogonRetryAction::CircuitOpen
+        );
+        assert!(retry.is_circuit_open());
+
+        retry.reset();
+        assert!(!retry.is_circuit_open());
+    }
+}
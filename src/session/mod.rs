@@ -1,6 +1,45 @@
 //! FIX session management module
 
+/// Order book integrity recovery
+mod book_integrity;
+/// Injectable clock and timers
+pub mod clock;
+/// Application keep-alive deadline cancelling every order when missed
+pub mod dead_mans_switch;
+/// Duplicate and replayed message detection
+mod dedup;
+/// Session event notifications
+pub mod events;
 /// FIX session implementation
 pub mod fix_session;
+/// Funding rate polls
+mod funding_polls;
+/// Orders held while trading is halted
+mod halt_queue;
+/// Index value and settlement price subscriptions
+mod index_streams;
+/// Application-level liveness checks
+pub mod liveness;
+/// Retries and circuit breaker of rejected logons
+pub mod logon_retry;
+/// Option ticker subscriptions
+mod option_tickers;
+/// Request deadlines and cancellation
+pub mod options;
+/// Paper trading of the session's orders
+mod paper_trading;
+/// Session-scoped request ID generation
+pub mod request_ids;
+/// Strict sequence number checks
+pub mod sequence;
+/// FIX session state machine
+pub mod state;
 
+pub use clock::*;
+pub use events::*;
 pub use fix_session::*;
+pub use liveness::LivenessAction;
+pub use options::*;
+pub use request_ids::*;
+pub use sequence::{SequenceAnomaly, SequenceAnomalyKind};
+pub use state::*;
@@ -0,0 +1,82 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Option ticker subscriptions of a session
+//!
+//! Each subscribed option has a full-refresh Market Data Request (V) of depth
+//! one, whose snapshots are turned into tickers by the
+//! [`OptionTickerStreams`](crate::model::option_ticker::OptionTickerStreams)
+//! of the session instead of feeding the order book.
+
+use crate::error::{DeribitFixError, Result};
+use crate::message::{MarketDataRequest, MdEntryType, MdUpdateType};
+use crate::model::option_ticker::OptionTicker;
+use crate::session::Session;
+use tokio::sync::broadcast;
+use tracing::info;
+
+impl Session {
+    /// Subscribe to the ticker of the option `symbol`
+    ///
+    /// Sends a Market Data Request (V) for the top of the book and trades
+    /// with MDUpdateType (265) = 0, so that every update is a full snapshot
+    /// carrying the mark and underlying prices, unless the option is already
+    /// subscribed. Each snapshot is delivered on the returned channel as an
+    /// [`OptionTicker`] with its implied volatilities and greeks; it never
+    /// reaches the order book.
+    pub async fn subscribe_option_ticker(
+        &mut self,
+        symbol: &str,
+    ) -> Result<broadcast::Receiver<OptionTicker>> {
+        let is_option = symbol
+            .parse::<crate::model::instrument::InstrumentName>()
+            .is_ok_and(|name| name.is_option());
+        if !is_option {
+            return Err(DeribitFixError::Session(format!(
+                "{symbol} is not an option"
+            )));
+        }
+        if self.option_tickers.request_for(symbol).is_none() {
+            let md_req_id = self.request_ids.next("OPT");
+            let mut request = MarketDataRequest::subscription(
+                md_req_id.clone(),
+                vec![symbol.to_string()],
+                vec![MdEntryType::Bid, MdEntryType::Offer, MdEntryType::Trade],
+                MdUpdateType::FullRefresh,
+            );
+            request.market_depth = Some(1);
+            self.send(&request).await?;
+            info!(
+                "Subscribed to option ticker {} with ID: {}",
+                symbol, md_req_id
+            );
+            self.option_tickers.track(md_req_id, symbol.to_string());
+        }
+        Ok(self.option_tickers.subscribe(symbol))
+    }
+
+    /// Cancel the ticker subscription of `symbol`, closing its channels
+    pub async fn unsubscribe_option_ticker(&mut self, symbol: &str) -> Result<()> {
+        let md_req_id = self
+            .option_tickers
+            .request_for(symbol)
+            .ok_or_else(|| {
+                DeribitFixError::Session(format!("No option ticker subscription for {symbol}"))
+            })?
+            .to_string();
+        let mut request = MarketDataRequest::unsubscribe(md_req_id.clone());
+        request.symbols = vec![symbol.to_string()];
+        self.send(&request).await?;
+        info!("Unsubscribed option ticker {} for {}", md_req_id, symbol);
+        self.option_tickers.remove(symbol);
+        Ok(())
+    }
+
+    /// Get the last ticker of a subscribed option
+    pub fn option_ticker(&self, symbol: &str) -> Option<&OptionTicker> {
+        self.option_tickers.latest(symbol)
+    }
+}
@@ -0,0 +1,177 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Request deadlines and cancellation
+//!
+//! [`RequestOptions`] bound how long a request waits for the session and for
+//! its response. Without a deadline the configured
+//! [`request_timeout`](crate::config::DeribitFixConfig::request_timeout)
+//! applies; a [`CancelToken`] stops the wait from another task.
+
+use crate::error::{DeribitFixError, Result};
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Token stopping the requests it is attached to
+///
+/// Clones share the same state, so a request can be cancelled from any task
+/// holding a clone.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every request waiting on the token
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolve once the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            // Register before checking, so a cancel in between is not missed
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Deadline and cancellation of a request
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Time by which the request must complete; the configured request
+    /// timeout applies when unset
+    pub deadline: Option<Instant>,
+    /// Token cancelling the request
+    pub cancel_token: Option<CancelToken>,
+}
+
+impl RequestOptions {
+    /// Options using the configured request timeout and no cancellation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Complete the request by `deadline`
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Complete the request within `timeout` from now
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Stop the request when `token` is cancelled
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Whether the cancel token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled)
+    }
+
+    /// Fail with [`DeribitFixError::Cancelled`] when the token is cancelled
+    pub fn check_cancelled(&self, description: &str) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(DeribitFixError::Cancelled(format!(
+                "{description} was cancelled"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run `future` until it completes, the deadline passes or the token is
+    /// cancelled
+    ///
+    /// `future` is polled first, so one that is ready completes even past the
+    /// deadline. It is dropped when it does not complete, so it must be safe
+    /// to abandon, such as waiting for a lock.
+    pub async fn run<T>(&self, description: &str, future: impl Future<Output = T>) -> Result<T> {
+        self.check_cancelled(description)?;
+        let cancelled = async {
+            match &self.cancel_token {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+        let expired = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            biased;
+            output = future => Ok(output),
+            () = cancelled => Err(DeribitFixError::Cancelled(format!("{description} was cancelled"))),
+            () = expired => Err(DeribitFixError::Timeout(format!("{description} passed its deadline"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_stops_on_cancel_and_deadline() {
+        let token = CancelToken::new();
+        let options = RequestOptions::new().with_cancel_token(token.clone());
+        assert_eq!(options.run("ready", async { 1 }).await.unwrap(), 1);
+
+        let waiter = {
+            let options = options.clone();
+            tokio::spawn(async move { options.run("wait", std::future::pending::<()>()).await })
+        };
+        tokio::task::yield_now().await;
+        token.cancel();
+        assert!(matches!(
+            waiter.await.unwrap(),
+            Err(DeribitFixError::Cancelled(_))
+        ));
+        // A cancelled token stops requests before they start
+        assert!(options.check_cancelled("later").is_err());
+
+        let options = RequestOptions::new().with_timeout(Duration::from_millis(10));
+        assert!(matches!(
+            options.run("wait", std::future::pending::<()>()).await,
+            Err(DeribitFixError::Timeout(_))
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Paper trading of a session
+//!
+//! With a [`PaperTradingEngine`] the orders of the session are simulated
+//! against the local order books instead of reaching the exchange; the
+//! simulated reports are returned before any message from the connection.
+
+use crate::error::Result;
+use crate::model::message::FixMessage;
+use crate::model::paper_trading::PaperTradingEngine;
+use crate::session::Session;
+use tracing::debug;
+
+impl Session {
+    /// Get the paper trading engine, `None` unless paper trading is enabled
+    pub fn paper_trading(&self) -> Option<&PaperTradingEngine> {
+        self.paper_trading.as_ref()
+    }
+
+    /// Match resting paper orders after the order book of `symbol` changed
+    pub(super) fn match_paper_orders(&mut self, symbol: &str) -> Result<()> {
+        let (Some(engine), Some(book)) = (&mut self.paper_trading, self.order_books.get(symbol))
+        else {
+            return Ok(());
+        };
+        if book.is_recovering() {
+            return Ok(());
+        }
+        let reports = engine.on_book_update(book)?;
+        self.simulated.extend(reports);
+        Ok(())
+    }
+
+    /// Simulate an outgoing message when paper trading
+    ///
+    /// Returns whether the engine handled the message, which must then not
+    /// reach the exchange.
+    pub(super) fn simulate(&mut self, message: &FixMessage) -> Result<bool> {
+        let Some(engine) = &mut self.paper_trading else {
+            return Ok(false);
+        };
+        let Some(reports) = engine.handle_outgoing(message, &self.order_books)? else {
+            return Ok(false);
+        };
+        debug!("Simulated FIX message: {}", self.printer.render(message));
+        self.simulated.extend(reports);
+        Ok(true)
+    }
+}
@@ -0,0 +1,113 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Request identifiers
+//!
+//! Outstanding requests are correlated with their responses by the IDs they
+//! carry: ClOrdID (11), MDReqID (262), TestReqID (112) and the like. A
+//! [`RequestIdGenerator`] builds each ID from a prefix, a token drawn once
+//! per session and a counter, as in `ORDER_K3F9X2QA_17`. The counter never
+//! repeats within a session and the token sets sessions apart, so two IDs
+//! never alias however fast they are generated.
+
+use crate::config::gen_id;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Length of the random token of a session
+const SESSION_ID_LEN: usize = 8;
+
+/// Generator of the IDs of messages built without a session
+static PROCESS_IDS: LazyLock<RequestIdGenerator> = LazyLock::new(RequestIdGenerator::new);
+
+/// Monotonic generator of request IDs, shared by everything sending on one
+/// session
+#[derive(Debug)]
+pub struct RequestIdGenerator {
+    session_id: String,
+    counter: AtomicU64,
+}
+
+impl RequestIdGenerator {
+    /// Create a generator with a new random session token
+    pub fn new() -> Self {
+        let mut session_id = gen_id();
+        session_id.truncate(SESSION_ID_LEN);
+        Self::with_session_id(session_id)
+    }
+
+    /// Create a generator with the session token `session_id`
+    pub fn with_session_id(session_id: impl Into<String>) -> Self {
+        Self {
+            session_id: session_id.into(),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Token of the session in the generated IDs
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Next ID, `{prefix}_{session_id}_{counter}`
+    pub fn next(&self, prefix: &str) -> String {
+        let counter = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+        format!("{prefix}_{}_{counter}", self.session_id)
+    }
+
+    /// Number of IDs generated so far
+    pub fn generated(&self) -> u64 {
+        self.counter.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for RequestIdGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Next ID of the process-wide generator, for messages built without a
+/// session
+pub fn next_request_id(prefix: &str) -> String {
+    PROCESS_IDS.next(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_ids_never_repeat() {
+        let ids = Arc::new(RequestIdGenerator::with_session_id("S1"));
+        assert_eq!(ids.next("ORDER"), "ORDER_S1_1");
+        assert_eq!(ids.next("MDR"), "MDR_S1_2");
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let ids = ids.clone();
+                std::thread::spawn(move || (0..1000).map(|_| ids.next("ORDER")).collect::<Vec<_>>())
+            })
+            .collect();
+        let generated: HashSet<String> = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect();
+        assert_eq!(generated.len(), 4000);
+        assert_eq!(ids.generated(), 4002);
+    }
+
+    #[test]
+    fn test_sessions_get_distinct_tokens() {
+        let first = RequestIdGenerator::new();
+        let second = RequestIdGenerator::new();
+        assert_eq!(first.session_id().len(), SESSION_ID_LEN);
+        assert_ne!(first.session_id(), second.session_id());
+        assert_ne!(next_request_id("TESTREQ"), next_request_id("TESTREQ"));
+    }
+}
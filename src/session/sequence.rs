@@ -0,0 +1,208 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Strict sequence number checks
+//!
+//! When
+//! [`strict_sequence_checks`](crate::config::DeribitFixConfig::strict_sequence_checks)
+//! is enabled the [`Session`](crate::session::Session) audits the MsgSeqNum
+//! (34) of every message it sends and receives:
+//!
+//! - each incoming message must carry the expected incoming sequence number,
+//!   so numbers increase by exactly one. Possible duplicates of processed
+//!   messages are dropped before the check and a Sequence Reset (4) in Reset
+//!   mode, whose MsgSeqNum is ignored, is not checked;
+//! - each outgoing message, except resent possible duplicates, must carry the
+//!   next outgoing sequence number;
+//! - sequence numbers the counterparty refers to, the RefSeqNum (45) of a
+//!   Reject (3) or Business Message Reject (j) and the range of a Resend
+//!   Request (2), must have been sent.
+//!
+//! Every violation is published as a [`SessionEvent::SequenceAnomaly`](crate::session::SessionEvent::SequenceAnomaly);
+//! the message itself is still processed.
+
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use crate::session::SessionState;
+use serde::{Deserialize, Serialize};
+
+/// Kind of sequence number violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SequenceAnomalyKind {
+    /// An incoming message has no MsgSeqNum (34)
+    MissingSeqNum,
+    /// An incoming MsgSeqNum is higher than expected, so messages were missed
+    IncomingGap,
+    /// An incoming MsgSeqNum is lower than expected and not a possible duplicate
+    IncomingTooLow,
+    /// A message was sent with a MsgSeqNum other than the next outgoing one
+    OutgoingOutOfOrder,
+    /// The counterparty referred to a MsgSeqNum that was never sent
+    UnsentReference,
+}
+
+/// Sequence number violation found by the strict sequence checks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceAnomaly {
+    /// Kind of violation
+    pub kind: SequenceAnomalyKind,
+    /// MsgType (35) of the message checked
+    pub msg_type: String,
+    /// MsgSeqNum (34) of the message checked, if it has one
+    pub msg_seq_num: Option<u32>,
+    /// Sequence number the session expected
+    pub expected: u32,
+    /// Sequence number found, 0 when the message has none
+    pub actual: u32,
+    /// Whether the message was a possible duplicate (43=Y)
+    pub poss_dup: bool,
+    /// Next expected incoming sequence number when the check ran
+    pub next_incoming_seq_num: u32,
+    /// Next outgoing sequence number when the check ran
+    pub next_outgoing_seq_num: u32,
+    /// Session state when the check ran
+    pub state: SessionState,
+}
+
+/// Kind, expected and actual sequence number of a violation
+pub(crate) type Violation = (SequenceAnomalyKind, u32, u32);
+
+/// Whether a message carries PossDupFlag (43=Y)
+pub(crate) fn is_poss_dup(message: &FixMessage) -> bool {
+    message
+        .get_field(tags::POSS_DUP_FLAG)
+        .is_some_and(|flag| flag == "Y")
+}
+
+/// Check an incoming message against the expected incoming sequence number
+/// and the next outgoing one
+pub(crate) fn check_incoming(
+    message: &FixMessage,
+    next_incoming: u32,
+    next_outgoing: u32,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let msg_type = message.msg_type();
+    let reset_mode = msg_type == Some(MsgType::SequenceReset)
+        && message
+            .get_field(tags::GAP_FILL_FLAG)
+            .is_none_or(|flag| flag != "Y");
+
+    match message.msg_seq_num() {
+        None => violations.push((SequenceAnomalyKind::MissingSeqNum, next_incoming, 0)),
+        Some(_) if reset_mode => {}
+        Some(seq) if seq > next_incoming => {
+            violations.push((SequenceAnomalyKind::IncomingGap, next_incoming, seq))
+        }
+        Some(seq) if seq < next_incoming => {
+            violations.push((SequenceAnomalyKind::IncomingTooLow, next_incoming, seq))
+        }
+        Some(_) => {}
+    }
+
+    let referenced: &[u32] = match msg_type {
+        Some(MsgType::Reject | MsgType::BusinessMessageReject) => &[tags::REF_SEQ_NUM],
+        Some(MsgType::ResendRequest) => &[tags::BEGIN_SEQ_NO, tags::END_SEQ_NO],
+        _ => &[],
+    };
+    let last_sent = next_outgoing.saturating_sub(1);
+    violations.extend(
+        referenced
+            .iter()
+            .filter_map(|tag| message.get_field(*tag)?.parse::<u32>().ok())
+            .filter(|seq| *seq > last_sent)
+            .map(|seq| (SequenceAnomalyKind::UnsentReference, last_sent, seq)),
+    );
+    violations
+}
+
+/// Check an outgoing message against the next outgoing sequence number
+pub(crate) fn check_outgoing(message: &FixMessage, next_outgoing: u32) -> Option<Violation> {
+    if is_poss_dup(message) {
+        return None;
+    }
+    match message.msg_seq_num() {
+        Some(seq) if seq == next_outgoing => None,
+        seq => Some((
+            SequenceAnomalyKind::OutgoingOutOfOrder,
+            next_outgoing,
+            seq.unwrap_or_default(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{MessageBuilder, ResendRequest, SequenceReset};
+
+    fn heartbeat(msg_seq_num: u32) -> FixMessage {
+        MessageBuilder::new()
+            .msg_type(MsgType::Heartbeat)
+            .sender_comp_id("DERIBIT".to_string())
+            .target_comp_id("CLIENT".to_string())
+            .msg_seq_num(msg_seq_num)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_incoming_sequence_must_increase_by_one() {
+        assert!(check_incoming(&heartbeat(5), 5, 1).is_empty());
+        assert_eq!(
+            check_incoming(&heartbeat(7), 5, 1),
+            vec![(SequenceAnomalyKind::IncomingGap, 5, 7)]
+        );
+        assert_eq!(
+            check_incoming(&heartbeat(3), 5, 1),
+            vec![(SequenceAnomalyKind::IncomingTooLow, 5, 3)]
+        );
+    }
+
+    #[test]
+    fn test_reset_mode_sequence_reset_is_not_checked() {
+        let reset = SequenceReset::new_reset(40)
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), 99)
+            .unwrap();
+        assert!(check_incoming(&reset, 5, 1).is_empty());
+
+        let gap_fill = SequenceReset::new_gap_fill(40)
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), 99)
+            .unwrap();
+        assert_eq!(
+            check_incoming(&gap_fill, 5, 1),
+            vec![(SequenceAnomalyKind::IncomingGap, 5, 99)]
+        );
+    }
+
+    #[test]
+    fn test_resend_request_of_unsent_messages() {
+        let request = ResendRequest::new(3, 12)
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), 5)
+            .unwrap();
+        assert!(check_incoming(&request, 5, 13).is_empty());
+        assert_eq!(
+            check_incoming(&request, 5, 10),
+            vec![(SequenceAnomalyKind::UnsentReference, 9, 12)]
+        );
+    }
+
+    #[test]
+    fn test_outgoing_sequence_must_be_the_next_one() {
+        assert_eq!(check_outgoing(&heartbeat(4), 4), None);
+        assert_eq!(
+            check_outgoing(&heartbeat(2), 4),
+            Some((SequenceAnomalyKind::OutgoingOutOfOrder, 4, 2))
+        );
+
+        let resent = MessageBuilder::from_message(&heartbeat(2))
+            .poss_dup("20261016-10:00:00.000".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(check_outgoing(&resent, 4), None);
+    }
+}
@@ -0,0 +1,252 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! FIX session state machine
+//!
+//! A session moves through its states along explicit transitions:
+//!
+//! ```text
+//! Disconnected -> Connecting -> LogonSent -> LoggedOn <-> ResendInProgress
+//!                                   |           |              |
+//!                                   +-----------+--> LogoutSent +--> Disconnected
+//! ```
+//!
+//! The connection can be lost at any point, so every state may move back to
+//! `Disconnected`. A Logon received while `LoggedOn`, such as one resetting
+//! the sequence numbers (ResetSeqNumFlag 141=Y), keeps the session in
+//! `LoggedOn`. A session logged out for exchange maintenance waits in
+//! `Maintenance` until it retries the logon:
+//!
+//! ```text
+//! Disconnected -> Maintenance -> Connecting | LogonSent
+//! ```
+//!
+//! Any other move is rejected with
+//! [`DeribitFixError::Session`](crate::error::DeribitFixError::Session).
+//! Each state also restricts the message types that can be sent and received; the [`Session`](crate::session::Session)
+//! enforces them when
+//! [`strict_session_state`](crate::config::DeribitFixConfig::strict_session_state)
+//! is enabled.
+
+use crate::error::{DeribitFixError, Result};
+use crate::model::types::MsgType;
+use serde::{Deserialize, Serialize};
+
+/// FIX session state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionState {
+    /// Session is disconnected
+    Disconnected,
+    /// Transport connection is being opened
+    Connecting,
+    /// Logon message sent, waiting for response
+    LogonSent,
+    /// Session is logged on and active
+    LoggedOn,
+    /// Resend Request sent, waiting for the gap to be filled
+    ResendInProgress,
+    /// Logout message sent, waiting for confirmation
+    LogoutSent,
+    /// Logged out for exchange maintenance, waiting to retry the logon
+    Maintenance,
+}
+
+impl SessionState {
+    /// Whether the session can move from this state to `next`
+    pub fn can_transition_to(self, next: SessionState) -> bool {
+        use SessionState::*;
+        matches!(
+            (self, next),
+            (_, Disconnected)
+                | (Disconnected, Connecting | LogonSent | Maintenance)
+                | (Maintenance, Connecting | LogonSent)
+                | (Connecting, LogonSent)
+                | (LogonSent, LoggedOn | LogoutSent)
+                | (LoggedOn, LoggedOn | ResendInProgress | LogoutSent)
+                | (ResendInProgress, LoggedOn | LogoutSent)
+        )
+    }
+
+    /// Move to `next`, failing when the transition is not allowed
+    pub fn transition(self, next: SessionState) -> Result<SessionState> {
+        if !self.can_transition_to(next) {
+            return Err(DeribitFixError::Session(format!(
+                "Illegal session state transition from {self:?} to {next:?}"
+            )));
+        }
+        Ok(next)
+    }
+
+    /// Whether the session is logged on, including while a resend is in progress
+    pub fn is_logged_on(self) -> bool {
+        matches!(
+            self,
+            SessionState::LoggedOn | SessionState::ResendInProgress
+        )
+    }
+
+    /// Whether a message of type `msg_type` can be sent in this state
+    ///
+    /// Only Logon can be sent before logging on and only Logout while the
+    /// logon is pending. Application messages wait for a resend to complete,
+    /// and after a Logout only the session-level messages needed to finish
+    /// the exchange are sent.
+    pub fn allows_outgoing(self, msg_type: &MsgType) -> bool {
+        match self {
+            SessionState::Disconnected | SessionState::Connecting | SessionState::Maintenance => {
+                *msg_type == MsgType::Logon
+            }
+            SessionState::LogonSent => *msg_type == MsgType::Logout,
+            SessionState::LoggedOn => *msg_type != MsgType::Logon,
+            SessionState::ResendInProgress => is_admin(msg_type) && *msg_type != MsgType::Logon,
+            SessionState::LogoutSent => {
+                is_admin(msg_type) && !matches!(msg_type, MsgType::Logon | MsgType::Logout)
+            }
+        }
+    }
+
+    /// Whether a message of type `msg_type` can be received in this state
+    ///
+    /// The counterparty answers a Logon with a Logon or a Logout, and can
+    /// refuse a connection with a Logout before any logon. Once logged on it
+    /// may send a Logon resetting the sequence numbers.
+    pub fn allows_incoming(self, msg_type: &MsgType) -> bool {
+        match self {
+            SessionState::Disconnected | SessionState::Connecting | SessionState::Maintenance => {
+                *msg_type == MsgType::Logout
+            }
+            SessionState::LogonSent => matches!(msg_type, MsgType::Logon | MsgType::Logout),
+            SessionState::LoggedOn => true,
+            SessionState::ResendInProgress | SessionState::LogoutSent => {
+                *msg_type != MsgType::Logon
+            }
+        }
+    }
+}
+
+/// Whether `msg_type` is a session-level (administrative) message
+pub(crate) fn is_admin(msg_type: &MsgType) -> bool {
+    matches!(
+        msg_type,
+        MsgType::Heartbeat
+            | MsgType::TestRequest
+            | MsgType::ResendRequest
+            | MsgType::Reject
+            | MsgType::SequenceReset
+            | MsgType::Logout
+            | MsgType::Logon
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SessionState::*;
+
+    const STATES: [SessionState; 7] = [
+        Disconnected,
+        Connecting,
+        LogonSent,
+        LoggedOn,
+        ResendInProgress,
+        LogoutSent,
+        Maintenance,
+    ];
+
+    #[test]
+    fn test_every_transition() {
+        let allowed = [
+            (Disconnected, Disconnected),
+            (Disconnected, Connecting),
+            (Disconnected, LogonSent),
+            (Disconnected, Maintenance),
+            (Connecting, Disconnected),
+            (Connecting, LogonSent),
+            (LogonSent, Disconnected),
+            (LogonSent, LoggedOn),
+            (LogonSent, LogoutSent),
+            (LoggedOn, Disconnected),
+            (LoggedOn, LoggedOn),
+            (LoggedOn, ResendInProgress),
+            (LoggedOn, LogoutSent),
+            (ResendInProgress, Disconnected),
+            (ResendInProgress, LoggedOn),
+            (ResendInProgress, LogoutSent),
+            (LogoutSent, Disconnected),
+            (Maintenance, Disconnected),
+            (Maintenance, Connecting),
+            (Maintenance, LogonSent),
+        ];
+        for from in STATES {
+            for to in STATES {
+                let legal = allowed.contains(&(from, to));
+                assert_eq!(from.can_transition_to(to), legal, "{from:?} -> {to:?}");
+                match from.transition(to) {
+                    Ok(state) => assert!(legal && state == to, "{from:?} -> {to:?}"),
+                    Err(DeribitFixError::Session(message)) => {
+                        assert!(!legal, "{from:?} -> {to:?}");
+                        assert!(message.contains(&format!("from {from:?} to {to:?}")));
+                    }
+                    Err(other) => panic!("Unexpected error {other:?}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_session_lifecycle() {
+        let state = [
+            Connecting,
+            LogonSent,
+            LoggedOn,
+            ResendInProgress,
+            LoggedOn,
+            LogoutSent,
+            Disconnected,
+        ]
+        .into_iter()
+        .try_fold(Disconnected, SessionState::transition)
+        .unwrap();
+        assert_eq!(state, Disconnected);
+    }
+
+    #[test]
+    fn test_logon_while_logged_on_stays_logged_on() {
+        assert_eq!(LoggedOn.transition(LoggedOn).unwrap(), LoggedOn);
+        assert!(LoggedOn.allows_incoming(&MsgType::Logon));
+        assert!(!LoggedOn.allows_outgoing(&MsgType::Logon));
+    }
+
+    #[test]
+    fn test_allowed_message_types() {
+        assert!(Disconnected.allows_outgoing(&MsgType::Logon));
+        assert!(!Disconnected.allows_outgoing(&MsgType::NewOrderSingle));
+        assert!(!Connecting.allows_outgoing(&MsgType::Heartbeat));
+        assert!(LogonSent.allows_outgoing(&MsgType::Logout));
+        assert!(!LogonSent.allows_outgoing(&MsgType::NewOrderSingle));
+        assert!(LoggedOn.allows_outgoing(&MsgType::NewOrderSingle));
+        assert!(!LoggedOn.allows_outgoing(&MsgType::Logon));
+        assert!(ResendInProgress.allows_outgoing(&MsgType::Heartbeat));
+        assert!(!ResendInProgress.allows_outgoing(&MsgType::NewOrderSingle));
+        assert!(LogoutSent.allows_outgoing(&MsgType::SequenceReset));
+        assert!(!LogoutSent.allows_outgoing(&MsgType::Logout));
+        assert!(Maintenance.allows_outgoing(&MsgType::Logon));
+        assert!(!Maintenance.allows_outgoing(&MsgType::Heartbeat));
+
+        assert!(Disconnected.allows_incoming(&MsgType::Logout));
+        assert!(!Disconnected.allows_incoming(&MsgType::ExecutionReport));
+        assert!(LogonSent.allows_incoming(&MsgType::Logon));
+        assert!(!LogonSent.allows_incoming(&MsgType::ExecutionReport));
+        assert!(LoggedOn.allows_incoming(&MsgType::ExecutionReport));
+        assert!(LoggedOn.allows_incoming(&MsgType::Logon));
+        assert!(!ResendInProgress.allows_incoming(&MsgType::Logon));
+        assert!(LogoutSent.allows_incoming(&MsgType::Logout));
+
+        assert!(LoggedOn.is_logged_on() && ResendInProgress.is_logged_on());
+        assert!(!LogonSent.is_logged_on() && !LogoutSent.is_logged_on());
+        assert!(!Maintenance.is_logged_on());
+    }
+}
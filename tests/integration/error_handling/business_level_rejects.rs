@@ -66,7 +66,7 @@ async fn test_business_message_reject_unsupported_message_type() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -210,7 +210,7 @@ async fn test_business_message_reject_invalid_application_request() -> Result<()
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Connect and logon
     client.connect().await?;
@@ -325,7 +325,7 @@ async fn test_business_message_reject_application_not_available() -> Result<()>
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Connect and logon
     client.connect().await?;
@@ -444,7 +444,7 @@ async fn test_business_message_reject_comprehensive_scenarios() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Connect and logon
     client.connect().await?;
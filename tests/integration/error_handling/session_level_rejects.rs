@@ -68,7 +68,7 @@ async fn test_reject_message_with_missing_required_tag() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -212,7 +212,7 @@ async fn test_reject_message_with_undefined_tag() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Connect and logon
     client.connect().await?;
@@ -325,7 +325,7 @@ async fn test_reject_message_with_invalid_tag_value() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Connect and logon
     client.connect().await?;
@@ -442,7 +442,7 @@ async fn test_reject_message_comprehensive_scenarios() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Connect and logon
     client.connect().await?;
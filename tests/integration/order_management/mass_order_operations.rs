@@ -13,7 +13,7 @@ use std::time::Duration;
 use tokio::time::{sleep, timeout};
 use tracing::{debug, info, warn};
 
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::model::request::NewOrderRequest;
 use deribit_fix::prelude::*;
 use deribit_fix::session::SessionState;
 
@@ -69,7 +69,7 @@ async fn test_mass_order_operations() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -119,32 +119,19 @@ async fn test_mass_order_operations() -> Result<()> {
     for i in 0..num_orders {
         let price = base_price + (i as f64 * 100.0); // Different prices to avoid conflicts
 
-        let order_request = NewOrderRequest {
-            instrument_name: symbol.clone(),
-            side: OrderSide::Buy,
-            order_type: OrderType::Limit,
-            amount: quantity,
-            price: Some(price),
-            time_in_force: TimeInForce::GoodTilCancelled,
-            post_only: Some(true), // Ensure they won't fill immediately
-            reduce_only: Some(false),
-            client_order_id: Some(format!(
+        let order_request = NewOrderRequest::limit_buy(symbol.clone(), quantity, price)
+            .with_post_only(true)
+            .with_reduce_only(false)
+            .with_client_order_id(format!(
                 "TEST_MASS_{}_{}",
                 i,
                 chrono::Utc::now().timestamp_millis()
-            )),
-            label: Some(format!(
+            ))
+            .with_label(format!(
                 "TEST_MASS_{}_{}",
                 i,
                 chrono::Utc::now().timestamp_millis()
-            )),
-            stop_price: None,
-            trigger: None,
-            advanced: None,
-            max_show: None,
-            reject_post_only: None,
-            valid_until: None,
-        };
+            ));
 
         // Send the order
         let order_id = client.send_order(order_request).await?;
@@ -220,7 +207,8 @@ async fn test_mass_order_operations() -> Result<()> {
     // Individual cancel requests (simulating mass cancel behavior)
     for order_id in &order_ids {
         if let Ok(()) = client.cancel_order(order_id.clone()).await {
-            info!("📤 Cancel request sent for OrderID: {}", order_id);
+            info!("✅ Cancel confirmed for OrderID: {}", order_id);
+            canceled_orders.insert(order_id.clone());
         }
     }
 
@@ -361,7 +349,7 @@ async fn test_mass_cancel_empty_instrument() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -13,7 +13,7 @@ use std::time::Duration;
 use tokio::time::{sleep, timeout};
 use tracing::{debug, info, warn};
 
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::model::request::NewOrderRequest;
 use deribit_fix::prelude::*;
 use deribit_fix::session::SessionState;
 
@@ -69,7 +69,7 @@ async fn test_new_order_single_limit_buy() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -114,30 +114,17 @@ async fn test_new_order_single_limit_buy() -> Result<()> {
     let price = 50000.0; // Set limit price
     let quantity = 10.0; // Small quantity for testing
 
-    let order_request = NewOrderRequest {
-        instrument_name: symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Limit,
-        amount: quantity,
-        price: Some(price),
-        time_in_force: TimeInForce::GoodTilCancelled,
-        post_only: Some(false),
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let order_request = NewOrderRequest::limit_buy(symbol.clone(), quantity, price)
+        .with_post_only(false)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "TEST_LIMIT_BUY_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "TEST_LIMIT_BUY_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     // Send the order
     let order_id = client.send_order(order_request).await?;
@@ -242,7 +229,7 @@ async fn test_new_order_single_market_sell() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -286,30 +273,17 @@ async fn test_new_order_single_market_sell() -> Result<()> {
     let symbol = "BTC-PERPETUAL".to_string();
     let quantity = 10.0; // Small quantity for testing
 
-    let order_request = NewOrderRequest {
-        instrument_name: symbol.clone(),
-        side: OrderSide::Sell,
-        order_type: OrderType::Market,
-        amount: quantity,
-        price: None,
-        time_in_force: TimeInForce::ImmediateOrCancel,
-        post_only: Some(false),
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let order_request = NewOrderRequest::market_sell(symbol.clone(), quantity)
+        .with_post_only(false)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "TEST_MARKET_SELL_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "TEST_MARKET_SELL_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     // Send the order
     let order_id = client.send_order(order_request).await?;
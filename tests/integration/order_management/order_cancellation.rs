@@ -13,7 +13,7 @@ use std::time::Duration;
 use tokio::time::{sleep, timeout};
 use tracing::{debug, info, warn};
 
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::model::request::NewOrderRequest;
 use deribit_fix::prelude::*;
 use deribit_fix::session::SessionState;
 
@@ -69,7 +69,7 @@ async fn test_order_cancellation_success() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -114,30 +114,17 @@ async fn test_order_cancellation_success() -> Result<()> {
     let price = 20000.0; // Far below market price to avoid immediate fill
     let quantity = 10.0;
 
-    let order_request = NewOrderRequest {
-        instrument_name: symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Limit,
-        amount: quantity,
-        price: Some(price),
-        time_in_force: TimeInForce::GoodTilCancelled,
-        post_only: Some(true), // Ensure it won't fill immediately
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let order_request = NewOrderRequest::limit_buy(symbol.clone(), quantity, price)
+        .with_post_only(true)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "TEST_CANCEL_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "TEST_CANCEL_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     // Send the order
     let order_id = client.send_order(order_request).await?;
@@ -199,73 +186,30 @@ async fn test_order_cancellation_success() -> Result<()> {
 
     let cancel_order_id = server_order_id.unwrap_or(order_id.clone());
 
-    // Step 6: Cancel the order
+    // Step 6: Cancel the order and wait for the ExecutionReport confirming it
     info!("🚫 Sending order cancellation request...");
-    client
+    let cancel_report = client
         .cancel_order_with_symbol(cancel_order_id.clone(), Some(symbol.clone()))
         .await?;
+    let CancelReport::Order(report) = cancel_report else {
+        panic!("Expected the ExecutionReport of the cancelled order, got {cancel_report:?}");
+    };
     info!(
-        "📤 Order cancellation request sent for OrderID: {}",
+        "✅ Order cancellation confirmed for OrderID: {}",
         cancel_order_id
     );
 
-    // Step 7: Wait for ExecutionReport confirming order is Canceled
-    info!("👁️ Waiting for ExecutionReport confirming order is Canceled...");
-    let mut order_canceled_confirmed = false;
-    let start_time = std::time::Instant::now();
-
-    while start_time.elapsed() < monitor_duration && !order_canceled_confirmed {
-        match timeout(Duration::from_millis(500), client.receive_message()).await {
-            Ok(Ok(Some(message))) => {
-                if let Some(msg_type) = message.get_field(35)
-                    && msg_type == "8"
-                {
-                    // ExecutionReport
-                    debug!("📊 Received ExecutionReport: {:?}", message);
-
-                    if let Some(server_ord_id) = message.get_field(37)
-                        && server_ord_id == &cancel_order_id
-                        && let Some(ord_status) = message.get_field(39)
-                        && ord_status == "4"
-                    {
-                        // Canceled
-                        info!("✅ Order status confirmed as Canceled: {}", ord_status);
-                        order_canceled_confirmed = true;
-
-                        // Additional validation for canceled order
-                        if let Some(exec_type) = message.get_field(150) {
-                            // Deribit uses "I" as a custom ExecType for various order states including cancellation
-                            assert!(
-                                exec_type == "4" || exec_type == "I",
-                                "ExecType should be Canceled (4) or Deribit custom (I), got: {}",
-                                exec_type
-                            );
-                            info!("✅ ExecType confirmed: {}", exec_type);
-                        }
-
-                        if let Some(recv_symbol) = message.get_field(55) {
-                            assert_eq!(recv_symbol, &symbol, "Symbol should match");
-                            info!("✅ Symbol confirmed: {}", recv_symbol);
-                        }
-                    }
-                }
-            }
-            Ok(Ok(None)) => {
-                debug!("⏳ No message received, continuing to wait...");
-            }
-            Ok(Err(e)) => {
-                warn!("❌ Error receiving message: {:?}", e);
-            }
-            Err(_) => {
-                debug!("⏰ Timeout waiting for message, continuing...");
-            }
-        }
-    }
-
+    // Deribit uses "I" as a custom ExecType for various order states including cancellation
+    let exec_type = char::from(report.exec_type);
     assert!(
-        order_canceled_confirmed,
-        "Expected ExecutionReport with Canceled order status was not received"
+        exec_type == '4' || exec_type == 'I',
+        "ExecType should be Canceled (4) or Deribit custom (I), got: {}",
+        exec_type
     );
+    info!("✅ ExecType confirmed: {}", exec_type);
+
+    assert_eq!(report.symbol, symbol, "Symbol should match");
+    info!("✅ Symbol confirmed: {}", report.symbol);
 
     // Clean up
     client.disconnect().await.ok();
@@ -293,7 +237,7 @@ async fn test_order_cancellation_unknown_order() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -12,7 +12,7 @@ use std::time::Duration;
 use tokio::time::{sleep, timeout};
 use tracing::{debug, info, warn};
 
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::model::request::{NewOrderRequest, TimeInForce};
 use deribit_fix::prelude::*;
 use deribit_fix::session::SessionState;
 
@@ -68,7 +68,7 @@ async fn test_post_only_order_rejection() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -114,30 +114,17 @@ async fn test_post_only_order_rejection() -> Result<()> {
     let aggressive_price = 200000.0; // Unrealistically high price to ensure immediate execution
     let quantity = 10.0;
 
-    let order_request = NewOrderRequest {
-        instrument_name: symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Limit,
-        amount: quantity,
-        price: Some(aggressive_price),
-        time_in_force: TimeInForce::GoodTilCancelled,
-        post_only: Some(true), // This should prevent immediate execution
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let order_request = NewOrderRequest::limit_buy(symbol.clone(), quantity, aggressive_price)
+        .with_post_only(true)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "TEST_POSTONLY_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "TEST_POSTONLY_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     // Send the order
     let order_id = client.send_order(order_request).await?;
@@ -252,7 +239,7 @@ async fn test_immediate_or_cancel_behavior() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -297,30 +284,18 @@ async fn test_immediate_or_cancel_behavior() -> Result<()> {
     let price = 10000.0; // Far below market to avoid immediate fill
     let quantity = 10.0;
 
-    let order_request = NewOrderRequest {
-        instrument_name: symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Limit,
-        amount: quantity,
-        price: Some(price),
-        time_in_force: TimeInForce::ImmediateOrCancel,
-        post_only: Some(false),
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let order_request = NewOrderRequest::limit_buy(symbol.clone(), quantity, price)
+        .with_time_in_force(TimeInForce::ImmediateOrCancel)
+        .with_post_only(false)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "TEST_IOC_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "TEST_IOC_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     // Send the order
     let order_id = client.send_order(order_request).await?;
@@ -429,7 +404,7 @@ async fn test_fill_or_kill_behavior() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -474,30 +449,18 @@ async fn test_fill_or_kill_behavior() -> Result<()> {
     let price = 5000.0; // Very low price, unlikely to fill completely
     let quantity = 1.0; // Larger quantity to make complete fill unlikely
 
-    let order_request = NewOrderRequest {
-        instrument_name: symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Limit,
-        amount: quantity,
-        price: Some(price),
-        time_in_force: TimeInForce::FillOrKill,
-        post_only: Some(false),
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let order_request = NewOrderRequest::limit_buy(symbol.clone(), quantity, price)
+        .with_time_in_force(TimeInForce::FillOrKill)
+        .with_post_only(false)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "TEST_FOK_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "TEST_FOK_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     // Send the order
     let order_id = client.send_order(order_request).await?;
@@ -14,7 +14,7 @@ use std::time::Duration;
 use tokio::time::{sleep, timeout};
 use tracing::{debug, info, warn};
 
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::model::request::NewOrderRequest;
 use deribit_fix::prelude::*;
 use deribit_fix::session::SessionState;
 
@@ -70,7 +70,7 @@ async fn test_cancel_filled_order_rejection() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -114,30 +114,17 @@ async fn test_cancel_filled_order_rejection() -> Result<()> {
     let symbol = "BTC-PERPETUAL".to_string();
     let quantity = 10.0; // Very small quantity for testing
 
-    let order_request = NewOrderRequest {
-        instrument_name: symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Market,
-        amount: quantity,
-        price: None,
-        time_in_force: TimeInForce::ImmediateOrCancel,
-        post_only: Some(false),
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let order_request = NewOrderRequest::market_buy(symbol.clone(), quantity)
+        .with_post_only(false)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "TEST_FILL_CANCEL_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "TEST_FILL_CANCEL_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     // Send the order
     let order_id = client.send_order(order_request).await?;
@@ -302,7 +289,7 @@ async fn test_cancel_nonexistent_order_rejection() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -19,7 +19,7 @@ use std::time::Duration;
 use tokio::time::{sleep, timeout};
 use tracing::{debug, info, warn};
 
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::model::request::NewOrderRequest;
 use deribit_fix::prelude::*;
 use deribit_fix::session::SessionState;
 
@@ -75,7 +75,7 @@ async fn test_full_trade_lifecycle() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 2: Connect and perform logon
@@ -183,30 +183,18 @@ async fn test_full_trade_lifecycle() -> Result<()> {
     let limit_price = market_price.map(|p| p * 0.8).unwrap_or(40000.0); // 20% below market or default
     let quantity = 10.0; // Small quantity for testing
 
-    let limit_order_request = NewOrderRequest {
-        instrument_name: target_symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Limit,
-        amount: quantity,
-        price: Some(limit_price),
-        time_in_force: TimeInForce::GoodTilCancelled,
-        post_only: Some(true), // Ensure it won't fill immediately
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
-            "LIMIT_ORDER_{}",
-            chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
-            "LIMIT_ORDER_{}",
-            chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+    let limit_order_request =
+        NewOrderRequest::limit_buy(target_symbol.clone(), quantity, limit_price)
+            .with_post_only(true)
+            .with_reduce_only(false)
+            .with_client_order_id(format!(
+                "LIMIT_ORDER_{}",
+                chrono::Utc::now().timestamp_millis()
+            ))
+            .with_label(format!(
+                "LIMIT_ORDER_{}",
+                chrono::Utc::now().timestamp_millis()
+            ));
 
     let limit_order_id = client.send_order(limit_order_request).await?;
     info!(
@@ -302,30 +290,17 @@ async fn test_full_trade_lifecycle() -> Result<()> {
     // Step 7: Place a market order
     info!("📤 Step 7: Placing market order...");
 
-    let market_order_request = NewOrderRequest {
-        instrument_name: target_symbol.clone(),
-        side: OrderSide::Buy,
-        order_type: OrderType::Market,
-        amount: quantity,
-        price: None,
-        time_in_force: TimeInForce::ImmediateOrCancel,
-        post_only: Some(false),
-        reduce_only: Some(false),
-        client_order_id: Some(format!(
+    let market_order_request = NewOrderRequest::market_buy(target_symbol.clone(), quantity)
+        .with_post_only(false)
+        .with_reduce_only(false)
+        .with_client_order_id(format!(
             "MARKET_ORDER_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        label: Some(format!(
+        ))
+        .with_label(format!(
             "MARKET_ORDER_{}",
             chrono::Utc::now().timestamp_millis()
-        )),
-        stop_price: None,
-        trigger: None,
-        advanced: None,
-        max_show: None,
-        reject_post_only: None,
-        valid_until: None,
-    };
+        ));
 
     let market_order_id = client.send_order(market_order_request).await?;
     info!(
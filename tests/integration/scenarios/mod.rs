@@ -1,3 +1,4 @@
 //! End-to-end scenario tests.
 
 pub mod full_trade_lifecycle;
+pub mod order_entry_certification;
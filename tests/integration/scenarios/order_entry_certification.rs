@@ -0,0 +1,100 @@
+//! TEST 91: ORDER ENTRY CERTIFICATION (SCENARIO)
+//!
+//! The certification flow written as a declarative scenario and run with the
+//! `ScenarioRunner` against the Deribit test server:
+//! 1. Logon.
+//! 2. Subscribe to BTC-PERPETUAL and expect a `MarketDataSnapshotFullRefresh` (W).
+//! 3. Place a post-only limit order far below the market and expect it to be acknowledged (8).
+//! 4. Cancel it and expect the cancel confirmation (8).
+//! 5. Logout.
+//!
+//! Needs test server credentials in `.env`, so it only runs when asked for
+//! with `cargo test -- --ignored`.
+
+use serial_test::serial;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+use deribit_fix::model::request::NewOrderRequest;
+use deribit_fix::model::types::MsgType;
+use deribit_fix::prelude::*;
+use deribit_fix::scenario::{ExpectedMessage, Scenario, ScenarioRunner};
+
+/// Check if .env file exists and contains required variables
+fn check_env_file() -> Result<()> {
+    // Check if .env file exists
+    if !Path::new(".env").exists() {
+        return Err(DeribitFixError::Config(
+            "Missing .env file. Please create one with DERIBIT_USERNAME and DERIBIT_PASSWORD"
+                .to_string(),
+        ));
+    }
+
+    // Load environment variables
+    dotenv::dotenv().ok();
+
+    // Check required variables
+    let required_vars = [
+        "DERIBIT_USERNAME",
+        "DERIBIT_PASSWORD",
+        "DERIBIT_HOST",
+        "DERIBIT_PORT",
+    ];
+
+    for var in &required_vars {
+        if std::env::var(var).is_err() {
+            return Err(DeribitFixError::Config(format!(
+                "Missing required environment variable: {}",
+                var
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Order entry flow shared by certification runs
+fn order_entry_scenario() -> Scenario {
+    let symbol = "BTC-PERPETUAL";
+    let mut order = NewOrderRequest::limit_buy(symbol.to_string(), 10.0, 1_000.0);
+    order.post_only = Some(true);
+    let report = || ExpectedMessage::new(MsgType::ExecutionReport).with_order("bid");
+
+    Scenario::new("order entry certification")
+        .logon()
+        .subscribe(symbol)
+        .expect(ExpectedMessage::new(MsgType::MarketDataSnapshotFullRefresh).with_field(55, symbol))
+        .send_order("bid", order)
+        .expect(report().with_field(39, "0"))
+        .cancel("bid")
+        .expect(report().with_field(39, "4"))
+        .logout()
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "requires Deribit test server credentials in .env"]
+async fn test_order_entry_certification_scenario() -> Result<()> {
+    setup_logger();
+
+    info!("=== Integration Test: Order Entry Certification (SCENARIO) ===");
+
+    check_env_file()?;
+    info!("✅ Environment file validation passed");
+
+    let config = DeribitFixConfig::new();
+    config.validate()?;
+    let client = DeribitFixClient::new(&config).await?;
+
+    let mut runner = ScenarioRunner::new(client).with_step_timeout(Duration::from_secs(60));
+    let report = runner.run(&order_entry_scenario()).await?;
+    info!("{}", report);
+    if !report.passed() {
+        runner.client().disconnect().await.ok();
+    }
+    report.into_result()?;
+
+    info!("🎉 Order entry certification scenario passed");
+    Ok(())
+}
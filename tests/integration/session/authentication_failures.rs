@@ -68,7 +68,7 @@ async fn test_authentication_with_wrong_password() -> Result<()> {
     info!("✅ Configuration created with invalid password");
 
     // Step 2: Create client
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 3: Attempt to connect with wrong credentials
@@ -154,7 +154,7 @@ async fn test_authentication_with_invalid_sender_comp_id() -> Result<()> {
         config.sender_comp_id
     );
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Attempt to connect with invalid sender comp ID
     info!("🔌 Attempting to connect with invalid SenderCompID...");
@@ -231,7 +231,7 @@ async fn test_authentication_with_invalid_username() -> Result<()> {
         config.username
     );
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Attempt to connect with invalid username
     info!("🔌 Attempting to connect with invalid username...");
@@ -337,7 +337,7 @@ async fn test_multiple_failed_authentication_attempts() -> Result<()> {
             DeribitFixClient::new(&config).await
         };
 
-        let mut client = match client_result {
+        let client = match client_result {
             Ok(client) => client,
             Err(_) => {
                 info!("✅ Attempt {} failed as expected (validation error)", i + 1);
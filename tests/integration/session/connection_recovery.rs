@@ -67,7 +67,7 @@ async fn test_basic_connection_recovery() -> Result<()> {
     let config = DeribitFixConfig::new();
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config.clone()).await?;
+    let client = DeribitFixClient::new(&config.clone()).await?;
     info!("✅ Initial client created successfully");
 
     // Step 2: Establish initial connection
@@ -138,7 +138,7 @@ async fn test_basic_connection_recovery() -> Result<()> {
 
     // Step 6: Attempt to recover connection
     info!("🔄 Attempting connection recovery...");
-    let mut recovery_client = DeribitFixClient::new(&config).await?;
+    let recovery_client = DeribitFixClient::new(&config).await?;
 
     let recovery_result = recovery_client.connect().await;
     match recovery_result {
@@ -229,7 +229,7 @@ async fn test_connection_resilience() -> Result<()> {
     for cycle in 1..=connection_cycles {
         info!("🔄 Connection cycle {}/{}", cycle, connection_cycles);
 
-        let mut client = DeribitFixClient::new(&config.clone()).await?;
+        let client = DeribitFixClient::new(&config.clone()).await?;
 
         // Connect
         let connect_result = client.connect().await;
@@ -286,7 +286,7 @@ async fn test_session_state_after_reconnection() -> Result<()> {
 
     // First connection - establish baseline
     info!("🔌 Establishing baseline connection...");
-    let mut client1 = DeribitFixClient::new(&config.clone()).await?;
+    let client1 = DeribitFixClient::new(&config.clone()).await?;
 
     match client1.connect().await {
         Ok(_) => {
@@ -323,7 +323,7 @@ async fn test_session_state_after_reconnection() -> Result<()> {
 
             // Second connection - test recovery
             info!("🔄 Testing reconnection session state...");
-            let mut client2 = DeribitFixClient::new(&config).await?;
+            let client2 = DeribitFixClient::new(&config).await?;
 
             match client2.connect().await {
                 Ok(_) => {
@@ -397,7 +397,7 @@ async fn test_rapid_connect_disconnect_cycles() -> Result<()> {
     for cycle in 1..=rapid_cycles {
         info!("⚡ Rapid cycle {}/{}", cycle, rapid_cycles);
 
-        let mut client = DeribitFixClient::new(&config.clone()).await?;
+        let client = DeribitFixClient::new(&config.clone()).await?;
 
         // Quick connect
         match client.connect().await {
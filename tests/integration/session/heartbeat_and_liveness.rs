@@ -72,7 +72,7 @@ async fn test_heartbeat_response_to_test_request() -> Result<()> {
     );
 
     // Step 2: Create client and connect
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
     info!("✅ Client created successfully");
 
     // Step 3: Connect and perform logon
@@ -228,7 +228,7 @@ async fn test_session_liveness_with_inactivity() -> Result<()> {
     config.heartbeat_interval = 5; // 5 seconds for faster testing
     config.validate()?;
 
-    let mut client = DeribitFixClient::new(&config).await?;
+    let client = DeribitFixClient::new(&config).await?;
 
     // Connect and logon
     client.connect().await?;
@@ -338,7 +338,7 @@ async fn test_heartbeat_interval_configuration() -> Result<()> {
         config.heartbeat_interval = interval;
         config.validate()?;
 
-        let mut client = DeribitFixClient::new(&config).await?;
+        let client = DeribitFixClient::new(&config).await?;
 
         // Quick connect/disconnect test to verify the configuration is accepted
         match client.connect().await {
@@ -0,0 +1,108 @@
+// Unit tests for the cancel-on-disconnect query and re-arm API
+
+use deribit_fix::client::DeribitFixClient;
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::model::types::MsgType;
+use deribit_fix::scenario::{MockExchange, MockExchangeHandle};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_client(cancel_on_disconnect: bool) -> (DeribitFixClient, MockExchangeHandle) {
+        let (connector, exchange) = MockExchange::new().spawn();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_cancel_on_disconnect(cancel_on_disconnect)
+            .with_reconnection(1, Duration::from_millis(10));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+        (client, exchange)
+    }
+
+    /// Process messages until the session is logged on
+    async fn wait_for_logon(client: &DeribitFixClient) {
+        for _ in 0..50 {
+            if client
+                .get_session_state()
+                .await
+                .is_some_and(|state| state.is_logged_on())
+            {
+                return;
+            }
+            let _ =
+                tokio::time::timeout(Duration::from_millis(100), client.receive_message()).await;
+        }
+        panic!("Not logged on");
+    }
+
+    fn sent(exchange: &MockExchangeHandle, msg_type: MsgType) -> Vec<Option<String>> {
+        exchange
+            .received()
+            .iter()
+            .filter(|message| message.msg_type().as_ref() == Some(&msg_type))
+            .map(|message| message.get_field(9001).or(message.get_field(9003)).cloned())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_on_disconnect_is_reported_after_logon() {
+        let (client, _exchange) = create_client(true).await;
+        assert_eq!(client.cancel_on_disconnect().await, None);
+
+        client.connect().await.unwrap();
+        wait_for_logon(&client).await;
+
+        let status = client.cancel_on_disconnect().await.unwrap();
+        assert!(status.requested);
+        assert_eq!(status.confirmed, Some(true));
+        assert!(status.is_active());
+        let stats = client.connection_stats().await.unwrap();
+        assert_eq!(stats.cancel_on_disconnect, Some(true));
+    }
+
+    /// Changing the setting logs out keeping the orders open and logs on
+    /// again with the new CancelOnDisconnect (9001)
+    #[tokio::test]
+    async fn test_set_cancel_on_disconnect_logs_on_again() {
+        let (client, exchange) = create_client(true).await;
+        client.connect().await.unwrap();
+        wait_for_logon(&client).await;
+
+        let status = client
+            .set_cancel_on_disconnect(false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!status.requested);
+        assert_eq!(status.confirmed, Some(false));
+        assert!(!status.is_active());
+        assert!(
+            client
+                .get_session_state()
+                .await
+                .is_some_and(|state| state.is_logged_on())
+        );
+        assert_eq!(
+            sent(&exchange, MsgType::Logon),
+            vec![Some("Y".to_string()), Some("N".to_string())]
+        );
+        assert_eq!(
+            sent(&exchange, MsgType::Logout),
+            vec![Some("Y".to_string())]
+        );
+        let stats = client.connection_stats().await.unwrap();
+        assert_eq!(stats.cancel_on_disconnect, Some(false));
+
+        // Already disabled, nothing is sent
+        let unchanged = client.set_cancel_on_disconnect(false).await.unwrap();
+        assert_eq!(unchanged, Some(status));
+        assert_eq!(sent(&exchange, MsgType::Logon).len(), 2);
+    }
+}
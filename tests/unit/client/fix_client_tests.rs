@@ -1,10 +1,25 @@
 // Unit tests for FixClient functionality
 
+use super::super::support::{frame, next_message};
 use deribit_fix::client::DeribitFixClient;
 use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::MemoryConnector;
 use deribit_fix::error::DeribitFixError;
-use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType, TimeInForce};
+use deribit_fix::message::{MdReqRejReason, QuoteRequestRejectReason, RfqRequest};
+use deribit_fix::model::ladder::Ladder;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::message_filter::MsgFilter;
+use deribit_fix::model::order_template::OrderTemplate;
+use deribit_fix::model::quoting::{QuoteSpec, QuotingEngine};
+use deribit_fix::model::request::{NewOrderRequest, OrderSide, OrderType};
+use deribit_fix::model::types::{ExecType, MsgType};
+use deribit_fix::session::{
+    CancelToken, ManualClock, RequestOptions, SESSION_MESSAGE_CHANNEL_CAPACITY, SessionState,
+};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 
 #[cfg(test)]
 mod tests {
@@ -128,24 +143,10 @@ mod tests {
         let client = DeribitFixClient::new(&config).await.unwrap();
 
         // Test send_order when not connected
-        let order = NewOrderRequest {
-            instrument_name: "BTC-PERPETUAL".to_string(),
-            side: OrderSide::Buy,
-            order_type: OrderType::Limit,
-            amount: 100.0,
-            price: Some(50000.0),
-            time_in_force: TimeInForce::GoodTilCancelled,
-            post_only: Some(false),
-            reduce_only: Some(false),
-            client_order_id: Some("test_order_1".to_string()),
-            label: None,
-            stop_price: None,
-            trigger: None,
-            advanced: None,
-            max_show: None,
-            reject_post_only: None,
-            valid_until: None,
-        };
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 100.0, 50000.0)
+            .with_post_only(false)
+            .with_reduce_only(false)
+            .with_client_order_id("test_order_1".to_string());
 
         let result = client.send_order(order).await;
         assert!(result.is_err(), "send_order should fail when not connected");
@@ -209,6 +210,13 @@ mod tests {
             _ => panic!("Expected Session error"),
         }
 
+        // Test close_position when not connected
+        let result = client.close_position("BTC-PERPETUAL", 1.0).await;
+        assert!(
+            matches!(result, Err(DeribitFixError::Session(_))),
+            "close_position should fail when not connected"
+        );
+
         // Test receive_message when not connected
         let result = client.receive_message().await;
         assert!(
@@ -226,6 +234,360 @@ mod tests {
         }
     }
 
+    /// The client is a cloneable handle that can be moved across tasks
+    #[test]
+    fn test_client_is_clone_send_sync() {
+        fn assert_handle<T: Clone + Send + Sync + 'static>() {}
+        assert_handle::<DeribitFixClient>();
+    }
+
+    /// Clones share one session and can submit orders concurrently
+    #[tokio::test]
+    async fn test_cloned_clients_share_session() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 8192];
+            while let Ok(Ok(n)) =
+                tokio::time::timeout(Duration::from_millis(500), socket.read(&mut buf)).await
+            {
+                if n == 0 {
+                    break;
+                }
+                received.extend_from_slice(&buf[..n]);
+            }
+            String::from_utf8_lossy(&received)
+                .matches("\x0135=D\x01")
+                .count()
+        });
+
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000));
+        let client = DeribitFixClient::new(&config).await.unwrap();
+        let clone = client.clone();
+        clone.connect().await.unwrap();
+        assert!(client.is_connected(), "Clones share the connection");
+
+        let tasks: Vec<_> = (0..4)
+            .map(|i| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    client
+                        .send_order(NewOrderRequest::limit_buy(
+                            "BTC-PERPETUAL".to_string(),
+                            10.0 + f64::from(i),
+                            50_000.0,
+                        ))
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert!(task.await.unwrap().is_ok());
+        }
+
+        assert_eq!(server.await.unwrap(), 4);
+        // The mock server has already closed the socket, so Logout may fail
+        let _ = client.disconnect().await;
+        assert!(!clone.is_connected(), "Disconnect applies to every clone");
+    }
+
+    /// Custom messages get a session header and are matched to their response
+    #[tokio::test]
+    async fn test_send_custom_waits_for_response_or_reject() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let header = "49=DERIBIT\x0156=CLIENT\x0152=20260101-00:00:00.000\x01";
+            let mut seq = 0;
+            let mut buf = [0u8; 8192];
+            while let Ok(Ok(n)) =
+                tokio::time::timeout(Duration::from_secs(2), socket.read(&mut buf)).await
+            {
+                if n == 0 {
+                    break;
+                }
+                let received = String::from_utf8_lossy(&buf[..n]).to_string();
+                for part in received.split("8=FIX.4.4").filter(|p| !p.is_empty()) {
+                    let Ok(message) = FixMessage::parse(&format!("8=FIX.4.4{part}")) else {
+                        continue;
+                    };
+                    let reply = match message.get_field(35).map(String::as_str) {
+                        Some("U99") => {
+                            seq += 1;
+                            format!(
+                                "35=BF\x0134={seq}\x01{header}923={}\x01926=1\x01",
+                                message.get_field(923).unwrap()
+                            )
+                        }
+                        Some("U98") => {
+                            seq += 1;
+                            format!(
+                                "35=3\x0134={seq}\x01{header}45={}\x01372=U98\x01373=11\x01\
+                                 58=Unsupported message\x01",
+                                message.get_field(34).unwrap()
+                            )
+                        }
+                        Some("U97") => {
+                            seq += 1;
+                            format!(
+                                "35=Y\x0134={seq}\x01{header}262={}\x01281=3\x0158=Not permitted\x01",
+                                message.get_field(262).unwrap()
+                            )
+                        }
+                        _ => continue,
+                    };
+                    let _ = socket.write_all(frame(&reply).as_bytes()).await;
+                }
+            }
+        });
+
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000));
+        let client = DeribitFixClient::new(&config).await.unwrap();
+        client.connect().await.unwrap();
+
+        assert!(
+            client.send_custom("A", vec![]).await.is_err(),
+            "Session-level messages are refused"
+        );
+
+        let pending = client
+            .send_custom(
+                "U99",
+                vec![
+                    (923, "REQ1".to_string()),
+                    (100010, "experimental".to_string()),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pending.msg_seq_num(),
+            Some(2),
+            "Logon used the first sequence number"
+        );
+        let response = pending.response().await.unwrap();
+        assert_eq!(response.get_field(35).unwrap(), "BF");
+        assert_eq!(response.get_field(923).unwrap(), "REQ1");
+
+        let pending = client
+            .send_custom("U98", vec![(923, "REQ2".to_string())])
+            .await
+            .unwrap();
+        match pending.response().await {
+            Err(DeribitFixError::MessageRejected {
+                ref_seq_num,
+                ref_msg_type,
+                reason,
+                text,
+            }) => {
+                assert_eq!(ref_seq_num, Some(3));
+                assert_eq!(ref_msg_type.as_deref(), Some("U98"));
+                assert_eq!(reason, Some(11));
+                assert_eq!(text.as_deref(), Some("Unsupported message"));
+            }
+            other => panic!("Expected reject, got {other:?}"),
+        }
+
+        // A response rejecting the request keeps the exchange's explanation
+        let pending = client
+            .send_custom("U97", vec![(262, "MDREQ".to_string())])
+            .await
+            .unwrap();
+        let error = pending.response().await.unwrap_err();
+        assert!(matches!(
+            &error,
+            DeribitFixError::MarketDataRejected { md_req_id, reason: Some(MdReqRejReason::InsufficientPermissions), .. }
+                if md_req_id == "MDREQ"
+        ));
+        assert_eq!(error.reject_text(), Some("Not permitted"));
+        assert!(error.to_string().contains("Not permitted"));
+
+        let _ = client.disconnect().await;
+    }
+
+    /// Request options bound the wait of a call on one handle only
+    #[tokio::test]
+    async fn test_request_options_bound_calls() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 8192];
+            while let Ok(n) = socket.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+            }
+        });
+
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+            .with_request_timeout(Duration::from_millis(200));
+        let client = DeribitFixClient::new(&config).await.unwrap();
+        client.connect().await.unwrap();
+
+        let bounded = client
+            .with_request_options(RequestOptions::new().with_timeout(Duration::from_millis(100)));
+        assert!(matches!(
+            bounded.ping().await,
+            Err(DeribitFixError::Timeout(_))
+        ));
+
+        let token = CancelToken::new();
+        token.cancel();
+        let cancelled = client.with_request_options(RequestOptions::new().with_cancel_token(token));
+        assert!(matches!(
+            cancelled.get_account_summary("BTC").await,
+            Err(DeribitFixError::Cancelled(_))
+        ));
+
+        // The options of other handles do not leak into the session
+        assert!(matches!(
+            client.ping().await,
+            Err(DeribitFixError::Timeout(_))
+        ));
+
+        let _ = client.disconnect().await;
+    }
+
+    /// Rotated credentials are used by a new logon over a new connection
+    #[tokio::test]
+    async fn test_update_credentials_logs_on_again() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_ssl(false)
+            .with_reconnection(1, Duration::from_millis(10));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+        assert!(matches!(
+            client
+                .update_credentials(String::new(), "secret".to_string())
+                .await,
+            Err(DeribitFixError::Config(_))
+        ));
+
+        client.connect().await.unwrap();
+        let mut first = listener.accept().await.unwrap();
+        let logon = next_message(&mut first).await;
+        assert_eq!(logon.get_field(553).unwrap(), "test_user");
+
+        client
+            .update_credentials("rotated_user".to_string(), "rotated_pass".to_string())
+            .await
+            .unwrap();
+        let logout = next_message(&mut first).await;
+        assert_eq!(logout.get_field(35).unwrap(), "5");
+        assert_eq!(logout.get_field(9003).unwrap(), "Y");
+
+        let mut second = listener.accept().await.unwrap();
+        let relogon = next_message(&mut second).await;
+        assert_eq!(relogon.get_field(35).unwrap(), "A");
+        assert_eq!(relogon.get_field(553).unwrap(), "rotated_user");
+        // Auth material is generated again for the new logon
+        assert_ne!(relogon.get_field(96), logon.get_field(96));
+
+        let _ = client.disconnect().await;
+    }
+
+    /// A transport-level standby logs on once the primary connection closes
+    #[tokio::test]
+    async fn test_failover_to_transport_standby() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_ssl(false)
+            .with_hot_standby(None);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut primary = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut primary).await.get_field(35).unwrap(), "A");
+        let mut standby = listener.accept().await.unwrap();
+        assert!(client.has_standby());
+
+        drop(primary);
+        // The closed primary is read as end of stream, then fails over
+        assert!(client.receive_message().await.unwrap().is_none());
+        assert!(client.receive_message().await.unwrap().is_none());
+        assert_eq!(client.failover_count(), 1);
+        assert!(!client.has_standby());
+
+        let logon = next_message(&mut standby).await;
+        assert_eq!(logon.get_field(35).unwrap(), "A");
+        assert_eq!(logon.get_field(49), Some(&config.sender_comp_id));
+        assert!(matches!(
+            client.failover().await,
+            Err(DeribitFixError::Connection(_))
+        ));
+
+        let _ = client.disconnect().await;
+    }
+
+    /// A standby logged on with its own SenderCompID takes over at once and
+    /// is replaced by a new standby
+    #[tokio::test]
+    async fn test_failover_to_logged_on_standby() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(1)
+            .with_hot_standby(Some("CLIENT_STANDBY".to_string()));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut primary = listener.accept().await.unwrap();
+        next_message(&mut primary).await;
+        let mut standby = listener.accept().await.unwrap();
+        let logon = next_message(&mut standby).await;
+        assert_eq!(logon.get_field(35).unwrap(), "A");
+        assert_eq!(logon.get_field(49).unwrap(), "CLIENT_STANDBY");
+
+        client.failover().await.unwrap();
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0);
+        client.send_order(order).await.unwrap();
+        let sent = next_message(&mut standby).await;
+        assert_eq!(sent.get_field(35).unwrap(), "D");
+        assert_eq!(sent.get_field(49).unwrap(), "CLIENT_STANDBY");
+
+        // The standby task opens a new standby within a heartbeat interval
+        let mut replacement = tokio::time::timeout(Duration::from_secs(2), listener.accept())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            next_message(&mut replacement).await.get_field(35).unwrap(),
+            "A"
+        );
+        drop(primary);
+
+        let _ = client.disconnect().await;
+    }
+
     /// Test configuration validation edge cases
     #[test]
     fn test_config_validation_edge_cases() {
@@ -342,24 +704,11 @@ mod tests {
     /// Test NewOrderRequest creation and validation
     #[test]
     fn test_new_order_request_creation() {
-        let order = NewOrderRequest {
-            instrument_name: "BTC-PERPETUAL".to_string(),
-            side: OrderSide::Buy,
-            order_type: OrderType::Limit,
-            amount: 100.0,
-            price: Some(50000.0),
-            time_in_force: TimeInForce::GoodTilCancelled,
-            post_only: Some(true),
-            reduce_only: Some(false),
-            client_order_id: Some("test_order_123".to_string()),
-            label: Some("test_label".to_string()),
-            stop_price: None,
-            trigger: None,
-            advanced: None,
-            max_show: None,
-            reject_post_only: None,
-            valid_until: None,
-        };
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 100.0, 50000.0)
+            .with_post_only(true)
+            .with_reduce_only(false)
+            .with_client_order_id("test_order_123".to_string())
+            .with_label("test_label".to_string());
 
         assert_eq!(order.instrument_name, "BTC-PERPETUAL");
         assert!(matches!(order.side, OrderSide::Buy));
@@ -376,48 +725,21 @@ mod tests {
     #[test]
     fn test_order_types_and_sides() {
         // Test Market Buy order
-        let market_buy = NewOrderRequest {
-            instrument_name: "ETH-PERPETUAL".to_string(),
-            side: OrderSide::Buy,
-            order_type: OrderType::Market,
-            amount: 50.0,
-            price: None, // Market orders don't have price
-            time_in_force: TimeInForce::ImmediateOrCancel,
-            post_only: Some(false),
-            reduce_only: Some(false),
-            client_order_id: Some("market_buy_1".to_string()),
-            label: None,
-            stop_price: None,
-            trigger: None,
-            advanced: None,
-            max_show: None,
-            reject_post_only: None,
-            valid_until: None,
-        };
+        let market_buy = NewOrderRequest::market_buy("ETH-PERPETUAL".to_string(), 50.0)
+            .with_post_only(false)
+            .with_reduce_only(false)
+            .with_client_order_id("market_buy_1".to_string());
 
         assert!(matches!(market_buy.order_type, OrderType::Market));
         assert!(matches!(market_buy.side, OrderSide::Buy));
         assert_eq!(market_buy.price, None);
 
         // Test Limit Sell order
-        let limit_sell = NewOrderRequest {
-            instrument_name: "BTC-PERPETUAL".to_string(),
-            side: OrderSide::Sell,
-            order_type: OrderType::Limit,
-            amount: 25.0,
-            price: Some(55000.0),
-            time_in_force: TimeInForce::GoodTilCancelled,
-            post_only: Some(true),
-            reduce_only: Some(true),
-            client_order_id: Some("limit_sell_1".to_string()),
-            label: Some("hedge_position".to_string()),
-            stop_price: None,
-            trigger: None,
-            advanced: None,
-            max_show: None,
-            reject_post_only: None,
-            valid_until: None,
-        };
+        let limit_sell = NewOrderRequest::limit_sell("BTC-PERPETUAL".to_string(), 25.0, 55000.0)
+            .with_post_only(true)
+            .with_reduce_only(true)
+            .with_client_order_id("limit_sell_1".to_string())
+            .with_label("hedge_position".to_string());
 
         assert!(matches!(limit_sell.order_type, OrderType::Limit));
         assert!(matches!(limit_sell.side, OrderSide::Sell));
@@ -445,4 +767,1010 @@ mod tests {
         assert!(debug_str.contains("Session"));
         assert!(debug_str.contains("Test session error"));
     }
+
+    /// Wait until the tasks of the client sleep on `count` clock timers
+    async fn wait_for_timers(clock: &ManualClock, count: usize) {
+        while clock.pending_timers() < count {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Heartbeats and response timeouts follow the configured clock
+    #[tokio::test]
+    async fn test_manual_clock_drives_heartbeats_and_timeouts() {
+        let clock = Arc::new(ManualClock::default());
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30)
+            .with_request_timeout(Duration::from_secs(3600))
+            .with_clock(clock.clone());
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+        let logon = frame(
+            "35=A\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x0198=0\x01108=30\x01",
+        );
+        server.write_all(logon.as_bytes()).await.unwrap();
+        client.receive_message().await.unwrap();
+
+        // No heartbeat is due before the interval passes on the clock
+        wait_for_timers(&clock, 1).await;
+        clock.advance(Duration::from_secs(29));
+        assert_eq!(clock.pending_timers(), 1);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "0");
+
+        // An hour-long response timeout passes without waiting for it
+        let pinging = client.clone();
+        let ping = tokio::spawn(async move { pinging.ping().await });
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "1");
+        wait_for_timers(&clock, 2).await;
+        clock.advance(Duration::from_secs(3600));
+        let result = tokio::time::timeout(Duration::from_secs(5), ping)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(result, Err(DeribitFixError::Timeout(_))));
+
+        let _ = client.disconnect().await;
+    }
+
+    /// A missed keep-alive cancels every order once
+    #[tokio::test]
+    async fn test_dead_mans_switch_cancels_orders_on_missed_keep_alive() {
+        let clock = Arc::new(ManualClock::default());
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(60)
+            .with_dead_mans_switch(Duration::from_secs(10))
+            .with_clock(clock.clone());
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+        let logon = frame(
+            "35=A\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x0198=0\x01108=60\x01",
+        );
+        server.write_all(logon.as_bytes()).await.unwrap();
+        client.receive_message().await.unwrap();
+
+        // Kept alive at 5s, the switch is due at 15s
+        for step in 1..=6 {
+            wait_for_timers(&clock, 2).await;
+            clock.advance(Duration::from_millis(2500));
+            if step == 2 {
+                client.keep_alive().await.unwrap();
+            }
+        }
+        let cancel = next_message(&mut server).await;
+        assert_eq!(cancel.get_field(35).unwrap(), "q");
+        assert_eq!(cancel.get_field(530).unwrap(), "7");
+
+        let _ = client.disconnect().await;
+    }
+
+    /// An order is written while a receive waits for data, and received
+    /// messages reach every subscriber
+    #[tokio::test]
+    async fn test_order_is_sent_while_a_receive_is_waiting() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+        let mut subscriber = client.subscribe_messages().unwrap();
+
+        let receiving = client.clone();
+        let receive = tokio::spawn(async move {
+            loop {
+                if let Some(message) = receiving.receive_message().await.unwrap() {
+                    return message;
+                }
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0);
+        tokio::time::timeout(Duration::from_millis(500), client.send_order(order))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "D");
+
+        let logon = frame(
+            "35=A\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x0198=0\x01108=30\x01",
+        );
+        server.write_all(logon.as_bytes()).await.unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(2), receive)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.get_field(35).unwrap(), "A");
+        let published = subscriber.recv().await.unwrap();
+        assert_eq!(published.get_field(35).unwrap(), "A");
+
+        let _ = client.disconnect().await;
+    }
+
+    /// The quoting engine quotes around the mid, requotes on a book jump and
+    /// pulls its quotes when cancelled
+    #[tokio::test]
+    async fn test_run_quoting_follows_the_book() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+        client.subscribe_top_of_book("BTC-PERPETUAL").await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "V");
+        let header = "49=DERIBITSERVER\x0156=CLIENT\x0152=20260101-00:00:00.000\x01";
+        let snapshot = frame(&format!(
+            "35=W\x0134=1\x01{header}55=BTC-PERPETUAL\x01268=2\x01\
+             269=0\x01270=50000\x01271=10\x01269=1\x01270=50010\x01271=5\x01"
+        ));
+        server.write_all(snapshot.as_bytes()).await.unwrap();
+        while client.mid_price("BTC-PERPETUAL").await.unwrap().is_none() {
+            tokio::task::yield_now().await;
+        }
+
+        let mut engine = QuotingEngine::new().with_jump_threshold(0.01);
+        engine
+            .set_quote(QuoteSpec::new("BTC-PERPETUAL".to_string(), 10.0, 100.0))
+            .unwrap();
+        let cancel = CancelToken::new();
+        let quoting = client.clone();
+        let stop = cancel.clone();
+        let run = tokio::spawn(async move {
+            let result = quoting.run_quoting(&mut engine, &stop).await;
+            (result, engine)
+        });
+
+        let quote = next_message(&mut server).await;
+        assert_eq!(quote.get_field(35).unwrap(), "i");
+        assert_eq!(quote.get_field(117).unwrap(), "quote-1");
+
+        let jump = frame(&format!(
+            "35=X\x0134=2\x01{header}55=BTC-PERPETUAL\x01268=2\x01\
+             279=0\x01269=0\x01270=51000\x01271=10\x01\
+             279=0\x01269=1\x01270=51010\x01271=5\x01"
+        ));
+        server.write_all(jump.as_bytes()).await.unwrap();
+        let mut sent = Vec::new();
+        while sent.len() < 2 {
+            let mut buf = [0u8; 4096];
+            let n = server.read(&mut buf).await.unwrap();
+            let data = String::from_utf8_lossy(&buf[..n]).to_string();
+            // The cancel and the new quote may arrive in one read
+            sent.extend(
+                data.split("\x0135=")
+                    .skip(1)
+                    .map(|rest| rest.split('\x01').next().unwrap().to_string()),
+            );
+        }
+        assert_eq!(sent, vec!["Z".to_string(), "i".to_string()]);
+
+        cancel.cancel();
+        let (result, engine) = tokio::time::timeout(Duration::from_secs(2), run)
+            .await
+            .unwrap()
+            .unwrap();
+        result.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "Z");
+        assert!(engine.live_quote("BTC-PERPETUAL").is_none());
+
+        let _ = client.disconnect().await;
+    }
+
+    /// The ladder rests its orders below the mid, moves them with the book
+    /// once acknowledged and cancels them when stopped
+    #[tokio::test]
+    async fn test_run_ladder_follows_the_book() {
+        /// Read `count` FIX messages written by the client
+        async fn read_messages(
+            server: &mut tokio::io::DuplexStream,
+            count: usize,
+        ) -> Vec<FixMessage> {
+            let mut messages = Vec::new();
+            while messages.len() < count {
+                let mut buf = [0u8; 8192];
+                let n = server.read(&mut buf).await.unwrap();
+                let data = String::from_utf8_lossy(&buf[..n]).to_string();
+                messages.extend(
+                    data.split("8=FIX.4.4")
+                        .filter(|part| !part.is_empty())
+                        .map(|part| FixMessage::parse(&format!("8=FIX.4.4{part}")).unwrap()),
+                );
+            }
+            messages
+        }
+
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+        client.subscribe_top_of_book("BTC-PERPETUAL").await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "V");
+        let header = "49=DERIBITSERVER\x0156=CLIENT\x0152=20260101-00:00:00.000\x01";
+        let snapshot = frame(&format!(
+            "35=W\x0134=1\x01{header}55=BTC-PERPETUAL\x01268=2\x01\
+             269=0\x01270=50000\x01271=10\x01269=1\x01270=50010\x01271=5\x01"
+        ));
+        server.write_all(snapshot.as_bytes()).await.unwrap();
+        while client.mid_price("BTC-PERPETUAL").await.unwrap().is_none() {
+            tokio::task::yield_now().await;
+        }
+
+        let mut ladder = Ladder::new("BTC-PERPETUAL".to_string(), OrderSide::Buy, 2, 10.0, 100.0)
+            .with_offset(5.0);
+        let cancel = CancelToken::new();
+        let running = client.clone();
+        let stop = cancel.clone();
+        let run = tokio::spawn(async move {
+            let result = running.run_ladder(&mut ladder, &stop).await;
+            (result, ladder)
+        });
+
+        let orders = read_messages(&mut server, 2).await;
+        let prices: Vec<&str> = orders
+            .iter()
+            .map(|order| order.get_field(44).unwrap().as_str())
+            .collect();
+        assert_eq!(prices, ["50000", "49990"]);
+        for (seq, order) in orders.iter().enumerate() {
+            assert_eq!(order.get_field(35).unwrap(), "D");
+            let ack = frame(&format!(
+                "35=8\x0134={}\x01{header}37=ORD-{seq}\x0111={}\x0117=EXEC-{seq}\x01\
+                 150=0\x0139=0\x0155=BTC-PERPETUAL\x0154=1\x0138=100\x01151=100\x01\
+                 14=0\x0144={}\x01",
+                seq + 2,
+                order.get_field(11).unwrap(),
+                order.get_field(44).unwrap()
+            ));
+            server.write_all(ack.as_bytes()).await.unwrap();
+        }
+
+        let jump = frame(&format!(
+            "35=X\x0134=4\x01{header}55=BTC-PERPETUAL\x01268=2\x01\
+             279=0\x01269=0\x01270=50100\x01271=10\x01\
+             279=0\x01269=1\x01270=50110\x01271=5\x01"
+        ));
+        server.write_all(jump.as_bytes()).await.unwrap();
+        let replaces = read_messages(&mut server, 2).await;
+        let moved: Vec<(&str, &str, &str)> = replaces
+            .iter()
+            .map(|replace| {
+                (
+                    replace.get_field(35).unwrap().as_str(),
+                    replace.get_field(41).unwrap().as_str(),
+                    replace.get_field(44).unwrap().as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(moved, [("G", "ORD-0", "50100"), ("G", "ORD-1", "50090")]);
+
+        cancel.cancel();
+        let (result, ladder) = tokio::time::timeout(Duration::from_secs(2), run)
+            .await
+            .unwrap()
+            .unwrap();
+        result.unwrap();
+        let cancels = read_messages(&mut server, 2).await;
+        assert!(
+            cancels
+                .iter()
+                .all(|cancel| cancel.get_field(35).unwrap() == "F")
+        );
+        assert!(ladder.levels().iter().all(Option::is_none));
+
+        let _ = client.disconnect().await;
+    }
+
+    #[tokio::test]
+    async fn test_order_from_template() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+        client
+            .register_template(
+                "post-only-limit-BTC",
+                OrderTemplate::new("BTC-PERPETUAL".to_string(), OrderSide::Buy)
+                    .with_post_only(true)
+                    .with_label_prefix("mm-bid-".to_string()),
+            )
+            .unwrap();
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        client
+            .order_from_template("post-only-limit-BTC", 50_000.0, 10.0)
+            .await
+            .unwrap();
+        let order = next_message(&mut server).await;
+        assert_eq!(order.get_field(35).unwrap(), "D");
+        assert_eq!(order.get_field(55).unwrap(), "BTC-PERPETUAL");
+        assert_eq!(order.get_field(54).unwrap(), "1");
+        assert_eq!(order.get_field(44).unwrap(), "50000");
+        assert_eq!(order.get_field(100010).unwrap(), "mm-bid-1");
+
+        assert!(
+            client
+                .order_from_template("post-only-limit-ETH", 3_000.0, 1.0)
+                .await
+                .is_err()
+        );
+
+        let _ = client.disconnect().await;
+    }
+
+    /// With latency tracing enabled an acknowledged order becomes a sample
+    #[tokio::test]
+    async fn test_latency_stats_of_acknowledged_orders() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30)
+            .with_latency_tracing(true);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+        assert_eq!(client.latency_stats().await.unwrap().samples, 0);
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0);
+        client.send_order(order).await.unwrap();
+        let sent = next_message(&mut server).await;
+        assert_eq!(sent.get_field(35).unwrap(), "D");
+        let cl_ord_id = sent.get_field(11).unwrap();
+
+        let ack = frame(&format!(
+            "35=8\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x0111={cl_ord_id}\x0137=1\x0117=1\x01\
+             150=0\x0139=0\x0155=BTC-PERPETUAL\x0154=1\x01"
+        ));
+        server.write_all(ack.as_bytes()).await.unwrap();
+        let stats = loop {
+            let stats = client.latency_stats().await.unwrap();
+            if stats.samples == 1 {
+                break stats;
+            }
+            tokio::task::yield_now().await;
+        };
+        let total = stats.total.unwrap();
+        assert!(total.p50 <= total.max);
+        assert!(stats.write.is_some());
+        assert_eq!(
+            client.connection_stats().await.unwrap().order_latency,
+            Some(stats)
+        );
+
+        let _ = client.disconnect().await;
+    }
+
+    #[tokio::test]
+    async fn test_rejected_rfq_returns_typed_error() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30)
+            .with_request_timeout(Duration::from_secs(5));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        let rfq = RfqRequest::new("RFQ_1".to_string(), "BTC-PERPETUAL".to_string(), 100_000.0);
+        let pending = client.send_rfq(rfq).await.unwrap();
+        let sent = next_message(&mut server).await;
+        assert_eq!(sent.get_field(35).unwrap(), "AH");
+        assert_eq!(sent.get_field(644).unwrap(), "RFQ_1");
+
+        let reject = frame(
+            "35=AG\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x01131=RFQ_1\x01658=6\x0158=rfq_not_allowed\x01",
+        );
+        server.write_all(reject.as_bytes()).await.unwrap();
+        match pending.response().await {
+            Err(DeribitFixError::QuoteRequestRejected {
+                quote_req_id,
+                reason,
+                text,
+            }) => {
+                assert_eq!(quote_req_id, "RFQ_1");
+                assert_eq!(
+                    reason,
+                    Some(QuoteRequestRejectReason::NotAuthorizedToRequestQuote)
+                );
+                assert_eq!(text.as_deref(), Some("rfq_not_allowed"));
+            }
+            other => panic!("Expected quote request reject, got {other:?}"),
+        }
+
+        let _ = client.disconnect().await;
+    }
+
+    #[tokio::test]
+    async fn test_full_command_queue_refuses_calls() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30)
+            .with_request_timeout(Duration::from_secs(5))
+            .with_max_pending_commands(1);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        // The session waits for the Mass Quote Acknowledgement of the cancel
+        let cancelling = client.clone();
+        let cancel = tokio::spawn(async move { cancelling.cancel_all_quotes().await });
+        let sent = next_message(&mut server).await;
+        assert_eq!(sent.get_field(35).unwrap(), "Z");
+
+        let waiting = client.clone();
+        let queued = tokio::spawn(async move { waiting.flush().await });
+        tokio::task::yield_now().await;
+        match client.flush().await {
+            Err(DeribitFixError::QueueFull(_)) => {}
+            other => panic!("Expected a full queue, got {other:?}"),
+        }
+
+        let ack = frame(&format!(
+            "35=b\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x01117={}\x01297=0\x01",
+            sent.get_field(117).unwrap()
+        ));
+        server.write_all(ack.as_bytes()).await.unwrap();
+        cancel.await.unwrap().unwrap();
+        queued.await.unwrap().unwrap();
+
+        let buffers = client.connection_stats().await.unwrap().buffers;
+        assert_eq!(buffers.pending_commands_high_water, 1);
+        assert_eq!(buffers.rejected_commands, 1);
+
+        let _ = client.disconnect().await;
+    }
+
+    /// Under a burst of orders, a cancel is sent as soon as the session is
+    /// free, ahead of the orders queued before it
+    #[tokio::test]
+    async fn test_cancel_jumps_ahead_of_queued_orders() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30)
+            .with_request_timeout(Duration::from_secs(5))
+            .with_max_pending_commands(3);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        // The session waits for the Mass Quote Acknowledgement of the cancel
+        let cancelling = client.clone();
+        let quotes = tokio::spawn(async move { cancelling.cancel_all_quotes().await });
+        let quote_cancel = next_message(&mut server).await;
+        assert_eq!(quote_cancel.get_field(35).unwrap(), "Z");
+
+        // Saturate the queue with orders, then cancel
+        let mut orders = Vec::new();
+        for i in 0..3 {
+            let ordering = client.clone();
+            let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+                .with_client_order_id(format!("ORDER-{i}"));
+            orders.push(tokio::spawn(
+                async move { ordering.send_order(order).await },
+            ));
+            tokio::task::yield_now().await;
+        }
+        match client
+            .send_order(NewOrderRequest::market_buy(
+                "BTC-PERPETUAL".to_string(),
+                10.0,
+            ))
+            .await
+        {
+            Err(DeribitFixError::QueueFull(_)) => {}
+            other => panic!("Expected a full queue, got {other:?}"),
+        }
+        let cancelling = client.clone();
+        let cancel = tokio::spawn(async move {
+            cancelling
+                .cancel_order_with_symbol("RESTING-1".to_string(), None)
+                .await
+        });
+        tokio::task::yield_now().await;
+
+        let ack = frame(&format!(
+            "35=b\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x01117={}\x01297=0\x01",
+            quote_cancel.get_field(117).unwrap()
+        ));
+        server.write_all(ack.as_bytes()).await.unwrap();
+        quotes.await.unwrap().unwrap();
+
+        let cancel_request = next_message(&mut server).await;
+        assert_eq!(cancel_request.get_field(35).unwrap(), "F");
+        assert_eq!(cancel_request.get_field(41).unwrap(), "RESTING-1");
+        let reject = frame(&format!(
+            "35=9\x0134=2\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x0111={}\x0141=RESTING-1\x01102=1\x01434=1\x01\
+             58=unknown order\x01",
+            cancel_request.get_field(11).unwrap()
+        ));
+        server.write_all(reject.as_bytes()).await.unwrap();
+        assert!(matches!(
+            cancel.await.unwrap(),
+            Err(DeribitFixError::CancelRejected { .. })
+        ));
+
+        for order in orders {
+            order.await.unwrap().unwrap();
+        }
+        let mut buf = vec![0u8; 8192];
+        let mut written = String::new();
+        while written.matches("35=D\x01").count() < 3 {
+            let n = server.read(&mut buf).await.unwrap();
+            written.push_str(&String::from_utf8_lossy(&buf[..n]));
+        }
+        let positions: Vec<_> = (0..3)
+            .map(|i| written.find(&format!("11=ORDER-{i}\x01")).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let _ = client.disconnect().await;
+    }
+
+    /// Connect a client whose session waits for the acknowledgement of a
+    /// Quote Cancel (Z), so later calls queue up behind it
+    async fn client_waiting_for_quote_cancel() -> (
+        DeribitFixClient,
+        tokio::io::DuplexStream,
+        FixMessage,
+        tokio::task::JoinHandle<deribit_fix::error::Result<()>>,
+    ) {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30)
+            .with_request_timeout(Duration::from_secs(5));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        let cancelling = client.clone();
+        let quotes = tokio::spawn(async move { cancelling.cancel_all_quotes().await });
+        let quote_cancel = next_message(&mut server).await;
+        assert_eq!(quote_cancel.get_field(35).unwrap(), "Z");
+        (client, server, quote_cancel, quotes)
+    }
+
+    /// Queue a limit order with ClOrdID `cl_ord_id` behind the running call
+    async fn queue_order(
+        client: &DeribitFixClient,
+        cl_ord_id: &str,
+    ) -> tokio::task::JoinHandle<deribit_fix::error::Result<String>> {
+        let ordering = client.clone();
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_client_order_id(cl_ord_id.to_string());
+        let queued = tokio::spawn(async move { ordering.send_order(order).await });
+        tokio::task::yield_now().await;
+        queued
+    }
+
+    /// Acknowledge the Quote Cancel (Z) the session waits for
+    async fn acknowledge_quote_cancel(
+        server: &mut tokio::io::DuplexStream,
+        quote_cancel: &FixMessage,
+        seq: u32,
+    ) {
+        let ack = frame(&format!(
+            "35=b\x0134={seq}\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x01117={}\x01297=0\x01",
+            quote_cancel.get_field(117).unwrap()
+        ));
+        server.write_all(ack.as_bytes()).await.unwrap();
+    }
+
+    /// A cancel of an order still waiting to be sent withdraws it, so the
+    /// order never reaches the exchange after its cancel
+    #[tokio::test]
+    async fn test_cancel_withdraws_unsent_order() {
+        let (client, mut server, quote_cancel, quotes) = client_waiting_for_quote_cancel().await;
+        let first = queue_order(&client, "ORDER-0").await;
+        let second = queue_order(&client, "ORDER-1").await;
+
+        client
+            .cancel_order_with_symbol("ORDER-1".to_string(), Some("BTC-PERPETUAL".to_string()))
+            .await
+            .unwrap();
+
+        acknowledge_quote_cancel(&mut server, &quote_cancel, 1).await;
+        quotes.await.unwrap().unwrap();
+        assert_eq!(first.await.unwrap().unwrap(), "ORDER-0");
+        assert!(matches!(
+            second.await.unwrap(),
+            Err(DeribitFixError::Cancelled(_))
+        ));
+
+        let order = next_message(&mut server).await;
+        assert_eq!(order.get_field(35).unwrap(), "D");
+        assert_eq!(order.get_field(11).unwrap(), "ORDER-0");
+        let mut buf = [0u8; 4096];
+        let nothing = tokio::time::timeout(Duration::from_millis(100), server.read(&mut buf)).await;
+        assert!(nothing.is_err(), "Neither the order nor a cancel is sent");
+
+        let _ = client.disconnect().await;
+    }
+
+    /// A mass cancel withdraws every order still waiting to be sent before
+    /// cancelling the open ones
+    #[tokio::test]
+    async fn test_mass_cancel_withdraws_queued_orders() {
+        let (client, mut server, quote_cancel, quotes) = client_waiting_for_quote_cancel().await;
+        let orders = vec![
+            queue_order(&client, "ORDER-0").await,
+            queue_order(&client, "ORDER-1").await,
+        ];
+
+        let cancelling = client.clone();
+        let cleanup = tokio::spawn(async move { cancelling.cancel_all_quotes_and_orders().await });
+        tokio::task::yield_now().await;
+        acknowledge_quote_cancel(&mut server, &quote_cancel, 1).await;
+        quotes.await.unwrap().unwrap();
+
+        let quote_cancel = next_message(&mut server).await;
+        assert_eq!(quote_cancel.get_field(35).unwrap(), "Z");
+        acknowledge_quote_cancel(&mut server, &quote_cancel, 2).await;
+        let mass_cancel = next_message(&mut server).await;
+        assert_eq!(mass_cancel.get_field(35).unwrap(), "q");
+        let report = frame(&format!(
+            "35=r\x0134=3\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x0111={}\x01530=7\x01531=7\x01533=0\x01",
+            mass_cancel.get_field(11).unwrap()
+        ));
+        server.write_all(report.as_bytes()).await.unwrap();
+        cleanup.await.unwrap().unwrap();
+
+        for order in orders {
+            assert!(matches!(
+                order.await.unwrap(),
+                Err(DeribitFixError::Cancelled(_))
+            ));
+        }
+        let mut buf = [0u8; 4096];
+        let nothing = tokio::time::timeout(Duration::from_millis(100), server.read(&mut buf)).await;
+        assert!(nothing.is_err(), "No order is sent after the mass cancel");
+
+        let _ = client.disconnect().await;
+    }
+
+    /// A batch of orders leaves in one write, and a rejected order cancels
+    /// the accepted ones
+    #[tokio::test]
+    async fn test_send_orders_in_one_write_and_cancel_on_reject() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30)
+            .with_request_timeout(Duration::from_secs(5));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        let before = client.write_stats().await.unwrap();
+        let orders = (0..3)
+            .map(|i| {
+                NewOrderRequest::limit_buy(
+                    "BTC-PERPETUAL".to_string(),
+                    10.0,
+                    50_000.0 - f64::from(i) * 10.0,
+                )
+                .with_client_order_id(format!("LADDER-{i}"))
+            })
+            .collect();
+        let batch = client.send_orders(orders).await.unwrap().cancel_on_reject();
+        assert_eq!(batch.len(), 3);
+        assert!(batch.orders().iter().all(|order| order.is_sent()));
+        let after = client.write_stats().await.unwrap();
+        assert_eq!(after.messages - before.messages, 3);
+        assert_eq!(after.writes - before.writes, 1);
+
+        let mut buf = vec![0u8; 8192];
+        let mut written = String::new();
+        while written.matches("35=D\x01").count() < 3 {
+            let n = server.read(&mut buf).await.unwrap();
+            written.push_str(&String::from_utf8_lossy(&buf[..n]));
+        }
+
+        let outcome = tokio::spawn(batch.acknowledgements());
+        for (seq, (cl_ord_id, status)) in [("LADDER-0", 0), ("LADDER-1", 0), ("LADDER-2", 8)]
+            .into_iter()
+            .enumerate()
+        {
+            let report = frame(&format!(
+                "35=8\x0134={}\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+                 52=20260101-00:00:00.000\x0111={cl_ord_id}\x0137=ID-{seq}\x0117={seq}\x01\
+                 150={status}\x0139={status}\x0155=BTC-PERPETUAL\x0154=1\x0158=rejected\x01",
+                seq + 1
+            ));
+            server.write_all(report.as_bytes()).await.unwrap();
+        }
+
+        // The accepted orders are cancelled by their exchange OrderID
+        for seq in 0..2 {
+            let cancel = next_message(&mut server).await;
+            assert_eq!(cancel.get_field(35).unwrap(), "F");
+            assert_eq!(cancel.get_field(41).unwrap(), &format!("ID-{seq}"));
+            let cancelled = frame(&format!(
+                "35=8\x0134={}\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+                 52=20260101-00:00:00.000\x0111=LADDER-{seq}\x0137=ID-{seq}\x0117=C{seq}\x01\
+                 150=4\x0139=4\x0155=BTC-PERPETUAL\x0154=1\x01",
+                seq + 4
+            ));
+            server.write_all(cancelled.as_bytes()).await.unwrap();
+        }
+
+        let outcome = outcome.await.unwrap();
+        assert!(!outcome.is_accepted());
+        assert!(outcome.acknowledgements[0].is_ok());
+        assert!(outcome.acknowledgements[1].is_ok());
+        assert!(matches!(
+            outcome.acknowledgements[2],
+            Err(DeribitFixError::OrderRejected { .. })
+        ));
+        assert_eq!(outcome.cancelled, ["LADDER-0", "LADDER-1"]);
+
+        let _ = client.disconnect().await;
+    }
+
+    #[tokio::test]
+    async fn test_stream_returns_only_filtered_messages() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        let mut fills = client.stream().unwrap().filter(
+            MsgFilter::exec_reports()
+                .symbol("BTC-PERPETUAL")
+                .exec_type(ExecType::Trade),
+        );
+        let header = "49=DERIBITSERVER\x0156=CLIENT\x0152=20260101-00:00:00.000\x01";
+        for (seq, body) in [
+            "35=0\x01".to_string(),
+            "35=8\x0111=ETH-1\x0137=1\x0117=1\x01150=F\x0139=2\x0155=ETH-PERPETUAL\x0154=1\x01"
+                .to_string(),
+            "35=8\x0111=BTC-1\x0137=2\x0117=2\x01150=0\x0139=0\x0155=BTC-PERPETUAL\x0154=1\x01"
+                .to_string(),
+            "35=8\x0111=BTC-1\x0137=2\x0117=3\x01150=F\x0139=2\x0155=BTC-PERPETUAL\x0154=1\x01"
+                .to_string(),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let (msg_type, rest) = body.split_once("\x01").unwrap();
+            let message = frame(&format!("{msg_type}\x0134={}\x01{header}{rest}", seq + 1));
+            server.write_all(message.as_bytes()).await.unwrap();
+        }
+
+        let fill = tokio::time::timeout(Duration::from_secs(2), fills.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fill.get_field(17).unwrap(), "3");
+
+        let _ = client.disconnect().await;
+    }
+    /// A receiver that falls behind the session is told how many messages it lost
+    #[tokio::test]
+    async fn test_receive_message_reports_lost_messages() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_heartbeat_interval(30);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut server).await.get_field(35).unwrap(), "A");
+
+        let header = "49=DERIBITSERVER\x0156=CLIENT\x0152=20260101-00:00:00.000\x01";
+        let sent = SESSION_MESSAGE_CHANNEL_CAPACITY + 10;
+        for seq in 1..=sent {
+            let message = frame(&format!("35=0\x0134={seq}\x01{header}"));
+            server.write_all(message.as_bytes()).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        match client.receive_message().await {
+            Err(DeribitFixError::MessagesLost(lost)) => assert_eq!(lost, 10),
+            other => panic!("Expected lost messages, got {other:?}"),
+        }
+        let next = client.receive_message().await.unwrap().unwrap();
+        assert_eq!(next.get_field(34).unwrap(), "11");
+
+        let _ = client.disconnect().await;
+    }
+    /// Read one FIX message written by the client, however the pipe splits it
+    async fn read_whole_message(server: &mut tokio::io::DuplexStream) -> FixMessage {
+        let mut bytes = Vec::new();
+        let mut byte = [0u8; 1];
+        loop {
+            server.read_exact(&mut byte).await.unwrap();
+            bytes.push(byte[0]);
+            let text = String::from_utf8_lossy(&bytes);
+            if let Some(checksum) = text.find("\x0110=")
+                && text[checksum + 1..].ends_with('\x01')
+            {
+                return FixMessage::parse(&text).unwrap();
+            }
+        }
+    }
+
+    /// A command sent while the session logs on again after exchange
+    /// maintenance runs once the Logon is written, without interrupting it
+    #[tokio::test]
+    async fn test_command_waits_for_relogon_after_maintenance() {
+        let (connector, mut listener) = MemoryConnector::new();
+        // Too small for a Logon, so writing one waits for the server to read
+        let connector = connector.with_buffer_size(16);
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_relogon_after_logout(true)
+            .with_maintenance_retry_interval(Duration::from_millis(100));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        let connecting = tokio::spawn({
+            let client = client.clone();
+            async move { client.connect().await }
+        });
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(
+            read_whole_message(&mut server).await.msg_type(),
+            Some(MsgType::Logon)
+        );
+        connecting.await.unwrap().unwrap();
+
+        let header = "49=DERIBITSERVER\x0156=CLIENT\x0152=20260101-00:00:00.000\x01";
+        let logout = frame(&format!(
+            "35=5\x0134=1\x01{header}58=Server going down for maintenance\x01"
+        ));
+        server.write_all(logout.as_bytes()).await.unwrap();
+        tokio::spawn(async move {
+            let mut sink = Vec::new();
+            let _ = server.read_to_end(&mut sink).await;
+        });
+
+        // The retry is writing its Logon when the command is sent
+        let mut server = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let state = tokio::spawn({
+            let client = client.clone();
+            async move { client.get_session_state().await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let logon = tokio::time::timeout(Duration::from_secs(2), read_whole_message(&mut server))
+            .await
+            .expect("the Logon is written in full");
+        assert_eq!(logon.msg_type(), Some(MsgType::Logon));
+        assert_eq!(state.await.unwrap(), Some(SessionState::LogonSent));
+
+        drop(server);
+        let _ = client.disconnect().await;
+    }
 }
@@ -1,3 +1,5 @@
 // Unit tests for client module
 
+mod cancel_on_disconnect_tests;
 mod fix_client_tests;
+mod supervisor_tests;
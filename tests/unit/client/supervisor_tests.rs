@@ -0,0 +1,73 @@
+// Unit tests for the session Supervisor
+
+use super::super::support::{frame, next_message};
+use deribit_fix::client::{DeribitFixClient, Remediation, Supervisor};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::MemoryConnector;
+use deribit_fix::model::health::{HealthState, HealthThresholds};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A silent counterparty makes the session unhealthy, which alerts and
+    /// logs on again over a new connection
+    #[tokio::test]
+    async fn test_unhealthy_session_is_remediated() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false)
+            .with_reconnection(1, Duration::from_millis(10));
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+
+        client.connect().await.unwrap();
+        let mut first = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut first).await.get_field(35).unwrap(), "A");
+        let logon = frame(
+            "35=A\x0134=1\x0149=DERIBITSERVER\x0156=CLIENT\x01\
+             52=20260101-00:00:00.000\x0198=0\x01108=30\x01",
+        );
+        first.write_all(logon.as_bytes()).await.unwrap();
+        client.receive_message().await.unwrap();
+
+        let alerts = Arc::new(AtomicU32::new(0));
+        let mut supervisor = Supervisor::new(client.clone())
+            .with_thresholds(HealthThresholds {
+                degraded_silence: Duration::from_millis(50),
+                unhealthy_silence: Duration::from_millis(100),
+                ..HealthThresholds::default()
+            })
+            .with_remediation(HealthState::Unhealthy, Remediation::Alert)
+            .with_remediation(HealthState::Unhealthy, Remediation::Relogon)
+            .with_alert({
+                let alerts = alerts.clone();
+                move |transition| {
+                    assert_eq!(transition.to, HealthState::Unhealthy);
+                    alerts.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+
+        assert!(supervisor.check().await.is_none());
+        assert_eq!(supervisor.state(), HealthState::Healthy);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let transition = supervisor.check().await.unwrap();
+        assert_eq!(transition.from, HealthState::Healthy);
+        assert_eq!(transition.to, HealthState::Unhealthy);
+        assert_eq!(alerts.load(Ordering::SeqCst), 1);
+
+        let mut second = listener.accept().await.unwrap();
+        assert_eq!(next_message(&mut second).await.get_field(35).unwrap(), "A");
+
+        let _ = client.disconnect().await;
+    }
+}
@@ -1,6 +1,8 @@
 // Unit tests for DeribitFixConfig
 
 use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::message::TimestampPrecision;
+use std::time::Duration;
 
 #[cfg(test)]
 mod tests {
@@ -68,4 +70,444 @@ mod tests {
         assert!(debug_str.contains("{") && debug_str.contains("}"));
         assert!(debug_str.contains("username") || debug_str.contains("host"));
     }
+
+    #[test]
+    fn test_config_socket_options() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string());
+        assert!(config.busy_poll.is_none());
+        assert_eq!(config.dns_cache_ttl, Duration::from_secs(300));
+
+        let config = config
+            .with_tcp_nodelay(false)
+            .with_tcp_keepalive(Duration::from_secs(30), Duration::from_secs(10))
+            .with_socket_buffer_sizes(1 << 20, 1 << 20)
+            .with_dns_cache_ttl(Duration::ZERO);
+        assert!(!config.tcp_nodelay);
+        assert!(config.dns_cache_ttl.is_zero());
+        assert_eq!(config.tcp_keepalive, Some(Duration::from_secs(30)));
+        assert_eq!(config.tcp_keepalive_interval, Some(Duration::from_secs(10)));
+        assert!(config.validate().is_ok());
+
+        let mut invalid = config.clone();
+        invalid.tcp_keepalive = None;
+        assert!(invalid.validate().is_err());
+
+        let invalid = config.with_socket_buffer_sizes(0, 1024);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_ping_watchdog() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_ping_interval(Duration::from_secs(5))
+            .with_max_ping_latency(Duration::from_millis(250));
+        assert_eq!(config.ping_interval, Some(Duration::from_secs(5)));
+        assert_eq!(config.max_ping_latency, Duration::from_millis(250));
+        assert!(config.validate().is_ok());
+
+        let invalid = config.with_ping_interval(Duration::ZERO);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_maintenance_retry_interval() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_maintenance_retry_interval(Duration::from_secs(300));
+        assert_eq!(config.maintenance_retry_interval, Duration::from_secs(300));
+        assert!(config.validate().is_ok());
+
+        let invalid = config.with_maintenance_retry_interval(Duration::ZERO);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_logout_timeout() {
+        let config =
+            DeribitFixConfig::new().with_credentials("user".to_string(), "pass".to_string());
+        assert_eq!(config.logout_timeout, Duration::from_secs(5));
+
+        let config = config.with_logout_timeout(Duration::from_millis(500));
+        assert_eq!(config.logout_timeout, Duration::from_millis(500));
+        assert!(config.validate().is_ok());
+
+        let invalid = config.with_logout_timeout(Duration::ZERO);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_message_rate() {
+        let config =
+            DeribitFixConfig::new().with_credentials("user".to_string(), "pass".to_string());
+        assert_eq!(config.max_message_rate, None);
+        assert!(config.adaptive_throttling);
+
+        let config = config
+            .with_max_message_rate(20.0)
+            .with_adaptive_throttling(false);
+        assert_eq!(config.max_message_rate, Some(20.0));
+        assert!(!config.adaptive_throttling);
+        assert!(config.validate().is_ok());
+
+        assert!(
+            config
+                .clone()
+                .with_max_message_rate(0.0)
+                .validate()
+                .is_err()
+        );
+        assert!(config.with_max_message_rate(f64::NAN).validate().is_err());
+    }
+
+    #[test]
+    fn test_config_logon_flags() {
+        let config =
+            DeribitFixConfig::new().with_credentials("user".to_string(), "pass".to_string());
+        assert_eq!(config.dont_cancel_on_disconnect, None);
+
+        let config = config
+            .with_cancel_on_disconnect(true)
+            .with_dont_cancel_on_disconnect(true)
+            .with_unsubscribe_execution_reports(false)
+            .with_connection_only_execution_reports(true)
+            .with_report_fills_as_exec_reports(true);
+        assert_eq!(config.dont_cancel_on_disconnect, Some(true));
+        assert!(config.validate().is_ok());
+
+        let report = config
+            .clone()
+            .with_unsubscribe_execution_reports(true)
+            .validation_report();
+        assert!(report.has_issue("connection_only_execution_reports"));
+        assert!(report.has_issue("report_fills_as_exec_reports"));
+
+        let report = config
+            .with_app_credentials("app\x01id".to_string(), String::new())
+            .validation_report();
+        assert!(report.has_issue("app_id"));
+        assert!(report.has_issue("app_secret"));
+    }
+
+    #[test]
+    fn test_config_write_batching() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_write_batching(Duration::from_micros(200), 4096);
+        assert_eq!(config.write_batch_delay, Some(Duration::from_micros(200)));
+        assert_eq!(config.write_batch_max_bytes, 4096);
+        assert!(config.validate().is_ok());
+
+        let invalid = config.with_write_batching(Duration::from_micros(200), 0);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_buffer_limits() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_max_read_buffer_bytes(8 * 1024 * 1024)
+            .with_max_pending_commands(100)
+            .with_max_market_data_backlog(500)
+            .with_max_stream_backlog(200);
+        assert_eq!(config.max_read_buffer_bytes, 8 * 1024 * 1024);
+        assert_eq!(config.max_pending_commands, 100);
+        assert_eq!(config.max_market_data_backlog, 500);
+        assert_eq!(config.max_stream_backlog, 200);
+        assert!(config.validate().is_ok());
+
+        // The read buffer must hold the largest message accepted
+        assert!(
+            config
+                .clone()
+                .with_max_read_buffer_bytes(64 * 1024)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            config
+                .clone()
+                .with_max_pending_commands(0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            config
+                .clone()
+                .with_max_market_data_backlog(0)
+                .validate()
+                .is_err()
+        );
+        assert!(config.with_max_stream_backlog(0).validate().is_err());
+    }
+
+    #[test]
+    fn test_config_funding_poll_interval() {
+        let config =
+            DeribitFixConfig::new().with_credentials("user".to_string(), "pass".to_string());
+        assert_eq!(config.funding_poll_interval, Duration::from_secs(60));
+
+        let config = config.with_funding_poll_interval(Duration::from_secs(300));
+        assert_eq!(config.funding_poll_interval, Duration::from_secs(300));
+        assert!(config.validate().is_ok());
+        assert!(
+            config
+                .with_funding_poll_interval(Duration::ZERO)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_dead_mans_switch() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_dead_mans_switch(Duration::from_secs(30));
+        assert_eq!(config.dead_mans_switch, Some(Duration::from_secs(30)));
+        assert!(config.validate().is_ok());
+        assert!(
+            config
+                .with_dead_mans_switch(Duration::ZERO)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_fragment_timeout() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_fragment_timeout(Duration::from_secs(5));
+        assert_eq!(config.fragment_timeout, Duration::from_secs(5));
+        assert!(config.validate().is_ok());
+        assert!(
+            config
+                .with_fragment_timeout(Duration::ZERO)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_logon_retry() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_logon_retry(3, Duration::from_millis(500), Duration::from_secs(10));
+        assert_eq!(config.logon_retry_attempts, 3);
+        assert_eq!(config.logon_retry_delay, Duration::from_millis(500));
+        assert_eq!(config.logon_retry_max_delay, Duration::from_secs(10));
+        assert!(config.validate().is_ok());
+
+        assert!(
+            config
+                .with_logon_retry(3, Duration::from_secs(20), Duration::from_secs(10))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_timestamp_precision() {
+        let config = DeribitFixConfig::new();
+        assert_eq!(config.timestamp_precision, TimestampPrecision::Millis);
+        let config = config.with_timestamp_precision(TimestampPrecision::Micros);
+        assert_eq!(config.timestamp_precision, TimestampPrecision::Micros);
+        assert_eq!(
+            "micros".parse::<TimestampPrecision>().unwrap(),
+            TimestampPrecision::Micros
+        );
+    }
+
+    #[test]
+    fn test_config_capability_probe() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_capability_probe(true)
+            .with_default_appl_ver_id("9".to_string());
+        assert!(config.capability_probe);
+        assert_eq!(config.default_appl_ver_id.as_deref(), Some("9"));
+        assert!(config.validate().is_ok());
+
+        assert!(
+            config
+                .with_default_appl_ver_id(String::new())
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_header_ids() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_on_behalf_of_comp_id("FUND".to_string())
+            .with_sub_ids(Some("DESK".to_string()), None);
+        assert_eq!(config.on_behalf_of_comp_id.as_deref(), Some("FUND"));
+        assert_eq!(config.sender_sub_id.as_deref(), Some("DESK"));
+        assert_eq!(config.target_sub_id, None);
+        assert!(config.validate().is_ok());
+
+        assert!(
+            config
+                .clone()
+                .with_sub_ids(None, Some(String::new()))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            config
+                .with_on_behalf_of_comp_id("FU\x01ND".to_string())
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_liveness_checks() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_liveness_checks(2, Some(Duration::from_secs(10)));
+        assert_eq!(config.liveness_missed_heartbeats, Some(2));
+        assert_eq!(config.liveness_probe_timeout, Some(Duration::from_secs(10)));
+        assert!(config.validate().is_ok());
+
+        assert!(
+            config
+                .clone()
+                .with_liveness_checks(0, None)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            config
+                .with_liveness_checks(2, Some(Duration::ZERO))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_hot_standby() {
+        let config = DeribitFixConfig::new()
+            .with_credentials("user".to_string(), "pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_hot_standby(Some("CLIENT_STANDBY".to_string()));
+        assert!(config.hot_standby);
+        assert_eq!(
+            config.standby_sender_comp_id.as_deref(),
+            Some("CLIENT_STANDBY")
+        );
+        assert!(config.validate().is_ok());
+        assert!(config.clone().with_hot_standby(None).validate().is_ok());
+
+        let same = config.clone().with_hot_standby(Some("CLIENT".to_string()));
+        assert!(same.validate().is_err());
+        let empty = config.with_hot_standby(Some(String::new()));
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_report_lists_every_issue() {
+        let config = DeribitFixConfig::new()
+            .with_credentials(String::new(), String::new())
+            .with_heartbeat_interval(0);
+        let report = config.validation_report();
+        assert!(report.has_issue("username"));
+        assert!(report.has_issue("password"));
+        assert!(report.has_issue("heartbeat_interval"));
+        // validate() still reports the first problem
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            format!("Configuration error: {}", report.issues[0].message)
+        );
+    }
+
+    #[cfg(feature = "config-files")]
+    fn write_config(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("deribit-fix-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[cfg(feature = "config-files")]
+    #[test]
+    fn test_config_from_toml_and_yaml_files() {
+        use deribit_fix::model::SelfTradePrevention;
+
+        let toml = write_config(
+            "config.toml",
+            r#"
+username = "file_user"
+password = "file_pass"
+test_mode = false
+use_ssl = true
+connection_timeout = 5
+max_ping_latency = 250
+use_wordsafe_tags = "Y"
+proxy = "socks5://proxy.example.com:1080"
+self_trade_prevention = "cancel-both"
+
+[risk_limits]
+max_order_amount = 1000.5
+"#,
+        );
+        let config = DeribitFixConfig::from_file(&toml).unwrap();
+        std::fs::remove_file(&toml).unwrap();
+        assert_eq!(config.username, "file_user");
+        assert!(!config.test_mode);
+        // The endpoint follows test_mode and use_ssl
+        assert_eq!(config.connection_url(), "fix.deribit.com:9883");
+        assert_eq!(config.connection_timeout, Duration::from_secs(5));
+        assert_eq!(config.max_ping_latency, Duration::from_millis(250));
+        assert_eq!(config.use_wordsafe_tags, Some(true));
+        assert!(config.proxy.is_some());
+        assert_eq!(
+            config.self_trade_prevention,
+            Some(SelfTradePrevention::CancelBoth)
+        );
+        assert_eq!(config.risk_limits.max_order_amount, Some(1000.5));
+
+        let yaml = write_config(
+            "config.yml",
+            "username: file_user\npassword: file_pass\nhost: localhost\nport: 9881\n",
+        );
+        let config = DeribitFixConfig::from_file(&yaml).unwrap();
+        std::fs::remove_file(&yaml).unwrap();
+        assert_eq!(config.connection_url(), "localhost:9881");
+    }
+
+    #[cfg(feature = "config-files")]
+    #[test]
+    fn test_config_file_reports_every_invalid_field() {
+        use deribit_fix::error::DeribitFixError;
+
+        let path = write_config(
+            "invalid.toml",
+            "username = \"\"\npassword = \"pass\"\nport = 70000\nheartbeat = 30\n\
+             connection_timeout = \"soon\"\nself_trade_prevention = \"cancel_none\"\n",
+        );
+        let error = DeribitFixConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        let DeribitFixError::InvalidConfig(report) = error else {
+            panic!("unexpected error: {error}");
+        };
+        assert!(report.has_issue("port"));
+        assert!(report.has_issue("heartbeat"));
+        assert!(report.has_issue("connection_timeout"));
+        assert!(report.has_issue("self_trade_prevention"));
+
+        // Values that parse are still validated
+        let path = write_config("empty.yaml", "username: ''\npassword: ''\n");
+        let error = DeribitFixConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        let DeribitFixError::InvalidConfig(report) = error else {
+            panic!("unexpected error: {error}");
+        };
+        assert!(report.has_issue("username"));
+        assert!(report.has_issue("password"));
+
+        let error = DeribitFixConfig::from_file("config.ini").unwrap_err();
+        assert!(matches!(error, DeribitFixError::Config(_)));
+    }
 }
@@ -0,0 +1,139 @@
+// Fuzz tests for BodyLength based FIX message framing
+
+use deribit_fix::connection::FixFramer;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Field values that confuse delimiter based framing
+    const ADVERSARIAL_VALUES: [&str; 10] = [
+        "10=123",
+        "10=",
+        "8=FIX.4.4",
+        "9=999",
+        "\x0110=000",
+        "=",
+        "==10=10=",
+        "8=FIX.4.4\x019=5\x0135=0\x0110=123",
+        "",
+        "BTC-PERPETUAL",
+    ];
+
+    /// Deterministic xorshift generator so failures are reproducible
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            (self.next() % bound as u64) as usize
+        }
+    }
+
+    /// Build a correctly framed message with random, partly adversarial, fields
+    fn random_message(rng: &mut Rng) -> Vec<u8> {
+        let mut body = String::from("35=8\x01");
+        for _ in 0..rng.below(8) {
+            let tag = [58, 55, 11, 10, 8, 9, 9999][rng.below(7)];
+            let value = if rng.below(2) == 0 {
+                ADVERSARIAL_VALUES[rng.below(ADVERSARIAL_VALUES.len())].to_string()
+            } else {
+                (0..rng.below(12))
+                    .map(|_| (b' ' + rng.below(95) as u8) as char)
+                    .collect()
+            };
+            body.push_str(&format!("{tag}={value}\x01"));
+        }
+
+        let mut message = format!("8=FIX.4.4\x019={}\x01{body}", body.len()).into_bytes();
+        let checksum = message.iter().map(|&b| b as u32).sum::<u32>() % 256;
+        message.extend_from_slice(format!("10={checksum:03}\x01").as_bytes());
+        message
+    }
+
+    /// Feed `stream` in random chunks and collect every frame
+    fn frame_in_chunks(rng: &mut Rng, stream: &[u8]) -> Vec<Vec<u8>> {
+        let mut framer = FixFramer::new();
+        let mut frames = Vec::new();
+        let mut position = 0;
+        while position < stream.len() {
+            let end = (position + 1 + rng.below(64)).min(stream.len());
+            framer.push(&stream[position..end]);
+            position = end;
+            while let Some(frame) = framer.next_frame().expect("valid stream must frame") {
+                frames.push(frame);
+            }
+        }
+        assert!(framer.buffered().is_empty(), "no bytes must be left over");
+        frames
+    }
+
+    #[test]
+    fn test_adversarial_values_never_misframe() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..500 {
+            let messages: Vec<Vec<u8>> = (0..1 + rng.below(6))
+                .map(|_| random_message(&mut rng))
+                .collect();
+            let stream = messages.concat();
+
+            assert_eq!(frame_in_chunks(&mut rng, &stream), messages);
+        }
+    }
+
+    #[test]
+    fn test_framer_recovers_after_corrupted_message() {
+        let mut rng = Rng(0xDEAD_BEEF_CAFE_F00D);
+        for _ in 0..200 {
+            let valid = random_message(&mut rng);
+            let mut corrupted = random_message(&mut rng);
+            // Shorten the body so BodyLength no longer matches
+            let cut = corrupted.len() - 8;
+            corrupted.remove(cut);
+
+            let mut framer = FixFramer::new();
+            framer.push(&corrupted);
+            framer.push(&valid);
+
+            let mut frames = Vec::new();
+            for _ in 0..corrupted.len() + 2 {
+                match framer.next_frame() {
+                    Ok(Some(frame)) => frames.push(frame),
+                    Ok(None) => break,
+                    Err(_) => continue,
+                }
+            }
+            assert_eq!(frames.last(), Some(&valid));
+        }
+    }
+
+    #[test]
+    fn test_random_garbage_does_not_panic() {
+        let mut rng = Rng(0x0123_4567_89AB_CDEF);
+        for _ in 0..500 {
+            let garbage: Vec<u8> = (0..rng.below(256))
+                .map(|_| match rng.below(6) {
+                    0 => 0x01,
+                    1 => b'=',
+                    2 => b'8',
+                    3 => b'9',
+                    _ => rng.below(256) as u8,
+                })
+                .collect();
+
+            let mut framer = FixFramer::new();
+            framer.push(&garbage);
+            for _ in 0..garbage.len() + 1 {
+                if let Ok(None) = framer.next_frame() {
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 // Unit tests for Connection module
 
 mod fix_connection_tests;
+mod framing_tests;
 mod tcp_connection_tests;
+mod transport_tests;
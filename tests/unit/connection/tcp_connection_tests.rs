@@ -264,7 +264,7 @@ mod tests {
         // Server that sends a FIX message
         tokio::spawn(async move {
             if let Ok((mut socket, _)) = listener.accept().await {
-                let fix_message = "8=FIX.4.4\x0135=0\x0149=DERIBIT\x0156=CLIENT\x0134=1\x0152=20240101-12:00:00.000\x0110=123\x01";
+                let fix_message = "8=FIX.4.4\x019=56\x0135=0\x0149=DERIBIT\x0156=CLIENT\x0134=1\x0152=20240101-12:00:00.000\x0110=123\x01";
                 let _ = socket.write_all(fix_message.as_bytes()).await;
                 let _ = socket.flush().await;
                 tokio::time::sleep(Duration::from_millis(100)).await;
@@ -286,6 +286,40 @@ mod tests {
         assert!(message.is_some(), "Should receive a message");
     }
 
+    #[tokio::test]
+    async fn test_receive_message_with_tuned_socket_and_busy_poll() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Server that sends a FIX message shortly after accepting
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                let fix_message = "8=FIX.4.4\x019=56\x0135=0\x0149=DERIBIT\x0156=CLIENT\x0134=1\x0152=20240101-12:00:00.000\x0110=123\x01";
+                let _ = socket.write_all(fix_message.as_bytes()).await;
+                let _ = socket.flush().await;
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+
+        let mut config = create_test_config()
+            .with_tcp_nodelay(true)
+            .with_tcp_keepalive(Duration::from_secs(30), Duration::from_secs(5))
+            .with_socket_buffer_sizes(64 * 1024, 64 * 1024)
+            .with_busy_poll(Duration::from_millis(500));
+        config.host = addr.ip().to_string();
+        config.port = addr.port();
+        config.use_ssl = false;
+
+        let mut connection = Connection::new(&config).await.unwrap();
+
+        let message = connection.receive_message().await.unwrap();
+        assert!(
+            message.is_some(),
+            "Should receive a message while busy polling"
+        );
+    }
+
     #[tokio::test]
     async fn test_receive_message_connection_closed() {
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -387,6 +421,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_reconnect_reuses_the_resolved_address() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                drop(socket);
+            }
+        });
+
+        let mut config = create_test_config();
+        config.host = "localhost".to_string();
+        config.port = addr.port();
+        config.use_ssl = false;
+
+        let mut connection = Connection::new(&config).await.unwrap();
+        let handshake = connection.connection_stats().handshake.unwrap();
+        assert!(!handshake.cached_address);
+        assert!(handshake.tls_handshake.is_none());
+
+        connection.reconnect().await.unwrap();
+        let handshake = connection.connection_stats().handshake.unwrap();
+        assert!(handshake.cached_address);
+        assert_eq!(handshake.resolve + handshake.tcp_connect, handshake.total());
+    }
+
     #[tokio::test]
     async fn test_connection_reconnect_failure() {
         // Create a connection to a server that will close immediately
@@ -429,7 +489,7 @@ mod tests {
         tokio::spawn(async move {
             if let Ok((mut socket, _)) = listener.accept().await {
                 // Send partial message first
-                let partial = "8=FIX.4.4\x0135=0\x01";
+                let partial = "8=FIX.4.4\x019=56\x0135=0\x01";
                 let _ = socket.write_all(partial.as_bytes()).await;
                 let _ = socket.flush().await;
 
@@ -544,4 +604,110 @@ mod tests {
             "Receive should fail on closed connection"
         );
     }
+
+    /// Helper function to create a mock application message
+    fn create_app_message(seq: u32) -> FixMessage {
+        use std::str::FromStr;
+        FixMessage::from_str(&format!("8=FIX.4.4\x0135=V\x0149=CLIENT\x0156=DERIBIT\x0134={seq}\x0152=20240101-12:00:00.000\x01262=MD{seq}\x0110=123\x01")).unwrap()
+    }
+
+    /// Start a server collecting everything it receives
+    async fn start_collecting_server() -> (
+        std::net::SocketAddr,
+        std::sync::Arc<tokio::sync::Mutex<Vec<u8>>>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let received_data = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        let received_clone = received_data.clone();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let mut buffer = vec![0u8; 4096];
+                while let Ok(n) = socket.read(&mut buffer).await {
+                    if n == 0 {
+                        break;
+                    }
+                    received_clone.lock().await.extend_from_slice(&buffer[..n]);
+                }
+            }
+        });
+        (addr, received_data)
+    }
+
+    #[tokio::test]
+    async fn test_batched_writes_wait_for_flush() {
+        let (addr, received_data) = start_collecting_server().await;
+        let mut config = create_test_config().with_write_batching(Duration::from_secs(60), 65536);
+        config.port = addr.port();
+
+        let mut connection = Connection::new(&config).await.unwrap();
+        for seq in 1..=3 {
+            connection
+                .send_message(&create_app_message(seq))
+                .await
+                .unwrap();
+        }
+
+        let stats = connection.write_stats();
+        assert_eq!(stats.messages, 3);
+        assert_eq!(stats.writes, 0);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(received_data.lock().await.is_empty());
+
+        connection.flush().await.unwrap();
+        let stats = connection.write_stats();
+        assert_eq!(stats.writes, 1);
+        assert_eq!(stats.messages_per_write(), 3.0);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let data = received_data.lock().await;
+        assert_eq!(data.len() as u64, stats.bytes);
+        assert_eq!(String::from_utf8_lossy(&data).matches("35=V").count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_batch_flushed_by_session_message_size_and_delay() {
+        let (addr, _received_data) = start_collecting_server().await;
+        let mut config = create_test_config().with_write_batching(Duration::from_secs(60), 65536);
+        config.port = addr.port();
+
+        // Session-level messages are never delayed
+        let mut connection = Connection::new(&config).await.unwrap();
+        connection
+            .send_message(&create_app_message(1))
+            .await
+            .unwrap();
+        connection
+            .send_message(&create_test_message())
+            .await
+            .unwrap();
+        assert_eq!(connection.write_stats().writes, 1);
+
+        // A full batch is written at once
+        let (addr, _received_data) = start_collecting_server().await;
+        let mut config = create_test_config().with_write_batching(Duration::from_secs(60), 1);
+        config.port = addr.port();
+        let mut connection = Connection::new(&config).await.unwrap();
+        connection
+            .send_message(&create_app_message(1))
+            .await
+            .unwrap();
+        assert_eq!(connection.write_stats().writes, 1);
+
+        // A pending batch is written by the receive loop once its delay passes
+        let (addr, _received_data) = start_collecting_server().await;
+        let mut config = create_test_config().with_write_batching(Duration::from_millis(20), 65536);
+        config.port = addr.port();
+        let mut connection = Connection::new(&config).await.unwrap();
+        connection
+            .send_message(&create_app_message(1))
+            .await
+            .unwrap();
+        assert_eq!(connection.write_stats().writes, 0);
+        let started = tokio::time::Instant::now();
+        assert!(connection.receive_message().await.unwrap().is_none());
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(connection.write_stats().writes, 1);
+    }
 }
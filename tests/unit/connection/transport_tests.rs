@@ -0,0 +1,155 @@
+// Unit tests for pluggable connection transports
+
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::{Connection, ConnectionStats, MemoryConnector, ProxyConfig};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::types::MsgType;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_config() -> DeribitFixConfig {
+        DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_endpoint("fix-test.deribit.com".to_string(), 9881)
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+    }
+
+    /// Heartbeat framed with BeginString, BodyLength and CheckSum
+    fn heartbeat() -> FixMessage {
+        let body = "35=0\x0149=DERIBIT\x0156=CLIENT\x0134=1\x0152=20240101-12:00:00.000\x01";
+        let head = format!("8=FIX.4.4\x019={}\x01{}", body.len(), body);
+        let checksum = head.bytes().map(u32::from).sum::<u32>() % 256;
+        FixMessage::from_str(&format!("{head}10={checksum:03}\x01")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_memory_transport_round_trip_and_reconnect() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let mut connection = Connection::with_connector(&create_test_config(), Arc::new(connector))
+            .await
+            .unwrap();
+        let mut server = listener.accept().await.unwrap();
+
+        connection.send_message(&heartbeat()).await.unwrap();
+        let mut buf = [0u8; 1024];
+        let n = server.read(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf[..n]).contains("35=0\x01"));
+
+        server
+            .write_all(heartbeat().to_string().as_bytes())
+            .await
+            .unwrap();
+        let received = connection.receive_message().await.unwrap().unwrap();
+        assert_eq!(received.get_field(35).map(String::as_str), Some("0"));
+
+        // Reconnecting opens a new pipe through the same connector
+        connection.reconnect().await.unwrap();
+        let mut server = listener.accept().await.unwrap();
+        connection.send_message(&heartbeat()).await.unwrap();
+        let n = server.read(&mut buf).await.unwrap();
+        assert!(n > 0);
+
+        // Without a listener no connection can be opened
+        drop(listener);
+        assert!(matches!(
+            connection.reconnect().await,
+            Err(DeribitFixError::Connection(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_count_traffic_by_msg_type() {
+        let (connector, mut listener) = MemoryConnector::new();
+        let mut connection = Connection::with_connector(&create_test_config(), Arc::new(connector))
+            .await
+            .unwrap();
+        let mut server = listener.accept().await.unwrap();
+        assert_eq!(connection.connection_stats(), ConnectionStats::default());
+
+        connection.send_message(&heartbeat()).await.unwrap();
+        connection.send_message(&heartbeat()).await.unwrap();
+        let sent = heartbeat().to_string();
+        server
+            .write_all(format!("{sent}{sent}").as_bytes())
+            .await
+            .unwrap();
+        connection.receive_message().await.unwrap().unwrap();
+
+        let stats = connection.connection_stats();
+        assert_eq!(stats.sent(MsgType::Heartbeat), 2);
+        assert_eq!(stats.total_sent(), 2);
+        assert_eq!(stats.bytes_out, 2 * sent.len() as u64);
+        // Both messages were read and parsed, even though one is still queued
+        assert_eq!(stats.received(MsgType::Heartbeat), 2);
+        assert_eq!(stats.received(MsgType::Logon), 0);
+        assert_eq!(stats.bytes_in, 2 * sent.len() as u64);
+        assert!(stats.last_inbound.is_some() && stats.last_outbound.is_some());
+        assert_eq!(stats.reconnects, 0);
+
+        // Counters survive reconnects
+        connection.reconnect().await.unwrap();
+        let stats = connection.connection_stats();
+        assert_eq!(stats.reconnects, 1);
+        assert_eq!(stats.total_received(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_transport_through_http_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            socket
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await
+                .unwrap();
+            // Act as the FIX server behind the tunnel
+            let n = socket.read(&mut buf).await.unwrap();
+            (request, String::from_utf8_lossy(&buf[..n]).to_string())
+        });
+
+        let config =
+            create_test_config().with_proxy(ProxyConfig::http(addr.ip().to_string(), addr.port()));
+        let mut connection = Connection::new(&config).await.unwrap();
+        connection.send_message(&heartbeat()).await.unwrap();
+
+        let (request, tunnelled) = proxy.await.unwrap();
+        assert!(request.starts_with("CONNECT fix-test.deribit.com:9881 HTTP/1.1\r\n"));
+        assert!(tunnelled.contains("35=0\x01"));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_refusal_is_a_connection_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n")
+                .await;
+        });
+
+        let config =
+            create_test_config().with_proxy(ProxyConfig::http(addr.ip().to_string(), addr.port()));
+        match Connection::new(&config).await {
+            Err(DeribitFixError::Connection(message)) => assert!(message.contains("403")),
+            Err(other) => panic!("Expected a connection error, got {other:?}"),
+            Ok(_) => panic!("Expected the proxy to refuse the tunnel"),
+        }
+    }
+}
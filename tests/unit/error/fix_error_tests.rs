@@ -1,6 +1,7 @@
 // Unit tests for DeribitFixError
 
 use deribit_fix::error::{DeribitFixError, Result};
+use deribit_fix::message::{OrderRejectReason, QuoteRejectReason};
 
 #[cfg(test)]
 mod tests {
@@ -96,4 +97,45 @@ mod tests {
         assert!(debug_str.contains("MessageConstruction"));
         assert!(debug_str.contains("Failed to build message"));
     }
+
+    #[test]
+    fn test_reject_errors_show_the_exchange_text() {
+        let error = DeribitFixError::QuoteRejected {
+            quote_id: "Q1".to_string(),
+            reason: Some(QuoteRejectReason::InvalidPrice),
+            text: Some("price_too_high".to_string()),
+        };
+        assert_eq!(error.to_string(), "Quote Q1 rejected: price_too_high");
+        assert_eq!(error.reject_text(), Some("price_too_high"));
+
+        // Without Text (58) the reason code is shown
+        let error = DeribitFixError::OrderRejected {
+            cl_ord_id: "C1".to_string(),
+            reason: Some(OrderRejectReason::UnknownSymbol),
+            text: None,
+        };
+        assert_eq!(error.to_string(), "Order C1 rejected: UnknownSymbol");
+        assert_eq!(error.reject_text(), None);
+        assert_eq!(
+            DeribitFixError::Generic("x".to_string()).reject_text(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_rejections_are_recognised() {
+        let error = DeribitFixError::OrderRejected {
+            cl_ord_id: "C1".to_string(),
+            reason: None,
+            text: Some("too_many_requests".to_string()),
+        };
+        assert!(error.is_rate_limited());
+        let error = DeribitFixError::QuoteRejected {
+            quote_id: "Q1".to_string(),
+            reason: Some(QuoteRejectReason::InvalidPrice),
+            text: Some("price_too_high".to_string()),
+        };
+        assert!(!error.is_rate_limited());
+        assert!(!DeribitFixError::Timeout("too many requests".to_string()).is_rate_limited());
+    }
 }
@@ -1,7 +1,7 @@
 // Unit tests for MessageBuilder
 
 use chrono::Utc;
-use deribit_fix::message::MessageBuilder;
+use deribit_fix::message::{MessageBuilder, TimestampPrecision, parse_utc_timestamp};
 use deribit_fix::model::types::MsgType;
 
 #[cfg(test)]
@@ -127,6 +127,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_message_builder_sending_time_precision() {
+        let now = parse_utc_timestamp("20260101-12:30:45.123456789").unwrap();
+        let millis = create_complete_builder().sending_time(now).build().unwrap();
+        assert_eq!(millis.get_field(52).unwrap(), "20260101-12:30:45.123");
+
+        let micros = create_complete_builder()
+            .timestamp_precision(TimestampPrecision::Micros)
+            .sending_time(now)
+            .build()
+            .unwrap();
+        assert_eq!(micros.get_field(52).unwrap(), "20260101-12:30:45.123456");
+
+        // The SendingTime set when building follows the precision too
+        let stamped = create_complete_builder()
+            .timestamp_precision(TimestampPrecision::Micros)
+            .build()
+            .unwrap();
+        let fraction = stamped.get_field(52).unwrap().rsplit_once('.').unwrap().1;
+        assert_eq!(fraction.len(), 6);
+    }
+
     #[test]
     fn test_message_builder_custom_field() {
         let message = MessageBuilder::new()
@@ -232,7 +254,7 @@ mod tests {
 
         for (msg_type, expected_str) in msg_types {
             let message = MessageBuilder::new()
-                .msg_type(msg_type)
+                .msg_type(msg_type.clone())
                 .sender_comp_id("CLIENT".to_string())
                 .target_comp_id("DERIBIT".to_string())
                 .msg_seq_num(1)
@@ -378,4 +400,150 @@ mod tests {
 
         assert!(result.is_err(), "Should fail without MsgSeqNum");
     }
+
+    #[test]
+    fn test_message_builder_poss_dup_resend() {
+        let original = create_complete_builder()
+            .sending_time(Utc::now() - chrono::Duration::seconds(5))
+            .field(112, "PING".to_string())
+            .build()
+            .unwrap();
+        let original_sending_time = original.get_field(52).unwrap().clone();
+
+        let resent = MessageBuilder::from_message(&original)
+            .poss_dup(original_sending_time.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(resent.get_field(34), original.get_field(34));
+        assert_eq!(resent.get_field(112).unwrap(), "PING");
+        assert_eq!(resent.get_field(43).unwrap(), "Y");
+        assert_eq!(resent.get_field(122).unwrap(), &original_sending_time);
+        assert_ne!(resent.get_field(52).unwrap(), &original_sending_time);
+
+        // BodyLength and CheckSum must be recalculated for the new fields
+        assert_ne!(resent.get_field(9), original.get_field(9));
+        assert_eq!(
+            resent.fields.iter().filter(|(tag, _)| *tag == 10).count(),
+            1
+        );
+    }
+
+    /// BodyLength (9) and CheckSum (10) computed independently from the
+    /// serialized message, as the FIX specification defines them
+    fn reference_trailer(raw: &str) -> (usize, u8) {
+        let body_start = raw.find("\x0135=").unwrap() + 1;
+        let checksum_start = raw.rfind("10=").unwrap();
+        let body_length = checksum_start - body_start;
+        let checksum = raw.as_bytes()[..checksum_start]
+            .iter()
+            .fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        (body_length, checksum)
+    }
+
+    #[test]
+    fn test_message_builder_header_order_and_trailer() {
+        let sending_time = chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 1, 1, 0, 0, 0).unwrap();
+        let message = MessageBuilder::new()
+            .field(112, "PING".to_string())
+            .target_sub_id("ROUTER".to_string())
+            .msg_seq_num(1)
+            .sending_time(sending_time)
+            .sender_sub_id("DESK".to_string())
+            .on_behalf_of_comp_id("FUND".to_string())
+            .target_comp_id("DERIBIT".to_string())
+            .sender_comp_id("CLIENT".to_string())
+            .msg_type(MsgType::TestRequest)
+            .build()
+            .unwrap();
+
+        // Reference message serialized by hand
+        assert_eq!(
+            message.to_string(),
+            "8=FIX.4.4\x019=92\x0135=1\x0149=CLIENT\x0156=DERIBIT\x01115=FUND\x0134=1\x01\
+             50=DESK\x0157=ROUTER\x0152=20260101-00:00:00.000\x01112=PING\x0110=205\x01"
+        );
+        assert_eq!(message.get_field(9).unwrap(), "92");
+        assert_eq!(message.get_field(10).unwrap(), "205");
+    }
+
+    #[test]
+    fn test_message_builder_trailer_matches_reference() {
+        let messages = [
+            create_complete_builder().build().unwrap(),
+            MessageBuilder::new()
+                .msg_type(MsgType::NewOrderSingle)
+                .field(55, "BTC-PERPETUAL".to_string())
+                .field(11, "ORDER-1".to_string())
+                .field(38, "10".to_string())
+                .field(44, "50000.5".to_string())
+                .sender_comp_id("CLIENT".to_string())
+                .target_comp_id("DERIBITSERVER".to_string())
+                .msg_seq_num(42)
+                .field(54, "1".to_string())
+                .field(40, "2".to_string())
+                .build()
+                .unwrap(),
+            MessageBuilder::new()
+                .msg_type(MsgType::Logon)
+                .sender_comp_id("CLIENT".to_string())
+                .target_comp_id("DERIBITSERVER".to_string())
+                .msg_seq_num(1)
+                .field(58, "naïve – UTF-8 text".to_string())
+                .field(108, "30".to_string())
+                .build()
+                .unwrap(),
+        ];
+
+        for message in messages {
+            let raw = message.to_string();
+            let (body_length, checksum) = reference_trailer(&raw);
+            assert_eq!(message.get_field(9).unwrap(), &body_length.to_string());
+            assert_eq!(message.get_field(10).unwrap(), &format!("{checksum:03}"));
+            assert!(raw.starts_with(&format!("8=FIX.4.4\x019={body_length}\x0135=")));
+            assert!(raw.ends_with(&format!("\x0110={checksum:03}\x01")));
+        }
+    }
+
+    #[test]
+    fn test_message_builder_keeps_body_and_groups_in_insertion_order() {
+        let sending_time = chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 1, 1, 0, 0, 0).unwrap();
+        let message = MessageBuilder::new()
+            .msg_type(MsgType::MarketDataRequest)
+            .sender_comp_id("CLIENT".to_string())
+            .target_comp_id("DERIBIT".to_string())
+            .msg_seq_num(2)
+            .sending_time(sending_time)
+            .field(262, "MDR-1".to_string())
+            .field(263, "0".to_string())
+            .field(267, "2".to_string())
+            .push_field(269, "0".to_string())
+            .push_field(269, "1".to_string())
+            .field(146, "2".to_string())
+            .push_field(55, "BTC-PERPETUAL".to_string())
+            .push_field(48, "BTC-PERP".to_string())
+            .push_field(55, "ETH-PERPETUAL".to_string())
+            .push_field(48, "ETH-PERP".to_string())
+            .build()
+            .unwrap();
+
+        // Each group count precedes its entries, and each entry starts with
+        // its delimiter field
+        let body = "35=V\x0149=CLIENT\x0156=DERIBIT\x0134=2\x0152=20260101-00:00:00.000\x01\
+                    262=MDR-1\x01263=0\x01267=2\x01269=0\x01269=1\x01146=2\x01\
+                    55=BTC-PERPETUAL\x0148=BTC-PERP\x0155=ETH-PERPETUAL\x0148=ETH-PERP\x01";
+        let head = format!("8=FIX.4.4\x019={}\x01{body}", body.len());
+        let checksum = head.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+        assert_eq!(message.to_string(), format!("{head}10={checksum:03}\x01"));
+        assert_eq!(
+            message
+                .fields
+                .iter()
+                .map(|(tag, _)| *tag)
+                .collect::<Vec<_>>(),
+            [
+                8, 9, 35, 49, 56, 34, 52, 262, 263, 267, 269, 269, 146, 55, 48, 55, 48, 10
+            ]
+        );
+    }
 }
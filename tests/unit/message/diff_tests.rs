@@ -0,0 +1,70 @@
+// Round-trip tests of FIX messages compared with FixDiff
+
+use deribit_fix::message::{ExecutionReport, FixDiff, MessageBuilder, OrderSide};
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::types::MsgType;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_report_round_trip() {
+        let report = ExecutionReport::fill(
+            "ORD-1".to_string(),
+            "CL-1".to_string(),
+            "EXEC-1".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            OrderSide::Buy,
+            100.0,
+            40.0,
+            60.0,
+            65000.5,
+            60.0,
+            65000.5,
+        )
+        .with_label("strategy-a".to_string());
+        let sent = report.to_fix_message("DERIBIT", "CLIENT", 7).unwrap();
+
+        let parsed =
+            ExecutionReport::from_fix_message(&FixMessage::parse(&sent.raw_message).unwrap())
+                .unwrap();
+        let resent = parsed.to_fix_message("DERIBIT", "CLIENT", 8).unwrap();
+        FixDiff::new().assert_matches(&sent, &resent);
+
+        let report = FixDiff::strict().diff(&sent, &resent);
+        assert!(report.differences.iter().any(|d| d.tag == 34));
+    }
+
+    #[test]
+    fn test_rebuilt_message_matches_the_original() {
+        let original = MessageBuilder::new()
+            .msg_type(MsgType::MarketDataRequest)
+            .sender_comp_id("CLIENT".to_string())
+            .target_comp_id("DERIBIT".to_string())
+            .msg_seq_num(3)
+            .field(262, "MDR_1".to_string())
+            .field(263, "1".to_string())
+            .field(267, "2".to_string())
+            .field(269, "0".to_string())
+            .field(269, "1".to_string())
+            .field(146, "1".to_string())
+            .field(55, "BTC-PERPETUAL".to_string())
+            .build()
+            .unwrap();
+
+        let parsed = FixMessage::parse(&original.raw_message).unwrap();
+        let rebuilt = MessageBuilder::from_message(&parsed).build().unwrap();
+        FixDiff::strict().assert_matches(&original, &rebuilt);
+
+        let mut changed = parsed.clone();
+        changed.set_field(34, "4".to_string());
+        let changed = MessageBuilder::from_message(&changed).build().unwrap();
+        FixDiff::new().assert_matches(&original, &changed);
+        let report = FixDiff::strict().diff(&original, &changed);
+        assert_eq!(
+            report.differences.iter().map(|d| d.tag).collect::<Vec<_>>(),
+            [34, 10]
+        );
+    }
+}
@@ -2,3 +2,4 @@
 
 mod admin_tests;
 mod builder_tests;
+mod diff_tests;
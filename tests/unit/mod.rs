@@ -19,5 +19,11 @@ mod message;
 // Model module tests
 mod model;
 
+// Scenario module tests
+mod scenario;
+
 // Session module tests
 mod session;
+
+// Helpers shared by the tests
+mod support;
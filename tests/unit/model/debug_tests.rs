@@ -113,7 +113,8 @@ mod tests {
         assert!(debug_output.contains("EncryptMethod(98)=0"));
         assert!(debug_output.contains("HeartBtInt(108)=30"));
         assert!(debug_output.contains("Username(553)=username"));
-        assert!(debug_output.contains("Password(554)=password"));
+        assert!(debug_output.contains("Password(554)=***"));
+        assert!(!debug_output.contains("=password"));
     }
 
     #[test]
@@ -9,7 +9,7 @@ mod tests {
     #[test]
     fn test_msg_type_clone() {
         let msg_type = MsgType::Heartbeat;
-        let cloned = msg_type;
+        let cloned = msg_type.clone();
         assert_eq!(msg_type, cloned);
     }
 
@@ -44,9 +44,9 @@ mod tests {
     }
 
     #[test]
-    fn test_msg_type_copy() {
-        let msg_type = MsgType::Heartbeat;
-        let copied = msg_type;
-        assert_eq!(msg_type, copied);
+    fn test_msg_type_clone_unknown() {
+        let msg_type = MsgType::Unknown("U99".to_string());
+        let cloned = msg_type.clone();
+        assert_eq!(msg_type, cloned);
     }
 }
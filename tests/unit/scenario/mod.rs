@@ -0,0 +1,3 @@
+// Unit tests for scenario module
+
+mod runner_tests;
@@ -0,0 +1,148 @@
+// Unit tests for the scenario runner against the mock exchange
+
+use deribit_fix::client::DeribitFixClient;
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::model::request::NewOrderRequest;
+use deribit_fix::model::types::MsgType;
+use deribit_fix::scenario::{
+    ExpectedMessage, MockExchange, MockExchangeHandle, Scenario, ScenarioRunner, StepOutcome,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_runner(exchange: MockExchange) -> (ScenarioRunner, MockExchangeHandle) {
+        let (connector, handle) = exchange.spawn();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_pass".to_string())
+            .with_session_ids("CLIENT".to_string(), "DERIBITSERVER".to_string())
+            .with_ssl(false);
+        let client = DeribitFixClient::new(&config)
+            .await
+            .unwrap()
+            .with_connector(Arc::new(connector));
+        let runner = ScenarioRunner::new(client).with_step_timeout(Duration::from_secs(2));
+        (runner, handle)
+    }
+
+    fn execution_report(order: &str) -> ExpectedMessage {
+        ExpectedMessage::new(MsgType::ExecutionReport).with_order(order)
+    }
+
+    #[tokio::test]
+    async fn test_order_entry_scenario_passes() {
+        let exchange = MockExchange::new().with_book("BTC-PERPETUAL", 49_990.0, 50_010.0);
+        let (mut runner, exchange) = create_runner(exchange).await;
+        let scenario = Scenario::new("order entry")
+            .logon()
+            .subscribe("BTC-PERPETUAL")
+            .expect(
+                ExpectedMessage::new(MsgType::MarketDataSnapshotFullRefresh)
+                    .with_field(55, "BTC-PERPETUAL")
+                    .with_field(270, "49990"),
+            )
+            .send_order(
+                "bid",
+                NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 40_000.0),
+            )
+            .expect(execution_report("bid").with_field(39, "0"))
+            .cancel("bid")
+            .expect(execution_report("bid").with_field(39, "4"))
+            .send_order(
+                "take",
+                NewOrderRequest::market_buy("BTC-PERPETUAL".to_string(), 10.0),
+            )
+            .expect(
+                execution_report("take")
+                    .with_field(150, "F")
+                    .with_field(31, "50010"),
+            )
+            .logout();
+
+        let report = runner.run(&scenario).await.unwrap();
+        assert!(report.passed(), "{report}");
+        assert_eq!(report.steps.len(), 10);
+        let fill = report.steps[8].matched.as_ref().unwrap();
+        assert_eq!(
+            fill.get_field(11).map(String::as_str),
+            runner.cl_ord_id("take")
+        );
+        assert_eq!(exchange.open_orders(), 0);
+        let sent: Vec<String> = exchange
+            .received()
+            .iter()
+            .filter_map(|message| message.get_field(35).cloned())
+            .collect();
+        assert_eq!(sent, ["A", "V", "D", "F", "D", "5"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_expectation_stops_the_scenario() {
+        let (mut runner, _exchange) = create_runner(MockExchange::new()).await;
+        let scenario = Scenario::new("empty book")
+            .logon()
+            .send_order(
+                "take",
+                NewOrderRequest::market_sell("BTC-PERPETUAL".to_string(), 10.0),
+            )
+            .expect(
+                execution_report("take")
+                    .with_field(150, "F")
+                    .with_timeout_ms(200),
+            )
+            .logout();
+
+        let report = runner.run(&scenario).await.unwrap();
+        assert!(!report.passed());
+        let failure = report.failure().unwrap();
+        assert_eq!(failure.index, 3);
+        assert!(matches!(failure.outcome, StepOutcome::Failed(_)));
+        assert_eq!(report.skipped, 1);
+        assert!(report.into_result().is_err());
+
+        let _ = runner.client().disconnect().await;
+    }
+
+    #[cfg(feature = "config-files")]
+    #[tokio::test]
+    async fn test_yaml_scenario_runs() {
+        use deribit_fix::config::ConfigFormat;
+
+        let scenario = Scenario::from_contents(
+            r#"
+name: resting order
+steps:
+  - step: logon
+  - step: send_order
+    name: bid
+    order:
+      instrument_name: BTC-PERPETUAL
+      side: Buy
+      type: limit
+      amount: 10
+      price: 40000
+      time_in_force: good_til_cancelled
+  - step: expect
+    msg_type: 8
+    order: bid
+    fields:
+      39: 0
+      37: "*"
+  - step: cancel
+    order: bid
+  - step: logout
+"#,
+            ConfigFormat::Yaml,
+        )
+        .unwrap();
+        assert_eq!(scenario.steps.len(), 5);
+
+        let (mut runner, exchange) = create_runner(MockExchange::new()).await;
+        let report = runner.run(&scenario).await.unwrap();
+        assert!(report.passed(), "{report}");
+        assert_eq!(exchange.open_orders(), 0);
+    }
+}
@@ -0,0 +1,71 @@
+// Unit tests for Session account summary requests
+
+use super::super::support::{HEADER, create_session, field, frame, start_raw_mock_server_with};
+use deribit_fix::error::DeribitFixError;
+use tokio::sync::mpsc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server answering every User Request (BE) with a User
+    /// Response (BF) carrying `body` and forwarding what it reads to a channel
+    async fn start_user_server(
+        body: &'static str,
+    ) -> (std::net::SocketAddr, mpsc::UnboundedReceiver<String>) {
+        start_raw_mock_server_with(Vec::new(), move |data| {
+            let Some(user_request_id) = field(data, "923") else {
+                return Vec::new();
+            };
+            // An unrelated response is skipped
+            vec![
+                frame(&format!(
+                    "35=BF\x0134=1\x01{HEADER}923=OTHER\x01926=1\x01100001=1\x01"
+                )),
+                frame(&format!(
+                    "35=BF\x0134=2\x01{HEADER}923={user_request_id}\x01553=test_user\x01{body}"
+                )),
+            ]
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_account_summary_from_user_response() {
+        let (addr, mut outgoing) = start_user_server(
+            "926=1\x01100001=12.5\x01100002=12\x01100003=2.5\x01100004=1.25\x01100005=0.5\x01100006=-0.1\x01100011=0.4\x01",
+        )
+        .await;
+        let mut session = create_session(addr).await;
+
+        let summary = session.get_account_summary("BTC").await.unwrap();
+        assert_eq!(summary.currency, "BTC");
+        assert_eq!(summary.equity, Some(12.5));
+        assert_eq!(summary.balance, Some(12.0));
+        assert_eq!(summary.initial_margin, Some(2.5));
+        assert_eq!(summary.maintenance_margin, Some(1.25));
+        assert_eq!(summary.unrealized_pl, Some(0.5));
+        assert_eq!(summary.realized_pl, Some(-0.1));
+        assert_eq!(summary.total_pl, Some(0.4));
+        assert_eq!(summary.margin_balance, None);
+        assert_eq!(summary.available_funds(), Some(10.0));
+
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(field(&request, "35").as_deref(), Some("BE"));
+        assert_eq!(field(&request, "924").as_deref(), Some("4"));
+        assert_eq!(field(&request, "553").as_deref(), Some("test_user"));
+        assert_eq!(field(&request, "15").as_deref(), Some("BTC"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_account_summary_is_an_error() {
+        let (addr, _outgoing) = start_user_server("926=99\x01927=unknown currency\x01").await;
+        let mut session = create_session(addr).await;
+
+        let error = session.get_account_summary("XYZ").await.unwrap_err();
+        match error {
+            DeribitFixError::Session(message) => assert!(message.contains("unknown currency")),
+            other => panic!("Expected a session error, got {other:?}"),
+        }
+    }
+}
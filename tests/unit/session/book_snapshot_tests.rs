@@ -0,0 +1,74 @@
+// Unit tests for Session one-off order book snapshots
+
+use super::super::support::{
+    HEADER, create_session, field, field_values, frame, start_market_data_server,
+};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::MdReqRejReason;
+use deribit_fix::model::message::FixMessage;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snapshot_is_returned_without_a_subscription() {
+        let book = frame(&format!(
+            "35=W\x0134=1\x01{HEADER}55=BTC-PERPETUAL\x01268=1\x01269=0\x01270=99\x01271=5\x01"
+        ));
+        let (addr, mut outgoing) = start_market_data_server(vec![book], |md_req_id| {
+            frame(&format!(
+                "35=W\x0134=2\x01{HEADER}262={md_req_id}\x0155=BTC-PERPETUAL\x01268=3\x01\
+                 269=0\x01270=100\x01271=2\x01269=0\x01270=99.5\x01271=4\x01\
+                 269=1\x01270=100.5\x01271=1\x01"
+            ))
+        })
+        .await;
+        let mut session = create_session(addr).await;
+        session.receive_and_process_message().await.unwrap();
+
+        let snapshot = session
+            .get_order_book_snapshot("BTC-PERPETUAL", 2)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.bids(), [(100.0, 2.0), (99.5, 4.0)]);
+        assert_eq!(snapshot.asks(), [(100.5, 1.0)]);
+
+        // The local book and the subscriptions are left alone
+        let book = session.order_book("BTC-PERPETUAL").unwrap();
+        assert_eq!(book.best_bid(), Some((99.0, 5.0)));
+        assert!(session.market_data_subscriptions().is_empty());
+
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(field(&request, "35").as_deref(), Some("V"));
+        assert_eq!(field(&request, "263").as_deref(), Some("0"));
+        assert_eq!(field(&request, "264").as_deref(), Some("2"));
+        assert_eq!(field(&request, "55").as_deref(), Some("BTC-PERPETUAL"));
+        // Both sides of the book are requested
+        let request = FixMessage::parse(&request).unwrap();
+        assert_eq!(request.get_field(267).unwrap(), "2");
+        assert_eq!(field_values(&request, 269), ["0", "1"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_snapshot_request() {
+        let (addr, _outgoing) = start_market_data_server(Vec::new(), |md_req_id| {
+            frame(&format!(
+                "35=Y\x0134=1\x01{HEADER}262={md_req_id}\x01281=0\x0158=unknown symbol\x01"
+            ))
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let error = session
+            .get_order_book_snapshot("UNKNOWN", 0)
+            .await
+            .unwrap_err();
+        match error {
+            DeribitFixError::MarketDataRejected { reason, .. } => {
+                assert_eq!(reason, Some(MdReqRejReason::UnknownSymbol));
+            }
+            other => panic!("Expected a market data reject, got {other:?}"),
+        }
+    }
+}
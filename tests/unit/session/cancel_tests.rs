@@ -0,0 +1,402 @@
+// Unit tests for Session typed cancel targets
+
+use super::super::support::{HEADER, create_session, frame, start_mock_server_with};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::{
+    CxlRejReason, CxlRejResponseTo, OrderCancelReplaceRequest, OrderRejectReason,
+    OrderSide as FixOrderSide,
+};
+use deribit_fix::model::cancel::{CancelReport, CancelTarget};
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::request::{NewOrderRequest, OrderSide};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cancelled_report(order_id: &str, cl_ord_id: &str) -> String {
+        frame(&format!(
+            "35=8\x0134=1\x01{HEADER}37={order_id}\x0111={cl_ord_id}\x0117=EXEC-1\x01150=4\x0139=4\x0155=BTC-PERPETUAL\x0154=1\x0138=10\x01151=0\x0114=0\x01"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_cancel_by_order_id_returns_execution_report() {
+        let (addr, mut outgoing) = start_mock_server_with(Vec::new(), |_| {
+            vec![
+                // An unrelated order is cancelled first and must be skipped
+                cancelled_report("OTHER", "OTHER"),
+                cancelled_report("ETH-123", "MY-ORDER"),
+            ]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let report = session
+            .cancel(CancelTarget::OrderId("ETH-123".to_string()))
+            .await
+            .unwrap();
+        match report {
+            CancelReport::Order(report) => {
+                assert_eq!(report.order_id, "ETH-123");
+                assert_eq!(report.cl_ord_id, "MY-ORDER");
+            }
+            other => panic!("unexpected report {other:?}"),
+        }
+
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "F");
+        assert_eq!(request.get_field(41).unwrap(), "ETH-123");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_by_label_sends_mass_cancel() {
+        let (addr, mut outgoing) = start_mock_server_with(Vec::new(), |request| {
+            let cl_ord_id = request.get_field(11).unwrap();
+            vec![frame(&format!(
+                "35=r\x0134=1\x01{HEADER}11={cl_ord_id}\x01530=10\x01531=10\x01533=2\x0158=done\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let report = session
+            .cancel(CancelTarget::Label("mm-btc".to_string()))
+            .await
+            .unwrap();
+        match report {
+            CancelReport::Mass(report) => assert_eq!(report.total_affected_orders, Some(2)),
+            other => panic!("unexpected report {other:?}"),
+        }
+
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "q");
+        assert_eq!(request.get_field(530).unwrap(), "10");
+        assert_eq!(request.get_field(100010).unwrap(), "mm-btc");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_by_symbol_and_side() {
+        let (addr, mut outgoing) = start_mock_server_with(Vec::new(), |request| {
+            let cl_ord_id = request.get_field(11).unwrap();
+            vec![frame(&format!(
+                "35=r\x0134=1\x01{HEADER}11={cl_ord_id}\x01530=1\x01531=1\x01533=0\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let report = session
+            .cancel(CancelTarget::SymbolSide {
+                symbol: "BTC-PERPETUAL".to_string(),
+                side: OrderSide::Sell,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(report, CancelReport::Mass(_)));
+
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(530).unwrap(), "1");
+        assert_eq!(request.get_field(55).unwrap(), "BTC-PERPETUAL");
+        assert_eq!(request.get_field(54).unwrap(), "2");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_reject_is_returned_as_error() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), |_| {
+            vec![frame(&format!(
+                "35=9\x0134=1\x01{HEADER}11=MY-ORDER\x0139=2\x01102=0\x01434=1\x0158=order already filled\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let result = session
+            .cancel(CancelTarget::ClOrdId {
+                cl_ord_id: "MY-ORDER".to_string(),
+                symbol: "BTC-PERPETUAL".to_string(),
+            })
+            .await;
+        match result {
+            Err(DeribitFixError::CancelRejected {
+                order_id,
+                reason,
+                response_to,
+                text,
+            }) => {
+                assert_eq!(order_id, "MY-ORDER");
+                assert_eq!(reason, Some(CxlRejReason::TooLateToCancel));
+                assert_eq!(response_to, Some(CxlRejResponseTo::OrderCancelRequest));
+                assert_eq!(text.as_deref(), Some("order already filled"));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_order_returns_cancel_rejected() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), |request| {
+            let cancel_id = request.get_field(11).unwrap();
+            vec![frame(&format!(
+                "35=9\x0134=1\x01{HEADER}11={cancel_id}\x0141=ETH-404\x01102=1\x01434=1\x0158=unknown order\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let result = session.cancel_order("ETH-404".to_string()).await;
+        match result {
+            Err(DeribitFixError::CancelRejected {
+                order_id, reason, ..
+            }) => {
+                assert_eq!(order_id, "ETH-404");
+                assert_eq!(reason, Some(CxlRejReason::UnknownOrder));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_order_waits_for_confirmation() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), |_| {
+            vec![cancelled_report("ETH-123", "MY-ORDER")]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        session.cancel_order("ETH-123".to_string()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replace_reject_is_returned_as_error() {
+        let (addr, mut outgoing) = start_mock_server_with(Vec::new(), |_| {
+            vec![frame(&format!(
+                "35=9\x0134=1\x01{HEADER}11=NEW-ID\x0141=ETH-123\x01102=99\x01434=2\x0158=price out of band\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let request = OrderCancelReplaceRequest::new(
+            "ETH-123".to_string(),
+            "NEW-ID".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            FixOrderSide::Buy,
+        )
+        .with_price(1.0);
+        match session.replace_order(request).await {
+            Err(DeribitFixError::CancelRejected {
+                order_id,
+                response_to,
+                text,
+                ..
+            }) => {
+                assert_eq!(order_id, "ETH-123");
+                assert_eq!(
+                    response_to,
+                    Some(CxlRejResponseTo::OrderCancelReplaceRequest)
+                );
+                assert_eq!(text.as_deref(), Some("price out of band"));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+        assert_eq!(outgoing.recv().await.unwrap().get_field(35).unwrap(), "G");
+    }
+
+    #[tokio::test]
+    async fn test_replace_rejected_by_execution_report_keeps_text() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), |_| {
+            vec![frame(&format!(
+                "35=8\x0134=1\x01{HEADER}37=ETH-123\x0111=NEW-ID\x0141=ETH-123\x0117=EXEC-1\x01\
+                 150=8\x0139=8\x0155=BTC-PERPETUAL\x0154=1\x0138=10\x01151=0\x0114=0\x01\
+                 103=3\x0158=not_enough_funds\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let request = OrderCancelReplaceRequest::new(
+            "ETH-123".to_string(),
+            "NEW-ID".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            FixOrderSide::Buy,
+        )
+        .with_price(1.0);
+        match session.replace_order(request).await {
+            Err(DeribitFixError::OrderRejected {
+                cl_ord_id,
+                reason,
+                text,
+            }) => {
+                assert_eq!(cl_ord_id, "NEW-ID");
+                assert_eq!(reason, Some(OrderRejectReason::OrderExceedsLimit));
+                assert_eq!(text.as_deref(), Some("not_enough_funds"));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_reject_of_cancel_keeps_text() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), |request| {
+            let seq = request.get_field(34).unwrap();
+            vec![frame(&format!(
+                "35=3\x0134=1\x01{HEADER}45={seq}\x01372=F\x01373=1\x0158=OrigClOrdID missing\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let error = session
+            .cancel(CancelTarget::OrderId("ETH-123".to_string()))
+            .await
+            .unwrap_err();
+        match &error {
+            DeribitFixError::MessageRejected {
+                ref_msg_type,
+                reason,
+                ..
+            } => {
+                assert_eq!(ref_msg_type.as_deref(), Some("F"));
+                assert_eq!(*reason, Some(1));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+        assert_eq!(error.reject_text(), Some("OrigClOrdID missing"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_quotes_and_orders() {
+        let (addr, mut outgoing) =
+            start_mock_server_with(Vec::new(), |request| match request.get_field(35) {
+                Some(msg_type) if msg_type == "Z" => {
+                    let quote_id = request.get_field(117).unwrap();
+                    vec![frame(&format!(
+                        "35=b\x0134=1\x01{HEADER}117={quote_id}\x01297=0\x01"
+                    ))]
+                }
+                Some(msg_type) if msg_type == "q" => {
+                    let cl_ord_id = request.get_field(11).unwrap();
+                    vec![frame(&format!(
+                        "35=r\x0134=2\x01{HEADER}11={cl_ord_id}\x01530=7\x01531=7\x01533=3\x01"
+                    ))]
+                }
+                _ => Vec::new(),
+            })
+            .await;
+        let mut session = create_session(addr).await;
+
+        let report = session.cancel_all_quotes_and_orders().await.unwrap();
+        assert_eq!(report.total_affected_orders, Some(3));
+
+        let quote_cancel = outgoing.recv().await.unwrap();
+        assert_eq!(quote_cancel.get_field(35).unwrap(), "Z");
+        assert_eq!(quote_cancel.get_field(298).unwrap(), "5");
+        let mass_cancel = outgoing.recv().await.unwrap();
+        assert_eq!(mass_cancel.get_field(35).unwrap(), "q");
+        assert_eq!(mass_cancel.get_field(530).unwrap(), "7");
+    }
+
+    #[tokio::test]
+    async fn test_rejected_quote_cancel_stops_the_cleanup() {
+        let (addr, mut outgoing) = start_mock_server_with(Vec::new(), |request| {
+            let quote_id = request.get_field(117).unwrap();
+            vec![frame(&format!(
+                "35=b\x0134=1\x01{HEADER}117={quote_id}\x01297=5\x0158=no quotes\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let result = session.cancel_all_quotes_and_orders().await;
+        assert!(matches!(result, Err(DeribitFixError::QuoteRejected { .. })));
+        assert_eq!(outgoing.recv().await.unwrap().get_field(35).unwrap(), "Z");
+        assert!(outgoing.try_recv().is_err());
+    }
+
+    /// Answer orders with an acknowledgement carrying the OrderID `EXCH-1`
+    /// and cancels and replaces with their confirmation
+    fn order_lifecycle_replies(request: &FixMessage) -> Vec<String> {
+        let cl_ord_id = request.get_field(11).cloned().unwrap_or_default();
+        match request.get_field(35).unwrap().as_str() {
+            "D" => vec![frame(&format!(
+                "35=8\x0134=1\x01{HEADER}37=EXCH-1\x0111={cl_ord_id}\x0117=EXEC-1\x01150=0\x0139=0\x0155=BTC-PERPETUAL\x0154=1\x0138=10\x01151=10\x0114=0\x01"
+            ))],
+            "G" => vec![frame(&format!(
+                "35=8\x0134=1\x01{HEADER}37=EXCH-1\x0111={cl_ord_id}\x0141={}\x0117=EXEC-2\x01150=5\x0139=0\x0155=BTC-PERPETUAL\x0154=1\x0138=20\x01151=20\x0114=0\x01",
+                request.get_field(41).unwrap()
+            ))],
+            "F" => match request.get_field(41) {
+                Some(order_id) => vec![cancelled_report(order_id, "MY-ORDER")],
+                None => vec![cancelled_report("EXCH-2", &cl_ord_id)],
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orders_are_cancelled_and_replaced_by_cl_ord_id() {
+        let (addr, mut outgoing) =
+            start_mock_server_with(Vec::new(), order_lifecycle_replies).await;
+        let mut session = create_session(addr).await;
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_client_order_id("MY-ORDER".to_string());
+        session.send_new_order(order).await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(
+            session.order_tracker().order_id_of("MY-ORDER"),
+            Some("EXCH-1")
+        );
+        assert_eq!(
+            session.order_tracker().cl_ord_id_of("EXCH-1"),
+            Some("MY-ORDER")
+        );
+        outgoing.recv().await.unwrap();
+
+        let replace = OrderCancelReplaceRequest::new(
+            "MY-ORDER".to_string(),
+            "MY-ORDER-2".to_string(),
+            "BTC-PERPETUAL".to_string(),
+            FixOrderSide::Buy,
+        );
+        let report = session.replace_order(replace).await.unwrap();
+        assert_eq!(report.cl_ord_id, "MY-ORDER-2");
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "G");
+        assert_eq!(request.get_field(41).unwrap(), "EXCH-1");
+
+        // The replacement's ClOrdID resolves to the same order
+        session
+            .cancel(CancelTarget::OrderId("MY-ORDER-2".to_string()))
+            .await
+            .unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "F");
+        assert_eq!(request.get_field(41).unwrap(), "EXCH-1");
+    }
+
+    #[tokio::test]
+    async fn test_unacknowledged_order_is_cancelled_by_cl_ord_id() {
+        let (addr, mut outgoing) =
+            start_mock_server_with(Vec::new(), order_lifecycle_replies).await;
+        let mut session = create_session(addr).await;
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_client_order_id("PENDING".to_string());
+        session.send_new_order(order).await.unwrap();
+        outgoing.recv().await.unwrap();
+
+        // The cancel skips the acknowledgement of the order and waits for its own
+        let report = session
+            .cancel(CancelTarget::OrderId("PENDING".to_string()))
+            .await
+            .unwrap();
+        assert!(matches!(report, CancelReport::Order(report) if report.cl_ord_id == "PENDING"));
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "F");
+        assert_eq!(request.get_field(41), None);
+        assert_eq!(request.get_field(11).unwrap(), "PENDING");
+        assert_eq!(request.get_field(55).unwrap(), "BTC-PERPETUAL");
+    }
+}
@@ -0,0 +1,73 @@
+// Unit tests for Session server capability detection
+
+use super::super::support::{HEADER, frame, start_mock_server_with};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::session::{Session, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Accept the Logon with DefaultApplVerID 9 and answer the probe Test
+    /// Request with a Heartbeat carrying a custom tag, followed by a Reject of
+    /// tag 9999
+    fn answer_probe(message: &FixMessage) -> Vec<String> {
+        match message.get_field(35).map(String::as_str) {
+            Some("A") => vec![frame(&format!(
+                "35=A\x0134=1\x01{HEADER}108=30\x011137=9\x019001=Y\x01"
+            ))],
+            Some("1") => vec![
+                frame(&format!(
+                    "35=0\x0134=2\x01{HEADER}112={}\x015001=test\x01",
+                    message.get_field(112).unwrap()
+                )),
+                frame(&format!(
+                    "35=3\x0134=3\x01{HEADER}45=2\x01371=9999\x01373=3\x01"
+                )),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_are_detected_at_logon() {
+        let (addr, mut server) = start_mock_server_with(Vec::new(), answer_probe).await;
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+            .with_capability_probe(true)
+            .with_default_appl_ver_id("9".to_string());
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+
+        session.logon().await.unwrap();
+        let logon = server.recv().await.unwrap();
+        assert_eq!(logon.get_field(1137).map(String::as_str), Some("9"));
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.state(), SessionState::LoggedOn);
+        let probe = server.recv().await.unwrap();
+        assert_eq!(probe.get_field(35).map(String::as_str), Some("1"));
+
+        let capabilities = session.server_capabilities();
+        assert_eq!(capabilities.heartbeat_interval, Some(30));
+        assert_eq!(capabilities.default_appl_ver_id.as_deref(), Some("9"));
+        assert!(capabilities.sends_tag(9001));
+        assert!(!capabilities.answers_test_requests());
+
+        session.receive_and_process_message().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        let capabilities = session.server_capabilities();
+        assert!(capabilities.answers_test_requests());
+        assert!(capabilities.sends_tag(5001));
+        assert!(capabilities.rejects_tag(9999));
+    }
+}
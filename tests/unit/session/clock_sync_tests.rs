@@ -0,0 +1,57 @@
+// Unit tests for Session clock drift compensation of logon auth data
+
+use super::super::support::{create_session, frame, start_writing_mock_server};
+use chrono::{TimeDelta, Utc};
+use deribit_fix::message::time::format_utc_timestamp;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Timestamp of the RawData (96) `timestamp.nonce`
+    fn auth_timestamp(raw_data: &str) -> i64 {
+        raw_data.split('.').next().unwrap().parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_auth_timestamp_follows_server_clock() {
+        let server_time = Utc::now() + TimeDelta::minutes(10);
+        let addr = start_writing_mock_server(vec![
+            frame(&format!(
+                "35=0\x0134=1\x0149=DERIBIT\x0156=CLIENT\x0152={}\x01",
+                format_utc_timestamp(&server_time)
+            )),
+            // Resent messages keep their original SendingTime and are ignored
+            frame(&format!(
+                "35=0\x0134=2\x0149=DERIBIT\x0156=CLIENT\x0143=Y\x0152={}\x01",
+                format_utc_timestamp(&(server_time - TimeDelta::hours(1)))
+            )),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        assert_eq!(session.clock_offset(), TimeDelta::zero());
+
+        session.receive_and_process_message().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        let offset = session.clock_offset();
+        assert!(
+            (offset - TimeDelta::minutes(10)).abs() < TimeDelta::seconds(1),
+            "{offset:?}"
+        );
+
+        let (raw_data, _) = session.generate_auth_data("secret").unwrap();
+        let timestamp = auth_timestamp(&raw_data);
+        assert!(timestamp >= (server_time - TimeDelta::seconds(1)).timestamp_millis());
+    }
+
+    #[tokio::test]
+    async fn test_auth_timestamps_strictly_increase() {
+        let addr = start_writing_mock_server(Vec::new()).await;
+        let session = create_session(addr).await;
+
+        let timestamps: Vec<i64> = (0..20)
+            .map(|_| auth_timestamp(&session.generate_auth_data("secret").unwrap().0))
+            .collect();
+        assert!(timestamps.windows(2).all(|pair| pair[1] > pair[0]));
+    }
+}
@@ -0,0 +1,37 @@
+// Unit tests for Session combo order entry
+
+use super::super::support::{HEADER, create_session, frame, start_raw_mock_server};
+use deribit_fix::model::combo::{ComboLeg, ComboOrderRequest};
+use deribit_fix::model::request::OrderSide;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_combo_order_by_legs_is_sent_on_combo_instrument() {
+        let (addr, mut outgoing) = start_raw_mock_server(vec![frame(&format!(
+            "35=y\x0134=1\x01{HEADER}320=SLR_1\x01322=R1\x01560=0\x01146=1\x0155=BTC-FS-27DEC24_PERP\x01167=FUTCO\x01555=2\x01600=BTC-PERPETUAL\x01624=2\x01623=1\x01600=BTC-27DEC24\x01624=1\x01623=1\x01"
+        ))])
+        .await;
+        let mut session = create_session(addr).await;
+        let legs = vec![
+            ComboLeg::sell("BTC-PERPETUAL".to_string(), 1.0),
+            ComboLeg::buy("BTC-27DEC24".to_string(), 1.0),
+        ];
+
+        // Legs cannot be resolved before the combo is known
+        let order = ComboOrderRequest::from_legs(legs, OrderSide::Buy, 10.0).with_limit_price(25.0);
+        assert!(session.send_combo_order(order.clone()).await.is_err());
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.combos().len(), 1);
+
+        session.send_combo_order(order).await.unwrap();
+        let sent = outgoing.recv().await.unwrap();
+        assert!(sent.contains("35=D\x01"));
+        assert!(sent.contains("\x0155=BTC-FS-27DEC24_PERP\x01"));
+        assert!(sent.contains("\x0154=1\x01"));
+        assert!(sent.contains("\x0144=25\x01"));
+    }
+}
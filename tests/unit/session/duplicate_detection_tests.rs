@@ -0,0 +1,128 @@
+// Unit tests for Session duplicate (PossDupFlag) detection
+
+use super::super::support::{create_session, frame, start_writing_mock_server};
+use deribit_fix::message::{Heartbeat, MessageBuilder};
+use deribit_fix::session::SessionEvent;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fill of order `A` with ExecID `exec_id`, resent when `poss_dup` is set
+    fn fill(seq: u32, exec_id: &str, cum_qty: u32, poss_dup: bool) -> String {
+        let flags = if poss_dup {
+            "43=Y\x01122=20260101-00:00:00.000\x01"
+        } else {
+            ""
+        };
+        frame(&format!(
+            "35=8\x0134={seq}\x01{flags}49=DERIBIT\x0156=CLIENT\x0152=20260101-00:00:01.000\x01\
+             11=A\x0137=ORD-1\x0117={exec_id}\x01150=F\x0139=1\x0155=BTC-PERPETUAL\x0154=1\x01\
+             38=30\x0132=10\x0131=50000\x0114={cum_qty}\x01151={}\x01",
+            30 - cum_qty
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_poss_dup_of_processed_message_is_dropped() {
+        let original = Heartbeat::new()
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), 1)
+            .unwrap();
+        let duplicate = MessageBuilder::from_message(&original)
+            .poss_dup(original.get_field(52).unwrap().clone())
+            .build()
+            .unwrap();
+
+        let addr =
+            start_writing_mock_server(vec![original.to_string(), duplicate.to_string()]).await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        assert!(
+            session
+                .receive_and_process_message()
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(session.incoming_seq_num(), 2);
+
+        assert!(
+            session
+                .receive_and_process_message()
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(session.incoming_seq_num(), 2);
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::DuplicateMessage {
+                msg_seq_num: 1,
+                msg_type: "0".to_string(),
+                poss_resend: false,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_poss_dup_of_unseen_message_is_processed() {
+        let original = Heartbeat::new()
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), 1)
+            .unwrap();
+        let resent = MessageBuilder::from_message(&original)
+            .poss_dup(original.get_field(52).unwrap().clone())
+            .build()
+            .unwrap();
+
+        let addr = start_writing_mock_server(vec![resent.to_string()]).await;
+        let mut session = create_session(addr).await;
+
+        let message = session.receive_and_process_message().await.unwrap();
+        assert!(message.is_some());
+        assert_eq!(session.incoming_seq_num(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_execution_is_delivered_once() {
+        // After a gap, the resend replays EXEC-1 under a new sequence number
+        // before the unseen EXEC-2
+        let addr = start_writing_mock_server(vec![
+            fill(1, "EXEC-1", 10, false),
+            fill(2, "EXEC-1", 10, true),
+            fill(3, "EXEC-2", 20, true),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        let first = session.receive_and_process_message().await.unwrap();
+        assert_eq!(first.unwrap().get_field(17).unwrap(), "EXEC-1");
+
+        assert!(
+            session
+                .receive_and_process_message()
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(session.incoming_seq_num(), 3);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::DuplicateExecution {
+                msg_seq_num: 2,
+                exec_id: "EXEC-1".to_string(),
+                cl_ord_id: Some("A".to_string()),
+            }
+        );
+
+        let resent = session.receive_and_process_message().await.unwrap();
+        assert_eq!(resent.unwrap().get_field(17).unwrap(), "EXEC-2");
+        assert_eq!(session.incoming_seq_num(), 4);
+
+        let order = session.order_tracker().order("A").unwrap();
+        assert_eq!(order.fills().count(), 2);
+        assert_eq!(order.filled_qty, 20.0);
+    }
+}
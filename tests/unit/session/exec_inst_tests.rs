@@ -0,0 +1,74 @@
+// Unit tests for Session post-only, reduce-only, hidden and self-trade prevention orders
+
+use super::super::support::{
+    create_session, field, session_with_config, start_raw_mock_server, test_config,
+};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::model::SelfTradePrevention;
+use deribit_fix::model::request::NewOrderRequest;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_order_flags_are_sent() {
+        let (addr, mut outgoing) = start_raw_mock_server(Vec::new()).await;
+        let mut session = create_session(addr).await;
+
+        let order = NewOrderRequest::limit_sell("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_post_only(true)
+            .with_reduce_only(true)
+            .hidden();
+        session.send_new_order(order).await.unwrap();
+
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(field(&request, "35").as_deref(), Some("D"));
+        assert_eq!(field(&request, "18").as_deref(), Some("6 E"));
+        assert_eq!(field(&request, "1138").as_deref(), Some("0"));
+        assert_eq!(field(&request, "2964"), None);
+    }
+
+    #[tokio::test]
+    async fn test_self_trade_prevention_defaults_to_the_session() {
+        let (addr, mut outgoing) = start_raw_mock_server(Vec::new()).await;
+        let config = test_config(addr).with_self_trade_prevention(SelfTradePrevention::CancelMaker);
+        let mut session = session_with_config(config).await;
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0);
+        session.send_new_order(order.clone()).await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(field(&request, "2964").as_deref(), Some("2"));
+
+        // The order overrides the session default
+        let order = order.with_self_trade_prevention(SelfTradePrevention::CancelTaker);
+        session.send_new_order(order).await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(field(&request, "2964").as_deref(), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_flags_are_rejected_locally() {
+        let (addr, mut outgoing) = start_raw_mock_server(Vec::new()).await;
+        let mut session = create_session(addr).await;
+
+        let market = NewOrderRequest::market_buy("BTC-PERPETUAL".to_string(), 10.0);
+        for order in [
+            market.clone().with_post_only(true),
+            market.with_max_show(5.0),
+            NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+                .with_max_show(20.0),
+        ] {
+            let error = session.send_new_order(order).await.unwrap_err();
+            assert!(matches!(error, DeribitFixError::MessageConstruction(_)));
+        }
+
+        // Nothing reached the server
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), outgoing.recv())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,76 @@
+// Unit tests for exporting the market data of a Session
+
+use super::super::support::{HEADER, frame, start_paced_mock_server};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::recorder::{EXPORT_COLUMNS, ExportFormat, MarketDataExporter};
+use deribit_fix::session::Session;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_market_data_is_exported_to_rotating_csv_files() {
+        // A heartbeat, a snapshot and a trade, each in a read of its own
+        let addr = start_paced_mock_server(vec![
+            frame(&format!("35=0\x0134=1\x01{HEADER}")),
+            frame(&format!(
+                "35=W\x0134=2\x01{HEADER}55=BTC-PERPETUAL\x01268=2\x01269=0\x01270=49990\x01271=5\x01269=1\x01270=50000\x01271=2\x01"
+            )),
+            frame(&format!(
+                "35=X\x0134=3\x01{HEADER}55=BTC-PERPETUAL\x01268=1\x01279=0\x01269=2\x01270=49995\x01271=1\x0154=2\x01100009=T1\x01"
+            )),
+        ])
+        .await;
+        let directory = std::env::temp_dir().join(format!("deribit_fix_export_{}", addr.port()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000));
+
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        let export = MarketDataExporter::new(&directory, "md", ExportFormat::Csv)
+            .with_max_rows(2)
+            .spawn(session.subscribe_messages(), 16)
+            .unwrap();
+
+        let mut received = 0;
+        for _ in 0..100 {
+            if session
+                .receive_and_process_message()
+                .await
+                .unwrap()
+                .is_some()
+            {
+                received += 1;
+                if received == 3 {
+                    break;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(received, 3);
+
+        let files = export.finish().await.unwrap();
+        assert_eq!(files.len(), 2);
+        let first = std::fs::read_to_string(&files[0]).unwrap();
+        let lines: Vec<&str> = first.lines().collect();
+        assert_eq!(lines[0], EXPORT_COLUMNS.join(","));
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains(",BTC-PERPETUAL,book,bid,snapshot,49990,5,"));
+        assert!(lines[2].contains(",BTC-PERPETUAL,book,offer,snapshot,50000,2,"));
+
+        let second = std::fs::read_to_string(&files[1]).unwrap();
+        let trade = second.lines().nth(1).unwrap();
+        assert!(trade.contains(",BTC-PERPETUAL,trade,trade,new,49995,1,"));
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
@@ -259,9 +259,9 @@ mod tests {
         {
             // Test methods that don't require actual network operations
 
-            // Test cancel_order (doesn't actually send, just prepares message)
+            // Cancelling an unknown order waits for the report and fails
             let cancel_result = session.cancel_order("ORDER_123".to_string()).await;
-            assert!(cancel_result.is_ok());
+            assert!(cancel_result.is_err());
 
             // Test calculate_app_signature (private method tested indirectly)
             let auth_result = session.generate_auth_data("test_secret");
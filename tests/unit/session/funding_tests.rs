@@ -0,0 +1,118 @@
+// Unit tests for Session funding rate polling
+
+use super::super::support::{HEADER, frame};
+use chrono::TimeDelta;
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::session::{ManualClock, Session, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, mpsc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server answering each Market Data Request with a snapshot
+    /// of rising funding rates, forwarding every request it reads
+    async fn start_funding_server() -> (std::net::SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut seq = 0;
+            let mut buf = [0u8; 8192];
+            while let Ok(Ok(n)) =
+                tokio::time::timeout(Duration::from_secs(2), socket.read(&mut buf)).await
+            {
+                if n == 0 {
+                    break;
+                }
+                let received = String::from_utf8_lossy(&buf[..n]).to_string();
+                for part in received.split("8=FIX.4.4").filter(|p| !p.is_empty()) {
+                    let Ok(request) = FixMessage::parse(&format!("8=FIX.4.4{part}")) else {
+                        continue;
+                    };
+                    seq += 1;
+                    let snapshot = frame(&format!(
+                        "35=W\x0134={seq}\x01{HEADER}262={}\x0155={}\x01\
+                         100092=0.000{seq}\x01100093=0.00{seq}\x01\
+                         268=2\x01269=0\x01270=100\x01271=1\x01269=1\x01270=101\x01271=1\x01",
+                        request.get_field(262).unwrap(),
+                        request.get_field(55).unwrap()
+                    ));
+                    let _ = socket.write_all(snapshot.as_bytes()).await;
+                    let _ = tx.send(request);
+                }
+            }
+        });
+
+        (addr, rx)
+    }
+
+    #[tokio::test]
+    async fn test_tracked_perpetuals_are_polled_every_interval() {
+        let (addr, mut server) = start_funding_server().await;
+        let clock = Arc::new(ManualClock::default());
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+            .with_funding_poll_interval(Duration::from_secs(60))
+            .with_clock(clock.clone());
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        assert!(session.track_funding("BTC-PERPETUAL"));
+
+        // Nothing is polled before logging on
+        assert_eq!(session.poll_funding().await.unwrap(), 0);
+        session.set_state(SessionState::LoggedOn);
+
+        assert_eq!(session.poll_funding().await.unwrap(), 1);
+        let request = server.recv().await.unwrap();
+        assert_eq!(request.get_field(35).map(String::as_str), Some("V"));
+        assert_eq!(request.get_field(263).map(String::as_str), Some("0"));
+        assert_eq!(request.get_field(264).map(String::as_str), Some("1"));
+        session.receive_and_process_message().await.unwrap();
+
+        // Not due again until the interval has passed
+        assert_eq!(session.poll_funding().await.unwrap(), 0);
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(session.poll_funding().await.unwrap(), 1);
+        server.recv().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+
+        let history = session.funding_history("BTC-PERPETUAL", TimeDelta::hours(1));
+        let rates: Vec<_> = history
+            .iter()
+            .map(|sample| (sample.current_funding, sample.funding_8h))
+            .collect();
+        assert_eq!(
+            rates,
+            [(Some(0.0001), Some(0.001)), (Some(0.0002), Some(0.002))]
+        );
+        // The polls do not build an order book
+        assert!(session.order_book("BTC-PERPETUAL").is_none());
+
+        assert!(session.untrack_funding("BTC-PERPETUAL"));
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(session.poll_funding().await.unwrap(), 0);
+
+        // The history window follows the session clock
+        clock.advance(Duration::from_secs(2 * 3600));
+        assert!(
+            session
+                .funding_history("BTC-PERPETUAL", TimeDelta::hours(1))
+                .is_empty()
+        );
+    }
+}
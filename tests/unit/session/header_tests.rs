@@ -0,0 +1,92 @@
+// Unit tests for Session optional header fields
+
+use super::super::support::start_mock_server;
+use chrono::{TimeZone, Utc};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::message::{TimestampPrecision, parse_utc_timestamp};
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::request::NewOrderRequest;
+use deribit_fix::session::{ManualClock, Session, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_configured_header_ids_are_sent_in_header_order() {
+        let (addr, mut server) = start_mock_server(Vec::new()).await;
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_on_behalf_of_comp_id("FUND".to_string())
+            .with_sub_ids(Some("DESK".to_string()), Some("ROUTER".to_string()))
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000));
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        session.set_state(SessionState::LoggedOn);
+
+        session.send_heartbeat(None).await.unwrap();
+        let heartbeat = tokio::time::timeout(Duration::from_secs(2), server.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(heartbeat.get_field(115).map(String::as_str), Some("FUND"));
+        assert_eq!(heartbeat.get_field(50).map(String::as_str), Some("DESK"));
+        assert_eq!(heartbeat.get_field(57).map(String::as_str), Some("ROUTER"));
+
+        // MsgType follows BodyLength and the header precedes the trailer
+        let tags: Vec<u32> = heartbeat.fields.iter().map(|(tag, _)| *tag).collect();
+        assert_eq!(tags, [8, 9, 35, 49, 56, 115, 34, 50, 57, 52, 10]);
+    }
+
+    #[tokio::test]
+    async fn test_timestamps_are_sent_with_configured_precision() {
+        let (addr, mut server) = start_mock_server(Vec::new()).await;
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_timestamp_precision(TimestampPrecision::Micros)
+            .with_clock(Arc::new(ManualClock::new(now)))
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000));
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        session.set_state(SessionState::LoggedOn);
+        let fraction = |message: &FixMessage, tag: u32| {
+            let value = message.get_field(tag).unwrap();
+            value.rsplit_once('.').unwrap().1.len()
+        };
+
+        session.send_heartbeat(None).await.unwrap();
+        session
+            .send_new_order(NewOrderRequest::limit_buy(
+                "BTC-PERPETUAL".to_string(),
+                10.0,
+                50000.0,
+            ))
+            .await
+            .unwrap();
+        let heartbeat = server.recv().await.unwrap();
+        assert_eq!(fraction(&heartbeat, 52), 6);
+        let order = server.recv().await.unwrap();
+        assert_eq!(fraction(&order, 52), 6);
+        assert_eq!(fraction(&order, 60), 6);
+        // Both timestamps come from the session clock
+        assert_eq!(
+            parse_utc_timestamp(order.get_field(52).unwrap()).unwrap(),
+            now
+        );
+        assert_eq!(
+            parse_utc_timestamp(order.get_field(60).unwrap()).unwrap(),
+            now
+        );
+    }
+}
@@ -0,0 +1,145 @@
+// Unit tests for Session Test Request round trips and connection health
+
+use super::super::support::{HEADER, frame, session_with_config, test_config};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::types::MsgType;
+use deribit_fix::session::{ConnectionHealth, ManualClock, Session, SessionEvent};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server answering each Test Request after `delays[n]`
+    async fn start_heartbeat_server(delays: Vec<Duration>) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut buf = [0u8; 4096];
+            let mut delays = delays.into_iter();
+            let mut seq = 1;
+            while let Ok(n) = socket.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                let received = String::from_utf8_lossy(&buf[..n]).to_string();
+                for test_req_id in received
+                    .split('\x01')
+                    .filter_map(|field| field.strip_prefix("112="))
+                {
+                    let Some(delay) = delays.next() else {
+                        return;
+                    };
+                    tokio::time::sleep(delay).await;
+                    let heartbeat = frame(&format!(
+                        "35=0\x0134={seq}\x01{HEADER}112={test_req_id}\x01"
+                    ));
+                    seq += 1;
+                    let _ = socket.write_all(heartbeat.as_bytes()).await;
+                }
+            }
+        });
+
+        addr
+    }
+
+    async fn create_session(addr: std::net::SocketAddr) -> Session {
+        session_with_config(test_config(addr).with_max_ping_latency(Duration::from_millis(100)))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_ping_measures_round_trip_and_health() {
+        let addr = start_heartbeat_server(vec![
+            Duration::ZERO,
+            Duration::from_millis(250),
+            Duration::ZERO,
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        let round_trip = session.ping().await.unwrap();
+        assert!(round_trip < Duration::from_millis(100), "{round_trip:?}");
+        assert_eq!(session.connection_health(), ConnectionHealth::Healthy);
+        assert!(events.try_recv().is_err());
+
+        let round_trip = session.ping().await.unwrap();
+        assert!(round_trip >= Duration::from_millis(250), "{round_trip:?}");
+        assert_eq!(session.connection_health(), ConnectionHealth::Degraded);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::ConnectionHealth {
+                health: ConnectionHealth::Degraded,
+                ..
+            }
+        ));
+
+        session.ping().await.unwrap();
+        assert_eq!(session.connection_health(), ConnectionHealth::Healthy);
+        assert_eq!(
+            session.last_round_trip(),
+            match events.try_recv().unwrap() {
+                SessionEvent::ConnectionHealth {
+                    health: ConnectionHealth::Healthy,
+                    round_trip,
+                } => Some(round_trip),
+                other => panic!("unexpected event: {other:?}"),
+            }
+        );
+
+        // Connection statistics carry the round trip measured by the session
+        let stats = session.connection_stats().await.unwrap();
+        assert_eq!(stats.round_trip, session.last_round_trip());
+        assert_eq!(stats.sent(MsgType::TestRequest), 3);
+        assert_eq!(stats.received(MsgType::Heartbeat), 3);
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_test_request_degrades_connection() {
+        let addr = start_heartbeat_server(vec![]).await;
+        let mut session = create_session(addr).await;
+
+        assert!(!session.check_ping_latency());
+        session.send_test_request().await.unwrap();
+        assert!(session.check_ping_latency());
+        assert_eq!(session.connection_health(), ConnectionHealth::Healthy);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(session.check_ping_latency());
+        assert_eq!(session.connection_health(), ConnectionHealth::Degraded);
+    }
+
+    #[tokio::test]
+    async fn test_ping_latency_follows_the_session_clock() {
+        let addr = start_heartbeat_server(vec![]).await;
+        let clock = Arc::new(ManualClock::default());
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_max_ping_latency(Duration::from_secs(10))
+            .with_clock(clock.clone());
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+
+        session.send_test_request().await.unwrap();
+        clock.advance(Duration::from_secs(10));
+        assert!(session.check_ping_latency());
+        assert_eq!(session.connection_health(), ConnectionHealth::Healthy);
+
+        clock.advance(Duration::from_millis(1));
+        assert!(session.check_ping_latency());
+        assert_eq!(session.connection_health(), ConnectionHealth::Degraded);
+    }
+}
@@ -0,0 +1,69 @@
+// Unit tests for Session index value and settlement price streams
+
+use super::super::support::{HEADER, create_session, field_values, start_subscription_server};
+use deribit_fix::model::IndexUpdate;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_index_and_settlement_entries_are_streamed_apart_from_the_book() {
+        let (addr, mut outgoing) = start_subscription_server(vec![
+            format!(
+                "35=W\x0134=1\x01{HEADER}262={{md_req_id}}\x0155=BTC-USD\x01268=2\x01\
+                 269=3\x01270=50000.5\x01269=6\x01270=50010\x01"
+            ),
+            // Index entries interleaved with book entries of a book subscription
+            format!(
+                "35=X\x0134=2\x01{HEADER}55=BTC-USD\x01268=2\x01\
+                 279=0\x01269=0\x01270=49990\x01271=10\x01\
+                 279=0\x01269=3\x01270=50001\x01"
+            ),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+
+        let mut index = session.subscribe_index("BTC-USD").await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "V");
+        assert_eq!(request.get_field(263).unwrap(), "1");
+        assert_eq!(request.get_field(55).unwrap(), "BTC-USD");
+
+        for _ in 0..2 {
+            session.receive_and_process_message().await.unwrap();
+        }
+
+        let IndexUpdate::Index(tick) = index.try_recv().unwrap() else {
+            panic!("expected an index tick");
+        };
+        assert_eq!(tick.symbol, "BTC-USD");
+        assert_eq!(tick.price, 50_000.5);
+        let IndexUpdate::Settlement(estimate) = index.try_recv().unwrap() else {
+            panic!("expected a settlement estimate");
+        };
+        assert_eq!(estimate.price, 50_010.0);
+        assert_eq!(index.try_recv().unwrap().price(), 50_001.0);
+        assert!(index.try_recv().is_err());
+
+        // The index snapshot did not create an order book
+        assert!(session.order_book("BTC-USD").is_none());
+
+        session.unsubscribe_index("BTC-USD").await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(263).unwrap(), "2");
+        assert!(session.unsubscribe_index("BTC-USD").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_index_request_asks_for_index_and_settlement_entries() {
+        let (addr, mut outgoing) = start_subscription_server(Vec::new()).await;
+        let mut session = create_session(addr).await;
+
+        let _index = session.subscribe_index("BTC-USD").await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "V");
+        assert_eq!(request.get_field(267).unwrap(), "2");
+        assert_eq!(field_values(&request, 269), ["3", "6"]);
+    }
+}
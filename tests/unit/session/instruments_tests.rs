@@ -0,0 +1,196 @@
+// Unit tests for Session instrument requests with filters and fragmented responses
+
+use super::super::support::{
+    HEADER, create_session, frame, session_with_config, start_mock_server, test_config,
+};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::orders::{NewOrderSingle, OrderSide};
+use deribit_fix::message::security_list::{
+    SecurityListProgress, SecurityListRequest, SecurityStatus, SecurityType,
+};
+use deribit_fix::model::request::NewOrderRequest;
+use deribit_fix::session::{Session, SessionEvent};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_session_with(
+        addr: std::net::SocketAddr,
+        configure: impl FnOnce(DeribitFixConfig) -> DeribitFixConfig,
+    ) -> Session {
+        session_with_config(configure(test_config(addr))).await
+    }
+
+    fn security_list(seq: u32, req_id: &str, fragment: &str, entries: &str) -> String {
+        frame(&format!(
+            "35=y\x0134={seq}\x01{HEADER}320={req_id}\x01322=RESP\x01560=0\x01393=3\x01\
+             {fragment}{entries}"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_get_instruments_reassembles_fragments_and_filters() {
+        let option = |symbol: &str, status: u8| {
+            format!("55={symbol}\x01167=OPT\x0115=BTC\x01201=1\x01965={status}\x01")
+        };
+        let (addr, mut outgoing) = start_mock_server(vec![
+            // Response to another request
+            security_list(1, "OTHER", "893=Y\x01", &option("BTC-1JAN26-1-C", 1)),
+            security_list(
+                2,
+                "BTC-OPTIONS",
+                "893=N\x01146=2\x01",
+                &(option("BTC-27JUN25-90000-C", 1) + &option("BTC-27JUN25-95000-C", 4)),
+            ),
+            security_list(
+                3,
+                "BTC-OPTIONS",
+                "893=Y\x01146=1\x01",
+                &option("BTC-26SEP25-90000-C", 1),
+            ),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+
+        let request = SecurityListRequest::snapshot("BTC-OPTIONS".to_string())
+            .with_currency("BTC".to_string())
+            .with_security_type(SecurityType::Option)
+            .with_security_status(SecurityStatus::Active);
+        let instruments = session.get_instruments(request).await.unwrap();
+
+        let sent = outgoing.recv().await.unwrap();
+        assert_eq!(sent.get_field(35).unwrap(), "x");
+        assert_eq!(sent.get_field(320).unwrap(), "BTC-OPTIONS");
+        assert_eq!(sent.get_field(15).unwrap(), "BTC");
+        assert_eq!(sent.get_field(167).unwrap(), "OPT");
+
+        // The closed instrument is filtered out, both fragments are joined
+        let symbols: Vec<_> = instruments.iter().map(|i| i.symbol.as_str()).collect();
+        assert_eq!(symbols, ["BTC-27JUN25-90000-C", "BTC-26SEP25-90000-C"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_instruments_hands_out_fragments_until_one_is_missing() {
+        let future = |symbol: &str| format!("55={symbol}\x01167=FUT\x0115=BTC\x01");
+        let (addr, _outgoing) = start_mock_server(vec![security_list(
+            1,
+            "FUTURES",
+            "893=N\x01146=2\x01",
+            &(future("BTC-PERPETUAL") + &future("BTC-27JUN25")),
+        )])
+        .await;
+        let mut session = create_session_with(addr, |config| {
+            config.with_fragment_timeout(Duration::from_millis(200))
+        })
+        .await;
+        let mut events = session.subscribe_events();
+
+        let mut batches = Vec::new();
+        let result = session
+            .stream_instruments(
+                SecurityListRequest::snapshot("FUTURES".to_string()),
+                |securities, progress| batches.push((securities.len(), progress.clone())),
+            )
+            .await;
+
+        // The second fragment never arrives
+        assert!(matches!(result, Err(DeribitFixError::Timeout(_))));
+        let progress = SecurityListProgress {
+            security_req_id: "FUTURES".to_string(),
+            fragments: 1,
+            received: 2,
+            total: Some(3),
+            complete: false,
+        };
+        assert_eq!(batches, [(2, progress.clone())]);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SessionEvent::SecurityListProgress(event)) if event == progress
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_orders_sized_in_contracts_of_received_instruments() {
+        let (addr, mut outgoing) = start_mock_server(vec![security_list(
+            1,
+            "FUTURES",
+            "893=Y\x01146=2\x01",
+            "55=BTC-PERPETUAL\x01167=FUT\x0115=BTC\x01120=BTC\x011524=USD\x01231=10\x01\
+             55=BTC_USDC-PERPETUAL\x01167=FUT\x0115=BTC\x01120=USDC\x011524=USDC\x01231=0.001\x01",
+        )])
+        .await;
+        let mut session = create_session(addr).await;
+        session.receive_and_process_message().await.unwrap();
+
+        let instruments = session.instruments();
+        assert_eq!(instruments.len(), 2);
+        assert_eq!(instruments.amount_currency("BTC-PERPETUAL"), Some("USD"));
+        assert_eq!(
+            instruments.amount_currency("BTC_USDC-PERPETUAL"),
+            Some("BTC")
+        );
+        assert_eq!(
+            instruments.to_contracts("BTC-PERPETUAL", 100.0).unwrap(),
+            10.0
+        );
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 0.0, 50000.0)
+            .with_contracts(5.0, instruments)
+            .unwrap();
+        session.send_new_order(order).await.unwrap();
+        let sent = outgoing.recv().await.unwrap();
+        assert_eq!(sent.get_field(35).unwrap(), "D");
+        assert_eq!(sent.get_field(38).unwrap(), "50");
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_instruments_do_not_fail_the_security_list() {
+        let (addr, _outgoing) = start_mock_server(vec![security_list(
+            1,
+            "FUTURES",
+            "893=Y\x01146=2\x01",
+            "55=BTC-10OCT26-60000-C\x01167=OPT\x01202=abc\x01\
+             55=BTC-PERPETUAL\x01167=FUT\x0115=BTC\x01120=BTC\x011524=USD\x01231=10\x01",
+        )])
+        .await;
+        let mut session = create_session(addr).await;
+        let incoming_seq_num = session.incoming_seq_num();
+        session.receive_and_process_message().await.unwrap();
+
+        assert_eq!(session.incoming_seq_num(), incoming_seq_num + 1);
+        let instruments = session.instruments();
+        assert_eq!(instruments.len(), 1);
+        assert!(instruments.get("BTC-PERPETUAL").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_typed_orders_are_sent_with_instrument_precision() {
+        let (addr, mut outgoing) = start_mock_server(vec![security_list(
+            1,
+            "FUTURES",
+            "893=Y\x01146=1\x01",
+            "55=BTC-PERPETUAL\x01167=FUT\x0115=BTC\x01969=0.5\x01562=10\x01",
+        )])
+        .await;
+        let mut session = create_session(addr).await;
+        session.receive_and_process_message().await.unwrap();
+
+        // OrderQty (38) and Price (44) are written before Symbol (55)
+        let order = NewOrderSingle::limit(
+            "ORDER_1".to_string(),
+            OrderSide::Buy,
+            10.000000000000002,
+            0.1 + 65000.2,
+            "BTC-PERPETUAL".to_string(),
+        );
+        session.send(&order).await.unwrap();
+
+        let sent = outgoing.recv().await.unwrap();
+        assert_eq!(sent.get_field(35).unwrap(), "D");
+        assert_eq!(sent.get_field(38).unwrap(), "10");
+        assert_eq!(sent.get_field(44).unwrap(), "65000.3");
+    }
+}
@@ -0,0 +1,55 @@
+// Unit tests for Session Execution Report routing by order label
+
+use super::super::support::{HEADER, create_session, frame, start_mock_server};
+use deribit_fix::message::OrderStatus;
+use deribit_fix::model::NewOrderRequest;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution_report(seq: u32, cl_ord_id: &str, ord_status: char, extra: &str) -> String {
+        frame(&format!(
+            "35=8\x0134={seq}\x01{HEADER}11={cl_ord_id}\x0137=ID-{cl_ord_id}\x01150=0\x01\
+             39={ord_status}\x0155=BTC-PERPETUAL\x0154=1\x01{extra}"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_execution_reports_are_routed_by_label() {
+        let (addr, mut outgoing) = start_mock_server(vec![
+            // Deribit does not echo the label, the ClOrdID routes it
+            execution_report(1, "MM-1", '0', ""),
+            execution_report(2, "ARB-1", '0', "100010=arb\x01"),
+            execution_report(3, "OTHER-1", '0', ""),
+            execution_report(4, "MM-1", '2', "14=10\x01"),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut mm = session.executions_for_label("mm-btc");
+        let mut arb = session.executions_for_label("arb");
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_client_order_id("MM-1".to_string())
+            .with_label("mm-btc".to_string());
+        session.send_new_order(order).await.unwrap();
+        let sent = outgoing.recv().await.unwrap();
+        assert_eq!(sent.get_field(100010).unwrap(), "mm-btc");
+
+        for _ in 0..4 {
+            // Every report is still returned to the caller
+            let message = session.receive_and_process_message().await.unwrap();
+            assert_eq!(message.unwrap().get_field(35).unwrap(), "8");
+        }
+
+        let new = mm.try_recv().unwrap().unwrap();
+        assert_eq!(new.cl_ord_id, "MM-1");
+        assert_eq!(new.ord_status, OrderStatus::New);
+        let filled = mm.try_recv().unwrap().unwrap();
+        assert_eq!(filled.ord_status, OrderStatus::Filled);
+        assert!(mm.try_recv().unwrap().is_none());
+
+        assert_eq!(arb.try_recv().unwrap().unwrap().cl_ord_id, "ARB-1");
+        assert!(arb.try_recv().unwrap().is_none());
+    }
+}
@@ -0,0 +1,145 @@
+// Unit tests for Session liveness checks with FIX-level heartbeats
+
+use super::super::support::{HEADER, frame};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::types::MsgType;
+use deribit_fix::session::{LivenessAction, ManualClock, Session, SessionEvent, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, mpsc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server that answers the first Logon and then stays
+    /// silent, forwarding every message read on each connection
+    async fn start_silent_server() -> (std::net::SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut answered = false;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 8192];
+                while let Ok(Ok(n)) =
+                    tokio::time::timeout(Duration::from_secs(2), socket.read(&mut buf)).await
+                {
+                    if n == 0 {
+                        break;
+                    }
+                    let received = String::from_utf8_lossy(&buf[..n]).to_string();
+                    for part in received.split("8=FIX.4.4").filter(|p| !p.is_empty()) {
+                        if let Ok(message) = FixMessage::parse(&format!("8=FIX.4.4{part}")) {
+                            let _ = tx.send(message);
+                        }
+                    }
+                    if !answered {
+                        answered = true;
+                        let logon = frame(&format!("35=A\x0134=1\x01{HEADER}98=0\x01108=30\x01"));
+                        let _ = socket.write_all(logon.as_bytes()).await;
+                    }
+                }
+            }
+        });
+
+        (addr, rx)
+    }
+
+    async fn next_message(rx: &mut mpsc::UnboundedReceiver<FixMessage>) -> FixMessage {
+        tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_silent_counterparty_is_probed_then_reconnected() {
+        let (addr, mut server) = start_silent_server().await;
+        let clock = Arc::new(ManualClock::default());
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+            .with_heartbeat_interval(30)
+            .with_reconnection(1, Duration::from_millis(10))
+            .with_liveness_checks(2, Some(Duration::from_secs(5)))
+            .with_clock(clock.clone());
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+
+        // Not checked before logging on
+        clock.advance(Duration::from_secs(120));
+        assert_eq!(
+            session.check_liveness().await.unwrap(),
+            LivenessAction::Alive
+        );
+
+        session.logon().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.get_state(), SessionState::LoggedOn);
+        assert_eq!(
+            next_message(&mut server).await.msg_type(),
+            Some(MsgType::Logon)
+        );
+        let mut events = session.subscribe_events();
+
+        // One silent heartbeat interval is tolerated
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(
+            session.check_liveness().await.unwrap(),
+            LivenessAction::Alive
+        );
+
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(
+            session.check_liveness().await.unwrap(),
+            LivenessAction::Probe
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LivenessProbe {
+                silent_for: Duration::from_secs(60)
+            }
+        );
+        assert_eq!(
+            next_message(&mut server).await.msg_type(),
+            Some(MsgType::TestRequest)
+        );
+
+        // The probe is given its timeout to be answered
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(
+            session.check_liveness().await.unwrap(),
+            LivenessAction::Alive
+        );
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(
+            session.check_liveness().await.unwrap(),
+            LivenessAction::Reconnect
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LivenessLost {
+                silent_for: Duration::from_secs(65)
+            }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::RelogonAttempt { attempt: 1 }
+        );
+        assert_eq!(session.get_state(), SessionState::LogonSent);
+        assert_eq!(
+            next_message(&mut server).await.msg_type(),
+            Some(MsgType::Logon)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// Unit tests for the two-way logout handshake
+
+use super::super::support::{HEADER, frame, start_mock_server_with, test_config};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::session::{Session, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, mpsc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server answering the first message it reads with `reply`,
+    /// if any, and forwarding every message it reads
+    async fn start_logout_server(
+        reply: Option<String>,
+    ) -> (std::net::SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+        let mut reply = reply;
+        start_mock_server_with(Vec::new(), move |_| reply.take().into_iter().collect()).await
+    }
+
+    async fn create_session(addr: std::net::SocketAddr) -> (Session, Arc<Mutex<Connection>>) {
+        create_session_with(test_config(addr)).await
+    }
+
+    async fn create_session_with(config: DeribitFixConfig) -> (Session, Arc<Mutex<Connection>>) {
+        let config = config.with_logout_timeout(Duration::from_millis(200));
+        let connection = Arc::new(Mutex::new(Connection::new(&config).await.unwrap()));
+        let mut session = Session::new(&config, connection.clone()).unwrap();
+        session.set_state(SessionState::LoggedOn);
+        (session, connection)
+    }
+
+    #[tokio::test]
+    async fn test_acknowledged_logout() {
+        let (addr, mut sent) = start_logout_server(Some(frame(&format!(
+            "35=5\x0134=1\x01{HEADER}58=Logout acknowledged\x01"
+        ))))
+        .await;
+        let (mut session, connection) = create_session(addr).await;
+
+        let acknowledged = session
+            .logout_and_wait(Some("End of day".to_string()))
+            .await
+            .unwrap();
+        assert!(acknowledged);
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+
+        let logout = sent.recv().await.unwrap();
+        assert_eq!(logout.get_field(35).unwrap(), "5");
+        assert_eq!(logout.get_field(58).unwrap(), "End of day");
+        // Closing the connection is left to the caller
+        assert!(connection.lock().await.is_connected());
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_logout_closes_the_connection() {
+        let (addr, mut sent) = start_logout_server(None).await;
+        let (mut session, connection) = create_session(addr).await;
+
+        let acknowledged = session.logout_and_wait(None).await.unwrap();
+        assert!(!acknowledged);
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+        assert!(!connection.lock().await.is_connected());
+        assert_eq!(
+            sent.recv().await.unwrap().get_field(58).unwrap(),
+            "Normal logout"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_logout_carries_configured_dont_cancel_on_disconnect() {
+        let (addr, mut sent) = start_logout_server(None).await;
+        let config = test_config(addr)
+            .with_cancel_on_disconnect(true)
+            .with_dont_cancel_on_disconnect(true);
+        let (mut session, _connection) = create_session_with(config).await;
+
+        session.logout_and_wait(None).await.unwrap();
+        assert_eq!(sent.recv().await.unwrap().get_field(9003).unwrap(), "Y");
+    }
+}
@@ -0,0 +1,367 @@
+// Unit tests for Session logout reasons and re-logon after logout
+
+use super::super::support::{HEADER, frame, session_with_config, test_config};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::LogoutReason;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::session::{ManualClock, Session, SessionEvent, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, mpsc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server that sends `logout` on the first connection and
+    /// forwards every message read on later connections
+    async fn start_relogon_server(
+        logout: String,
+    ) -> (std::net::SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let _ = socket.write_all(logout.as_bytes()).await;
+            }
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 8192];
+                while let Ok(Ok(n)) =
+                    tokio::time::timeout(Duration::from_secs(2), socket.read(&mut buf)).await
+                {
+                    if n == 0 {
+                        break;
+                    }
+                    let received = String::from_utf8_lossy(&buf[..n]).to_string();
+                    for part in received.split("8=FIX.4.4").filter(|p| !p.is_empty()) {
+                        if let Ok(message) = FixMessage::parse(&format!("8=FIX.4.4{part}")) {
+                            let _ = tx.send(message);
+                        }
+                    }
+                }
+            }
+        });
+
+        (addr, rx)
+    }
+
+    /// Start a mock server that sends `logout` on the first connection, then
+    /// answers the first message read on each later connection with the next
+    /// of `replies`, forwarding what it reads
+    async fn start_reply_server(
+        logout: String,
+        replies: Vec<String>,
+    ) -> (std::net::SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let _ = socket.write_all(logout.as_bytes()).await;
+            }
+            for reply in replies {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = [0u8; 8192];
+                if let Ok(Ok(n)) =
+                    tokio::time::timeout(Duration::from_secs(2), socket.read(&mut buf)).await
+                {
+                    let received = String::from_utf8_lossy(&buf[..n]).to_string();
+                    if let Ok(message) = FixMessage::parse(&received) {
+                        let _ = tx.send(message);
+                    }
+                    let _ = socket.write_all(reply.as_bytes()).await;
+                }
+            }
+        });
+
+        (addr, rx)
+    }
+
+    async fn create_session(addr: std::net::SocketAddr) -> Session {
+        let config = test_config(addr)
+            .with_reconnection(1, Duration::from_millis(10))
+            .with_relogon_after_logout(true);
+        session_with_config(config).await
+    }
+
+    #[tokio::test]
+    async fn test_non_fatal_logout_triggers_relogon() {
+        let (addr, mut outgoing) = start_relogon_server(frame(&format!(
+            "35=5\x0134=1\x01{HEADER}58=Heartbeat timeout\x01"
+        )))
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut {
+                reason: LogoutReason::HeartbeatMissed,
+                text: Some("Heartbeat timeout".to_string()),
+                relogon: true,
+            }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::RelogonAttempt { attempt: 1 }
+        );
+        assert_eq!(session.get_state(), SessionState::LogonSent);
+
+        let logon = tokio::time::timeout(Duration::from_secs(2), outgoing.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(logon.get_field(35).unwrap(), "A");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_credentials_logout_is_fatal() {
+        let (addr, _outgoing) = start_relogon_server(frame(&format!(
+            "35=5\x0134=1\x01{HEADER}1409=5\x0158=invalid credentials\x01"
+        )))
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut {
+                reason: LogoutReason::CredentialsInvalid,
+                text: Some("invalid credentials".to_string()),
+                relogon: false,
+            }
+        );
+        assert!(events.try_recv().is_err());
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_logon_is_retried_with_backoff() {
+        let busy = |seq: u32| {
+            frame(&format!(
+                "35=5\x0134={seq}\x01{HEADER}58=Too many requests\x01"
+            ))
+        };
+        let (addr, mut outgoing) = start_reply_server(busy(1), vec![busy(2), busy(3)]).await;
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+            .with_reconnection(1, Duration::from_millis(10))
+            .with_relogon_after_logout(false)
+            .with_logon_retry(2, Duration::from_millis(10), Duration::from_millis(15));
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        let mut events = session.subscribe_events();
+        session.logon().await.unwrap();
+
+        for (attempt, delay) in [(1, 10), (2, 15)] {
+            session.receive_and_process_message().await.unwrap();
+            assert!(matches!(
+                events.try_recv().unwrap(),
+                SessionEvent::LoggedOut { relogon: true, .. }
+            ));
+            assert_eq!(
+                events.try_recv().unwrap(),
+                SessionEvent::LogonRetryScheduled {
+                    attempt,
+                    delay: Duration::from_millis(delay),
+                }
+            );
+            assert_eq!(
+                events.try_recv().unwrap(),
+                SessionEvent::RelogonAttempt { attempt: 1 }
+            );
+            let logon = outgoing.recv().await.unwrap();
+            assert_eq!(logon.get_field(35).unwrap(), "A");
+        }
+
+        session.receive_and_process_message().await.unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut { relogon: false, .. }
+        ));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LogonRetriesExhausted { attempts: 2 }
+        );
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_credentials_open_the_logon_circuit() {
+        let (addr, _outgoing) = start_relogon_server(frame(&format!(
+            "35=5\x0134=1\x01{HEADER}1409=5\x0158=invalid credentials\x01"
+        )))
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+        session.logon().await.unwrap();
+
+        session.receive_and_process_message().await.unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut {
+                reason: LogoutReason::CredentialsInvalid,
+                relogon: false,
+                ..
+            }
+        ));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LogonCircuitOpen {
+                text: Some("invalid credentials".to_string()),
+            }
+        );
+        assert!(session.is_logon_circuit_open());
+
+        // No further logon is sent with the rejected credentials
+        assert!(matches!(
+            session.logon().await,
+            Err(DeribitFixError::Authentication(_))
+        ));
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+
+        session.update_credentials("test_user".to_string(), "new_password".to_string());
+        assert!(!session.is_logon_circuit_open());
+    }
+
+    #[tokio::test]
+    async fn test_logout_response_is_not_followed_by_relogon() {
+        let (addr, _outgoing) = start_relogon_server(frame(&format!(
+            "35=5\x0134=1\x01{HEADER}58=Heartbeat timeout\x01"
+        )))
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+        session.set_state(SessionState::LoggedOn);
+
+        session.logout().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut {
+                reason: LogoutReason::Requested,
+                text: Some("Heartbeat timeout".to_string()),
+                relogon: false,
+            }
+        );
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_logout_retries_logon_at_the_maintenance_cadence() {
+        let maintenance = |seq: u32| {
+            frame(&format!(
+                "35=5\x0134={seq}\x01{HEADER}58=Server going down for maintenance\x01"
+            ))
+        };
+        let (addr, mut outgoing) = start_reply_server(
+            maintenance(1),
+            vec![
+                maintenance(1),
+                frame(&format!("35=A\x0134=1\x01{HEADER}98=0\x01108=30\x01")),
+            ],
+        )
+        .await;
+        let clock = Arc::new(ManualClock::default());
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+            .with_reset_seq_num_on_logon(true)
+            .with_maintenance_retry_interval(Duration::from_secs(60))
+            .with_clock(clock.clone());
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::MarketState(_)
+        ));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut {
+                reason: LogoutReason::Maintenance,
+                text: Some("Server going down for maintenance".to_string()),
+                relogon: true,
+            }
+        );
+        assert_eq!(session.get_state(), SessionState::Maintenance);
+
+        // Nothing is attempted before the retry interval has passed
+        clock.advance(Duration::from_secs(59));
+        let received = session
+            .receive_and_process_message_within(Duration::ZERO)
+            .await
+            .unwrap();
+        assert!(received.is_none());
+        assert!(events.try_recv().is_err());
+        assert_eq!(session.get_state(), SessionState::Maintenance);
+
+        // Still in maintenance on the first attempt
+        clock.advance(Duration::from_secs(1));
+        session
+            .receive_and_process_message_within(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::RelogonAttempt { attempt: 1 }
+        );
+        let logon = outgoing.recv().await.unwrap();
+        assert_eq!(logon.get_field(35).unwrap(), "A");
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::SequenceNumbersReset
+        );
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut { relogon: true, .. }
+        ));
+        assert_eq!(session.get_state(), SessionState::Maintenance);
+
+        // Back once the exchange accepts the second attempt
+        clock.advance(Duration::from_secs(60));
+        session
+            .receive_and_process_message_within(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::RelogonAttempt { attempt: 2 }
+        );
+        assert_eq!(outgoing.recv().await.unwrap().get_field(35).unwrap(), "A");
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.get_state(), SessionState::LoggedOn);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::SequenceNumbersReset
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::ServiceResumed {
+                attempts: 2,
+                downtime: Duration::from_secs(120),
+            }
+        );
+    }
+}
@@ -0,0 +1,142 @@
+// Unit tests for Session market state tracking, halts and maintenance windows
+
+use super::super::support::{
+    HEADER, frame, session_with_config, start_raw_mock_server, test_config,
+};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::LogoutReason;
+use deribit_fix::model::NewOrderRequest;
+use deribit_fix::model::market_state::{InstrumentState, MarketStateEvent};
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::session::{Session, SessionEvent, SessionState};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_session(addr: std::net::SocketAddr, queue_orders: bool) -> Session {
+        session_with_config(test_config(addr).with_queue_orders_during_halt(queue_orders)).await
+    }
+
+    fn security_status(seq: u32, status: i32) -> String {
+        frame(&format!(
+            "35=f\x0134={seq}\x01{HEADER}55=BTC-PERPETUAL\x01326={status}\x01"
+        ))
+    }
+
+    fn order() -> NewOrderRequest {
+        NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+    }
+
+    #[tokio::test]
+    async fn test_halted_instrument_rejects_orders_locally() {
+        let (addr, _outgoing) = start_raw_mock_server(vec![security_status(1, 8)]).await;
+        let mut session = create_session(addr, false).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::MarketState(MarketStateEvent::TradingStateChanged {
+                symbol: "BTC-PERPETUAL".to_string(),
+                previous: None,
+                current: InstrumentState::Halted,
+            })
+        );
+        assert!(matches!(
+            session.send_new_order(order()).await,
+            Err(DeribitFixError::InstrumentHalted {
+                state: InstrumentState::Halted,
+                ..
+            })
+        ));
+        assert_eq!(session.outgoing_seq_num(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_queued_order_released_when_instrument_reopens() {
+        let (addr, mut outgoing) =
+            start_raw_mock_server(vec![security_status(1, 8), security_status(2, 7)]).await;
+        let mut session = create_session(addr, true).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        let cl_ord_id = session.send_new_order(order()).await.unwrap();
+        assert_eq!(session.market_state().queued_orders(), 1);
+        assert_eq!(session.outgoing_seq_num(), 1);
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.market_state().queued_orders(), 0);
+
+        let received: Vec<_> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(
+            received[1],
+            SessionEvent::MarketState(MarketStateEvent::OrderQueued {
+                symbol: "BTC-PERPETUAL".to_string(),
+                cl_ord_id: cl_ord_id.clone(),
+            })
+        );
+        assert_eq!(
+            received[3],
+            SessionEvent::MarketState(MarketStateEvent::OrderReleased {
+                symbol: "BTC-PERPETUAL".to_string(),
+                cl_ord_id: cl_ord_id.clone(),
+            })
+        );
+
+        let raw = tokio::time::timeout(Duration::from_secs(2), outgoing.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let sent = FixMessage::parse(&raw).unwrap();
+        assert_eq!(sent.get_field(35).unwrap(), "D");
+        assert_eq!(sent.get_field(11).unwrap(), &cl_ord_id);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_logout_pauses_until_logon() {
+        let (addr, _outgoing) = start_raw_mock_server(vec![
+            frame(&format!(
+                "35=5\x0134=1\x01{HEADER}58=Server going down for maintenance\x01"
+            )),
+            frame(&format!("35=A\x0134=2\x01{HEADER}98=0\x01108=30\x01")),
+        ])
+        .await;
+        let mut session = create_session(addr, false).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        assert!(session.market_state().in_maintenance());
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::MarketState(MarketStateEvent::MaintenanceStarted {
+                text: "Server going down for maintenance".to_string(),
+            })
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::LoggedOut {
+                reason: LogoutReason::Maintenance,
+                text: Some("Server going down for maintenance".to_string()),
+                relogon: true,
+            }
+        );
+        assert_eq!(session.get_state(), SessionState::Maintenance);
+        assert!(session.send_new_order(order()).await.is_err());
+
+        // Logged on again once the maintenance is over
+        session.set_state(SessionState::LogonSent);
+        session.receive_and_process_message().await.unwrap();
+        assert!(!session.market_state().in_maintenance());
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::ServiceResumed { attempts: 0, .. }
+        ));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::MarketState(MarketStateEvent::MaintenanceEnded)
+        );
+    }
+}
@@ -0,0 +1,34 @@
+// Unit tests for Session market data statistics
+
+use super::super::support::{HEADER, create_session, frame, start_writing_mock_server};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_market_stats_follow_market_data() {
+        let addr = start_writing_mock_server(vec![
+            frame(&format!(
+                "35=W\x0134=1\x01{HEADER}55=BTC-PERPETUAL\x01100087=1250.5\x01746=300\x01100092=0.0001\x01100093=0.0008\x01268=1\x01269=0\x01270=100\x01271=1\x01"
+            )),
+            frame(&format!(
+                "35=X\x0134=2\x01{HEADER}55=BTC-PERPETUAL\x01268=2\x01279=0\x01269=2\x01270=100\x01271=1\x011003=T1\x01279=0\x01269=2\x01270=106\x01271=2\x011003=T2\x01"
+            )),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        assert!(session.market_stats("BTC-PERPETUAL").is_none());
+
+        for _ in 0..2 {
+            session.receive_and_process_message().await.unwrap();
+        }
+
+        let stats = session.market_stats("BTC-PERPETUAL").unwrap();
+        assert_eq!(stats.volume_24h, Some(1250.5));
+        assert_eq!(stats.open_interest(), Some(300.0));
+        assert_eq!(stats.last_funding().unwrap().funding_8h, Some(0.0008));
+        assert_eq!(stats.rolling_volume(), 3.0);
+        assert_eq!(stats.vwap(), Some(104.0));
+    }
+}
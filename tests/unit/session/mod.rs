@@ -1,4 +1,44 @@
 // Unit tests for session module
 
+mod account_tests;
 mod auth_tests;
+mod book_snapshot_tests;
+mod cancel_tests;
+mod capabilities_tests;
+mod clock_sync_tests;
+mod combo_tests;
+mod duplicate_detection_tests;
+mod exec_inst_tests;
+mod export_tests;
 mod fix_session_tests;
+mod funding_tests;
+mod header_tests;
+mod health_tests;
+mod index_stream_tests;
+mod instruments_tests;
+mod label_routing_tests;
+mod liveness_tests;
+mod logout_handshake_tests;
+mod logout_tests;
+mod market_state_tests;
+mod market_stats_tests;
+mod multi_subscription_tests;
+mod option_ticker_tests;
+mod order_audit_tests;
+mod order_book_recovery_tests;
+mod order_group_tests;
+mod order_index_tests;
+mod paper_trading_tests;
+mod recording_tests;
+mod request_options_tests;
+mod risk_tests;
+mod sequence_check_tests;
+mod sequence_reset_tests;
+mod state_machine_tests;
+mod subscription_tests;
+mod symbol_map_tests;
+mod throttle_tests;
+mod trade_history_tests;
+mod trade_stream_tests;
+mod typed_send_tests;
+mod unknown_message_tests;
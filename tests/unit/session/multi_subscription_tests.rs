@@ -0,0 +1,215 @@
+// Unit tests for several market data subscriptions of one instrument
+
+use super::super::support::{HEADER, create_session, field, field_values, frame};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::MdEntryType;
+use deribit_fix::model::message::FixMessage;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server forwarding what it reads to a channel and writing
+    /// what it is sent on the other
+    async fn start_scripted_server() -> (
+        std::net::SocketAddr,
+        mpsc::UnboundedReceiver<String>,
+        mpsc::UnboundedSender<String>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel();
+        let (reply_tx, mut reply_rx) = mpsc::unbounded_channel::<String>();
+
+        tokio::spawn(async move {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut buf = [0u8; 4096];
+            loop {
+                tokio::select! {
+                    read = socket.read(&mut buf) => match read {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => {
+                            let _ = outgoing_tx.send(String::from_utf8_lossy(&buf[..n]).to_string());
+                        }
+                    },
+                    reply = reply_rx.recv() => match reply {
+                        Some(reply) => {
+                            let _ = socket.write_all(reply.as_bytes()).await;
+                        }
+                        None => break,
+                    },
+                }
+            }
+        });
+
+        (addr, outgoing_rx, reply_tx)
+    }
+
+    /// Read from the server until `count` messages have arrived
+    async fn recv_messages(outgoing: &mut mpsc::UnboundedReceiver<String>, count: usize) -> String {
+        let mut data = String::new();
+        while data.matches("35=V\x01").count() < count {
+            data.push_str(&outgoing.recv().await.unwrap());
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_book_and_trade_subscriptions_of_one_symbol() {
+        let (addr, mut outgoing, replies) = start_scripted_server().await;
+        let mut session = create_session(addr).await;
+
+        session
+            .subscribe_market_data("BTC-PERPETUAL".to_string())
+            .await
+            .unwrap();
+        let book_id = field(&recv_messages(&mut outgoing, 1).await, "262").unwrap();
+        let trade_id = session
+            .subscribe_market_data_entries("BTC-PERPETUAL", &[MdEntryType::Trade], 0)
+            .await
+            .unwrap();
+        assert_ne!(trade_id, book_id);
+        let request = recv_messages(&mut outgoing, 1).await;
+        assert_eq!(field(&request, "262"), Some(trade_id.clone()));
+        assert_eq!(field(&request, "267").as_deref(), Some("1"));
+        assert_eq!(field(&request, "269").as_deref(), Some("2"));
+
+        let subscriptions = session.market_data_subscriptions();
+        assert_eq!(subscriptions.all_for_symbol("BTC-PERPETUAL").count(), 2);
+        assert_eq!(
+            subscriptions
+                .book_for_symbol("BTC-PERPETUAL")
+                .unwrap()
+                .md_req_id,
+            book_id
+        );
+
+        // A second book subscription would feed the same order book
+        let error = session
+            .subscribe_market_data_entries("BTC-PERPETUAL", &[MdEntryType::Bid], 5)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DeribitFixError::Session(_)));
+
+        // Trade data does not touch the order book
+        replies
+            .send(frame(&format!(
+                "35=W\x0134=1\x01{HEADER}262={trade_id}\x0155=BTC-PERPETUAL\x01268=1\x01\
+                 269=2\x01270=50005\x01271=1\x01"
+            )))
+            .unwrap();
+        session.receive_and_process_message().await.unwrap();
+        assert!(session.order_book("BTC-PERPETUAL").is_none());
+
+        replies
+            .send(frame(&format!(
+                "35=W\x0134=2\x01{HEADER}262={book_id}\x0155=BTC-PERPETUAL\x01268=2\x01\
+                 269=0\x01270=50000\x01271=10\x01269=1\x01270=50010\x01271=5\x01"
+            )))
+            .unwrap();
+        session.receive_and_process_message().await.unwrap();
+        replies
+            .send(frame(&format!(
+                "35=W\x0134=3\x01{HEADER}262={trade_id}\x0155=BTC-PERPETUAL\x01268=1\x01\
+                 269=2\x01270=50006\x01271=2\x01"
+            )))
+            .unwrap();
+        session.receive_and_process_message().await.unwrap();
+        let book = session.order_book("BTC-PERPETUAL").unwrap();
+        assert_eq!(book.bids(), vec![(50000.0, 10.0)]);
+        assert_eq!(book.asks(), vec![(50010.0, 5.0)]);
+
+        // Cancelling the trades keeps the book
+        let removed = session.unsubscribe_market_data(&trade_id).await.unwrap();
+        assert_eq!(removed.entry_types, [MdEntryType::Trade]);
+        let unsubscribe = recv_messages(&mut outgoing, 1).await;
+        assert_eq!(field(&unsubscribe, "262"), Some(trade_id));
+        assert_eq!(field(&unsubscribe, "263").as_deref(), Some("2"));
+        assert!(session.order_book("BTC-PERPETUAL").is_some());
+        assert_eq!(session.market_data_subscriptions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_depth_change_and_symbol_unsubscribe_keep_other_subscriptions() {
+        let (addr, mut outgoing, _replies) = start_scripted_server().await;
+        let mut session = create_session(addr).await;
+
+        let trade_id = session
+            .subscribe_market_data_entries("ETH-PERPETUAL", &[MdEntryType::Trade], 0)
+            .await
+            .unwrap();
+        let book_id = session.set_market_depth("ETH-PERPETUAL", 10).await.unwrap();
+        let top_id = session
+            .subscribe_top_of_book("ETH-PERPETUAL")
+            .await
+            .unwrap();
+        let data = recv_messages(&mut outgoing, 4).await;
+        // The depth change to top of book cancelled the book subscription only
+        assert_eq!(data.matches("\x01263=2\x01").count(), 1);
+        assert!(session.market_data_subscriptions().get(&book_id).is_none());
+        assert!(session.market_data_subscriptions().get(&trade_id).is_some());
+
+        // A symbol stands for the book subscription
+        assert_eq!(
+            session
+                .market_data_subscriptions()
+                .find("ETH-PERPETUAL")
+                .unwrap()
+                .md_req_id,
+            top_id
+        );
+
+        let mut cancelled = session.unsubscribe_symbol("ETH-PERPETUAL").await.unwrap();
+        cancelled.sort_by(|a, b| a.md_req_id.cmp(&b.md_req_id));
+        let mut expected = vec![trade_id, top_id];
+        expected.sort();
+        let ids: Vec<String> = cancelled.into_iter().map(|s| s.md_req_id).collect();
+        assert_eq!(ids, expected);
+        assert!(session.market_data_subscriptions().is_empty());
+        assert!(session.top_of_book("ETH-PERPETUAL").is_none());
+        assert!(
+            session
+                .unsubscribe_symbol("ETH-PERPETUAL")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_request_every_entry_type_of_their_set() {
+        let (addr, mut outgoing, _replies) = start_scripted_server().await;
+        let mut session = create_session(addr).await;
+
+        let book_id = session
+            .subscribe_market_data_entries(
+                "BTC-PERPETUAL",
+                &[MdEntryType::Bid, MdEntryType::Offer],
+                10,
+            )
+            .await
+            .unwrap();
+        let request = FixMessage::parse(&recv_messages(&mut outgoing, 1).await).unwrap();
+        assert_eq!(request.get_field(262), Some(&book_id));
+        assert_eq!(request.get_field(267).unwrap(), "2");
+        assert_eq!(field_values(&request, 269), ["0", "1"]);
+
+        let trade_id = session
+            .subscribe_market_data_entries(
+                "BTC-PERPETUAL",
+                &[MdEntryType::Trade, MdEntryType::IndexValue],
+                0,
+            )
+            .await
+            .unwrap();
+        let request = FixMessage::parse(&recv_messages(&mut outgoing, 1).await).unwrap();
+        assert_eq!(request.get_field(262), Some(&trade_id));
+        assert_eq!(request.get_field(267).unwrap(), "2");
+        assert_eq!(field_values(&request, 269), ["2", "3"]);
+    }
+}
@@ -0,0 +1,75 @@
+// Unit tests for Session option ticker streams
+
+use super::super::support::{HEADER, create_session, field_values, start_subscription_server};
+use deribit_fix::message::PutOrCall;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTION: &str = "BTC-26DEC36-60000-C";
+
+    #[tokio::test]
+    async fn test_option_snapshots_are_streamed_as_tickers() {
+        let (addr, mut outgoing) = start_subscription_server(vec![format!(
+            "35=W\x0134=1\x01{HEADER}262={{md_req_id}}\x0155={OPTION}\x01\
+             311=BTC-26DEC36\x01810=60000\x01100090=0.25\x01746=120\x01268=2\x01\
+             269=0\x01270=0.245\x01271=10\x01269=1\x01270=0.255\x01271=4\x01"
+        )])
+        .await;
+        let mut session = create_session(addr).await;
+
+        assert!(
+            session
+                .subscribe_option_ticker("BTC-PERPETUAL")
+                .await
+                .is_err()
+        );
+        let mut tickers = session.subscribe_option_ticker(OPTION).await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "V");
+        assert_eq!(request.get_field(265).unwrap(), "0");
+        assert_eq!(request.get_field(55).unwrap(), OPTION);
+
+        session.receive_and_process_message().await.unwrap();
+        let ticker = tickers.try_recv().unwrap();
+        assert_eq!(ticker.symbol, OPTION);
+        assert_eq!(ticker.option_type, PutOrCall::Call);
+        assert_eq!(ticker.underlying_symbol.as_deref(), Some("BTC-26DEC36"));
+        assert_eq!(ticker.underlying_price, Some(60_000.0));
+        assert_eq!(ticker.mark_price, Some(0.25));
+        assert_eq!(ticker.open_interest, Some(120.0));
+        assert_eq!(ticker.best_bid, Some((0.245, 10.0)));
+        assert_eq!(ticker.best_ask, Some((0.255, 4.0)));
+        let (bid_iv, mark_iv, ask_iv) = (
+            ticker.bid_iv.unwrap(),
+            ticker.mark_iv.unwrap(),
+            ticker.ask_iv.unwrap(),
+        );
+        assert!(bid_iv < mark_iv && mark_iv < ask_iv);
+        let delta = ticker.greeks.unwrap().delta;
+        assert!(delta > 0.5 && delta < 1.0);
+
+        assert_eq!(session.option_ticker(OPTION), Some(&ticker));
+        // The ticker snapshot did not create an order book
+        assert!(session.order_book(OPTION).is_none());
+
+        session.unsubscribe_option_ticker(OPTION).await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(263).unwrap(), "2");
+        assert!(session.option_ticker(OPTION).is_none());
+        assert!(session.unsubscribe_option_ticker(OPTION).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_option_ticker_request_asks_for_bids_offers_and_trades() {
+        let (addr, mut outgoing) = start_subscription_server(Vec::new()).await;
+        let mut session = create_session(addr).await;
+
+        let _tickers = session.subscribe_option_ticker(OPTION).await.unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "V");
+        assert_eq!(request.get_field(267).unwrap(), "3");
+        assert_eq!(field_values(&request, 269), ["0", "1", "2"]);
+    }
+}
@@ -0,0 +1,134 @@
+// Unit tests for Session order lifecycle tracking and audit export
+
+use super::super::support::{HEADER, create_session, frame, start_mock_server};
+use deribit_fix::message::OrderStatus;
+use deribit_fix::model::{AuditEventKind, AuditFormat, NewOrderRequest, OrderLifecycle};
+use deribit_fix::session::SessionEvent;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution_report(seq: u32, cl_ord_id: &str, ord_status: char, extra: &str) -> String {
+        frame(&format!(
+            "35=8\x0134={seq}\x01{HEADER}11={cl_ord_id}\x0137=ID-{cl_ord_id}\x01150=0\x01\
+             39={ord_status}\x0155=BTC-PERPETUAL\x0154=1\x01{extra}"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_order_lifecycle_is_exported() {
+        let (addr, mut outgoing) = start_mock_server(vec![
+            execution_report(1, "A-1", '0', "14=0\x01151=10\x01"),
+            execution_report(
+                2,
+                "A-1",
+                '1',
+                "32=4\x0131=49990\x0114=4\x01151=6\x0112=0.25\x01",
+            ),
+            // Order placed outside the session
+            execution_report(3, "WEB-1", '4', ""),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_client_order_id("A-1".to_string());
+        session.send_new_order(order).await.unwrap();
+        outgoing.recv().await.unwrap();
+        for _ in 0..3 {
+            session.receive_and_process_message().await.unwrap();
+        }
+
+        let tracker = session.order_tracker();
+        assert_eq!(tracker.len(), 2);
+        let order = tracker.order("ID-A-1").unwrap();
+        assert_eq!(order.cl_ord_id, "A-1");
+        assert_eq!(order.status, Some(OrderStatus::PartiallyFilled));
+        assert_eq!(order.filled_qty, 4.0);
+        assert_eq!(order.fees, 0.25);
+        let kinds: Vec<_> = order.events.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                AuditEventKind::Submitted,
+                AuditEventKind::ExecutionReport,
+                AuditEventKind::ExecutionReport
+            ]
+        );
+        assert!(tracker.order("WEB-1").unwrap().is_complete());
+
+        let mut csv = Vec::new();
+        session
+            .export_order_audit(AuditFormat::Csv, &mut csv)
+            .unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        // A header and one row per event
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.lines().nth(3).unwrap().contains(",4,49990,4,6,"));
+
+        let mut json = Vec::new();
+        session
+            .export_order_audit(AuditFormat::Json, &mut json)
+            .unwrap();
+        let orders: Vec<OrderLifecycle> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(orders, tracker.orders());
+
+        session.clear_completed_orders();
+        assert_eq!(session.order_tracker().len(), 1);
+    }
+
+    fn fill(seq: u32, last_qty: u32, cum_qty: u32, ord_status: char) -> String {
+        frame(&format!(
+            "35=8\x0134={seq}\x01{HEADER}11=ICE-1\x0137=ID-ICE-1\x0117=F-{seq}\x01150=F\x01\
+             39={ord_status}\x0155=BTC-PERPETUAL\x0154=1\x0138=10\x011138=4\x01\
+             32={last_qty}\x0131=50000\x0114={cum_qty}\x01151={}\x01",
+            10 - cum_qty
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_refills_are_reported() {
+        let (addr, mut outgoing) = start_mock_server(vec![
+            fill(1, 3, 3, '1'),
+            fill(2, 2, 5, '1'),
+            fill(3, 5, 10, '2'),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+            .with_client_order_id("ICE-1".to_string())
+            .with_max_show(4.0);
+        session.send_new_order(order).await.unwrap();
+        let sent = outgoing.recv().await.unwrap();
+        assert_eq!(sent.get_field(1138).unwrap(), "4");
+
+        session.receive_and_process_message().await.unwrap();
+        assert!(events.try_recv().is_err());
+        let order = session.order_tracker().order("ICE-1").unwrap();
+        assert_eq!(order.displayed_qty(), 1.0);
+        assert_eq!(order.remaining_qty(), 7.0);
+
+        session.receive_and_process_message().await.unwrap();
+        let SessionEvent::IcebergRefilled(refill) = events.try_recv().unwrap() else {
+            panic!("expected an iceberg refill");
+        };
+        assert_eq!(refill.cl_ord_id, "ICE-1");
+        assert_eq!(refill.order_id.as_deref(), Some("ID-ICE-1"));
+        assert_eq!(refill.refills, 1);
+        assert_eq!(refill.displayed_qty, 3.0);
+        assert_eq!(refill.remaining_qty, 5.0);
+
+        // The last fill takes the rest of the slice and the final one at once
+        session.receive_and_process_message().await.unwrap();
+        let order = session.order_tracker().order("ICE-1").unwrap();
+        assert_eq!(order.refills, 2);
+        assert_eq!(order.remaining_qty(), 0.0);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::IcebergRefilled(_)
+        ));
+    }
+}
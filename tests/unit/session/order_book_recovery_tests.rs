@@ -0,0 +1,191 @@
+// Unit tests for Session order book validation and snapshot recovery
+
+use super::super::support::{
+    HEADER, create_session, field_values, frame, session_with_config, start_raw_mock_server,
+    test_config,
+};
+use deribit_fix::message::MdEntryType;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::order_book::{BookIntegrityEvent, BookIntegrityIssue};
+use deribit_fix::session::SessionEvent;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(seq: u32, bid: f64, ask: f64) -> String {
+        frame(&format!(
+            "35=W\x0134={seq}\x01{HEADER}55=BTC-PERPETUAL\x01268=2\x01269=0\x01270={bid}\x01271=1\x01269=1\x01270={ask}\x01271=1\x01"
+        ))
+    }
+
+    fn incremental(seq: u32, entries: &str) -> String {
+        frame(&format!(
+            "35=X\x0134={seq}\x01{HEADER}55=BTC-PERPETUAL\x01268=1\x01{entries}"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_missing_level_triggers_snapshot_and_rebuild() {
+        let (addr, mut outgoing) = start_raw_mock_server(vec![
+            snapshot(1, 100.0, 101.0),
+            // Delete of a level that was never in the book
+            incremental(2, "279=2\x01269=1\x01270=150\x01271=0\x01"),
+            // Ignored while recovering
+            incremental(3, "279=0\x01269=0\x01270=100.5\x01271=1\x01"),
+            snapshot(4, 90.0, 91.0),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        for _ in 0..4 {
+            session.receive_and_process_message().await.unwrap();
+        }
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::BookIntegrity(BookIntegrityEvent::Violation {
+                symbol: "BTC-PERPETUAL".to_string(),
+                issue: BookIntegrityIssue::MissingLevel {
+                    side: MdEntryType::Offer,
+                    price: 150.0,
+                },
+            })
+        );
+        let md_req_id = match events.try_recv().unwrap() {
+            SessionEvent::BookIntegrity(BookIntegrityEvent::SnapshotRequested {
+                symbol,
+                md_req_id,
+            }) => {
+                assert_eq!(symbol, "BTC-PERPETUAL");
+                md_req_id
+            }
+            other => panic!("unexpected event: {other:?}"),
+        };
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::BookIntegrity(BookIntegrityEvent::Rebuilt {
+                symbol: "BTC-PERPETUAL".to_string(),
+            })
+        );
+
+        let book = session.order_book("BTC-PERPETUAL").unwrap();
+        assert!(!book.is_recovering());
+        assert_eq!(book.best_bid(), Some((90.0, 1.0)));
+        assert_eq!(book.best_ask(), Some((91.0, 1.0)));
+
+        let raw = tokio::time::timeout(Duration::from_secs(2), outgoing.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let request = FixMessage::parse(&raw).unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "V");
+        assert_eq!(request.get_field(262).unwrap(), &md_req_id);
+        assert_eq!(request.get_field(263).unwrap(), "0");
+        assert_eq!(request.get_field(55).unwrap(), "BTC-PERPETUAL");
+        // The snapshot rebuilds both sides of the book
+        assert_eq!(request.get_field(267).unwrap(), "2");
+        assert_eq!(field_values(&request, 269), ["0", "1"]);
+    }
+
+    #[tokio::test]
+    async fn test_unparsable_incremental_is_dropped_and_book_rebuilt() {
+        let (addr, mut outgoing) = start_raw_mock_server(vec![
+            snapshot(1, 100.0, 101.0),
+            incremental(2, "279=0\x01269=0\x01270=abc\x01271=1\x01"),
+            incremental(3, "279=0\x01269=0\x01270=100.5\x01271=1\x01"),
+            snapshot(4, 90.0, 91.0),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+        let incoming_seq_num = session.incoming_seq_num();
+
+        for _ in 0..4 {
+            session.receive_and_process_message().await.unwrap();
+        }
+        assert_eq!(session.incoming_seq_num(), incoming_seq_num + 4);
+
+        match events.try_recv().unwrap() {
+            SessionEvent::BookIntegrity(BookIntegrityEvent::Violation {
+                symbol,
+                issue: BookIntegrityIssue::Unparsable { .. },
+            }) => assert_eq!(symbol, "BTC-PERPETUAL"),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        let md_req_id = match events.try_recv().unwrap() {
+            SessionEvent::BookIntegrity(BookIntegrityEvent::SnapshotRequested {
+                md_req_id,
+                ..
+            }) => md_req_id,
+            other => panic!("unexpected event: {other:?}"),
+        };
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::BookIntegrity(BookIntegrityEvent::Rebuilt { .. })
+        ));
+        assert_eq!(
+            session.order_book("BTC-PERPETUAL").unwrap().best_bid(),
+            Some((90.0, 1.0))
+        );
+
+        let raw = tokio::time::timeout(Duration::from_secs(2), outgoing.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let request = FixMessage::parse(&raw).unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "V");
+        assert_eq!(request.get_field(262).unwrap(), &md_req_id);
+        assert_eq!(request.get_field(55).unwrap(), "BTC-PERPETUAL");
+        assert_eq!(field_values(&request, 269), ["0", "1"]);
+    }
+
+    #[tokio::test]
+    async fn test_valid_incremental_updates_book() {
+        let (addr, _outgoing) = start_raw_mock_server(vec![
+            snapshot(1, 100.0, 101.0),
+            incremental(2, "279=1\x01269=0\x01270=100\x01271=4\x01"),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+
+        assert!(events.try_recv().is_err());
+        let book = session.order_book("BTC-PERPETUAL").unwrap();
+        assert_eq!(book.best_bid(), Some((100.0, 4.0)));
+    }
+
+    #[tokio::test]
+    async fn test_order_level_book_estimates_queue_position() {
+        let (addr, _outgoing) = start_raw_mock_server(vec![
+            frame(&format!(
+                "35=W\x0134=1\x01{HEADER}55=BTC-PERPETUAL\x01268=3\x01\
+                 269=0\x01270=100\x01271=1\x0137=B1\x01\
+                 269=0\x01270=100\x01271=2\x0137=B2\x01\
+                 269=1\x01270=101\x01271=1\x01"
+            )),
+            // The first order in the queue is partially filled
+            incremental(2, "279=1\x01269=0\x01270=100\x01271=0.25\x0137=B1\x01"),
+        ])
+        .await;
+        let config = test_config(addr).with_order_level_books(true);
+        let mut session = session_with_config(config).await;
+
+        session.receive_and_process_message().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+
+        let book = session.order_book("BTC-PERPETUAL").unwrap();
+        assert!(book.is_order_level());
+        assert_eq!(book.best_bid(), Some((100.0, 2.25)));
+        // The offer has no order id and stays a price level
+        assert_eq!(book.best_ask(), Some((101.0, 1.0)));
+        let position = book.queue_position("B2").unwrap();
+        assert_eq!(position.orders_ahead, 1);
+        assert_eq!(position.size_ahead, 0.25);
+    }
+}
@@ -0,0 +1,155 @@
+// Unit tests for Session OCO order group management
+
+use super::super::support::{
+    HEADER, create_session, frame, session_with_config, start_mock_server, test_config,
+};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::model::NewOrderRequest;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::order_group::OrderGroupEvent;
+use deribit_fix::model::risk::RiskLimits;
+use deribit_fix::session::{SessionEvent, SessionState};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution_report(seq: u32, cl_ord_id: &str, ord_status: char) -> String {
+        frame(&format!(
+            "35=8\x0134={seq}\x01{HEADER}11={cl_ord_id}\x0137=ID-{cl_ord_id}\x0139={ord_status}\x0155=BTC-PERPETUAL\x01"
+        ))
+    }
+
+    fn take_profit_and_stop_loss() -> Vec<NewOrderRequest> {
+        vec![
+            NewOrderRequest::limit_sell("BTC-PERPETUAL".to_string(), 10.0, 60_000.0)
+                .with_client_order_id("TP".to_string()),
+            NewOrderRequest::limit_sell("BTC-PERPETUAL".to_string(), 10.0, 40_000.0)
+                .with_client_order_id("SL".to_string()),
+        ]
+    }
+
+    async fn next_sent(outgoing: &mut mpsc::UnboundedReceiver<FixMessage>) -> FixMessage {
+        tokio::time::timeout(Duration::from_secs(2), outgoing.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fill_cancels_sibling_and_completes_group() {
+        let (addr, mut outgoing) = start_mock_server(vec![
+            execution_report(1, "TP", '2'),
+            execution_report(2, "SL", '4'),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        let group_id = session
+            .submit_order_group(take_profit_and_stop_loss())
+            .await
+            .unwrap();
+        assert_eq!(next_sent(&mut outgoing).await.get_field(11).unwrap(), "TP");
+        assert_eq!(next_sent(&mut outgoing).await.get_field(11).unwrap(), "SL");
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::OrderGroup(OrderGroupEvent::Triggered {
+                group_id: group_id.clone(),
+                filled_cl_ord_id: "TP".to_string(),
+            })
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::OrderGroup(OrderGroupEvent::SiblingCancelRequested {
+                group_id: group_id.clone(),
+                cl_ord_id: "SL".to_string(),
+            })
+        );
+        let cancel = next_sent(&mut outgoing).await;
+        assert_eq!(cancel.get_field(35).unwrap(), "F");
+        assert_eq!(cancel.get_field(11).unwrap(), "SL");
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::OrderGroup(OrderGroupEvent::Completed {
+                group_id: group_id.clone(),
+            })
+        );
+        assert!(session.order_groups().group(&group_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_logon_requests_status_of_open_members() {
+        let (addr, mut outgoing) = start_mock_server(vec![frame(&format!(
+            "35=A\x0134=1\x01{HEADER}98=0\x01108=30\x01"
+        ))])
+        .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        let group_id = session
+            .submit_order_group(take_profit_and_stop_loss())
+            .await
+            .unwrap();
+        next_sent(&mut outgoing).await;
+        next_sent(&mut outgoing).await;
+
+        session.set_state(SessionState::LogonSent);
+        session.receive_and_process_message().await.unwrap();
+
+        let requested: Vec<String> = vec![
+            next_sent(&mut outgoing).await,
+            next_sent(&mut outgoing).await,
+        ]
+        .into_iter()
+        .inspect(|message| assert_eq!(message.get_field(35).unwrap(), "AF"))
+        .map(|message| message.get_field(584).unwrap().clone())
+        .collect();
+        assert_eq!(requested, vec!["TP".to_string(), "SL".to_string()]);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::OrderGroup(OrderGroupEvent::RecoveryRequested { group_id })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refused_member_cancels_sent_members_and_drops_group() {
+        let (addr, mut outgoing) = start_mock_server(Vec::new()).await;
+        let limits = RiskLimits::new().with_max_open_orders_per_instrument(1);
+        let mut session = session_with_config(test_config(addr).with_risk_limits(limits)).await;
+
+        let error = session
+            .submit_order_group(take_profit_and_stop_loss())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DeribitFixError::RiskLimit(_)));
+
+        let order = next_sent(&mut outgoing).await;
+        assert_eq!(order.get_field(35).unwrap(), "D");
+        assert_eq!(order.get_field(11).unwrap(), "TP");
+        let cancel = next_sent(&mut outgoing).await;
+        assert_eq!(cancel.get_field(35).unwrap(), "F");
+        assert_eq!(cancel.get_field(11).unwrap(), "TP");
+        assert_eq!(session.order_groups().groups().count(), 0);
+        assert!(session.order_groups().group_of("SL").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_group_requires_two_orders() {
+        let (addr, _outgoing) = start_mock_server(Vec::new()).await;
+        let mut session = create_session(addr).await;
+
+        let single = vec![NewOrderRequest::limit_buy(
+            "BTC-PERPETUAL".to_string(),
+            1.0,
+            100.0,
+        )];
+        assert!(session.submit_order_group(single).await.is_err());
+    }
+}
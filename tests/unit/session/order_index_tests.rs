@@ -0,0 +1,71 @@
+// Unit tests for the Session order index kept across restarts
+
+use super::super::support::{HEADER, frame, session_with_config, start_mock_server, test_config};
+use deribit_fix::message::{ExecutionReport, OrderStatus};
+use deribit_fix::model::NewOrderRequest;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::order_index::{MemoryOrderIndexStore, OrderIndexStore};
+use deribit_fix::session::Session;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_session(
+        addr: std::net::SocketAddr,
+        store: Arc<MemoryOrderIndexStore>,
+    ) -> Session {
+        session_with_config(test_config(addr).with_order_index_store(store)).await
+    }
+
+    fn execution_report(seq: u32, cl_ord_id: &str, ord_status: char) -> String {
+        frame(&format!(
+            "35=8\x0134={seq}\x01{HEADER}11={cl_ord_id}\x0137=ID-{cl_ord_id}\x01150=0\x01\
+             39={ord_status}\x0155=BTC-PERPETUAL\x0154=1\x01"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_order_owners_survive_a_restart() {
+        let store = Arc::new(MemoryOrderIndexStore::new());
+        let (addr, mut outgoing) = start_mock_server(vec![
+            execution_report(1, "MM-1", '0'),
+            execution_report(2, "HEDGE-1", '0'),
+            execution_report(3, "HEDGE-1", '2'),
+        ])
+        .await;
+        let mut session = create_session(addr, store.clone()).await;
+
+        for (cl_ord_id, label) in [("MM-1", "mm"), ("HEDGE-1", "hedge")] {
+            let order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 10.0, 50_000.0)
+                .with_client_order_id(cl_ord_id.to_string())
+                .with_label(label.to_string());
+            session.send_new_order(order).await.unwrap();
+            outgoing.recv().await.unwrap();
+        }
+        for _ in 0..3 {
+            session.receive_and_process_message().await.unwrap();
+        }
+        // The filled order no longer needs an owner
+        assert_eq!(session.order_index().len(), 1);
+        assert_eq!(store.load().unwrap().len(), 1);
+
+        // A new session, as after a restart, loads the owners from the store
+        let (addr, _outgoing) = start_mock_server(Vec::new()).await;
+        let restarted = create_session(addr, store).await;
+        let owner = restarted.order_index().order("ID-MM-1").unwrap();
+        assert_eq!(owner.cl_ord_id, "MM-1");
+        assert_eq!(owner.label.as_deref(), Some("mm"));
+
+        let working = ExecutionReport::from_fix_message(
+            &FixMessage::parse(&execution_report(1, "MM-1", '0')).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(working.ord_status, OrderStatus::New);
+        let reconciliation = restarted.reconcile_orders(&[working]);
+        assert_eq!(reconciliation.owned.len(), 1);
+        assert!(reconciliation.unknown.is_empty());
+        assert!(reconciliation.missing.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+// Unit tests for Session paper trading
+
+use super::super::support::{
+    HEADER, frame, session_with_config, start_mock_server_with, test_config,
+};
+use deribit_fix::message::{ExecutionReport, OrderStatus};
+use deribit_fix::model::cancel::{CancelReport, CancelTarget};
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::model::request::NewOrderRequest;
+use deribit_fix::session::Session;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answer Market Data Requests (V) with a snapshot
+    fn answer_snapshot_request(request: &FixMessage) -> Vec<String> {
+        if request.get_field(35).is_some_and(|t| t == "V") {
+            vec![frame(&format!(
+                "35=W\x0134=1\x01{HEADER}55=BTC-PERPETUAL\x01268=3\x01269=0\x01270=49990\x01271=5\x01269=1\x01270=50000\x01271=2\x01269=1\x01270=50010\x01271=3\x01"
+            ))]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Create a paper trading session whose order book has been seeded
+    async fn create_session(addr: std::net::SocketAddr) -> Session {
+        let mut session = session_with_config(test_config(addr).with_paper_trading(true)).await;
+        assert!(session.paper_trading().is_some());
+
+        session
+            .subscribe_market_data("BTC-PERPETUAL".to_string())
+            .await
+            .unwrap();
+        for _ in 0..100 {
+            session.receive_and_process_message().await.unwrap();
+            if session.order_book("BTC-PERPETUAL").is_some() {
+                return session;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("snapshot not received");
+    }
+
+    async fn receive_reports(session: &mut Session, count: usize) -> Vec<ExecutionReport> {
+        let mut reports = Vec::new();
+        while reports.len() < count {
+            let message = session
+                .receive_and_process_message()
+                .await
+                .unwrap()
+                .expect("simulated report");
+            reports.push(ExecutionReport::from_fix_message(&message).unwrap());
+        }
+        reports
+    }
+
+    #[tokio::test]
+    async fn test_orders_are_filled_locally_against_market_data() {
+        let (addr, mut outgoing) =
+            start_mock_server_with(Vec::new(), answer_snapshot_request).await;
+        let mut session = create_session(addr).await;
+        let seq_num = session.outgoing_seq_num();
+
+        let cl_ord_id = session
+            .send_new_order(NewOrderRequest::limit_buy(
+                "BTC-PERPETUAL".to_string(),
+                3.0,
+                50_005.0,
+            ))
+            .await
+            .unwrap();
+
+        let reports = receive_reports(&mut session, 2).await;
+        assert_eq!(reports[0].cl_ord_id, cl_ord_id);
+        assert_eq!(reports[0].ord_status, OrderStatus::New);
+        assert_eq!(reports[1].ord_status, OrderStatus::PartiallyFilled);
+        assert_eq!(reports[1].last_px, Some(50_000.0));
+        assert_eq!(reports[1].leaves_qty, 1.0);
+        assert_eq!(session.paper_trading().unwrap().open_orders().len(), 1);
+
+        // The order never reached the exchange nor consumed a sequence number
+        assert_eq!(session.outgoing_seq_num(), seq_num);
+        assert_eq!(outgoing.recv().await.unwrap().get_field(35).unwrap(), "V");
+        assert!(outgoing.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_of_resting_order_is_simulated() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), answer_snapshot_request).await;
+        let mut session = create_session(addr).await;
+
+        session
+            .send_new_order(NewOrderRequest::limit_sell(
+                "BTC-PERPETUAL".to_string(),
+                1.0,
+                51_000.0,
+            ))
+            .await
+            .unwrap();
+        let order_id = receive_reports(&mut session, 1).await[0].order_id.clone();
+
+        let report = session
+            .cancel(CancelTarget::OrderId(order_id.clone()))
+            .await
+            .unwrap();
+        match report {
+            CancelReport::Order(report) => {
+                assert_eq!(report.order_id, order_id);
+                assert_eq!(report.ord_status, OrderStatus::Cancelled);
+            }
+            other => panic!("unexpected report {other:?}"),
+        }
+        assert!(session.paper_trading().unwrap().open_orders().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_simulated_batch_reports_no_sequence_number() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), answer_snapshot_request).await;
+        let mut session = create_session(addr).await;
+        let seq_num = session.outgoing_seq_num();
+
+        let sent = session
+            .send_new_orders(vec![
+                NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), 1.0, 49_000.0),
+                NewOrderRequest::limit_sell("BTC-PERPETUAL".to_string(), 1.0, 51_000.0),
+            ])
+            .await;
+        assert_eq!(sent.len(), 2);
+        for (_, result) in sent {
+            assert_eq!(result.unwrap(), None);
+        }
+        assert_eq!(session.outgoing_seq_num(), seq_num);
+    }
+}
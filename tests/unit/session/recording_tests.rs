@@ -0,0 +1,76 @@
+// Unit tests for Session market data recording
+
+use super::super::support::{HEADER, frame, start_paced_mock_server};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::recorder::{MarketDataPlayback, MarketDataUpdate, PlaybackSpeed};
+use deribit_fix::session::Session;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_received_market_data_is_recorded_and_replayed() {
+        // A heartbeat, a snapshot and an incremental update, each in a read of its own
+        let addr = start_paced_mock_server(vec![
+            frame(&format!("35=0\x0134=1\x01{HEADER}")),
+            frame(&format!(
+                "35=W\x0134=2\x01{HEADER}55=BTC-PERPETUAL\x01268=2\x01269=0\x01270=49990\x01271=5\x01269=1\x01270=50000\x01271=2\x01"
+            )),
+            frame(&format!(
+                "35=X\x0134=3\x01{HEADER}55=BTC-PERPETUAL\x01268=1\x01279=0\x01269=0\x01270=49995\x01271=1\x01"
+            )),
+        ])
+        .await;
+        let path = std::env::temp_dir().join(format!("deribit_fix_md_{}.rec", addr.port()));
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000))
+            .with_market_data_recording(path.display().to_string());
+
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+
+        let mut received = 0;
+        for _ in 0..100 {
+            if session
+                .receive_and_process_message()
+                .await
+                .unwrap()
+                .is_some()
+            {
+                received += 1;
+                if received == 3 {
+                    break;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(received, 3);
+        let live_bid = session.order_book("BTC-PERPETUAL").unwrap().best_bid();
+        drop(session);
+
+        let mut playback = MarketDataPlayback::open(&path)
+            .unwrap()
+            .with_speed(PlaybackSpeed::AsFastAsPossible);
+        let first = playback.next_update().await.unwrap().unwrap();
+        assert!(matches!(first, MarketDataUpdate::Snapshot(_)));
+        let second = playback.next_update().await.unwrap().unwrap();
+        assert!(matches!(second, MarketDataUpdate::Incremental(_)));
+        assert!(playback.next_update().await.unwrap().is_none());
+
+        assert_eq!(live_bid, Some((49995.0, 1.0)));
+        assert_eq!(
+            playback.order_book("BTC-PERPETUAL").unwrap().best_bid(),
+            live_bid
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,123 @@
+// Unit tests for Session request deadlines and cancellation
+
+use super::super::support::{session_with_config, test_config};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::session::{CancelToken, ManualClock, RequestOptions, Session};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpListener;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server that reads everything and never answers
+    async fn start_silent_server() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                while let Ok(n) = socket.read(&mut buf).await {
+                    if n == 0 {
+                        break;
+                    }
+                }
+            }
+        });
+
+        addr
+    }
+
+    async fn create_session(addr: std::net::SocketAddr) -> Session {
+        session_with_config(test_config(addr).with_request_timeout(Duration::from_millis(300)))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_request_times_out() {
+        let addr = start_silent_server().await;
+        let mut session = create_session(addr).await;
+
+        // The configured request timeout applies without a deadline
+        let started = tokio::time::Instant::now();
+        let error = session.get_account_summary("BTC").await.unwrap_err();
+        assert!(matches!(error, DeribitFixError::Timeout(_)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // An explicit deadline replaces it
+        session.set_request_options(RequestOptions::new().with_timeout(Duration::from_millis(50)));
+        let started = tokio::time::Instant::now();
+        match session.ping().await.unwrap_err() {
+            DeribitFixError::Timeout(message) => assert!(message.contains("deadline")),
+            other => panic!("Expected a timeout, got {other:?}"),
+        }
+        assert!(started.elapsed() < Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_token_stops_the_wait() {
+        let addr = start_silent_server().await;
+        let mut session = create_session(addr).await;
+
+        let token = CancelToken::new();
+        session.set_request_options(
+            RequestOptions::new()
+                .with_timeout(Duration::from_secs(10))
+                .with_cancel_token(token.clone()),
+        );
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            token.cancel();
+        });
+
+        let started = tokio::time::Instant::now();
+        let error = session.get_account_summary("BTC").await.unwrap_err();
+        assert!(matches!(error, DeribitFixError::Cancelled(_)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // The token stays cancelled for later requests
+        assert!(matches!(
+            session.ping().await,
+            Err(DeribitFixError::Cancelled(_))
+        ));
+    }
+
+    /// Advance `clock` by `duration` once the session waits on one of its timers
+    async fn advance_when_waiting(clock: &ManualClock, duration: Duration) {
+        while clock.pending_timers() == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        clock.advance(duration);
+    }
+
+    #[tokio::test]
+    async fn test_waits_follow_the_session_clock() {
+        let addr = start_silent_server().await;
+        let clock = Arc::new(ManualClock::default());
+        let mut session = session_with_config(
+            test_config(addr)
+                .with_request_timeout(Duration::from_secs(30))
+                .with_clock(clock.clone()),
+        )
+        .await;
+        let started = tokio::time::Instant::now();
+
+        let (summary, ()) = tokio::join!(
+            session.get_account_summary("BTC"),
+            advance_when_waiting(&clock, Duration::from_secs(30)),
+        );
+        assert!(matches!(summary, Err(DeribitFixError::Timeout(_))));
+
+        let (positions, ()) = tokio::join!(
+            session.request_positions(),
+            advance_when_waiting(&clock, Duration::from_secs(30)),
+        );
+        assert!(positions.unwrap().is_empty());
+
+        // Neither request waited for the wall clock
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,90 @@
+// Unit tests for Session pre-trade risk checks
+
+use super::super::support::{
+    HEADER, frame, session_with_config, start_raw_mock_server, test_config,
+};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::model::request::NewOrderRequest;
+use deribit_fix::model::risk::{RiskLimits, RiskViolation};
+use deribit_fix::session::Session;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_session(addr: std::net::SocketAddr, limits: RiskLimits) -> Session {
+        session_with_config(test_config(addr).with_risk_limits(limits)).await
+    }
+
+    fn limit_buy(amount: f64, price: f64) -> NewOrderRequest {
+        let mut order = NewOrderRequest::limit_buy("BTC-PERPETUAL".to_string(), amount, price);
+        order.client_order_id = Some("ORDER_1".to_string());
+        order
+    }
+
+    #[tokio::test]
+    async fn test_open_order_cap_is_released_by_execution_report() {
+        let (addr, mut outgoing) = start_raw_mock_server(vec![frame(&format!(
+            "35=8\x0134=1\x01{HEADER}37=1\x0111=ORDER_1\x0117=E1\x01150=4\x0139=4\x0155=BTC-PERPETUAL\x0154=1\x0138=10\x01151=0\x0114=0\x016=0\x01"
+        ))])
+        .await;
+        let limits = RiskLimits::new().with_max_open_orders_per_instrument(1);
+        let mut session = create_session(addr, limits).await;
+
+        session
+            .send_new_order(limit_buy(10.0, 100.0))
+            .await
+            .unwrap();
+        assert!(outgoing.recv().await.unwrap().contains("35=D\x01"));
+        assert_eq!(session.risk_guard().open_orders("BTC-PERPETUAL"), 1);
+
+        let error = session
+            .send_new_order(limit_buy(10.0, 100.0))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            DeribitFixError::RiskLimit(RiskViolation::TooManyOpenOrders { limit: 1, .. })
+        ));
+
+        // The cancel report closes the order
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.risk_guard().open_orders("BTC-PERPETUAL"), 0);
+        session
+            .send_new_order(limit_buy(10.0, 100.0))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_price_collar_uses_mark_price() {
+        let (addr, mut outgoing) = start_raw_mock_server(vec![frame(&format!(
+            "35=W\x0134=1\x01{HEADER}55=BTC-PERPETUAL\x01100090=100\x01268=0\x01"
+        ))])
+        .await;
+        let limits = RiskLimits::new().with_price_collar(0.05);
+        let mut session = create_session(addr, limits).await;
+        session.receive_and_process_message().await.unwrap();
+
+        let error = session
+            .send_new_order(limit_buy(1.0, 90.0))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            DeribitFixError::RiskLimit(RiskViolation::PriceOutsideCollar {
+                mark_price: 100.0,
+                ..
+            })
+        ));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), outgoing.recv())
+                .await
+                .is_err()
+        );
+
+        session.send_new_order(limit_buy(1.0, 102.0)).await.unwrap();
+        assert!(outgoing.recv().await.unwrap().contains("35=D\x01"));
+    }
+}
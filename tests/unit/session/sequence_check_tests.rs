@@ -0,0 +1,80 @@
+// Unit tests for Session strict sequence checks
+
+use super::super::support::{session_with_config, start_writing_mock_server, test_config};
+use deribit_fix::message::{Heartbeat, Reject};
+use deribit_fix::session::{
+    SequenceAnomaly, SequenceAnomalyKind, Session, SessionEvent, SessionState,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_session(addr: std::net::SocketAddr, strict: bool) -> Session {
+        session_with_config(test_config(addr).with_strict_sequence_checks(strict)).await
+    }
+
+    fn raw_heartbeat(msg_seq_num: u32) -> String {
+        Heartbeat::new()
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), msg_seq_num)
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_incoming_gap_raises_anomaly() {
+        let addr = start_writing_mock_server(vec![raw_heartbeat(1), raw_heartbeat(3)]).await;
+        let mut session = create_session(addr, true).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        assert!(events.try_recv().is_err());
+
+        let message = session.receive_and_process_message().await.unwrap();
+        assert!(message.is_some());
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SessionEvent::SequenceAnomaly(SequenceAnomaly {
+                kind: SequenceAnomalyKind::IncomingGap,
+                msg_type: "0".to_string(),
+                msg_seq_num: Some(3),
+                expected: 2,
+                actual: 3,
+                poss_dup: false,
+                next_incoming_seq_num: 2,
+                next_outgoing_seq_num: 1,
+                state: SessionState::Disconnected,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reject_of_unsent_message_raises_anomaly() {
+        let reject = Reject::new(5)
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), 1)
+            .unwrap()
+            .to_string();
+        let addr = start_writing_mock_server(vec![reject]).await;
+        let mut session = create_session(addr, true).await;
+        let mut events = session.subscribe_events();
+
+        let _ = session.receive_and_process_message().await;
+        let Ok(SessionEvent::SequenceAnomaly(anomaly)) = events.try_recv() else {
+            panic!("expected a sequence anomaly");
+        };
+        assert_eq!(anomaly.kind, SequenceAnomalyKind::UnsentReference);
+        assert_eq!(anomaly.msg_type, "3");
+        assert_eq!((anomaly.expected, anomaly.actual), (0, 5));
+    }
+
+    #[tokio::test]
+    async fn test_gaps_are_not_checked_by_default() {
+        let addr = start_writing_mock_server(vec![raw_heartbeat(1), raw_heartbeat(3)]).await;
+        let mut session = create_session(addr, false).await;
+        let mut events = session.subscribe_events();
+
+        session.receive_and_process_message().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        assert!(events.try_recv().is_err());
+    }
+}
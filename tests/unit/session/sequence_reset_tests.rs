@@ -0,0 +1,90 @@
+// Unit tests for Session sequence reset handling
+
+use super::super::support::{create_session, start_writing_mock_server};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::SequenceReset;
+use deribit_fix::session::SessionEvent;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_sequence_reset(reset: SequenceReset, msg_seq_num: u32) -> String {
+        reset
+            .to_fix_message("DERIBIT".to_string(), "CLIENT".to_string(), msg_seq_num)
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_gap_fill_advances_incoming_sequence() {
+        let addr =
+            start_writing_mock_server(vec![raw_sequence_reset(SequenceReset::new_gap_fill(10), 1)])
+                .await;
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        let message = session.receive_and_process_message().await.unwrap();
+        assert!(message.is_some());
+        assert_eq!(session.incoming_seq_num(), 10);
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(
+            event,
+            SessionEvent::SequenceReset {
+                previous_seq_num: 1,
+                new_seq_no: 10,
+                gap_fill: true,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reset_mode_sets_incoming_sequence() {
+        let addr =
+            start_writing_mock_server(vec![raw_sequence_reset(SequenceReset::new_reset(25), 99)])
+                .await;
+        let mut session = create_session(addr).await;
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.incoming_seq_num(), 25);
+    }
+
+    #[tokio::test]
+    async fn test_reset_mode_rejects_decrease() {
+        let addr = start_writing_mock_server(vec![
+            raw_sequence_reset(SequenceReset::new_reset(20), 1),
+            raw_sequence_reset(SequenceReset::new_reset(5), 20),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.incoming_seq_num(), 20);
+
+        let result = session.receive_and_process_message().await;
+        assert!(matches!(result, Err(DeribitFixError::Protocol(_))));
+        assert_eq!(session.incoming_seq_num(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_stale_gap_fill_is_ignored() {
+        let addr = start_writing_mock_server(vec![
+            raw_sequence_reset(SequenceReset::new_gap_fill(15), 1),
+            raw_sequence_reset(SequenceReset::new_gap_fill(8), 15),
+        ])
+        .await;
+        let mut session = create_session(addr).await;
+
+        session.receive_and_process_message().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.incoming_seq_num(), 15);
+    }
+
+    #[test]
+    fn test_reset_seq_num_on_logon_config() {
+        let config = DeribitFixConfig::new().with_reset_seq_num_on_logon(true);
+        assert!(config.reset_seq_num_on_logon);
+    }
+}
@@ -0,0 +1,148 @@
+// Unit tests for Session state transitions and per-state message guards
+
+use super::super::support::{HEADER, frame, session_with_config, start_mock_server, test_config};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::model::NewOrderRequest;
+use deribit_fix::session::{Session, SessionState};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_session(addr: std::net::SocketAddr, strict: bool) -> Session {
+        session_with_config(test_config(addr).with_strict_session_state(strict)).await
+    }
+
+    fn logon_response(seq_num: u32) -> String {
+        frame(&format!(
+            "35=A\x0134={seq_num}\x01{HEADER}98=0\x01108=30\x01"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_logon_and_logout_transitions() {
+        let (addr, _outgoing) = start_mock_server(vec![
+            logon_response(1),
+            frame(&format!(
+                "35=5\x0134=2\x01{HEADER}58=Logout acknowledged\x01"
+            )),
+        ])
+        .await;
+        let mut session = create_session(addr, false).await;
+
+        // Logout is not possible before logging on
+        assert!(matches!(
+            session.logout().await,
+            Err(DeribitFixError::Session(_))
+        ));
+
+        session.logon().await.unwrap();
+        assert_eq!(session.get_state(), SessionState::LogonSent);
+        assert!(matches!(
+            session.logon().await,
+            Err(DeribitFixError::Session(_))
+        ));
+
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.get_state(), SessionState::LoggedOn);
+
+        session.logout().await.unwrap();
+        assert_eq!(session.get_state(), SessionState::LogoutSent);
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_logon_is_an_illegal_transition() {
+        let (addr, _outgoing) = start_mock_server(vec![logon_response(1)]).await;
+        let mut session = create_session(addr, false).await;
+
+        assert!(matches!(
+            session.receive_and_process_message().await,
+            Err(DeribitFixError::Session(_))
+        ));
+        assert_eq!(session.get_state(), SessionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_logon_while_logged_on_keeps_the_session() {
+        for strict in [false, true] {
+            let (addr, _outgoing) = start_mock_server(vec![
+                logon_response(1),
+                frame(&format!(
+                    "35=A\x0134=1\x01{HEADER}98=0\x01108=30\x01141=Y\x01"
+                )),
+            ])
+            .await;
+            let mut session = create_session(addr, strict).await;
+
+            session.logon().await.unwrap();
+            session.receive_and_process_message().await.unwrap();
+            assert_eq!(session.get_state(), SessionState::LoggedOn);
+
+            // An in-session logon resetting the sequence numbers is not fatal
+            session.receive_and_process_message().await.unwrap();
+            assert_eq!(session.get_state(), SessionState::LoggedOn);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resend_in_progress_until_gap_is_filled() {
+        let (addr, mut outgoing) = start_mock_server(vec![
+            logon_response(1),
+            frame(&format!(
+                "35=4\x0134=2\x01{HEADER}43=Y\x01123=Y\x0136=5\x01"
+            )),
+        ])
+        .await;
+        let mut session = create_session(addr, false).await;
+
+        // A resend can only be requested once logged on
+        assert!(matches!(
+            session.send_resend_request(2, 4).await,
+            Err(DeribitFixError::Session(_))
+        ));
+
+        session.logon().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        session.send_resend_request(2, 4).await.unwrap();
+        assert_eq!(session.get_state(), SessionState::ResendInProgress);
+
+        let logon = outgoing.recv().await.unwrap();
+        assert_eq!(logon.get_field(35).unwrap(), "A");
+        let resend = outgoing.recv().await.unwrap();
+        assert_eq!(resend.get_field(35).unwrap(), "2");
+        assert_eq!(resend.get_field(7).unwrap(), "2");
+        assert_eq!(resend.get_field(16).unwrap(), "4");
+
+        // The GapFill moves the expected sequence number past EndSeqNo
+        session.receive_and_process_message().await.unwrap();
+        assert_eq!(session.incoming_seq_num(), 5);
+        assert_eq!(session.get_state(), SessionState::LoggedOn);
+    }
+
+    #[tokio::test]
+    async fn test_strict_state_rejects_disallowed_messages() {
+        let (addr, mut outgoing) =
+            start_mock_server(vec![frame(&format!("35=0\x0134=1\x01{HEADER}"))]).await;
+        let mut session = create_session(addr, true).await;
+
+        let order = NewOrderRequest::limit_sell("BTC-PERPETUAL".to_string(), 10.0, 50_000.0);
+        match session.send_new_order(order).await {
+            Err(DeribitFixError::Session(message)) => {
+                assert!(message.contains("NewOrderSingle"));
+                assert!(message.contains("Disconnected"));
+            }
+            other => panic!("Expected a session error, got {other:?}"),
+        }
+        assert!(matches!(
+            session.receive_and_process_message().await,
+            Err(DeribitFixError::Session(_))
+        ));
+
+        // Logon is the one message allowed before logging on
+        session.logon().await.unwrap();
+        let sent = outgoing.recv().await.unwrap();
+        assert_eq!(sent.get_field(35).unwrap(), "A");
+    }
+}
@@ -0,0 +1,207 @@
+// Unit tests for Session market data unsubscribe, depth changes and top of book
+
+use super::super::support::{
+    HEADER, create_session, field, frame, start_raw_mock_server, start_raw_mock_server_with,
+};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::session::SessionEvent;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server answering every Market Data Request with a Market
+    /// Data Request Reject (Y) of its MDReqID
+    async fn start_rejecting_server() -> (std::net::SocketAddr, mpsc::UnboundedReceiver<String>) {
+        start_raw_mock_server_with(Vec::new(), |data| {
+            field(data, "262")
+                .map(|md_req_id| {
+                    frame(&format!(
+                        "35=Y\x0134=1\x01{HEADER}262={md_req_id}\x01281=0\x0158=unknown symbol\x01"
+                    ))
+                })
+                .into_iter()
+                .collect()
+        })
+        .await
+    }
+
+    /// Read from the server until `count` messages have arrived
+    async fn recv_messages(outgoing: &mut mpsc::UnboundedReceiver<String>, count: usize) -> String {
+        let mut data = String::new();
+        while data.matches("35=V\x01").count() < count {
+            data.push_str(&outgoing.recv().await.unwrap());
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_set_market_depth_replaces_subscription() {
+        let (addr, mut outgoing) = start_raw_mock_server(Vec::new()).await;
+        let mut session = create_session(addr).await;
+
+        session
+            .subscribe_market_data("BTC-PERPETUAL".to_string())
+            .await
+            .unwrap();
+        let subscribe = recv_messages(&mut outgoing, 1).await;
+        let first_id = field(&subscribe, "262").unwrap();
+        assert_eq!(field(&subscribe, "264").as_deref(), Some("0"));
+        assert_eq!(
+            session
+                .market_data_subscriptions()
+                .for_symbol("BTC-PERPETUAL")
+                .unwrap()
+                .md_req_id,
+            first_id
+        );
+
+        let new_id = session.set_market_depth("BTC-PERPETUAL", 10).await.unwrap();
+        assert_ne!(new_id, first_id);
+        let data = recv_messages(&mut outgoing, 2).await;
+        let (unsubscribe, resubscribe) = data.split_at(data.rfind("8=FIX.4.4").unwrap());
+        assert_eq!(field(unsubscribe, "262"), Some(first_id.clone()));
+        assert_eq!(field(unsubscribe, "263").as_deref(), Some("2"));
+        assert_eq!(field(unsubscribe, "55").as_deref(), Some("BTC-PERPETUAL"));
+        assert_eq!(field(resubscribe, "262"), Some(new_id.clone()));
+        assert_eq!(field(resubscribe, "264").as_deref(), Some("10"));
+
+        let subscriptions = session.market_data_subscriptions();
+        assert_eq!(subscriptions.len(), 1);
+        assert!(subscriptions.get(&first_id).is_none());
+        assert_eq!(subscriptions.get(&new_id).unwrap().market_depth, 10);
+
+        // Unsubscribe by request ID
+        let removed = session.unsubscribe_market_data(&new_id).await.unwrap();
+        assert_eq!(removed.symbol, "BTC-PERPETUAL");
+        assert!(session.market_data_subscriptions().is_empty());
+        let unsubscribe = recv_messages(&mut outgoing, 1).await;
+        assert_eq!(field(&unsubscribe, "263").as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_rejected_subscriptions() {
+        let (addr, mut outgoing) = start_rejecting_server().await;
+        let mut session = create_session(addr).await;
+
+        let error = session
+            .unsubscribe_market_data("ETH-PERPETUAL")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DeribitFixError::Session(_)));
+
+        session
+            .subscribe_market_data("UNKNOWN".to_string())
+            .await
+            .unwrap();
+        recv_messages(&mut outgoing, 1).await;
+        assert_eq!(session.market_data_subscriptions().len(), 1);
+
+        // The reject removes the subscription
+        session.receive_and_process_message().await.unwrap();
+        assert!(session.market_data_subscriptions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_top_of_book_skips_the_order_book() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, mut outgoing) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let _ = tx.send(String::from_utf8_lossy(&buf[..n]).to_string());
+            let snapshot = frame(&format!(
+                "35=W\x0134=1\x01{HEADER}55=BTC-PERPETUAL\x01268=2\x01\
+                 269=0\x01270=50000\x01271=10\x01269=1\x01270=50010\x01271=5\x01"
+            ));
+            let update = frame(&format!(
+                "35=X\x0134=2\x01{HEADER}55=BTC-PERPETUAL\x01268=1\x01\
+                 279=0\x01269=1\x01270=50008\x01271=2\x01"
+            ));
+            let _ = socket
+                .write_all(format!("{snapshot}{update}").as_bytes())
+                .await;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        });
+        let mut session = create_session(addr).await;
+        let mut events = session.subscribe_events();
+
+        let md_req_id = session
+            .subscribe_top_of_book("BTC-PERPETUAL")
+            .await
+            .unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(field(&request, "262"), Some(md_req_id.clone()));
+        assert_eq!(field(&request, "264").as_deref(), Some("1"));
+        assert!(
+            session
+                .market_data_subscriptions()
+                .get(&md_req_id)
+                .unwrap()
+                .top_of_book
+        );
+
+        session.receive_and_process_message().await.unwrap();
+        session.receive_and_process_message().await.unwrap();
+        let top = session.top_of_book("BTC-PERPETUAL").unwrap();
+        assert_eq!(top.bid, Some((50000.0, 10.0)));
+        assert_eq!(top.ask, Some((50008.0, 2.0)));
+        assert!(session.order_book("BTC-PERPETUAL").is_none());
+
+        let first = match events.try_recv().unwrap() {
+            SessionEvent::TopOfBook(top) => top,
+            other => panic!("Expected a top of book event, got {other:?}"),
+        };
+        assert_eq!(first.ask, Some((50010.0, 5.0)));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::TopOfBook(_)
+        ));
+
+        session
+            .unsubscribe_market_data("BTC-PERPETUAL")
+            .await
+            .unwrap();
+        assert!(session.top_of_book("BTC-PERPETUAL").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_all_market_data() {
+        let (addr, mut outgoing) = start_raw_mock_server(Vec::new()).await;
+        let mut session = create_session(addr).await;
+
+        session
+            .subscribe_market_data("BTC-PERPETUAL".to_string())
+            .await
+            .unwrap();
+        session
+            .subscribe_top_of_book("ETH-PERPETUAL")
+            .await
+            .unwrap();
+        let _index = session.subscribe_index("btc_usd").await.unwrap();
+        recv_messages(&mut outgoing, 3).await;
+
+        let mut cancelled = session.unsubscribe_all_market_data().await.unwrap();
+        cancelled.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        let symbols: Vec<&str> = cancelled.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, ["BTC-PERPETUAL", "ETH-PERPETUAL"]);
+        assert!(session.market_data_subscriptions().is_empty());
+        assert!(session.top_of_book("ETH-PERPETUAL").is_none());
+
+        let data = recv_messages(&mut outgoing, 3).await;
+        assert_eq!(data.matches("\x01263=2\x01").count(), 3);
+        assert!(
+            session
+                .unsubscribe_all_market_data()
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
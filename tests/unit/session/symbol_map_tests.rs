@@ -0,0 +1,69 @@
+// Unit tests for Session symbol normalization
+
+use super::super::support::{HEADER, frame, start_mock_server};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::request::NewOrderRequest;
+use deribit_fix::model::symbol_map::SymbolTable;
+use deribit_fix::session::{Session, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_symbols_are_translated_both_ways() {
+        let report = frame(&format!(
+            "35=8\x0134=1\x01{HEADER}37=ORD1\x0111=MAPPED_1\x0117=EXEC1\x01150=0\x0139=0\x01\
+             55=BTC-PERPETUAL\x0154=1\x0138=10\x01151=10\x0114=0\x0144=50000\x01"
+        ));
+        let (addr, mut server) = start_mock_server(vec![report]).await;
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_symbol_mapper(Arc::new(
+                SymbolTable::new().with("BTC-PERP", "BTC-PERPETUAL"),
+            ))
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000));
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        session.set_state(SessionState::LoggedOn);
+
+        let mut order = NewOrderRequest::limit_buy("BTC-PERP".to_string(), 10.0, 50000.0);
+        order.client_order_id = Some("MAPPED_1".to_string());
+        session.send_new_order(order).await.unwrap();
+        let sent = server.recv().await.unwrap();
+        assert_eq!(
+            sent.get_field(55).map(String::as_str),
+            Some("BTC-PERPETUAL")
+        );
+
+        let received = session
+            .receive_and_process_message()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.get_field(55).map(String::as_str), Some("BTC-PERP"));
+        assert!(received.raw_message.contains("\x0155=BTC-PERP\x01"));
+
+        // Symbols the mapper does not know are sent as given
+        session
+            .send_new_order(NewOrderRequest::limit_buy(
+                "ETH-PERPETUAL".to_string(),
+                1.0,
+                3000.0,
+            ))
+            .await
+            .unwrap();
+        let sent = server.recv().await.unwrap();
+        assert_eq!(
+            sent.get_field(55).map(String::as_str),
+            Some("ETH-PERPETUAL")
+        );
+    }
+}
@@ -0,0 +1,71 @@
+// Unit tests for the adaptive pacing of outgoing messages
+
+use super::super::support::{
+    HEADER, frame, session_with_config, start_raw_mock_server, test_config,
+};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::session::{Session, SessionEvent, SessionState};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_session(config: DeribitFixConfig) -> Session {
+        let mut session = session_with_config(config).await;
+        session.set_state(SessionState::LoggedOn);
+        session
+    }
+
+    fn rate_limit_reject() -> String {
+        frame(&format!(
+            "35=8\x0134=1\x01{HEADER}37=ORD-1\x0111=ORDER_1\x0117=EXEC-1\x01150=8\x0139=8\x01\
+             55=BTC-PERPETUAL\x0154=1\x0138=10\x01151=0\x0114=0\x0158=too_many_requests\x01"
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_reject_lowers_the_allowed_rate() {
+        let (addr, mut outgoing) = start_raw_mock_server(vec![rate_limit_reject()]).await;
+        let mut session = create_session(test_config(addr).with_max_message_rate(20.0)).await;
+        let mut events = session.subscribe_events();
+        assert_eq!(session.throttle_stats().allowed_rate, Some(20.0));
+
+        session.receive_and_process_message().await.unwrap();
+        let stats = session.throttle_stats();
+        assert_eq!(stats.allowed_rate, Some(10.0));
+        assert_eq!(stats.max_rate, Some(20.0));
+        assert_eq!(stats.rate_limit_rejects, 1);
+        let reject = stats.last_reject.unwrap();
+        assert_eq!(reject.cl_ord_id.as_deref(), Some("ORDER_1"));
+        assert_eq!(reject.text, "too_many_requests");
+
+        let allowed_rate = loop {
+            if let SessionEvent::RateLimited { allowed_rate, .. } = events.try_recv().unwrap() {
+                break allowed_rate;
+            }
+        };
+        assert_eq!(allowed_rate, Some(10.0));
+
+        // The bucket was drained, so the next message waits for a token
+        session
+            .subscribe_market_data("BTC-PERPETUAL".to_string())
+            .await
+            .unwrap();
+        assert!(outgoing.recv().await.unwrap().contains("35=V\x01"));
+        let stats = session.throttle_stats();
+        assert_eq!(stats.delayed_messages, 1);
+        assert!(stats.total_delay >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn test_fixed_rate_ignores_rejections() {
+        let (addr, _outgoing) = start_raw_mock_server(vec![rate_limit_reject()]).await;
+        let mut session = create_session(test_config(addr).with_adaptive_throttling(false)).await;
+
+        session.receive_and_process_message().await.unwrap();
+        let stats = session.throttle_stats();
+        assert_eq!(stats.allowed_rate, None);
+        assert_eq!(stats.rate_limit_rejects, 1);
+    }
+}
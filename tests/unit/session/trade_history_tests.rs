@@ -0,0 +1,90 @@
+// Unit tests for Session trade history requests
+
+use super::super::support::{HEADER, create_session, field, frame, start_market_data_server};
+use chrono::{TimeZone, Utc};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::MdReqRejReason;
+use deribit_fix::model::request::OrderSide;
+use deribit_fix::model::types::Liquidation;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recent_trades_are_parsed_and_keep_the_book() {
+        let book = frame(&format!(
+            "35=W\x0134=1\x01{HEADER}55=BTC-PERPETUAL\x01268=1\x01269=0\x01270=99\x01271=5\x01"
+        ));
+        let (addr, mut outgoing) = start_market_data_server(vec![book], |md_req_id| {
+            frame(&format!(
+                "35=W\x0134=2\x01{HEADER}262={md_req_id}\x0155=BTC-PERPETUAL\x01268=2\x01\
+                 269=2\x01270=101\x01271=3\x01272=1767225660000\x0154=2\x01100009=T2\x01\
+                 269=2\x01270=100\x01271=1\x01272=1767225600000\x0154=1\x01100009=T1\x01100091=T\x01"
+            ))
+        })
+        .await;
+        let mut session = create_session(addr).await;
+        session.receive_and_process_message().await.unwrap();
+
+        let since = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let trades = session
+            .get_recent_trades("BTC-PERPETUAL", Some(since), Some(50))
+            .await
+            .unwrap();
+
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].trade_id.as_deref(), Some("T1"));
+        assert_eq!(trades[0].price, 100.0);
+        assert_eq!(trades[0].amount, 1.0);
+        assert_eq!(trades[0].side, Some(OrderSide::Buy));
+        assert_eq!(trades[0].timestamp, Some(since));
+        assert_eq!(trades[0].liquidation, Liquidation::Taker);
+        assert_eq!(trades[1].trade_id.as_deref(), Some("T2"));
+        assert_eq!(trades[1].side, Some(OrderSide::Sell));
+
+        // The trade snapshot does not replace the order book
+        let book = session.order_book("BTC-PERPETUAL").unwrap();
+        assert_eq!(book.best_bid(), Some((99.0, 5.0)));
+
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(field(&request, "35").as_deref(), Some("V"));
+        assert_eq!(field(&request, "263").as_deref(), Some("0"));
+        assert_eq!(field(&request, "269").as_deref(), Some("2"));
+        assert_eq!(field(&request, "55").as_deref(), Some("BTC-PERPETUAL"));
+        assert_eq!(field(&request, "100007").as_deref(), Some("50"));
+        assert_eq!(
+            field(&request, "100008"),
+            Some(since.timestamp_millis().to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_invalid_trade_requests() {
+        let (addr, _outgoing) = start_market_data_server(Vec::new(), |md_req_id| {
+            frame(&format!(
+                "35=Y\x0134=1\x01{HEADER}262={md_req_id}\x01281=0\x0158=unknown symbol\x01"
+            ))
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        let error = session
+            .get_recent_trades("BTC-PERPETUAL", None, Some(1001))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DeribitFixError::MessageConstruction(_)));
+
+        let error = session
+            .get_recent_trades("UNKNOWN", None, None)
+            .await
+            .unwrap_err();
+        match error {
+            DeribitFixError::MarketDataRejected { reason, text, .. } => {
+                assert_eq!(reason, Some(MdReqRejReason::UnknownSymbol));
+                assert_eq!(text.as_deref(), Some("unknown symbol"));
+            }
+            other => panic!("Expected a market data reject, got {other:?}"),
+        }
+    }
+}
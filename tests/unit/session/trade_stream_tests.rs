@@ -0,0 +1,87 @@
+// Unit tests for Session own trade subscriptions
+
+use super::super::support::{HEADER, create_session, frame, start_mock_server_with};
+use deribit_fix::error::DeribitFixError;
+use deribit_fix::message::TradeCaptureRequestResult;
+use deribit_fix::model::message::FixMessage;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Acknowledge a trade subscription and report one trade of it
+    fn accept_and_trade(request: &FixMessage) -> Vec<String> {
+        if request.get_field(263).map(String::as_str) != Some("1") {
+            return Vec::new();
+        }
+        let id = request.get_field(568).unwrap();
+        vec![
+            frame(&format!(
+                "35=AQ\x0134=1\x01{HEADER}568={id}\x01750=0\x01749=0\x01"
+            )),
+            frame(&format!(
+                "35=AE\x0134=2\x01{HEADER}568={id}\x01571=TR-1\x011003=T-1\x01\
+                 55=BTC-PERPETUAL\x0154=1\x0132=10\x0131=50000\x01"
+            )),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_subscribed_trades_are_streamed_until_unsubscribed() {
+        let (addr, mut outgoing) = start_mock_server_with(Vec::new(), accept_and_trade).await;
+        let mut session = create_session(addr).await;
+
+        let mut stream = session
+            .subscribe_trades(Some("BTC-PERPETUAL"))
+            .await
+            .unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(35).unwrap(), "AD");
+        assert_eq!(request.get_field(263).unwrap(), "1");
+        assert_eq!(request.get_field(55).unwrap(), "BTC-PERPETUAL");
+        assert_eq!(request.get_field(568), Some(&stream.trade_request_id));
+
+        while stream.reports.is_empty() {
+            session.receive_and_process_message().await.unwrap();
+        }
+        let trade = stream.recv().await.unwrap();
+        assert_eq!(trade.trade_report_id, "TR-1");
+        assert_eq!(trade.last_px, 50_000.0);
+
+        session
+            .unsubscribe_trades(&stream.trade_request_id)
+            .await
+            .unwrap();
+        let request = outgoing.recv().await.unwrap();
+        assert_eq!(request.get_field(263).unwrap(), "2");
+        assert_eq!(request.get_field(568), Some(&stream.trade_request_id));
+        assert!(stream.recv().await.is_none());
+        assert!(matches!(
+            session.unsubscribe_trades(&stream.trade_request_id).await,
+            Err(DeribitFixError::Session(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_trade_subscription_is_returned_as_error() {
+        let (addr, _outgoing) = start_mock_server_with(Vec::new(), |request| {
+            let id = request.get_field(568).unwrap();
+            vec![frame(&format!(
+                "35=AQ\x0134=1\x01{HEADER}568={id}\x01750=2\x01749=9\x0158=not allowed\x01"
+            ))]
+        })
+        .await;
+        let mut session = create_session(addr).await;
+
+        match session.subscribe_trades(None).await {
+            Err(DeribitFixError::TradeCaptureRejected { result, text, .. }) => {
+                assert_eq!(
+                    result,
+                    Some(TradeCaptureRequestResult::UnauthorizedForTradeCaptureReportRequest)
+                );
+                assert_eq!(text.as_deref(), Some("not allowed"));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Unit tests for sending typed messages through the Session
+
+use super::super::support::create_session;
+use deribit_fix::message::{Heartbeat, TestRequest};
+use deribit_fix::model::message::FixMessage;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a mock server that forwards everything it reads to the returned channel
+    async fn start_capturing_server() -> (std::net::SocketAddr, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let mut received = Vec::new();
+                let mut buf = [0u8; 1024];
+                while let Ok(Ok(n)) =
+                    tokio::time::timeout(Duration::from_millis(300), socket.read(&mut buf)).await
+                {
+                    if n == 0 {
+                        break;
+                    }
+                    received.extend_from_slice(&buf[..n]);
+                }
+                let _ = tx.send(String::from_utf8_lossy(&received).to_string());
+            }
+        });
+
+        (addr, rx)
+    }
+
+    #[tokio::test]
+    async fn test_send_typed_messages_assigns_header_fields() {
+        let (addr, received) = start_capturing_server().await;
+        let mut session = create_session(addr).await;
+
+        let first = session
+            .send(&TestRequest::new("PING".to_string()))
+            .await
+            .unwrap();
+        let second = session.send(&Heartbeat::new()).await.unwrap();
+
+        assert_eq!(first, 1);
+        assert_eq!(second, 2);
+        assert_eq!(session.outgoing_seq_num(), 3);
+
+        let raw = received.await.unwrap();
+        let frames: Vec<&str> = raw
+            .split("8=FIX.4.4")
+            .filter(|frame| !frame.is_empty())
+            .collect();
+        assert_eq!(frames.len(), 2);
+
+        let test_request = FixMessage::parse(&format!("8=FIX.4.4{}", frames[0])).unwrap();
+        assert_eq!(test_request.get_field(35).unwrap(), "1");
+        assert_eq!(test_request.get_field(49).unwrap(), "CLIENT");
+        assert_eq!(test_request.get_field(56).unwrap(), "DERIBIT");
+        assert_eq!(test_request.get_field(34).unwrap(), "1");
+        assert_eq!(test_request.get_field(112).unwrap(), "PING");
+        assert!(test_request.has_field(52));
+
+        let heartbeat = FixMessage::parse(&format!("8=FIX.4.4{}", frames[1])).unwrap();
+        assert_eq!(heartbeat.get_field(35).unwrap(), "0");
+        assert_eq!(heartbeat.get_field(34).unwrap(), "2");
+    }
+}
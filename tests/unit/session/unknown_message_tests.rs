@@ -0,0 +1,59 @@
+// Unit tests for Session handling of message types added by the exchange later
+
+use super::super::support::{HEADER, frame, start_mock_server};
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::types::MsgType;
+use deribit_fix::session::{Session, SessionState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unknown_message_type_is_rejected_not_fatal() {
+        let (addr, mut server) = start_mock_server(vec![
+            frame(&format!("35=ZZ\x0134=1\x01{HEADER}58=new feature\x01")),
+            frame(&format!("35=0\x0134=2\x01{HEADER}")),
+        ])
+        .await;
+        let config = DeribitFixConfig::new()
+            .with_credentials("test_user".to_string(), "test_password".to_string())
+            .with_endpoint(addr.ip().to_string(), addr.port())
+            .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+            .with_ssl(false)
+            .with_connection_timeout(Duration::from_millis(1000));
+        let connection = Connection::new(&config).await.unwrap();
+        let mut session = Session::new(&config, Arc::new(Mutex::new(connection))).unwrap();
+        session.set_state(SessionState::LoggedOn);
+
+        let unknown = session
+            .receive_and_process_message()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unknown.msg_type(), Some(MsgType::Unknown("ZZ".to_string())));
+        assert_eq!(session.incoming_seq_num(), 2);
+
+        // The session keeps processing the messages that follow
+        let heartbeat = session
+            .receive_and_process_message()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(heartbeat.msg_type(), Some(MsgType::Heartbeat));
+        assert_eq!(session.incoming_seq_num(), 3);
+
+        let reject = tokio::time::timeout(Duration::from_secs(2), server.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reject.msg_type(), Some(MsgType::Reject));
+        assert_eq!(reject.get_field(45).map(String::as_str), Some("1"));
+        assert_eq!(reject.get_field(372).map(String::as_str), Some("ZZ"));
+        assert_eq!(reject.get_field(373).map(String::as_str), Some("11"));
+    }
+}
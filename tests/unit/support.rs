@@ -0,0 +1,220 @@
+// Helpers shared by the unit tests: FIX framing, mock servers and sessions
+// connected to them
+
+use deribit_fix::config::DeribitFixConfig;
+use deribit_fix::connection::Connection;
+use deribit_fix::model::message::FixMessage;
+use deribit_fix::session::Session;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, mpsc};
+
+/// Header of the messages written by the mock servers
+pub const HEADER: &str = "49=DERIBIT\x0156=CLIENT\x0152=20260101-00:00:00.000\x01";
+
+/// Frame a message body with BeginString, BodyLength and CheckSum
+pub fn frame(body: &str) -> String {
+    let head = format!("8=FIX.4.4\x019={}\x01{}", body.len(), body);
+    let checksum = head.bytes().map(u32::from).sum::<u32>() % 256;
+    format!("{head}10={checksum:03}\x01")
+}
+
+/// Value of the first `tag` field in raw FIX text
+pub fn field(data: &str, tag: &str) -> Option<String> {
+    data.split('\x01')
+        .find_map(|field| field.strip_prefix(&format!("{tag}=")))
+        .map(str::to_string)
+}
+
+/// Values of every `tag` field of a message, in order, such as the entries
+/// of a repeating group
+pub fn field_values(message: &FixMessage, tag: u32) -> Vec<&str> {
+    message
+        .fields
+        .iter()
+        .filter(|(field_tag, _)| *field_tag == tag)
+        .map(|(_, value)| value.as_str())
+        .collect()
+}
+
+/// Split raw FIX text read from a socket into its messages
+fn parse_messages(received: &str) -> Vec<FixMessage> {
+    received
+        .split("8=FIX.4.4")
+        .filter(|part| !part.is_empty())
+        .filter_map(|part| FixMessage::parse(&format!("8=FIX.4.4{part}")).ok())
+        .collect()
+}
+
+/// Start a mock server writing `messages` and forwarding every FIX message
+/// it reads
+pub async fn start_mock_server(
+    messages: Vec<String>,
+) -> (SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+    start_mock_server_with(messages, |_| Vec::new()).await
+}
+
+/// Start a mock server writing `messages`, then answering every FIX message
+/// it reads with `reply` and forwarding it
+///
+/// `reply` receives the request so it can echo identifiers such as ClOrdID.
+pub async fn start_mock_server_with(
+    messages: Vec<String>,
+    mut reply: impl FnMut(&FixMessage) -> Vec<String> + Send + 'static,
+) -> (SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+    serve(messages, move |received| {
+        let received = parse_messages(&received);
+        let responses = received.iter().flat_map(&mut reply).collect();
+        (responses, received)
+    })
+    .await
+}
+
+/// Start a mock server writing `messages` and forwarding what it reads as
+/// text
+pub async fn start_raw_mock_server(
+    messages: Vec<String>,
+) -> (SocketAddr, mpsc::UnboundedReceiver<String>) {
+    start_raw_mock_server_with(messages, |_| Vec::new()).await
+}
+
+/// Start a mock server writing `messages`, then answering what it reads
+/// with `reply` and forwarding it as text
+pub async fn start_raw_mock_server_with(
+    messages: Vec<String>,
+    mut reply: impl FnMut(&str) -> Vec<String> + Send + 'static,
+) -> (SocketAddr, mpsc::UnboundedReceiver<String>) {
+    serve(messages, move |received| (reply(&received), vec![received])).await
+}
+
+/// Start a mock server writing `messages` and keeping the connection open
+pub async fn start_writing_mock_server(messages: Vec<String>) -> SocketAddr {
+    start_raw_mock_server(messages).await.0
+}
+
+/// Start a mock server that first writes `initial`, then answers every
+/// Market Data Request (V) with `respond(md_req_id)`, forwarding what it
+/// reads as text
+pub async fn start_market_data_server(
+    initial: Vec<String>,
+    respond: fn(&str) -> String,
+) -> (SocketAddr, mpsc::UnboundedReceiver<String>) {
+    start_raw_mock_server_with(initial, move |received| {
+        field(received, "262")
+            .map(|md_req_id| respond(&md_req_id))
+            .into_iter()
+            .collect()
+    })
+    .await
+}
+
+/// Start a mock server forwarding every FIX message it reads and writing
+/// `messages` after the first Market Data Request (V), with `{md_req_id}`
+/// replaced by its MDReqID (262)
+pub async fn start_subscription_server(
+    messages: Vec<String>,
+) -> (SocketAddr, mpsc::UnboundedReceiver<FixMessage>) {
+    let mut messages = Some(messages);
+    start_mock_server_with(Vec::new(), move |request| {
+        let Some(md_req_id) = request.get_field(262) else {
+            return Vec::new();
+        };
+        messages
+            .take()
+            .unwrap_or_default()
+            .iter()
+            .map(|message| frame(&message.replace("{md_req_id}", md_req_id)))
+            .collect()
+    })
+    .await
+}
+
+/// Start a mock server writing `messages`, then passing what it reads to
+/// `handle`, which returns the responses to write and the items to forward
+///
+/// Stops reading once the client is silent for two seconds.
+async fn serve<T: Send + 'static>(
+    messages: Vec<String>,
+    mut handle: impl FnMut(String) -> (Vec<String>, Vec<T>) + Send + 'static,
+) -> (SocketAddr, mpsc::UnboundedReceiver<T>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let Ok((mut socket, _)) = listener.accept().await else {
+            return;
+        };
+        for message in messages {
+            let _ = socket.write_all(message.as_bytes()).await;
+        }
+        let mut buf = [0u8; 8192];
+        while let Ok(Ok(n)) =
+            tokio::time::timeout(Duration::from_secs(2), socket.read(&mut buf)).await
+        {
+            if n == 0 {
+                break;
+            }
+            let (responses, received) = handle(String::from_utf8_lossy(&buf[..n]).to_string());
+            for response in responses {
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+            for item in received {
+                let _ = tx.send(item);
+            }
+        }
+    });
+
+    (addr, rx)
+}
+
+/// Start a mock server writing `messages` 20ms apart, so each arrives in a
+/// read of its own
+pub async fn start_paced_mock_server(messages: Vec<String>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            for message in messages {
+                let _ = socket.write_all(message.as_bytes()).await;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            let mut buf = [0u8; 1024];
+            let _ = tokio::time::timeout(Duration::from_secs(2), socket.read(&mut buf)).await;
+        }
+    });
+
+    addr
+}
+
+/// Configuration of a session connecting to the mock server at `addr`
+pub fn test_config(addr: SocketAddr) -> DeribitFixConfig {
+    DeribitFixConfig::new()
+        .with_credentials("test_user".to_string(), "test_password".to_string())
+        .with_endpoint(addr.ip().to_string(), addr.port())
+        .with_session_ids("CLIENT".to_string(), "DERIBIT".to_string())
+        .with_ssl(false)
+        .with_connection_timeout(Duration::from_millis(1000))
+}
+
+/// Create a session connecting to the mock server at `addr`
+pub async fn create_session(addr: SocketAddr) -> Session {
+    session_with_config(test_config(addr)).await
+}
+
+/// Create a session connecting as `config` says
+pub async fn session_with_config(config: DeribitFixConfig) -> Session {
+    let connection = Connection::new(&config).await.unwrap();
+    Session::new(&config, Arc::new(Mutex::new(connection))).unwrap()
+}
+
+/// Read one FIX message written by a client to an in-memory connection
+pub async fn next_message(server: &mut tokio::io::DuplexStream) -> FixMessage {
+    let mut buf = [0u8; 4096];
+    let n = server.read(&mut buf).await.unwrap();
+    FixMessage::parse(&String::from_utf8_lossy(&buf[..n])).unwrap()
+}
@@ -0,0 +1,878 @@
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 16/10/26
+******************************************************************************/
+
+//! Code generated from the FIX dictionary
+//!
+//! `dictionary/deribit_fix44.json` lists the FIX 4.4 and Deribit custom
+//! fields and the message types used by the crate. From it this task writes
+//! the tag constants in `src/model/tags.rs`, the `MsgType` enum with its
+//! string conversions in `src/model/msg_type.rs` and, in
+//! `src/message/generated.rs`, the structs of the messages whose body the
+//! dictionary describes, each with a `to_fix_message` and a
+//! `from_fix_message` reading back the fields it writes.
+//!
+//! Only flat bodies of strings, integers and booleans are described; messages
+//! with repeating groups or enumerated values stay hand-written.
+
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Dictionary location, relative to the workspace root
+const DICTIONARY: &str = "dictionary/deribit_fix44.json";
+
+const HEADER: &str = "\
+/******************************************************************************
+   Author: Joaquín Béjar García
+   Email: jb@taunais.com
+   Date: 21/7/25
+******************************************************************************/
+";
+
+const GENERATED: &str = "\
+// @generated by `cargo xtask codegen` from dictionary/deribit_fix44.json.
+// Edit the dictionary and regenerate instead of changing this file.
+";
+
+/// FIX dictionary
+#[derive(Debug, Deserialize)]
+pub struct Dictionary {
+    /// Standard FIX fields
+    pub fields: Vec<Field>,
+    /// Aliases kept for compatibility with earlier releases
+    pub legacy_fields: Vec<Field>,
+    /// Deribit custom fields
+    pub deribit_fields: Vec<Field>,
+    /// Fields whose tag means something else within one message family
+    pub groups: Vec<Group>,
+    /// Message types
+    pub messages: Vec<Message>,
+    /// Messages generated as structs
+    pub structs: Vec<Struct>,
+}
+
+/// Field of the dictionary
+#[derive(Debug, Deserialize)]
+pub struct Field {
+    /// Tag number
+    pub tag: u32,
+    /// Field name in the specification
+    pub name: String,
+    /// Name of the generated constant
+    #[serde(rename = "const")]
+    pub const_name: String,
+    /// Usage note appended to the documentation
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Deprecation note, for constants that should no longer be used
+    #[serde(default)]
+    pub deprecated: Option<String>,
+}
+
+/// Fields generated in their own submodule
+#[derive(Debug, Deserialize)]
+pub struct Group {
+    /// Submodule name
+    pub module: String,
+    /// Documentation lines of the submodule
+    pub doc: Vec<String>,
+    /// Fields of the submodule
+    pub fields: Vec<Field>,
+}
+
+/// Message type of the dictionary
+#[derive(Debug, Deserialize)]
+pub struct Message {
+    /// MsgType (35) value
+    pub msg_type: String,
+    /// Message name in the specification
+    pub name: String,
+    /// Name of the generated enum variant
+    pub variant: String,
+}
+
+/// Message generated as a struct with its FIX conversions
+#[derive(Debug, Deserialize)]
+pub struct Struct {
+    /// Variant of the message type, also the struct name
+    pub message: String,
+    /// Documentation lines of the struct
+    pub doc: Vec<String>,
+    /// Body fields, in the order they are written
+    pub fields: Vec<StructField>,
+}
+
+/// Body field of a generated struct
+#[derive(Debug, Deserialize)]
+pub struct StructField {
+    /// Constant of the field's tag
+    pub field: String,
+    /// Name of the struct field
+    pub name: String,
+    /// Type of the value
+    #[serde(rename = "type")]
+    pub value_type: ValueType,
+    /// Whether parsing fails without the field
+    pub required: bool,
+    /// Documentation lines of the struct field
+    pub doc: Vec<String>,
+}
+
+/// Type of a body field value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    /// Text, written as is
+    String,
+    /// Unsigned integer
+    U32,
+    /// Boolean, written as Y or N
+    Bool,
+}
+
+impl ValueType {
+    fn rust_type(self) -> &'static str {
+        match self {
+            ValueType::String => "String",
+            ValueType::U32 => "u32",
+            ValueType::Bool => "bool",
+        }
+    }
+
+    /// Function of the generated file parsing a value of this type
+    fn parser(self) -> &'static str {
+        match self {
+            ValueType::String => "parse_string",
+            ValueType::U32 => "parse_u32",
+            ValueType::Bool => "parse_bool",
+        }
+    }
+
+    /// Expression converting `value` to the text written
+    fn to_fix(self, value: &str) -> String {
+        match self {
+            ValueType::String => format!("{value}.clone()"),
+            ValueType::U32 => format!("{value}.to_string()"),
+            ValueType::Bool => format!("if {value} {{ \"Y\" }} else {{ \"N\" }}.to_string()"),
+        }
+    }
+}
+
+impl Dictionary {
+    /// Load and validate the dictionary of the workspace at `root`
+    pub fn load(root: &Path) -> Result<Self, String> {
+        let path = root.join(DICTIONARY);
+        let json = fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let dictionary: Self = serde_json::from_str(&json)
+            .map_err(|e| format!("invalid dictionary {}: {e}", path.display()))?;
+        dictionary.validate()?;
+        Ok(dictionary)
+    }
+
+    /// Check that the dictionary generates valid, unambiguous code
+    fn validate(&self) -> Result<(), String> {
+        let top_level = self
+            .fields
+            .iter()
+            .chain(&self.legacy_fields)
+            .chain(&self.deribit_fields);
+        check_fields("tags", top_level)?;
+        // Legacy aliases may repeat a tag, the current names may not
+        check_unique_tags("tags", self.fields.iter().chain(&self.deribit_fields))?;
+        for group in &self.groups {
+            check_identifier(&group.module, |c| c.is_ascii_lowercase() || c == '_')?;
+            check_fields(&group.module, group.fields.iter())?;
+            check_unique_tags(&group.module, group.fields.iter())?;
+        }
+
+        let mut msg_types = HashSet::new();
+        let mut variants = HashSet::new();
+        for message in &self.messages {
+            if message.msg_type.is_empty()
+                || !message.msg_type.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return Err(format!("invalid MsgType {:?}", message.msg_type));
+            }
+            check_identifier(&message.variant, |c| c.is_ascii_alphanumeric())?;
+            if !msg_types.insert(&message.msg_type) {
+                return Err(format!("MsgType {} is defined twice", message.msg_type));
+            }
+            if !variants.insert(&message.variant) {
+                return Err(format!("variant {} is defined twice", message.variant));
+            }
+        }
+
+        let mut structs = HashSet::new();
+        for generated in &self.structs {
+            if !variants.contains(&generated.message) {
+                return Err(format!(
+                    "struct {} is not a message type",
+                    generated.message
+                ));
+            }
+            if !structs.insert(&generated.message) {
+                return Err(format!("struct {} is defined twice", generated.message));
+            }
+            if generated.fields.is_empty() {
+                return Err(format!("struct {} has no fields", generated.message));
+            }
+            let mut names = HashSet::new();
+            let mut tags = HashSet::new();
+            for field in &generated.fields {
+                check_identifier(&field.name, |c| {
+                    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'
+                })?;
+                let tag = self.field(&field.field).ok_or_else(|| {
+                    format!(
+                        "{}::{} has no tag {}",
+                        generated.message, field.name, field.field
+                    )
+                })?;
+                if !names.insert(&field.name) || !tags.insert(tag.tag) {
+                    return Err(format!(
+                        "{}::{} is defined twice",
+                        generated.message, field.name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Top-level field whose constant is `const_name`, legacy aliases aside
+    fn field(&self, const_name: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .chain(&self.deribit_fields)
+            .find(|field| field.const_name == const_name)
+    }
+}
+
+fn check_identifier(name: &str, allowed: impl Fn(char) -> bool) -> Result<(), String> {
+    let valid =
+        name.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) && name.chars().all(allowed);
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid identifier {name:?}"))
+    }
+}
+
+fn check_fields<'a>(scope: &str, fields: impl Iterator<Item = &'a Field>) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for field in fields {
+        check_identifier(&field.const_name, |c| {
+            c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'
+        })?;
+        if field.tag == 0 {
+            return Err(format!("{scope}::{} has tag 0", field.const_name));
+        }
+        if !names.insert(&field.const_name) {
+            return Err(format!("{scope}::{} is defined twice", field.const_name));
+        }
+    }
+    Ok(())
+}
+
+fn check_unique_tags<'a>(
+    scope: &str,
+    fields: impl Iterator<Item = &'a Field>,
+) -> Result<(), String> {
+    let mut tags = HashSet::new();
+    for field in fields {
+        if !tags.insert(field.tag) {
+            return Err(format!("{scope}: tag {} is defined twice", field.tag));
+        }
+    }
+    Ok(())
+}
+
+/// Generated files and their contents, formatted with rustfmt
+pub fn generate(dictionary: &Dictionary) -> Result<Vec<(&'static str, String)>, String> {
+    [
+        ("src/model/tags.rs", tags(dictionary)),
+        ("src/model/msg_type.rs", msg_type(dictionary)),
+        ("src/message/generated.rs", structs(dictionary)),
+    ]
+    .into_iter()
+    .map(|(file, contents)| Ok((file, rustfmt(contents)?)))
+    .collect()
+}
+
+fn rustfmt(code: String) -> Result<String, String> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2024", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run rustfmt: {e}"))?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(code.as_bytes())
+        .map_err(|e| format!("failed to write to rustfmt: {e}"))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("failed to run rustfmt: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "rustfmt failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| format!("rustfmt wrote invalid UTF-8: {e}"))
+}
+
+/// Regenerate the files of the workspace at `root`
+pub fn write(root: &Path) -> Result<(), String> {
+    let dictionary = Dictionary::load(root)?;
+    for (file, contents) in generate(&dictionary)? {
+        let path = root.join(file);
+        fs::write(&path, contents)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+        println!("generated {file}");
+    }
+    Ok(())
+}
+
+/// Fail if a generated file of the workspace at `root` is out of date
+pub fn check(root: &Path) -> Result<(), String> {
+    let stale = stale_files(root)?;
+    if stale.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "out of date with {DICTIONARY}, run `cargo xtask codegen`: {}",
+            stale
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+fn stale_files(root: &Path) -> Result<Vec<PathBuf>, String> {
+    let dictionary = Dictionary::load(root)?;
+    Ok(generate(&dictionary)?
+        .into_iter()
+        .map(|(file, contents)| (root.join(file), contents))
+        .filter(|(path, contents)| fs::read_to_string(path).ok().as_ref() != Some(contents))
+        .map(|(path, _)| path)
+        .collect())
+}
+
+fn write_field(out: &mut String, field: &Field, indent: &str) {
+    match &field.note {
+        Some(note) => writeln!(out, "{indent}/// {} ({}), {note}", field.name, field.tag),
+        None => writeln!(out, "{indent}/// {} ({})", field.name, field.tag),
+    }
+    .unwrap();
+    if let Some(deprecated) = &field.deprecated {
+        writeln!(out, "{indent}#[deprecated(note = {deprecated:?})]").unwrap();
+    }
+    writeln!(
+        out,
+        "{indent}pub const {}: u32 = {};",
+        field.const_name, field.tag
+    )
+    .unwrap();
+}
+
+/// Contents of `src/model/tags.rs`
+fn tags(dictionary: &Dictionary) -> String {
+    let mut out = String::from(HEADER);
+    out.push_str(
+        "
+//! FIX field tags used with Deribit
+//!
+//! Standard FIX 4.4 tags and the Deribit custom tags, named after the
+//! specification. Builders and parsers use these constants rather than
+//! numeric literals. Tags that carry a different meaning in a single message
+//! family are grouped in a submodule named after it.
+
+",
+    );
+    out.push_str(GENERATED);
+    out.push('\n');
+
+    for field in &dictionary.fields {
+        write_field(&mut out, field, "");
+    }
+    out.push('\n');
+    for field in &dictionary.legacy_fields {
+        write_field(&mut out, field, "");
+    }
+    out.push_str("\n// Deribit custom tags\n");
+    for field in &dictionary.deribit_fields {
+        write_field(&mut out, field, "");
+    }
+
+    for group in &dictionary.groups {
+        out.push('\n');
+        for line in &group.doc {
+            if line.is_empty() {
+                out.push_str("///\n");
+            } else {
+                writeln!(out, "/// {line}").unwrap();
+            }
+        }
+        writeln!(out, "pub mod {} {{", group.module).unwrap();
+        for field in &group.fields {
+            write_field(&mut out, field, "    ");
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// Contents of `src/model/msg_type.rs`
+fn msg_type(dictionary: &Dictionary) -> String {
+    let mut out = String::from(HEADER);
+    out.push_str(
+        "
+//! FIX message types
+
+",
+    );
+    out.push_str(GENERATED);
+    out.push_str(
+        "
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// FIX message type identifiers
+///
+/// New message types are added as the exchange introduces them, so matches
+/// outside this crate need a wildcard arm. Types this version does not know
+/// parse to [`MsgType::Unknown`].
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum MsgType {
+",
+    );
+    for message in &dictionary.messages {
+        writeln!(out, "    /// {} ({})", message.name, message.msg_type).unwrap();
+        writeln!(out, "    {},", message.variant).unwrap();
+    }
+    out.push_str(
+        "    /// Value this version does not know, kept as received
+    Unknown(String),
+}
+
+impl MsgType {
+    /// Convert to FIX message type string
+    pub fn as_str(&self) -> &str {
+        match self {
+",
+    );
+    for message in &dictionary.messages {
+        writeln!(
+            out,
+            "            MsgType::{} => {:?},",
+            message.variant, message.msg_type
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "            MsgType::Unknown(value) => value,
+        }
+    }
+}
+
+/// Error type for parsing MsgType from string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMsgTypeError(pub String);
+
+impl std::fmt::Display for ParseMsgTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, \"Unknown message type: {}\", self.0)
+    }
+}
+
+impl std::error::Error for ParseMsgTypeError {}
+
+/// Never fails: types this version does not know become [`MsgType::Unknown`]
+impl FromStr for MsgType {
+    type Err = ParseMsgTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+",
+    );
+    for message in &dictionary.messages {
+        writeln!(
+            out,
+            "            {:?} => Ok(MsgType::{}),",
+            message.msg_type, message.variant
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "            _ => Ok(MsgType::Unknown(s.to_string())),
+        }
+    }
+}
+",
+    );
+    out
+}
+
+fn write_doc(out: &mut String, doc: &[String], indent: &str) {
+    for line in doc {
+        if line.is_empty() {
+            writeln!(out, "{indent}///").unwrap();
+        } else {
+            writeln!(out, "{indent}/// {line}").unwrap();
+        }
+    }
+}
+
+/// Contents of `src/message/generated.rs`
+fn structs(dictionary: &Dictionary) -> String {
+    let mut out = String::from(HEADER);
+    out.push_str(
+        "
+//! Message structs generated from the FIX dictionary
+//!
+//! `from_fix_message` reads back every body field `to_fix_message` writes.
+//! Constructors and helpers are hand-written next to the other messages of
+//! the family, which re-exports the structs.
+
+",
+    );
+    out.push_str(GENERATED);
+    if dictionary.structs.is_empty() {
+        return out;
+    }
+    out.push_str(
+        "
+use crate::error::{DeribitFixError, Result};
+use crate::message::MessageBuilder;
+use crate::model::message::FixMessage;
+use crate::model::tags;
+use crate::model::types::MsgType;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+",
+    );
+
+    let mut parsers = Vec::new();
+    for generated in &dictionary.structs {
+        out.push('\n');
+        write_doc(&mut out, &generated.doc, "");
+        writeln!(
+            out,
+            "#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]\npub struct {} {{",
+            generated.message
+        )
+        .unwrap();
+        for (i, field) in generated.fields.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            write_doc(&mut out, &field.doc, "    ");
+            let rust_type = field.value_type.rust_type();
+            if field.required {
+                writeln!(out, "    pub {}: {rust_type},", field.name).unwrap();
+            } else {
+                writeln!(out, "    pub {}: Option<{rust_type}>,", field.name).unwrap();
+            }
+        }
+        out.push_str("}\n");
+
+        let name = dictionary
+            .messages
+            .iter()
+            .find(|message| message.variant == generated.message)
+            .map_or(generated.message.as_str(), |message| message.name.as_str());
+        writeln!(
+            out,
+            "
+impl {0} {{
+    /// Parse a {name} from a FIX message
+    pub fn from_fix_message(message: &FixMessage) -> Result<Self> {{
+        Ok(Self {{",
+            generated.message
+        )
+        .unwrap();
+        for field in &generated.fields {
+            let spec = dictionary.field(&field.field).expect("validated");
+            let parser = field.value_type.parser();
+            if !parsers.contains(&field.value_type) {
+                parsers.push(field.value_type);
+            }
+            let read = if field.required {
+                "required"
+            } else {
+                "optional"
+            };
+            writeln!(
+                out,
+                "{}: {read}(message, tags::{}, {:?}, {parser})?,",
+                field.name, field.field, spec.name
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "        }})
+    }}
+
+    /// Build a FIX message for this {name}
+    pub fn to_fix_message(
+        &self,
+        sender_comp_id: String,
+        target_comp_id: String,
+        msg_seq_num: u32,
+    ) -> Result<FixMessage> {{
+        let mut builder = MessageBuilder::new()
+            .msg_type(MsgType::{})
+            .sender_comp_id(sender_comp_id)
+            .target_comp_id(target_comp_id)
+            .msg_seq_num(msg_seq_num)
+            .sending_time(Utc::now());",
+            generated.message
+        )
+        .unwrap();
+        for field in &generated.fields {
+            let value_type = field.value_type;
+            if field.required {
+                writeln!(
+                    out,
+                    "builder = builder.field(tags::{}, {});",
+                    field.field,
+                    value_type.to_fix(&format!("self.{}", field.name))
+                )
+                .unwrap();
+            } else {
+                let binding = match value_type {
+                    ValueType::String => "&",
+                    _ => "",
+                };
+                writeln!(
+                    out,
+                    "if let Some({0}) = {binding}self.{0} {{ builder = builder.field(tags::{1}, {2}); }}",
+                    field.name,
+                    field.field,
+                    value_type.to_fix(&field.name)
+                )
+                .unwrap();
+            }
+        }
+        out.push_str("builder.build()\n}\n}\n");
+    }
+
+    out.push_str(
+        "
+/// Value of the required `tag`, converted with `parse`
+fn required<T>(
+    message: &FixMessage,
+    tag: u32,
+    name: &str,
+    parse: fn(&str) -> Option<T>,
+) -> Result<T> {
+    optional(message, tag, name, parse)?.ok_or_else(|| {
+        DeribitFixError::MessageParsing(format!(\"{name} ({tag}) is required\"))
+    })
+}
+
+/// Value of `tag` if present, converted with `parse`
+fn optional<T>(
+    message: &FixMessage,
+    tag: u32,
+    name: &str,
+    parse: fn(&str) -> Option<T>,
+) -> Result<Option<T>> {
+    message
+        .get_field(tag)
+        .map(|value| {
+            parse(value).ok_or_else(|| {
+                DeribitFixError::MessageParsing(format!(\"Invalid {name} ({tag}): {value}\"))
+            })
+        })
+        .transpose()
+}
+",
+    );
+    for value_type in [ValueType::String, ValueType::U32, ValueType::Bool] {
+        if !parsers.contains(&value_type) {
+            continue;
+        }
+        out.push_str(match value_type {
+            ValueType::String => {
+                "
+fn parse_string(value: &str) -> Option<String> {
+    Some(value.to_string())
+}
+"
+            }
+            ValueType::U32 => {
+                "
+fn parse_u32(value: &str) -> Option<u32> {
+    value.parse().ok()
+}
+"
+            }
+            ValueType::Bool => {
+                "
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        \"Y\" => Some(true),
+        \"N\" => Some(false),
+        _ => None,
+    }
+}
+"
+            }
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> PathBuf {
+        crate::workspace_root()
+    }
+
+    #[test]
+    fn test_generated_files_are_up_to_date() {
+        assert_eq!(stale_files(&root()), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_invalid_dictionaries_are_rejected() {
+        let field = |tag: u32, const_name: &str| Field {
+            tag,
+            name: const_name.to_string(),
+            const_name: const_name.to_string(),
+            note: None,
+            deprecated: None,
+        };
+        let message = |msg_type: &str, variant: &str| Message {
+            msg_type: msg_type.to_string(),
+            name: variant.to_string(),
+            variant: variant.to_string(),
+        };
+        let dictionary = |fields: Vec<Field>, messages: Vec<Message>| Dictionary {
+            fields,
+            legacy_fields: Vec::new(),
+            deribit_fields: Vec::new(),
+            groups: Vec::new(),
+            messages,
+            structs: Vec::new(),
+        };
+
+        assert!(
+            dictionary(vec![field(1, "ACCOUNT")], vec![message("0", "Heartbeat")])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            dictionary(vec![field(1, "ACCOUNT"), field(1, "OTHER")], vec![])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            dictionary(vec![field(1, "ACCOUNT"), field(2, "ACCOUNT")], vec![])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            dictionary(vec![field(1, "account")], vec![])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            dictionary(
+                vec![],
+                vec![message("0", "Heartbeat"), message("0", "Other")]
+            )
+            .validate()
+            .is_err()
+        );
+        assert!(
+            dictionary(vec![], vec![message("0=", "Heartbeat")])
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_invalid_structs_are_rejected() {
+        let dictionary = |message: &str, fields: &[(&str, &str)]| Dictionary {
+            fields: vec![Field {
+                tag: 112,
+                name: "TestReqID".to_string(),
+                const_name: "TEST_REQ_ID".to_string(),
+                note: None,
+                deprecated: None,
+            }],
+            legacy_fields: Vec::new(),
+            deribit_fields: Vec::new(),
+            groups: Vec::new(),
+            messages: vec![Message {
+                msg_type: "1".to_string(),
+                name: "Test Request".to_string(),
+                variant: "TestRequest".to_string(),
+            }],
+            structs: vec![Struct {
+                message: message.to_string(),
+                doc: Vec::new(),
+                fields: fields
+                    .iter()
+                    .map(|(field, name)| StructField {
+                        field: field.to_string(),
+                        name: name.to_string(),
+                        value_type: ValueType::String,
+                        required: true,
+                        doc: Vec::new(),
+                    })
+                    .collect(),
+            }],
+        };
+
+        assert!(
+            dictionary("TestRequest", &[("TEST_REQ_ID", "test_req_id")])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            dictionary("Heartbeat", &[("TEST_REQ_ID", "test_req_id")])
+                .validate()
+                .is_err()
+        );
+        assert!(dictionary("TestRequest", &[]).validate().is_err());
+        assert!(
+            dictionary("TestRequest", &[("TEXT", "text")])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            dictionary("TestRequest", &[("TEST_REQ_ID", "TestReqId")])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            dictionary(
+                "TestRequest",
+                &[("TEST_REQ_ID", "test_req_id"), ("TEST_REQ_ID", "other")]
+            )
+            .validate()
+            .is_err()
+        );
+    }
+}